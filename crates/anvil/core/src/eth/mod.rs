@@ -1,11 +1,17 @@
-use crate::eth::subscription::SubscriptionId;
+use crate::{
+    eth::subscription::{AnvilSubscriptionKind, SubscriptionId},
+    types::{
+        BaseFeeParamsUpdate, DebugBreakpoint, DepositRequest, ImpersonateContractRequest,
+        MiningModeConfig, ReorgTransaction,
+    },
+};
 use alloy_primitives::{Address, Bytes, TxHash, B256, B64, U256};
 use alloy_rpc_types::{
     anvil::{Forking, MineOptions},
     pubsub::{Params as SubscriptionParams, SubscriptionKind},
     request::TransactionRequest,
     state::StateOverride,
-    trace::geth::{GethDebugTracingOptions, GethDefaultTracingOptions},
+    trace::geth::{GethDebugTracingCallOptions, GethDebugTracingOptions},
     BlockId, BlockNumberOrTag as BlockNumber, Filter, Index,
 };
 use alloy_serde::WithOtherFields;
@@ -292,7 +298,7 @@ pub enum EthRequest {
     DebugTraceCall(
         WithOtherFields<TransactionRequest>,
         #[cfg_attr(feature = "serde", serde(default))] Option<BlockId>,
-        #[cfg_attr(feature = "serde", serde(default))] GethDefaultTracingOptions,
+        #[cfg_attr(feature = "serde", serde(default))] GethDebugTracingCallOptions,
     ),
 
     /// Trace transaction endpoint for parity's `trace_transaction`
@@ -340,6 +346,12 @@ pub enum EthRequest {
         )
     )]
     AutoImpersonateAccount(bool),
+    /// Executes a call as a contract by temporarily replacing its code
+    #[cfg_attr(feature = "serde", serde(rename = "anvil_impersonateContract", with = "sequence"))]
+    ImpersonateContract(ImpersonateContractRequest),
+    /// Adds an OP-stack deposit transaction to the pool, as if it was deposited on L1
+    #[cfg_attr(feature = "serde", serde(rename = "anvil_depositTransaction", with = "sequence"))]
+    DepositTransaction(DepositRequest),
     /// Returns true if automatic mining is enabled, and false.
     #[cfg_attr(
         feature = "serde",
@@ -378,6 +390,10 @@ pub enum EthRequest {
     )]
     SetIntervalMining(u64),
 
+    /// Sets the mining mode, e.g. `{"mode": "mixed", "blockTime": 5}`
+    #[cfg_attr(feature = "serde", serde(rename = "anvil_setMiningMode", with = "sequence"))]
+    SetMiningMode(MiningModeConfig),
+
     /// Removes transactions from the pool
     #[cfg_attr(
         feature = "serde",
@@ -493,6 +509,10 @@ pub enum EthRequest {
     )]
     SetNextBlockBaseFeePerGas(U256),
 
+    /// Updates the EIP-1559 parameters used to calculate the base fee of the next blocks
+    #[cfg_attr(feature = "serde", serde(rename = "anvil_setBaseFeeParams", with = "sequence"))]
+    SetBaseFeeParams(BaseFeeParamsUpdate),
+
     /// Sets the specific timestamp
     /// Accepts timestamp (Unix epoch) with millisecond precision and returns the number of seconds
     /// between the given timestamp and the current time.
@@ -521,6 +541,31 @@ pub enum EthRequest {
     )]
     LoadState(Bytes),
 
+    /// Exports the blocks of the chain as a stream of RLP encoded blocks, the format used by
+    /// `geth export` and `geth import`
+    #[cfg_attr(feature = "serde", serde(rename = "anvil_exportChain", with = "empty_params"))]
+    ExportChain(()),
+
+    /// Imports blocks exported with `ExportChain` or `geth export` by replaying their
+    /// transactions on top of the current chain
+    #[cfg_attr(feature = "serde", serde(rename = "anvil_importChain", with = "sequence"))]
+    ImportChain(Bytes),
+
+    /// Rolls the chain back to the given block, discarding all later blocks and their state
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "anvil_rewindToBlock", deserialize_with = "deserialize_number_seq")
+    )]
+    RewindToBlock(U256),
+
+    /// Replaces the last `depth` blocks with the same number of new blocks, which include the
+    /// given transactions, each paired with the index of the new block to include it in
+    #[cfg_attr(feature = "serde", serde(rename = "anvil_reorg"))]
+    Reorg(
+        #[cfg_attr(feature = "serde", serde(deserialize_with = "deserialize_number"))] U256,
+        #[cfg_attr(feature = "serde", serde(default))] Vec<(ReorgTransaction, u64)>,
+    ),
+
     /// Retrieves the Anvil node configuration params
     #[cfg_attr(feature = "serde", serde(rename = "anvil_nodeInfo", with = "empty_params"))]
     NodeInfo(()),
@@ -753,6 +798,77 @@ pub enum EthRequest {
         serde(rename = "anvil_removePoolTransactions", with = "sequence")
     )]
     RemovePoolTransactions(Address),
+
+    /// Returns the hashes of the transactions the next block includes, in order
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "anvil_nextBlockTransactions", with = "empty_params")
+    )]
+    NextBlockTransactions(),
+
+    /// Places the first transaction before the second one in upcoming blocks, holding back the
+    /// second one until the first one can be included
+    #[cfg_attr(feature = "serde", serde(rename = "anvil_placeTransactionBefore"))]
+    PlaceTransactionBefore(B256, B256),
+
+    /// Includes the transactions together and back-to-back in upcoming blocks, in the given order
+    #[cfg_attr(feature = "serde", serde(rename = "anvil_bundleTransactions", with = "sequence"))]
+    BundleTransactions(Vec<B256>),
+
+    /// Removes all the constraints on the order of the transactions of upcoming blocks
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "anvil_clearOrderingConstraints", with = "empty_params")
+    )]
+    ClearOrderingConstraints(),
+
+    /// Executes a read-only SQL query against the SQLite database mined blocks are persisted to
+    #[cfg_attr(feature = "serde", serde(rename = "anvil_query", with = "sequence"))]
+    Query(String),
+
+    /// Executes a call without committing it and starts a step debugger session over its
+    /// execution, returning the id of the session along with its first step
+    #[cfg_attr(feature = "serde", serde(rename = "anvil_debugStart"))]
+    DebugStart(
+        WithOtherFields<TransactionRequest>,
+        #[cfg_attr(feature = "serde", serde(default))] Option<BlockId>,
+    ),
+
+    /// Moves a debugger session forward by the given number of steps, one by default
+    #[cfg_attr(feature = "serde", serde(rename = "anvil_debugStep"))]
+    DebugStep(
+        #[cfg_attr(feature = "serde", serde(deserialize_with = "deserialize_number"))] U256,
+        #[cfg_attr(feature = "serde", serde(default))] Option<usize>,
+    ),
+
+    /// Returns the stack of the current step of a debugger session, from bottom to top
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "anvil_debugStack", deserialize_with = "deserialize_number_seq")
+    )]
+    DebugStack(U256),
+
+    /// Returns the memory of the current step of a debugger session
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "anvil_debugMemory", deserialize_with = "deserialize_number_seq")
+    )]
+    DebugMemory(U256),
+
+    /// Runs a debugger session to the next step hitting the breakpoint, or to the end of the
+    /// execution
+    #[cfg_attr(feature = "serde", serde(rename = "anvil_debugContinue"))]
+    DebugContinue(
+        #[cfg_attr(feature = "serde", serde(deserialize_with = "deserialize_number"))] U256,
+        #[cfg_attr(feature = "serde", serde(default))] Option<DebugBreakpoint>,
+    ),
+
+    /// Ends a debugger session, returning whether it existed
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "anvil_debugStop", deserialize_with = "deserialize_number_seq")
+    )]
+    DebugStop(U256),
 }
 
 /// Represents ethereum JSON-RPC API
@@ -770,6 +886,10 @@ pub enum EthPubSub {
     /// Unsubscribe from an eth subscription
     #[cfg_attr(feature = "serde", serde(rename = "eth_unsubscribe", with = "sequence"))]
     EthUnSubscribe(SubscriptionId),
+
+    /// Subscribe to an anvil specific subscription, can be canceled with `eth_unsubscribe`
+    #[cfg_attr(feature = "serde", serde(rename = "anvil_subscribe", with = "sequence"))]
+    AnvilSubscribe(AnvilSubscriptionKind),
 }
 
 /// Container type for either a request or a pub sub
@@ -940,6 +1060,24 @@ mod tests {
         let _req = serde_json::from_value::<EthRequest>(value).unwrap();
     }
 
+    #[test]
+    fn test_serde_custom_mining_mode() {
+        let s =
+            r#"{"method": "anvil_setMiningMode", "params": [{"mode": "mixed", "blockTime": 5}]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        match req {
+            EthRequest::SetMiningMode(mode) => {
+                assert_eq!(mode, MiningModeConfig::Mixed { block_time: 5 })
+            }
+            _ => unreachable!(),
+        }
+
+        let s = r#"{"method": "anvil_setMiningMode", "params": [{"mode": "auto"}]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let _req = serde_json::from_value::<EthRequest>(value).unwrap();
+    }
+
     #[test]
     fn test_custom_drop_tx() {
         let s = r#"{"method": "anvil_dropTransaction", "params":
@@ -1178,6 +1316,21 @@ mod tests {
         let _req = serde_json::from_value::<EthRequest>(value).unwrap();
     }
 
+    #[test]
+    fn test_serde_custom_base_fee_params() {
+        let s = r#"{"method": "anvil_setBaseFeeParams", "params": [{"elasticityMultiplier": 4, "minBaseFee": 7}]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        match req {
+            EthRequest::SetBaseFeeParams(params) => {
+                assert_eq!(params.elasticity_multiplier, Some(4));
+                assert_eq!(params.base_fee_change_denominator, None);
+                assert_eq!(params.min_base_fee, Some(7));
+            }
+            _ => unreachable!(),
+        }
+    }
+
     #[test]
     fn test_serde_set_time() {
         let s = r#"{"method": "anvil_setTime", "params": ["0x0"]}"#;
@@ -1203,6 +1356,121 @@ mod tests {
         let _req = serde_json::from_value::<EthRequest>(value).unwrap();
     }
 
+    #[test]
+    fn test_serde_custom_export_chain() {
+        let s = r#"{"method": "anvil_exportChain", "params": [] }"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let _req = serde_json::from_value::<EthRequest>(value).unwrap();
+    }
+
+    #[test]
+    fn test_serde_custom_import_chain() {
+        let s = r#"{"method": "anvil_importChain", "params": ["0x0001"] }"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let _req = serde_json::from_value::<EthRequest>(value).unwrap();
+    }
+
+    #[test]
+    fn test_serde_custom_rewind_to_block() {
+        let s = r#"{"method": "anvil_rewindToBlock", "params": ["0x5"] }"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        assert!(matches!(req, EthRequest::RewindToBlock(n) if n == U256::from(5)));
+    }
+
+    #[test]
+    fn test_serde_custom_impersonate_contract() {
+        let s = r#"{"method": "anvil_impersonateContract", "params": [{
+            "address": "0x70997970c51812dc3a010c7d01b50e0d17dc79c8",
+            "to": "0xf39fd6e51aad88f6f4ce6ab8827279cfffb92266",
+            "data": "0x1234",
+            "value": "0x1"
+        }]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        match req {
+            EthRequest::ImpersonateContract(request) => {
+                assert!(request.to.is_some());
+                assert_eq!(request.code, None);
+                assert_eq!(request.data.len(), 2);
+                assert_eq!(request.value, Some(U256::from(1)));
+                assert_eq!(request.gas, None);
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_serde_custom_ordering_constraints() {
+        let s = r#"{"method": "anvil_nextBlockTransactions", "params": []}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let _req = serde_json::from_value::<EthRequest>(value).unwrap();
+
+        let s = r#"{"method": "anvil_placeTransactionBefore", "params":
+["0x4a3b0fce2cb9707b0baa68640cf2fe858c8bb4121b2a8cb904ff369d38a560ff",
+"0x4a3b0fce2cb9707b0baa68640cf2fe858c8bb4121b2a8cb904ff369d38a560fe"]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let _req = serde_json::from_value::<EthRequest>(value).unwrap();
+
+        let s = r#"{"method": "anvil_bundleTransactions", "params":
+[["0x4a3b0fce2cb9707b0baa68640cf2fe858c8bb4121b2a8cb904ff369d38a560ff",
+"0x4a3b0fce2cb9707b0baa68640cf2fe858c8bb4121b2a8cb904ff369d38a560fe"]]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        match serde_json::from_value::<EthRequest>(value).unwrap() {
+            EthRequest::BundleTransactions(hashes) => assert_eq!(hashes.len(), 2),
+            req => panic!("unexpected request {req:?}"),
+        }
+
+        let s = r#"{"method": "anvil_clearOrderingConstraints", "params": []}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let _req = serde_json::from_value::<EthRequest>(value).unwrap();
+    }
+
+    #[test]
+    fn test_serde_custom_deposit_transaction() {
+        let s = r#"{"method": "anvil_depositTransaction", "params": [{
+            "from": "0x70997970c51812dc3a010c7d01b50e0d17dc79c8",
+            "to": "0xf39fd6e51aad88f6f4ce6ab8827279cfffb92266",
+            "mint": "0xde0b6b3a7640000",
+            "gasLimit": "0x5208"
+        }]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        match req {
+            EthRequest::DepositTransaction(request) => {
+                assert!(request.to.is_some());
+                assert_eq!(request.mint, U256::from(10).pow(U256::from(18)));
+                assert_eq!(request.value, U256::ZERO);
+                assert_eq!(request.gas_limit, 21000);
+                assert!(!request.is_system_tx);
+                assert_eq!(request.source_hash, None);
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_serde_custom_reorg() {
+        let s = r#"{"method": "anvil_reorg", "params": [2, [
+            [{"from": "0xf39fd6e51aad88f6f4ce6ab8827279cfffb92266", "to": "0x70997970c51812dc3a010c7d01b50e0d17dc79c8", "value": "0x1"}, 0],
+            ["0x02f8", 1]
+        ]] }"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        match req {
+            EthRequest::Reorg(depth, txs) => {
+                assert_eq!(depth, U256::from(2));
+                assert!(matches!(txs[0], (ReorgTransaction::Request(_), 0)));
+                assert!(matches!(txs[1], (ReorgTransaction::Raw(_), 1)));
+            }
+            _ => unreachable!(),
+        }
+
+        let s = r#"{"method": "anvil_reorg", "params": ["0x1"] }"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let _req = serde_json::from_value::<EthRequest>(value).unwrap();
+    }
+
     #[test]
     fn test_serde_custom_snapshot() {
         let s = r#"{"method": "anvil_snapshot", "params": [] }"#;
@@ -1435,6 +1703,14 @@ mod tests {
         let _req = serde_json::from_value::<EthPubSub>(value).unwrap();
     }
 
+    #[test]
+    fn test_serde_anvil_subscribe() {
+        let s = r#"{"id": 1, "method": "anvil_subscribe", "params": ["stateDiffs"]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthPubSub>(value).unwrap();
+        assert_eq!(req, EthPubSub::AnvilSubscribe(AnvilSubscriptionKind::StateDiffs));
+    }
+
     #[test]
     fn test_serde_debug_raw_transaction() {
         let s = r#"{"jsonrpc":"2.0","method":"debug_getRawTransaction","params":["0x3ed3a89bc10115a321aee238c02de214009f8532a65368e5df5eaf732ee7167c"],"id":1}"#;
@@ -1494,6 +1770,16 @@ true}]}"#;
         let s = r#"{"method": "debug_traceCall", "params": [{"data":"0xcfae3217","from":"0xd84de507f3fada7df80908082d3239466db55a71","to":"0xcbe828fdc46e3b1c351ec90b1a5e7d9742c0398d"}, { "blockNumber": "0x0" }, {"disableStorage": true}]}"#;
         let value: serde_json::Value = serde_json::from_str(s).unwrap();
         let _req = serde_json::from_value::<EthRequest>(value).unwrap();
+
+        let s = r#"{"method": "debug_traceCall", "params": [{"data":"0xcfae3217","from":"0xd84de507f3fada7df80908082d3239466db55a71","to":"0xcbe828fdc46e3b1c351ec90b1a5e7d9742c0398d"}, "latest", {"tracer": "prestateTracer", "tracerConfig": {"diffMode": true}}]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        match req {
+            EthRequest::DebugTraceCall(_, _, opts) => {
+                assert!(opts.tracing_options.tracer.is_some());
+            }
+            _ => unreachable!(),
+        }
     }
 
     #[test]
@@ -1580,4 +1866,43 @@ true}]}"#;
         let value: serde_json::Value = serde_json::from_str(s).unwrap();
         let _req = serde_json::from_value::<EthRequest>(value).unwrap();
     }
+
+    #[test]
+    fn test_serde_anvil_query() {
+        let s = r#"{"method": "anvil_query", "params": ["SELECT * FROM blocks"]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        assert!(matches!(req, EthRequest::Query(sql) if sql == "SELECT * FROM blocks"));
+    }
+
+    #[test]
+    fn test_serde_anvil_debug() {
+        let s = r#"{"method": "anvil_debugStart", "params": [{"data":"0x600160020100"}]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        assert!(matches!(req, EthRequest::DebugStart(_, None)));
+
+        let s = r#"{"method": "anvil_debugStep", "params": ["0x1"]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        assert!(matches!(req, EthRequest::DebugStep(id, None) if id == U256::from(1)));
+
+        let s = r#"{"method": "anvil_debugStep", "params": [1, 10]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        assert!(matches!(req, EthRequest::DebugStep(_, Some(10))));
+
+        let s = r#"{"method": "anvil_debugStack", "params": ["0x1"]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        assert!(matches!(req, EthRequest::DebugStack(id) if id == U256::from(1)));
+
+        let s = r#"{"method": "anvil_debugContinue", "params": ["0x1", {"pc": 8}]}"#;
+        let value: serde_json::Value = serde_json::from_str(s).unwrap();
+        let req = serde_json::from_value::<EthRequest>(value).unwrap();
+        assert!(matches!(
+            req,
+            EthRequest::DebugContinue(_, Some(DebugBreakpoint { pc: 8, address: None }))
+        ));
+    }
 }
@@ -39,6 +39,15 @@ impl fmt::Debug for SubscriptionId {
     }
 }
 
+/// Anvil specific subscription kinds, see `anvil_subscribe`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub enum AnvilSubscriptionKind {
+    /// The accounts, balances, nonces, code and storage changed by every mined transaction
+    StateDiffs,
+}
+
 /// Provides random hex identifier with a certain length
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct HexIdProvider {
@@ -15,7 +15,10 @@ use alloy_rpc_types::{
 };
 use alloy_serde::{OtherFields, WithOtherFields};
 use bytes::BufMut;
-use foundry_evm::traces::CallTraceNode;
+use foundry_evm::{
+    eip7702::{SignedAuthorization, TxEip7702, EIP7702_TX_TYPE_ID},
+    traces::CallTraceNode,
+};
 use revm::{
     interpreter::InstructionResult,
     primitives::{OptimismFields, TxEnv},
@@ -378,6 +381,36 @@ pub fn to_alloy_transaction_with_hash_and_sender(
             blob_versioned_hashes: Some(t.tx().tx().blob_versioned_hashes.clone()),
             other: Default::default(),
         },
+        TypedTransaction::EIP7702(t) => RpcTransaction {
+            hash,
+            nonce: t.tx().nonce,
+            block_hash: None,
+            block_number: None,
+            transaction_index: None,
+            from,
+            to: None,
+            value: t.tx().value,
+            gas_price: None,
+            max_fee_per_gas: Some(t.tx().max_fee_per_gas),
+            max_priority_fee_per_gas: Some(t.tx().max_priority_fee_per_gas),
+            gas: t.tx().gas_limit,
+            input: t.tx().input.clone(),
+            chain_id: Some(t.tx().chain_id),
+            signature: Some(RpcSignature {
+                r: t.signature().r(),
+                s: t.signature().s(),
+                v: U256::from(t.signature().v().y_parity_byte()),
+                y_parity: Some(alloy_rpc_types::Parity::from(t.signature().v().y_parity())),
+            }),
+            access_list: Some(t.tx().access_list.clone()),
+            transaction_type: Some(EIP7702_TX_TYPE_ID),
+            max_fee_per_blob_gas: None,
+            blob_versioned_hashes: None,
+            other: serde_json::from_value(
+                serde_json::json!({ "authorizationList": t.tx().authorization_list }),
+            )
+            .unwrap_or_default(),
+        },
         TypedTransaction::Deposit(t) => RpcTransaction {
             hash,
             nonce: t.nonce,
@@ -431,6 +464,17 @@ impl PendingTransaction {
         }
     }
 
+    /// Creates a new pending transaction from a mined transaction, which may be impersonated
+    #[cfg(feature = "impersonated-tx")]
+    pub fn from_maybe_impersonated(
+        transaction: MaybeImpersonatedTransaction,
+    ) -> Result<Self, alloy_primitives::SignatureError> {
+        if let Some(sender) = transaction.impersonated_sender {
+            return Ok(Self::with_impersonated(transaction.transaction, sender))
+        }
+        Self::new(transaction.transaction)
+    }
+
     pub fn nonce(&self) -> u64 {
         self.transaction.nonce()
     }
@@ -556,6 +600,33 @@ impl PendingTransaction {
                     ..Default::default()
                 }
             }
+            TypedTransaction::EIP7702(tx) => {
+                let TxEip7702 {
+                    chain_id,
+                    nonce,
+                    max_priority_fee_per_gas,
+                    max_fee_per_gas,
+                    gas_limit,
+                    to,
+                    value,
+                    input,
+                    access_list,
+                    ..
+                } = tx.tx();
+                TxEnv {
+                    caller,
+                    transact_to: TxKind::Call(*to),
+                    data: input.clone(),
+                    chain_id: Some(*chain_id),
+                    nonce: Some(*nonce),
+                    value: *value,
+                    gas_price: U256::from(*max_fee_per_gas),
+                    gas_priority_fee: Some(U256::from(*max_priority_fee_per_gas)),
+                    gas_limit: *gas_limit as u64,
+                    access_list: access_list.flattened(),
+                    ..Default::default()
+                }
+            }
             TypedTransaction::Deposit(tx) => {
                 let chain_id = tx.chain_id();
                 let DepositTransaction {
@@ -604,14 +675,16 @@ pub enum TypedTransaction {
     EIP1559(Signed<TxEip1559>),
     /// EIP-4844 transaction
     EIP4844(Signed<TxEip4844Variant>),
+    /// EIP-7702 transaction
+    EIP7702(Signed<TxEip7702>),
     /// op-stack deposit transaction
     Deposit(DepositTransaction),
 }
 
 impl TypedTransaction {
-    /// Returns true if the transaction uses dynamic fees: EIP1559 or EIP4844
+    /// Returns true if the transaction uses dynamic fees: EIP1559, EIP4844 or EIP7702
     pub fn is_dynamic_fee(&self) -> bool {
-        matches!(self, Self::EIP1559(_)) || matches!(self, Self::EIP4844(_))
+        matches!(self, Self::EIP1559(_) | Self::EIP4844(_) | Self::EIP7702(_))
     }
 
     pub fn gas_price(&self) -> u128 {
@@ -620,6 +693,7 @@ impl TypedTransaction {
             Self::EIP2930(tx) => tx.tx().gas_price,
             Self::EIP1559(tx) => tx.tx().max_fee_per_gas,
             Self::EIP4844(tx) => tx.tx().tx().max_fee_per_gas,
+            Self::EIP7702(tx) => tx.tx().max_fee_per_gas,
             Self::Deposit(_) => 0,
         }
     }
@@ -630,6 +704,7 @@ impl TypedTransaction {
             Self::EIP2930(tx) => tx.tx().gas_limit,
             Self::EIP1559(tx) => tx.tx().gas_limit,
             Self::EIP4844(tx) => tx.tx().tx().gas_limit,
+            Self::EIP7702(tx) => tx.tx().gas_limit,
             Self::Deposit(tx) => tx.gas_limit,
         }
     }
@@ -640,6 +715,7 @@ impl TypedTransaction {
             Self::EIP2930(tx) => tx.tx().value,
             Self::EIP1559(tx) => tx.tx().value,
             Self::EIP4844(tx) => tx.tx().tx().value,
+            Self::EIP7702(tx) => tx.tx().value,
             Self::Deposit(tx) => tx.value,
         })
     }
@@ -650,6 +726,7 @@ impl TypedTransaction {
             Self::EIP2930(tx) => &tx.tx().input,
             Self::EIP1559(tx) => &tx.tx().input,
             Self::EIP4844(tx) => &tx.tx().tx().input,
+            Self::EIP7702(tx) => &tx.tx().input,
             Self::Deposit(tx) => &tx.input,
         }
     }
@@ -661,6 +738,7 @@ impl TypedTransaction {
             Self::EIP2930(_) => Some(1),
             Self::EIP1559(_) => Some(2),
             Self::EIP4844(_) => Some(3),
+            Self::EIP7702(_) => Some(EIP7702_TX_TYPE_ID),
             Self::Deposit(_) => Some(0x7E),
         }
     }
@@ -695,6 +773,14 @@ impl TypedTransaction {
         }
     }
 
+    /// Returns the authorizations that are applied before executing an EIP-7702 transaction
+    pub fn authorization_list(&self) -> &[SignedAuthorization] {
+        match self {
+            Self::EIP7702(tx) => &tx.tx().authorization_list,
+            _ => &[],
+        }
+    }
+
     /// Returns a helper type that contains commonly used values as fields
     pub fn essentials(&self) -> TransactionEssentials {
         match self {
@@ -754,6 +840,20 @@ impl TypedTransaction {
                 chain_id: Some(t.tx().tx().chain_id),
                 access_list: t.tx().tx().access_list.clone(),
             },
+            Self::EIP7702(t) => TransactionEssentials {
+                kind: TxKind::Call(t.tx().to),
+                input: t.tx().input.clone(),
+                nonce: t.tx().nonce,
+                gas_limit: t.tx().gas_limit,
+                gas_price: None,
+                max_fee_per_gas: Some(U256::from(t.tx().max_fee_per_gas)),
+                max_priority_fee_per_gas: Some(U256::from(t.tx().max_priority_fee_per_gas)),
+                max_fee_per_blob_gas: None,
+                blob_versioned_hashes: None,
+                value: t.tx().value,
+                chain_id: Some(t.tx().chain_id),
+                access_list: t.tx().access_list.clone(),
+            },
             Self::Deposit(t) => TransactionEssentials {
                 kind: t.kind,
                 input: t.input.clone(),
@@ -777,6 +877,7 @@ impl TypedTransaction {
             Self::EIP2930(t) => t.tx().nonce,
             Self::EIP1559(t) => t.tx().nonce,
             Self::EIP4844(t) => t.tx().tx().nonce,
+            Self::EIP7702(t) => t.tx().nonce,
             Self::Deposit(t) => t.nonce,
         }
     }
@@ -787,6 +888,7 @@ impl TypedTransaction {
             Self::EIP2930(t) => Some(t.tx().chain_id),
             Self::EIP1559(t) => Some(t.tx().chain_id),
             Self::EIP4844(t) => Some(t.tx().tx().chain_id),
+            Self::EIP7702(t) => Some(t.tx().chain_id),
             Self::Deposit(t) => t.chain_id(),
         }
     }
@@ -818,6 +920,25 @@ impl TypedTransaction {
         matches!(self, Self::EIP4844(_))
     }
 
+    /// Returns true whether this tx is a EIP7702 transaction
+    pub fn is_eip7702(&self) -> bool {
+        matches!(self, Self::EIP7702(_))
+    }
+
+    /// Returns true whether this tx is an op-stack deposit transaction
+    pub fn is_deposit(&self) -> bool {
+        matches!(self, Self::Deposit(_))
+    }
+
+    /// Returns the amount of ETH minted to the sender before execution, which is only non-zero for
+    /// op-stack deposit transactions
+    pub fn mint(&self) -> U256 {
+        match self {
+            Self::Deposit(tx) => tx.mint,
+            _ => U256::ZERO,
+        }
+    }
+
     /// Returns the hash of the transaction.
     ///
     /// Note: If this transaction has the Impersonated signature then this returns a modified unique
@@ -828,6 +949,7 @@ impl TypedTransaction {
             Self::EIP2930(t) => *t.hash(),
             Self::EIP1559(t) => *t.hash(),
             Self::EIP4844(t) => *t.hash(),
+            Self::EIP7702(t) => *t.hash(),
             Self::Deposit(t) => t.hash(),
         }
     }
@@ -856,6 +978,7 @@ impl TypedTransaction {
             Self::EIP2930(tx) => tx.recover_signer(),
             Self::EIP1559(tx) => tx.recover_signer(),
             Self::EIP4844(tx) => tx.recover_signer(),
+            Self::EIP7702(tx) => tx.recover_signer(),
             Self::Deposit(tx) => tx.recover(),
         }
     }
@@ -867,6 +990,7 @@ impl TypedTransaction {
             Self::EIP2930(tx) => tx.tx().to,
             Self::EIP1559(tx) => tx.tx().to,
             Self::EIP4844(tx) => TxKind::Call(tx.tx().tx().to),
+            Self::EIP7702(tx) => TxKind::Call(tx.tx().to),
             Self::Deposit(tx) => tx.kind,
         }
     }
@@ -883,6 +1007,7 @@ impl TypedTransaction {
             Self::EIP2930(tx) => *tx.signature(),
             Self::EIP1559(tx) => *tx.signature(),
             Self::EIP4844(tx) => *tx.signature(),
+            Self::EIP7702(tx) => *tx.signature(),
             Self::Deposit(_) => Signature::from_scalars_and_parity(
                 B256::with_last_byte(1),
                 B256::with_last_byte(1),
@@ -897,6 +1022,34 @@ impl TryFrom<RpcTransaction> for TypedTransaction {
     type Error = ConversionError;
 
     fn try_from(tx: RpcTransaction) -> Result<Self, Self::Error> {
+        if tx.transaction_type == Some(EIP7702_TX_TYPE_ID) {
+            let eip7702 = TxEip7702 {
+                chain_id: tx.chain_id.ok_or(ConversionError::MissingChainId)?,
+                nonce: tx.nonce,
+                gas_limit: tx.gas,
+                max_fee_per_gas: tx.max_fee_per_gas.ok_or(ConversionError::MissingMaxFeePerGas)?,
+                max_priority_fee_per_gas: tx
+                    .max_priority_fee_per_gas
+                    .ok_or(ConversionError::MissingMaxPriorityFeePerGas)?,
+                to: tx.to.ok_or(ConversionError::MissingTo)?,
+                value: tx.value,
+                access_list: tx.access_list.ok_or(ConversionError::MissingAccessList)?,
+                authorization_list: tx
+                    .other
+                    .get_deserialized::<Vec<SignedAuthorization>>("authorizationList")
+                    .transpose()
+                    .map_err(|err| ConversionError::Custom(err.to_string()))?
+                    .unwrap_or_default(),
+                input: tx.input,
+            };
+            let signature = tx
+                .signature
+                .ok_or(ConversionError::MissingSignature)?
+                .try_into()
+                .map_err(ConversionError::SignatureError)?;
+            return Ok(Self::EIP7702(Signed::new_unchecked(eip7702, signature, tx.hash)))
+        }
+
         // TODO(sergerad): Handle Arbitrum system transactions?
         match tx.transaction_type.unwrap_or_default().try_into()? {
             TxType::Legacy => {
@@ -997,6 +1150,11 @@ impl Encodable for TypedTransaction {
             Self::EIP2930(tx) => TxEnvelope::from(tx.clone()).encode(out),
             Self::EIP1559(tx) => TxEnvelope::from(tx.clone()).encode(out),
             Self::EIP4844(tx) => TxEnvelope::from(tx.clone()).encode(out),
+            Self::EIP7702(tx) => {
+                let payload_length = tx.tx().encoded_len_with_signature(tx.signature());
+                Header { list: false, payload_length }.encode(out);
+                tx.tx().encode_with_signature(tx.signature(), out);
+            }
             Self::Deposit(tx) => {
                 let tx_payload_len = tx.fields_len();
                 let tx_header_len = Header { list: false, payload_length: tx_payload_len }.length();
@@ -1022,6 +1180,13 @@ impl Decodable for TypedTransaction {
         // Check byte after header
         let ty = *h_decode_copy.first().ok_or(alloy_rlp::Error::Custom("empty slice"))?;
 
+        if ty == EIP7702_TX_TYPE_ID {
+            let mut payload = &h_decode_copy[1..];
+            let tx = TxEip7702::decode_signed_fields(&mut payload)?;
+            *buf = payload;
+            return Ok(Self::EIP7702(tx))
+        }
+
         if ty != 0x7E {
             Ok(TxEnvelope::decode(buf)?.into())
         } else {
@@ -1041,6 +1206,7 @@ impl Encodable2718 for TypedTransaction {
             Self::EIP2930(tx) => TxEnvelope::from(tx.clone()).encode_2718_len(),
             Self::EIP1559(tx) => TxEnvelope::from(tx.clone()).encode_2718_len(),
             Self::EIP4844(tx) => TxEnvelope::from(tx.clone()).encode_2718_len(),
+            Self::EIP7702(tx) => tx.tx().encoded_len_with_signature(tx.signature()),
             Self::Deposit(tx) => 1 + tx.length(),
         }
     }
@@ -1051,6 +1217,7 @@ impl Encodable2718 for TypedTransaction {
             Self::EIP2930(tx) => TxEnvelope::from(tx.clone()).encode_2718(out),
             Self::EIP1559(tx) => TxEnvelope::from(tx.clone()).encode_2718(out),
             Self::EIP4844(tx) => TxEnvelope::from(tx.clone()).encode_2718(out),
+            Self::EIP7702(tx) => tx.tx().encode_with_signature(tx.signature(), out),
             Self::Deposit(tx) => {
                 out.put_u8(0x7E);
                 tx.encode(out);
@@ -1064,6 +1231,9 @@ impl Decodable2718 for TypedTransaction {
         if ty == 0x7E {
             return Ok(Self::Deposit(DepositTransaction::decode(buf)?))
         }
+        if ty == EIP7702_TX_TYPE_ID {
+            return Ok(Self::EIP7702(TxEip7702::decode_signed_fields(buf)?))
+        }
         match TxEnvelope::typed_decode(ty, buf)? {
             TxEnvelope::Eip2930(tx) => Ok(Self::EIP2930(tx)),
             TxEnvelope::Eip1559(tx) => Ok(Self::EIP1559(tx)),
@@ -1232,6 +1402,8 @@ pub enum TypedReceipt<T = alloy_primitives::Log> {
     EIP1559(ReceiptWithBloom<T>),
     #[serde(rename = "0x3", alias = "0x03")]
     EIP4844(ReceiptWithBloom<T>),
+    #[serde(rename = "0x4", alias = "0x04")]
+    EIP7702(ReceiptWithBloom<T>),
     #[serde(rename = "0x7E", alias = "0x7e")]
     Deposit(DepositReceipt<T>),
 }
@@ -1239,7 +1411,11 @@ pub enum TypedReceipt<T = alloy_primitives::Log> {
 impl<T> TypedReceipt<T> {
     pub fn as_receipt_with_bloom(&self) -> &ReceiptWithBloom<T> {
         match self {
-            Self::Legacy(r) | Self::EIP1559(r) | Self::EIP2930(r) | Self::EIP4844(r) => r,
+            Self::Legacy(r) |
+            Self::EIP1559(r) |
+            Self::EIP2930(r) |
+            Self::EIP4844(r) |
+            Self::EIP7702(r) => r,
             Self::Deposit(r) => &r.inner,
         }
     }
@@ -1280,6 +1456,7 @@ impl Encodable for TypedReceipt {
                     Self::EIP2930(r) => r.length() + 1,
                     Self::EIP1559(r) => r.length() + 1,
                     Self::EIP4844(r) => r.length() + 1,
+                    Self::EIP7702(r) => r.length() + 1,
                     Self::Deposit(r) => r.length() + 1,
                     _ => unreachable!("receipt already matched"),
                 };
@@ -1300,6 +1477,11 @@ impl Encodable for TypedReceipt {
                         3u8.encode(out);
                         r.encode(out);
                     }
+                    Self::EIP7702(r) => {
+                        Header { list: true, payload_length: payload_len }.encode(out);
+                        EIP7702_TX_TYPE_ID.encode(out);
+                        r.encode(out);
+                    }
                     Self::Deposit(r) => {
                         Header { list: true, payload_length: payload_len }.encode(out);
                         0x7Eu8.encode(out);
@@ -1340,6 +1522,9 @@ impl Decodable for TypedReceipt {
                 } else if receipt_type == 0x03 {
                     buf.advance(1);
                     <ReceiptWithBloom as Decodable>::decode(buf).map(TypedReceipt::EIP4844)
+                } else if receipt_type == EIP7702_TX_TYPE_ID {
+                    buf.advance(1);
+                    <ReceiptWithBloom as Decodable>::decode(buf).map(TypedReceipt::EIP7702)
                 } else if receipt_type == 0x7E {
                     buf.advance(1);
                     <DepositReceipt as Decodable>::decode(buf).map(TypedReceipt::Deposit)
@@ -1364,6 +1549,7 @@ impl Encodable2718 for TypedReceipt {
             Self::EIP2930(_) => Some(1),
             Self::EIP1559(_) => Some(2),
             Self::EIP4844(_) => Some(3),
+            Self::EIP7702(_) => Some(EIP7702_TX_TYPE_ID),
             Self::Deposit(_) => Some(0x7E),
         }
     }
@@ -1374,6 +1560,7 @@ impl Encodable2718 for TypedReceipt {
             Self::EIP2930(r) => ReceiptEnvelope::Eip2930(r.clone()).encode_2718_len(),
             Self::EIP1559(r) => ReceiptEnvelope::Eip1559(r.clone()).encode_2718_len(),
             Self::EIP4844(r) => ReceiptEnvelope::Eip4844(r.clone()).encode_2718_len(),
+            Self::EIP7702(r) => 1 + r.length(),
             Self::Deposit(r) => 1 + r.length(),
         }
     }
@@ -1384,6 +1571,10 @@ impl Encodable2718 for TypedReceipt {
             Self::EIP2930(r) => ReceiptEnvelope::Eip2930(r.clone()).encode_2718(out),
             Self::EIP1559(r) => ReceiptEnvelope::Eip1559(r.clone()).encode_2718(out),
             Self::EIP4844(r) => ReceiptEnvelope::Eip4844(r.clone()).encode_2718(out),
+            Self::EIP7702(r) => {
+                out.put_u8(EIP7702_TX_TYPE_ID);
+                r.encode(out);
+            }
             Self::Deposit(r) => {
                 out.put_u8(0x7E);
                 r.encode(out);
@@ -1397,6 +1588,9 @@ impl Decodable2718 for TypedReceipt {
         if ty == 0x7E {
             return Ok(Self::Deposit(DepositReceipt::decode(buf)?))
         }
+        if ty == EIP7702_TX_TYPE_ID {
+            return Ok(Self::EIP7702(ReceiptWithBloom::decode(buf)?))
+        }
         match ReceiptEnvelope::typed_decode(ty, buf)? {
             ReceiptEnvelope::Eip2930(tx) => Ok(Self::EIP2930(tx)),
             ReceiptEnvelope::Eip1559(tx) => Ok(Self::EIP1559(tx)),
@@ -1454,6 +1648,7 @@ pub fn convert_to_anvil_receipt(receipt: AnyTransactionReceipt) -> Option<Receip
             0x01 => TypedReceipt::EIP2930(receipt_with_bloom),
             0x02 => TypedReceipt::EIP1559(receipt_with_bloom),
             0x03 => TypedReceipt::EIP4844(receipt_with_bloom),
+            EIP7702_TX_TYPE_ID => TypedReceipt::EIP7702(receipt_with_bloom),
             0x7E => TypedReceipt::Deposit(DepositReceipt {
                 inner: receipt_with_bloom,
                 deposit_nonce: other
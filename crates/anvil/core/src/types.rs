@@ -1,4 +1,6 @@
-use alloy_primitives::{B256, U256};
+use alloy_primitives::{Address, Bytes, B256, U256};
+use alloy_rpc_types::TransactionRequest;
+use alloy_serde::WithOtherFields;
 
 #[cfg(feature = "serde")]
 use serde::Serializer;
@@ -26,3 +28,167 @@ impl serde::Serialize for Work {
         }
     }
 }
+
+/// The mining mode to switch to via `anvil_setMiningMode`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize, serde::Serialize),
+    serde(tag = "mode", rename_all = "camelCase")
+)]
+pub enum MiningModeConfig {
+    /// Only mine on demand
+    None,
+    /// Mine a block for every ready transaction
+    Auto,
+    /// Mine a block every `block_time` seconds
+    Interval {
+        #[cfg_attr(feature = "serde", serde(rename = "blockTime"))]
+        block_time: u64,
+    },
+    /// Mine ready transactions right away, and an empty block after `block_time` seconds without
+    /// blocks
+    Mixed {
+        #[cfg_attr(feature = "serde", serde(rename = "blockTime"))]
+        block_time: u64,
+    },
+}
+
+/// The EIP-1559 parameters to update via `anvil_setBaseFeeParams`
+///
+/// Parameters that are not set are left unchanged.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize, serde::Serialize),
+    serde(rename_all = "camelCase")
+)]
+pub struct BaseFeeParamsUpdate {
+    /// The ratio of the block gas limit to the block gas target
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub elasticity_multiplier: Option<u64>,
+    /// Bounds the change of the base fee between blocks to `1 / base_fee_change_denominator`
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub base_fee_change_denominator: Option<u64>,
+    /// The minimum base fee of the next blocks
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub min_base_fee: Option<u128>,
+}
+
+/// A call executed as a contract by `anvil_impersonateContract`
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize, serde::Serialize),
+    serde(rename_all = "camelCase")
+)]
+pub struct ImpersonateContractRequest {
+    /// The contract to act as
+    pub address: Address,
+    /// The sender of the transaction, defaults to the first account of the node
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub from: Option<Address>,
+    /// The code to run as the contract with `data` as calldata, mutually exclusive with `to`
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub code: Option<Bytes>,
+    /// The target the contract calls with `data` and `value`, mutually exclusive with `code`
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub to: Option<Address>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub data: Bytes,
+    /// The value the contract sends to `to` from its own balance
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub value: Option<U256>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            default,
+            skip_serializing_if = "Option::is_none",
+            with = "alloy_serde::quantity::opt"
+        )
+    )]
+    pub gas: Option<u128>,
+}
+
+/// An OP-stack deposit transaction to execute with `anvil_depositTransaction`, with the fields of
+/// the `TransactionDeposited` event emitted by the L1 portal
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize, serde::Serialize),
+    serde(rename_all = "camelCase")
+)]
+pub struct DepositRequest {
+    pub from: Address,
+    /// The recipient of the deposit, a contract creation if not set
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub to: Option<Address>,
+    /// The ETH minted to `from` on L2 before the deposit is executed
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub mint: U256,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub value: U256,
+    #[cfg_attr(feature = "serde", serde(with = "alloy_serde::quantity"))]
+    pub gas_limit: u128,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub is_system_tx: bool,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub data: Bytes,
+    /// The source hash of the deposit, derived from the latest block and the nonce of `from` if
+    /// not set
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub source_hash: Option<B256>,
+}
+
+/// A transaction to include in the blocks mined by `anvil_reorg`
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize), serde(untagged))]
+pub enum ReorgTransaction {
+    /// A transaction request, signed by the node or sent from an impersonated account
+    Request(WithOtherFields<TransactionRequest>),
+    /// A raw signed transaction
+    Raw(Bytes),
+}
+
+/// Where a session of the `anvil_debug*` step debugger is at
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize, serde::Serialize),
+    serde(rename_all = "camelCase")
+)]
+pub struct DebugState {
+    /// The id of the session
+    pub id: U256,
+    /// The index of the current step, among all the steps of the execution
+    pub step: usize,
+    /// The number of steps of the execution
+    pub total_steps: usize,
+    /// Whether the current step is the last one
+    pub finished: bool,
+    /// The address of the code being executed
+    pub address: Address,
+    /// The depth of the call frame
+    pub depth: u64,
+    /// The program counter of the current step
+    pub pc: usize,
+    /// The name of the opcode about to be executed
+    pub op: String,
+    pub gas_remaining: u64,
+    pub gas_cost: u64,
+}
+
+/// A breakpoint to run to with `anvil_debugContinue`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Deserialize, serde::Serialize),
+    serde(rename_all = "camelCase")
+)]
+pub struct DebugBreakpoint {
+    /// The program counter to stop at
+    pub pc: usize,
+    /// The code the program counter is in, any code if not set
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub address: Option<Address>,
+}
@@ -1,6 +1,6 @@
 use crate::HeaderValue;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
-use std::str::FromStr;
+use std::{fmt, net::IpAddr, path::PathBuf, str::FromStr};
 
 /// Additional server options.
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -14,9 +14,55 @@ pub struct ServerConfig {
     #[cfg_attr(feature = "clap", arg(long, conflicts_with = "allow_origin"))]
     pub no_cors: bool,
 
+    /// Only allow browsers on the given origin to call the methods matching a pattern, e.g.
+    /// `anvil_*=https://admin.example.com`.
+    ///
+    /// Can be repeated. Methods not matching any pattern are allowed for `--allow-origin`.
+    #[cfg_attr(
+        feature = "clap",
+        arg(long = "allow-origin-for", value_name = "METHODS=ORIGIN", conflicts_with = "no_cors")
+    )]
+    #[serde(default)]
+    pub method_origins: Vec<MethodOrigin>,
+
+    /// Trust the `X-Forwarded-For` header of requests sent by the given proxy to determine the
+    /// address of the client.
+    ///
+    /// Can be repeated.
+    #[cfg_attr(feature = "clap", arg(long = "trusted-proxy", value_name = "IP"))]
+    #[serde(default)]
+    pub trusted_proxies: Vec<IpAddr>,
+
+    /// Serve HTTPS and WSS with the given PEM encoded certificate chain.
+    #[cfg_attr(feature = "clap", arg(long, value_name = "FILE", requires = "tls_key"))]
+    #[serde(default)]
+    pub tls_cert: Option<PathBuf>,
+
+    /// The PEM encoded private key of `--tls-cert`.
+    #[cfg_attr(feature = "clap", arg(long, value_name = "FILE", requires = "tls_cert"))]
+    #[serde(default)]
+    pub tls_key: Option<PathBuf>,
+
     /// Disable the default request body size limit. At time of writing the default limit is 2MB.
     #[cfg_attr(feature = "clap", arg(long))]
     pub no_request_size_limit: bool,
+
+    /// Serve a dev dashboard with a block explorer at `/ui`.
+    #[cfg_attr(feature = "clap", arg(long))]
+    pub ui: bool,
+
+    /// Serve a Sourcify-compatible verification API at `/sourcify`, with the sources of the
+    /// contracts deployed from the artifacts of the Foundry project at the given root.
+    ///
+    /// Defaults to the project anvil is started in, if any.
+    #[cfg_attr(feature = "clap", arg(long, value_name = "ROOT"))]
+    #[serde(default)]
+    pub sourcify: Option<PathBuf>,
+
+    /// Don't serve the verified sources of the project anvil is started in.
+    #[cfg_attr(feature = "clap", arg(long, conflicts_with = "sourcify"))]
+    #[serde(default)]
+    pub no_sourcify: bool,
 }
 
 impl ServerConfig {
@@ -31,6 +77,37 @@ impl ServerConfig {
         self.no_cors = !cors;
         self
     }
+
+    /// Only allows browsers on the given origin to call the methods matching the pattern.
+    pub fn with_method_origin(mut self, method_origin: MethodOrigin) -> Self {
+        self.method_origins.push(method_origin);
+        self
+    }
+
+    /// Trusts the forwarding headers of requests sent by the given proxy.
+    pub fn with_trusted_proxy(mut self, proxy: IpAddr) -> Self {
+        self.trusted_proxies.push(proxy);
+        self
+    }
+
+    /// Serves HTTPS and WSS with the given PEM encoded certificate chain and private key.
+    pub fn with_tls(mut self, cert: impl Into<PathBuf>, key: impl Into<PathBuf>) -> Self {
+        self.tls_cert = Some(cert.into());
+        self.tls_key = Some(key.into());
+        self
+    }
+
+    /// Serves the verified sources of the contracts deployed from the artifacts of the project at
+    /// `root`.
+    pub fn with_sourcify(mut self, root: impl Into<PathBuf>) -> Self {
+        self.sourcify = Some(root.into());
+        self
+    }
+
+    /// Returns whether HTTPS and WSS are served.
+    pub fn is_tls(&self) -> bool {
+        self.tls_cert.is_some() && self.tls_key.is_some()
+    }
 }
 
 impl Default for ServerConfig {
@@ -38,9 +115,63 @@ impl Default for ServerConfig {
         Self {
             allow_origin: "*".parse::<HeaderValue>().unwrap().into(),
             no_cors: false,
+            method_origins: Vec::new(),
+            trusted_proxies: Vec::new(),
+            tls_cert: None,
+            tls_key: None,
             no_request_size_limit: false,
+            ui: false,
+            sourcify: None,
+            no_sourcify: false,
+        }
+    }
+}
+
+/// Restricts the methods matching a pattern to browsers on an origin, in the form
+/// `<METHODS>=<ORIGIN>`.
+///
+/// The pattern is either a method name, or a prefix followed by `*`, e.g. `anvil_*`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MethodOrigin {
+    /// The pattern of the methods.
+    pub methods: String,
+    /// The origin allowed to call the methods.
+    pub origin: HeaderValueWrapper,
+}
+
+impl MethodOrigin {
+    /// Returns whether the method matches the pattern.
+    pub fn matches(&self, method: &str) -> bool {
+        match self.methods.strip_suffix('*') {
+            Some(prefix) => method.starts_with(prefix),
+            None => method == self.methods,
         }
     }
+
+    /// Returns whether the origin is allowed.
+    pub fn allows(&self, origin: &HeaderValue) -> bool {
+        self.origin.0 == "*" || self.origin.0 == origin
+    }
+}
+
+impl FromStr for MethodOrigin {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (methods, origin) =
+            s.split_once('=').ok_or_else(|| format!("expected `<METHODS>=<ORIGIN>`, got `{s}`"))?;
+        if methods.is_empty() {
+            return Err(format!("missing methods in `{s}`"))
+        }
+        let origin = origin.parse().map_err(|err| format!("invalid origin `{origin}`: {err}"))?;
+        Ok(Self { methods: methods.to_string(), origin })
+    }
+}
+
+impl fmt::Display for MethodOrigin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}={}", self.methods, self.origin.to_str().unwrap_or_default())
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -92,3 +223,28 @@ impl From<HeaderValue> for HeaderValueWrapper {
         Self(header)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_method_origins() {
+        let rule: MethodOrigin = "anvil_*=https://admin.example.com".parse().unwrap();
+        assert_eq!(rule.methods, "anvil_*");
+        assert_eq!(rule.origin.0, "https://admin.example.com");
+        assert_eq!(rule.to_string(), "anvil_*=https://admin.example.com");
+        assert!(rule.matches("anvil_mine"));
+        assert!(!rule.matches("eth_call"));
+        assert!(rule.allows(&HeaderValue::from_static("https://admin.example.com")));
+        assert!(!rule.allows(&HeaderValue::from_static("https://example.com")));
+
+        let rule: MethodOrigin = "eth_sendTransaction=*".parse().unwrap();
+        assert!(rule.matches("eth_sendTransaction"));
+        assert!(!rule.matches("eth_sendRawTransaction"));
+        assert!(rule.allows(&HeaderValue::from_static("https://example.com")));
+
+        assert!("anvil_*".parse::<MethodOrigin>().is_err());
+        assert!("=https://example.com".parse::<MethodOrigin>().is_err());
+    }
+}
@@ -1,4 +1,7 @@
-use crate::RpcHandler;
+use crate::{
+    origin::{MethodOrigins, OriginFilter},
+    RpcHandler,
+};
 use anvil_rpc::{
     error::RpcError,
     request::{Request, RpcCall},
@@ -6,7 +9,8 @@ use anvil_rpc::{
 };
 use axum::{
     extract::{rejection::JsonRejection, State},
-    Json,
+    http::HeaderMap,
+    Extension, Json,
 };
 use futures::{future, FutureExt};
 
@@ -14,10 +18,13 @@ use futures::{future, FutureExt};
 // NOTE: `handler` must come first because the `request` extractor consumes the request body.
 pub async fn handle<Http: RpcHandler, Ws>(
     State((handler, _)): State<(Http, Ws)>,
+    Extension(method_origins): Extension<MethodOrigins>,
+    headers: HeaderMap,
     request: Result<Json<Request>, JsonRejection>,
 ) -> Json<Response> {
+    let filter = OriginFilter::new(method_origins, &headers);
     Json(match request {
-        Ok(Json(req)) => handle_request(req, handler)
+        Ok(Json(req)) => handle_request(req, handler, filter)
             .await
             .unwrap_or_else(|| Response::error(RpcError::invalid_request())),
         Err(err) => {
@@ -30,10 +37,11 @@ pub async fn handle<Http: RpcHandler, Ws>(
 /// Handle the JSON-RPC [Request]
 ///
 /// This will try to deserialize the payload into the request type of the handler and if successful
-/// invoke the handler
+/// invoke the handler, for the calls allowed by the [`OriginFilter`].
 pub async fn handle_request<Handler: RpcHandler>(
     req: Request,
     handler: Handler,
+    filter: OriginFilter,
 ) -> Option<Response> {
     /// processes batch calls
     fn responses_as_batch(outs: Vec<Option<RpcResponse>>) -> Option<Response> {
@@ -42,20 +50,29 @@ pub async fn handle_request<Handler: RpcHandler>(
     }
 
     match req {
-        Request::Single(call) => handle_call(call, handler).await.map(Response::Single),
+        Request::Single(call) => handle_call(call, handler, &filter).await.map(Response::Single),
         Request::Batch(calls) => {
-            future::join_all(calls.into_iter().map(move |call| handle_call(call, handler.clone())))
-                .map(responses_as_batch)
-                .await
+            future::join_all(
+                calls.into_iter().map(|call| handle_call(call, handler.clone(), &filter)),
+            )
+            .map(responses_as_batch)
+            .await
         }
     }
 }
 
 /// handle a single RPC method call
-async fn handle_call<Handler: RpcHandler>(call: RpcCall, handler: Handler) -> Option<RpcResponse> {
+async fn handle_call<Handler: RpcHandler>(
+    call: RpcCall,
+    handler: Handler,
+    filter: &OriginFilter,
+) -> Option<RpcResponse> {
     match call {
         RpcCall::MethodCall(call) => {
             trace!(target: "rpc", id = ?call.id , method = ?call.method,  "handling call");
+            if let Err(err) = filter.check(&call.method) {
+                return Some(RpcResponse::new(call.id, err))
+            }
             Some(handler.on_call(call).await)
         }
         RpcCall::Notification(notification) => {
@@ -14,18 +14,30 @@ use anvil_rpc::{
 use axum::{
     extract::DefaultBodyLimit,
     http::{header, HeaderValue, Method},
+    middleware,
     routing::{post, MethodRouter},
-    Router,
+    Extension, Router,
 };
+use origin::MethodOrigins;
 use serde::de::DeserializeOwned;
-use std::fmt;
-use tower_http::{cors::CorsLayer, trace::TraceLayer};
+use std::{fmt, io, iter, net::SocketAddr};
+use tokio::net::TcpListener;
+use tower_http::{
+    cors::{AllowOrigin, CorsLayer},
+    trace::TraceLayer,
+};
 
 mod config;
-pub use config::ServerConfig;
+pub use config::{MethodOrigin, ServerConfig};
 
 mod error;
 mod handler;
+mod origin;
+
+mod proxy;
+pub use proxy::ClientAddr;
+
+mod tls;
 
 mod pubsub;
 pub use pubsub::{PubSubContext, PubSubRpcHandler};
@@ -57,18 +69,46 @@ fn router_inner<S: Clone + Send + Sync + 'static>(
     root_method_router: MethodRouter<S>,
     state: S,
 ) -> Router {
-    let ServerConfig { allow_origin, no_cors, no_request_size_limit } = config;
+    let ServerConfig {
+        allow_origin,
+        no_cors,
+        method_origins,
+        trusted_proxies,
+        no_request_size_limit,
+        ui: _,
+        sourcify: _,
+        no_sourcify: _,
+        tls_cert: _,
+        tls_key: _,
+    } = config;
+
+    // Browsers on the origins restricted to some methods must pass the CORS checks, the methods
+    // they may call are checked by the handlers.
+    let allow_origin = if method_origins.is_empty() {
+        AllowOrigin::from(allow_origin.0)
+    } else {
+        let origins = iter::once(allow_origin.0)
+            .chain(method_origins.iter().map(|rule| rule.origin.0.clone()))
+            .collect::<Vec<_>>();
+        if origins.iter().any(|origin| origin == "*") {
+            AllowOrigin::from(HeaderValue::from_static("*"))
+        } else {
+            AllowOrigin::list(origins)
+        }
+    };
 
     let mut router = Router::new()
         .route("/", root_method_router)
         .with_state(state)
+        .layer(Extension(MethodOrigins(method_origins.into())))
+        .layer(middleware::from_fn_with_state(trusted_proxies.into(), proxy::resolve_client_addr))
         .layer(TraceLayer::new_for_http());
     if !no_cors {
         // See [`tower_http::cors`](https://docs.rs/tower-http/latest/tower_http/cors/index.html)
         // for more details.
         router = router.layer(
             CorsLayer::new()
-                .allow_origin(allow_origin.0)
+                .allow_origin(allow_origin)
                 .allow_headers([header::CONTENT_TYPE])
                 .allow_methods([Method::GET, Method::POST]),
         );
@@ -79,6 +119,19 @@ fn router_inner<S: Clone + Send + Sync + 'static>(
     router
 }
 
+/// Serves the router on the listener, over TLS if a certificate is configured.
+///
+/// The address of the peer of every request is available as
+/// [`ConnectInfo`](axum::extract::ConnectInfo), and the address of the client behind the
+/// configured trusted proxies as [`ClientAddr`].
+pub async fn serve(listener: TcpListener, router: Router, config: &ServerConfig) -> io::Result<()> {
+    if let (Some(cert), Some(key)) = (&config.tls_cert, &config.tls_key) {
+        let acceptor = tls::acceptor(cert, key)?;
+        return tls::serve(listener, router, acceptor).await
+    }
+    axum::serve(listener, router.into_make_service_with_connect_info::<SocketAddr>()).await
+}
+
 /// Helper trait that is used to execute ethereum rpc calls
 #[async_trait::async_trait]
 pub trait RpcHandler: Clone + Send + Sync + 'static {
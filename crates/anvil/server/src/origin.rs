@@ -0,0 +1,78 @@
+//! Restricts methods to the origins configured with [`MethodOrigin`]s.
+
+use crate::config::MethodOrigin;
+use anvil_rpc::error::{ErrorCode, RpcError};
+use axum::http::{header, HeaderMap, HeaderValue};
+use std::sync::Arc;
+
+/// The configured [`MethodOrigin`]s, shared by all requests.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct MethodOrigins(pub(crate) Arc<[MethodOrigin]>);
+
+/// Checks the methods called by a request against the [`MethodOrigins`], given the origin of the
+/// request.
+///
+/// Requests without an `Origin` header weren't sent by a browser and may call any method, as may
+/// requests calling methods that don't match any pattern.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct OriginFilter {
+    rules: Arc<[MethodOrigin]>,
+    origin: Option<HeaderValue>,
+}
+
+impl OriginFilter {
+    pub(crate) fn new(MethodOrigins(rules): MethodOrigins, headers: &HeaderMap) -> Self {
+        Self { rules, origin: headers.get(header::ORIGIN).cloned() }
+    }
+
+    /// Returns an error if the request isn't allowed to call the method.
+    pub(crate) fn check(&self, method: &str) -> Result<(), RpcError> {
+        let Some(origin) = &self.origin else { return Ok(()) };
+        let mut rules = self.rules.iter().filter(|rule| rule.matches(method)).peekable();
+        if rules.peek().is_none() || rules.any(|rule| rule.allows(origin)) {
+            return Ok(())
+        }
+        warn!(target: "rpc", ?origin, method, "rejected call from disallowed origin");
+        Err(RpcError {
+            code: ErrorCode::InvalidRequest,
+            message: format!("origin is not allowed to call `{method}`").into(),
+            data: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn filter(origin: Option<&'static str>) -> OriginFilter {
+        let rules = ["anvil_*=https://admin.example.com", "anvil_mine=https://miner.example.com"]
+            .iter()
+            .map(|rule| rule.parse().unwrap())
+            .collect::<Vec<_>>();
+        let mut headers = HeaderMap::new();
+        if let Some(origin) = origin {
+            headers.insert(header::ORIGIN, HeaderValue::from_static(origin));
+        }
+        OriginFilter::new(MethodOrigins(rules.into()), &headers)
+    }
+
+    #[test]
+    fn checks_methods_against_origin() {
+        let admin = filter(Some("https://admin.example.com"));
+        assert!(admin.check("anvil_setBalance").is_ok());
+        assert!(admin.check("anvil_mine").is_ok());
+        assert!(admin.check("eth_call").is_ok());
+
+        let miner = filter(Some("https://miner.example.com"));
+        assert!(miner.check("anvil_mine").is_ok());
+        assert!(miner.check("anvil_setBalance").is_err());
+
+        let other = filter(Some("https://example.com"));
+        assert!(other.check("anvil_mine").is_err());
+        assert!(other.check("eth_call").is_ok());
+
+        // Not a browser.
+        assert!(filter(None).check("anvil_setBalance").is_ok());
+    }
+}
@@ -0,0 +1,89 @@
+//! Resolves the address of the client behind trusted reverse proxies.
+
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    http::HeaderMap,
+    middleware::Next,
+    response::Response,
+};
+use std::{
+    net::{IpAddr, SocketAddr},
+    sync::Arc,
+};
+use tracing::Instrument;
+
+/// The address of the client that sent a request.
+///
+/// Inserted in the extensions of the requests of servers that record the address of their peers,
+/// see [`serve`](crate::serve).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ClientAddr(pub IpAddr);
+
+/// Resolves the [`ClientAddr`] of the request, and runs the request in a span recording it.
+pub(crate) async fn resolve_client_addr(
+    State(trusted_proxies): State<Arc<[IpAddr]>>,
+    connect_info: Option<ConnectInfo<SocketAddr>>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let Some(ConnectInfo(peer)) = connect_info else { return next.run(request).await };
+    let client = client_addr(peer.ip(), request.headers(), &trusted_proxies);
+    request.extensions_mut().insert(ClientAddr(client));
+    next.run(request).instrument(debug_span!(target: "rpc", "request", %client)).await
+}
+
+/// Returns the address of the client, given the address of the peer that sent the request.
+///
+/// The `X-Forwarded-For` header is only trusted if the peer is a trusted proxy. The hops it lists
+/// are then walked from the closest one, up to the first that isn't a trusted proxy itself, since
+/// any hop before it may have been forged by the client.
+fn client_addr(peer: IpAddr, headers: &HeaderMap, trusted_proxies: &[IpAddr]) -> IpAddr {
+    if !trusted_proxies.contains(&peer) {
+        return peer
+    }
+
+    let hops = headers
+        .get_all("x-forwarded-for")
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(str::trim)
+        .collect::<Vec<_>>();
+    let mut client = peer;
+    for hop in hops.into_iter().rev() {
+        let Ok(addr) = hop.parse() else { break };
+        client = addr;
+        if !trusted_proxies.contains(&addr) {
+            break
+        }
+    }
+    client
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    #[test]
+    fn resolves_client_behind_trusted_proxies() {
+        let ingress: IpAddr = "10.0.0.1".parse().unwrap();
+        let sidecar: IpAddr = "10.0.0.2".parse().unwrap();
+        let trusted = [ingress, sidecar];
+
+        let mut headers = HeaderMap::new();
+        headers.append("x-forwarded-for", HeaderValue::from_static("1.1.1.1, 203.0.113.7"));
+        headers.append("x-forwarded-for", HeaderValue::from_static("10.0.0.2"));
+
+        // The first hop is forged, the client is the one the trusted proxies saw.
+        assert_eq!(
+            client_addr(ingress, &headers, &trusted),
+            "203.0.113.7".parse::<IpAddr>().unwrap()
+        );
+        // Untrusted peers can't spoof their address.
+        let peer = "198.51.100.1".parse().unwrap();
+        assert_eq!(client_addr(peer, &headers, &trusted), peer);
+        // Without forwarding headers, the proxy is the client.
+        assert_eq!(client_addr(ingress, &HeaderMap::new(), &trusted), ingress);
+    }
+}
@@ -1,4 +1,4 @@
-use crate::{error::RequestError, handler::handle_request, RpcHandler};
+use crate::{error::RequestError, handler::handle_request, origin::OriginFilter, RpcHandler};
 use anvil_rpc::{
     error::RpcError,
     request::Request,
@@ -121,6 +121,8 @@ pub struct PubSubConnection<Handler: PubSubRpcHandler, Connection> {
     processing: Vec<Pin<Box<dyn Future<Output = Response> + Send>>>,
     /// pending messages to send
     pending: VecDeque<String>,
+    /// the methods the connection may call
+    filter: OriginFilter,
 }
 
 impl<Handler: PubSubRpcHandler, Connection> PubSubConnection<Handler, Connection> {
@@ -131,9 +133,16 @@ impl<Handler: PubSubRpcHandler, Connection> PubSubConnection<Handler, Connection
             context: Default::default(),
             pending: Default::default(),
             processing: Default::default(),
+            filter: Default::default(),
         }
     }
 
+    /// Restricts the methods the connection may call.
+    pub(crate) fn with_origin_filter(mut self, filter: OriginFilter) -> Self {
+        self.filter = filter;
+        self
+    }
+
     /// Returns a compatibility `RpcHandler`
     fn compat_helper(&self) -> ContextAwareHandler<Handler> {
         ContextAwareHandler { handler: self.handler.clone(), context: self.context.clone() }
@@ -141,9 +150,10 @@ impl<Handler: PubSubRpcHandler, Connection> PubSubConnection<Handler, Connection
 
     fn process_request(&mut self, req: serde_json::Result<Request>) {
         let handler = self.compat_helper();
+        let filter = self.filter.clone();
         self.processing.push(Box::pin(async move {
             match req {
-                Ok(req) => handle_request(req, handler)
+                Ok(req) => handle_request(req, handler, filter)
                     .await
                     .unwrap_or_else(|| Response::error(RpcError::invalid_request())),
                 Err(err) => {
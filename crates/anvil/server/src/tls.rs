@@ -0,0 +1,78 @@
+//! Serving HTTPS and WSS.
+
+use axum::{extract::ConnectInfo, Extension, Router};
+use hyper_util::{
+    rt::{TokioExecutor, TokioIo},
+    server::conn::auto::Builder,
+    service::TowerToHyperService,
+};
+use std::{fs::File, io, io::BufReader, path::Path, sync::Arc, time::Duration};
+use tokio::net::TcpListener;
+use tokio_rustls::{
+    rustls::{crypto::ring, ServerConfig},
+    TlsAcceptor,
+};
+
+/// Loads the PEM encoded certificate chain and private key into a [`TlsAcceptor`].
+pub(crate) fn acceptor(cert: &Path, key: &Path) -> io::Result<TlsAcceptor> {
+    let certs = rustls_pemfile::certs(&mut BufReader::new(File::open(cert)?))
+        .collect::<Result<Vec<_>, _>>()?;
+    if certs.is_empty() {
+        return Err(invalid_input(format!("no certificate found in {}", cert.display())))
+    }
+    let key = rustls_pemfile::private_key(&mut BufReader::new(File::open(key)?))?
+        .ok_or_else(|| invalid_input(format!("no private key found in {}", key.display())))?;
+
+    let mut config = ServerConfig::builder_with_provider(Arc::new(ring::default_provider()))
+        .with_safe_default_protocol_versions()
+        .map_err(io::Error::other)?
+        .with_no_client_auth()
+        .with_single_cert(certs, key)
+        .map_err(invalid_input)?;
+    // Websocket upgrades require HTTP/1.1.
+    config.alpn_protocols = vec![b"http/1.1".to_vec()];
+    Ok(TlsAcceptor::from(Arc::new(config)))
+}
+
+/// Serves the router over TLS on the listener.
+pub(crate) async fn serve(
+    listener: TcpListener,
+    router: Router,
+    acceptor: TlsAcceptor,
+) -> io::Result<()> {
+    loop {
+        let (stream, peer) = match listener.accept().await {
+            Ok(conn) => conn,
+            Err(err) => {
+                // Like `axum::serve`, back off on errors such as running out of file descriptors.
+                error!(target: "rpc", %err, "failed to accept connection");
+                tokio::time::sleep(Duration::from_secs(1)).await;
+                continue
+            }
+        };
+        let acceptor = acceptor.clone();
+        let router = router.clone();
+        tokio::spawn(async move {
+            let stream = match acceptor.accept(stream).await {
+                Ok(stream) => stream,
+                Err(err) => {
+                    debug!(target: "rpc", %peer, %err, "TLS handshake failed");
+                    return
+                }
+            };
+            // Makes the peer available to the handlers, like
+            // `Router::into_make_service_with_connect_info` does.
+            let service = TowerToHyperService::new(router.layer(Extension(ConnectInfo(peer))));
+            if let Err(err) = Builder::new(TokioExecutor::new())
+                .serve_connection_with_upgrades(TokioIo::new(stream), service)
+                .await
+            {
+                debug!(target: "rpc", %peer, %err, "failed to serve connection");
+            }
+        });
+    }
+}
+
+fn invalid_input(err: impl ToString) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, err.to_string())
+}
@@ -1,11 +1,18 @@
-use crate::{error::RequestError, pubsub::PubSubConnection, PubSubRpcHandler};
+use crate::{
+    error::RequestError,
+    origin::{MethodOrigins, OriginFilter},
+    pubsub::PubSubConnection,
+    PubSubRpcHandler,
+};
 use anvil_rpc::request::Request;
 use axum::{
     extract::{
         ws::{Message, WebSocket},
         State, WebSocketUpgrade,
     },
+    http::HeaderMap,
     response::Response,
+    Extension,
 };
 use futures::{ready, Sink, Stream};
 use std::{
@@ -19,8 +26,13 @@ use std::{
 pub async fn handle_ws<Http, Ws: PubSubRpcHandler>(
     ws: WebSocketUpgrade,
     State((_, handler)): State<(Http, Ws)>,
+    Extension(method_origins): Extension<MethodOrigins>,
+    headers: HeaderMap,
 ) -> Response {
-    ws.on_upgrade(|socket| PubSubConnection::new(SocketConn(socket), handler))
+    let filter = OriginFilter::new(method_origins, &headers);
+    ws.on_upgrade(|socket| {
+        PubSubConnection::new(SocketConn(socket), handler).with_origin_filter(filter)
+    })
 }
 
 #[pin_project::pin_project]
@@ -1,15 +1,18 @@
 use crate::{
     config::{ForkChoice, DEFAULT_MNEMONIC},
     eth::{backend::db::SerializableState, pool::transactions::TransactionOrder, EthApi},
+    server::find_project_root,
     AccountGenerator, Hardfork, NodeConfig, CHAIN_ID,
 };
 use alloy_genesis::Genesis;
-use alloy_primitives::{utils::Unit, B256, U256};
+use alloy_primitives::{utils::Unit, Bytes, B256, U256};
 use alloy_signer_local::coins_bip39::{English, Mnemonic};
 use anvil_server::ServerConfig;
 use clap::Parser;
 use core::fmt;
+use eyre::WrapErr;
 use foundry_config::{Chain, Config, FigmentProviders};
+use foundry_evm::rng::RandomSource;
 use futures::FutureExt;
 use rand::{rngs::StdRng, SeedableRng};
 use std::{
@@ -53,6 +56,7 @@ pub struct NodeArgs {
     /// Automatically generates a BIP39 mnemonic phrase, and derives accounts from it.
     /// Cannot be used with other `mnemonic` options.
     /// You can specify the number of words you want in the mnemonic.
+    /// The mnemonic is derived from the `FOUNDRY_SEED` environment variable, if set.
     /// [default: 12]
     #[arg(long, conflicts_with_all = &["mnemonic", "mnemonic_seed"], default_missing_value = "12", num_args(0..=1))]
     pub mnemonic_random: Option<usize>,
@@ -98,6 +102,11 @@ pub struct NodeArgs {
     #[arg(long, visible_alias = "no-mine", conflicts_with = "block_time")]
     pub no_mining: bool,
 
+    /// Mine transactions as soon as they're ready, and mine an empty block whenever no block was
+    /// mined for `--block-time` seconds.
+    #[arg(long, requires = "block_time")]
+    pub mixed_mining: bool,
+
     /// The hosts the server will listen on.
     #[arg(
         long,
@@ -166,6 +175,12 @@ pub struct NodeArgs {
     #[arg(long)]
     pub transaction_block_keeper: Option<usize>,
 
+    /// Persist all mined blocks, transactions and logs to the SQLite database at the given path.
+    ///
+    /// The database can be queried with read-only SQL statements via `anvil_query`.
+    #[arg(long, value_name = "PATH")]
+    pub sqlite_db: Option<PathBuf>,
+
     #[command(flatten)]
     pub evm_opts: AnvilEvmArgs,
 
@@ -193,13 +208,20 @@ impl NodeArgs {
             self.evm_opts.compute_units_per_second
         };
 
+        let mut server_config = self.server_config;
+        if server_config.sourcify.is_none() && !server_config.no_sourcify {
+            server_config.sourcify = find_project_root();
+        }
+
         NodeConfig::default()
             .with_gas_limit(self.evm_opts.gas_limit)
             .disable_block_gas_limit(self.evm_opts.disable_block_gas_limit)
+            .with_strict(self.evm_opts.strict)
             .with_gas_price(self.evm_opts.gas_price)
             .with_hardfork(self.hardfork)
             .with_blocktime(self.block_time)
             .with_no_mining(self.no_mining)
+            .with_mixed_mining(self.mixed_mining)
             .with_account_generator(self.account_generator())
             .with_genesis_balance(genesis_balance)
             .with_genesis_timestamp(self.timestamp)
@@ -221,8 +243,12 @@ impl NodeArgs {
             .fork_compute_units_per_second(compute_units_per_second)
             .with_eth_rpc_url(self.evm_opts.fork_url.map(|fork| fork.url))
             .with_base_fee(self.evm_opts.block_base_fee_per_gas)
+            .with_base_fee_elasticity_multiplier(self.evm_opts.base_fee_elasticity_multiplier)
+            .with_base_fee_change_denominator(self.evm_opts.base_fee_change_denominator)
+            .with_min_base_fee(self.evm_opts.min_base_fee)
+            .with_optimism_base_fee_params(self.evm_opts.optimism_base_fee_params)
             .with_storage_caching(self.evm_opts.no_storage_caching)
-            .with_server_config(self.server_config)
+            .with_server_config(server_config)
             .with_host(self.host)
             .set_silent(self.silent)
             .set_config_out(self.config_out)
@@ -236,6 +262,7 @@ impl NodeArgs {
             .set_pruned_history(self.prune_history)
             .with_init_state(self.load_state.or_else(|| self.state.and_then(|s| s.state)))
             .with_transaction_block_keeper(self.transaction_block_keeper)
+            .with_sqlite_db(self.sqlite_db)
             .with_optimism(self.evm_opts.optimism)
             .with_disable_default_create2_deployer(self.evm_opts.disable_default_create2_deployer)
             .with_slots_in_an_epoch(self.slots_in_an_epoch)
@@ -249,7 +276,15 @@ impl NodeArgs {
         if let Some(ref mnemonic) = self.mnemonic {
             gen = gen.phrase(mnemonic);
         } else if let Some(count) = self.mnemonic_random {
-            let mut rng = rand::thread_rng();
+            let random = match RandomSource::from_env() {
+                Ok(Some(random)) => random,
+                Ok(None) => RandomSource::random(),
+                Err(err) => {
+                    warn!(target: "node", %err, "generating a random mnemonic");
+                    RandomSource::random()
+                }
+            };
+            let mut rng = random.rng("anvil", &[]);
             let mnemonic = match Mnemonic::<English>::new_with_count(&mut rng, count) {
                 Ok(mnemonic) => mnemonic.to_phrase(),
                 Err(_) => DEFAULT_MNEMONIC.to_string(),
@@ -281,6 +316,14 @@ impl NodeArgs {
 
         let (api, mut handle) = crate::try_spawn(self.into_node_config()).await?;
 
+        // Only projects configuring precompiles in their foundry.toml get their shims installed.
+        if Config::find_config_file().is_some() {
+            let config = Config::load_with_providers(FigmentProviders::Anvil);
+            if !config.precompiles.is_empty() {
+                install_precompile_shims(&api, &config).await?;
+            }
+        }
+
         // sets the signal handler to gracefully shutdown.
         let mut fork = api.get_fork();
         let running = Arc::new(AtomicUsize::new(0));
@@ -486,6 +529,23 @@ pub struct AnvilEvmArgs {
     #[arg(long, value_name = "CODE_SIZE", help_heading = "Environment config")]
     pub code_size_limit: Option<usize>,
 
+    /// Enforce the validation rules of mainnet.
+    ///
+    /// Rejects the transactions a real node would reject: impersonated transactions,
+    /// transactions below the intrinsic gas, oversized transactions and init code, and
+    /// transactions sent by contracts.
+    #[arg(
+        long,
+        help_heading = "Environment config",
+        conflicts_with_all = [
+            "disable_block_gas_limit",
+            "code_size_limit",
+            "auto_impersonate",
+            "optimism",
+        ]
+    )]
+    pub strict: bool,
+
     /// The gas price.
     #[arg(long, help_heading = "Environment config")]
     pub gas_price: Option<u128>,
@@ -499,6 +559,41 @@ pub struct AnvilEvmArgs {
     )]
     pub block_base_fee_per_gas: Option<u128>,
 
+    /// The EIP-1559 elasticity multiplier, the ratio of the block gas limit to the block gas
+    /// target.
+    ///
+    /// Defaults to 2, or 6 with `--optimism-base-fee-params`.
+    #[arg(
+        long,
+        value_name = "MULTIPLIER",
+        value_parser = clap::value_parser!(u64).range(1..),
+        help_heading = "Environment config"
+    )]
+    pub base_fee_elasticity_multiplier: Option<u64>,
+
+    /// The EIP-1559 base fee change denominator: the base fee changes by at most 1/DENOMINATOR
+    /// between blocks.
+    ///
+    /// Defaults to 8, or 250 with `--optimism-base-fee-params`.
+    #[arg(
+        long,
+        value_name = "DENOMINATOR",
+        value_parser = clap::value_parser!(u64).range(1..),
+        help_heading = "Environment config"
+    )]
+    pub base_fee_change_denominator: Option<u64>,
+
+    /// The minimum base fee of the mined blocks.
+    #[arg(long, value_name = "FEE", help_heading = "Environment config")]
+    pub min_base_fee: Option<u128>,
+
+    /// Use the EIP-1559 parameters of OP Stack chains, an elasticity multiplier of 6 and a base
+    /// fee change denominator of 250, unless they are set explicitly.
+    ///
+    /// `--optimism` alone keeps the Ethereum parameters.
+    #[arg(long, help_heading = "Environment config")]
+    pub optimism_base_fee_params: bool,
+
     /// The chain ID.
     #[arg(long, alias = "chain", help_heading = "Environment config")]
     pub chain_id: Option<Chain>,
@@ -538,6 +633,47 @@ impl AnvilEvmArgs {
     }
 }
 
+/// Places the deployed code of the Solidity shims in the `precompiles` section of the project
+/// config at their addresses, reading it from the compiled artifacts of the project.
+///
+/// Precompiles implemented in Rust can only be installed when using anvil as a library, see
+/// [`NodeConfig::with_precompile_factory`], so precompiles without a shim are an error, like in
+/// `forge test`.
+async fn install_precompile_shims(api: &EthApi, config: &Config) -> eyre::Result<()> {
+    let out = config.root.0.join(&config.out);
+    for (name, precompile) in &config.precompiles {
+        let Some(shim) = &precompile.shim else {
+            eyre::bail!("precompile `{name}`: no shim set and no Rust implementation registered")
+        };
+        let code = read_deployed_code(&out, shim)
+            .wrap_err_with(|| format!("precompile `{name}`: could not read shim `{shim}`"))?;
+        api.anvil_set_code(precompile.address, code).await?;
+    }
+    Ok(())
+}
+
+/// Reads the deployed code of a contract, given by name or as `path:name`, from the artifacts in
+/// the `out` directory.
+fn read_deployed_code(out: &Path, contract: &str) -> eyre::Result<Bytes> {
+    let (file, name) = match contract.rsplit_once(':') {
+        Some((path, name)) => (Path::new(path).file_name(), name),
+        None => (None, contract),
+    };
+    let artifact = match file {
+        Some(file) => out.join(file).join(format!("{name}.json")),
+        None => std::fs::read_dir(out)?
+            .flatten()
+            .map(|entry| entry.path().join(format!("{name}.json")))
+            .find(|path| path.is_file())
+            .ok_or_else(|| eyre::eyre!("no artifact found in {}", out.display()))?,
+    };
+    let artifact: serde_json::Value = foundry_common::fs::read_json_file(&artifact)?;
+    let code = artifact["deployedBytecode"]["object"]
+        .as_str()
+        .ok_or_else(|| eyre::eyre!("`{name}` has no deployed bytecode"))?;
+    Ok(code.parse()?)
+}
+
 /// Helper type to periodically dump the state of the chain to disk
 struct PeriodicStateDumper {
     in_progress_dump: Option<Pin<Box<dyn Future<Output = ()> + Send + Sync + 'static>>>,
@@ -781,6 +917,37 @@ mod tests {
         assert!(args.is_err());
     }
 
+    #[test]
+    fn can_parse_strict() {
+        let args: NodeArgs = NodeArgs::parse_from(["anvil", "--strict"]);
+        assert!(args.evm_opts.strict);
+
+        let args = NodeArgs::try_parse_from(["anvil", "--strict", "--auto-impersonate"]);
+        assert!(args.is_err());
+    }
+
+    #[test]
+    fn can_parse_base_fee_params() {
+        let args: NodeArgs = NodeArgs::parse_from([
+            "anvil",
+            "--base-fee-elasticity-multiplier",
+            "4",
+            "--base-fee-change-denominator",
+            "50",
+            "--min-base-fee",
+            "100",
+        ]);
+        assert_eq!(args.evm_opts.base_fee_elasticity_multiplier, Some(4));
+        assert_eq!(args.evm_opts.base_fee_change_denominator, Some(50));
+        assert_eq!(args.evm_opts.min_base_fee, Some(100));
+
+        let args = NodeArgs::try_parse_from(["anvil", "--base-fee-change-denominator", "0"]);
+        assert!(args.is_err());
+
+        let args: NodeArgs = NodeArgs::parse_from(["anvil", "--optimism-base-fee-params"]);
+        assert!(args.evm_opts.optimism_base_fee_params);
+    }
+
     #[test]
     fn can_parse_host() {
         let args = NodeArgs::parse_from(["anvil"]);
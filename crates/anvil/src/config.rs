@@ -8,12 +8,13 @@ use crate::{
             mem::fork_db::ForkedDatabase,
             time::duration_since_unix_epoch,
         },
-        fees::{INITIAL_BASE_FEE, INITIAL_GAS_PRICE},
+        fees::{INITIAL_BASE_FEE, INITIAL_GAS_PRICE, OPTIMISM_BASE_FEE_PARAMS},
         pool::transactions::{PoolTransaction, TransactionOrder},
     },
     mem::{self, in_memory_db::MemDb},
     FeeManager, Hardfork, PrecompileFactory,
 };
+use alloy_eips::eip1559::BaseFeeParams;
 use alloy_genesis::Genesis;
 use alloy_network::AnyNetwork;
 use alloy_primitives::{hex, utils::Unit, BlockNumber, TxHash, U256};
@@ -47,6 +48,7 @@ use std::{
     collections::HashMap,
     fmt::Write as FmtWrite,
     fs::File,
+    io,
     net::{IpAddr, Ipv4Addr},
     path::{Path, PathBuf},
     sync::Arc,
@@ -93,10 +95,21 @@ pub struct NodeConfig {
     pub gas_limit: u128,
     /// If set to `true`, disables the block gas limit
     pub disable_block_gas_limit: bool,
+    /// If set to `true`, rejects the transactions a mainnet node would reject
+    pub strict: bool,
     /// Default gas price for all txs
     pub gas_price: Option<u128>,
     /// Default base fee
     pub base_fee: Option<u128>,
+    /// The EIP-1559 elasticity multiplier, the ratio of the block gas limit to the gas target
+    pub base_fee_elasticity_multiplier: Option<u64>,
+    /// The EIP-1559 base fee change denominator, which bounds the change of the base fee between
+    /// blocks
+    pub base_fee_change_denominator: Option<u64>,
+    /// The minimum base fee of the blocks
+    pub min_base_fee: Option<u128>,
+    /// Whether the EIP-1559 parameters default to the ones of OP Stack chains
+    pub optimism_base_fee_params: bool,
     /// Default blob excess gas and price
     pub blob_excess_gas_and_price: Option<BlobExcessGasAndPrice>,
     /// The hardfork to use
@@ -113,6 +126,9 @@ pub struct NodeConfig {
     pub block_time: Option<Duration>,
     /// Disable auto, interval mining mode uns use `MiningMode::None` instead
     pub no_mining: bool,
+    /// Mine transactions as soon as they're ready, and an empty block after `block_time` without
+    /// blocks
+    pub mixed_mining: bool,
     /// port to use for the server
     pub port: u16,
     /// maximum number of transactions in a block
@@ -177,6 +193,8 @@ pub struct NodeConfig {
     pub memory_limit: Option<u64>,
     /// Factory used by `anvil` to extend the EVM's precompiles.
     pub precompile_factory: Option<Arc<dyn PrecompileFactory>>,
+    /// The SQLite database to persist mined blocks, transactions and logs to
+    pub sqlite_db: Option<PathBuf>,
 }
 
 impl NodeConfig {
@@ -326,6 +344,7 @@ Genesis Timestamp
             wallet_description.insert("mnemonic".to_string(), phrase);
         };
 
+        let base_fee_params = self.get_base_fee_params();
         if let Some(fork) = fork {
             json!({
               "available_accounts": available_accounts,
@@ -336,6 +355,11 @@ Genesis Timestamp
               "chain_id": fork.chain_id(),
               "wallet": wallet_description,
               "base_fee": format!("{}", self.get_base_fee()),
+              "base_fee_params": {
+                "elasticity_multiplier": format!("{}", base_fee_params.elasticity_multiplier),
+                "change_denominator": format!("{}", base_fee_params.max_change_denominator),
+                "min_base_fee": format!("{}", self.get_min_base_fee()),
+              },
               "gas_price": format!("{}", self.get_gas_price()),
               "gas_limit": format!("{}", self.gas_limit),
             })
@@ -345,6 +369,11 @@ Genesis Timestamp
               "private_keys": private_keys,
               "wallet": wallet_description,
               "base_fee": format!("{}", self.get_base_fee()),
+              "base_fee_params": {
+                "elasticity_multiplier": format!("{}", base_fee_params.elasticity_multiplier),
+                "change_denominator": format!("{}", base_fee_params.max_change_denominator),
+                "min_base_fee": format!("{}", self.get_min_base_fee()),
+              },
               "gas_price": format!("{}", self.get_gas_price()),
               "gas_limit": format!("{}", self.gas_limit),
               "genesis_timestamp": format!("{}", self.get_genesis_timestamp()),
@@ -380,6 +409,7 @@ impl Default for NodeConfig {
             chain_id: None,
             gas_limit: 30_000_000,
             disable_block_gas_limit: false,
+            strict: false,
             gas_price: None,
             hardfork: None,
             signer_accounts: genesis_accounts.clone(),
@@ -389,6 +419,7 @@ impl Default for NodeConfig {
             genesis_balance: Unit::ETHER.wei().saturating_mul(U256::from(100u64)),
             block_time: None,
             no_mining: false,
+            mixed_mining: false,
             port: NODE_PORT,
             // TODO make this something dependent on block capacity
             max_transactions: 1_000,
@@ -397,6 +428,10 @@ impl Default for NodeConfig {
             fork_choice: None,
             account_generator: None,
             base_fee: None,
+            base_fee_elasticity_multiplier: None,
+            base_fee_change_denominator: None,
+            min_base_fee: None,
+            optimism_base_fee_params: false,
             blob_excess_gas_and_price: None,
             enable_tracing: true,
             enable_steps_tracing: false,
@@ -424,6 +459,7 @@ impl Default for NodeConfig {
             slots_in_an_epoch: 32,
             memory_limit: None,
             precompile_factory: None,
+            sqlite_db: None,
         }
     }
 }
@@ -435,6 +471,28 @@ impl NodeConfig {
         self.memory_limit = mems_value;
         self
     }
+    /// Returns the EIP-1559 parameters to use
+    ///
+    /// Parameters that are not set default to the ones of Ethereum, or of OP Stack chains if
+    /// opted in with [`NodeConfig::with_optimism_base_fee_params`]. `--optimism` alone keeps the
+    /// Ethereum parameters.
+    pub fn get_base_fee_params(&self) -> BaseFeeParams {
+        let defaults = if self.optimism_base_fee_params {
+            OPTIMISM_BASE_FEE_PARAMS
+        } else {
+            BaseFeeParams::ethereum()
+        };
+        BaseFeeParams::new(
+            self.base_fee_change_denominator.map_or(defaults.max_change_denominator, u128::from),
+            self.base_fee_elasticity_multiplier.map_or(defaults.elasticity_multiplier, u128::from),
+        )
+    }
+
+    /// Returns the minimum base fee to use
+    pub fn get_min_base_fee(&self) -> u128 {
+        self.min_base_fee.unwrap_or_default()
+    }
+
     /// Returns the base fee to use
     pub fn get_base_fee(&self) -> u128 {
         self.base_fee
@@ -528,6 +586,16 @@ impl NodeConfig {
         self
     }
 
+    /// Sets whether to enforce the validation rules of mainnet
+    ///
+    /// If set to `true`, transactions a mainnet node would reject are rejected as well, e.g.
+    /// impersonated transactions or transactions below the intrinsic gas
+    #[must_use]
+    pub fn with_strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
     /// Sets the gas price
     #[must_use]
     pub fn with_gas_price(mut self, gas_price: Option<u128>) -> Self {
@@ -552,6 +620,13 @@ impl NodeConfig {
         self
     }
 
+    /// Sets the SQLite database to persist mined blocks, transactions and logs to
+    #[must_use]
+    pub fn with_sqlite_db(mut self, path: Option<PathBuf>) -> Self {
+        self.sqlite_db = path;
+        self
+    }
+
     /// Sets the base fee
     #[must_use]
     pub fn with_base_fee(mut self, base_fee: Option<u128>) -> Self {
@@ -559,6 +634,34 @@ impl NodeConfig {
         self
     }
 
+    /// Sets the EIP-1559 elasticity multiplier
+    #[must_use]
+    pub fn with_base_fee_elasticity_multiplier(mut self, multiplier: Option<u64>) -> Self {
+        self.base_fee_elasticity_multiplier = multiplier;
+        self
+    }
+
+    /// Sets the EIP-1559 base fee change denominator
+    #[must_use]
+    pub fn with_base_fee_change_denominator(mut self, denominator: Option<u64>) -> Self {
+        self.base_fee_change_denominator = denominator;
+        self
+    }
+
+    /// Sets the minimum base fee
+    #[must_use]
+    pub fn with_min_base_fee(mut self, min_base_fee: Option<u128>) -> Self {
+        self.min_base_fee = min_base_fee;
+        self
+    }
+
+    /// Sets whether the EIP-1559 parameters default to the ones of OP Stack chains
+    #[must_use]
+    pub fn with_optimism_base_fee_params(mut self, optimism_base_fee_params: bool) -> Self {
+        self.optimism_base_fee_params = optimism_base_fee_params;
+        self
+    }
+
     /// Sets the init genesis (genesis.json)
     #[must_use]
     pub fn with_genesis(mut self, genesis: Option<Genesis>) -> Self {
@@ -633,6 +736,14 @@ impl NodeConfig {
         self
     }
 
+    /// If set to `true` transactions are mined right away, and the block time only applies when
+    /// idle
+    #[must_use]
+    pub fn with_mixed_mining(mut self, mixed_mining: bool) -> Self {
+        self.mixed_mining = mixed_mining;
+        self
+    }
+
     /// Sets the slots in an epoch
     #[must_use]
     pub fn with_slots_in_an_epoch(mut self, slots_in_an_epoch: u64) -> Self {
@@ -873,7 +984,7 @@ impl NodeConfig {
     /// [Backend](mem::Backend)
     ///
     /// *Note*: only memory based backend for now
-    pub(crate) async fn setup(&mut self) -> mem::Backend {
+    pub(crate) async fn setup(&mut self) -> io::Result<mem::Backend> {
         // configure the revm environment
 
         let mut cfg =
@@ -907,6 +1018,8 @@ impl NodeConfig {
             self.get_base_fee(),
             self.get_gas_price(),
             self.get_blob_excess_gas_and_price(),
+            self.get_base_fee_params(),
+            self.get_min_base_fee(),
         );
 
         let (db, fork): (Arc<tokio::sync::RwLock<Box<dyn Db>>>, Option<ClientFork>) =
@@ -950,7 +1063,7 @@ impl NodeConfig {
             self.block_time,
             Arc::new(tokio::sync::RwLock::new(self.clone())),
         )
-        .await;
+        .await?;
 
         // Writes the default create2 deployer to the backend,
         // if the option is not disabled and we are not forking.
@@ -965,7 +1078,7 @@ impl NodeConfig {
             backend.load_state(state).await.expect("Failed to load init state");
         }
 
-        backend
+        Ok(backend)
     }
 
     /// Configures everything related to forking based on the passed `eth_rpc_url`:
@@ -8,9 +8,10 @@ use crate::{
         backend::{
             db::SerializableState,
             mem::{MIN_CREATE_GAS, MIN_TRANSACTION_GAS},
-            notifications::NewBlockNotifications,
+            notifications::{NewBlockNotifications, StateDiffNotifications},
             validate::TransactionValidator,
         },
+        debugger::{DebugSession, DebugSessions},
         error::{
             BlockchainError, FeeHistoryError, InvalidTransactionError, Result, ToRpcResponseResult,
         },
@@ -18,6 +19,7 @@ use crate::{
         macros::node_info,
         miner::FixedBlockTimeMiner,
         pool::{
+            ordering::OrderingConstraint,
             transactions::{
                 to_marker, PoolTransaction, TransactionOrder, TransactionPriority, TxMarker,
             },
@@ -33,9 +35,10 @@ use crate::{
 };
 use alloy_consensus::{transaction::eip4844::TxEip4844Variant, TxEnvelope};
 use alloy_dyn_abi::TypedData;
-use alloy_eips::eip2718::Encodable2718;
+use alloy_eips::{eip1559::BaseFeeParams, eip2718::Encodable2718};
 use alloy_network::eip2718::Decodable2718;
-use alloy_primitives::{Address, Bytes, TxHash, TxKind, B256, B64, U256, U64};
+use alloy_primitives::{hex, Address, Bytes, TxHash, TxKind, B256, B64, U256, U64};
+use alloy_rlp::Decodable;
 use alloy_rpc_types::{
     anvil::{
         ForkedNetwork, Forking, Metadata, MineOptions, NodeEnvironment, NodeForkConfig, NodeInfo,
@@ -43,7 +46,7 @@ use alloy_rpc_types::{
     request::TransactionRequest,
     state::StateOverride,
     trace::{
-        geth::{DefaultFrame, GethDebugTracingOptions, GethDefaultTracingOptions, GethTrace},
+        geth::{GethDebugTracingCallOptions, GethDebugTracingOptions, GethTrace},
         parity::LocalizedTransactionTrace,
     },
     txpool::{TxpoolContent, TxpoolInspect, TxpoolInspectSummary, TxpoolStatus},
@@ -55,14 +58,17 @@ use alloy_serde::WithOtherFields;
 use alloy_transport::TransportErrorKind;
 use anvil_core::{
     eth::{
-        block::BlockInfo,
+        block::{self, BlockInfo},
         transaction::{
-            transaction_request_to_typed, PendingTransaction, ReceiptResponse, TypedTransaction,
-            TypedTransactionRequest,
+            optimism::DepositTransactionRequest, transaction_request_to_typed, PendingTransaction,
+            ReceiptResponse, TypedTransaction, TypedTransactionRequest,
         },
         EthRequest,
     },
-    types::Work,
+    types::{
+        BaseFeeParamsUpdate, DebugBreakpoint, DebugState, DepositRequest,
+        ImpersonateContractRequest, MiningModeConfig, ReorgTransaction, Work,
+    },
 };
 use anvil_rpc::{error::RpcError, response::ResponseResult};
 use foundry_common::provider::ProviderBuilder;
@@ -77,11 +83,24 @@ use foundry_evm::{
 };
 use futures::channel::{mpsc::Receiver, oneshot};
 use parking_lot::RwLock;
-use std::{collections::HashSet, future::Future, sync::Arc, time::Duration};
+use std::{
+    collections::{HashMap, HashSet},
+    future::Future,
+    sync::Arc,
+    time::Duration,
+};
 
 /// The client version: `anvil/v{major}.{minor}.{patch}`
 pub const CLIENT_VERSION: &str = concat!("anvil/v", env!("CARGO_PKG_VERSION"));
 
+/// The code injected by `anvil_impersonateContract` to forward calls.
+///
+/// Calls the address in the first 20 bytes of the calldata with the value in the next 32 bytes and
+/// the rest of the calldata, and returns or reverts with the result.
+const FORWARDER_CODE: &[u8] = &hex!(
+    "603436038060346000376000600082600060143560003560601c5af13d600060003e6029573d6000fd5b3d6000f3"
+);
+
 /// The entry point for executing eth api RPC call - The Eth RPC interface.
 ///
 /// This type is cheap to clone and can be used concurrently
@@ -115,6 +134,8 @@ pub struct EthApi {
     net_listening: bool,
     /// The instance ID. Changes on every reset.
     instance_id: Arc<RwLock<B256>>,
+    /// The open sessions of the `anvil_debug*` step debugger
+    debug_sessions: DebugSessions,
 }
 
 impl EthApi {
@@ -144,6 +165,7 @@ impl EthApi {
             net_listening: true,
             transaction_order: Arc::new(RwLock::new(transactions_order)),
             instance_id: Arc::new(RwLock::new(B256::random())),
+            debug_sessions: Default::default(),
         }
     }
 
@@ -297,6 +319,12 @@ impl EthApi {
             EthRequest::AutoImpersonateAccount(enable) => {
                 self.anvil_auto_impersonate_account(enable).await.to_rpc_result()
             }
+            EthRequest::ImpersonateContract(request) => {
+                self.anvil_impersonate_contract(request).await.to_rpc_result()
+            }
+            EthRequest::DepositTransaction(request) => {
+                self.anvil_deposit_transaction(request).await.to_rpc_result()
+            }
             EthRequest::GetAutoMine(()) => self.anvil_get_auto_mine().to_rpc_result(),
             EthRequest::Mine(blocks, interval) => {
                 self.anvil_mine(blocks, interval).await.to_rpc_result()
@@ -307,6 +335,7 @@ impl EthApi {
             EthRequest::SetIntervalMining(interval) => {
                 self.anvil_set_interval_mining(interval).to_rpc_result()
             }
+            EthRequest::SetMiningMode(mode) => self.anvil_set_mining_mode(mode).to_rpc_result(),
             EthRequest::DropTransaction(tx) => {
                 self.anvil_drop_transaction(tx).await.to_rpc_result()
             }
@@ -337,8 +366,17 @@ impl EthApi {
             EthRequest::SetNextBlockBaseFeePerGas(gas) => {
                 self.anvil_set_next_block_base_fee_per_gas(gas).await.to_rpc_result()
             }
+            EthRequest::SetBaseFeeParams(params) => {
+                self.anvil_set_base_fee_params(params).await.to_rpc_result()
+            }
             EthRequest::DumpState(_) => self.anvil_dump_state().await.to_rpc_result(),
             EthRequest::LoadState(buf) => self.anvil_load_state(buf).await.to_rpc_result(),
+            EthRequest::ExportChain(_) => self.anvil_export_chain().await.to_rpc_result(),
+            EthRequest::ImportChain(buf) => self.anvil_import_chain(buf).await.to_rpc_result(),
+            EthRequest::RewindToBlock(number) => {
+                self.anvil_rewind_to_block(number).await.to_rpc_result()
+            }
+            EthRequest::Reorg(depth, txs) => self.anvil_reorg(depth, txs).await.to_rpc_result(),
             EthRequest::NodeInfo(_) => self.anvil_node_info().await.to_rpc_result(),
             EthRequest::AnvilMetadata(_) => self.anvil_metadata().await.to_rpc_result(),
             EthRequest::EvmSnapshot(_) => self.evm_snapshot().await.to_rpc_result(),
@@ -431,6 +469,29 @@ impl EthApi {
             EthRequest::RemovePoolTransactions(address) => {
                 self.anvil_remove_pool_transactions(address).await.to_rpc_result()
             }
+            EthRequest::NextBlockTransactions() => {
+                self.anvil_next_block_transactions().to_rpc_result()
+            }
+            EthRequest::PlaceTransactionBefore(first, then) => {
+                self.anvil_place_transaction_before(first, then).to_rpc_result()
+            }
+            EthRequest::BundleTransactions(hashes) => {
+                self.anvil_bundle_transactions(hashes).to_rpc_result()
+            }
+            EthRequest::ClearOrderingConstraints() => {
+                self.anvil_clear_ordering_constraints().to_rpc_result()
+            }
+            EthRequest::Query(sql) => self.anvil_query(sql).to_rpc_result(),
+            EthRequest::DebugStart(request, block) => {
+                self.anvil_debug_start(request, block).await.to_rpc_result()
+            }
+            EthRequest::DebugStep(id, count) => self.anvil_debug_step(id, count).to_rpc_result(),
+            EthRequest::DebugStack(id) => self.anvil_debug_stack(id).to_rpc_result(),
+            EthRequest::DebugMemory(id) => self.anvil_debug_memory(id).to_rpc_result(),
+            EthRequest::DebugContinue(id, breakpoint) => {
+                self.anvil_debug_continue(id, breakpoint).to_rpc_result()
+            }
+            EthRequest::DebugStop(id) => self.anvil_debug_stop(id).to_rpc_result(),
         }
     }
 
@@ -465,7 +526,7 @@ impl EthApi {
     async fn block_request(&self, block_number: Option<BlockId>) -> Result<BlockRequest> {
         let block_request = match block_number {
             Some(BlockId::Number(BlockNumber::Pending)) => {
-                let pending_txs = self.pool.ready_transactions().collect();
+                let pending_txs = self.pool.block_transactions();
                 BlockRequest::Pending(pending_txs)
             }
             _ => {
@@ -1520,8 +1581,8 @@ impl EthApi {
         &self,
         request: WithOtherFields<TransactionRequest>,
         block_number: Option<BlockId>,
-        opts: GethDefaultTracingOptions,
-    ) -> Result<DefaultFrame> {
+        opts: GethDebugTracingCallOptions,
+    ) -> Result<GethTrace> {
         node_info!("debug_traceCall");
         let block_request = self.block_request(block_number).await?;
         let fees = FeeDetails::new(
@@ -1582,6 +1643,123 @@ impl EthApi {
         Ok(())
     }
 
+    /// Executes a call as a contract, e.g. to act as the timelock of a protocol without going
+    /// through its governance.
+    ///
+    /// The code of the contract is replaced for the duration of a transaction to it, so the call
+    /// runs with the address, balance and storage of the contract. Either `code` is run with `data`
+    /// as calldata, or a forwarder calls `to` with `data` and `value` from the contract. The
+    /// transaction is mined in a block of its own, after which the original code is restored.
+    ///
+    /// Handler for ETH RPC call: `anvil_impersonateContract`
+    pub async fn anvil_impersonate_contract(
+        &self,
+        request: ImpersonateContractRequest,
+    ) -> Result<TxHash> {
+        node_info!("anvil_impersonateContract");
+        let ImpersonateContractRequest { address, from, code, to, data, value, gas } = request;
+
+        let (code, input) = match (code, to) {
+            (Some(code), None) if value.is_none() => (code, data),
+            (None, Some(to)) => {
+                let value = value.unwrap_or_default().to_be_bytes::<32>();
+                (Bytes::from_static(FORWARDER_CODE), [&to[..], &value, &data].concat().into())
+            }
+            _ => {
+                return Err(RpcError::invalid_params(
+                    "either `code` or `to` must be set, and `value` only with `to`",
+                )
+                .into())
+            }
+        };
+
+        let request = WithOtherFields::new(TransactionRequest {
+            from,
+            to: Some(TxKind::Call(address)),
+            input: input.into(),
+            gas,
+            ..Default::default()
+        });
+
+        let original = self.backend.get_code(address, None).await?;
+        self.backend.set_code(address, code).await?;
+        let result = self.mine_transaction(request).await;
+        self.backend.set_code(address, original).await?;
+        result
+    }
+
+    /// Adds an OP-stack deposit transaction to the pool, as if it was deposited on L1 and picked up
+    /// by the sequencer, so that bridge flows can be tested without running L1 and a sequencer.
+    ///
+    /// The deposit is executed with deposit semantics: `mint` is credited to `from` before the
+    /// execution, no fee is charged and `from` does not need to be signed for. Requires the node to
+    /// run with `--optimism`.
+    ///
+    /// Without a `sourceHash`, it is derived like the source hash of a user deposit, with the
+    /// latest block hash in place of the L1 block hash and the pending nonce of `from` in place of
+    /// the index of the L1 log.
+    ///
+    /// Handler for ETH RPC call: `anvil_depositTransaction`
+    pub async fn anvil_deposit_transaction(&self, request: DepositRequest) -> Result<TxHash> {
+        node_info!("anvil_depositTransaction");
+        self.backend.ensure_op_deposits_active()?;
+        let DepositRequest { from, to, mint, value, gas_limit, is_system_tx, data, source_hash } =
+            request;
+
+        let (nonce, on_chain_nonce) = self.request_nonce(&Default::default(), from).await?;
+        let source_hash = source_hash.unwrap_or_else(|| {
+            let log_index = U256::from(nonce).to_be_bytes::<32>();
+            let deposit_id =
+                alloy_primitives::keccak256([&self.backend.best_hash()[..], &log_index].concat());
+            alloy_primitives::keccak256([&[0u8; 32][..], &deposit_id[..]].concat())
+        });
+        let request = TypedTransactionRequest::Deposit(DepositTransactionRequest {
+            source_hash,
+            from,
+            kind: to.map_or(TxKind::Create, TxKind::Call),
+            mint,
+            value,
+            gas_limit,
+            is_system_tx,
+            input: data,
+        });
+
+        let transaction = self.sign_request(&from, request)?;
+        let pending_transaction = PendingTransaction::new(transaction)?;
+        self.backend.validate_pool_transaction(&pending_transaction).await?;
+
+        let requires = required_marker(nonce, on_chain_nonce, from);
+        let provides = vec![to_marker(nonce, from)];
+        self.add_pending_transaction(pending_transaction, requires, provides)
+    }
+
+    /// Mines the transaction in a block of its own, bypassing the pool.
+    async fn mine_transaction(
+        &self,
+        request: WithOtherFields<TransactionRequest>,
+    ) -> Result<TxHash> {
+        let best_number = self.backend.best_number();
+        let pending_transaction =
+            self.build_transaction_at(request, best_number, &mut HashMap::new()).await?;
+        self.backend.validate_pool_transaction(&pending_transaction).await?;
+
+        let hash = *pending_transaction.hash();
+        let priority = self.transaction_priority(&pending_transaction.transaction);
+        let transaction =
+            PoolTransaction { pending_transaction, requires: vec![], provides: vec![], priority };
+        let outcome = self.backend.mine_block(vec![Arc::new(transaction)]).await;
+        let included = outcome.included.iter().any(|tx| tx.hash() == hash);
+        self.pool.on_mined_block(outcome);
+
+        if !included {
+            return Err(RpcError::invalid_params(format!(
+                "transaction {hash:?} was invalid and dropped"
+            ))
+            .into())
+        }
+        Ok(hash)
+    }
+
     /// Returns true if auto mining is enabled, and false.
     ///
     /// Handler for ETH RPC call: `anvil_getAutomine`
@@ -1652,6 +1830,33 @@ impl EthApi {
         Ok(())
     }
 
+    /// Switches the mining behavior to the given mode
+    ///
+    /// Handler for RPC call: `anvil_setMiningMode`
+    pub fn anvil_set_mining_mode(&self, mode: MiningModeConfig) -> Result<()> {
+        node_info!("anvil_setMiningMode");
+        let mining_mode = match mode {
+            MiningModeConfig::None => MiningMode::None,
+            MiningModeConfig::Auto => MiningMode::instant(1_000, self.pool.add_ready_listener()),
+            MiningModeConfig::Interval { block_time } | MiningModeConfig::Mixed { block_time } => {
+                if block_time == 0 {
+                    return Err(
+                        RpcError::invalid_params("block time must be greater than zero").into()
+                    );
+                }
+                let block_time = Duration::from_secs(block_time);
+                self.backend.update_interval_mine_block_time(block_time);
+                if matches!(mode, MiningModeConfig::Mixed { .. }) {
+                    MiningMode::mixed(1_000, self.pool.add_ready_listener(), block_time)
+                } else {
+                    MiningMode::interval(block_time)
+                }
+            }
+        };
+        self.miner.set_mining_mode(mining_mode);
+        Ok(())
+    }
+
     /// Removes transactions from the pool
     ///
     /// Handler for RPC call: `anvil_dropTransaction`
@@ -1771,6 +1976,37 @@ impl EthApi {
         Ok(())
     }
 
+    /// Updates the EIP-1559 parameters used to calculate the base fee of the next blocks.
+    ///
+    /// Handler for RPC call: `anvil_setBaseFeeParams`
+    pub async fn anvil_set_base_fee_params(&self, params: BaseFeeParamsUpdate) -> Result<()> {
+        node_info!("anvil_setBaseFeeParams");
+        if !self.backend.is_eip1559() {
+            return Err(RpcError::invalid_params(
+                "anvil_setBaseFeeParams is only supported when EIP-1559 is active",
+            )
+            .into());
+        }
+        if params.elasticity_multiplier == Some(0) || params.base_fee_change_denominator == Some(0)
+        {
+            return Err(RpcError::invalid_params(
+                "the elasticity multiplier and base fee change denominator must be non-zero",
+            )
+            .into());
+        }
+
+        let fees = self.backend.fees();
+        let current = fees.base_fee_params();
+        fees.set_base_fee_params(BaseFeeParams::new(
+            params.base_fee_change_denominator.map_or(current.max_change_denominator, u128::from),
+            params.elasticity_multiplier.map_or(current.elasticity_multiplier, u128::from),
+        ));
+        if let Some(min_base_fee) = params.min_base_fee {
+            fees.set_min_base_fee(min_base_fee);
+        }
+        Ok(())
+    }
+
     /// Sets the coinbase address.
     ///
     /// Handler for RPC call: `anvil_setCoinbase`
@@ -1803,6 +2039,206 @@ impl EthApi {
         self.backend.load_state_bytes(buf).await
     }
 
+    /// Exports all blocks of the chain as a stream of RLP encoded blocks, which can be imported
+    /// into other execution clients with `geth import` or into another node with
+    /// `anvil_importChain`.
+    ///
+    /// **Note**: impersonated transactions are not signed by their sender and are rejected by
+    /// other clients.
+    ///
+    /// Handler for RPC call: `anvil_exportChain`
+    pub async fn anvil_export_chain(&self) -> Result<Bytes> {
+        node_info!("anvil_exportChain");
+        Ok(self.backend.export_chain())
+    }
+
+    /// Imports a stream of RLP encoded blocks, as written by `geth export` or
+    /// `anvil_exportChain`, by mining a block with the same transactions, beneficiary and
+    /// timestamp for each of them. Blocks up to the current block, e.g. the genesis, are skipped.
+    ///
+    /// Returns the number of imported blocks.
+    ///
+    /// Handler for RPC call: `anvil_importChain`
+    pub async fn anvil_import_chain(&self, buf: Bytes) -> Result<u64> {
+        node_info!("anvil_importChain");
+
+        let coinbase = self.backend.coinbase();
+        let result = self.import_blocks(&buf).await;
+        self.backend.set_coinbase(coinbase);
+        result
+    }
+
+    async fn import_blocks(&self, mut buf: &[u8]) -> Result<u64> {
+        let mut imported = 0;
+        while !buf.is_empty() {
+            let block = block::Block::decode(&mut buf)
+                .map_err(|err| RpcError::invalid_params(format!("invalid block: {err}")))?;
+            let number = block.header.number;
+            let best_number = self.backend.best_number();
+            if number <= best_number {
+                continue
+            }
+            if number != best_number + 1 {
+                return Err(RpcError::invalid_params(format!(
+                    "block {number} does not follow the current block {best_number}"
+                ))
+                .into())
+            }
+
+            let transactions = block
+                .transactions
+                .into_iter()
+                .map(|tx| {
+                    let pending_transaction = PendingTransaction::from_maybe_impersonated(tx)?;
+                    Ok(Arc::new(PoolTransaction {
+                        pending_transaction,
+                        requires: vec![],
+                        provides: vec![],
+                        priority: TransactionPriority(0),
+                    }))
+                })
+                .collect::<Result<Vec<_>>>()?;
+
+            self.backend.set_coinbase(block.header.beneficiary);
+            self.backend.time().set_next_block_timestamp(block.header.timestamp)?;
+            if let Some(base_fee) = block.header.base_fee_per_gas {
+                self.backend.set_base_fee(base_fee);
+            }
+
+            let outcome = self.backend.mine_block(transactions).await;
+            if let Some(tx) = outcome.invalid.first() {
+                return Err(RpcError::invalid_params(format!(
+                    "transaction {} of block {number} is invalid",
+                    tx.hash()
+                ))
+                .into())
+            }
+            self.pool.on_mined_block(outcome);
+            imported += 1;
+        }
+        Ok(imported)
+    }
+
+    /// Rolls the chain back to the given block, discarding all later blocks along with their
+    /// transactions and state. The discarded transactions are not added back to the pool.
+    ///
+    /// Handler for RPC call: `anvil_rewindToBlock`
+    pub async fn anvil_rewind_to_block(&self, number: U256) -> Result<()> {
+        node_info!("anvil_rewindToBlock");
+        self.backend.rewind_to_block(number.saturating_to()).await
+    }
+
+    /// Simulates a reorg: rolls the chain back by `depth` blocks and mines `depth` new blocks in
+    /// their place, each including the transactions paired with its index.
+    ///
+    /// Transaction requests without a nonce are assigned the next nonce of their sender on top of
+    /// the common ancestor.
+    ///
+    /// Handler for RPC call: `anvil_reorg`
+    pub async fn anvil_reorg(
+        &self,
+        depth: U256,
+        transactions: Vec<(ReorgTransaction, u64)>,
+    ) -> Result<()> {
+        node_info!("anvil_reorg");
+
+        let depth = depth.saturating_to::<u64>();
+        let best_number = self.backend.best_number();
+        if depth == 0 || depth > best_number {
+            return Err(RpcError::invalid_params(format!(
+                "reorg depth must be between 1 and the current block {best_number}"
+            ))
+            .into())
+        }
+        let common_number = best_number - depth;
+
+        // build all transactions before touching the chain, so that an invalid one leaves it as is
+        let mut blocks = vec![Vec::new(); depth as usize];
+        let mut nonces = HashMap::new();
+        for (transaction, index) in transactions {
+            let Some(block) = blocks.get_mut(index as usize) else {
+                return Err(RpcError::invalid_params(format!(
+                    "block index {index} exceeds the reorg depth {depth}"
+                ))
+                .into())
+            };
+            let pending_transaction = match transaction {
+                ReorgTransaction::Raw(tx) => {
+                    let transaction = TypedTransaction::decode_2718(&mut tx.as_ref())
+                        .map_err(|_| BlockchainError::FailedToDecodeSignedTransaction)?;
+                    self.ensure_typed_transaction_supported(&transaction)?;
+                    PendingTransaction::new(transaction)?
+                }
+                ReorgTransaction::Request(request) => {
+                    self.build_transaction_at(request, common_number, &mut nonces).await?
+                }
+            };
+            let priority = self.transaction_priority(&pending_transaction.transaction);
+            block.push(Arc::new(PoolTransaction {
+                pending_transaction,
+                requires: vec![],
+                provides: vec![],
+                priority,
+            }));
+        }
+
+        self.backend.rewind_to_block(common_number).await?;
+
+        let mut invalid = Vec::new();
+        for transactions in blocks {
+            let outcome = self.backend.mine_block(transactions).await;
+            invalid.extend(outcome.invalid.iter().map(|tx| *tx.hash()));
+            self.pool.on_mined_block(outcome);
+        }
+        if !invalid.is_empty() {
+            return Err(RpcError::invalid_params(format!(
+                "the reorg was applied but these transactions were invalid and dropped: {invalid:?}"
+            ))
+            .into())
+        }
+        Ok(())
+    }
+
+    /// Signs a transaction request, filling its nonce and gas limit from the state at the given
+    /// block.
+    async fn build_transaction_at(
+        &self,
+        mut request: WithOtherFields<TransactionRequest>,
+        common_number: u64,
+        nonces: &mut HashMap<Address, u64>,
+    ) -> Result<PendingTransaction> {
+        let from = request.from.map(Ok).unwrap_or_else(|| {
+            self.accounts()?.first().cloned().ok_or(BlockchainError::NoSignerAvailable)
+        })?;
+        let block = Some(BlockId::number(common_number));
+        let nonce = match request.nonce.or_else(|| nonces.get(&from).copied()) {
+            Some(nonce) => nonce,
+            None => self.get_transaction_count(from, block).await?,
+        };
+        nonces.insert(from, nonce + 1);
+
+        if request.gas.is_none() {
+            // estimate if not provided
+            if let Ok(gas) = self.estimate_gas(request.clone(), block, None).await {
+                request.gas = Some(gas.to());
+            }
+        }
+
+        let request = self.build_typed_tx_request(request, nonce)?;
+
+        // if the sender is currently impersonated we need to "bypass" signing
+        if self.is_impersonated(from) {
+            let bypass_signature = self.backend.cheats().bypass_signature();
+            let transaction = sign::build_typed_transaction(request, bypass_signature)?;
+            self.ensure_typed_transaction_supported(&transaction)?;
+            Ok(PendingTransaction::with_impersonated(transaction, from))
+        } else {
+            let transaction = self.sign_request(&from, request)?;
+            self.ensure_typed_transaction_supported(&transaction)?;
+            Ok(PendingTransaction::new(transaction)?)
+        }
+    }
+
     /// Retrieves the Anvil node configuration params.
     ///
     /// Handler for RPC call: `anvil_nodeInfo`
@@ -1872,6 +2308,155 @@ impl EthApi {
         Ok(())
     }
 
+    /// Returns the hashes of the transactions the next block includes, in order.
+    ///
+    /// Handler for RPC call: `anvil_nextBlockTransactions`
+    pub fn anvil_next_block_transactions(&self) -> Result<Vec<TxHash>> {
+        node_info!("anvil_nextBlockTransactions");
+        Ok(self.pool.block_transactions().iter().map(|tx| tx.hash()).collect())
+    }
+
+    /// Places the `first` transaction before the `then` transaction in upcoming blocks.
+    ///
+    /// The transactions don't have to be in the pool yet: `then` is held back until `first` can
+    /// be included.
+    ///
+    /// Handler for RPC call: `anvil_placeTransactionBefore`
+    pub fn anvil_place_transaction_before(&self, first: TxHash, then: TxHash) -> Result<()> {
+        node_info!("anvil_placeTransactionBefore");
+        if first == then {
+            return Err(RpcError::invalid_params("cannot place a transaction before itself").into())
+        }
+        self.pool.add_ordering_constraint(OrderingConstraint::Before { first, then });
+        Ok(())
+    }
+
+    /// Includes the transactions together and back-to-back in upcoming blocks, in the given
+    /// order.
+    ///
+    /// None of the transactions is included until all of them can be.
+    ///
+    /// Handler for RPC call: `anvil_bundleTransactions`
+    pub fn anvil_bundle_transactions(&self, hashes: Vec<TxHash>) -> Result<()> {
+        node_info!("anvil_bundleTransactions");
+        if hashes.is_empty() {
+            return Err(RpcError::invalid_params("bundle has no transactions").into())
+        }
+        self.pool.add_ordering_constraint(OrderingConstraint::Bundle(hashes));
+        Ok(())
+    }
+
+    /// Removes all the constraints on the order of the transactions of upcoming blocks.
+    ///
+    /// Handler for RPC call: `anvil_clearOrderingConstraints`
+    pub fn anvil_clear_ordering_constraints(&self) -> Result<()> {
+        node_info!("anvil_clearOrderingConstraints");
+        self.pool.clear_ordering_constraints();
+        Ok(())
+    }
+
+    /// Executes a read-only SQL query against the SQLite database mined blocks are persisted to,
+    /// returning the rows as objects keyed by column name
+    ///
+    /// Handler for RPC call: `anvil_query`
+    pub fn anvil_query(
+        &self,
+        sql: String,
+    ) -> Result<Vec<serde_json::Map<String, serde_json::Value>>> {
+        node_info!("anvil_query");
+        let Some(db) = self.backend.sqlite_db() else {
+            return Err(RpcError::invalid_params(
+                "no SQLite database configured, start anvil with `--sqlite-db <PATH>`",
+            )
+            .into())
+        };
+        db.query(&sql).map_err(|err| RpcError::invalid_params(err.to_string()).into())
+    }
+
+    /// Executes the call without committing it, and starts a step debugger session over its
+    /// execution, at its first step.
+    ///
+    /// The session keeps the stack and memory of every step, so that external debuggers can walk
+    /// through the execution with `anvil_debugStep` and `anvil_debugContinue`.
+    ///
+    /// Handler for RPC call: `anvil_debugStart`
+    pub async fn anvil_debug_start(
+        &self,
+        request: WithOtherFields<TransactionRequest>,
+        block_number: Option<BlockId>,
+    ) -> Result<DebugState> {
+        node_info!("anvil_debugStart");
+        let block_request = self.block_request(block_number).await?;
+        let fees = FeeDetails::new(
+            request.gas_price,
+            request.max_fee_per_gas,
+            request.max_priority_fee_per_gas,
+            request.max_fee_per_blob_gas,
+        )?
+        .or_zero_fees();
+
+        let nodes = self.backend.debug_call(request, fees, Some(block_request)).await?;
+        let session = DebugSession::new(nodes)
+            .ok_or_else(|| RpcError::invalid_params("the call did not execute any code"))?;
+        Ok(self.debug_sessions.insert(session))
+    }
+
+    /// Moves a debugger session forward by `count` steps, one by default.
+    ///
+    /// Handler for RPC call: `anvil_debugStep`
+    pub fn anvil_debug_step(&self, id: U256, count: Option<usize>) -> Result<DebugState> {
+        node_info!("anvil_debugStep");
+        self.with_debug_session(id, |session| {
+            session.step(count.unwrap_or(1));
+            session.state(id)
+        })
+    }
+
+    /// Returns the stack of the current step of a debugger session, from bottom to top.
+    ///
+    /// Handler for RPC call: `anvil_debugStack`
+    pub fn anvil_debug_stack(&self, id: U256) -> Result<Vec<U256>> {
+        node_info!("anvil_debugStack");
+        self.with_debug_session(id, |session| session.stack())
+    }
+
+    /// Returns the memory of the current step of a debugger session.
+    ///
+    /// Handler for RPC call: `anvil_debugMemory`
+    pub fn anvil_debug_memory(&self, id: U256) -> Result<Bytes> {
+        node_info!("anvil_debugMemory");
+        self.with_debug_session(id, |session| session.memory())
+    }
+
+    /// Runs a debugger session to the next step hitting the breakpoint, or to the last step.
+    ///
+    /// Handler for RPC call: `anvil_debugContinue`
+    pub fn anvil_debug_continue(
+        &self,
+        id: U256,
+        breakpoint: Option<DebugBreakpoint>,
+    ) -> Result<DebugState> {
+        node_info!("anvil_debugContinue");
+        self.with_debug_session(id, |session| {
+            session.continue_to(breakpoint);
+            session.state(id)
+        })
+    }
+
+    /// Ends a debugger session, returning whether it existed.
+    ///
+    /// Handler for RPC call: `anvil_debugStop`
+    pub fn anvil_debug_stop(&self, id: U256) -> Result<bool> {
+        node_info!("anvil_debugStop");
+        Ok(self.debug_sessions.remove(id))
+    }
+
+    fn with_debug_session<T>(&self, id: U256, f: impl FnOnce(&mut DebugSession) -> T) -> Result<T> {
+        self.debug_sessions.with_session(id, f).ok_or_else(|| {
+            RpcError::invalid_params(format!("no debug session with id {id}")).into()
+        })
+    }
+
     /// Snapshot the state of the blockchain at the current block.
     ///
     /// Handler for RPC call: `evm_snapshot`
@@ -2439,6 +3024,11 @@ impl EthApi {
         self.backend.new_block_notifications()
     }
 
+    /// Returns a new stream that yields the state changed by every mined transaction
+    pub fn state_diff_notifications(&self) -> StateDiffNotifications {
+        self.backend.state_diff_notifications()
+    }
+
     /// Returns a new listeners for ready transactions
     pub fn new_ready_transactions(&self) -> Receiver<TxHash> {
         self.pool.add_ready_listener()
@@ -2456,7 +3046,7 @@ impl EthApi {
 
     /// Mines exactly one block
     pub async fn mine_one(&self) {
-        let transactions = self.pool.ready_transactions().collect::<Vec<_>>();
+        let transactions = self.pool.block_transactions();
         let outcome = self.backend.mine_block(transactions).await;
 
         trace!(target: "node", blocknumber = ?outcome.block_number, "mined block");
@@ -2465,14 +3055,14 @@ impl EthApi {
 
     /// Returns the pending block with tx hashes
     async fn pending_block(&self) -> Block {
-        let transactions = self.pool.ready_transactions().collect::<Vec<_>>();
+        let transactions = self.pool.block_transactions();
         let info = self.backend.pending_block(transactions).await;
         self.backend.convert_block(info.block)
     }
 
     /// Returns the full pending block with `Transaction` objects
     async fn pending_block_full(&self) -> Option<Block> {
-        let transactions = self.pool.ready_transactions().collect::<Vec<_>>();
+        let transactions = self.pool.block_transactions();
         let BlockInfo { block, transactions, receipts: _ } =
             self.backend.pending_block(transactions).await;
 
@@ -2641,6 +3231,7 @@ impl EthApi {
             TypedTransaction::EIP2930(_) => self.backend.ensure_eip2930_active(),
             TypedTransaction::EIP1559(_) => self.backend.ensure_eip1559_active(),
             TypedTransaction::EIP4844(_) => self.backend.ensure_eip4844_active(),
+            TypedTransaction::EIP7702(_) => self.backend.ensure_eip1559_active(),
             TypedTransaction::Deposit(_) => self.backend.ensure_op_deposits_active(),
             TypedTransaction::Legacy(_) => Ok(()),
         }
@@ -5,6 +5,7 @@ use alloy_consensus::Header;
 use alloy_primitives::{keccak256, Address, Bytes, B256, U256, U64};
 use alloy_rpc_types::BlockId;
 use anvil_core::eth::{block::Block, transaction::TypedTransaction};
+use flate2::read::GzDecoder;
 use foundry_common::errors::FsPathError;
 use foundry_evm::{
     backend::{DatabaseError, DatabaseResult, MemDb, RevertSnapshotAction, StateSnapshot},
@@ -16,7 +17,7 @@ use foundry_evm::{
     },
 };
 use serde::{Deserialize, Serialize};
-use std::{collections::BTreeMap, fmt, path::Path};
+use std::{collections::BTreeMap, fmt, io::Read, path::Path};
 
 /// Helper trait get access to the full state data of the database
 #[auto_impl::auto_impl(Box)]
@@ -328,13 +329,22 @@ pub struct SerializableState {
 
 impl SerializableState {
     /// Loads the `Genesis` object from the given json file path
+    ///
+    /// Gzip-compressed files, such as `forge --fork-state` snapshots, are decompressed first.
     pub fn load(path: impl AsRef<Path>) -> Result<Self, FsPathError> {
         let path = path.as_ref();
-        if path.is_dir() {
-            foundry_common::fs::read_json_file(&path.join("state.json"))
+        let path = if path.is_dir() { path.join("state.json") } else { path.to_path_buf() };
+        let bytes = foundry_common::fs::read(&path)?;
+
+        let mut decoder = GzDecoder::new(bytes.as_slice());
+        let mut decoded = Vec::new();
+        let bytes = if decoder.header().is_some() {
+            decoder.read_to_end(&mut decoded).map_err(|err| FsPathError::read(err, &path))?;
+            &decoded
         } else {
-            foundry_common::fs::read_json_file(path)
-        }
+            &bytes
+        };
+        serde_json::from_slice(bytes).map_err(|source| FsPathError::ReadJson { source, path })
     }
 
     /// This is used as the clap `value_parser` implementation
@@ -1,6 +1,10 @@
 use crate::{
     eth::{
-        backend::{db::Db, validate::TransactionValidator},
+        backend::{
+            db::Db,
+            notifications::{AccountDiff, StateDiffNotification, ValueChange},
+            validate::TransactionValidator,
+        },
         error::InvalidTransactionError,
         pool::transactions::PoolTransaction,
     },
@@ -10,7 +14,7 @@ use crate::{
 };
 use alloy_consensus::{Header, Receipt, ReceiptWithBloom};
 use alloy_eips::eip2718::Encodable2718;
-use alloy_primitives::{Bloom, BloomInput, Log, B256};
+use alloy_primitives::{Address, Bloom, BloomInput, Log, B256};
 use anvil_core::eth::{
     block::{Block, BlockInfo, PartialHeader},
     transaction::{
@@ -23,14 +27,14 @@ use foundry_evm::{
     revm::{
         interpreter::InstructionResult,
         primitives::{
-            BlockEnv, CfgEnvWithHandlerCfg, EVMError, EnvWithHandlerCfg, ExecutionResult, Output,
-            SpecId,
+            BlockEnv, CfgEnvWithHandlerCfg, EVMError, EnvWithHandlerCfg, EvmState, ExecutionResult,
+            Output, ResultAndState, SpecId,
         },
     },
     traces::CallTraceNode,
 };
-use revm::primitives::MAX_BLOB_GAS_PER_BLOCK;
-use std::sync::Arc;
+use revm::{primitives::MAX_BLOB_GAS_PER_BLOCK, DatabaseCommit};
+use std::{collections::BTreeMap, sync::Arc};
 
 /// Represents an executed transaction (transacted on the DB)
 #[derive(Debug)]
@@ -42,6 +46,8 @@ pub struct ExecutedTransaction {
     logs: Vec<Log>,
     traces: Vec<CallTraceNode>,
     nonce: u64,
+    /// The accounts changed by the transaction, if state diffs are recorded
+    state_diff: Option<BTreeMap<Address, AccountDiff>>,
 }
 
 // == impl ExecutedTransaction ==
@@ -66,6 +72,7 @@ impl ExecutedTransaction {
             TypedTransaction::EIP2930(_) => TypedReceipt::EIP2930(receipt_with_bloom),
             TypedTransaction::EIP1559(_) => TypedReceipt::EIP1559(receipt_with_bloom),
             TypedTransaction::EIP4844(_) => TypedReceipt::EIP4844(receipt_with_bloom),
+            TypedTransaction::EIP7702(_) => TypedReceipt::EIP7702(receipt_with_bloom),
             TypedTransaction::Deposit(tx) => TypedReceipt::Deposit(DepositReceipt {
                 inner: receipt_with_bloom,
                 deposit_nonce: Some(tx.nonce),
@@ -85,6 +92,8 @@ pub struct ExecutedTransactions {
     /// All transactions that were invalid at the point of their execution and were not included in
     /// the block
     pub invalid: Vec<Arc<PoolTransaction>>,
+    /// The state changed by each included transaction, if state diffs are recorded
+    pub state_diffs: Vec<StateDiffNotification>,
 }
 
 /// An executor for a series of transactions
@@ -104,6 +113,8 @@ pub struct TransactionExecutor<'a, Db: ?Sized, Validator: TransactionValidator>
     /// Cumulative blob gas used by all executed transactions
     pub blob_gas_used: u128,
     pub enable_steps_tracing: bool,
+    /// Whether to record the state changed by each transaction
+    pub record_state_diffs: bool,
     /// Precompiles to inject to the EVM.
     pub precompile_factory: Option<Arc<dyn PrecompileFactory>>,
 }
@@ -118,6 +129,7 @@ impl<'a, DB: Db + ?Sized, Validator: TransactionValidator> TransactionExecutor<'
         let mut cumulative_gas_used: u128 = 0;
         let mut invalid = Vec::new();
         let mut included = Vec::new();
+        let mut state_diffs = Vec::new();
         let gas_limit = self.block_env.gas_limit.to::<u128>();
         let parent_hash = self.parent_hash;
         let block_number = self.block_env.number.to::<u64>();
@@ -173,7 +185,15 @@ impl<'a, DB: Db + ?Sized, Validator: TransactionValidator> TransactionExecutor<'
             }
             let receipt = tx.create_receipt(&mut cumulative_gas_used);
 
-            let ExecutedTransaction { transaction, logs, out, traces, exit_reason: exit, .. } = tx;
+            let ExecutedTransaction {
+                transaction,
+                logs,
+                out,
+                traces,
+                exit_reason: exit,
+                state_diff,
+                ..
+            } = tx;
             build_logs_bloom(logs.clone(), &mut bloom);
 
             let contract_address = out.as_ref().and_then(|out| {
@@ -186,6 +206,15 @@ impl<'a, DB: Db + ?Sized, Validator: TransactionValidator> TransactionExecutor<'
             });
 
             let transaction_index = transaction_infos.len() as u64;
+            if let Some(accounts) = state_diff {
+                state_diffs.push(StateDiffNotification {
+                    block_number,
+                    block_hash: B256::ZERO,
+                    transaction_hash: transaction.hash(),
+                    transaction_index,
+                    accounts,
+                });
+            }
             let info = TransactionInfo {
                 transaction_hash: transaction.hash(),
                 transaction_index,
@@ -229,12 +258,21 @@ impl<'a, DB: Db + ?Sized, Validator: TransactionValidator> TransactionExecutor<'
         };
 
         let block = Block::new(partial_header, transactions.clone(), ommers);
+        if !state_diffs.is_empty() {
+            let block_hash = block.header.hash_slow();
+            for diff in &mut state_diffs {
+                diff.block_hash = block_hash;
+            }
+        }
         let block = BlockInfo { block, transactions: transaction_infos, receipts };
-        ExecutedTransactions { block, included, invalid }
+        ExecutedTransactions { block, included, invalid, state_diffs }
     }
 
     fn env_for(&self, tx: &PendingTransaction) -> EnvWithHandlerCfg {
         let mut tx_env = tx.to_revm_tx_env();
+        // The L1 block info the L1 data fee is computed with is not part of the env: the optimism
+        // handler loads it from the `L1Block` predeploy into the context of each transaction, and
+        // only needs the enveloped transaction to compute the fee.
         if self.cfg_env.handler_cfg.is_optimism {
             tx_env.optimism.enveloped_tx =
                 Some(alloy_rlp::encode(&tx.transaction.transaction).into());
@@ -300,12 +338,14 @@ impl<'a, 'b, DB: Db + ?Sized, Validator: TransactionValidator> Iterator
         let nonce = account.nonce;
 
         // records all call and step traces
-        let mut inspector = Inspector::default().with_tracing();
+        let mut inspector = Inspector::default().with_tracing().with_authorization_list(
+            transaction.pending_transaction.transaction.authorization_list().to_vec(),
+        );
         if self.enable_steps_tracing {
             inspector = inspector.with_steps_tracing();
         }
 
-        let exec_result = {
+        let ResultAndState { result: exec_result, state } = {
             let mut evm =
                 foundry_evm::utils::new_evm_with_inspector(&mut *self.db, env, &mut inspector);
             if let Some(factory) = &self.precompile_factory {
@@ -313,9 +353,8 @@ impl<'a, 'b, DB: Db + ?Sized, Validator: TransactionValidator> Iterator
             }
 
             trace!(target: "backend", "[{:?}] executing", transaction.hash());
-            // transact and commit the transaction
-            match evm.transact_commit() {
-                Ok(exec_result) => exec_result,
+            match evm.transact() {
+                Ok(result) => result,
                 Err(err) => {
                     warn!(target: "backend", "[{:?}] failed to execute: {:?}", transaction.hash(), err);
                     match err {
@@ -338,6 +377,9 @@ impl<'a, 'b, DB: Db + ?Sized, Validator: TransactionValidator> Iterator
                 }
             }
         };
+        let state_diff = self.record_state_diffs.then(|| state_diff(&*self.db, &state));
+        // commit the transaction
+        self.db.commit(state);
         inspector.print_logs();
 
         let (exit_reason, gas_used, out, logs) = match exec_result {
@@ -375,6 +417,7 @@ impl<'a, 'b, DB: Db + ?Sized, Validator: TransactionValidator> Iterator
             logs: logs.unwrap_or_default(),
             traces: inspector.tracer.map(|t| t.into_traces().into_nodes()).unwrap_or_default(),
             nonce,
+            state_diff,
         };
 
         Some(TransactionExecutionOutcome::Executed(tx))
@@ -390,3 +433,50 @@ fn build_logs_bloom(logs: Vec<Log>, bloom: &mut Bloom) {
         }
     }
 }
+
+/// Returns the changes the transaction made to the accounts, compared to the given database.
+fn state_diff<DB: Db + ?Sized>(db: &DB, state: &EvmState) -> BTreeMap<Address, AccountDiff> {
+    let mut diff = BTreeMap::new();
+    for (address, account) in state {
+        if !account.is_touched() {
+            continue
+        }
+        let before = db.basic_ref(*address).ok().flatten().unwrap_or_default();
+        let after = &account.info;
+        let code = if before.code_hash == after.code_hash {
+            None
+        } else {
+            let code_before = match before.code {
+                Some(code) => code.original_bytes(),
+                None => db
+                    .code_by_hash_ref(before.code_hash)
+                    .map(|code| code.original_bytes())
+                    .unwrap_or_default(),
+            };
+            let code_after =
+                after.code.as_ref().map(|code| code.original_bytes()).unwrap_or_default();
+            ValueChange::new(code_before, code_after)
+        };
+        let account_diff = AccountDiff {
+            balance: ValueChange::new(before.balance, after.balance),
+            nonce: ValueChange::new(before.nonce, after.nonce),
+            code,
+            storage: account
+                .storage
+                .iter()
+                .filter(|(_, slot)| slot.is_changed())
+                .map(|(key, slot)| {
+                    let change = ValueChange {
+                        from: B256::from(slot.original_value()),
+                        to: B256::from(slot.present_value()),
+                    };
+                    (B256::from(*key), change)
+                })
+                .collect(),
+        };
+        if !account_diff.is_empty() {
+            diff.insert(*address, account_diff);
+        }
+    }
+    diff
+}
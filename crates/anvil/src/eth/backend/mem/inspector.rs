@@ -1,14 +1,18 @@
 //! Anvil specific [`revm::Inspector`] implementation
 
 use crate::{eth::macros::node_info, revm::Database};
-use alloy_primitives::{Address, Log};
+use alloy_primitives::{Address, Bytes, Log};
 use foundry_evm::{
     call_inspectors,
     decode::decode_console_logs,
+    eip7702::{
+        apply_authorization, resolve_delegation, SignedAuthorization, PER_AUTHORIZATION_COST,
+    },
     inspectors::{LogCollector, TracingInspector},
     revm::{
         interpreter::{
-            CallInputs, CallOutcome, CreateInputs, CreateOutcome, EOFCreateInputs, Interpreter,
+            CallInputs, CallOutcome, CreateInputs, CreateOutcome, EOFCreateInputs, Gas,
+            InstructionResult, Interpreter, InterpreterResult,
         },
         primitives::U256,
         EvmContext,
@@ -23,6 +27,8 @@ pub struct Inspector {
     pub tracer: Option<TracingInspector>,
     /// collects all `console.sol` logs
     pub log_collector: LogCollector,
+    /// The authorizations of an EIP-7702 transaction, applied before its call is executed
+    pub authorization_list: Vec<SignedAuthorization>,
 }
 
 impl Inspector {
@@ -44,6 +50,53 @@ impl Inspector {
         self.tracer = Some(TracingInspector::new(TracingInspectorConfig::all()));
         self
     }
+
+    /// Configures the `Tracer` [`revm::Inspector`] with the given config.
+    pub fn with_tracing_config(mut self, config: TracingInspectorConfig) -> Self {
+        self.tracer = Some(TracingInspector::new(config));
+        self
+    }
+
+    /// Sets the authorizations to apply before executing the transaction.
+    pub fn with_authorization_list(mut self, authorization_list: Vec<SignedAuthorization>) -> Self {
+        self.authorization_list = authorization_list;
+        self
+    }
+
+    /// Applies the authorization list to the state and charges its cost from the gas of the
+    /// top-level call.
+    ///
+    /// Returns an outcome if the call runs out of gas, in which case it must not be executed.
+    fn apply_authorization_list<DB: Database>(
+        &mut self,
+        ecx: &mut EvmContext<DB>,
+        inputs: &mut CallInputs,
+    ) -> Option<CallOutcome> {
+        let authorization_list = std::mem::take(&mut self.authorization_list);
+        let cost = PER_AUTHORIZATION_COST * authorization_list.len() as u64;
+        if inputs.gas_limit < cost {
+            let mut gas = Gas::new(inputs.gas_limit);
+            gas.record_cost(inputs.gas_limit);
+            return Some(CallOutcome {
+                result: InterpreterResult {
+                    result: InstructionResult::OutOfGas,
+                    output: Bytes::new(),
+                    gas,
+                },
+                memory_offset: inputs.return_memory_offset.clone(),
+            })
+        }
+        inputs.gas_limit -= cost;
+
+        for authorization in &authorization_list {
+            // Invalid authorizations are skipped.
+            if let Err(err) = apply_authorization(&mut ecx.inner, authorization) {
+                ecx.error = Err(err);
+                break
+            }
+        }
+        None
+    }
 }
 
 impl<DB: Database> revm::Inspector<DB> for Inspector {
@@ -72,11 +125,21 @@ impl<DB: Database> revm::Inspector<DB> for Inspector {
     }
 
     fn call(&mut self, ecx: &mut EvmContext<DB>, inputs: &mut CallInputs) -> Option<CallOutcome> {
+        if ecx.journaled_state.depth() == 0 && !self.authorization_list.is_empty() {
+            if let Some(outcome) = self.apply_authorization_list(ecx, inputs) {
+                return Some(outcome);
+            }
+        }
+
         call_inspectors!(
             #[ret]
             [&mut self.tracer, Some(&mut self.log_collector)],
             |inspector| inspector.call(ecx, inputs).map(Some),
         );
+
+        if let Err(err) = resolve_delegation(ecx, inputs) {
+            ecx.error = Err(err);
+        }
         None
     }
 
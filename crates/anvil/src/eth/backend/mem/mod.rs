@@ -12,9 +12,13 @@ use crate::{
             genesis::GenesisConfig,
             mem::{
                 state::{storage_root, trie_accounts},
-                storage::MinedTransactionReceipt,
+                storage::{four_byte_frame, MinedTransactionReceipt},
             },
-            notifications::{NewBlockNotification, NewBlockNotifications},
+            notifications::{
+                NewBlockNotification, NewBlockNotifications, StateDiffNotification,
+                StateDiffNotifications,
+            },
+            sqlite::SqliteDb,
             time::{utc_from_secs, TimeManager},
             validate::TransactionValidator,
         },
@@ -33,7 +37,7 @@ use crate::{
     NodeConfig, PrecompileFactory,
 };
 use alloy_consensus::{Header, Receipt, ReceiptWithBloom};
-use alloy_eips::eip4844::MAX_BLOBS_PER_BLOCK;
+use alloy_eips::{eip2718::Encodable2718, eip4844::MAX_BLOBS_PER_BLOCK};
 use alloy_primitives::{keccak256, Address, Bytes, TxHash, TxKind, B256, U256, U64};
 use alloy_rpc_types::{
     anvil::Forking,
@@ -41,7 +45,10 @@ use alloy_rpc_types::{
     serde_helpers::JsonStorageKey,
     state::StateOverride,
     trace::{
-        geth::{DefaultFrame, GethDebugTracingOptions, GethDefaultTracingOptions, GethTrace},
+        geth::{
+            GethDebugBuiltInTracerType, GethDebugTracerType, GethDebugTracingCallOptions,
+            GethDebugTracingOptions, GethTrace, NoopFrame,
+        },
         parity::LocalizedTransactionTrace,
     },
     AccessList, Block as AlloyBlock, BlockId, BlockNumberOrTag as BlockNumber,
@@ -60,19 +67,22 @@ use anvil_core::eth::{
 };
 use anvil_rpc::error::RpcError;
 use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use foundry_debugger::{flatten_call_trace, DebugNode};
 use foundry_evm::{
-    backend::{DatabaseError, DatabaseResult, RevertSnapshotAction},
+    backend::{DatabaseError, DatabaseResult, RevertSnapshotAction, StateSnapshot},
     constants::DEFAULT_CREATE2_DEPLOYER_RUNTIME_CODE,
     decode::RevertDecoder,
+    eip7702::{DELEGATION_PREFIX, PER_AUTHORIZATION_COST},
     inspectors::AccessListInspector,
     revm::{
-        db::CacheDB,
-        interpreter::InstructionResult,
+        db::{AccountState, CacheDB},
+        interpreter::{gas::validate_initial_tx_gas, InstructionResult},
         primitives::{
             BlockEnv, CfgEnvWithHandlerCfg, EnvWithHandlerCfg, ExecutionResult, Output, SpecId,
-            TxEnv, KECCAK_EMPTY,
+            TxEnv, KECCAK_EMPTY, MAX_INITCODE_SIZE,
         },
     },
+    traces::TracingInspectorConfig,
     utils::new_evm_with_inspector_ref,
     InspectorExt,
 };
@@ -83,10 +93,11 @@ use revm::{
     primitives::{
         calc_blob_gasprice, BlobExcessGasAndPrice, HashMap, OptimismFields, ResultAndState,
     },
+    DatabaseCommit,
 };
 use std::{
     collections::BTreeMap,
-    io::{Read, Write},
+    io::{self, Read, Write},
     sync::Arc,
     time::Duration,
 };
@@ -104,6 +115,8 @@ pub mod storage;
 pub const MIN_TRANSACTION_GAS: u128 = 21000;
 // Gas per transaction creating a contract.
 pub const MIN_CREATE_GAS: u128 = 53000;
+// Maximum size of an encoded transaction in strict mode, as enforced by geth's pool.
+pub const MAX_TRANSACTION_SIZE: usize = 128 * 1024;
 
 pub type State = foundry_evm::utils::StateChangeset;
 
@@ -163,6 +176,8 @@ pub struct Backend {
     genesis: GenesisConfig,
     /// listeners for new blocks that get notified when a new block was imported
     new_block_listeners: Arc<Mutex<Vec<UnboundedSender<NewBlockNotification>>>>,
+    /// listeners for the state changed by every mined transaction
+    state_diff_listeners: Arc<Mutex<Vec<UnboundedSender<StateDiffNotification>>>>,
     /// keeps track of active snapshots at a specific block
     active_snapshots: Arc<Mutex<HashMap<U256, (u64, B256)>>>,
     enable_steps_tracing: bool,
@@ -173,8 +188,12 @@ pub struct Backend {
     node_config: Arc<AsyncRwLock<NodeConfig>>,
     /// Slots in an epoch
     slots_in_an_epoch: u64,
+    /// Whether transactions a mainnet node would reject are rejected
+    strict: bool,
     /// Precompiles to inject to the EVM.
     precompile_factory: Option<Arc<dyn PrecompileFactory>>,
+    /// The SQLite database mined blocks are persisted to, if any
+    sqlite_db: Option<Arc<SqliteDb>>,
 }
 
 impl Backend {
@@ -191,7 +210,7 @@ impl Backend {
         transaction_block_keeper: Option<usize>,
         automine_block_time: Option<Duration>,
         node_config: Arc<AsyncRwLock<NodeConfig>>,
-    ) -> Self {
+    ) -> io::Result<Self> {
         // if this is a fork then adjust the blockchain storage
         let blockchain = if let Some(fork) = fork.read().as_ref() {
             trace!(target: "backend", "using forked blockchain at {}", fork.block_number());
@@ -221,11 +240,27 @@ impl Backend {
             Default::default()
         };
 
-        let (slots_in_an_epoch, precompile_factory) = {
+        let (slots_in_an_epoch, strict, precompile_factory, sqlite_db) = {
             let cfg = node_config.read().await;
-            (cfg.slots_in_an_epoch, cfg.precompile_factory.clone())
+            (
+                cfg.slots_in_an_epoch,
+                cfg.strict,
+                cfg.precompile_factory.clone(),
+                cfg.sqlite_db.clone(),
+            )
         };
 
+        let sqlite_db = sqlite_db
+            .map(|path| {
+                SqliteDb::open(&path).map(Arc::new).map_err(|err| {
+                    io::Error::other(format!(
+                        "failed to open SQLite database at {}: {err}",
+                        path.display()
+                    ))
+                })
+            })
+            .transpose()?;
+
         let backend = Self {
             db,
             blockchain,
@@ -235,6 +270,7 @@ impl Backend {
             time: TimeManager::new(start_timestamp),
             cheats: Default::default(),
             new_block_listeners: Default::default(),
+            state_diff_listeners: Default::default(),
             fees,
             genesis,
             active_snapshots: Arc::new(Mutex::new(Default::default())),
@@ -243,7 +279,9 @@ impl Backend {
             transaction_block_keeper,
             node_config,
             slots_in_an_epoch,
+            strict,
             precompile_factory,
+            sqlite_db,
         };
 
         if let Some(interval_block_time) = automine_block_time {
@@ -252,7 +290,7 @@ impl Backend {
 
         // Note: this can only fail in forking mode, in which case we can't recover
         backend.apply_genesis().await.expect("Failed to create genesis");
-        backend
+        Ok(backend)
     }
 
     /// Writes the CREATE2 deployer code directly to the database at the address provided.
@@ -364,6 +402,11 @@ impl Backend {
         Ok(self.db.read().await.basic_ref(address)?.unwrap_or_default())
     }
 
+    /// Returns the SQLite database mined blocks are persisted to, if any
+    pub fn sqlite_db(&self) -> Option<&SqliteDb> {
+        self.sqlite_db.as_deref()
+    }
+
     /// Whether we're forked off some remote client
     pub fn is_fork(&self) -> bool {
         self.fork.read().is_some()
@@ -687,23 +730,7 @@ impl Backend {
         if let Some((num, hash)) = block {
             let best_block_hash = {
                 // revert the storage that's newer than the snapshot
-                let current_height = self.best_number();
-                let mut storage = self.blockchain.storage.write();
-
-                for n in ((num + 1)..=current_height).rev() {
-                    trace!(target: "backend", "reverting block {}", n);
-                    let n = U64::from(n);
-                    if let Some(hash) = storage.hashes.remove(&n) {
-                        if let Some(block) = storage.blocks.remove(&hash) {
-                            for tx in block.transactions {
-                                let _ = storage.transactions.remove(&tx.hash());
-                            }
-                        }
-                    }
-                }
-
-                storage.best_number = U64::from(num);
-                storage.best_hash = hash;
+                self.blockchain.storage.write().unwind_to(num, hash);
                 hash
             };
             let block =
@@ -729,6 +756,96 @@ impl Backend {
         Ok(self.db.write().await.revert(id, RevertSnapshotAction::RevertRemove))
     }
 
+    /// Rolls the chain back to the block with the given number.
+    ///
+    /// All later blocks are removed along with their transactions, and the state, the time and the
+    /// base fee are reset to what they were right after the block was mined. Snapshots taken after
+    /// the block are discarded.
+    ///
+    /// This requires the state of the block to still be in the state history, which is not
+    /// available in forking mode.
+    pub async fn rewind_to_block(&self, number: u64) -> Result<(), BlockchainError> {
+        let best_number = self.best_number();
+        if number > best_number {
+            return Err(BlockchainError::BlockOutOfRange(best_number, number))
+        }
+        if number == best_number {
+            return Ok(())
+        }
+        let block = self.get_block(number).ok_or(BlockchainError::BlockNotFound)?;
+        let hash = block.header.hash_slow();
+
+        // the state history stores the state of a block when the next block is mined
+        let mut snapshot = {
+            let mut states = self.states.write();
+            let state = states.get(&hash).ok_or(BlockchainError::DataUnavailable)?;
+            let accounts = state.maybe_as_full_db().ok_or_else(|| {
+                BlockchainError::Message("rewinding is not supported in forking mode".to_string())
+            })?;
+            let mut snapshot = StateSnapshot::default();
+            for (address, account) in accounts {
+                if account.account_state == AccountState::NotExisting {
+                    continue
+                }
+                let mut info = account.info.clone();
+                if info.code.is_none() && info.code_hash != KECCAK_EMPTY {
+                    info.code = Some(state.code_by_hash_ref(info.code_hash)?);
+                }
+                snapshot.accounts.insert(*address, info);
+                snapshot.storage.insert(*address, account.storage.clone());
+            }
+            snapshot
+        };
+
+        {
+            let mut storage = self.blockchain.storage.write();
+            storage.unwind_to(number, hash);
+            snapshot.block_hashes =
+                storage.hashes.iter().map(|(number, hash)| (U256::from(*number), *hash)).collect();
+        }
+        {
+            let mut db = self.db.write().await;
+            db.clear();
+            db.init_from_snapshot(snapshot);
+        }
+        self.active_snapshots.lock().retain(|_, (snapshot_number, _)| *snapshot_number <= number);
+
+        if let Some(sqlite_db) = &self.sqlite_db {
+            if let Err(err) = sqlite_db.remove_blocks_from(number + 1) {
+                warn!(target: "backend", ?err, "failed to remove blocks from SQLite database");
+            }
+        }
+
+        let header = block.header;
+        self.time.reset(header.timestamp);
+        {
+            let mut env = self.env.write();
+            env.block = BlockEnv {
+                number: U256::from(number),
+                timestamp: U256::from(header.timestamp),
+                difficulty: header.difficulty,
+                prevrandao: Some(header.mix_hash),
+                gas_limit: U256::from(header.gas_limit),
+                // Keep previous `coinbase` and `basefee` value
+                coinbase: env.block.coinbase,
+                basefee: env.block.basefee,
+                ..Default::default()
+            };
+        }
+        self.fees.set_base_fee(self.fees.get_next_block_base_fee_per_gas(
+            header.gas_used,
+            header.gas_limit,
+            header.base_fee_per_gas.unwrap_or_default(),
+        ));
+        self.fees.set_blob_excess_gas_and_price(BlobExcessGasAndPrice::new(
+            self.fees.get_next_block_blob_excess_gas(
+                header.excess_blob_gas.unwrap_or_default(),
+                header.blob_gas_used.unwrap_or_default(),
+            ),
+        ));
+        Ok(())
+    }
+
     pub fn list_snapshots(&self) -> BTreeMap<U256, (u64, B256)> {
         self.active_snapshots.lock().clone().into_iter().collect()
     }
@@ -755,6 +872,22 @@ impl Backend {
         Ok(encoder.finish().unwrap_or_default().into())
     }
 
+    /// Encodes all blocks of the chain, ordered by number, as a stream of RLP encoded blocks.
+    ///
+    /// This is the format written by `geth export` and read by `geth import`. When forking, only
+    /// the blocks mined locally are included.
+    pub fn export_chain(&self) -> Bytes {
+        let storage = self.blockchain.storage.read();
+        let mut blocks = storage.blocks.values().collect::<Vec<_>>();
+        blocks.sort_by_key(|block| block.header.number);
+
+        let mut out = Vec::new();
+        for block in blocks {
+            alloy_rlp::Encodable::encode(block, &mut out);
+        }
+        out.into()
+    }
+
     /// Apply [SerializableState] data to the backend storage.
     pub async fn load_state(&self, state: SerializableState) -> Result<bool, BlockchainError> {
         // reset the block env
@@ -898,6 +1031,7 @@ impl Backend {
             gas_used: 0,
             blob_gas_used: 0,
             enable_steps_tracing: self.enable_steps_tracing,
+            record_state_diffs: false,
             precompile_factory: self.precompile_factory.clone(),
         };
 
@@ -923,7 +1057,7 @@ impl Backend {
     ) -> MinedBlockOutcome {
         trace!(target: "backend", "creating new block with {} transactions", pool_transactions.len());
 
-        let (outcome, header, block_hash) = {
+        let (outcome, header, block_hash, state_diffs) = {
             let current_base_fee = self.base_fee();
             let current_excess_blob_gas_and_price = self.excess_blob_gas_and_price();
 
@@ -969,6 +1103,7 @@ impl Backend {
                     gas_used: 0,
                     blob_gas_used: 0,
                     enable_steps_tracing: self.enable_steps_tracing,
+                    record_state_diffs: self.has_state_diff_listeners(),
                     precompile_factory: self.precompile_factory.clone(),
                 };
                 let executed_tx = executor.execute();
@@ -981,9 +1116,17 @@ impl Backend {
             };
 
             // create the new block with the current timestamp
-            let ExecutedTransactions { block, included, invalid } = executed_tx;
+            let ExecutedTransactions { block, included, invalid, state_diffs } = executed_tx;
             let BlockInfo { block, transactions, receipts } = block;
 
+            if let Some(sqlite_db) = &self.sqlite_db {
+                if let Err(err) =
+                    sqlite_db.insert_block(&block, block_hash, &transactions, &receipts)
+                {
+                    warn!(target: "backend", ?err, "failed to write block to SQLite database");
+                }
+            }
+
             let mut storage = self.blockchain.storage.write();
             let header = block.header.clone();
             let block_number = storage.best_number.saturating_add(U64::from(1));
@@ -1060,7 +1203,7 @@ impl Backend {
 
             let outcome = MinedBlockOutcome { block_number, included, invalid };
 
-            (outcome, header, block_hash)
+            (outcome, header, block_hash, state_diffs)
         };
         let next_block_base_fee = self.fees.get_next_block_base_fee_per_gas(
             header.gas_used,
@@ -1078,6 +1221,7 @@ impl Backend {
             .set_blob_excess_gas_and_price(BlobExcessGasAndPrice::new(next_block_excess_blob_gas));
 
         // notify all listeners
+        self.notify_on_state_diffs(state_diffs);
         self.notify_on_new_block(header, block_hash);
 
         outcome
@@ -1219,36 +1363,121 @@ impl Backend {
         request: WithOtherFields<TransactionRequest>,
         fee_details: FeeDetails,
         block_request: Option<BlockRequest>,
-        opts: GethDefaultTracingOptions,
-    ) -> Result<DefaultFrame, BlockchainError> {
+        opts: GethDebugTracingCallOptions,
+    ) -> Result<GethTrace, BlockchainError> {
+        let GethDebugTracingCallOptions { tracing_options, state_overrides, .. } = opts;
         self.with_database_at(block_request, |state, block| {
-            let mut inspector = Inspector::default().with_steps_tracing();
             let block_number = block.number;
-
             let env = self.build_call_env(request, fee_details, block);
-            let mut evm = self.new_evm_with_inspector_ref(state, env, &mut inspector);
-            let ResultAndState { result, state: _ } = evm.transact()?;
-
-            let (exit_reason, gas_used, out) = match result {
-                ExecutionResult::Success { reason, gas_used, output, .. } => {
-                    (reason.into(), gas_used, Some(output))
-                }
-                ExecutionResult::Revert { gas_used, output } => {
-                    (InstructionResult::Revert, gas_used, Some(Output::Call(output)))
+            trace!(target: "backend", %block_number, "trace call");
+            match state_overrides {
+                None => self.geth_trace_with_state(state, env, tracing_options),
+                Some(overrides) => {
+                    let state =
+                        state::apply_state_override(overrides.into_iter().collect(), state)?;
+                    self.geth_trace_with_state(state, env, tracing_options)
                 }
-                ExecutionResult::Halt { reason, gas_used } => (reason.into(), gas_used, None),
-            };
+            }
+        })
+        .await?
+    }
 
+    /// Executes the call without committing it, and records every step of its execution for the
+    /// `anvil_debug*` step debugger.
+    pub async fn debug_call(
+        &self,
+        request: WithOtherFields<TransactionRequest>,
+        fee_details: FeeDetails,
+        block_request: Option<BlockRequest>,
+    ) -> Result<Vec<DebugNode>, BlockchainError> {
+        self.with_database_at(block_request, |state, block| {
+            let env = self.build_call_env(request, fee_details, block);
+            let mut inspector = Inspector::default().with_steps_tracing();
+            let mut evm = self.new_evm_with_inspector_ref(state, env, &mut inspector);
+            evm.transact()?;
             drop(evm);
-            let tracer = inspector.tracer.expect("tracer disappeared");
-            let return_value = out.as_ref().map(|o| o.data().clone()).unwrap_or_default();
-            let res = tracer.into_geth_builder().geth_traces(gas_used, return_value, opts);
-            trace!(target: "backend", ?exit_reason, ?out, %gas_used, %block_number, "trace call");
-            Ok(res)
+            inspector.print_logs();
+
+            let mut nodes = Vec::new();
+            let arena = inspector.tracer.expect("tracer disappeared").into_traces();
+            flatten_call_trace(arena, &mut nodes);
+            Ok(nodes)
         })
         .await?
     }
 
+    /// Executes the transaction of the given env on top of `state`, without committing it, and
+    /// builds the geth trace requested by `opts`.
+    fn geth_trace_with_state<D>(
+        &self,
+        state: D,
+        env: EnvWithHandlerCfg,
+        opts: GethDebugTracingOptions,
+    ) -> Result<GethTrace, BlockchainError>
+    where
+        D: DatabaseRef<Error = DatabaseError>,
+    {
+        let GethDebugTracingOptions { config, tracer, tracer_config, .. } = opts;
+
+        let transact = |tracing_config| -> Result<_, BlockchainError> {
+            let mut inspector = Inspector::default().with_tracing_config(tracing_config);
+            let mut evm = self.new_evm_with_inspector_ref(&state, env, &mut inspector);
+            let result = evm.transact()?;
+            drop(evm);
+            inspector.print_logs();
+            Ok((result, inspector.tracer.expect("tracer disappeared")))
+        };
+
+        let tracer = match tracer {
+            None => {
+                let (ResultAndState { result, .. }, tracer) =
+                    transact(TracingInspectorConfig::from_geth_config(&config))?;
+                let return_value = result.output().cloned().unwrap_or_default();
+                return Ok(tracer
+                    .into_geth_builder()
+                    .geth_traces(result.gas_used(), return_value, config)
+                    .into())
+            }
+            Some(GethDebugTracerType::BuiltInTracer(tracer)) => tracer,
+            Some(GethDebugTracerType::JsTracer(_)) => {
+                return Err(RpcError::invalid_params("JS tracers are not supported").into())
+            }
+        };
+
+        match tracer {
+            GethDebugBuiltInTracerType::FourByteTracer => {
+                let (_, tracer) = transact(TracingInspectorConfig::default_parity())?;
+                Ok(four_byte_frame(tracer.traces().nodes()).into())
+            }
+            GethDebugBuiltInTracerType::CallTracer => {
+                let call_config = tracer_config
+                    .into_call_config()
+                    .map_err(|err| RpcError::invalid_params(err.to_string()))?;
+                let (ResultAndState { result, .. }, tracer) =
+                    transact(TracingInspectorConfig::from_geth_call_config(&call_config))?;
+                Ok(tracer
+                    .into_geth_builder()
+                    .geth_call_traces(call_config, result.gas_used())
+                    .into())
+            }
+            GethDebugBuiltInTracerType::PreStateTracer => {
+                let prestate_config = tracer_config
+                    .into_pre_state_config()
+                    .map_err(|err| RpcError::invalid_params(err.to_string()))?;
+                let (result, tracer) =
+                    transact(TracingInspectorConfig::from_geth_prestate_config(&prestate_config))?;
+                Ok(tracer
+                    .into_geth_builder()
+                    .geth_prestate_traces(&result, prestate_config, &state)?
+                    .into())
+            }
+            GethDebugBuiltInTracerType::NoopTracer => Ok(NoopFrame::default().into()),
+            GethDebugBuiltInTracerType::MuxTracer => {
+                Err(RpcError::invalid_params("muxTracer is not supported").into())
+            }
+        }
+    }
+
     pub fn build_access_list_with_state<D>(
         &self,
         state: D,
@@ -1926,7 +2155,16 @@ impl Backend {
         hash: B256,
         opts: GethDebugTracingOptions,
     ) -> Result<GethTrace, BlockchainError> {
-        if let Some(trace) = self.mined_geth_trace_transaction(hash, opts.clone()) {
+        // the prestate can't be derived from the recorded traces, so the transaction is re-executed
+        if matches!(
+            opts.tracer,
+            Some(GethDebugTracerType::BuiltInTracer(GethDebugBuiltInTracerType::PreStateTracer))
+        ) {
+            let tx = self.blockchain.storage.read().transactions.get(&hash).cloned();
+            if let Some(tx) = tx {
+                return self.replay_geth_trace_transaction(tx, opts).await;
+            }
+        } else if let Some(trace) = self.mined_geth_trace_transaction(hash, opts.clone()) {
             return trace;
         }
 
@@ -1945,6 +2183,57 @@ impl Backend {
         self.blockchain.storage.read().transactions.get(&hash).map(|tx| tx.geth_trace(opts))
     }
 
+    /// Re-executes a mined transaction on top of the state of its parent block and the
+    /// transactions that precede it in its block, and builds the geth trace requested by `opts`.
+    async fn replay_geth_trace_transaction(
+        &self,
+        tx: MinedTransaction,
+        opts: GethDebugTracingOptions,
+    ) -> Result<GethTrace, BlockchainError> {
+        let block = self.get_block(tx.block_number).ok_or(BlockchainError::BlockNotFound)?;
+        let index = tx.info.transaction_index as usize;
+
+        let mut env = self.env.read().clone();
+        env.block = BlockEnv {
+            number: U256::from(block.header.number),
+            coinbase: block.header.beneficiary,
+            timestamp: U256::from(block.header.timestamp),
+            difficulty: block.header.difficulty,
+            prevrandao: Some(block.header.mix_hash),
+            basefee: U256::from(block.header.base_fee_per_gas.unwrap_or_default()),
+            gas_limit: U256::from(block.header.gas_limit),
+            ..Default::default()
+        };
+        let tx_env = |tx: &MaybeImpersonatedTransaction| -> Result<TxEnv, BlockchainError> {
+            let pending = PendingTransaction::from_maybe_impersonated(tx.clone())?;
+            let mut tx_env = pending.to_revm_tx_env();
+            if env.handler_cfg.is_optimism {
+                tx_env.optimism.enveloped_tx = Some(alloy_rlp::encode(&tx.transaction).into());
+            }
+            Ok(tx_env)
+        };
+
+        let parent = BlockRequest::Number(tx.block_number.saturating_sub(1));
+        self.with_database_at(Some(parent), |state, _| {
+            let mut cache = CacheDB::new(state);
+            for preceding in &block.transactions[..index] {
+                let mut env = env.clone();
+                env.tx = tx_env(preceding)?;
+                let mut inspector = Inspector::default()
+                    .with_authorization_list(preceding.authorization_list().to_vec());
+                let mut evm = self.new_evm_with_inspector_ref(&cache, env, &mut inspector);
+                let ResultAndState { state: changes, .. } = evm.transact()?;
+                drop(evm);
+                cache.commit(changes);
+            }
+
+            let mut env = env.clone();
+            env.tx = tx_env(&block.transactions[index])?;
+            self.geth_trace_with_state(&cache, env, opts)
+        })
+        .await?
+    }
+
     /// Returns the traces for the given block
     pub async fn trace_block(
         &self,
@@ -2038,6 +2327,11 @@ impl Backend {
                 .base_fee_per_gas
                 .unwrap_or_else(|| self.base_fee())
                 .saturating_add(t.tx().tx().max_priority_fee_per_gas),
+            TypedTransaction::EIP7702(t) => block
+                .header
+                .base_fee_per_gas
+                .unwrap_or_else(|| self.base_fee())
+                .saturating_add(t.tx().max_priority_fee_per_gas),
             TypedTransaction::Deposit(_) => 0_u128,
         };
 
@@ -2072,6 +2366,7 @@ impl Backend {
             TypedReceipt::Legacy(_) => TypedReceipt::Legacy(receipt_with_bloom),
             TypedReceipt::EIP2930(_) => TypedReceipt::EIP2930(receipt_with_bloom),
             TypedReceipt::EIP4844(_) => TypedReceipt::EIP4844(receipt_with_bloom),
+            TypedReceipt::EIP7702(_) => TypedReceipt::EIP7702(receipt_with_bloom),
             TypedReceipt::Deposit(r) => TypedReceipt::Deposit(DepositReceipt {
                 inner: receipt_with_bloom,
                 deposit_nonce: r.deposit_nonce,
@@ -2272,6 +2567,32 @@ impl Backend {
         rx
     }
 
+    /// Returns a new stream of the state changed by every mined transaction
+    pub fn state_diff_notifications(&self) -> StateDiffNotifications {
+        let (tx, rx) = unbounded();
+        self.state_diff_listeners.lock().push(tx);
+        trace!(target: "backed", "added new state diff listener");
+        rx
+    }
+
+    /// Returns true if there are active `state_diff_listeners`, in which case the state changed by
+    /// every mined transaction is recorded
+    fn has_state_diff_listeners(&self) -> bool {
+        let mut listeners = self.state_diff_listeners.lock();
+        listeners.retain(|tx| !tx.is_closed());
+        !listeners.is_empty()
+    }
+
+    /// Notifies all `state_diff_listeners` about the state changed by the mined transactions
+    fn notify_on_state_diffs(&self, state_diffs: Vec<StateDiffNotification>) {
+        if state_diffs.is_empty() {
+            return
+        }
+        self.state_diff_listeners.lock().retain(|tx| {
+            state_diffs.iter().all(|notification| tx.unbounded_send(notification.clone()).is_ok())
+        });
+    }
+
     /// Notifies all `new_block_listeners` about the new block
     fn notify_on_new_block(&self, header: Header, hash: B256) {
         // cleanup closed notification streams first, if the channel is closed we can remove the
@@ -2284,6 +2605,59 @@ impl Backend {
             .lock()
             .retain(|tx| tx.unbounded_send(notification.clone()).is_ok());
     }
+
+    /// Validates the transaction against the rules a mainnet node enforces on top of the default
+    /// validation, see [`NodeConfig::strict`].
+    fn validate_strict(
+        &self,
+        pending: &PendingTransaction,
+        account: &AccountInfo,
+        env: &EnvWithHandlerCfg,
+    ) -> Result<(), InvalidTransactionError> {
+        let tx = &pending.transaction;
+
+        if tx.impersonated_sender.is_some() {
+            warn!(target: "backend", "[{:?}] impersonated transaction", tx.hash());
+            return Err(InvalidTransactionError::ImpersonatedTransaction);
+        }
+
+        // The blobs of an EIP-4844 transaction don't count towards its size.
+        if !tx.transaction.is_eip4844() && tx.transaction.encode_2718_len() > MAX_TRANSACTION_SIZE {
+            warn!(target: "backend", "[{:?}] oversized data", tx.hash());
+            return Err(InvalidTransactionError::OversizedData);
+        }
+
+        let essentials = tx.essentials();
+        let is_create = tx.kind().is_create();
+        if is_create &&
+            env.spec_id() >= SpecId::SHANGHAI &&
+            essentials.input.len() > MAX_INITCODE_SIZE
+        {
+            warn!(target: "backend", "[{:?}] max initcode size exceeded", tx.hash());
+            return Err(InvalidTransactionError::MaxInitCodeSizeExceeded);
+        }
+
+        let access_list = essentials.access_list.flattened();
+        let intrinsic_gas =
+            validate_initial_tx_gas(env.spec_id(), &essentials.input, is_create, &access_list);
+        let authorization_gas = tx.authorization_list().len() as u64 * PER_AUTHORIZATION_COST;
+        if tx.gas_limit() < (intrinsic_gas + authorization_gas) as u128 {
+            warn!(target: "backend", "[{:?}] intrinsic gas too low", tx.hash());
+            return Err(InvalidTransactionError::GasTooLow);
+        }
+
+        // EIP-3607: accounts delegated to with EIP-7702 can still send transactions.
+        let sender_has_code =
+            account.code.as_ref().map_or(account.code_hash != KECCAK_EMPTY, |code| {
+                !code.is_empty() && !code.original_bytes().starts_with(&DELEGATION_PREFIX)
+            });
+        if sender_has_code {
+            warn!(target: "backend", "[{:?}] sender not an eoa", tx.hash());
+            return Err(InvalidTransactionError::SenderNoEOA);
+        }
+
+        Ok(())
+    }
 }
 
 /// Get max nonce from transaction pool by address
@@ -2341,11 +2715,18 @@ impl TransactionValidator for Backend {
             }
         }
 
-        if tx.gas_limit() < MIN_TRANSACTION_GAS {
+        // The authorizations of an EIP-7702 transaction are charged on top of the intrinsic gas.
+        let authorization_gas =
+            tx.authorization_list().len() as u128 * PER_AUTHORIZATION_COST as u128;
+        if tx.gas_limit() < MIN_TRANSACTION_GAS + authorization_gas {
             warn!(target: "backend", "[{:?}] gas too low", tx.hash());
             return Err(InvalidTransactionError::GasTooLow);
         }
 
+        if self.strict {
+            self.validate_strict(pending, account, env)?;
+        }
+
         // Check gas limit, iff block gas limit is set.
         if !env.cfg.disable_block_gas_limit && tx.gas_limit() > env.block.gas_limit.to() {
             warn!(target: "backend", "[{:?}] gas too high", tx.hash());
@@ -2355,8 +2736,7 @@ impl TransactionValidator for Backend {
         }
 
         // check nonce
-        let is_deposit_tx =
-            matches!(&pending.transaction.transaction, TypedTransaction::Deposit(_));
+        let is_deposit_tx = pending.transaction.transaction.is_deposit();
         let nonce = tx.nonce();
         if nonce < account.nonce && !is_deposit_tx {
             warn!(target: "backend", "[{:?}] nonce too low", tx.hash());
@@ -2423,7 +2803,9 @@ impl TransactionValidator for Backend {
             tx.hash());
             InvalidTransactionError::InsufficientFunds
         })?;
-        if account.balance < U256::from(req_funds) {
+        // deposits are credited their mint before execution
+        let balance = account.balance.saturating_add(tx.mint());
+        if balance < U256::from(req_funds) {
             warn!(target: "backend", "[{:?}] insufficient allowance={}, required={} account={:?}", tx.hash(), account.balance, req_funds, *pending.sender());
             return Err(InvalidTransactionError::InsufficientFunds);
         }
@@ -7,7 +7,7 @@ use crate::eth::{
     error::BlockchainError,
     pool::transactions::PoolTransaction,
 };
-use alloy_primitives::{Bytes, TxHash, B256, U256, U64};
+use alloy_primitives::{hex, Bytes, TxHash, B256, U256, U64};
 use alloy_rpc_types::{
     trace::{
         geth::{
@@ -25,7 +25,7 @@ use anvil_core::eth::{
 use anvil_rpc::error::RpcError;
 use foundry_evm::{
     revm::primitives::Env,
-    traces::{FourByteInspector, GethTraceBuilder, ParityTraceBuilder, TracingInspectorConfig},
+    traces::{CallTraceNode, GethTraceBuilder, ParityTraceBuilder, TracingInspectorConfig},
 };
 use parking_lot::RwLock;
 use std::{
@@ -297,6 +297,24 @@ impl BlockchainStorage {
             block.transactions.clear();
         }
     }
+
+    /// Removes all blocks after the given block, along with their transactions, and makes the
+    /// given block the best block
+    pub fn unwind_to(&mut self, block_number: u64, block_hash: B256) {
+        let best_number = self.best_number.to::<u64>();
+        for n in ((block_number + 1)..=best_number).rev() {
+            trace!(target: "backend", "reverting block {}", n);
+            if let Some(hash) = self.hashes.remove(&U64::from(n)) {
+                if let Some(block) = self.blocks.remove(&hash) {
+                    for tx in block.transactions {
+                        let _ = self.transactions.remove(&tx.hash());
+                    }
+                }
+            }
+        }
+        self.best_number = U64::from(block_number);
+        self.best_hash = block_hash;
+    }
 }
 
 impl BlockchainStorage {
@@ -433,8 +451,7 @@ impl MinedTransaction {
             match tracer {
                 GethDebugTracerType::BuiltInTracer(tracer) => match tracer {
                     GethDebugBuiltInTracerType::FourByteTracer => {
-                        let inspector = FourByteInspector::default();
-                        return Ok(FourByteFrame::from(inspector).into())
+                        return Ok(four_byte_frame(&self.info.traces).into())
                     }
                     GethDebugBuiltInTracerType::CallTracer => {
                         return match tracer_config.into_call_config() {
@@ -450,11 +467,18 @@ impl MinedTransaction {
                             Err(e) => Err(RpcError::invalid_params(e.to_string()).into()),
                         };
                     }
+                    GethDebugBuiltInTracerType::NoopTracer => {}
                     GethDebugBuiltInTracerType::PreStateTracer |
-                    GethDebugBuiltInTracerType::NoopTracer |
-                    GethDebugBuiltInTracerType::MuxTracer => {}
+                    GethDebugBuiltInTracerType::MuxTracer => {
+                        return Err(RpcError::invalid_params(format!(
+                            "{tracer:?} is not supported for recorded traces"
+                        ))
+                        .into())
+                    }
                 },
-                GethDebugTracerType::JsTracer(_code) => {}
+                GethDebugTracerType::JsTracer(_code) => {
+                    return Err(RpcError::invalid_params("JS tracers are not supported").into())
+                }
             }
 
             return Ok(NoopFrame::default().into());
@@ -474,6 +498,23 @@ impl MinedTransaction {
     }
 }
 
+/// Builds the `4byteTracer` frame from the call traces of a transaction.
+///
+/// Every call with at least 4 bytes of input is counted by its selector and the size of the rest
+/// of its input, like geth does.
+pub(crate) fn four_byte_frame(nodes: &[CallTraceNode]) -> FourByteFrame {
+    let mut frame = FourByteFrame::default();
+    for node in nodes {
+        let data = &node.trace.data;
+        if node.trace.kind.is_any_create() || data.len() < 4 {
+            continue
+        }
+        let key = format!("{}-{}", hex::encode_prefixed(&data[..4]), data.len() - 4);
+        *frame.0.entry(key).or_default() += 1;
+    }
+    frame
+}
+
 /// Intermediary Anvil representation of a receipt
 #[derive(Clone, Debug)]
 pub struct MinedTransactionReceipt {
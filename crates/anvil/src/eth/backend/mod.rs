@@ -13,4 +13,5 @@ pub mod fork;
 pub mod genesis;
 pub mod info;
 pub mod notifications;
+pub mod sqlite;
 pub mod validate;
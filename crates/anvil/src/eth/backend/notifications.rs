@@ -1,9 +1,10 @@
 //! Notifications emitted from the backed
 
 use alloy_consensus::Header;
-use alloy_primitives::B256;
+use alloy_primitives::{Address, Bytes, B256, U256};
 use futures::channel::mpsc::UnboundedReceiver;
-use std::sync::Arc;
+use serde::Serialize;
+use std::{collections::BTreeMap, sync::Arc};
 
 /// A notification that's emitted when a new block was imported
 #[derive(Clone, Debug)]
@@ -16,3 +17,60 @@ pub struct NewBlockNotification {
 
 /// Type alias for a receiver that receives [NewBlockNotification]
 pub type NewBlockNotifications = UnboundedReceiver<NewBlockNotification>;
+
+/// A notification that's emitted for every mined transaction, containing the state it changed
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StateDiffNotification {
+    /// Number of the block the transaction was mined in
+    pub block_number: u64,
+    /// Hash of the block the transaction was mined in
+    pub block_hash: B256,
+    /// Hash of the transaction
+    pub transaction_hash: B256,
+    /// Index of the transaction in the block
+    pub transaction_index: u64,
+    /// All accounts changed by the transaction
+    pub accounts: BTreeMap<Address, AccountDiff>,
+}
+
+/// Type alias for a receiver that receives [StateDiffNotification]
+pub type StateDiffNotifications = UnboundedReceiver<StateDiffNotification>;
+
+/// The changes made to a single account by a transaction
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountDiff {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub balance: Option<ValueChange<U256>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<ValueChange<u64>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<ValueChange<Bytes>>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub storage: BTreeMap<B256, ValueChange<B256>>,
+}
+
+impl AccountDiff {
+    /// Returns true if the account was not changed
+    pub fn is_empty(&self) -> bool {
+        self.balance.is_none() &&
+            self.nonce.is_none() &&
+            self.code.is_none() &&
+            self.storage.is_empty()
+    }
+}
+
+/// A value before and after a transaction
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct ValueChange<T> {
+    pub from: T,
+    pub to: T,
+}
+
+impl<T: PartialEq> ValueChange<T> {
+    /// Returns the change if the value changed
+    pub fn new(from: T, to: T) -> Option<Self> {
+        (from != to).then_some(Self { from, to })
+    }
+}
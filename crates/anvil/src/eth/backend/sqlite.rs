@@ -0,0 +1,223 @@
+//! Persists mined blocks, transactions and logs into a SQLite database
+
+use alloy_primitives::{hex, B256};
+use anvil_core::eth::{
+    block::Block,
+    transaction::{TransactionInfo, TypedReceipt},
+};
+use parking_lot::Mutex;
+use rusqlite::{params, types::ValueRef, Connection, OpenFlags};
+use serde_json::{Map, Value};
+use std::path::{Path, PathBuf};
+
+/// The tables of the database
+const SCHEMA: &str = r#"
+CREATE TABLE IF NOT EXISTS blocks (
+    number INTEGER PRIMARY KEY,
+    hash TEXT NOT NULL,
+    parent_hash TEXT NOT NULL,
+    timestamp INTEGER NOT NULL,
+    miner TEXT NOT NULL,
+    gas_limit INTEGER NOT NULL,
+    gas_used INTEGER NOT NULL,
+    base_fee_per_gas INTEGER,
+    transaction_count INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS transactions (
+    hash TEXT PRIMARY KEY,
+    block_number INTEGER NOT NULL,
+    block_hash TEXT NOT NULL,
+    transaction_index INTEGER NOT NULL,
+    from_address TEXT NOT NULL,
+    to_address TEXT,
+    contract_address TEXT,
+    nonce INTEGER NOT NULL,
+    value TEXT NOT NULL,
+    gas_limit INTEGER NOT NULL,
+    gas_price INTEGER NOT NULL,
+    gas_used INTEGER NOT NULL,
+    input TEXT NOT NULL,
+    output TEXT,
+    status INTEGER NOT NULL
+);
+CREATE INDEX IF NOT EXISTS transactions_block_number ON transactions (block_number);
+CREATE INDEX IF NOT EXISTS transactions_from_address ON transactions (from_address);
+CREATE INDEX IF NOT EXISTS transactions_to_address ON transactions (to_address);
+CREATE TABLE IF NOT EXISTS logs (
+    block_number INTEGER NOT NULL,
+    log_index INTEGER NOT NULL,
+    transaction_hash TEXT NOT NULL,
+    transaction_index INTEGER NOT NULL,
+    address TEXT NOT NULL,
+    topic0 TEXT,
+    topic1 TEXT,
+    topic2 TEXT,
+    topic3 TEXT,
+    data TEXT NOT NULL,
+    PRIMARY KEY (block_number, log_index)
+);
+CREATE INDEX IF NOT EXISTS logs_address ON logs (address);
+CREATE INDEX IF NOT EXISTS logs_topic0 ON logs (topic0);
+"#;
+
+/// A SQLite database that all mined blocks are written to, so that the chain can be queried with
+/// SQL via `anvil_query`.
+///
+/// Hashes, addresses and bytes are stored as lowercase `0x`-prefixed hex strings, and ether values
+/// as decimal strings.
+#[derive(Debug)]
+pub struct SqliteDb {
+    path: PathBuf,
+    /// The connection blocks are written with
+    conn: Mutex<Connection>,
+}
+
+impl SqliteDb {
+    /// Opens the database at the given path, creating it and its tables if necessary
+    pub fn open(path: impl AsRef<Path>) -> rusqlite::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let conn = Connection::open(&path)?;
+        // allows queries to read while blocks are being written
+        conn.pragma_update(None, "journal_mode", "WAL")?;
+        conn.execute_batch(SCHEMA)?;
+        Ok(Self { path, conn: Mutex::new(conn) })
+    }
+
+    /// Returns the path of the database
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Writes a mined block along with its transactions and logs.
+    ///
+    /// Any previously written block with the same or a higher number is removed first, so that
+    /// the database follows the chain after reverts, resets and restarts.
+    pub fn insert_block(
+        &self,
+        block: &Block,
+        hash: B256,
+        transactions: &[TransactionInfo],
+        receipts: &[TypedReceipt],
+    ) -> rusqlite::Result<()> {
+        let header = &block.header;
+        let number = header.number as i64;
+
+        let mut conn = self.conn.lock();
+        let db = conn.transaction()?;
+        delete_blocks_from(&db, number)?;
+
+        db.execute(
+            "INSERT INTO blocks VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            params![
+                number,
+                format!("{hash:#x}"),
+                format!("{:#x}", header.parent_hash),
+                header.timestamp as i64,
+                format!("{:#x}", header.beneficiary),
+                header.gas_limit as i64,
+                header.gas_used as i64,
+                header.base_fee_per_gas.map(|fee| fee as i64),
+                block.transactions.len() as i64,
+            ],
+        )?;
+
+        let mut log_index = 0i64;
+        for ((tx, info), receipt) in block.transactions.iter().zip(transactions).zip(receipts) {
+            let tx_hash = format!("{:#x}", info.transaction_hash);
+            db.execute(
+                "INSERT INTO transactions \
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)",
+                params![
+                    tx_hash,
+                    number,
+                    format!("{hash:#x}"),
+                    info.transaction_index as i64,
+                    format!("{:#x}", info.from),
+                    info.to.map(|to| format!("{to:#x}")),
+                    info.contract_address.map(|address| format!("{address:#x}")),
+                    info.nonce as i64,
+                    tx.value().to_string(),
+                    tx.gas_limit() as i64,
+                    tx.gas_price() as i64,
+                    info.gas_used as i64,
+                    hex::encode_prefixed(tx.data()),
+                    info.out.as_ref().map(hex::encode_prefixed),
+                    info.exit.is_ok(),
+                ],
+            )?;
+
+            for log in receipt.logs() {
+                let topics = log.data.topics();
+                let topic = |i: usize| topics.get(i).map(|topic| format!("{topic:#x}"));
+                db.execute(
+                    "INSERT INTO logs VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+                    params![
+                        number,
+                        log_index,
+                        tx_hash,
+                        info.transaction_index as i64,
+                        format!("{:#x}", log.address),
+                        topic(0),
+                        topic(1),
+                        topic(2),
+                        topic(3),
+                        hex::encode_prefixed(&log.data.data),
+                    ],
+                )?;
+                log_index += 1;
+            }
+        }
+
+        db.commit()
+    }
+
+    /// Removes the block with the given number and all later blocks, along with their
+    /// transactions and logs, e.g. after the chain was rewound
+    pub fn remove_blocks_from(&self, number: u64) -> rusqlite::Result<()> {
+        let mut conn = self.conn.lock();
+        let db = conn.transaction()?;
+        delete_blocks_from(&db, number as i64)?;
+        db.commit()
+    }
+
+    /// Executes a read-only SQL statement, returning the rows as objects keyed by column name
+    pub fn query(&self, sql: &str) -> rusqlite::Result<Vec<Map<String, Value>>> {
+        // a separate read-only connection, so that queries can't modify the database even via
+        // pragmas or attached databases
+        let conn = Connection::open_with_flags(
+            &self.path,
+            OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+        )?;
+        let mut stmt = conn.prepare(sql)?;
+        if !stmt.readonly() {
+            return Err(rusqlite::Error::InvalidQuery)
+        }
+
+        let columns = stmt.column_names().into_iter().map(str::to_string).collect::<Vec<_>>();
+        let mut rows = stmt.query([])?;
+        let mut result = Vec::new();
+        while let Some(row) = rows.next()? {
+            let mut object = Map::with_capacity(columns.len());
+            for (i, column) in columns.iter().enumerate() {
+                let value = match row.get_ref(i)? {
+                    ValueRef::Null => Value::Null,
+                    ValueRef::Integer(value) => value.into(),
+                    ValueRef::Real(value) => value.into(),
+                    ValueRef::Text(text) => String::from_utf8_lossy(text).into_owned().into(),
+                    ValueRef::Blob(blob) => hex::encode_prefixed(blob).into(),
+                };
+                object.insert(column.clone(), value);
+            }
+            result.push(object);
+        }
+        Ok(result)
+    }
+}
+
+/// Deletes the block with the given number and all later blocks
+fn delete_blocks_from(db: &Connection, number: i64) -> rusqlite::Result<()> {
+    db.execute("DELETE FROM logs WHERE block_number >= ?1", [number])?;
+    db.execute("DELETE FROM transactions WHERE block_number >= ?1", [number])?;
+    db.execute("DELETE FROM blocks WHERE number >= ?1", [number])?;
+    Ok(())
+}
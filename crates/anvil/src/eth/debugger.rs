@@ -0,0 +1,128 @@
+//! Sessions of the `anvil_debug*` step debugger.
+
+use alloy_primitives::{Bytes, U256};
+use anvil_core::types::{DebugBreakpoint, DebugState};
+use foundry_debugger::DebugNode;
+use parking_lot::Mutex;
+use std::{collections::BTreeMap, sync::Arc};
+
+/// The maximum number of sessions kept at once, the oldest ones are dropped first.
+const MAX_SESSIONS: usize = 16;
+
+/// The recorded execution of a call, and the step a client is at.
+#[derive(Debug)]
+pub struct DebugSession {
+    /// The call frames of the execution, split at the calls they make.
+    nodes: Vec<DebugNode>,
+    /// The node and step indices of every step, in execution order.
+    steps: Vec<(usize, usize)>,
+    /// The index in `steps` of the current step.
+    current: usize,
+}
+
+impl DebugSession {
+    /// Creates a session at the first step of the given nodes, or returns `None` if no code was
+    /// executed.
+    pub fn new(nodes: Vec<DebugNode>) -> Option<Self> {
+        let steps = nodes
+            .iter()
+            .enumerate()
+            .flat_map(|(node, debug_node)| (0..debug_node.steps.len()).map(move |i| (node, i)))
+            .collect::<Vec<_>>();
+        (!steps.is_empty()).then_some(Self { nodes, steps, current: 0 })
+    }
+
+    /// Moves `count` steps forward, stopping at the last step.
+    pub fn step(&mut self, count: usize) {
+        self.current = self.current.saturating_add(count).min(self.last());
+    }
+
+    /// Moves to the next step hitting `breakpoint`, or to the last step if there is none.
+    pub fn continue_to(&mut self, breakpoint: Option<DebugBreakpoint>) {
+        let hit = breakpoint.and_then(|breakpoint| {
+            (self.current + 1..=self.last()).find(|&i| {
+                let (node, step) = self.steps[i];
+                let node = &self.nodes[node];
+                node.steps[step].pc == breakpoint.pc &&
+                    breakpoint.address.map_or(true, |address| address == node.address)
+            })
+        });
+        self.current = hit.unwrap_or(self.last());
+    }
+
+    /// Returns where the session is at.
+    pub fn state(&self, id: U256) -> DebugState {
+        let (node, step) = self.steps[self.current];
+        let node = &self.nodes[node];
+        let step = &node.steps[step];
+        DebugState {
+            id,
+            step: self.current,
+            total_steps: self.steps.len(),
+            finished: self.current == self.last(),
+            address: node.address,
+            depth: step.depth,
+            pc: step.pc,
+            op: step.op.to_string(),
+            gas_remaining: step.gas_remaining,
+            gas_cost: step.gas_cost,
+        }
+    }
+
+    /// Returns the stack before the current step, from bottom to top.
+    pub fn stack(&self) -> Vec<U256> {
+        let (node, step) = self.steps[self.current];
+        self.nodes[node].steps[step].stack.clone().unwrap_or_default()
+    }
+
+    /// Returns the memory before the current step.
+    pub fn memory(&self) -> Bytes {
+        let (node, step) = self.steps[self.current];
+        self.nodes[node].steps[step]
+            .memory
+            .as_ref()
+            .map(|memory| Bytes::copy_from_slice(memory.as_ref()))
+            .unwrap_or_default()
+    }
+
+    fn last(&self) -> usize {
+        self.steps.len() - 1
+    }
+}
+
+/// The open debugger sessions, by id.
+#[derive(Clone, Debug, Default)]
+pub struct DebugSessions {
+    inner: Arc<Mutex<SessionsInner>>,
+}
+
+#[derive(Debug, Default)]
+struct SessionsInner {
+    next_id: U256,
+    sessions: BTreeMap<U256, DebugSession>,
+}
+
+impl DebugSessions {
+    /// Opens a new session, returning its first step.
+    pub fn insert(&self, session: DebugSession) -> DebugState {
+        let mut inner = self.inner.lock();
+        inner.next_id += U256::from(1);
+        let id = inner.next_id;
+        let state = session.state(id);
+        inner.sessions.insert(id, session);
+        while inner.sessions.len() > MAX_SESSIONS {
+            inner.sessions.pop_first();
+        }
+        state
+    }
+
+    /// Applies `f` to the session with the given id, if it exists.
+    pub fn with_session<T>(&self, id: U256, f: impl FnOnce(&mut DebugSession) -> T) -> Option<T> {
+        self.inner.lock().sessions.get_mut(&id).map(f)
+    }
+
+    /// Closes the session with the given id, returning whether it existed.
+    pub fn remove(&self, id: U256) -> bool {
+        self.inner.lock().sessions.remove(&id).is_some()
+    }
+}
@@ -192,6 +192,12 @@ pub enum InvalidTransactionError {
     /// Thrown if the sender of a transaction is a contract.
     #[error("sender not an eoa")]
     SenderNoEOA,
+    /// Thrown if the encoded transaction is larger than the maximum transaction size.
+    #[error("oversized data")]
+    OversizedData,
+    /// Thrown if an impersonated transaction is sent to a node running in strict mode.
+    #[error("impersonated transactions are not allowed in strict mode")]
+    ImpersonatedTransaction,
     /// Thrown when a tx was signed with a different chain_id
     #[error("invalid chain id for signer")]
     InvalidChainId,
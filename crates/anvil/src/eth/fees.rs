@@ -34,6 +34,9 @@ pub const BASE_FEE_CHANGE_DENOMINATOR: u128 = 8;
 /// Minimum suggested priority fee
 pub const MIN_SUGGESTED_PRIORITY_FEE: u128 = 1e9 as u128;
 
+/// The EIP-1559 parameters of OP Stack chains since the Canyon hardfork.
+pub const OPTIMISM_BASE_FEE_PARAMS: BaseFeeParams = BaseFeeParams::new(250, 6);
+
 pub fn default_elasticity() -> f64 {
     1f64 / BaseFeeParams::ethereum().elasticity_multiplier as f64
 }
@@ -55,7 +58,10 @@ pub struct FeeManager {
     ///
     /// This will be constant value unless changed manually
     gas_price: Arc<RwLock<u128>>,
-    elasticity: Arc<RwLock<f64>>,
+    /// The elasticity multiplier and base fee change denominator of the EIP-1559 fee market
+    base_fee_params: Arc<RwLock<BaseFeeParams>>,
+    /// The base fee never drops below this value, unless it is 0
+    min_base_fee: Arc<RwLock<u128>>,
 }
 
 impl FeeManager {
@@ -64,18 +70,43 @@ impl FeeManager {
         base_fee: u128,
         gas_price: u128,
         blob_excess_gas_and_price: BlobExcessGasAndPrice,
+        base_fee_params: BaseFeeParams,
+        min_base_fee: u128,
     ) -> Self {
         Self {
             spec_id,
             base_fee: Arc::new(RwLock::new(base_fee)),
             gas_price: Arc::new(RwLock::new(gas_price)),
             blob_excess_gas_and_price: Arc::new(RwLock::new(blob_excess_gas_and_price)),
-            elasticity: Arc::new(RwLock::new(default_elasticity())),
+            base_fee_params: Arc::new(RwLock::new(base_fee_params)),
+            min_base_fee: Arc::new(RwLock::new(min_base_fee)),
         }
     }
 
     pub fn elasticity(&self) -> f64 {
-        *self.elasticity.read()
+        1f64 / self.base_fee_params.read().elasticity_multiplier as f64
+    }
+
+    /// Returns the current EIP-1559 parameters
+    pub fn base_fee_params(&self) -> BaseFeeParams {
+        *self.base_fee_params.read()
+    }
+
+    /// Sets the EIP-1559 parameters used to calculate the base fee of the next blocks
+    pub fn set_base_fee_params(&self, params: BaseFeeParams) {
+        trace!(target: "backend::fees", "updated base fee params {:?}", params);
+        *self.base_fee_params.write() = params;
+    }
+
+    /// Returns the minimum base fee
+    pub fn min_base_fee(&self) -> u128 {
+        *self.min_base_fee.read()
+    }
+
+    /// Sets the minimum base fee
+    pub fn set_min_base_fee(&self, min_base_fee: u128) {
+        trace!(target: "backend::fees", "updated min base fee {:?}", min_base_fee);
+        *self.min_base_fee.write() = min_base_fee;
     }
 
     /// Returns true for post London
@@ -158,7 +189,8 @@ impl FeeManager {
         if self.base_fee() == 0 {
             return 0
         }
-        calculate_next_block_base_fee(gas_used, gas_limit, last_fee_per_gas)
+        calc_next_block_base_fee(gas_used, gas_limit, last_fee_per_gas, self.base_fee_params())
+            .max(self.min_base_fee())
     }
 
     /// Calculates the next block blob base fee, using the provided excess blob gas
@@ -287,6 +319,10 @@ impl FeeHistoryService {
                             .tx()
                             .max_priority_fee_per_gas
                             .min(t.tx().tx().max_fee_per_gas.saturating_sub(base_fee)),
+                        Some(TypedTransaction::EIP7702(t)) => t
+                            .tx()
+                            .max_priority_fee_per_gas
+                            .min(t.tx().max_fee_per_gas.saturating_sub(base_fee)),
                         Some(TypedTransaction::Deposit(_)) => 0,
                         None => 0,
                     };
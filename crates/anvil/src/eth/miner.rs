@@ -58,10 +58,10 @@ impl Miner {
         self.mode.write()
     }
 
-    /// Returns `true` if auto mining is enabled
+    /// Returns `true` if auto mining is enabled, including in mixed mode
     pub fn is_auto_mine(&self) -> bool {
         let mode = self.mode.read();
-        matches!(*mode, MiningMode::Auto(_))
+        matches!(*mode, MiningMode::Auto(_) | MiningMode::Mixed(_))
     }
 
     pub fn is_interval(&self) -> bool {
@@ -141,6 +141,9 @@ pub enum MiningMode {
     Auto(ReadyTransactionMiner),
     /// A miner that constructs a new block every `interval` tick
     FixedBlockTime(FixedBlockTimeMiner),
+    /// A miner that mines ready transactions right away like [MiningMode::Auto], and mines an
+    /// empty block whenever no block was mined for `interval`
+    Mixed(MixedMiner),
 }
 
 impl MiningMode {
@@ -156,6 +159,17 @@ impl MiningMode {
         Self::FixedBlockTime(FixedBlockTimeMiner::new(duration))
     }
 
+    pub fn mixed(max_transactions: usize, listener: Receiver<TxHash>, duration: Duration) -> Self {
+        Self::Mixed(MixedMiner {
+            instant: ReadyTransactionMiner {
+                max_transactions,
+                has_pending_txs: None,
+                rx: listener.fuse(),
+            },
+            idle: FixedBlockTimeMiner::new(duration),
+        })
+    }
+
     /// polls the [Pool] and returns those transactions that should be put in a block, if any.
     pub fn poll(
         &mut self,
@@ -166,6 +180,7 @@ impl MiningMode {
             Self::None => Poll::Pending,
             Self::Auto(miner) => miner.poll(pool, cx),
             Self::FixedBlockTime(miner) => miner.poll(pool, cx),
+            Self::Mixed(miner) => miner.poll(pool, cx),
         }
     }
 }
@@ -194,7 +209,7 @@ impl FixedBlockTimeMiner {
     fn poll(&mut self, pool: &Arc<Pool>, cx: &mut Context<'_>) -> Poll<Vec<Arc<PoolTransaction>>> {
         if self.interval.poll_tick(cx).is_ready() {
             // drain the pool
-            return Poll::Ready(pool.ready_transactions().collect())
+            return Poll::Ready(pool.block_transactions())
         }
         Poll::Pending
     }
@@ -206,6 +221,27 @@ impl Default for FixedBlockTimeMiner {
     }
 }
 
+/// A miner that mines ready transactions right away, and an empty block whenever no block was mined
+/// for an entire interval.
+#[derive(Debug)]
+pub struct MixedMiner {
+    /// Mines the ready transactions
+    instant: ReadyTransactionMiner,
+    /// Mines a block when idle
+    idle: FixedBlockTimeMiner,
+}
+
+impl MixedMiner {
+    fn poll(&mut self, pool: &Arc<Pool>, cx: &mut Context<'_>) -> Poll<Vec<Arc<PoolTransaction>>> {
+        if let Poll::Ready(transactions) = self.instant.poll(pool, cx) {
+            // a block is mined, so the node isn't idle anymore
+            self.idle.interval.reset();
+            return Poll::Ready(transactions)
+        }
+        self.idle.poll(pool, cx)
+    }
+}
+
 /// A miner that Listens for new ready transactions
 pub struct ReadyTransactionMiner {
     /// how many transactions to mine per block
@@ -227,8 +263,8 @@ impl ReadyTransactionMiner {
             return Poll::Pending
         }
 
-        let transactions =
-            pool.ready_transactions().take(self.max_transactions).collect::<Vec<_>>();
+        let mut transactions = pool.block_transactions();
+        transactions.truncate(self.max_transactions);
 
         // there are pending transactions if we didn't drain the pool
         self.has_pending_txs = Some(transactions.len() >= self.max_transactions);
@@ -5,6 +5,8 @@ pub use api::EthApi;
 
 pub mod backend;
 
+pub mod debugger;
+
 pub mod error;
 
 pub mod fees;
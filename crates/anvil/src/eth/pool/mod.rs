@@ -29,9 +29,12 @@
 use crate::{
     eth::{
         error::PoolError,
-        pool::transactions::{
-            PendingPoolTransaction, PendingTransactions, PoolTransaction, ReadyTransactions,
-            TransactionsIterator, TxMarker,
+        pool::{
+            ordering::{OrderingConstraint, OrderingConstraints},
+            transactions::{
+                PendingPoolTransaction, PendingTransactions, PoolTransaction, ReadyTransactions,
+                TransactionsIterator, TxMarker,
+            },
         },
     },
     mem::storage::MinedBlockOutcome,
@@ -41,8 +44,13 @@ use alloy_rpc_types::txpool::TxpoolStatus;
 use anvil_core::eth::transaction::PendingTransaction;
 use futures::channel::mpsc::{channel, Receiver, Sender};
 use parking_lot::{Mutex, RwLock};
-use std::{collections::VecDeque, fmt, sync::Arc};
+use std::{
+    collections::{HashSet, VecDeque},
+    fmt,
+    sync::Arc,
+};
 
+pub mod ordering;
 pub mod transactions;
 
 /// Transaction pool that performs validation.
@@ -52,6 +60,8 @@ pub struct Pool {
     inner: RwLock<PoolInner>,
     /// listeners for new ready transactions
     transaction_listener: Mutex<Vec<Sender<TxHash>>>,
+    /// constraints on the order of the transactions of upcoming blocks
+    ordering: RwLock<OrderingConstraints>,
 }
 
 // == impl Pool ==
@@ -62,6 +72,25 @@ impl Pool {
         self.inner.read().ready_transactions()
     }
 
+    /// Returns the ready transactions in the order they are included in the next block.
+    ///
+    /// This is the order of [Self::ready_transactions()] with the ordering constraints applied,
+    /// which may hold back some of the ready transactions.
+    pub fn block_transactions(&self) -> Vec<Arc<PoolTransaction>> {
+        let transactions = self.ready_transactions().collect();
+        self.ordering.read().apply(transactions, |tx| (tx.hash(), *tx.pending_transaction.sender()))
+    }
+
+    /// Adds a constraint on the order of the transactions of upcoming blocks.
+    pub fn add_ordering_constraint(&self, constraint: OrderingConstraint) {
+        self.ordering.write().add(constraint);
+    }
+
+    /// Removes all the ordering constraints.
+    pub fn clear_ordering_constraints(&self) {
+        self.ordering.write().clear();
+    }
+
     /// Returns all transactions that are not ready to be included in a block yet
     pub fn pending_transactions(&self) -> Vec<Arc<PoolTransaction>> {
         self.inner.read().pending_transactions.transactions().collect()
@@ -89,6 +118,10 @@ impl Pool {
         // remove invalid transactions from the pool
         self.remove_invalid(invalid.into_iter().map(|tx| tx.hash()).collect());
 
+        // the ordering constraints of the mined transactions are satisfied
+        let mined = included.iter().map(|tx| tx.hash()).collect::<HashSet<_>>();
+        self.ordering.write().prune(&mined);
+
         // prune all the markers the mined transactions provide
         let res = self
             .prune_markers(block_number, included.into_iter().flat_map(|tx| tx.provides.clone()));
@@ -170,6 +203,7 @@ impl Pool {
     pub fn clear(&self) {
         let mut pool = self.inner.write();
         pool.clear();
+        self.ordering.write().clear();
     }
 
     /// notifies all listeners about the transaction
@@ -0,0 +1,205 @@
+//! Constraints on the order of the transactions of upcoming blocks.
+//!
+//! The ready transactions of the pool are ordered by priority, which makes the position of a
+//! transaction in the next block depend on its fees and arrival. Ordering constraints pin down the
+//! order of specific transactions, so that MEV-sensitive contracts and bots can be tested against a
+//! deterministic sequencer:
+//!   * a transaction can be placed before another one, which is held back until the first one is
+//!     ready
+//!   * a bundle of transactions is included atomically: all of its transactions are included
+//!     back-to-back in the given order, or none of them is
+
+use alloy_primitives::{Address, TxHash};
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, HashSet},
+};
+
+/// A constraint on the order of the transactions of upcoming blocks.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum OrderingConstraint {
+    /// `first` is included before `then`.
+    Before { first: TxHash, then: TxHash },
+    /// The transactions are included together and back-to-back, in this order.
+    Bundle(Vec<TxHash>),
+}
+
+impl OrderingConstraint {
+    /// Returns `true` if the constraint applies to the given transaction.
+    pub fn contains(&self, hash: &TxHash) -> bool {
+        match self {
+            Self::Before { first, then } => first == hash || then == hash,
+            Self::Bundle(hashes) => hashes.contains(hash),
+        }
+    }
+}
+
+/// The ordering constraints of the pool.
+#[derive(Clone, Debug, Default)]
+pub struct OrderingConstraints {
+    constraints: Vec<OrderingConstraint>,
+}
+
+impl OrderingConstraints {
+    pub fn add(&mut self, constraint: OrderingConstraint) {
+        self.constraints.push(constraint);
+    }
+
+    pub fn clear(&mut self) {
+        self.constraints.clear();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.constraints.is_empty()
+    }
+
+    /// Removes the constraints on any of the given mined transactions.
+    ///
+    /// A transaction placed after a mined one is free to be included, and bundles are only mined
+    /// as a whole.
+    pub fn prune(&mut self, mined: &HashSet<TxHash>) {
+        self.constraints.retain(|constraint| !mined.iter().any(|tx| constraint.contains(tx)));
+    }
+
+    /// Orders the given ready transactions according to the constraints.
+    ///
+    /// `key` returns the hash and the sender of a transaction. The transactions of a sender keep
+    /// their order, and otherwise the given order is kept as much as possible. Transactions that
+    /// can't be included yet are left out, along with the later transactions of their sender:
+    ///   * transactions placed after a transaction that is not ready
+    ///   * bundles with transactions that are not ready
+    ///   * transactions in a cycle of constraints
+    pub fn apply<T>(&self, txs: Vec<T>, key: impl Fn(&T) -> (TxHash, Address)) -> Vec<T> {
+        if self.constraints.is_empty() {
+            return txs
+        }
+        let keys = txs.iter().map(&key).collect::<Vec<_>>();
+        let index =
+            keys.iter().enumerate().map(|(i, (hash, _))| (*hash, i)).collect::<HashMap<_, _>>();
+
+        // Every transaction is a group of its own, except for the transactions of bundles.
+        let mut group_of = (0..txs.len()).collect::<Vec<_>>();
+        let mut members = (0..txs.len()).map(|i| vec![i]).collect::<Vec<_>>();
+        let mut blocked = vec![false; txs.len()];
+        for constraint in &self.constraints {
+            match constraint {
+                OrderingConstraint::Bundle(hashes) => {
+                    let present = hashes.iter().filter_map(|hash| index.get(hash).copied());
+                    let present = present.collect::<Vec<_>>();
+                    if present.is_empty() || present.len() < hashes.len() {
+                        present.iter().for_each(|&i| blocked[group_of[i]] = true);
+                        continue
+                    }
+                    let group = group_of[present[0]];
+                    for &i in &present[1..] {
+                        let old = std::mem::replace(&mut group_of[i], group);
+                        members[old].retain(|&member| member != i);
+                        blocked[group] |= blocked[old];
+                    }
+                    members[group] = present;
+                }
+                OrderingConstraint::Before { first, then } => {
+                    if let (None, Some(&then)) = (index.get(first), index.get(then)) {
+                        blocked[group_of[then]] = true;
+                    }
+                }
+            }
+        }
+
+        // Orders the groups topologically, preferring the earliest group whenever there is a
+        // choice.
+        let mut edges = vec![Vec::new(); txs.len()];
+        let mut in_degree = vec![0usize; txs.len()];
+        let mut add_edge = |from: usize, to: usize| {
+            let (from, to) = (group_of[from], group_of[to]);
+            if from != to {
+                edges[from].push(to);
+                in_degree[to] += 1;
+            }
+        };
+        let mut last_of_sender = HashMap::new();
+        for (i, (_, sender)) in keys.iter().enumerate() {
+            if let Some(prev) = last_of_sender.insert(*sender, i) {
+                add_edge(prev, i);
+            }
+        }
+        for constraint in &self.constraints {
+            if let OrderingConstraint::Before { first, then } = constraint {
+                if let (Some(&first), Some(&then)) = (index.get(first), index.get(then)) {
+                    add_edge(first, then);
+                }
+            }
+        }
+
+        let first_member =
+            |group: usize| members[group].iter().min().copied().unwrap_or(usize::MAX);
+        let mut available = (0..txs.len())
+            .filter(|&group| !members[group].is_empty() && in_degree[group] == 0 && !blocked[group])
+            .map(|group| Reverse((first_member(group), group)))
+            .collect::<BinaryHeap<_>>();
+        let mut order = Vec::new();
+        while let Some(Reverse((_, group))) = available.pop() {
+            order.extend(members[group].iter().copied());
+            for &next in &edges[group] {
+                in_degree[next] -= 1;
+                if in_degree[next] == 0 && !blocked[next] {
+                    available.push(Reverse((first_member(next), next)));
+                }
+            }
+        }
+
+        let mut txs = txs.into_iter().map(Some).collect::<Vec<_>>();
+        order.into_iter().filter_map(|i| txs[i].take()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hash(n: u8) -> TxHash {
+        TxHash::with_last_byte(n)
+    }
+
+    /// Orders transactions `(hash, sender)`, returning their hashes.
+    fn apply(constraints: &OrderingConstraints, txs: &[(u8, u8)]) -> Vec<u8> {
+        let txs = txs.iter().map(|&(tx, sender)| (hash(tx), Address::with_last_byte(sender)));
+        let ordered = constraints.apply(txs.collect(), |&(hash, sender)| (hash, sender));
+        ordered.into_iter().map(|(hash, _)| hash[31]).collect()
+    }
+
+    #[test]
+    fn places_transactions_before_others() {
+        let mut constraints = OrderingConstraints::default();
+        assert_eq!(apply(&constraints, &[(1, 1), (2, 2), (3, 3)]), [1, 2, 3]);
+
+        constraints.add(OrderingConstraint::Before { first: hash(3), then: hash(1) });
+        assert_eq!(apply(&constraints, &[(1, 1), (2, 2), (3, 3)]), [2, 3, 1]);
+
+        // The later transactions of the sender are held back too.
+        assert_eq!(apply(&constraints, &[(1, 1), (2, 1), (4, 2), (3, 3)]), [4, 3, 1, 2]);
+
+        // Transactions placed after a transaction that's not ready are left out.
+        assert_eq!(apply(&constraints, &[(1, 1), (2, 1), (4, 2)]), [4]);
+
+        // Cycles are left out.
+        constraints.add(OrderingConstraint::Before { first: hash(1), then: hash(3) });
+        assert_eq!(apply(&constraints, &[(1, 1), (2, 2), (3, 3)]), [2]);
+    }
+
+    #[test]
+    fn includes_bundles_atomically() {
+        let mut constraints = OrderingConstraints::default();
+        constraints.add(OrderingConstraint::Bundle(vec![hash(4), hash(2)]));
+        assert_eq!(apply(&constraints, &[(1, 1), (2, 2), (3, 3), (4, 4)]), [1, 4, 2, 3]);
+        assert_eq!(apply(&constraints, &[(1, 1), (2, 2), (3, 3)]), [1, 3]);
+
+        constraints.add(OrderingConstraint::Before { first: hash(3), then: hash(4) });
+        assert_eq!(apply(&constraints, &[(1, 1), (2, 2), (3, 3), (4, 4)]), [1, 3, 4, 2]);
+
+        constraints.prune(&HashSet::from([hash(2)]));
+        assert_eq!(apply(&constraints, &[(1, 1), (3, 3), (4, 4)]), [1, 3, 4]);
+        constraints.prune(&HashSet::from([hash(3)]));
+        assert!(constraints.is_empty());
+    }
+}
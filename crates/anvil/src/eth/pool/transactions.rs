@@ -40,7 +40,13 @@ pub enum TransactionOrder {
 
 impl TransactionOrder {
     /// Returns the priority of the transactions
+    ///
+    /// op-stack deposit transactions always have the highest priority, since they must be included
+    /// at the start of a block.
     pub fn priority(&self, tx: &TypedTransaction) -> TransactionPriority {
+        if tx.is_deposit() {
+            return TransactionPriority(u128::MAX)
+        }
         match self {
             Self::Fifo => TransactionPriority::default(),
             Self::Fees => TransactionPriority(tx.gas_price()),
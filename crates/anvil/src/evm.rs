@@ -1,5 +1,5 @@
 use alloy_primitives::Address;
-use foundry_evm::revm::precompile::Precompile;
+use foundry_evm::{precompiles::CustomPrecompiles, revm::precompile::Precompile};
 use std::{fmt::Debug, sync::Arc};
 
 /// Object-safe trait that enables injecting extra precompiles when using
@@ -9,6 +9,12 @@ pub trait PrecompileFactory: Send + Sync + Unpin + Debug {
     fn precompiles(&self) -> Vec<(Address, Precompile)>;
 }
 
+impl PrecompileFactory for CustomPrecompiles {
+    fn precompiles(&self) -> Vec<(Address, Precompile)> {
+        self.to_precompiles()
+    }
+}
+
 /// Appends a handler register to `evm` that injects the given `precompiles`.
 ///
 /// This will add an additional handler that extends the default precompiles with the given set of
@@ -125,7 +125,7 @@ pub async fn try_spawn(mut config: NodeConfig) -> io::Result<(EthApi, NodeHandle
     let logger = if config.enable_tracing { init_tracing() } else { Default::default() };
     logger.set_enabled(!config.silent);
 
-    let backend = Arc::new(config.setup().await);
+    let backend = Arc::new(config.setup().await?);
 
     if config.enable_auto_impersonate {
         backend.auto_impersonate_account(true).await;
@@ -140,6 +140,7 @@ pub async fn try_spawn(mut config: NodeConfig) -> io::Result<(EthApi, NodeHandle
         max_transactions,
         server_config,
         no_mining,
+        mixed_mining,
         transaction_order,
         genesis,
         ..
@@ -148,7 +149,12 @@ pub async fn try_spawn(mut config: NodeConfig) -> io::Result<(EthApi, NodeHandle
     let pool = Arc::new(Pool::default());
 
     let mode = if let Some(block_time) = block_time {
-        MiningMode::interval(block_time)
+        if mixed_mining {
+            let listener = pool.add_ready_listener();
+            MiningMode::mixed(max_transactions, listener, block_time)
+        } else {
+            MiningMode::interval(block_time)
+        }
     } else if no_mining {
         MiningMode::None
     } else {
@@ -291,6 +297,18 @@ impl NodeHandle {
                     .collect::<Vec<String>>()
                     .join(", ")
             );
+            if self.config.server_config.ui && !self.addresses.is_empty() {
+                println!("Dashboard: {}/ui", self.http_endpoint());
+            }
+            if let Some(root) = &self.config.server_config.sourcify {
+                if !self.addresses.is_empty() {
+                    println!(
+                        "Sourcify: {}/sourcify (sources of {})",
+                        self.http_endpoint(),
+                        root.display()
+                    );
+                }
+            }
         }
     }
 
@@ -304,12 +322,14 @@ impl NodeHandle {
 
     /// Returns the http endpoint
     pub fn http_endpoint(&self) -> String {
-        format!("http://{}", self.socket_address())
+        let scheme = if self.config.server_config.is_tls() { "https" } else { "http" };
+        format!("{scheme}://{}", self.socket_address())
     }
 
     /// Returns the websocket endpoint
     pub fn ws_endpoint(&self) -> String {
-        format!("ws://{}", self.socket_address())
+        let scheme = if self.config.server_config.is_tls() { "wss" } else { "ws" };
+        format!("{scheme}://{}", self.socket_address())
     }
 
     /// Returns the path of the launched ipc server, if any
@@ -1,5 +1,8 @@
 use crate::{
-    eth::{backend::notifications::NewBlockNotifications, error::to_rpc_result},
+    eth::{
+        backend::notifications::{NewBlockNotifications, StateDiffNotifications},
+        error::to_rpc_result,
+    },
     StorageInfo,
 };
 use alloy_primitives::{TxHash, B256};
@@ -86,6 +89,7 @@ pub enum EthSubscription {
     Logs(Box<LogsSubscription>),
     Header(NewBlockNotifications, StorageInfo, SubscriptionId),
     PendingTransactions(Receiver<TxHash>, SubscriptionId),
+    StateDiffs(StateDiffNotifications, SubscriptionId),
 }
 
 impl EthSubscription {
@@ -120,6 +124,13 @@ impl EthSubscription {
                     });
                 Poll::Ready(res)
             }
+            Self::StateDiffs(diffs, id) => {
+                let res = ready!(diffs.poll_next_unpin(cx)).map(to_rpc_result).map(|result| {
+                    let params = EthSubscriptionParams { subscription: id.clone(), result };
+                    EthSubscriptionResponse::new(params)
+                });
+                Poll::Ready(res)
+            }
         }
     }
 }
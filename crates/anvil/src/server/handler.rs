@@ -8,7 +8,10 @@ use alloy_rpc_types::{
     pubsub::{Params, SubscriptionKind},
     FilteredParams,
 };
-use anvil_core::eth::{subscription::SubscriptionId, EthPubSub, EthRequest, EthRpcCall};
+use anvil_core::eth::{
+    subscription::{AnvilSubscriptionKind, SubscriptionId},
+    EthPubSub, EthRequest, EthRpcCall,
+};
 use anvil_rpc::{error::RpcError, response::ResponseResult};
 use anvil_server::{PubSubContext, PubSubRpcHandler, RpcHandler};
 
@@ -103,6 +106,19 @@ impl PubSubEthRpcHandler {
 
                 cx.add_subscription(id.clone(), subscription);
 
+                trace!(target: "rpc::ws", "created new subscription: {:?}", id);
+                to_rpc_result(id)
+            }
+            EthPubSub::AnvilSubscribe(kind) => {
+                let subscription = match kind {
+                    AnvilSubscriptionKind::StateDiffs => {
+                        trace!(target: "rpc::ws", "received state diffs subscription");
+                        EthSubscription::StateDiffs(self.api.state_diff_notifications(), id.clone())
+                    }
+                };
+
+                cx.add_subscription(id.clone(), subscription);
+
                 trace!(target: "rpc::ws", "created new subscription: {:?}", id);
                 to_rpc_result(id)
             }
@@ -3,13 +3,22 @@
 use crate::{EthApi, IpcTask};
 use anvil_server::{ipc::IpcEndpoint, ServerConfig};
 use axum::Router;
+use foundry_config::Config;
 use futures::StreamExt;
 use handler::{HttpEthRpcHandler, PubSubEthRpcHandler};
-use std::{future::Future, io, net::SocketAddr, pin::pin};
+use std::{
+    future::Future,
+    io,
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    pin::pin,
+};
 use tokio::net::TcpListener;
 
 pub mod error;
 mod handler;
+mod sourcify;
+mod ui;
 
 /// Configures a server that handles [`EthApi`] related JSON-RPC calls via HTTP and WS.
 ///
@@ -25,19 +34,42 @@ pub async fn serve(
 }
 
 /// Configures a server that handles [`EthApi`] related JSON-RPC calls via HTTP and WS.
+///
+/// If a TLS certificate is configured, HTTPS and WSS are served instead.
 pub async fn serve_on(
     tcp_listener: TcpListener,
     api: EthApi,
     config: ServerConfig,
 ) -> io::Result<()> {
-    axum::serve(tcp_listener, router(api, config).into_make_service()).await
+    anvil_server::serve(tcp_listener, router(api, config.clone()), &config).await
 }
 
 /// Configures an [`axum::Router`] that handles [`EthApi`] related JSON-RPC calls via HTTP and WS.
+///
+/// If enabled in the [`ServerConfig`], the dev dashboard is served at `/ui` and the verified
+/// sources of the project at `/sourcify`. The dashboard decodes traces with the contracts of the
+/// Sourcify project, or else of the project anvil is started in.
 pub fn router(api: EthApi, config: ServerConfig) -> Router {
+    let ui = config
+        .ui
+        .then(|| ui::router(api.clone(), config.sourcify.clone().or_else(find_project_root)));
+    let sourcify = config.sourcify.clone().map(|root| sourcify::router(api.clone(), root));
     let http = HttpEthRpcHandler::new(api.clone());
     let ws = PubSubEthRpcHandler::new(api);
-    anvil_server::http_ws_router(config, http, ws)
+    let mut router = anvil_server::http_ws_router(config, http, ws);
+    if let Some(ui) = ui {
+        router = router.merge(ui);
+    }
+    if let Some(sourcify) = sourcify {
+        router = router.merge(sourcify);
+    }
+    router
+}
+
+/// Returns the root of the Foundry project anvil is started in, if any.
+pub fn find_project_root() -> Option<PathBuf> {
+    let cwd = std::env::current_dir().ok()?;
+    cwd.ancestors().find(|dir| dir.join(Config::FILE_NAME).is_file()).map(Path::to_path_buf)
 }
 
 /// Launches an ipc server at the given path in a new task
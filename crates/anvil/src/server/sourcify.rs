@@ -0,0 +1,387 @@
+//! Sourcify-compatible verification API served by the node at `/sourcify`.
+//!
+//! The contracts deployed on the node are matched against the artifacts of a local Foundry
+//! project, so that explorers configured with the node as their Sourcify server, e.g. Otterscan or
+//! Blockscout in dev mode, show their verified sources. Nothing is submitted: a contract is
+//! verified as soon as its code matches the deployed bytecode of an artifact.
+//!
+//! Both the API (`/files/any/{chain}/{address}`, `/check-by-addresses`) and the repository layout
+//! (`/contracts/full_match/{chain}/{address}/...`) are served. The artifacts are read on every
+//! request, so that recompiled contracts are picked up without restarting the node.
+
+use crate::EthApi;
+use alloy_primitives::{Address, Bytes};
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use foundry_config::Config;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    path::{Component, PathBuf},
+    sync::Arc,
+};
+
+/// Configures an [`axum::Router`] that serves the verified sources of the contracts deployed from
+/// the artifacts of the project at `root`.
+pub fn router(api: EthApi, root: PathBuf) -> Router {
+    let state = SourcifyState { api, root: Arc::new(root) };
+    Router::new()
+        .route("/sourcify/check-by-addresses", get(check_by_addresses))
+        .route("/sourcify/files/any/:chain/:address", get(files_any))
+        .route("/sourcify/files/:chain/:address", get(files_full))
+        .route("/sourcify/contracts/:match/:chain/:address/*file", get(repository_file))
+        .with_state(state)
+}
+
+#[derive(Clone)]
+struct SourcifyState {
+    api: EthApi,
+    root: Arc<PathBuf>,
+}
+
+/// How closely the code of a contract matches an artifact.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum MatchKind {
+    /// The code matches, including its metadata hash.
+    Full,
+    /// The code matches, except for its metadata hash.
+    Partial,
+}
+
+impl MatchKind {
+    /// The name of the match in the repository layout.
+    fn repository_dir(self) -> &'static str {
+        match self {
+            Self::Full => "full_match",
+            Self::Partial => "partial_match",
+        }
+    }
+}
+
+/// The deployed bytecode and metadata of a local artifact.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LocalArtifact {
+    #[serde(default)]
+    deployed_bytecode: DeployedBytecode,
+    #[serde(default)]
+    raw_metadata: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DeployedBytecode {
+    #[serde(default)]
+    object: String,
+    #[serde(default)]
+    immutable_references: BTreeMap<String, Vec<Offsets>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Offsets {
+    start: usize,
+    length: usize,
+}
+
+impl LocalArtifact {
+    /// Returns how closely the deployed `code` matches the artifact, if it does.
+    ///
+    /// The immutables are set at deployment, so they are ignored.
+    fn matches(&self, code: &[u8]) -> Option<MatchKind> {
+        // Unlinked bytecode isn't valid hex.
+        let expected = self.deployed_bytecode.object.parse::<Bytes>().ok()?;
+        if expected.is_empty() || expected.len() != code.len() {
+            return None
+        }
+        let mut code = code.to_vec();
+        for Offsets { start, length } in
+            self.deployed_bytecode.immutable_references.values().flatten()
+        {
+            if let Some(immutable) = code.get_mut(*start..start + length) {
+                immutable.fill(0);
+            }
+        }
+        if code == expected[..] {
+            return Some(MatchKind::Full)
+        }
+        let (code, expected) = (strip_metadata(&code), strip_metadata(&expected));
+        (code == expected).then_some(MatchKind::Partial)
+    }
+}
+
+/// Strips the CBOR encoded metadata appended to the code by solc, whose length is given by the
+/// last two bytes.
+fn strip_metadata(code: &[u8]) -> &[u8] {
+    let Some(len) = code.len().checked_sub(2) else { return code };
+    let metadata_len = u16::from_be_bytes([code[len], code[len + 1]]) as usize;
+    code.get(..len.saturating_sub(metadata_len)).filter(|_| metadata_len <= len).unwrap_or(code)
+}
+
+/// A file of a verified contract.
+#[derive(Debug, Serialize)]
+struct SourceFile {
+    name: String,
+    path: String,
+    content: String,
+}
+
+/// The solc metadata, of which only the sources are needed.
+#[derive(Deserialize)]
+struct Metadata {
+    #[serde(default)]
+    sources: BTreeMap<String, MetadataSource>,
+}
+
+#[derive(Deserialize)]
+struct MetadataSource {
+    #[serde(default)]
+    content: Option<String>,
+}
+
+/// A contract matched against a local artifact.
+struct VerifiedContract {
+    kind: MatchKind,
+    /// The raw solc metadata.
+    metadata: String,
+    /// The sources listed in the metadata, by their path.
+    sources: BTreeMap<String, String>,
+}
+
+impl VerifiedContract {
+    /// Returns the files of the contract, the metadata first, with their paths in the repository
+    /// layout.
+    fn files(&self, chain: u64, address: Address) -> Vec<SourceFile> {
+        let dir = format!("contracts/{}/{chain}/{address}", self.kind.repository_dir());
+        let metadata = SourceFile {
+            name: "metadata.json".to_string(),
+            path: format!("{dir}/metadata.json"),
+            content: self.metadata.clone(),
+        };
+        let sources = self.sources.iter().map(|(path, content)| SourceFile {
+            name: path.rsplit('/').next().unwrap_or(path).to_string(),
+            path: format!("{dir}/sources/{path}"),
+            content: content.clone(),
+        });
+        std::iter::once(metadata).chain(sources).collect()
+    }
+}
+
+impl SourcifyState {
+    /// Matches the code deployed at `address` against the artifacts of the project.
+    ///
+    /// Returns `None` if the chain isn't the one of the node or no artifact matches.
+    async fn verify(&self, chain: u64, address: Address) -> Option<VerifiedContract> {
+        if chain != self.api.chain_id() {
+            return None
+        }
+        let code = self.api.get_code(address, None).await.ok()?;
+        if code.is_empty() {
+            return None
+        }
+
+        let root = self.root.as_path();
+        let config = Config::load_with_root(root);
+        let out = root.join(&config.out);
+        // Prefer a full match, e.g. over a copy of the contract compiled with other settings.
+        let mut found = None;
+        for path in artifact_files(&out) {
+            let Ok(artifact) = foundry_common::fs::read_json_file::<LocalArtifact>(&path) else {
+                continue
+            };
+            let Some(metadata) = artifact.raw_metadata.clone() else { continue };
+            match artifact.matches(&code) {
+                Some(MatchKind::Full) => {
+                    found = Some((MatchKind::Full, metadata));
+                    break
+                }
+                Some(MatchKind::Partial) if found.is_none() => {
+                    found = Some((MatchKind::Partial, metadata));
+                }
+                _ => {}
+            }
+        }
+        let (kind, metadata) = found?;
+
+        let sources = serde_json::from_str::<Metadata>(&metadata)
+            .ok()?
+            .sources
+            .into_iter()
+            .filter_map(|(path, source)| {
+                let content = match source.content {
+                    Some(content) => content,
+                    // Only the files listed in the metadata are read, and only inside the project.
+                    None if is_relative(&path) => std::fs::read_to_string(root.join(&path)).ok()?,
+                    None => return None,
+                };
+                Some((path, content))
+            })
+            .collect();
+        Some(VerifiedContract { kind, metadata, sources })
+    }
+}
+
+/// Returns the paths of the artifacts in the `out` directory, `out/<file>/<contract>.json`.
+fn artifact_files(out: &std::path::Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(out) else { return Vec::new() };
+    entries
+        .flatten()
+        .filter(|entry| entry.file_name() != "build-info")
+        .filter_map(|entry| std::fs::read_dir(entry.path()).ok())
+        .flat_map(|dir| dir.flatten().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect()
+}
+
+/// Returns whether the path stays inside the directory it is relative to.
+fn is_relative(path: &str) -> bool {
+    std::path::Path::new(path).components().all(|c| matches!(c, Component::Normal(_)))
+}
+
+fn not_found() -> Response {
+    let error = serde_json::json!({ "error": "Files have not been found!" });
+    (StatusCode::NOT_FOUND, Json(error)).into_response()
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CheckQuery {
+    addresses: String,
+    chain_ids: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CheckResult {
+    address: Address,
+    status: &'static str,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    chain_ids: Vec<String>,
+}
+
+/// Returns whether the contracts at the given addresses are verified on any of the given chains.
+async fn check_by_addresses(
+    State(state): State<SourcifyState>,
+    Query(query): Query<CheckQuery>,
+) -> Response {
+    let chains = query.chain_ids.split(',').filter_map(|chain| chain.trim().parse::<u64>().ok());
+    let chains = chains.collect::<Vec<_>>();
+    let mut results = Vec::new();
+    for address in query.addresses.split(',') {
+        let Ok(address) = address.trim().parse::<Address>() else {
+            let error = serde_json::json!({ "error": format!("invalid address `{address}`") });
+            return (StatusCode::BAD_REQUEST, Json(error)).into_response()
+        };
+        let mut result = CheckResult { address, status: "false", chain_ids: Vec::new() };
+        for &chain in &chains {
+            if let Some(contract) = state.verify(chain, address).await {
+                result.status = match contract.kind {
+                    MatchKind::Full => "perfect",
+                    MatchKind::Partial => "partial",
+                };
+                result.chain_ids.push(chain.to_string());
+            }
+        }
+        results.push(result);
+    }
+    Json(results).into_response()
+}
+
+/// Returns the files of a contract verified with a full or partial match.
+async fn files_any(
+    State(state): State<SourcifyState>,
+    Path((chain, address)): Path<(u64, Address)>,
+) -> Response {
+    let Some(contract) = state.verify(chain, address).await else { return not_found() };
+    let status = match contract.kind {
+        MatchKind::Full => "full",
+        MatchKind::Partial => "partial",
+    };
+    Json(serde_json::json!({ "status": status, "files": contract.files(chain, address) }))
+        .into_response()
+}
+
+/// Returns the files of a contract verified with a full match.
+async fn files_full(
+    State(state): State<SourcifyState>,
+    Path((chain, address)): Path<(u64, Address)>,
+) -> Response {
+    match state.verify(chain, address).await {
+        Some(contract) if contract.kind == MatchKind::Full => {
+            Json(contract.files(chain, address)).into_response()
+        }
+        _ => not_found(),
+    }
+}
+
+/// Returns a file of the repository layout: `metadata.json` or `sources/<path>`.
+async fn repository_file(
+    State(state): State<SourcifyState>,
+    Path((kind, chain, address, file)): Path<(String, u64, Address, String)>,
+) -> Response {
+    let Some(contract) = state.verify(chain, address).await else { return not_found() };
+    // A full match is also listed as a partial match.
+    match (kind.as_str(), contract.kind) {
+        ("full_match", MatchKind::Full) | ("partial_match", _) => {}
+        _ => return not_found(),
+    }
+    let content = match file.strip_prefix("sources/") {
+        Some(path) => contract.sources.get(path).cloned(),
+        None => (file == "metadata.json").then_some(contract.metadata),
+    };
+    match content {
+        Some(content) => content.into_response(),
+        None => not_found(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn artifact(code: &str, immutables: &[(usize, usize)]) -> LocalArtifact {
+        let offsets = immutables.iter().map(|&(start, length)| Offsets { start, length });
+        LocalArtifact {
+            deployed_bytecode: DeployedBytecode {
+                object: code.to_string(),
+                immutable_references: BTreeMap::from([("1".to_string(), offsets.collect())]),
+            },
+            raw_metadata: None,
+        }
+    }
+
+    #[test]
+    fn matches_deployed_code() {
+        // `PUSH32 <immutable>` followed by a 3 bytes long metadata.
+        let immutable = "00".repeat(32);
+        let artifact = artifact(&format!("0x7f{immutable}a1b2c30003"), &[(1, 32)]);
+
+        let deployed = |immutable: &str, metadata: &str| {
+            Bytes::from(alloy_primitives::hex::decode(format!("7f{immutable}{metadata}")).unwrap())
+        };
+        let set = "11".repeat(32);
+        assert_eq!(artifact.matches(&deployed(&set, "a1b2c30003")), Some(MatchKind::Full));
+        assert_eq!(artifact.matches(&deployed(&set, "a1b2c40003")), Some(MatchKind::Partial));
+        assert_eq!(artifact.matches(&deployed(&set, "a1b2c40004")), None);
+        assert_eq!(artifact.matches(&deployed(&set[2..], "a1b2c30003")), None);
+    }
+
+    #[test]
+    fn strips_metadata() {
+        assert_eq!(strip_metadata(&[1, 2, 3, 0, 2]), &[1]);
+        assert_eq!(strip_metadata(&[1, 0, 9]), &[1, 0, 9]);
+        assert_eq!(strip_metadata(&[1]), &[1]);
+    }
+
+    #[test]
+    fn only_reads_sources_inside_the_project() {
+        assert!(is_relative("src/Counter.sol"));
+        assert!(is_relative("lib/forge-std/src/Test.sol"));
+        assert!(!is_relative("../secrets.txt"));
+        assert!(!is_relative("/etc/passwd"));
+    }
+}
@@ -0,0 +1,125 @@
+//! Dev dashboard served by the node when launched with `--ui`.
+//!
+//! The dashboard is a single static page that queries the node's own JSON-RPC endpoint, mostly
+//! through the `eth_` and `ots_` namespaces. Call traces are decoded and rendered by the node, with
+//! the contracts of the local Foundry project and the signature identifier, like in `forge test`.
+
+use crate::EthApi;
+use alloy_primitives::B256;
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::Html,
+    routing::get,
+    Router,
+};
+use foundry_common::{compile::ProjectCompiler, ContractsByArtifact};
+use foundry_config::Config;
+use foundry_evm::traces::{
+    identifier::{LocalTraceIdentifier, SignaturesIdentifier, SingleSignaturesIdentifier},
+    render_trace_arena, CallTraceArena, CallTraceDecoderBuilder,
+};
+use std::{path::PathBuf, sync::Arc};
+
+/// The dashboard page.
+const INDEX_HTML: &str = include_str!("ui.html");
+
+/// Configures an [`axum::Router`] that serves the dev dashboard at `/ui`, decoding traces with the
+/// contracts of the project at `root`, if any.
+pub fn router(api: EthApi, root: Option<PathBuf>) -> Router {
+    let config = root.as_deref().map_or_else(Config::default, Config::load_with_root);
+    let signatures = SignaturesIdentifier::from_config(&config)
+        .inspect_err(|err| warn!(target: "node", "failed to load the signature identifier: {err}"))
+        .ok();
+    let state = UiState { api, root: root.map(Arc::new), signatures };
+    Router::new().route("/ui", get(index)).route("/ui/trace/:hash", get(trace)).with_state(state)
+}
+
+#[derive(Clone)]
+struct UiState {
+    api: EthApi,
+    root: Option<Arc<PathBuf>>,
+    signatures: Option<SingleSignaturesIdentifier>,
+}
+
+impl UiState {
+    /// Returns the contracts of the project, if any.
+    ///
+    /// The project is compiled on every request, so that changed contracts are picked up without
+    /// restarting the node. Up to date sources are read from the compiler cache.
+    async fn known_contracts(&self) -> eyre::Result<ContractsByArtifact> {
+        let Some(root) = self.root.clone() else { return Ok(ContractsByArtifact::default()) };
+        tokio::task::spawn_blocking(move || {
+            let project = Config::load_with_root(root.as_path()).project()?;
+            if !project.paths.has_input_files() {
+                return Ok(ContractsByArtifact::default())
+            }
+            let output = ProjectCompiler::new().quiet(true).compile(&project)?;
+            Ok(ContractsByArtifact::new(
+                output.artifact_ids().map(|(id, artifact)| (id, artifact.clone().into())),
+            ))
+        })
+        .await?
+    }
+}
+
+async fn index() -> Html<&'static str> {
+    Html(INDEX_HTML)
+}
+
+/// Returns the decoded call trace of a mined transaction.
+async fn trace(State(state): State<UiState>, Path(hash): Path<B256>) -> (StatusCode, String) {
+    let Some(tx) = state.api.backend.mined_transaction(hash) else {
+        return (StatusCode::NOT_FOUND, format!("transaction {hash} not found"))
+    };
+
+    let mut arena = CallTraceArena::default();
+    *arena.nodes_mut() = tx.info.traces;
+
+    let contracts = match state.known_contracts().await {
+        Ok(contracts) => contracts,
+        Err(err) => return (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()),
+    };
+    let mut builder = CallTraceDecoderBuilder::new().with_known_contracts(&contracts);
+    if let Some(signatures) = state.signatures.clone() {
+        builder = builder.with_signature_identifier(signatures);
+    }
+    let mut decoder = builder.build();
+    decoder.identify(&arena, &mut LocalTraceIdentifier::new(&contracts));
+    match render_trace_arena(&arena, &decoder).await {
+        Ok(trace) => (StatusCode::OK, strip_ansi(&trace)),
+        Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()),
+    }
+}
+
+/// Removes the ANSI escape sequences used to color the rendered traces in the terminal.
+fn strip_ansi(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            // Skip the control sequence up to and including its final byte.
+            if chars.next() == Some('[') {
+                for c in chars.by_ref() {
+                    if ('@'..='~').contains(&c) {
+                        break
+                    }
+                }
+            }
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strips_ansi_colors() {
+        assert_eq!(strip_ansi("\x1b[32m[Return]\x1b[0m 1"), "[Return] 1");
+        assert_eq!(strip_ansi("no colors"), "no colors");
+    }
+}
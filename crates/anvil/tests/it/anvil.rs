@@ -1,8 +1,16 @@
 //! tests for anvil specific logic
 
-use alloy_primitives::Address;
+use alloy_primitives::{Address, B256, U256};
 use alloy_provider::Provider;
-use anvil::{spawn, NodeConfig};
+use alloy_rpc_types::TransactionRequest;
+use alloy_serde::WithOtherFields;
+use anvil::{spawn, try_spawn, NodeConfig};
+use anvil_core::types::MiningModeConfig;
+use axum::{
+    body::{to_bytes, Body},
+    http::{Request, StatusCode},
+};
+use tower::ServiceExt;
 
 #[tokio::test(flavor = "multi_thread")]
 async fn test_can_change_mining_mode() {
@@ -33,6 +41,101 @@ async fn test_can_change_mining_mode() {
     assert_eq!(num, 1);
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_mixed_mining() {
+    let (api, handle) = spawn(
+        NodeConfig::test()
+            .with_blocktime(Some(std::time::Duration::from_secs(2)))
+            .with_mixed_mining(true),
+    )
+    .await;
+    let provider = handle.http_provider();
+    assert!(api.anvil_get_auto_mine().unwrap());
+
+    // transactions are mined right away
+    let accounts = handle.dev_accounts().collect::<Vec<_>>();
+    let tx = TransactionRequest::default().to(accounts[1]).value(U256::from(1)).from(accounts[0]);
+    let tx = provider.send_transaction(WithOtherFields::new(tx)).await.unwrap();
+    let receipt = tx.get_receipt().await.unwrap();
+    assert_eq!(receipt.block_number, Some(1));
+
+    // an empty block is mined once idle for the block time
+    tokio::time::sleep(std::time::Duration::from_millis(1000)).await;
+    assert_eq!(provider.get_block_number().await.unwrap(), 1);
+    tokio::time::sleep(std::time::Duration::from_millis(1500)).await;
+    assert_eq!(provider.get_block_number().await.unwrap(), 2);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_can_set_mining_mode() {
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    let provider = handle.http_provider();
+
+    api.anvil_set_mining_mode(MiningModeConfig::None).unwrap();
+    assert!(!api.anvil_get_auto_mine().unwrap());
+    assert!(api.anvil_set_mining_mode(MiningModeConfig::Mixed { block_time: 0 }).is_err());
+
+    api.anvil_set_mining_mode(MiningModeConfig::Mixed { block_time: 1 }).unwrap();
+    assert!(api.anvil_get_auto_mine().unwrap());
+    tokio::time::sleep(std::time::Duration::from_millis(1200)).await;
+    assert_eq!(provider.get_block_number().await.unwrap(), 1);
+
+    api.anvil_set_mining_mode(MiningModeConfig::Auto).unwrap();
+    tokio::time::sleep(std::time::Duration::from_millis(1200)).await;
+    assert_eq!(provider.get_block_number().await.unwrap(), 1);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_can_query_sqlite_db() {
+    let tmp = tempfile::tempdir().unwrap();
+    let path = tmp.path().join("anvil.db");
+    let (api, handle) = spawn(NodeConfig::test().with_sqlite_db(Some(path.clone()))).await;
+    let provider = handle.http_provider();
+
+    let accounts = handle.dev_accounts().collect::<Vec<_>>();
+    let tx = TransactionRequest::default().to(accounts[1]).value(U256::from(1)).from(accounts[0]);
+    let tx = provider.send_transaction(WithOtherFields::new(tx)).await.unwrap();
+    let receipt = tx.get_receipt().await.unwrap();
+    api.evm_mine(None).await.unwrap();
+
+    let blocks =
+        api.anvil_query("SELECT number, transaction_count FROM blocks".to_string()).unwrap();
+    assert_eq!(blocks.len(), 2);
+    assert_eq!(blocks[0]["number"], 1);
+    assert_eq!(blocks[0]["transaction_count"], 1);
+    assert_eq!(blocks[1]["transaction_count"], 0);
+
+    let txs = api
+        .anvil_query(format!(
+            "SELECT hash, value, status FROM transactions WHERE from_address = '{:#x}'",
+            accounts[0]
+        ))
+        .unwrap();
+    assert_eq!(txs.len(), 1);
+    assert_eq!(txs[0]["hash"], format!("{:#x}", receipt.transaction_hash));
+    assert_eq!(txs[0]["value"], "1");
+    assert_eq!(txs[0]["status"], 1);
+
+    // only read-only statements are allowed
+    assert!(api.anvil_query("DELETE FROM blocks".to_string()).is_err());
+    assert!(api.anvil_query("SELECT * FROM blocks; DELETE FROM blocks".to_string()).is_err());
+    assert_eq!(api.anvil_query("SELECT * FROM blocks".to_string()).unwrap().len(), 2);
+
+    // the database can be read independently of the node
+    let db = anvil::eth::backend::sqlite::SqliteDb::open(&path).unwrap();
+    assert_eq!(db.query("SELECT * FROM transactions").unwrap().len(), 1);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_fails_on_unopenable_sqlite_db() {
+    let tmp = tempfile::tempdir().unwrap();
+    let path = tmp.path().join("missing").join("anvil.db");
+    let Err(err) = try_spawn(NodeConfig::test().with_sqlite_db(Some(path))).await else {
+        panic!("expected anvil to fail to start");
+    };
+    assert!(err.to_string().contains("failed to open SQLite database"), "{err}");
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn can_get_default_dev_keys() {
     let (_api, handle) = spawn(NodeConfig::test()).await;
@@ -76,3 +179,113 @@ async fn test_can_use_default_genesis_timestamp() {
         provider.get_block(0.into(), false.into()).await.unwrap().unwrap().header.timestamp
     );
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_serve_ui() {
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    let provider = handle.http_provider();
+    let router =
+        anvil::server::router(api, anvil_server::ServerConfig { ui: true, ..Default::default() });
+
+    let res = router.clone().oneshot(Request::get("/ui").body(Body::empty()).unwrap()).await;
+    assert_eq!(res.unwrap().status(), StatusCode::OK);
+
+    let accounts = handle.dev_accounts().collect::<Vec<_>>();
+    let tx = TransactionRequest::default().to(accounts[1]).value(U256::from(1)).from(accounts[0]);
+    let tx = provider.send_transaction(WithOtherFields::new(tx)).await.unwrap();
+    let hash = tx.get_receipt().await.unwrap().transaction_hash;
+
+    let uri = format!("/ui/trace/{hash}");
+    let res = router.clone().oneshot(Request::get(uri).body(Body::empty()).unwrap()).await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let trace = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+    let trace = String::from_utf8(trace.to_vec()).unwrap();
+    assert!(trace.contains("[Stop]"), "{trace}");
+    assert!(!trace.contains('\x1b'));
+
+    let uri = format!("/ui/trace/{}", B256::ZERO);
+    let res = router.oneshot(Request::get(uri).body(Body::empty()).unwrap()).await.unwrap();
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_serve_verified_sources() {
+    let (api, _handle) = spawn(NodeConfig::test()).await;
+    let project = tempfile::tempdir().unwrap();
+    let root = project.path();
+    std::fs::create_dir_all(root.join("src")).unwrap();
+    std::fs::create_dir_all(root.join("out/Store.sol")).unwrap();
+    std::fs::write(root.join("src/Store.sol"), "contract Store {}").unwrap();
+    let artifact = serde_json::json!({
+        "deployedBytecode": { "object": "0x60006000f3a1b2c30003" },
+        "rawMetadata": r#"{"sources":{"src/Store.sol":{"urls":[]}}}"#,
+    });
+    std::fs::write(root.join("out/Store.sol/Store.json"), artifact.to_string()).unwrap();
+
+    let store = Address::random();
+    api.anvil_set_code(store, "0x60006000f3a1b2c30003".parse().unwrap()).await.unwrap();
+    let router = anvil::server::router(
+        api.clone(),
+        anvil_server::ServerConfig::default().with_sourcify(root),
+    );
+    let get = |uri: String| {
+        let router = router.clone();
+        async move {
+            let res = router.oneshot(Request::get(uri).body(Body::empty()).unwrap()).await.unwrap();
+            let status = res.status();
+            let body = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+            (status, String::from_utf8(body.to_vec()).unwrap())
+        }
+    };
+
+    let chain = api.chain_id();
+    let (status, body) = get(format!("/sourcify/files/any/{chain}/{store}")).await;
+    assert_eq!(status, StatusCode::OK);
+    let files: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(files["status"], "full");
+    assert_eq!(files["files"][1]["content"], "contract Store {}");
+
+    let path = format!("/sourcify/contracts/full_match/{chain}/{store}/sources/src/Store.sol");
+    assert_eq!(get(path).await, (StatusCode::OK, "contract Store {}".to_string()));
+
+    let other = Address::random();
+    let (_, body) =
+        get(format!("/sourcify/check-by-addresses?addresses={store},{other}&chainIds={chain}"))
+            .await;
+    let checks: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(checks[0]["status"], "perfect");
+    assert_eq!(checks[1]["status"], "false");
+
+    let (status, _) = get(format!("/sourcify/files/any/{}/{store}", chain + 1)).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_restrict_methods_to_origins() {
+    let (api, _handle) = spawn(NodeConfig::test()).await;
+    let config = anvil_server::ServerConfig::default()
+        .with_method_origin("anvil_*=https://admin.example.com".parse().unwrap());
+    let router = anvil::server::router(api, config);
+
+    let call = |origin: &'static str, method: &'static str| {
+        let body = format!(r#"{{"jsonrpc":"2.0","id":1,"method":"{method}","params":[]}}"#);
+        let request = Request::post("/")
+            .header("content-type", "application/json")
+            .header("origin", origin)
+            .body(Body::from(body))
+            .unwrap();
+        let router = router.clone();
+        async move {
+            let res = router.oneshot(request).await.unwrap();
+            let body = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+            String::from_utf8(body.to_vec()).unwrap()
+        }
+    };
+
+    let res = call("https://example.com", "anvil_mine").await;
+    assert!(res.contains("origin is not allowed to call `anvil_mine`"), "{res}");
+    let res = call("https://admin.example.com", "anvil_mine").await;
+    assert!(res.contains(r#""result""#), "{res}");
+    let res = call("https://example.com", "eth_blockNumber").await;
+    assert!(res.contains(r#""result""#), "{res}");
+}
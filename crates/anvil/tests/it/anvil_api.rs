@@ -6,7 +6,7 @@ use crate::{
     utils::http_provider_with_signer,
 };
 use alloy_network::{EthereumWallet, TransactionBuilder};
-use alloy_primitives::{address, fixed_bytes, Address, U256};
+use alloy_primitives::{address, bytes, fixed_bytes, Address, Bytes, U256};
 use alloy_provider::{ext::TxPoolApi, Provider};
 use alloy_rpc_types::{
     anvil::{ForkedNetwork, Forking, Metadata, NodeEnvironment, NodeForkConfig, NodeInfo},
@@ -14,7 +14,10 @@ use alloy_rpc_types::{
 };
 use alloy_serde::WithOtherFields;
 use anvil::{eth::api::CLIENT_VERSION, spawn, Hardfork, NodeConfig};
-use anvil_core::eth::EthRequest;
+use anvil_core::{
+    eth::EthRequest,
+    types::{DebugBreakpoint, ImpersonateContractRequest, ReorgTransaction},
+};
 use foundry_evm::revm::primitives::SpecId;
 use std::{
     str::FromStr,
@@ -211,6 +214,57 @@ async fn can_impersonate_gnosis_safe() {
     assert!(!code.is_empty());
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn can_execute_as_contract() {
+    let (api, _handle) = spawn(NodeConfig::test()).await;
+
+    let timelock = Address::random();
+    let target = Address::random();
+    // STOP
+    let timelock_code = Bytes::from_static(&[0x00]);
+    api.anvil_set_code(timelock, timelock_code.clone()).await.unwrap();
+    api.anvil_set_balance(timelock, U256::from(1e18 as u64)).await.unwrap();
+    // CALLER PUSH1 0 SSTORE STOP
+    api.anvil_set_code(target, bytes!("3360005500")).await.unwrap();
+
+    // forward a call with value from the timelock
+    let value = U256::from(1337);
+    api.anvil_impersonate_contract(ImpersonateContractRequest {
+        address: timelock,
+        to: Some(target),
+        value: Some(value),
+        ..Default::default()
+    })
+    .await
+    .unwrap();
+
+    let caller = api.storage_at(target, U256::ZERO, None).await.unwrap();
+    assert_eq!(Address::from_word(caller), timelock);
+    assert_eq!(api.balance(target, None).await.unwrap(), value);
+    assert_eq!(api.get_code(timelock, None).await.unwrap(), timelock_code);
+
+    // run code in the context of the timelock: PUSH1 42 PUSH1 0 SSTORE STOP
+    api.anvil_impersonate_contract(ImpersonateContractRequest {
+        address: timelock,
+        code: Some(bytes!("602a60005500")),
+        ..Default::default()
+    })
+    .await
+    .unwrap();
+
+    let slot = api.storage_at(timelock, U256::ZERO, None).await.unwrap();
+    assert_eq!(U256::from_be_bytes(slot.0), U256::from(42));
+    assert_eq!(api.get_code(timelock, None).await.unwrap(), timelock_code);
+
+    // exactly one of `code` and `to` must be set
+    api.anvil_impersonate_contract(ImpersonateContractRequest {
+        address: timelock,
+        ..Default::default()
+    })
+    .await
+    .unwrap_err();
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn can_impersonate_multiple_accounts() {
     let (api, handle) = spawn(NodeConfig::test()).await;
@@ -656,3 +710,121 @@ async fn can_remove_pool_transactions() {
     let final_txs = provider.txpool_inspect().await.unwrap();
     assert_eq!(final_txs.pending.len(), 0);
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_rewind_to_block() {
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    let provider = handle.http_provider();
+    let accounts = handle.dev_accounts().collect::<Vec<_>>();
+
+    let tx = TransactionRequest::default()
+        .with_from(accounts[0])
+        .with_to(accounts[1])
+        .with_value(U256::from(1337));
+    let tx = WithOtherFields::new(tx);
+    provider.send_transaction(tx.clone()).await.unwrap().get_receipt().await.unwrap();
+    let balance = provider.get_balance(accounts[1]).await.unwrap();
+    let block = provider.get_block(BlockId::latest(), false.into()).await.unwrap().unwrap();
+
+    let receipt = provider.send_transaction(tx.clone()).await.unwrap().get_receipt().await.unwrap();
+    assert_eq!(api.block_number().unwrap(), U256::from(2));
+
+    api.anvil_rewind_to_block(U256::from(1)).await.unwrap();
+    assert_eq!(api.block_number().unwrap(), U256::from(1));
+    assert_eq!(provider.get_balance(accounts[1]).await.unwrap(), balance);
+    assert!(provider.get_transaction_receipt(receipt.transaction_hash).await.unwrap().is_none());
+    let latest = provider.get_block(BlockId::latest(), false.into()).await.unwrap().unwrap();
+    assert_eq!(latest.header.hash, block.header.hash);
+
+    // the chain continues from the rewound block
+    let receipt = provider.send_transaction(tx).await.unwrap().get_receipt().await.unwrap();
+    assert_eq!(receipt.block_number, Some(2));
+    assert_eq!(provider.get_transaction_count(accounts[0]).await.unwrap(), 2);
+
+    assert!(api.anvil_rewind_to_block(U256::from(3)).await.is_err());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_reorg() {
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    let provider = handle.http_provider();
+    let accounts = handle.dev_accounts().collect::<Vec<_>>();
+
+    let tx = TransactionRequest::default()
+        .with_from(accounts[0])
+        .with_to(accounts[1])
+        .with_value(U256::from(1337));
+    let mut receipts = Vec::new();
+    for _ in 0..3 {
+        let tx = WithOtherFields::new(tx.clone());
+        receipts.push(provider.send_transaction(tx).await.unwrap().get_receipt().await.unwrap());
+    }
+    let old_block = provider.get_block(BlockId::number(3), false.into()).await.unwrap().unwrap();
+
+    // replace the last two blocks, with a transfer to another account in the last one
+    let reorg_tx = TransactionRequest::default()
+        .with_from(accounts[0])
+        .with_to(accounts[2])
+        .with_value(U256::from(42));
+    let txs = vec![(ReorgTransaction::Request(WithOtherFields::new(reorg_tx)), 1)];
+    let balance = provider.get_balance(accounts[2]).await.unwrap();
+    api.anvil_reorg(U256::from(2), txs).await.unwrap();
+
+    assert_eq!(api.block_number().unwrap(), U256::from(3));
+    let new_block = provider.get_block(BlockId::number(3), false.into()).await.unwrap().unwrap();
+    assert_ne!(new_block.header.hash, old_block.header.hash);
+    assert_eq!(new_block.transactions.len(), 1);
+    let empty = provider.get_block(BlockId::number(2), false.into()).await.unwrap().unwrap();
+    assert!(empty.transactions.is_empty());
+
+    assert!(provider
+        .get_transaction_receipt(receipts[0].transaction_hash)
+        .await
+        .unwrap()
+        .is_some());
+    for receipt in &receipts[1..] {
+        let receipt = provider.get_transaction_receipt(receipt.transaction_hash).await.unwrap();
+        assert!(receipt.is_none());
+    }
+    assert_eq!(provider.get_balance(accounts[2]).await.unwrap(), balance + U256::from(42));
+    assert_eq!(provider.get_transaction_count(accounts[0]).await.unwrap(), 2);
+
+    // transactions can only go into the new blocks
+    let tx = ReorgTransaction::Request(WithOtherFields::new(tx));
+    assert!(api.anvil_reorg(U256::from(1), vec![(tx, 1)]).await.is_err());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_step_through_call() {
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    let from = handle.dev_accounts().next().unwrap();
+
+    // PUSH1 1, PUSH1 2, ADD, PUSH1 0, MSTORE, STOP
+    let tx = TransactionRequest::default()
+        .with_from(from)
+        .with_deploy_code(bytes!("600160020160005200"));
+    let state = api.anvil_debug_start(WithOtherFields::new(tx), None).await.unwrap();
+    let id = state.id;
+    assert_eq!((state.step, state.total_steps, state.pc), (0, 6, 0));
+    assert_eq!(state.op, "PUSH1");
+    assert!(!state.finished);
+
+    let state = api.anvil_debug_step(id, Some(2)).unwrap();
+    assert_eq!((state.pc, state.op.as_str()), (4, "ADD"));
+    assert_eq!(api.anvil_debug_stack(id).unwrap(), vec![U256::from(1), U256::from(2)]);
+
+    let state =
+        api.anvil_debug_continue(id, Some(DebugBreakpoint { pc: 8, address: None })).unwrap();
+    assert_eq!((state.pc, state.op.as_str()), (8, "STOP"));
+    assert!(state.finished);
+    let memory = api.anvil_debug_memory(id).unwrap();
+    assert_eq!(memory.len(), 32);
+    assert_eq!(memory[31], 3);
+
+    // stepping past the end stays at the last step
+    assert_eq!(api.anvil_debug_step(id, None).unwrap().step, 5);
+
+    assert!(api.anvil_debug_stop(id).unwrap());
+    assert!(!api.anvil_debug_stop(id).unwrap());
+    assert!(api.anvil_debug_step(id, None).is_err());
+}
@@ -0,0 +1,57 @@
+use crate::utils::http_provider;
+use alloy_consensus::SignableTransaction;
+use alloy_primitives::{address, bytes, U256};
+use alloy_provider::Provider;
+use alloy_signer::SignerSync;
+use anvil::{spawn, NodeConfig};
+use foundry_evm::eip7702::{delegation_code, Authorization, TxEip7702};
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_send_eip7702_tx() {
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    let provider = http_provider(&handle.http_endpoint());
+
+    let wallets = handle.dev_wallets().collect::<Vec<_>>();
+    let (sponsor, authority) = (&wallets[0], &wallets[1]);
+
+    // PUSH1 0x01 PUSH1 0x00 SSTORE STOP
+    let delegate = address!("0000000000000000000000000000000000007702");
+    api.anvil_set_code(delegate, bytes!("600160005500")).await.unwrap();
+
+    let chain_id = provider.get_chain_id().await.unwrap();
+    let authorization =
+        Authorization { chain_id: U256::from(chain_id), address: delegate, nonce: 0 };
+    let signature = authority.sign_hash_sync(&authorization.signature_hash()).unwrap();
+
+    let eip1559_est = provider.estimate_eip1559_fees(None).await.unwrap();
+    let tx = TxEip7702 {
+        chain_id,
+        nonce: 0,
+        gas_limit: 100_000,
+        max_fee_per_gas: eip1559_est.max_fee_per_gas,
+        max_priority_fee_per_gas: eip1559_est.max_priority_fee_per_gas,
+        to: authority.address(),
+        authorization_list: vec![authorization.into_signed(signature)],
+        ..Default::default()
+    };
+    let signature = sponsor.sign_hash_sync(&tx.signature_hash()).unwrap();
+    let tx = tx.into_signed(signature);
+    let mut encoded = Vec::new();
+    tx.tx().encode_with_signature(tx.signature(), &mut encoded);
+
+    let receipt =
+        provider.send_raw_transaction(&encoded).await.unwrap().get_receipt().await.unwrap();
+    assert!(receipt.status());
+    assert_eq!(receipt.inner.inner.r#type, 4);
+
+    // The authority delegates to the contract, which ran with the storage of the authority.
+    let code = provider.get_code_at(authority.address()).await.unwrap();
+    assert_eq!(code, delegation_code(delegate));
+    let value = provider.get_storage_at(authority.address(), U256::ZERO).await.unwrap();
+    assert_eq!(value, U256::from(1));
+    let nonce = provider.get_transaction_count(authority.address()).await.unwrap();
+    assert_eq!(nonce, 1);
+
+    let fetched = provider.get_transaction_by_hash(*tx.hash()).await.unwrap().unwrap();
+    assert_eq!(fetched.transaction_type, Some(4));
+}
@@ -7,6 +7,7 @@ use alloy_provider::Provider;
 use alloy_rpc_types::{BlockId, TransactionRequest};
 use alloy_serde::WithOtherFields;
 use anvil::{eth::fees::INITIAL_BASE_FEE, spawn, NodeConfig};
+use anvil_core::types::BaseFeeParamsUpdate;
 
 const GAS_TRANSFER: u128 = 21_000;
 
@@ -129,6 +130,85 @@ async fn test_basefee_empty_block() {
     assert!(next_base_fee < base_fee);
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_basefee_custom_params() {
+    let (_api, handle) = spawn(
+        NodeConfig::test()
+            .with_base_fee(Some(INITIAL_BASE_FEE))
+            .with_gas_limit(Some(GAS_TRANSFER))
+            .with_base_fee_change_denominator(Some(4)),
+    )
+    .await;
+
+    let wallet = handle.dev_wallets().next().unwrap();
+    let signer: EthereumWallet = wallet.clone().into();
+
+    let provider = http_provider_with_signer(&handle.http_endpoint(), signer);
+
+    let tx = TransactionRequest::default().to(Address::random()).with_value(U256::from(1337));
+    let tx = WithOtherFields::new(tx);
+
+    provider.send_transaction(tx.clone()).await.unwrap().get_receipt().await.unwrap();
+    provider.send_transaction(tx.clone()).await.unwrap().get_receipt().await.unwrap();
+
+    let next_base_fee = provider
+        .get_block(BlockId::latest(), false.into())
+        .await
+        .unwrap()
+        .unwrap()
+        .header
+        .base_fee_per_gas
+        .unwrap();
+
+    // max increase of a quarter, full block
+    assert_eq!(next_base_fee, INITIAL_BASE_FEE + 250_000_000);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_min_basefee() {
+    let (api, handle) = spawn(
+        NodeConfig::test()
+            .with_base_fee(Some(INITIAL_BASE_FEE))
+            .with_min_base_fee(Some(INITIAL_BASE_FEE)),
+    )
+    .await;
+    let provider = &handle.http_provider();
+
+    let latest_base_fee = || async move {
+        provider
+            .get_block(BlockId::latest(), false.into())
+            .await
+            .unwrap()
+            .unwrap()
+            .header
+            .base_fee_per_gas
+            .unwrap()
+    };
+
+    // empty blocks do not decrease the base fee below the minimum
+    api.mine_one().await;
+    api.mine_one().await;
+    assert_eq!(latest_base_fee().await, INITIAL_BASE_FEE);
+
+    api.anvil_set_base_fee_params(BaseFeeParamsUpdate {
+        min_base_fee: Some(0),
+        ..Default::default()
+    })
+    .await
+    .unwrap();
+    api.mine_one().await;
+    api.mine_one().await;
+    assert!(latest_base_fee().await < INITIAL_BASE_FEE);
+
+    let err = api
+        .anvil_set_base_fee_params(BaseFeeParamsUpdate {
+            elasticity_multiplier: Some(0),
+            ..Default::default()
+        })
+        .await;
+    assert!(err.is_err());
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn test_respect_base_fee() {
     let base_fee = 50u128;
@@ -3,11 +3,14 @@
 use crate::utils::http_provider_with_signer;
 use alloy_eips::eip2718::Encodable2718;
 use alloy_network::{EthereumWallet, TransactionBuilder};
-use alloy_primitives::{b256, U128, U256};
+use alloy_primitives::{b256, Address, U128, U256};
 use alloy_provider::Provider;
-use alloy_rpc_types::{optimism::OptimismTransactionFields, TransactionRequest};
+use alloy_rpc_types::{
+    optimism::OptimismTransactionFields, BlockId, BlockTransactions, TransactionRequest,
+};
 use alloy_serde::WithOtherFields;
 use anvil::{spawn, Hardfork, NodeConfig};
+use anvil_core::types::DepositRequest;
 
 #[tokio::test(flavor = "multi_thread")]
 async fn test_deposits_not_supported_if_optimism_disabled() {
@@ -144,3 +147,160 @@ async fn test_send_value_raw_deposit_transaction() {
     let after_balance_to = provider.get_balance(to).await.unwrap();
     assert_eq!(after_balance_to, before_balance_to + send_value);
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_deposit_transaction_mints_value() {
+    let (api, handle) =
+        spawn(NodeConfig::test().with_optimism(true).with_hardfork(Some(Hardfork::Paris))).await;
+    let provider = handle.http_provider();
+
+    // the sender has no balance, the transferred value is funded by the mint
+    let from = Address::random();
+    let to = Address::random();
+    let mint = U256::from(1234);
+
+    let tx = TransactionRequest::default()
+        .with_from(from)
+        .with_to(to)
+        .with_value(mint)
+        .with_gas_limit(21000);
+    let tx = WithOtherFields {
+        inner: tx,
+        other: OptimismTransactionFields {
+            source_hash: Some(b256!(
+                "0000000000000000000000000000000000000000000000000000000000000000"
+            )),
+            mint: Some(U128::from(1234)),
+            is_system_tx: Some(false),
+        }
+        .into(),
+    };
+
+    let pending = provider.send_transaction(tx).await.unwrap().register().await.unwrap();
+    api.evm_mine(None).await.unwrap();
+
+    let receipt =
+        provider.get_transaction_receipt(pending.tx_hash().to_owned()).await.unwrap().unwrap();
+    assert!(receipt.inner.inner.status());
+    assert_eq!(provider.get_balance(to).await.unwrap(), mint);
+    assert_eq!(provider.get_balance(from).await.unwrap(), U256::ZERO);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_deposit_transactions_are_mined_first() {
+    let (api, handle) =
+        spawn(NodeConfig::test().with_optimism(true).with_hardfork(Some(Hardfork::Paris))).await;
+    let provider = handle.http_provider();
+
+    api.anvil_set_auto_mine(false).await.unwrap();
+
+    let accounts: Vec<_> = handle.dev_wallets().collect();
+    let from = accounts[0].address();
+    let to = accounts[1].address();
+
+    let gas_price = provider.get_gas_price().await.unwrap();
+    let tx = TransactionRequest::default()
+        .with_from(from)
+        .with_to(to)
+        .with_value(U256::from(1))
+        .with_gas_price(gas_price + 1);
+    let regular = provider.send_transaction(WithOtherFields::new(tx)).await.unwrap();
+
+    let tx = TransactionRequest::default()
+        .with_from(to)
+        .with_to(from)
+        .with_value(U256::from(1))
+        .with_gas_limit(21000);
+    let tx = WithOtherFields {
+        inner: tx,
+        other: OptimismTransactionFields {
+            source_hash: Some(b256!(
+                "0000000000000000000000000000000000000000000000000000000000000001"
+            )),
+            mint: Some(U128::from(0)),
+            is_system_tx: Some(false),
+        }
+        .into(),
+    };
+    let deposit = provider.send_transaction(tx).await.unwrap();
+
+    api.mine_one().await;
+
+    let regular = regular.get_receipt().await.unwrap().transaction_hash;
+    let deposit = deposit.get_receipt().await.unwrap().transaction_hash;
+
+    let block = provider.get_block(BlockId::latest(), false.into()).await.unwrap().unwrap();
+    assert_eq!(block.transactions, BlockTransactions::Hashes(vec![deposit, regular]));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_anvil_deposit_transaction() {
+    let (api, handle) =
+        spawn(NodeConfig::test().with_optimism(true).with_hardfork(Some(Hardfork::Paris))).await;
+    let provider = handle.http_provider();
+
+    api.anvil_set_auto_mine(false).await.unwrap();
+
+    let from = Address::random();
+    let to = Address::random();
+    let request = DepositRequest {
+        from,
+        to: Some(to),
+        mint: U256::from(1000),
+        value: U256::from(600),
+        gas_limit: 21000,
+        ..Default::default()
+    };
+
+    // two identical deposits get different source hashes
+    let first = api.anvil_deposit_transaction(request.clone()).await.unwrap();
+    api.mine_one().await;
+    let second = api.anvil_deposit_transaction(request).await.unwrap();
+    api.mine_one().await;
+    assert_ne!(first, second);
+
+    for hash in [first, second] {
+        let receipt = provider.get_transaction_receipt(hash).await.unwrap().unwrap();
+        assert!(receipt.inner.inner.status());
+    }
+    // the sender keeps what it did not transfer, without paying any fee
+    assert_eq!(provider.get_balance(to).await.unwrap(), U256::from(1200));
+    assert_eq!(provider.get_balance(from).await.unwrap(), U256::from(800));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_anvil_deposit_transaction_requires_optimism() {
+    let (api, _handle) = spawn(NodeConfig::test()).await;
+
+    let request =
+        DepositRequest { from: Address::random(), gas_limit: 21000, ..Default::default() };
+    assert!(api.anvil_deposit_transaction(request).await.is_err());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_regular_transaction_loads_l1_block_info() {
+    let (_api, handle) =
+        spawn(NodeConfig::test().with_optimism(true).with_hardfork(Some(Hardfork::Paris))).await;
+    let provider = handle.http_provider();
+
+    let accounts: Vec<_> = handle.dev_wallets().collect();
+    let from = accounts[0].address();
+    let to = accounts[1].address();
+    let value = U256::from(1);
+    let before = provider.get_balance(from).await.unwrap();
+
+    let tx = TransactionRequest::default().with_from(from).with_to(to).with_value(value);
+    let receipt = provider
+        .send_transaction(WithOtherFields::new(tx))
+        .await
+        .unwrap()
+        .get_receipt()
+        .await
+        .unwrap();
+    assert!(receipt.inner.inner.status());
+
+    // the `L1Block` predeploy is not deployed, so the L1 data fee is zero and only the execution
+    // is paid for
+    let fee = U256::from(receipt.gas_used) * U256::from(receipt.effective_gas_price);
+    assert_eq!(provider.get_balance(from).await.unwrap(), before - value - fee);
+}
@@ -270,3 +270,34 @@ async fn test_sub_new_heads_fast() {
     let numbers = (1..=num).collect::<Vec<_>>();
     assert_eq!(block_numbers, numbers);
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_sub_state_diffs() {
+    let (_api, handle) = spawn(NodeConfig::test()).await;
+    let provider = connect_pubsub(&handle.ws_endpoint()).await;
+
+    let sub_id: U256 =
+        provider.raw_request("anvil_subscribe".into(), ["stateDiffs"]).await.unwrap();
+    let stream: Subscription<serde_json::Value> = provider.get_subscription(sub_id).await.unwrap();
+    let mut diffs = stream.into_stream();
+
+    let accounts: Vec<_> = handle.dev_wallets().collect();
+    let from = accounts[0].address();
+    let to = Address::random();
+    let tx = TransactionRequest::default().from(from).to(to).value(U256::from(1337));
+    let receipt = provider
+        .send_transaction(WithOtherFields::new(tx))
+        .await
+        .unwrap()
+        .get_receipt()
+        .await
+        .unwrap();
+
+    let diff = diffs.next().await.unwrap();
+    assert_eq!(diff["transactionHash"], serde_json::json!(receipt.transaction_hash));
+    assert_eq!(diff["blockNumber"], 1);
+
+    let recipient = &diff["accounts"][to.to_checksum(None)];
+    assert_eq!(recipient["balance"]["from"], serde_json::json!(U256::ZERO));
+    assert_eq!(recipient["balance"]["to"], serde_json::json!(U256::from(1337)));
+}
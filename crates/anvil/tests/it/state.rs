@@ -1,5 +1,10 @@
 //! general eth api tests
 
+use alloy_network::TransactionBuilder;
+use alloy_primitives::U256;
+use alloy_provider::Provider;
+use alloy_rpc_types::TransactionRequest;
+use alloy_serde::WithOtherFields;
 use anvil::{spawn, NodeConfig};
 
 #[tokio::test(flavor = "multi_thread")]
@@ -21,3 +26,64 @@ async fn can_load_state() {
     let num2 = api.block_number().unwrap();
     assert_eq!(num, num2);
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_load_compressed_state() {
+    let tmp = tempfile::tempdir().unwrap();
+    let state_file = tmp.path().join("state.json.gz");
+
+    let (api, _handle) = spawn(NodeConfig::test()).await;
+
+    api.mine_one().await;
+
+    let num = api.block_number().unwrap();
+
+    // `anvil_dumpState` returns the gzip-compressed state
+    let state = api.anvil_dump_state().await.unwrap();
+    foundry_common::fs::write(&state_file, state).unwrap();
+
+    let (api, _handle) = spawn(NodeConfig::test().with_init_state_path(state_file)).await;
+
+    let num2 = api.block_number().unwrap();
+    assert_eq!(num, num2);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_export_and_import_chain() {
+    // both nodes share the same genesis block
+    let config = NodeConfig::test().with_genesis_timestamp(Some(1_700_000_000u64));
+    let (api, handle) = spawn(config.clone()).await;
+    let provider = handle.http_provider();
+    let accounts = handle.dev_accounts().collect::<Vec<_>>();
+
+    let tx = TransactionRequest::default()
+        .with_from(accounts[0])
+        .with_to(accounts[1])
+        .with_value(U256::from(1337));
+    let receipt = provider
+        .send_transaction(WithOtherFields::new(tx))
+        .await
+        .unwrap()
+        .get_receipt()
+        .await
+        .unwrap();
+    api.mine_one().await;
+
+    let chain = api.anvil_export_chain().await.unwrap();
+
+    let (api2, handle2) = spawn(config).await;
+    // the genesis block is skipped
+    assert_eq!(api2.anvil_import_chain(chain.clone()).await.unwrap(), 2);
+    assert_eq!(api2.block_number().unwrap(), api.block_number().unwrap());
+
+    let provider2 = handle2.http_provider();
+    let imported = provider2.get_transaction_receipt(receipt.transaction_hash).await.unwrap();
+    assert_eq!(imported.unwrap().block_number, receipt.block_number);
+    assert_eq!(
+        provider2.get_balance(accounts[1]).await.unwrap(),
+        provider.get_balance(accounts[1]).await.unwrap()
+    );
+
+    // importing the same blocks again is a no-op
+    assert_eq!(api2.anvil_import_chain(chain).await.unwrap(), 0);
+}
@@ -7,7 +7,10 @@ use alloy_provider::{
 };
 use alloy_rpc_types::{
     trace::{
-        geth::{GethDebugTracingCallOptions, GethTrace},
+        geth::{
+            GethDebugBuiltInTracerType, GethDebugTracerType, GethDebugTracingCallOptions,
+            GethDebugTracingOptions, GethTrace, PreStateFrame,
+        },
         parity::{Action, LocalizedTransactionTrace},
     },
     BlockNumberOrTag, TransactionRequest,
@@ -139,6 +142,116 @@ async fn test_transfer_debug_trace_call() {
 }
 
 // <https://github.com/foundry-rs/foundry/issues/2656>
+#[tokio::test(flavor = "multi_thread")]
+async fn test_debug_trace_call_builtin_tracers() {
+    let (_api, handle) = spawn(NodeConfig::test()).await;
+    let wallets = handle.dev_wallets().collect::<Vec<_>>();
+    let deployer: EthereumWallet = wallets[0].clone().into();
+    let provider = http_provider_with_signer(&handle.http_endpoint(), deployer);
+
+    let contract_addr = DebugTraceContract::deploy_builder(provider.clone())
+        .from(wallets[0].clone().address())
+        .deploy()
+        .await
+        .unwrap();
+    let contract = DebugTraceContract::new(contract_addr, provider.clone());
+    let calldata = contract.goodbye().calldata().to_owned();
+
+    let tx = TransactionRequest::default()
+        .from(wallets[1].address())
+        .to(contract_addr)
+        .with_input(calldata);
+    let opts = |tracer| GethDebugTracingCallOptions {
+        tracing_options: GethDebugTracingOptions::default()
+            .with_tracer(GethDebugTracerType::BuiltInTracer(tracer)),
+        ..Default::default()
+    };
+
+    let trace = provider
+        .debug_trace_call(
+            tx.clone(),
+            BlockNumberOrTag::Latest,
+            opts(GethDebugBuiltInTracerType::CallTracer),
+        )
+        .await
+        .unwrap();
+    match trace {
+        GethTrace::CallTracer(frame) => {
+            assert_eq!(frame.from, wallets[1].address());
+            assert_eq!(frame.to, Some(contract_addr));
+            assert_eq!(frame.typ, "CALL");
+        }
+        _ => unreachable!(),
+    }
+
+    let trace = provider
+        .debug_trace_call(
+            tx.clone(),
+            BlockNumberOrTag::Latest,
+            opts(GethDebugBuiltInTracerType::FourByteTracer),
+        )
+        .await
+        .unwrap();
+    match trace {
+        GethTrace::FourByteTracer(frame) => {
+            assert_eq!(frame.0.get("0x75fc8e3c-0"), Some(&1));
+        }
+        _ => unreachable!(),
+    }
+
+    let trace = provider
+        .debug_trace_call(
+            tx,
+            BlockNumberOrTag::Latest,
+            opts(GethDebugBuiltInTracerType::PreStateTracer),
+        )
+        .await
+        .unwrap();
+    match trace {
+        GethTrace::PreStateTracer(PreStateFrame::Default(prestate)) => {
+            assert!(prestate.0.contains_key(&wallets[1].address()));
+            assert!(prestate.0[&contract_addr].code.is_some());
+        }
+        _ => unreachable!(),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_debug_trace_transaction_prestate() {
+    let (_api, handle) = spawn(NodeConfig::test()).await;
+    let provider = handle.http_provider();
+    let accounts = handle.dev_wallets().collect::<Vec<_>>();
+    let from = accounts[0].address();
+    let to = accounts[1].address();
+    let amount = U256::from(1000);
+
+    let mut hashes = vec![];
+    for _ in 0..2 {
+        let tx = TransactionRequest::default().to(to).value(amount).from(from);
+        let receipt = provider
+            .send_transaction(WithOtherFields::new(tx))
+            .await
+            .unwrap()
+            .get_receipt()
+            .await
+            .unwrap();
+        hashes.push(receipt.transaction_hash);
+    }
+
+    let opts = GethDebugTracingOptions::default().with_tracer(GethDebugTracerType::BuiltInTracer(
+        GethDebugBuiltInTracerType::PreStateTracer,
+    ));
+    let trace = provider.debug_trace_transaction(hashes[1], opts).await.unwrap();
+    match trace {
+        GethTrace::PreStateTracer(PreStateFrame::Default(prestate)) => {
+            // the state before the second transfer includes the first one
+            assert_eq!(prestate.0[&to].balance, Some(handle.genesis_balance() + amount));
+            assert_eq!(prestate.0[&from].nonce, Some(1));
+        }
+        _ => unreachable!(),
+    }
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn test_trace_address_fork() {
     let (api, handle) = spawn(fork_config().with_fork_block_number(Some(15291050u64))).await;
@@ -1236,3 +1236,33 @@ async fn can_mine_multiple_in_block() {
     let txs = block.transactions.hashes().copied().collect::<Vec<_>>();
     assert_eq!(txs, vec![first, second]);
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_reject_invalid_txs_in_strict_mode() {
+    let (api, handle) = spawn(NodeConfig::test().with_strict(true)).await;
+    let provider = handle.http_provider();
+
+    let from = handle.dev_wallets().next().unwrap().address();
+    let to = Address::random();
+
+    // calldata costs gas on top of the base cost of a transaction
+    let tx = TransactionRequest::default()
+        .from(from)
+        .to(to)
+        .input(Bytes::from(vec![1u8; 32]).into())
+        .with_gas_limit(21000u128);
+    let err = provider.send_transaction(WithOtherFields::new(tx)).await.unwrap_err();
+    assert!(err.to_string().contains("intrinsic gas too low"));
+
+    let impersonate = Address::random();
+    api.anvil_set_balance(impersonate, U256::from(1e18 as u64)).await.unwrap();
+    api.anvil_impersonate_account(impersonate).await.unwrap();
+    let tx = TransactionRequest::default().from(impersonate).to(to).value(U256::from(1));
+    let err = provider.send_transaction(WithOtherFields::new(tx)).await.unwrap_err();
+    assert!(err.to_string().contains("impersonated transactions are not allowed"));
+
+    let tx = TransactionRequest::default().from(from).to(to).value(U256::from(1));
+    let receipt =
+        provider.send_transaction(WithOtherFields::new(tx)).await.unwrap().get_receipt().await;
+    assert!(receipt.unwrap().status());
+}
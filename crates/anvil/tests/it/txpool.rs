@@ -56,3 +56,48 @@ async fn geth_txpool() {
         assert!(content.contains_key(&nonce.to_string()));
     }
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_constrain_next_block_order() {
+    let (api, handle) = spawn(NodeConfig::test()).await;
+    let provider = handle.http_provider();
+
+    api.anvil_set_auto_mine(false).await.unwrap();
+
+    let accounts = provider.get_accounts().await.unwrap();
+    let send = |from, gas_price| {
+        let tx = TransactionRequest::default()
+            .with_to(accounts[9])
+            .with_from(from)
+            .with_value(U256::from(1))
+            .with_gas_price(gas_price);
+        provider.send_transaction(WithOtherFields::new(tx))
+    };
+    let low = *send(accounts[0], 1_000_000_000).await.unwrap().tx_hash();
+    let mid = *send(accounts[1], 2_000_000_000).await.unwrap().tx_hash();
+    let high = *send(accounts[2], 3_000_000_000).await.unwrap().tx_hash();
+
+    // transactions are ordered by fees by default
+    assert_eq!(api.anvil_next_block_transactions().unwrap(), vec![high, mid, low]);
+
+    api.anvil_place_transaction_before(low, high).unwrap();
+    assert_eq!(api.anvil_next_block_transactions().unwrap(), vec![mid, low, high]);
+
+    // a transaction placed after one that isn't in the pool is held back
+    let backrun = *send(accounts[3], 4_000_000_000).await.unwrap().tx_hash();
+    let victim = alloy_primitives::B256::with_last_byte(1);
+    api.anvil_place_transaction_before(victim, backrun).unwrap();
+    assert_eq!(api.anvil_next_block_transactions().unwrap(), vec![mid, low, high]);
+
+    api.anvil_clear_ordering_constraints().unwrap();
+    api.anvil_bundle_transactions(vec![low, mid]).unwrap();
+    assert_eq!(api.anvil_next_block_transactions().unwrap(), vec![backrun, high, low, mid]);
+
+    api.mine_one().await;
+    let block = provider.get_block(1.into(), false.into()).await.unwrap().unwrap();
+    assert_eq!(
+        block.transactions.hashes().copied().collect::<Vec<_>>(),
+        vec![backrun, high, low, mid]
+    );
+    assert!(api.anvil_next_block_transactions().unwrap().is_empty());
+}
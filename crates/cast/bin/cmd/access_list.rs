@@ -1,4 +1,4 @@
-use crate::tx::CastTxBuilder;
+use crate::{output::OutputArgs, tx::CastTxBuilder};
 use alloy_primitives::TxKind;
 use alloy_rpc_types::BlockId;
 use cast::Cast;
@@ -36,9 +36,8 @@ pub struct AccessListArgs {
     #[arg(long, short = 'B')]
     block: Option<BlockId>,
 
-    /// Print the access list as JSON.
-    #[arg(long, short, help_heading = "Display options")]
-    json: bool,
+    #[command(flatten)]
+    output: OutputArgs,
 
     #[command(flatten)]
     tx: TransactionOpts,
@@ -49,7 +48,7 @@ pub struct AccessListArgs {
 
 impl AccessListArgs {
     pub async fn run(self) -> Result<()> {
-        let Self { to, sig, args, tx, eth, block, json: to_json } = self;
+        let Self { to, sig, args, tx, eth, block, output } = self;
 
         let config = Config::from(&eth);
         let provider = utils::get_provider(&config)?;
@@ -71,9 +70,13 @@ impl AccessListArgs {
 
         let cast = Cast::new(&provider);
 
-        let access_list: String = cast.access_list(&tx, block, to_json).await?;
-
-        println!("{access_list}");
+        if output.is_structured() {
+            let access_list = cast.access_list(&tx, block, true).await?;
+            println!("{}", output.render(&serde_json::from_str(&access_list)?)?);
+        } else {
+            let access_list = cast.access_list(&tx, block, false).await?;
+            println!("{access_list}");
+        }
 
         Ok(())
     }
@@ -0,0 +1,315 @@
+use alloy_consensus::TxEnvelope;
+use alloy_network::eip2718::Decodable2718;
+use alloy_primitives::{Address, Bytes, TxKind, U256};
+use alloy_rpc_types::TransactionRequest;
+use cast::{
+    revm::primitives::{Env, TxEnv},
+    traces::{
+        identifier::SignaturesIdentifier, CallTraceDecoder, CallTraceDecoderBuilder, TraceKind,
+    },
+};
+use clap::Parser;
+use eyre::{OptionExt, Result, WrapErr};
+use foundry_cli::{
+    opts::RpcOpts,
+    utils::{print_traces, TraceResult},
+};
+use foundry_common::fs;
+use foundry_compilers::artifacts::EvmVersion;
+use foundry_config::{find_project_root_path, Config};
+use foundry_evm::{
+    executors::{AccountDiff, BundleTransactionResult, TracingExecutor},
+    opts::EvmOpts,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::{collections::BTreeMap, path::PathBuf};
+use yansi::Paint;
+
+/// CLI arguments for `cast bundle`.
+#[derive(Debug, Parser)]
+pub enum BundleSubcommands {
+    /// Simulate an ordered bundle of transactions on top of a block.
+    #[command(visible_alias = "s")]
+    Simulate(SimulateArgs),
+}
+
+impl BundleSubcommands {
+    pub async fn run(self) -> Result<()> {
+        match self {
+            Self::Simulate(args) => args.run().await,
+        }
+    }
+}
+
+/// CLI arguments for `cast bundle simulate`.
+#[derive(Clone, Debug, Parser)]
+pub struct SimulateArgs {
+    /// Path to the JSON file containing the bundle.
+    ///
+    /// The bundle is an array of raw signed transactions and transaction objects, or an object
+    /// with such an array in its `txs` field. The transactions are executed in order.
+    bundle: PathBuf,
+
+    /// The block on top of which the bundle is executed, using the block's environment.
+    ///
+    /// Defaults to the latest block.
+    #[arg(long, short = 'B')]
+    block: Option<u64>,
+
+    /// Print the traces of the transactions.
+    #[arg(long, short)]
+    trace: bool,
+
+    /// Print the results as JSON.
+    #[arg(long, short)]
+    json: bool,
+
+    /// The EVM version to use.
+    ///
+    /// Overrides the version specified in the config.
+    #[arg(long, short)]
+    evm_version: Option<EvmVersion>,
+
+    #[command(flatten)]
+    rpc: RpcOpts,
+}
+
+/// The contents of a bundle file.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Bundle {
+    Transactions(Vec<BundleTransaction>),
+    Object { txs: Vec<BundleTransaction> },
+}
+
+/// A transaction of a bundle.
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub(crate) enum BundleTransaction {
+    /// A raw signed transaction.
+    Raw(Bytes),
+    /// An unsigned transaction, which is executed as its `from` field.
+    Request(TransactionRequest),
+}
+
+impl BundleTransaction {
+    /// Converts the transaction into the environment it's executed with.
+    pub(crate) fn into_tx_env(self, env: &Env) -> Result<TxEnv> {
+        let request = match self {
+            Self::Raw(raw) => {
+                let envelope = TxEnvelope::decode_2718(&mut raw.as_ref())?;
+                let from = envelope.recover_signer()?;
+                let mut request: TransactionRequest = match envelope {
+                    TxEnvelope::Legacy(tx) => tx.strip_signature().into(),
+                    TxEnvelope::Eip2930(tx) => tx.strip_signature().into(),
+                    TxEnvelope::Eip1559(tx) => tx.strip_signature().into(),
+                    TxEnvelope::Eip4844(tx) => tx.strip_signature().into(),
+                    _ => eyre::bail!("unsupported transaction type"),
+                };
+                request.from = Some(from);
+                request
+            }
+            Self::Request(request) => request,
+        };
+
+        let caller = request.from.ok_or_eyre("transaction is missing the `from` field")?;
+        // Unpriced transactions pay the base fee, so that they can always be included.
+        let gas_price = request.gas_price.or(request.max_fee_per_gas);
+        Ok(TxEnv {
+            caller,
+            transact_to: request.to.unwrap_or(TxKind::Create),
+            data: request.input.into_input().unwrap_or_default(),
+            value: request.value.unwrap_or_default(),
+            gas_limit: request.gas.map(|gas| gas as u64).unwrap_or(env.block.gas_limit.to()),
+            gas_price: gas_price.map(U256::from).unwrap_or(env.block.basefee),
+            gas_priority_fee: request.max_priority_fee_per_gas.map(U256::from),
+            nonce: request.nonce,
+            chain_id: request.chain_id,
+            access_list: request
+                .access_list
+                .unwrap_or_default()
+                .0
+                .into_iter()
+                .map(|item| {
+                    let keys = item.storage_keys.into_iter().map(|key| key.into()).collect();
+                    (item.address, keys)
+                })
+                .collect(),
+            blob_hashes: request.blob_versioned_hashes.unwrap_or_default(),
+            max_fee_per_blob_gas: request.max_fee_per_blob_gas.map(U256::from),
+            ..Default::default()
+        })
+    }
+}
+
+/// The JSON representation of a simulated transaction.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct SimulatedTransaction {
+    success: bool,
+    output: Bytes,
+    gas_used: u64,
+    cumulative_gas_used: u64,
+    state_diff: BTreeMap<Address, Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    traces: Option<Value>,
+}
+
+impl SimulateArgs {
+    pub async fn run(self) -> Result<()> {
+        let Self { bundle, block, trace, json, evm_version, rpc } = self;
+
+        let bundle: Bundle = fs::read_json_file(&bundle)
+            .wrap_err_with(|| format!("failed to read bundle {}", bundle.display()))?;
+        let txs = match bundle {
+            Bundle::Transactions(txs) | Bundle::Object { txs } => txs,
+        };
+
+        let figment = Config::figment_with_root(find_project_root_path(None).unwrap()).merge(rpc);
+        let evm_opts = figment.extract::<EvmOpts>()?;
+        let mut config = Config::try_from(figment)?.sanitized();
+        config.fork_block_number = block;
+
+        let (env, fork, _) = TracingExecutor::get_fork_material(&config, evm_opts).await?;
+        let txs = txs
+            .into_iter()
+            .enumerate()
+            .map(|(index, tx)| {
+                tx.into_tx_env(&env).wrap_err_with(|| format!("invalid transaction {index}"))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut executor = TracingExecutor::new(env, fork, evm_version, false);
+        executor.inspector_mut().collect_keccak_preimages(true);
+        let results = executor.simulate_bundle(txs)?;
+
+        if json {
+            let results = results
+                .into_iter()
+                .map(|result| simulated_transaction(result, trace))
+                .collect::<Result<Vec<_>>>()?;
+            println!("{}", serde_json::to_string_pretty(&results)?);
+            return Ok(())
+        }
+
+        let decoder = if trace {
+            Some(
+                CallTraceDecoderBuilder::new()
+                    .with_labels(config.labels.clone())
+                    .with_signature_identifier(SignaturesIdentifier::from_config(&config)?)
+                    .build(),
+            )
+        } else {
+            None
+        };
+
+        for (index, result) in results.into_iter().enumerate() {
+            print_transaction(index, result, decoder.as_ref()).await?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Prints the result of a simulated transaction.
+pub(crate) async fn print_transaction(
+    index: usize,
+    result: BundleTransactionResult,
+    decoder: Option<&CallTraceDecoder>,
+) -> Result<()> {
+    let BundleTransactionResult { mut raw, state_diff, cumulative_gas_used } = result;
+    let preimages = raw.keccak_preimages.take().unwrap_or_default();
+
+    println!("{}", format!("Transaction {index}").bold());
+    match decoder {
+        Some(decoder) => {
+            let mut result = TraceResult::from_raw(raw, TraceKind::Execution);
+            print_traces(&mut result, decoder).await?;
+        }
+        None => {
+            if raw.reverted {
+                println!("{}", "Transaction failed.".red());
+            } else {
+                println!("{}", "Transaction successfully executed.".green());
+            }
+            println!("Gas used: {}", raw.gas_used);
+        }
+    }
+    println!("Cumulative gas used: {cumulative_gas_used}");
+
+    if !state_diff.is_empty() {
+        println!("State diff:");
+    }
+    for (address, diff) in state_diff {
+        println!("  {address}");
+        let AccountDiff { balance, nonce, code, storage } = diff;
+        if let Some((before, after)) = balance {
+            println!("    balance: {before} -> {after}");
+        }
+        if let Some((before, after)) = nonce {
+            println!("    nonce: {before} -> {after}");
+        }
+        if let Some((before, after)) = code {
+            println!("    code: {} bytes -> {} bytes", before.len(), after.len());
+        }
+        for (slot, (before, after)) in storage {
+            print!("    {slot:#066x}: {before:#066x} -> {after:#066x}");
+            // resolve mapping and array slots to the keys they were derived from
+            match preimages.decode_slot(&slot.into()) {
+                Some(decoded) => println!(" ({decoded})"),
+                None => println!(),
+            }
+        }
+    }
+    println!();
+
+    Ok(())
+}
+
+/// Converts the result of a simulated transaction into its JSON representation, including the
+/// traces if `trace` is set.
+pub(crate) fn simulated_transaction(
+    result: BundleTransactionResult,
+    trace: bool,
+) -> Result<SimulatedTransaction> {
+    let BundleTransactionResult { raw, state_diff, cumulative_gas_used } = result;
+    let state_diff = state_diff
+        .into_iter()
+        .map(|(address, diff)| {
+            let AccountDiff { balance, nonce, code, storage } = diff;
+            let mut value = json!({});
+            if let Some((from, to)) = balance {
+                value["balance"] = json!({ "from": from, "to": to });
+            }
+            if let Some((from, to)) = nonce {
+                value["nonce"] = json!({ "from": from, "to": to });
+            }
+            if let Some((from, to)) = code {
+                value["code"] = json!({ "from": from, "to": to });
+            }
+            if !storage.is_empty() {
+                let storage = storage
+                    .into_iter()
+                    .map(|(slot, (from, to))| {
+                        (
+                            format!("{slot:#066x}"),
+                            json!({ "from": format!("{from:#066x}"), "to": format!("{to:#066x}") }),
+                        )
+                    })
+                    .collect::<serde_json::Map<_, _>>();
+                value["storage"] = storage.into();
+            }
+            (address, value)
+        })
+        .collect();
+
+    Ok(SimulatedTransaction {
+        success: !raw.reverted,
+        output: raw.result,
+        gas_used: raw.gas_used,
+        cumulative_gas_used,
+        state_diff,
+        traces: raw.traces.filter(|_| trace).as_ref().map(serde_json::to_value).transpose()?,
+    })
+}
@@ -5,7 +5,7 @@ use cast::{traces::TraceKind, Cast};
 use clap::Parser;
 use eyre::Result;
 use foundry_cli::{
-    opts::{EthereumOpts, TransactionOpts},
+    opts::{EthereumOpts, StateOverrideOpts, TransactionOpts},
     utils::{self, handle_traces, parse_ether_value, TraceResult},
 };
 use foundry_common::ens::NameOrAddress;
@@ -59,12 +59,19 @@ pub struct CallArgs {
     #[arg(long, short)]
     block: Option<BlockId>,
 
+    /// Do not follow offchain lookups (EIP-3668 CCIP-read) requested by the called contract.
+    #[arg(long)]
+    no_ccip: bool,
+
     #[command(subcommand)]
     command: Option<CallSubcommands>,
 
     #[command(flatten)]
     tx: TransactionOpts,
 
+    #[command(flatten)]
+    state_override: StateOverrideOpts,
+
     #[command(flatten)]
     eth: EthereumOpts,
 }
@@ -103,12 +110,15 @@ impl CallArgs {
             eth,
             command,
             block,
+            no_ccip,
             trace,
             evm_version,
             debug,
             labels,
             data,
+            state_override,
         } = self;
+        let overrides = state_override.state_override();
 
         if let Some(data) = data {
             sig = Some(data);
@@ -160,6 +170,9 @@ impl CallArgs {
 
             let (env, fork, chain) = TracingExecutor::get_fork_material(&config, evm_opts).await?;
             let mut executor = TracingExecutor::new(env, fork, evm_version, debug);
+            if let Some(overrides) = &overrides {
+                executor.apply_state_override(overrides)?;
+            }
 
             let value = tx.value.unwrap_or_default();
             let input = tx.inner.input.into_input().unwrap_or_default();
@@ -180,7 +193,11 @@ impl CallArgs {
             return Ok(());
         }
 
-        println!("{}", Cast::new(provider).call(&tx, func.as_ref(), block).await?);
+        let cast = Cast::new(provider).with_ccip_read(!no_ccip);
+        println!(
+            "{}",
+            cast.call_with_overrides(&tx, func.as_ref(), block, overrides.as_ref()).await?
+        );
 
         Ok(())
     }
@@ -216,4 +233,21 @@ mod tests {
 
         assert!(args.is_err());
     }
+
+    #[test]
+    fn can_parse_state_overrides() {
+        let args = CallArgs::parse_from([
+            "foundry-cli",
+            Address::ZERO.to_string().as_str(),
+            "balanceOf(address)(uint256)",
+            "0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045",
+            "--override-balance",
+            "0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045:1ether",
+            "--override-storage",
+            "0x0000000000000000000000000000000000000000:0:1",
+        ]);
+        let overrides = args.state_override.state_override().unwrap();
+        assert_eq!(overrides.len(), 2);
+        assert_eq!(args.args, vec!["0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045"]);
+    }
 }
@@ -0,0 +1,370 @@
+use crate::tx::CastTxBuilder;
+use alloy_network::AnyNetwork;
+use alloy_primitives::{Address, Bytes, TxKind, B256, U256};
+use alloy_provider::Provider;
+use alloy_rpc_types::{BlockId, BlockNumberOrTag, TransactionRequest};
+use alloy_serde::WithOtherFields;
+use alloy_transport::Transport;
+use cast::Cast;
+use clap::Parser;
+use eyre::Result;
+use foundry_cli::{
+    opts::{EthereumOpts, TransactionOpts},
+    utils,
+};
+use foundry_common::ens::NameOrAddress;
+use foundry_config::Config;
+use serde::{Deserialize, Serialize};
+use std::{fmt::Write, str::FromStr};
+use yansi::Paint;
+
+/// CLI arguments for `cast compare-call`.
+///
+/// Runs the same call against two endpoints, or two blocks of the same endpoint, and compares the
+/// results, the gas and, with `--trace`, the emitted logs. Exits with an error if they differ.
+#[derive(Debug, Parser)]
+pub struct CompareCallArgs {
+    /// The destination of the call.
+    #[arg(value_parser = NameOrAddress::from_str)]
+    to: NameOrAddress,
+
+    /// The signature of the function to call.
+    sig: Option<String>,
+
+    /// The arguments of the function to call.
+    args: Vec<String>,
+
+    /// Data for the call.
+    #[arg(long, conflicts_with_all = &["sig", "args"])]
+    data: Option<String>,
+
+    /// The block height to run the first call at.
+    ///
+    /// Can also be the tags earliest, finalized, safe, latest, or pending.
+    #[arg(long, short)]
+    block: Option<BlockId>,
+
+    /// The block height to run the second call at, defaults to `--block`.
+    #[arg(long, required_unless_present = "other_rpc_url")]
+    other_block: Option<BlockId>,
+
+    /// The RPC endpoint to run the second call against, defaults to `--rpc-url`.
+    #[arg(long, value_name = "URL")]
+    other_rpc_url: Option<String>,
+
+    /// Trace the calls with `debug_traceCall` to compare the gas used and the emitted logs.
+    ///
+    /// Both endpoints must support the `callTracer`.
+    #[arg(long)]
+    trace: bool,
+
+    /// Print both calls as JSON.
+    #[arg(long, short)]
+    json: bool,
+
+    #[command(flatten)]
+    tx: TransactionOpts,
+
+    #[command(flatten)]
+    eth: EthereumOpts,
+}
+
+impl CompareCallArgs {
+    pub async fn run(self) -> Result<()> {
+        let Self {
+            to,
+            mut sig,
+            args,
+            data,
+            block,
+            other_block,
+            other_rpc_url,
+            trace,
+            json,
+            tx,
+            eth,
+        } = self;
+        if let Some(data) = data {
+            sig = Some(data);
+        }
+
+        let config = Config::from(&eth);
+        let provider = utils::get_provider(&config)?;
+        let mut other_config = config.clone();
+        if let Some(url) = other_rpc_url {
+            other_config.eth_rpc_url = Some(url);
+        }
+        let other_provider = utils::get_provider(&other_config)?;
+        let sender = eth.wallet.sender().await;
+
+        let to = to.resolve(&provider).await?;
+        let (tx, func) = CastTxBuilder::new(&provider, tx, &config)
+            .await?
+            .with_tx_kind(TxKind::Call(to))
+            .with_code_sig_and_args(None, sig, args)
+            .await?
+            .build_raw(sender)
+            .await?;
+        let other_block = other_block.or(block);
+
+        let (a, b) = tokio::try_join!(
+            CallSnapshot::take(
+                provider,
+                config.get_rpc_url_or_localhost_http()?.into_owned(),
+                &tx,
+                func.as_ref(),
+                block,
+                trace,
+            ),
+            CallSnapshot::take(
+                other_provider,
+                other_config.get_rpc_url_or_localhost_http()?.into_owned(),
+                &tx,
+                func.as_ref(),
+                other_block,
+                trace,
+            ),
+        )?;
+
+        let diff = a.diff(&b);
+        if json {
+            println!("{}", serde_json::to_string_pretty(&[&a, &b])?);
+        } else {
+            println!("a: {} at {}", a.endpoint, a.block);
+            println!("b: {} at {}", b.endpoint, b.block);
+            if diff.is_empty() {
+                println!("\n{}", "The calls match.".green());
+            } else {
+                print!("\n{diff}");
+            }
+        }
+        if !diff.is_empty() {
+            eyre::bail!("the calls differ");
+        }
+        Ok(())
+    }
+}
+
+/// The outcome of a call against an endpoint at a block.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CallSnapshot {
+    endpoint: String,
+    block: String,
+    /// The decoded output of the call, if it succeeded.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    output: Option<String>,
+    /// The error of the call, if it failed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+    /// The gas used by the call when traced, its gas estimate otherwise.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    gas: Option<u64>,
+    /// The logs emitted by the call, in order, when traced.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    logs: Option<Vec<TracedLog>>,
+}
+
+impl CallSnapshot {
+    async fn take<P: Provider<T, AnyNetwork> + Clone, T: Transport + Clone>(
+        provider: P,
+        endpoint: String,
+        tx: &WithOtherFields<TransactionRequest>,
+        func: Option<&alloy_json_abi::Function>,
+        block: Option<BlockId>,
+        trace: bool,
+    ) -> Result<Self> {
+        let block_id = block.unwrap_or_default();
+        let mut snapshot = Self { endpoint, block: format_block(block_id), ..Default::default() };
+
+        match Cast::new(provider.clone()).call_with_overrides(tx, func, block, None).await {
+            Ok(output) => snapshot.output = Some(output.trim().to_string()),
+            Err(err) => snapshot.error = Some(format!("{err:#}")),
+        }
+
+        if trace {
+            let frame = provider
+                .raw_request::<_, TraceFrame>(
+                    "debug_traceCall".into(),
+                    (
+                        tx,
+                        block_id,
+                        serde_json::json!({
+                            "tracer": "callTracer",
+                            "tracerConfig": { "withLog": true },
+                        }),
+                    ),
+                )
+                .await?;
+            let mut logs = Vec::new();
+            frame.flatten_logs(&mut logs);
+            snapshot.gas = Some(frame.gas_used.saturating_to());
+            snapshot.logs = Some(logs);
+        } else {
+            snapshot.gas =
+                provider.estimate_gas(tx).block(block_id).await.ok().map(|gas| gas as u64);
+        }
+        Ok(snapshot)
+    }
+
+    /// Returns the differences between two snapshots, one paragraph per differing field.
+    fn diff(&self, other: &Self) -> String {
+        let mut out = String::new();
+        let mut field = |name: &str, a: String, b: String| {
+            if a != b {
+                let _ = writeln!(out, "{}:\n  a: {a}\n  b: {b}", name.yellow().bold());
+            }
+        };
+        let outcome = |snapshot: &Self| match (&snapshot.output, &snapshot.error) {
+            (Some(output), _) => output.clone(),
+            (None, Some(error)) => format!("error: {error}"),
+            (None, None) => String::new(),
+        };
+        field("result", outcome(self), outcome(other));
+        field("gas", format_gas(self.gas), format_gas(other.gas));
+
+        if let (Some(a), Some(b)) = (&self.logs, &other.logs) {
+            if a.len() != b.len() {
+                field("log count", a.len().to_string(), b.len().to_string());
+            }
+            for (i, (a, b)) in a.iter().zip(b).enumerate() {
+                field(&format!("log {i}"), a.to_string(), b.to_string());
+            }
+            for (i, log) in a.iter().enumerate().skip(b.len()) {
+                field(&format!("log {i}"), log.to_string(), "-".to_string());
+            }
+            for (i, log) in b.iter().enumerate().skip(a.len()) {
+                field(&format!("log {i}"), "-".to_string(), log.to_string());
+            }
+        }
+        out
+    }
+}
+
+/// A log emitted by a traced call.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+struct TracedLog {
+    address: Address,
+    #[serde(default)]
+    topics: Vec<B256>,
+    #[serde(default)]
+    data: Bytes,
+}
+
+impl std::fmt::Display for TracedLog {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} topics=[", self.address)?;
+        for (i, topic) in self.topics.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "{topic}")?;
+        }
+        write!(f, "] data={}", self.data)
+    }
+}
+
+/// A frame of a `callTracer` trace, with only the fields that are compared.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TraceFrame {
+    #[serde(default)]
+    gas_used: U256,
+    #[serde(default)]
+    logs: Vec<FrameLog>,
+    #[serde(default)]
+    calls: Vec<TraceFrame>,
+}
+
+#[derive(Debug, Deserialize)]
+struct FrameLog {
+    #[serde(flatten)]
+    log: TracedLog,
+    /// The number of calls made by the frame before the log was emitted.
+    position: Option<U256>,
+}
+
+impl TraceFrame {
+    /// Appends the logs of the frame and of its calls to `out`, in the order they were emitted.
+    fn flatten_logs(&self, out: &mut Vec<TracedLog>) {
+        let mut calls = self.calls.iter();
+        let mut made = 0;
+        for log in &self.logs {
+            let position = log.position.map_or(self.calls.len(), |p| p.saturating_to());
+            while made < position {
+                let Some(call) = calls.next() else { break };
+                call.flatten_logs(out);
+                made += 1;
+            }
+            out.push(log.log.clone());
+        }
+        for call in calls {
+            call.flatten_logs(out);
+        }
+    }
+}
+
+fn format_gas(gas: Option<u64>) -> String {
+    gas.map_or_else(|| "-".to_string(), |gas| gas.to_string())
+}
+
+fn format_block(block: BlockId) -> String {
+    match block {
+        BlockId::Number(BlockNumberOrTag::Number(number)) => number.to_string(),
+        BlockId::Number(tag) => tag.to_string(),
+        BlockId::Hash(hash) => hash.block_hash.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn log(n: u8) -> TracedLog {
+        TracedLog { address: Address::with_last_byte(n), topics: vec![], data: Bytes::new() }
+    }
+
+    #[test]
+    fn flattens_logs_in_emission_order() {
+        let frame: TraceFrame = serde_json::from_value(serde_json::json!({
+            "gasUsed": "0x5208",
+            "logs": [
+                { "address": Address::with_last_byte(1), "position": "0x0" },
+                { "address": Address::with_last_byte(3), "position": "0x1" },
+            ],
+            "calls": [
+                { "logs": [{ "address": Address::with_last_byte(2) }] },
+                { "logs": [{ "address": Address::with_last_byte(4) }] },
+            ],
+        }))
+        .unwrap();
+        let mut logs = Vec::new();
+        frame.flatten_logs(&mut logs);
+        assert_eq!(logs, [log(1), log(2), log(3), log(4)]);
+        assert_eq!(frame.gas_used, U256::from(21000));
+    }
+
+    #[test]
+    fn diffs_snapshots() {
+        let a = CallSnapshot {
+            output: Some("1".to_string()),
+            gas: Some(21000),
+            logs: Some(vec![log(1)]),
+            ..Default::default()
+        };
+        assert!(a.diff(&a.clone()).is_empty());
+
+        let b = CallSnapshot {
+            error: Some("execution reverted".to_string()),
+            gas: Some(21000),
+            logs: Some(vec![log(1), log(2)]),
+            ..Default::default()
+        };
+        let diff = a.diff(&b);
+        assert!(diff.contains("b: error: execution reverted"), "{diff}");
+        assert!(!diff.contains("gas"), "{diff}");
+        assert!(diff.contains("log count"), "{diff}");
+        assert!(diff.contains("log 1"), "{diff}");
+        assert!(!diff.contains("log 0"), "{diff}");
+    }
+}
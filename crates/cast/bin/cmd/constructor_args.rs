@@ -0,0 +1,45 @@
+use super::creation_code::{
+    fetch_creation_code, load_abi, provider_and_client, split_constructor_args,
+};
+use alloy_primitives::Address;
+use clap::{Parser, ValueHint};
+use eyre::Result;
+use foundry_cli::opts::{EtherscanOpts, RpcOpts};
+use foundry_common::fmt::format_token;
+use std::path::PathBuf;
+
+/// CLI arguments for `cast constructor-args`.
+#[derive(Clone, Debug, Parser)]
+pub struct ConstructorArgsArgs {
+    /// The address of the contract.
+    contract: Address,
+
+    /// Path to a file containing the contract's ABI.
+    ///
+    /// If not specified, the verified ABI is fetched from Etherscan.
+    #[arg(long, value_hint = ValueHint::FilePath, value_name = "PATH")]
+    abi_path: Option<PathBuf>,
+
+    #[command(flatten)]
+    etherscan: EtherscanOpts,
+
+    #[command(flatten)]
+    rpc: RpcOpts,
+}
+
+impl ConstructorArgsArgs {
+    pub async fn run(self) -> Result<()> {
+        let Self { contract, abi_path, etherscan, rpc } = self;
+        let (provider, client) = provider_and_client(&rpc, &etherscan).await?;
+
+        let code = fetch_creation_code(&provider, client.as_ref(), contract).await?;
+        let abi = load_abi(client.as_ref(), contract, abi_path).await?;
+        let (_, _, values) = split_constructor_args(&abi, &code)?;
+
+        let params = abi.constructor.iter().flat_map(|constructor| &constructor.inputs);
+        for (param, value) in params.zip(&values) {
+            println!("{} ({})", format_token(value), param.ty);
+        }
+        Ok(())
+    }
+}
@@ -1,16 +1,10 @@
 use alloy_primitives::{hex, keccak256, Address, B256, U256};
 use clap::Parser;
 use eyre::{Result, WrapErr};
+use foundry_common::create2::SaltMiner;
 use rand::{rngs::StdRng, RngCore, SeedableRng};
 use regex::RegexSetBuilder;
-use std::{
-    num::NonZeroUsize,
-    sync::{
-        atomic::{AtomicBool, Ordering},
-        Arc,
-    },
-    time::Instant,
-};
+use std::{num::NonZeroUsize, time::Instant};
 
 // https://etherscan.io/address/0x4e59b44847b379578588920ca78fbf26c0b4956c#code
 const DEPLOYER: &str = "0x4e59b44847b379578588920ca78fbf26c0b4956c";
@@ -171,59 +165,22 @@ impl Create2Args {
         println!("Regex patterns: {:?}", regex.patterns());
         println!();
         println!("Starting to generate deterministic contract address with {n_threads} threads...");
-        let mut handles = Vec::with_capacity(n_threads);
-        let found = Arc::new(AtomicBool::new(false));
         let timer = Instant::now();
 
-        // Loops through all possible salts in parallel until a result is found.
-        // Each thread iterates over `(i..).step_by(n_threads)`.
-        for i in 0..n_threads {
-            // Create local copies for the thread.
-            let increment = n_threads;
-            let regex = regex.clone();
-            let regex_len = regex.patterns().len();
-            let found = Arc::clone(&found);
-            handles.push(std::thread::spawn(move || {
-                // Read the first bytes of the salt as a usize to be able to increment it.
-                struct B256Aligned(B256, [usize; 0]);
-                let mut salt = B256Aligned(salt, []);
-                // SAFETY: B256 is aligned to `usize`.
-                let salt_word = unsafe {
-                    &mut *salt.0.as_mut_ptr().add(32 - usize::BITS as usize / 8).cast::<usize>()
-                };
-                // Important: add the thread index to the salt to avoid duplicate results.
-                *salt_word = salt_word.wrapping_add(i);
-
+        let regex_len = regex.patterns().len();
+        let miner = SaltMiner::new(deployer, init_code_hash).salt(salt).threads(n_threads);
+        let (address, salt) = miner
+            .mine(|address| {
+                // Check if the regex matches the calculated address' checksum.
                 let mut checksum = [0; 42];
-                loop {
-                    // Stop if a result was found in another thread.
-                    if found.load(Ordering::Relaxed) {
-                        break None;
-                    }
-
-                    // Calculate the `CREATE2` address.
-                    #[allow(clippy::needless_borrows_for_generic_args)]
-                    let addr = deployer.create2(&salt.0, &init_code_hash);
-
-                    // Check if the regex matches the calculated address' checksum.
-                    let _ = addr.to_checksum_raw(&mut checksum, None);
-                    // SAFETY: stripping 2 ASCII bytes ("0x") off of an already valid UTF-8 string
-                    // is safe.
-                    let s = unsafe { std::str::from_utf8_unchecked(checksum.get_unchecked(2..)) };
-                    if regex.matches(s).into_iter().count() == regex_len {
-                        // Notify other threads that we found a result.
-                        found.store(true, Ordering::Relaxed);
-                        break Some((addr, salt.0));
-                    }
-
-                    // Increment the salt for the next iteration.
-                    *salt_word = salt_word.wrapping_add(increment);
-                }
-            }));
-        }
+                let _ = address.to_checksum_raw(&mut checksum, None);
+                // SAFETY: stripping 2 ASCII bytes ("0x") off of an already valid UTF-8 string is
+                // safe.
+                let s = unsafe { std::str::from_utf8_unchecked(checksum.get_unchecked(2..)) };
+                regex.matches(s).into_iter().count() == regex_len
+            })
+            .ok_or_else(|| eyre::eyre!("no salt found for the given patterns"))?;
 
-        let results = handles.into_iter().filter_map(|h| h.join().unwrap()).collect::<Vec<_>>();
-        let (address, salt) = results.into_iter().next().unwrap();
         println!("Successfully found contract address in {:?}", timer.elapsed());
         println!("Address: {address}");
         println!("Salt: {salt} ({})", U256::from_be_bytes(salt.0));
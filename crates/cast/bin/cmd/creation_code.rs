@@ -0,0 +1,251 @@
+use alloy_dyn_abi::{DynSolValue, JsonAbiExt};
+use alloy_json_abi::{ContractObject, JsonAbi};
+use alloy_primitives::{Address, Bytes, B256};
+use alloy_provider::{ext::TraceApi, Provider};
+use alloy_rpc_types::{
+    trace::parity::{Action, LocalizedTransactionTrace, TraceOutput},
+    BlockTransactions,
+};
+use clap::{Parser, ValueHint};
+use eyre::{OptionExt, Result, WrapErr};
+use foundry_block_explorers::Client;
+use foundry_cli::{
+    opts::{EtherscanOpts, RpcOpts},
+    utils,
+};
+use foundry_common::provider::RetryProvider;
+use foundry_config::Config;
+use std::path::PathBuf;
+
+/// CLI arguments for `cast creation-code`.
+#[derive(Clone, Debug, Parser)]
+pub struct CreationCodeArgs {
+    /// The address of the contract.
+    contract: Address,
+
+    /// Strip the constructor arguments from the creation code.
+    #[arg(long)]
+    without_args: bool,
+
+    /// Only print the ABI-encoded constructor arguments.
+    #[arg(long, conflicts_with = "without_args")]
+    only_args: bool,
+
+    /// Path to a file containing the contract's ABI.
+    ///
+    /// Required to split off the constructor arguments of contracts that aren't verified on
+    /// Etherscan.
+    #[arg(long, value_hint = ValueHint::FilePath, value_name = "PATH")]
+    abi_path: Option<PathBuf>,
+
+    #[command(flatten)]
+    etherscan: EtherscanOpts,
+
+    #[command(flatten)]
+    rpc: RpcOpts,
+}
+
+impl CreationCodeArgs {
+    pub async fn run(self) -> Result<()> {
+        let Self { contract, without_args, only_args, abi_path, etherscan, rpc } = self;
+        let (provider, client) = provider_and_client(&rpc, &etherscan).await?;
+
+        let code = fetch_creation_code(&provider, client.as_ref(), contract).await?;
+        if !without_args && !only_args {
+            println!("{code}");
+            return Ok(())
+        }
+
+        let abi = load_abi(client.as_ref(), contract, abi_path).await?;
+        let (init_code, args, _) = split_constructor_args(&abi, &code)?;
+        println!("{}", if only_args { args } else { init_code });
+        Ok(())
+    }
+}
+
+/// Returns the provider configured by `rpc`, and the Etherscan client configured by `etherscan`
+/// if an API key is available.
+pub(crate) async fn provider_and_client(
+    rpc: &RpcOpts,
+    etherscan: &EtherscanOpts,
+) -> Result<(RetryProvider, Option<Client>)> {
+    let config = Config::from(rpc);
+    let provider = utils::get_provider(&config)?;
+    let chain = utils::get_chain(etherscan.chain.or(config.chain), &provider).await?;
+    let client = etherscan
+        .key()
+        .or_else(|| config.get_etherscan_api_key(Some(chain)))
+        .map(|api_key| Client::new(chain, api_key))
+        .transpose()?;
+    Ok((provider, client))
+}
+
+/// Loads the ABI of the contract from the given file, or from Etherscan.
+pub(crate) async fn load_abi(
+    client: Option<&Client>,
+    contract: Address,
+    abi_path: Option<PathBuf>,
+) -> Result<JsonAbi> {
+    if let Some(path) = abi_path {
+        let file = foundry_common::fs::read_to_string(&path)?;
+        let obj: ContractObject = serde_json::from_str(&file)?;
+        return obj.abi.ok_or_else(|| eyre::eyre!("could not find ABI in {}", path.display()))
+    }
+    let client = client.ok_or_eyre(
+        "an Etherscan API key or --abi-path is required to decode the constructor arguments",
+    )?;
+    client.contract_abi(contract).await.wrap_err("failed to fetch the verified ABI")
+}
+
+/// Splits the creation code of a contract into its init code and ABI-encoded constructor
+/// arguments, also returning the decoded arguments.
+///
+/// The constructor arguments are the shortest suffix of the creation code that is the canonical
+/// encoding of the constructor's parameters.
+pub(crate) fn split_constructor_args(
+    abi: &JsonAbi,
+    code: &Bytes,
+) -> Result<(Bytes, Bytes, Vec<DynSolValue>)> {
+    let Some(constructor) = abi.constructor.as_ref().filter(|c| !c.inputs.is_empty()) else {
+        return Ok((code.clone(), Bytes::new(), vec![]))
+    };
+    for len in (32..=code.len()).step_by(32) {
+        let offset = code.len() - len;
+        let args = &code[offset..];
+        let Ok(values) = constructor.abi_decode_input(args, true) else { continue };
+        if constructor.abi_encode_input(&values).is_ok_and(|encoded| encoded == args) {
+            return Ok((code.slice(..offset), code.slice(offset..), values))
+        }
+    }
+    eyre::bail!("could not find the constructor arguments in the creation code")
+}
+
+/// Fetches the creation code of the contract, including its constructor arguments.
+///
+/// The creation transaction is looked up on Etherscan if a client is provided, otherwise the
+/// creation block is found by binary searching the contract's code over the block history, which
+/// requires an archive node. Contracts deployed by other contracts are found using the `trace_*`
+/// RPC methods.
+pub(crate) async fn fetch_creation_code(
+    provider: &RetryProvider,
+    client: Option<&Client>,
+    contract: Address,
+) -> Result<Bytes> {
+    if let Some(client) = client {
+        match client.contract_creation_data(contract).await {
+            Ok(data) => {
+                return creation_code_from_tx(provider, contract, data.transaction_hash).await
+            }
+            Err(err) => {
+                warn!(%err, "failed to fetch the creation transaction from Etherscan");
+            }
+        }
+    }
+
+    let number = find_creation_block(provider, contract).await?;
+    let block = provider
+        .get_block(number.into(), true.into())
+        .await?
+        .ok_or_else(|| eyre::eyre!("block {number} not found"))?;
+    if let BlockTransactions::Full(txs) = block.transactions {
+        if let Some(tx) =
+            txs.into_iter().find(|tx| tx.to.is_none() && tx.from.create(tx.nonce) == contract)
+        {
+            return Ok(tx.input.clone())
+        }
+    }
+
+    let traces = provider.trace_block(number.into()).await?;
+    creation_code_from_traces(traces, contract)
+        .ok_or_else(|| eyre::eyre!("could not find the creation of {contract} in block {number}"))
+}
+
+/// Returns the creation code of the contract created by the given transaction.
+async fn creation_code_from_tx(
+    provider: &RetryProvider,
+    contract: Address,
+    tx_hash: B256,
+) -> Result<Bytes> {
+    let tx = provider
+        .get_transaction_by_hash(tx_hash)
+        .await?
+        .ok_or_else(|| eyre::eyre!("tx not found: {tx_hash:?}"))?;
+    if tx.to.is_none() && tx.from.create(tx.nonce) == contract {
+        return Ok(tx.input.clone())
+    }
+
+    let traces = provider.trace_transaction(tx_hash).await?;
+    creation_code_from_traces(traces, contract)
+        .ok_or_else(|| eyre::eyre!("could not find the creation of {contract} in tx {tx_hash:?}"))
+}
+
+/// Returns the init code of the trace that created the contract, if any.
+fn creation_code_from_traces(
+    traces: impl IntoIterator<Item = LocalizedTransactionTrace>,
+    contract: Address,
+) -> Option<Bytes> {
+    traces.into_iter().find_map(|trace| match (trace.trace.action, trace.trace.result) {
+        (Action::Create(action), Some(TraceOutput::Create(output)))
+            if output.address == contract =>
+        {
+            Some(action.init)
+        }
+        _ => None,
+    })
+}
+
+/// Returns the number of the block the contract was created in, by binary searching the first
+/// block at which the contract has code.
+async fn find_creation_block(provider: &RetryProvider, contract: Address) -> Result<u64> {
+    let has_code = |number: u64| async move {
+        let code = provider.get_code_at(contract).block_id(number.into()).await?;
+        Ok::<_, eyre::Error>(!code.is_empty())
+    };
+
+    let mut high = provider.get_block_number().await?;
+    if !has_code(high).await? {
+        eyre::bail!("no contract deployed at {contract}");
+    }
+    let mut low = 0;
+    while low < high {
+        let mid = low + (high - low) / 2;
+        if has_code(mid).await? {
+            high = mid;
+        } else {
+            low = mid + 1;
+        }
+    }
+    Ok(high)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::{hex, U256};
+
+    #[test]
+    fn can_split_constructor_args() {
+        let abi: JsonAbi = serde_json::from_str(
+            r#"[{"type":"constructor","inputs":[{"name":"a","type":"uint256"},{"name":"b","type":"string"}],"stateMutability":"nonpayable"}]"#,
+        )
+        .unwrap();
+        let init_code = hex::decode("6080604052348015600f57600080fd5b50").unwrap();
+        let values = vec![DynSolValue::Uint(U256::from(42), 256), DynSolValue::String("hi".into())];
+        let args = abi.constructor.as_ref().unwrap().abi_encode_input(&values).unwrap();
+        let code = Bytes::from([init_code.clone(), args.clone()].concat());
+
+        let (init, encoded, decoded) = split_constructor_args(&abi, &code).unwrap();
+        assert_eq!(init, Bytes::from(init_code));
+        assert_eq!(encoded, Bytes::from(args));
+        assert_eq!(decoded, values);
+    }
+
+    #[test]
+    fn no_constructor_args() {
+        let code = Bytes::from_static(&[0x60, 0x80]);
+        let (init, args, values) = split_constructor_args(&JsonAbi::new(), &code).unwrap();
+        assert_eq!(init, code);
+        assert!(args.is_empty());
+        assert!(values.is_empty());
+    }
+}
@@ -0,0 +1,366 @@
+use alloy_primitives::{Address, Bytes, LogData};
+use alloy_rpc_types::trace::{
+    geth::CallFrame,
+    parity::{Action, CallType, TraceOutput, TransactionTrace},
+};
+use cast::{
+    revm::interpreter::InstructionResult,
+    traces::{
+        identifier::{EtherscanIdentifier, SignaturesIdentifier},
+        CallKind, CallLog, CallTrace, CallTraceArena, CallTraceDecoderBuilder, CallTraceNode,
+        TraceKind, TraceMemberOrder,
+    },
+};
+use clap::Parser;
+use eyre::{Context, Result};
+use foundry_cli::{
+    opts::EtherscanOpts,
+    utils::{print_traces, TraceResult},
+};
+use foundry_common::{compile::ProjectCompiler, fs, ContractsByArtifact};
+use foundry_config::Config;
+use serde_json::Value;
+use std::{collections::HashMap, io::Read, path::PathBuf, str::FromStr};
+
+/// CLI arguments for `cast decode-trace`.
+#[derive(Clone, Debug, Parser)]
+pub struct DecodeTraceArgs {
+    /// The file containing the trace, or `-` to read it from stdin.
+    ///
+    /// Accepts the output of `debug_traceTransaction` with the `callTracer`, and of
+    /// `trace_transaction` or `trace_replayTransaction`, with or without the JSON-RPC envelope.
+    file: PathBuf,
+
+    /// Label addresses in the trace.
+    ///
+    /// Example: 0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045:vitalik.eth
+    #[arg(long, short)]
+    label: Vec<String>,
+
+    /// Decode the trace with the ABIs of the contracts of the current project.
+    #[arg(long)]
+    with_local_artifacts: bool,
+
+    #[command(flatten)]
+    etherscan: EtherscanOpts,
+}
+
+impl DecodeTraceArgs {
+    pub async fn run(self) -> Result<()> {
+        let Self { file, label, with_local_artifacts, etherscan } = self;
+
+        let input = if file == PathBuf::from("-") {
+            let mut input = String::new();
+            std::io::stdin().read_to_string(&mut input)?;
+            input
+        } else {
+            fs::read_to_string(&file)?
+        };
+        let value: Value = serde_json::from_str(&input).wrap_err("invalid trace JSON")?;
+        let mut result = parse_trace(value)?;
+
+        let config = Config::from(&etherscan);
+        let labels = label.iter().filter_map(|label| {
+            let (address, label) = label.split_once(':')?;
+            Some((Address::from_str(address).ok()?, label.to_string()))
+        });
+        let mut builder = CallTraceDecoderBuilder::new()
+            .with_labels(labels.chain(config.labels.clone()))
+            .with_signature_identifier(SignaturesIdentifier::from_config(&config)?);
+        if with_local_artifacts {
+            let project = config.project()?;
+            let output = ProjectCompiler::new().quiet(true).compile(&project)?;
+            let contracts = ContractsByArtifact::new(
+                output.artifact_ids().map(|(id, artifact)| (id, artifact.clone().into())),
+            );
+            builder = builder.with_known_contracts(&contracts);
+        }
+        let mut decoder = builder.build();
+
+        if let Some(mut identifier) = EtherscanIdentifier::new(&config, etherscan.chain)? {
+            for (_, arena) in result.traces.as_deref().unwrap_or_default() {
+                decoder.identify(arena, &mut identifier);
+            }
+        }
+
+        print_traces(&mut result, &decoder).await
+    }
+}
+
+/// Parses a geth `callTracer` frame or a list of parity traces into a trace result.
+fn parse_trace(mut value: Value) -> Result<TraceResult> {
+    // Unwrap the JSON-RPC response and the `trace_replayTransaction` output.
+    if let Some(result) = value.get_mut("result") {
+        value = result.take();
+    }
+    if let Some(trace) = value.get_mut("trace").filter(|trace| trace.is_array()) {
+        value = trace.take();
+    }
+
+    let arena = if value.is_array() {
+        let traces: Vec<TransactionTrace> =
+            serde_json::from_value(value).wrap_err("invalid parity trace")?;
+        parity_arena(&traces)?
+    } else {
+        let frame: CallFrame = serde_json::from_value(value).wrap_err("invalid geth call frame")?;
+        geth_arena(&frame)
+    };
+
+    let root = &arena.nodes()[0].trace;
+    Ok(TraceResult {
+        success: root.success,
+        gas_used: root.gas_used,
+        traces: Some(vec![(TraceKind::Execution, arena)]),
+    })
+}
+
+/// Converts a geth `callTracer` frame and its subcalls into a trace arena.
+fn geth_arena(frame: &CallFrame) -> CallTraceArena {
+    fn push(nodes: &mut Vec<CallTraceNode>, frame: &CallFrame, parent: Option<usize>) {
+        let idx = nodes.len();
+        let kind = match frame.typ.as_str() {
+            "STATICCALL" => CallKind::StaticCall,
+            "DELEGATECALL" => CallKind::DelegateCall,
+            "CALLCODE" => CallKind::CallCode,
+            "CREATE" => CallKind::Create,
+            "CREATE2" => CallKind::Create2,
+            _ => CallKind::Call,
+        };
+        let trace = CallTrace {
+            depth: parent.map_or(0, |parent| nodes[parent].trace.depth + 1),
+            success: frame.error.is_none(),
+            caller: frame.from,
+            address: frame.to.unwrap_or_default(),
+            kind,
+            value: frame.value.unwrap_or_default(),
+            data: frame.input.clone(),
+            output: frame.output.clone().unwrap_or_default(),
+            gas_used: frame.gas_used.saturating_to(),
+            gas_limit: frame.gas.saturating_to(),
+            status: status(frame.error.as_deref()),
+            ..Default::default()
+        };
+        let logs = frame
+            .logs
+            .iter()
+            .map(|log| CallLog {
+                raw_log: LogData::new_unchecked(
+                    log.topics.clone().unwrap_or_default(),
+                    log.data.clone().unwrap_or_default(),
+                ),
+                ..Default::default()
+            })
+            .collect::<Vec<_>>();
+        nodes.push(CallTraceNode { parent, idx, trace, ..Default::default() });
+
+        // The position of the logs relative to the subcalls is not known, list them last.
+        for call in &frame.calls {
+            if call.typ == "SELFDESTRUCT" {
+                continue
+            }
+            let child = nodes.len();
+            push(nodes, call, Some(idx));
+            let node = &mut nodes[idx];
+            node.ordering.push(TraceMemberOrder::Call(node.children.len()));
+            node.children.push(child);
+        }
+        let node = &mut nodes[idx];
+        node.ordering.extend((0..logs.len()).map(TraceMemberOrder::Log));
+        node.logs = logs;
+    }
+
+    let mut arena = CallTraceArena::default();
+    let nodes = arena.nodes_mut();
+    nodes.clear();
+    push(nodes, frame, None);
+    arena
+}
+
+/// Converts a list of parity traces, ordered depth-first as returned by `trace_transaction`,
+/// into a trace arena.
+fn parity_arena(traces: &[TransactionTrace]) -> Result<CallTraceArena> {
+    let mut arena = CallTraceArena::default();
+    let nodes = arena.nodes_mut();
+    nodes.clear();
+    let mut indices = HashMap::<&[usize], usize>::new();
+
+    for trace in traces {
+        let (caller, address, kind, value, data, gas_limit) = match &trace.action {
+            Action::Call(call) => {
+                let kind = match call.call_type {
+                    CallType::StaticCall => CallKind::StaticCall,
+                    CallType::DelegateCall => CallKind::DelegateCall,
+                    CallType::CallCode => CallKind::CallCode,
+                    CallType::AuthCall => CallKind::AuthCall,
+                    CallType::None | CallType::Call => CallKind::Call,
+                };
+                (call.from, call.to, kind, call.value, call.input.clone(), call.gas)
+            }
+            Action::Create(create) => {
+                let address = match &trace.result {
+                    Some(TraceOutput::Create(output)) => output.address,
+                    _ => Address::ZERO,
+                };
+                (
+                    create.from,
+                    address,
+                    CallKind::Create,
+                    create.value,
+                    create.init.clone(),
+                    create.gas,
+                )
+            }
+            // Self-destructs and rewards are not calls.
+            _ => continue,
+        };
+        let (output, gas_used) = match &trace.result {
+            Some(TraceOutput::Call(output)) => (output.output.clone(), output.gas_used),
+            Some(TraceOutput::Create(output)) => (output.code.clone(), output.gas_used),
+            None => (Bytes::new(), Default::default()),
+        };
+
+        let parent =
+            match trace.trace_address.split_last() {
+                Some((_, parent)) => Some(*indices.get(parent).ok_or_else(|| {
+                    eyre::eyre!("missing parent of trace {:?}", trace.trace_address)
+                })?),
+                None if nodes.is_empty() => None,
+                None => eyre::bail!("multiple root traces"),
+            };
+        let idx = nodes.len();
+        indices.insert(&trace.trace_address, idx);
+        nodes.push(CallTraceNode {
+            parent,
+            idx,
+            trace: CallTrace {
+                depth: trace.trace_address.len(),
+                success: trace.error.is_none(),
+                caller,
+                address,
+                kind,
+                value,
+                data,
+                output,
+                gas_used: u64::try_from(gas_used).unwrap_or(u64::MAX),
+                gas_limit: u64::try_from(gas_limit).unwrap_or(u64::MAX),
+                status: status(trace.error.as_deref()),
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+        if let Some(parent) = parent {
+            let parent = &mut nodes[parent];
+            parent.ordering.push(TraceMemberOrder::Call(parent.children.len()));
+            parent.children.push(idx);
+        }
+    }
+
+    eyre::ensure!(!nodes.is_empty(), "no call traces found");
+    Ok(arena)
+}
+
+/// Maps the error of a trace to the closest instruction result.
+fn status(error: Option<&str>) -> InstructionResult {
+    match error {
+        None => InstructionResult::Return,
+        Some(error) if error.to_lowercase().contains("out of gas") => InstructionResult::OutOfGas,
+        Some(_) => InstructionResult::Revert,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::U256;
+    use serde_json::json;
+
+    #[test]
+    fn parse_geth_call_frame() {
+        let value = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "result": {
+                "type": "CALL",
+                "from": "0x0000000000000000000000000000000000000001",
+                "to": "0x0000000000000000000000000000000000000002",
+                "value": "0x0",
+                "gas": "0x10000",
+                "gasUsed": "0x5208",
+                "input": "0x12345678",
+                "output": "0x",
+                "calls": [{
+                    "type": "DELEGATECALL",
+                    "from": "0x0000000000000000000000000000000000000002",
+                    "to": "0x0000000000000000000000000000000000000003",
+                    "gas": "0x8000",
+                    "gasUsed": "0x100",
+                    "input": "0x",
+                    "error": "execution reverted"
+                }],
+                "logs": [{
+                    "address": "0x0000000000000000000000000000000000000002",
+                    "topics": [],
+                    "data": "0x01"
+                }]
+            }
+        });
+        let result = parse_trace(value).unwrap();
+        assert!(result.success);
+        assert_eq!(result.gas_used, 0x5208);
+
+        let (_, arena) = &result.traces.unwrap()[0];
+        let nodes = arena.nodes();
+        assert_eq!(nodes.len(), 2);
+        assert_eq!(nodes[0].children, [1]);
+        assert_eq!(nodes[0].logs.len(), 1);
+        assert_eq!(nodes[0].ordering, [TraceMemberOrder::Call(0), TraceMemberOrder::Log(0)]);
+        assert_eq!(nodes[1].trace.kind, CallKind::DelegateCall);
+        assert_eq!(nodes[1].trace.depth, 1);
+        assert_eq!(nodes[1].trace.status, InstructionResult::Revert);
+    }
+
+    #[test]
+    fn parse_parity_traces() {
+        let value = json!([
+            {
+                "action": {
+                    "callType": "call",
+                    "from": "0x0000000000000000000000000000000000000001",
+                    "to": "0x0000000000000000000000000000000000000002",
+                    "gas": "0x10000",
+                    "input": "0x12345678",
+                    "value": "0x0"
+                },
+                "result": { "gasUsed": "0x5208", "output": "0x" },
+                "subtraces": 1,
+                "traceAddress": [],
+                "type": "call"
+            },
+            {
+                "action": {
+                    "from": "0x0000000000000000000000000000000000000002",
+                    "gas": "0x8000",
+                    "init": "0x6000",
+                    "value": "0x0"
+                },
+                "result": {
+                    "address": "0x0000000000000000000000000000000000000004",
+                    "code": "0x00",
+                    "gasUsed": "0x100"
+                },
+                "subtraces": 0,
+                "traceAddress": [0],
+                "type": "create"
+            }
+        ]);
+        let result = parse_trace(value).unwrap();
+        let (_, arena) = &result.traces.unwrap()[0];
+        let nodes = arena.nodes();
+        assert_eq!(nodes.len(), 2);
+        assert_eq!(nodes[0].children, [1]);
+        assert_eq!(nodes[1].parent, Some(0));
+        assert_eq!(nodes[1].trace.kind, CallKind::Create);
+        assert_eq!(nodes[1].trace.address, Address::with_last_byte(4));
+        assert_eq!(nodes[1].trace.value, U256::ZERO);
+    }
+}
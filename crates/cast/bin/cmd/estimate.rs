@@ -1,11 +1,11 @@
 use crate::tx::CastTxBuilder;
-use alloy_primitives::{TxKind, U256};
+use alloy_primitives::{TxKind, U256, U64};
 use alloy_provider::Provider;
 use alloy_rpc_types::BlockId;
 use clap::Parser;
 use eyre::Result;
 use foundry_cli::{
-    opts::{EthereumOpts, TransactionOpts},
+    opts::{EthereumOpts, StateOverrideOpts, TransactionOpts},
     utils::{self, parse_ether_value},
 };
 use foundry_common::ens::NameOrAddress;
@@ -37,6 +37,9 @@ pub struct EstimateArgs {
     #[command(flatten)]
     tx: TransactionOpts,
 
+    #[command(flatten)]
+    state_override: StateOverrideOpts,
+
     #[command(flatten)]
     eth: EthereumOpts,
 }
@@ -67,7 +70,7 @@ pub enum EstimateSubcommands {
 
 impl EstimateArgs {
     pub async fn run(self) -> Result<()> {
-        let Self { to, mut sig, mut args, mut tx, block, eth, command } = self;
+        let Self { to, mut sig, mut args, mut tx, block, state_override, eth, command } = self;
 
         let config = Config::from(&eth);
         let provider = utils::get_provider(&config)?;
@@ -104,7 +107,17 @@ impl EstimateArgs {
             .build_raw(sender)
             .await?;
 
-        let gas = provider.estimate_gas(&tx).block(block.unwrap_or_default()).await?;
+        let block = block.unwrap_or_default();
+        let gas = if let Some(overrides) = state_override.state_override() {
+            // the provider doesn't support overrides for `eth_estimateGas` yet
+            provider
+                .client()
+                .request::<_, U64>("eth_estimateGas", (&tx, block, overrides))
+                .await?
+                .to::<u128>()
+        } else {
+            provider.estimate_gas(&tx).block(block).await?
+        };
         println!("{gas}");
         Ok(())
     }
@@ -0,0 +1,57 @@
+use super::tenderly::{SimulationSource, TenderlyOpts};
+use alloy_chains::Chain;
+use clap::Parser;
+use eyre::Result;
+use foundry_cli::opts::RpcOpts;
+use foundry_config::Config;
+
+/// CLI arguments for `cast explorer`.
+#[derive(Clone, Debug, Parser)]
+pub struct ExplorerArgs {
+    /// The hash of a published transaction, or the path of a script broadcast file, e.g.
+    /// `broadcast/Deploy.s.sol/1/dry-run/run-latest.json`.
+    #[arg(value_name = "TX_HASH|FILE")]
+    source: SimulationSource,
+
+    #[command(flatten)]
+    tenderly: TenderlyOpts,
+
+    #[command(flatten)]
+    rpc: RpcOpts,
+}
+
+impl ExplorerArgs {
+    pub async fn run(self) -> Result<()> {
+        let Self { source, tenderly, rpc } = self;
+
+        let config = Config::from(&rpc);
+        let simulations = source.simulations(&config).await?;
+
+        // Published transactions are linked on the block explorer of their chain as well.
+        if let SimulationSource::Tx(hash) = &source {
+            let chain =
+                simulations.first().and_then(|simulation| simulation.network_id.parse().ok());
+            if let Some((_, browser_url)) = chain.and_then(|id| Chain::from_id(id).etherscan_urls())
+            {
+                println!("{}/tx/{hash}", browser_url.trim_end_matches('/'));
+            }
+        }
+
+        let (account, project) = match tenderly.resolve(&config) {
+            Ok(project) => project,
+            // Without a Tenderly project, published transactions only get an explorer link.
+            Err(_) if matches!(source, SimulationSource::Tx(_)) => return Ok(()),
+            Err(err) => return Err(err),
+        };
+        for (index, simulation) in simulations.iter().enumerate() {
+            match simulation.simulator_link(&account, &project) {
+                Some(link) => println!("{link}"),
+                None => eprintln!(
+                    "transaction {index} is a contract creation and can't be pre-filled in the \
+                     simulator, upload it with `cast tenderly` instead"
+                ),
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,582 @@
+use super::bundle::{print_transaction, simulated_transaction};
+use alloy_dyn_abi::JsonAbiExt;
+use alloy_json_abi::Function;
+use alloy_primitives::{hex, keccak256, Address, Bytes, TxKind, U256};
+use alloy_sol_types::{sol, SolInterface};
+use cast::revm::primitives::TxEnv;
+use clap::Parser;
+use eyre::{ContextCompat, Result, WrapErr};
+use foundry_cli::opts::{EtherscanOpts, RpcOpts};
+use foundry_common::fmt::format_token;
+use foundry_compilers::artifacts::EvmVersion;
+use foundry_config::{find_project_root_path, Config};
+use foundry_evm::{
+    executors::TracingExecutor,
+    opts::EvmOpts,
+    traces::identifier::{EtherscanIdentifier, SignaturesIdentifier, TraceIdentifier},
+};
+use serde::Serialize;
+use serde_json::json;
+use std::{collections::HashMap, str::FromStr};
+use yansi::Paint;
+
+sol! {
+    interface IGovernor {
+        function propose(address[] targets, uint256[] values, bytes[] calldatas, string description);
+        function queue(address[] targets, uint256[] values, bytes[] calldatas, bytes32 descriptionHash);
+        function execute(address[] targets, uint256[] values, bytes[] calldatas, bytes32 descriptionHash);
+    }
+
+    interface IGovernorBravo {
+        function propose(address[] targets, uint256[] values, string[] signatures, bytes[] calldatas, string description);
+    }
+
+    interface ITimelock {
+        function schedule(address target, uint256 value, bytes data, bytes32 predecessor, bytes32 salt, uint256 delay);
+        function scheduleBatch(address[] targets, uint256[] values, bytes[] payloads, bytes32 predecessor, bytes32 salt, uint256 delay);
+        function execute(address target, uint256 value, bytes payload, bytes32 predecessor, bytes32 salt);
+        function executeBatch(address[] targets, uint256[] values, bytes[] payloads, bytes32 predecessor, bytes32 salt);
+    }
+
+    interface ICompoundTimelock {
+        function queueTransaction(address target, uint256 value, string signature, bytes data, uint256 eta);
+        function executeTransaction(address target, uint256 value, string signature, bytes data, uint256 eta);
+    }
+
+    interface ISafe {
+        function execTransaction(address to, uint256 value, bytes data, uint8 operation, uint256 safeTxGas, uint256 baseGas, uint256 gasPrice, address gasToken, address refundReceiver, bytes signatures);
+    }
+
+    interface IMultiSend {
+        function multiSend(bytes transactions);
+    }
+}
+
+/// CLI arguments for `cast gov`.
+#[derive(Debug, Parser)]
+pub enum GovSubcommands {
+    /// Decode the calls of a governance proposal, timelock operation or Safe transaction.
+    #[command(visible_alias = "d")]
+    Decode(DecodeArgs),
+}
+
+impl GovSubcommands {
+    pub async fn run(self) -> Result<()> {
+        match self {
+            Self::Decode(args) => args.run().await,
+        }
+    }
+}
+
+/// CLI arguments for `cast gov decode`.
+#[derive(Clone, Debug, Parser)]
+pub struct DecodeArgs {
+    /// The calldata of the proposal.
+    ///
+    /// Supported are the calldata of Governor `propose`, `queue` and `execute` calls, of
+    /// GovernorBravo `propose` calls, of TimelockController `schedule(Batch)` and
+    /// `execute(Batch)` calls, of Compound Timelock `queueTransaction` and `executeTransaction`
+    /// calls, of Safe `execTransaction` calls and of MultiSend `multiSend` calls. Nested payloads
+    /// are expanded as well.
+    calldata: String,
+
+    /// Simulate the calls in order on a fork, executed by the given address, and print their
+    /// state changes.
+    ///
+    /// This is usually the timelock or the Safe that executes the proposal.
+    #[arg(long, value_name = "EXECUTOR")]
+    simulate: Option<Address>,
+
+    /// The block on top of which the calls are simulated.
+    ///
+    /// Defaults to the latest block.
+    #[arg(long, short = 'B', requires = "simulate")]
+    block: Option<u64>,
+
+    /// The EVM version to simulate the calls with.
+    #[arg(long, requires = "simulate")]
+    evm_version: Option<EvmVersion>,
+
+    /// Labels to apply to the targets; format: `address:label`.
+    #[arg(long)]
+    labels: Vec<String>,
+
+    /// Print the decoded calls as JSON.
+    #[arg(long, short)]
+    json: bool,
+
+    #[command(flatten)]
+    etherscan: EtherscanOpts,
+
+    #[command(flatten)]
+    rpc: RpcOpts,
+}
+
+/// A decoded bundle of calls.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Payload {
+    /// The kind of the payload, e.g. `Governor proposal`.
+    kind: &'static str,
+    /// The description of the proposal, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    /// The calls, in execution order.
+    calls: Vec<PayloadCall>,
+}
+
+/// A call of a [`Payload`].
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PayloadCall {
+    target: Address,
+    value: U256,
+    data: Bytes,
+    /// Whether the call is a delegate call, only possible in Safe transactions.
+    delegate: bool,
+    /// The name of the target.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+    /// The signature of the called function, if it could be identified.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    signature: Option<String>,
+    /// The decoded arguments of the call.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    args: Vec<String>,
+    /// The payload the calldata itself encodes, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    payload: Option<Payload>,
+}
+
+impl PayloadCall {
+    fn new(target: Address, value: U256, data: Bytes) -> Self {
+        let payload = decode_payload(&data);
+        Self {
+            target,
+            value,
+            data,
+            delegate: false,
+            label: None,
+            signature: None,
+            args: Vec::new(),
+            payload,
+        }
+    }
+
+    /// Returns the calls of a batch.
+    fn batch(targets: Vec<Address>, values: Vec<U256>, calldatas: Vec<Bytes>) -> Vec<Self> {
+        targets
+            .into_iter()
+            .zip(values)
+            .zip(calldatas)
+            .map(|((target, value), data)| Self::new(target, value, data))
+            .collect()
+    }
+}
+
+impl DecodeArgs {
+    pub async fn run(self) -> Result<()> {
+        let Self { calldata, simulate, block, evm_version, labels, json, etherscan, rpc } = self;
+
+        let calldata = hex::decode(calldata.trim()).wrap_err("invalid calldata")?;
+        let Some(mut payload) = decode_payload(&calldata) else {
+            eyre::bail!(
+                "unsupported calldata, see `cast gov decode --help` for the supported calls"
+            )
+        };
+
+        let figment = Config::figment_with_root(find_project_root_path(None).unwrap())
+            .merge(etherscan)
+            .merge(rpc);
+        let evm_opts = figment.extract::<EvmOpts>()?;
+        let mut config = Config::try_from(figment)?.sanitized();
+
+        let mut names = config.labels.clone();
+        for label in labels {
+            let (address, label) = label.split_once(':').wrap_err("expected `address:label`")?;
+            names.insert(Address::from_str(address)?, label.to_string());
+        }
+        let mut decoder = CallDecoder {
+            names,
+            abis: HashMap::new(),
+            signatures: SignaturesIdentifier::from_config(&config)?,
+        };
+        if let Some(mut etherscan) = EtherscanIdentifier::new(&config, config.chain)? {
+            let mut targets = Vec::new();
+            collect_targets(&payload, &mut targets);
+            for identity in etherscan.identify_addresses(targets.iter().map(|t| (t, None::<&[u8]>)))
+            {
+                if let Some(contract) = identity.contract {
+                    decoder.names.entry(identity.address).or_insert(contract);
+                }
+                if let Some(abi) = identity.abi {
+                    decoder.abis.insert(identity.address, abi.functions().cloned().collect());
+                }
+            }
+        }
+        decoder.decode(&mut payload).await;
+
+        let Some(executor_address) = simulate else {
+            if json {
+                println!("{}", serde_json::to_string_pretty(&payload)?);
+            } else {
+                print_payload(&payload, 0);
+            }
+            return Ok(())
+        };
+
+        let mut calls = Vec::new();
+        collect_simulated_calls(&payload, &mut calls)?;
+
+        config.fork_block_number = block;
+        let (mut env, fork, _) = TracingExecutor::get_fork_material(&config, evm_opts).await?;
+        // The executor doesn't pay for gas.
+        env.block.basefee = U256::ZERO;
+        let txs = calls
+            .iter()
+            .map(|call| TxEnv {
+                caller: executor_address,
+                transact_to: TxKind::Call(call.target),
+                data: call.data.clone(),
+                value: call.value,
+                gas_limit: env.block.gas_limit.to(),
+                gas_price: U256::ZERO,
+                ..Default::default()
+            })
+            .collect::<Vec<_>>();
+        let mut executor = TracingExecutor::new(env, fork, evm_version, false);
+        executor.inspector_mut().collect_keccak_preimages(true);
+        let results = executor.simulate_bundle(txs)?;
+
+        if json {
+            let simulation = results
+                .into_iter()
+                .map(|result| simulated_transaction(result, false))
+                .collect::<Result<Vec<_>>>()?;
+            let output = json!({ "payload": payload, "simulation": simulation });
+            println!("{}", serde_json::to_string_pretty(&output)?);
+            return Ok(())
+        }
+
+        print_payload(&payload, 0);
+        println!();
+        for (index, result) in results.into_iter().enumerate() {
+            print_transaction(index, result, None).await?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Decodes the calls encoded in the given calldata, if it's a supported payload.
+fn decode_payload(data: &[u8]) -> Option<Payload> {
+    let payload = |kind, calls| Payload { kind, description: None, calls };
+
+    if let Ok(call) = IGovernor::IGovernorCalls::abi_decode(data, false) {
+        return Some(match call {
+            IGovernor::IGovernorCalls::propose(call) => Payload {
+                kind: "Governor proposal",
+                description: Some(call.description),
+                calls: PayloadCall::batch(call.targets, call.values, call.calldatas),
+            },
+            IGovernor::IGovernorCalls::queue(call) => payload(
+                "Governor queue",
+                PayloadCall::batch(call.targets, call.values, call.calldatas),
+            ),
+            IGovernor::IGovernorCalls::execute(call) => payload(
+                "Governor execution",
+                PayloadCall::batch(call.targets, call.values, call.calldatas),
+            ),
+        })
+    }
+
+    if let Ok(IGovernorBravo::IGovernorBravoCalls::propose(call)) =
+        IGovernorBravo::IGovernorBravoCalls::abi_decode(data, false)
+    {
+        let calldatas = call
+            .signatures
+            .iter()
+            .zip(call.calldatas)
+            .map(|(signature, data)| with_signature(signature, data))
+            .collect();
+        return Some(Payload {
+            kind: "GovernorBravo proposal",
+            description: Some(call.description),
+            calls: PayloadCall::batch(call.targets, call.values, calldatas),
+        })
+    }
+
+    if let Ok(call) = ITimelock::ITimelockCalls::abi_decode(data, false) {
+        return Some(match call {
+            ITimelock::ITimelockCalls::schedule(call) => payload(
+                "Timelock operation",
+                vec![PayloadCall::new(call.target, call.value, call.data)],
+            ),
+            ITimelock::ITimelockCalls::scheduleBatch(call) => payload(
+                "Timelock batch",
+                PayloadCall::batch(call.targets, call.values, call.payloads),
+            ),
+            ITimelock::ITimelockCalls::execute(call) => payload(
+                "Timelock execution",
+                vec![PayloadCall::new(call.target, call.value, call.payload)],
+            ),
+            ITimelock::ITimelockCalls::executeBatch(call) => payload(
+                "Timelock batch execution",
+                PayloadCall::batch(call.targets, call.values, call.payloads),
+            ),
+        })
+    }
+
+    if let Ok(call) = ICompoundTimelock::ICompoundTimelockCalls::abi_decode(data, false) {
+        let (kind, target, value, signature, data) = match call {
+            ICompoundTimelock::ICompoundTimelockCalls::queueTransaction(call) => {
+                ("Timelock transaction", call.target, call.value, call.signature, call.data)
+            }
+            ICompoundTimelock::ICompoundTimelockCalls::executeTransaction(call) => {
+                ("Timelock execution", call.target, call.value, call.signature, call.data)
+            }
+        };
+        let data = with_signature(&signature, data);
+        return Some(payload(kind, vec![PayloadCall::new(target, value, data)]))
+    }
+
+    if let Ok(ISafe::ISafeCalls::execTransaction(call)) = ISafe::ISafeCalls::abi_decode(data, false)
+    {
+        let mut safe_call = PayloadCall::new(call.to, call.value, call.data);
+        safe_call.delegate = call.operation == 1;
+        return Some(payload("Safe transaction", vec![safe_call]))
+    }
+
+    if let Ok(IMultiSend::IMultiSendCalls::multiSend(call)) =
+        IMultiSend::IMultiSendCalls::abi_decode(data, false)
+    {
+        return decode_multi_send(&call.transactions).map(|calls| payload("MultiSend", calls))
+    }
+
+    None
+}
+
+/// Decodes the packed transactions of a MultiSend call.
+///
+/// Each transaction is encoded as `operation (uint8) . to (address) . value (uint256) .
+/// dataLength (uint256) . data (bytes)`.
+fn decode_multi_send(mut transactions: &[u8]) -> Option<Vec<PayloadCall>> {
+    let mut calls = Vec::new();
+    while !transactions.is_empty() {
+        if transactions.len() < 85 {
+            return None
+        }
+        let operation = transactions[0];
+        let target = Address::from_slice(&transactions[1..21]);
+        let value = U256::from_be_slice(&transactions[21..53]);
+        let len = usize::try_from(U256::from_be_slice(&transactions[53..85])).ok()?;
+        let data = transactions.get(85..85usize.checked_add(len)?)?;
+
+        let mut call = PayloadCall::new(target, value, Bytes::copy_from_slice(data));
+        call.delegate = operation == 1;
+        calls.push(call);
+        transactions = &transactions[85 + len..];
+    }
+    Some(calls)
+}
+
+/// Prepends the selector of the given signature to the calldata, like GovernorBravo and the
+/// Compound Timelock do if the signature isn't empty.
+fn with_signature(signature: &str, data: Bytes) -> Bytes {
+    if signature.is_empty() {
+        return data
+    }
+    let selector = &keccak256(signature)[..4];
+    [selector, &data[..]].concat().into()
+}
+
+/// Collects the targets of all calls of the payload, including nested ones.
+fn collect_targets(payload: &Payload, targets: &mut Vec<Address>) {
+    for call in &payload.calls {
+        if !targets.contains(&call.target) {
+            targets.push(call.target);
+        }
+        if let Some(payload) = &call.payload {
+            collect_targets(payload, targets);
+        }
+    }
+}
+
+/// Collects the calls that are executed when the payload is executed.
+///
+/// Delegate calls to MultiSend are replaced with the calls they batch, since they are executed in
+/// the context of the executor.
+fn collect_simulated_calls<'a>(
+    payload: &'a Payload,
+    calls: &mut Vec<&'a PayloadCall>,
+) -> Result<()> {
+    for call in &payload.calls {
+        if !call.delegate {
+            calls.push(call);
+            continue
+        }
+        match &call.payload {
+            Some(payload) if payload.kind == "MultiSend" => {
+                collect_simulated_calls(payload, calls)?
+            }
+            _ => eyre::bail!("cannot simulate a delegate call to {}", call.target),
+        }
+    }
+    Ok(())
+}
+
+/// Identifies the targets and functions of the calls of a payload.
+struct CallDecoder {
+    names: HashMap<Address, String>,
+    /// The functions of the verified contracts.
+    abis: HashMap<Address, Vec<Function>>,
+    signatures: foundry_evm::traces::identifier::SingleSignaturesIdentifier,
+}
+
+impl CallDecoder {
+    async fn decode(&mut self, payload: &mut Payload) {
+        for call in &mut payload.calls {
+            call.label = self.names.get(&call.target).cloned();
+
+            if call.data.len() >= 4 {
+                let selector = &call.data[..4];
+                let func = match self
+                    .abis
+                    .get(&call.target)
+                    .and_then(|functions| functions.iter().find(|f| f.selector() == selector))
+                {
+                    Some(func) => Some(func.clone()),
+                    None => self.signatures.write().await.identify_function(selector).await,
+                };
+                if let Some(func) = func {
+                    call.signature = Some(func.signature());
+                    if let Ok(args) = func.abi_decode_input(&call.data[4..], false) {
+                        call.args = args.iter().map(format_token).collect();
+                    }
+                }
+            }
+
+            if let Some(payload) = &mut call.payload {
+                Box::pin(self.decode(payload)).await;
+            }
+        }
+    }
+}
+
+/// Prints the decoded payload as a tree.
+fn print_payload(payload: &Payload, indent: usize) {
+    let pad = " ".repeat(indent);
+    println!("{pad}{} ({} calls)", payload.kind.bold(), payload.calls.len());
+    if let Some(description) = &payload.description {
+        let title = description.lines().next().unwrap_or_default();
+        println!("{pad}  {}", title.dim());
+    }
+
+    for (index, call) in payload.calls.iter().enumerate() {
+        let mut target = call.target.to_string();
+        if let Some(label) = &call.label {
+            target = format!("{label} ({target})");
+        }
+        let kind = if call.delegate { " [delegatecall]" } else { "" };
+        println!("{pad}  [{index}] {}{kind}", target.cyan());
+        if !call.value.is_zero() {
+            println!("{pad}      value: {}", call.value);
+        }
+        match (&call.signature, call.data.is_empty()) {
+            (Some(signature), _) => {
+                println!("{pad}      {signature}");
+                for arg in &call.args {
+                    println!("{pad}        {arg}");
+                }
+            }
+            (None, false) => println!("{pad}      {}", hex::encode_prefixed(&call.data)),
+            (None, true) => {}
+        }
+        if let Some(payload) = &call.payload {
+            print_payload(payload, indent + 6);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_sol_types::SolCall;
+
+    #[test]
+    fn decodes_nested_payloads() {
+        let transfer = Bytes::from(hex!("a9059cbb"));
+        let token = Address::with_last_byte(1);
+        let safe = Address::with_last_byte(2);
+
+        // MultiSend with a call and a value transfer
+        let mut transactions = Vec::new();
+        for (target, value, data) in [(token, 0u64, &transfer), (Address::ZERO, 5, &Bytes::new())] {
+            transactions.push(0u8);
+            transactions.extend_from_slice(target.as_slice());
+            transactions.extend_from_slice(&U256::from(value).to_be_bytes::<32>());
+            transactions.extend_from_slice(&U256::from(data.len()).to_be_bytes::<32>());
+            transactions.extend_from_slice(data);
+        }
+        let multi_send = IMultiSend::multiSendCall { transactions: transactions.into() };
+
+        let schedule = ITimelock::scheduleBatchCall {
+            targets: vec![safe],
+            values: vec![U256::ZERO],
+            payloads: vec![ISafe::execTransactionCall {
+                to: Address::with_last_byte(3),
+                value: U256::ZERO,
+                data: multi_send.abi_encode().into(),
+                operation: 1,
+                safeTxGas: U256::ZERO,
+                baseGas: U256::ZERO,
+                gasPrice: U256::ZERO,
+                gasToken: Address::ZERO,
+                refundReceiver: Address::ZERO,
+                signatures: Bytes::new(),
+            }
+            .abi_encode()
+            .into()],
+            predecessor: Default::default(),
+            salt: Default::default(),
+            delay: U256::ZERO,
+        };
+
+        let payload = decode_payload(&schedule.abi_encode()).unwrap();
+        assert_eq!(payload.kind, "Timelock batch");
+        assert_eq!(payload.calls[0].target, safe);
+
+        let safe_tx = payload.calls[0].payload.as_ref().unwrap();
+        assert_eq!(safe_tx.kind, "Safe transaction");
+        assert!(safe_tx.calls[0].delegate);
+
+        let multi_send = safe_tx.calls[0].payload.as_ref().unwrap();
+        assert_eq!(multi_send.kind, "MultiSend");
+        assert_eq!(multi_send.calls.len(), 2);
+        assert_eq!(multi_send.calls[0].target, token);
+        assert_eq!(multi_send.calls[0].data, transfer);
+        assert_eq!(multi_send.calls[1].value, U256::from(5));
+
+        let mut calls = Vec::new();
+        collect_simulated_calls(safe_tx, &mut calls).unwrap();
+        assert_eq!(calls.len(), 2);
+        assert!(collect_simulated_calls(&payload, &mut Vec::new()).is_ok());
+
+        assert!(decode_payload(&transfer).is_none());
+    }
+
+    #[test]
+    fn prepends_bravo_signatures() {
+        let proposal = IGovernorBravo::proposeCall {
+            targets: vec![Address::ZERO],
+            values: vec![U256::ZERO],
+            signatures: vec!["transfer(address,uint256)".to_string()],
+            calldatas: vec![Bytes::from(vec![0; 64])],
+            description: "# Title\nBody".to_string(),
+        };
+        let payload = decode_payload(&proposal.abi_encode()).unwrap();
+        assert_eq!(payload.description.as_deref(), Some("# Title\nBody"));
+        assert_eq!(&payload.calls[0].data[..4], &hex!("a9059cbb"));
+        assert_eq!(payload.calls[0].data.len(), 68);
+    }
+}
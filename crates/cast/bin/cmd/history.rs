@@ -0,0 +1,319 @@
+use alloy_json_abi::Function;
+use alloy_network::{AnyNetwork, TransactionBuilder};
+use alloy_primitives::{Address, B256};
+use alloy_provider::Provider;
+use alloy_rpc_types::{BlockId, TransactionRequest};
+use alloy_serde::WithOtherFields;
+use alloy_transport::Transport;
+use cast::Cast;
+use clap::Parser;
+use eyre::{Result, WrapErr};
+use foundry_cli::{
+    opts::RpcOpts,
+    utils::{self, parse_function_args},
+};
+use foundry_common::ens::NameOrAddress;
+use foundry_config::Config;
+use futures::{StreamExt, TryStreamExt};
+use serde::Serialize;
+use std::{collections::BTreeMap, str::FromStr};
+
+/// The maximum number of concurrent requests made while sampling.
+const CONCURRENCY: usize = 16;
+
+/// CLI arguments for `cast history`.
+#[derive(Clone, Debug, Parser)]
+pub struct HistoryArgs {
+    /// The address to sample.
+    #[arg(value_parser = NameOrAddress::from_str)]
+    who: NameOrAddress,
+
+    /// Sample the output of calling the given function instead of the balance.
+    ///
+    /// The signature must include the return types, e.g. "totalSupply()(uint256)".
+    #[arg(long, conflicts_with = "slot")]
+    sig: Option<String>,
+
+    /// The arguments of the function.
+    #[arg(requires = "sig")]
+    args: Vec<String>,
+
+    /// Sample the given storage slot instead of the balance.
+    #[arg(long)]
+    slot: Option<B256>,
+
+    /// The first block to sample.
+    #[arg(long, value_name = "BLOCK")]
+    from_block: u64,
+
+    /// The last block to sample.
+    ///
+    /// Defaults to the latest block.
+    #[arg(long, value_name = "BLOCK")]
+    to_block: Option<u64>,
+
+    /// Sample every STEP blocks.
+    ///
+    /// Defaults to the interval that yields `--samples` samples.
+    #[arg(long, conflicts_with = "samples")]
+    step: Option<u64>,
+
+    /// The number of blocks to sample, if no step is given.
+    #[arg(long, default_value = "100")]
+    samples: u64,
+
+    /// Bisect the intervals in which the value changed to find the exact block of every change.
+    #[arg(long)]
+    refine: bool,
+
+    /// Include the timestamp of each sampled block.
+    #[arg(long)]
+    timestamps: bool,
+
+    /// Print the time series as JSON instead of CSV.
+    #[arg(long, short)]
+    json: bool,
+
+    #[command(flatten)]
+    rpc: RpcOpts,
+}
+
+/// A sampled value.
+#[derive(Debug, Serialize)]
+struct Sample {
+    block: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    timestamp: Option<u64>,
+    value: String,
+}
+
+impl HistoryArgs {
+    pub async fn run(self) -> Result<()> {
+        let Self {
+            who,
+            sig,
+            args,
+            slot,
+            from_block,
+            to_block,
+            step,
+            samples,
+            refine,
+            timestamps,
+            json,
+            rpc,
+        } = self;
+
+        let config = Config::from(&rpc);
+        let provider = utils::get_provider(&config)?;
+        let who = who.resolve(&provider).await?;
+        let to_block = match to_block {
+            Some(block) => block,
+            None => provider.get_block_number().await?,
+        };
+        if from_block > to_block {
+            eyre::bail!("--from-block {from_block} is after --to-block {to_block}");
+        }
+
+        let sampler = match (sig, slot) {
+            (Some(sig), _) => {
+                let chain = utils::get_chain(config.chain, &provider).await?;
+                let api_key = config.get_etherscan_api_key(Some(chain));
+                let (data, func) = parse_function_args(
+                    &sig,
+                    args,
+                    Some(who),
+                    chain,
+                    &provider,
+                    api_key.as_deref(),
+                )
+                .await?;
+                let func = func.ok_or_else(|| eyre::eyre!("could not find function {sig}"))?;
+                let mut tx = WithOtherFields::<TransactionRequest>::default();
+                tx.set_to(who);
+                tx.set_input(data);
+                Sampler::Call(func, tx)
+            }
+            (None, Some(slot)) => Sampler::Storage(slot),
+            (None, None) => Sampler::Balance,
+        };
+        let cast = Cast::new(provider);
+
+        // Nodes that don't keep historical state fail to serve old blocks, but not the latest ones.
+        let first = match sampler.sample(&cast, who, from_block).await {
+            Ok(value) => value,
+            Err(err) if sampler.sample(&cast, who, to_block).await.is_ok() => {
+                return Err(err.wrap_err(format!(
+                    "the state at block {from_block} is not available; \
+                     querying historical state requires an archive node"
+                )))
+            }
+            Err(err) => return Err(err),
+        };
+
+        let step = step.unwrap_or_else(|| default_step(from_block, to_block, samples));
+        let blocks = sample_blocks(from_block, to_block, step);
+        let mut values: BTreeMap<u64, String> = futures::stream::iter(&blocks[1..])
+            .map(|&block| {
+                let cast = &cast;
+                let sampler = &sampler;
+                async move { Ok::<_, eyre::Error>((block, sampler.sample(cast, who, block).await?)) }
+            })
+            .buffered(CONCURRENCY)
+            .try_collect()
+            .await?;
+        values.insert(from_block, first);
+
+        if refine {
+            values = refine_changes(values, |block| sampler.sample(&cast, who, block)).await?;
+        }
+
+        let mut series = Vec::with_capacity(values.len());
+        for (block, value) in values {
+            let timestamp =
+                if timestamps { Some(cast.timestamp(block).await?.to::<u64>()) } else { None };
+            series.push(Sample { block, timestamp, value });
+        }
+
+        if json {
+            println!("{}", serde_json::to_string_pretty(&series)?);
+        } else {
+            println!("{}", if timestamps { "block,timestamp,value" } else { "block,value" });
+            for Sample { block, timestamp, value } in series {
+                let value = csv_field(&value);
+                match timestamp {
+                    Some(timestamp) => println!("{block},{timestamp},{value}"),
+                    None => println!("{block},{value}"),
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The value to sample at each block.
+enum Sampler {
+    Balance,
+    Storage(B256),
+    Call(Function, WithOtherFields<TransactionRequest>),
+}
+
+impl Sampler {
+    async fn sample<P: Provider<T, AnyNetwork>, T: Transport + Clone>(
+        &self,
+        cast: &Cast<P, T>,
+        who: Address,
+        block: u64,
+    ) -> Result<String> {
+        let block_id = Some(BlockId::number(block));
+        let value = match self {
+            Self::Balance => cast.balance(who, block_id).await?.to_string(),
+            Self::Storage(slot) => cast.storage(who, *slot, block_id).await?,
+            Self::Call(func, tx) => cast.call(tx, Some(func), block_id).await?,
+        };
+        Ok(value)
+    }
+}
+
+/// Returns the step that yields the given number of samples, including both ends of the range.
+fn default_step(from_block: u64, to_block: u64, samples: u64) -> u64 {
+    ((to_block - from_block) / samples.saturating_sub(1).max(1)).max(1)
+}
+
+/// Returns the blocks to sample, always including the first and last block of the range.
+fn sample_blocks(from_block: u64, to_block: u64, step: u64) -> Vec<u64> {
+    let mut blocks: Vec<u64> = (from_block..=to_block).step_by(step.max(1) as usize).collect();
+    if blocks.last() != Some(&to_block) {
+        blocks.push(to_block);
+    }
+    blocks
+}
+
+/// Bisects the intervals between consecutive samples with different values until the exact block
+/// of every change is found.
+///
+/// Changes that are reverted within an interval can't be detected.
+async fn refine_changes<F, Fut>(
+    mut values: BTreeMap<u64, String>,
+    sample: F,
+) -> Result<BTreeMap<u64, String>>
+where
+    F: Fn(u64) -> Fut,
+    Fut: std::future::Future<Output = Result<String>>,
+{
+    let mut intervals: Vec<(u64, u64)> = values
+        .iter()
+        .zip(values.iter().skip(1))
+        .filter(|((_, a), (_, b))| a != b)
+        .map(|((&low, _), (&high, _))| (low, high))
+        .collect();
+
+    while let Some((low, high)) = intervals.pop() {
+        if high - low <= 1 {
+            continue
+        }
+        let mid = low + (high - low) / 2;
+        let value = sample(mid).await.wrap_err_with(|| format!("failed to sample block {mid}"))?;
+        if value != values[&low] {
+            intervals.push((low, mid));
+        }
+        if value != values[&high] {
+            intervals.push((mid, high));
+        }
+        values.insert(mid, value);
+    }
+
+    // only keep the samples at which the value changed, and the ends of the range
+    let last = values.keys().next_back().copied();
+    let mut previous: Option<String> = None;
+    values.retain(|block, value| {
+        let keep = previous.as_ref() != Some(value) || Some(*block) == last;
+        previous = Some(value.clone());
+        keep
+    });
+    Ok(values)
+}
+
+/// Quotes the field if it contains characters that are special in CSV.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_compute_sample_blocks() {
+        assert_eq!(sample_blocks(10, 20, 5), vec![10, 15, 20]);
+        assert_eq!(sample_blocks(10, 22, 5), vec![10, 15, 20, 22]);
+        assert_eq!(sample_blocks(10, 10, 5), vec![10]);
+        assert_eq!(default_step(0, 990, 100), 10);
+        assert_eq!(default_step(0, 10, 100), 1);
+    }
+
+    #[tokio::test]
+    async fn can_refine_changes() {
+        // the value changes at blocks 13 and 37
+        let value_at = |block: u64| {
+            let value = match block {
+                ..=12 => "a",
+                13..=36 => "b",
+                _ => "c",
+            };
+            async move { Ok(value.to_string()) }
+        };
+        let mut values = BTreeMap::new();
+        for block in sample_blocks(0, 50, 25) {
+            values.insert(block, value_at(block).await.unwrap());
+        }
+
+        let refined = refine_changes(values, value_at).await.unwrap();
+        assert_eq!(refined.keys().copied().collect::<Vec<_>>(), vec![0, 13, 37, 50]);
+        assert_eq!(refined[&37], "c");
+    }
+}
@@ -1,3 +1,4 @@
+use crate::output::{OutputArgs, OutputFormat};
 use alloy_dyn_abi::{DynSolType, DynSolValue, Specifier};
 use alloy_json_abi::Event;
 use alloy_network::AnyNetwork;
@@ -49,9 +50,9 @@ pub struct LogsArgs {
     #[arg(long)]
     subscribe: bool,
 
-    /// Print the logs as JSON.s
-    #[arg(long, short, help_heading = "Display options")]
-    json: bool,
+    // Streamed logs are only printed as text or JSON.
+    #[command(flatten)]
+    output: OutputArgs,
 
     #[command(flatten)]
     eth: EthereumOpts,
@@ -66,7 +67,7 @@ impl LogsArgs {
             sig_or_topic,
             topics_or_args,
             subscribe,
-            json,
+            output,
             eth,
         } = self;
 
@@ -88,9 +89,13 @@ impl LogsArgs {
         let filter = build_filter(from_block, to_block, address, sig_or_topic, topics_or_args)?;
 
         if !subscribe {
-            let logs = cast.filter_logs(filter, json).await?;
-
-            println!("{logs}");
+            if output.is_structured() {
+                let logs = cast.filter_logs(filter, true).await?;
+                println!("{}", output.render(&serde_json::from_str(&logs)?)?);
+            } else {
+                let logs = cast.filter_logs(filter, false).await?;
+                println!("{logs}");
+            }
 
             return Ok(())
         }
@@ -104,7 +109,7 @@ impl LogsArgs {
             .await?;
         let cast = Cast::new(&provider);
         let mut stdout = io::stdout();
-        cast.subscribe(filter, &mut stdout, json).await?;
+        cast.subscribe(filter, &mut stdout, output.format() == OutputFormat::Json).await?;
 
         Ok(())
     }
@@ -0,0 +1,397 @@
+use anvil_rpc::{
+    error::{ErrorCode, RpcError},
+    request::RpcMethodCall,
+    response::{ResponseResult, RpcResponse},
+};
+use anvil_server::{RpcHandler, ServerConfig};
+use clap::{Parser, ValueHint};
+use eyre::{Context, Result};
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::Value;
+use std::{
+    fs::{File, OpenOptions},
+    io::Write,
+    net::IpAddr,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
+
+/// CLI arguments for `cast mock-rpc`.
+#[derive(Clone, Debug, Parser)]
+pub struct MockRpcArgs {
+    /// The spec of the responses to return, as YAML or JSON.
+    #[arg(long, value_hint = ValueHint::FilePath, value_name = "FILE")]
+    spec: PathBuf,
+
+    /// The host to listen on.
+    #[arg(long, default_value = "127.0.0.1", value_name = "IP_ADDR")]
+    host: IpAddr,
+
+    /// The port to listen on.
+    #[arg(long, short, default_value = "8545", value_name = "NUM")]
+    port: u16,
+
+    /// Append the received requests to the given file, as JSON lines.
+    #[arg(long, value_hint = ValueHint::FilePath, value_name = "FILE")]
+    record: Option<PathBuf>,
+}
+
+impl MockRpcArgs {
+    pub async fn run(self) -> Result<()> {
+        let Self { spec, host, port, record } = self;
+
+        let spec = std::fs::read_to_string(&spec)
+            .wrap_err_with(|| format!("failed to read mock spec {}", spec.display()))?;
+        let spec: MockSpec = serde_yaml::from_str(&spec).wrap_err("failed to parse mock spec")?;
+        spec.validate()?;
+
+        let record = record
+            .map(|path| {
+                OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(&path)
+                    .wrap_err_with(|| format!("failed to open {}", path.display()))
+            })
+            .transpose()?;
+        let handler = MockRpcHandler::new(spec, record);
+
+        let listener = tokio::net::TcpListener::bind((host, port)).await?;
+        println!("Mock RPC server listening on http://{}", listener.local_addr()?);
+        let router = anvil_server::http_router(ServerConfig::default(), handler);
+        axum::serve(listener, router.into_make_service()).await?;
+        Ok(())
+    }
+}
+
+/// The responses a mock RPC server returns.
+///
+/// ```yaml
+/// responses:
+///   - method: eth_chainId
+///     result: "0x1"
+///   - method: eth_sendUserOperation
+///     result: "{{params.0.nonce}}"
+///   - method: eth_estimateUserOperationGas
+///     params: [{ sender: "0x5FbDB2315678afecb367f032d93F642f64180aa3" }]
+///     error: { code: -32500, message: "AA21 didn't pay prefund" }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MockSpec {
+    /// The rules to respond with, the first one that matches a request is used.
+    #[serde(default)]
+    pub responses: Vec<MockResponse>,
+}
+
+impl MockSpec {
+    /// Checks that every rule responds with either a result or an error.
+    pub fn validate(&self) -> Result<()> {
+        for (idx, response) in self.responses.iter().enumerate() {
+            if response.result.is_some() == response.error.is_some() {
+                eyre::bail!(
+                    "response #{idx} for `{}` must have either a `result` or an `error`",
+                    response.method
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A rule of a [`MockSpec`], matching requests and the response to return for them.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MockResponse {
+    /// The method to match, or `*` for any.
+    pub method: String,
+    /// The params to match, if any.
+    ///
+    /// Objects match if all their fields match, arrays if their first elements match, and hex
+    /// strings regardless of case.
+    #[serde(default)]
+    pub params: Option<Value>,
+    /// The result to return.
+    ///
+    /// Strings can reference the request as `{{method}}` and `{{params.<path>}}`, and the number
+    /// of requests the rule matched as `{{count}}`. A string that's just a reference is replaced
+    /// by the referenced value.
+    #[serde(default, deserialize_with = "deserialize_present")]
+    pub result: Option<Value>,
+    /// The error to return.
+    #[serde(default)]
+    pub error: Option<RpcError>,
+    /// The number of requests the rule matches, unlimited if not set.
+    #[serde(default)]
+    pub times: Option<usize>,
+}
+
+/// Deserializes a value that is present, including `null`, as `Some`.
+fn deserialize_present<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<Value>, D::Error> {
+    Value::deserialize(deserializer).map(Some)
+}
+
+/// A request received by the mock server.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct RecordedRequest {
+    pub method: String,
+    pub params: Value,
+}
+
+/// Responds to requests according to a [`MockSpec`], recording them.
+///
+/// Besides the methods of the spec, it serves `mock_requests`, which returns the requests
+/// received so far, and `mock_reset`, which clears them and the match counts of the rules.
+#[derive(Clone, Debug)]
+pub struct MockRpcHandler {
+    state: Arc<Mutex<MockState>>,
+}
+
+#[derive(Debug)]
+struct MockState {
+    spec: MockSpec,
+    /// The number of requests each rule matched.
+    counts: Vec<usize>,
+    requests: Vec<RecordedRequest>,
+    record: Option<File>,
+}
+
+impl MockRpcHandler {
+    pub fn new(spec: MockSpec, record: Option<File>) -> Self {
+        let counts = vec![0; spec.responses.len()];
+        Self {
+            state: Arc::new(Mutex::new(MockState { spec, counts, requests: Vec::new(), record })),
+        }
+    }
+
+    /// Returns the response to a request.
+    pub fn respond(&self, method: &str, params: Value) -> ResponseResult {
+        let mut state = self.state.lock().unwrap();
+        match method {
+            "mock_requests" => return ResponseResult::success(state.requests.clone()),
+            "mock_reset" => {
+                state.requests.clear();
+                state.counts.iter_mut().for_each(|count| *count = 0);
+                return ResponseResult::success(true)
+            }
+            _ => {}
+        }
+
+        let request = RecordedRequest { method: method.to_string(), params };
+        if let Some(file) = &mut state.record {
+            if let Err(err) = serde_json::to_writer(&mut *file, &request)
+                .map_err(std::io::Error::from)
+                .and_then(|()| writeln!(file))
+            {
+                warn!(?err, "failed to record request");
+            }
+        }
+
+        let MockState { spec, counts, requests, .. } = &mut *state;
+        let matched = spec.responses.iter().zip(counts.iter_mut()).find(|(response, count)| {
+            (response.method == "*" || response.method == method) &&
+                response.times.map_or(true, |times| **count < times) &&
+                response.params.as_ref().map_or(true, |params| matches(params, &request.params))
+        });
+        let result = match matched {
+            Some((response, count)) => {
+                *count += 1;
+                if let Some(error) = &response.error {
+                    ResponseResult::Error(error.clone())
+                } else {
+                    let context = serde_json::json!({
+                        "method": method,
+                        "params": request.params,
+                        "count": *count,
+                    });
+                    ResponseResult::Success(render(response.result.as_ref().unwrap(), &context))
+                }
+            }
+            None => ResponseResult::Error(RpcError {
+                code: ErrorCode::MethodNotFound,
+                message: format!("no mock response for `{method}`").into(),
+                data: None,
+            }),
+        };
+        requests.push(request);
+        result
+    }
+}
+
+#[async_trait::async_trait]
+impl RpcHandler for MockRpcHandler {
+    type Request = Value;
+
+    async fn on_request(&self, request: Self::Request) -> ResponseResult {
+        let method = request["method"].as_str().unwrap_or_default().to_string();
+        self.respond(&method, request["params"].clone())
+    }
+
+    async fn on_call(&self, call: RpcMethodCall) -> RpcResponse {
+        let id = call.id();
+        RpcResponse::new(id, self.respond(&call.method, call.params.into()))
+    }
+}
+
+/// Returns `true` if `actual` contains `expected`.
+fn matches(expected: &Value, actual: &Value) -> bool {
+    match (expected, actual) {
+        (Value::Object(expected), Value::Object(actual)) => expected
+            .iter()
+            .all(|(key, value)| actual.get(key).is_some_and(|actual| matches(value, actual))),
+        (Value::Array(expected), Value::Array(actual)) => {
+            expected.len() <= actual.len() &&
+                expected.iter().zip(actual).all(|(expected, actual)| matches(expected, actual))
+        }
+        (Value::String(expected), Value::String(actual)) if expected.starts_with("0x") => {
+            expected.eq_ignore_ascii_case(actual)
+        }
+        _ => expected == actual,
+    }
+}
+
+/// Replaces the `{{path}}` references in the strings of a template with the values of the context.
+fn render(template: &Value, context: &Value) -> Value {
+    match template {
+        Value::String(s) => {
+            // A string that's just a reference is replaced by the referenced value.
+            if let Some(path) = s.strip_prefix("{{").and_then(|s| s.strip_suffix("}}")) {
+                if !path.contains("{{") {
+                    if let Some(value) = lookup(context, path) {
+                        return value.clone()
+                    }
+                }
+            }
+
+            let mut rendered = String::with_capacity(s.len());
+            let mut rest = s.as_str();
+            while let Some(start) = rest.find("{{") {
+                let Some(end) = rest[start..].find("}}") else { break };
+                rendered.push_str(&rest[..start]);
+                let reference = &rest[start..start + end + 2];
+                match lookup(context, &reference[2..reference.len() - 2]) {
+                    Some(Value::String(value)) => rendered.push_str(value),
+                    Some(value) => rendered.push_str(&value.to_string()),
+                    None => rendered.push_str(reference),
+                }
+                rest = &rest[start + end + 2..];
+            }
+            rendered.push_str(rest);
+            Value::String(rendered)
+        }
+        Value::Array(values) => values.iter().map(|value| render(value, context)).collect(),
+        Value::Object(values) => Value::Object(
+            values.iter().map(|(key, value)| (key.clone(), render(value, context))).collect(),
+        ),
+        _ => template.clone(),
+    }
+}
+
+/// Looks up a dot-separated path, e.g. `params.0.sender`, in a value.
+fn lookup<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
+    path.trim().split('.').try_fold(value, |value, segment| match value {
+        Value::Array(values) => values.get(segment.parse::<usize>().ok()?),
+        _ => value.get(segment),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const SPEC: &str = r#"
+responses:
+  - method: eth_chainId
+    result: "0x1"
+  - method: eth_getTransactionReceipt
+    result: null
+  - method: eth_sendUserOperation
+    result: "{{params.0.sender}}"
+  - method: eth_estimateUserOperationGas
+    params: [{ sender: "0x5FbDB2315678afecb367f032d93F642f64180aa3" }]
+    error: { code: -32500, message: "AA21 didn't pay prefund" }
+  - method: eth_blockNumber
+    times: 1
+    result: "0x1"
+  - method: "*"
+    result: { method: "{{method}}", count: "{{count}}", label: "call #{{count}}" }
+"#;
+
+    fn handler() -> MockRpcHandler {
+        let spec: MockSpec = serde_yaml::from_str(SPEC).unwrap();
+        spec.validate().unwrap();
+        MockRpcHandler::new(spec, None)
+    }
+
+    #[test]
+    fn validates_spec() {
+        let spec: MockSpec = serde_yaml::from_str("responses: [{ method: eth_chainId }]").unwrap();
+        assert!(spec.validate().is_err());
+        assert!(serde_yaml::from_str::<MockSpec>("responses: [{ methods: [] }]").is_err());
+    }
+
+    #[test]
+    fn responds_from_spec() {
+        let handler = handler();
+        assert_eq!(
+            handler.respond("eth_chainId", json!([])),
+            ResponseResult::Success(json!("0x1"))
+        );
+        assert_eq!(
+            handler.respond("eth_getTransactionReceipt", json!(["0x01"])),
+            ResponseResult::Success(Value::Null)
+        );
+        assert_eq!(
+            handler.respond("eth_sendUserOperation", json!([{ "sender": "0xabc" }, "0xentry"])),
+            ResponseResult::Success(json!("0xabc"))
+        );
+
+        // Params match partially, and hex strings regardless of case.
+        let ResponseResult::Error(err) = handler.respond(
+            "eth_estimateUserOperationGas",
+            json!([{ "sender": "0x5fbdb2315678afecb367f032d93f642f64180aa3", "nonce": "0x0" }]),
+        ) else {
+            panic!("expected an error")
+        };
+        assert_eq!(err.code, ErrorCode::ServerError(-32500));
+
+        assert_eq!(
+            handler.respond("eth_blockNumber", json!([])),
+            ResponseResult::Success(json!("0x1"))
+        );
+        assert_eq!(
+            handler.respond("eth_blockNumber", json!([])),
+            ResponseResult::Success(
+                json!({ "method": "eth_blockNumber", "count": 1, "label": "call #1" })
+            )
+        );
+    }
+
+    #[test]
+    fn records_requests() {
+        let handler = handler();
+        handler.respond("eth_chainId", json!([]));
+        handler.respond("eth_call", json!([{ "to": "0x01" }, "latest"]));
+
+        let ResponseResult::Success(requests) = handler.respond("mock_requests", Value::Null)
+        else {
+            panic!("expected a result")
+        };
+        assert_eq!(
+            requests,
+            json!([
+                { "method": "eth_chainId", "params": [] },
+                { "method": "eth_call", "params": [{ "to": "0x01" }, "latest"] },
+            ])
+        );
+
+        handler.respond("mock_reset", Value::Null);
+        assert_eq!(
+            handler.respond("mock_requests", Value::Null),
+            ResponseResult::Success(json!([]))
+        );
+    }
+}
@@ -7,15 +7,27 @@
 
 pub mod access_list;
 pub mod bind;
+pub mod bundle;
 pub mod call;
+pub mod compare_call;
+pub mod constructor_args;
 pub mod create2;
+pub mod creation_code;
+pub mod decode_trace;
 pub mod estimate;
+pub mod explorer;
 pub mod find_block;
+pub mod gov;
+pub mod history;
 pub mod interface;
 pub mod logs;
 pub mod mktx;
+pub mod mock_rpc;
+pub mod pool;
 pub mod rpc;
 pub mod run;
 pub mod send;
+pub mod sig_verify;
 pub mod storage;
+pub mod tenderly;
 pub mod wallet;
@@ -0,0 +1,335 @@
+use super::bundle::{
+    print_transaction, simulated_transaction, BundleTransaction, SimulatedTransaction,
+};
+use alloy_json_abi::Function;
+use alloy_primitives::{Address, Selector, U256};
+use alloy_provider::Provider;
+use alloy_rpc_types::{BlockId, BlockTransactions, Transaction, TransactionRequest};
+use cast::traces::{identifier::SignaturesIdentifier, CallTraceDecoderBuilder};
+use clap::Parser;
+use eyre::{Result, WrapErr};
+use foundry_cli::{opts::RpcOpts, utils};
+use foundry_common::fmt::format_token;
+use foundry_compilers::artifacts::EvmVersion;
+use foundry_config::{find_project_root_path, Config};
+use foundry_evm::{
+    executors::{BundleTransactionResult, TracingExecutor},
+    opts::EvmOpts,
+};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, str::FromStr};
+use yansi::Paint;
+
+/// CLI arguments for `cast pool`.
+#[derive(Clone, Debug, Parser)]
+pub struct PoolArgs {
+    /// Only show the transactions sent by one of the given addresses.
+    #[arg(long, value_name = "ADDRESS")]
+    from: Vec<Address>,
+
+    /// Only show the transactions sent to one of the given addresses.
+    #[arg(long, value_name = "ADDRESS")]
+    to: Vec<Address>,
+
+    /// Only show the transactions calling one of the given functions, given by signature or
+    /// selector, e.g. `transfer(address,uint256)` or `0xa9059cbb`.
+    #[arg(long, value_name = "SIG")]
+    selector: Vec<FunctionSelector>,
+
+    /// Include the queued transactions, which can't be included until a gap in the nonces of
+    /// their sender is filled.
+    #[arg(long)]
+    queued: bool,
+
+    /// Show at most this many transactions.
+    #[arg(long, short = 'n', value_name = "COUNT")]
+    limit: Option<usize>,
+
+    /// Execute the pending transactions, in order, on top of the latest block and print their
+    /// effects.
+    #[arg(long, short)]
+    simulate: bool,
+
+    /// Print the traces of the simulated transactions.
+    #[arg(long, short, requires = "simulate")]
+    trace: bool,
+
+    /// Print the transactions as JSON.
+    #[arg(long, short)]
+    json: bool,
+
+    /// The EVM version to simulate the transactions with.
+    ///
+    /// Overrides the version specified in the config.
+    #[arg(long, short, requires = "simulate")]
+    evm_version: Option<EvmVersion>,
+
+    #[command(flatten)]
+    rpc: RpcOpts,
+}
+
+/// A function to filter transactions by, given by signature or selector.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FunctionSelector(Selector);
+
+impl FromStr for FunctionSelector {
+    type Err = eyre::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        if s.starts_with("0x") {
+            return Ok(Self(s.parse().wrap_err_with(|| format!("invalid selector `{s}`"))?))
+        }
+        Ok(Self(
+            Function::parse(s).wrap_err_with(|| format!("invalid signature `{s}`"))?.selector(),
+        ))
+    }
+}
+
+/// The `txpool_content` of a node: its transactions by sender and nonce.
+#[derive(Deserialize)]
+struct TxpoolContent {
+    pending: BTreeMap<Address, BTreeMap<String, Transaction>>,
+    #[serde(default)]
+    queued: BTreeMap<Address, BTreeMap<String, Transaction>>,
+}
+
+/// The JSON representation of a pool transaction.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PoolTransaction<'a> {
+    #[serde(flatten)]
+    tx: &'a Transaction,
+    queued: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    function: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    args: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    simulation: Option<SimulatedTransaction>,
+}
+
+impl PoolArgs {
+    pub async fn run(self) -> Result<()> {
+        let config = Config::from(&self.rpc);
+        let provider = utils::get_provider(&config)?;
+
+        // Not all nodes expose their pool, the transactions of the pending block are the next best
+        // thing.
+        let txs = match provider.raw_request::<_, TxpoolContent>("txpool_content".into(), ()).await
+        {
+            Ok(content) => {
+                let pending =
+                    content.pending.into_values().flat_map(by_nonce).map(|tx| (tx, false));
+                let queued = content.queued.into_values().flat_map(by_nonce).map(|tx| (tx, true));
+                pending.chain(queued.filter(|_| self.queued)).collect::<Vec<_>>()
+            }
+            Err(err) => {
+                warn!(%err, "txpool_content is not supported, using the pending block");
+                let block = provider
+                    .get_block(BlockId::pending(), true.into())
+                    .await?
+                    .ok_or_else(|| eyre::eyre!("the node doesn't expose pending transactions"))?;
+                match block.transactions {
+                    BlockTransactions::Full(txs) => txs.into_iter().map(|tx| (tx, false)).collect(),
+                    _ => Vec::new(),
+                }
+            }
+        };
+
+        let mut txs = txs.into_iter().filter(|(tx, _)| self.matches(tx)).collect::<Vec<_>>();
+        if let Some(limit) = self.limit {
+            txs.truncate(limit);
+        }
+
+        let signatures = SignaturesIdentifier::from_config(&config)?;
+        let mut functions = Vec::with_capacity(txs.len());
+        for (tx, _) in &txs {
+            let function = match tx.input.get(..4) {
+                Some(selector) if tx.to.is_some() => {
+                    signatures.write().await.identify_function(selector).await
+                }
+                _ => None,
+            };
+            functions.push(function);
+        }
+
+        // Queued transactions can't be executed on top of the latest block.
+        let results = if self.simulate {
+            let pending = txs.iter().filter(|(_, queued)| !queued).map(|(tx, _)| tx);
+            Some(self.simulate(pending).await?)
+        } else {
+            None
+        };
+
+        if self.json {
+            let mut results = results.map(|results| results.into_iter());
+            let txs = txs
+                .iter()
+                .zip(&functions)
+                .map(|((tx, queued), function)| {
+                    let simulation = match (&mut results, queued) {
+                        (Some(results), false) => results
+                            .next()
+                            .map(|result| simulated_transaction(result, self.trace))
+                            .transpose()?,
+                        _ => None,
+                    };
+                    Ok(PoolTransaction {
+                        tx,
+                        queued: *queued,
+                        function: function.as_ref().map(|function| function.signature()),
+                        args: decode_args(tx, function.as_ref()),
+                        simulation,
+                    })
+                })
+                .collect::<Result<Vec<_>>>()?;
+            println!("{}", serde_json::to_string_pretty(&txs)?);
+            return Ok(())
+        }
+
+        if txs.is_empty() {
+            println!("No pending transactions found");
+            return Ok(())
+        }
+        for (index, ((tx, queued), function)) in txs.iter().zip(&functions).enumerate() {
+            print_pool_transaction(index, tx, *queued, function.as_ref());
+        }
+
+        if let Some(results) = results {
+            let decoder = if self.trace {
+                Some(
+                    CallTraceDecoderBuilder::new()
+                        .with_labels(config.labels.clone())
+                        .with_signature_identifier(signatures.clone())
+                        .build(),
+                )
+            } else {
+                None
+            };
+            for (index, result) in results.into_iter().enumerate() {
+                print_transaction(index, result, decoder.as_ref()).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns whether the transaction matches all the filters.
+    fn matches(&self, tx: &Transaction) -> bool {
+        (self.from.is_empty() || self.from.contains(&tx.from)) &&
+            (self.to.is_empty() || tx.to.is_some_and(|to| self.to.contains(&to))) &&
+            (self.selector.is_empty() ||
+                tx.input.get(..4).is_some_and(|selector| {
+                    self.selector.iter().any(|FunctionSelector(s)| s.as_slice() == selector)
+                }))
+    }
+
+    /// Executes the transactions in order on top of the latest block.
+    async fn simulate<'a>(
+        &self,
+        txs: impl Iterator<Item = &'a Transaction>,
+    ) -> Result<Vec<BundleTransactionResult>> {
+        let figment = Config::figment_with_root(find_project_root_path(None).unwrap())
+            .merge(self.rpc.clone());
+        let evm_opts = figment.extract::<EvmOpts>()?;
+        let config = Config::try_from(figment)?.sanitized();
+
+        let (env, fork, _) = TracingExecutor::get_fork_material(&config, evm_opts).await?;
+        let txs = txs
+            .map(|tx| {
+                BundleTransaction::Request(TransactionRequest::from(tx.clone()))
+                    .into_tx_env(&env)
+                    .wrap_err_with(|| format!("invalid transaction {}", tx.hash))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut executor = TracingExecutor::new(env, fork, self.evm_version, false);
+        executor.inspector_mut().collect_keccak_preimages(true);
+        executor.simulate_bundle(txs)
+    }
+}
+
+/// Returns the transactions of a sender ordered by nonce.
+fn by_nonce(txs: BTreeMap<String, Transaction>) -> impl Iterator<Item = Transaction> {
+    let mut txs = txs.into_values().collect::<Vec<_>>();
+    txs.sort_by_key(|tx| tx.nonce);
+    txs.into_iter()
+}
+
+/// Returns the decoded arguments of the function called by the transaction.
+fn decode_args(tx: &Transaction, function: Option<&Function>) -> Vec<String> {
+    function
+        .and_then(|function| function.abi_decode_input(&tx.input[4..], false).ok())
+        .map(|args| args.iter().map(format_token).collect())
+        .unwrap_or_default()
+}
+
+/// Prints a pool transaction and its decoded calldata.
+fn print_pool_transaction(
+    index: usize,
+    tx: &Transaction,
+    queued: bool,
+    function: Option<&Function>,
+) {
+    let status = if queued { " (queued)" } else { "" };
+    println!("{}{}", format!("[{index}] {}", tx.hash).bold(), status.yellow());
+    println!("  from: {} (nonce {})", tx.from, tx.nonce);
+    match tx.to {
+        Some(to) => println!("  to: {to}"),
+        None => println!("  to: contract creation"),
+    }
+    if tx.value > U256::ZERO {
+        println!("  value: {}", tx.value);
+    }
+    match (tx.gas_price, tx.max_fee_per_gas) {
+        (_, Some(max_fee)) => println!("  max fee per gas: {max_fee}"),
+        (Some(gas_price), None) => println!("  gas price: {gas_price}"),
+        (None, None) => {}
+    }
+    println!("  gas limit: {}", tx.gas);
+    match function {
+        Some(function) => {
+            println!("  function: {}", function.signature());
+            for arg in decode_args(tx, Some(function)) {
+                println!("    {arg}");
+            }
+        }
+        None if !tx.input.is_empty() => println!("  input: {}", tx.input),
+        None => {}
+    }
+    println!();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::address;
+
+    fn args(filters: &[&str]) -> PoolArgs {
+        PoolArgs::parse_from(["pool"].iter().chain(filters))
+    }
+
+    #[test]
+    fn parses_function_selectors() {
+        let selector = "0xa9059cbb".parse::<FunctionSelector>().unwrap();
+        assert_eq!(selector, "transfer(address,uint256)".parse().unwrap());
+        assert!("0x1234".parse::<FunctionSelector>().is_err());
+    }
+
+    #[test]
+    fn filters_transactions() {
+        let to = address!("0000000000000000000000000000000000000022");
+        let tx = Transaction {
+            from: address!("0000000000000000000000000000000000000011"),
+            to: Some(to),
+            input: alloy_primitives::hex!("a9059cbb").into(),
+            ..Default::default()
+        };
+
+        assert!(args(&[]).matches(&tx));
+        assert!(args(&["--to", &to.to_string()]).matches(&tx));
+        assert!(args(&["--selector", "transfer(address,uint256)"]).matches(&tx));
+        assert!(!args(&["--selector", "approve(address,uint256)"]).matches(&tx));
+        assert!(!args(&["--from", &to.to_string()]).matches(&tx));
+        assert!(!args(&["--to", &to.to_string()]).matches(&Transaction { to: None, ..tx }));
+    }
+}
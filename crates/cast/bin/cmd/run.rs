@@ -1,6 +1,7 @@
 use alloy_primitives::U256;
 use alloy_provider::Provider;
-use alloy_rpc_types::BlockTransactions;
+use alloy_rpc_types::{BlockTransactions, Transaction, TransactionRequest};
+use alloy_serde::WithOtherFields;
 use cast::{revm::primitives::EnvWithHandlerCfg, traces::TraceKind};
 use clap::Parser;
 use eyre::{Result, WrapErr};
@@ -12,8 +13,9 @@ use foundry_common::{is_known_system_sender, SYSTEM_TRANSACTION_TYPE};
 use foundry_compilers::artifacts::EvmVersion;
 use foundry_config::{find_project_root_path, Config};
 use foundry_evm::{
-    executors::{EvmError, TracingExecutor},
+    executors::{EvmError, Executor, TracingExecutor},
     opts::EvmOpts,
+    tx_type::STANDARD_TX_TYPES,
     utils::configure_tx_env,
 };
 
@@ -172,6 +174,12 @@ impl RunArgs {
                         break;
                     }
 
+                    apply_custom_tx_type(&mut executor, &tx).wrap_err_with(|| {
+                        format!(
+                            "Failed to prepare transaction: {:?} in block {}",
+                            tx.hash, env.block.number
+                        )
+                    })?;
                     configure_tx_env(&mut env, &tx);
 
                     if let Some(to) = tx.to {
@@ -209,6 +217,7 @@ impl RunArgs {
         let result = {
             executor.set_trace_printer(self.trace_printer);
 
+            apply_custom_tx_type(&mut executor, &tx)?;
             configure_tx_env(&mut env, &tx);
 
             if let Some(to) = tx.to {
@@ -225,3 +234,16 @@ impl RunArgs {
         Ok(())
     }
 }
+
+/// Validates the transaction and applies the preamble of its type, if it's of a custom type
+/// registered for the chain.
+fn apply_custom_tx_type(executor: &mut Executor, tx: &WithOtherFields<Transaction>) -> Result<()> {
+    if tx.transaction_type.map_or(true, |ty| STANDARD_TX_TYPES.contains(&ty)) {
+        return Ok(())
+    }
+    let request = WithOtherFields {
+        inner: TransactionRequest::from(tx.inner.clone()),
+        other: tx.other.clone(),
+    };
+    executor.apply_custom_tx_type(&request)
+}
@@ -1,5 +1,6 @@
 use crate::tx::{self, CastTxBuilder};
 use alloy_network::{AnyNetwork, EthereumWallet};
+use alloy_primitives::{Address, TxHash};
 use alloy_provider::{Provider, ProviderBuilder};
 use alloy_rpc_types::TransactionRequest;
 use alloy_serde::WithOtherFields;
@@ -7,13 +8,14 @@ use alloy_signer::Signer;
 use alloy_transport::Transport;
 use cast::Cast;
 use clap::Parser;
-use eyre::Result;
+use eyre::{ContextCompat, Result};
 use foundry_cli::{
     opts::{EthereumOpts, TransactionOpts},
     utils,
 };
 use foundry_common::{cli_warn, ens::NameOrAddress};
 use foundry_config::Config;
+use foundry_wallets::{SenderOpts, TransactionSender};
 use std::{path::PathBuf, str::FromStr};
 
 /// CLI arguments for `cast send`.
@@ -47,9 +49,12 @@ pub struct SendTxArgs {
     command: Option<SendTxSubcommands>,
 
     /// Send via `eth_sendTransaction using the `--from` argument or $ETH_FROM as sender
-    #[arg(long, requires = "from")]
+    #[arg(long, requires = "from", conflicts_with_all = ["remote_signer", "bundler"])]
     unlocked: bool,
 
+    #[command(flatten)]
+    sender: SenderOpts,
+
     #[command(flatten)]
     tx: TransactionOpts,
 
@@ -59,6 +64,12 @@ pub struct SendTxArgs {
     /// The path of blob data to be sent.
     #[arg(long, value_name = "BLOB_DATA_PATH", conflicts_with = "legacy", requires = "blob")]
     path: Option<PathBuf>,
+
+    /// Delegate the sender to the given address by sending an EIP-7702 set-code transaction.
+    ///
+    /// The authorization is signed with the key of the sender.
+    #[arg(long, value_name = "ADDRESS", conflicts_with_all = ["unlocked", "legacy", "blob"])]
+    auth: Option<Address>,
 }
 
 #[derive(Debug, Parser)]
@@ -91,7 +102,9 @@ impl SendTxArgs {
             json: to_json,
             command,
             unlocked,
+            sender,
             path,
+            auth,
         } = self;
 
         let blob_data = if let Some(path) = path { Some(std::fs::read(path)?) } else { None };
@@ -112,6 +125,7 @@ impl SendTxArgs {
         let config = Config::from(&eth);
         let provider = utils::get_provider(&config)?;
         let tx_kind = tx::resolve_tx_kind(&provider, &code, &to).await?;
+        let estimate_gas = tx.gas_limit.is_none();
 
         let builder = CastTxBuilder::new(&provider, tx, &config)
             .await?
@@ -148,6 +162,32 @@ impl SendTxArgs {
 
             cast_send(provider, tx, cast_async, confirmations, to_json).await
         // Case 2:
+        // Sign with a remote signer, sending from `--from` or its first account.
+        } else if let Some(remote) = sender.remote_signer()? {
+            let from = match eth.wallet.from {
+                Some(from) => from,
+                None => *remote
+                    .accounts()
+                    .await?
+                    .first()
+                    .wrap_err("The remote signer has no accounts")?,
+            };
+            let (tx, _) = builder.build(from).await?;
+            let tx_hash = remote.send_transaction(&provider, tx).await?;
+            print_tx(&Cast::new(provider), tx_hash, cast_async, confirmations, to_json).await
+        // Case 3:
+        // Send as a user operation of the smart account `--from`, signed by its owner.
+        } else if sender.bundler.is_some() {
+            let account = eth
+                .wallet
+                .from
+                .wrap_err("Sending through a bundler requires --from to be the smart account")?;
+            let owner = eth.wallet.signer().await?;
+            let bundler = sender.bundler(owner)?.expect("bundler is set");
+            let (tx, _) = builder.build(account).await?;
+            let tx_hash = bundler.send_transaction(&provider, tx).await?;
+            print_tx(&Cast::new(provider), tx_hash, cast_async, confirmations, to_json).await
+        // Case 4:
         // An option to use a local signer was provided.
         // If we cannot successfully instantiate a local signer, then we will assume we don't have
         // enough information to sign and we must bail.
@@ -158,6 +198,16 @@ impl SendTxArgs {
 
             tx::validate_from_address(eth.wallet.from, from)?;
 
+            if let Some(delegate) = auth {
+                let (tx, _) = builder.build(from).await?;
+                let tx = tx::sign_with_authorization(tx, delegate, &signer, estimate_gas).await?;
+                let mut raw = Vec::new();
+                tx.tx().encode_with_signature(tx.signature(), &mut raw);
+                let tx_hash = *provider.send_raw_transaction(&raw).await?.tx_hash();
+                let cast = Cast::new(provider);
+                return print_tx(&cast, tx_hash, cast_async, confirmations, to_json).await
+            }
+
             let wallet = EthereumWallet::from(signer);
             let provider = ProviderBuilder::<_, _, AnyNetwork>::default()
                 .wallet(wallet)
@@ -180,8 +230,18 @@ async fn cast_send<P: Provider<T, AnyNetwork>, T: Transport + Clone>(
     let cast = Cast::new(provider);
     let pending_tx = cast.send(tx).await?;
 
-    let tx_hash = pending_tx.inner().tx_hash();
+    let tx_hash = *pending_tx.inner().tx_hash();
+    print_tx(&cast, tx_hash, cast_async, confs, to_json).await
+}
 
+/// Prints the hash of a sent transaction, or its receipt once it is confirmed.
+async fn print_tx<P: Provider<T, AnyNetwork>, T: Transport + Clone>(
+    cast: &Cast<P, T>,
+    tx_hash: TxHash,
+    cast_async: bool,
+    confs: u64,
+    to_json: bool,
+) -> Result<()> {
     if cast_async {
         println!("{tx_hash:#x}");
     } else {
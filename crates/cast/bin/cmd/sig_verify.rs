@@ -0,0 +1,152 @@
+use crate::output::OutputArgs;
+use alloy_dyn_abi::TypedData;
+use alloy_primitives::{eip191_hash_message, hex, Address, Bytes, FixedBytes, Signature, B256};
+use alloy_provider::Provider;
+use alloy_sol_types::sol;
+use clap::Parser;
+use eyre::{Result, WrapErr};
+use foundry_cli::{opts::RpcOpts, utils};
+use foundry_config::Config;
+use serde_json::json;
+
+sol! {
+    #[sol(rpc)]
+    interface IERC1271 {
+        function isValidSignature(bytes32 hash, bytes signature) external view returns (bytes4 magicValue);
+    }
+}
+
+/// The value that ERC-1271 contracts return for valid signatures.
+const ERC1271_MAGIC_VALUE: FixedBytes<4> = FixedBytes([0x16, 0x26, 0xba, 0x7e]);
+
+/// CLI arguments for `cast sig-verify`.
+#[derive(Clone, Debug, Parser)]
+pub struct SigVerifyArgs {
+    /// The message, typed data, or hash that was signed.
+    ///
+    /// Messages starting with 0x are expected to be hex encoded, which get decoded before
+    /// being verified.
+    ///
+    /// The message is prefixed with the Ethereum Signed Message header and hashed, unless
+    /// `--no-hash` is provided.
+    ///
+    /// Use --data to denote the message is a string of typed data, and --data --from-file to
+    /// denote the message is a file name containing typed data.
+    message: String,
+
+    /// The signature to verify.
+    signature: Bytes,
+
+    /// The address that is expected to have signed the message.
+    ///
+    /// If the signature was not made by the key of the address and an RPC URL is provided, the
+    /// signature is checked with the `isValidSignature` function of the contract at the address
+    /// (ERC-1271).
+    #[arg(long, short)]
+    address: Option<Address>,
+
+    /// Treat the message as JSON typed data.
+    #[arg(long)]
+    data: bool,
+
+    /// Treat the message as a file containing JSON typed data. Requires `--data`.
+    #[arg(long, requires = "data")]
+    from_file: bool,
+
+    /// Treat the message as a raw 32-byte hash.
+    #[arg(long, conflicts_with = "data")]
+    no_hash: bool,
+
+    #[command(flatten)]
+    output: OutputArgs,
+
+    #[command(flatten)]
+    rpc: RpcOpts,
+}
+
+impl SigVerifyArgs {
+    pub async fn run(self) -> Result<()> {
+        let Self { message, signature, address, data, from_file, no_hash, output, rpc } = self;
+
+        let hash = if data {
+            let typed_data: TypedData = if from_file {
+                foundry_common::fs::read_json_file(message.as_ref())?
+            } else {
+                serde_json::from_str(&message)?
+            };
+            typed_data.eip712_signing_hash()?
+        } else if no_hash {
+            B256::try_from(&hex::decode(&message)?[..])
+                .wrap_err("The hash must be exactly 32 bytes.")?
+        } else {
+            eip191_hash_message(hex_str_to_bytes(&message)?)
+        };
+
+        // Only 65-byte signatures can be made by a key, contracts accept any format.
+        let signer = Signature::try_from(&signature[..])
+            .ok()
+            .and_then(|sig| sig.recover_address_from_prehash(&hash).ok());
+
+        let (valid, method) = match address {
+            Some(address) if signer == Some(address) => (true, Some("ecdsa")),
+            Some(address) => {
+                let config = Config::from(&rpc);
+                if config.get_rpc_url().is_some() {
+                    let provider = utils::get_provider(&config)?;
+                    let valid = if provider.get_code_at(address).await?.is_empty() {
+                        false
+                    } else {
+                        IERC1271::new(address, &provider)
+                            .isValidSignature(hash, signature.clone())
+                            .call()
+                            .await
+                            .is_ok_and(|ret| ret.magicValue == ERC1271_MAGIC_VALUE)
+                    };
+                    (valid, valid.then_some("erc1271"))
+                } else {
+                    (false, None)
+                }
+            }
+            None => (signer.is_some(), signer.map(|_| "ecdsa")),
+        };
+
+        if output.is_structured() {
+            let result = json!({
+                "valid": valid,
+                "signer": signer,
+                "address": address,
+                "hash": hash,
+                "method": method,
+            });
+            println!("{}", output.render(&result)?);
+        }
+
+        match address {
+            Some(address) if valid => {
+                if !output.is_structured() {
+                    println!("Validation succeeded. Address {address} signed this message.");
+                }
+            }
+            Some(address) => {
+                eyre::bail!("Validation failed. Address {address} did not sign this message.")
+            }
+            None => {
+                let Some(signer) = signer else {
+                    eyre::bail!("Could not recover the signer of the message.")
+                };
+                if !output.is_structured() {
+                    println!("{signer}");
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn hex_str_to_bytes(s: &str) -> Result<Vec<u8>> {
+    Ok(match s.strip_prefix("0x") {
+        Some(data) => hex::decode(data).wrap_err("Could not decode 0x-prefixed string.")?,
+        None => s.as_bytes().to_vec(),
+    })
+}
@@ -1,17 +1,20 @@
 use crate::opts::parse_slot;
 use alloy_network::AnyNetwork;
-use alloy_primitives::{Address, B256, U256};
+use alloy_primitives::{hex, keccak256, Address, B256, I256, U256};
 use alloy_provider::Provider;
 use alloy_rpc_types::BlockId;
 use alloy_transport::Transport;
 use cast::Cast;
-use clap::Parser;
+use clap::{Parser, ValueEnum};
 use comfy_table::{presets::ASCII_MARKDOWN, Table};
 use eyre::Result;
 use foundry_block_explorers::Client;
 use foundry_cli::{
     opts::{CoreBuildArgs, EtherscanOpts, RpcOpts},
-    utils,
+    utils::{
+        self, element_position, encode_mapping_key, format_slot, mapping_slot, DecodeOpts,
+        DecodedSlot, MappingKey, StorageDecoder,
+    },
 };
 use foundry_common::{
     abi::find_source,
@@ -19,7 +22,7 @@ use foundry_common::{
     ens::NameOrAddress,
 };
 use foundry_compilers::{
-    artifacts::{ConfigurableContractArtifact, StorageLayout},
+    artifacts::{ConfigurableContractArtifact, Storage, StorageLayout},
     compilers::{
         solc::{Solc, SolcCompiler},
         Compiler, CompilerSettings,
@@ -55,6 +58,28 @@ pub struct StorageArgs {
     #[arg(long, short)]
     block: Option<BlockId>,
 
+    /// Decode the whole state of the contract by walking its storage layout.
+    ///
+    /// Structs, arrays, strings and bytes are expanded into their members, elements and values,
+    /// and mappings into the entries of the keys given with `--key`.
+    #[arg(long, conflicts_with = "slot")]
+    decode: bool,
+
+    /// A mapping key to decode the entries of, implies `--decode`.
+    ///
+    /// The key is used for every mapping whose key type it can be parsed as, including nested
+    /// mappings. Prefix it with the name of a state variable as `<VARIABLE>=<KEY>` to only use
+    /// it for the mappings of that variable, e.g.
+    /// `balances=0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045`.
+    ///
+    /// Can be specified multiple times.
+    #[arg(long = "key", value_name = "KEY", conflicts_with = "slot")]
+    keys: Vec<MappingKey>,
+
+    /// The maximum number of elements of an array to decode.
+    #[arg(long, default_value = "16", value_name = "N")]
+    max_elements: usize,
+
     #[command(flatten)]
     rpc: RpcOpts,
 
@@ -85,7 +110,12 @@ impl StorageArgs {
     pub async fn run(self) -> Result<()> {
         let config = Config::from(&self);
 
-        let Self { address, slot, block, build, .. } = self;
+        let Self { address, slot, block, decode, keys, max_elements, build, .. } = self;
+        let decode = (decode || !keys.is_empty()).then(|| DecodeOpts {
+            keys,
+            max_elements,
+            ..Default::default()
+        });
         let provider = utils::get_provider(&config)?;
         let address = address.resolve(&provider).await?;
 
@@ -96,92 +126,401 @@ impl StorageArgs {
             return Ok(());
         }
 
-        // No slot was provided
-        // Get deployed bytecode at given address
-        let address_code =
-            provider.get_code_at(address).block_id(block.unwrap_or_default()).await?;
-        if address_code.is_empty() {
-            eyre::bail!("Provided address has no deployed code and thus no storage");
+        let artifact =
+            find_deployed_artifact(&provider, address, block, &build, &self.etherscan, &config)
+                .await?;
+        fetch_and_print_storage(provider, address, block, &artifact, decode.as_ref(), true).await
+    }
+}
+
+/// Returns the artifact of the contract deployed at `address`, with its storage layout.
+///
+/// The artifact is looked up in the current project by deployed bytecode, and compiled from the
+/// verified source on Etherscan otherwise.
+async fn find_deployed_artifact<P: Provider<T, AnyNetwork>, T: Transport + Clone>(
+    provider: &P,
+    address: Address,
+    block: Option<BlockId>,
+    build: &CoreBuildArgs,
+    etherscan: &EtherscanOpts,
+    config: &Config,
+) -> Result<ConfigurableContractArtifact> {
+    // Get deployed bytecode at given address
+    let address_code = provider.get_code_at(address).block_id(block.unwrap_or_default()).await?;
+    if address_code.is_empty() {
+        eyre::bail!("Provided address has no deployed code and thus no storage");
+    }
+
+    // Check if we're in a forge project and if we can find the address' code
+    let mut project = build.project()?;
+    if project.paths.has_input_files() {
+        // Find in artifacts and pretty print
+        add_storage_layout_output(&mut project);
+        let out = ProjectCompiler::new().compile(&project)?;
+        let artifact = out.artifacts().find(|(_, artifact)| {
+            artifact.get_deployed_bytecode_bytes().is_some_and(|b| *b == address_code)
+        });
+        if let Some((_, artifact)) = artifact {
+            return Ok(artifact.clone())
         }
+    }
+
+    // Not a forge project or artifact not found
+    // Get code from Etherscan
+    eprintln!("No matching artifacts found, fetching source code from Etherscan...");
+
+    if !etherscan.has_key() {
+        eyre::bail!(
+            "You must provide an Etherscan API key if you're fetching a remote contract's storage."
+        );
+    }
+
+    let chain = utils::get_chain(config.chain, provider).await?;
+    let api_key = config.get_etherscan_api_key(Some(chain)).unwrap_or_default();
+    let client = Client::new(chain, api_key)?;
+    let source = find_source(client, address).await?;
+    let metadata = source.items.first().unwrap();
+    if metadata.is_vyper() {
+        eyre::bail!("Contract at provided address is not a valid Solidity contract")
+    }
+
+    let version = metadata.compiler_version()?;
+    let auto_detect = version < MIN_SOLC;
 
-        // Check if we're in a forge project and if we can find the address' code
-        let mut project = build.project()?;
-        if project.paths.has_input_files() {
-            // Find in artifacts and pretty print
-            add_storage_layout_output(&mut project);
-            let out = ProjectCompiler::new().compile(&project)?;
-            let artifact = out.artifacts().find(|(_, artifact)| {
-                artifact.get_deployed_bytecode_bytes().is_some_and(|b| *b == address_code)
-            });
-            if let Some((_, artifact)) = artifact {
-                return fetch_and_print_storage(provider, address, block, artifact, true).await;
+    // Create a new temp project
+    // TODO: Cache instead of using a temp directory: metadata from Etherscan won't change
+    let root = tempfile::tempdir()?;
+    let root_path = root.path();
+    let mut project = etherscan_project(metadata, root_path)?;
+    add_storage_layout_output(&mut project);
+
+    project.compiler = if auto_detect {
+        SolcCompiler::AutoDetect
+    } else {
+        SolcCompiler::Specific(Solc::find_or_install(&version)?)
+    };
+
+    // Compile
+    let mut out = ProjectCompiler::new().quiet(true).compile(&project)?;
+    let artifact = {
+        let (_, mut artifact) = out
+            .artifacts()
+            .find(|(name, _)| name == &metadata.contract_name)
+            .ok_or_else(|| eyre::eyre!("Could not find artifact"))?;
+
+        if is_storage_layout_empty(&artifact.storage_layout) && auto_detect {
+            // try recompiling with the minimum version
+            eprintln!("The requested contract was compiled with {version} while the minimum version for storage layouts is {MIN_SOLC} and as a result the output may be empty.");
+            let solc = Solc::find_or_install(&MIN_SOLC)?;
+            project.compiler = SolcCompiler::Specific(solc);
+            if let Ok(output) = ProjectCompiler::new().quiet(true).compile(&project) {
+                out = output;
+                let (_, new_artifact) = out
+                    .artifacts()
+                    .find(|(name, _)| name == &metadata.contract_name)
+                    .ok_or_else(|| eyre::eyre!("Could not find artifact"))?;
+                artifact = new_artifact;
             }
         }
 
-        // Not a forge project or artifact not found
-        // Get code from Etherscan
-        eprintln!("No matching artifacts found, fetching source code from Etherscan...");
+        artifact.clone()
+    };
 
-        if !self.etherscan.has_key() {
-            eyre::bail!("You must provide an Etherscan API key if you're fetching a remote contract's storage.");
-        }
+    // Clear temp directory
+    root.close()?;
+
+    Ok(artifact)
+}
+
+/// The RPC method to write storage with.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum SetStorageMethod {
+    /// `anvil_setStorageAt`
+    #[default]
+    Anvil,
+    /// `hardhat_setStorageAt`
+    Hardhat,
+}
 
-        let chain = utils::get_chain(config.chain, &provider).await?;
-        let api_key = config.get_etherscan_api_key(Some(chain)).unwrap_or_default();
-        let client = Client::new(chain, api_key)?;
-        let source = find_source(client, address).await?;
-        let metadata = source.items.first().unwrap();
-        if metadata.is_vyper() {
-            eyre::bail!("Contract at provided address is not a valid Solidity contract")
+impl SetStorageMethod {
+    fn rpc_method(self) -> &'static str {
+        match self {
+            Self::Anvil => "anvil_setStorageAt",
+            Self::Hardhat => "hardhat_setStorageAt",
         }
+    }
+}
 
-        let version = metadata.compiler_version()?;
-        let auto_detect = version < MIN_SOLC;
+/// CLI arguments for `cast storage-write`.
+#[derive(Clone, Debug, Parser)]
+pub struct StorageWriteArgs {
+    /// The contract address.
+    #[arg(value_parser = NameOrAddress::from_str)]
+    address: NameOrAddress,
 
-        // Create a new temp project
-        // TODO: Cache instead of using a temp directory: metadata from Etherscan won't change
-        let root = tempfile::tempdir()?;
-        let root_path = root.path();
-        let mut project = etherscan_project(metadata, root_path)?;
-        add_storage_layout_output(&mut project);
+    /// The path to the value to write, e.g. `owner`,
+    /// `balances[0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045]` or `pools[2].fee`.
+    path: String,
+
+    /// The value to write.
+    ///
+    /// Numbers can be given in decimal or hex, `bytes` values in hex and `string` values as is.
+    value: String,
+
+    /// The RPC method to write the storage with.
+    #[arg(long, value_enum, default_value_t)]
+    method: SetStorageMethod,
+
+    #[command(flatten)]
+    rpc: RpcOpts,
+
+    #[command(flatten)]
+    etherscan: EtherscanOpts,
+
+    #[command(flatten)]
+    build: CoreBuildArgs,
+}
+
+impl_figment_convert_cast!(StorageWriteArgs);
+
+impl figment::Provider for StorageWriteArgs {
+    fn metadata(&self) -> Metadata {
+        Metadata::named("StorageWriteArgs")
+    }
+
+    fn data(&self) -> Result<figment::value::Map<Profile, Dict>, figment::Error> {
+        let mut map = self.build.data()?;
+        let dict = map.get_mut(&Config::selected_profile()).unwrap();
+        dict.extend(self.rpc.dict());
+        dict.extend(self.etherscan.dict());
+        Ok(map)
+    }
+}
+
+impl StorageWriteArgs {
+    pub async fn run(self) -> Result<()> {
+        let config = Config::from(&self);
+        let provider = utils::get_provider(&config)?;
+        let address = self.address.resolve(&provider).await?;
 
-        project.compiler = if auto_detect {
-            SolcCompiler::AutoDetect
+        let artifact =
+            find_deployed_artifact(&provider, address, None, &self.build, &self.etherscan, &config)
+                .await?;
+        if is_storage_layout_empty(&artifact.storage_layout) {
+            eyre::bail!("The storage layout of the contract is empty");
+        }
+        let layout = artifact.storage_layout.as_ref().unwrap();
+
+        let location = resolve_path(layout, &self.path)?;
+        let ty = &layout.types[&location.type_id];
+        let writes = if ty.encoding == "bytes" {
+            let data = if ty.label == "string" {
+                self.value.as_bytes().to_vec()
+            } else {
+                hex::decode(&self.value)?
+            };
+            encode_bytes(location.slot, &data)
+        } else if ty.encoding == "inplace" &&
+            !ty.other.contains_key("members") &&
+            !ty.other.contains_key("base")
+        {
+            let size = ty.number_of_bytes.parse()?;
+            let value = encode_storage_value(&ty.label, &self.value, size)?;
+            // Keep the other values packed in the slot.
+            let current = provider.get_storage_at(address, location.slot).await?;
+            vec![(location.slot, pack_value(current, value, location.offset, size))]
         } else {
-            SolcCompiler::Specific(Solc::find_or_install(&version)?)
+            eyre::bail!(
+                "`{}` is a `{}`, write its members or elements one by one",
+                self.path,
+                ty.label
+            );
         };
 
-        // Compile
-        let mut out = ProjectCompiler::new().quiet(true).compile(&project)?;
-        let artifact = {
-            let (_, mut artifact) = out
-                .artifacts()
-                .find(|(name, _)| name == &metadata.contract_name)
-                .ok_or_else(|| eyre::eyre!("Could not find artifact"))?;
-
-            if is_storage_layout_empty(&artifact.storage_layout) && auto_detect {
-                // try recompiling with the minimum version
-                eprintln!("The requested contract was compiled with {version} while the minimum version for storage layouts is {MIN_SOLC} and as a result the output may be empty.");
-                let solc = Solc::find_or_install(&MIN_SOLC)?;
-                project.compiler = SolcCompiler::Specific(solc);
-                if let Ok(output) = ProjectCompiler::new().quiet(true).compile(&project) {
-                    out = output;
-                    let (_, new_artifact) = out
-                        .artifacts()
-                        .find(|(name, _)| name == &metadata.contract_name)
-                        .ok_or_else(|| eyre::eyre!("Could not find artifact"))?;
-                    artifact = new_artifact;
-                }
+        for (slot, value) in writes {
+            provider
+                .raw_request::<_, serde_json::Value>(
+                    self.method.rpc_method().into(),
+                    (address, B256::from(slot), value),
+                )
+                .await?;
+            println!("{}: {value}", format_slot(slot));
+        }
+        Ok(())
+    }
+}
+
+/// The location of a value in the storage of a contract.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct StorageLocation {
+    slot: U256,
+    offset: usize,
+    type_id: String,
+}
+
+/// Resolves a path like `balances[0x..]` or `pools[2].owner` to the location of the value in the
+/// storage layout.
+fn resolve_path(layout: &StorageLayout, path: &str) -> Result<StorageLocation> {
+    let (variable, mut rest) = split_identifier(path);
+    let storage = layout
+        .storage
+        .iter()
+        .find(|storage| storage.label == variable)
+        .ok_or_else(|| eyre::eyre!("no state variable named `{variable}`"))?;
+    let mut location = StorageLocation {
+        slot: U256::from_str(&storage.slot)?,
+        offset: storage.offset as usize,
+        type_id: storage.storage_type.clone(),
+    };
+
+    let mut walked = variable.to_string();
+    while !rest.is_empty() {
+        let ty = layout
+            .types
+            .get(&location.type_id)
+            .ok_or_else(|| eyre::eyre!("unknown type of `{walked}`"))?;
+        if let Some(after) = rest.strip_prefix('.') {
+            let (member, after) = split_identifier(after);
+            let members = ty
+                .other
+                .get("members")
+                .and_then(|members| serde_json::from_value::<Vec<Storage>>(members.clone()).ok())
+                .ok_or_else(|| eyre::eyre!("`{walked}` is a `{}`, not a struct", ty.label))?;
+            let member = members
+                .iter()
+                .find(|m| m.label == member)
+                .ok_or_else(|| eyre::eyre!("`{walked}` has no member `{member}`"))?;
+            location = StorageLocation {
+                slot: location.slot.wrapping_add(U256::from_str(&member.slot)?),
+                offset: member.offset as usize,
+                type_id: member.storage_type.clone(),
+            };
+            walked = format!("{walked}.{}", member.label);
+            rest = after;
+        } else if let Some(after) = rest.strip_prefix('[') {
+            let (key, after) = split_key(after)
+                .ok_or_else(|| eyre::eyre!("unclosed `[` after `{walked}` in `{path}`"))?;
+            if let (Some(key_id), Some(value_id)) = (&ty.key, &ty.value) {
+                let key_label = layout.types.get(key_id).map_or("", |t| t.label.as_str());
+                let encoded = encode_mapping_key(key_label, key)?;
+                location = StorageLocation {
+                    slot: mapping_slot(&encoded, location.slot),
+                    offset: 0,
+                    type_id: value_id.clone(),
+                };
+            } else if let Some(base) = ty.other.get("base").and_then(|base| base.as_str()) {
+                let index: usize = key
+                    .parse()
+                    .map_err(|_| eyre::eyre!("`{key}` is not a valid index of `{walked}`"))?;
+                let data = if ty.encoding == "dynamic_array" {
+                    U256::from_be_bytes(keccak256(location.slot.to_be_bytes::<32>()).0)
+                } else {
+                    let length: usize = ty
+                        .label
+                        .rsplit_once('[')
+                        .and_then(|(_, length)| length.trim_end_matches(']').parse().ok())
+                        .unwrap_or_default();
+                    if index >= length {
+                        eyre::bail!("index {index} is out of bounds of `{walked}`");
+                    }
+                    location.slot
+                };
+                let size = layout
+                    .types
+                    .get(base)
+                    .and_then(|base| base.number_of_bytes.parse().ok())
+                    .unwrap_or(32);
+                let (slot, offset) = element_position(data, index, size);
+                location = StorageLocation { slot, offset, type_id: base.to_string() };
+            } else {
+                eyre::bail!("`{walked}` is a `{}`, it can't be indexed", ty.label);
             }
+            walked = format!("{walked}[{key}]");
+            rest = after;
+        } else {
+            eyre::bail!("invalid path `{path}`, expected `.` or `[` after `{walked}`");
+        }
+    }
+    Ok(location)
+}
 
-            artifact
-        };
+/// Splits the identifier at the start of `s` from the rest.
+fn split_identifier(s: &str) -> (&str, &str) {
+    let end =
+        s.find(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '$')).unwrap_or(s.len());
+    s.split_at(end)
+}
+
+/// Splits the key of an index expression, after its `[`, from the rest. Quotes around the key
+/// are removed, e.g. for `string` keys containing `]`.
+fn split_key(s: &str) -> Option<(&str, &str)> {
+    if let Some(quoted) = s.strip_prefix('"') {
+        let (key, rest) = quoted.split_once('"')?;
+        return Some((key, rest.strip_prefix(']')?))
+    }
+    let (key, rest) = s.split_once(']')?;
+    Some((key.trim(), rest))
+}
+
+/// Encodes a value of the given Solidity type of `size` bytes, right-aligned as it's stored.
+fn encode_storage_value(label: &str, value: &str, size: usize) -> Result<U256> {
+    let bits = size.min(32) * 8;
+    let word = match label {
+        "bool" => U256::from(value.parse::<bool>()? as u8),
+        "address" | "address payable" => {
+            U256::from_be_bytes(Address::from_str(value)?.into_word().0)
+        }
+        _ if label.starts_with("contract ") => {
+            U256::from_be_bytes(Address::from_str(value)?.into_word().0)
+        }
+        _ if label.starts_with("uint") || label.starts_with("enum ") => U256::from_str(value)?,
+        _ if label.starts_with("int") => {
+            let int = value.parse::<I256>()?;
+            let shift = 256 - bits;
+            if I256::from_raw(int.into_raw() << shift).asr(shift) != int {
+                eyre::bail!("`{value}` doesn't fit in a `{label}`");
+            }
+            int.into_raw() & (U256::MAX >> shift)
+        }
+        _ if label.starts_with("bytes") => {
+            let bytes = hex::decode(value)?;
+            if bytes.len() > size {
+                eyre::bail!("`{value}` is longer than {size} bytes");
+            }
+            U256::from_be_bytes(B256::right_padding_from(&bytes).0) >> (256 - bits)
+        }
+        _ => eyre::bail!("unsupported type `{label}`"),
+    };
+    if word.bit_len() > bits {
+        eyre::bail!("`{value}` doesn't fit in a `{label}`");
+    }
+    Ok(word)
+}
 
-        // Clear temp directory
-        root.close()?;
+/// Writes a value of `size` bytes at `offset` into a slot, keeping the other bytes of the slot.
+fn pack_value(current: U256, value: U256, offset: usize, size: usize) -> B256 {
+    let mask = if size >= 32 { U256::MAX } else { (U256::from(1) << (size * 8)) - U256::from(1) };
+    let shift = offset * 8;
+    B256::from((current & !(mask << shift)) | (value << shift))
+}
 
-        fetch_and_print_storage(provider, address, block, artifact, true).await
+/// Returns the slots to write to store a `bytes` or `string` value at `slot`.
+///
+/// Values shorter than 32 bytes are stored in the slot itself along with their length, longer ones
+/// start at the hash of the slot.
+fn encode_bytes(slot: U256, data: &[u8]) -> Vec<(U256, B256)> {
+    if data.len() < 32 {
+        let mut word = B256::right_padding_from(data);
+        word[31] = (data.len() * 2) as u8;
+        return vec![(slot, word)]
     }
+
+    let mut writes = vec![(slot, B256::from(U256::from(data.len() * 2 + 1)))];
+    let start = U256::from_be_bytes(keccak256(slot.to_be_bytes::<32>()).0);
+    writes.extend(data.chunks(32).enumerate().map(|(index, chunk)| {
+        (start.wrapping_add(U256::from(index)), B256::right_padding_from(chunk))
+    }));
+    writes
 }
 
 /// Represents the value of a storage slot `eth_getStorageAt` call.
@@ -220,11 +559,17 @@ async fn fetch_and_print_storage<P: Provider<T, AnyNetwork>, T: Transport + Clon
     address: Address,
     block: Option<BlockId>,
     artifact: &ConfigurableContractArtifact,
+    decode: Option<&DecodeOpts>,
     pretty: bool,
 ) -> Result<()> {
     if is_storage_layout_empty(&artifact.storage_layout) {
         eprintln!("Storage layout is empty.");
         Ok(())
+    } else if let Some(opts) = decode {
+        let layout = artifact.storage_layout.as_ref().unwrap();
+        let decoder = StorageDecoder::new(&provider, address, block, layout, opts);
+        print_decoded_storage(decoder.decode().await?);
+        Ok(())
     } else {
         let layout = artifact.storage_layout.as_ref().unwrap().clone();
         let values = fetch_storage_slots(provider, address, block, &layout).await?;
@@ -286,6 +631,25 @@ fn print_storage(layout: StorageLayout, values: Vec<StorageValue>, pretty: bool)
     Ok(())
 }
 
+fn print_decoded_storage(decoded: Vec<DecodedSlot>) {
+    let mut table = Table::new();
+    table.load_preset(ASCII_MARKDOWN);
+    table.set_header(["Name", "Type", "Slot", "Offset", "Bytes", "Value", "Hex Value", "Contract"]);
+    for slot in decoded {
+        table.add_row([
+            slot.name,
+            slot.label,
+            format_slot(slot.slot),
+            slot.offset.to_string(),
+            slot.number_of_bytes,
+            slot.value,
+            slot.hex_value,
+            slot.contract,
+        ]);
+    }
+    println!("{table}");
+}
+
 fn add_storage_layout_output<C: Compiler>(project: &mut Project<C>) {
     project.artifacts.additional_values.storage_layout = true;
     project.settings.update_output_selection(|selection| {
@@ -323,4 +687,148 @@ mod tests {
         let key = config.get_etherscan_api_key(None).unwrap();
         assert_eq!(key, "dummykey".to_string());
     }
+
+    #[test]
+    fn parse_mapping_keys() {
+        let args = StorageArgs::parse_from([
+            "foundry-cli",
+            "addr",
+            "--key",
+            "balances=0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045",
+            "--key",
+            "1",
+            "--key",
+            "a b=c",
+        ]);
+        assert_eq!(
+            args.keys,
+            vec![
+                MappingKey {
+                    variable: Some("balances".to_string()),
+                    key: "0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045".to_string()
+                },
+                MappingKey { variable: None, key: "1".to_string() },
+                MappingKey { variable: None, key: "a b=c".to_string() },
+            ]
+        );
+    }
+
+    fn layout() -> StorageLayout {
+        serde_json::from_value(serde_json::json!({
+            "storage": [
+                { "astId": 1, "contract": "C", "label": "owner", "offset": 0, "slot": "0", "type": "t_address" },
+                { "astId": 2, "contract": "C", "label": "paused", "offset": 20, "slot": "0", "type": "t_bool" },
+                { "astId": 3, "contract": "C", "label": "balances", "offset": 0, "slot": "1", "type": "t_mapping(t_address,t_uint256)" },
+                { "astId": 4, "contract": "C", "label": "pools", "offset": 0, "slot": "2", "type": "t_array(t_struct(Pool)1_storage)dyn_storage" },
+                { "astId": 5, "contract": "C", "label": "fees", "offset": 0, "slot": "3", "type": "t_array(t_uint64)4_storage" }
+            ],
+            "types": {
+                "t_address": { "encoding": "inplace", "label": "address", "numberOfBytes": "20" },
+                "t_bool": { "encoding": "inplace", "label": "bool", "numberOfBytes": "1" },
+                "t_uint24": { "encoding": "inplace", "label": "uint24", "numberOfBytes": "3" },
+                "t_uint64": { "encoding": "inplace", "label": "uint64", "numberOfBytes": "8" },
+                "t_uint256": { "encoding": "inplace", "label": "uint256", "numberOfBytes": "32" },
+                "t_mapping(t_address,t_uint256)": {
+                    "encoding": "mapping", "key": "t_address", "label": "mapping(address => uint256)",
+                    "numberOfBytes": "32", "value": "t_uint256"
+                },
+                "t_struct(Pool)1_storage": {
+                    "encoding": "inplace", "label": "struct C.Pool", "numberOfBytes": "64",
+                    "members": [
+                        { "astId": 6, "contract": "C", "label": "owner", "offset": 0, "slot": "0", "type": "t_address" },
+                        { "astId": 7, "contract": "C", "label": "fee", "offset": 0, "slot": "1", "type": "t_uint24" }
+                    ]
+                },
+                "t_array(t_struct(Pool)1_storage)dyn_storage": {
+                    "encoding": "dynamic_array", "label": "struct C.Pool[]", "numberOfBytes": "32",
+                    "base": "t_struct(Pool)1_storage"
+                },
+                "t_array(t_uint64)4_storage": {
+                    "encoding": "inplace", "label": "uint64[4]", "numberOfBytes": "32",
+                    "base": "t_uint64"
+                }
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn resolves_paths() {
+        let layout = layout();
+        let location = |slot: U256, offset, type_id: &str| StorageLocation {
+            slot,
+            offset,
+            type_id: type_id.to_string(),
+        };
+
+        assert_eq!(resolve_path(&layout, "paused").unwrap(), location(U256::ZERO, 20, "t_bool"));
+
+        let account = "0x0000000000000000000000000000000000000001";
+        let key = encode_mapping_key("address", account).unwrap();
+        assert_eq!(
+            resolve_path(&layout, &format!("balances[{account}]")).unwrap(),
+            location(mapping_slot(&key, U256::from(1)), 0, "t_uint256")
+        );
+
+        let data = U256::from_be_bytes(keccak256(U256::from(2).to_be_bytes::<32>()).0);
+        assert_eq!(
+            resolve_path(&layout, "pools[1].fee").unwrap(),
+            location(data + U256::from(3), 0, "t_uint24")
+        );
+        assert_eq!(
+            resolve_path(&layout, "fees[3]").unwrap(),
+            location(U256::from(3), 24, "t_uint64")
+        );
+
+        assert!(resolve_path(&layout, "fees[4]").is_err());
+        assert!(resolve_path(&layout, "owner[0]").is_err());
+        assert!(resolve_path(&layout, "pools[0].missing").is_err());
+        assert!(resolve_path(&layout, "missing").is_err());
+        assert!(resolve_path(&layout, "balances[0x01").is_err());
+    }
+
+    #[test]
+    fn encodes_storage_values() {
+        assert_eq!(encode_storage_value("bool", "true", 1).unwrap(), U256::from(1));
+        assert_eq!(encode_storage_value("uint24", "0x10", 3).unwrap(), U256::from(16));
+        assert_eq!(encode_storage_value("int8", "-1", 1).unwrap(), U256::from(0xff));
+        assert_eq!(encode_storage_value("bytes2", "0xabcd", 2).unwrap(), U256::from(0xabcd));
+        assert_eq!(encode_storage_value("bytes4", "0xabcd", 4).unwrap(), U256::from(0xabcd0000u32));
+        assert!(encode_storage_value("uint8", "256", 1).is_err());
+        assert!(encode_storage_value("int8", "128", 1).is_err());
+        assert!(encode_storage_value("int8", "-129", 1).is_err());
+        assert!(encode_storage_value("bytes1", "0xabcd", 1).is_err());
+    }
+
+    #[test]
+    fn packs_values() {
+        let current =
+            U256::from_str("0xffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff")
+                .unwrap();
+        let packed = pack_value(current, U256::ZERO, 20, 1);
+        assert_eq!(packed[11], 0);
+        assert_eq!(packed.iter().filter(|b| **b == 0xff).count(), 31);
+
+        let packed = pack_value(U256::ZERO, U256::from(7), 0, 32);
+        assert_eq!(packed, B256::from(U256::from(7)));
+    }
+
+    #[test]
+    fn encodes_bytes() {
+        let writes = encode_bytes(U256::from(5), b"abc");
+        assert_eq!(writes.len(), 1);
+        assert_eq!(writes[0].0, U256::from(5));
+        assert_eq!(&writes[0].1[..3], b"abc");
+        assert_eq!(writes[0].1[31], 6);
+
+        let data = [1u8; 40];
+        let writes = encode_bytes(U256::from(5), &data);
+        let start = U256::from_be_bytes(keccak256(U256::from(5).to_be_bytes::<32>()).0);
+        assert_eq!(writes.len(), 3);
+        assert_eq!(writes[0], (U256::from(5), B256::from(U256::from(81))));
+        assert_eq!(writes[1], (start, B256::from([1; 32])));
+        assert_eq!(writes[2].0, start + U256::from(1));
+        assert_eq!(&writes[2].1[..8], &[1; 8]);
+        assert_eq!(&writes[2].1[8..], &[0; 24]);
+    }
 }
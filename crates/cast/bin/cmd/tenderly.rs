@@ -0,0 +1,329 @@
+use alloy_primitives::{Address, Bytes, B256};
+use alloy_provider::Provider;
+use alloy_rpc_types::TransactionRequest;
+use alloy_serde::WithOtherFields;
+use clap::Parser;
+use eyre::{Context, Result};
+use foundry_cli::{opts::RpcOpts, utils};
+use foundry_common::fs;
+use foundry_config::Config;
+use serde::{Deserialize, Serialize};
+use std::{path::PathBuf, str::FromStr};
+
+/// The base URL of the Tenderly API.
+const TENDERLY_API_URL: &str = "https://api.tenderly.co/api/v1";
+
+/// The base URL of the Tenderly dashboard.
+pub const TENDERLY_DASHBOARD_URL: &str = "https://dashboard.tenderly.co";
+
+/// CLI arguments for `cast tenderly`.
+#[derive(Clone, Debug, Parser)]
+pub struct TenderlyArgs {
+    /// The hash of a published transaction, or the path of a script broadcast file, e.g.
+    /// `broadcast/Deploy.s.sol/1/dry-run/run-latest.json`.
+    ///
+    /// The transactions of a broadcast file are simulated in order, on the latest block.
+    #[arg(value_name = "TX_HASH|FILE")]
+    source: SimulationSource,
+
+    /// The access key of the Tenderly API.
+    ///
+    /// Defaults to `tenderly.access_key` in the config.
+    #[arg(long, env = "TENDERLY_ACCESS_KEY", value_name = "KEY")]
+    access_key: Option<String>,
+
+    #[command(flatten)]
+    tenderly: TenderlyOpts,
+
+    #[command(flatten)]
+    rpc: RpcOpts,
+}
+
+impl TenderlyArgs {
+    pub async fn run(self) -> Result<()> {
+        let Self { source, access_key, tenderly, rpc } = self;
+
+        let config = Config::from(&rpc);
+        let (account, project) = tenderly.resolve(&config)?;
+        let access_key = match access_key {
+            Some(access_key) => access_key,
+            None => config.tenderly.resolved_access_key()?.ok_or_else(|| {
+                eyre::eyre!(
+                    "no Tenderly access key set, pass --access-key or set `tenderly.access_key` \
+                     in the config"
+                )
+            })?,
+        };
+
+        let simulations = source.simulations(&config).await?;
+        let url = format!("{TENDERLY_API_URL}/account/{account}/project/{project}");
+        let results = if let [simulation] = simulations.as_slice() {
+            let response: SimulationResponse =
+                post(&format!("{url}/simulate"), &access_key, simulation).await?;
+            vec![response]
+        } else {
+            let bundle = serde_json::json!({ "simulations": simulations });
+            let response: BundleResponse =
+                post(&format!("{url}/simulate-bundle"), &access_key, &bundle).await?;
+            response.simulation_results
+        };
+
+        for result in results {
+            let status = if result.simulation.status { "success" } else { "reverted" };
+            println!(
+                "{status}: {TENDERLY_DASHBOARD_URL}/{account}/{project}/simulator/{}",
+                result.simulation.id
+            );
+        }
+        Ok(())
+    }
+}
+
+/// The Tenderly project to upload simulations to or link to.
+#[derive(Clone, Debug, Default, Parser)]
+#[command(next_help_heading = "Tenderly options")]
+pub struct TenderlyOpts {
+    /// The slug of the Tenderly account that owns the project.
+    ///
+    /// Defaults to `tenderly.account` in the config.
+    #[arg(long, env = "TENDERLY_ACCOUNT", value_name = "SLUG")]
+    pub account: Option<String>,
+
+    /// The slug of the Tenderly project.
+    ///
+    /// Defaults to `tenderly.project` in the config.
+    #[arg(long, env = "TENDERLY_PROJECT", value_name = "SLUG")]
+    pub project: Option<String>,
+}
+
+impl TenderlyOpts {
+    /// Returns the account and project, from the arguments or the config.
+    pub fn resolve(&self, config: &Config) -> Result<(String, String)> {
+        Ok((self.account(config)?, self.project(config)?))
+    }
+
+    fn account(&self, config: &Config) -> Result<String> {
+        self.account.clone().or_else(|| config.tenderly.account.clone()).ok_or_else(|| {
+            eyre::eyre!("no Tenderly account set, pass --account or set `tenderly.account`")
+        })
+    }
+
+    fn project(&self, config: &Config) -> Result<String> {
+        self.project.clone().or_else(|| config.tenderly.project.clone()).ok_or_else(|| {
+            eyre::eyre!("no Tenderly project set, pass --project or set `tenderly.project`")
+        })
+    }
+}
+
+/// A transaction simulated locally, either a published transaction replayed by `cast run` or the
+/// transactions of a script broadcast file.
+#[derive(Clone, Debug)]
+pub enum SimulationSource {
+    Tx(B256),
+    Sequence(PathBuf),
+}
+
+impl FromStr for SimulationSource {
+    type Err = std::convert::Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match B256::from_str(s) {
+            Ok(hash) => Self::Tx(hash),
+            Err(_) => Self::Sequence(s.into()),
+        })
+    }
+}
+
+impl SimulationSource {
+    /// Returns the simulations of the transactions of the source.
+    pub async fn simulations(&self, config: &Config) -> Result<Vec<Simulation>> {
+        match self {
+            Self::Tx(hash) => {
+                let provider = utils::get_provider(config)?;
+                let tx = provider
+                    .get_transaction_by_hash(*hash)
+                    .await?
+                    .ok_or_else(|| eyre::eyre!("tx not found: {hash}"))?;
+                let chain_id = provider.get_chain_id().await?;
+                let block_number = tx
+                    .block_number
+                    .ok_or_else(|| eyre::eyre!("tx may still be pending: {hash}"))?;
+                Ok(vec![Simulation {
+                    network_id: chain_id.to_string(),
+                    from: tx.from,
+                    to: tx.to,
+                    input: tx.input.clone(),
+                    gas: Some(tx.gas as u64),
+                    gas_price: tx.gas_price.map(|price| price.to_string()),
+                    value: tx.value.to_string(),
+                    block_number: Some(block_number),
+                    transaction_index: tx.transaction_index,
+                    save: true,
+                    save_if_fails: true,
+                }])
+            }
+            Self::Sequence(path) => {
+                let content = fs::read_to_string(path)?;
+                let sequence: SequenceFile = serde_json::from_str(&content)
+                    .wrap_err_with(|| format!("invalid broadcast file: {}", path.display()))?;
+                sequence
+                    .transactions
+                    .iter()
+                    .map(|tx| Simulation::from_request(sequence.chain, &tx.transaction))
+                    .collect()
+            }
+        }
+    }
+}
+
+/// The subset of a script broadcast file that is simulated.
+#[derive(Deserialize)]
+struct SequenceFile {
+    chain: u64,
+    transactions: Vec<SequenceTransaction>,
+}
+
+#[derive(Deserialize)]
+struct SequenceTransaction {
+    transaction: WithOtherFields<TransactionRequest>,
+}
+
+/// A simulation request of the Tenderly API.
+#[derive(Clone, Debug, Serialize)]
+pub struct Simulation {
+    pub network_id: String,
+    pub from: Address,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to: Option<Address>,
+    pub input: Bytes,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gas: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gas_price: Option<String>,
+    pub value: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub block_number: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transaction_index: Option<u64>,
+    pub save: bool,
+    pub save_if_fails: bool,
+}
+
+impl Simulation {
+    /// Creates the simulation of a transaction request on the latest block of the chain.
+    pub fn from_request(chain_id: u64, tx: &TransactionRequest) -> Result<Self> {
+        Ok(Self {
+            network_id: chain_id.to_string(),
+            from: tx.from.ok_or_else(|| eyre::eyre!("transaction has no sender"))?,
+            to: tx.to.and_then(|to| to.to().copied()),
+            input: tx.input.input().cloned().unwrap_or_default(),
+            gas: tx.gas.map(|gas| gas as u64),
+            gas_price: tx.gas_price.or(tx.max_fee_per_gas).map(|price| price.to_string()),
+            value: tx.value.unwrap_or_default().to_string(),
+            block_number: None,
+            transaction_index: None,
+            save: true,
+            save_if_fails: true,
+        })
+    }
+
+    /// Returns the link to the Tenderly simulator of the project, pre-filled with the simulation.
+    ///
+    /// Returns `None` for contract creations, which the simulator can't be pre-filled with.
+    pub fn simulator_link(&self, account: &str, project: &str) -> Option<String> {
+        let to = self.to?;
+        let mut link = format!(
+            "{TENDERLY_DASHBOARD_URL}/{account}/{project}/simulator/new?network={}\
+             &contractAddress={to}&from={}&rawFunctionInput={}&value={}",
+            self.network_id, self.from, self.input, self.value
+        );
+        if let Some(gas) = self.gas {
+            link.push_str(&format!("&gas={gas}"));
+        }
+        if let Some(gas_price) = &self.gas_price {
+            link.push_str(&format!("&gasPrice={gas_price}"));
+        }
+        if let Some(block) = self.block_number {
+            link.push_str(&format!("&block={block}"));
+        }
+        if let Some(index) = self.transaction_index {
+            link.push_str(&format!("&blockIndex={index}"));
+        }
+        Some(link)
+    }
+}
+
+#[derive(Deserialize)]
+struct SimulationResponse {
+    simulation: SimulationResult,
+}
+
+#[derive(Deserialize)]
+struct BundleResponse {
+    simulation_results: Vec<SimulationResponse>,
+}
+
+#[derive(Deserialize)]
+struct SimulationResult {
+    id: String,
+    status: bool,
+}
+
+/// Posts the body to the Tenderly API and returns the parsed response.
+async fn post<T: for<'de> Deserialize<'de>>(
+    url: &str,
+    access_key: &str,
+    body: &impl Serialize,
+) -> Result<T> {
+    let response = reqwest::Client::new()
+        .post(url)
+        .header("X-Access-Key", access_key)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .body(serde_json::to_string(body)?)
+        .send()
+        .await
+        .wrap_err("Failed to upload the simulation to Tenderly")?;
+    let status = response.status();
+    let body = response.text().await?;
+    if !status.is_success() {
+        eyre::bail!("Tenderly rejected the simulation ({status}): {body}");
+    }
+    serde_json::from_str(&body).wrap_err("invalid response from Tenderly")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::{address, TxKind, U256};
+
+    #[test]
+    fn parses_simulation_source() {
+        let hash = "0x0000000000000000000000000000000000000000000000000000000000000001";
+        assert!(matches!(hash.parse().unwrap(), SimulationSource::Tx(_)));
+        let path = "broadcast/Deploy.s.sol/1/dry-run/run-latest.json";
+        assert!(matches!(path.parse().unwrap(), SimulationSource::Sequence(_)));
+    }
+
+    #[test]
+    fn links_simulator() {
+        let tx = TransactionRequest {
+            from: Some(address!("0000000000000000000000000000000000000011")),
+            to: Some(TxKind::Call(address!("0000000000000000000000000000000000000022"))),
+            input: Bytes::from_static(&[0x12, 0x34]).into(),
+            value: Some(U256::from(5)),
+            gas: Some(21000),
+            ..Default::default()
+        };
+        let simulation = Simulation::from_request(10, &tx).unwrap();
+        assert_eq!(
+            simulation.simulator_link("acme", "token").unwrap(),
+            "https://dashboard.tenderly.co/acme/token/simulator/new?network=10\
+             &contractAddress=0x0000000000000000000000000000000000000022\
+             &from=0x0000000000000000000000000000000000000011&rawFunctionInput=0x1234&value=5\
+             &gas=21000"
+        );
+
+        let create = TransactionRequest { to: Some(TxKind::Create), ..tx };
+        assert!(Simulation::from_request(10, &create).unwrap().simulator_link("a", "b").is_none());
+    }
+}
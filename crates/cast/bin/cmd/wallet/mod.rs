@@ -295,7 +295,11 @@ impl WalletSubcommands {
                 }
             }
             Self::Vanity(cmd) => {
-                cmd.run()?;
+                if cmd.is_create2() {
+                    cmd.run_create2()?;
+                } else {
+                    cmd.run()?;
+                }
             }
             Self::Address { wallet, private_key_override } => {
                 let wallet = private_key_override
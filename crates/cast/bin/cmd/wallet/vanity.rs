@@ -1,14 +1,17 @@
-use alloy_primitives::{hex, Address};
+use alloy_primitives::{hex, keccak256, Address, B256, U256};
 use alloy_signer::{k256::ecdsa::SigningKey, utils::secret_key_to_address};
 use alloy_signer_local::PrivateKeySigner;
 use clap::Parser;
-use eyre::Result;
+use eyre::{Result, WrapErr};
+use foundry_common::create2::SaltMiner;
 use itertools::Either;
+use rand::RngCore;
 use rayon::iter::{self, ParallelIterator};
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 use std::{
     fs,
+    num::NonZeroUsize,
     path::{Path, PathBuf},
     time::Instant,
 };
@@ -33,6 +36,31 @@ pub struct VanityArgs {
     #[arg(long)]
     pub nonce: Option<u64>,
 
+    /// Mine a `CREATE2` salt for a contract deployed by this address, instead of generating a
+    /// keypair.
+    ///
+    /// The patterns are matched against the address of the contract created with the salt.
+    #[arg(
+        long,
+        value_name = "ADDRESS",
+        requires = "init_code_source",
+        conflicts_with_all = ["nonce", "save_path"]
+    )]
+    pub deployer: Option<Address>,
+
+    /// Init code of the contract to be deployed with `CREATE2`.
+    #[arg(long, value_name = "HEX", requires = "deployer", group = "init_code_source")]
+    pub init_code: Option<String>,
+
+    /// Init code hash of the contract to be deployed with `CREATE2`.
+    #[arg(long, value_name = "HASH", requires = "deployer", group = "init_code_source")]
+    pub init_code_hash: Option<B256>,
+
+    /// Number of threads to use when mining a `CREATE2` salt. Defaults to and caps at the number
+    /// of logical cores.
+    #[arg(long, short, requires = "deployer")]
+    pub jobs: Option<NonZeroUsize>,
+
     /// Path to save the generated vanity contract address to.
     ///
     /// If provided, the generated vanity addresses will appended to a JSON array in the specified
@@ -67,76 +95,101 @@ impl WalletData {
     }
 }
 
-impl VanityArgs {
-    pub fn run(self) -> Result<PrivateKeySigner> {
-        let Self { starts_with, ends_with, nonce, save_path } = self;
+/// The parsed `--starts-with` and `--ends-with` patterns.
+struct Patterns {
+    left_exact_hex: Option<Vec<u8>>,
+    left_regex: Option<Regex>,
+    right_exact_hex: Option<Vec<u8>>,
+    right_regex: Option<Regex>,
+}
 
-        let mut left_exact_hex = None;
-        let mut left_regex = None;
+impl Patterns {
+    fn parse(starts_with: Option<String>, ends_with: Option<String>) -> Result<Self> {
+        let mut patterns = Self {
+            left_exact_hex: None,
+            left_regex: None,
+            right_exact_hex: None,
+            right_regex: None,
+        };
         if let Some(prefix) = starts_with {
             match parse_pattern(&prefix, true)? {
-                Either::Left(left) => left_exact_hex = Some(left),
-                Either::Right(re) => left_regex = Some(re),
+                Either::Left(left) => patterns.left_exact_hex = Some(left),
+                Either::Right(re) => patterns.left_regex = Some(re),
             }
         }
-
-        let mut right_exact_hex = None;
-        let mut right_regex = None;
         if let Some(suffix) = ends_with {
             match parse_pattern(&suffix, false)? {
-                Either::Left(right) => right_exact_hex = Some(right),
-                Either::Right(re) => right_regex = Some(re),
+                Either::Left(right) => patterns.right_exact_hex = Some(right),
+                Either::Right(re) => patterns.right_regex = Some(re),
             }
         }
+        Ok(patterns)
+    }
+}
 
-        macro_rules! find_vanity {
-            ($m:ident, $nonce: ident) => {
-                if let Some(nonce) = $nonce {
-                    find_vanity_address_with_nonce($m, nonce)
-                } else {
-                    find_vanity_address($m)
-                }
-            };
-        }
-
-        println!("Starting to generate vanity address...");
-        let timer = Instant::now();
-
-        let wallet = match (left_exact_hex, left_regex, right_exact_hex, right_regex) {
+/// Builds the most specific [VanityMatcher] for the given [Patterns], and evaluates `$body` with
+/// it bound to `$matcher`.
+macro_rules! with_matcher {
+    ($patterns:expr, $matcher:ident => $body:expr) => {{
+        let Patterns { left_exact_hex, left_regex, right_exact_hex, right_regex } = $patterns;
+        match (left_exact_hex, left_regex, right_exact_hex, right_regex) {
             (Some(left), _, Some(right), _) => {
-                let matcher = HexMatcher { left, right };
-                find_vanity!(matcher, nonce)
+                let $matcher = HexMatcher { left, right };
+                $body
             }
             (Some(left), _, _, Some(right)) => {
-                let matcher = LeftExactRightRegexMatcher { left, right };
-                find_vanity!(matcher, nonce)
+                let $matcher = LeftExactRightRegexMatcher { left, right };
+                $body
             }
             (_, Some(left), _, Some(right)) => {
-                let matcher = RegexMatcher { left, right };
-                find_vanity!(matcher, nonce)
+                let $matcher = RegexMatcher { left, right };
+                $body
             }
             (_, Some(left), Some(right), _) => {
-                let matcher = LeftRegexRightExactMatcher { left, right };
-                find_vanity!(matcher, nonce)
+                let $matcher = LeftRegexRightExactMatcher { left, right };
+                $body
             }
             (Some(left), None, None, None) => {
-                let matcher = LeftHexMatcher { left };
-                find_vanity!(matcher, nonce)
+                let $matcher = LeftHexMatcher { left };
+                $body
             }
             (None, None, Some(right), None) => {
-                let matcher = RightHexMatcher { right };
-                find_vanity!(matcher, nonce)
+                let $matcher = RightHexMatcher { right };
+                $body
             }
             (None, Some(re), None, None) => {
-                let matcher = SingleRegexMatcher { re };
-                find_vanity!(matcher, nonce)
+                let $matcher = SingleRegexMatcher { re };
+                $body
             }
             (None, None, None, Some(re)) => {
-                let matcher = SingleRegexMatcher { re };
-                find_vanity!(matcher, nonce)
+                let $matcher = SingleRegexMatcher { re };
+                $body
             }
             _ => unreachable!(),
         }
+    }};
+}
+
+impl VanityArgs {
+    /// Returns `true` if a `CREATE2` salt should be mined instead of a keypair.
+    pub fn is_create2(&self) -> bool {
+        self.deployer.is_some()
+    }
+
+    pub fn run(self) -> Result<PrivateKeySigner> {
+        let Self { starts_with, ends_with, nonce, save_path, .. } = self;
+        let patterns = Patterns::parse(starts_with, ends_with)?;
+
+        println!("Starting to generate vanity address...");
+        let timer = Instant::now();
+
+        let wallet = with_matcher!(patterns, matcher => {
+            if let Some(nonce) = nonce {
+                find_vanity_address_with_nonce(matcher, nonce)
+            } else {
+                find_vanity_address(matcher)
+            }
+        })
         .expect("failed to generate vanity wallet");
 
         // If a save path is provided, save the generated vanity wallet to the specified path.
@@ -159,6 +212,47 @@ impl VanityArgs {
 
         Ok(wallet)
     }
+
+    /// Mines a `CREATE2` salt for `--deployer`, returning the salt and the contract address.
+    pub fn run_create2(self) -> Result<(B256, Address)> {
+        let Self { starts_with, ends_with, deployer, init_code, init_code_hash, jobs, .. } = self;
+        let patterns = Patterns::parse(starts_with, ends_with)?;
+        let deployer = deployer.ok_or_else(|| eyre::eyre!("--deployer is required"))?;
+        let init_code_hash = match (init_code_hash, init_code) {
+            (Some(hash), _) => hash,
+            (None, Some(init_code)) => {
+                keccak256(hex::decode(init_code).wrap_err("invalid init code provided")?)
+            }
+            (None, None) => eyre::bail!("either --init-code or --init-code-hash is required"),
+        };
+
+        let mut salt = B256::ZERO;
+        rand::thread_rng().fill_bytes(salt.as_mut_slice());
+        let mut miner = SaltMiner::new(deployer, init_code_hash).salt(salt).progress(true);
+        if let Some(jobs) = jobs {
+            miner = miner.threads(jobs.get());
+        }
+
+        println!(
+            "Starting to mine vanity contract address salt with {} threads...",
+            miner.num_threads()
+        );
+        let timer = Instant::now();
+
+        let (address, salt) = with_matcher!(patterns, matcher => {
+            miner.mine(|address| matcher.is_match(address))
+        })
+        .ok_or_else(|| eyre::eyre!("no salt found for the given patterns"))?;
+
+        println!(
+            "Successfully found vanity contract address in {:.3} seconds.\nAddress: {}\nSalt: {salt} ({})",
+            timer.elapsed().as_secs_f64(),
+            address.to_checksum(None),
+            U256::from_be_bytes(salt.0),
+        );
+
+        Ok((salt, address))
+    }
 }
 
 /// Saves the specified `wallet` to a 'vanity_addresses.json' file at the given `save_path`.
@@ -368,6 +462,45 @@ mod tests {
         assert!(addr.ends_with("00"));
     }
 
+    #[test]
+    fn find_create2_vanity_salt() {
+        let deployer = "0x4e59b44847b379578588920ca78fbf26c0b4956c";
+        let init_code_hash = "0xbc36789e7a1e281436464229828f817d6612f7b477d66591ff96a9e064bcc98a";
+        let args: VanityArgs = VanityArgs::parse_from([
+            "foundry-cli",
+            "--starts-with",
+            "00",
+            "--ends-with",
+            "f",
+            "--deployer",
+            deployer,
+            "--init-code-hash",
+            init_code_hash,
+            "--jobs",
+            "2",
+        ]);
+        assert!(args.is_create2());
+        let (salt, address) = args.run_create2().unwrap();
+        let addr = format!("{address:x}");
+        assert!(addr.starts_with("00"));
+        assert!(addr.ends_with('f'));
+        let deployer: Address = deployer.parse().unwrap();
+        assert_eq!(address, deployer.create2(salt, init_code_hash.parse::<B256>().unwrap()));
+    }
+
+    #[test]
+    fn create2_requires_init_code() {
+        let deployer = "0x4e59b44847b379578588920ca78fbf26c0b4956c";
+        let res = VanityArgs::try_parse_from([
+            "foundry-cli",
+            "--starts-with",
+            "00",
+            "--deployer",
+            deployer,
+        ]);
+        assert!(res.is_err());
+    }
+
     #[test]
     fn save_path() {
         let tmp = tempfile::NamedTempFile::new().unwrap();
@@ -17,7 +17,7 @@ use foundry_common::{
     selectors::{
         decode_calldata, decode_event_topic, decode_function_selector, decode_selectors,
         import_selectors, parse_signatures, pretty_calldata, ParsedSignatures, SelectorImportData,
-        SelectorType,
+        SelectorType, SignatureDatabase,
     },
 };
 use foundry_config::Config;
@@ -25,6 +25,7 @@ use std::time::Instant;
 
 pub mod cmd;
 pub mod opts;
+pub mod output;
 pub mod tx;
 
 use opts::{Cast as Opts, CastSubcommand, ToBaseArgs};
@@ -168,7 +169,19 @@ async fn main() -> Result<()> {
             let tokens = format_tokens(&tokens);
             tokens.for_each(|t| println!("{t}"));
         }
-        CastSubcommand::AbiEncode { sig, packed, args } => {
+        CastSubcommand::AbiEncode { sig, packed, input_file: Some(path), .. } => {
+            let args = fs::read_json_file::<serde_json::Value>(&path)?;
+            println!("{}", SimpleCast::abi_encode_json(&sig, &args, packed)?);
+        }
+        CastSubcommand::AbiEncode { sig, packed, decode: true, args, .. } => {
+            let [data] = args.as_slice() else {
+                eyre::bail!("expected the encoded data as the only argument")
+            };
+            let tokens = SimpleCast::abi_decode_args(&sig, data, packed)?;
+            let tokens = format_tokens(&tokens);
+            tokens.for_each(|t| println!("{t}"));
+        }
+        CastSubcommand::AbiEncode { sig, packed, args, .. } => {
             if !packed {
                 println!("{}", SimpleCast::abi_encode(&sig, &args)?);
             } else {
@@ -243,15 +256,17 @@ async fn main() -> Result<()> {
                 Cast::new(provider).base_fee(block.unwrap_or(BlockId::Number(Latest))).await?
             );
         }
-        CastSubcommand::Block { block, full, field, json, rpc } => {
+        CastSubcommand::Block { block, full, field, output, rpc } => {
             let config = Config::from(&rpc);
             let provider = utils::get_provider(&config)?;
-            println!(
-                "{}",
-                Cast::new(provider)
-                    .block(block.unwrap_or(BlockId::Number(Latest)), full, field, json)
-                    .await?
-            );
+            let block = block.unwrap_or(BlockId::Number(Latest));
+            let cast = Cast::new(provider);
+            if field.is_none() && output.is_structured() {
+                let block = cast.block(block, full, None, true).await?;
+                println!("{}", output.render(&serde_json::from_str(&block)?)?);
+            } else {
+                println!("{}", cast.block(block, full, field, output.json).await?);
+            }
         }
         CastSubcommand::BlockNumber { rpc, block } => {
             let config = Config::from(&rpc);
@@ -295,6 +310,8 @@ async fn main() -> Result<()> {
             let who = who.resolve(&provider).await?;
             println!("{}", Cast::new(provider).codesize(who, block).await?);
         }
+        CastSubcommand::CreationCode(cmd) => cmd.run().await?,
+        CastSubcommand::ConstructorArgs(cmd) => cmd.run().await?,
         CastSubcommand::ComputeAddress { address, nonce, rpc } => {
             let config = Config::from(&rpc);
             let provider = utils::get_provider(&config)?;
@@ -330,6 +347,11 @@ async fn main() -> Result<()> {
             }
         }
         CastSubcommand::FindBlock(cmd) => cmd.run().await?,
+        CastSubcommand::History(cmd) => cmd.run().await?,
+        CastSubcommand::SigVerify(cmd) => cmd.run().await?,
+        CastSubcommand::Bundle { command } => command.run().await?,
+        CastSubcommand::Pool(cmd) => cmd.run().await?,
+        CastSubcommand::Gov { command } => command.run().await?,
         CastSubcommand::GasPrice { rpc } => {
             let config = Config::from(&rpc);
             let provider = utils::get_provider(&config)?;
@@ -372,10 +394,13 @@ async fn main() -> Result<()> {
             println!("{}", serde_json::to_string(&value)?);
         }
         CastSubcommand::Rpc(cmd) => cmd.run().await?,
+        CastSubcommand::MockRpc(cmd) => cmd.run().await?,
         CastSubcommand::Storage(cmd) => cmd.run().await?,
+        CastSubcommand::StorageWrite(cmd) => cmd.run().await?,
 
         // Calls & transactions
         CastSubcommand::Call(cmd) => cmd.run().await?,
+        CastSubcommand::CompareCall(cmd) => cmd.run().await?,
         CastSubcommand::Estimate(cmd) => cmd.run().await?,
         CastSubcommand::MakeTx(cmd) => cmd.run().await?,
         CastSubcommand::PublishTx { raw_tx, cast_async, rpc } => {
@@ -392,32 +417,44 @@ async fn main() -> Result<()> {
                 println!("{}", serde_json::json!(receipt));
             }
         }
-        CastSubcommand::Receipt { tx_hash, field, json, cast_async, confirmations, rpc } => {
+        CastSubcommand::Receipt { tx_hash, field, output, cast_async, confirmations, rpc } => {
             let config = Config::from(&rpc);
             let provider = utils::get_provider(&config)?;
-            println!(
-                "{}",
-                Cast::new(provider)
-                    .receipt(tx_hash, field, confirmations, cast_async, json)
-                    .await?
-            );
+            let cast = Cast::new(provider);
+            if field.is_none() && output.is_structured() {
+                let receipt = cast.receipt(tx_hash, None, confirmations, cast_async, true).await?;
+                println!("{}", output.render(&serde_json::from_str(&receipt)?)?);
+            } else {
+                let receipt =
+                    cast.receipt(tx_hash, field, confirmations, cast_async, output.json).await?;
+                println!("{receipt}");
+            }
         }
         CastSubcommand::Run(cmd) => cmd.run().await?,
+        CastSubcommand::DecodeTrace(cmd) => cmd.run().await?,
+        CastSubcommand::Tenderly(cmd) => cmd.run().await?,
+        CastSubcommand::Explorer(cmd) => cmd.run().await?,
         CastSubcommand::SendTx(cmd) => cmd.run().await?,
-        CastSubcommand::Tx { tx_hash, field, raw, json, rpc } => {
+        CastSubcommand::Tx { tx_hash, field, raw, output, rpc } => {
             let config = Config::from(&rpc);
             let provider = utils::get_provider(&config)?;
 
             // Can use either --raw or specify raw as a field
             let raw = raw || field.as_ref().is_some_and(|f| f == "raw");
 
-            println!("{}", Cast::new(&provider).transaction(tx_hash, field, raw, json).await?)
+            let cast = Cast::new(&provider);
+            if field.is_none() && !raw && output.is_structured() {
+                let tx = cast.transaction(tx_hash, None, false, true).await?;
+                println!("{}", output.render(&serde_json::from_str(&tx)?)?);
+            } else {
+                println!("{}", cast.transaction(tx_hash, field, raw, output.json).await?)
+            }
         }
 
         // 4Byte
-        CastSubcommand::FourByte { selector } => {
+        CastSubcommand::FourByte { selector, offline } => {
             let selector = stdin::unwrap_line(selector)?;
-            let sigs = decode_function_selector(&selector).await?;
+            let sigs = lookup_signatures(&selector, SelectorType::Function, offline).await?;
             if sigs.is_empty() {
                 eyre::bail!("No matching function signatures found for selector `{selector}`");
             }
@@ -425,9 +462,20 @@ async fn main() -> Result<()> {
                 println!("{sig}");
             }
         }
-        CastSubcommand::FourByteDecode { calldata } => {
+        CastSubcommand::FourByteDecode { calldata, offline } => {
             let calldata = stdin::unwrap_line(calldata)?;
-            let sigs = decode_calldata(&calldata).await?;
+            let selector = calldata.strip_prefix("0x").unwrap_or(&calldata).get(..8).unwrap_or("");
+            let config = Config::load();
+            let local = SignatureDatabase::load(&config)
+                .functions(selector)
+                .into_iter()
+                .filter(|sig| SimpleCast::calldata_decode(sig, &calldata, true).is_ok())
+                .collect::<Vec<_>>();
+            let sigs = if !local.is_empty() || offline || config.offline {
+                local
+            } else {
+                decode_calldata(&calldata).await?
+            };
             sigs.iter().enumerate().for_each(|(i, sig)| println!("{}) \"{sig}\"", i + 1));
 
             let sig = match sigs.len() {
@@ -444,9 +492,9 @@ async fn main() -> Result<()> {
                 println!("{token}");
             }
         }
-        CastSubcommand::FourByteEvent { topic } => {
+        CastSubcommand::FourByteEvent { topic, offline } => {
             let topic = stdin::unwrap_line(topic)?;
-            let sigs = decode_event_topic(&topic).await?;
+            let sigs = lookup_signatures(&topic, SelectorType::Event, offline).await?;
             if sigs.is_empty() {
                 eyre::bail!("No matching event signatures found for topic `{topic}`");
             }
@@ -570,3 +618,25 @@ async fn main() -> Result<()> {
     };
     Ok(())
 }
+
+/// Looks up the signatures of a selector in the signature database of the current project, then
+/// on OpenChain unless offline.
+async fn lookup_signatures(
+    selector: &str,
+    selector_type: SelectorType,
+    offline: bool,
+) -> Result<Vec<String>> {
+    let config = Config::load();
+    let db = SignatureDatabase::load(&config);
+    let local = match selector_type {
+        SelectorType::Function => db.functions(selector),
+        SelectorType::Event => db.events(selector),
+    };
+    if !local.is_empty() || offline || config.offline {
+        return Ok(local)
+    }
+    match selector_type {
+        SelectorType::Function => decode_function_selector(selector).await,
+        SelectorType::Event => decode_event_topic(selector).await,
+    }
+}
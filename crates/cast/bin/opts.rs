@@ -1,8 +1,33 @@
-use crate::cmd::{
-    access_list::AccessListArgs, bind::BindArgs, call::CallArgs, create2::Create2Args,
-    estimate::EstimateArgs, find_block::FindBlockArgs, interface::InterfaceArgs, logs::LogsArgs,
-    mktx::MakeTxArgs, rpc::RpcArgs, run::RunArgs, send::SendTxArgs, storage::StorageArgs,
-    wallet::WalletSubcommands,
+use crate::{
+    cmd::{
+        access_list::AccessListArgs,
+        bind::BindArgs,
+        bundle::BundleSubcommands,
+        call::CallArgs,
+        compare_call::CompareCallArgs,
+        constructor_args::ConstructorArgsArgs,
+        create2::Create2Args,
+        creation_code::CreationCodeArgs,
+        decode_trace::DecodeTraceArgs,
+        estimate::EstimateArgs,
+        explorer::ExplorerArgs,
+        find_block::FindBlockArgs,
+        gov::GovSubcommands,
+        history::HistoryArgs,
+        interface::InterfaceArgs,
+        logs::LogsArgs,
+        mktx::MakeTxArgs,
+        mock_rpc::MockRpcArgs,
+        pool::PoolArgs,
+        rpc::RpcArgs,
+        run::RunArgs,
+        send::SendTxArgs,
+        sig_verify::SigVerifyArgs,
+        storage::{StorageArgs, StorageWriteArgs},
+        tenderly::TenderlyArgs,
+        wallet::WalletSubcommands,
+    },
+    output::OutputArgs,
 };
 use alloy_primitives::{Address, B256, U256};
 use alloy_rpc_types::BlockId;
@@ -319,9 +344,8 @@ pub enum CastSubcommand {
         #[arg(long, env = "CAST_FULL_BLOCK")]
         full: bool,
 
-        /// Print the block as JSON.
-        #[arg(long, short, help_heading = "Display options")]
-        json: bool,
+        #[command(flatten)]
+        output: OutputArgs,
 
         #[command(flatten)]
         rpc: RpcOpts,
@@ -340,6 +364,10 @@ pub enum CastSubcommand {
     #[command(visible_alias = "c")]
     Call(CallArgs),
 
+    /// Run the same call against two endpoints or two blocks and compare the results.
+    #[command(visible_alias = "cmpc")]
+    CompareCall(CompareCallArgs),
+
     /// ABI-encode a function with arguments.
     #[command(name = "calldata", visible_alias = "cd")]
     CalldataEncode {
@@ -414,9 +442,8 @@ pub enum CastSubcommand {
         #[arg(long, conflicts_with = "field")]
         raw: bool,
 
-        /// Print as JSON.
-        #[arg(long, short, help_heading = "Display options")]
-        json: bool,
+        #[command(flatten)]
+        output: OutputArgs,
 
         #[command(flatten)]
         rpc: RpcOpts,
@@ -439,9 +466,8 @@ pub enum CastSubcommand {
         #[arg(id = "async", long = "async", env = "CAST_ASYNC", alias = "cast-async")]
         cast_async: bool,
 
-        /// Print as JSON.
-        #[arg(long, short, help_heading = "Display options")]
-        json: bool,
+        #[command(flatten)]
+        output: OutputArgs,
 
         #[command(flatten)]
         rpc: RpcOpts,
@@ -501,15 +527,35 @@ pub enum CastSubcommand {
     },
 
     /// ABI encode the given function argument, excluding the selector.
+    ///
+    /// The arguments can also be read from a JSON file, and encoded data can be decoded back into
+    /// the arguments with `--decode`.
     #[command(visible_alias = "ae")]
     AbiEncode {
-        /// The function signature.
+        /// The function signature, or a tuple of types, e.g. `(address,uint256[])`.
         sig: String,
 
         /// Whether to use packed encoding.
         #[arg(long)]
         packed: bool,
 
+        /// Read the arguments from a JSON file instead: an array of the arguments, or an object of
+        /// the arguments by parameter name.
+        ///
+        /// Arrays are given as JSON arrays, and structs as JSON arrays or as objects by field
+        /// name, e.g. `[{"to": "0x...", "amounts": [1, 2]}]` for `f((address to, uint256[]
+        /// amounts))`.
+        #[arg(long, value_name = "PATH", conflicts_with_all = ["args", "decode"])]
+        input_file: Option<PathBuf>,
+
+        /// Decode the encoded data given as the only argument into the arguments of the
+        /// signature instead.
+        ///
+        /// With `--packed`, only the last argument can be of a dynamic type, since the packed
+        /// encoding doesn't include the length of dynamic values.
+        #[arg(long)]
+        decode: bool,
+
         /// The arguments of the function.
         #[arg(allow_hyphen_values = true)]
         args: Vec<String>,
@@ -572,26 +618,40 @@ pub enum CastSubcommand {
         rpc: RpcOpts,
     },
 
-    /// Get the function signatures for the given selector from https://openchain.xyz.
+    /// Get the function signatures for the given selector from the local signature database or
+    /// https://openchain.xyz.
     #[command(name = "4byte", visible_aliases = &["4", "4b"])]
     FourByte {
         /// The function selector.
         selector: Option<String>,
+
+        /// Only look up the selector in the local signature database.
+        #[arg(long)]
+        offline: bool,
     },
 
-    /// Decode ABI-encoded calldata using https://openchain.xyz.
+    /// Decode ABI-encoded calldata using the local signature database or https://openchain.xyz.
     #[command(name = "4byte-decode", visible_aliases = &["4d", "4bd"])]
     FourByteDecode {
         /// The ABI-encoded calldata.
         calldata: Option<String>,
+
+        /// Only look up the selector in the local signature database.
+        #[arg(long)]
+        offline: bool,
     },
 
-    /// Get the event signature for a given topic 0 from https://openchain.xyz.
+    /// Get the event signature for a given topic 0 from the local signature database or
+    /// https://openchain.xyz.
     #[command(name = "4byte-event", visible_aliases = &["4e", "4be", "topic0-event", "t0e"])]
     FourByteEvent {
         /// Topic 0
         #[arg(value_name = "TOPIC_0")]
         topic: Option<String>,
+
+        /// Only look up the topic in the local signature database.
+        #[arg(long)]
+        offline: bool,
     },
 
     /// Upload the given signatures to https://openchain.xyz.
@@ -711,6 +771,14 @@ pub enum CastSubcommand {
         rpc: RpcOpts,
     },
 
+    /// Get the creation code of a contract from its creation transaction.
+    #[command(visible_alias = "cc")]
+    CreationCode(CreationCodeArgs),
+
+    /// Decode the constructor arguments of a contract from its creation transaction.
+    #[command(visible_alias = "cra")]
+    ConstructorArgs(ConstructorArgsArgs),
+
     /// Get the current gas price.
     #[command(visible_alias = "g")]
     GasPrice {
@@ -764,6 +832,11 @@ pub enum CastSubcommand {
     #[command(visible_alias = "st")]
     Storage(StorageArgs),
 
+    /// Write a value to a contract's storage on Anvil or a Hardhat node, computing its slot from
+    /// the storage layout.
+    #[command(visible_alias = "stw")]
+    StorageWrite(StorageWriteArgs),
+
     /// Generate a storage proof for a given storage slot.
     #[command(visible_alias = "pr")]
     Proof {
@@ -855,6 +928,32 @@ pub enum CastSubcommand {
     #[command(visible_alias = "f")]
     FindBlock(FindBlockArgs),
 
+    /// Sample the balance, a storage slot or the output of a call over a range of blocks.
+    History(HistoryArgs),
+
+    /// Verify a signature against a message, typed data or hash.
+    ///
+    /// Signatures of contract accounts are checked with ERC-1271 if an RPC URL is provided.
+    #[command(visible_alias = "sv")]
+    SigVerify(SigVerifyArgs),
+
+    /// Simulate bundles of transactions on top of a block.
+    #[command(visible_alias = "bd")]
+    Bundle {
+        #[command(subcommand)]
+        command: BundleSubcommands,
+    },
+
+    /// Inspect the pending transactions of the node, decode their calldata and simulate them on
+    /// top of the latest block.
+    Pool(PoolArgs),
+
+    /// Decode and simulate governance proposals, timelock operations and Safe transactions.
+    Gov {
+        #[command(subcommand)]
+        command: GovSubcommands,
+    },
+
     /// Generate shell completions script.
     #[command(visible_alias = "com")]
     Completions {
@@ -870,10 +969,25 @@ pub enum CastSubcommand {
     #[command(visible_alias = "r")]
     Run(RunArgs),
 
+    /// Decodes and prints a geth `callTracer` or parity trace from a file, without executing it.
+    DecodeTrace(DecodeTraceArgs),
+
+    /// Uploads a published transaction, or the transactions of a script broadcast file, as
+    /// simulations to Tenderly and prints their links.
+    Tenderly(TenderlyArgs),
+
+    /// Prints the block explorer link of a published transaction, and links to the Tenderly
+    /// simulator pre-filled with it or with the transactions of a script broadcast file.
+    Explorer(ExplorerArgs),
+
     /// Perform a raw JSON-RPC request.
     #[command(visible_alias = "rp")]
     Rpc(RpcArgs),
 
+    /// Serve canned JSON-RPC responses from a spec and record the requests, to test off-chain
+    /// components, e.g. ERC-4337 bundler clients, without a node.
+    MockRpc(MockRpcArgs),
+
     /// Formats a string into bytes32 encoding.
     #[command(name = "format-bytes32-string", visible_aliases = &["--format-bytes32-string"])]
     FormatBytes32String {
@@ -0,0 +1,263 @@
+//! Structured output of the read subcommands.
+//!
+//! Commands that support it flatten [`OutputArgs`] and, when
+//! [structured output](OutputArgs::is_structured) is requested, hand their result as JSON to
+//! [`OutputArgs::render`] instead of printing it themselves.
+
+use clap::{Parser, ValueEnum};
+use comfy_table::{presets::ASCII_MARKDOWN, Table};
+use eyre::Result;
+use serde_json::{Map, Value};
+
+/// The format to print the output of a command in.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Human-readable text.
+    #[default]
+    Text,
+    /// A markdown table.
+    Table,
+    Json,
+    Yaml,
+    Csv,
+}
+
+/// CLI arguments that control the output of a command.
+#[derive(Clone, Debug, Default, Parser)]
+#[command(next_help_heading = "Display options")]
+pub struct OutputArgs {
+    /// The format to print the output in.
+    #[arg(long, value_enum, default_value_t, value_name = "FORMAT")]
+    pub format: OutputFormat,
+
+    /// Print as JSON. Alias for `--format json`.
+    #[arg(long, short, conflicts_with = "format")]
+    pub json: bool,
+
+    /// Only print the given comma-separated fields, e.g. `--fields hash,from,to,value`.
+    #[arg(long, value_delimiter = ',', value_name = "FIELDS")]
+    pub fields: Vec<String>,
+
+    /// Only print the raw values, one record per line with the fields separated by tabs.
+    #[arg(long, short)]
+    pub quiet: bool,
+}
+
+impl OutputArgs {
+    /// Returns the requested format.
+    pub fn format(&self) -> OutputFormat {
+        if self.json {
+            OutputFormat::Json
+        } else {
+            self.format
+        }
+    }
+
+    /// Returns whether anything but the default human-readable output was requested.
+    pub fn is_structured(&self) -> bool {
+        self.format() != OutputFormat::Text || !self.fields.is_empty() || self.quiet
+    }
+
+    /// Renders the given value, either a single record or an array of records.
+    pub fn render(&self, value: &Value) -> Result<String> {
+        let records = match value {
+            Value::Object(_) => std::slice::from_ref(value),
+            Value::Array(values) if values.iter().all(Value::is_object) => values.as_slice(),
+            _ => {
+                if !self.fields.is_empty() {
+                    eyre::bail!("cannot select fields of `{value}`");
+                }
+                return Ok(match self.format() {
+                    OutputFormat::Json => value.to_string(),
+                    OutputFormat::Yaml => yaml(value)?,
+                    _ => cell(value),
+                })
+            }
+        };
+        let single = value.is_object();
+        let columns = self.columns(records)?;
+        let rows = records
+            .iter()
+            .map(|record| {
+                columns.iter().map(|column| record.get(column).unwrap_or(&Value::Null)).collect()
+            })
+            .collect::<Vec<Vec<&Value>>>();
+
+        if self.quiet {
+            let lines = rows
+                .iter()
+                .map(|row| row.iter().map(|value| cell(value)).collect::<Vec<_>>().join("\t"));
+            return Ok(lines.collect::<Vec<_>>().join("\n"))
+        }
+
+        let selected = || {
+            let mut records = rows.iter().map(|row| {
+                let record = columns.iter().cloned().zip(row.iter().map(|&value| value.clone()));
+                Value::Object(record.collect::<Map<_, _>>())
+            });
+            if single {
+                records.next().unwrap_or_default()
+            } else {
+                Value::Array(records.collect())
+            }
+        };
+
+        Ok(match self.format() {
+            OutputFormat::Text => {
+                let width = columns.iter().map(String::len).max().unwrap_or_default();
+                rows.iter()
+                    .map(|row| {
+                        let lines = columns
+                            .iter()
+                            .zip(row)
+                            .map(|(column, value)| format!("{column:<width$} {}", cell(value)));
+                        lines.collect::<Vec<_>>().join("\n")
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n\n")
+            }
+            OutputFormat::Table => {
+                let mut table = Table::new();
+                table.load_preset(ASCII_MARKDOWN);
+                if single {
+                    table.set_header(["Field", "Value"]);
+                    for (column, value) in columns.iter().zip(&rows[0]) {
+                        table.add_row([column.clone(), cell(value)]);
+                    }
+                } else {
+                    table.set_header(&columns);
+                    for row in &rows {
+                        table.add_row(row.iter().map(|value| cell(value)));
+                    }
+                }
+                table.to_string()
+            }
+            OutputFormat::Json if self.fields.is_empty() => value.to_string(),
+            OutputFormat::Json => selected().to_string(),
+            OutputFormat::Yaml => yaml(&selected())?,
+            OutputFormat::Csv => {
+                let mut lines = vec![columns.iter().map(|column| csv(column)).collect::<Vec<_>>()];
+                lines.extend(rows.iter().map(|row| row.iter().map(|v| csv(&cell(v))).collect()));
+                lines.iter().map(|line| line.join(",")).collect::<Vec<_>>().join("\n")
+            }
+        })
+    }
+
+    /// Returns the fields to print, in order.
+    ///
+    /// These are the requested fields, or all fields of the records in the order they first
+    /// appear.
+    fn columns(&self, records: &[Value]) -> Result<Vec<String>> {
+        let mut available = Vec::<&String>::new();
+        for record in records {
+            for key in record.as_object().into_iter().flat_map(Map::keys) {
+                if !available.contains(&key) {
+                    available.push(key);
+                }
+            }
+        }
+        if self.fields.is_empty() {
+            return Ok(available.into_iter().cloned().collect())
+        }
+        // An empty array has no fields to check against.
+        if !records.is_empty() {
+            if let Some(field) = self.fields.iter().find(|field| !available.contains(field)) {
+                let available = available.iter().map(|key| key.as_str()).collect::<Vec<_>>();
+                eyre::bail!("unknown field `{field}`, expected one of: {}", available.join(", "));
+            }
+        }
+        Ok(self.fields.clone())
+    }
+}
+
+/// Renders a value as a single table cell: strings without quotes, nested values as JSON.
+fn cell(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        value => value.to_string(),
+    }
+}
+
+/// Quotes a CSV field if needed.
+fn csv(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Renders a value as YAML.
+fn yaml(value: &Value) -> Result<String> {
+    let yaml = serde_yaml::to_string(value)?;
+    Ok(yaml.trim_end().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn args(format: OutputFormat, fields: &[&str], quiet: bool) -> OutputArgs {
+        OutputArgs {
+            format,
+            json: false,
+            fields: fields.iter().map(|field| field.to_string()).collect(),
+            quiet,
+        }
+    }
+
+    fn txs() -> Value {
+        json!([
+            {"hash": "0x01", "from": "0xaa", "to": null, "value": "0x0", "input": "a,b"},
+            {"hash": "0x02", "from": "0xbb", "to": "0xcc", "value": "0x1", "input": "\"c\""},
+        ])
+    }
+
+    #[test]
+    fn can_select_fields() {
+        let tx = json!({"hash": "0x01", "nonce": 1, "status": true});
+        let out = args(OutputFormat::Json, &["hash", "status"], false).render(&tx).unwrap();
+        assert_eq!(out, r#"{"hash":"0x01","status":true}"#);
+
+        let out = args(OutputFormat::Text, &["hash", "nonce"], false).render(&tx).unwrap();
+        assert_eq!(out, "hash  0x01\nnonce 1");
+
+        let err = args(OutputFormat::Text, &["gas"], false).render(&tx).unwrap_err();
+        assert_eq!(err.to_string(), "unknown field `gas`, expected one of: hash, nonce, status");
+    }
+
+    #[test]
+    fn can_print_quiet() {
+        let out = args(OutputFormat::Text, &["hash", "to"], true).render(&txs()).unwrap();
+        assert_eq!(out, "0x01\t\n0x02\t0xcc");
+    }
+
+    #[test]
+    fn can_print_csv() {
+        let out = args(OutputFormat::Csv, &["hash", "input"], false).render(&txs()).unwrap();
+        assert_eq!(out, "hash,input\n0x01,\"a,b\"\n0x02,\"\"\"c\"\"\"");
+    }
+
+    #[test]
+    fn can_print_table() {
+        let out = args(OutputFormat::Table, &["hash", "value"], false).render(&txs()).unwrap();
+        assert!(out.contains("| hash | value |"), "{out}");
+        assert!(out.contains("| 0x02 | 0x1   |"), "{out}");
+    }
+
+    #[test]
+    fn can_print_yaml() {
+        let block = json!({
+            "miner": "0xaa",
+            "number": "0x1",
+            "transactions": [{"hash": "0x01", "logs": ["a", "true"]}],
+            "uncles": [],
+        });
+        let out = args(OutputFormat::Yaml, &[], false).render(&block).unwrap();
+        assert!(out.contains("uncles: []"), "{out}");
+        assert!(!out.ends_with('\n'));
+        assert_eq!(serde_yaml::from_str::<Value>(&out).unwrap(), block);
+    }
+}
@@ -1,10 +1,11 @@
-use alloy_consensus::{SidecarBuilder, SimpleCoder};
+use alloy_consensus::{SidecarBuilder, SignableTransaction, Signed, SimpleCoder};
 use alloy_json_abi::Function;
 use alloy_network::{AnyNetwork, TransactionBuilder};
-use alloy_primitives::{hex, Address, TxKind};
+use alloy_primitives::{hex, Address, TxKind, U256};
 use alloy_provider::Provider;
 use alloy_rpc_types::TransactionRequest;
 use alloy_serde::WithOtherFields;
+use alloy_signer::Signer;
 use alloy_transport::Transport;
 use eyre::Result;
 use foundry_cli::{
@@ -13,6 +14,50 @@ use foundry_cli::{
 };
 use foundry_common::ens::NameOrAddress;
 use foundry_config::{Chain, Config};
+use foundry_evm::eip7702::{Authorization, TxEip7702, PER_AUTHORIZATION_COST};
+
+/// Signs an authorization delegating the sender to `delegate` and turns the filled transaction
+/// into a signed EIP-7702 transaction carrying it.
+///
+/// The sender pays for its own delegation, so the authorization is signed for the nonce the sender
+/// has after the transaction nonce is consumed. If `estimated` is set, the gas limit was estimated
+/// for a plain call and is raised by the cost of the authorization.
+pub async fn sign_with_authorization<S: Signer>(
+    tx: WithOtherFields<TransactionRequest>,
+    delegate: Address,
+    signer: &S,
+    estimated: bool,
+) -> Result<Signed<TxEip7702>> {
+    let tx = tx.inner;
+    let Some(TxKind::Call(to)) = tx.to else {
+        eyre::bail!("set-code transactions cannot create contracts")
+    };
+    let chain_id = tx.chain_id.unwrap_or_default();
+    let nonce = tx.nonce.unwrap_or_default();
+
+    let authorization =
+        Authorization { chain_id: U256::from(chain_id), address: delegate, nonce: nonce + 1 };
+    let signature = signer.sign_hash(&authorization.signature_hash()).await?;
+
+    let mut gas_limit = tx.gas.unwrap_or_default();
+    if estimated {
+        gas_limit += PER_AUTHORIZATION_COST as u128;
+    }
+    let tx = TxEip7702 {
+        chain_id,
+        nonce,
+        gas_limit,
+        max_fee_per_gas: tx.max_fee_per_gas.unwrap_or_default(),
+        max_priority_fee_per_gas: tx.max_priority_fee_per_gas.unwrap_or_default(),
+        to,
+        value: tx.value.unwrap_or_default(),
+        access_list: tx.access_list.unwrap_or_default(),
+        authorization_list: vec![authorization.into_signed(signature)],
+        input: tx.input.into_input().unwrap_or_default(),
+    };
+    let signature = signer.sign_hash(&tx.signature_hash()).await?;
+    Ok(tx.into_signed(signature))
+}
 
 /// Prevents a misconfigured hwlib from sending a transaction that defies user-specified --from
 pub fn validate_from_address(
@@ -0,0 +1,150 @@
+//! Offchain lookups of [EIP-3668](https://eips.ethereum.org/EIPS/eip-3668), also known as
+//! CCIP-read.
+//!
+//! A contract requests an offchain lookup by reverting with [`OffchainLookup`]. The caller fetches
+//! the response from one of the gateways and calls back the contract with it.
+
+use alloy_primitives::{hex, Address, Bytes};
+use alloy_sol_types::{sol, SolError, SolValue};
+use alloy_transport::TransportError;
+use eyre::Result;
+use serde::Deserialize;
+
+/// The maximum number of offchain lookups of a single call.
+pub const MAX_LOOKUPS: usize = 4;
+
+sol! {
+    /// Reverted with by contracts to request an offchain lookup.
+    #[derive(Debug, PartialEq, Eq)]
+    error OffchainLookup(
+        address sender,
+        string[] urls,
+        bytes callData,
+        bytes4 callbackFunction,
+        bytes extraData
+    );
+}
+
+/// Returns the offchain lookup requested by the revert of a call, if any.
+pub fn offchain_lookup(err: &TransportError) -> Option<OffchainLookup> {
+    let TransportError::ErrorResp(payload) = err else { return None };
+    let data = serde_json::from_str::<Bytes>(payload.data.as_ref()?.get()).ok()?;
+    OffchainLookup::abi_decode(&data, true).ok()
+}
+
+impl OffchainLookup {
+    /// Returns the calldata of the callback with the response of the gateway.
+    pub fn callback(&self, response: Bytes) -> Bytes {
+        let args = (response, self.extraData.clone()).abi_encode_params();
+        [&self.callbackFunction[..], &args].concat().into()
+    }
+
+    /// Fetches the response from the gateways, in order.
+    ///
+    /// Gateways with `{data}` in their URL are queried with `GET`, the others with a `POST` of the
+    /// sender and the calldata. A server error moves on to the next gateway, while a client error
+    /// fails the lookup.
+    pub async fn fetch(&self, client: &reqwest::Client) -> Result<Bytes> {
+        let sender = hex::encode_prefixed(self.sender);
+        let data = hex::encode_prefixed(&self.callData);
+
+        let mut errors = Vec::new();
+        for url in &self.urls {
+            let url = url.replace("{sender}", &sender);
+            let request = if url.contains("{data}") {
+                client.get(url.replace("{data}", &data))
+            } else {
+                let body = serde_json::json!({ "data": data, "sender": sender });
+                client
+                    .post(&url)
+                    .header(reqwest::header::CONTENT_TYPE, "application/json")
+                    .body(body.to_string())
+            };
+            tracing::trace!(%url, "offchain lookup");
+
+            let response = match request.send().await {
+                Ok(response) => response,
+                Err(err) => {
+                    errors.push(format!("{url}: {err}"));
+                    continue
+                }
+            };
+            let status = response.status();
+            let body = response.text().await?;
+            if status.is_success() {
+                let response: GatewayResponse = serde_json::from_str(&body)
+                    .map_err(|err| eyre::eyre!("invalid response from gateway {url}: {err}"))?;
+                return Ok(response.data)
+            }
+            if status.is_client_error() {
+                eyre::bail!("gateway {url} rejected the offchain lookup ({status}): {body}")
+            }
+            errors.push(format!("{url}: {status}"));
+        }
+        eyre::bail!("all the gateways of the offchain lookup failed:\n{}", errors.join("\n"))
+    }
+
+    /// Returns an error if the lookup was not requested by the called contract.
+    pub fn ensure_sender(&self, to: Option<Address>) -> Result<()> {
+        if to != Some(self.sender) {
+            eyre::bail!(
+                "offchain lookup requested by {}, which is not the called contract",
+                self.sender
+            )
+        }
+        Ok(())
+    }
+}
+
+#[derive(Deserialize)]
+struct GatewayResponse {
+    data: Bytes,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_json_rpc::ErrorPayload;
+    use alloy_primitives::FixedBytes;
+
+    fn lookup() -> OffchainLookup {
+        OffchainLookup {
+            sender: Address::with_last_byte(1),
+            urls: vec!["https://gateway.example/{sender}/{data}.json".to_string()],
+            callData: Bytes::from_static(&[1, 2]),
+            callbackFunction: FixedBytes([0xaa, 0xbb, 0xcc, 0xdd]),
+            extraData: Bytes::from_static(&[3]),
+        }
+    }
+
+    #[test]
+    fn decodes_offchain_lookup_reverts() {
+        let revert = hex::encode_prefixed(lookup().abi_encode());
+        let err = TransportError::ErrorResp(ErrorPayload {
+            code: 3,
+            message: "execution reverted".into(),
+            data: Some(serde_json::value::to_raw_value(&revert).unwrap()),
+        });
+        assert_eq!(offchain_lookup(&err), Some(lookup()));
+
+        let err = TransportError::ErrorResp(ErrorPayload {
+            code: 3,
+            message: "execution reverted".into(),
+            data: Some(serde_json::value::to_raw_value("0x08c379a0").unwrap()),
+        });
+        assert_eq!(offchain_lookup(&err), None);
+    }
+
+    #[test]
+    fn encodes_callbacks() {
+        let callback = lookup().callback(Bytes::from_static(&[4]));
+        assert_eq!(&callback[..4], &[0xaa, 0xbb, 0xcc, 0xdd]);
+        let (response, extra_data) =
+            <(Bytes, Bytes)>::abi_decode_params(&callback[4..], true).unwrap();
+        assert_eq!(response, Bytes::from_static(&[4]));
+        assert_eq!(extra_data, Bytes::from_static(&[3]));
+
+        assert!(lookup().ensure_sender(Some(Address::with_last_byte(1))).is_ok());
+        assert!(lookup().ensure_sender(Some(Address::with_last_byte(2))).is_err());
+    }
+}
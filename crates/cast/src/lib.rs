@@ -2,7 +2,7 @@
 #![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
 
 use alloy_consensus::TxEnvelope;
-use alloy_dyn_abi::{DynSolType, DynSolValue, FunctionExt};
+use alloy_dyn_abi::{DynSolType, DynSolValue, FunctionExt, JsonAbiExt};
 use alloy_json_abi::Function;
 use alloy_network::AnyNetwork;
 use alloy_primitives::{
@@ -15,7 +15,9 @@ use alloy_provider::{
     PendingTransactionBuilder, Provider,
 };
 use alloy_rlp::Decodable;
-use alloy_rpc_types::{BlockId, BlockNumberOrTag, Filter, TransactionRequest};
+use alloy_rpc_types::{
+    state::StateOverride, BlockId, BlockNumberOrTag, Filter, TransactionInput, TransactionRequest,
+};
 use alloy_serde::WithOtherFields;
 use alloy_sol_types::sol;
 use alloy_transport::Transport;
@@ -25,7 +27,7 @@ use evm_disassembler::{disassemble_bytes, disassemble_str, format_operations};
 use eyre::{Context, ContextCompat, Result};
 use foundry_block_explorers::Client;
 use foundry_common::{
-    abi::{encode_function_args, get_func},
+    abi::{abi_decode_packed, coerce_json_args, encode_function_args, get_func},
     compile::etherscan_project,
     fmt::*,
     fs, get_pretty_tx_receipt_attr, TransactionReceiptWithRevertReason,
@@ -48,6 +50,7 @@ use foundry_common::abi::encode_function_args_packed;
 pub use foundry_evm::*;
 
 pub mod base;
+pub mod ccip;
 pub mod errors;
 mod rlp_converter;
 
@@ -65,6 +68,8 @@ sol! {
 
 pub struct Cast<P, T> {
     provider: P,
+    /// Whether to follow the offchain lookups of calls, enabled by default.
+    ccip_read: bool,
     transport: PhantomData<T>,
 }
 
@@ -89,7 +94,14 @@ where
     /// # }
     /// ```
     pub fn new(provider: P) -> Self {
-        Self { provider, transport: PhantomData }
+        Self { provider, ccip_read: true, transport: PhantomData }
+    }
+
+    /// Sets whether calls follow the offchain lookups requested by the called contract, see
+    /// [EIP-3668](https://eips.ethereum.org/EIPS/eip-3668).
+    pub fn with_ccip_read(mut self, ccip_read: bool) -> Self {
+        self.ccip_read = ccip_read;
+        self
     }
 
     /// Makes a read-only call to the specified address
@@ -128,7 +140,41 @@ where
         func: Option<&Function>,
         block: Option<BlockId>,
     ) -> Result<String> {
-        let res = self.provider.call(req).block(block.unwrap_or_default()).await?;
+        self.call_with_overrides(req, func, block, None).await
+    }
+
+    /// Makes a read-only call like [`Cast::call`], on top of the given state overrides.
+    pub async fn call_with_overrides(
+        &self,
+        req: &WithOtherFields<TransactionRequest>,
+        func: Option<&Function>,
+        block: Option<BlockId>,
+        overrides: Option<&StateOverride>,
+    ) -> Result<String> {
+        let mut req = Cow::Borrowed(req);
+        let mut lookups = 0;
+        let res = loop {
+            let mut call = self.provider.call(&req).block(block.unwrap_or_default());
+            if let Some(overrides) = overrides {
+                call = call.overrides(overrides);
+            }
+            let err = match call.await {
+                Ok(res) => break res,
+                Err(err) => err,
+            };
+            let lookup = match ccip::offchain_lookup(&err) {
+                Some(lookup) if self.ccip_read => lookup,
+                _ => return Err(err.into()),
+            };
+            if lookups == ccip::MAX_LOOKUPS {
+                eyre::bail!("exceeded the maximum of {} offchain lookups", ccip::MAX_LOOKUPS)
+            }
+            lookups += 1;
+
+            lookup.ensure_sender(req.to.and_then(|to| to.to().copied()))?;
+            let response = lookup.fetch(&reqwest::Client::new()).await?;
+            req.to_mut().inner.input = TransactionInput::new(lookup.callback(response));
+        };
 
         let mut decoded = vec![];
 
@@ -1601,11 +1647,7 @@ impl SimpleCast {
     /// # Ok::<_, eyre::Report>(())
     /// ```
     pub fn abi_encode_packed(sig: &str, args: &[impl AsRef<str>]) -> Result<String> {
-        // If the signature is a tuple, we need to prefix it to make it a function
-        let sig =
-            if sig.trim_start().starts_with('(') { format!("foo{sig}") } else { sig.to_string() };
-
-        let func = get_func(sig.as_str())?;
+        let func = get_func_or_tuple(sig)?;
         let encoded = match encode_function_args_packed(&func, args) {
             Ok(res) => hex::encode(res),
             Err(e) => eyre::bail!("Could not ABI encode the function and arguments. Did you pass in the right types?\nError\n{}", e),
@@ -1613,6 +1655,67 @@ impl SimpleCast {
         Ok(format!("0x{encoded}"))
     }
 
+    /// Performs ABI encoding, or packed ABI encoding if `packed`, of the arguments given as JSON,
+    /// based off of the function signature or tuple. Does not include the function selector in the
+    /// result.
+    ///
+    /// The arguments are either an array of the arguments or an object of the arguments by
+    /// parameter name, see [`coerce_json_args`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cast::SimpleCast as Cast;
+    ///
+    /// let args = serde_json::json!([{ "a": 1, "b": [2] }]);
+    /// assert_eq!(
+    ///     "0x00010000000000000000000000000000000000000000000000000000000000000002",
+    ///     Cast::abi_encode_json("((uint16 a, uint16[] b))", &args, true).unwrap().as_str()
+    /// );
+    /// # Ok::<_, eyre::Report>(())
+    /// ```
+    pub fn abi_encode_json(sig: &str, args: &serde_json::Value, packed: bool) -> Result<String> {
+        let func = get_func_or_tuple(sig)?;
+        let values = coerce_json_args(&func, args)?;
+        let encoded = if packed {
+            values.iter().flat_map(|value| value.abi_encode_packed()).collect()
+        } else {
+            DynSolValue::Tuple(values).abi_encode_params()
+        };
+        Ok(hex::encode_prefixed(encoded))
+    }
+
+    /// Decodes data ABI encoded by [`Self::abi_encode`], or packed ABI encoded by
+    /// [`Self::abi_encode_packed`] if `packed`, back into the arguments of the function signature
+    /// or tuple.
+    ///
+    /// The packed encoding doesn't include the length of dynamic values, so only the last argument
+    /// can be dynamic when decoding it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use cast::SimpleCast as Cast;
+    ///
+    /// let data = "0x8dbd1b711dc621e1404633da156fcc779e1c6f3e68656c6c6f20776f726c64";
+    /// let decoded = Cast::abi_decode_args("(address a, string b)", data, true)?;
+    /// assert_eq!(decoded[1].as_str(), Some("hello world"));
+    /// # Ok::<_, eyre::Report>(())
+    /// ```
+    pub fn abi_decode_args(sig: &str, data: &str, packed: bool) -> Result<Vec<DynSolValue>> {
+        let func = get_func_or_tuple(sig)?;
+        let data = hex::decode(data)?;
+        if !packed {
+            return Ok(func.abi_decode_input(&data, false)?)
+        }
+        let types = func
+            .inputs
+            .iter()
+            .map(|input| DynSolType::parse(&input.selector_type()))
+            .collect::<Result<Vec<_>, _>>()?;
+        abi_decode_packed(&types, &data)
+    }
+
     /// Performs ABI encoding to produce the hexadecimal calldata with the given arguments.
     ///
     /// # Example
@@ -1992,6 +2095,15 @@ impl SimpleCast {
     }
 }
 
+/// Parses a function signature, or a tuple of types as the parameters of a function.
+fn get_func_or_tuple(sig: &str) -> Result<Function> {
+    if sig.trim_start().starts_with('(') {
+        get_func(&format!("foo{sig}"))
+    } else {
+        get_func(sig)
+    }
+}
+
 fn strip_0x(s: &str) -> &str {
     s.strip_prefix("0x").unwrap_or(s)
 }
@@ -1,9 +1,9 @@
 //! Contains various tests for checking cast commands
 
-use alloy_primitives::{address, b256, Address, B256};
+use alloy_primitives::{address, b256, Address, B256, U256};
 use foundry_test_utils::{
     casttest,
-    rpc::{next_http_rpc_endpoint, next_ws_rpc_endpoint},
+    rpc::{next_http_archive_rpc_endpoint, next_http_rpc_endpoint, next_ws_rpc_endpoint},
     util::OutputExt,
 };
 use std::{fs, io::Write, path::Path, str::FromStr};
@@ -27,7 +27,37 @@ casttest!(latest_block, |_prj, cmd| {
     // <https://etherscan.io/block/15007840>
     cmd.cast_fuse().args(["block", "15007840", "-f", "hash", "--rpc-url", eth_rpc_url.as_str()]);
     let output = cmd.stdout_lossy();
-    assert_eq!(output.trim(), "0x950091817a57e22b6c1f3b951a15f52d41ac89b299cc8f9c89bb6d185f80c415")
+    assert_eq!(output.trim(), "0x950091817a57e22b6c1f3b951a15f52d41ac89b299cc8f9c89bb6d185f80c415");
+
+    cmd.cast_fuse().args([
+        "block",
+        "15007840",
+        "--fields",
+        "number,hash",
+        "--quiet",
+        "--rpc-url",
+        eth_rpc_url.as_str(),
+    ]);
+    let output = cmd.stdout_lossy();
+    assert_eq!(
+        output.trim(),
+        "0xe50060\t0x950091817a57e22b6c1f3b951a15f52d41ac89b299cc8f9c89bb6d185f80c415"
+    );
+
+    cmd.cast_fuse().args([
+        "block",
+        "15007840",
+        "--format",
+        "csv",
+        "--fields",
+        "hash,miner",
+        "--rpc-url",
+        eth_rpc_url.as_str(),
+    ]);
+    let output = cmd.stdout_lossy();
+    assert!(output.starts_with(
+        "hash,miner\n0x950091817a57e22b6c1f3b951a15f52d41ac89b299cc8f9c89bb6d185f80c415,"
+    ));
 });
 
 // tests that the `cast find-block` command works correctly
@@ -75,6 +105,36 @@ casttest!(wallet_address_keystore_with_password_file, |_prj, cmd| {
     assert!(out.contains("0xeC554aeAFE75601AaAb43Bd4621A22284dB566C2"));
 });
 
+// tests that `cast wallet vanity` mines a CREATE2 salt for the deployer
+casttest!(wallet_vanity_create2_salt, |_prj, cmd| {
+    let deployer = address!("4e59b44847b379578588920ca78fbf26c0b4956c");
+    let init_code_hash = b256!("bc36789e7a1e281436464229828f817d6612f7b477d66591ff96a9e064bcc98a");
+
+    cmd.args([
+        "wallet",
+        "vanity",
+        "--starts-with",
+        "ab",
+        "--deployer",
+        &deployer.to_string(),
+        "--init-code-hash",
+        &init_code_hash.to_string(),
+        "--jobs",
+        "2",
+    ]);
+    let out = cmd.stdout_lossy();
+    let field = |name: &str| {
+        out.lines()
+            .find_map(|line| line.strip_prefix(name))
+            .unwrap_or_else(|| panic!("no {name} in output:\n{out}"))
+            .to_string()
+    };
+    let address: Address = field("Address: ").parse().unwrap();
+    let salt: B256 = field("Salt: ").split_whitespace().next().unwrap().parse().unwrap();
+    assert_eq!(address[0], 0xab);
+    assert_eq!(address, deployer.create2(salt, init_code_hash));
+});
+
 // tests that `cast wallet sign message` outputs the expected signature
 casttest!(wallet_sign_message_utf8_data, |_prj, cmd| {
     let pk = "0x0000000000000000000000000000000000000000000000000000000000000001";
@@ -95,6 +155,24 @@ casttest!(wallet_sign_message_utf8_data, |_prj, cmd| {
     cmd.cast_fuse().args(["wallet", "verify", "-a", address, "other msg", expected]).assert_err();
 });
 
+// tests that `cast sig-verify` recovers and validates the signer of a message
+casttest!(sig_verify_message, |_prj, cmd| {
+    let address = "0x7E5F4552091A69125d5DfCb7b8C2659029395Bdf";
+    let sig = "0xfe28833983d6faa0715c7e8c3873c725ddab6fa5bf84d40e780676e463e6bea20fc6aea97dc273a98eb26b0914e224c8dd5c615ceaab69ddddcf9b0ae3de0e371c";
+
+    cmd.args(["sig-verify", "test", sig]);
+    assert_eq!(cmd.stdout_lossy().trim(), address);
+
+    cmd.cast_fuse().args(["sig-verify", "-a", address, "test", sig]).assert_non_empty_stdout();
+
+    cmd.cast_fuse().args(["sig-verify", "-a", address, "--json", "test", sig]);
+    let output: serde_json::Value = serde_json::from_str(&cmd.stdout_lossy()).unwrap();
+    assert_eq!(output["valid"], true);
+    assert_eq!(output["method"], "ecdsa");
+
+    cmd.cast_fuse().args(["sig-verify", "-a", address, "other msg", sig]).assert_err();
+});
+
 // tests that `cast wallet sign message` outputs the expected signature, given a 0x-prefixed data
 casttest!(wallet_sign_message_hex_data, |_prj, cmd| {
     cmd.args([
@@ -280,6 +358,26 @@ casttest!(estimate_function_gas, |_prj, cmd| {
     assert!(out.ge(&0));
 });
 
+// tests that `cast call` executes on top of state overrides
+casttest!(call_with_state_overrides, |_prj, cmd| {
+    let eth_rpc_url = next_http_rpc_endpoint();
+    // returns 42
+    let code = "0x0000000000000000000000000000000000001234:0x602a60005260206000f3";
+    cmd.args([
+        "call",
+        "0x0000000000000000000000000000000000001234",
+        "answer()(uint256)",
+        "--override-code",
+        code,
+        "--rpc-url",
+        eth_rpc_url.as_str(),
+    ]);
+    assert_eq!(cmd.stdout_lossy().trim(), "42");
+
+    cmd.arg("--trace");
+    assert!(cmd.stdout_lossy().contains("000000000000002a"));
+});
+
 // tests that `cast estimate --create` is working correctly.
 casttest!(estimate_contract_deploy_gas, |_prj, cmd| {
     let eth_rpc_url = next_http_rpc_endpoint();
@@ -447,6 +545,21 @@ casttest!(calldata_array, |_prj, cmd| {
     );
 });
 
+// checks `cast abi-encode` can encode structs read from JSON and decode them back
+casttest!(abi_encode_json_packed, |prj, cmd| {
+    let path = prj.root().join("args.json");
+    let to = "0x0000000000000000000000000000000000000001";
+    fs::write(&path, format!(r#"[{{"to": "{to}", "amounts": [1, "2"]}}]"#)).unwrap();
+    let sig = "f((address to, uint16[] amounts) transfer)";
+    cmd.args(["abi-encode", "--packed", sig, "--input-file"]).arg(&path);
+    let encoded = cmd.stdout_lossy();
+    let expected = "0x000000000000000000000000000000000000000100000000000000000000000000000000000000000000000000000000000000010000000000000000000000000000000000000000000000000000000000000002";
+    assert_eq!(encoded.trim(), expected);
+
+    cmd.cast_fuse().args(["abi-encode", "--packed", "--decode", sig, encoded.trim()]);
+    assert_eq!(cmd.stdout_lossy().trim(), format!("({to}, [1, 2])"));
+});
+
 // <https://github.com/foundry-rs/foundry/issues/2705>
 casttest!(run_succeeds, |_prj, cmd| {
     let rpc = next_http_rpc_endpoint();
@@ -973,3 +1086,65 @@ casttest!(block_number_hash, |_prj, cmd| {
         .stdout_lossy();
     assert_eq!(s.trim().parse::<u64>().unwrap(), 1, "{s}")
 });
+
+casttest!(history_call, |_prj, cmd| {
+    let eth_rpc_url = next_http_rpc_endpoint();
+    let s = cmd
+        .args([
+            "history",
+            "0xdAC17F958D2ee523a2206206994597C13D831ec7",
+            "--sig",
+            "totalSupply()(uint256)",
+            "--from-block",
+            "15000000",
+            "--to-block",
+            "15000010",
+            "--step",
+            "5",
+            "--rpc-url",
+            eth_rpc_url.as_str(),
+        ])
+        .stdout_lossy();
+    let lines = s.trim().lines().collect::<Vec<_>>();
+    assert_eq!(lines[0], "block,value");
+    let blocks = lines[1..].iter().map(|line| line.split(',').next().unwrap()).collect::<Vec<_>>();
+    assert_eq!(blocks, ["15000000", "15000005", "15000010"]);
+});
+
+// tests that the transactions of a bundle are executed in order on top of each other
+casttest!(bundle_simulate, |prj, cmd| {
+    let eth_rpc_url = next_http_archive_rpc_endpoint();
+    let bundle = prj.root().join("bundle.json");
+    fs::write(
+        &bundle,
+        r#"[
+            {"from": "0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045", "to": "0x000000000000000000000000000000000000dEaD", "value": "0xde0b6b3a7640000"},
+            {"from": "0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045", "to": "0x000000000000000000000000000000000000dEaD", "value": "0xde0b6b3a7640000"}
+        ]"#,
+    )
+    .unwrap();
+
+    let output = cmd
+        .args(["bundle", "simulate"])
+        .arg(&bundle)
+        .args(["--block", "16000000", "--json", "--rpc-url", eth_rpc_url.as_str()])
+        .stdout_lossy();
+    let results: serde_json::Value = serde_json::from_str(&output).unwrap();
+    let results = results.as_array().unwrap();
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[0]["success"], true);
+    assert_eq!(results[0]["gasUsed"], 21000);
+    assert_eq!(results[1]["cumulativeGasUsed"], 42000);
+
+    let (_, dead) = results[1]["stateDiff"]
+        .as_object()
+        .unwrap()
+        .iter()
+        .find(|(address, _)| {
+            address.eq_ignore_ascii_case("0x000000000000000000000000000000000000dead")
+        })
+        .unwrap();
+    let from = dead["balance"]["from"].as_str().unwrap().parse::<U256>().unwrap();
+    let to = dead["balance"]["to"].as_str().unwrap().parse::<U256>().unwrap();
+    assert_eq!(to - from, U256::from(10u64.pow(18)));
+});
@@ -86,6 +86,16 @@ interface Vm {
         Unknown,
     }
 
+    /// The context an expected call is made in. Used by `expectCallWithMetadata`.
+    enum ExpectedCallContext {
+        /// The call may be made in any context.
+        Any,
+        /// The call must be made in a non-static context.
+        NonStatic,
+        /// The call must be made in a static context, e.g. with `STATICCALL`.
+        Static,
+    }
+
     /// An Ethereum log. Returned by `getRecordedLogs`.
     struct Log {
         /// The topics of the log, including the signature, if any.
@@ -110,6 +120,23 @@ interface Vm {
         uint64 gasRemaining;
     }
 
+    /// Constraints on the metadata of an expected call. Used by `expectCallWithMetadata`.
+    struct ExpectedCallMetadata {
+        /// The minimum gas forwarded to the call, excluding the stipend of value transfers.
+        uint64 minGas;
+        /// The maximum gas forwarded to the call, excluding the stipend of value transfers.
+        /// Use `type(uint64).max` for no upper bound.
+        uint64 maxGas;
+        /// The minimum `msg.value` of the call.
+        uint256 minValue;
+        /// The maximum `msg.value` of the call. Use `type(uint256).max` for no upper bound.
+        uint256 maxValue;
+        /// The depth of the call, where calls made by the test contract have depth 1. Use 0 for any depth.
+        uint64 depth;
+        /// The context the call is made in.
+        ExpectedCallContext context;
+    }
+
     /// An RPC URL and its alias. Returned by `rpcUrlStructs`.
     struct Rpc {
         /// The alias of the RPC URL.
@@ -176,6 +203,20 @@ interface Vm {
         uint256 created;
     }
 
+    /// An EIP-7702 authorization to delegate the code of an account to an implementation.
+    struct SignedDelegation {
+        /// The y-parity of the signature.
+        uint8 v;
+        /// The `r` value of the signature.
+        bytes32 r;
+        /// The `s` value of the signature.
+        bytes32 s;
+        /// The nonce of the signing account the delegation is valid for.
+        uint64 nonce;
+        /// The address of the contract the account delegates to.
+        address implementation;
+    }
+
     /// A wallet with a public and private key.
     struct Wallet {
         /// The wallet's address.
@@ -222,6 +263,16 @@ interface Vm {
         bool reverted;
     }
 
+    /// A transient storage write recorded during a `recordTransient` session.
+    struct TransientWrite {
+        /// The slot that was written to.
+        bytes32 slot;
+        /// The previous value of the slot.
+        bytes32 previousValue;
+        /// The new value of the slot.
+        bytes32 newValue;
+    }
+
     /// The result of a `stopAndReturnStateDiff` call.
     struct AccountAccess {
         /// The chain and fork the access occurred.
@@ -278,10 +329,30 @@ interface Vm {
     #[cheatcode(group = Evm, safety = Safe)]
     function load(address target, bytes32 slot) external view returns (bytes32 data);
 
+    /// Loads a transient storage slot from an address.
+    #[cheatcode(group = Evm, safety = Safe)]
+    function tload(address target, bytes32 slot) external view returns (bytes32 data);
+
     /// Load a genesis JSON file's `allocs` into the in-memory revm state.
     #[cheatcode(group = Evm, safety = Unsafe)]
     function loadAllocs(string calldata pathToAllocsJson) external;
 
+    /// Saves the accounts changed in the current transaction, except for the caller, as the
+    /// fixture `name` together with `data`, e.g. the addresses of the deployed contracts.
+    ///
+    /// The fixture is shared with all test contracts of the run, which restore it with
+    /// `loadFixture` instead of repeating an expensive setup.
+    #[cheatcode(group = Evm, safety = Unsafe)]
+    function saveFixture(string calldata name, bytes calldata data) external;
+
+    /// Restores the accounts of the fixture `name`, saved by `saveFixture` in any test contract of
+    /// the run, and returns the data saved with it.
+    ///
+    /// Returns `found = false` if the fixture wasn't saved yet, in which case the caller should set
+    /// it up and save it.
+    #[cheatcode(group = Evm, safety = Unsafe)]
+    function loadFixture(string calldata name) external returns (bool found, bytes memory data);
+
     /// Signs `digest` with `privateKey` using the secp256k1 curve.
     #[cheatcode(group = Evm, safety = Safe)]
     function sign(uint256 privateKey, bytes32 digest) external pure returns (uint8 v, bytes32 r, bytes32 s);
@@ -306,6 +377,21 @@ interface Vm {
     #[cheatcode(group = Evm, safety = Safe)]
     function signP256(uint256 privateKey, bytes32 digest) external pure returns (bytes32 r, bytes32 s);
 
+    /// Signs an EIP-7702 authorization delegating the account of `privateKey` to `implementation`,
+    /// valid for the current chain and nonce of the account.
+    #[cheatcode(group = Evm, safety = Safe)]
+    function signDelegation(address implementation, uint256 privateKey) external returns (SignedDelegation memory signedDelegation);
+
+    /// Applies a signed EIP-7702 authorization: the code of the signing account is set to a
+    /// delegation to the implementation, and its nonce is incremented.
+    #[cheatcode(group = Evm, safety = Unsafe)]
+    function attachDelegation(SignedDelegation calldata signedDelegation) external;
+
+    /// Signs an EIP-7702 authorization delegating the account of `privateKey` to `implementation`
+    /// and applies it.
+    #[cheatcode(group = Evm, safety = Unsafe)]
+    function signAndAttachDelegation(address implementation, uint256 privateKey) external returns (SignedDelegation memory signedDelegation);
+
     // -------- Record Storage --------
 
     /// Records all storage reads and writes.
@@ -316,6 +402,15 @@ interface Vm {
     #[cheatcode(group = Evm, safety = Safe)]
     function accesses(address target) external returns (bytes32[] memory readSlots, bytes32[] memory writeSlots);
 
+    /// Records all transient storage writes.
+    #[cheatcode(group = Evm, safety = Safe)]
+    function recordTransient() external;
+
+    /// Gets all transient storage writes from a `vm.recordTransient` session, for a given address,
+    /// in the order they occurred.
+    #[cheatcode(group = Evm, safety = Safe)]
+    function getTransientWrites(address target) external returns (TransientWrite[] memory writes);
+
     /// Record all account accesses as part of CREATE, CALL or SELFDESTRUCT opcodes in order,
     /// along with the context of the calls
     #[cheatcode(group = Evm, safety = Safe)]
@@ -419,6 +514,22 @@ interface Vm {
     #[cheatcode(group = Evm, safety = Safe)]
     function getBlockTimestamp() external view returns (uint256 timestamp);
 
+    /// Simulates mining `blocks` empty blocks: advances `block.number` by `blocks`, and `block.timestamp` and `block.basefee`
+    /// as configured with `setMineSchedule`. The skipped blocks are given a hash, so that `blockhash` works for them.
+    #[cheatcode(group = Evm, safety = Unsafe)]
+    function mine(uint256 blocks) external;
+
+    /// Simulates mining empty blocks until `block.number` is `blockNumber`, see `mine`.
+    #[cheatcode(group = Evm, safety = Unsafe)]
+    function mineTo(uint256 blockNumber) external;
+
+    /// Configures how `mine` and `mineTo` advance the block environment.
+    /// `blockTime` is the number of seconds between blocks, and `gasUsedBps` the share of the gas limit used by each block, in basis
+    /// points, from which the base fee of the next block is derived as per EIP-1559.
+    /// Defaults to 12 seconds and 5000 basis points, the gas target, which keeps the base fee constant.
+    #[cheatcode(group = Evm, safety = Unsafe)]
+    function setMineSchedule(uint256 blockTime, uint256 gasUsedBps) external;
+
     /// Sets `block.blobbasefee`
     #[cheatcode(group = Evm, safety = Unsafe)]
     function blobBaseFee(uint256 newBlobBaseFee) external;
@@ -456,6 +567,11 @@ interface Vm {
     #[cheatcode(group = Evm, safety = Unsafe)]
     function store(address target, bytes32 slot, bytes32 value) external;
 
+    /// Stores a value to an address' transient storage slot.
+    /// Transient storage is cleared at the end of the transaction, i.e. of the current test.
+    #[cheatcode(group = Evm, safety = Unsafe)]
+    function tstore(address target, bytes32 slot, bytes32 value) external;
+
     /// Marks the slots of an account and the account address as cold.
     #[cheatcode(group = Evm, safety = Unsafe, status = Experimental)]
     function cool(address target) external;
@@ -463,7 +579,7 @@ interface Vm {
     // -------- Call Manipulation --------
     // --- Mocks ---
 
-    /// Clears all mocked calls.
+    /// Clears all mocked calls and precompiles.
     #[cheatcode(group = Evm, safety = Unsafe)]
     function clearMockedCalls() external;
 
@@ -488,6 +604,12 @@ interface Vm {
     function mockCallRevert(address callee, uint256 msgValue, bytes calldata data, bytes calldata revertData)
         external;
 
+    /// Mocks the output of a precompile for the given input, without consuming gas.
+    /// Calls to the precompile with any other input are executed by the precompile itself.
+    /// Only the precompiles of the EVM spec can be mocked.
+    #[cheatcode(group = Evm, safety = Unsafe)]
+    function mockPrecompile(address precompile, bytes calldata input, bytes calldata output) external;
+
     // --- Impersonation (pranks) ---
 
     /// Sets the *next* call's `msg.sender` to be the input address.
@@ -747,6 +869,33 @@ interface Vm {
     function expectCallMinGas(address callee, uint256 msgValue, uint64 minGas, bytes calldata data, uint64 count)
         external;
 
+    /// Expects a call to an address with the specified calldata, forwarding an amount of gas within the given range.
+    /// The gas excludes the stipend of value transfers.
+    #[cheatcode(group = Testing, safety = Unsafe)]
+    function expectCallWithGas(address callee, uint64 minGas, uint64 maxGas, bytes calldata data) external;
+
+    /// Expects given number of calls to an address with the specified calldata, forwarding an amount of gas within
+    /// the given range. The gas excludes the stipend of value transfers.
+    #[cheatcode(group = Testing, safety = Unsafe)]
+    function expectCallWithGas(address callee, uint64 minGas, uint64 maxGas, bytes calldata data, uint64 count)
+        external;
+
+    /// Expects a call to an address with the specified calldata, whose gas, `msg.value`, depth and context match
+    /// the given metadata.
+    #[cheatcode(group = Testing, safety = Unsafe)]
+    function expectCallWithMetadata(address callee, bytes calldata data, ExpectedCallMetadata calldata metadata)
+        external;
+
+    /// Expects given number of calls to an address with the specified calldata, whose gas, `msg.value`, depth and
+    /// context match the given metadata.
+    #[cheatcode(group = Testing, safety = Unsafe)]
+    function expectCallWithMetadata(
+        address callee,
+        bytes calldata data,
+        ExpectedCallMetadata calldata metadata,
+        uint64 count
+    ) external;
+
     /// Prepare an expected log with (bool checkTopic1, bool checkTopic2, bool checkTopic3, bool checkData.).
     /// Call this function, then emit an event, then call a function. Internally after the call, we check if
     /// logs were emitted in the expected order with the expected topics and data (as specified by the booleans).
@@ -768,6 +917,41 @@ interface Vm {
     #[cheatcode(group = Testing, safety = Unsafe)]
     function expectEmit(address emitter) external;
 
+    /// Prepare an expected log with (bool checkTopic1, bool checkTopic2, bool checkTopic3) and a per-word data mask.
+    /// Each element of `checkData` toggles the check of the corresponding 32-byte word of the log's non-indexed data,
+    /// allowing individual parameters to be wildcarded. Words past the end of the mask are always checked.
+    #[cheatcode(group = Testing, safety = Unsafe)]
+    function expectEmit(bool checkTopic1, bool checkTopic2, bool checkTopic3, bool[] calldata checkData) external;
+
+    /// Same as the previous method, but also checks supplied address against emitting contract.
+    #[cheatcode(group = Testing, safety = Unsafe)]
+    function expectEmit(
+        bool checkTopic1,
+        bool checkTopic2,
+        bool checkTopic3,
+        bool[] calldata checkData,
+        address emitter
+    ) external;
+
+    /// Prepare an expected log with (bool checkTopic1, bool checkTopic2, bool checkTopic3, bool checkData.) that may be
+    /// emitted in any order. Call this function, then emit an event, then call a function. Internally after the call, we
+    /// check that every unordered expected log was matched by a distinct emitted log, regardless of their order.
+    #[cheatcode(group = Testing, safety = Unsafe)]
+    function expectEmitUnordered(bool checkTopic1, bool checkTopic2, bool checkTopic3, bool checkData) external;
+
+    /// Same as the previous method, but also checks supplied address against emitting contract.
+    #[cheatcode(group = Testing, safety = Unsafe)]
+    function expectEmitUnordered(bool checkTopic1, bool checkTopic2, bool checkTopic3, bool checkData, address emitter)
+        external;
+
+    /// Prepare an expected log with all topic and data checks enabled that may be emitted in any order.
+    #[cheatcode(group = Testing, safety = Unsafe)]
+    function expectEmitUnordered() external;
+
+    /// Same as the previous method, but also checks supplied address against emitting contract.
+    #[cheatcode(group = Testing, safety = Unsafe)]
+    function expectEmitUnordered(address emitter) external;
+
     /// Expects an error on next call with any revert data.
     #[cheatcode(group = Testing, safety = Unsafe)]
     function expectRevert() external;
@@ -1357,6 +1541,100 @@ interface Vm {
         string calldata error
     ) external pure;
 
+    /// Compares two `uint256` fixed point values with `decimals` decimals. Expects them to differ by at most `maxUlps` units
+    /// in the last place, i.e. by `maxUlps * 10 ** -decimals`.
+    /// Formats values with decimals in failure message.
+    #[cheatcode(group = Testing, safety = Safe)]
+    function assertApproxEqUlp(
+        uint256 left,
+        uint256 right,
+        uint256 maxUlps,
+        uint256 decimals
+    ) external pure;
+
+    /// Compares two `uint256` fixed point values with `decimals` decimals. Expects them to differ by at most `maxUlps` units
+    /// in the last place, i.e. by `maxUlps * 10 ** -decimals`.
+    /// Formats values with decimals in failure message. Includes error message into revert string on failure.
+    #[cheatcode(group = Testing, safety = Safe)]
+    function assertApproxEqUlp(
+        uint256 left,
+        uint256 right,
+        uint256 maxUlps,
+        uint256 decimals,
+        string calldata error
+    ) external pure;
+
+    /// Compares two `int256` fixed point values with `decimals` decimals. Expects them to differ by at most `maxUlps` units
+    /// in the last place, i.e. by `maxUlps * 10 ** -decimals`.
+    /// Formats values with decimals in failure message.
+    #[cheatcode(group = Testing, safety = Safe)]
+    function assertApproxEqUlp(
+        int256 left,
+        int256 right,
+        uint256 maxUlps,
+        uint256 decimals
+    ) external pure;
+
+    /// Compares two `int256` fixed point values with `decimals` decimals. Expects them to differ by at most `maxUlps` units
+    /// in the last place, i.e. by `maxUlps * 10 ** -decimals`.
+    /// Formats values with decimals in failure message. Includes error message into revert string on failure.
+    #[cheatcode(group = Testing, safety = Safe)]
+    function assertApproxEqUlp(
+        int256 left,
+        int256 right,
+        uint256 maxUlps,
+        uint256 decimals,
+        string calldata error
+    ) external pure;
+
+    /// Compares two `uint256` WAD values, 18 decimal fixed point numbers. Expects their difference relative to `right` to be
+    /// less than or equal to `maxRelDelta`, a WAD where 1e18 == 100%. Computed without rounding.
+    /// Formats values with decimals in failure message.
+    #[cheatcode(group = Testing, safety = Safe)]
+    function assertApproxEqRelWad(uint256 left, uint256 right, uint256 maxRelDelta) external pure;
+
+    /// Compares two `uint256` WAD values, 18 decimal fixed point numbers. Expects their difference relative to `right` to be
+    /// less than or equal to `maxRelDelta`, a WAD where 1e18 == 100%. Computed without rounding.
+    /// Formats values with decimals in failure message. Includes error message into revert string on failure.
+    #[cheatcode(group = Testing, safety = Safe)]
+    function assertApproxEqRelWad(uint256 left, uint256 right, uint256 maxRelDelta, string calldata error) external pure;
+
+    /// Compares two `int256` WAD values, 18 decimal fixed point numbers. Expects their difference relative to `right` to be
+    /// less than or equal to `maxRelDelta`, a WAD where 1e18 == 100%. Computed without rounding.
+    /// Formats values with decimals in failure message.
+    #[cheatcode(group = Testing, safety = Safe)]
+    function assertApproxEqRelWad(int256 left, int256 right, uint256 maxRelDelta) external pure;
+
+    /// Compares two `int256` WAD values, 18 decimal fixed point numbers. Expects their difference relative to `right` to be
+    /// less than or equal to `maxRelDelta`, a WAD where 1e18 == 100%. Computed without rounding.
+    /// Formats values with decimals in failure message. Includes error message into revert string on failure.
+    #[cheatcode(group = Testing, safety = Safe)]
+    function assertApproxEqRelWad(int256 left, int256 right, uint256 maxRelDelta, string calldata error) external pure;
+
+    /// Compares two `uint256` RAY values, 27 decimal fixed point numbers. Expects their difference relative to `right` to be
+    /// less than or equal to `maxRelDelta`, a RAY where 1e27 == 100%. Computed without rounding.
+    /// Formats values with decimals in failure message.
+    #[cheatcode(group = Testing, safety = Safe)]
+    function assertApproxEqRelRay(uint256 left, uint256 right, uint256 maxRelDelta) external pure;
+
+    /// Compares two `uint256` RAY values, 27 decimal fixed point numbers. Expects their difference relative to `right` to be
+    /// less than or equal to `maxRelDelta`, a RAY where 1e27 == 100%. Computed without rounding.
+    /// Formats values with decimals in failure message. Includes error message into revert string on failure.
+    #[cheatcode(group = Testing, safety = Safe)]
+    function assertApproxEqRelRay(uint256 left, uint256 right, uint256 maxRelDelta, string calldata error) external pure;
+
+    /// Compares two `int256` RAY values, 27 decimal fixed point numbers. Expects their difference relative to `right` to be
+    /// less than or equal to `maxRelDelta`, a RAY where 1e27 == 100%. Computed without rounding.
+    /// Formats values with decimals in failure message.
+    #[cheatcode(group = Testing, safety = Safe)]
+    function assertApproxEqRelRay(int256 left, int256 right, uint256 maxRelDelta) external pure;
+
+    /// Compares two `int256` RAY values, 27 decimal fixed point numbers. Expects their difference relative to `right` to be
+    /// less than or equal to `maxRelDelta`, a RAY where 1e27 == 100%. Computed without rounding.
+    /// Formats values with decimals in failure message. Includes error message into revert string on failure.
+    #[cheatcode(group = Testing, safety = Safe)]
+    function assertApproxEqRelRay(int256 left, int256 right, uint256 maxRelDelta, string calldata error) external pure;
+
     // ======== OS and Filesystem ========
 
     // -------- Metadata --------
@@ -1733,6 +2011,40 @@ interface Vm {
     #[cheatcode(group = Scripting)]
     function stopBroadcast() external;
 
+    /// Designates the next broadcasted call as an EIP-4844 blob transaction carrying the given data.
+    ///
+    /// The data is encoded into blobs, and the blob sidecar with its commitments and proofs is
+    /// attached to the transaction. Requires the Cancun hard fork.
+    #[cheatcode(group = Scripting)]
+    function attachBlob(bytes calldata data) external;
+
+    /// Sets the gas limit of the next broadcasted transaction, instead of estimating it.
+    #[cheatcode(group = Scripting)]
+    function txGasLimit(uint64 gasLimit) external;
+
+    /// Sets the gas price of the next broadcasted transaction, which is sent as a legacy transaction
+    /// unless a type is set with `txType`.
+    #[cheatcode(group = Scripting)]
+    function txFees(uint256 gasPrice) external;
+
+    /// Sets the EIP-1559 fees of the next broadcasted transaction, overriding the ones estimated or
+    /// passed on the command line.
+    #[cheatcode(group = Scripting)]
+    function txFees(uint256 maxFeePerGas, uint256 maxPriorityFeePerGas) external;
+
+    /// Sets the type of the next broadcasted transaction: 0 for legacy, 1 for EIP-2930 and 2 for
+    /// EIP-1559 transactions, or a custom type registered for the chain.
+    #[cheatcode(group = Scripting)]
+    function txType(uint8 transactionType) external;
+
+    /// Returns the address of the latest deployment of the given contract by one of the scripts
+    /// this script depends on.
+    ///
+    /// Dependencies are declared with a `dependsOn() returns (string[] memory)` function on the
+    /// script, and are executed before it in the same invocation.
+    #[cheatcode(group = Scripting)]
+    function getDeployment(string calldata contractName) external view returns (address deployedAddress);
+
     // ======== Utilities ========
 
     // -------- Strings --------
@@ -1877,6 +2189,28 @@ interface Vm {
     #[cheatcode(group = Json)]
     function parseJsonKeys(string calldata json, string calldata key) external pure returns (string[] memory keys);
 
+    /// Parses a string of JSON data and coerces it to the type given by `typeDescription`, returning its ABI encoding.
+    ///
+    /// `typeDescription` is either a Solidity type, or an EIP-712 `encodeType` string describing a struct and the
+    /// structs it references, e.g. `Foo(uint256 a,Bar b)Bar(address c)`.
+    #[cheatcode(group = Json)]
+    function parseJsonType(string calldata json, string calldata typeDescription)
+        external
+        pure
+        returns (bytes memory);
+    /// Parses a string of JSON data at `key` and coerces it to the type given by `typeDescription`.
+    #[cheatcode(group = Json)]
+    function parseJsonType(string calldata json, string calldata key, string calldata typeDescription)
+        external
+        pure
+        returns (bytes memory);
+    /// Parses a string of JSON data at `key` and coerces it to an array of the type given by `typeDescription`.
+    #[cheatcode(group = Json)]
+    function parseJsonTypeArray(string calldata json, string calldata key, string calldata typeDescription)
+        external
+        pure
+        returns (bytes memory);
+
     // -------- Writing --------
 
     // NOTE: Please read https://book.getfoundry.sh/cheatcodes/serialize-json to understand how
@@ -1964,6 +2298,23 @@ interface Vm {
         external
         returns (string memory json);
 
+    /// Serializes the ABI-encoded `value` of the type given by `typeDescription` to JSON.
+    ///
+    /// See `parseJsonType` for the format of `typeDescription`.
+    #[cheatcode(group = Json)]
+    function serializeJsonType(string calldata typeDescription, bytes calldata value)
+        external
+        pure
+        returns (string memory json);
+    /// See `serializeJson` and `serializeJsonType`.
+    #[cheatcode(group = Json)]
+    function serializeJsonType(
+        string calldata objectKey,
+        string calldata valueKey,
+        string calldata typeDescription,
+        bytes calldata value
+    ) external returns (string memory json);
+
     // NOTE: Please read https://book.getfoundry.sh/cheatcodes/write-json to understand how
     // to use the JSON writing cheats.
 
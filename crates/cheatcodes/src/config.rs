@@ -1,16 +1,16 @@
 use super::Result;
-use crate::{script::ScriptWallets, Vm::Rpc};
-use alloy_primitives::Address;
+use crate::{evm::Fixtures, script::ScriptWallets, Vm::Rpc};
+use alloy_primitives::{Address, Bytes, U256};
 use foundry_common::{fs::normalize_path, ContractsByArtifact};
 use foundry_compilers::{utils::canonicalize, ProjectPathsConfig};
 use foundry_config::{
-    cache::StorageCachingConfig, fs_permissions::FsAccessKind, Config, FsPermissions,
-    ResolvedRpcEndpoints,
+    cache::StorageCachingConfig, fs_permissions::FsAccessKind, CheatcodeCapability, Config,
+    FsPermissions, ResolvedRpcEndpoints,
 };
-use foundry_evm_core::opts::EvmOpts;
+use foundry_evm_core::{opts::EvmOpts, rng::RandomSource};
 use semver::Version;
 use std::{
-    collections::HashMap,
+    collections::{BTreeSet, HashMap},
     path::{Path, PathBuf},
     time::Duration,
 };
@@ -54,6 +54,41 @@ pub struct CheatsConfig {
     pub running_version: Option<Version>,
     /// Whether to enable legacy (non-reverting) assertions.
     pub assertions_revert: bool,
+    /// Whether the hashes of blocks older than the last 256 ones are served, see
+    /// `historical_block_hashes` in the config.
+    pub historical_block_hashes: bool,
+    /// Whether host-dependent cheatcodes are forbidden, see `forge test --deterministic`.
+    pub deterministic: bool,
+    /// The seed of the run, which the values of the `random*` cheatcodes are derived from.
+    pub seed: Option<U256>,
+    /// Contracts whose creation code is substituted, see `test_overrides` in the config.
+    pub create_overrides: Vec<CreateOverride>,
+    /// The fixtures shared by the test contracts of the run.
+    pub fixtures: Fixtures,
+    /// The capabilities of dangerous cheatcodes granted to the running test contract by
+    /// `cheatcode_permissions` in the config. All capabilities are granted if `None`.
+    pub granted_capabilities: Option<BTreeSet<CheatcodeCapability>>,
+}
+
+/// A contract whose creation code is substituted by the creation code of another contract.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CreateOverride {
+    /// The creation code of the original contract.
+    pub original: Bytes,
+    /// The creation code of the contract that is deployed in its place.
+    pub replacement: Bytes,
+}
+
+impl CreateOverride {
+    /// Returns the init code with the creation code of the original contract replaced, keeping
+    /// the constructor arguments, or `None` if the init code does not create the original.
+    pub fn apply(&self, init_code: &[u8]) -> Option<Bytes> {
+        if self.original.is_empty() {
+            return None
+        }
+        let args = init_code.strip_prefix(self.original.as_ref())?;
+        Some([self.replacement.as_ref(), args].concat().into())
+    }
 }
 
 impl CheatsConfig {
@@ -93,6 +128,15 @@ impl CheatsConfig {
             available_artifacts,
             running_version,
             assertions_revert: config.assertions_revert,
+            historical_block_hashes: config.historical_block_hashes,
+            deterministic: false,
+            seed: config
+                .fuzz
+                .seed
+                .or_else(|| RandomSource::from_env().ok().flatten().map(|source| source.seed())),
+            create_overrides: Vec::new(),
+            fixtures: Default::default(),
+            granted_capabilities: None,
         }
     }
 
@@ -120,12 +164,18 @@ impl CheatsConfig {
 
     /// Returns an error if no access is granted to access `path`, See also [Self::is_path_allowed]
     ///
+    /// Writes also require the [CheatcodeCapability::FsWrite] capability, see
+    /// [Self::ensure_capability].
+    ///
     /// Returns the normalized version of `path`, see [`CheatsConfig::normalized_path`]
     pub fn ensure_path_allowed(
         &self,
         path: impl AsRef<Path>,
         kind: FsAccessKind,
     ) -> Result<PathBuf> {
+        if kind == FsAccessKind::Write {
+            self.ensure_capability(CheatcodeCapability::FsWrite)?;
+        }
         let path = path.as_ref();
         let normalized = self.normalized_path(path);
         ensure!(
@@ -136,6 +186,19 @@ impl CheatsConfig {
         Ok(normalized)
     }
 
+    /// Returns an error if `cheatcode_permissions` doesn't grant the capability to the running test
+    /// contract.
+    pub fn ensure_capability(&self, capability: CheatcodeCapability) -> Result<()> {
+        if let Some(granted) = &self.granted_capabilities {
+            ensure!(
+                granted.contains(&capability),
+                "the `{capability}` capability is not granted to this test contract; \
+                 allow it with a `cheatcode_permissions` rule matching the contract in foundry.toml"
+            );
+        }
+        Ok(())
+    }
+
     /// Returns true if the given `path` is the project's foundry.toml file
     ///
     /// Note: this should be called with normalized path
@@ -221,6 +284,12 @@ impl Default for CheatsConfig {
             available_artifacts: Default::default(),
             running_version: Default::default(),
             assertions_revert: true,
+            historical_block_hashes: false,
+            deterministic: false,
+            seed: None,
+            create_overrides: Vec::new(),
+            fixtures: Default::default(),
+            granted_capabilities: None,
         }
     }
 }
@@ -253,6 +322,21 @@ mod tests {
         assert!(config.ensure_path_allowed("../../root/t.txt", FsAccessKind::Write).is_err());
     }
 
+    #[test]
+    fn test_granted_capabilities() {
+        let root = "/my/project/root/";
+        let mut config = config(root, FsPermissions::new(vec![PathPermission::read_write("./")]));
+        config.granted_capabilities = Some(BTreeSet::from([CheatcodeCapability::Http]));
+
+        assert!(config.ensure_capability(CheatcodeCapability::Http).is_ok());
+        assert!(config.ensure_capability(CheatcodeCapability::Ffi).is_err());
+        assert!(config.ensure_path_allowed("./t.txt", FsAccessKind::Read).is_ok());
+        assert!(config.ensure_path_allowed("./t.txt", FsAccessKind::Write).is_err());
+
+        config.granted_capabilities = None;
+        assert!(config.ensure_capability(CheatcodeCapability::Ffi).is_ok());
+    }
+
     #[test]
     fn test_is_foundry_toml() {
         let root = "/my/project/root/";
@@ -267,4 +351,20 @@ mod tests {
         let f = format!("{root}lib/other/foundry.toml");
         assert!(!config.is_foundry_toml(f));
     }
+
+    #[test]
+    fn test_create_override() {
+        let create_override = CreateOverride {
+            original: Bytes::from_static(&[0x60, 0x80]),
+            replacement: Bytes::from_static(&[0x61, 0x01, 0x00]),
+        };
+        assert_eq!(
+            create_override.apply(&[0x60, 0x80, 0x2a]),
+            Some(Bytes::from_static(&[0x61, 0x01, 0x00, 0x2a]))
+        );
+        assert_eq!(create_override.apply(&[0x60, 0x40]), None);
+
+        let empty = CreateOverride { original: Bytes::new(), ..create_override };
+        assert_eq!(empty.apply(&[0x60, 0x80]), None);
+    }
 }
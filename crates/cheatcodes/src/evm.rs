@@ -2,12 +2,17 @@
 
 use crate::{Cheatcode, Cheatcodes, CheatsCtxt, Result, Vm::*};
 use alloy_genesis::{Genesis, GenesisAccount};
-use alloy_primitives::{Address, Bytes, B256, U256};
+use alloy_primitives::{keccak256, Address, Bytes, B256, U256};
+use alloy_signer::SignerSync;
 use alloy_sol_types::SolValue;
 use foundry_common::fs::{read_json_file, write_json_file};
 use foundry_evm_core::{
     backend::{DatabaseExt, RevertSnapshotAction},
-    constants::{CALLER, CHEATCODE_ADDRESS, HARDHAT_CONSOLE_ADDRESS, TEST_CONTRACT_ADDRESS},
+    constants::{
+        CALLER, CHEATCODE_ADDRESS, HARDHAT_CONSOLE_ADDRESS, HISTORY_SERVE_WINDOW,
+        TEST_CONTRACT_ADDRESS,
+    },
+    eip7702::{apply_authorization, Authorization, SignedAuthorization},
 };
 use revm::{
     primitives::{Account, Bytecode, SpecId, KECCAK_EMPTY},
@@ -18,6 +23,9 @@ use std::{
     path::Path,
 };
 
+mod fixture;
+pub use fixture::{Fixture, Fixtures};
+
 mod fork;
 pub(crate) mod mapping;
 pub(crate) mod mock;
@@ -58,6 +66,52 @@ pub struct DealRecord {
     pub new_balance: U256,
 }
 
+/// The gas target of a block, in basis points of its gas limit, as per EIP-1559.
+const GAS_TARGET_BPS: u64 = 5_000;
+
+/// The maximum number of blocks over which the base fee is updated by the `mine` cheatcodes. The
+/// base fee reaches a fixed point or saturates well before that.
+const MAX_BASE_FEE_UPDATES: u64 = 10_000;
+
+/// The number of most recent blocks whose hash is available with `BLOCKHASH`.
+const BLOCK_HASH_HISTORY: u64 = 256;
+
+/// How the `mine` cheatcodes advance the block environment, configured with `setMineSchedule`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MineSchedule {
+    /// The number of seconds between blocks.
+    pub block_time: U256,
+    /// The share of the gas limit used by each block, in basis points.
+    pub gas_used_bps: U256,
+}
+
+impl Default for MineSchedule {
+    fn default() -> Self {
+        Self { block_time: U256::from(12), gas_used_bps: U256::from(GAS_TARGET_BPS) }
+    }
+}
+
+impl MineSchedule {
+    /// Returns the base fee after `blocks` blocks, starting with `base_fee`.
+    fn base_fee_after(&self, mut base_fee: U256, blocks: U256) -> U256 {
+        let target = U256::from(GAS_TARGET_BPS);
+        let denominator = target * U256::from(8);
+        for _ in 0..blocks.saturating_to::<u64>().min(MAX_BASE_FEE_UPDATES) {
+            let next = if self.gas_used_bps > target {
+                let delta = base_fee.saturating_mul(self.gas_used_bps - target) / denominator;
+                base_fee.saturating_add(delta.max(U256::from(1)))
+            } else {
+                base_fee - base_fee.saturating_mul(target - self.gas_used_bps) / denominator
+            };
+            if next == base_fee {
+                break
+            }
+            base_fee = next;
+        }
+        base_fee
+    }
+}
+
 impl Cheatcode for addrCall {
     fn apply(&self, _state: &mut Cheatcodes) -> Result {
         let Self { privateKey } = self;
@@ -83,6 +137,15 @@ impl Cheatcode for loadCall {
     }
 }
 
+impl Cheatcode for tloadCall {
+    fn apply_stateful<DB: DatabaseExt>(&self, ccx: &mut CheatsCtxt<DB>) -> Result {
+        let Self { target, slot } = *self;
+        ensure_not_precompile!(&target, ccx);
+        let val = ccx.ecx.journaled_state.tload(target, slot.into());
+        Ok(val.abi_encode())
+    }
+}
+
 impl Cheatcode for loadAllocsCall {
     fn apply_stateful<DB: DatabaseExt>(&self, ccx: &mut CheatsCtxt<DB>) -> Result {
         let Self { pathToAllocsJson } = self;
@@ -113,48 +176,52 @@ impl Cheatcode for dumpStateCall {
     fn apply_stateful<DB: DatabaseExt>(&self, ccx: &mut CheatsCtxt<DB>) -> Result {
         let Self { pathToStateJson } = self;
         let path = Path::new(pathToStateJson);
-
-        // Do not include system account or empty accounts in the dump.
-        let skip = |key: &Address, val: &Account| {
-            key == &CHEATCODE_ADDRESS ||
-                key == &CALLER ||
-                key == &HARDHAT_CONSOLE_ADDRESS ||
-                key == &TEST_CONTRACT_ADDRESS ||
-                key == &ccx.caller ||
-                key == &ccx.state.config.evm_opts.sender ||
-                val.is_empty()
-        };
-
-        let alloc = ccx
-            .ecx
-            .journaled_state
-            .state()
-            .iter_mut()
-            .filter(|(key, val)| !skip(key, val))
-            .map(|(key, val)| {
-                (
-                    key,
-                    GenesisAccount {
-                        nonce: Some(val.info.nonce),
-                        balance: val.info.balance,
-                        code: val.info.code.as_ref().map(|o| o.original_bytes()),
-                        storage: Some(
-                            val.storage
-                                .iter()
-                                .map(|(k, v)| (B256::from(*k), B256::from(v.present_value())))
-                                .collect(),
-                        ),
-                        private_key: None,
-                    },
-                )
-            })
-            .collect::<HashMap<_, _>>();
-
-        write_json_file(path, &alloc)?;
+        write_json_file(path, &dump_allocs(ccx))?;
         Ok(Default::default())
     }
 }
 
+/// Returns the accounts changed in the current transaction as genesis `allocs`.
+///
+/// System accounts, the caller and empty accounts are not included.
+pub(crate) fn dump_allocs<DB: DatabaseExt>(
+    ccx: &mut CheatsCtxt<DB>,
+) -> BTreeMap<Address, GenesisAccount> {
+    let skip = |key: &Address, val: &Account| {
+        key == &CHEATCODE_ADDRESS ||
+            key == &CALLER ||
+            key == &HARDHAT_CONSOLE_ADDRESS ||
+            key == &TEST_CONTRACT_ADDRESS ||
+            key == &ccx.caller ||
+            key == &ccx.state.config.evm_opts.sender ||
+            val.is_empty()
+    };
+
+    ccx.ecx
+        .journaled_state
+        .state()
+        .iter()
+        .filter(|(key, val)| !skip(key, val))
+        .map(|(key, val)| {
+            (
+                *key,
+                GenesisAccount {
+                    nonce: Some(val.info.nonce),
+                    balance: val.info.balance,
+                    code: val.info.code.as_ref().map(|o| o.original_bytes()),
+                    storage: Some(
+                        val.storage
+                            .iter()
+                            .map(|(k, v)| (B256::from(*k), B256::from(v.present_value())))
+                            .collect(),
+                    ),
+                    private_key: None,
+                },
+            )
+        })
+        .collect()
+}
+
 impl Cheatcode for sign_0Call {
     fn apply_stateful<DB: DatabaseExt>(&self, _: &mut CheatsCtxt<DB>) -> Result {
         let Self { privateKey, digest } = self;
@@ -183,6 +250,30 @@ impl Cheatcode for signP256Call {
     }
 }
 
+impl Cheatcode for signDelegationCall {
+    fn apply_stateful<DB: DatabaseExt>(&self, ccx: &mut CheatsCtxt<DB>) -> Result {
+        let Self { implementation, privateKey } = self;
+        Ok(sign_delegation(ccx, *implementation, privateKey)?.abi_encode())
+    }
+}
+
+impl Cheatcode for attachDelegationCall {
+    fn apply_stateful<DB: DatabaseExt>(&self, ccx: &mut CheatsCtxt<DB>) -> Result {
+        let Self { signedDelegation } = self;
+        attach_delegation(ccx, signedDelegation)?;
+        Ok(Default::default())
+    }
+}
+
+impl Cheatcode for signAndAttachDelegationCall {
+    fn apply_stateful<DB: DatabaseExt>(&self, ccx: &mut CheatsCtxt<DB>) -> Result {
+        let Self { implementation, privateKey } = self;
+        let delegation = sign_delegation(ccx, *implementation, privateKey)?;
+        attach_delegation(ccx, &delegation)?;
+        Ok(delegation.abi_encode())
+    }
+}
+
 impl Cheatcode for recordCall {
     fn apply(&self, state: &mut Cheatcodes) -> Result {
         let Self {} = self;
@@ -191,6 +282,27 @@ impl Cheatcode for recordCall {
     }
 }
 
+impl Cheatcode for recordTransientCall {
+    fn apply(&self, state: &mut Cheatcodes) -> Result {
+        let Self {} = self;
+        state.transient_writes = Some(Default::default());
+        Ok(Default::default())
+    }
+}
+
+impl Cheatcode for getTransientWritesCall {
+    fn apply(&self, state: &mut Cheatcodes) -> Result {
+        let Self { target } = self;
+        let writes = state
+            .transient_writes
+            .as_ref()
+            .and_then(|writes| writes.get(target))
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        Ok(writes.abi_encode())
+    }
+}
+
 impl Cheatcode for accessesCall {
     fn apply(&self, state: &mut Cheatcodes) -> Result {
         let Self { target } = *self;
@@ -381,6 +493,34 @@ impl Cheatcode for getBlockTimestampCall {
     }
 }
 
+impl Cheatcode for mineCall {
+    fn apply_stateful<DB: DatabaseExt>(&self, ccx: &mut CheatsCtxt<DB>) -> Result {
+        let Self { blocks } = self;
+        mine(ccx, *blocks)
+    }
+}
+
+impl Cheatcode for mineToCall {
+    fn apply_stateful<DB: DatabaseExt>(&self, ccx: &mut CheatsCtxt<DB>) -> Result {
+        let Self { blockNumber } = self;
+        let number = ccx.ecx.env.block.number;
+        ensure!(
+            *blockNumber >= number,
+            "cannot mine to block {blockNumber} before the current block {number}; use `roll` instead"
+        );
+        mine(ccx, *blockNumber - number)
+    }
+}
+
+impl Cheatcode for setMineScheduleCall {
+    fn apply(&self, state: &mut Cheatcodes) -> Result {
+        let Self { blockTime, gasUsedBps } = self;
+        ensure!(*gasUsedBps <= U256::from(10_000), "gas used must be at most 10000 basis points");
+        state.mine_schedule = MineSchedule { block_time: *blockTime, gas_used_bps: *gasUsedBps };
+        Ok(Default::default())
+    }
+}
+
 impl Cheatcode for blobBaseFeeCall {
     fn apply_stateful<DB: DatabaseExt>(&self, ccx: &mut CheatsCtxt<DB>) -> Result {
         let Self { newBlobBaseFee } = self;
@@ -474,6 +614,15 @@ impl Cheatcode for storeCall {
     }
 }
 
+impl Cheatcode for tstoreCall {
+    fn apply_stateful<DB: DatabaseExt>(&self, ccx: &mut CheatsCtxt<DB>) -> Result {
+        let Self { target, slot, value } = *self;
+        ensure_not_precompile!(&target, ccx);
+        ccx.ecx.journaled_state.tstore(target, slot.into(), value.into());
+        Ok(Default::default())
+    }
+}
+
 impl Cheatcode for coolCall {
     fn apply_stateful<DB: DatabaseExt>(&self, ccx: &mut CheatsCtxt<DB>) -> Result {
         let Self { target } = self;
@@ -572,6 +721,82 @@ pub(super) fn get_nonce<DB: DatabaseExt>(ccx: &mut CheatsCtxt<DB>, address: &Add
     Ok(account.info.nonce.abi_encode())
 }
 
+/// Advances the block environment by `blocks` blocks as per the mine schedule.
+///
+/// The skipped blocks that are still available with `BLOCKHASH` are given the same hash as unknown
+/// blocks of the in-memory database, `keccak256(number.to_string())`, so that they also have one in
+/// forking mode. With `historical_block_hashes`, the blocks of the EIP-2935 history window are
+/// given one instead.
+fn mine<DB: DatabaseExt>(ccx: &mut CheatsCtxt<DB>, blocks: U256) -> Result {
+    let schedule = ccx.state.mine_schedule;
+    let history = if ccx.state.config.historical_block_hashes {
+        HISTORY_SERVE_WINDOW
+    } else {
+        BLOCK_HASH_HISTORY
+    };
+    let block = &mut ccx.ecx.env.block;
+    let start = block.number;
+    let end = start.saturating_add(blocks);
+    block.number = end;
+    block.timestamp = block.timestamp.saturating_add(blocks.saturating_mul(schedule.block_time));
+    block.basefee = schedule.base_fee_after(block.basefee, blocks);
+
+    let mut number =
+        start.saturating_add(U256::from(1)).max(end.saturating_sub(U256::from(history)));
+    while number < end {
+        ccx.ecx.db.set_block_hash(number, keccak256(number.to_string()));
+        number += U256::from(1);
+    }
+    Ok(Default::default())
+}
+
+/// Signs an EIP-7702 authorization delegating the account of `private_key` to `implementation`,
+/// for the current chain and nonce of the account.
+fn sign_delegation<DB: DatabaseExt>(
+    ccx: &mut CheatsCtxt<DB>,
+    implementation: Address,
+    private_key: &U256,
+) -> Result<SignedDelegation> {
+    let wallet = super::utils::parse_wallet(private_key)?;
+    let (account, _) = ccx.ecx.journaled_state.load_account(wallet.address(), &mut ccx.ecx.db)?;
+    let authorization = Authorization {
+        chain_id: U256::from(ccx.ecx.env.cfg.chain_id),
+        address: implementation,
+        nonce: account.info.nonce,
+    };
+    let signature = wallet.sign_hash_sync(&authorization.signature_hash())?;
+    Ok(SignedDelegation {
+        v: signature.v().y_parity() as u8,
+        r: signature.r().into(),
+        s: signature.s().into(),
+        nonce: authorization.nonce,
+        implementation,
+    })
+}
+
+/// Applies a signed EIP-7702 authorization for the current chain.
+fn attach_delegation<DB: DatabaseExt>(
+    ccx: &mut CheatsCtxt<DB>,
+    delegation: &SignedDelegation,
+) -> Result<()> {
+    let SignedDelegation { v, r, s, nonce, implementation } = *delegation;
+    let authorization = SignedAuthorization {
+        chain_id: U256::from(ccx.ecx.env.cfg.chain_id),
+        address: implementation,
+        nonce,
+        // Accept both the y-parity and the legacy `v` returned by `vm.sign`.
+        y_parity: if v >= 27 { v - 27 } else { v },
+        r: U256::from_be_bytes(r.0),
+        s: U256::from_be_bytes(s.0),
+    };
+    ensure!(
+        apply_authorization(ccx.ecx, &authorization)?.is_some(),
+        "invalid delegation: the signature, the nonce or the code of the signing account does not \
+         allow delegating to {implementation}"
+    );
+    Ok(())
+}
+
 /// Reads the current caller information and returns the current [CallerMode], `msg.sender` and
 /// `tx.origin`.
 ///
@@ -0,0 +1,87 @@
+use super::{dump_allocs, journaled_account};
+use crate::{Cheatcode, CheatsCtxt, DatabaseExt, Result, Vm::*};
+use alloy_genesis::GenesisAccount;
+use alloy_primitives::{keccak256, Address, Bytes, B256};
+use alloy_sol_types::SolValue;
+use parking_lot::Mutex;
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+};
+
+/// The fixtures saved with `vm.saveFixture`, shared by all test contracts of a run.
+///
+/// Fixtures are keyed by the hash of their name. Test contracts running in parallel may both set
+/// up a fixture that isn't saved yet, in which case the last one saved is kept.
+#[derive(Clone, Debug, Default)]
+pub struct Fixtures(Arc<Mutex<HashMap<B256, Arc<Fixture>>>>);
+
+impl Fixtures {
+    /// Returns the fixture with the given name, if it was saved.
+    pub fn get(&self, name: &str) -> Option<Arc<Fixture>> {
+        self.0.lock().get(&keccak256(name)).cloned()
+    }
+
+    /// Saves the fixture with the given name, replacing any previous one.
+    pub fn insert(&self, name: &str, fixture: Fixture) {
+        self.0.lock().insert(keccak256(name), Arc::new(fixture));
+    }
+}
+
+/// The state saved by a fixture.
+#[derive(Clone, Debug, Default)]
+pub struct Fixture {
+    /// The accounts changed while setting up the fixture.
+    pub allocs: BTreeMap<Address, GenesisAccount>,
+    /// The data saved with the fixture.
+    pub data: Bytes,
+    /// The nonce of the contract that set up the fixture, so that the contracts restoring it don't
+    /// deploy to the addresses of the contracts it deployed.
+    pub caller_nonce: u64,
+}
+
+impl Cheatcode for saveFixtureCall {
+    fn apply_stateful<DB: DatabaseExt>(&self, ccx: &mut CheatsCtxt<DB>) -> Result {
+        let Self { name, data } = self;
+        let allocs = dump_allocs(ccx);
+        let caller_nonce = journaled_account(ccx.ecx, ccx.caller)?.info.nonce;
+        ccx.state
+            .config
+            .fixtures
+            .insert(name, Fixture { allocs, data: data.clone(), caller_nonce });
+        Ok(Default::default())
+    }
+}
+
+impl Cheatcode for loadFixtureCall {
+    fn apply_stateful<DB: DatabaseExt>(&self, ccx: &mut CheatsCtxt<DB>) -> Result {
+        let Self { name } = self;
+        let Some(fixture) = ccx.state.config.fixtures.get(name) else {
+            return Ok((false, Bytes::new()).abi_encode_params())
+        };
+        ccx.ecx
+            .db
+            .load_allocs(&fixture.allocs, &mut ccx.ecx.journaled_state)
+            .map_err(|e| fmt_err!("failed to load fixture {name}: {e}"))?;
+        let caller = journaled_account(ccx.ecx, ccx.caller)?;
+        caller.info.nonce = caller.info.nonce.max(fixture.caller_nonce);
+        Ok((true, fixture.data.clone()).abi_encode_params())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shares_fixtures() {
+        let fixtures = Fixtures::default();
+        let shared = fixtures.clone();
+        assert!(fixtures.get("protocol").is_none());
+
+        let fixture = Fixture { data: Bytes::from_static(&[1]), ..Default::default() };
+        shared.insert("protocol", fixture);
+        assert_eq!(fixtures.get("protocol").unwrap().data, Bytes::from_static(&[1]));
+        assert!(fixtures.get("other").is_none());
+    }
+}
@@ -356,6 +356,8 @@ fn create_fork_request<DB: DatabaseExt>(
     let url = ccx.state.config.rpc_url(url_or_alias)?;
     let mut evm_opts = ccx.state.config.evm_opts.clone();
     evm_opts.fork_block_number = block;
+    // the state snapshot only applies to the fork configured for the run
+    evm_opts.fork_state = None;
     let fork = CreateFork {
         enable_caching: !ccx.state.config.no_storage_caching &&
             ccx.state.config.rpc_storage_caching.enable_for_endpoint(&url),
@@ -1,7 +1,15 @@
 use crate::{Cheatcode, Cheatcodes, CheatsCtxt, DatabaseExt, Result, Vm::*};
 use alloy_primitives::{Address, Bytes, U256};
-use revm::{interpreter::InstructionResult, primitives::Bytecode};
-use std::cmp::Ordering;
+use revm::{
+    interpreter::InstructionResult,
+    precompile::{PrecompileSpecId, Precompiles},
+    primitives::{
+        Bytecode, Env, Precompile, PrecompileOutput, PrecompileResult, SpecId,
+        StatefulPrecompileMut,
+    },
+    ContextPrecompiles,
+};
+use std::{cmp::Ordering, collections::HashMap};
 
 /// Mocked call data.
 #[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
@@ -38,10 +46,59 @@ impl Ord for MockCallDataContext {
     }
 }
 
+/// A precompile with mocked outputs for some inputs.
+///
+/// Calls with any other input are executed by the original precompile.
+#[derive(Clone)]
+struct MockedPrecompile {
+    original: Precompile,
+    /// The mocked outputs, by input
+    mocks: HashMap<Bytes, Bytes>,
+}
+
+impl StatefulPrecompileMut for MockedPrecompile {
+    fn call_mut(&mut self, bytes: &Bytes, gas_limit: u64, env: &Env) -> PrecompileResult {
+        match self.mocks.get(bytes) {
+            Some(output) => Ok(PrecompileOutput::new(0, output.clone())),
+            None => self.original.call(bytes, gas_limit, env),
+        }
+    }
+}
+
+/// Returns the precompile of the given spec at `address`, if any.
+fn spec_precompile(spec_id: SpecId, address: &Address) -> Option<Precompile> {
+    Precompiles::new(PrecompileSpecId::from_spec_id(spec_id)).get(address).cloned()
+}
+
+/// Replaces the precompile at `address` with one returning the given mocked outputs, or restores
+/// the original precompile if there are no mocks.
+///
+/// The precompiles are loaded anew for every transaction, so this has to be applied before each
+/// call to a mocked precompile. Only the precompiles of the spec can be mocked, see
+/// [`mockPrecompileCall`].
+pub(crate) fn apply_precompile_mocks<DB: DatabaseExt>(
+    precompiles: &mut ContextPrecompiles<DB>,
+    spec_id: SpecId,
+    address: Address,
+    mocks: &HashMap<Bytes, Bytes>,
+) {
+    let Some(original) = spec_precompile(spec_id, &address) else { return };
+    let precompile = if mocks.is_empty() {
+        original
+    } else {
+        Precompile::StatefulMut(Box::new(MockedPrecompile { original, mocks: mocks.clone() }))
+    };
+    precompiles.extend([(address, precompile.into())]);
+}
+
 impl Cheatcode for clearMockedCallsCall {
-    fn apply(&self, state: &mut Cheatcodes) -> Result {
+    fn apply_stateful<DB: DatabaseExt>(&self, ccx: &mut CheatsCtxt<DB>) -> Result {
         let Self {} = self;
-        state.mocked_calls = Default::default();
+        ccx.state.mocked_calls = Default::default();
+        let spec_id = ccx.ecx.spec_id();
+        for (address, _) in std::mem::take(&mut ccx.state.mocked_precompiles) {
+            apply_precompile_mocks(ccx.precompiles, spec_id, address, &HashMap::new());
+        }
         Ok(Default::default())
     }
 }
@@ -89,6 +146,25 @@ impl Cheatcode for mockCallRevert_1Call {
     }
 }
 
+impl Cheatcode for mockPrecompileCall {
+    fn apply_stateful<DB: DatabaseExt>(&self, ccx: &mut CheatsCtxt<DB>) -> Result {
+        let Self { precompile, input, output } = self;
+        if !ccx.is_precompile(precompile) {
+            bail!("{precompile} is not a precompile");
+        }
+        // The mocked precompile falls back to the precompile of the spec, so custom and chain
+        // specific precompiles can't be mocked.
+        let spec_id = ccx.ecx.spec_id();
+        if spec_precompile(spec_id, precompile).is_none() {
+            bail!("{precompile} is not a precompile of the EVM spec and can't be mocked");
+        }
+        let mocks = ccx.state.mocked_precompiles.entry(*precompile).or_default();
+        mocks.insert(input.clone(), output.clone());
+        apply_precompile_mocks(ccx.precompiles, spec_id, *precompile, mocks);
+        Ok(Default::default())
+    }
+}
+
 #[allow(clippy::ptr_arg)] // Not public API, doesn't matter
 fn mock_call(
     state: &mut Cheatcodes,
@@ -81,8 +81,12 @@ impl Cheatcode for projectRootCall {
 }
 
 impl Cheatcode for unixTimeCall {
-    fn apply(&self, _state: &mut Cheatcodes) -> Result {
+    fn apply_stateful<DB: DatabaseExt>(&self, ccx: &mut CheatsCtxt<DB>) -> Result {
         let Self {} = self;
+        // use the block timestamp so results don't depend on when the test is run
+        if ccx.state.config.deterministic {
+            return Ok((ccx.ecx.env.block.timestamp * U256::from(1000)).abi_encode())
+        }
         let difference = SystemTime::now()
             .duration_since(UNIX_EPOCH)
             .map_err(|e| fmt_err!("failed getting Unix timestamp: {e}"))?;
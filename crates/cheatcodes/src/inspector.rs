@@ -3,12 +3,12 @@
 use crate::{
     evm::{
         mapping::{self, MappingSlots},
-        mock::{MockCallDataContext, MockCallReturnData},
+        mock::{self, MockCallDataContext, MockCallReturnData},
         prank::Prank,
-        DealRecord, RecordAccess,
+        DealRecord, MineSchedule, RecordAccess,
     },
     inspector::utils::CommonCreateInput,
-    script::{Broadcast, ScriptWallets},
+    script::{Broadcast, ScriptWallets, TxOverrides},
     test::expect::{
         self, ExpectedCallData, ExpectedCallTracker, ExpectedCallType, ExpectedEmit,
         ExpectedRevert, ExpectedRevertKind,
@@ -16,20 +16,23 @@ use crate::{
     CheatsConfig, CheatsCtxt, DynCheatcode, Error, Result, Vm,
     Vm::AccountAccess,
 };
+use alloy_consensus::BlobTransactionSidecar;
 use alloy_primitives::{hex, Address, Bytes, Log, TxKind, B256, U256};
 use alloy_rpc_types::request::{TransactionInput, TransactionRequest};
 use alloy_sol_types::{SolCall, SolInterface, SolValue};
 use foundry_common::{evm::Breakpoints, SELECTOR_LEN};
-use foundry_config::Config;
+use foundry_config::{CheatcodeCapability, Config};
 use foundry_evm_abi::Console;
 use foundry_evm_core::{
     abi::Vm::stopExpectSafeMemoryCall,
     backend::{DatabaseExt, RevertDiagnostic},
     constants::{CHEATCODE_ADDRESS, HARDHAT_CONSOLE_ADDRESS},
+    rng::RandomSource,
     utils::new_evm_with_existing_context,
     InspectorExt,
 };
 use itertools::Itertools;
+use rand::{rngs::StdRng, SeedableRng};
 use revm::{
     interpreter::{
         opcode, CallInputs, CallOutcome, CallScheme, CreateInputs, CreateOutcome, EOFCreateInputs,
@@ -240,6 +243,9 @@ pub struct Cheatcodes {
     /// in the execution environment.
     pub gas_price: Option<U256>,
 
+    /// How the `mine` cheatcodes advance the block environment.
+    pub mine_schedule: MineSchedule,
+
     /// Address labels
     pub labels: HashMap<Address, String>,
 
@@ -255,6 +261,9 @@ pub struct Cheatcodes {
     /// Recorded storage reads and writes
     pub accesses: Option<RecordAccess>,
 
+    /// Recorded transient storage writes, by address
+    pub transient_writes: Option<HashMap<Address, Vec<Vm::TransientWrite>>>,
+
     /// Recorded account accesses (calls, creates) organized by relative call depth, where the
     /// topmost vector corresponds to accesses at the depth at which account access recording
     /// began. Each vector in the matrix represents a list of accesses at a specific call
@@ -273,6 +282,9 @@ pub struct Cheatcodes {
     // **Note**: inner must a BTreeMap because of special `Ord` impl for `MockCallDataContext`
     pub mocked_calls: HashMap<Address, BTreeMap<MockCallDataContext, MockCallReturnData>>,
 
+    /// Mocked precompile outputs, by precompile address and input
+    pub mocked_precompiles: HashMap<Address, HashMap<Bytes, Bytes>>,
+
     /// Expected calls
     pub expected_calls: ExpectedCallTracker,
     /// Expected emits
@@ -287,6 +299,13 @@ pub struct Cheatcodes {
     /// Scripting based transactions
     pub broadcastable_transactions: BroadcastableTransactions,
 
+    /// The blob sidecar to attach to the next broadcasted call, set by `attachBlob`
+    pub active_blob_sidecar: Option<BlobTransactionSidecar>,
+
+    /// The gas limit, fees and type of the next broadcasted transaction, set by `txGasLimit`,
+    /// `txFees` and `txType`
+    pub next_tx_overrides: TxOverrides,
+
     /// Additional, user configurable context this Inspector has access to when inspecting a call
     pub config: Arc<CheatsConfig>,
 
@@ -325,6 +344,19 @@ pub struct Cheatcodes {
     /// Breakpoints supplied by the `breakpoint` cheatcode.
     /// `char -> (address, pc)`
     pub breakpoints: Breakpoints,
+    /// The breakpoint added by the last `breakpoint` call, if execution hasn't paused at it yet.
+    ///
+    /// Only used when debugging interactively.
+    pub pending_breakpoint: Option<char>,
+
+    /// The contracts deployed by the scripts the current script depends on, by contract name.
+    ///
+    /// Filled in by `forge script` and read by the `getDeployment` cheatcode.
+    pub deployments: HashMap<String, Address>,
+
+    /// The random number generator of the `random*` cheatcodes, created on first use, see
+    /// [`Cheatcodes::rng`].
+    pub rng: Option<StdRng>,
 }
 
 // This is not derived because calling this in `fn new` with `..Default::default()` creates a second
@@ -342,22 +374,26 @@ impl Cheatcodes {
         Self {
             fs_commit: true,
             labels: config.labels.clone(),
-            config,
             block: Default::default(),
             gas_price: Default::default(),
+            mine_schedule: Default::default(),
             prank: Default::default(),
             expected_revert: Default::default(),
             fork_revert_diagnostic: Default::default(),
             accesses: Default::default(),
+            transient_writes: Default::default(),
             recorded_account_diffs_stack: Default::default(),
             recorded_logs: Default::default(),
             last_call_gas: Default::default(),
             mocked_calls: Default::default(),
+            mocked_precompiles: Default::default(),
             expected_calls: Default::default(),
             expected_emits: Default::default(),
             allowed_mem_writes: Default::default(),
             broadcast: Default::default(),
             broadcastable_transactions: Default::default(),
+            active_blob_sidecar: Default::default(),
+            next_tx_overrides: Default::default(),
             context: Default::default(),
             serialized_jsons: Default::default(),
             eth_deals: Default::default(),
@@ -366,6 +402,10 @@ impl Cheatcodes {
             mapping_slots: Default::default(),
             pc: Default::default(),
             breakpoints: Default::default(),
+            pending_breakpoint: Default::default(),
+            deployments: Default::default(),
+            rng: None,
+            config,
         }
     }
 
@@ -374,6 +414,20 @@ impl Cheatcodes {
         self.config.script_wallets.as_ref()
     }
 
+    /// Returns the random number generator of the `random*` cheatcodes, given the calldata of the
+    /// current transaction.
+    ///
+    /// The generator is derived from the seed of the run and the calldata, so that every fuzz run
+    /// draws different values, all of which can be reproduced from the seed. Without a seed, it is
+    /// seeded by the OS.
+    pub fn rng(&mut self, calldata: &[u8]) -> &mut StdRng {
+        let seed = self.config.seed;
+        self.rng.get_or_insert_with(|| match seed {
+            Some(seed) => RandomSource::new(seed).rng("cheatcodes", calldata),
+            None => StdRng::from_entropy(),
+        })
+    }
+
     /// Decodes the input data and applies the cheatcode.
     fn apply_cheatcode<DB: DatabaseExt, E: CheatcodesExecutor>(
         &mut self,
@@ -400,6 +454,15 @@ impl Cheatcodes {
         // but only if the backend is in forking mode
         ecx.db.ensure_cheatcode_access_forking_mode(&caller)?;
 
+        if self.config.deterministic {
+            ensure_deterministic(&decoded)?;
+        }
+        if let Some(capability) = required_capability(&decoded) {
+            self.config.ensure_capability(capability).map_err(|err| {
+                fmt_err!("`{}` is not allowed: {err}", calls_as_dyn_cheatcode(&decoded).id())
+            })?;
+        }
+
         apply_dispatch(
             &decoded,
             &mut CheatsCtxt {
@@ -509,21 +572,24 @@ impl Cheatcodes {
                     let is_fixed_gas_limit = check_if_fixed_gas_limit(ecx, input.gas_limit());
 
                     let account = &ecx.journaled_state.state()[&broadcast.new_origin];
+                    let mut transaction = TransactionRequest {
+                        from: Some(broadcast.new_origin),
+                        to: None,
+                        value: Some(input.value()),
+                        input: TransactionInput::new(input.init_code()),
+                        nonce: Some(account.info.nonce),
+                        gas: if is_fixed_gas_limit {
+                            Some(input.gas_limit() as u128)
+                        } else {
+                            None
+                        },
+                        ..Default::default()
+                    };
+                    std::mem::take(&mut self.next_tx_overrides).apply(&mut transaction);
+
                     self.broadcastable_transactions.push_back(BroadcastableTransaction {
                         rpc: ecx.db.active_fork_url(),
-                        transaction: TransactionRequest {
-                            from: Some(broadcast.new_origin),
-                            to: None,
-                            value: Some(input.value()),
-                            input: TransactionInput::new(input.init_code()),
-                            nonce: Some(account.info.nonce),
-                            gas: if is_fixed_gas_limit {
-                                Some(input.gas_limit() as u128)
-                            } else {
-                                None
-                            },
-                            ..Default::default()
-                        },
+                        transaction,
                     });
 
                     input.log_debug(self, &input.scheme().unwrap_or(CreateScheme::Create));
@@ -752,13 +818,27 @@ impl Cheatcodes {
                     // The gas matches, if provided
                     expected.gas.map_or(true, |gas| gas == call.gas_limit) &&
                     // The minimum gas matches, if provided
-                    expected.min_gas.map_or(true, |min_gas| min_gas <= call.gas_limit)
+                    expected.min_gas.map_or(true, |min_gas| min_gas <= call.gas_limit) &&
+                    // The gas range, value range, depth and context match, if provided
+                    expected.constraints.matches(call, ecx.journaled_state.depth())
                 {
                     *actual_count += 1;
                 }
             }
         }
 
+        // Handle mocked precompiles, which are dispatched by the EVM with the precompiles of the
+        // current transaction
+        if let Some(mocks) = self.mocked_precompiles.get(&call.bytecode_address) {
+            let spec_id = ecx.spec_id();
+            mock::apply_precompile_mocks(
+                &mut ecx.precompiles,
+                spec_id,
+                call.bytecode_address,
+                mocks,
+            );
+        }
+
         // Handle mocked calls
         if let Some(mocks) = self.mocked_calls.get(&call.bytecode_address) {
             let ctx =
@@ -845,21 +925,30 @@ impl Cheatcodes {
                     let account =
                         ecx.journaled_state.state().get_mut(&broadcast.new_origin).unwrap();
 
+                    let mut transaction = TransactionRequest {
+                        from: Some(broadcast.new_origin),
+                        to: Some(TxKind::from(Some(call.target_address))),
+                        value: call.transfer_value(),
+                        input: TransactionInput::new(call.input.clone()),
+                        nonce: Some(account.info.nonce),
+                        gas: if is_fixed_gas_limit { Some(call.gas_limit as u128) } else { None },
+                        ..Default::default()
+                    };
+
+                    // Turn the call into a blob transaction if `attachBlob` was called, and expose
+                    // the blob hashes to the call.
+                    if let Some(sidecar) = self.active_blob_sidecar.take() {
+                        transaction.sidecar = Some(sidecar);
+                        transaction.populate_blob_hashes();
+                        ecx.env.tx.blob_hashes =
+                            transaction.blob_versioned_hashes.clone().unwrap_or_default();
+                    }
+
+                    std::mem::take(&mut self.next_tx_overrides).apply(&mut transaction);
+
                     self.broadcastable_transactions.push_back(BroadcastableTransaction {
                         rpc: ecx.db.active_fork_url(),
-                        transaction: TransactionRequest {
-                            from: Some(broadcast.new_origin),
-                            to: Some(TxKind::from(Some(call.target_address))),
-                            value: call.transfer_value(),
-                            input: TransactionInput::new(call.input.clone()),
-                            nonce: Some(account.info.nonce),
-                            gas: if is_fixed_gas_limit {
-                                Some(call.gas_limit as u128)
-                            } else {
-                                None
-                            },
-                            ..Default::default()
-                        },
+                        transaction,
                     });
                     debug!(target: "cheatcodes", tx=?self.broadcastable_transactions.back().unwrap(), "broadcastable call");
 
@@ -959,6 +1048,11 @@ impl<DB: DatabaseExt> Inspector<DB> for Cheatcodes {
             self.record_accesses(interpreter);
         }
 
+        // `recordTransient`: record transient storage writes.
+        if self.transient_writes.is_some() {
+            self.record_transient_writes(interpreter, ecx);
+        }
+
         // `startStateDiffRecording`: record granular ordered storage accesses.
         if self.recorded_account_diffs_stack.is_some() {
             self.record_state_diffs(interpreter, ecx);
@@ -1159,8 +1253,18 @@ impl<DB: DatabaseExt> Inspector<DB> for Cheatcodes {
         if should_check_emits {
             // Not all emits were matched.
             if self.expected_emits.iter().any(|expected| !expected.found) {
+                // Report the first mismatch against a log with the expected signature, if any.
+                let mismatch = self
+                    .expected_emits
+                    .iter()
+                    .filter(|expected| !expected.found)
+                    .find_map(|expected| expected.mismatch.as_deref());
+                let msg = match mismatch {
+                    Some(mismatch) => format!("log != expected log: {mismatch}"),
+                    None => "log != expected log".to_string(),
+                };
                 outcome.result.result = InstructionResult::Revert;
-                outcome.result.output = "log != expected log".abi_encode().into();
+                outcome.result.output = msg.abi_encode().into();
                 return outcome
             } else {
                 // All emits were found, we're good.
@@ -1212,7 +1316,8 @@ impl<DB: DatabaseExt> Inspector<DB> for Cheatcodes {
                 // Loop over each address, and for each address, loop over each calldata it expects.
                 for (calldata, (expected, actual_count)) in calldatas {
                     // Grab the values we expect to see
-                    let ExpectedCallData { gas, min_gas, value, count, call_type } = expected;
+                    let ExpectedCallData { gas, min_gas, value, count, call_type, constraints } =
+                        expected;
 
                     let failed = match call_type {
                         // If the cheatcode was called with a `count` argument,
@@ -1234,6 +1339,7 @@ impl<DB: DatabaseExt> Inspector<DB> for Cheatcodes {
                         ]
                         .into_iter()
                         .flatten()
+                        .chain(constraints.describe())
                         .join(", ");
                         let but = if outcome.result.is_ok() {
                             let s = if *actual_count == 1 { "" } else { "s" };
@@ -1282,6 +1388,12 @@ impl<DB: DatabaseExt> Inspector<DB> for Cheatcodes {
         ecx: &mut EvmContext<DB>,
         call: &mut CreateInputs,
     ) -> Option<CreateOutcome> {
+        // Substitute the implementations overridden in the config.
+        if let Some(init_code) =
+            self.config.create_overrides.iter().find_map(|o| o.apply(&call.init_code))
+        {
+            call.init_code = init_code;
+        }
         self.create_common(ecx, call)
     }
 
@@ -1410,6 +1522,28 @@ impl Cheatcodes {
         }
     }
 
+    /// Records transient storage writes, along with the previous values of the slots.
+    #[cold]
+    fn record_transient_writes<DB: DatabaseExt>(
+        &mut self,
+        interpreter: &mut Interpreter,
+        ecx: &mut EvmContext<DB>,
+    ) {
+        let Some(writes) = &mut self.transient_writes else { return };
+        if interpreter.current_opcode() != opcode::TSTORE {
+            return
+        }
+        let key = try_or_return!(interpreter.stack().peek(0));
+        let value = try_or_return!(interpreter.stack().peek(1));
+        let target = interpreter.contract().target_address;
+        let previous = ecx.journaled_state.tload(target, key);
+        writes.entry(target).or_default().push(Vm::TransientWrite {
+            slot: key.into(),
+            previousValue: previous.into(),
+            newValue: value.into(),
+        });
+    }
+
     #[cold]
     fn record_state_diffs<DB: DatabaseExt>(
         &mut self,
@@ -1854,6 +1988,46 @@ fn apply_dispatch<DB: DatabaseExt, E: CheatcodesExecutor>(
     result
 }
 
+/// Returns an error if the cheatcode depends on the host environment or the network, which is not
+/// allowed in deterministic mode.
+fn ensure_deterministic(calls: &Vm::VmCalls) -> Result<()> {
+    let id = calls_as_dyn_cheatcode(calls).id();
+    let name = id.split('_').next().unwrap_or(id);
+    let host_dependent = matches!(
+        name,
+        "ffi" |
+            "tryFfi" |
+            "fsMetadata" |
+            "projectRoot" |
+            "rpc" |
+            "eth" |
+            "createFork" |
+            "createSelectFork" |
+            "rollFork" |
+            "transact"
+    ) || name.starts_with("prompt") ||
+        name.starts_with("env");
+    ensure!(!host_dependent, "`{id}` is not allowed in deterministic mode");
+    Ok(())
+}
+
+/// Returns the capability the cheatcode requires, see `cheatcode_permissions` in the config.
+///
+/// Writes to the filesystem are checked when their path is, see
+/// [`CheatsConfig::ensure_path_allowed`](crate::CheatsConfig::ensure_path_allowed).
+fn required_capability(calls: &Vm::VmCalls) -> Option<CheatcodeCapability> {
+    let id = calls_as_dyn_cheatcode(calls).id();
+    let name = id.split('_').next().unwrap_or(id);
+    Some(match name {
+        "ffi" | "tryFfi" => CheatcodeCapability::Ffi,
+        "rpc" | "eth" | "createFork" | "createSelectFork" | "transact" => CheatcodeCapability::Http,
+        // Rolling a fork to a transaction fetches and replays the transactions before it.
+        "rollFork" if matches!(id, "rollFork_1" | "rollFork_3") => CheatcodeCapability::Http,
+        "mockPrecompile" => CheatcodeCapability::Precompiles,
+        _ => return None,
+    })
+}
+
 fn trace_span_and_call(calls: &Vm::VmCalls) -> tracing::span::EnteredSpan {
     let mut cheat = None;
     let mut get_cheat = || *cheat.get_or_insert_with(|| calls_as_dyn_cheatcode(calls));
@@ -1,7 +1,10 @@
 //! Implementations of [`Json`](spec::Group::Json) cheatcodes.
 
 use crate::{string, Cheatcode, Cheatcodes, Result, Vm::*};
-use alloy_dyn_abi::{DynSolType, DynSolValue};
+use alloy_dyn_abi::{
+    eip712::{parser::EncodeType, Resolver},
+    DynSolType, DynSolValue,
+};
 use alloy_primitives::{hex, Address, B256, I256};
 use alloy_sol_types::SolValue;
 use foundry_common::fs;
@@ -142,6 +145,28 @@ impl Cheatcode for parseJsonKeysCall {
     }
 }
 
+impl Cheatcode for parseJsonType_0Call {
+    fn apply(&self, _state: &mut Cheatcodes) -> Result {
+        let Self { json, typeDescription } = self;
+        parse_json_coerce_type(json, "$", typeDescription)
+    }
+}
+
+impl Cheatcode for parseJsonType_1Call {
+    fn apply(&self, _state: &mut Cheatcodes) -> Result {
+        let Self { json, key, typeDescription } = self;
+        parse_json_coerce_type(json, key, typeDescription)
+    }
+}
+
+impl Cheatcode for parseJsonTypeArrayCall {
+    fn apply(&self, _state: &mut Cheatcodes) -> Result {
+        let Self { json, key, typeDescription } = self;
+        let ty = resolve_type(typeDescription)?;
+        parse_json_coerce_type_with(json, key, &DynSolType::Array(Box::new(ty)))
+    }
+}
+
 impl Cheatcode for serializeJsonCall {
     fn apply(&self, state: &mut Cheatcodes) -> Result {
         let Self { objectKey, value } = self;
@@ -248,6 +273,23 @@ impl Cheatcode for serializeBytes_1Call {
     }
 }
 
+impl Cheatcode for serializeJsonType_0Call {
+    fn apply(&self, _state: &mut Cheatcodes) -> Result {
+        let Self { typeDescription, value } = self;
+        let value = decode_as_type(typeDescription, value)?;
+        Ok(serde_json::to_string(&sol_to_json(&value))?.abi_encode())
+    }
+}
+
+impl Cheatcode for serializeJsonType_1Call {
+    fn apply(&self, state: &mut Cheatcodes) -> Result {
+        let Self { objectKey, valueKey, typeDescription, value } = self;
+        let value = decode_as_type(typeDescription, value)?;
+        let value = serde_json::to_string(&sol_to_json(&value))?;
+        serialize_json(state, objectKey, Some(valueKey), &value)
+    }
+}
+
 impl Cheatcode for serializeUintToHexCall {
     fn apply(&self, state: &mut Cheatcodes) -> Result {
         let Self { objectKey, valueKey, value } = self;
@@ -321,6 +363,21 @@ pub(super) fn parse_json_coerce(json: &str, path: &str, ty: &DynSolType) -> Resu
     }
 }
 
+fn parse_json_coerce_type(json: &str, path: &str, type_description: &str) -> Result {
+    let ty = resolve_type(type_description)?;
+    parse_json_coerce_type_with(json, path, &ty)
+}
+
+fn parse_json_coerce_type_with(json: &str, path: &str, ty: &DynSolType) -> Result {
+    let value = parse_json_str(json)?;
+    let values = select(&value, path)?;
+    let [value] = values[..] else {
+        bail!("path {path:?} must return exactly one JSON value");
+    };
+    // The cheatcodes return `bytes`, so the encoded value is encoded again.
+    Ok(DynSolValue::Bytes(json_to_sol_as(value, ty)?.abi_encode()).abi_encode())
+}
+
 pub(super) fn parse_json_keys(json: &str, key: &str) -> Result {
     let json = parse_json_str(json)?;
     let values = select(&json, key)?;
@@ -461,6 +518,111 @@ pub(super) fn json_value_to_token(value: &Value) -> Result<DynSolValue> {
     }
 }
 
+/// Resolves a type description, which is either a Solidity type or an EIP-712 `encodeType` string,
+/// in which case the first type is the one being described.
+fn resolve_type(type_description: &str) -> Result<DynSolType> {
+    if let Ok(ty) = DynSolType::parse(type_description) {
+        return Ok(ty)
+    }
+    if let Ok(encoded) = EncodeType::parse(type_description) {
+        let main_type = encoded.types[0].type_name;
+        let mut resolver = Resolver::default();
+        for ty in encoded.types {
+            resolver.ingest(ty.to_owned());
+        }
+        return resolver.resolve(main_type).map_err(|e| fmt_err!("failed resolving type: {e}"))
+    }
+    bail!("type description should be a valid Solidity type or an EIP-712 `encodeType` string")
+}
+
+/// ABI-decodes `value` as the type given by `type_description`.
+fn decode_as_type(type_description: &str, value: &[u8]) -> Result<DynSolValue> {
+    let ty = resolve_type(type_description)?;
+    ty.abi_decode(value).map_err(|e| fmt_err!("failed decoding value as `{ty}`: {e}"))
+}
+
+/// Converts a JSON [`Value`] to a [`DynSolValue`] of the given type.
+///
+/// Unlike [`json_value_to_token`], objects are matched to struct fields by name, so the order of
+/// the keys doesn't matter.
+fn json_to_sol_as(value: &Value, ty: &DynSolType) -> Result<DynSolValue> {
+    match (value, ty) {
+        (Value::Object(object), DynSolType::CustomStruct { name, prop_names, tuple }) => {
+            let mut values = Vec::with_capacity(tuple.len());
+            for (field, ty) in prop_names.iter().zip(tuple) {
+                let value = object
+                    .get(field)
+                    .ok_or_else(|| fmt_err!("field {field:?} of struct {name} not found"))?;
+                values.push(json_to_sol_as(value, ty)?);
+            }
+            Ok(DynSolValue::CustomStruct {
+                name: name.clone(),
+                prop_names: prop_names.clone(),
+                tuple: values,
+            })
+        }
+        (Value::Array(array), DynSolType::Array(inner)) => array
+            .iter()
+            .map(|value| json_to_sol_as(value, inner))
+            .collect::<Result<_>>()
+            .map(DynSolValue::Array),
+        (Value::Array(array), DynSolType::FixedArray(inner, len)) => {
+            ensure!(array.len() == *len, "expected {len} elements, found {}", array.len());
+            array
+                .iter()
+                .map(|value| json_to_sol_as(value, inner))
+                .collect::<Result<_>>()
+                .map(DynSolValue::FixedArray)
+        }
+        (Value::Array(array), DynSolType::Tuple(types)) => {
+            ensure!(
+                array.len() == types.len(),
+                "expected {} elements, found {}",
+                types.len(),
+                array.len()
+            );
+            array
+                .iter()
+                .zip(types)
+                .map(|(value, ty)| json_to_sol_as(value, ty))
+                .collect::<Result<_>>()
+                .map(DynSolValue::Tuple)
+        }
+        (Value::String(s), DynSolType::String) => Ok(DynSolValue::String(s.clone())),
+        (Value::String(s), _) => string::parse_value(s, ty),
+        (Value::Bool(_) | Value::Number(_), _) => string::parse_value(&value.to_string(), ty),
+        _ => bail!("expected JSON value of type `{ty}`, found {value}"),
+    }
+}
+
+/// Converts a [`DynSolValue`] to JSON, using the field names of structs as keys.
+///
+/// Integers that don't fit in a JSON number are serialized as decimal strings.
+fn sol_to_json(value: &DynSolValue) -> Value {
+    match value {
+        DynSolValue::Bool(b) => Value::Bool(*b),
+        DynSolValue::Int(i, _) => match i64::try_from(*i) {
+            Ok(n) => n.into(),
+            Err(_) => i.to_string().into(),
+        },
+        DynSolValue::Uint(n, _) => match u64::try_from(*n) {
+            Ok(n) => n.into(),
+            Err(_) => n.to_string().into(),
+        },
+        DynSolValue::FixedBytes(bytes, size) => hex::encode_prefixed(&bytes[..*size]).into(),
+        DynSolValue::Address(address) => address.to_string().into(),
+        DynSolValue::Function(function) => function.to_string().into(),
+        DynSolValue::Bytes(bytes) => hex::encode_prefixed(bytes).into(),
+        DynSolValue::String(s) => s.clone().into(),
+        DynSolValue::Array(values) |
+        DynSolValue::FixedArray(values) |
+        DynSolValue::Tuple(values) => values.iter().map(sol_to_json).collect(),
+        DynSolValue::CustomStruct { prop_names, tuple, .. } => {
+            Value::Object(prop_names.iter().cloned().zip(tuple.iter().map(sol_to_json)).collect())
+        }
+    }
+}
+
 /// Serializes a key:value pair to a specific object. If the key is Some(valueKey), the value is
 /// expected to be an object, which will be set as the root object for the provided object key,
 /// overriding the whole root object if the object key already exists. By calling this function
@@ -15,7 +15,7 @@ use alloy_primitives::Address;
 use foundry_evm_core::backend::DatabaseExt;
 use revm::{ContextPrecompiles, InnerEvmContext};
 
-pub use config::CheatsConfig;
+pub use config::{CheatsConfig, CreateOverride};
 pub use error::{Error, ErrorKind, Result};
 pub use inspector::{
     BroadcastableTransaction, BroadcastableTransactions, Cheatcodes, CheatcodesExecutor, Context,
@@ -34,6 +34,7 @@ mod env;
 pub use env::set_execution_context;
 
 mod evm;
+pub use evm::{Fixture, Fixtures};
 
 mod fs;
 
@@ -1,10 +1,15 @@
 //! Implementations of [`Scripting`](spec::Group::Scripting) cheatcodes.
 
-use crate::{Cheatcode, CheatsCtxt, DatabaseExt, Result, Vm::*};
+use crate::{Cheatcode, Cheatcodes, CheatsCtxt, DatabaseExt, Result, Vm::*};
+use alloy_consensus::{SidecarBuilder, SimpleCoder};
 use alloy_primitives::{Address, B256, U256};
+use alloy_rpc_types::request::TransactionRequest;
 use alloy_signer_local::PrivateKeySigner;
+use alloy_sol_types::SolValue;
+use foundry_evm_core::tx_type::custom_tx_type;
 use foundry_wallets::{multi_wallet::MultiWallet, WalletSigner};
 use parking_lot::Mutex;
+use revm::primitives::SpecId;
 use std::sync::Arc;
 
 impl Cheatcode for broadcast_0Call {
@@ -60,6 +65,81 @@ impl Cheatcode for stopBroadcastCall {
     }
 }
 
+impl Cheatcode for attachBlobCall {
+    fn apply_stateful<DB: DatabaseExt>(&self, ccx: &mut CheatsCtxt<DB>) -> Result {
+        let Self { data } = self;
+        ensure!(
+            ccx.ecx.spec_id() >= SpecId::CANCUN,
+            "`attachBlob` is not supported before the Cancun hard fork; \
+             see EIP-4844: https://eips.ethereum.org/EIPS/eip-4844"
+        );
+        let mut coder = SidecarBuilder::<SimpleCoder>::default();
+        coder.ingest(data);
+        let sidecar = coder.build().map_err(|e| fmt_err!("failed to build blob sidecar: {e}"))?;
+        ccx.state.active_blob_sidecar = Some(sidecar);
+        Ok(Default::default())
+    }
+}
+
+impl Cheatcode for txGasLimitCall {
+    fn apply(&self, state: &mut Cheatcodes) -> Result {
+        let Self { gasLimit } = self;
+        ensure!(*gasLimit > 0, "gas limit must be greater than zero");
+        state.next_tx_overrides.gas_limit = Some(*gasLimit);
+        Ok(Default::default())
+    }
+}
+
+impl Cheatcode for txFees_0Call {
+    fn apply(&self, state: &mut Cheatcodes) -> Result {
+        let Self { gasPrice } = self;
+        let overrides = &mut state.next_tx_overrides;
+        overrides.gas_price = Some(gasPrice.saturating_to());
+        overrides.max_fee_per_gas = None;
+        overrides.max_priority_fee_per_gas = None;
+        Ok(Default::default())
+    }
+}
+
+impl Cheatcode for txFees_1Call {
+    fn apply(&self, state: &mut Cheatcodes) -> Result {
+        let Self { maxFeePerGas, maxPriorityFeePerGas } = self;
+        ensure!(
+            maxPriorityFeePerGas <= maxFeePerGas,
+            "max priority fee per gas is greater than max fee per gas"
+        );
+        let overrides = &mut state.next_tx_overrides;
+        overrides.gas_price = None;
+        overrides.max_fee_per_gas = Some(maxFeePerGas.saturating_to());
+        overrides.max_priority_fee_per_gas = Some(maxPriorityFeePerGas.saturating_to());
+        Ok(Default::default())
+    }
+}
+
+impl Cheatcode for txTypeCall {
+    fn apply_stateful<DB: DatabaseExt>(&self, ccx: &mut CheatsCtxt<DB>) -> Result {
+        let Self { transactionType } = self;
+        let chain_id = ccx.ecx.env.cfg.chain_id;
+        ensure!(
+            *transactionType <= 2 || custom_tx_type(chain_id, Some(*transactionType)).is_some(),
+            "unsupported transaction type {transactionType}; \
+             use `attachBlob` for blob transactions and `attachDelegation` for EIP-7702"
+        );
+        ccx.state.next_tx_overrides.transaction_type = Some(*transactionType);
+        Ok(Default::default())
+    }
+}
+
+impl Cheatcode for getDeploymentCall {
+    fn apply(&self, state: &mut Cheatcodes) -> Result {
+        let Self { contractName } = self;
+        let Some(address) = state.deployments.get(contractName) else {
+            bail!("no deployment of {contractName} by the scripts this script depends on");
+        };
+        Ok(address.abi_encode())
+    }
+}
+
 #[derive(Clone, Debug, Default)]
 pub struct Broadcast {
     /// Address of the transaction origin
@@ -74,6 +154,40 @@ pub struct Broadcast {
     pub single_call: bool,
 }
 
+/// The gas limit, fees and type of the next broadcasted transaction, set by `txGasLimit`, `txFees`
+/// and `txType`.
+#[derive(Clone, Debug, Default)]
+pub struct TxOverrides {
+    pub gas_limit: Option<u64>,
+    pub gas_price: Option<u128>,
+    pub max_fee_per_gas: Option<u128>,
+    pub max_priority_fee_per_gas: Option<u128>,
+    pub transaction_type: Option<u8>,
+}
+
+impl TxOverrides {
+    /// Applies the overrides to the transaction.
+    ///
+    /// A gas limit set here is kept as is when broadcasting, and fees set here take precedence over
+    /// the estimated ones.
+    pub fn apply(self, tx: &mut TransactionRequest) {
+        let Self {
+            gas_limit,
+            gas_price,
+            max_fee_per_gas,
+            max_priority_fee_per_gas,
+            transaction_type,
+        } = self;
+        if let Some(gas_limit) = gas_limit {
+            tx.gas = Some(gas_limit as u128);
+        }
+        tx.gas_price = gas_price.or(tx.gas_price);
+        tx.max_fee_per_gas = max_fee_per_gas.or(tx.max_fee_per_gas);
+        tx.max_priority_fee_per_gas = max_priority_fee_per_gas.or(tx.max_priority_fee_per_gas);
+        tx.transaction_type = transaction_type.or(tx.transaction_type);
+    }
+}
+
 /// Contains context for wallet management.
 #[derive(Debug)]
 pub struct ScriptWalletsInner {
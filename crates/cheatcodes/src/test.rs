@@ -87,6 +87,7 @@ fn breakpoint(state: &mut Cheatcodes, caller: &Address, s: &str, add: bool) -> R
 
     if add {
         state.breakpoints.insert(point, (*caller, state.pc));
+        state.pending_breakpoint = Some(point);
     } else {
         state.breakpoints.remove(&point);
     }
@@ -1,5 +1,5 @@
 use crate::{CheatcodesExecutor, CheatsCtxt, Result, Vm::*};
-use alloy_primitives::{hex, I256, U256};
+use alloy_primitives::{hex, I256, U256, U512};
 use foundry_evm_core::{
     abi::{format_units_int, format_units_uint},
     backend::{DatabaseExt, GLOBAL_FAIL_SLOT},
@@ -10,6 +10,12 @@ use std::fmt::{Debug, Display};
 
 const EQ_REL_DELTA_RESOLUTION: U256 = U256::from_limbs([18, 0, 0, 0]);
 
+/// The number of decimals of WAD fixed point numbers.
+const WAD_DECIMALS: u8 = 18;
+
+/// The number of decimals of RAY fixed point numbers.
+const RAY_DECIMALS: u8 = 27;
+
 #[derive(Debug, thiserror::Error)]
 #[error("assertion failed")]
 struct SimpleAssertionError;
@@ -167,6 +173,65 @@ impl EqRelAssertionError<I256> {
     }
 }
 
+#[derive(thiserror::Error, Debug)]
+#[error("{left} !~= {right} (max delta: {max_ulps} ulp, real delta: {real_ulps} ulp)")]
+struct EqUlpAssertionError<T> {
+    left: T,
+    right: T,
+    max_ulps: U256,
+    real_ulps: U256,
+}
+
+impl EqUlpAssertionError<U256> {
+    fn format_with_decimals(&self, decimals: &U256) -> String {
+        format!(
+            "{} !~= {} (max delta: {} ulp, real delta: {} ulp)",
+            format_units_uint(&self.left, decimals),
+            format_units_uint(&self.right, decimals),
+            self.max_ulps,
+            self.real_ulps,
+        )
+    }
+}
+
+impl EqUlpAssertionError<I256> {
+    fn format_with_decimals(&self, decimals: &U256) -> String {
+        format!(
+            "{} !~= {} (max delta: {} ulp, real delta: {} ulp)",
+            format_units_int(&self.left, decimals),
+            format_units_int(&self.right, decimals),
+            self.max_ulps,
+            self.real_ulps,
+        )
+    }
+}
+
+/// A failed relative comparison of two fixed point values, whose relative deltas have the same
+/// number of decimals as the values.
+#[derive(Debug)]
+struct EqFixedRelAssertionError<T> {
+    left: T,
+    right: T,
+    decimals: u8,
+    max_delta: U256,
+    real_delta: Option<U256>,
+}
+
+impl<T> EqFixedRelAssertionError<T> {
+    fn format_with(&self, format_value: impl Fn(&T, &U256) -> String) -> String {
+        let decimals = U256::from(self.decimals);
+        let percent =
+            |delta: &U256| format!("{}%", format_units_uint(delta, &(decimals - U256::from(2))));
+        format!(
+            "{} !~= {} (max delta: {}, real delta: {})",
+            format_value(&self.left, &decimals),
+            format_value(&self.right, &decimals),
+            percent(&self.max_delta),
+            self.real_delta.as_ref().map_or_else(|| "undefined".to_string(), percent),
+        )
+    }
+}
+
 type ComparisonResult<'a, T> = Result<Vec<u8>, ComparisonAssertionError<'a, T>>;
 
 fn handle_assertion_result<DB: DatabaseExt, E: CheatcodesExecutor, ERR>(
@@ -447,6 +512,42 @@ impl_assertions! {
     (assertApproxEqRelDecimal_2Call, assertApproxEqRelDecimal_3Call),
 }
 
+impl_assertions! {
+    |left, right, decimals, maxUlps| uint_assert_approx_eq_ulp(*left, *right, *maxUlps),
+    |e| e.format_with_decimals(decimals),
+    (assertApproxEqUlp_0Call, assertApproxEqUlp_1Call),
+}
+
+impl_assertions! {
+    |left, right, decimals, maxUlps| int_assert_approx_eq_ulp(*left, *right, *maxUlps),
+    |e| e.format_with_decimals(decimals),
+    (assertApproxEqUlp_2Call, assertApproxEqUlp_3Call),
+}
+
+impl_assertions! {
+    |left, right, maxRelDelta| uint_assert_approx_eq_fixed_rel(*left, *right, *maxRelDelta, WAD_DECIMALS),
+    |e| e.format_with(format_units_uint),
+    (assertApproxEqRelWad_0Call, assertApproxEqRelWad_1Call),
+}
+
+impl_assertions! {
+    |left, right, maxRelDelta| int_assert_approx_eq_fixed_rel(*left, *right, *maxRelDelta, WAD_DECIMALS),
+    |e| e.format_with(format_units_int),
+    (assertApproxEqRelWad_2Call, assertApproxEqRelWad_3Call),
+}
+
+impl_assertions! {
+    |left, right, maxRelDelta| uint_assert_approx_eq_fixed_rel(*left, *right, *maxRelDelta, RAY_DECIMALS),
+    |e| e.format_with(format_units_uint),
+    (assertApproxEqRelRay_0Call, assertApproxEqRelRay_1Call),
+}
+
+impl_assertions! {
+    |left, right, maxRelDelta| int_assert_approx_eq_fixed_rel(*left, *right, *maxRelDelta, RAY_DECIMALS),
+    |e| e.format_with(format_units_int),
+    (assertApproxEqRelRay_2Call, assertApproxEqRelRay_3Call),
+}
+
 fn assert_true(condition: bool) -> Result<Vec<u8>, SimpleAssertionError> {
     if condition {
         Ok(Default::default())
@@ -601,6 +702,85 @@ fn int_assert_approx_eq_rel(
     }
 }
 
+fn uint_assert_approx_eq_ulp(
+    left: U256,
+    right: U256,
+    max_ulps: U256,
+) -> Result<Vec<u8>, Box<EqUlpAssertionError<U256>>> {
+    let ulps = get_delta_uint(left, right);
+
+    if ulps <= max_ulps {
+        Ok(Default::default())
+    } else {
+        Err(Box::new(EqUlpAssertionError { left, right, max_ulps, real_ulps: ulps }))
+    }
+}
+
+fn int_assert_approx_eq_ulp(
+    left: I256,
+    right: I256,
+    max_ulps: U256,
+) -> Result<Vec<u8>, Box<EqUlpAssertionError<I256>>> {
+    let ulps = get_delta_int(left, right);
+
+    if ulps <= max_ulps {
+        Ok(Default::default())
+    } else {
+        Err(Box::new(EqUlpAssertionError { left, right, max_ulps, real_ulps: ulps }))
+    }
+}
+
+/// Checks that `delta / abs_right <= max_delta / 10 ** decimals`.
+///
+/// The comparison is made on 512 bit integers, so that it neither overflows nor rounds.
+fn fixed_rel_delta_within(
+    delta: U256,
+    abs_right: U256,
+    max_delta: U256,
+    decimals: u8,
+) -> Result<(), Option<U256>> {
+    if abs_right.is_zero() {
+        return if delta.is_zero() { Ok(()) } else { Err(None) }
+    }
+
+    let one = U512::from(10).pow(U512::from(decimals));
+    let scaled_delta = U512::from(delta) * one;
+    if scaled_delta <= U512::from(max_delta) * U512::from(abs_right) {
+        Ok(())
+    } else {
+        Err(Some(U256::saturating_from(scaled_delta / U512::from(abs_right))))
+    }
+}
+
+fn uint_assert_approx_eq_fixed_rel(
+    left: U256,
+    right: U256,
+    max_delta: U256,
+    decimals: u8,
+) -> Result<Vec<u8>, Box<EqFixedRelAssertionError<U256>>> {
+    match fixed_rel_delta_within(get_delta_uint(left, right), right, max_delta, decimals) {
+        Ok(()) => Ok(Default::default()),
+        Err(real_delta) => {
+            Err(Box::new(EqFixedRelAssertionError { left, right, decimals, max_delta, real_delta }))
+        }
+    }
+}
+
+fn int_assert_approx_eq_fixed_rel(
+    left: I256,
+    right: I256,
+    max_delta: U256,
+    decimals: u8,
+) -> Result<Vec<u8>, Box<EqFixedRelAssertionError<I256>>> {
+    let (_, abs_right) = right.into_sign_and_abs();
+    match fixed_rel_delta_within(get_delta_int(left, right), abs_right, max_delta, decimals) {
+        Ok(()) => Ok(Default::default()),
+        Err(real_delta) => {
+            Err(Box::new(EqFixedRelAssertionError { left, right, decimals, max_delta, real_delta }))
+        }
+    }
+}
+
 fn assert_gt<'a, T: PartialOrd>(left: &'a T, right: &'a T) -> ComparisonResult<'a, T> {
     if left > right {
         Ok(Default::default())
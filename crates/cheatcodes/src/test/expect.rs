@@ -1,7 +1,7 @@
 use crate::{Cheatcode, Cheatcodes, CheatsCtxt, DatabaseExt, Result, Vm::*};
 use alloy_primitives::{address, hex, Address, Bytes, LogData as RawLog, U256};
 use alloy_sol_types::{SolError, SolValue};
-use revm::interpreter::{return_ok, InstructionResult};
+use revm::interpreter::{return_ok, CallInputs, InstructionResult};
 use spec::Vm;
 use std::collections::{hash_map::Entry, HashMap};
 
@@ -42,6 +42,71 @@ pub struct ExpectedCallData {
     pub count: u64,
     /// The type of expected call.
     pub call_type: ExpectedCallType,
+    /// Constraints on the metadata of the call.
+    pub constraints: ExpectedCallConstraints,
+}
+
+/// Constraints on the metadata of an expected call, set with `expectCallWithGas` and
+/// `expectCallWithMetadata`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ExpectedCallConstraints {
+    /// The expected range of gas forwarded to the call, excluding the stipend of value transfers.
+    pub gas: Option<(u64, u64)>,
+    /// The expected range of value sent in the call.
+    pub value: Option<(U256, U256)>,
+    /// The expected depth of the call.
+    pub depth: Option<u64>,
+    /// Whether the call is expected to be made in a static context.
+    pub is_static: Option<bool>,
+}
+
+impl ExpectedCallConstraints {
+    fn from_metadata(metadata: &ExpectedCallMetadata) -> Result<Self> {
+        let ExpectedCallMetadata { minGas, maxGas, minValue, maxValue, depth, context } = metadata;
+        ensure!(minGas <= maxGas, "minimum gas {minGas} is greater than maximum gas {maxGas}");
+        ensure!(
+            minValue <= maxValue,
+            "minimum value {minValue} is greater than maximum value {maxValue}"
+        );
+        Ok(Self {
+            gas: Some((*minGas, *maxGas)).filter(|range| *range != (0, u64::MAX)),
+            value: Some((*minValue, *maxValue)).filter(|range| *range != (U256::ZERO, U256::MAX)),
+            depth: Some(*depth).filter(|depth| *depth != 0),
+            is_static: match context {
+                ExpectedCallContext::Any => None,
+                ExpectedCallContext::NonStatic => Some(false),
+                ExpectedCallContext::Static => Some(true),
+                _ => bail!("invalid call context"),
+            },
+        })
+    }
+
+    /// Returns `true` if the call, made at the given depth, satisfies the constraints.
+    pub fn matches(&self, call: &CallInputs, depth: u64) -> bool {
+        let value = call.call_value();
+        // The stipend of value transfers is added to the gas limit of the call.
+        let stipend =
+            if call.transfer_value().is_some_and(|value| !value.is_zero()) { 2300 } else { 0 };
+        let gas = call.gas_limit.saturating_sub(stipend);
+        self.gas.map_or(true, |(min, max)| min <= gas && gas <= max) &&
+            self.value.map_or(true, |(min, max)| min <= value && value <= max) &&
+            self.depth.map_or(true, |expected| expected == depth) &&
+            self.is_static.map_or(true, |is_static| is_static == call.is_static)
+    }
+
+    /// Returns a description of each constraint, for error messages.
+    pub fn describe(&self) -> impl Iterator<Item = String> {
+        [
+            self.gas.map(|(min, max)| format!("gas between {min} and {max}")),
+            self.value.map(|(min, max)| format!("value between {min} and {max}")),
+            self.depth.map(|depth| format!("depth {depth}")),
+            self.is_static.map(|is_static| {
+                if is_static { "static context" } else { "non-static context" }.to_string()
+            }),
+        ]
+        .into_iter()
+        .flatten()
+    }
 }
 
 /// The type of expected call.
@@ -89,30 +154,80 @@ pub struct ExpectedEmit {
     /// └───────┴───────┴───────┴────┘
     /// ```
     pub checks: [bool; 4],
+    /// If present, only the 32-byte words of the data whose mask entry is `true` are checked.
+    /// Words past the end of the mask are always checked.
+    pub data_mask: Option<Vec<bool>>,
     /// If present, check originating address against this
     pub address: Option<Address>,
+    /// How this log is matched against the emitted logs
+    pub mode: ExpectedEmitMode,
     /// Whether the log was actually found in the subcalls
     pub found: bool,
+    /// Describes the last mismatch against a log with the same signature, if any
+    pub mismatch: Option<String>,
+}
+
+/// How an expected emit is matched against the emitted logs.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ExpectedEmitMode {
+    /// The log must be emitted after all the previously declared ordered logs were matched.
+    ///
+    /// Unexpected logs emitted in between are ignored, so the ordered expected logs only need to
+    /// be a subset of the emitted logs, in the same order.
+    #[default]
+    Ordered,
+    /// The log may be emitted at any point, as long as it's matched by a distinct log.
+    Unordered,
 }
 
 impl Cheatcode for expectCall_0Call {
     fn apply(&self, state: &mut Cheatcodes) -> Result {
         let Self { callee, data } = self;
-        expect_call(state, callee, data, None, None, None, 1, ExpectedCallType::NonCount)
+        expect_call(
+            state,
+            callee,
+            data,
+            None,
+            None,
+            None,
+            1,
+            ExpectedCallType::NonCount,
+            Default::default(),
+        )
     }
 }
 
 impl Cheatcode for expectCall_1Call {
     fn apply(&self, state: &mut Cheatcodes) -> Result {
         let Self { callee, data, count } = self;
-        expect_call(state, callee, data, None, None, None, *count, ExpectedCallType::Count)
+        expect_call(
+            state,
+            callee,
+            data,
+            None,
+            None,
+            None,
+            *count,
+            ExpectedCallType::Count,
+            Default::default(),
+        )
     }
 }
 
 impl Cheatcode for expectCall_2Call {
     fn apply(&self, state: &mut Cheatcodes) -> Result {
         let Self { callee, msgValue, data } = self;
-        expect_call(state, callee, data, Some(msgValue), None, None, 1, ExpectedCallType::NonCount)
+        expect_call(
+            state,
+            callee,
+            data,
+            Some(msgValue),
+            None,
+            None,
+            1,
+            ExpectedCallType::NonCount,
+            Default::default(),
+        )
     }
 }
 
@@ -128,6 +243,7 @@ impl Cheatcode for expectCall_3Call {
             None,
             *count,
             ExpectedCallType::Count,
+            Default::default(),
         )
     }
 }
@@ -144,6 +260,7 @@ impl Cheatcode for expectCall_4Call {
             None,
             1,
             ExpectedCallType::NonCount,
+            Default::default(),
         )
     }
 }
@@ -160,6 +277,7 @@ impl Cheatcode for expectCall_5Call {
             None,
             *count,
             ExpectedCallType::Count,
+            Default::default(),
         )
     }
 }
@@ -176,6 +294,7 @@ impl Cheatcode for expectCallMinGas_0Call {
             Some(*minGas),
             1,
             ExpectedCallType::NonCount,
+            Default::default(),
         )
     }
 }
@@ -192,6 +311,83 @@ impl Cheatcode for expectCallMinGas_1Call {
             Some(*minGas),
             *count,
             ExpectedCallType::Count,
+            Default::default(),
+        )
+    }
+}
+
+impl Cheatcode for expectCallWithGas_0Call {
+    fn apply(&self, state: &mut Cheatcodes) -> Result {
+        let Self { callee, minGas, maxGas, data } = self;
+        ensure!(minGas <= maxGas, "minimum gas {minGas} is greater than maximum gas {maxGas}");
+        let constraints =
+            ExpectedCallConstraints { gas: Some((*minGas, *maxGas)), ..Default::default() };
+        expect_call(
+            state,
+            callee,
+            data,
+            None,
+            None,
+            None,
+            1,
+            ExpectedCallType::NonCount,
+            constraints,
+        )
+    }
+}
+
+impl Cheatcode for expectCallWithGas_1Call {
+    fn apply(&self, state: &mut Cheatcodes) -> Result {
+        let Self { callee, minGas, maxGas, data, count } = self;
+        ensure!(minGas <= maxGas, "minimum gas {minGas} is greater than maximum gas {maxGas}");
+        let constraints =
+            ExpectedCallConstraints { gas: Some((*minGas, *maxGas)), ..Default::default() };
+        expect_call(
+            state,
+            callee,
+            data,
+            None,
+            None,
+            None,
+            *count,
+            ExpectedCallType::Count,
+            constraints,
+        )
+    }
+}
+
+impl Cheatcode for expectCallWithMetadata_0Call {
+    fn apply(&self, state: &mut Cheatcodes) -> Result {
+        let Self { callee, data, metadata } = self;
+        let constraints = ExpectedCallConstraints::from_metadata(metadata)?;
+        expect_call(
+            state,
+            callee,
+            data,
+            None,
+            None,
+            None,
+            1,
+            ExpectedCallType::NonCount,
+            constraints,
+        )
+    }
+}
+
+impl Cheatcode for expectCallWithMetadata_1Call {
+    fn apply(&self, state: &mut Cheatcodes) -> Result {
+        let Self { callee, data, metadata, count } = self;
+        let constraints = ExpectedCallConstraints::from_metadata(metadata)?;
+        expect_call(
+            state,
+            callee,
+            data,
+            None,
+            None,
+            None,
+            *count,
+            ExpectedCallType::Count,
+            constraints,
         )
     }
 }
@@ -204,6 +400,8 @@ impl Cheatcode for expectEmit_0Call {
             ccx.ecx.journaled_state.depth(),
             [checkTopic1, checkTopic2, checkTopic3, checkData],
             None,
+            None,
+            ExpectedEmitMode::Ordered,
         )
     }
 }
@@ -215,7 +413,9 @@ impl Cheatcode for expectEmit_1Call {
             ccx.state,
             ccx.ecx.journaled_state.depth(),
             [checkTopic1, checkTopic2, checkTopic3, checkData],
+            None,
             Some(emitter),
+            ExpectedEmitMode::Ordered,
         )
     }
 }
@@ -223,14 +423,112 @@ impl Cheatcode for expectEmit_1Call {
 impl Cheatcode for expectEmit_2Call {
     fn apply_stateful<DB: DatabaseExt>(&self, ccx: &mut CheatsCtxt<DB>) -> Result {
         let Self {} = self;
-        expect_emit(ccx.state, ccx.ecx.journaled_state.depth(), [true; 4], None)
+        expect_emit(
+            ccx.state,
+            ccx.ecx.journaled_state.depth(),
+            [true; 4],
+            None,
+            None,
+            ExpectedEmitMode::Ordered,
+        )
     }
 }
 
 impl Cheatcode for expectEmit_3Call {
     fn apply_stateful<DB: DatabaseExt>(&self, ccx: &mut CheatsCtxt<DB>) -> Result {
         let Self { emitter } = *self;
-        expect_emit(ccx.state, ccx.ecx.journaled_state.depth(), [true; 4], Some(emitter))
+        expect_emit(
+            ccx.state,
+            ccx.ecx.journaled_state.depth(),
+            [true; 4],
+            None,
+            Some(emitter),
+            ExpectedEmitMode::Ordered,
+        )
+    }
+}
+
+impl Cheatcode for expectEmit_4Call {
+    fn apply_stateful<DB: DatabaseExt>(&self, ccx: &mut CheatsCtxt<DB>) -> Result {
+        let Self { checkTopic1, checkTopic2, checkTopic3, checkData } = self;
+        expect_emit(
+            ccx.state,
+            ccx.ecx.journaled_state.depth(),
+            [*checkTopic1, *checkTopic2, *checkTopic3, true],
+            Some(checkData.clone()),
+            None,
+            ExpectedEmitMode::Ordered,
+        )
+    }
+}
+
+impl Cheatcode for expectEmit_5Call {
+    fn apply_stateful<DB: DatabaseExt>(&self, ccx: &mut CheatsCtxt<DB>) -> Result {
+        let Self { checkTopic1, checkTopic2, checkTopic3, checkData, emitter } = self;
+        expect_emit(
+            ccx.state,
+            ccx.ecx.journaled_state.depth(),
+            [*checkTopic1, *checkTopic2, *checkTopic3, true],
+            Some(checkData.clone()),
+            Some(*emitter),
+            ExpectedEmitMode::Ordered,
+        )
+    }
+}
+
+impl Cheatcode for expectEmitUnordered_0Call {
+    fn apply_stateful<DB: DatabaseExt>(&self, ccx: &mut CheatsCtxt<DB>) -> Result {
+        let Self { checkTopic1, checkTopic2, checkTopic3, checkData } = *self;
+        expect_emit(
+            ccx.state,
+            ccx.ecx.journaled_state.depth(),
+            [checkTopic1, checkTopic2, checkTopic3, checkData],
+            None,
+            None,
+            ExpectedEmitMode::Unordered,
+        )
+    }
+}
+
+impl Cheatcode for expectEmitUnordered_1Call {
+    fn apply_stateful<DB: DatabaseExt>(&self, ccx: &mut CheatsCtxt<DB>) -> Result {
+        let Self { checkTopic1, checkTopic2, checkTopic3, checkData, emitter } = *self;
+        expect_emit(
+            ccx.state,
+            ccx.ecx.journaled_state.depth(),
+            [checkTopic1, checkTopic2, checkTopic3, checkData],
+            None,
+            Some(emitter),
+            ExpectedEmitMode::Unordered,
+        )
+    }
+}
+
+impl Cheatcode for expectEmitUnordered_2Call {
+    fn apply_stateful<DB: DatabaseExt>(&self, ccx: &mut CheatsCtxt<DB>) -> Result {
+        let Self {} = self;
+        expect_emit(
+            ccx.state,
+            ccx.ecx.journaled_state.depth(),
+            [true; 4],
+            None,
+            None,
+            ExpectedEmitMode::Unordered,
+        )
+    }
+}
+
+impl Cheatcode for expectEmitUnordered_3Call {
+    fn apply_stateful<DB: DatabaseExt>(&self, ccx: &mut CheatsCtxt<DB>) -> Result {
+        let Self { emitter } = *self;
+        expect_emit(
+            ccx.state,
+            ccx.ecx.journaled_state.depth(),
+            [true; 4],
+            None,
+            Some(emitter),
+            ExpectedEmitMode::Unordered,
+        )
     }
 }
 
@@ -323,6 +621,7 @@ fn expect_call(
     mut min_gas: Option<u64>,
     count: u64,
     call_type: ExpectedCallType,
+    constraints: ExpectedCallConstraints,
 ) -> Result {
     let expecteds = state.expected_calls.entry(*target).or_default();
 
@@ -351,7 +650,17 @@ fn expect_call(
             );
             expecteds.insert(
                 calldata.clone(),
-                (ExpectedCallData { value: value.copied(), gas, min_gas, count, call_type }, 0),
+                (
+                    ExpectedCallData {
+                        value: value.copied(),
+                        gas,
+                        min_gas,
+                        count,
+                        call_type,
+                        constraints,
+                    },
+                    0,
+                ),
             );
         }
         ExpectedCallType::NonCount => {
@@ -370,7 +679,14 @@ fn expect_call(
                 // If it does not exist, then create it.
                 Entry::Vacant(entry) => {
                     entry.insert((
-                        ExpectedCallData { value: value.copied(), gas, min_gas, count, call_type },
+                        ExpectedCallData {
+                            value: value.copied(),
+                            gas,
+                            min_gas,
+                            count,
+                            call_type,
+                            constraints,
+                        },
                         0,
                     ));
                 }
@@ -385,14 +701,19 @@ fn expect_emit(
     state: &mut Cheatcodes,
     depth: u64,
     checks: [bool; 4],
+    data_mask: Option<Vec<bool>>,
     address: Option<Address>,
+    mode: ExpectedEmitMode,
 ) -> Result {
     state.expected_emits.push_back(ExpectedEmit {
         depth,
         checks,
+        data_mask,
         address,
+        mode,
         found: false,
         log: None,
+        mismatch: None,
     });
     Ok(Default::default())
 }
@@ -400,10 +721,9 @@ fn expect_emit(
 pub(crate) fn handle_expect_emit(state: &mut Cheatcodes, log: &alloy_primitives::Log) {
     // Fill or check the expected emits.
     // We expect for emit checks to be filled as they're declared (from oldest to newest),
-    // so we fill them and push them to the back of the queue.
-    // If the user has properly filled all the emits, they'll end up in their original order.
-    // If not, the queue will not be in the order the events will be intended to be filled,
-    // and we'll be able to later detect this and bail.
+    // so the most recently declared expectation is the one being filled.
+    // Once all of them are filled, ordered emits are checked in the order they were declared,
+    // while unordered emits may be matched by any emitted log.
 
     // First, we can return early if all events have been matched.
     // This allows a contract to arbitrarily emit more events than expected (additive behavior),
@@ -412,59 +732,94 @@ pub(crate) fn handle_expect_emit(state: &mut Cheatcodes, log: &alloy_primitives:
         return
     }
 
-    // if there's anything to fill, we need to pop back.
-    // Otherwise, if there are any events that are unmatched, we try to match to match them
-    // in the order declared, so we start popping from the front (like a queue).
-    let mut event_to_fill_or_check =
-        if state.expected_emits.iter().any(|expected| expected.log.is_none()) {
-            state.expected_emits.pop_back()
-        } else {
-            state.expected_emits.pop_front()
-        }
-        .expect("we should have an emit to fill or check");
-
-    let Some(expected) = &event_to_fill_or_check.log else {
-        // Fill the event.
-        event_to_fill_or_check.log = Some(log.data.clone());
-        state.expected_emits.push_back(event_to_fill_or_check);
+    // If there's anything to fill, fill the last declared expectation.
+    if let Some(expected) =
+        state.expected_emits.iter_mut().rev().find(|expected| expected.log.is_none())
+    {
+        expected.log = Some(log.data.clone());
         return
-    };
+    }
+
+    // Otherwise, try to match the next ordered expectation first, so that unordered expectations
+    // don't steal logs from it.
+    let next_ordered = state
+        .expected_emits
+        .iter()
+        .position(|expected| !expected.found && expected.mode == ExpectedEmitMode::Ordered);
+    let unordered =
+        state.expected_emits.iter().enumerate().filter(|(_, expected)| {
+            !expected.found && expected.mode == ExpectedEmitMode::Unordered
+        });
+    let candidates = next_ordered.into_iter().chain(unordered.map(|(i, _)| i)).collect::<Vec<_>>();
+
+    for i in candidates {
+        let expected = &mut state.expected_emits[i];
+        match expected.matches(log) {
+            Ok(()) => {
+                expected.found = true;
+                return
+            }
+            Err(Some(mismatch)) => expected.mismatch = Some(mismatch),
+            Err(None) => {}
+        }
+    }
+}
 
-    let expected_topic_0 = expected.topics().first();
-    let log_topic_0 = log.topics().first();
+impl ExpectedEmit {
+    /// Checks whether the given log matches this expectation.
+    ///
+    /// Returns a description of the mismatch if the log is the same event as the expected one, but
+    /// doesn't match it.
+    fn matches(&self, log: &alloy_primitives::Log) -> Result<(), Option<String>> {
+        let Some(expected) = &self.log else { return Err(None) };
+
+        // Logs with a different signature or number of topics are different events.
+        let expected_topic_0 = expected.topics().first();
+        let log_topic_0 = log.topics().first();
+        if expected_topic_0
+            .zip(log_topic_0)
+            .map_or(true, |(a, b)| a != b || expected.topics().len() != log.topics().len())
+        {
+            return Err(None)
+        }
 
-    if expected_topic_0
-        .zip(log_topic_0)
-        .map_or(false, |(a, b)| a == b && expected.topics().len() == log.topics().len())
-    {
         // Match topics
-        event_to_fill_or_check.found = log
-            .topics()
-            .iter()
-            .skip(1)
-            .enumerate()
-            .filter(|(i, _)| event_to_fill_or_check.checks[*i])
-            .all(|(i, topic)| topic == &expected.topics()[i + 1]);
+        for (i, (expected, got)) in expected.topics().iter().zip(log.topics()).enumerate().skip(1) {
+            if self.checks[i - 1] && expected != got {
+                return Err(Some(format!("topic {i}: expected {expected}, got {got}")))
+            }
+        }
 
         // Maybe match source address
-        if let Some(addr) = event_to_fill_or_check.address {
-            event_to_fill_or_check.found &= addr == log.address;
+        if let Some(addr) = self.address {
+            if addr != log.address {
+                return Err(Some(format!("emitter: expected {addr}, got {}", log.address)))
+            }
         }
 
         // Maybe match data
-        if event_to_fill_or_check.checks[3] {
-            event_to_fill_or_check.found &= expected.data.as_ref() == log.data.data.as_ref();
+        if self.checks[3] {
+            let (expected, got) = (expected.data.as_ref(), log.data.data.as_ref());
+            if expected.len() != got.len() {
+                return Err(Some(format!(
+                    "data: expected {} bytes, got {} bytes",
+                    expected.len(),
+                    got.len()
+                )))
+            }
+            for (i, (expected, got)) in expected.chunks(32).zip(got.chunks(32)).enumerate() {
+                let checked = self.data_mask.as_ref().and_then(|mask| mask.get(i)).copied();
+                if checked.unwrap_or(true) && expected != got {
+                    return Err(Some(format!(
+                        "data word {i}: expected {}, got {}",
+                        hex::encode_prefixed(expected),
+                        hex::encode_prefixed(got)
+                    )))
+                }
+            }
         }
-    }
 
-    // If we found the event, we can push it to the back of the queue
-    // and begin expecting the next event.
-    if event_to_fill_or_check.found {
-        state.expected_emits.push_back(event_to_fill_or_check);
-    } else {
-        // We did not match this event, so we need to keep waiting for the right one to
-        // appear.
-        state.expected_emits.push_front(event_to_fill_or_check);
+        Ok(())
     }
 }
 
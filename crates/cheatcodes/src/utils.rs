@@ -147,35 +147,36 @@ impl Cheatcode for ensNamehashCall {
 }
 
 impl Cheatcode for randomUint_0Call {
-    fn apply(&self, _state: &mut Cheatcodes) -> Result {
+    fn apply_stateful<DB: DatabaseExt>(&self, ccx: &mut CheatsCtxt<DB>) -> Result {
         let Self {} = self;
-        // Use thread_rng to get a random number
-        let mut rng = rand::thread_rng();
-        let random_number: U256 = rng.gen();
-        Ok(random_number.abi_encode())
+        Ok(random_u256(ccx).abi_encode())
     }
 }
 
 impl Cheatcode for randomUint_1Call {
-    fn apply(&self, _state: &mut Cheatcodes) -> Result {
+    fn apply_stateful<DB: DatabaseExt>(&self, ccx: &mut CheatsCtxt<DB>) -> Result {
         let Self { min, max } = *self;
         ensure!(min <= max, "min must be less than or equal to max");
         // Generate random between range min..=max
-        let mut rng = rand::thread_rng();
         let range = max - min + U256::from(1);
-        let random_number = rng.gen::<U256>() % range + min;
+        let random_number = random_u256(ccx) % range + min;
         Ok(random_number.abi_encode())
     }
 }
 
 impl Cheatcode for randomAddressCall {
-    fn apply(&self, _state: &mut Cheatcodes) -> Result {
+    fn apply_stateful<DB: DatabaseExt>(&self, ccx: &mut CheatsCtxt<DB>) -> Result {
         let Self {} = self;
-        let addr = Address::random();
+        let addr = Address::from_word(random_u256(ccx).into());
         Ok(addr.abi_encode())
     }
 }
 
+/// Returns a random number, derived from the seed of the run when set.
+fn random_u256<DB: DatabaseExt>(ccx: &mut CheatsCtxt<DB>) -> U256 {
+    ccx.state.rng(&ccx.ecx.env.tx.data).gen()
+}
+
 /// Using a given private key, return its public ETH address, its public key affine x and y
 /// coordinates, and its private key (see the 'Wallet' struct)
 ///
@@ -32,8 +32,10 @@ pub enum ChiselCommand {
     /// Clear the cache of all stored sessions
     ClearCache,
     /// Fork an RPC in the current session
-    /// Takes <fork-url|env-var|rpc_endpoints-alias>
+    /// Takes <fork-url|env-var|rpc_endpoints-alias> [block]
     Fork,
+    /// Display the state changes of the current session versus the fork, or the local state
+    State,
     /// Enable / disable traces for the current session
     Traces,
     /// Set calldata (`msg.data`) for the current session (appended after function selector)
@@ -70,6 +72,7 @@ impl FromStr for ChiselCommand {
             "load" | "l" => Ok(Self::Load),
             "clearcache" | "cc" => Ok(Self::ClearCache),
             "fork" | "f" => Ok(Self::Fork),
+            "state" | "st" => Ok(Self::State),
             "traces" | "t" => Ok(Self::Traces),
             "calldata" | "cd" => Ok(Self::Calldata),
             "memdump" | "md" => Ok(Self::MemDump),
@@ -133,7 +136,8 @@ impl From<ChiselCommand> for CmdDescriptor {
             ChiselCommand::Export => (&["export", "ex"], "Export the current session source to a script file", CmdCategory::Session),
             ChiselCommand::Fetch => (&["fetch <addr> <name>", "fe <addr> <name>"], "Fetch the interface of a verified contract on Etherscan", CmdCategory::Session),
             // Environment
-            ChiselCommand::Fork => (&["fork <url>", "f <url>"], "Fork an RPC for the current session. Supply 0 arguments to return to a local network", CmdCategory::Env),
+            ChiselCommand::Fork => (&["fork <url> [block]", "f <url> [block]"], "Fork an RPC for the current session, optionally at a block. Supply 0 arguments to return to a local network", CmdCategory::Env),
+            ChiselCommand::State => (&["state", "st"], "Display the state changes of the current session versus the fork, or the local network", CmdCategory::Env),
             ChiselCommand::Traces => (&["traces", "t"], "Enable / disable traces for the current session", CmdCategory::Env),
             ChiselCommand::Calldata => (&["calldata [data]", "cd [data]"], "Set calldata (`msg.data`) for the current session (appended after function selector). Clears it if no argument provided.", CmdCategory::Env),
             // Debug
@@ -13,7 +13,7 @@ use crate::{
 use alloy_json_abi::JsonAbi;
 use alloy_primitives::{hex, Address};
 use forge_fmt::FormatterConfig;
-use foundry_config::{Config, RpcEndpoint};
+use foundry_config::RpcEndpoint;
 use foundry_evm::{
     decode::decode_console_logs,
     traces::{
@@ -335,16 +335,27 @@ impl ChiselDispatcher {
             ChiselCommand::Fork => {
                 if args.is_empty() || args[0].trim().is_empty() {
                     self.source_mut().config.evm_opts.fork_url = None;
+                    self.source_mut().config.evm_opts.fork_block_number = None;
+                    self.source_mut().config.backend = None;
                     return DispatchResult::CommandSuccess(Some(
                         "Now using local environment.".to_string(),
                     ))
                 }
-                if args.len() != 1 {
+                if args.len() > 2 {
                     return DispatchResult::CommandFailed(Self::make_error(
-                        "Must supply a session ID as the argument.",
+                        "Too many arguments supplied! Expected: <url> [block]",
                     ))
                 }
                 let arg = *args.first().unwrap();
+                let fork_block_number = match args.get(1).map(|block| block.parse::<u64>()) {
+                    Some(Ok(block)) => Some(block),
+                    Some(Err(_)) => {
+                        return DispatchResult::CommandFailed(Self::make_error(
+                            "Invalid block number!",
+                        ))
+                    }
+                    None => None,
+                };
 
                 // If the argument is an RPC alias designated in the
                 // `[rpc_endpoints]` section of the `foundry.toml` within
@@ -372,11 +383,15 @@ impl ChiselDispatcher {
                 }
 
                 // Create success message before moving the fork_url
-                let success_msg = format!("Set fork URL to {}", &fork_url.yellow());
+                let mut success_msg = format!("Set fork URL to {}", &fork_url.yellow());
+                if let Some(block) = fork_block_number {
+                    success_msg.push_str(&format!(" at block {}", block.yellow()));
+                }
 
-                // Update the fork_url inside of the [SessionSourceConfig]'s [EvmOpts]
+                // Update the fork_url and block inside of the [SessionSourceConfig]'s [EvmOpts]
                 // field
                 self.source_mut().config.evm_opts.fork_url = Some(fork_url);
+                self.source_mut().config.evm_opts.fork_block_number = fork_block_number;
 
                 // Clear the backend so that it is re-instantiated with the new fork
                 // upon the next execution of the session source.
@@ -384,6 +399,36 @@ impl ChiselDispatcher {
 
                 DispatchResult::CommandSuccess(Some(success_msg))
             }
+            ChiselCommand::State => match self.source_mut().state_diff().await {
+                Ok(diffs) if diffs.is_empty() => {
+                    DispatchResult::CommandSuccess(Some("No state changes.".to_string()))
+                }
+                Ok(diffs) => {
+                    let against = if self.source().config.evm_opts.fork_url.is_some() {
+                        "the fork"
+                    } else {
+                        "the local network"
+                    };
+                    let mut out = format!("State changes versus {against}").cyan().to_string();
+                    for diff in diffs {
+                        out.push_str(&format!("\n{}", diff.address.to_string().yellow()));
+                        if let Some((before, after)) = diff.balance {
+                            out.push_str(&format!("\n  balance: {before} → {after}"));
+                        }
+                        if let Some((before, after)) = diff.nonce {
+                            out.push_str(&format!("\n  nonce: {before} → {after}"));
+                        }
+                        if diff.code_changed {
+                            out.push_str("\n  code: changed");
+                        }
+                        for (slot, (before, after)) in diff.storage {
+                            out.push_str(&format!("\n  {slot:#x}: {before:#x} → {after:#x}"));
+                        }
+                    }
+                    DispatchResult::CommandSuccess(Some(out))
+                }
+                Err(e) => DispatchResult::CommandFailed(Self::make_error(e.to_string())),
+            },
             ChiselCommand::Traces => {
                 self.source_mut().config.traces = !self.source_mut().config.traces;
                 DispatchResult::CommandSuccess(Some(format!(
@@ -895,9 +940,8 @@ impl ChiselDispatcher {
     ) -> eyre::Result<CallTraceDecoder> {
         let mut decoder = CallTraceDecoderBuilder::new()
             .with_labels(result.labeled_addresses.clone())
-            .with_signature_identifier(SignaturesIdentifier::new(
-                Config::foundry_cache_dir(),
-                session_config.foundry_config.offline,
+            .with_signature_identifier(SignaturesIdentifier::from_config(
+                &session_config.foundry_config,
             )?)
             .build();
 
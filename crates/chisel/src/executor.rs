@@ -3,7 +3,8 @@
 //! This module contains the execution logic for the [SessionSource].
 
 use crate::prelude::{
-    ChiselDispatcher, ChiselResult, ChiselRunner, IntermediateOutput, SessionSource, SolidityHelper,
+    diff_state, AccountDiff, ChiselDispatcher, ChiselResult, ChiselRunner, IntermediateOutput,
+    SessionSource, SolidityHelper,
 };
 use alloy_dyn_abi::{DynSolType, DynSolValue};
 use alloy_json_abi::EventParam;
@@ -12,7 +13,10 @@ use core::fmt::Debug;
 use eyre::{Result, WrapErr};
 use foundry_compilers::Artifact;
 use foundry_evm::{
-    backend::Backend, decode::decode_console_logs, executors::ExecutorBuilder,
+    backend::Backend,
+    constants::{CHEATCODE_ADDRESS, HARDHAT_CONSOLE_ADDRESS},
+    decode::decode_console_logs,
+    executors::ExecutorBuilder,
     inspectors::CheatsConfig,
 };
 use solang_parser::pt::{self, CodeLocation};
@@ -119,6 +123,20 @@ impl SessionSource {
         }
     }
 
+    /// Runs the source and compares the state of the accounts it touched to the state before the
+    /// session, i.e. the fork or the local state.
+    ///
+    /// ### Returns
+    ///
+    /// The [AccountDiff]s of the accounts that changed, excluding the REPL contract, its sender,
+    /// the cheatcodes and the console.
+    pub async fn state_diff(&mut self) -> Result<Vec<AccountDiff>> {
+        let (address, result) = self.execute().await?;
+        let Some(backend) = self.config.backend.as_ref() else { return Ok(Vec::new()) };
+        let ignored = [address, Address::ZERO, CHEATCODE_ADDRESS, HARDHAT_CONSOLE_ADDRESS];
+        Ok(diff_state(backend, &result.state_changeset, &ignored)?)
+    }
+
     /// Inspect a contract element inside of the current session
     ///
     /// ### Takes
@@ -288,9 +306,10 @@ impl SessionSource {
         let env =
             self.config.evm_opts.evm_env().await.expect("Could not instantiate fork environment");
 
-        // Create an in-memory backend
-        let backend = match self.config.backend.take() {
-            Some(backend) => backend,
+        // Reuse the backend of the session, so that forks are only created once. The executor gets
+        // a copy, leaving the session's backend in its initial state for the next run.
+        let backend = match self.config.backend.as_ref() {
+            Some(backend) => backend.clone(),
             None => {
                 let fork = self.config.evm_opts.get_fork(&self.config.foundry_config, env.clone());
                 let backend = Backend::spawn(fork);
@@ -1666,6 +1685,50 @@ mod tests {
         generic_type_test(&mut source(), global_variables);
     }
 
+    #[test]
+    fn test_state_diff() {
+        use foundry_evm::revm::{
+            db::{CacheDB, EmptyDB},
+            primitives::{Account, AccountInfo, EvmStorageSlot},
+        };
+
+        let unchanged = Address::with_last_byte(1);
+        let dealt = Address::with_last_byte(2);
+        let ignored = Address::with_last_byte(3);
+        let mut db = CacheDB::new(EmptyDB::default());
+        db.insert_account_info(unchanged, AccountInfo { nonce: 1, ..Default::default() });
+        db.insert_account_storage(dealt, U256::from(1), U256::from(5)).unwrap();
+
+        let account = |nonce, balance, storage: Vec<(u64, u64)>| Account {
+            info: AccountInfo { nonce, balance: U256::from(balance), ..Default::default() },
+            storage: storage
+                .into_iter()
+                .map(|(slot, value)| {
+                    (U256::from(slot), EvmStorageSlot::new_changed(U256::ZERO, U256::from(value)))
+                })
+                .collect(),
+            ..Default::default()
+        };
+        let changeset = [
+            (unchanged, account(1, 0, vec![])),
+            (dealt, account(0, 100, vec![(1, 5), (2, 7)])),
+            (ignored, account(0, 100, vec![])),
+        ]
+        .into_iter()
+        .collect();
+
+        let diff = diff_state(&db, &changeset, &[ignored]).unwrap();
+        assert_eq!(
+            diff,
+            vec![AccountDiff {
+                address: dealt,
+                balance: Some((U256::ZERO, U256::from(100))),
+                storage: [(U256::from(2), (U256::ZERO, U256::from(7)))].into_iter().collect(),
+                ..Default::default()
+            }]
+        );
+    }
+
     #[track_caller]
     fn source() -> SessionSource {
         // synchronize solc install
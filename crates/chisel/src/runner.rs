@@ -3,14 +3,19 @@
 //! This module contains the `ChiselRunner` struct, which assists with deploying
 //! and calling the REPL contract on a in-memory REVM instance.
 
-use alloy_primitives::{Address, Bytes, Log, U256};
+use alloy_primitives::{Address, Bytes, Log, B256, U256};
 use eyre::Result;
 use foundry_evm::{
     executors::{DeployResult, Executor, RawCallResult},
     traces::{CallTraceArena, TraceKind},
+    utils::StateChangeset,
 };
-use revm::interpreter::{return_ok, InstructionResult};
-use std::collections::HashMap;
+use revm::{
+    interpreter::{return_ok, InstructionResult},
+    primitives::KECCAK_EMPTY,
+    DatabaseRef,
+};
+use std::collections::{BTreeMap, HashMap};
 
 /// The function selector of the REPL contract's entrypoint, the `run()` function.
 static RUN_SELECTOR: [u8; 4] = [0xc0, 0x40, 0x62, 0x26];
@@ -50,6 +55,76 @@ pub struct ChiselResult {
     pub address: Option<Address>,
     /// EVM State at the final instruction of the `run()` function
     pub state: Option<(Vec<U256>, Vec<u8>, InstructionResult)>,
+    /// The accounts touched by the `run()` function, with their state after the call
+    pub state_changeset: StateChangeset,
+}
+
+/// The difference of an account's state after a run, versus its state before the session
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AccountDiff {
+    /// The address of the account
+    pub address: Address,
+    /// The balance before and after, if it changed
+    pub balance: Option<(U256, U256)>,
+    /// The nonce before and after, if it changed
+    pub nonce: Option<(u64, u64)>,
+    /// Whether the code changed, e.g. by deploying or etching a contract
+    pub code_changed: bool,
+    /// The changed storage slots, with their values before and after
+    pub storage: BTreeMap<U256, (U256, U256)>,
+}
+
+impl AccountDiff {
+    /// Returns `true` if nothing changed
+    pub fn is_empty(&self) -> bool {
+        self.balance.is_none() &&
+            self.nonce.is_none() &&
+            !self.code_changed &&
+            self.storage.is_empty()
+    }
+}
+
+/// Compares the accounts of a changeset to their state in `db`, skipping the `ignored` accounts.
+///
+/// ### Returns
+///
+/// The [AccountDiff]s of the accounts that changed, ordered by address.
+pub fn diff_state<DB: DatabaseRef>(
+    db: &DB,
+    changeset: &StateChangeset,
+    ignored: &[Address],
+) -> Result<Vec<AccountDiff>, DB::Error> {
+    let mut diffs = Vec::new();
+    for (address, account) in changeset {
+        if ignored.contains(address) {
+            continue
+        }
+        let before = db.basic_ref(*address)?.unwrap_or_default();
+        let after = &account.info;
+
+        let mut diff = AccountDiff { address: *address, ..Default::default() };
+        if before.balance != after.balance {
+            diff.balance = Some((before.balance, after.balance));
+        }
+        if before.nonce != after.nonce {
+            diff.nonce = Some((before.nonce, after.nonce));
+        }
+        // An account that doesn't exist has the empty code hash by default
+        let code_hash = |hash: B256| if hash.is_zero() { KECCAK_EMPTY } else { hash };
+        diff.code_changed = code_hash(before.code_hash) != code_hash(after.code_hash);
+        for (slot, value) in &account.storage {
+            let before = db.storage_ref(*address, *slot)?;
+            if before != value.present_value {
+                diff.storage.insert(*slot, (before, value.present_value));
+            }
+        }
+
+        if !diff.is_empty() {
+            diffs.push(diff);
+        }
+    }
+    diffs.sort_by_key(|diff| diff.address);
+    Ok(diffs)
 }
 
 /// ChiselRunner implementation
@@ -193,7 +268,16 @@ impl ChiselRunner {
             res = self.executor.transact_raw(from, to, calldata, value)?;
         }
 
-        let RawCallResult { result, reverted, logs, traces, labels, chisel_state, .. } = res;
+        let RawCallResult {
+            result,
+            reverted,
+            logs,
+            traces,
+            labels,
+            chisel_state,
+            state_changeset,
+            ..
+        } = res;
 
         Ok(ChiselResult {
             returned: result,
@@ -210,6 +294,7 @@ impl ChiselRunner {
             labeled_addresses: labels,
             address: None,
             state: chisel_state,
+            state_changeset,
         })
     }
 }
@@ -1,5 +1,8 @@
 use super::ProjectPathsArgs;
-use crate::{opts::CompilerArgs, utils::LoadConfig};
+use crate::{
+    opts::{CompilerArgs, ProgressArgs},
+    utils::LoadConfig,
+};
 use clap::{Parser, ValueHint};
 use eyre::Result;
 use foundry_compilers::{
@@ -74,6 +77,14 @@ pub struct CoreBuildArgs {
     #[serde(skip)]
     pub via_ir: bool,
 
+    /// The maximum number of compiler processes to run in parallel.
+    ///
+    /// Defaults to the number of available CPUs. Fewer processes are run if they are not expected
+    /// to fit into `compiler_memory_limit`, which defaults to the available memory.
+    #[arg(long, help_heading = "Compiler options", value_name = "JOBS")]
+    #[serde(rename = "compiler_jobs", skip_serializing_if = "Option::is_none")]
+    pub jobs: Option<usize>,
+
     /// Do not append any metadata to the bytecode.
     ///
     /// This is equivalent to setting `bytecode_hash` to `none` and `cbor_metadata` to `false`.
@@ -105,6 +116,10 @@ pub struct CoreBuildArgs {
     #[serde(skip)]
     pub silent: bool,
 
+    #[command(flatten)]
+    #[serde(skip)]
+    pub progress: ProgressArgs,
+
     /// Generate build info files.
     #[arg(long, help_heading = "Project options")]
     #[serde(skip)]
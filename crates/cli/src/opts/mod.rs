@@ -2,10 +2,14 @@ mod build;
 mod chain;
 mod dependency;
 mod ethereum;
+mod progress;
+mod state_override;
 mod transaction;
 
 pub use build::*;
 pub use chain::*;
 pub use dependency::*;
 pub use ethereum::*;
+pub use progress::*;
+pub use state_override::*;
 pub use transaction::*;
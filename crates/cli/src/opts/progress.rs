@@ -0,0 +1,34 @@
+use clap::Parser;
+use foundry_common::progress::{set_progress_mode, ProgressMode};
+
+/// How the progress of long running phases is reported.
+#[derive(Clone, Copy, Debug, Default, Parser)]
+#[command(next_help_heading = "Display options")]
+pub struct ProgressArgs {
+    /// Do not report progress, e.g. compilation spinners or broadcast progress bars.
+    #[arg(long, conflicts_with = "porcelain")]
+    pub quiet: bool,
+
+    /// Report progress as stable, tab separated `<phase>\t<event>\t<detail>` lines on stderr
+    /// instead of drawing bars and spinners.
+    #[arg(long)]
+    pub porcelain: bool,
+}
+
+impl ProgressArgs {
+    /// Returns the selected progress mode.
+    pub fn mode(&self) -> ProgressMode {
+        if self.quiet {
+            ProgressMode::Quiet
+        } else if self.porcelain {
+            ProgressMode::Porcelain
+        } else {
+            ProgressMode::Auto
+        }
+    }
+
+    /// Sets the progress mode of the program to the selected one.
+    pub fn init(&self) {
+        set_progress_mode(self.mode());
+    }
+}
@@ -0,0 +1,124 @@
+use crate::utils::parse_ether_value;
+use alloy_primitives::{Address, Bytes, B256, U256};
+use alloy_rpc_types::state::StateOverride;
+use clap::Parser;
+use eyre::{Context, ContextCompat, Result};
+use std::str::FromStr;
+
+/// Overrides of the state a call is executed on, like `eth_call`'s state override set.
+#[derive(Clone, Debug, Default, Parser)]
+#[command(next_help_heading = "State override options")]
+pub struct StateOverrideOpts {
+    /// Overrides the balance of an account, either specified in wei, or as a string with a unit
+    /// type.
+    ///
+    /// Examples: 0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045:1ether
+    #[arg(long, value_name = "ADDRESS:BALANCE", value_parser = parse_balance_override)]
+    pub override_balance: Vec<(Address, U256)>,
+
+    /// Overrides the code of an account.
+    #[arg(long, value_name = "ADDRESS:CODE", value_parser = parse_code_override)]
+    pub override_code: Vec<(Address, Bytes)>,
+
+    /// Overrides the value of a storage slot of an account, leaving the other slots untouched.
+    #[arg(long, value_name = "ADDRESS:SLOT:VALUE", value_parser = parse_storage_override)]
+    pub override_storage: Vec<(Address, B256, B256)>,
+}
+
+impl StateOverrideOpts {
+    /// Returns true if no override is set.
+    pub fn is_empty(&self) -> bool {
+        self.override_balance.is_empty() &&
+            self.override_code.is_empty() &&
+            self.override_storage.is_empty()
+    }
+
+    /// Returns the state override set, or `None` if no override is set.
+    pub fn state_override(&self) -> Option<StateOverride> {
+        if self.is_empty() {
+            return None
+        }
+
+        let mut overrides = StateOverride::default();
+        for (address, balance) in &self.override_balance {
+            overrides.entry(*address).or_default().balance = Some(*balance);
+        }
+        for (address, code) in &self.override_code {
+            overrides.entry(*address).or_default().code = Some(code.clone());
+        }
+        for (address, slot, value) in &self.override_storage {
+            overrides
+                .entry(*address)
+                .or_default()
+                .state_diff
+                .get_or_insert_with(Default::default)
+                .insert(*slot, *value);
+        }
+        Some(overrides)
+    }
+}
+
+/// Splits an override into the address it applies to and the rest.
+fn split_override(s: &str) -> Result<(Address, &str)> {
+    let (address, rest) = s.split_once(':').wrap_err("expected `ADDRESS:...`")?;
+    let address = Address::from_str(address).wrap_err("invalid address")?;
+    Ok((address, rest))
+}
+
+fn parse_balance_override(s: &str) -> Result<(Address, U256)> {
+    let (address, balance) = split_override(s)?;
+    Ok((address, parse_ether_value(balance)?))
+}
+
+fn parse_code_override(s: &str) -> Result<(Address, Bytes)> {
+    let (address, code) = split_override(s)?;
+    Ok((address, Bytes::from_str(code).wrap_err("invalid code")?))
+}
+
+fn parse_storage_override(s: &str) -> Result<(Address, B256, B256)> {
+    let (address, rest) = split_override(s)?;
+    let (slot, value) = rest.split_once(':').wrap_err("expected `ADDRESS:SLOT:VALUE`")?;
+    let word = |s: &str| -> Result<B256> {
+        Ok(U256::from_str(s).wrap_err_with(|| format!("invalid storage word: {s}"))?.into())
+    };
+    Ok((address, word(slot)?, word(value)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::address;
+
+    #[test]
+    fn parse_state_overrides() {
+        let opts = StateOverrideOpts::parse_from([
+            "foundry-cli",
+            "--override-balance",
+            "0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045:1ether",
+            "--override-code",
+            "0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045:0x6000",
+            "--override-storage",
+            "0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045:1:0x2a",
+            "--override-storage",
+            "0x0000000000000000000000000000000000000001:0:1",
+        ]);
+
+        let overrides = opts.state_override().unwrap();
+        assert_eq!(overrides.len(), 2);
+
+        let vitalik = &overrides[&address!("d8dA6BF26964aF9D7eEd9e03E53415D37aA96045")];
+        assert_eq!(vitalik.balance, Some(U256::from(10).pow(U256::from(18))));
+        assert_eq!(vitalik.code, Some(Bytes::from_static(&[0x60, 0x00])));
+        let state_diff = vitalik.state_diff.as_ref().unwrap();
+        assert_eq!(state_diff[&B256::with_last_byte(1)], B256::with_last_byte(0x2a));
+
+        let one = &overrides[&Address::with_last_byte(1)];
+        assert_eq!(one.balance, None);
+        assert_eq!(one.state_diff.as_ref().unwrap()[&B256::ZERO], B256::with_last_byte(1));
+
+        assert!(StateOverrideOpts::parse_from(["foundry-cli"]).state_override().is_none());
+        assert!(
+            StateOverrideOpts::try_parse_from(["foundry-cli", "--override-code", "0x60"]).is_err()
+        );
+    }
+}
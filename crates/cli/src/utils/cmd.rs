@@ -1,7 +1,7 @@
 use alloy_json_abi::JsonAbi;
 use alloy_primitives::Address;
 use eyre::{Result, WrapErr};
-use foundry_common::{cli_warn, fs, TestFunctionExt};
+use foundry_common::{cli_warn, fs, progress, TestFunctionExt};
 use foundry_compilers::{
     artifacts::{CompactBytecode, CompactDeployedBytecode, Settings},
     cache::{CacheEntry, CompilerCache},
@@ -19,7 +19,6 @@ use foundry_evm::{
     },
 };
 use std::{
-    fmt::Write,
     path::{Path, PathBuf},
     str::FromStr,
 };
@@ -136,24 +135,9 @@ pub fn needs_setup(abi: &JsonAbi) -> bool {
     setup_fns.len() == 1 && setup_fns[0].name == "setUp"
 }
 
-pub fn eta_key(state: &indicatif::ProgressState, f: &mut dyn Write) {
-    write!(f, "{:.1}s", state.eta().as_secs_f64()).unwrap()
-}
-
+/// Returns a new bar counting `len` items named `label`, see [`progress::bar`].
 pub fn init_progress(len: u64, label: &str) -> indicatif::ProgressBar {
-    let pb = indicatif::ProgressBar::new(len);
-    let mut template =
-        "{prefix}{spinner:.green} [{elapsed_precise}] [{wide_bar:.cyan/blue}] {pos}/{len} "
-            .to_string();
-    write!(template, "{label}").unwrap();
-    template += " ({eta})";
-    pb.set_style(
-        indicatif::ProgressStyle::with_template(&template)
-            .unwrap()
-            .with_key("eta", crate::utils::eta_key)
-            .progress_chars("#>-"),
-    );
-    pb
+    progress::bar(len, label)
 }
 
 /// True if the network calculates gas costs differently.
@@ -364,10 +348,7 @@ pub async fn handle_traces(
     let config_labels = config.labels.clone().into_iter();
     let mut decoder = CallTraceDecoderBuilder::new()
         .with_labels(labels.chain(config_labels))
-        .with_signature_identifier(SignaturesIdentifier::new(
-            Config::foundry_cache_dir(),
-            config.offline,
-        )?)
+        .with_signature_identifier(SignaturesIdentifier::from_config(config)?)
         .build();
 
     let mut etherscan_identifier = EtherscanIdentifier::new(config, chain)?;
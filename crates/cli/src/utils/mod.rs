@@ -8,6 +8,7 @@ use foundry_config::{Chain, Config};
 use std::{
     ffi::OsStr,
     future::Future,
+    io::Write,
     path::{Path, PathBuf},
     process::{Command, Output, Stdio},
     time::{Duration, SystemTime, UNIX_EPOCH},
@@ -23,6 +24,9 @@ pub use suggestions::*;
 mod abi;
 pub use abi::*;
 
+mod storage;
+pub use storage::*;
+
 // reexport all `foundry_config::utils`
 #[doc(hidden)]
 pub use foundry_config::utils::*;
@@ -444,6 +448,51 @@ impl<'a> Git<'a> {
         self.cmd().args(["status", "--porcelain"]).exec().map(|out| out.stdout.is_empty())
     }
 
+    /// Returns the files added, copied, modified or renamed in the staging area, relative to the
+    /// root of the repository.
+    pub fn staged_files(self) -> Result<Vec<PathBuf>> {
+        self.cmd()
+            .args(["diff", "--cached", "--name-only", "--diff-filter=ACMR"])
+            .get_stdout_lossy()
+            .map(|stdout| stdout.lines().map(PathBuf::from).collect())
+    }
+
+    /// Returns the content of a file in the staging area, `path` being relative to the root of
+    /// the repository.
+    pub fn staged_content(self, path: &Path) -> Result<String> {
+        let output = self.cmd().arg("show").arg(format!(":{}", path.display())).exec()?;
+        Ok(String::from_utf8(output.stdout)?)
+    }
+
+    /// Replaces the content of a file in the staging area, keeping its mode.
+    ///
+    /// `path` is relative to the root of the repository, which must be the root of `self`.
+    pub fn stage_content(self, path: &Path, content: &str) -> Result<()> {
+        let entry = self.cmd().args(["ls-files", "--stage", "--"]).arg(path).get_stdout_lossy()?;
+        let mode = entry
+            .split_whitespace()
+            .next()
+            .wrap_err_with(|| format!("{} is not staged", path.display()))?;
+
+        let mut hash_object =
+            self.cmd().args(["hash-object", "-w", "--stdin"]).stdin(Stdio::piped()).spawn()?;
+        hash_object.stdin.take().unwrap().write_all(content.as_bytes())?;
+        let output = hash_object.wait_with_output()?;
+        if !output.status.success() {
+            eyre::bail!(
+                "git hash-object failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )
+        }
+        let hash = String::from_utf8_lossy(&output.stdout);
+
+        self.cmd()
+            .args(["update-index", "--cacheinfo"])
+            .arg(format!("{mode},{},{}", hash.trim(), path.display()))
+            .exec()
+            .map(drop)
+    }
+
     pub fn has_branch(self, branch: impl AsRef<OsStr>) -> Result<bool> {
         self.cmd()
             .args(["branch", "--list", "--no-color"])
@@ -0,0 +1,498 @@
+use alloy_primitives::{hex, keccak256, Address, B256, I256, U256};
+use alloy_provider::{network::AnyNetwork, Provider};
+use alloy_rpc_types::BlockId;
+use alloy_transport::Transport;
+use eyre::Result;
+use foundry_compilers::artifacts::{Storage, StorageLayout};
+use foundry_evm::inspectors::KeccakPreimages;
+use std::{
+    collections::{HashMap, HashSet},
+    marker::PhantomData,
+    str::FromStr,
+};
+
+/// The maximum length of a `bytes` or `string` value that is read when decoding the storage.
+const MAX_BYTES_LENGTH: usize = 32 * 1024;
+
+/// A mapping key to decode the entries of, optionally scoped to a state variable.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MappingKey {
+    /// The state variable whose mappings the key is used for, or all if `None`.
+    pub variable: Option<String>,
+    /// The key, as given.
+    pub key: String,
+}
+
+impl FromStr for MappingKey {
+    type Err = std::convert::Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // Only split on `=` if it's preceded by an identifier, `string` keys may contain it too.
+        if let Some((variable, key)) = s.split_once('=') {
+            if !variable.is_empty() &&
+                !variable.starts_with(|c: char| c.is_ascii_digit()) &&
+                variable.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$')
+            {
+                return Ok(Self { variable: Some(variable.to_string()), key: key.to_string() })
+            }
+        }
+        Ok(Self { variable: None, key: s.to_string() })
+    }
+}
+
+/// Options for decoding the storage of a contract by walking its storage layout.
+#[derive(Clone, Debug, Default)]
+pub struct DecodeOpts {
+    /// The keys to decode the entries of mappings of.
+    pub keys: Vec<MappingKey>,
+    /// The maximum number of elements of an array to decode.
+    pub max_elements: usize,
+    /// Recorded `KECCAK256` preimages, the keys hashed with the slot of a mapping are decoded
+    /// too.
+    pub preimages: KeccakPreimages,
+}
+
+/// A decoded value in the storage of a contract.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DecodedSlot {
+    /// The path to the value, e.g. `balances[0x..]` or `pools[2].owner`.
+    pub name: String,
+    /// The segments of the path, e.g. `["pools", "[2]", "owner"]`.
+    pub path: Vec<String>,
+    /// The Solidity type of the value.
+    pub label: String,
+    /// The slot the value is stored in, or starts at.
+    pub slot: U256,
+    /// The offset of the value in the slot.
+    pub offset: usize,
+    /// The number of bytes of the value in the slot.
+    pub number_of_bytes: String,
+    /// The decoded value.
+    pub value: String,
+    /// The raw value.
+    pub hex_value: String,
+    /// The contract that declared the state variable.
+    pub contract: String,
+}
+
+/// A value in the storage that is yet to be decoded.
+struct PendingSlot {
+    name: String,
+    path: Vec<String>,
+    type_id: String,
+    slot: U256,
+    offset: usize,
+    /// The state variable the value belongs to, to look up the keys of its mappings.
+    variable: String,
+    contract: String,
+}
+
+impl PendingSlot {
+    /// Returns the member `.name` or the entry `[key]` of this value.
+    fn child(&self, segment: String, type_id: &str, slot: U256, offset: usize) -> Self {
+        let mut path = self.path.clone();
+        path.push(segment.strip_prefix('.').unwrap_or(&segment).to_string());
+        Self {
+            name: format!("{}{segment}", self.name),
+            path,
+            type_id: type_id.to_string(),
+            slot,
+            offset,
+            variable: self.variable.clone(),
+            contract: self.contract.clone(),
+        }
+    }
+}
+
+/// Decodes the storage of a contract by walking its storage layout, reading the slots it needs
+/// from the provider.
+pub struct StorageDecoder<'a, P, T> {
+    provider: &'a P,
+    address: Address,
+    block: BlockId,
+    layout: &'a StorageLayout,
+    opts: &'a DecodeOpts,
+    /// The slots that were already read.
+    cache: HashMap<U256, B256>,
+    _transport: PhantomData<T>,
+}
+
+impl<'a, P: Provider<T, AnyNetwork>, T: Transport + Clone> StorageDecoder<'a, P, T> {
+    pub fn new(
+        provider: &'a P,
+        address: Address,
+        block: Option<BlockId>,
+        layout: &'a StorageLayout,
+        opts: &'a DecodeOpts,
+    ) -> Self {
+        Self {
+            provider,
+            address,
+            block: block.unwrap_or_default(),
+            layout,
+            opts,
+            cache: HashMap::new(),
+            _transport: PhantomData,
+        }
+    }
+
+    /// Walks the storage layout depth-first, returning the decoded values in declaration order.
+    pub async fn decode(mut self) -> Result<Vec<DecodedSlot>> {
+        let layout = self.layout;
+        let mut pending = Vec::new();
+        for storage in layout.storage.iter().rev() {
+            pending.push(PendingSlot {
+                name: storage.label.clone(),
+                path: vec![storage.label.clone()],
+                type_id: storage.storage_type.clone(),
+                slot: U256::from_str(&storage.slot)?,
+                offset: storage.offset as usize,
+                variable: storage.label.clone(),
+                contract: storage.contract.clone(),
+            });
+        }
+
+        let mut decoded = Vec::new();
+        let mut used_keys = HashSet::new();
+        while let Some(item) = pending.pop() {
+            let Some(ty) = layout.types.get(&item.type_id) else { continue };
+            let mut children = Vec::new();
+            match ty.encoding.as_str() {
+                "mapping" => {
+                    let (Some(key_id), Some(value_id)) = (&ty.key, &ty.value) else { continue };
+                    let key_ty = layout.types.get(key_id);
+                    let key_label = key_ty.map_or("", |t| t.label.as_str());
+                    let mut encoded_keys = HashSet::new();
+                    for (idx, key) in self.opts.keys.iter().enumerate() {
+                        if key.variable.as_ref().is_some_and(|variable| *variable != item.variable)
+                        {
+                            continue
+                        }
+                        let Ok(encoded) = encode_mapping_key(key_label, &key.key) else { continue };
+                        used_keys.insert(idx);
+                        let slot = mapping_slot(&encoded, item.slot);
+                        encoded_keys.insert(encoded);
+                        children.push(item.child(format!("[{}]", key.key), value_id, slot, 0));
+                    }
+
+                    // Keys that were hashed with the slot of the mapping in a recorded execution.
+                    let key_size =
+                        key_ty.and_then(|t| t.number_of_bytes.parse().ok()).unwrap_or(32);
+                    for key in self.opts.preimages.mapping_keys(&B256::from(item.slot)) {
+                        if encoded_keys.contains(key.as_ref()) {
+                            continue
+                        }
+                        let Some(formatted) = format_mapping_key(key_label, &key, key_size) else {
+                            continue
+                        };
+                        let slot = mapping_slot(&key, item.slot);
+                        children.push(item.child(format!("[{formatted}]"), value_id, slot, 0));
+                    }
+                }
+                "dynamic_array" => {
+                    let word = self.read(item.slot).await?;
+                    let length = U256::from_be_bytes(word.0);
+                    decoded.push(self.decoded(&item, length.to_string(), word.to_string()));
+                    if let Some(base) = ty.other.get("base").and_then(|base| base.as_str()) {
+                        let data = U256::from_be_bytes(keccak256(item.slot.to_be_bytes::<32>()).0);
+                        let length = usize::try_from(length).unwrap_or(usize::MAX);
+                        children = self.elements(&item, base, data, length);
+                    }
+                }
+                "bytes" => {
+                    let data = self.read_bytes(item.slot).await?;
+                    let value = if ty.label == "string" {
+                        format!("{:?}", String::from_utf8_lossy(&data))
+                    } else {
+                        hex::encode_prefixed(&data)
+                    };
+                    decoded.push(self.decoded(&item, value, hex::encode_prefixed(&data)));
+                }
+                _ => {
+                    if let Some(members) = ty.other.get("members").and_then(|members| {
+                        serde_json::from_value::<Vec<Storage>>(members.clone()).ok()
+                    }) {
+                        for member in members {
+                            let slot = item.slot.wrapping_add(U256::from_str(&member.slot)?);
+                            children.push(item.child(
+                                format!(".{}", member.label),
+                                &member.storage_type,
+                                slot,
+                                member.offset as usize,
+                            ));
+                        }
+                    } else if let Some(base) = ty.other.get("base").and_then(|base| base.as_str()) {
+                        let length = ty
+                            .label
+                            .rsplit_once('[')
+                            .and_then(|(_, length)| length.trim_end_matches(']').parse().ok())
+                            .unwrap_or_default();
+                        children = self.elements(&item, base, item.slot, length);
+                    } else {
+                        let number_of_bytes = ty.number_of_bytes.parse().unwrap_or(32);
+                        let word =
+                            slot_value(self.read(item.slot).await?, item.offset, number_of_bytes);
+                        let value = format_value(&ty.label, word, number_of_bytes);
+                        decoded.push(self.decoded(&item, value, word.to_string()));
+                    }
+                }
+            }
+            pending.extend(children.into_iter().rev());
+        }
+
+        for (idx, key) in self.opts.keys.iter().enumerate() {
+            if let (Some(variable), false) = (&key.variable, used_keys.contains(&idx)) {
+                eyre::bail!("`{}` is not a valid key of any mapping of `{variable}`", key.key);
+            }
+        }
+
+        Ok(decoded)
+    }
+
+    /// Returns the first elements of an array, up to the configured maximum.
+    fn elements(
+        &self,
+        item: &PendingSlot,
+        base: &str,
+        data: U256,
+        length: usize,
+    ) -> Vec<PendingSlot> {
+        let size = self
+            .layout
+            .types
+            .get(base)
+            .and_then(|base| base.number_of_bytes.parse().ok())
+            .unwrap_or(32);
+        (0..length.min(self.opts.max_elements))
+            .map(|index| {
+                let (slot, offset) = element_position(data, index, size);
+                item.child(format!("[{index}]"), base, slot, offset)
+            })
+            .collect()
+    }
+
+    fn decoded(&self, item: &PendingSlot, value: String, hex_value: String) -> DecodedSlot {
+        let ty = &self.layout.types[&item.type_id];
+        DecodedSlot {
+            name: item.name.clone(),
+            path: item.path.clone(),
+            label: ty.label.clone(),
+            slot: item.slot,
+            offset: item.offset,
+            number_of_bytes: ty.number_of_bytes.clone(),
+            value,
+            hex_value,
+            contract: item.contract.clone(),
+        }
+    }
+
+    async fn read(&mut self, slot: U256) -> Result<B256> {
+        if let Some(value) = self.cache.get(&slot) {
+            return Ok(*value)
+        }
+        let value: B256 =
+            self.provider.get_storage_at(self.address, slot).block_id(self.block).await?.into();
+        self.cache.insert(slot, value);
+        Ok(value)
+    }
+
+    /// Reads a `bytes` or `string` value, which is stored in the slot itself if it's shorter than
+    /// 32 bytes, and starting at the hash of the slot otherwise.
+    async fn read_bytes(&mut self, slot: U256) -> Result<Vec<u8>> {
+        let word = self.read(slot).await?;
+        if word[31] & 1 == 0 {
+            let length = (word[31] / 2) as usize;
+            return Ok(word[..length.min(31)].to_vec())
+        }
+
+        let length = U256::from_be_bytes(word.0) >> 1;
+        let length = usize::try_from(length).unwrap_or(usize::MAX).min(MAX_BYTES_LENGTH);
+        let data = U256::from_be_bytes(keccak256(slot.to_be_bytes::<32>()).0);
+        let mut bytes = Vec::with_capacity(length.next_multiple_of(32));
+        for index in 0..length.div_ceil(32) {
+            bytes.extend_from_slice(
+                self.read(data.wrapping_add(U256::from(index))).await?.as_slice(),
+            );
+        }
+        bytes.truncate(length);
+        Ok(bytes)
+    }
+}
+
+/// Returns the value of `number_of_bytes` bytes at `offset` in a slot, right-aligned.
+fn slot_value(word: B256, offset: usize, number_of_bytes: usize) -> B256 {
+    let end = (offset + number_of_bytes).min(32);
+    B256::left_padding_from(&word[32 - end..32 - offset.min(end)])
+}
+
+/// Returns the slot of the entry of a mapping at `slot` for the given encoded key.
+pub fn mapping_slot(key: &[u8], slot: U256) -> U256 {
+    let mut preimage = key.to_vec();
+    preimage.extend_from_slice(&slot.to_be_bytes::<32>());
+    U256::from_be_bytes(keccak256(preimage).0)
+}
+
+/// Returns the slot and offset of the element at `index` of an array whose data starts at `data`,
+/// with elements of `size` bytes.
+///
+/// Elements that fit are packed into a slot, larger ones start at a new slot.
+pub fn element_position(data: U256, index: usize, size: usize) -> (U256, usize) {
+    if size == 0 || size > 32 {
+        let slots = size.div_ceil(32).max(1);
+        (data.wrapping_add(U256::from(index) * U256::from(slots)), 0)
+    } else {
+        let per_slot = 32 / size;
+        (data.wrapping_add(U256::from(index / per_slot)), (index % per_slot) * size)
+    }
+}
+
+/// Encodes a mapping key of the given Solidity type, as it is hashed with the slot of the mapping.
+pub fn encode_mapping_key(label: &str, key: &str) -> Result<Vec<u8>> {
+    let word = match label {
+        "string" => return Ok(key.as_bytes().to_vec()),
+        "bytes" => return Ok(hex::decode(key)?),
+        "bool" => B256::with_last_byte(key.parse::<bool>()? as u8),
+        "address" | "address payable" => Address::from_str(key)?.into_word(),
+        _ if label.starts_with("contract ") => Address::from_str(key)?.into_word(),
+        _ if label.starts_with("uint") || label.starts_with("enum ") => U256::from_str(key)?.into(),
+        _ if label.starts_with("int") => key.parse::<I256>()?.into_raw().into(),
+        _ if label.starts_with("bytes") => {
+            let size: usize = label["bytes".len()..].parse()?;
+            let bytes = hex::decode(key)?;
+            if bytes.len() > size {
+                eyre::bail!("`{key}` is longer than {size} bytes");
+            }
+            B256::right_padding_from(&bytes)
+        }
+        _ => eyre::bail!("unsupported mapping key type `{label}`"),
+    };
+    Ok(word.to_vec())
+}
+
+/// Formats an encoded mapping key of the given Solidity type of `size` bytes.
+///
+/// Returns `None` if the key is not a valid encoding of the type, e.g. for a preimage that was
+/// hashed for another reason.
+fn format_mapping_key(label: &str, key: &[u8], size: usize) -> Option<String> {
+    match label {
+        "string" => Some(format!("{:?}", String::from_utf8_lossy(key))),
+        "bytes" => Some(hex::encode_prefixed(key)),
+        _ => {
+            let word = B256::try_from(key).ok()?;
+            let formatted = if label.starts_with("bytes") {
+                hex::encode_prefixed(&word[..size.min(32)])
+            } else {
+                format_value(label, word, size)
+            };
+            (encode_mapping_key(label, &formatted).ok()? == key).then_some(formatted)
+        }
+    }
+}
+
+/// Formats a value of the given Solidity type, right-aligned in `word`.
+pub fn format_value(label: &str, word: B256, number_of_bytes: usize) -> String {
+    let value = U256::from_be_bytes(word.0);
+    match label {
+        "bool" => (!value.is_zero()).to_string(),
+        "address" | "address payable" => Address::from_word(word).to_checksum(None),
+        _ if label.starts_with("contract ") => Address::from_word(word).to_checksum(None),
+        _ if label.starts_with("uint") || label.starts_with("enum ") => value.to_string(),
+        _ if label.starts_with("int") => {
+            // Sign-extend the value from its size.
+            let shift = 256 - number_of_bytes.min(32) * 8;
+            I256::from_raw(value << shift).asr(shift).to_string()
+        }
+        _ if label.starts_with("bytes") => {
+            hex::encode_prefixed(&word[32 - number_of_bytes.min(32)..])
+        }
+        _ => word.to_string(),
+    }
+}
+
+/// Formats a slot as a number if it's small, e.g. a state variable, and as hex otherwise.
+pub fn format_slot(slot: U256) -> String {
+    if slot.bit_len() <= 64 {
+        slot.to_string()
+    } else {
+        B256::from(slot).to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn computes_mapping_slots() {
+        // `mapping(address => uint256)` at slot 0
+        let key =
+            encode_mapping_key("address", "0x0000000000000000000000000000000000000001").unwrap();
+        assert_eq!(
+            B256::from(mapping_slot(&key, U256::ZERO)),
+            keccak256(
+                hex::decode(
+                    "0000000000000000000000000000000000000000000000000000000000000001\
+                0000000000000000000000000000000000000000000000000000000000000000"
+                )
+                .unwrap()
+            )
+        );
+
+        assert_eq!(encode_mapping_key("string", "a=b").unwrap(), b"a=b");
+        assert_eq!(encode_mapping_key("int8", "-1").unwrap(), [0xff; 32]);
+        assert_eq!(encode_mapping_key("bytes4", "0x01020304").unwrap()[..5], [1, 2, 3, 4, 0]);
+        assert_eq!(encode_mapping_key("enum Status", "2").unwrap()[31], 2);
+        assert!(encode_mapping_key("bytes2", "0x010203").is_err());
+        assert!(encode_mapping_key("address", "1").is_err());
+    }
+
+    #[test]
+    fn computes_element_positions() {
+        let data = U256::from(100);
+        // uint128[]: two elements per slot
+        assert_eq!(element_position(data, 0, 16), (data, 0));
+        assert_eq!(element_position(data, 1, 16), (data, 16));
+        assert_eq!(element_position(data, 2, 16), (data + U256::from(1), 0));
+        // address[]: one element per slot
+        assert_eq!(element_position(data, 3, 20), (data + U256::from(3), 0));
+        // structs of 3 slots
+        assert_eq!(element_position(data, 2, 96), (data + U256::from(6), 0));
+    }
+
+    #[test]
+    fn formats_values() {
+        let word = |value: &[u8]| B256::left_padding_from(value);
+        assert_eq!(format_value("bool", word(&[1]), 1), "true");
+        assert_eq!(format_value("uint8", word(&[255]), 1), "255");
+        assert_eq!(format_value("int8", word(&[255]), 1), "-1");
+        assert_eq!(format_value("int16", word(&[0x7f, 0xff]), 2), "32767");
+        assert_eq!(format_value("bytes2", word(&[0xab, 0xcd]), 2), "0xabcd");
+        assert_eq!(
+            format_value("contract IERC20", word(&[1; 20]), 20),
+            "0x0101010101010101010101010101010101010101"
+        );
+        assert_eq!(format_slot(U256::from(3)), "3");
+
+        let packed = B256::right_padding_from(&[0xab, 0xcd, 0x01]);
+        assert_eq!(slot_value(packed, 30, 2), word(&[0xab, 0xcd]));
+        assert_eq!(slot_value(packed, 0, 32), packed);
+    }
+
+    #[test]
+    fn formats_mapping_keys() {
+        let account = "0x0000000000000000000000000000000000000001";
+        let key = encode_mapping_key("address", account).unwrap();
+        assert_eq!(format_mapping_key("address", &key, 20).unwrap(), account);
+        assert_eq!(format_mapping_key("int8", &[0xff; 32], 1).unwrap(), "-1");
+        assert_eq!(
+            format_mapping_key("bytes2", &encode_mapping_key("bytes2", "0xabcd").unwrap(), 2)
+                .unwrap(),
+            "0xabcd"
+        );
+        assert_eq!(format_mapping_key("string", b"alice", 32).unwrap(), "\"alice\"");
+
+        // Not a valid encoding of the key type.
+        assert_eq!(format_mapping_key("address", &[0xff; 32], 20), None);
+        assert_eq!(format_mapping_key("uint256", &[1; 5], 32), None);
+    }
+}
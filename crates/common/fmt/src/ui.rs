@@ -302,7 +302,7 @@ impl UIfmt for OtherFields {
 
 impl UIfmt for Transaction {
     fn pretty(&self) -> String {
-        format!(
+        let mut pretty = format!(
             "
 blockHash            {}
 blockNumber          {}
@@ -317,7 +317,7 @@ s                    {}
 to                   {}
 transactionIndex     {}
 v                    {}
-value                {}{}",
+value                {}",
             self.block_hash.pretty(),
             self.block_number.pretty(),
             self.from.pretty(),
@@ -332,8 +332,21 @@ value                {}{}",
             self.transaction_index.pretty(),
             self.signature.map(|s| s.v).pretty(),
             self.value.pretty(),
-            self.other.pretty()
-        )
+        );
+
+        // EIP-4844 blob transaction fields
+        if let Some(max_fee_per_blob_gas) = self.max_fee_per_blob_gas {
+            pretty.push_str(&format!("\nmaxFeePerBlobGas     {}", max_fee_per_blob_gas.pretty()));
+        }
+        if let Some(blob_versioned_hashes) = &self.blob_versioned_hashes {
+            pretty.push_str(&format!(
+                "\nblobVersionedHashes  {}",
+                blob_versioned_hashes.as_slice().pretty()
+            ));
+        }
+
+        pretty.push_str(&self.other.pretty());
+        pretty
     }
 }
 
@@ -384,6 +397,12 @@ pub fn get_pretty_tx_attr(transaction: &Transaction, attr: &str) -> Option<Strin
         "transactionIndex" | "transaction_index" => Some(transaction.transaction_index.pretty()),
         "v" => transaction.signature.map(|s| s.v.pretty()),
         "value" => Some(transaction.value.pretty()),
+        "maxFeePerBlobGas" | "max_fee_per_blob_gas" => {
+            transaction.max_fee_per_blob_gas.map(|fee| fee.pretty())
+        }
+        "blobVersionedHashes" | "blob_versioned_hashes" => {
+            transaction.blob_versioned_hashes.as_deref().map(|hashes| hashes.pretty())
+        }
         other => {
             if let Some(value) = transaction.other.get(other) {
                 return Some(value.to_string().trim_matches('"').to_string())
@@ -554,6 +573,48 @@ txType               0
         );
     }
 
+    #[test]
+    fn can_pretty_print_blob_tx() {
+        let s = r#"
+        {
+        "blockHash": "0x02b853cf50bc1c335b70790f93d5a390a35a166bea9c895e685cc866e4961cae",
+        "blockNumber": "0x1b4",
+        "from": "0x3b179DcfC5fAa677044c27dCe958e4BC0ad696A6",
+        "gas": "0x5208",
+        "gasPrice": "0x3b9aca00",
+        "maxFeePerGas": "0x3b9aca00",
+        "maxPriorityFeePerGas": "0x1",
+        "maxFeePerBlobGas": "0x2",
+        "hash": "0x2642e960d3150244e298d52b5b0f024782253e6d0b2c9a01dd4858f7b4665a3f",
+        "input": "0x",
+        "nonce": "0x1",
+        "to": "0x4a16A42407AA491564643E1dfc1fd50af29794eF",
+        "transactionIndex": "0x0",
+        "value": "0x0",
+        "type": "0x3",
+        "chainId": "0x1",
+        "accessList": [],
+        "blobVersionedHashes": ["0x01b0a4cdd5f55589f5c5b4d46c76704bb6ce95c0a8c09f77f197a57808dded28"],
+        "v": "0x0",
+        "yParity": "0x0",
+        "r": "0x6fca94073a0cf3381978662d46cf890602d3e9ccf6a31e4b69e8ecbd995e2bee",
+        "s": "0x0e804161a2b56a37ca1f6f4c4b8bce926587afa0d9b1acc5165e6556c959d583"
+    }
+        "#;
+
+        let tx: Transaction = serde_json::from_str(s).unwrap();
+        let pretty = tx.pretty();
+        assert!(pretty.contains("maxFeePerBlobGas     2"), "{pretty}");
+        assert!(
+            pretty.contains(
+                "blobVersionedHashes  [\n\t0x01b0a4cdd5f55589f5c5b4d46c76704bb6ce95c0a8c09f77f197a57808dded28\n]"
+            ),
+            "{pretty}"
+        );
+        assert_eq!(get_pretty_tx_attr(&tx, "maxFeePerBlobGas").as_deref(), Some("2"));
+        assert!(get_pretty_tx_attr(&tx, "blobVersionedHashes").is_some());
+    }
+
     #[test]
     fn print_block_w_txs() {
         let block = r#"{"number":"0x3","hash":"0xda53da08ef6a3cbde84c33e51c04f68c3853b6a3731f10baa2324968eee63972","parentHash":"0x689c70c080ca22bc0e681694fa803c1aba16a69c8b6368fed5311d279eb9de90","mixHash":"0x0000000000000000000000000000000000000000000000000000000000000000","nonce":"0x0000000000000000","sha3Uncles":"0x1dcc4de8dec75d7aab85b567b6ccd41ad312451b948a7413f0a142fd40d49347","logsBloom":"0x00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000","transactionsRoot":"0x7270c1c4440180f2bd5215809ee3d545df042b67329499e1ab97eb759d31610d","stateRoot":"0x29f32984517a7d25607da485b23cefabfd443751422ca7e603395e1de9bc8a4b","receiptsRoot":"0x056b23fbba480696b65fe5a59b8f2148a1299103c4f57df839233af2cf4ca2d2","miner":"0x0000000000000000000000000000000000000000","difficulty":"0x0","totalDifficulty":"0x0","extraData":"0x","size":"0x3e8","gasLimit":"0x6691b7","gasUsed":"0x5208","timestamp":"0x5ecedbb9","transactions":[{"hash":"0xc3c5f700243de37ae986082fd2af88d2a7c2752a0c0f7b9d6ac47c729d45e067","nonce":"0x2","blockHash":"0xda53da08ef6a3cbde84c33e51c04f68c3853b6a3731f10baa2324968eee63972","blockNumber":"0x3","transactionIndex":"0x0","from":"0xfdcedc3bfca10ecb0890337fbdd1977aba84807a","to":"0xdca8ce283150ab773bcbeb8d38289bdb5661de1e","value":"0x0","gas":"0x15f90","gasPrice":"0x4a817c800","input":"0x","v":"0x25","r":"0x19f2694eb9113656dbea0b925e2e7ceb43df83e601c4116aee9c0dd99130be88","s":"0x73e5764b324a4f7679d890a198ba658ba1c8cd36983ff9797e10b1b89dbb448e"}],"uncles":[]}"#;
@@ -1,11 +1,12 @@
 //! ABI related helper functions.
 
 use alloy_dyn_abi::{DynSolType, DynSolValue, FunctionExt, JsonAbiExt};
-use alloy_json_abi::{Event, Function};
-use alloy_primitives::{hex, Address, LogData};
+use alloy_json_abi::{Event, Function, Param};
+use alloy_primitives::{hex, Address, LogData, B256, I256, U256};
 use eyre::{Context, ContextCompat, Result};
 use foundry_block_explorers::{contract::ContractMetadata, errors::EtherscanError, Client};
 use foundry_config::Chain;
+use serde_json::Value;
 use std::{future::Future, pin::Pin};
 
 /// Given a function and a vector of string arguments, it proceeds to convert the args to alloy
@@ -38,6 +39,203 @@ where
     Ok(params.concat())
 }
 
+/// Given a function and its arguments as JSON, either an array of the arguments or an object of
+/// the arguments by parameter name, converts the arguments to alloy [DynSolValue]s.
+///
+/// Arrays are given as JSON arrays and tuples as JSON arrays or as objects by component name.
+/// Numbers can be given as JSON numbers or strings, other values as strings, e.g.
+/// `[{"to": "0x...", "amounts": [1, "2"]}]` for `f((address to, uint256[] amounts))`.
+pub fn coerce_json_args(func: &Function, args: &Value) -> Result<Vec<DynSolValue>> {
+    let args = match args {
+        Value::Array(args) => {
+            eyre::ensure!(
+                args.len() == func.inputs.len(),
+                "expected {} arguments, got {}",
+                func.inputs.len(),
+                args.len()
+            );
+            args.iter().collect::<Vec<_>>()
+        }
+        Value::Object(args) => func
+            .inputs
+            .iter()
+            .enumerate()
+            .map(|(i, input)| {
+                eyre::ensure!(!input.name.is_empty(), "parameter {i} of {} has no name", func.name);
+                args.get(&input.name).wrap_err_with(|| format!("missing argument `{}`", input.name))
+            })
+            .collect::<Result<Vec<_>>>()?,
+        _ => eyre::bail!("expected an array or an object of arguments, got `{args}`"),
+    };
+    std::iter::zip(&func.inputs, args)
+        .enumerate()
+        .map(|(i, (input, arg))| {
+            let ty = DynSolType::parse(&input.selector_type())?;
+            coerce_json_value(&ty, &input.components, arg)
+                .wrap_err_with(|| format!("invalid argument {i} `{}`", input.name))
+        })
+        .collect()
+}
+
+/// Converts a JSON value to a [DynSolValue] of the given type, see [coerce_json_args].
+///
+/// `components` are the components of the innermost tuple of the type, used to look up the values
+/// of tuples given as objects.
+fn coerce_json_value(ty: &DynSolType, components: &[Param], value: &Value) -> Result<DynSolValue> {
+    Ok(match (ty, value) {
+        (DynSolType::Tuple(types), Value::Array(values)) => {
+            eyre::ensure!(
+                types.len() == values.len(),
+                "expected a tuple of {} values, got {}",
+                types.len(),
+                values.len()
+            );
+            let values = types.iter().zip(values).enumerate().map(|(i, (ty, value))| {
+                let components = components.get(i).map_or(&[][..], |c| &c.components[..]);
+                coerce_json_value(ty, components, value)
+            });
+            DynSolValue::Tuple(values.collect::<Result<_>>()?)
+        }
+        (DynSolType::Tuple(types), Value::Object(values)) => {
+            let values = types.iter().enumerate().map(|(i, ty)| {
+                let component = components
+                    .get(i)
+                    .filter(|component| !component.name.is_empty())
+                    .wrap_err_with(|| format!("component {i} of the tuple has no name"))?;
+                let value = values
+                    .get(&component.name)
+                    .wrap_err_with(|| format!("missing tuple component `{}`", component.name))?;
+                coerce_json_value(ty, &component.components, value)
+            });
+            DynSolValue::Tuple(values.collect::<Result<_>>()?)
+        }
+        (DynSolType::Array(inner), Value::Array(values)) => DynSolValue::Array(
+            values
+                .iter()
+                .map(|value| coerce_json_value(inner, components, value))
+                .collect::<Result<_>>()?,
+        ),
+        (DynSolType::FixedArray(inner, len), Value::Array(values)) => {
+            eyre::ensure!(
+                values.len() == *len,
+                "expected an array of {len} values, got {}",
+                values.len()
+            );
+            DynSolValue::FixedArray(
+                values
+                    .iter()
+                    .map(|value| coerce_json_value(inner, components, value))
+                    .collect::<Result<_>>()?,
+            )
+        }
+        (DynSolType::Bool, Value::Bool(value)) => DynSolValue::Bool(*value),
+        (_, Value::Number(value)) => ty.coerce_str(&value.to_string())?,
+        (_, Value::String(value)) => ty.coerce_str(value)?,
+        _ => eyre::bail!("cannot convert `{value}` to `{}`", ty.sol_type_name()),
+    })
+}
+
+/// Decodes data encoded with the packed encoding of the given types, i.e. `abi.encodePacked`.
+///
+/// The packed encoding doesn't include the length of dynamic values, so only the last type can be
+/// dynamic: `string`, `bytes`, an array of static values, or a tuple ending with one of those.
+pub fn abi_decode_packed(types: &[DynSolType], mut data: &[u8]) -> Result<Vec<DynSolValue>> {
+    let mut values = Vec::with_capacity(types.len());
+    for (i, ty) in types.iter().enumerate() {
+        let size = match packed_size(ty) {
+            Some(size) => size,
+            None if i + 1 == types.len() => data.len(),
+            None => eyre::bail!(
+                "only the last type can be dynamic in the packed encoding, got `{}`",
+                ty.sol_type_name()
+            ),
+        };
+        eyre::ensure!(data.len() >= size, "data too short to decode `{}`", ty.sol_type_name());
+        let (value, rest) = data.split_at(size);
+        values.push(decode_packed_value(ty, value)?);
+        data = rest;
+    }
+    eyre::ensure!(data.is_empty(), "{} bytes left after decoding", data.len());
+    Ok(values)
+}
+
+/// Returns the size of the packed encoding of a static type, or `None` for dynamic types.
+fn packed_size(ty: &DynSolType) -> Option<usize> {
+    Some(match ty {
+        DynSolType::Bool => 1,
+        DynSolType::Address => 20,
+        DynSolType::Function => 24,
+        DynSolType::Int(bits) | DynSolType::Uint(bits) => bits / 8,
+        DynSolType::FixedBytes(size) => *size,
+        DynSolType::FixedArray(inner, len) => packed_element_size(inner)? * len,
+        DynSolType::Tuple(types) => types.iter().map(packed_size).sum::<Option<usize>>()?,
+        _ => return None,
+    })
+}
+
+/// Returns the size of an element of an array in the packed encoding: elements are left-padded to
+/// 32 bytes.
+fn packed_element_size(ty: &DynSolType) -> Option<usize> {
+    packed_size(ty).map(|size| size.max(32))
+}
+
+/// Decodes a value from its packed encoding, which spans the whole data.
+fn decode_packed_value(ty: &DynSolType, data: &[u8]) -> Result<DynSolValue> {
+    Ok(match ty {
+        DynSolType::Bool => match data {
+            [0] => DynSolValue::Bool(false),
+            [1] => DynSolValue::Bool(true),
+            _ => eyre::bail!("invalid bool {}", hex::encode_prefixed(data)),
+        },
+        DynSolType::Address => DynSolValue::Address(Address::from_slice(data)),
+        DynSolType::Function => DynSolValue::Function(alloy_primitives::Function::from_slice(data)),
+        DynSolType::Uint(bits) => DynSolValue::Uint(U256::from_be_slice(data), *bits),
+        DynSolType::Int(bits) => {
+            // Sign-extend the value to 256 bits.
+            let fill = if data.first().is_some_and(|byte| byte & 0x80 != 0) { 0xff } else { 0 };
+            let mut word = [fill; 32];
+            word[32 - data.len()..].copy_from_slice(data);
+            DynSolValue::Int(I256::from_be_bytes(word), *bits)
+        }
+        DynSolType::FixedBytes(size) => {
+            let mut word = B256::ZERO;
+            word[..*size].copy_from_slice(data);
+            DynSolValue::FixedBytes(word, *size)
+        }
+        DynSolType::Bytes => DynSolValue::Bytes(data.to_vec()),
+        DynSolType::String => DynSolValue::String(
+            String::from_utf8(data.to_vec()).wrap_err("string is not valid UTF-8")?,
+        ),
+        DynSolType::Tuple(types) => DynSolValue::Tuple(abi_decode_packed(types, data)?),
+        DynSolType::Array(inner) | DynSolType::FixedArray(inner, _) => {
+            let (Some(size), Some(element_size)) = (packed_size(inner), packed_element_size(inner))
+            else {
+                eyre::bail!("cannot decode arrays of dynamic type `{}`", inner.sol_type_name())
+            };
+            eyre::ensure!(
+                data.len() % element_size == 0,
+                "the data of `{}` is not a whole number of elements",
+                ty.sol_type_name()
+            );
+            let values = data
+                .chunks(element_size)
+                .map(|element| decode_packed_value(inner, &element[element_size - size..]))
+                .collect::<Result<Vec<_>>>()?;
+            if let DynSolType::FixedArray(_, len) = ty {
+                eyre::ensure!(
+                    values.len() == *len,
+                    "expected {len} elements, got {}",
+                    values.len()
+                );
+                DynSolValue::FixedArray(values)
+            } else {
+                DynSolValue::Array(values)
+            }
+        }
+        _ => eyre::bail!("cannot decode `{}` from the packed encoding", ty.sol_type_name()),
+    })
+}
+
 /// Decodes the calldata of the function
 pub fn abi_decode_calldata(
     sig: &str,
@@ -191,6 +389,60 @@ mod tests {
         assert_eq!(func.outputs[0].ty, "bytes4");
     }
 
+    #[test]
+    fn test_coerce_json_args() {
+        let func = get_func("f((address to, uint256[] amounts)[] transfers, bool flag)").unwrap();
+        let to = Address::with_last_byte(1);
+        let expected = vec![
+            DynSolValue::Array(vec![DynSolValue::Tuple(vec![
+                DynSolValue::Address(to),
+                DynSolValue::Array(vec![
+                    DynSolValue::Uint(U256::from(1), 256),
+                    DynSolValue::Uint(U256::from(2), 256),
+                ]),
+            ])]),
+            DynSolValue::Bool(true),
+        ];
+
+        let args = serde_json::json!([[{ "to": to.to_string(), "amounts": [1, "2"] }], true]);
+        assert_eq!(coerce_json_args(&func, &args).unwrap(), expected);
+        let args = serde_json::json!({ "flag": "true", "transfers": [[to.to_string(), [1, 2]]] });
+        assert_eq!(coerce_json_args(&func, &args).unwrap(), expected);
+
+        let args = serde_json::json!([[{ "to": to.to_string() }], true]);
+        assert!(coerce_json_args(&func, &args).is_err());
+        assert!(coerce_json_args(&func, &serde_json::json!([[]])).is_err());
+    }
+
+    #[test]
+    fn test_abi_decode_packed() {
+        let values = vec![
+            DynSolValue::Address(Address::with_last_byte(1)),
+            DynSolValue::Int(I256::MINUS_ONE, 16),
+            DynSolValue::FixedArray(vec![
+                DynSolValue::Uint(U256::from(1), 8),
+                DynSolValue::Uint(U256::from(2), 8),
+            ]),
+            DynSolValue::Tuple(vec![
+                DynSolValue::Bool(true),
+                DynSolValue::Array(vec![DynSolValue::Uint(U256::from(3), 64)]),
+            ]),
+        ];
+        let data = values.iter().flat_map(|value| value.abi_encode_packed()).collect::<Vec<_>>();
+        let types = ["address", "int16", "uint8[2]", "(bool,uint64[])"]
+            .map(|ty| DynSolType::parse(ty).unwrap());
+        assert_eq!(abi_decode_packed(&types, &data).unwrap(), values);
+
+        // The length of a dynamic value is only known if it's last.
+        let types = ["string", "uint8"].map(|ty| DynSolType::parse(ty).unwrap());
+        assert!(abi_decode_packed(&types, b"hello\x01").is_err());
+        let types = ["uint8", "string"].map(|ty| DynSolType::parse(ty).unwrap());
+        assert_eq!(
+            abi_decode_packed(&types, b"\x01hello").unwrap(),
+            [DynSolValue::Uint(U256::from(1), 8), DynSolValue::String("hello".to_string())]
+        );
+    }
+
     #[test]
     fn test_indexed_only_address() {
         let event = get_event("event Ev(address,uint256,address)").unwrap();
@@ -1,6 +1,11 @@
 //! Support for compiling [foundry_compilers::Project]
 
-use crate::{compact_to_contract, term::SpinnerReporter, TestFunctionExt};
+use crate::{
+    compact_to_contract,
+    progress::{progress_mode, PorcelainReporter, ProgressMode},
+    term::SpinnerReporter,
+    TestFunctionExt,
+};
 use comfy_table::{presets::ASCII_MARKDOWN, Attribute, Cell, CellAlignment, Color, Table};
 use eyre::{Context, Result};
 use foundry_block_explorers::contract::Metadata;
@@ -9,11 +14,12 @@ use foundry_compilers::{
     compilers::{
         multi::MultiCompilerLanguage,
         solc::{Solc, SolcCompiler},
-        Compiler,
+        CompilationError, Compiler,
     },
     report::{BasicStdoutReporter, NoReporter, Report},
     Artifact, Project, ProjectBuilder, ProjectCompileOutput, ProjectPathsConfig, SolcConfig,
 };
+use foundry_config::{DenyWarningsPolicy, SolidityErrorCode};
 use foundry_linking::Linker;
 use num_format::{Locale, ToFormattedString};
 use rustc_hash::FxHashMap;
@@ -49,6 +55,9 @@ pub struct ProjectCompiler {
 
     /// Extra files to include, that are not necessarily in the project's source dir.
     files: Vec<PathBuf>,
+
+    /// Scoped warnings-as-errors policy to enforce on the compiler output.
+    deny_warnings: Option<DenyWarningsPolicy>,
 }
 
 impl Default for ProjectCompiler {
@@ -69,6 +78,7 @@ impl ProjectCompiler {
             quiet: Some(crate::shell::verbosity().is_silent()),
             bail: None,
             files: Vec::new(),
+            deny_warnings: None,
         }
     }
 
@@ -117,6 +127,15 @@ impl ProjectCompiler {
         self
     }
 
+    /// Sets the scoped warnings-as-errors policy to enforce on the compiler output.
+    ///
+    /// See [`foundry_config::Config::deny_warnings_policy`].
+    #[inline]
+    pub fn deny_warnings(mut self, policy: Option<DenyWarningsPolicy>) -> Self {
+        self.deny_warnings = policy;
+        self
+    }
+
     /// Sets extra files to include, that are not necessarily in the project's source dir.
     #[inline]
     pub fn files(mut self, files: impl IntoIterator<Item = PathBuf>) -> Self {
@@ -181,6 +200,13 @@ impl ProjectCompiler {
             eyre::bail!("{output}")
         }
 
+        if let Some(policy) = self.deny_warnings.as_ref().filter(|_| bail) {
+            let denied = DeniedWarnings::new(policy, &output);
+            if !denied.is_empty() {
+                eyre::bail!("{output}\n{denied}")
+            }
+        }
+
         if !quiet {
             if output.is_unchanged() {
                 println!("No files changed, compilation skipped");
@@ -261,6 +287,57 @@ impl ProjectCompiler {
     }
 }
 
+/// Compiler warnings that are treated as errors by a [DenyWarningsPolicy], aggregated by source
+/// file and warning code.
+#[derive(Clone, Debug, Default)]
+pub struct DeniedWarnings {
+    /// `source file -> warning code -> number of occurrences`
+    pub files: BTreeMap<String, BTreeMap<Option<u64>, usize>>,
+}
+
+impl DeniedWarnings {
+    /// Collects all warnings in the compiler output that are denied by the given policy.
+    pub fn new<C: Compiler>(policy: &DenyWarningsPolicy, output: &ProjectCompileOutput<C>) -> Self {
+        let mut denied = Self::default();
+        for err in output.output().errors.iter().filter(|err| err.is_warning()) {
+            let file = err.source_location().map(|loc| loc.file);
+            let code = err.error_code();
+            if policy.is_denied(file.as_deref().map(Path::new), code) {
+                let file = file.unwrap_or_else(|| "<unknown>".to_string());
+                *denied.files.entry(file).or_default().entry(code).or_default() += 1;
+            }
+        }
+        denied
+    }
+
+    /// Returns the total number of denied warnings.
+    pub fn len(&self) -> usize {
+        self.files.values().flat_map(|codes| codes.values()).sum()
+    }
+
+    /// Returns true if no warnings were denied.
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+}
+
+impl Display for DeniedWarnings {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "{} compiler warning(s) denied by `deny_warnings_from`:", self.len())?;
+        for (file, codes) in &self.files {
+            for (code, count) in codes {
+                match code.map(|code| (code, SolidityErrorCode::from(code).as_str())) {
+                    Some((code, Ok(name))) => write!(f, "  {file}: {name} ({code})")?,
+                    Some((code, Err(_))) => write!(f, "  {file}: {code}")?,
+                    None => write!(f, "  {file}: <no code>")?,
+                }
+                writeln!(f, " x{count}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct SourceData {
     pub source: Arc<String>,
@@ -541,10 +618,14 @@ pub fn etherscan_project(
 }
 
 /// Configures the reporter and runs the given closure.
+///
+/// The reporter follows the [`ProgressMode`] of the program, unless `quiet` is set.
 pub fn with_compilation_reporter<O>(quiet: bool, f: impl FnOnce() -> O) -> O {
     #[allow(clippy::collapsible_else_if)]
-    let reporter = if quiet {
+    let reporter = if quiet || progress_mode() == ProgressMode::Quiet {
         Report::new(NoReporter::default())
+    } else if progress_mode() == ProgressMode::Porcelain {
+        Report::new(PorcelainReporter)
     } else {
         if std::io::stdout().is_terminal() {
             Report::new(SpinnerReporter::spawn())
@@ -0,0 +1,341 @@
+//! Shell completion of values that depend on the project, like RPC aliases or contract names.
+//!
+//! The completion scripts generated by clap only know about the static structure of the CLI. For
+//! the supported shells, [`generate`] extends them to ask a hidden `complete-candidates`
+//! subcommand for the values of some arguments.
+
+use clap::{Command, ValueEnum};
+use clap_complete::Shell;
+use foundry_config::Config;
+use std::{
+    collections::BTreeSet,
+    io::{self, Write},
+    path::Path,
+};
+
+/// The name of the hidden subcommand that prints the candidates of a [`CandidateKind`].
+pub const CANDIDATES_COMMAND: &str = "complete-candidates";
+
+/// The flags whose values are RPC URLs or aliases.
+const RPC_FLAGS: &[&str] = &["--rpc-url", "-r", "--fork-url", "-f"];
+
+/// The flags whose values are test contract names.
+const CONTRACT_FILTER_FLAGS: &[&str] =
+    &["--match-contract", "--mc", "--no-match-contract", "--nmc"];
+
+/// The flags whose values are test function names.
+const TEST_FILTER_FLAGS: &[&str] = &["--match-test", "--mt", "--no-match-test", "--nmt"];
+
+/// The subcommands whose first argument is a contract name.
+const CONTRACT_SUBCOMMANDS: &[&str] =
+    &["create", "inspect", "verify-contract", "verify-storage-layout", "debug"];
+
+/// The subcommands whose first argument is a script.
+const SCRIPT_SUBCOMMANDS: &[&str] = &["script"];
+
+/// The kinds of values that are completed dynamically.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum CandidateKind {
+    /// The aliases of the `rpc_endpoints` in the config.
+    RpcAliases,
+    /// The names of the compiled contracts.
+    Contracts,
+    /// The names of the compiled contracts that have tests.
+    TestContracts,
+    /// The names of the compiled test functions.
+    Tests,
+    /// The names of the compiled scripts.
+    Scripts,
+}
+
+impl CandidateKind {
+    fn as_str(self) -> &'static str {
+        self.to_possible_value().expect("no skipped variants").get_name()
+    }
+}
+
+/// Returns the candidates of the given kind, sorted and deduplicated.
+///
+/// Contract and test names are read from the artifacts of the last build, so that completing is
+/// fast and never compiles.
+pub fn candidates(kind: CandidateKind, config: &Config) -> Vec<String> {
+    let mut candidates = BTreeSet::new();
+    match kind {
+        CandidateKind::RpcAliases => candidates.extend(config.rpc_endpoints.keys().cloned()),
+        _ => {
+            let out = config.root.0.join(&config.out);
+            for artifact in read_artifacts(&out) {
+                match kind {
+                    CandidateKind::Contracts => candidates.insert(artifact.name),
+                    CandidateKind::TestContracts if !artifact.tests.is_empty() => {
+                        candidates.insert(artifact.name)
+                    }
+                    CandidateKind::Tests => {
+                        candidates.extend(artifact.tests);
+                        continue
+                    }
+                    CandidateKind::Scripts if artifact.source.ends_with(".s.sol") => {
+                        candidates.insert(artifact.name)
+                    }
+                    _ => continue,
+                };
+            }
+        }
+    }
+    candidates.into_iter().collect()
+}
+
+/// A compiled contract, as far as completion is concerned.
+struct ArtifactSummary {
+    /// The name of the contract.
+    name: String,
+    /// The file name of the source of the contract.
+    source: String,
+    /// The test functions of the contract.
+    tests: Vec<String>,
+}
+
+/// Reads the artifacts of the output directory, laid out as `<out>/<source file>/<name>.json`.
+fn read_artifacts(out: &Path) -> Vec<ArtifactSummary> {
+    let mut artifacts = Vec::new();
+    for entry in walkdir::WalkDir::new(out).min_depth(2).max_depth(2).into_iter().flatten() {
+        let path = entry.path();
+        if path.extension().map_or(true, |ext| ext != "json") {
+            continue
+        }
+        let (Some(name), Some(source)) = (
+            path.file_stem().and_then(|name| name.to_str()),
+            path.parent().and_then(|dir| dir.file_name()).and_then(|dir| dir.to_str()),
+        ) else {
+            continue
+        };
+        // Artifacts compiled with multiple versions are named `<name>.<version>.json`.
+        let name = name.split('.').next().unwrap_or(name).to_string();
+        let tests = std::fs::read_to_string(path)
+            .ok()
+            .and_then(|contents| serde_json::from_str::<serde_json::Value>(&contents).ok())
+            .map(|artifact| test_functions(&artifact))
+            .unwrap_or_default();
+        artifacts.push(ArtifactSummary { name, source: source.to_string(), tests });
+    }
+    artifacts
+}
+
+/// Returns the names of the test functions in the ABI of the given artifact.
+fn test_functions(artifact: &serde_json::Value) -> Vec<String> {
+    let Some(abi) = artifact.get("abi").and_then(|abi| abi.as_array()) else { return Vec::new() };
+    abi.iter()
+        .filter(|item| item.get("type").and_then(|ty| ty.as_str()) == Some("function"))
+        .filter_map(|item| item.get("name").and_then(|name| name.as_str()))
+        .filter(|name| name.starts_with("test") || name.starts_with("invariant"))
+        .map(String::from)
+        .collect()
+}
+
+/// Writes the completion script of the given shell, completing dynamic values where supported.
+///
+/// Bash, zsh and fish call `<bin> complete-candidates <kind>` to complete RPC aliases, test
+/// filters and contract or script names. Other shells only get the static completions of clap.
+pub fn generate(shell: Shell, cmd: &mut Command, bin: &str, out: &mut dyn Write) -> io::Result<()> {
+    let mut script = Vec::new();
+    clap_complete::generate(shell, cmd, bin, &mut script);
+    let script = String::from_utf8_lossy(&script);
+    match shell {
+        Shell::Bash => {
+            out.write_all(script.as_bytes())?;
+            out.write_all(bash_script(bin).as_bytes())
+        }
+        Shell::Zsh => {
+            // Hook the dynamic completion in front of the generated one, both when the script is
+            // autoloaded and when it's sourced.
+            let script = script
+                .replace(
+                    &format!("then\n    _{bin} \"$@\""),
+                    &format!("then\n    _{bin}_dynamic \"$@\""),
+                )
+                .replace(
+                    &format!("compdef _{bin} {bin}"),
+                    &format!("compdef _{bin}_dynamic {bin}"),
+                );
+            let (head, tail) =
+                script.rsplit_once("if [ \"$funcstack[1]\"").unwrap_or((script.as_str(), ""));
+            out.write_all(head.as_bytes())?;
+            out.write_all(zsh_script(bin).as_bytes())?;
+            if !tail.is_empty() {
+                write!(out, "if [ \"$funcstack[1]\"{tail}")?;
+            }
+            Ok(())
+        }
+        Shell::Fish => {
+            out.write_all(script.as_bytes())?;
+            out.write_all(fish_script(bin).as_bytes())
+        }
+        _ => out.write_all(script.as_bytes()),
+    }
+}
+
+/// Returns the shell `case` patterns of the given flags.
+fn patterns(flags: &[&str]) -> String {
+    flags.join("|")
+}
+
+fn bash_script(bin: &str) -> String {
+    let rpc = patterns(RPC_FLAGS);
+    let contracts = patterns(CONTRACT_FILTER_FLAGS);
+    let tests = patterns(TEST_FILTER_FLAGS);
+    let contract_cmds = patterns(CONTRACT_SUBCOMMANDS);
+    let script_cmds = patterns(SCRIPT_SUBCOMMANDS);
+    format!(
+        r#"
+_{bin}_dynamic() {{
+    local cur="${{COMP_WORDS[COMP_CWORD]}}" prev="${{COMP_WORDS[COMP_CWORD-1]}}" kind=""
+    case "$prev" in
+        {rpc}) kind={rpc_kind} ;;
+        {contracts}) kind={contracts_kind} ;;
+        {tests}) kind={tests_kind} ;;
+    esac
+    if [[ -z "$kind" && $COMP_CWORD -eq 2 && "$cur" != -* ]]; then
+        case "${{COMP_WORDS[1]}}" in
+            {contract_cmds}) kind={all_contracts_kind} ;;
+            {script_cmds}) kind={scripts_kind} ;;
+        esac
+    fi
+    if [[ -n "$kind" ]]; then
+        local IFS=$'\n'
+        COMPREPLY=($(compgen -W "$({bin} {CANDIDATES_COMMAND} $kind 2>/dev/null)" -- "$cur"))
+        [[ ${{#COMPREPLY[@]}} -gt 0 ]] && return 0
+    fi
+    _{bin} "$@"
+}}
+complete -F _{bin}_dynamic -o nosort -o bashdefault -o default {bin}
+"#,
+        rpc_kind = CandidateKind::RpcAliases.as_str(),
+        contracts_kind = CandidateKind::TestContracts.as_str(),
+        tests_kind = CandidateKind::Tests.as_str(),
+        all_contracts_kind = CandidateKind::Contracts.as_str(),
+        scripts_kind = CandidateKind::Scripts.as_str(),
+    )
+}
+
+fn zsh_script(bin: &str) -> String {
+    let rpc = patterns(RPC_FLAGS);
+    let contracts = patterns(CONTRACT_FILTER_FLAGS);
+    let tests = patterns(TEST_FILTER_FLAGS);
+    let contract_cmds = patterns(CONTRACT_SUBCOMMANDS);
+    let script_cmds = patterns(SCRIPT_SUBCOMMANDS);
+    format!(
+        r#"
+_{bin}_dynamic() {{
+    local kind=""
+    case "${{words[CURRENT-1]}}" in
+        {rpc}) kind={rpc_kind} ;;
+        {contracts}) kind={contracts_kind} ;;
+        {tests}) kind={tests_kind} ;;
+    esac
+    if [[ -z "$kind" && $CURRENT -eq 3 && "${{words[CURRENT]}}" != -* ]]; then
+        case "${{words[2]}}" in
+            {contract_cmds}) kind={all_contracts_kind} ;;
+            {script_cmds}) kind={scripts_kind} ;;
+        esac
+    fi
+    if [[ -n "$kind" ]]; then
+        local -a candidates
+        candidates=(${{(f)"$({bin} {CANDIDATES_COMMAND} $kind 2>/dev/null)"}})
+        if (( ${{#candidates}} )); then
+            compadd -a candidates
+            return
+        fi
+    fi
+    _{bin} "$@"
+}}
+
+"#,
+        rpc_kind = CandidateKind::RpcAliases.as_str(),
+        contracts_kind = CandidateKind::TestContracts.as_str(),
+        tests_kind = CandidateKind::Tests.as_str(),
+        all_contracts_kind = CandidateKind::Contracts.as_str(),
+        scripts_kind = CandidateKind::Scripts.as_str(),
+    )
+}
+
+fn fish_script(bin: &str) -> String {
+    let mut script = String::from("\n");
+    let mut flags = |flags: &[&str], kind: CandidateKind, condition: Option<&str>| {
+        for flag in flags {
+            let option = match flag.strip_prefix("--") {
+                Some(long) => format!("-l {long}"),
+                None => format!("-s {}", flag.trim_start_matches('-')),
+            };
+            let condition = condition
+                .map(|c| format!(" -n \"__fish_seen_subcommand_from {c}\""))
+                .unwrap_or_default();
+            script.push_str(&format!(
+                "complete -c {bin}{condition} {option} -f -a \"({bin} {CANDIDATES_COMMAND} {} 2>/dev/null)\"\n",
+                kind.as_str()
+            ));
+        }
+    };
+    flags(RPC_FLAGS, CandidateKind::RpcAliases, None);
+    flags(CONTRACT_FILTER_FLAGS, CandidateKind::TestContracts, None);
+    flags(TEST_FILTER_FLAGS, CandidateKind::Tests, None);
+    for (subcommands, kind) in [
+        (CONTRACT_SUBCOMMANDS, CandidateKind::Contracts),
+        (SCRIPT_SUBCOMMANDS, CandidateKind::Scripts),
+    ] {
+        script.push_str(&format!(
+            "complete -c {bin} -n \"__fish_seen_subcommand_from {}\" -f -a \"({bin} {CANDIDATES_COMMAND} {} 2>/dev/null)\"\n",
+            subcommands.join(" "),
+            kind.as_str()
+        ));
+    }
+    script
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use foundry_config::{RpcEndpoint, RpcEndpoints};
+
+    #[test]
+    fn reads_candidates_from_artifacts() {
+        let dir = tempfile::tempdir().unwrap();
+        let write = |path: &str, abi: serde_json::Value| {
+            let path = dir.path().join("out").join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, serde_json::json!({ "abi": abi }).to_string()).unwrap();
+        };
+        let function = |name: &str| serde_json::json!({ "type": "function", "name": name });
+        write("Counter.sol/Counter.json", serde_json::json!([function("increment")]));
+        write(
+            "Counter.t.sol/CounterTest.json",
+            serde_json::json!([function("setUp"), function("test_Increment")]),
+        );
+        write("Counter.s.sol/CounterScript.0.8.26.json", serde_json::json!([function("run")]));
+
+        let mut config = Config::with_root(dir.path());
+        config.rpc_endpoints =
+            RpcEndpoints::new([("mainnet", RpcEndpoint::Url("http://localhost:8545".to_string()))]);
+
+        assert_eq!(candidates(CandidateKind::RpcAliases, &config), ["mainnet"]);
+        assert_eq!(
+            candidates(CandidateKind::Contracts, &config),
+            ["Counter", "CounterScript", "CounterTest"]
+        );
+        assert_eq!(candidates(CandidateKind::TestContracts, &config), ["CounterTest"]);
+        assert_eq!(candidates(CandidateKind::Tests, &config), ["test_Increment"]);
+        assert_eq!(candidates(CandidateKind::Scripts, &config), ["CounterScript"]);
+    }
+
+    #[test]
+    fn extends_completion_scripts() {
+        let mut cmd = Command::new("forge").arg(clap::Arg::new("rpc-url").long("rpc-url"));
+        for shell in [Shell::Bash, Shell::Zsh, Shell::Fish] {
+            let mut out = Vec::new();
+            generate(shell, &mut cmd, "forge", &mut out).unwrap();
+            let out = String::from_utf8(out).unwrap();
+            assert!(out.contains("forge complete-candidates"), "{shell}:\n{out}");
+            assert!(out.contains("rpc-aliases"), "{shell}:\n{out}");
+            assert!(out.contains("--rpc-url"), "{shell}:\n{out}");
+        }
+    }
+}
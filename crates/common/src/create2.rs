@@ -0,0 +1,188 @@
+//! Mining of `CREATE2` salts for vanity contract addresses.
+//!
+//! Shared by `cast create2`, `cast wallet vanity --create2` and `forge create --salt-mine`.
+
+use crate::progress;
+use alloy_primitives::{keccak256, Address, B256};
+use std::{
+    sync::atomic::{AtomicU64, AtomicUsize, Ordering},
+    time::{Duration, Instant},
+};
+
+/// The number of salts hashed by a thread before checking whether it should stop.
+const BATCH_SIZE: u64 = 256;
+
+/// The offset of the salt in the `0xff ++ deployer ++ salt ++ init_code_hash` preimage.
+const SALT_OFFSET: usize = 21;
+
+/// The offset of the counter in the preimage, the counter is the little-endian `u64` in the last
+/// 8 bytes of the salt.
+const COUNTER_OFFSET: usize = SALT_OFFSET + 24;
+
+/// Mines a `CREATE2` salt for which the address of a contract deployed by `deployer` with
+/// `init_code_hash` matches a pattern.
+///
+/// Salts are tried by incrementing the counter in the last 8 bytes of the starting salt. The
+/// lowest matching salt is always returned, so the result does not depend on the number of
+/// threads.
+#[derive(Clone, Debug)]
+#[must_use]
+pub struct SaltMiner {
+    deployer: Address,
+    init_code_hash: B256,
+    salt: B256,
+    threads: usize,
+    timeout: Option<Duration>,
+    progress: bool,
+}
+
+impl SaltMiner {
+    /// Creates a miner starting from the zero salt, with one thread per logical core and no
+    /// timeout.
+    pub fn new(deployer: Address, init_code_hash: B256) -> Self {
+        Self {
+            deployer,
+            init_code_hash,
+            salt: B256::ZERO,
+            threads: max_threads(),
+            timeout: None,
+            progress: false,
+        }
+    }
+
+    /// Sets the salt to start mining from.
+    pub fn salt(mut self, salt: B256) -> Self {
+        self.salt = salt;
+        self
+    }
+
+    /// Sets the number of threads, between 1 and the number of logical cores.
+    pub fn threads(mut self, threads: usize) -> Self {
+        self.threads = threads.clamp(1, max_threads());
+        self
+    }
+
+    /// Stops mining after `timeout`.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Shows the number of salts tried so far in a spinner.
+    pub fn progress(mut self, progress: bool) -> Self {
+        self.progress = progress;
+        self
+    }
+
+    /// Returns the number of threads used to mine.
+    pub fn num_threads(&self) -> usize {
+        self.threads
+    }
+
+    /// Mines a salt for which `matcher` accepts the contract address, returning the address and
+    /// the salt.
+    ///
+    /// Returns `None` if the timeout elapsed before a salt was found, or if all the salts were
+    /// tried.
+    pub fn mine(&self, matcher: impl Fn(&Address) -> bool + Sync) -> Option<(Address, B256)> {
+        let mut preimage = [0u8; 85];
+        preimage[0] = 0xff;
+        preimage[1..SALT_OFFSET].copy_from_slice(self.deployer.as_slice());
+        preimage[SALT_OFFSET..SALT_OFFSET + 32].copy_from_slice(self.salt.as_slice());
+        preimage[SALT_OFFSET + 32..].copy_from_slice(self.init_code_hash.as_slice());
+        let start = u64::from_le_bytes(self.salt[24..].try_into().unwrap());
+
+        let deadline = self.timeout.map(|timeout| Instant::now() + timeout);
+        let threads = self.threads as u64;
+        let lowest = AtomicU64::new(u64::MAX);
+        let attempts = AtomicU64::new(0);
+        let finished = AtomicUsize::new(0);
+
+        std::thread::scope(|scope| {
+            for thread in 0..threads {
+                let (matcher, lowest, attempts, finished) =
+                    (&matcher, &lowest, &attempts, &finished);
+                scope.spawn(move || {
+                    let mut preimages = [preimage; BATCH_SIZE as usize];
+                    // Thread `i` tries the batches `(i..).step_by(threads)`, and stops once it
+                    // reaches a batch after the lowest match found so far.
+                    let mut batch = thread;
+                    while let Some(first) = batch.checked_mul(BATCH_SIZE) {
+                        if first >= lowest.load(Ordering::Relaxed) ||
+                            deadline.is_some_and(|deadline| Instant::now() >= deadline)
+                        {
+                            break;
+                        }
+
+                        for (i, preimage) in preimages.iter_mut().enumerate() {
+                            let counter = start.wrapping_add(first + i as u64).to_le_bytes();
+                            preimage[COUNTER_OFFSET..COUNTER_OFFSET + 8].copy_from_slice(&counter);
+                        }
+                        let hashes = preimages.map(keccak256);
+                        if let Some(i) =
+                            hashes.iter().position(|hash| matcher(&Address::from_word(*hash)))
+                        {
+                            lowest.fetch_min(first + i as u64, Ordering::Relaxed);
+                        }
+
+                        attempts.fetch_add(BATCH_SIZE, Ordering::Relaxed);
+                        batch += threads;
+                    }
+                    finished.fetch_add(1, Ordering::Relaxed);
+                });
+            }
+
+            if self.progress {
+                let spinner = progress::spinner("Mining salt...");
+                let timer = Instant::now();
+                while finished.load(Ordering::Relaxed) < self.threads {
+                    std::thread::sleep(Duration::from_millis(100));
+                    let attempts = attempts.load(Ordering::Relaxed);
+                    let rate = attempts as f64 / timer.elapsed().as_secs_f64();
+                    spinner.set_message(format!("{attempts} salts tried ({rate:.0}/s)"));
+                }
+                spinner.finish_and_clear();
+            }
+        });
+
+        let offset = lowest.into_inner();
+        (offset != u64::MAX).then(|| {
+            let mut salt = self.salt;
+            salt[24..].copy_from_slice(&start.wrapping_add(offset).to_le_bytes());
+            (self.deployer.create2(salt, self.init_code_hash), salt)
+        })
+    }
+}
+
+fn max_threads() -> usize {
+    std::thread::available_parallelism().map_or(1, |n| n.get())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::{address, b256};
+
+    const INIT_CODE_HASH: B256 =
+        b256!("479d7e8f31234e208d704ba1a123c76385cea8a6981fd675b784fbd9cffb918d");
+
+    #[test]
+    fn mines_lowest_salt() {
+        let miner =
+            SaltMiner::new(address!("4e59b44847b379578588920ca78fbf26c0b4956c"), INIT_CODE_HASH);
+        let matcher = |address: &Address| address.starts_with(&[0x00]);
+
+        let (address, salt) = miner.clone().threads(1).mine(matcher).unwrap();
+        assert_eq!(address, address!("00bF495b8b42fdFeb91c8bCEB42CA4eE7186AEd2"));
+        assert_eq!(salt, b256!("000000000000000000000000000000000000000000000000df00000000000000"));
+        assert_eq!(miner.threads(4).mine(matcher), Some((address, salt)));
+    }
+
+    #[test]
+    fn stops_after_timeout() {
+        let miner = SaltMiner::new(Address::ZERO, INIT_CODE_HASH)
+            .threads(2)
+            .timeout(Duration::from_millis(100));
+        assert_eq!(miner.mine(|_| false), None);
+    }
+}
@@ -13,6 +13,7 @@ use foundry_config::{
 };
 use rustc_hash::FxHashMap;
 use serde::Serialize;
+use std::path::PathBuf;
 
 /// Map keyed by breakpoints char to their location (contract address, pc)
 pub type Breakpoints = FxHashMap<char, (Address, usize)>;
@@ -44,6 +45,10 @@ pub struct EvmArgs {
     /// Fetch state over a remote endpoint instead of starting from an empty state.
     ///
     /// If you want to fetch state from a specific block number, see --fork-block-number.
+    ///
+    /// This can be a comma-separated list of endpoints, which are tried in order when an endpoint
+    /// is rate limited or unavailable. The compute units per second of a single endpoint can be
+    /// set with a `#cups=<CUPS>` suffix, e.g. `https://a.io#cups=330,https://b.io`.
     #[arg(long, short, visible_alias = "rpc-url", value_name = "URL")]
     #[serde(rename = "eth_rpc_url", skip_serializing_if = "Option::is_none")]
     pub fork_url: Option<String>,
@@ -69,6 +74,38 @@ pub struct EvmArgs {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub fork_retry_backoff: Option<u64>,
 
+    /// Delay in milliseconds after which a slow request is also sent to the next endpoint.
+    ///
+    /// Only applies if the fork URL is a comma-separated list of endpoints, which are otherwise
+    /// only used to fail over when an endpoint is rate limited or unavailable.
+    ///
+    /// See --fork-url.
+    #[arg(long, requires = "fork_url", value_name = "MILLIS")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fork_hedge_delay: Option<u64>,
+
+    /// Load the state of the fork from a snapshot file, and save all the fetched state to it.
+    ///
+    /// The snapshot is a compact, shareable file that avoids fetching the same state over RPC
+    /// again. It can also be loaded into anvil with --load-state, and anvil state dumps can be
+    /// used as snapshots. Snapshots taken at a different block are ignored.
+    ///
+    /// See --fork-url.
+    #[arg(long, requires = "fork_url", value_name = "PATH")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fork_state: Option<PathBuf>,
+
+    /// Number of storage slots to prefetch speculatively.
+    ///
+    /// When a storage slot is fetched from the fork, the following slots of the account are
+    /// fetched along with it, and when a contract is fetched, its first slots are fetched as well.
+    /// Slots are fetched in batches with eth_getProof if the endpoint supports it.
+    ///
+    /// See --fork-url.
+    #[arg(long, requires = "fork_url", value_name = "SLOTS")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fork_prefetch: Option<usize>,
+
     /// Explicitly disables the use of RPC caching.
     ///
     /// All storage slots are read entirely from the endpoint.
@@ -133,6 +170,15 @@ pub struct EvmArgs {
     #[serde(skip)]
     pub no_rpc_rate_limit: bool,
 
+    /// The maximum total weight, in compute units, of the RPC requests that forks may send.
+    ///
+    /// Fork requests that would exceed the budget fail, and so do the tests that send them.
+    ///
+    /// See also --fork-url.
+    #[arg(long, value_name = "CU", help_heading = "Fork config")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rpc_budget: Option<u64>,
+
     /// All ethereum environment related arguments
     #[command(flatten)]
     #[serde(flatten)]
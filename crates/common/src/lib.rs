@@ -16,12 +16,15 @@ pub use foundry_common_fmt as fmt;
 pub mod abi;
 pub mod calc;
 pub mod compile;
+pub mod completions;
 pub mod constants;
 pub mod contracts;
+pub mod create2;
 pub mod ens;
 pub mod errors;
 pub mod evm;
 pub mod fs;
+pub mod progress;
 pub mod provider;
 pub mod retry;
 pub mod selectors;
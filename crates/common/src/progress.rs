@@ -0,0 +1,206 @@
+//! Progress reporting shared by all commands.
+//!
+//! Long running phases like compilation, fork data fetching, fuzz campaigns, broadcast
+//! confirmations and verification polling create their bars and spinners here, so that they look
+//! the same across commands and honor the [`ProgressMode`] selected with `--quiet` or
+//! `--porcelain`.
+
+use foundry_compilers::{
+    artifacts::remappings::Remapping,
+    report::{self, Reporter},
+};
+use indicatif::{MultiProgress, ProgressBar, ProgressDrawTarget, ProgressState, ProgressStyle};
+use once_cell::sync::OnceCell;
+use semver::Version;
+use std::{
+    borrow::Cow,
+    fmt::{self, Write},
+    io::IsTerminal,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+/// The progress mode of the program, set once at startup.
+static MODE: OnceCell<ProgressMode> = OnceCell::new();
+
+/// The tick characters of all spinners.
+pub const TICK_CHARS: &str = "⠁⠂⠄⡀⢀⠠⠐⠈ ";
+
+/// How the progress of long running phases is reported.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ProgressMode {
+    /// Bars and spinners are drawn on stderr if it is a terminal.
+    #[default]
+    Auto,
+    /// Progress is not reported.
+    Quiet,
+    /// Progress is reported as stable, tab separated lines on stderr, see [`event`].
+    Porcelain,
+}
+
+impl ProgressMode {
+    /// Returns true if bars and spinners are drawn.
+    pub fn draws_bars(self) -> bool {
+        self == Self::Auto && std::io::stderr().is_terminal()
+    }
+}
+
+/// Sets the progress mode of the program. Only the first call has an effect.
+pub fn set_progress_mode(mode: ProgressMode) {
+    let _ = MODE.set(mode);
+}
+
+/// Returns the progress mode of the program, [`ProgressMode::Auto`] if it was not set.
+pub fn progress_mode() -> ProgressMode {
+    MODE.get().copied().unwrap_or_default()
+}
+
+/// A long running phase whose progress is reported.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Phase {
+    /// Compiling the project, or installing compilers.
+    Compile,
+    /// Fetching the environment of a fork.
+    Fork,
+    /// Running test suites.
+    Test,
+    /// Running a fuzz or invariant campaign.
+    Fuzz,
+    /// Sending transactions and waiting for their receipts.
+    Broadcast,
+    /// Submitting contracts for verification and polling the result.
+    Verify,
+}
+
+impl Phase {
+    /// Returns the name of the phase, as printed in porcelain mode.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Compile => "compile",
+            Self::Fork => "fork",
+            Self::Test => "test",
+            Self::Fuzz => "fuzz",
+            Self::Broadcast => "broadcast",
+            Self::Verify => "verify",
+        }
+    }
+}
+
+impl fmt::Display for Phase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Reports an event of a phase in porcelain mode, does nothing otherwise.
+///
+/// Events are printed on stderr as `<phase>\t<event>\t<detail>` lines, e.g.
+/// `compile\tstart\t3 files with Solc 0.8.26`. Phases and events are stable, while the details are
+/// meant for humans and are kept on a single line.
+pub fn event(phase: Phase, event: &str, detail: impl fmt::Display) {
+    if progress_mode() == ProgressMode::Porcelain {
+        eprintln!("{}", format_event(phase, event, detail));
+    }
+}
+
+fn format_event(phase: Phase, event: &str, detail: impl fmt::Display) -> String {
+    let detail = detail.to_string().replace(['\t', '\n', '\r'], " ");
+    format!("{phase}\t{event}\t{}", detail.trim())
+}
+
+/// Returns a new [`MultiProgress`] grouping the bars of a phase, hidden unless bars are drawn.
+pub fn multi_progress() -> MultiProgress {
+    if progress_mode().draws_bars() {
+        MultiProgress::new()
+    } else {
+        MultiProgress::with_draw_target(ProgressDrawTarget::hidden())
+    }
+}
+
+/// Returns a new spinner with the given message, hidden unless bars are drawn.
+pub fn spinner(message: impl Into<Cow<'static, str>>) -> ProgressBar {
+    let spinner = hidden_unless_drawn(ProgressBar::new_spinner())
+        .with_style(spinner_style("{spinner:.green} {msg}"))
+        .with_message(message);
+    spinner.enable_steady_tick(Duration::from_millis(100));
+    spinner
+}
+
+/// Returns the style of spinners with the given template.
+pub fn spinner_style(template: &str) -> ProgressStyle {
+    ProgressStyle::with_template(template).unwrap().tick_chars(TICK_CHARS)
+}
+
+/// Returns a new bar counting `len` items named `label`, hidden unless bars are drawn.
+pub fn bar(len: u64, label: &str) -> ProgressBar {
+    let mut template =
+        "{prefix}{spinner:.green} [{elapsed_precise}] [{wide_bar:.cyan/blue}] {pos}/{len} "
+            .to_string();
+    write!(template, "{label}").unwrap();
+    template += " ({eta})";
+    hidden_unless_drawn(ProgressBar::new(len)).with_style(
+        ProgressStyle::with_template(&template)
+            .unwrap()
+            .with_key("eta", eta_key)
+            .progress_chars("#>-"),
+    )
+}
+
+fn eta_key(state: &ProgressState, f: &mut dyn fmt::Write) {
+    write!(f, "{:.1}s", state.eta().as_secs_f64()).unwrap()
+}
+
+fn hidden_unless_drawn(bar: ProgressBar) -> ProgressBar {
+    if !progress_mode().draws_bars() {
+        bar.set_draw_target(ProgressDrawTarget::hidden());
+    }
+    bar
+}
+
+/// A [`Reporter`] printing the compilation events in porcelain mode.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct PorcelainReporter;
+
+impl Reporter for PorcelainReporter {
+    fn on_compiler_spawn(&self, compiler_name: &str, version: &Version, dirty_files: &[PathBuf]) {
+        let files = dirty_files.len();
+        event(Phase::Compile, "start", format!("{files} files with {compiler_name} {version}"));
+    }
+
+    fn on_compiler_success(&self, compiler_name: &str, version: &Version, duration: &Duration) {
+        event(Phase::Compile, "finish", format!("{compiler_name} {version} in {duration:.2?}"));
+    }
+
+    fn on_solc_installation_start(&self, version: &Version) {
+        event(Phase::Compile, "install", format!("Solc {version}"));
+    }
+
+    fn on_solc_installation_success(&self, version: &Version) {
+        event(Phase::Compile, "installed", format!("Solc {version}"));
+    }
+
+    fn on_solc_installation_error(&self, version: &Version, error: &str) {
+        event(Phase::Compile, "error", format!("failed to install Solc {version}: {error}"));
+    }
+
+    fn on_unresolved_imports(&self, imports: &[(&Path, &Path)], remappings: &[Remapping]) {
+        event(Phase::Compile, "error", report::format_unresolved_imports(imports, remappings));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_events_on_one_line() {
+        assert_eq!(
+            format_event(Phase::Verify, "status", "Pass - Verified"),
+            "verify\tstatus\tPass - Verified"
+        );
+        assert_eq!(
+            format_event(Phase::Compile, "error", "Unable to resolve imports:\n\t\"A.sol\"\n"),
+            "compile\terror\tUnable to resolve imports:  \"A.sol\""
+        );
+    }
+}
@@ -0,0 +1,339 @@
+//! Transport that spreads requests over a list of endpoints, failing over to the next endpoint if
+//! one is rate limited or unavailable, and hedging slow requests.
+
+use super::{
+    retry::{RateLimitRetryPolicy, RetryPolicy},
+    runtime_transport::RuntimeTransport,
+};
+use alloy_json_rpc::{RequestPacket, ResponsePacket};
+use alloy_transport::{TransportError, TransportFut};
+use futures::future::{select, Either};
+use std::{
+    fmt,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+    time::Duration,
+};
+use tokio::time::Instant;
+
+/// The assumed average cost of a request in compute units, see
+/// [`RetryBackoffService`](super::tower::RetryBackoffService).
+const AVG_COST: u64 = 17;
+
+/// The time a failed endpoint is skipped for, doubled with every consecutive failure.
+const INITIAL_COOLDOWN: Duration = Duration::from_secs(1);
+
+/// The maximum time a failed endpoint is skipped for.
+const MAX_COOLDOWN: Duration = Duration::from_secs(30);
+
+/// A transport over a list of endpoints.
+///
+/// Requests are sent to the first available endpoint. If an endpoint is rate limited or
+/// unavailable, the request is sent to the next one and the failed endpoint is skipped for a
+/// while. Endpoints can be rate limited individually, and if a hedge delay is set, a request that
+/// didn't complete within the delay is also sent to the next endpoint, using whichever response
+/// arrives first.
+///
+/// A single endpoint behaves like the [RuntimeTransport] it wraps.
+#[derive(Clone, Debug)]
+pub struct FallbackTransport {
+    /// The endpoints, in the order they are preferred in.
+    endpoints: Arc<Vec<Endpoint>>,
+    /// The delay after which a slow request is also sent to the next endpoint.
+    hedge_delay: Option<Duration>,
+    /// The policy that decides whether an error response is a rate limit.
+    policy: RateLimitRetryPolicy,
+}
+
+impl FallbackTransport {
+    /// Creates a new transport over the given endpoints and their compute units per second, if
+    /// they are rate limited individually.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `endpoints` is empty.
+    pub fn new(endpoints: impl IntoIterator<Item = (RuntimeTransport, Option<u64>)>) -> Self {
+        let endpoints: Vec<_> = endpoints
+            .into_iter()
+            .map(|(transport, compute_units_per_second)| {
+                Endpoint::new(transport, compute_units_per_second)
+            })
+            .collect();
+        assert!(!endpoints.is_empty(), "fallback transport without endpoints");
+        Self { endpoints: Arc::new(endpoints), hedge_delay: None, policy: RateLimitRetryPolicy }
+    }
+
+    /// Sets the delay after which a slow request is also sent to the next endpoint.
+    pub fn with_hedge_delay(mut self, hedge_delay: Option<Duration>) -> Self {
+        self.hedge_delay = hedge_delay;
+        self
+    }
+
+    /// Sends a request to the endpoints, see [FallbackTransport].
+    pub fn request(&self, req: RequestPacket) -> TransportFut<'static> {
+        let this = self.clone();
+        Box::pin(async move { this.send(req).await })
+    }
+
+    async fn send(&self, req: RequestPacket) -> Result<ResponsePacket, TransportError> {
+        let order = self.order();
+        let mut last_err = None;
+        let mut position = 0;
+        while let Some(&index) = order.get(position) {
+            let (result, tried) = match (self.hedge_delay, order.get(position + 1)) {
+                (Some(delay), Some(&backup)) => self.send_hedged(index, backup, delay, &req).await,
+                _ => (self.send_to(index, req.clone()).await, 1),
+            };
+            position += tried;
+
+            match result {
+                Err(err) if self.should_fail_over(&err) => {
+                    trace!(endpoint = %self.endpoints[index].transport, ?err, "failing over");
+                    last_err = Some(err);
+                }
+                result => return result,
+            }
+        }
+        Err(last_err.expect("endpoints are not empty"))
+    }
+
+    /// Sends the request to `primary`, and also to `backup` if `primary` didn't respond within
+    /// `delay`.
+    ///
+    /// Returns the first successful response and the number of endpoints the request was sent to.
+    async fn send_hedged(
+        &self,
+        primary: usize,
+        backup: usize,
+        delay: Duration,
+        req: &RequestPacket,
+    ) -> (Result<ResponsePacket, TransportError>, usize) {
+        let first = self.send_to(primary, req.clone());
+        tokio::pin!(first);
+        if let Ok(result) = tokio::time::timeout(delay, &mut first).await {
+            return (result, 1)
+        }
+
+        trace!(endpoint = %self.endpoints[backup].transport, ?delay, "hedging slow request");
+        let second = self.send_to(backup, req.clone());
+        tokio::pin!(second);
+        let result = match select(first, second).await {
+            Either::Left((Ok(res), _)) | Either::Right((Ok(res), _)) => Ok(res),
+            Either::Left((Err(_), other)) | Either::Right((Err(_), other)) => other.await,
+        };
+        (result, 2)
+    }
+
+    /// Sends the request to the endpoint at `index`, keeping track of its availability.
+    ///
+    /// Rate limit error responses are returned as errors, so that they fail over.
+    async fn send_to(
+        &self,
+        index: usize,
+        req: RequestPacket,
+    ) -> Result<ResponsePacket, TransportError> {
+        let endpoint = &self.endpoints[index];
+        endpoint.throttle().await;
+
+        let result = endpoint.transport.request(req).await.and_then(|res| {
+            match res.as_error().cloned().map(TransportError::ErrorResp) {
+                Some(err) if self.policy.should_retry(&err) => Err(err),
+                _ => Ok(res),
+            }
+        });
+        match &result {
+            Ok(_) => endpoint.on_success(),
+            Err(err) if self.should_fail_over(err) => {
+                endpoint.on_failure(self.policy.backoff_hint(err))
+            }
+            Err(_) => {}
+        }
+        result
+    }
+
+    /// Returns the indices of the endpoints in the order they are tried in: the available
+    /// endpoints in their configured order, followed by the failed ones, soonest available first.
+    fn order(&self) -> Vec<usize> {
+        let mut available = Vec::with_capacity(self.endpoints.len());
+        let mut failed = Vec::new();
+        for (index, endpoint) in self.endpoints.iter().enumerate() {
+            match endpoint.cooldown_until() {
+                Some(until) => failed.push((until, index)),
+                None => available.push(index),
+            }
+        }
+        failed.sort_unstable();
+        available.extend(failed.into_iter().map(|(_, index)| index));
+        available
+    }
+
+    /// Returns whether the error means that the endpoint is rate limited or unavailable, and the
+    /// request should be sent to the next endpoint.
+    ///
+    /// This includes any transport error, e.g. HTTP 429 and 5xx responses or connection failures,
+    /// but not error responses to the request itself, like reverts.
+    fn should_fail_over(&self, err: &TransportError) -> bool {
+        match err {
+            TransportError::Transport(_) |
+            TransportError::NullResp |
+            TransportError::DeserError { .. } => true,
+            _ => self.policy.should_retry(err),
+        }
+    }
+}
+
+impl fmt::Display for FallbackTransport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, endpoint) in self.endpoints.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "{}", endpoint.transport)?;
+        }
+        Ok(())
+    }
+}
+
+impl tower::Service<RequestPacket> for FallbackTransport {
+    type Response = ResponsePacket;
+    type Error = TransportError;
+    type Future = TransportFut<'static>;
+
+    #[inline]
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    #[inline]
+    fn call(&mut self, req: RequestPacket) -> Self::Future {
+        self.request(req)
+    }
+}
+
+/// A single endpoint of a [FallbackTransport].
+#[derive(Debug)]
+struct Endpoint {
+    transport: RuntimeTransport,
+    /// The minimum interval between two requests, derived from the compute units per second.
+    interval: Option<Duration>,
+    state: Mutex<EndpointState>,
+}
+
+#[derive(Debug, Default)]
+struct EndpointState {
+    /// The earliest time the next request may be sent at.
+    next_request: Option<Instant>,
+    /// The time until which the endpoint is skipped after a failure.
+    cooldown_until: Option<Instant>,
+    /// The number of consecutive failures.
+    failures: u32,
+}
+
+impl Endpoint {
+    fn new(transport: RuntimeTransport, compute_units_per_second: Option<u64>) -> Self {
+        let interval = compute_units_per_second
+            .filter(|cups| *cups > 0)
+            .map(|cups| Duration::from_nanos(AVG_COST * 1_000_000_000 / cups));
+        Self { transport, interval, state: Default::default() }
+    }
+
+    /// Waits until the rate limit of the endpoint allows another request.
+    async fn throttle(&self) {
+        let Some(interval) = self.interval else { return };
+        let slot = {
+            let mut state = self.state.lock().unwrap();
+            let now = Instant::now();
+            let slot = state.next_request.map_or(now, |next| next.max(now));
+            state.next_request = Some(slot + interval);
+            slot
+        };
+        tokio::time::sleep_until(slot).await;
+    }
+
+    fn on_success(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.failures = 0;
+        state.cooldown_until = None;
+    }
+
+    fn on_failure(&self, backoff_hint: Option<Duration>) {
+        let mut state = self.state.lock().unwrap();
+        state.failures = state.failures.saturating_add(1);
+        let cooldown = backoff_hint.unwrap_or_else(|| {
+            INITIAL_COOLDOWN
+                .saturating_mul(2u32.saturating_pow(state.failures - 1))
+                .min(MAX_COOLDOWN)
+        });
+        state.cooldown_until = Some(Instant::now() + cooldown);
+    }
+
+    /// Returns the time until which the endpoint is skipped, if it recently failed.
+    fn cooldown_until(&self) -> Option<Instant> {
+        self.state.lock().unwrap().cooldown_until.filter(|until| *until > Instant::now())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::runtime_transport::RuntimeTransportBuilder;
+    use alloy_transport::TransportErrorKind;
+
+    fn transport(urls: &[(&str, Option<u64>)]) -> FallbackTransport {
+        FallbackTransport::new(
+            urls.iter().map(|(url, cups)| {
+                (RuntimeTransportBuilder::new(url.parse().unwrap()).build(), *cups)
+            }),
+        )
+    }
+
+    #[test]
+    fn orders_failed_endpoints_last() {
+        let transport =
+            transport(&[("http://a.io", None), ("http://b.io", None), ("http://c.io", None)]);
+        assert_eq!(transport.order(), vec![0, 1, 2]);
+
+        transport.endpoints[0].on_failure(None);
+        assert_eq!(transport.order(), vec![1, 2, 0]);
+
+        transport.endpoints[1].on_failure(Some(Duration::from_millis(500)));
+        assert_eq!(transport.order(), vec![2, 1, 0]);
+
+        transport.endpoints[0].on_success();
+        assert_eq!(transport.order(), vec![0, 2, 1]);
+    }
+
+    #[test]
+    fn backs_off_repeated_failures() {
+        let transport = transport(&[("http://a.io", None)]);
+        let endpoint = &transport.endpoints[0];
+        for _ in 0..10 {
+            endpoint.on_failure(None);
+        }
+        let cooldown = endpoint.cooldown_until().unwrap() - Instant::now();
+        assert!(cooldown > MAX_COOLDOWN / 2 && cooldown <= MAX_COOLDOWN);
+    }
+
+    #[test]
+    fn derives_request_interval() {
+        let transport = transport(&[("http://a.io", Some(340)), ("http://b.io", None)]);
+        assert_eq!(transport.endpoints[0].interval, Some(Duration::from_millis(50)));
+        assert_eq!(transport.endpoints[1].interval, None);
+    }
+
+    #[test]
+    fn fails_over_on_unavailable_endpoints() {
+        let transport = transport(&[("http://a.io", None)]);
+        let http = |status, body: &str| TransportErrorKind::http_error(status, body.to_string());
+        assert!(transport.should_fail_over(&http(429, "")));
+        assert!(transport.should_fail_over(&http(503, "Service Unavailable")));
+        assert!(transport.should_fail_over(&TransportErrorKind::custom_str("connection refused")));
+        assert!(transport.should_fail_over(&TransportError::NullResp));
+
+        let revert = serde_json::from_str(r#"{"code":3,"message":"execution reverted"}"#).unwrap();
+        assert!(!transport.should_fail_over(&TransportError::ErrorResp(revert)));
+        let rate_limit =
+            serde_json::from_str(r#"{"code":429,"message":"Too many requests"}"#).unwrap();
+        assert!(transport.should_fail_over(&TransportError::ErrorResp(rate_limit)));
+    }
+}
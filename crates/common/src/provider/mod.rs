@@ -1,5 +1,6 @@
 //! Provider-related instantiation and usage utilities.
 
+pub mod fallback;
 pub mod retry;
 pub mod runtime_transport;
 pub mod tower;
@@ -15,9 +16,9 @@ use alloy_provider::{
 use alloy_rpc_client::ClientBuilder;
 use alloy_transport::utils::guess_local_url;
 use eyre::{Result, WrapErr};
-use foundry_config::NamedChain;
+use fallback::FallbackTransport;
+use foundry_config::{split_rpc_urls, NamedChain};
 use reqwest::Url;
-use runtime_transport::RuntimeTransport;
 use std::{
     net::SocketAddr,
     path::{Path, PathBuf},
@@ -28,7 +29,7 @@ use tower::{RetryBackoffLayer, RetryBackoffService};
 use url::ParseError;
 
 /// Helper type alias for a retry provider
-pub type RetryProvider<N = AnyNetwork> = RootProvider<RetryBackoffService<RuntimeTransport>, N>;
+pub type RetryProvider<N = AnyNetwork> = RootProvider<RetryBackoffService<FallbackTransport>, N>;
 
 /// Helper type alias for a retry provider with a signer
 pub type RetryProviderWithSigner<N = AnyNetwork> = FillProvider<
@@ -36,8 +37,8 @@ pub type RetryProviderWithSigner<N = AnyNetwork> = FillProvider<
         JoinFill<JoinFill<JoinFill<Identity, GasFiller>, NonceFiller>, ChainIdFiller>,
         WalletFiller<EthereumWallet>,
     >,
-    RootProvider<RetryBackoffService<RuntimeTransport>, N>,
-    RetryBackoffService<RuntimeTransport>,
+    RootProvider<RetryBackoffService<FallbackTransport>, N>,
+    RetryBackoffService<FallbackTransport>,
     N,
 >;
 
@@ -71,10 +72,13 @@ pub fn try_get_http_provider(builder: impl AsRef<str>) -> Result<RetryProvider>
 }
 
 /// Helper type to construct a `RetryProvider`
+///
+/// The URL can be a list of endpoints, see [`foundry_config::RPC_URL_SEPARATOR`].
 #[derive(Debug)]
 pub struct ProviderBuilder {
     // Note: this is a result, so we can easily chain builder calls
-    url: Result<Url>,
+    /// The endpoint URLs and their compute units per second, if set
+    endpoints: Result<Vec<(Url, Option<u64>)>>,
     chain: NamedChain,
     max_retry: u32,
     timeout_retry: u32,
@@ -86,46 +90,30 @@ pub struct ProviderBuilder {
     jwt: Option<String>,
     headers: Vec<String>,
     is_local: bool,
+    /// Delay after which a slow request is also sent to the next endpoint
+    hedge_delay: Option<Duration>,
 }
 
 impl ProviderBuilder {
     /// Creates a new builder instance
     pub fn new(url_str: &str) -> Self {
-        // a copy is needed for the next lines to work
-        let mut url_str = url_str;
-
-        // invalid url: non-prefixed URL scheme is not allowed, so we prepend the default http
-        // prefix
-        let storage;
-        if url_str.starts_with("localhost:") {
-            storage = format!("http://{url_str}");
-            url_str = storage.as_str();
-        }
-
-        let url = Url::parse(url_str)
-            .or_else(|err| match err {
-                ParseError::RelativeUrlWithoutBase => {
-                    if SocketAddr::from_str(url_str).is_ok() {
-                        Url::parse(&format!("http://{url_str}"))
-                    } else {
-                        let path = Path::new(url_str);
-
-                        if let Ok(path) = resolve_path(path) {
-                            Url::parse(&format!("file://{}", path.display()))
-                        } else {
-                            Err(err)
-                        }
-                    }
+        let endpoints = split_rpc_urls(url_str)
+            .map(|(url, compute_units_per_second)| Ok((parse_url(url)?, compute_units_per_second)))
+            .collect::<Result<Vec<_>>>()
+            .and_then(|endpoints| {
+                if endpoints.is_empty() {
+                    eyre::bail!("invalid provider URL: {url_str:?}")
                 }
-                _ => Err(err),
-            })
-            .wrap_err_with(|| format!("invalid provider URL: {url_str:?}"));
+                Ok(endpoints)
+            });
 
-        // Use the final URL string to guess if it's a local URL.
-        let is_local = url.as_ref().map_or(false, |url| guess_local_url(url.as_str()));
+        // Use the final URL string of the first endpoint to guess if it's a local URL.
+        let is_local = endpoints.as_ref().map_or(false, |endpoints| {
+            endpoints.first().map_or(false, |(url, _)| guess_local_url(url.as_str()))
+        });
 
         Self {
-            url,
+            endpoints,
             chain: NamedChain::Mainnet,
             max_retry: 8,
             timeout_retry: 8,
@@ -136,6 +124,7 @@ impl ProviderBuilder {
             jwt: None,
             headers: vec![],
             is_local,
+            hedge_delay: None,
         }
     }
 
@@ -213,6 +202,17 @@ impl ProviderBuilder {
         self
     }
 
+    /// Sets the delay in milliseconds after which a slow request is also sent to the next
+    /// endpoint. If `None`, defaults to the already-set value.
+    ///
+    /// This only has an effect if the URL is a list of endpoints.
+    pub fn maybe_hedge_delay(mut self, hedge_delay: Option<u64>) -> Self {
+        if let Some(hedge_delay) = hedge_delay {
+            self.hedge_delay = Some(Duration::from_millis(hedge_delay));
+        }
+        self
+    }
+
     /// Sets aggressive `max_retry` and `initial_backoff` values
     ///
     /// This is only recommend for local dev nodes
@@ -236,7 +236,7 @@ impl ProviderBuilder {
     /// Constructs the `RetryProvider` taking all configs into account.
     pub fn build(self) -> Result<RetryProvider> {
         let Self {
-            url,
+            endpoints,
             chain: _,
             max_retry,
             timeout_retry,
@@ -246,8 +246,9 @@ impl ProviderBuilder {
             jwt,
             headers,
             is_local,
+            hedge_delay,
         } = self;
-        let url = url?;
+        let endpoints = endpoints?;
 
         let retry_layer = RetryBackoffLayer::new(
             max_retry,
@@ -255,11 +256,7 @@ impl ProviderBuilder {
             initial_backoff,
             compute_units_per_second,
         );
-        let transport = RuntimeTransportBuilder::new(url)
-            .with_timeout(timeout)
-            .with_headers(headers)
-            .with_jwt(jwt)
-            .build();
+        let transport = build_transport(endpoints, timeout, headers, jwt, hedge_delay);
         let client = ClientBuilder::default().layer(retry_layer).transport(transport, is_local);
 
         let provider = AlloyProviderBuilder::<_, _, AnyNetwork>::default()
@@ -271,7 +268,7 @@ impl ProviderBuilder {
     /// Constructs the `RetryProvider` with a wallet.
     pub fn build_with_wallet(self, wallet: EthereumWallet) -> Result<RetryProviderWithSigner> {
         let Self {
-            url,
+            endpoints,
             chain: _,
             max_retry,
             timeout_retry,
@@ -281,8 +278,9 @@ impl ProviderBuilder {
             jwt,
             headers,
             is_local,
+            hedge_delay,
         } = self;
-        let url = url?;
+        let endpoints = endpoints?;
 
         let retry_layer = RetryBackoffLayer::new(
             max_retry,
@@ -291,11 +289,7 @@ impl ProviderBuilder {
             compute_units_per_second,
         );
 
-        let transport = RuntimeTransportBuilder::new(url)
-            .with_timeout(timeout)
-            .with_headers(headers)
-            .with_jwt(jwt)
-            .build();
+        let transport = build_transport(endpoints, timeout, headers, jwt, hedge_delay);
 
         let client = ClientBuilder::default().layer(retry_layer).transport(transport, is_local);
 
@@ -308,6 +302,59 @@ impl ProviderBuilder {
     }
 }
 
+/// Builds the transport over all endpoints, each of which only connects on its first request.
+fn build_transport(
+    endpoints: Vec<(Url, Option<u64>)>,
+    timeout: Duration,
+    headers: Vec<String>,
+    jwt: Option<String>,
+    hedge_delay: Option<Duration>,
+) -> FallbackTransport {
+    FallbackTransport::new(endpoints.into_iter().map(|(url, compute_units_per_second)| {
+        let transport = RuntimeTransportBuilder::new(url)
+            .with_timeout(timeout)
+            .with_headers(headers.clone())
+            .with_jwt(jwt.clone())
+            .build();
+        (transport, compute_units_per_second)
+    }))
+    .with_hedge_delay(hedge_delay)
+}
+
+/// Parses a single provider URL, which may be missing its scheme, or be a socket address or IPC
+/// path.
+fn parse_url(url_str: &str) -> Result<Url> {
+    // a copy is needed for the next lines to work
+    let mut url_str = url_str;
+
+    // invalid url: non-prefixed URL scheme is not allowed, so we prepend the default http
+    // prefix
+    let storage;
+    if url_str.starts_with("localhost:") {
+        storage = format!("http://{url_str}");
+        url_str = storage.as_str();
+    }
+
+    Url::parse(url_str)
+        .or_else(|err| match err {
+            ParseError::RelativeUrlWithoutBase => {
+                if SocketAddr::from_str(url_str).is_ok() {
+                    Url::parse(&format!("http://{url_str}"))
+                } else {
+                    let path = Path::new(url_str);
+
+                    if let Ok(path) = resolve_path(path) {
+                        Url::parse(&format!("file://{}", path.display()))
+                    } else {
+                        Err(err)
+                    }
+                }
+            }
+            _ => Err(err),
+        })
+        .wrap_err_with(|| format!("invalid provider URL: {url_str:?}"))
+}
+
 #[cfg(not(windows))]
 fn resolve_path(path: &Path) -> Result<PathBuf, ()> {
     if path.is_absolute() {
@@ -334,9 +381,26 @@ mod tests {
     #[test]
     fn can_auto_correct_missing_prefix() {
         let builder = ProviderBuilder::new("localhost:8545");
-        assert!(builder.url.is_ok());
+        assert!(builder.endpoints.is_ok());
+
+        let endpoints = builder.endpoints.unwrap();
+        assert_eq!(endpoints, vec![(Url::parse("http://localhost:8545").unwrap(), None)]);
+    }
+
+    #[test]
+    fn can_parse_endpoint_list() {
+        let builder = ProviderBuilder::new("https://a.io/v2/key#cups=330, localhost:8545");
+        assert!(!builder.is_local);
+
+        let endpoints = builder.endpoints.unwrap();
+        assert_eq!(
+            endpoints,
+            vec![
+                (Url::parse("https://a.io/v2/key").unwrap(), Some(330)),
+                (Url::parse("http://localhost:8545").unwrap(), None),
+            ]
+        );
 
-        let url = builder.url.unwrap();
-        assert_eq!(url, Url::parse("http://localhost:8545").unwrap());
+        assert!(ProviderBuilder::new(",").endpoints.is_err());
     }
 }
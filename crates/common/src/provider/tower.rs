@@ -12,8 +12,8 @@ use alloy_json_rpc::{RequestPacket, ResponsePacket};
 use alloy_transport::{TransportError, TransportErrorKind, TransportFut};
 
 use super::{
+    fallback::FallbackTransport,
     retry::{RateLimitRetryPolicy, RetryPolicy},
-    runtime_transport::RuntimeTransport,
 };
 
 /// An Alloy Tower Layer that is responsible for retrying requests based on the
@@ -84,7 +84,7 @@ pub struct RetryBackoffService<S> {
 }
 
 // impl tower service
-impl tower::Service<RequestPacket> for RetryBackoffService<RuntimeTransport> {
+impl tower::Service<RequestPacket> for RetryBackoffService<FallbackTransport> {
     type Response = ResponsePacket;
     type Error = TransportError;
     type Future = TransportFut<'static>;
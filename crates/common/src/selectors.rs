@@ -2,13 +2,15 @@
 
 #![allow(missing_docs)]
 
-use crate::abi::abi_decode_calldata;
-use alloy_json_abi::JsonAbi;
+use crate::{abi::abi_decode_calldata, fs};
+use alloy_json_abi::{Error, Event, Function, JsonAbi};
+use foundry_config::Config;
 use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, BTreeSet, HashMap},
     fmt,
+    path::{Path, PathBuf},
     sync::{
         atomic::{AtomicBool, AtomicUsize, Ordering},
         Arc,
@@ -35,6 +37,8 @@ pub struct OpenChainClient {
     timedout_requests: Arc<AtomicUsize>,
     /// Max allowed request that can time out
     max_timedout_requests: usize,
+    /// The base URL of a self-hosted registry to upload selectors to, instead of OpenChain
+    registry: Option<String>,
 }
 
 impl OpenChainClient {
@@ -52,9 +56,20 @@ impl OpenChainClient {
             spurious_connection: Arc::new(Default::default()),
             timedout_requests: Arc::new(Default::default()),
             max_timedout_requests: MAX_TIMEDOUT_REQ,
+            registry: None,
         })
     }
 
+    /// Creates a new client uploading selectors to the given registry, or to OpenChain if `None`.
+    ///
+    /// A self-hosted registry implements the import endpoint of the OpenChain signature database
+    /// API at `<url>/import`, and serves all of its signatures at `<url>/export`, in any format
+    /// accepted by [`SignatureDatabase::import`]. Lookups are still made against OpenChain.
+    pub fn with_registry(registry: Option<String>) -> reqwest::Result<Self> {
+        let registry = registry.map(|url| url.trim_end_matches('/').to_string());
+        Ok(Self { registry, ..Self::new()? })
+    }
+
     async fn get_text(&self, url: &str) -> reqwest::Result<String> {
         trace!(%url, "GET");
         self.inner
@@ -306,7 +321,7 @@ impl OpenChainClient {
         Ok(possible_info)
     }
 
-    /// uploads selectors to OpenChain using the given data
+    /// uploads selectors to OpenChain, or to the registry of the client, using the given data
     pub async fn import_selectors(
         &self,
         data: SelectorImportData,
@@ -339,7 +354,31 @@ impl OpenChainClient {
             }
         };
 
-        Ok(self.post_json(SELECTOR_IMPORT_URL, &request).await?)
+        let url = match &self.registry {
+            Some(registry) => format!("{registry}/import"),
+            None => SELECTOR_IMPORT_URL.to_string(),
+        };
+        Ok(self.post_json(&url, &request).await?)
+    }
+
+    /// Downloads all the signatures of the registry of the client.
+    ///
+    /// OpenChain does not support this, so the client must have a self-hosted registry.
+    pub async fn export_signatures(&self) -> eyre::Result<SignatureDatabase> {
+        let Some(registry) = &self.registry else {
+            eyre::bail!(
+                "OpenChain does not support downloading signatures, \
+                 set `selectors_registry` to a self-hosted registry"
+            )
+        };
+        self.ensure_not_spurious()?;
+
+        let url = format!("{registry}/export");
+        trace!(%url, "GET");
+        let dump = self.inner.get(&url).send().await?.error_for_status()?.text().await?;
+        let mut db = SignatureDatabase::default();
+        db.import(&dump)?;
+        Ok(db)
     }
 }
 
@@ -508,7 +547,7 @@ impl SelectorImportResponse {
             .iter()
             .for_each(|(k, v)| println!("Duplicated: Event {k}: {v}"));
 
-        println!("Selectors successfully uploaded to OpenChain");
+        println!("Selectors successfully uploaded");
     }
 }
 
@@ -577,10 +616,267 @@ pub fn parse_signatures(tokens: Vec<String>) -> ParsedSignatures {
     ParsedSignatures { signatures, abis }
 }
 
+/// The file name of the local signature database, in the project's cache directory.
+pub const SIGNATURE_DATABASE_FILE: &str = "signatures.json";
+
+/// A local database of function, event and error signatures, keyed by their selectors.
+///
+/// The database is built from the ABIs of the project's artifacts and from imported signature
+/// dumps, so that selectors can be decoded without access to OpenChain or Etherscan.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignatureDatabase {
+    #[serde(default)]
+    pub functions: BTreeMap<String, BTreeSet<String>>,
+    #[serde(default)]
+    pub events: BTreeMap<String, BTreeSet<String>>,
+    #[serde(default)]
+    pub errors: BTreeMap<String, BTreeSet<String>>,
+}
+
+impl SignatureDatabase {
+    /// Returns the path of the signature database of the project with the given config.
+    pub fn path(config: &Config) -> PathBuf {
+        config.root.0.join(&config.cache_path).join(SIGNATURE_DATABASE_FILE)
+    }
+
+    /// Reads the database at the given path, or returns an empty database if there is none.
+    pub fn read(path: &Path) -> eyre::Result<Self> {
+        if path.is_file() {
+            Ok(fs::read_json_file(path)?)
+        } else {
+            Ok(Self::default())
+        }
+    }
+
+    /// Loads the signature database of the project with the given config.
+    ///
+    /// Errors are logged and result in an empty database.
+    pub fn load(config: &Config) -> Self {
+        let path = Self::path(config);
+        Self::read(&path)
+            .inspect_err(|err| warn!(?path, ?err, "failed to read the signature database"))
+            .unwrap_or_default()
+    }
+
+    /// Writes the database to the given path.
+    pub fn write(&self, path: &Path) -> eyre::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        Ok(fs::write_json_file(path, self)?)
+    }
+
+    /// Returns the total number of signatures.
+    pub fn len(&self) -> usize {
+        [&self.functions, &self.events, &self.errors]
+            .iter()
+            .flat_map(|map| map.values())
+            .map(BTreeSet::len)
+            .sum()
+    }
+
+    /// Returns whether the database contains no signatures.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Adds the functions, events and errors of the given ABI.
+    pub fn add_abi(&mut self, abi: &JsonAbi) {
+        for function in abi.functions() {
+            self.insert_function(function);
+        }
+        for event in abi.events().filter(|event| !event.anonymous) {
+            self.insert_event(event);
+        }
+        for error in abi.errors() {
+            self.insert_error(error);
+        }
+    }
+
+    /// Imports a signature dump, returning the number of signatures read.
+    ///
+    /// The dump is either an OpenChain lookup response, in JSON, or a list of signatures, one
+    /// per line. Lines are either of the form `[function|event|error] <signature>` or
+    /// `<selector>,<signature>`, where signatures without a type are functions.
+    pub fn import(&mut self, dump: &str) -> eyre::Result<usize> {
+        if dump.trim_start().starts_with('{') {
+            return self.import_openchain(dump)
+        }
+
+        let mut count = 0;
+        for (i, line) in dump.lines().map(str::trim).enumerate() {
+            if line.is_empty() || line.starts_with('#') {
+                continue
+            }
+            // `<selector>,<signature>`, where 32 byte selectors are event topics.
+            let selector = line
+                .split_once(',')
+                .filter(|(selector, _)| alloy_primitives::hex::decode(selector.trim()).is_ok());
+            let signature = match selector {
+                Some((selector, signature)) if selector.trim().len() == 66 => {
+                    format!("event {}", signature.trim())
+                }
+                Some((_, signature)) => signature.trim().to_string(),
+                None => line.to_string(),
+            };
+            self.add_signature(&signature)
+                .map_err(|err| eyre::eyre!("invalid signature on line {}: {err}", i + 1))?;
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    /// Imports an OpenChain lookup response, or its `result` object.
+    fn import_openchain(&mut self, dump: &str) -> eyre::Result<usize> {
+        #[derive(Deserialize)]
+        struct Decoded {
+            name: String,
+        }
+
+        #[derive(Deserialize)]
+        struct Dump {
+            #[serde(default)]
+            event: HashMap<String, Option<Vec<Decoded>>>,
+            #[serde(default)]
+            function: HashMap<String, Option<Vec<Decoded>>>,
+        }
+
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Response {
+            Full { result: Dump },
+            Result(Dump),
+        }
+
+        let dump = match serde_json::from_str(dump)? {
+            Response::Full { result } | Response::Result(result) => result,
+        };
+        let mut count = 0;
+        for (kind, signatures) in [("function", dump.function), ("event", dump.event)] {
+            for decoded in signatures.into_values().flatten().flatten() {
+                // Signatures that don't parse are skipped rather than failing the whole import.
+                if self.add_signature(&format!("{kind} {}", decoded.name)).is_ok() {
+                    count += 1;
+                }
+            }
+        }
+        Ok(count)
+    }
+
+    /// Adds a signature of the form `[function|event|error] <signature>`, defaulting to a
+    /// function.
+    pub fn add_signature(&mut self, signature: &str) -> eyre::Result<()> {
+        match signature.split_once(' ') {
+            Some(("event", signature)) => self.insert_event(&Event::parse(signature.trim())?),
+            Some(("error", signature)) => self.insert_error(&Error::parse(signature.trim())?),
+            Some(("function", signature)) => {
+                self.insert_function(&Function::parse(signature.trim())?)
+            }
+            _ => self.insert_function(&Function::parse(signature)?),
+        }
+        Ok(())
+    }
+
+    /// Merges the signatures of another database.
+    pub fn extend(&mut self, other: Self) {
+        for (map, other) in [
+            (&mut self.functions, other.functions),
+            (&mut self.events, other.events),
+            (&mut self.errors, other.errors),
+        ] {
+            for (selector, signatures) in other {
+                map.entry(selector).or_default().extend(signatures);
+            }
+        }
+    }
+
+    /// Returns the signatures of the functions with the given selector.
+    pub fn functions(&self, selector: &str) -> Vec<String> {
+        lookup(&self.functions, selector)
+    }
+
+    /// Returns the signatures of the events with the given topic 0.
+    pub fn events(&self, topic: &str) -> Vec<String> {
+        lookup(&self.events, topic)
+    }
+
+    /// Returns the signatures of the errors with the given selector.
+    pub fn errors(&self, selector: &str) -> Vec<String> {
+        lookup(&self.errors, selector)
+    }
+
+    fn insert_function(&mut self, function: &Function) {
+        let selector = alloy_primitives::hex::encode_prefixed(function.selector());
+        self.functions.entry(selector).or_default().insert(function.signature());
+    }
+
+    fn insert_event(&mut self, event: &Event) {
+        let selector = alloy_primitives::hex::encode_prefixed(event.selector());
+        self.events.entry(selector).or_default().insert(event.signature());
+    }
+
+    fn insert_error(&mut self, error: &Error) {
+        let selector = alloy_primitives::hex::encode_prefixed(error.selector());
+        self.errors.entry(selector).or_default().insert(error.signature());
+    }
+}
+
+fn lookup(map: &BTreeMap<String, BTreeSet<String>>, selector: &str) -> Vec<String> {
+    let selector = selector.trim().to_lowercase();
+    let selector = if selector.starts_with("0x") { selector } else { format!("0x{selector}") };
+    map.get(&selector).map(|signatures| signatures.iter().cloned().collect()).unwrap_or_default()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn can_build_signature_database() {
+        let mut db = SignatureDatabase::default();
+        let abi = JsonAbi::parse([
+            "function transfer(address to, uint256 amount) returns (bool)",
+            "event Transfer(address indexed from, address indexed to, uint256 value)",
+            "error InsufficientBalance(uint256 available)",
+        ])
+        .unwrap();
+        db.add_abi(&abi);
+        assert_eq!(db.len(), 3);
+        assert_eq!(db.functions("A9059CBB"), vec!["transfer(address,uint256)"]);
+        assert_eq!(
+            db.events("0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef"),
+            vec!["Transfer(address,address,uint256)"]
+        );
+        assert_eq!(db.errors("0xcf479181"), vec!["InsufficientBalance(uint256)"]);
+
+        let imported = db
+            .import(
+                "# comment\nfunction approve(address,uint256)\n0x70a08231,balanceOf(address)\n\
+                 error Unauthorized()\n",
+            )
+            .unwrap();
+        assert_eq!(imported, 3);
+        assert_eq!(db.functions("0x095ea7b3"), vec!["approve(address,uint256)"]);
+        assert_eq!(db.functions("0x70a08231"), vec!["balanceOf(address)"]);
+        assert_eq!(db.errors("0x82b42900"), vec!["Unauthorized()"]);
+        assert!(db.import("function approve(").is_err());
+    }
+
+    #[test]
+    fn can_import_openchain_dump() {
+        let mut db = SignatureDatabase::default();
+        let dump = r#"{"ok":true,"result":{"event":{},"function":{
+            "0xa9059cbb":[{"name":"transfer(address,uint256)","filtered":false}],
+            "0x00000000":null
+        }}}"#;
+        assert_eq!(db.import(dump).unwrap(), 1);
+        assert_eq!(db.functions("0xa9059cbb"), vec!["transfer(address,uint256)"]);
+
+        let dump = r#"{"event":{"0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef":[{"name":"Transfer(address,address,uint256)"}]}}"#;
+        assert_eq!(db.import(dump).unwrap(), 1);
+        assert_eq!(db.len(), 2);
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn test_decode_selector() {
         let sigs = decode_function_selector("0xa9059cbb").await;
@@ -1,8 +1,25 @@
 //! Misc Serde helpers for foundry crates.
 
 use alloy_primitives::U256;
-use serde::{de, Deserialize, Deserializer};
-use std::str::FromStr;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::{
+    collections::{BTreeMap, HashMap},
+    str::FromStr,
+};
+
+/// Serializes a hash map with its keys in order, so that the output doesn't depend on the iteration
+/// order of the map.
+pub fn serialize_sorted<S, K, V, H>(
+    map: &HashMap<K, V, H>,
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    K: Serialize + Ord,
+    V: Serialize,
+{
+    serializer.collect_map(map.iter().collect::<BTreeMap<_, _>>())
+}
 
 /// Helper type to parse both `u64` and `U256`
 #[derive(Copy, Clone, Deserialize)]
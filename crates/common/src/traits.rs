@@ -49,6 +49,11 @@ pub trait TestFunctionExt {
         self.test_function_kind().is_fuzz_test()
     }
 
+    /// Returns `true` if this function is a differential fuzz test, `testDiff_*`.
+    fn is_differential_test(&self) -> bool {
+        self.is_fuzz_test() && self.tfe_as_str().starts_with("testDiff_")
+    }
+
     /// Returns `true` if this function is an invariant test.
     fn is_invariant_test(&self) -> bool {
         self.test_function_kind().is_invariant_test()
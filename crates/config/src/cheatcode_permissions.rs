@@ -0,0 +1,157 @@
+//! Support for scoping dangerous cheatcodes to test paths and contracts
+
+use crate::filter::GlobMatcher;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeSet,
+    fmt,
+    path::{Path, PathBuf},
+};
+
+/// A capability of dangerous cheatcodes, which can be scoped with `cheatcode_permissions`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CheatcodeCapability {
+    /// Executing commands with `ffi` and `tryFfi`.
+    Ffi,
+    /// Writing, copying or removing files and directories, e.g. `writeFile` or `removeDir`.
+    FsWrite,
+    /// Sending requests to RPC endpoints, e.g. `createFork`, `rpc` or `eth_getLogs`.
+    Http,
+    /// Replacing the output of precompiles with `mockPrecompile`.
+    Precompiles,
+}
+
+impl CheatcodeCapability {
+    /// Returns the name of the capability, as used in the config.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Ffi => "ffi",
+            Self::FsWrite => "fs-write",
+            Self::Http => "http",
+            Self::Precompiles => "precompiles",
+        }
+    }
+}
+
+impl fmt::Display for CheatcodeCapability {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A single `cheatcode_permissions` entry.
+///
+/// Grants capabilities to the test contracts defined in the sources matching `path` and,
+/// optionally, whose name matches `contract`.
+///
+/// ```toml
+/// [[profile.default.cheatcode_permissions]]
+/// path = "test/fork/**"
+/// allow = ["http"]
+///
+/// [[profile.default.cheatcode_permissions]]
+/// path = "test/differential/**"
+/// contract = "*Ffi*"
+/// allow = ["ffi", "fs-write"]
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CheatcodePermission {
+    /// Glob matched against test source paths, relative to the project root.
+    #[serde(with = "crate::from_glob")]
+    pub path: globset::Glob,
+    /// Glob matched against test contract names. Applies to all contracts if not set.
+    #[serde(default, with = "crate::from_opt_glob", skip_serializing_if = "Option::is_none")]
+    pub contract: Option<globset::Glob>,
+    /// The capabilities granted to the matching test contracts.
+    #[serde(default)]
+    pub allow: Vec<CheatcodeCapability>,
+}
+
+impl CheatcodePermission {
+    /// Creates a new rule granting `allow` to the test contracts in sources matching `path`.
+    pub fn new(path: globset::Glob, allow: impl IntoIterator<Item = CheatcodeCapability>) -> Self {
+        Self { path, contract: None, allow: allow.into_iter().collect() }
+    }
+
+    /// Restricts the rule to the test contracts whose name matches `contract`.
+    pub fn contract(mut self, contract: globset::Glob) -> Self {
+        self.contract = Some(contract);
+        self
+    }
+}
+
+/// Resolved cheatcode permissions of a project.
+///
+/// A capability is only granted to the test contracts matched by a rule granting it. This is in
+/// addition to the global settings like `ffi` and `fs_permissions`, which still apply.
+#[derive(Clone, Debug)]
+pub struct CheatcodePermissionsPolicy {
+    /// The rules, with their compiled path and contract matchers.
+    rules: Vec<(GlobMatcher, Option<globset::GlobMatcher>, CheatcodePermission)>,
+    /// Root of the project.
+    root: PathBuf,
+}
+
+impl CheatcodePermissionsPolicy {
+    /// Creates a new policy with the given rules.
+    pub fn new(rules: Vec<CheatcodePermission>, root: impl Into<PathBuf>) -> Self {
+        let rules = rules.into_iter().map(|rule| {
+            let contract = rule.contract.as_ref().map(|contract| contract.compile_matcher());
+            (GlobMatcher::new(rule.path.clone()), contract, rule)
+        });
+        Self { rules: rules.collect(), root: root.into() }
+    }
+
+    /// Returns the capabilities granted to the test contract `name` defined in `file`.
+    pub fn granted(&self, file: &Path, name: &str) -> BTreeSet<CheatcodeCapability> {
+        let file = file.strip_prefix(&self.root).unwrap_or(file);
+        self.rules
+            .iter()
+            .filter(|(path, contract, _)| {
+                path.is_match(file) && contract.as_ref().map_or(true, |c| c.is_match(name))
+            })
+            .flat_map(|(_, _, rule)| rule.allow.iter().copied())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn glob(s: &str) -> globset::Glob {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn grants_capabilities_of_matching_rules() {
+        let policy = CheatcodePermissionsPolicy::new(
+            vec![
+                CheatcodePermission::new(glob("test/fork/**"), [CheatcodeCapability::Http]),
+                CheatcodePermission::new(
+                    glob("test/**"),
+                    [CheatcodeCapability::Ffi, CheatcodeCapability::FsWrite],
+                )
+                .contract(glob("*Ffi*")),
+            ],
+            "/root",
+        );
+
+        let granted = |file: &str, name: &str| policy.granted(Path::new(file), name);
+        assert_eq!(
+            granted("/root/test/fork/Fork.t.sol", "ForkTest"),
+            BTreeSet::from([CheatcodeCapability::Http])
+        );
+        assert_eq!(
+            granted("test/fork/Fork.t.sol", "ForkFfiTest"),
+            BTreeSet::from([
+                CheatcodeCapability::Ffi,
+                CheatcodeCapability::FsWrite,
+                CheatcodeCapability::Http
+            ])
+        );
+        assert!(granted("test/Unit.t.sol", "UnitTest").is_empty());
+        assert!(granted("src/Ffi.sol", "FfiTest").is_empty());
+    }
+}
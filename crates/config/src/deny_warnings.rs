@@ -0,0 +1,165 @@
+//! Support for scoping `deny_warnings` to source paths and solc warning codes
+
+use crate::{filter::GlobMatcher, SolidityErrorCode};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// A single `deny_warnings_from` entry.
+///
+/// Scopes whether compiler warnings are treated as errors to the sources matching `path` and,
+/// optionally, to a set of warning `codes`.
+///
+/// ```toml
+/// [[profile.default.deny_warnings_from]]
+/// path = "src/**"
+/// codes = ["shadowing"]
+///
+/// [[profile.default.deny_warnings_from]]
+/// path = "test/**"
+/// allow = true
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DenyWarningsRule {
+    /// Glob matched against source paths, relative to the project root.
+    #[serde(with = "crate::from_glob")]
+    pub path: globset::Glob,
+    /// The warning codes this rule applies to. Applies to all warnings if empty.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub codes: Vec<SolidityErrorCode>,
+    /// Whether matching warnings are allowed instead of denied.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub allow: bool,
+}
+
+impl DenyWarningsRule {
+    /// Creates a new rule that denies the given `codes` in sources matching `path`.
+    pub fn deny(path: globset::Glob, codes: impl IntoIterator<Item = SolidityErrorCode>) -> Self {
+        Self { path, codes: codes.into_iter().collect(), allow: false }
+    }
+
+    /// Creates a new rule that allows the given `codes` in sources matching `path`.
+    pub fn allow(path: globset::Glob, codes: impl IntoIterator<Item = SolidityErrorCode>) -> Self {
+        Self { path, codes: codes.into_iter().collect(), allow: true }
+    }
+
+    /// Returns true if the rule applies to the given warning code.
+    fn applies_to_code(&self, code: Option<u64>) -> bool {
+        self.codes.is_empty() ||
+            code.map_or(false, |code| self.codes.iter().any(|c| u64::from(*c) == code))
+    }
+}
+
+/// Resolved warnings-as-errors policy for a project.
+///
+/// Rules are evaluated in order and the last matching rule wins. Warnings that aren't matched by
+/// any rule fall back to the global `deny_warnings` setting.
+#[derive(Clone, Debug)]
+pub struct DenyWarningsPolicy {
+    /// The global `deny_warnings` setting.
+    pub default: bool,
+    /// The scoped rules, with their compiled path matchers.
+    rules: Vec<(GlobMatcher, DenyWarningsRule)>,
+    /// Error codes that are always ignored.
+    ignored_error_codes: Vec<u64>,
+    /// Paths whose warnings are always ignored.
+    ignored_file_paths: Vec<PathBuf>,
+    /// Root of the project.
+    root: PathBuf,
+}
+
+impl DenyWarningsPolicy {
+    /// Creates a new policy with the given fallback and scoped rules.
+    pub fn new(default: bool, rules: Vec<DenyWarningsRule>, root: impl Into<PathBuf>) -> Self {
+        let rules = rules.into_iter().map(|rule| (GlobMatcher::new(rule.path.clone()), rule));
+        Self {
+            default,
+            rules: rules.collect(),
+            ignored_error_codes: Vec::new(),
+            ignored_file_paths: Vec::new(),
+            root: root.into(),
+        }
+    }
+
+    /// Sets the error codes that are never denied.
+    pub fn ignored_error_codes(mut self, codes: impl IntoIterator<Item = u64>) -> Self {
+        self.ignored_error_codes = codes.into_iter().collect();
+        self
+    }
+
+    /// Sets the paths whose warnings are never denied.
+    pub fn ignored_file_paths(mut self, paths: impl IntoIterator<Item = PathBuf>) -> Self {
+        self.ignored_file_paths = paths.into_iter().collect();
+        self
+    }
+
+    /// Returns true if a warning with the given `code` emitted for `file` must be treated as an
+    /// error.
+    pub fn is_denied(&self, file: Option<&Path>, code: Option<u64>) -> bool {
+        if code.map_or(false, |code| self.ignored_error_codes.contains(&code)) {
+            return false;
+        }
+
+        let file = file.map(|file| file.strip_prefix(&self.root).unwrap_or(file));
+        if let Some(file) = file {
+            if self.ignored_file_paths.iter().any(|ignored| file.starts_with(ignored)) {
+                return false;
+            }
+        }
+
+        self.rules
+            .iter()
+            .rev()
+            .find(|(matcher, rule)| {
+                rule.applies_to_code(code) && file.map_or(false, |file| matcher.is_match(file))
+            })
+            .map_or(self.default, |(_, rule)| !rule.allow)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn glob(s: &str) -> globset::Glob {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn last_matching_rule_wins() {
+        let policy = DenyWarningsPolicy::new(
+            false,
+            vec![
+                DenyWarningsRule::deny(
+                    glob("src/**"),
+                    [SolidityErrorCode::ShadowsExistingDeclaration],
+                ),
+                DenyWarningsRule::allow(glob("src/legacy/**"), []),
+            ],
+            "/root",
+        );
+
+        let shadowing = Some(2519);
+        assert!(policy.is_denied(Some(Path::new("/root/src/A.sol")), shadowing));
+        assert!(policy.is_denied(Some(Path::new("src/A.sol")), shadowing));
+        assert!(!policy.is_denied(Some(Path::new("src/A.sol")), Some(2072)));
+        assert!(!policy.is_denied(Some(Path::new("test/A.t.sol")), shadowing));
+        assert!(!policy.is_denied(Some(Path::new("src/legacy/A.sol")), shadowing));
+    }
+
+    #[test]
+    fn falls_back_to_default() {
+        let policy = DenyWarningsPolicy::new(
+            true,
+            vec![DenyWarningsRule::allow(glob("test/**"), [])],
+            "/root",
+        )
+        .ignored_error_codes([1878])
+        .ignored_file_paths([PathBuf::from("lib")]);
+
+        assert!(policy.is_denied(Some(Path::new("src/A.sol")), Some(2072)));
+        assert!(policy.is_denied(None, Some(2072)));
+        assert!(!policy.is_denied(Some(Path::new("src/A.sol")), Some(1878)));
+        assert!(!policy.is_denied(Some(Path::new("lib/B.sol")), Some(2072)));
+        assert!(!policy.is_denied(Some(Path::new("test/A.t.sol")), Some(2072)));
+    }
+}
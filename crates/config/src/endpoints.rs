@@ -354,10 +354,70 @@ impl DerefMut for ResolvedRpcEndpoints {
     }
 }
 
+/// Separates the endpoints of an RPC URL list, e.g. `https://a.io,https://b.io`.
+///
+/// The endpoints of a list are tried in order, failing over to the next one if an endpoint is
+/// rate limited or unavailable.
+pub const RPC_URL_SEPARATOR: char = ',';
+
+/// The URL fragment that sets the compute units per second of a single endpoint of an RPC URL
+/// list, e.g. `https://a.io#cups=330`.
+pub const RPC_URL_CUPS_FRAGMENT: &str = "cups=";
+
+/// Splits an RPC URL list into its endpoints and their compute units per second, if set.
+///
+/// A single URL is a list with one endpoint.
+pub fn split_rpc_urls(urls: &str) -> impl Iterator<Item = (&str, Option<u64>)> {
+    urls.split(RPC_URL_SEPARATOR).map(str::trim).filter(|url| !url.is_empty()).map(|url| {
+        url.rsplit_once('#')
+            .and_then(|(url, fragment)| {
+                let cups = fragment.strip_prefix(RPC_URL_CUPS_FRAGMENT)?.parse().ok()?;
+                Some((url, Some(cups)))
+            })
+            .unwrap_or((url, None))
+    })
+}
+
+/// Deserializes an RPC URL that's either a single string or a list of endpoints, see
+/// [`RPC_URL_SEPARATOR`].
+pub fn deserialize_rpc_urls<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum RpcUrls {
+        Single(String),
+        List(Vec<String>),
+    }
+
+    Ok(Option::<RpcUrls>::deserialize(deserializer)?.map(|urls| match urls {
+        RpcUrls::Single(url) => url,
+        RpcUrls::List(urls) => urls.join(&RPC_URL_SEPARATOR.to_string()),
+    }))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn can_split_rpc_urls() {
+        let urls: Vec<_> =
+            split_rpc_urls("https://a.io/v2/key, https://b.io#cups=330,,ws://c.io#frag").collect();
+        assert_eq!(
+            urls,
+            vec![
+                ("https://a.io/v2/key", None),
+                ("https://b.io", Some(330)),
+                ("ws://c.io#frag", None)
+            ]
+        );
+
+        let urls: Vec<_> = split_rpc_urls("http://localhost:8545").collect();
+        assert_eq!(urls, vec![("http://localhost:8545", None)]);
+    }
+
     #[test]
     fn serde_rpc_config() {
         let s = r#"{
@@ -1,7 +1,8 @@
 //! Configuration for fuzz testing.
 
 use crate::inline::{
-    parse_config_u32, InlineConfigParser, InlineConfigParserError, INLINE_CONFIG_FUZZ_KEY,
+    parse_config_bool, parse_config_u32, InlineConfigParser, InlineConfigParserError,
+    INLINE_CONFIG_FUZZ_KEY,
 };
 use alloy_primitives::U256;
 use serde::{Deserialize, Serialize};
@@ -29,6 +30,20 @@ pub struct FuzzConfig {
     pub failure_persist_dir: Option<PathBuf>,
     /// Name of the file to record fuzz failures, defaults to `failures`.
     pub failure_persist_file: Option<String>,
+    /// Whether to use edge coverage feedback to guide input generation.
+    pub coverage_guided: bool,
+    /// Path where inputs discovering new coverage are stored and loaded from on subsequent runs.
+    ///
+    /// Only used if `coverage_guided` is enabled.
+    pub corpus_dir: Option<PathBuf>,
+    /// Number of threads to split the runs of each fuzz test across.
+    ///
+    /// Each thread executes on its own copy of the test state.
+    pub threads: usize,
+    /// Whether to report hints on the inputs needed to reach branches that were never taken.
+    pub branch_hints: bool,
+    /// What differential fuzz tests, named `testDiff_*`, compare between the two implementations.
+    pub differential: FuzzDifferentialConfig,
 }
 
 impl Default for FuzzConfig {
@@ -41,6 +56,11 @@ impl Default for FuzzConfig {
             gas_report_samples: 256,
             failure_persist_dir: None,
             failure_persist_file: None,
+            coverage_guided: false,
+            corpus_dir: None,
+            threads: 1,
+            branch_hints: false,
+            differential: FuzzDifferentialConfig::default(),
         }
     }
 }
@@ -56,8 +76,31 @@ impl FuzzConfig {
             gas_report_samples: 256,
             failure_persist_dir: Some(cache_dir),
             failure_persist_file: Some("failures".to_string()),
+            coverage_guided: false,
+            corpus_dir: None,
+            threads: 1,
+            branch_hints: false,
+            differential: FuzzDifferentialConfig::default(),
         }
     }
+
+    /// Returns path to the replay file of the given fuzz test, if failures are persisted.
+    ///
+    /// Replay files are keyed on the full `path:Contract` identifier of the test contract, so that
+    /// contracts with the same name in different files don't overwrite each other's failures.
+    pub fn replay_file(&self, contract_id: &str, test_name: &str) -> Option<PathBuf> {
+        self.failure_persist_dir.as_ref().map(|dir| {
+            let (path, name) = contract_id.rsplit_once(':').unwrap_or(("", contract_id));
+            dir.join("replays").join(path).join(name).join(format!("{test_name}.json"))
+        })
+    }
+
+    /// Returns the directory holding the corpus of the given test, if a corpus dir is configured.
+    pub fn corpus_path(&self, contract_name: &str, test_name: &str) -> Option<PathBuf> {
+        self.corpus_dir
+            .as_ref()
+            .map(|dir| dir.join(contract_name.split(':').last().unwrap()).join(test_name))
+    }
 }
 
 impl InlineConfigParser for FuzzConfig {
@@ -84,6 +127,9 @@ impl InlineConfigParser for FuzzConfig {
                     conf_clone.dictionary.dictionary_weight = parse_config_u32(key, value)?
                 }
                 "failure-persist-file" => conf_clone.failure_persist_file = Some(value),
+                "coverage-guided" => conf_clone.coverage_guided = parse_config_bool(key, value)?,
+                "branch-hints" => conf_clone.branch_hints = parse_config_bool(key, value)?,
+                "threads" => conf_clone.threads = parse_config_u32(key, value)? as usize,
                 _ => Err(InlineConfigParserError::InvalidConfigProperty(key))?,
             }
         }
@@ -91,6 +137,27 @@ impl InlineConfigParser for FuzzConfig {
     }
 }
 
+/// What differential fuzz tests compare between the two implementations they call.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FuzzDifferentialConfig {
+    /// Whether the return data of successful calls must match.
+    pub return_data: bool,
+    /// Whether both calls must either succeed or revert with the same data.
+    pub reverts: bool,
+    /// Whether the emitted logs must match.
+    pub logs: bool,
+    /// Whether the storage writes must match.
+    ///
+    /// Only meaningful if both implementations share the same storage layout.
+    pub state: bool,
+}
+
+impl Default for FuzzDifferentialConfig {
+    fn default() -> Self {
+        Self { return_data: true, reverts: true, logs: true, state: false }
+    }
+}
+
 /// Contains for fuzz testing
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct FuzzDictionaryConfig {
@@ -148,12 +215,16 @@ mod tests {
             "forge-config: default.fuzz.runs = 42424242".to_string(),
             "forge-config: default.fuzz.dictionary-weight = 42".to_string(),
             "forge-config: default.fuzz.failure-persist-file = fuzz-failure".to_string(),
+            "forge-config: default.fuzz.coverage-guided = true".to_string(),
+            "forge-config: default.fuzz.branch-hints = true".to_string(),
         ];
         let base_config = FuzzConfig::default();
         let merged: FuzzConfig = base_config.try_merge(configs).expect("No errors").unwrap();
         assert_eq!(merged.runs, 42424242);
         assert_eq!(merged.dictionary.dictionary_weight, 42);
         assert_eq!(merged.failure_persist_file, Some("fuzz-failure".to_string()));
+        assert!(merged.coverage_guided);
+        assert!(merged.branch_hints);
     }
 
     #[test]
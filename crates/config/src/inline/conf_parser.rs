@@ -81,8 +81,8 @@ where
     fn get_config_overrides(config_lines: &[String]) -> Vec<(String, String)> {
         let mut result: Vec<(String, String)> = vec![];
         let config_key = Self::config_key();
-        let profile = ".*";
-        let prefix = format!("^{INLINE_CONFIG_PREFIX}:{profile}{config_key}\\.");
+        let profile = "[^.]+";
+        let prefix = format!("^{INLINE_CONFIG_PREFIX}:{profile}\\.{config_key}\\.");
         let re = Regex::new(&prefix).unwrap();
 
         config_lines
@@ -91,11 +91,9 @@ where
             .filter(|l| re.is_match(l))
             .map(|l| re.replace(&l, "").to_string())
             .for_each(|line| {
-                let key_value = line.split('=').collect::<Vec<&str>>(); // i.e. "['runs', '500']"
-                if let Some(key) = key_value.first() {
-                    if let Some(value) = key_value.last() {
-                        result.push((key.to_string(), value.to_string()));
-                    }
+                // Values may contain `=` themselves, e.g. the query of a fork URL.
+                if let Some((key, value)) = line.split_once('=') {
+                    result.push((key.to_string(), value.to_string()));
                 }
             });
 
@@ -131,6 +129,12 @@ pub fn parse_config_u32(key: String, value: String) -> Result<u32, InlineConfigP
     value.parse().map_err(|_| InlineConfigParserError::ParseInt(key, value))
 }
 
+/// Tries to parse a `u64` from `value`. The `key` argument is used to give details
+/// in the case of an error.
+pub fn parse_config_u64(key: String, value: String) -> Result<u64, InlineConfigParserError> {
+    value.parse().map_err(|_| InlineConfigParserError::ParseInt(key, value))
+}
+
 /// Tries to parse a `bool` from `value`. The `key` argument is used to give details
 /// in the case of an error.
 pub fn parse_config_bool(key: String, value: String) -> Result<bool, InlineConfigParserError> {
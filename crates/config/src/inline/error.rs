@@ -14,6 +14,9 @@ pub enum InlineConfigParserError {
     /// An error occurred while trying to parse a boolean configuration value
     #[error("Invalid config value for key '{0}'. Unable to parse '{1}' into a boolean value")]
     ParseBool(String, String),
+    /// An invalid configuration value has been provided
+    #[error("Invalid config value for key '{0}': '{1}'")]
+    InvalidValue(String, String),
 }
 
 /// Wrapper error struct that catches config parsing errors, enriching them with context information
@@ -0,0 +1,128 @@
+use super::{
+    parse_config_bool, parse_config_u64, InlineConfigParser, InlineConfigParserError,
+    INLINE_CONFIG_EVM_KEY,
+};
+use foundry_compilers::artifacts::EvmVersion;
+
+/// EVM settings that can be overridden for a single test.
+///
+/// Unlike the fuzz and invariant settings, these apply to the executor the test runs in, so
+/// overriding any of them makes the test deploy and set up its contract separately:
+///
+/// ```solidity
+/// /// forge-config: default.evm.evm-version = cancun
+/// /// forge-config: default.evm.fork-url = mainnet
+/// /// forge-config: default.evm.fork-block-number = 19000000
+/// function test_OnMainnet() public {...}
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct InlineEvmConfig {
+    /// The EVM version to execute the test with.
+    pub evm_version: Option<EvmVersion>,
+    /// Whether to run the top-level calls of the test as separate transactions.
+    pub isolate: Option<bool>,
+    /// The URL, or the alias of an `rpc_endpoints` entry, to fork from.
+    pub fork_url: Option<String>,
+    /// The block to fork from, the latest one if not set.
+    ///
+    /// If no `fork_url` is set, the fork the tests run on is forked again at this block.
+    pub fork_block_number: Option<u64>,
+    /// The gas limit of the calls of the test.
+    pub gas_limit: Option<u64>,
+}
+
+impl InlineEvmConfig {
+    /// Returns `true` if the test must be set up separately from the rest of its contract, i.e.
+    /// if its contract must be deployed in a different environment.
+    pub fn requires_setup(&self) -> bool {
+        self.evm_version.is_some() ||
+            self.isolate.is_some() ||
+            self.fork_url.is_some() ||
+            self.fork_block_number.is_some()
+    }
+}
+
+impl InlineConfigParser for InlineEvmConfig {
+    fn config_key() -> String {
+        INLINE_CONFIG_EVM_KEY.into()
+    }
+
+    fn try_merge(&self, configs: &[String]) -> Result<Option<Self>, InlineConfigParserError> {
+        let overrides: Vec<(String, String)> = Self::get_config_overrides(configs);
+
+        if overrides.is_empty() {
+            return Ok(None)
+        }
+
+        let mut conf_clone = self.clone();
+
+        for pair in overrides {
+            let key = pair.0;
+            let value = pair.1;
+            match key.as_str() {
+                "evm-version" => {
+                    conf_clone.evm_version = Some(
+                        value
+                            .parse()
+                            .map_err(|_| InlineConfigParserError::InvalidValue(key, value))?,
+                    )
+                }
+                "isolate" => conf_clone.isolate = Some(parse_config_bool(key, value)?),
+                "fork-url" => conf_clone.fork_url = Some(value),
+                "fork-block-number" => {
+                    conf_clone.fork_block_number = Some(parse_config_u64(key, value)?)
+                }
+                "gas-limit" => conf_clone.gas_limit = Some(parse_config_u64(key, value)?),
+                _ => Err(InlineConfigParserError::InvalidConfigProperty(key))?,
+            }
+        }
+        Ok(Some(conf_clone))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merge_evm_configs() {
+        let configs = &[
+            "forge-config: default.evm.evm-version = cancun".to_string(),
+            "forge-config: default.evm.isolate = true".to_string(),
+            "forge-config: default.evm.fork-url = https://eth.example.com/?key=abc".to_string(),
+            "forge-config: default.evm.fork-block-number = 19000000".to_string(),
+            "forge-config: default.evm.gas-limit = 30000000".to_string(),
+            "forge-config: default.fuzz.runs = 10".to_string(),
+        ];
+        let merged = InlineEvmConfig::default().try_merge(configs).unwrap().unwrap();
+        assert_eq!(
+            merged,
+            InlineEvmConfig {
+                evm_version: Some(EvmVersion::Cancun),
+                isolate: Some(true),
+                fork_url: Some("https://eth.example.com/?key=abc".to_string()),
+                fork_block_number: Some(19000000),
+                gas_limit: Some(30000000),
+            }
+        );
+        assert!(merged.requires_setup());
+
+        let configs = &["forge-config: default.evm.gas-limit = 1000".to_string()];
+        let merged = InlineEvmConfig::default().try_merge(configs).unwrap().unwrap();
+        assert!(!merged.requires_setup());
+    }
+
+    #[test]
+    fn reject_invalid_evm_configs() {
+        let configs = &["forge-config: default.evm.evm-version = frontier2".to_string()];
+        let err = InlineEvmConfig::default().try_merge(configs).unwrap_err();
+        assert_eq!(
+            err,
+            InlineConfigParserError::InvalidValue("evm-version".into(), "frontier2".into())
+        );
+
+        let configs = &["forge-config: default.evm.fork = mainnet".to_string()];
+        let err = InlineEvmConfig::default().try_merge(configs).unwrap_err();
+        assert_eq!(err, InlineConfigParserError::InvalidConfigProperty("fork".into()));
+    }
+}
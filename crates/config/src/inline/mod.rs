@@ -11,8 +11,16 @@ pub use error::*;
 mod natspec;
 pub use natspec::*;
 
+mod evm;
+pub use evm::InlineEvmConfig;
+
+mod fork;
+pub use fork::ForkSpec;
+
 pub const INLINE_CONFIG_FUZZ_KEY: &str = "fuzz";
 pub const INLINE_CONFIG_INVARIANT_KEY: &str = "invariant";
+pub const INLINE_CONFIG_EVM_KEY: &str = "evm";
+pub const INLINE_CONFIG_FORK_KEY: &str = "fork";
 const INLINE_CONFIG_PREFIX: &str = "forge-config";
 
 static INLINE_CONFIG_PREFIX_SELECTED_PROFILE: Lazy<String> = Lazy::new(|| {
@@ -4,7 +4,7 @@ use foundry_compilers::{
     ProjectCompileOutput,
 };
 use serde_json::Value;
-use solang_parser::pt;
+use solang_parser::pt::{self, CodeLocation};
 use std::{collections::BTreeMap, path::Path};
 
 /// Convenient struct to hold in-line per-test configurations
@@ -12,7 +12,7 @@ use std::{collections::BTreeMap, path::Path};
 pub struct NatSpec {
     /// The parent contract of the natspec
     pub contract: String,
-    /// The function annotated with the natspec
+    /// The function annotated with the natspec, empty if the contract itself is annotated
     pub function: String,
     /// The line the natspec appears, in the form
     /// `row:col:length` i.e. `10:21:122`
@@ -41,6 +41,10 @@ impl NatSpec {
             let mut used_solc_ast = false;
             if let Some(ast) = &artifact.ast {
                 if let Some(node) = solc.contract_root_node(&ast.nodes, &contract) {
+                    if let Some((docs, line)) = solc.get_fn_docs(&node.other) {
+                        let function = String::new();
+                        natspecs.push(NatSpec { contract: contract.clone(), function, line, docs });
+                    }
                     solc.parse(&mut natspecs, &contract, node);
                     used_solc_ast = true;
                 }
@@ -60,6 +64,9 @@ impl NatSpec {
     /// context, for debugging purposes 🐞
     /// i.e. `test/Counter.t.sol:CounterTest:testFuzz_SetNumber`
     pub fn debug_context(&self) -> String {
+        if self.function.is_empty() {
+            return self.contract.clone()
+        }
         format!("{}:{}", self.contract, self.function)
     }
 
@@ -189,12 +196,27 @@ impl SolangParser {
         }
 
         let Ok((pt, comments)) = solang_parser::parse(src, 0) else { return };
+        let mut prev_item_end = 0;
         for item in &pt.0 {
+            let prev_end = std::mem::replace(&mut prev_item_end, item.loc().end());
             let pt::SourceUnitPart::ContractDefinition(c) = item else { continue };
             let Some(id) = c.name.as_ref() else { continue };
             if id.name != contract_name {
                 continue
             };
+            // Parse doc comments in between the previous item and the contract.
+            let docs =
+                solang_parser::doccomment::parse_doccomments(&comments, prev_end, c.loc.start());
+            for doc in docs.into_iter().flat_map(|doc| doc.into_comments()) {
+                if doc.value.contains(INLINE_CONFIG_PREFIX) {
+                    natspecs.push(NatSpec {
+                        contract: contract_id.to_string(),
+                        function: String::new(),
+                        line: "0:0:0".to_string(),
+                        docs: doc.value,
+                    });
+                }
+            }
             let mut prev_end = c.loc.start();
             for part in &c.parts {
                 let pt::ContractPart::FunctionDefinition(f) = part else { continue };
@@ -318,6 +340,44 @@ contract FuzzInlineConf is DSTest {
         );
     }
 
+    #[test]
+    fn parse_solang_contract() {
+        let src = r#"
+pragma solidity >=0.8.0;
+
+/// forge-config: default.fuzz.runs = 1
+
+/// forge-config: default.fork = "optimism@118000000"
+contract ForkInlineConf {
+    /// forge-config: default.fuzz.runs = 2
+    function testFork() public {}
+}
+        "#;
+        let mut natspecs = vec![];
+        let solang = SolangParser::new();
+        let id = || "inline/ForkInlineConf.t.sol:ForkInlineConf".to_string();
+        let default_line = || "0:0:0".to_string();
+        solang.parse(&mut natspecs, src, &id(), "ForkInlineConf");
+        assert_eq!(
+            natspecs,
+            [
+                NatSpec {
+                    contract: id(),
+                    function: String::new(),
+                    line: default_line(),
+                    docs: "forge-config: default.fuzz.runs = 1\nforge-config: default.fork = \"optimism@118000000\"".to_string(),
+                },
+                NatSpec {
+                    contract: id(),
+                    function: "testFork".to_string(),
+                    line: default_line(),
+                    docs: "forge-config: default.fuzz.runs = 2".to_string(),
+                },
+            ]
+        );
+        assert_eq!(natspecs[0].debug_context(), id());
+    }
+
     #[test]
     fn config_lines() {
         let natspec = natspec();
@@ -34,6 +34,9 @@ pub struct InvariantConfig {
     pub gas_report_samples: u32,
     /// Path where invariant failures are recorded and replayed.
     pub failure_persist_dir: Option<PathBuf>,
+    /// Whether to explain shrunk failures by finding the calls and arguments that are needed to
+    /// break the invariant.
+    pub explain_failures: bool,
 }
 
 impl Default for InvariantConfig {
@@ -48,6 +51,7 @@ impl Default for InvariantConfig {
             max_assume_rejects: 65536,
             gas_report_samples: 256,
             failure_persist_dir: None,
+            explain_failures: false,
         }
     }
 }
@@ -65,6 +69,7 @@ impl InvariantConfig {
             max_assume_rejects: 65536,
             gas_report_samples: 256,
             failure_persist_dir: Some(cache_dir),
+            explain_failures: false,
         }
     }
 
@@ -103,6 +108,7 @@ impl InlineConfigParser for InvariantConfig {
                     conf_clone.failure_persist_dir = Some(PathBuf::from(value))
                 }
                 "shrink-run-limit" => conf_clone.shrink_run_limit = parse_config_u32(key, value)?,
+                "explain-failures" => conf_clone.explain_failures = parse_config_bool(key, value)?,
                 _ => Err(InlineConfigParserError::InvalidConfigProperty(key.to_string()))?,
             }
         }
@@ -0,0 +1,74 @@
+//! Support for limiting the number of compiler processes that run in parallel
+
+use std::num::NonZeroUsize;
+
+/// The memory a solc process is assumed to need, in MiB.
+pub const SOLC_JOB_MEMORY_MIB: u64 = 512;
+
+/// The memory a solc process is assumed to need with the IR pipeline, in MiB.
+pub const SOLC_VIA_IR_JOB_MEMORY_MIB: u64 = 2048;
+
+/// Returns the number of compiler processes to run in parallel.
+///
+/// Defaults to the number of available CPUs if `jobs` is not set. The number is then capped so that
+/// the processes fit into `memory_limit` MiB, or into the available memory of the machine if no
+/// limit is set. At least one process is always run.
+pub fn compiler_jobs(jobs: Option<usize>, memory_limit: Option<u64>, via_ir: bool) -> usize {
+    let jobs = jobs
+        .filter(|jobs| *jobs > 0)
+        .unwrap_or_else(|| std::thread::available_parallelism().map_or(1, NonZeroUsize::get));
+    match memory_limit.or_else(available_memory_mib) {
+        Some(memory) => jobs.min(max_jobs_for_memory(memory, via_ir)),
+        None => jobs,
+    }
+}
+
+/// Returns the number of compiler processes that fit into `memory` MiB.
+pub fn max_jobs_for_memory(memory: u64, via_ir: bool) -> usize {
+    let per_job = if via_ir { SOLC_VIA_IR_JOB_MEMORY_MIB } else { SOLC_JOB_MEMORY_MIB };
+    usize::try_from(memory / per_job).unwrap_or(usize::MAX).max(1)
+}
+
+/// Returns the memory available for new processes, in MiB, if it can be determined.
+#[cfg(target_os = "linux")]
+pub fn available_memory_mib() -> Option<u64> {
+    let meminfo = std::fs::read_to_string("/proc/meminfo").ok()?;
+    parse_mem_available(&meminfo)
+}
+
+/// Returns the memory available for new processes, in MiB, if it can be determined.
+#[cfg(not(target_os = "linux"))]
+pub fn available_memory_mib() -> Option<u64> {
+    None
+}
+
+/// Parses the `MemAvailable` entry of `/proc/meminfo`, in MiB.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_mem_available(meminfo: &str) -> Option<u64> {
+    let line = meminfo.lines().find_map(|line| line.strip_prefix("MemAvailable:"))?;
+    let kib = line.trim().trim_end_matches("kB").trim().parse::<u64>().ok()?;
+    Some(kib / 1024)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn caps_jobs_by_memory() {
+        assert_eq!(max_jobs_for_memory(4096, false), 8);
+        assert_eq!(max_jobs_for_memory(4096, true), 2);
+        assert_eq!(max_jobs_for_memory(100, true), 1);
+
+        assert_eq!(compiler_jobs(Some(16), Some(4096), false), 8);
+        assert_eq!(compiler_jobs(Some(4), Some(4096), false), 4);
+        assert_eq!(compiler_jobs(Some(4), Some(0), true), 1);
+    }
+
+    #[test]
+    fn parses_mem_available() {
+        let meminfo = "MemTotal:       32768000 kB\nMemFree:         1024000 kB\nMemAvailable:   16384000 kB\n";
+        assert_eq!(parse_mem_available(meminfo), Some(16000));
+        assert_eq!(parse_mem_available("MemTotal: 1 kB"), None);
+    }
+}
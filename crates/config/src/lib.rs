@@ -41,7 +41,7 @@ use semver::Version;
 use serde::{Deserialize, Serialize, Serializer};
 use std::{
     borrow::Cow,
-    collections::HashMap,
+    collections::{BTreeMap, HashMap},
     fs,
     path::{Path, PathBuf},
     str::FromStr,
@@ -53,7 +53,10 @@ pub mod utils;
 pub use utils::*;
 
 mod endpoints;
-pub use endpoints::{ResolvedRpcEndpoints, RpcEndpoint, RpcEndpoints};
+pub use endpoints::{
+    deserialize_rpc_urls, split_rpc_urls, ResolvedRpcEndpoints, RpcEndpoint, RpcEndpoints,
+    RPC_URL_CUPS_FRAGMENT, RPC_URL_SEPARATOR,
+};
 
 mod etherscan;
 use etherscan::{
@@ -83,11 +86,23 @@ pub use doc::DocConfig;
 pub mod filter;
 pub use filter::SkipBuildFilters;
 
+pub mod deny_warnings;
+pub use deny_warnings::{DenyWarningsPolicy, DenyWarningsRule};
+
+pub mod cheatcode_permissions;
+pub use cheatcode_permissions::{
+    CheatcodeCapability, CheatcodePermission, CheatcodePermissionsPolicy,
+};
+
 mod warning;
 pub use warning::*;
 
 pub mod fix;
 
+pub mod jobs;
+
+pub mod validate;
+
 // reexport so cli types can implement `figment::Provider` to easily merge compiler arguments
 pub use alloy_chains::{Chain, NamedChain};
 pub use figment;
@@ -96,13 +111,19 @@ pub mod providers;
 use providers::{remappings::RemappingsProvider, FallbackProfileProvider, WarningsProvider};
 
 mod fuzz;
-pub use fuzz::{FuzzConfig, FuzzDictionaryConfig};
+pub use fuzz::{FuzzConfig, FuzzDictionaryConfig, FuzzDifferentialConfig};
 
 mod invariant;
 pub use invariant::InvariantConfig;
 
+mod precompiles;
+pub use precompiles::PrecompileConfig;
+
 mod inline;
-pub use inline::{validate_profiles, InlineConfig, InlineConfigError, InlineConfigParser, NatSpec};
+pub use inline::{
+    validate_profiles, ForkSpec, InlineConfig, InlineConfigError, InlineConfigParser,
+    InlineConfigParserError, InlineEvmConfig, NatSpec,
+};
 
 pub mod soldeer;
 use soldeer::SoldeerConfig;
@@ -110,6 +131,9 @@ use soldeer::SoldeerConfig;
 mod vyper;
 use vyper::VyperConfig;
 
+pub mod tenderly;
+use tenderly::TenderlyConfig;
+
 /// Foundry configuration
 ///
 /// # Defaults
@@ -219,6 +243,10 @@ pub struct Config {
     /// verbosity to use
     pub verbosity: u8,
     /// url of the rpc server that should be used for any rpc calls
+    ///
+    /// This can also be a list of urls or aliases, which are tried in order when an endpoint is
+    /// rate limited or unavailable, see [`RPC_URL_SEPARATOR`].
+    #[serde(default, deserialize_with = "deserialize_rpc_urls")]
     pub eth_rpc_url: Option<String>,
     /// JWT secret that should be used for any rpc calls
     pub eth_rpc_jwt: Option<String>,
@@ -234,6 +262,21 @@ pub struct Config {
     pub ignored_file_paths: Vec<PathBuf>,
     /// When true, compiler warnings are treated as errors
     pub deny_warnings: bool,
+    /// Scopes `deny_warnings` to source paths and warning codes.
+    ///
+    /// The last matching rule wins, warnings that aren't matched by any rule fall back to
+    /// `deny_warnings`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub deny_warnings_from: Vec<DenyWarningsRule>,
+    /// The maximum number of compiler processes to run in parallel.
+    ///
+    /// Defaults to the number of available CPUs.
+    pub compiler_jobs: Option<usize>,
+    /// The memory budget of the compiler processes that run in parallel, in MiB.
+    ///
+    /// Fewer processes are run in parallel if they are not expected to fit into the budget.
+    /// Defaults to the available memory of the machine.
+    pub compiler_memory_limit: Option<u64>,
     /// Only run test functions matching the specified regex pattern.
     #[serde(rename = "match_test")]
     pub test_pattern: Option<RegexWrapper>,
@@ -301,6 +344,25 @@ pub struct Config {
     ///
     /// The default is 128MiB.
     pub memory_limit: u64,
+    /// The maximum depth of nested calls made by a test, if any.
+    ///
+    /// Exceeding any of the resource limits fails the test.
+    pub max_call_depth: Option<usize>,
+    /// The maximum number of events emitted by a test, if any.
+    pub max_events: Option<usize>,
+    /// The maximum number of storage writes performed by a test, if any.
+    pub max_state_writes: Option<usize>,
+    /// The maximum size in bytes of the data returned by any call made by a test, if any.
+    pub max_return_data_size: Option<usize>,
+    /// The addresses of the pre-existing contracts whose storage tests may write to, if any.
+    ///
+    /// If set, a test writing to the storage of any other contract that it didn't create fails.
+    /// Useful to catch accidental writes to production contracts when forking.
+    pub write_allowlist: Option<Vec<Address>>,
+    /// The addresses that tests may not call or read from.
+    ///
+    /// A test calling any of these, or reading their balance or code, fails.
+    pub read_denylist: Vec<Address>,
     /// Additional output selection for all contracts, such as "ir", "devdoc", "storageLayout",
     /// etc.
     ///
@@ -348,9 +410,16 @@ pub struct Config {
     /// Disables rate limiting entirely. This overrides any settings made in
     /// `compute_units_per_second`
     pub no_rpc_rate_limit: bool,
+    /// The maximum total weight, in compute units, of the RPC requests that forks may send during
+    /// a test run. Requests that would exceed it fail.
+    pub rpc_budget: Option<u64>,
     /// Multiple rpc endpoints and their aliases
     #[serde(default, skip_serializing_if = "RpcEndpoints::is_empty")]
     pub rpc_endpoints: RpcEndpoints,
+    /// Named forks test contracts can run on with `forge-config: default.fork = <name>`, in the
+    /// form `<endpoint>[@<block>]`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub forks: BTreeMap<String, String>,
     /// Whether to store the referenced sources in the metadata as literal data.
     pub use_literal_content: bool,
     /// Whether to include the metadata hash.
@@ -376,6 +445,12 @@ pub struct Config {
     pub build_info: bool,
     /// The path to the `build-info` directory that contains the build info json files.
     pub build_info_path: Option<PathBuf>,
+    /// The base URL of a self-hosted selector registry implementing the OpenChain signature
+    /// database API, used by `forge selectors` instead of OpenChain.
+    pub selectors_registry: Option<String>,
+    /// Whether `forge build` uploads the selectors of the project to the selector registry, and
+    /// downloads the signatures of a self-hosted registry to the local signature database.
+    pub sync_selectors: bool,
     /// Configuration for `forge fmt`
     pub fmt: FormatterConfig,
     /// Configuration for `forge doc`
@@ -384,6 +459,13 @@ pub struct Config {
     ///
     /// This includes what operations can be executed (read, write)
     pub fs_permissions: FsPermissions,
+    /// Scopes dangerous cheatcodes to test paths and contracts.
+    ///
+    /// If any rule is configured, the `ffi`, `fs-write`, `http` and `precompiles` capabilities
+    /// are only granted to the test contracts matched by a rule allowing them, see
+    /// [CheatcodePermission].
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub cheatcode_permissions: Vec<CheatcodePermission>,
 
     /// Temporary config to enable [SpecId::PRAGUE]
     ///
@@ -401,6 +483,21 @@ pub struct Config {
     /// Address labels
     pub labels: HashMap<Address, String>,
 
+    /// Contracts whose implementation is substituted in tests, mapping the name or identifier of
+    /// the original contract to the one deployed in its place, e.g.
+    /// `ChainlinkOracle = "MockOracle"`.
+    ///
+    /// Wherever the original is created, the creation code of the substitute is used instead,
+    /// with the same constructor arguments.
+    pub test_overrides: BTreeMap<String, String>,
+
+    /// Precompiles registered in addition to those of the EVM spec, keyed by name, e.g.
+    /// `arb_sys = { address = "0x0000000000000000000000000000000000000064", shim = "ArbSys" }`.
+    ///
+    /// Each precompile is either a Solidity shim, whose deployed code is placed at the address,
+    /// or the Rust implementation registered under its name.
+    pub precompiles: BTreeMap<String, PrecompileConfig>,
+
     /// Whether to enable safety checks for `vm.getCode` and `vm.getDeployedCode` invocations.
     /// If disabled, it is possible to access artifacts which were not recompiled or cached.
     pub unchecked_cheatcode_artifacts: bool,
@@ -411,6 +508,9 @@ pub struct Config {
     /// Configuration for Vyper compiler
     pub vyper: VyperConfig,
 
+    /// The Tenderly project to upload simulations to
+    pub tenderly: TenderlyConfig,
+
     /// Soldeer dependencies
     pub dependencies: Option<SoldeerConfig>,
 
@@ -429,6 +529,29 @@ pub struct Config {
     /// Whether `failed()` should be invoked to check if the test have failed.
     pub legacy_assertions: bool,
 
+    /// Whether `BLOCKHASH` returns the hashes of blocks older than the last 256 ones.
+    ///
+    /// In forking mode the hashes of the blocks of the forked chain are fetched from the RPC, and
+    /// otherwise the hashes of the last 8191 blocks are served, like the history contract of
+    /// EIP-2935.
+    pub historical_block_hashes: bool,
+
+    /// Whether to run tests in deterministic mode.
+    ///
+    /// Host-dependent cheatcodes such as `ffi` and fork creation are forbidden, `unixTime` returns
+    /// the block timestamp, random values are derived from the fuzz seed, and every test is
+    /// executed twice to verify that its results are reproducible.
+    pub deterministic: bool,
+
+    /// Whether to persist the logs, traces, gas data and counterexamples of every test to
+    /// `<out>/test-artifacts`, so they can be inspected with `forge test-report` without
+    /// re-running the tests.
+    pub test_artifacts: bool,
+
+    /// The number of test runs kept in `<out>/test-artifacts`. The artifacts of older runs are
+    /// removed.
+    pub test_artifacts_retention: usize,
+
     /// Warnings gathered when loading the Config. See [`WarningsProvider`] for more information
     #[serde(rename = "__warnings", default, skip_serializing)]
     pub warnings: Vec<Warning>,
@@ -467,6 +590,7 @@ impl Config {
     /// Standalone sections in the config which get integrated into the selected profile
     pub const STANDALONE_SECTIONS: &'static [&'static str] = &[
         "rpc_endpoints",
+        "forks",
         "etherscan",
         "fmt",
         "doc",
@@ -475,6 +599,7 @@ impl Config {
         "labels",
         "dependencies",
         "vyper",
+        "tenderly",
     ];
 
     /// File name of config toml file
@@ -805,12 +930,15 @@ impl Config {
             .settings(self.compiler_settings()?)
             .ignore_error_codes(self.ignored_error_codes.iter().copied().map(Into::into))
             .ignore_paths(self.ignored_file_paths.clone())
-            .set_compiler_severity_filter(if self.deny_warnings {
-                Severity::Warning
-            } else {
-                Severity::Error
-            })
+            .set_compiler_severity_filter(
+                if self.deny_warnings && self.deny_warnings_from.is_empty() {
+                    Severity::Warning
+                } else {
+                    Severity::Error
+                },
+            )
             .set_offline(self.offline)
+            .solc_jobs(self.compiler_jobs())
             .set_cached(cached)
             .set_build_info(!no_artifacts && self.build_info)
             .set_no_artifacts(no_artifacts);
@@ -829,6 +957,44 @@ impl Config {
         Ok(project)
     }
 
+    /// Returns the number of compiler processes to run in parallel.
+    ///
+    /// See [`jobs::compiler_jobs`].
+    pub fn compiler_jobs(&self) -> usize {
+        jobs::compiler_jobs(self.compiler_jobs, self.compiler_memory_limit, self.via_ir)
+    }
+
+    /// Returns the [DenyWarningsPolicy] if warnings-as-errors are scoped via `deny_warnings_from`.
+    ///
+    /// Returns `None` if no rules are configured, in which case `deny_warnings` is enforced by the
+    /// compiler severity filter of the [Project].
+    pub fn deny_warnings_policy(&self) -> Option<DenyWarningsPolicy> {
+        if self.deny_warnings_from.is_empty() {
+            return None;
+        }
+
+        let policy = DenyWarningsPolicy::new(
+            self.deny_warnings,
+            self.deny_warnings_from.clone(),
+            &self.root.0,
+        )
+        .ignored_error_codes(self.ignored_error_codes.iter().copied().map(Into::into))
+        .ignored_file_paths(self.ignored_file_paths.clone());
+        Some(policy)
+    }
+
+    /// Returns the [CheatcodePermissionsPolicy] if dangerous cheatcodes are scoped via
+    /// `cheatcode_permissions`.
+    ///
+    /// Returns `None` if no rules are configured, in which case all test contracts are granted all
+    /// capabilities.
+    pub fn cheatcode_permissions_policy(&self) -> Option<CheatcodePermissionsPolicy> {
+        if self.cheatcode_permissions.is_empty() {
+            return None;
+        }
+        Some(CheatcodePermissionsPolicy::new(self.cheatcode_permissions.clone(), &self.root.0))
+    }
+
     /// Cleans the project.
     pub fn cleanup<C: Compiler>(&self, project: &Project<C>) -> Result<(), SolcError> {
         project.cleanup()?;
@@ -1040,10 +1206,37 @@ impl Config {
         if let Some(alias) = self.get_rpc_url_with_alias(maybe_alias) {
             Some(alias)
         } else {
-            Some(Ok(Cow::Borrowed(self.eth_rpc_url.as_deref()?)))
+            let urls = self.eth_rpc_url.as_deref()?;
+            if urls.contains(RPC_URL_SEPARATOR) {
+                Some(self.resolve_rpc_url_list(urls).map(Cow::Owned))
+            } else {
+                Some(Ok(Cow::Borrowed(urls)))
+            }
         }
     }
 
+    /// Resolves the aliases of an RPC URL list, see [`RPC_URL_SEPARATOR`].
+    ///
+    /// The `compute_units_per_second` of an aliased endpoint is carried over to the list as the
+    /// [`RPC_URL_CUPS_FRAGMENT`] of its url.
+    fn resolve_rpc_url_list(&self, urls: &str) -> Result<String, UnresolvedEnvVarError> {
+        let mut resolved = Vec::new();
+        for url in urls.split(RPC_URL_SEPARATOR).map(str::trim).filter(|url| !url.is_empty()) {
+            let Some(endpoint) = self.rpc_endpoints.get(url) else {
+                resolved.push(url.to_string());
+                continue
+            };
+            let endpoint_url = endpoint.clone().resolve()?;
+            match endpoint.compute_units_per_second {
+                Some(cups) if !endpoint_url.contains('#') => {
+                    resolved.push(format!("{endpoint_url}#{RPC_URL_CUPS_FRAGMENT}{cups}"))
+                }
+                _ => resolved.push(endpoint_url),
+            }
+        }
+        Ok(resolved.join(&RPC_URL_SEPARATOR.to_string()))
+    }
+
     /// Resolves the given alias to a matching rpc url
     ///
     /// Returns:
@@ -1913,6 +2106,26 @@ impl From<regex::Regex> for RegexWrapper {
     }
 }
 
+/// Ser/de `globset::Glob` explicitly
+pub(crate) mod from_glob {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S>(value: &globset::Glob, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(value.glob())
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<globset::Glob, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        globset::Glob::new(&s).map_err(serde::de::Error::custom)
+    }
+}
+
 /// Ser/de `globset::Glob` explicitly to handle `Option<Glob>` properly
 pub(crate) mod from_opt_glob {
     use serde::{Deserialize, Deserializer, Serializer};
@@ -2035,6 +2248,7 @@ impl Default for Config {
         Self {
             profile: Self::DEFAULT_PROFILE,
             fs_permissions: FsPermissions::new([PathPermission::read("out")]),
+            cheatcode_permissions: vec![],
             prague: false,
             #[cfg(not(feature = "isolate-by-default"))]
             isolate: false,
@@ -2057,6 +2271,7 @@ impl Default for Config {
             gas_reports_ignore: vec![],
             solc: None,
             vyper: Default::default(),
+            tenderly: Default::default(),
             auto_detect_solc: true,
             offline: false,
             optimizer: true,
@@ -2095,6 +2310,12 @@ impl Default for Config {
             block_gas_limit: None,
             disable_block_gas_limit: false,
             memory_limit: 1 << 27, // 2**27 = 128MiB = 134_217_728 bytes
+            max_call_depth: None,
+            max_events: None,
+            max_state_writes: None,
+            max_return_data_size: None,
+            write_allowlist: None,
+            read_denylist: vec![],
             eth_rpc_url: None,
             eth_rpc_jwt: None,
             etherscan_api_key: None,
@@ -2110,13 +2331,18 @@ impl Default for Config {
             ],
             ignored_file_paths: vec![],
             deny_warnings: false,
+            deny_warnings_from: vec![],
+            compiler_jobs: None,
+            compiler_memory_limit: None,
             via_ir: false,
             ast: false,
             rpc_storage_caching: Default::default(),
             rpc_endpoints: Default::default(),
+            forks: Default::default(),
             etherscan: Default::default(),
             no_storage_caching: false,
             no_rpc_rate_limit: false,
+            rpc_budget: None,
             use_literal_content: false,
             bytecode_hash: BytecodeHash::Ipfs,
             cbor_metadata: true,
@@ -2124,15 +2350,23 @@ impl Default for Config {
             sparse_mode: false,
             build_info: false,
             build_info_path: None,
+            selectors_registry: None,
+            sync_selectors: false,
             fmt: Default::default(),
             doc: Default::default(),
             labels: Default::default(),
+            test_overrides: Default::default(),
+            precompiles: Default::default(),
             unchecked_cheatcode_artifacts: false,
             create2_library_salt: Self::DEFAULT_CREATE2_LIBRARY_SALT,
             skip: vec![],
             dependencies: Default::default(),
             assertions_revert: true,
             legacy_assertions: false,
+            historical_block_hashes: false,
+            deterministic: false,
+            test_artifacts: false,
+            test_artifacts_retention: 5,
             warnings: vec![],
             _non_exhaustive: (),
         }
@@ -3388,6 +3622,41 @@ mod tests {
         })
     }
 
+    #[test]
+    fn test_resolve_rpc_url_list() {
+        figment::Jail::expect_with(|jail| {
+            jail.create_file(
+                "foundry.toml",
+                r#"
+                [profile.default]
+                eth_rpc_url = ["mainnet", "https://backup.example.com/"]
+                [rpc_endpoints]
+                mainnet = { endpoint = "https://eth-mainnet.alchemyapi.io/v2/123455", compute_units_per_second = 330 }
+            "#,
+            )?;
+
+            let config = Config::load();
+            assert_eq!(config.eth_rpc_url.as_deref(), Some("mainnet,https://backup.example.com/"));
+            assert_eq!(
+                "https://eth-mainnet.alchemyapi.io/v2/123455#cups=330,https://backup.example.com/",
+                config.get_rpc_url().unwrap().unwrap()
+            );
+
+            let urls: Vec<_> = split_rpc_urls(&config.get_rpc_url().unwrap().unwrap())
+                .map(|(url, cups)| (url.to_string(), cups))
+                .collect();
+            assert_eq!(
+                urls,
+                vec![
+                    ("https://eth-mainnet.alchemyapi.io/v2/123455".to_string(), Some(330)),
+                    ("https://backup.example.com/".to_string(), None),
+                ]
+            );
+
+            Ok(())
+        })
+    }
+
     #[test]
     fn test_resolve_rpc_aliases() {
         figment::Jail::expect_with(|jail| {
@@ -4880,6 +5149,98 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_parse_compiler_jobs() {
+        figment::Jail::expect_with(|jail| {
+            jail.create_file(
+                "foundry.toml",
+                r#"
+                [default]
+                compiler_jobs = 4
+                compiler_memory_limit = 1024
+            "#,
+            )?;
+
+            let config = Config::load();
+            assert_eq!(config.compiler_jobs, Some(4));
+            assert_eq!(config.compiler_memory_limit, Some(1024));
+            assert_eq!(config.compiler_jobs(), 2);
+
+            jail.set_env("FOUNDRY_COMPILER_JOBS", "1");
+            let config = Config::load();
+            assert_eq!(config.compiler_jobs(), 1);
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn test_parse_deny_warnings_from() {
+        figment::Jail::expect_with(|jail| {
+            jail.create_file(
+                "foundry.toml",
+                r#"
+                [default]
+                deny_warnings_from = [
+                    { path = "src/**", codes = ["shadowing"] },
+                    { path = "test/**", allow = true },
+                ]
+            "#,
+            )?;
+
+            let config = Config::load();
+            assert_eq!(
+                config.deny_warnings_from,
+                vec![
+                    DenyWarningsRule::deny(
+                        "src/**".parse().unwrap(),
+                        [SolidityErrorCode::ShadowsExistingDeclaration]
+                    ),
+                    DenyWarningsRule::allow("test/**".parse().unwrap(), []),
+                ]
+            );
+            assert!(config.deny_warnings_policy().is_some());
+
+            Ok(())
+        });
+    }
+
+    #[test]
+    fn test_parse_cheatcode_permissions() {
+        figment::Jail::expect_with(|jail| {
+            jail.create_file(
+                "foundry.toml",
+                r#"
+                [default]
+                cheatcode_permissions = [
+                    { path = "test/fork/**", allow = ["http"] },
+                    { path = "test/**", contract = "*Ffi*", allow = ["ffi", "fs-write"] },
+                ]
+            "#,
+            )?;
+
+            let config = Config::load();
+            assert_eq!(
+                config.cheatcode_permissions,
+                vec![
+                    CheatcodePermission::new(
+                        "test/fork/**".parse().unwrap(),
+                        [CheatcodeCapability::Http]
+                    ),
+                    CheatcodePermission::new(
+                        "test/**".parse().unwrap(),
+                        [CheatcodeCapability::Ffi, CheatcodeCapability::FsWrite]
+                    )
+                    .contract("*Ffi*".parse().unwrap()),
+                ]
+            );
+            assert!(config.cheatcode_permissions_policy().is_some());
+            assert!(Config::default().cheatcode_permissions_policy().is_none());
+
+            Ok(())
+        });
+    }
+
     #[test]
     fn test_parse_optimizer_settings() {
         figment::Jail::expect_with(|jail| {
@@ -4898,6 +5259,40 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_parse_precompiles() {
+        figment::Jail::expect_with(|jail| {
+            jail.create_file(
+                "foundry.toml",
+                r#"
+                [profile.default.precompiles]
+                arb_sys = { address = "0x0000000000000000000000000000000000000064", shim = "ArbSysShim" }
+                arb_gas_info = { address = "0x000000000000000000000000000000000000006c" }
+            "#,
+            )?;
+
+            let config = Config::load();
+            assert_eq!(
+                config.precompiles,
+                BTreeMap::from([
+                    (
+                        "arb_gas_info".to_string(),
+                        PrecompileConfig { address: Address::with_last_byte(0x6c), shim: None }
+                    ),
+                    (
+                        "arb_sys".to_string(),
+                        PrecompileConfig {
+                            address: Address::with_last_byte(0x64),
+                            shim: Some("ArbSysShim".to_string())
+                        }
+                    ),
+                ])
+            );
+
+            Ok(())
+        });
+    }
+
     #[test]
     fn test_parse_labels() {
         figment::Jail::expect_with(|jail| {
@@ -4953,4 +5348,28 @@ mod tests {
             Ok(())
         });
     }
+
+    #[test]
+    fn test_parse_tenderly() {
+        figment::Jail::expect_with(|jail| {
+            jail.create_file(
+                "foundry.toml",
+                r#"
+                [tenderly]
+                account = "acme"
+                project = "token"
+                access_key = "${TENDERLY_ACCESS_KEY}"
+            "#,
+            )?;
+            jail.set_env("TENDERLY_ACCESS_KEY", "secret");
+            jail.set_env("FOUNDRY_TENDERLY_PROJECT", "vault");
+
+            let config = Config::load();
+            assert_eq!(config.tenderly.account.as_deref(), Some("acme"));
+            assert_eq!(config.tenderly.project.as_deref(), Some("vault"));
+            assert_eq!(config.tenderly.resolved_access_key().unwrap().as_deref(), Some("secret"));
+
+            Ok(())
+        });
+    }
 }
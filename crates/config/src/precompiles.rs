@@ -0,0 +1,18 @@
+//! Configuration of the precompiles registered in addition to those of the EVM spec.
+
+use alloy_primitives::Address;
+use serde::{Deserialize, Serialize};
+
+/// A precompile registered in addition to those of the EVM spec, e.g. a precompile of an L2.
+///
+/// The precompile is either a Solidity shim, or, if no shim is set, the Rust implementation
+/// registered under the name of the entry.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PrecompileConfig {
+    /// The address of the precompile.
+    pub address: Address,
+    /// The name or identifier of the contract whose deployed code is placed at the address, e.g.
+    /// `ArbSysShim` or `test/shims/ArbSys.sol:ArbSysShim`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shim: Option<String>,
+}
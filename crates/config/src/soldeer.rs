@@ -19,6 +19,13 @@ pub struct SoldeerDependency {
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct SoldeerConfig(BTreeMap<String, SoldeerDependency>);
 
+impl SoldeerConfig {
+    /// Returns the dependencies, by name
+    pub fn dependencies(&self) -> &BTreeMap<String, SoldeerDependency> {
+        &self.0
+    }
+}
+
 impl AsRef<Self> for SoldeerConfig {
     fn as_ref(&self) -> &Self {
         self
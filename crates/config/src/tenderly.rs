@@ -0,0 +1,25 @@
+//! Configuration for uploading simulations to Tenderly.
+
+use crate::resolve::{interpolate, UnresolvedEnvVarError};
+use serde::{Deserialize, Serialize};
+
+/// The Tenderly project that `cast tenderly` uploads simulations to, and `cast explorer` links to.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TenderlyConfig {
+    /// The slug of the account that owns the project.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub account: Option<String>,
+    /// The slug of the project.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project: Option<String>,
+    /// The access key of the Tenderly API, or an env var that holds it in the form `${ENV_VAR}`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub access_key: Option<String>,
+}
+
+impl TenderlyConfig {
+    /// Returns the access key, with env vars resolved.
+    pub fn resolved_access_key(&self) -> Result<Option<String>, UnresolvedEnvVarError> {
+        self.access_key.as_deref().map(interpolate).transpose()
+    }
+}
@@ -0,0 +1,369 @@
+//! JSON schema export and validation of `foundry.toml` files.
+//!
+//! Both are derived from the serialized [`Config::default()`], so that they can't drift from the
+//! keys the config actually supports.
+
+use crate::{Config, DEPRECATIONS};
+use figment::{providers::Serialized, Figment};
+use inflector::Inflector;
+use serde_json::{json, Map, Value};
+use std::{fmt, path::Path};
+
+/// Keys replaced by other keys, which are still accepted for backwards compatibility.
+const RENAMED_KEYS: &[(&str, &str)] = &[("solc_version", "solc")];
+
+/// Other names accepted for keys.
+const ALIASES: &[(&str, &str)] = &[("chain", "chain_id")];
+
+/// Keys that are not serialized when empty, and so are missing from the serialized defaults.
+const SKIPPED_WHEN_EMPTY: &[&str] =
+    &["etherscan", "rpc_endpoints", "deny_warnings_from", "cheatcode_permissions"];
+
+/// Sections whose keys are chosen by the user, e.g. the aliases of `rpc_endpoints`.
+const FREE_FORM_SECTIONS: &[&str] = &["rpc_endpoints", "etherscan", "labels", "dependencies"];
+
+/// Numeric keys that also accept strings, e.g. `"max"`.
+const NUMBER_OR_STRING_KEYS: &[&str] = &[
+    "gas_limit",
+    "block_gas_limit",
+    "dictionary_weight",
+    "max_fuzz_dictionary_addresses",
+    "max_fuzz_dictionary_values",
+];
+
+/// An issue found in a `foundry.toml` file by [`validate_toml`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ConfigIssue {
+    /// A key that is not a config option, and is ignored.
+    UnknownKey {
+        /// The path of the key, e.g. `profile.default.optimizer_run`.
+        key: String,
+        /// The closest known key, if any is similar enough.
+        suggestion: Option<String>,
+    },
+    /// A value that can't be converted to the type of its option.
+    TypeMismatch {
+        /// The path of the key.
+        key: String,
+        /// Why the value was rejected.
+        error: String,
+    },
+    /// A deprecated key.
+    DeprecatedKey {
+        /// The path of the key.
+        key: String,
+        /// What to use instead.
+        replacement: String,
+    },
+}
+
+impl fmt::Display for ConfigIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownKey { key, suggestion: Some(suggestion) } => {
+                write!(f, "unknown key `{key}`, did you mean `{suggestion}`?")
+            }
+            Self::UnknownKey { key, suggestion: None } => write!(f, "unknown key `{key}`"),
+            Self::TypeMismatch { key, error } => write!(f, "invalid value for `{key}`: {error}"),
+            Self::DeprecatedKey { key, replacement } => {
+                write!(f, "`{key}` is deprecated, use `{replacement}` instead")
+            }
+        }
+    }
+}
+
+/// Returns a JSON schema of `foundry.toml`, for editors and CI checks.
+pub fn json_schema() -> Value {
+    let mut properties = Map::new();
+    for (key, value) in defaults() {
+        let schema = schema_of(&key, &value, true);
+        properties.insert(key, schema);
+    }
+    for key in SKIPPED_WHEN_EMPTY {
+        properties.entry(*key).or_insert_with(|| json!({}));
+    }
+    for (alias, key) in ALIASES {
+        let description = format!("Alias of `{key}`.");
+        properties.insert(alias.to_string(), json!({ "description": description }));
+    }
+    for (old, new) in DEPRECATIONS.iter().chain(RENAMED_KEYS) {
+        let description = format!("Deprecated, use `{new}` instead.");
+        properties
+            .insert(old.to_string(), json!({ "deprecated": true, "description": description }));
+    }
+
+    let mut sections = Map::new();
+    sections.insert(
+        Config::PROFILE_SECTION.to_string(),
+        json!({
+            "description": "The profiles of the config, e.g. `[profile.default]`.",
+            "type": "object",
+            "additionalProperties": { "$ref": "#/definitions/profile" },
+        }),
+    );
+    for section in Config::STANDALONE_SECTIONS {
+        let reference = format!("#/definitions/profile/properties/{section}");
+        sections.insert(section.to_string(), json!({ "$ref": reference }));
+    }
+
+    json!({
+        "$schema": "http://json-schema.org/draft-07/schema#",
+        "title": Config::FILE_NAME,
+        "type": "object",
+        "properties": sections,
+        "additionalProperties": false,
+        "definitions": {
+            "profile": {
+                "type": "object",
+                "properties": properties,
+                "additionalProperties": false,
+            },
+        },
+    })
+}
+
+/// Validates the `foundry.toml` file at the given path.
+pub fn validate_file(path: impl AsRef<Path>) -> eyre::Result<Vec<ConfigIssue>> {
+    let content = std::fs::read_to_string(path)?;
+    Ok(validate_toml(&content)?)
+}
+
+/// Validates the content of a `foundry.toml` file, returning the unknown keys, values of the
+/// wrong type and deprecated keys it contains.
+///
+/// Unlike loading the config, which stops at the first invalid value and ignores unknown keys,
+/// every key is checked.
+pub fn validate_toml(content: &str) -> Result<Vec<ConfigIssue>, toml::de::Error> {
+    let table: toml::Table = toml::from_str(content)?;
+    let defaults = defaults();
+    let mut issues = Vec::new();
+    for (key, value) in &table {
+        if key == Config::PROFILE_SECTION {
+            let Some(profiles) = value.as_table() else {
+                issues.push(ConfigIssue::TypeMismatch {
+                    key: key.clone(),
+                    error: "expected a table of profiles".to_string(),
+                });
+                continue
+            };
+            for (name, profile) in profiles {
+                let path = format!("{key}.{name}");
+                match profile.as_table() {
+                    Some(profile) => {
+                        for (key, value) in profile {
+                            validate_entry(&path, key, value, &defaults, &mut issues);
+                        }
+                    }
+                    None => issues.push(ConfigIssue::TypeMismatch {
+                        key: path,
+                        error: "expected a table".to_string(),
+                    }),
+                }
+            }
+        } else if Config::STANDALONE_SECTIONS.contains(&key.as_str()) {
+            validate_entry("", key, value, &defaults, &mut issues);
+        } else {
+            let sections = std::iter::once(Config::PROFILE_SECTION)
+                .chain(Config::STANDALONE_SECTIONS.iter().copied());
+            let suggestion = did_you_mean(key, sections);
+            if value.is_table() && suggestion.is_none() {
+                // The deprecated `[default]` notation for profiles.
+                issues.push(ConfigIssue::DeprecatedKey {
+                    key: key.clone(),
+                    replacement: format!("{}.{key}", Config::PROFILE_SECTION),
+                });
+            } else {
+                issues.push(ConfigIssue::UnknownKey { key: key.clone(), suggestion });
+            }
+        }
+    }
+    Ok(issues)
+}
+
+/// Validates a key of a profile, or a standalone section if `prefix` is empty.
+fn validate_entry(
+    prefix: &str,
+    key: &str,
+    value: &toml::Value,
+    defaults: &Map<String, Value>,
+    issues: &mut Vec<ConfigIssue>,
+) {
+    let path = if prefix.is_empty() { key.to_string() } else { format!("{prefix}.{key}") };
+    let is_section = Config::STANDALONE_SECTIONS.contains(&key);
+    // Keys outside of standalone sections are converted to snake case when loading the config.
+    let name = if is_section { key.to_string() } else { key.to_snake_case() };
+
+    if let Some((_, new)) = DEPRECATIONS.iter().chain(RENAMED_KEYS).find(|(old, _)| *old == name) {
+        issues.push(ConfigIssue::DeprecatedKey { key: path, replacement: new.to_string() });
+        return
+    }
+    let name =
+        ALIASES.iter().find(|(alias, _)| *alias == name).map_or(name, |(_, key)| key.to_string());
+    let default = match defaults.get(&name) {
+        Some(default) => default,
+        None if SKIPPED_WHEN_EMPTY.contains(&name.as_str()) => &Value::Null,
+        None => {
+            issues.push(ConfigIssue::UnknownKey {
+                key: path,
+                suggestion: did_you_mean(&name, defaults.keys()),
+            });
+            return
+        }
+    };
+
+    if let (Some(table), Value::Object(fields)) = (value.as_table(), default) {
+        if is_section && !FREE_FORM_SECTIONS.contains(&name.as_str()) {
+            for field in table.keys().filter(|field| !fields.contains_key(*field)) {
+                issues.push(ConfigIssue::UnknownKey {
+                    key: format!("{path}.{field}"),
+                    suggestion: did_you_mean(field, fields.keys()),
+                });
+            }
+        }
+    }
+
+    // Extract the defaults with only this value overridden, so that every invalid value is
+    // reported and not only the first one.
+    let figment = Figment::from(Config::default()).merge(Serialized::default(&name, value));
+    if let Err(err) = figment.extract::<Config>() {
+        issues.push(ConfigIssue::TypeMismatch { key: path, error: err.kind.to_string() });
+    }
+}
+
+/// Returns the serialized default config.
+fn defaults() -> Map<String, Value> {
+    match serde_json::to_value(Config::default()) {
+        Ok(Value::Object(defaults)) => defaults,
+        _ => Map::new(),
+    }
+}
+
+/// Returns the schema of a key, inferred from its default value.
+///
+/// The keys of objects are only restricted for the sections of the config, not for user-defined
+/// tables or the items of arrays.
+fn schema_of(key: &str, default: &Value, strict: bool) -> Value {
+    match default {
+        // Optional values don't tell their type.
+        Value::Null => json!({}),
+        Value::Bool(_) => json!({ "type": "boolean", "default": default }),
+        Value::Number(_) if NUMBER_OR_STRING_KEYS.contains(&key) => {
+            json!({ "type": ["integer", "string"], "default": default })
+        }
+        Value::Number(number) if number.is_f64() => json!({ "type": "number", "default": default }),
+        Value::Number(_) => json!({ "type": "integer", "default": default }),
+        Value::String(_) => json!({ "type": "string", "default": default }),
+        Value::Array(items) => match items.first() {
+            Some(item) => json!({ "type": "array", "items": schema_of("", item, false) }),
+            None => json!({ "type": "array" }),
+        },
+        Value::Object(fields) => {
+            if !strict || fields.is_empty() || FREE_FORM_SECTIONS.contains(&key) {
+                return json!({ "type": "object" })
+            }
+            let properties = fields
+                .iter()
+                .map(|(key, value)| (key.clone(), schema_of(key, value, false)))
+                .collect::<Map<_, _>>();
+            json!({ "type": "object", "properties": properties, "additionalProperties": false })
+        }
+    }
+}
+
+/// Returns the candidate most similar to `key`, if any is similar enough to be a typo.
+fn did_you_mean<T: AsRef<str>>(
+    key: &str,
+    candidates: impl IntoIterator<Item = T>,
+) -> Option<String> {
+    candidates
+        .into_iter()
+        .map(|candidate| (strsim::jaro_winkler(key, candidate.as_ref()), candidate))
+        .filter(|(similarity, _)| *similarity > 0.8)
+        .max_by(|(a, _), (b, _)| a.total_cmp(b))
+        .map(|(_, candidate)| candidate.as_ref().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_every_issue() {
+        let issues = validate_toml(
+            r#"
+            [profile.default]
+            optimizer_run = 200
+            optimizer-runs = "many"
+            via_ir = "yes"
+            solc_version = "0.8.26"
+            chain = 1
+
+            [fuzz]
+            runz = 10
+
+            [rpc_endpoints]
+            mainnet = "https://eth.llamarpc.com"
+
+            [ci]
+            verbosity = 4
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(issues.len(), 6, "{issues:#?}");
+        assert_eq!(
+            issues[0],
+            ConfigIssue::UnknownKey {
+                key: "profile.default.optimizer_run".to_string(),
+                suggestion: Some("optimizer_runs".to_string())
+            }
+        );
+        assert!(matches!(
+            &issues[1],
+            ConfigIssue::TypeMismatch { key, .. } if key == "profile.default.optimizer-runs"
+        ));
+        assert!(matches!(
+            &issues[2],
+            ConfigIssue::TypeMismatch { key, .. } if key == "profile.default.via_ir"
+        ));
+        assert_eq!(
+            issues[3],
+            ConfigIssue::DeprecatedKey {
+                key: "profile.default.solc_version".to_string(),
+                replacement: "solc".to_string()
+            }
+        );
+        assert_eq!(
+            issues[4],
+            ConfigIssue::UnknownKey {
+                key: "fuzz.runz".to_string(),
+                suggestion: Some("runs".to_string())
+            }
+        );
+        assert_eq!(
+            issues[5],
+            ConfigIssue::DeprecatedKey {
+                key: "ci".to_string(),
+                replacement: "profile.ci".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn accepts_default_config() {
+        let config = Config::default().to_string_pretty().unwrap();
+        assert_eq!(validate_toml(&config).unwrap(), vec![]);
+    }
+
+    #[test]
+    fn schema_covers_config() {
+        let schema = json_schema();
+        let profile = &schema["definitions"]["profile"]["properties"];
+        assert_eq!(profile["optimizer_runs"]["type"], "integer");
+        assert_eq!(profile["via_ir"]["type"], "boolean");
+        assert_eq!(profile["gas_limit"]["type"], json!(["integer", "string"]));
+        assert_eq!(profile["fuzz"]["properties"]["runs"]["type"], "integer");
+        assert_eq!(profile["solc_version"]["deprecated"], true);
+        assert_eq!(schema["properties"]["fuzz"]["$ref"], "#/definitions/profile/properties/fuzz");
+    }
+}
@@ -14,4 +14,4 @@ mod tui;
 pub use tui::{Debugger, DebuggerBuilder, ExitReason};
 
 mod node;
-pub use node::DebugNode;
+pub use node::{flatten_call_trace, DebugNode};
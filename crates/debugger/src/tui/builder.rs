@@ -18,6 +18,8 @@ pub struct DebuggerBuilder {
     sources: ContractSources,
     /// Map of the debugger breakpoints.
     breakpoints: Breakpoints,
+    /// The state of the accounts at the breakpoint, when debugging a paused execution.
+    live_state: Option<Vec<String>>,
 }
 
 impl DebuggerBuilder {
@@ -83,10 +85,23 @@ impl DebuggerBuilder {
         self
     }
 
+    /// Debugs an execution that is paused at a breakpoint, with the given description of the
+    /// state of the accounts.
+    ///
+    /// The debugger starts at the last recorded step and can be exited by resuming or stepping the
+    /// execution.
+    #[inline]
+    pub fn live_state(mut self, state: Vec<String>) -> Self {
+        self.live_state = Some(state);
+        self
+    }
+
     /// Builds the debugger.
     #[inline]
     pub fn build(self) -> Debugger {
-        let Self { debug_arena, identified_contracts, sources, breakpoints } = self;
-        Debugger::new(debug_arena, identified_contracts, sources, breakpoints)
+        let Self { debug_arena, identified_contracts, sources, breakpoints, live_state } = self;
+        let mut debugger = Debugger::new(debug_arena, identified_contracts, sources, breakpoints);
+        debugger.live_state = live_state;
+        debugger
     }
 }
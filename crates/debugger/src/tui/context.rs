@@ -60,6 +60,8 @@ pub(crate) struct DebuggerContext<'a> {
     pub(crate) show_shortcuts: bool,
     /// The currently active buffer (memory, calldata, returndata) to be drawn.
     pub(crate) active_buffer: BufferKind,
+    /// Whether to show the state of the accounts instead of the source, when paused.
+    pub(crate) show_state: bool,
 }
 
 impl<'a> DebuggerContext<'a> {
@@ -77,13 +79,25 @@ impl<'a> DebuggerContext<'a> {
             buf_utf: false,
             show_shortcuts: true,
             active_buffer: BufferKind::Memory,
+            show_state: false,
         }
     }
 
     pub(crate) fn init(&mut self) {
+        // A paused execution is debugged from the instruction it is paused at.
+        if self.is_live() {
+            self.draw_memory.inner_call_index = self.debug_arena().len() - 1;
+            self.last_index = self.draw_memory.inner_call_index;
+            self.current_step = self.n_steps().saturating_sub(1);
+        }
         self.gen_opcode_list();
     }
 
+    /// Returns true if the execution being debugged is paused at a breakpoint.
+    pub(crate) fn is_live(&self) -> bool {
+        self.debugger.live_state.is_some()
+    }
+
     pub(crate) fn debug_arena(&self) -> &[DebugNode] {
         &self.debugger.debug_arena
     }
@@ -163,6 +177,13 @@ impl DebuggerContext<'_> {
             // Exit
             KeyCode::Char('q') => return ControlFlow::Break(ExitReason::CharExit),
 
+            // Resume or step the paused execution
+            KeyCode::Char('r') if self.is_live() => return ControlFlow::Break(ExitReason::Resume),
+            KeyCode::Char('n') if self.is_live() => return ControlFlow::Break(ExitReason::Step),
+
+            // Toggle the account state
+            KeyCode::Char('S') if self.is_live() => self.show_state = !self.show_state,
+
             // Scroll up the memory buffer
             KeyCode::Char('k') | KeyCode::Up if control => self.repeat(|this| {
                 this.draw_memory.current_buf_startline =
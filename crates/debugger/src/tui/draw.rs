@@ -184,6 +184,11 @@ impl DebuggerContext<'_> {
     fn draw_footer(&self, f: &mut Frame<'_>, area: Rect) {
         let l1 = "[q]: quit | [k/j]: prev/next op | [a/s]: prev/next jump | [c/C]: prev/next call | [g/G]: start/end | [b]: cycle memory/calldata/returndata buffers";
         let l2 = "[t]: stack labels | [m]: buffer decoding | [shift + j/k]: scroll stack | [ctrl + j/k]: scroll buffer | ['<char>]: goto breakpoint | [h] toggle help";
+        let l1 = if self.is_live() {
+            format!("[r]: resume | [n]: execute next op | [S]: toggle state | {l1}")
+        } else {
+            l1.to_string()
+        };
         let dimmed = Style::new().add_modifier(Modifier::DIM);
         let lines =
             vec![Line::from(Span::styled(l1, dimmed)), Line::from(Span::styled(l2, dimmed))];
@@ -193,6 +198,11 @@ impl DebuggerContext<'_> {
     }
 
     fn draw_src(&self, f: &mut Frame<'_>, area: Rect) {
+        if self.show_state {
+            self.draw_state(f, area);
+            return;
+        }
+
         let (text_output, source_name) = self.src_text(area);
         let call_kind_text = match self.call_kind() {
             CallKind::Create | CallKind::Create2 => "Contract creation",
@@ -212,6 +222,14 @@ impl DebuggerContext<'_> {
         f.render_widget(paragraph, area);
     }
 
+    fn draw_state(&self, f: &mut Frame<'_>, area: Rect) {
+        let lines = self.debugger.live_state.iter().flatten().map(|line| Line::from(line.as_str()));
+        let block = Block::default().title("Account state").borders(Borders::ALL);
+        let paragraph =
+            Paragraph::new(lines.collect::<Vec<_>>()).block(block).wrap(Wrap { trim: false });
+        f.render_widget(paragraph, area);
+    }
+
     fn src_text(&self, area: Rect) -> (Text<'_>, Option<&str>) {
         let (source_element, source_code, source_file) = match self.src_map() {
             Ok(r) => r,
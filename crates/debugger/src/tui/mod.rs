@@ -39,6 +39,10 @@ type DebuggerTerminal = Terminal<CrosstermBackend<io::Stdout>>;
 pub enum ExitReason {
     /// Exit using 'q'.
     CharExit,
+    /// Resume the paused execution using 'r'.
+    Resume,
+    /// Execute the next instruction of the paused execution using 'n'.
+    Step,
 }
 
 /// The TUI debugger.
@@ -50,6 +54,8 @@ pub struct Debugger {
     /// A mapping of source -> (PC -> IC map for deploy code, PC -> IC map for runtime code)
     pc_ic_maps: BTreeMap<String, (PcIcMap, PcIcMap)>,
     breakpoints: Breakpoints,
+    /// The state of the accounts, if the execution being debugged is paused at a breakpoint.
+    live_state: Option<Vec<String>>,
 }
 
 impl Debugger {
@@ -78,13 +84,20 @@ impl Debugger {
                 ))
             })
             .collect();
-        Self { debug_arena, identified_contracts, contracts_sources, pc_ic_maps, breakpoints }
+        Self {
+            debug_arena,
+            identified_contracts,
+            contracts_sources,
+            pc_ic_maps,
+            breakpoints,
+            live_state: None,
+        }
     }
 
     /// Starts the debugger TUI. Terminates the current process on failure or user exit.
     pub fn run_exit(mut self) -> ! {
         let code = match self.try_run() {
-            Ok(ExitReason::CharExit) => 0,
+            Ok(_) => 0,
             Err(e) => {
                 println!("{e}");
                 1
@@ -0,0 +1,117 @@
+//! A process-wide cache of analyzed bytecode.
+
+use alloy_primitives::B256;
+use parking_lot::RwLock;
+use revm::{interpreter::analysis::to_analysed, primitives::Bytecode};
+use rustc_hash::FxHashMap;
+use std::{
+    fmt,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        OnceLock,
+    },
+    time::{Duration, Instant},
+};
+
+/// Caches the jump table analysis of legacy bytecode by code hash.
+///
+/// revm analyzes raw bytecode every time a frame is created for it, which adds up when the same
+/// contracts are called by thousands of tests on many threads. The [`Backend`](super::Backend)
+/// hands out analyzed bytecode from the [global](Self::global) cache instead, so every contract is
+/// analyzed once per process.
+#[derive(Debug, Default)]
+pub struct AnalyzedBytecodeCache {
+    codes: RwLock<FxHashMap<B256, Bytecode>>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    analysis_nanos: AtomicU64,
+}
+
+impl AnalyzedBytecodeCache {
+    /// Returns the cache shared by all backends of the process.
+    pub fn global() -> &'static Self {
+        static CACHE: OnceLock<AnalyzedBytecodeCache> = OnceLock::new();
+        CACHE.get_or_init(Default::default)
+    }
+
+    /// Returns the analyzed version of the given bytecode, analyzing it if it isn't cached yet.
+    ///
+    /// Bytecode that is empty or already analyzed is returned as is.
+    pub fn analyze(&self, code_hash: B256, code: Bytecode) -> Bytecode {
+        if !matches!(code, Bytecode::LegacyRaw(_)) || code.is_empty() {
+            return code
+        }
+
+        if let Some(analyzed) = self.codes.read().get(&code_hash) {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            return analyzed.clone()
+        }
+
+        let start = Instant::now();
+        let analyzed = to_analysed(code);
+        self.analysis_nanos.fetch_add(start.elapsed().as_nanos() as u64, Ordering::Relaxed);
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        self.codes.write().entry(code_hash).or_insert(analyzed).clone()
+    }
+
+    /// Returns the statistics of the cache.
+    pub fn stats(&self) -> BytecodeCacheStats {
+        BytecodeCacheStats {
+            entries: self.codes.read().len(),
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            analysis_time: Duration::from_nanos(self.analysis_nanos.load(Ordering::Relaxed)),
+        }
+    }
+}
+
+/// Statistics of an [`AnalyzedBytecodeCache`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct BytecodeCacheStats {
+    /// The number of cached bytecodes.
+    pub entries: usize,
+    /// The number of lookups served from the cache.
+    pub hits: u64,
+    /// The number of lookups that had to analyze the bytecode.
+    pub misses: u64,
+    /// The total time spent analyzing bytecode.
+    pub analysis_time: Duration,
+}
+
+impl fmt::Display for BytecodeCacheStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let lookups = self.hits + self.misses;
+        let hit_rate = if lookups == 0 { 0.0 } else { self.hits as f64 * 100.0 / lookups as f64 };
+        write!(
+            f,
+            "bytecode analysis cache: {} contracts, {} hits, {} misses ({hit_rate:.1}% hit rate), {:?} spent analyzing",
+            self.entries, self.hits, self.misses, self.analysis_time
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::{bytes, keccak256};
+
+    #[test]
+    fn analyzes_bytecode_once() {
+        let cache = AnalyzedBytecodeCache::default();
+        let code = bytes!("6001600055");
+        let hash = keccak256(&code);
+
+        let analyzed = cache.analyze(hash, Bytecode::new_raw(code.clone()));
+        assert!(matches!(analyzed, Bytecode::LegacyAnalyzed(_)));
+        assert!(matches!(
+            cache.analyze(hash, Bytecode::new_raw(code)),
+            Bytecode::LegacyAnalyzed(_)
+        ));
+        // Analyzed and empty bytecode isn't looked up.
+        cache.analyze(hash, analyzed);
+        cache.analyze(B256::ZERO, Bytecode::new());
+
+        let stats = cache.stats();
+        assert_eq!((stats.entries, stats.hits, stats.misses), (1, 1, 1));
+    }
+}
@@ -221,6 +221,10 @@ impl<'a> DatabaseExt for CowBackend<'a> {
         self.backend_mut(&Env::default()).load_allocs(allocs, journaled_state)
     }
 
+    fn set_block_hash(&mut self, number: U256, hash: B256) {
+        self.backend.to_mut().set_block_hash(number, hash)
+    }
+
     fn is_persistent(&self, acc: &Address) -> bool {
         self.backend.is_persistent(acc)
     }
@@ -2,7 +2,7 @@
 
 use crate::{
     constants::{CALLER, CHEATCODE_ADDRESS, DEFAULT_CREATE2_DEPLOYER, TEST_CONTRACT_ADDRESS},
-    fork::{CreateFork, ForkId, MultiFork, SharedBackend},
+    fork::{CreateFork, ForkId, MultiFork, RpcUsageRegistry, SharedBackend},
     snapshot::Snapshots,
     utils::configure_tx_env,
     InspectorExt,
@@ -25,6 +25,7 @@ use revm::{
 };
 use std::{
     collections::{BTreeMap, HashMap, HashSet},
+    sync::Arc,
     time::Instant,
 };
 
@@ -34,6 +35,9 @@ pub use diagnostic::RevertDiagnostic;
 mod error;
 pub use error::{DatabaseError, DatabaseResult};
 
+mod analysis;
+pub use analysis::{AnalyzedBytecodeCache, BytecodeCacheStats};
+
 mod cow;
 pub use cow::CowBackend;
 
@@ -268,6 +272,10 @@ pub trait DatabaseExt: Database<Error = DatabaseError> + DatabaseCommit {
         journaled_state: &mut JournaledState,
     ) -> Result<(), DatabaseError>;
 
+    /// Sets the hash of the block with the given number in the active database, e.g. for the
+    /// blocks simulated with the `mine` cheatcode.
+    fn set_block_hash(&mut self, number: U256, hash: B256);
+
     /// Returns true if the given account is currently marked as persistent.
     fn is_persistent(&self, acc: &Address) -> bool;
 
@@ -427,6 +435,11 @@ impl Backend {
         Self::new(MultiFork::spawn(), fork)
     }
 
+    /// Returns the RPC usage of all forks of this backend.
+    pub fn rpc_usage(&self) -> &Arc<RpcUsageRegistry> {
+        self.forks.rpc_usage()
+    }
+
     /// Creates a new instance of `Backend`
     ///
     /// If `fork` is `Some` this will use a `fork` database, otherwise with an in-memory
@@ -1343,6 +1356,14 @@ impl DatabaseExt for Backend {
         Ok(())
     }
 
+    fn set_block_hash(&mut self, number: U256, hash: B256) {
+        if let Some(db) = self.active_fork_db_mut() {
+            db.block_hashes.insert(number, hash);
+        } else {
+            self.mem_db.block_hashes.insert(number, hash);
+        }
+    }
+
     fn add_persistent_account(&mut self, account: Address) -> bool {
         trace!(?account, "add persistent account");
         self.inner.persistent_accounts.insert(account)
@@ -1376,19 +1397,21 @@ impl DatabaseRef for Backend {
     type Error = DatabaseError;
 
     fn basic_ref(&self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
-        if let Some(db) = self.active_fork_db() {
-            db.basic_ref(address)
+        let info = if let Some(db) = self.active_fork_db() {
+            db.basic_ref(address)?
         } else {
-            Ok(self.mem_db.basic_ref(address)?)
-        }
+            self.mem_db.basic_ref(address)?
+        };
+        Ok(info.map(analyze_account_code))
     }
 
     fn code_by_hash_ref(&self, code_hash: B256) -> Result<Bytecode, Self::Error> {
-        if let Some(db) = self.active_fork_db() {
-            db.code_by_hash_ref(code_hash)
+        let code = if let Some(db) = self.active_fork_db() {
+            db.code_by_hash_ref(code_hash)?
         } else {
-            Ok(self.mem_db.code_by_hash_ref(code_hash)?)
-        }
+            self.mem_db.code_by_hash_ref(code_hash)?
+        };
+        Ok(AnalyzedBytecodeCache::global().analyze(code_hash, code))
     }
 
     fn storage_ref(&self, address: Address, index: U256) -> Result<U256, Self::Error> {
@@ -1408,6 +1431,15 @@ impl DatabaseRef for Backend {
     }
 }
 
+/// Replaces the code of the account with its analyzed version from the
+/// [global cache](AnalyzedBytecodeCache::global).
+fn analyze_account_code(mut info: AccountInfo) -> AccountInfo {
+    if let Some(code) = info.code.take() {
+        info.code = Some(AnalyzedBytecodeCache::global().analyze(info.code_hash, code));
+    }
+    info
+}
+
 impl DatabaseCommit for Backend {
     fn commit(&mut self, changes: Map<Address, Account>) {
         if let Some(db) = self.active_fork_db_mut() {
@@ -1421,19 +1453,21 @@ impl DatabaseCommit for Backend {
 impl Database for Backend {
     type Error = DatabaseError;
     fn basic(&mut self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
-        if let Some(db) = self.active_fork_db_mut() {
-            db.basic(address)
+        let info = if let Some(db) = self.active_fork_db_mut() {
+            db.basic(address)?
         } else {
-            Ok(self.mem_db.basic(address)?)
-        }
+            self.mem_db.basic(address)?
+        };
+        Ok(info.map(analyze_account_code))
     }
 
     fn code_by_hash(&mut self, code_hash: B256) -> Result<Bytecode, Self::Error> {
-        if let Some(db) = self.active_fork_db_mut() {
-            db.code_by_hash(code_hash)
+        let code = if let Some(db) = self.active_fork_db_mut() {
+            db.code_by_hash(code_hash)?
         } else {
-            Ok(self.mem_db.code_by_hash(code_hash)?)
-        }
+            self.mem_db.code_by_hash(code_hash)?
+        };
+        Ok(AnalyzedBytecodeCache::global().analyze(code_hash, code))
     }
 
     fn storage(&mut self, address: Address, index: U256) -> Result<U256, Self::Error> {
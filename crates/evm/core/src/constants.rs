@@ -37,6 +37,10 @@ pub const MAGIC_ASSUME: &[u8] = b"FOUNDRY::ASSUME";
 /// Magic return value returned by the `skip` cheatcode.
 pub const MAGIC_SKIP: &[u8] = b"FOUNDRY::SKIP";
 
+/// The number of most recent blocks whose hash is served by the block hash history contract of
+/// EIP-2935.
+pub const HISTORY_SERVE_WINDOW: u64 = 8191;
+
 /// The default CREATE2 deployer.
 pub const DEFAULT_CREATE2_DEPLOYER: Address = address!("4e59b44847b379578588920ca78fbf26c0b4956c");
 /// The initcode of the default CREATE2 deployer.
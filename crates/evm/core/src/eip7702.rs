@@ -0,0 +1,403 @@
+//! Support for [EIP-7702](https://eips.ethereum.org/EIPS/eip-7702) set-code transactions.
+//!
+//! An EOA delegates to a contract by signing an [`Authorization`]. Once the authorization is
+//! applied, the code of the EOA is set to a delegation designator, `0xef0100 || address`, and
+//! calls to the EOA execute the code of the delegate in the context of the EOA.
+
+use alloy_consensus::{SignableTransaction, Signed, Transaction};
+use alloy_primitives::{keccak256, Address, Bytes, ChainId, Signature, TxKind, B256, U256};
+use alloy_rlp::{length_of_length, Decodable, Encodable, Header, RlpDecodable, RlpEncodable};
+use alloy_rpc_types::AccessList;
+use revm::{
+    interpreter::CallInputs,
+    primitives::{Bytecode, EVMError},
+    Database, EvmContext, InnerEvmContext,
+};
+use serde::{Deserialize, Serialize};
+
+/// The type of set-code transactions.
+pub const EIP7702_TX_TYPE_ID: u8 = 0x04;
+
+/// The magic byte prefixed to an RLP-encoded authorization when computing its signature hash.
+pub const MAGIC: u8 = 0x05;
+
+/// The prefix of the code of a delegated account, followed by the address of the delegate.
+pub const DELEGATION_PREFIX: [u8; 3] = [0xef, 0x01, 0x00];
+
+/// The gas charged for each authorization in the authorization list.
+pub const PER_AUTHORIZATION_COST: u64 = 25_000;
+
+/// An authorization to set the code of the signer to a delegation to `address`.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    Hash,
+    RlpEncodable,
+    RlpDecodable,
+    Serialize,
+    Deserialize,
+)]
+#[serde(rename_all = "camelCase")]
+pub struct Authorization {
+    /// The chain the authorization is valid on, or zero for any chain.
+    pub chain_id: U256,
+    /// The address to delegate to.
+    pub address: Address,
+    /// The nonce of the signer the authorization is valid for.
+    #[serde(with = "alloy_serde::quantity")]
+    pub nonce: u64,
+}
+
+impl Authorization {
+    /// Returns the hash that is signed by the authority.
+    pub fn signature_hash(&self) -> B256 {
+        let mut buf = Vec::with_capacity(1 + self.length());
+        buf.push(MAGIC);
+        self.encode(&mut buf);
+        keccak256(buf)
+    }
+
+    /// Attaches a signature to the authorization.
+    pub fn into_signed(self, signature: Signature) -> SignedAuthorization {
+        SignedAuthorization {
+            chain_id: self.chain_id,
+            address: self.address,
+            nonce: self.nonce,
+            y_parity: signature.v().y_parity() as u8,
+            r: signature.r(),
+            s: signature.s(),
+        }
+    }
+
+    /// Returns whether the authorization is valid on the given chain.
+    pub fn is_valid_on(&self, chain_id: u64) -> bool {
+        self.chain_id.is_zero() || self.chain_id == U256::from(chain_id)
+    }
+}
+
+/// A signed [`Authorization`], as included in the authorization list of a transaction.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    Hash,
+    RlpEncodable,
+    RlpDecodable,
+    Serialize,
+    Deserialize,
+)]
+#[serde(rename_all = "camelCase")]
+pub struct SignedAuthorization {
+    pub chain_id: U256,
+    pub address: Address,
+    #[serde(with = "alloy_serde::quantity")]
+    pub nonce: u64,
+    #[serde(with = "alloy_serde::quantity")]
+    pub y_parity: u8,
+    pub r: U256,
+    pub s: U256,
+}
+
+impl SignedAuthorization {
+    /// Returns the authorization without its signature.
+    pub fn authorization(&self) -> Authorization {
+        Authorization { chain_id: self.chain_id, address: self.address, nonce: self.nonce }
+    }
+
+    /// Returns the signature of the authorization.
+    pub fn signature(&self) -> Result<Signature, alloy_primitives::SignatureError> {
+        Signature::from_rs_and_parity(self.r, self.s, self.y_parity != 0)
+    }
+
+    /// Recovers the address of the account that signed the authorization.
+    pub fn recover_authority(&self) -> Result<Address, alloy_primitives::SignatureError> {
+        self.signature()?.recover_address_from_prehash(&self.authorization().signature_hash())
+    }
+}
+
+/// Returns the code of an account that delegates to `delegate`.
+pub fn delegation_code(delegate: Address) -> Bytes {
+    [&DELEGATION_PREFIX[..], delegate.as_slice()].concat().into()
+}
+
+/// Returns the delegate of an account with the given code, if the code is a delegation
+/// designator.
+pub fn delegate_of(code: &[u8]) -> Option<Address> {
+    let delegate = code.strip_prefix(&DELEGATION_PREFIX[..])?;
+    (delegate.len() == 20).then(|| Address::from_slice(delegate))
+}
+
+/// Executes calls to a delegated account with the code of its delegate.
+///
+/// This is meant to be called from the `call` hook of an inspector. The call keeps the context
+/// of the delegated account, i.e. its storage, balance and address.
+pub fn resolve_delegation<DB: Database>(
+    ecx: &mut EvmContext<DB>,
+    call: &mut CallInputs,
+) -> Result<(), EVMError<DB::Error>> {
+    let (account, _) = ecx.journaled_state.load_code(call.bytecode_address, &mut ecx.db)?;
+    let delegate = account.info.code.as_ref().and_then(|code| delegate_of(&code.original_bytes()));
+    if let Some(delegate) = delegate {
+        trace!(address=?call.bytecode_address, ?delegate, "resolved delegation");
+        call.bytecode_address = delegate;
+    }
+    Ok(())
+}
+
+/// Applies an authorization, setting the code of its authority to a delegation designator.
+///
+/// Returns the authority, or `None` if the authorization is invalid and must be skipped: signed
+/// for another chain, with a nonce that does not match the one of the authority, or by an
+/// account with code that is not a delegation.
+pub fn apply_authorization<DB: Database>(
+    ecx: &mut InnerEvmContext<DB>,
+    authorization: &SignedAuthorization,
+) -> Result<Option<Address>, EVMError<DB::Error>> {
+    if !authorization.authorization().is_valid_on(ecx.env.cfg.chain_id) {
+        return Ok(None)
+    }
+    let Ok(authority) = authorization.recover_authority() else { return Ok(None) };
+
+    let (account, _) = ecx.journaled_state.load_code(authority, &mut ecx.db)?;
+    let code = account.info.code.as_ref().map(|code| code.original_bytes()).unwrap_or_default();
+    if account.info.nonce != authorization.nonce ||
+        !(code.is_empty() || delegate_of(&code).is_some())
+    {
+        return Ok(None)
+    }
+
+    account.info.nonce += 1;
+    ecx.journaled_state
+        .set_code(authority, Bytecode::new_raw(delegation_code(authorization.address)));
+    Ok(Some(authority))
+}
+
+/// A set-code transaction, which applies a list of authorizations before executing a call.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TxEip7702 {
+    #[serde(with = "alloy_serde::quantity")]
+    pub chain_id: ChainId,
+    #[serde(with = "alloy_serde::quantity")]
+    pub nonce: u64,
+    #[serde(with = "alloy_serde::quantity")]
+    pub gas_limit: u128,
+    #[serde(with = "alloy_serde::quantity")]
+    pub max_fee_per_gas: u128,
+    #[serde(with = "alloy_serde::quantity")]
+    pub max_priority_fee_per_gas: u128,
+    /// Set-code transactions cannot create contracts.
+    pub to: Address,
+    pub value: U256,
+    pub access_list: AccessList,
+    pub authorization_list: Vec<SignedAuthorization>,
+    pub input: Bytes,
+}
+
+impl TxEip7702 {
+    /// Calculates the length of the RLP-encoded transaction's fields.
+    fn fields_len(&self) -> usize {
+        self.chain_id.length() +
+            self.nonce.length() +
+            self.max_priority_fee_per_gas.length() +
+            self.max_fee_per_gas.length() +
+            self.gas_limit.length() +
+            self.to.length() +
+            self.value.length() +
+            self.input.length() +
+            self.access_list.length() +
+            self.authorization_list.length()
+    }
+
+    /// Encodes only the transaction's fields into the desired buffer, without a RLP header.
+    fn encode_fields(&self, out: &mut dyn alloy_rlp::BufMut) {
+        self.chain_id.encode(out);
+        self.nonce.encode(out);
+        self.max_priority_fee_per_gas.encode(out);
+        self.max_fee_per_gas.encode(out);
+        self.gas_limit.encode(out);
+        self.to.encode(out);
+        self.value.encode(out);
+        self.input.encode(out);
+        self.access_list.encode(out);
+        self.authorization_list.encode(out);
+    }
+
+    /// Decodes the transaction's fields, after the RLP header.
+    fn decode_fields(buf: &mut &[u8]) -> alloy_rlp::Result<Self> {
+        Ok(Self {
+            chain_id: Decodable::decode(buf)?,
+            nonce: Decodable::decode(buf)?,
+            max_priority_fee_per_gas: Decodable::decode(buf)?,
+            max_fee_per_gas: Decodable::decode(buf)?,
+            gas_limit: Decodable::decode(buf)?,
+            to: Decodable::decode(buf)?,
+            value: Decodable::decode(buf)?,
+            input: Decodable::decode(buf)?,
+            access_list: Decodable::decode(buf)?,
+            authorization_list: Decodable::decode(buf)?,
+        })
+    }
+
+    /// Returns the length of the EIP-2718 encoding of the signed transaction.
+    pub fn encoded_len_with_signature(&self, signature: &Signature) -> usize {
+        let payload_length = self.fields_len() + signature.rlp_vrs_len();
+        1 + length_of_length(payload_length) + payload_length
+    }
+
+    /// Writes the EIP-2718 encoding of the signed transaction: the type byte followed by the RLP
+    /// list of the fields and the signature.
+    pub fn encode_with_signature(&self, signature: &Signature, out: &mut dyn alloy_rlp::BufMut) {
+        out.put_u8(EIP7702_TX_TYPE_ID);
+        let payload_length = self.fields_len() + signature.rlp_vrs_len();
+        Header { list: true, payload_length }.encode(out);
+        self.encode_fields(out);
+        signature.write_rlp_vrs(out);
+    }
+
+    /// Decodes a signed transaction from its EIP-2718 encoding, without the type byte.
+    pub fn decode_signed_fields(buf: &mut &[u8]) -> alloy_rlp::Result<Signed<Self>> {
+        let header = Header::decode(buf)?;
+        if !header.list {
+            return Err(alloy_rlp::Error::UnexpectedString)
+        }
+        let original = *buf;
+        let tx = Self::decode_fields(buf)?;
+        let signature = Signature::decode_rlp_vrs(buf)?;
+        let consumed = original.len() - buf.len();
+        if consumed != header.payload_length {
+            return Err(alloy_rlp::Error::ListLengthMismatch {
+                expected: header.payload_length,
+                got: consumed,
+            })
+        }
+
+        let mut encoded = Vec::with_capacity(tx.encoded_len_with_signature(&signature));
+        tx.encode_with_signature(&signature, &mut encoded);
+        let hash = keccak256(encoded);
+        Ok(Signed::new_unchecked(tx, signature, hash))
+    }
+}
+
+impl Transaction for TxEip7702 {
+    fn input(&self) -> &[u8] {
+        &self.input
+    }
+
+    fn to(&self) -> TxKind {
+        TxKind::Call(self.to)
+    }
+
+    fn value(&self) -> U256 {
+        self.value
+    }
+
+    fn chain_id(&self) -> Option<ChainId> {
+        Some(self.chain_id)
+    }
+
+    fn nonce(&self) -> u64 {
+        self.nonce
+    }
+
+    fn gas_limit(&self) -> u128 {
+        self.gas_limit
+    }
+
+    fn gas_price(&self) -> Option<u128> {
+        None
+    }
+}
+
+impl SignableTransaction<Signature> for TxEip7702 {
+    fn set_chain_id(&mut self, chain_id: ChainId) {
+        self.chain_id = chain_id;
+    }
+
+    fn encode_for_signing(&self, out: &mut dyn alloy_rlp::BufMut) {
+        out.put_u8(EIP7702_TX_TYPE_ID);
+        Header { list: true, payload_length: self.fields_len() }.encode(out);
+        self.encode_fields(out);
+    }
+
+    fn payload_len_for_signature(&self) -> usize {
+        let payload_length = self.fields_len();
+        1 + length_of_length(payload_length) + payload_length
+    }
+
+    fn into_signed(self, signature: Signature) -> Signed<Self> {
+        // The signature of typed transactions only holds the y-parity.
+        let signature = signature.with_parity_bool();
+        let mut buf = Vec::with_capacity(self.encoded_len_with_signature(&signature));
+        self.encode_with_signature(&signature, &mut buf);
+        let hash = keccak256(&buf);
+        Signed::new_unchecked(self, signature, hash)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::address;
+
+    fn signature() -> Signature {
+        Signature::from_rs_and_parity(U256::from(1), U256::from(2), true).unwrap()
+    }
+
+    #[test]
+    fn can_detect_delegation() {
+        let delegate = address!("70997970C51812dc3A010C7d01b50e0d17dc79C8");
+        let code = delegation_code(delegate);
+        assert_eq!(code.len(), 23);
+        assert_eq!(delegate_of(&code), Some(delegate));
+        assert_eq!(delegate_of(&code[..22]), None);
+        assert_eq!(delegate_of(&[0x60, 0x80]), None);
+    }
+
+    #[test]
+    fn can_roundtrip_signed_authorization() {
+        let authorization = Authorization {
+            chain_id: U256::from(31337),
+            address: address!("70997970C51812dc3A010C7d01b50e0d17dc79C8"),
+            nonce: 1,
+        };
+        let signed = authorization.into_signed(signature());
+        assert_eq!(signed.signature().unwrap(), signature());
+        assert_eq!(signed.authorization(), authorization);
+
+        let encoded = alloy_rlp::encode(signed);
+        assert_eq!(SignedAuthorization::decode(&mut encoded.as_slice()).unwrap(), signed);
+    }
+
+    #[test]
+    fn can_roundtrip_set_code_transaction() {
+        let tx = TxEip7702 {
+            chain_id: 31337,
+            nonce: 2,
+            gas_limit: 100_000,
+            max_fee_per_gas: 2_000_000_000,
+            max_priority_fee_per_gas: 1_000_000_000,
+            to: address!("70997970C51812dc3A010C7d01b50e0d17dc79C8"),
+            value: U256::from(1),
+            access_list: Default::default(),
+            authorization_list: vec![Authorization::default().into_signed(signature())],
+            input: Bytes::from_static(&[0xde, 0xad]),
+        };
+        let signed = tx.clone().into_signed(signature());
+
+        let mut encoded = Vec::new();
+        signed.tx().encode_with_signature(signed.signature(), &mut encoded);
+        assert_eq!(encoded[0], EIP7702_TX_TYPE_ID);
+
+        let decoded = TxEip7702::decode_signed_fields(&mut &encoded[1..]).unwrap();
+        assert_eq!(decoded.tx(), &tx);
+        assert_eq!(decoded.hash(), signed.hash());
+    }
+}
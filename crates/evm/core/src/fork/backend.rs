@@ -1,7 +1,11 @@
 //! Smart caching and deduplication of requests when using a forking provider
 use crate::{
     backend::{DatabaseError, DatabaseResult},
-    fork::{cache::FlushJsonBlockCacheDB, BlockchainDb},
+    fork::{
+        cache::FlushJsonBlockCacheDB,
+        usage::{request_weight, RpcAccounting},
+        BlockchainDb, ForkMetrics, RpcUsageTracker,
+    },
 };
 use alloy_primitives::{keccak256, Address, Bytes, B256, U256};
 use alloy_provider::{network::AnyNetwork, Provider};
@@ -11,7 +15,8 @@ use alloy_transport::Transport;
 use eyre::WrapErr;
 use foundry_common::NON_ARCHIVE_NODE_WARNING;
 use futures::{
-    channel::mpsc::{channel, Receiver, Sender},
+    channel::mpsc::{channel, Receiver, Sender, TrySendError},
+    future::try_join_all,
     stream::Stream,
     task::{Context, Poll},
     Future, FutureExt,
@@ -37,6 +42,8 @@ use std::{
 type AccountFuture<Err> =
     Pin<Box<dyn Future<Output = (Result<(U256, u64, Bytes), Err>, Address)> + Send>>;
 type StorageFuture<Err> = Pin<Box<dyn Future<Output = (Result<U256, Err>, Address, U256)> + Send>>;
+type StorageBatchFuture<Err> =
+    Pin<Box<dyn Future<Output = (Result<Vec<U256>, Err>, Address, Vec<U256>, bool)> + Send>>;
 type BlockHashFuture<Err> = Pin<Box<dyn Future<Output = (Result<B256, Err>, u64)> + Send>>;
 type FullBlockFuture<Err> =
     Pin<Box<dyn Future<Output = (FullBlockSender, Result<Option<Block>, Err>, BlockId)> + Send>>;
@@ -53,10 +60,14 @@ type BlockHashSender = OneshotSender<DatabaseResult<B256>>;
 type FullBlockSender = OneshotSender<DatabaseResult<Block>>;
 type TransactionSender = OneshotSender<DatabaseResult<WithOtherFields<Transaction>>>;
 
+/// The usage tracker of the test that sent a request, see [`RpcUsageTracker::scope`]
+type Scope = Option<Arc<RpcUsageTracker>>;
+
 /// Request variants that are executed by the provider
 enum ProviderRequest<Err> {
     Account(AccountFuture<Err>),
     Storage(StorageFuture<Err>),
+    StorageBatch(StorageBatchFuture<Err>),
     BlockHash(BlockHashFuture<Err>),
     FullBlock(FullBlockFuture<Err>),
     Transaction(TransactionFuture<Err>),
@@ -94,16 +105,28 @@ pub struct BackendHandler<T, P> {
     /// Listeners that wait for a `get_account` related response
     account_requests: HashMap<Address, Vec<AccountInfoSender>>,
     /// Listeners that wait for a `get_storage_at` response
+    ///
+    /// Prefetched slots have no listeners until they are requested.
     storage_requests: HashMap<(Address, U256), Vec<StorageSender>>,
+    /// Storage slots to fetch in the next batch, grouped by account
+    batched_storage: HashMap<Address, Vec<U256>>,
     /// Listeners that wait for a `get_block` response
     block_requests: FxHashMap<u64, Vec<BlockHashSender>>,
     /// Incoming commands.
-    incoming: Receiver<BackendRequest>,
+    incoming: Receiver<(BackendRequest, Scope)>,
     /// unprocessed queued requests
-    queued_requests: VecDeque<BackendRequest>,
+    queued_requests: VecDeque<(BackendRequest, Scope)>,
     /// The block to fetch data from.
     // This is an `Option` so that we can have less code churn in the functions below
     block_id: Option<BlockId>,
+    /// The number of storage slots to prefetch, see [`Self::set_prefetch`]
+    prefetch: usize,
+    /// Whether the endpoint supports `eth_getProof`, this is disabled after the first failure
+    proofs_supported: bool,
+    /// Cache metrics, shared with the `SharedBackend`s
+    metrics: Arc<ForkMetrics>,
+    /// Accounting of the requests sent to the provider, see [`Self::set_rpc_accounting`]
+    rpc: RpcAccounting,
 }
 
 impl<T, P> BackendHandler<T, P>
@@ -114,8 +137,9 @@ where
     fn new(
         provider: P,
         db: BlockchainDb,
-        rx: Receiver<BackendRequest>,
+        rx: Receiver<(BackendRequest, Scope)>,
         block_id: Option<BlockId>,
+        metrics: Arc<ForkMetrics>,
     ) -> Self {
         Self {
             provider,
@@ -123,29 +147,53 @@ where
             pending_requests: Default::default(),
             account_requests: Default::default(),
             storage_requests: Default::default(),
+            batched_storage: Default::default(),
             block_requests: Default::default(),
             queued_requests: Default::default(),
             incoming: rx,
             block_id,
+            prefetch: 0,
+            proofs_supported: true,
+            metrics,
+            rpc: Default::default(),
             transport: PhantomData,
         }
     }
 
+    /// Sets the number of storage slots to prefetch speculatively.
+    ///
+    /// When a storage slot is fetched, the next `slots` slots of the same account are fetched along
+    /// with it, and when a contract account is fetched, its first `slots` slots are fetched as
+    /// well. All slots of an account are fetched in a single `eth_getProof` request if the
+    /// endpoint supports it.
+    pub fn set_prefetch(&mut self, slots: usize) {
+        self.prefetch = slots;
+    }
+
+    /// Sets the accounting of the requests sent to the provider.
+    ///
+    /// Requests that would exceed the RPC budget of the accounting fail without being sent.
+    pub(crate) fn set_rpc_accounting(&mut self, rpc: RpcAccounting) {
+        self.rpc = rpc;
+    }
+
     /// handle the request in queue in the future.
     ///
     /// We always check:
     ///  1. if the requested value is already stored in the cache, then answer the sender
     ///  2. otherwise, fetch it via the provider but check if a request for that value is already in
     ///     progress (e.g. another Sender just requested the same account)
-    fn on_request(&mut self, req: BackendRequest) {
+    fn on_request(&mut self, req: BackendRequest, scope: Scope) {
+        let scope = scope.as_deref();
         match req {
             BackendRequest::Basic(addr, sender) => {
                 trace!(target: "backendhandler", "received request basic address={:?}", addr);
                 let acc = self.db.accounts().read().get(&addr).cloned();
+                self.metrics.record_account(acc.is_some());
                 if let Some(basic) = acc {
                     let _ = sender.send(Ok(basic));
                 } else {
-                    self.request_account(addr, sender);
+                    self.request_account(addr, sender, scope);
                 }
             }
             BackendRequest::BlockHash(number, sender) => {
@@ -153,24 +201,25 @@ where
                 if let Some(hash) = hash {
                     let _ = sender.send(Ok(hash));
                 } else {
-                    self.request_hash(number, sender);
+                    self.request_hash(number, sender, scope);
                 }
             }
             BackendRequest::FullBlock(number, sender) => {
-                self.request_full_block(number, sender);
+                self.request_full_block(number, sender, scope);
             }
             BackendRequest::Transaction(tx, sender) => {
-                self.request_transaction(tx, sender);
+                self.request_transaction(tx, sender, scope);
             }
             BackendRequest::Storage(addr, idx, sender) => {
                 // account is already stored in the cache
                 let value =
                     self.db.storage().read().get(&addr).and_then(|acc| acc.get(&idx).copied());
+                self.metrics.record_storage(value.is_some());
                 if let Some(value) = value {
                     let _ = sender.send(Ok(value));
                 } else {
                     // account present but not storage -> fetch storage
-                    self.request_account_storage(addr, idx, sender);
+                    self.request_account_storage(addr, idx, sender, scope);
                 }
             }
             BackendRequest::SetPinnedBlock(block_id) => {
@@ -180,35 +229,149 @@ where
     }
 
     /// process a request for account's storage
-    fn request_account_storage(&mut self, address: Address, idx: U256, listener: StorageSender) {
+    ///
+    /// The provider is charged when the batch is sent, but the scope is charged for a single
+    /// storage request right away, since batches mix the slots of several requests.
+    fn request_account_storage(
+        &mut self,
+        address: Address,
+        idx: U256,
+        listener: StorageSender,
+        scope: Option<&RpcUsageTracker>,
+    ) {
         match self.storage_requests.entry((address, idx)) {
             Entry::Occupied(mut entry) => {
                 entry.get_mut().push(listener);
             }
             Entry::Vacant(entry) => {
-                trace!(target: "backendhandler", %address, %idx, "preparing storage request");
                 entry.insert(vec![listener]);
-                let provider = self.provider.clone();
-                let block_id = self.block_id.unwrap_or_default();
-                let fut = Box::pin(async move {
-                    let storage = provider
-                        .get_storage_at(address, idx)
-                        .block_id(block_id)
-                        .await
-                        .map_err(Into::into);
-                    (storage, address, idx)
-                });
-                self.pending_requests.push(ProviderRequest::Storage(fut));
+                if let Some(scope) = scope {
+                    scope.record(1, request_weight("eth_getStorageAt"));
+                }
+                self.batched_storage.entry(address).or_default().push(idx);
+                let next = (1..=self.prefetch).filter_map(|i| idx.checked_add(U256::from(i)));
+                self.prefetch_storage(address, next);
             }
         }
     }
 
+    /// Adds the given storage slots to the next batch, unless they are already cached or requested
+    fn prefetch_storage(&mut self, address: Address, slots: impl IntoIterator<Item = U256>) {
+        let slots = {
+            let storage = self.db.storage().read();
+            let cached = storage.get(&address);
+            slots
+                .into_iter()
+                .filter(|idx| {
+                    !cached.is_some_and(|cached| cached.contains_key(idx)) &&
+                        !self.storage_requests.contains_key(&(address, *idx))
+                })
+                .collect::<Vec<_>>()
+        };
+        if slots.is_empty() {
+            return
+        }
+        trace!(target: "backendhandler", %address, slots = slots.len(), "prefetching storage");
+        self.metrics.record_prefetch(slots.len());
+        for idx in &slots {
+            self.storage_requests.insert((address, *idx), Vec::new());
+        }
+        self.batched_storage.entry(address).or_default().extend(slots);
+    }
+
+    /// Sends the storage requests batched since the last call.
+    ///
+    /// All slots of an account are fetched with a single `eth_getProof` request if there are
+    /// multiple, otherwise they're fetched with `eth_getStorageAt`.
+    fn flush_storage_batches(&mut self) {
+        for (address, slots) in std::mem::take(&mut self.batched_storage) {
+            if slots.len() > 1 && self.proofs_supported {
+                self.metrics.record_batch();
+                self.pending_requests.push(self.get_storage_batch_req(address, slots));
+            } else {
+                for idx in slots {
+                    self.pending_requests.push(self.get_storage_req(address, idx));
+                }
+            }
+        }
+    }
+
+    /// returns the future that fetches a single storage slot
+    fn get_storage_req(&self, address: Address, idx: U256) -> ProviderRequest<eyre::Report> {
+        trace!(target: "backendhandler", %address, %idx, "preparing storage request");
+        let provider = self.provider.clone();
+        let block_id = self.block_id.unwrap_or_default();
+        let charged = self.rpc.charge(&["eth_getStorageAt"], None);
+        let fut = Box::pin(async move {
+            if let Err(err) = charged {
+                return (Err(err.into()), address, idx)
+            }
+            let storage =
+                provider.get_storage_at(address, idx).block_id(block_id).await.map_err(Into::into);
+            (storage, address, idx)
+        });
+        ProviderRequest::Storage(fut)
+    }
+
+    /// returns the future that fetches multiple storage slots of an account.
+    ///
+    /// Falls back to concurrent `eth_getStorageAt` requests if `eth_getProof` fails, which is
+    /// reported in the output of the future.
+    fn get_storage_batch_req(
+        &self,
+        address: Address,
+        slots: Vec<U256>,
+    ) -> ProviderRequest<eyre::Report> {
+        trace!(target: "backendhandler", %address, slots = slots.len(), "preparing storage batch request");
+        let provider = self.provider.clone();
+        let block_id = self.block_id.unwrap_or_default();
+        let charged = self.rpc.charge(&["eth_getProof"], None);
+        let rpc = self.rpc.clone();
+        let fut = Box::pin(async move {
+            if let Err(err) = charged {
+                return (Err(err.into()), address, slots, true)
+            }
+            let keys = slots.iter().map(|idx| B256::from(*idx)).collect();
+            match provider.get_proof(address, keys).block_id(block_id).await {
+                Ok(proof) if proof.storage_proof.len() == slots.len() => {
+                    let values = proof.storage_proof.into_iter().map(|proof| proof.value).collect();
+                    (Ok(values), address, slots, true)
+                }
+                resp => {
+                    if let Err(err) = resp {
+                        debug!(target: "backendhandler", %err, %address, "eth_getProof failed, falling back to eth_getStorageAt");
+                    }
+                    let methods = vec!["eth_getStorageAt"; slots.len()];
+                    if let Err(err) = rpc.charge(&methods, None) {
+                        return (Err(err.into()), address, slots, false)
+                    }
+                    let values = try_join_all(slots.iter().map(|idx| {
+                        provider.get_storage_at(address, *idx).block_id(block_id).into_future()
+                    }))
+                    .await
+                    .map_err(Into::into);
+                    (values, address, slots, false)
+                }
+            }
+        });
+        ProviderRequest::StorageBatch(fut)
+    }
+
     /// returns the future that fetches the account data
-    fn get_account_req(&self, address: Address) -> ProviderRequest<eyre::Report> {
+    fn get_account_req(
+        &self,
+        address: Address,
+        scope: Option<&RpcUsageTracker>,
+    ) -> ProviderRequest<eyre::Report> {
         trace!(target: "backendhandler", "preparing account request, address={:?}", address);
         let provider = self.provider.clone();
         let block_id = self.block_id.unwrap_or_default();
+        let charged =
+            self.rpc.charge(&["eth_getBalance", "eth_getTransactionCount", "eth_getCode"], scope);
         let fut = Box::pin(async move {
+            if let Err(err) = charged {
+                return (Err(err.into()), address)
+            }
             let balance = provider.get_balance(address).block_id(block_id).into_future();
             let nonce = provider.get_transaction_count(address).block_id(block_id).into_future();
             let code = provider.get_code_at(address).block_id(block_id).into_future();
@@ -219,22 +382,36 @@ where
     }
 
     /// process a request for an account
-    fn request_account(&mut self, address: Address, listener: AccountInfoSender) {
+    fn request_account(
+        &mut self,
+        address: Address,
+        listener: AccountInfoSender,
+        scope: Option<&RpcUsageTracker>,
+    ) {
         match self.account_requests.entry(address) {
             Entry::Occupied(mut entry) => {
                 entry.get_mut().push(listener);
             }
             Entry::Vacant(entry) => {
                 entry.insert(vec![listener]);
-                self.pending_requests.push(self.get_account_req(address));
+                self.pending_requests.push(self.get_account_req(address, scope));
             }
         }
     }
 
     /// process a request for an entire block
-    fn request_full_block(&mut self, number: BlockId, sender: FullBlockSender) {
+    fn request_full_block(
+        &mut self,
+        number: BlockId,
+        sender: FullBlockSender,
+        scope: Option<&RpcUsageTracker>,
+    ) {
         let provider = self.provider.clone();
+        let charged = self.rpc.charge(&["eth_getBlockByNumber"], scope);
         let fut = Box::pin(async move {
+            if let Err(err) = charged {
+                return (sender, Err(err.into()), number)
+            }
             let block = provider
                 .get_block(number, true.into())
                 .await
@@ -246,9 +423,18 @@ where
     }
 
     /// process a request for a transactions
-    fn request_transaction(&mut self, tx: B256, sender: TransactionSender) {
+    fn request_transaction(
+        &mut self,
+        tx: B256,
+        sender: TransactionSender,
+        scope: Option<&RpcUsageTracker>,
+    ) {
         let provider = self.provider.clone();
+        let charged = self.rpc.charge(&["eth_getTransactionByHash"], scope);
         let fut = Box::pin(async move {
+            if let Err(err) = charged {
+                return (sender, Err(err.into()), tx)
+            }
             let block = provider
                 .get_transaction_by_hash(tx)
                 .await
@@ -263,7 +449,12 @@ where
     }
 
     /// process a request for a block hash
-    fn request_hash(&mut self, number: u64, listener: BlockHashSender) {
+    fn request_hash(
+        &mut self,
+        number: u64,
+        listener: BlockHashSender,
+        scope: Option<&RpcUsageTracker>,
+    ) {
         match self.block_requests.entry(number) {
             Entry::Occupied(mut entry) => {
                 entry.get_mut().push(listener);
@@ -272,7 +463,11 @@ where
                 trace!(target: "backendhandler", number, "preparing block hash request");
                 entry.insert(vec![listener]);
                 let provider = self.provider.clone();
+                let charged = self.rpc.charge(&["eth_getBlockByNumber"], scope);
                 let fut = Box::pin(async move {
+                    if let Err(err) = charged {
+                        return (Err(err.into()), number)
+                    }
                     let block = provider
                         .get_block_by_number(number.into(), false)
                         .await
@@ -313,9 +508,10 @@ where
         let pin = self.get_mut();
         loop {
             // Drain queued requests first.
-            while let Some(req) = pin.queued_requests.pop_front() {
-                pin.on_request(req)
+            while let Some((req, scope)) = pin.queued_requests.pop_front() {
+                pin.on_request(req, scope)
             }
+            pin.flush_storage_batches();
 
             // receive new requests to delegate to the underlying provider
             loop {
@@ -325,6 +521,7 @@ where
                     }
                     Poll::Ready(None) => {
                         trace!(target: "backendhandler", "last sender dropped, ready to drop (&flush cache)");
+                        debug!(target: "backendhandler", metrics = %pin.metrics, "fork cache metrics");
                         return Poll::Ready(());
                     }
                     Poll::Pending => break,
@@ -370,6 +567,11 @@ where
                             };
                             pin.db.accounts().write().insert(addr, acc.clone());
 
+                            // speculatively fetch the first storage slots of contracts
+                            if pin.prefetch > 0 && code_hash != KECCAK_EMPTY {
+                                pin.prefetch_storage(addr, (0..pin.prefetch).map(U256::from));
+                            }
+
                             // notify all listeners
                             if let Some(listeners) = pin.account_requests.remove(&addr) {
                                 listeners.into_iter().for_each(|l| {
@@ -413,6 +615,55 @@ where
                             continue;
                         }
                     }
+                    ProviderRequest::StorageBatch(fut) => {
+                        if let Poll::Ready((resp, addr, slots, proofs_supported)) =
+                            fut.poll_unpin(cx)
+                        {
+                            if !proofs_supported && pin.proofs_supported {
+                                debug!(target: "backendhandler", "eth_getProof unavailable, disabling batched storage requests");
+                                pin.proofs_supported = false;
+                            }
+                            let values = match resp {
+                                Ok(values) => values,
+                                Err(err) => {
+                                    // notify all listeners
+                                    let err = Arc::new(err);
+                                    for idx in slots {
+                                        if let Some(listeners) =
+                                            pin.storage_requests.remove(&(addr, idx))
+                                        {
+                                            listeners.into_iter().for_each(|l| {
+                                                let _ = l.send(Err(DatabaseError::GetStorage(
+                                                    addr,
+                                                    idx,
+                                                    Arc::clone(&err),
+                                                )));
+                                            })
+                                        }
+                                    }
+                                    continue;
+                                }
+                            };
+
+                            // update the cache
+                            pin.db
+                                .storage()
+                                .write()
+                                .entry(addr)
+                                .or_default()
+                                .extend(slots.iter().copied().zip(values.iter().copied()));
+
+                            // notify all listeners
+                            for (idx, value) in slots.into_iter().zip(values) {
+                                if let Some(listeners) = pin.storage_requests.remove(&(addr, idx)) {
+                                    listeners.into_iter().for_each(|l| {
+                                        let _ = l.send(Ok(value));
+                                    })
+                                }
+                            }
+                            continue;
+                        }
+                    }
                     ProviderRequest::BlockHash(fut) => {
                         if let Poll::Ready((block_hash, number)) = fut.poll_unpin(cx) {
                             let value = match block_hash {
@@ -478,7 +729,7 @@ where
 
             // If no new requests have been queued, break to
             // be polled again later.
-            if pin.queued_requests.is_empty() {
+            if pin.queued_requests.is_empty() && pin.batched_storage.is_empty() {
                 return Poll::Pending;
             }
         }
@@ -507,6 +758,11 @@ where
 /// from `B` and simply adds it as an additional listener for the request already in progress,
 /// instead of sending another one. So that after the provider returns the response all listeners
 /// (`A` and `B`) get notified.
+///
+/// Storage slots of the same account that are requested concurrently are fetched together with a
+/// single `eth_getProof` request, and the `BackendHandler` can prefetch storage slots
+/// speculatively, see [`BackendHandler::set_prefetch`]. Cache hit rates are tracked in
+/// [`ForkMetrics`].
 // **Note**: the implementation makes use of [tokio::task::block_in_place()] when interacting with
 // the underlying [BackendHandler] which runs on a separate spawned tokio task.
 // [tokio::task::block_in_place()]
@@ -516,12 +772,14 @@ where
 #[derive(Clone, Debug)]
 pub struct SharedBackend {
     /// channel used for sending commands related to database operations
-    backend: Sender<BackendRequest>,
+    backend: Sender<(BackendRequest, Scope)>,
     /// Ensures that the underlying cache gets flushed once the last `SharedBackend` is dropped.
     ///
     /// There is only one instance of the type, so as soon as the last `SharedBackend` is deleted,
     /// `FlushJsonBlockCacheDB` is also deleted and the cache is flushed.
     cache: Arc<FlushJsonBlockCacheDB>,
+    /// Cache metrics of the `BackendHandler`
+    metrics: Arc<ForkMetrics>,
 }
 
 impl SharedBackend {
@@ -591,14 +849,21 @@ impl SharedBackend {
     {
         let (backend, backend_rx) = channel(1);
         let cache = Arc::new(FlushJsonBlockCacheDB(Arc::clone(db.cache())));
-        let handler = BackendHandler::new(provider, db, backend_rx, pin_block);
-        (Self { backend, cache }, handler)
+        let metrics = Arc::new(ForkMetrics::default());
+        let handler =
+            BackendHandler::new(provider, db, backend_rx, pin_block, Arc::clone(&metrics));
+        (Self { backend, cache, metrics }, handler)
+    }
+
+    /// Returns the cache metrics of the `BackendHandler`
+    pub fn metrics(&self) -> &ForkMetrics {
+        &self.metrics
     }
 
     /// Updates the pinned block to fetch data from
     pub fn set_pinned_block(&self, block: impl Into<BlockId>) -> eyre::Result<()> {
         let req = BackendRequest::SetPinnedBlock(block.into());
-        self.backend.clone().try_send(req).map_err(|e| eyre::eyre!("{:?}", e))
+        self.send(req).map_err(|e| eyre::eyre!("{:?}", e))
     }
 
     /// Returns the full block for the given block identifier
@@ -606,7 +871,7 @@ impl SharedBackend {
         tokio::task::block_in_place(|| {
             let (sender, rx) = oneshot_channel();
             let req = BackendRequest::FullBlock(block.into(), sender);
-            self.backend.clone().try_send(req)?;
+            self.send(req)?;
             rx.recv()?
         })
     }
@@ -616,7 +881,7 @@ impl SharedBackend {
         tokio::task::block_in_place(|| {
             let (sender, rx) = oneshot_channel();
             let req = BackendRequest::Transaction(tx, sender);
-            self.backend.clone().try_send(req)?;
+            self.send(req)?;
             rx.recv()?
         })
     }
@@ -625,7 +890,7 @@ impl SharedBackend {
         tokio::task::block_in_place(|| {
             let (sender, rx) = oneshot_channel();
             let req = BackendRequest::Basic(address, sender);
-            self.backend.clone().try_send(req)?;
+            self.send(req)?;
             rx.recv()?.map(Some)
         })
     }
@@ -634,7 +899,7 @@ impl SharedBackend {
         tokio::task::block_in_place(|| {
             let (sender, rx) = oneshot_channel();
             let req = BackendRequest::Storage(address, index, sender);
-            self.backend.clone().try_send(req)?;
+            self.send(req)?;
             rx.recv()?
         })
     }
@@ -643,11 +908,17 @@ impl SharedBackend {
         tokio::task::block_in_place(|| {
             let (sender, rx) = oneshot_channel();
             let req = BackendRequest::BlockHash(number, sender);
-            self.backend.clone().try_send(req)?;
+            self.send(req)?;
             rx.recv()?
         })
     }
 
+    /// Sends the request to the `BackendHandler`, along with the usage tracker of the current
+    /// thread, see [`RpcUsageTracker::scope`]
+    fn send(&self, req: BackendRequest) -> Result<(), TrySendError<(BackendRequest, Scope)>> {
+        self.backend.clone().try_send((req, RpcUsageTracker::current()))
+    }
+
     /// Flushes the DB to disk if caching is enabled
     pub(crate) fn flush_cache(&self) {
         self.cache.0.flush();
@@ -759,6 +1030,37 @@ mod tests {
         assert_eq!(slots.len() as u64, max_slots);
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn shared_backend_prefetch() {
+        let Some(endpoint) = ENDPOINT else { return };
+
+        let provider = get_http_provider(endpoint);
+        let meta = BlockchainDbMeta {
+            cfg_env: Default::default(),
+            block_env: Default::default(),
+            hosts: BTreeSet::from([endpoint.to_string()]),
+        };
+
+        let db = BlockchainDb::new(meta, None);
+        let (backend, mut handler) = SharedBackend::new(Arc::new(provider), db.clone(), None);
+        handler.set_prefetch(4);
+        tokio::spawn(handler);
+
+        // some rng contract from etherscan
+        let address: Address = "63091244180ae240c87d1f528f5f269134cb07b3".parse().unwrap();
+
+        // fetching the account prefetches slots 0..4
+        let _ = backend.basic_ref(address).unwrap().unwrap();
+        let _ = backend.storage_ref(address, U256::from(2)).unwrap();
+        let slots = db.storage().read().get(&address).unwrap().clone();
+        assert!(slots.len() >= 4);
+
+        let metrics = backend.metrics();
+        assert_eq!(metrics.account_misses(), 1);
+        assert!(metrics.prefetched_slots() >= 4);
+        assert!(metrics.batched_requests() >= 1);
+    }
+
     #[test]
     fn can_read_cache() {
         let cache_path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("test-data/storage.json");
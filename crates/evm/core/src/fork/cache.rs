@@ -1,5 +1,5 @@
 //! Cache related abstraction
-use crate::backend::StateSnapshot;
+use crate::{backend::StateSnapshot, fork::ForkState};
 use alloy_primitives::{Address, B256, U256};
 use parking_lot::RwLock;
 use revm::{
@@ -11,7 +11,7 @@ use std::{
     collections::BTreeSet,
     fs,
     io::{BufWriter, Write},
-    path::PathBuf,
+    path::{Path, PathBuf},
     sync::Arc,
 };
 use url::Url;
@@ -41,7 +41,7 @@ impl BlockchainDb {
     ///   - the file contains malformed data, or if it couldn't be read
     ///   - the provided `meta` differs from [BlockchainDbMeta] that's stored on disk
     pub fn new(meta: BlockchainDbMeta, cache_path: Option<PathBuf>) -> Self {
-        Self::new_db(meta, cache_path, false)
+        Self::new_db(meta, cache_path, false, None)
     }
 
     /// Creates a new instance of the [BlockchainDb] and skips check when comparing meta
@@ -56,13 +56,32 @@ impl BlockchainDb {
     ///   - the file contains malformed data, or if it couldn't be read
     ///   - the provided `meta` differs from [BlockchainDbMeta] that's stored on disk
     pub fn new_skip_check(meta: BlockchainDbMeta, cache_path: Option<PathBuf>) -> Self {
-        Self::new_db(meta, cache_path, true)
+        Self::new_db(meta, cache_path, true, None)
     }
 
-    fn new_db(meta: BlockchainDbMeta, cache_path: Option<PathBuf>, skip_check: bool) -> Self {
+    /// Creates a new instance of the [BlockchainDb] that is also persisted as a [ForkState]
+    /// snapshot at `state_path`.
+    ///
+    /// If the snapshot exists and was taken at the same block, its contents are loaded on top of
+    /// the cached entries. The snapshot is rewritten with all the fetched state whenever the cache
+    /// is flushed.
+    pub fn new_with_fork_state(
+        meta: BlockchainDbMeta,
+        cache_path: Option<PathBuf>,
+        state_path: PathBuf,
+    ) -> Self {
+        Self::new_db(meta, cache_path, false, Some(state_path))
+    }
+
+    fn new_db(
+        meta: BlockchainDbMeta,
+        cache_path: Option<PathBuf>,
+        skip_check: bool,
+        state_path: Option<PathBuf>,
+    ) -> Self {
         trace!(target: "forge::cache", cache=?cache_path, "initialising blockchain db");
         // read cache and check if metadata matches
-        let cache = cache_path
+        let mut cache = cache_path
             .as_ref()
             .and_then(|p| {
                 JsonBlockCacheDB::load(p).ok().filter(|cache| {
@@ -81,6 +100,11 @@ impl BlockchainDb {
             })
             .unwrap_or_else(|| JsonBlockCacheDB::new(Arc::new(RwLock::new(meta)), cache_path));
 
+        if let Some(path) = state_path {
+            cache.load_fork_state(&path);
+            cache.state_path = Some(path);
+        }
+
         Self { db: Arc::clone(cache.db()), meta: Arc::clone(cache.meta()), cache: Arc::new(cache) }
     }
 
@@ -334,6 +358,8 @@ pub struct JsonBlockCacheDB {
     ///
     /// If this is a [None] then caching is disabled
     cache_path: Option<PathBuf>,
+    /// Where the [ForkState] snapshot of this cache is stored, if any.
+    state_path: Option<PathBuf>,
     /// Object that's stored in a json file
     data: JsonBlockCacheData,
 }
@@ -341,7 +367,11 @@ pub struct JsonBlockCacheDB {
 impl JsonBlockCacheDB {
     /// Creates a new instance.
     fn new(meta: Arc<RwLock<BlockchainDbMeta>>, cache_path: Option<PathBuf>) -> Self {
-        Self { cache_path, data: JsonBlockCacheData { meta, data: Arc::new(Default::default()) } }
+        Self {
+            cache_path,
+            state_path: None,
+            data: JsonBlockCacheData { meta, data: Arc::new(Default::default()) },
+        }
     }
 
     /// Loads the contents of the diskmap file and returns the read object
@@ -361,7 +391,25 @@ impl JsonBlockCacheDB {
             warn!(target: "cache", ?err, ?path, "Failed to deserialize cache data");
             err
         })?;
-        Ok(Self { cache_path: Some(path), data })
+        Ok(Self { cache_path: Some(path), state_path: None, data })
+    }
+
+    /// Loads the [ForkState] snapshot at the given path into the cache, if it exists and was taken
+    /// at the block of this cache.
+    fn load_fork_state(&self, path: &Path) {
+        if !path.exists() {
+            return
+        }
+        let state = match ForkState::read(path) {
+            Ok(state) => state,
+            Err(err) => return warn!(target: "cache", ?err, ?path, "Failed to read fork state"),
+        };
+        let number = self.meta().read().block_env.number;
+        if !state.matches(number) {
+            return warn!(target: "cache", ?path, %number, "ignoring fork state of another block")
+        }
+        trace!(target: "cache", ?path, accounts = state.accounts.len(), "loading fork state");
+        state.apply(self.db());
     }
 
     /// Returns the [MemDb] it holds access to
@@ -376,12 +424,16 @@ impl JsonBlockCacheDB {
 
     /// Returns `true` if this is a transient cache and nothing will be flushed
     pub fn is_transient(&self) -> bool {
-        self.cache_path.is_none()
+        self.cache_path.is_none() && self.state_path.is_none()
     }
 
     /// Flushes the DB to disk if caching is enabled.
     #[instrument(level = "warn", skip_all, fields(path = ?self.cache_path))]
     pub fn flush(&self) {
+        if let Some(path) = &self.state_path {
+            self.flush_fork_state(path);
+        }
+
         let Some(path) = &self.cache_path else { return };
         trace!(target: "cache", "saving json cache");
 
@@ -404,6 +456,16 @@ impl JsonBlockCacheDB {
 
         trace!(target: "cache", "saved json cache");
     }
+
+    /// Writes a [ForkState] snapshot of the cache to the given path.
+    fn flush_fork_state(&self, path: &Path) {
+        trace!(target: "cache", ?path, "saving fork state");
+        let block = self.meta().read().block_env.clone();
+        if let Err(err) = ForkState::new(self.db(), block).write(path) {
+            return warn!(target: "cache", ?err, ?path, "Failed to write fork state")
+        }
+        trace!(target: "cache", ?path, "saved fork state");
+    }
 }
 
 /// The Data the [JsonBlockCacheDB] can read and flush
@@ -617,4 +679,23 @@ mod tests {
 
         let _s = serde_json::to_string(&cache).unwrap();
     }
+
+    #[test]
+    fn can_persist_fork_state() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("fork-state.json.gz");
+        let meta = BlockchainDbMeta {
+            cfg_env: Default::default(),
+            block_env: Default::default(),
+            hosts: Default::default(),
+        };
+
+        let db = BlockchainDb::new_with_fork_state(meta.clone(), None, path.clone());
+        assert!(!db.cache().is_transient());
+        db.accounts().write().insert(Address::ZERO, AccountInfo::default());
+        db.cache().flush();
+
+        let db = BlockchainDb::new_with_fork_state(meta, None, path);
+        assert!(db.accounts().read().contains_key(&Address::ZERO));
+    }
 }
@@ -0,0 +1,115 @@
+//! Metrics of the requests served by a fork backend.
+
+use std::{
+    fmt,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+/// Counters of the requests served by a [`BackendHandler`](super::BackendHandler).
+///
+/// A request is a hit if the value was already in the fork's cache, either because it was
+/// requested before or because it was prefetched.
+#[derive(Debug, Default)]
+pub struct ForkMetrics {
+    account_hits: AtomicU64,
+    account_misses: AtomicU64,
+    storage_hits: AtomicU64,
+    storage_misses: AtomicU64,
+    prefetched_slots: AtomicU64,
+    batched_requests: AtomicU64,
+}
+
+impl ForkMetrics {
+    pub(crate) fn record_account(&self, hit: bool) {
+        let counter = if hit { &self.account_hits } else { &self.account_misses };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_storage(&self, hit: bool) {
+        let counter = if hit { &self.storage_hits } else { &self.storage_misses };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_prefetch(&self, slots: usize) {
+        self.prefetched_slots.fetch_add(slots as u64, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_batch(&self) {
+        self.batched_requests.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Returns the number of account requests served from the cache.
+    pub fn account_hits(&self) -> u64 {
+        self.account_hits.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of account requests fetched from the endpoint.
+    pub fn account_misses(&self) -> u64 {
+        self.account_misses.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of storage requests served from the cache.
+    pub fn storage_hits(&self) -> u64 {
+        self.storage_hits.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of storage requests fetched from the endpoint.
+    pub fn storage_misses(&self) -> u64 {
+        self.storage_misses.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of storage slots fetched speculatively.
+    pub fn prefetched_slots(&self) -> u64 {
+        self.prefetched_slots.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of requests that fetched multiple storage slots at once.
+    pub fn batched_requests(&self) -> u64 {
+        self.batched_requests.load(Ordering::Relaxed)
+    }
+
+    /// Returns the ratio of account and storage requests served from the cache, or `None` if there
+    /// were no requests.
+    pub fn hit_rate(&self) -> Option<f64> {
+        let hits = self.account_hits() + self.storage_hits();
+        let total = hits + self.account_misses() + self.storage_misses();
+        (total > 0).then(|| hits as f64 / total as f64)
+    }
+}
+
+impl fmt::Display for ForkMetrics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "accounts: {} hits, {} misses; storage: {} hits, {} misses; {} prefetched slots, {} batched requests",
+            self.account_hits(),
+            self.account_misses(),
+            self.storage_hits(),
+            self.storage_misses(),
+            self.prefetched_slots(),
+            self.batched_requests(),
+        )?;
+        if let Some(rate) = self.hit_rate() {
+            write!(f, "; hit rate: {:.2}%", rate * 100.0)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_compute_hit_rate() {
+        let metrics = ForkMetrics::default();
+        assert_eq!(metrics.hit_rate(), None);
+
+        metrics.record_account(false);
+        metrics.record_storage(false);
+        metrics.record_storage(true);
+        metrics.record_storage(true);
+        assert_eq!(metrics.hit_rate(), Some(0.5));
+        assert!(metrics.to_string().ends_with("hit rate: 50.00%"));
+    }
+}
@@ -7,6 +7,12 @@ pub use backend::{BackendHandler, SharedBackend};
 mod init;
 pub use init::environment;
 
+mod metrics;
+pub use metrics::ForkMetrics;
+
+mod usage;
+pub use usage::{request_weight, RpcBudgetExceeded, RpcUsage, RpcUsageRegistry, RpcUsageTracker};
+
 mod cache;
 pub use cache::{
     BlockchainDb, BlockchainDbMeta, FlushJsonBlockCacheDB, JsonBlockCacheDB, JsonBlockCacheData,
@@ -15,6 +21,9 @@ pub use cache::{
 
 pub mod database;
 
+mod state;
+pub use state::{ForkState, ForkStateAccount};
+
 mod multi;
 pub use multi::{ForkId, MultiFork, MultiForkHandler};
 
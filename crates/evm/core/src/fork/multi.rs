@@ -3,9 +3,12 @@
 //! The design is similar to the single `SharedBackend`, `BackendHandler` but supports multiple
 //! concurrently active pairs at once.
 
-use crate::fork::{BackendHandler, BlockchainDb, BlockchainDbMeta, CreateFork, SharedBackend};
+use crate::fork::{
+    usage::RpcAccounting, BackendHandler, BlockchainDb, BlockchainDbMeta, CreateFork,
+    RpcUsageRegistry, SharedBackend,
+};
 use foundry_common::provider::{
-    runtime_transport::RuntimeTransport, tower::RetryBackoffService, ProviderBuilder, RetryProvider,
+    fallback::FallbackTransport, tower::RetryBackoffService, ProviderBuilder, RetryProvider,
 };
 use foundry_config::Config;
 use futures::{
@@ -71,6 +74,8 @@ pub struct MultiFork {
     handler: Sender<Request>,
     /// Ensures that all rpc resources get flushed properly
     _shutdown: Arc<ShutDownMultiFork>,
+    /// RPC usage of all forks
+    rpc_usage: Arc<RpcUsageRegistry>,
 }
 
 impl MultiFork {
@@ -110,7 +115,14 @@ impl MultiFork {
     pub fn new() -> (Self, MultiForkHandler) {
         let (handler, handler_rx) = channel(1);
         let _shutdown = Arc::new(ShutDownMultiFork { handler: Some(handler.clone()) });
-        (Self { handler, _shutdown }, MultiForkHandler::new(handler_rx))
+        let rpc_usage = Arc::new(RpcUsageRegistry::default());
+        let fork_handler = MultiForkHandler::new(handler_rx, Arc::clone(&rpc_usage));
+        (Self { handler, _shutdown, rpc_usage }, fork_handler)
+    }
+
+    /// Returns the RPC usage of all forks created by this `MultiFork`
+    pub fn rpc_usage(&self) -> &Arc<RpcUsageRegistry> {
+        &self.rpc_usage
     }
 
     /// Returns a fork backend
@@ -171,7 +183,7 @@ impl MultiFork {
     }
 }
 
-type Handler = BackendHandler<RetryBackoffService<RuntimeTransport>, Arc<RetryProvider>>;
+type Handler = BackendHandler<RetryBackoffService<FallbackTransport>, Arc<RetryProvider>>;
 
 type CreateFuture =
     Pin<Box<dyn Future<Output = eyre::Result<(ForkId, CreatedFork, Handler)>> + Send>>;
@@ -222,16 +234,20 @@ pub struct MultiForkHandler {
 
     /// Optional periodic interval to flush rpc cache
     flush_cache_interval: Option<tokio::time::Interval>,
+
+    /// RPC usage of all forks, shared with the `MultiFork`
+    rpc_usage: Arc<RpcUsageRegistry>,
 }
 
 impl MultiForkHandler {
-    fn new(incoming: Receiver<Request>) -> Self {
+    fn new(incoming: Receiver<Request>, rpc_usage: Arc<RpcUsageRegistry>) -> Self {
         Self {
             incoming: incoming.fuse(),
             handlers: Default::default(),
             pending_tasks: Default::default(),
             forks: Default::default(),
             flush_cache_interval: None,
+            rpc_usage,
         }
     }
 
@@ -266,7 +282,8 @@ impl MultiForkHandler {
         }
 
         // need to create a new fork
-        let task = Box::pin(create_fork(fork));
+        let rpc = self.rpc_usage.accounting(&fork.url, fork.evm_opts.rpc_budget);
+        let task = Box::pin(create_fork(fork, rpc));
         self.pending_tasks.push(ForkTask::Create(task, fork_id, sender, Vec::new()));
     }
 
@@ -300,6 +317,8 @@ impl MultiForkHandler {
                     trace!(target: "fork::multi", "rolling {} to {}", fork_id, block);
                     let mut opts = fork.opts.clone();
                     opts.evm_opts.fork_block_number = Some(block);
+                    // the state snapshot only applies to the block the fork was created at
+                    opts.evm_opts.fork_state = None;
                     self.create_fork(opts, sender)
                 } else {
                     let _ = sender.send(Err(eyre::eyre!("No matching fork exits for {}", fork_id)));
@@ -482,11 +501,15 @@ impl Drop for ShutDownMultiFork {
 /// Creates a new fork
 ///
 /// This will establish a new `Provider` to the endpoint and return the Fork Backend
-async fn create_fork(mut fork: CreateFork) -> eyre::Result<(ForkId, CreatedFork, Handler)> {
+async fn create_fork(
+    mut fork: CreateFork,
+    rpc: RpcAccounting,
+) -> eyre::Result<(ForkId, CreatedFork, Handler)> {
     let provider = Arc::new(
         ProviderBuilder::new(fork.url.as_str())
             .maybe_max_retry(fork.evm_opts.fork_retries)
             .maybe_initial_backoff(fork.evm_opts.fork_retry_backoff)
+            .maybe_hedge_delay(fork.evm_opts.fork_hedge_delay)
             .compute_units_per_second(fork.evm_opts.get_compute_units_per_second())
             .build()?,
     );
@@ -507,8 +530,13 @@ async fn create_fork(mut fork: CreateFork) -> eyre::Result<(ForkId, CreatedFork,
         None
     };
 
-    let db = BlockchainDb::new(meta, cache_path);
-    let (backend, handler) = SharedBackend::new(provider, db, Some(number.into()));
+    let db = match fork.evm_opts.fork_state.clone() {
+        Some(state_path) => BlockchainDb::new_with_fork_state(meta, cache_path, state_path),
+        None => BlockchainDb::new(meta, cache_path),
+    };
+    let (backend, mut handler) = SharedBackend::new(provider, db, Some(number.into()));
+    handler.set_prefetch(fork.evm_opts.fork_prefetch.unwrap_or_default());
+    handler.set_rpc_accounting(rpc);
     let fork = CreatedFork::new(fork, backend);
     let fork_id = ForkId::new(&fork.opts.url, number.into());
 
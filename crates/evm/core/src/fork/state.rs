@@ -0,0 +1,161 @@
+//! Persistent snapshots of the state fetched by a fork.
+
+use crate::fork::MemDb;
+use alloy_primitives::{keccak256, Address, Bytes, B256, U256};
+use eyre::WrapErr;
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use revm::primitives::{AccountInfo, BlockEnv, Bytecode, KECCAK_EMPTY};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fs,
+    io::{BufWriter, Read, Write},
+    path::Path,
+};
+
+/// A snapshot of the accounts, storage slots and block hashes fetched by a fork.
+///
+/// Snapshots are stored as gzip-compressed JSON in the same format as anvil's state dumps, so a
+/// snapshot can be loaded into anvil with `--load-state`, and an anvil state dump can be used as a
+/// fork state snapshot. Uncompressed snapshots are also accepted when reading.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ForkState {
+    /// The block environment of the fork.
+    pub block: Option<BlockEnv>,
+    /// The fetched accounts and their storage.
+    pub accounts: BTreeMap<Address, ForkStateAccount>,
+    /// The fetched block hashes.
+    #[serde(default)]
+    pub block_hashes: BTreeMap<U256, B256>,
+}
+
+/// An account of a [ForkState].
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ForkStateAccount {
+    pub nonce: u64,
+    pub balance: U256,
+    pub code: Bytes,
+    pub storage: BTreeMap<U256, U256>,
+}
+
+impl ForkState {
+    /// Creates a snapshot of the given database at the given block.
+    pub fn new(db: &MemDb, block: BlockEnv) -> Self {
+        let storage = db.storage.read();
+        let accounts = db
+            .accounts
+            .read()
+            .iter()
+            .map(|(address, info)| {
+                let account = ForkStateAccount {
+                    nonce: info.nonce,
+                    balance: info.balance,
+                    code: info.code.as_ref().map(|code| code.original_bytes()).unwrap_or_default(),
+                    storage: storage
+                        .get(address)
+                        .map(|slots| slots.iter().map(|(k, v)| (*k, *v)).collect())
+                        .unwrap_or_default(),
+                };
+                (*address, account)
+            })
+            .collect();
+        let block_hashes = db.block_hashes.read().iter().map(|(k, v)| (*k, *v)).collect();
+        Self { block: Some(block), accounts, block_hashes }
+    }
+
+    /// Returns true if this snapshot can be used for a fork at the given block number.
+    ///
+    /// Snapshots without block information are assumed to match.
+    pub fn matches(&self, number: U256) -> bool {
+        self.block.as_ref().map_or(true, |block| block.number == number)
+    }
+
+    /// Inserts the contents of the snapshot into the given database.
+    pub fn apply(self, db: &MemDb) {
+        let mut accounts = db.accounts.write();
+        let mut storage = db.storage.write();
+        for (address, account) in self.accounts {
+            let code_hash =
+                if account.code.is_empty() { KECCAK_EMPTY } else { keccak256(&account.code) };
+            accounts.insert(
+                address,
+                AccountInfo {
+                    balance: account.balance,
+                    nonce: account.nonce,
+                    code_hash,
+                    code: Some(Bytecode::new_raw(account.code)),
+                },
+            );
+            if !account.storage.is_empty() {
+                storage.entry(address).or_default().extend(account.storage);
+            }
+        }
+        db.block_hashes.write().extend(self.block_hashes);
+    }
+
+    /// Reads a snapshot from the given file.
+    pub fn read(path: &Path) -> eyre::Result<Self> {
+        let contents =
+            fs::read(path).wrap_err_with(|| format!("failed to read {}", path.display()))?;
+        let mut decoder = GzDecoder::new(contents.as_slice());
+        let state = if decoder.header().is_some() {
+            let mut decoded = Vec::new();
+            decoder.read_to_end(&mut decoded)?;
+            serde_json::from_slice(&decoded)
+        } else {
+            serde_json::from_slice(&contents)
+        };
+        state.wrap_err_with(|| format!("failed to decode fork state {}", path.display()))
+    }
+
+    /// Writes the snapshot to the given file, creating parent directories if needed.
+    pub fn write(&self, path: &Path) -> eyre::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let file = fs::File::create(path)?;
+        let mut encoder = GzEncoder::new(BufWriter::new(file), Compression::default());
+        serde_json::to_writer(&mut encoder, self)?;
+        encoder.finish()?.flush()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_roundtrip_fork_state() {
+        let address = Address::with_last_byte(1);
+        let db = MemDb::default();
+        db.do_insert_account(
+            address,
+            AccountInfo {
+                balance: U256::from(100),
+                nonce: 1,
+                code_hash: keccak256([0x00]),
+                code: Some(Bytecode::new_raw(Bytes::from_static(&[0x00]))),
+            },
+        );
+        db.storage.write().entry(address).or_default().insert(U256::from(1), U256::from(2));
+        db.block_hashes.write().insert(U256::from(9), B256::with_last_byte(9));
+
+        let block = BlockEnv { number: U256::from(10), ..Default::default() };
+        let state = ForkState::new(&db, block);
+        assert!(state.matches(U256::from(10)));
+        assert!(!state.matches(U256::from(11)));
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state.json.gz");
+        state.write(&path).unwrap();
+
+        let loaded = MemDb::default();
+        ForkState::read(&path).unwrap().apply(&loaded);
+        let info = loaded.accounts.read()[&address].clone();
+        assert_eq!(info.balance, U256::from(100));
+        assert_eq!(info.code_hash, keccak256([0x00]));
+        assert_eq!(loaded.storage.read()[&address][&U256::from(1)], U256::from(2));
+        assert_eq!(loaded.block_hashes.read()[&U256::from(9)], B256::with_last_byte(9));
+    }
+}
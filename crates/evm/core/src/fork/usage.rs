@@ -0,0 +1,213 @@
+//! Accounting of the RPC requests sent by fork backends.
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::{
+    cell::RefCell,
+    collections::BTreeMap,
+    fmt,
+    ops::AddAssign,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
+
+/// Returns the weight of a request to the given RPC method, in compute units.
+///
+/// The weights follow the compute units that metered RPC plans commonly charge. Other methods are
+/// weighted like a storage request.
+pub fn request_weight(method: &str) -> u64 {
+    match method {
+        "eth_chainId" | "net_version" => 0,
+        "eth_blockNumber" => 10,
+        "eth_getBlockByNumber" | "eth_getBlockByHash" => 16,
+        "eth_getStorageAt" | "eth_getTransactionByHash" => 17,
+        "eth_getBalance" => 19,
+        "eth_getProof" => 21,
+        "eth_getCode" | "eth_getTransactionCount" => 26,
+        _ => 17,
+    }
+}
+
+/// The number and the total weight of RPC requests.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct RpcUsage {
+    /// The number of requests.
+    pub requests: u64,
+    /// The total weight of the requests, in compute units.
+    pub weight: u64,
+}
+
+impl RpcUsage {
+    /// Returns whether no requests were made.
+    pub fn is_empty(&self) -> bool {
+        self.requests == 0
+    }
+}
+
+impl AddAssign for RpcUsage {
+    fn add_assign(&mut self, other: Self) {
+        self.requests += other.requests;
+        self.weight += other.weight;
+    }
+}
+
+impl fmt::Display for RpcUsage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = if self.requests == 1 { "" } else { "s" };
+        write!(f, "{} request{s}, {} CU", self.requests, self.weight)
+    }
+}
+
+thread_local! {
+    static SCOPE: RefCell<Option<Arc<RpcUsageTracker>>> = const { RefCell::new(None) };
+}
+
+/// Counts RPC requests, shared between threads.
+#[derive(Debug, Default)]
+pub struct RpcUsageTracker {
+    requests: AtomicU64,
+    weight: AtomicU64,
+}
+
+impl RpcUsageTracker {
+    /// Records `requests` requests with a total weight of `weight` compute units.
+    pub fn record(&self, requests: u64, weight: u64) {
+        self.requests.fetch_add(requests, Ordering::Relaxed);
+        self.weight.fetch_add(weight, Ordering::Relaxed);
+    }
+
+    /// Returns the recorded usage.
+    pub fn usage(&self) -> RpcUsage {
+        RpcUsage {
+            requests: self.requests.load(Ordering::Relaxed),
+            weight: self.weight.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Runs `f` with this tracker as the scope of the current thread.
+    ///
+    /// The fork requests that `f` misses the cache with are recorded in the tracker, even though
+    /// they are sent by the backend thread of the fork.
+    pub fn scope<R>(self: &Arc<Self>, f: impl FnOnce() -> R) -> R {
+        let previous = SCOPE.with(|scope| scope.replace(Some(Arc::clone(self))));
+        let result = f();
+        SCOPE.with(|scope| *scope.borrow_mut() = previous);
+        result
+    }
+
+    /// Returns the tracker of the scope of the current thread, if any.
+    pub(crate) fn current() -> Option<Arc<Self>> {
+        SCOPE.with(|scope| scope.borrow().clone())
+    }
+}
+
+/// The RPC usage of all forks of a test run, in total and by provider.
+#[derive(Debug, Default)]
+pub struct RpcUsageRegistry {
+    total: RpcUsageTracker,
+    providers: Mutex<BTreeMap<String, Arc<RpcUsageTracker>>>,
+    /// Set once a request would have exceeded the budget
+    exceeded: Mutex<Option<RpcBudgetExceeded>>,
+}
+
+impl RpcUsageRegistry {
+    /// Returns the total usage of all providers.
+    pub fn total(&self) -> RpcUsage {
+        self.total.usage()
+    }
+
+    /// Returns the usage of each provider, keyed by URL.
+    pub fn providers(&self) -> BTreeMap<String, RpcUsage> {
+        self.providers.lock().iter().map(|(url, tracker)| (url.clone(), tracker.usage())).collect()
+    }
+
+    /// Returns the error of the first request that would have exceeded the budget, if any.
+    pub fn budget_exceeded(&self) -> Option<RpcBudgetExceeded> {
+        *self.exceeded.lock()
+    }
+
+    /// Returns the accounting of a fork of the provider at `url`, with the given budget for the
+    /// whole run.
+    pub(crate) fn accounting(self: &Arc<Self>, url: &str, budget: Option<u64>) -> RpcAccounting {
+        let provider = Arc::clone(self.providers.lock().entry(url.to_string()).or_default());
+        RpcAccounting { registry: Arc::clone(self), provider, budget }
+    }
+}
+
+/// Records the requests that a fork backend sends to its provider.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct RpcAccounting {
+    registry: Arc<RpcUsageRegistry>,
+    provider: Arc<RpcUsageTracker>,
+    /// The maximum total weight of the requests of the run
+    budget: Option<u64>,
+}
+
+impl RpcAccounting {
+    /// Records requests to the given methods, also in `scope` if set.
+    ///
+    /// Fails without recording anything if the requests would exceed the budget of the run.
+    pub(crate) fn charge(
+        &self,
+        methods: &[&str],
+        scope: Option<&RpcUsageTracker>,
+    ) -> Result<(), RpcBudgetExceeded> {
+        let requests = methods.len() as u64;
+        let weight = methods.iter().map(|method| request_weight(method)).sum();
+        if let Some(budget) = self.budget {
+            if self.registry.total().weight + weight > budget {
+                let err = RpcBudgetExceeded(budget);
+                self.registry.exceeded.lock().get_or_insert(err);
+                return Err(err);
+            }
+        }
+        self.registry.total.record(requests, weight);
+        self.provider.record(requests, weight);
+        if let Some(scope) = scope {
+            scope.record(requests, weight);
+        }
+        Ok(())
+    }
+}
+
+/// The error of the fork requests that would exceed the RPC budget of the run.
+#[derive(Clone, Copy, Debug, thiserror::Error)]
+#[error(
+    "RPC budget of {0} compute units exceeded; raise `rpc_budget` or reduce the fork requests of \
+     the tests"
+)]
+pub struct RpcBudgetExceeded(pub u64);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn enforces_budget() {
+        let registry = Arc::new(RpcUsageRegistry::default());
+        let accounting = registry.accounting("http://localhost:8545", Some(40));
+        let scope = Arc::new(RpcUsageTracker::default());
+
+        accounting.charge(&["eth_getStorageAt", "eth_getBalance"], Some(&*scope)).unwrap();
+        assert_eq!(scope.usage(), RpcUsage { requests: 2, weight: 36 });
+
+        let err = accounting.charge(&["eth_getCode"], None).unwrap_err();
+        assert!(err.to_string().starts_with("RPC budget of 40 compute units exceeded"));
+        assert!(registry.budget_exceeded().is_some());
+        assert_eq!(registry.total(), RpcUsage { requests: 2, weight: 36 });
+        assert_eq!(registry.providers()["http://localhost:8545"].requests, 2);
+    }
+
+    #[test]
+    fn scopes_are_thread_local() {
+        let scope = Arc::new(RpcUsageTracker::default());
+        assert!(RpcUsageTracker::current().is_none());
+        scope.scope(|| {
+            assert!(RpcUsageTracker::current().is_some());
+            std::thread::spawn(|| assert!(RpcUsageTracker::current().is_none())).join().unwrap();
+        });
+        assert!(RpcUsageTracker::current().is_none());
+    }
+}
@@ -0,0 +1,162 @@
+//! Customizable gas metering.
+
+use crate::InspectorExt;
+use alloy_primitives::U256;
+use revm::{
+    handler::register::{EvmHandler, HandleRegisterBox},
+    interpreter::Gas,
+    primitives::{EVMError, Env, InvalidTransaction},
+    Database,
+};
+use std::{fmt, sync::Arc};
+
+/// Adjusts how a transaction is charged, e.g. to emulate the gas schedule of an L2 without
+/// patching revm.
+///
+/// Every method receives the value computed by revm and returns the value to use instead, so the
+/// default implementation keeps the behavior unchanged.
+///
+/// A policy is installed on every EVM created for an inspector that returns it from
+/// [`InspectorExt::gas_policy`].
+pub trait GasPolicy: fmt::Debug + Send + Sync {
+    /// Returns the intrinsic gas of the transaction, charged before execution.
+    fn intrinsic_gas(&self, _env: &Env, gas: u64) -> u64 {
+        gas
+    }
+
+    /// Returns the gas refunded to the caller at the end of the transaction, given the gas used
+    /// and the refund computed by revm, already capped.
+    fn refund(&self, _env: &Env, gas: &Gas) -> i64 {
+        gas.refunded()
+    }
+
+    /// Returns a fee charged to the caller before execution on top of the gas, e.g. the cost of
+    /// posting the transaction data on L1.
+    fn additional_fee(&self, _env: &Env) -> U256 {
+        U256::ZERO
+    }
+}
+
+/// Returns a handler register applying the given [`GasPolicy`].
+pub fn gas_policy_handler_register<'a, EXT: 'a, DB: Database + 'a>(
+    policy: Arc<dyn GasPolicy>,
+) -> HandleRegisterBox<'a, EXT, DB> {
+    Box::new(move |handler| {
+        let policy_inner = policy.clone();
+        let old_handle = handler.validation.initial_tx_gas.clone();
+        handler.validation.initial_tx_gas =
+            Arc::new(move |env| -> Result<u64, EVMError<DB::Error>> {
+                let gas = old_handle(env)?;
+                let gas = policy_inner.intrinsic_gas(env, gas);
+                if gas > env.tx.gas_limit {
+                    return Err(InvalidTransaction::CallGasCostMoreThanGasLimit.into());
+                }
+                Ok(gas)
+            });
+
+        let policy_inner = policy.clone();
+        let old_handle = handler.pre_execution.deduct_caller.clone();
+        handler.pre_execution.deduct_caller =
+            Arc::new(move |ctx| -> Result<(), EVMError<DB::Error>> {
+                old_handle(ctx)?;
+                let fee = policy_inner.additional_fee(&ctx.evm.env);
+                if fee.is_zero() {
+                    return Ok(());
+                }
+                let disable_balance_check = ctx.evm.env.cfg.disable_balance_check;
+                let caller = ctx.evm.env.tx.caller;
+                let (caller, _) = ctx.evm.load_account(caller)?;
+                let balance = caller.info.balance;
+                caller.info.balance = match balance.checked_sub(fee) {
+                    Some(balance) => balance,
+                    None if disable_balance_check => U256::ZERO,
+                    None => {
+                        return Err(EVMError::Transaction(InvalidTransaction::LackOfFundForMaxFee {
+                            fee: Box::new(fee),
+                            balance: Box::new(balance),
+                        }))
+                    }
+                };
+                Ok(())
+            });
+
+        let policy_inner = policy.clone();
+        let old_handle = handler.execution.last_frame_return.clone();
+        handler.execution.last_frame_return =
+            Arc::new(move |ctx, frame_result| -> Result<(), EVMError<DB::Error>> {
+                old_handle(ctx, frame_result)?;
+                let gas = frame_result.gas_mut();
+                let refund = policy_inner.refund(&ctx.evm.env, gas);
+                gas.set_refund(refund);
+                Ok(())
+            });
+    })
+}
+
+/// Installs the gas policy of the inspector, if any, on the given handler.
+pub(crate) fn append_gas_policy<'a, DB, I>(handler: &mut EvmHandler<'a, I, DB>, inspector: &I)
+where
+    DB: Database + 'a,
+    I: InspectorExt<DB> + 'a,
+{
+    if let Some(policy) = inspector.gas_policy() {
+        handler.append_handler_register_box(gas_policy_handler_register(policy));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::new_evm_with_inspector;
+    use alloy_primitives::{Address, TxKind};
+    use revm::{
+        db::{CacheDB, EmptyDB},
+        primitives::{AccountInfo, EnvWithHandlerCfg, HandlerCfg, SpecId},
+        Inspector,
+    };
+
+    #[derive(Debug)]
+    struct RollupPolicy;
+
+    impl GasPolicy for RollupPolicy {
+        fn intrinsic_gas(&self, env: &Env, gas: u64) -> u64 {
+            gas + 16 * env.tx.data.len() as u64
+        }
+
+        fn additional_fee(&self, _env: &Env) -> U256 {
+            U256::from(1000)
+        }
+    }
+
+    struct PolicyInspector(Arc<dyn GasPolicy>);
+
+    impl<DB: Database> Inspector<DB> for PolicyInspector {}
+
+    impl<DB: Database> InspectorExt<DB> for PolicyInspector {
+        fn gas_policy(&self) -> Option<Arc<dyn GasPolicy>> {
+            Some(self.0.clone())
+        }
+    }
+
+    #[test]
+    fn applies_gas_policy() {
+        let caller = Address::with_last_byte(1);
+        let balance = U256::from(1_000_000);
+        let mut db = CacheDB::new(EmptyDB::default());
+        db.insert_account_info(caller, AccountInfo { balance, ..Default::default() });
+
+        let mut env = Env::default();
+        env.tx.caller = caller;
+        env.tx.transact_to = TxKind::Call(Address::with_last_byte(2));
+        env.tx.data = vec![1; 10].into();
+        env.tx.gas_limit = 100_000;
+        let env = EnvWithHandlerCfg::new(Box::new(env), HandlerCfg::new(SpecId::CANCUN));
+
+        let mut evm = new_evm_with_inspector(&mut db, env, PolicyInspector(Arc::new(RollupPolicy)));
+        let result = evm.transact().unwrap();
+        // The base cost and 16 gas per non-zero calldata byte, charged by both revm and the policy.
+        assert_eq!(result.result.gas_used(), 21_000 + 2 * 160);
+        let caller = &result.state[&caller];
+        assert_eq!(caller.info.balance, balance - U256::from(1000));
+    }
+}
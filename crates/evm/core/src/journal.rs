@@ -0,0 +1,216 @@
+//! Helpers to inspect the journal of the EVM.
+//!
+//! Inspectors can [mark](JournalExt::journal_mark) the journal at the start of a call frame, and
+//! later collect the [state changes](JournalExt::changes_since) made since then, e.g. in
+//! `call_end`, to attribute them to that frame.
+
+use alloy_primitives::{Address, U256};
+use revm::{Database, InnerEvmContext, JournalEntry, JournaledState};
+
+/// A position in the journal of the EVM.
+///
+/// A mark stays valid across the reverts of nested frames, whose entries are removed from the
+/// journal and so are not part of the changes since the mark. It is invalidated when the frame it
+/// was taken in is reverted.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct JournalMark {
+    /// The number of journal checkpoints at the time of the mark.
+    journal_i: usize,
+    /// The number of entries in the last checkpoint at the time of the mark.
+    entry_i: usize,
+}
+
+/// A state change recorded in the journal.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum StateChange {
+    /// A storage slot was written.
+    Storage {
+        /// The account the slot belongs to.
+        address: Address,
+        /// The slot.
+        slot: U256,
+        /// The value of the slot before the write.
+        previous: U256,
+        /// The current value of the slot.
+        current: U256,
+    },
+    /// Value was transferred between two accounts.
+    Balance {
+        /// The sender of the value.
+        from: Address,
+        /// The receiver of the value.
+        to: Address,
+        /// The amount transferred.
+        value: U256,
+    },
+    /// An account was created.
+    AccountCreated {
+        /// The created account.
+        address: Address,
+    },
+    /// An account was self-destructed.
+    AccountDestroyed {
+        /// The destroyed account.
+        address: Address,
+        /// The account that received its balance.
+        target: Address,
+        /// The balance of the destroyed account.
+        balance: U256,
+    },
+    /// The nonce of an account was bumped.
+    Nonce {
+        /// The account.
+        address: Address,
+    },
+    /// The code of an account was set.
+    Code {
+        /// The account.
+        address: Address,
+    },
+}
+
+/// An extension trait to mark the journal and collect the state changes made since a mark.
+pub trait JournalExt {
+    /// Returns the journaled state.
+    fn journaled_state(&self) -> &JournaledState;
+
+    /// Marks the current position of the journal.
+    fn journal_mark(&self) -> JournalMark {
+        let journal = &self.journaled_state().journal;
+        JournalMark {
+            journal_i: journal.len(),
+            entry_i: journal.last().map(Vec::len).unwrap_or_default(),
+        }
+    }
+
+    /// Returns the journal entries recorded since the given mark, in order.
+    fn journal_entries_since(&self, mark: JournalMark) -> Vec<&JournalEntry> {
+        let journal = &self.journaled_state().journal;
+        let mut entries = Vec::new();
+        if let Some(checkpoint) = mark.journal_i.checked_sub(1).and_then(|i| journal.get(i)) {
+            entries.extend(checkpoint.iter().skip(mark.entry_i));
+        }
+        entries.extend(journal.iter().skip(mark.journal_i).flatten());
+        entries
+    }
+
+    /// Returns the storage writes, balance transfers, account creations and destructions, nonce
+    /// and code changes recorded since the given mark, in order.
+    ///
+    /// Warming and touching accounts and slots, and transient storage writes, are not state
+    /// changes and are skipped. Storage writes have the current value of the slot, so a slot
+    /// written multiple times shows up with the same current value for each write.
+    fn changes_since(&self, mark: JournalMark) -> Vec<StateChange> {
+        let state = &self.journaled_state().state;
+        self.journal_entries_since(mark)
+            .into_iter()
+            .filter_map(|entry| {
+                Some(match *entry {
+                    JournalEntry::StorageChanged { address, key, had_value } => {
+                        let current = state
+                            .get(&address)
+                            .and_then(|account| account.storage.get(&key))
+                            .map(|slot| slot.present_value)
+                            .unwrap_or_default();
+                        StateChange::Storage { address, slot: key, previous: had_value, current }
+                    }
+                    JournalEntry::BalanceTransfer { from, to, balance } => {
+                        StateChange::Balance { from, to, value: balance }
+                    }
+                    JournalEntry::AccountCreated { address } => {
+                        StateChange::AccountCreated { address }
+                    }
+                    JournalEntry::AccountDestroyed { address, target, had_balance, .. } => {
+                        StateChange::AccountDestroyed { address, target, balance: had_balance }
+                    }
+                    JournalEntry::NonceChange { address } => StateChange::Nonce { address },
+                    JournalEntry::CodeChange { address } => StateChange::Code { address },
+                    JournalEntry::AccountWarmed { .. } |
+                    JournalEntry::AccountTouched { .. } |
+                    JournalEntry::StorageWarmed { .. } |
+                    JournalEntry::TransientStorageChange { .. } => return None,
+                })
+            })
+            .collect()
+    }
+}
+
+impl JournalExt for JournaledState {
+    fn journaled_state(&self) -> &JournaledState {
+        self
+    }
+}
+
+impl<DB: Database> JournalExt for InnerEvmContext<DB> {
+    fn journaled_state(&self) -> &JournaledState {
+        &self.journaled_state
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::address;
+    use revm::primitives::{Account, EvmStorageSlot, SpecId};
+
+    const ALICE: Address = address!("00000000000000000000000000000000000a11ce");
+    const BOB: Address = address!("0000000000000000000000000000000000000b0b");
+
+    fn journaled_state() -> JournaledState {
+        let mut state = JournaledState::new(SpecId::CANCUN, Default::default());
+        let mut account = Account::default();
+        account
+            .storage
+            .insert(U256::from(1), EvmStorageSlot::new_changed(U256::ZERO, U256::from(7)));
+        state.state.insert(ALICE, account);
+        state.journal.push(vec![JournalEntry::AccountWarmed { address: ALICE }]);
+        state
+    }
+
+    #[test]
+    fn collects_changes_since_mark() {
+        let mut state = journaled_state();
+        let mark = state.journal_mark();
+        state.journal.last_mut().unwrap().push(JournalEntry::StorageChanged {
+            address: ALICE,
+            key: U256::from(1),
+            had_value: U256::ZERO,
+        });
+        // A nested frame.
+        state.journal.push(vec![
+            JournalEntry::StorageWarmed { address: BOB, key: U256::ZERO },
+            JournalEntry::BalanceTransfer { from: ALICE, to: BOB, balance: U256::from(5) },
+        ]);
+
+        assert_eq!(state.journal_entries_since(mark).len(), 3);
+        assert_eq!(
+            state.changes_since(mark),
+            vec![
+                StateChange::Storage {
+                    address: ALICE,
+                    slot: U256::from(1),
+                    previous: U256::ZERO,
+                    current: U256::from(7),
+                },
+                StateChange::Balance { from: ALICE, to: BOB, value: U256::from(5) },
+            ]
+        );
+
+        let inner = state.journal_mark();
+        assert!(state.changes_since(inner).is_empty());
+    }
+
+    #[test]
+    fn mark_survives_revert() {
+        let mut state = journaled_state();
+        let mark = state.journal_mark();
+        state.journal.push(vec![JournalEntry::NonceChange { address: ALICE }]);
+
+        // Reverting a nested frame removes its checkpoint.
+        state.journal.pop();
+        assert!(state.changes_since(mark).is_empty());
+
+        state.journal.push(vec![JournalEntry::AccountCreated { address: BOB }]);
+        assert_eq!(state.changes_since(mark), vec![StateChange::AccountCreated { address: BOB }]);
+    }
+}
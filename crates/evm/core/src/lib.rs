@@ -8,6 +8,7 @@
 use auto_impl::auto_impl;
 use revm::{inspectors::NoOpInspector, interpreter::CreateInputs, Database, EvmContext, Inspector};
 use revm_inspectors::access_list::AccessListInspector;
+use std::sync::Arc;
 
 #[macro_use]
 extern crate tracing;
@@ -22,10 +23,17 @@ mod ic;
 pub mod backend;
 pub mod constants;
 pub mod decode;
+pub mod eip7702;
 pub mod fork;
+pub mod gas;
+pub mod journal;
 pub mod opcodes;
 pub mod opts;
+pub mod outcome;
+pub mod precompiles;
+pub mod rng;
 pub mod snapshot;
+pub mod tx_type;
 pub mod utils;
 
 /// An extension trait that allows us to add additional hooks to Inspector for later use in
@@ -43,6 +51,18 @@ pub trait InspectorExt<DB: Database>: Inspector<DB> {
     ) -> bool {
         false
     }
+
+    /// Returns the [`GasPolicy`](gas::GasPolicy) applied to the transactions executed with this
+    /// inspector, if any.
+    fn gas_policy(&self) -> Option<Arc<dyn gas::GasPolicy>> {
+        None
+    }
+
+    /// Returns the [`CustomPrecompiles`](precompiles::CustomPrecompiles) installed on the EVMs
+    /// created for this inspector, if any.
+    fn custom_precompiles(&self) -> Option<Arc<precompiles::CustomPrecompiles>> {
+        None
+    }
 }
 
 impl<DB: Database> InspectorExt<DB> for NoOpInspector {}
@@ -4,10 +4,15 @@ use alloy_primitives::{Address, B256, U256};
 use alloy_provider::Provider;
 use alloy_rpc_types::Block;
 use eyre::WrapErr;
-use foundry_common::{provider::ProviderBuilder, ALCHEMY_FREE_TIER_CUPS};
+use foundry_common::{
+    progress::{self, Phase},
+    provider::ProviderBuilder,
+    ALCHEMY_FREE_TIER_CUPS,
+};
 use foundry_config::{Chain, Config};
 use revm::primitives::{BlockEnv, CfgEnv, TxEnv};
 use serde::{Deserialize, Deserializer, Serialize};
+use std::path::PathBuf;
 
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct EvmOpts {
@@ -16,7 +21,13 @@ pub struct EvmOpts {
     pub env: Env,
 
     /// Fetch state over a remote instead of starting from empty state.
-    #[serde(rename = "eth_rpc_url")]
+    ///
+    /// This can also be a list of endpoints, see [`foundry_config::RPC_URL_SEPARATOR`].
+    #[serde(
+        rename = "eth_rpc_url",
+        default,
+        deserialize_with = "foundry_config::deserialize_rpc_urls"
+    )]
     pub fork_url: Option<String>,
 
     /// Pins the block number for the state fork.
@@ -28,6 +39,20 @@ pub struct EvmOpts {
     /// Initial retry backoff.
     pub fork_retry_backoff: Option<u64>,
 
+    /// The delay in milliseconds after which a slow request is also sent to the next endpoint of
+    /// the fork URL list.
+    pub fork_hedge_delay: Option<u64>,
+
+    /// The file the state of the fork is loaded from and saved to.
+    ///
+    /// See [`ForkState`](crate::fork::ForkState).
+    pub fork_state: Option<PathBuf>,
+
+    /// The number of storage slots to prefetch speculatively when forking.
+    ///
+    /// See [`BackendHandler::set_prefetch`](crate::fork::BackendHandler::set_prefetch).
+    pub fork_prefetch: Option<usize>,
+
     /// The available compute units per second.
     ///
     /// See also <https://docs.alchemy.com/reference/compute-units#what-are-cups-compute-units-per-second>
@@ -36,6 +61,11 @@ pub struct EvmOpts {
     /// Disables RPC rate limiting entirely.
     pub no_rpc_rate_limit: bool,
 
+    /// The maximum total weight, in compute units, of the RPC requests of all forks.
+    ///
+    /// See [`RpcUsageRegistry`](crate::fork::RpcUsageRegistry).
+    pub rpc_budget: Option<u64>,
+
     /// Disables storage caching entirely.
     pub no_storage_caching: bool,
 
@@ -88,7 +118,10 @@ impl EvmOpts {
         let provider = ProviderBuilder::new(fork_url)
             .compute_units_per_second(self.get_compute_units_per_second())
             .build()?;
-        environment(
+        // The fork url is not reported as it may contain an API key.
+        let spinner = progress::spinner("Fetching fork environment");
+        progress::event(Phase::Fork, "start", "fetching fork environment");
+        let result = environment(
             &provider,
             self.memory_limit,
             self.env.gas_price.map(|v| v as u128),
@@ -100,7 +133,17 @@ impl EvmOpts {
         .await
         .wrap_err_with(|| {
             format!("Could not instantiate forked environment with fork url: {fork_url}")
-        })
+        });
+        spinner.finish_and_clear();
+        match &result {
+            Ok((env, _)) => progress::event(
+                Phase::Fork,
+                "finish",
+                format_args!("chain {} at block {}", env.cfg.chain_id, env.block.number),
+            ),
+            Err(_) => progress::event(Phase::Fork, "error", "could not fetch fork environment"),
+        }
+        result
     }
 
     /// Returns the `revm::Env` configured with only local settings
@@ -0,0 +1,200 @@
+//! Helpers to override the result of a frame from an inspector.
+//!
+//! Mocking and fault injection inspectors end frames early from [`Inspector::call`],
+//! [`Inspector::create`] or [`Inspector::step`], or replace the result of a frame in
+//! [`Inspector::call_end`]. [`FrameOverride`] builds the [`InterpreterResult`] of such frames,
+//! keeping the instruction result, output and gas consistent with each other.
+//!
+//! [`Inspector::call`]: revm::Inspector::call
+//! [`Inspector::create`]: revm::Inspector::create
+//! [`Inspector::step`]: revm::Inspector::step
+//! [`Inspector::call_end`]: revm::Inspector::call_end
+
+use crate::abi::Vm;
+use alloy_primitives::Bytes;
+use alloy_sol_types::{Revert, SolError};
+use revm::interpreter::{
+    CallInputs, CallOutcome, CreateInputs, CreateOutcome, Gas, InstructionResult, Interpreter,
+    InterpreterAction, InterpreterResult,
+};
+
+/// How to end a frame instead of, or in place of, its execution.
+///
+/// Frames ending with [`FrameOverride::return_data`] or a revert leave the unused gas to the
+/// caller, while halts like [`FrameOverride::out_of_gas`] consume all of it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[must_use]
+pub struct FrameOverride {
+    result: InstructionResult,
+    output: Bytes,
+    /// The gas used by the frame, if overridden.
+    gas_used: Option<u64>,
+}
+
+impl FrameOverride {
+    /// Ends the frame successfully, returning `output`.
+    pub fn return_data(output: impl Into<Bytes>) -> Self {
+        Self { result: InstructionResult::Return, output: output.into(), gas_used: None }
+    }
+
+    /// Ends the frame with a revert, returning the raw revert data `output`.
+    pub fn revert(output: impl Into<Bytes>) -> Self {
+        Self { result: InstructionResult::Revert, output: output.into(), gas_used: None }
+    }
+
+    /// Ends the frame with a revert, returning the ABI encoded custom `error`.
+    pub fn revert_error<E: SolError>(error: &E) -> Self {
+        Self::revert(error.abi_encode())
+    }
+
+    /// Ends the frame with a revert, returning `message` encoded as `Error(string)`, like
+    /// `require` and `revert` with a reason string.
+    pub fn revert_message(message: impl Into<String>) -> Self {
+        Self::revert_error(&Revert::from(message.into()))
+    }
+
+    /// Ends the frame with a revert, returning `message` encoded as a cheatcode error.
+    pub fn cheatcode_error(message: impl Into<String>) -> Self {
+        Self::revert_error(&Vm::CheatcodeError { message: message.into() })
+    }
+
+    /// Ends the frame with an exceptional halt, consuming all of its gas.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `reason` is not an error, use [`FrameOverride::return_data`] or
+    /// [`FrameOverride::revert`] instead.
+    pub fn halt(reason: InstructionResult) -> Self {
+        assert!(reason.is_error(), "{reason:?} is not an exceptional halt");
+        Self { result: reason, output: Bytes::new(), gas_used: None }
+    }
+
+    /// Ends the frame with an out of gas halt.
+    pub fn out_of_gas() -> Self {
+        Self::halt(InstructionResult::OutOfGas)
+    }
+
+    /// Sets the gas used by the frame, capped to its gas limit.
+    ///
+    /// Without it, frames ended before they started use no gas and frames ended during or after
+    /// their execution keep the gas they used so far. Halts always consume all of the gas.
+    pub fn with_gas_used(mut self, gas_used: u64) -> Self {
+        self.gas_used = Some(gas_used);
+        self
+    }
+
+    /// Returns the instruction result the frame ends with.
+    pub fn instruction_result(&self) -> InstructionResult {
+        self.result
+    }
+
+    /// Returns the output of the frame.
+    pub fn output(&self) -> &Bytes {
+        &self.output
+    }
+
+    /// Returns the result of a frame with the given gas limit that ends before it started.
+    pub fn into_result(self, gas_limit: u64) -> InterpreterResult {
+        let gas_used = self.gas_used.unwrap_or_default();
+        self.into_result_with_gas(Gas::new(gas_limit), gas_used)
+    }
+
+    /// Returns the outcome to return from [`Inspector::call`](revm::Inspector::call) to end the
+    /// call without executing it.
+    pub fn into_call_outcome(self, inputs: &CallInputs) -> CallOutcome {
+        CallOutcome::new(self.into_result(inputs.gas_limit), inputs.return_memory_offset.clone())
+    }
+
+    /// Returns the outcome to return from [`Inspector::create`](revm::Inspector::create) to end
+    /// the creation without executing it. No contract is created.
+    pub fn into_create_outcome(self, inputs: &CreateInputs) -> CreateOutcome {
+        CreateOutcome::new(self.into_result(inputs.gas_limit), None)
+    }
+
+    /// Replaces the result of an ended frame, e.g. from
+    /// [`Inspector::call_end`](revm::Inspector::call_end).
+    pub fn apply(self, result: &mut InterpreterResult) {
+        *result = self.into_result_with_spent(result.gas);
+    }
+
+    /// Stops the interpreter before its next instruction, ending the frame with this result.
+    ///
+    /// Meant to be called from [`Inspector::step`](revm::Inspector::step), unlike setting
+    /// [`Interpreter::instruction_result`] directly this also sets the output of the frame.
+    pub fn apply_to_interpreter(self, interp: &mut Interpreter) {
+        let result = self.into_result_with_spent(interp.gas);
+        interp.instruction_result = result.result;
+        interp.gas = result.gas;
+        interp.next_action = InterpreterAction::Return { result };
+    }
+
+    /// Returns the result of a frame that already used some of its `gas`.
+    fn into_result_with_spent(self, gas: Gas) -> InterpreterResult {
+        let gas_used = self.gas_used.unwrap_or_else(|| gas.spent());
+        self.into_result_with_gas(Gas::new(gas.limit()), gas_used)
+    }
+
+    fn into_result_with_gas(self, mut gas: Gas, gas_used: u64) -> InterpreterResult {
+        let gas_used = if self.result.is_error() { gas.limit() } else { gas_used };
+        gas.record_cost(gas_used.min(gas.limit()));
+        InterpreterResult { result: self.result, output: self.output, gas }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use revm::interpreter::Contract;
+
+    #[test]
+    fn builds_results() {
+        let result = FrameOverride::return_data(vec![1, 2]).with_gas_used(100).into_result(1000);
+        assert_eq!(result.result, InstructionResult::Return);
+        assert_eq!(result.output, Bytes::from(vec![1, 2]));
+        assert_eq!((result.gas.spent(), result.gas.remaining()), (100, 900));
+
+        let result = FrameOverride::revert_message("mocked").with_gas_used(2000).into_result(1000);
+        assert_eq!(result.result, InstructionResult::Revert);
+        assert_eq!(result.output, Bytes::from(Revert::from("mocked").abi_encode()));
+        assert_eq!(result.gas.remaining(), 0);
+
+        let result = FrameOverride::out_of_gas().with_gas_used(10).into_result(1000);
+        assert_eq!(result.result, InstructionResult::OutOfGas);
+        assert!(result.output.is_empty());
+        assert_eq!(result.gas.spent(), 1000);
+    }
+
+    #[test]
+    fn keeps_gas_used_so_far() {
+        let mut result = FrameOverride::return_data(Bytes::new()).into_result(1000);
+        result.gas.record_cost(300);
+
+        FrameOverride::cheatcode_error("denied").apply(&mut result);
+        assert_eq!(result.result, InstructionResult::Revert);
+        assert_eq!(
+            result.output,
+            Bytes::from(Vm::CheatcodeError { message: "denied".into() }.abi_encode())
+        );
+        assert_eq!((result.gas.limit(), result.gas.spent()), (1000, 300));
+    }
+
+    #[test]
+    fn stops_the_interpreter() {
+        let mut interp = Interpreter::new(Contract::default(), 1000, false);
+        interp.gas.record_cost(50);
+
+        FrameOverride::return_data(vec![42]).apply_to_interpreter(&mut interp);
+        assert_eq!(interp.instruction_result, InstructionResult::Return);
+        let InterpreterAction::Return { result } = &interp.next_action else {
+            panic!("unexpected action: {:?}", interp.next_action)
+        };
+        assert_eq!(result.output, Bytes::from(vec![42]));
+        assert_eq!(result.gas.spent(), 50);
+    }
+
+    #[test]
+    #[should_panic = "is not an exceptional halt"]
+    fn rejects_successful_halts() {
+        let _ = FrameOverride::halt(InstructionResult::Stop);
+    }
+}
@@ -0,0 +1,152 @@
+//! Precompiles in addition to those of the EVM spec, e.g. the precompiles of an L2.
+
+use crate::InspectorExt;
+use alloy_primitives::{Address, Bytes};
+use revm::{
+    handler::register::{EvmHandler, HandleRegisterBox},
+    primitives::{Env, Precompile, PrecompileResult, StatefulPrecompile},
+    Database,
+};
+use std::{collections::BTreeMap, fmt, sync::Arc};
+
+/// A precompile implemented in Rust.
+///
+/// Chains like Arbitrum or Moonbeam ship precompiles that are not part of any EVM spec, so calls to
+/// them revert in fork tests unless an implementation is registered at their address.
+pub trait CustomPrecompile: fmt::Debug + Send + Sync {
+    /// Executes the precompile with the given input, charging at most `gas_limit`.
+    fn call(&self, input: &Bytes, gas_limit: u64, env: &Env) -> PrecompileResult;
+}
+
+/// Adapts a [`CustomPrecompile`] to revm's stateful precompiles.
+struct Adapter(Arc<dyn CustomPrecompile>);
+
+impl StatefulPrecompile for Adapter {
+    fn call(&self, bytes: &Bytes, gas_limit: u64, env: &Env) -> PrecompileResult {
+        self.0.call(bytes, gas_limit, env)
+    }
+}
+
+/// A set of [`CustomPrecompile`]s, keyed by address.
+///
+/// The precompiles are installed on every EVM created for an inspector that returns them from
+/// [`InspectorExt::custom_precompiles`]. They take precedence over the precompiles of the spec at
+/// the same address.
+#[derive(Clone, Debug, Default)]
+pub struct CustomPrecompiles {
+    precompiles: BTreeMap<Address, Arc<dyn CustomPrecompile>>,
+}
+
+impl CustomPrecompiles {
+    /// Registers the precompile at the given address, replacing any previous one.
+    pub fn insert(&mut self, address: Address, precompile: Arc<dyn CustomPrecompile>) {
+        self.precompiles.insert(address, precompile);
+    }
+
+    /// Returns the precompile at the given address, if any.
+    pub fn get(&self, address: &Address) -> Option<&Arc<dyn CustomPrecompile>> {
+        self.precompiles.get(address)
+    }
+
+    /// Returns whether no precompiles are registered.
+    pub fn is_empty(&self) -> bool {
+        self.precompiles.is_empty()
+    }
+
+    /// Returns the addresses of the precompiles.
+    pub fn addresses(&self) -> impl Iterator<Item = &Address> {
+        self.precompiles.keys()
+    }
+
+    /// Returns the precompiles as revm precompiles.
+    pub fn to_precompiles(&self) -> Vec<(Address, Precompile)> {
+        self.precompiles
+            .iter()
+            .map(|(address, precompile)| {
+                (*address, Precompile::Stateful(Arc::new(Adapter(precompile.clone()))))
+            })
+            .collect()
+    }
+}
+
+/// Returns a handler register adding the given precompiles to those of the spec.
+pub fn custom_precompiles_handler_register<'a, EXT: 'a, DB: Database + 'a>(
+    precompiles: Arc<CustomPrecompiles>,
+) -> HandleRegisterBox<'a, EXT, DB> {
+    Box::new(move |handler| {
+        let precompiles = precompiles.to_precompiles();
+        let old_handle = handler.pre_execution.load_precompiles.clone();
+        handler.pre_execution.load_precompiles = Arc::new(move || {
+            let mut loaded = old_handle();
+            loaded.extend(precompiles.iter().cloned().map(|(address, p)| (address, p.into())));
+            loaded
+        });
+    })
+}
+
+/// Installs the custom precompiles of the inspector, if any, on the given handler.
+pub(crate) fn append_custom_precompiles<'a, DB, I>(
+    handler: &mut EvmHandler<'a, I, DB>,
+    inspector: &I,
+) where
+    DB: Database + 'a,
+    I: InspectorExt<DB> + 'a,
+{
+    if let Some(precompiles) = inspector.custom_precompiles() {
+        handler.append_handler_register_box(custom_precompiles_handler_register(precompiles));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::new_evm_with_inspector;
+    use alloy_primitives::{TxKind, U256};
+    use revm::{
+        db::{CacheDB, EmptyDB},
+        primitives::{EnvWithHandlerCfg, HandlerCfg, PrecompileOutput, SpecId},
+        Inspector,
+    };
+
+    /// Returns the input reversed.
+    #[derive(Debug)]
+    struct Reverse;
+
+    impl CustomPrecompile for Reverse {
+        fn call(&self, input: &Bytes, _gas_limit: u64, _env: &Env) -> PrecompileResult {
+            let bytes = input.iter().rev().copied().collect();
+            Ok(PrecompileOutput { bytes, gas_used: 100 })
+        }
+    }
+
+    struct PrecompilesInspector(Arc<CustomPrecompiles>);
+
+    impl<DB: Database> Inspector<DB> for PrecompilesInspector {}
+
+    impl<DB: Database> InspectorExt<DB> for PrecompilesInspector {
+        fn custom_precompiles(&self) -> Option<Arc<CustomPrecompiles>> {
+            Some(self.0.clone())
+        }
+    }
+
+    #[test]
+    fn calls_custom_precompile() {
+        let address = Address::with_last_byte(0x64);
+        let mut precompiles = CustomPrecompiles::default();
+        precompiles.insert(address, Arc::new(Reverse));
+
+        let mut db = CacheDB::new(EmptyDB::default());
+        let mut env = Env::default();
+        env.tx.transact_to = TxKind::Call(address);
+        env.tx.data = vec![1, 2, 3].into();
+        env.tx.gas_limit = 100_000;
+        env.tx.gas_price = U256::ZERO;
+        let env = EnvWithHandlerCfg::new(Box::new(env), HandlerCfg::new(SpecId::CANCUN));
+
+        let inspector = PrecompilesInspector(Arc::new(precompiles));
+        let mut evm = new_evm_with_inspector(&mut db, env, inspector);
+        let result = evm.transact().unwrap();
+        assert!(result.result.is_success());
+        assert_eq!(result.result.output().unwrap().as_ref(), &[3, 2, 1]);
+    }
+}
@@ -0,0 +1,98 @@
+//! The source of the randomness of a run.
+//!
+//! All the randomness of a run is derived from a single seed, so that the whole run can be
+//! reproduced from it:
+//!   * the fuzzer and the invariant fuzzer
+//!   * the `vm.random*` cheatcodes
+//!   * the dev accounts generated by anvil with `--mnemonic-random`
+//!
+//! The seed is taken from the config when set, then from the [`SEED_ENV`] environment variable,
+//! and is random otherwise.
+
+use alloy_primitives::{keccak256, U256};
+use rand::{rngs::StdRng, SeedableRng};
+use std::fmt;
+
+/// The environment variable the seed of a run is read from.
+pub const SEED_ENV: &str = "FOUNDRY_SEED";
+
+/// A seedable source of randomness.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RandomSource {
+    seed: U256,
+}
+
+impl RandomSource {
+    /// Creates a source from the given seed.
+    pub const fn new(seed: U256) -> Self {
+        Self { seed }
+    }
+
+    /// Creates a source with a random seed.
+    pub fn random() -> Self {
+        Self::new(U256::random())
+    }
+
+    /// Returns the source seeded from the [`SEED_ENV`] environment variable, if set.
+    pub fn from_env() -> eyre::Result<Option<Self>> {
+        let Ok(seed) = std::env::var(SEED_ENV) else { return Ok(None) };
+        let seed =
+            seed.trim().parse().map_err(|err| eyre::eyre!("invalid {SEED_ENV} `{seed}`: {err}"))?;
+        Ok(Some(Self::new(seed)))
+    }
+
+    /// Returns the source seeded from the given seed, the [`SEED_ENV`] environment variable, or a
+    /// random seed, in this order.
+    pub fn resolve(seed: Option<U256>) -> eyre::Result<Self> {
+        match seed {
+            Some(seed) => Ok(Self::new(seed)),
+            None => Ok(Self::from_env()?.unwrap_or_else(Self::random)),
+        }
+    }
+
+    /// Returns the seed.
+    pub const fn seed(&self) -> U256 {
+        self.seed
+    }
+
+    /// Returns a generator for the given consumer, derived from the seed and the given data.
+    ///
+    /// Consumers get independent streams, so that drawing values for one doesn't change the
+    /// values drawn for another.
+    pub fn rng(&self, consumer: &str, data: &[u8]) -> StdRng {
+        let mut preimage = self.seed.to_be_bytes_vec();
+        preimage.extend_from_slice(consumer.as_bytes());
+        preimage.extend_from_slice(data);
+        StdRng::from_seed(keccak256(preimage).0)
+    }
+}
+
+impl fmt::Display for RandomSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:#x}", self.seed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    #[test]
+    fn derives_reproducible_streams() {
+        let source = RandomSource::new(U256::from(42));
+        let draw = |consumer: &str, data: &[u8]| source.rng(consumer, data).gen::<u64>();
+
+        assert_eq!(draw("cheatcodes", b""), draw("cheatcodes", b""));
+        assert_ne!(draw("cheatcodes", b""), draw("anvil", b""));
+        assert_ne!(draw("cheatcodes", b""), draw("cheatcodes", b"\x01"));
+        let other = RandomSource::new(U256::from(43));
+        assert_ne!(draw("anvil", b""), other.rng("anvil", b"").gen::<u64>());
+    }
+
+    #[test]
+    fn displays_seed() {
+        assert_eq!(RandomSource::new(U256::from(255)).to_string(), "0xff");
+        assert_eq!("0xff".parse::<U256>().unwrap(), RandomSource::new(U256::from(255)).seed());
+    }
+}
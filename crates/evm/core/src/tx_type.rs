@@ -0,0 +1,167 @@
+//! Custom [EIP-2718](https://eips.ethereum.org/EIPS/eip-2718) transaction types.
+//!
+//! Appchains often define transaction types of their own, e.g. to pay fees in an ERC-20 token or
+//! to have a sponsor pay for the gas of its users. Such a type is implemented as a
+//! [`CustomTxType`] and registered for the chains that support it with
+//! [`register_custom_tx_type`], after which:
+//!   * `vm.txType` accepts the type in scripts running on these chains
+//!   * executors validate the transactions of the type and apply its preamble before executing
+//!     them, when simulating scripts or replaying transactions
+//!   * broadcast transactions of the type are signed and encoded by it
+
+use crate::backend::DatabaseExt;
+use alloy_primitives::{Bytes, Signature, B256};
+use alloy_rpc_types::TransactionRequest;
+use alloy_serde::WithOtherFields;
+use parking_lot::{const_rwlock, RwLock};
+use revm::primitives::Env;
+use std::{collections::BTreeMap, fmt, sync::Arc};
+
+/// The type bytes of the standard transaction types: legacy, EIP-2930, EIP-1559, EIP-4844 and
+/// EIP-7702.
+pub const STANDARD_TX_TYPES: [u8; 5] = [0, 1, 2, 3, 4];
+
+/// The highest type byte of a typed transaction, higher first bytes are legacy RLP lists.
+pub const MAX_TX_TYPE: u8 = 0x7f;
+
+/// The custom transaction types registered for each chain, by chain ID.
+static REGISTRY: RwLock<BTreeMap<u64, CustomTxTypes>> = const_rwlock(BTreeMap::new());
+
+/// A custom transaction type.
+///
+/// Transactions of the type are given as requests whose `transaction_type` is the type byte.
+/// Fields specific to the type can be passed along in the other fields of the request.
+pub trait CustomTxType: fmt::Debug + Send + Sync {
+    /// Returns the name of the type, used in errors.
+    fn name(&self) -> &str;
+
+    /// Checks that the transaction is a valid transaction of this type, before it is executed or
+    /// broadcast.
+    fn validate(&self, _tx: &WithOtherFields<TransactionRequest>) -> eyre::Result<()> {
+        Ok(())
+    }
+
+    /// Applies the changes made by the chain before executing a transaction of this type, e.g.
+    /// charging its fees to a sponsor or minting the value it bridges in.
+    fn preamble(
+        &self,
+        _tx: &WithOtherFields<TransactionRequest>,
+        _env: &Env,
+        _db: &mut dyn DatabaseExt,
+    ) -> eyre::Result<()> {
+        Ok(())
+    }
+
+    /// Returns the hash of the transaction that its sender signs.
+    fn signature_hash(&self, tx: &WithOtherFields<TransactionRequest>) -> eyre::Result<B256>;
+
+    /// Encodes the signed transaction as an EIP-2718 envelope, starting with the type byte, as
+    /// sent with `eth_sendRawTransaction`.
+    fn encode_signed(
+        &self,
+        tx: &WithOtherFields<TransactionRequest>,
+        signature: &Signature,
+    ) -> eyre::Result<Bytes>;
+}
+
+/// A set of [`CustomTxType`]s, keyed by type byte.
+#[derive(Clone, Debug, Default)]
+pub struct CustomTxTypes {
+    types: BTreeMap<u8, Arc<dyn CustomTxType>>,
+}
+
+impl CustomTxTypes {
+    /// Registers the transaction type with the given type byte, replacing any previous one.
+    ///
+    /// Returns an error if the type byte is one of a standard type or not a valid EIP-2718 type.
+    pub fn insert(&mut self, ty: u8, tx_type: Arc<dyn CustomTxType>) -> eyre::Result<()> {
+        if STANDARD_TX_TYPES.contains(&ty) {
+            eyre::bail!("transaction type {ty} is a standard type and can't be replaced");
+        }
+        if ty > MAX_TX_TYPE {
+            eyre::bail!("transaction type {ty} is higher than the maximum of {MAX_TX_TYPE}");
+        }
+        self.types.insert(ty, tx_type);
+        Ok(())
+    }
+
+    /// Returns the transaction type with the given type byte, if any.
+    pub fn get(&self, ty: u8) -> Option<&Arc<dyn CustomTxType>> {
+        self.types.get(&ty)
+    }
+
+    /// Returns whether no types are registered.
+    pub fn is_empty(&self) -> bool {
+        self.types.is_empty()
+    }
+
+    /// Returns the type bytes of the registered types.
+    pub fn types(&self) -> impl Iterator<Item = u8> + '_ {
+        self.types.keys().copied()
+    }
+}
+
+/// Registers a custom transaction type for the chain with the given ID, replacing any type
+/// previously registered with the same type byte.
+pub fn register_custom_tx_type(
+    chain_id: u64,
+    ty: u8,
+    tx_type: Arc<dyn CustomTxType>,
+) -> eyre::Result<()> {
+    REGISTRY.write().entry(chain_id).or_default().insert(ty, tx_type)
+}
+
+/// Returns the custom transaction types registered for the chain with the given ID.
+pub fn custom_tx_types(chain_id: u64) -> CustomTxTypes {
+    REGISTRY.read().get(&chain_id).cloned().unwrap_or_default()
+}
+
+/// Returns the custom type of a transaction with the given type on the chain with the given ID, if
+/// it is one.
+pub fn custom_tx_type(chain_id: u64, ty: Option<u8>) -> Option<Arc<dyn CustomTxType>> {
+    REGISTRY.read().get(&chain_id)?.get(ty?).cloned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug)]
+    struct Sponsored;
+
+    impl CustomTxType for Sponsored {
+        fn name(&self) -> &str {
+            "sponsored"
+        }
+
+        fn signature_hash(&self, _tx: &WithOtherFields<TransactionRequest>) -> eyre::Result<B256> {
+            Ok(B256::ZERO)
+        }
+
+        fn encode_signed(
+            &self,
+            _tx: &WithOtherFields<TransactionRequest>,
+            _signature: &Signature,
+        ) -> eyre::Result<Bytes> {
+            Ok(Bytes::from_static(&[0x7e]))
+        }
+    }
+
+    #[test]
+    fn registers_custom_tx_types() {
+        let mut types = CustomTxTypes::default();
+        assert!(types.insert(2, Arc::new(Sponsored)).is_err());
+        assert!(types.insert(0x80, Arc::new(Sponsored)).is_err());
+        types.insert(0x7e, Arc::new(Sponsored)).unwrap();
+        assert_eq!(types.types().collect::<Vec<_>>(), [0x7e]);
+
+        // A chain ID that no other test uses.
+        let chain_id = 0x7e7e_7e7e;
+        assert!(custom_tx_types(chain_id).is_empty());
+        register_custom_tx_type(chain_id, 0x7e, Arc::new(Sponsored)).unwrap();
+        assert_eq!(custom_tx_type(chain_id, Some(0x7e)).unwrap().name(), "sponsored");
+        assert!(custom_tx_type(chain_id, Some(2)).is_none());
+        assert!(custom_tx_type(chain_id, None).is_none());
+        assert!(custom_tx_type(1, Some(0x7e)).is_none());
+    }
+}
@@ -1,5 +1,8 @@
 pub use crate::ic::*;
-use crate::{constants::DEFAULT_CREATE2_DEPLOYER, InspectorExt};
+use crate::{
+    constants::DEFAULT_CREATE2_DEPLOYER, gas::append_gas_policy,
+    precompiles::append_custom_precompiles, InspectorExt,
+};
 use alloy_json_abi::{Function, JsonAbi};
 use alloy_primitives::{Address, Selector, TxKind, U256};
 use alloy_rpc_types::{Block, Transaction};
@@ -247,10 +250,12 @@ where
         .build()
     */
 
-    let context = revm::Context::new(revm::EvmContext::new_with_env(db, env), inspector);
     let mut handler = revm::Handler::new(handler_cfg);
     handler.append_handler_register_plain(revm::inspector_handle_register);
     handler.append_handler_register_plain(create2_handler_register);
+    append_gas_policy(&mut handler, &inspector);
+    append_custom_precompiles(&mut handler, &inspector);
+    let context = revm::Context::new(revm::EvmContext::new_with_env(db, env), inspector);
     revm::Evm::new(context, handler)
 }
 
@@ -276,11 +281,13 @@ where
     I: InspectorExt<DB>,
 {
     let handler_cfg = HandlerCfg::new(inner.spec_id());
-    let context =
-        revm::Context::new(revm::EvmContext { inner, precompiles: Default::default() }, inspector);
     let mut handler = revm::Handler::new(handler_cfg);
     handler.append_handler_register_plain(revm::inspector_handle_register);
     handler.append_handler_register_plain(create2_handler_register);
+    append_gas_policy(&mut handler, &inspector);
+    append_custom_precompiles(&mut handler, &inspector);
+    let context =
+        revm::Context::new(revm::EvmContext { inner, precompiles: Default::default() }, inspector);
     revm::Evm::new(context, handler)
 }
 
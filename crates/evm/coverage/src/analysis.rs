@@ -77,7 +77,7 @@ impl<'a> ContractVisitor<'a> {
         match &node.body {
             Some(body) => {
                 self.push_item(CoverageItem {
-                    kind: CoverageItemKind::Function { name },
+                    kind: CoverageItemKind::Modifier { name },
                     loc: self.source_location_for(&node.src),
                     hits: 0,
                 });
@@ -260,11 +260,8 @@ impl<'a> ContractVisitor<'a> {
                 // branch ID as we do
                 self.branch_id += 1;
 
-                self.push_item(CoverageItem {
-                    kind: CoverageItemKind::Branch { branch_id, path_id: 0 },
-                    loc: self.source_location_for(&node.src),
-                    hits: 0,
-                });
+                // The `if` is skipped if the condition is false, which is the second path.
+                self.push_branches(&node.src, branch_id);
                 self.visit_block(body)?;
 
                 Ok(())
@@ -347,6 +344,24 @@ impl<'a> ContractVisitor<'a> {
                     loc: self.source_location_for(&node.src),
                     hits: 0,
                 });
+
+                if let Some(expr) = node.attribute("condition") {
+                    self.visit_expression(&expr)?;
+                }
+
+                // The true expression is evaluated right after the jump, the false expression is
+                // the jump target.
+                let branch_id = self.branch_id;
+                self.branch_id += 1;
+                self.push_branches(&node.src, branch_id);
+
+                if let Some(expr) = node.attribute("trueExpression") {
+                    self.visit_expression(&expr)?;
+                }
+                if let Some(expr) = node.attribute("falseExpression") {
+                    self.visit_expression(&expr)?;
+                }
+
                 Ok(())
             }
             // Does not count towards coverage
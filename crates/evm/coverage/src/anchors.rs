@@ -6,6 +6,9 @@ use revm::interpreter::opcode;
 use rustc_hash::{FxHashMap, FxHashSet};
 
 /// Attempts to find anchors for the given items using the given source map and bytecode.
+///
+/// Items in the body of a modifier are anchored in every copy of the modifier, since modifiers
+/// are inlined into each function they are applied to.
 pub fn find_anchors(
     bytecode: &[u8],
     source_map: &SourceMap,
@@ -13,6 +16,12 @@ pub fn find_anchors(
     items: &[CoverageItem],
     items_by_source_id: &FxHashMap<usize, Vec<usize>>,
 ) -> Vec<ItemAnchor> {
+    let modifiers = items
+        .iter()
+        .filter(|item| matches!(item.kind, CoverageItemKind::Modifier { .. }))
+        .map(|item| &item.loc)
+        .collect::<Vec<_>>();
+
     let mut seen = FxHashSet::default();
     source_map
         .iter()
@@ -24,29 +33,35 @@ pub fn find_anchors(
             }
 
             let item = &items[item_id];
-            match item.kind {
+            let inlined = modifiers.iter().any(|modifier| modifier.contains(&item.loc));
+            let anchors = match item.kind {
                 CoverageItemKind::Branch { path_id, .. } => {
-                    match find_anchor_branch(bytecode, source_map, item_id, &item.loc) {
-                        Ok(anchors) => match path_id {
-                            0 => Some(anchors.0),
-                            1 => Some(anchors.1),
-                            _ => panic!("Too many paths for branch"),
-                        },
-                        Err(e) => {
-                            warn!("Could not find anchor for item {item}: {e}");
-                            None
-                        }
-                    }
+                    find_anchor_branch(bytecode, source_map, item_id, &item.loc).map(|anchors| {
+                        let copies = if inlined { anchors.len() } else { 1 };
+                        anchors
+                            .into_iter()
+                            .take(copies)
+                            .map(|anchors| match path_id {
+                                0 => anchors.0,
+                                1 => anchors.1,
+                                _ => panic!("Too many paths for branch"),
+                            })
+                            .collect()
+                    })
+                }
+                _ if inlined => find_anchors_inlined(source_map, ic_pc_map, item_id, &item.loc),
+                _ => find_anchor_simple(source_map, ic_pc_map, item_id, &item.loc)
+                    .map(|anchor| vec![anchor]),
+            };
+            match anchors {
+                Ok(anchors) => Some(anchors),
+                Err(e) => {
+                    warn!("Could not find anchor for item {item}: {e}");
+                    None
                 }
-                _ => match find_anchor_simple(source_map, ic_pc_map, item_id, &item.loc) {
-                    Ok(anchor) => Some(anchor),
-                    Err(e) => {
-                        warn!("Could not find anchor for item {item}: {e}");
-                        None
-                    }
-                },
             }
         })
+        .flatten()
         .collect()
 }
 
@@ -70,6 +85,32 @@ pub fn find_anchor_simple(
     })
 }
 
+/// Finds an anchor at the start of every copy of the given source range.
+///
+/// A copy starts at each instruction within the range that does not directly follow another
+/// instruction within the range. This is used for items in modifiers, whose code is inlined into
+/// every function they are applied to.
+pub fn find_anchors_inlined(
+    source_map: &SourceMap,
+    ic_pc_map: &IcPcMap,
+    item_id: usize,
+    loc: &SourceLocation,
+) -> eyre::Result<Vec<ItemAnchor>> {
+    let mut anchors = Vec::new();
+    let mut in_range = false;
+    for (ic, element) in source_map.iter().enumerate() {
+        let was_in_range = std::mem::replace(&mut in_range, is_in_source_range(element, loc));
+        if in_range && !was_in_range {
+            if let Some(instruction) = ic_pc_map.get(ic) {
+                anchors.push(ItemAnchor { instruction, item_id });
+            }
+        }
+    }
+
+    ensure!(!anchors.is_empty(), "Could not find anchor: No matching instruction in range {loc}");
+    Ok(anchors)
+}
+
 /// Finds the anchor corresponding to a branch item.
 ///
 /// This finds the relevant anchors for a branch coverage item. These anchors
@@ -95,19 +136,25 @@ pub fn find_anchor_simple(
 /// <true branch>
 /// ```
 ///
-/// This function will look for the last JUMPI instruction, backtrack to find the program
-/// counter of the first branch, and return an item for that program counter, and the
-/// program counter immediately after the JUMPI instruction.
+/// The JUMPI of the branch is the one that the source map attributes to the branching node
+/// itself, i.e. the `if` statement, the `require` call or the conditional expression, as opposed
+/// to JUMPIs of the condition or of the branch bodies. For each such JUMPI, this returns an anchor
+/// for the program counter immediately after the JUMPI, and one for the jump destination. There
+/// are several such JUMPIs only if the branch is in an inlined modifier.
+///
+/// If no JUMPI can be attributed to the branching node, this falls back to the last JUMPI within
+/// the source range of the branch.
 pub fn find_anchor_branch(
     bytecode: &[u8],
     source_map: &SourceMap,
     item_id: usize,
     loc: &SourceLocation,
-) -> eyre::Result<(ItemAnchor, ItemAnchor)> {
+) -> eyre::Result<Vec<(ItemAnchor, ItemAnchor)>> {
     // NOTE(onbjerg): We use `SpecId::LATEST` here since it does not matter; the only difference
     // is the gas cost.
 
-    let mut anchors: Option<(ItemAnchor, ItemAnchor)> = None;
+    let mut anchors = Vec::new();
+    let mut fallback: Option<(ItemAnchor, ItemAnchor)> = None;
     let mut pc = 0;
     let mut cumulative_push_size = 0;
     while pc < bytecode.len() {
@@ -133,7 +180,7 @@ pub fn find_anchor_branch(
 
             // Check if we are in the source range we are interested in, and if the next opcode
             // is a JUMPI
-            if is_in_source_range(element, loc) && bytecode[pc + 1] == opcode::JUMPI {
+            if is_in_source_range(element, loc) && bytecode.get(pc + 1) == Some(&opcode::JUMPI) {
                 // We do not support program counters bigger than usize. This is also an
                 // assumption in REVM, so this is just a sanity check.
                 ensure!(push_size <= 8, "jump destination overflow");
@@ -145,20 +192,41 @@ pub fn find_anchor_branch(
                 pc_bytes[8 - push_size..].copy_from_slice(push_bytes);
                 let pc_jump = u64::from_be_bytes(pc_bytes);
                 let pc_jump = usize::try_from(pc_jump).expect("PC is too big");
-                anchors = Some((
+                let branch_anchors = (
                     ItemAnchor {
                         item_id,
                         // The first branch is the opcode directly after JUMPI
                         instruction: pc + 2,
                     },
                     ItemAnchor { item_id, instruction: pc_jump },
-                ));
+                );
+
+                let jumpi = source_map.get(pc + 1 - cumulative_push_size);
+                if jumpi.is_some_and(|element| is_branching_node(element, loc)) {
+                    anchors.push(branch_anchors);
+                } else {
+                    fallback = Some(branch_anchors);
+                }
             }
         }
         pc += 1;
     }
 
-    anchors.ok_or_else(|| eyre::eyre!("Could not detect branches in source: {}", loc))
+    if anchors.is_empty() {
+        anchors.extend(fallback);
+    }
+    ensure!(!anchors.is_empty(), "Could not detect branches in source: {}", loc);
+    Ok(anchors)
+}
+
+/// Returns whether `element` is attributed to the branching node of the branch at `location`.
+///
+/// The source range of an `if` branch only covers the statement up to the end of its true body, so
+/// the element may be longer than the location.
+fn is_branching_node(element: &SourceElement, location: &SourceLocation) -> bool {
+    element.index().map_or(false, |a| a as usize == location.source_id) &&
+        element.offset() == location.start &&
+        element.length() >= location.length.unwrap_or_default()
 }
 
 /// Calculates whether `element` is within the range of the target `location`.
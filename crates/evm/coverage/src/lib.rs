@@ -27,6 +27,9 @@ pub mod anchors;
 mod inspector;
 pub use inspector::CoverageCollector;
 
+mod mutation;
+pub use mutation::{BranchMutation, BranchMutator};
+
 /// A coverage report.
 ///
 /// A coverage report contains coverage items and opcodes corresponding to those items (called
@@ -286,6 +289,14 @@ pub enum CoverageItemKind {
         /// The name of the function.
         name: String,
     },
+    /// A modifier in the code.
+    ///
+    /// Modifiers are inlined into every function they are applied to, so the modifier and the
+    /// items in its body are hit whenever any of these functions is called.
+    Modifier {
+        /// The name of the modifier.
+        name: String,
+    },
 }
 
 #[derive(Clone, Debug)]
@@ -313,6 +324,9 @@ impl Display for CoverageItem {
             CoverageItemKind::Function { name } => {
                 write!(f, r#"Function "{name}""#)?;
             }
+            CoverageItemKind::Modifier { name } => {
+                write!(f, r#"Modifier "{name}""#)?;
+            }
         }
         write!(f, " (location: {}, hits: {})", self.loc, self.hits)
     }
@@ -332,6 +346,18 @@ pub struct SourceLocation {
     pub line: usize,
 }
 
+impl SourceLocation {
+    /// Returns the end byte of the location in the source code.
+    pub fn end(&self) -> u32 {
+        self.start + self.length.unwrap_or_default()
+    }
+
+    /// Returns `true` if the given location is within this one.
+    pub fn contains(&self, other: &Self) -> bool {
+        self.source_id == other.source_id && self.start <= other.start && other.end() <= self.end()
+    }
+}
+
 impl Display for SourceLocation {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
@@ -360,9 +386,9 @@ pub struct CoverageSummary {
     pub branch_count: usize,
     /// The number of branches that were hit.
     pub branch_hits: usize,
-    /// The number of functions in the source file.
+    /// The number of functions and modifiers in the source file.
     pub function_count: usize,
-    /// The number of functions hit.
+    /// The number of functions and modifiers hit.
     pub function_hits: usize,
 }
 
@@ -400,7 +426,7 @@ impl AddAssign<&CoverageItem> for CoverageSummary {
                     self.branch_hits += 1;
                 }
             }
-            CoverageItemKind::Function { .. } => {
+            CoverageItemKind::Function { .. } | CoverageItemKind::Modifier { .. } => {
                 self.function_count += 1;
                 if item.hits > 0 {
                     self.function_hits += 1;
@@ -431,7 +457,7 @@ impl AddAssign<&CoverageItem> for &mut CoverageSummary {
                     self.branch_hits += 1;
                 }
             }
-            CoverageItemKind::Function { .. } => {
+            CoverageItemKind::Function { .. } | CoverageItemKind::Modifier { .. } => {
                 self.function_count += 1;
                 if item.hits > 0 {
                     self.function_hits += 1;
@@ -0,0 +1,46 @@
+use alloy_primitives::{B256, U256};
+use revm::{
+    interpreter::{opcode, Interpreter},
+    Database, EvmContext, Inspector,
+};
+
+/// A mutation of the condition of a branch.
+///
+/// Inverting the condition of the `JUMPI` of a branch has the same effect as flipping the
+/// comparison operator the branch is taken on, e.g. `<` to `>=`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct BranchMutation {
+    /// The hash of the code the branch is in.
+    pub code_hash: B256,
+    /// The program counter of the `JUMPI` of the branch.
+    pub pc: usize,
+}
+
+/// An inspector that applies a [`BranchMutation`] to every execution of its branch.
+#[derive(Clone, Debug)]
+pub struct BranchMutator {
+    mutation: BranchMutation,
+}
+
+impl BranchMutator {
+    pub fn new(mutation: BranchMutation) -> Self {
+        Self { mutation }
+    }
+}
+
+impl<DB: Database> Inspector<DB> for BranchMutator {
+    #[inline]
+    fn step(&mut self, interp: &mut Interpreter, _context: &mut EvmContext<DB>) {
+        if interp.program_counter() != self.mutation.pc ||
+            interp.current_opcode() != opcode::JUMPI ||
+            interp.contract.hash != Some(self.mutation.code_hash)
+        {
+            return
+        }
+        // `JUMPI` pops the destination, then the condition.
+        let stack = interp.stack.data_mut();
+        if let Some(index) = stack.len().checked_sub(2) {
+            stack[index] = U256::from(stack[index].is_zero());
+        }
+    }
+}
@@ -0,0 +1,262 @@
+//! Consumer-driven contract tests for the external calls of contracts.
+//!
+//! The calls a contract under test makes to its dependencies, and the results it got back, are
+//! the assumptions its unit tests are built on, e.g. the values a mocked oracle returns. They are
+//! recorded from the traces of the tests into a [`ContractTestFile`], and verified by replaying
+//! them against the real dependencies on a fork.
+
+use crate::{
+    constants::{CHEATCODE_ADDRESS, HARDHAT_CONSOLE_ADDRESS},
+    executors::Executor,
+    traces::{CallKind, CallTraceArena, TraceKind},
+};
+use alloy_primitives::{Address, Bytes, U256};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashSet, fmt};
+
+/// The current version of the [`ContractTestFile`] format.
+pub const CONTRACT_TEST_FILE_VERSION: u32 = 1;
+
+/// The recorded external calls of the contracts under test.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContractTestFile {
+    /// The version of the format.
+    pub version: u32,
+    /// The recorded calls, in the order they were first made.
+    pub calls: Vec<RecordedCall>,
+}
+
+impl Default for ContractTestFile {
+    fn default() -> Self {
+        Self { version: CONTRACT_TEST_FILE_VERSION, calls: Vec::new() }
+    }
+}
+
+impl ContractTestFile {
+    /// Adds the given calls, skipping those that were already recorded by another test.
+    pub fn extend(&mut self, calls: impl IntoIterator<Item = RecordedCall>) {
+        for call in calls {
+            if !self.calls.iter().any(|recorded| recorded.same_call(&call)) {
+                self.calls.push(call);
+            }
+        }
+    }
+}
+
+/// An external call made by a contract under test, and the result it got back.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecordedCall {
+    /// The test the call was recorded in, in the form `<contract>::<test>`.
+    pub test: String,
+    /// The contract under test that made the call.
+    pub caller: Address,
+    /// The dependency that was called.
+    pub target: Address,
+    /// The value sent with the call.
+    #[serde(default, skip_serializing_if = "U256::is_zero")]
+    pub value: U256,
+    /// The calldata of the call.
+    pub calldata: Bytes,
+    /// The data the call returned, or its revert data.
+    pub returndata: Bytes,
+    /// Whether the call succeeded.
+    pub success: bool,
+}
+
+impl RecordedCall {
+    /// Returns `true` if both calls are the same, regardless of the test they were recorded in.
+    fn same_call(&self, other: &Self) -> bool {
+        self.caller == other.caller &&
+            self.target == other.target &&
+            self.value == other.value &&
+            self.calldata == other.calldata &&
+            self.returndata == other.returndata &&
+            self.success == other.success
+    }
+}
+
+/// How a replayed call differs from the recorded one.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum CallMismatch {
+    /// There's no contract at the target.
+    NoCode,
+    /// The call reverted but was recorded to succeed, or the other way around.
+    Status {
+        /// Whether the replayed call succeeded.
+        success: bool,
+        /// The data the replayed call returned, or its revert data.
+        returndata: Bytes,
+    },
+    /// The call returned different data.
+    Returndata {
+        /// The data the replayed call returned.
+        returndata: Bytes,
+    },
+}
+
+impl fmt::Display for CallMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoCode => f.write_str("no contract at the target"),
+            Self::Status { success: true, returndata } => {
+                write!(f, "expected a revert, but the call returned {returndata}")
+            }
+            Self::Status { success: false, returndata } => {
+                write!(f, "expected success, but the call reverted with {returndata}")
+            }
+            Self::Returndata { returndata } => write!(f, "the call returned {returndata}"),
+        }
+    }
+}
+
+/// Records the external calls the contracts under test make in the traces of a test.
+///
+/// The contracts under test are the contracts the test creates, other than the test contract
+/// itself. Their calls to accounts the test didn't create, i.e. their dependencies, are recorded
+/// along with the results they got back, including mocked ones. Calls to cheatcodes, the console
+/// and precompiles are ignored, as are delegate calls, which run in the context of the caller.
+pub fn record_calls<'a>(
+    test: &str,
+    traces: impl IntoIterator<Item = &'a (TraceKind, CallTraceArena)>,
+) -> Vec<RecordedCall> {
+    let arenas: Vec<_> = traces.into_iter().map(|(_, arena)| arena).collect();
+
+    // Every trace starts with the deployment of, or a call into, the test contract.
+    let test_contracts: HashSet<_> = arenas
+        .iter()
+        .filter_map(|arena| arena.nodes().first())
+        .map(|node| node.trace.address)
+        .collect();
+    let created: HashSet<_> = arenas
+        .iter()
+        .flat_map(|arena| arena.nodes())
+        .filter(|node| node.trace.kind.is_any_create() && node.trace.success)
+        .map(|node| node.trace.address)
+        .collect();
+
+    let mut calls = Vec::new();
+    for node in arenas.iter().flat_map(|arena| arena.nodes()) {
+        let trace = &node.trace;
+        if trace.kind.is_any_create() ||
+            matches!(trace.kind, CallKind::DelegateCall | CallKind::CallCode) ||
+            !created.contains(&trace.caller) ||
+            test_contracts.contains(&trace.caller) ||
+            created.contains(&trace.address) ||
+            is_ignored_target(trace.address)
+        {
+            continue
+        }
+        calls.push(RecordedCall {
+            test: test.to_string(),
+            caller: trace.caller,
+            target: trace.address,
+            value: trace.value,
+            calldata: trace.data.clone(),
+            returndata: trace.output.clone(),
+            success: trace.success,
+        });
+    }
+    calls
+}
+
+/// Returns `true` for the cheatcodes, the console, and the low addresses reserved for
+/// precompiles.
+fn is_ignored_target(address: Address) -> bool {
+    address == CHEATCODE_ADDRESS ||
+        address == HARDHAT_CONSOLE_ADDRESS ||
+        address.0[..18].iter().all(|byte| *byte == 0)
+}
+
+/// Replays a recorded call on the state of the executor, usually a fork, and returns how its
+/// result differs from the recorded one, if at all.
+pub fn verify_call(executor: &Executor, call: &RecordedCall) -> eyre::Result<Option<CallMismatch>> {
+    if executor.is_empty_code(call.target)? {
+        return Ok(Some(CallMismatch::NoCode))
+    }
+
+    let result = executor.call_raw(call.caller, call.target, call.calldata.clone(), call.value)?;
+    let success = !result.reverted;
+    Ok(if success != call.success {
+        Some(CallMismatch::Status { success, returndata: result.result })
+    } else if result.result != call.returndata {
+        Some(CallMismatch::Returndata { returndata: result.result })
+    } else {
+        None
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traces::{CallTrace, CallTraceNode};
+    use alloy_primitives::{address, bytes};
+
+    const TEST: Address = address!("7FA9385bE102ac3EAc297483Dd6233D62b3e1496");
+    const UNIT: Address = address!("5615dEB798BB3E4dFa0139dFa1b3D433Cc23b72f");
+    const MOCK: Address = address!("2e234DAe75C793f67A35089C9d99245E1C58470b");
+    const ORACLE: Address = address!("5f4eC3Df9cbd43714FE2740f5E3616155c5b8419");
+
+    fn arena(traces: Vec<CallTrace>) -> CallTraceArena {
+        let mut arena = CallTraceArena::default();
+        let nodes = arena.nodes_mut();
+        nodes.clear();
+        for (idx, trace) in traces.into_iter().enumerate() {
+            nodes.push(CallTraceNode { idx, trace, ..Default::default() });
+        }
+        arena
+    }
+
+    fn trace(kind: CallKind, caller: Address, address: Address, data: Bytes) -> CallTrace {
+        CallTrace { kind, caller, address, data, success: true, ..Default::default() }
+    }
+
+    #[test]
+    fn records_external_calls_of_created_contracts() {
+        let setup = arena(vec![
+            trace(CallKind::Call, Address::ZERO, TEST, bytes!("0a9254e4")),
+            trace(CallKind::Create, TEST, UNIT, Bytes::new()),
+            trace(CallKind::Create, TEST, MOCK, Bytes::new()),
+        ]);
+        let mut oracle_call = trace(CallKind::StaticCall, UNIT, ORACLE, bytes!("feaf968c"));
+        oracle_call.output = bytes!("01");
+        let execution = arena(vec![
+            trace(CallKind::Call, Address::ZERO, TEST, bytes!("d09de08a")),
+            // The test calling the unit or a dependency directly.
+            trace(CallKind::Call, TEST, UNIT, bytes!("d09de08a")),
+            trace(CallKind::StaticCall, TEST, ORACLE, bytes!("feaf968c")),
+            trace(CallKind::Call, TEST, CHEATCODE_ADDRESS, bytes!("b4d6c782")),
+            // The unit calling a dependency, a mock deployed by the test, and a precompile.
+            oracle_call,
+            trace(CallKind::Call, UNIT, MOCK, bytes!("d09de08a")),
+            trace(
+                CallKind::StaticCall,
+                UNIT,
+                address!("0000000000000000000000000000000000000001"),
+                Bytes::new(),
+            ),
+            trace(CallKind::DelegateCall, UNIT, ORACLE, bytes!("d09de08a")),
+        ]);
+
+        let traces = vec![(TraceKind::Setup, setup), (TraceKind::Execution, execution)];
+        let calls = record_calls("CounterTest::test_Increment", &traces);
+        assert_eq!(
+            calls,
+            vec![RecordedCall {
+                test: "CounterTest::test_Increment".to_string(),
+                caller: UNIT,
+                target: ORACLE,
+                value: U256::ZERO,
+                calldata: bytes!("feaf968c"),
+                returndata: bytes!("01"),
+                success: true,
+            }]
+        );
+
+        let mut file = ContractTestFile::default();
+        file.extend(calls.clone());
+        file.extend(calls.into_iter().map(|call| RecordedCall { test: "other".into(), ..call }));
+        assert_eq!(file.calls.len(), 1);
+        assert_eq!(file.calls[0].test, "CounterTest::test_Increment");
+    }
+}
@@ -1,6 +1,10 @@
-use crate::{executors::Executor, inspectors::InspectorStackBuilder};
+use crate::{
+    executors::{EvmExecutorStrategy, Executor, ExecutorStrategy},
+    inspectors::InspectorStackBuilder,
+};
 use foundry_evm_core::backend::Backend;
 use revm::primitives::{Env, EnvWithHandlerCfg, SpecId};
+use std::sync::Arc;
 
 /// The builder that allows to configure an evm [`Executor`] which a stack of optional
 /// [`revm::Inspector`]s, such as [`Cheatcodes`].
@@ -19,6 +23,8 @@ pub struct ExecutorBuilder {
     /// The spec ID.
     spec_id: SpecId,
     legacy_assertions: bool,
+    /// The strategy transactions are executed with.
+    strategy: Arc<dyn ExecutorStrategy>,
 }
 
 impl Default for ExecutorBuilder {
@@ -29,6 +35,7 @@ impl Default for ExecutorBuilder {
             gas_limit: None,
             spec_id: SpecId::LATEST,
             legacy_assertions: false,
+            strategy: EvmExecutorStrategy::shared(),
         }
     }
 }
@@ -71,10 +78,19 @@ impl ExecutorBuilder {
         self
     }
 
+    /// Sets the strategy transactions are executed with.
+    ///
+    /// Defaults to [`EvmExecutorStrategy`].
+    #[inline]
+    pub fn strategy(mut self, strategy: Arc<dyn ExecutorStrategy>) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
     /// Builds the executor as configured.
     #[inline]
     pub fn build(self, env: Env, db: Backend) -> Executor {
-        let Self { mut stack, gas_limit, spec_id, legacy_assertions } = self;
+        let Self { mut stack, gas_limit, spec_id, legacy_assertions, strategy } = self;
         if stack.block.is_none() {
             stack.block = Some(env.block.clone());
         }
@@ -83,6 +99,8 @@ impl ExecutorBuilder {
         }
         let gas_limit = gas_limit.unwrap_or_else(|| env.block.gas_limit.saturating_to());
         let env = EnvWithHandlerCfg::new_with_spec_id(Box::new(env), spec_id);
-        Executor::new(db, env, stack.build(), gas_limit, legacy_assertions)
+        let mut executor = Executor::new(db, env, stack.build(), gas_limit, legacy_assertions);
+        executor.set_strategy(strategy);
+        executor
     }
 }
@@ -0,0 +1,281 @@
+use alloy_dyn_abi::{DynSolValue, JsonAbiExt};
+use alloy_json_abi::Function;
+use alloy_primitives::{hex, keccak256, Bytes, U256};
+use foundry_evm_fuzz::{strategies::fuzz_calldata, FuzzFixtures};
+use parking_lot::RwLock;
+use proptest::prelude::*;
+use std::{collections::HashSet, path::PathBuf, sync::Arc};
+
+/// An input that hit new edges when it was first executed.
+#[derive(Clone, Debug)]
+struct CorpusEntry {
+    /// The calldata of the input.
+    calldata: Bytes,
+    /// The number of edges first hit by this input.
+    new_edges: usize,
+    /// How many times the input was selected for mutation.
+    selections: u32,
+}
+
+impl CorpusEntry {
+    /// Returns the scheduling weight of the entry.
+    ///
+    /// Inputs that discovered more edges are favored, and the weight decays each time the input is
+    /// selected so that newer entries get a chance to be explored.
+    fn energy(&self) -> u64 {
+        ((1 + self.new_edges as u64) * 1024 / (1 + self.selections as u64)).max(1)
+    }
+}
+
+/// The corpus of a coverage-guided fuzz test.
+///
+/// Keeps every input that hit an edge not hit by any previous input, along with the set of all
+/// edges hit so far. If a directory is configured, new inputs are also written there, one file
+/// per input, and used to seed subsequent runs.
+#[derive(Debug, Default)]
+pub struct FuzzCorpus {
+    /// The directory the corpus is persisted to, if any.
+    dir: Option<PathBuf>,
+    /// The interesting inputs.
+    entries: Vec<CorpusEntry>,
+    /// The hashes of all the edges hit so far.
+    edges: HashSet<u64>,
+}
+
+impl FuzzCorpus {
+    /// Creates a new empty corpus, persisted to the given directory.
+    pub fn new(dir: Option<PathBuf>) -> Self {
+        Self { dir, ..Default::default() }
+    }
+
+    /// Returns the number of inputs in the corpus.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns true if the corpus has no inputs.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the number of distinct edges hit so far.
+    pub fn edges(&self) -> usize {
+        self.edges.len()
+    }
+
+    /// Reads the inputs persisted in the corpus directory, sorted for deterministic replay.
+    pub fn load_inputs(&self) -> Vec<Bytes> {
+        let Some(entries) = self.dir.as_ref().and_then(|dir| std::fs::read_dir(dir).ok()) else {
+            return vec![];
+        };
+        let mut inputs = entries
+            .flatten()
+            .filter_map(|entry| {
+                let path = entry.path();
+                let input = foundry_common::fs::read_to_string(&path).ok()?;
+                match input.trim().parse::<Bytes>() {
+                    Ok(calldata) => Some(calldata),
+                    Err(err) => {
+                        warn!(?path, %err, "skipping invalid corpus entry");
+                        None
+                    }
+                }
+            })
+            .collect::<Vec<_>>();
+        inputs.sort();
+        inputs
+    }
+
+    /// Records the edges hit by the given input and adds it to the corpus if any of them is new.
+    ///
+    /// Returns the number of new edges.
+    pub fn add(&mut self, calldata: &Bytes, edges: HashSet<u64>) -> usize {
+        let new_edges = edges.into_iter().filter(|edge| self.edges.insert(*edge)).count();
+        if new_edges > 0 {
+            self.persist(calldata);
+            self.entries.push(CorpusEntry { calldata: calldata.clone(), new_edges, selections: 0 });
+        }
+        new_edges
+    }
+
+    /// Writes the given input to the corpus directory, if not already present.
+    fn persist(&self, calldata: &Bytes) {
+        let Some(dir) = &self.dir else { return };
+        let path = dir.join(hex::encode(keccak256(calldata)));
+        if path.exists() {
+            return;
+        }
+        if let Err(err) = foundry_common::fs::create_dir_all(dir)
+            .and_then(|()| foundry_common::fs::write(&path, calldata.to_string()))
+        {
+            warn!(%err, "failed to persist corpus entry");
+        }
+    }
+
+    /// Selects an input to mutate given a random `target`, with a probability proportional to
+    /// its energy.
+    fn select(&mut self, target: u64) -> Option<&Bytes> {
+        let total = self.entries.iter().map(CorpusEntry::energy).sum::<u64>();
+        if total == 0 {
+            return None;
+        }
+        let mut target = target % total;
+        for entry in &mut self.entries {
+            let energy = entry.energy();
+            if target < energy {
+                entry.selections += 1;
+                return Some(&entry.calldata);
+            }
+            target -= energy;
+        }
+        None
+    }
+}
+
+/// A mutation applied to a single argument of a corpus input.
+#[derive(Clone, Copy, Debug)]
+enum Mutation {
+    /// Replaces the argument with a newly generated value.
+    Splice,
+    /// Increments an integer argument, wrapping around.
+    Increment,
+    /// Decrements an integer argument, wrapping around.
+    Decrement,
+    /// Flips a single bit of an integer argument.
+    FlipBit(u8),
+}
+
+fn mutation_strat() -> impl Strategy<Value = Mutation> {
+    prop_oneof![
+        Just(Mutation::Splice),
+        Just(Mutation::Increment),
+        Just(Mutation::Decrement),
+        any::<u8>().prop_map(Mutation::FlipBit),
+    ]
+}
+
+/// Given a function and a corpus, it returns a strategy which mutates inputs selected from the
+/// corpus. Newly generated calldata is used while the corpus is empty.
+pub fn fuzz_calldata_from_corpus(
+    func: Function,
+    fuzz_fixtures: &FuzzFixtures,
+    corpus: Arc<RwLock<FuzzCorpus>>,
+) -> impl Strategy<Value = Bytes> {
+    (fuzz_calldata(func.clone(), fuzz_fixtures), any::<u64>(), any::<usize>(), mutation_strat())
+        .prop_map(move |(fresh, entry, param, mutation)| {
+            let Some(input) = corpus.write().select(entry).cloned() else { return fresh };
+            mutate(&func, &input, &fresh, param, mutation).unwrap_or(fresh)
+        })
+        .no_shrink()
+}
+
+/// Applies `mutation` to one of the arguments of `input`, splicing in the matching argument of
+/// `fresh` if the mutation doesn't apply to the argument's type.
+fn mutate(
+    func: &Function,
+    input: &Bytes,
+    fresh: &Bytes,
+    param: usize,
+    mutation: Mutation,
+) -> Option<Bytes> {
+    let mut values = func.abi_decode_input(input.get(4..)?, false).ok()?;
+    if values.is_empty() {
+        return None;
+    }
+    let i = param % values.len();
+    match (mutation, &mut values[i]) {
+        (Mutation::Increment, DynSolValue::Uint(value, size)) => {
+            *value = value.wrapping_add(U256::from(1)) & uint_mask(*size);
+        }
+        (Mutation::Decrement, DynSolValue::Uint(value, size)) => {
+            *value = value.wrapping_sub(U256::from(1)) & uint_mask(*size);
+        }
+        (Mutation::FlipBit(bit), DynSolValue::Uint(value, size)) => {
+            *value ^= U256::from(1) << (bit as usize % *size);
+        }
+        (
+            Mutation::Increment | Mutation::Decrement | Mutation::FlipBit(_),
+            DynSolValue::Bool(b),
+        ) => {
+            *b = !*b;
+        }
+        (_, value) => {
+            *value = func.abi_decode_input(fresh.get(4..)?, false).ok()?.into_iter().nth(i)?;
+        }
+    }
+    func.abi_encode_input(&values).ok().map(Into::into)
+}
+
+/// Returns the mask of the valid bits of a `uint<size>`.
+fn uint_mask(size: usize) -> U256 {
+    U256::MAX >> (256 - size)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn calldata(func: &Function, values: &[DynSolValue]) -> Bytes {
+        func.abi_encode_input(values).unwrap().into()
+    }
+
+    #[test]
+    fn keeps_inputs_with_new_edges() {
+        let mut corpus = FuzzCorpus::default();
+        assert_eq!(corpus.add(&Bytes::from_static(&[1]), HashSet::from([1, 2])), 2);
+        assert_eq!(corpus.add(&Bytes::from_static(&[2]), HashSet::from([1, 2])), 0);
+        assert_eq!(corpus.add(&Bytes::from_static(&[3]), HashSet::from([2, 3])), 1);
+        assert_eq!(corpus.len(), 2);
+        assert_eq!(corpus.edges(), 3);
+    }
+
+    fn energies(corpus: &FuzzCorpus) -> Vec<u64> {
+        corpus.entries.iter().map(CorpusEntry::energy).collect()
+    }
+
+    #[test]
+    fn favors_inputs_with_more_new_edges() {
+        let mut corpus = FuzzCorpus::default();
+        assert_eq!(corpus.select(0), None);
+
+        corpus.add(&Bytes::from_static(&[1]), HashSet::from([1]));
+        corpus.add(&Bytes::from_static(&[2]), (2..10).collect());
+        assert_eq!(energies(&corpus), [2048, 9216]);
+
+        assert_eq!(corpus.select(2047), Some(&Bytes::from_static(&[1])));
+        assert_eq!(corpus.select(1024), Some(&Bytes::from_static(&[2])));
+        assert_eq!(corpus.select(1024 + 4608), Some(&Bytes::from_static(&[1])));
+        assert_eq!(energies(&corpus), [682, 4608]);
+    }
+
+    #[test]
+    fn mutations_produce_valid_calldata() {
+        let func = Function::parse("test(uint8 a, bool b, string c)").unwrap();
+        let input = calldata(
+            &func,
+            &[
+                DynSolValue::Uint(U256::from(255), 8),
+                DynSolValue::Bool(false),
+                DynSolValue::String("a".into()),
+            ],
+        );
+        let fresh = calldata(
+            &func,
+            &[
+                DynSolValue::Uint(U256::from(1), 8),
+                DynSolValue::Bool(false),
+                DynSolValue::String("b".into()),
+            ],
+        );
+        let decode = |calldata: Bytes| func.abi_decode_input(&calldata[4..], false).unwrap();
+
+        let increment = mutate(&func, &input, &fresh, 0, Mutation::Increment).unwrap();
+        assert_eq!(decode(increment)[0], DynSolValue::Uint(U256::ZERO, 8));
+        let flip = mutate(&func, &input, &fresh, 3, Mutation::FlipBit(15)).unwrap();
+        assert_eq!(decode(flip)[0], DynSolValue::Uint(U256::from(127), 8));
+        let negate = mutate(&func, &input, &fresh, 1, Mutation::Decrement).unwrap();
+        assert_eq!(decode(negate)[1], DynSolValue::Bool(true));
+        let splice = mutate(&func, &input, &fresh, 2, Mutation::Increment).unwrap();
+        assert_eq!(decode(splice)[2], DynSolValue::String("b".into()));
+    }
+}
@@ -0,0 +1,177 @@
+use crate::executors::{Executor, RawCallResult};
+use alloy_json_abi::Function;
+use alloy_primitives::{hex, Address, Bytes, LogData, Selector, U256};
+use alloy_sol_types::sol;
+use eyre::{Result, WrapErr};
+use foundry_config::FuzzDifferentialConfig;
+use std::collections::BTreeMap;
+
+sol! {
+    interface IDifferentialTest {
+        function differentialTargets() external view returns (address reference, address candidate);
+    }
+}
+
+/// The two implementations called by a differential fuzz test.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DifferentialTargets {
+    /// The implementation that is considered correct.
+    pub reference: Address,
+    /// The implementation that is checked against the reference.
+    pub candidate: Address,
+    /// The selector of the compared function, which replaces the selector of the test function in
+    /// the fuzzed calldata.
+    pub selector: Selector,
+}
+
+impl DifferentialTargets {
+    /// Resolves the implementations compared by the differential fuzz test `func` of the test
+    /// contract at `address`.
+    ///
+    /// A test `testDiff_<name>(<params>)` compares the results of calling `<name>(<params>)` on the
+    /// two addresses returned by the `differentialTargets()` function of the test contract.
+    pub fn resolve(
+        executor: &Executor,
+        sender: Address,
+        address: Address,
+        func: &Function,
+    ) -> Result<Self> {
+        let name =
+            func.name.strip_prefix("testDiff_").filter(|name| !name.is_empty()).ok_or_else(
+                || eyre::eyre!("`{}` does not name a function to compare", func.name),
+            )?;
+        let compared = Function { name: name.to_string(), ..func.clone() };
+
+        let targets = executor
+            .call_sol(
+                sender,
+                address,
+                &IDifferentialTest::differentialTargetsCall {},
+                U256::ZERO,
+                None,
+            )
+            .wrap_err(
+                "differential tests require a `differentialTargets() returns (address, address)` \
+                 function in the test contract",
+            )?
+            .decoded_result;
+        Ok(Self {
+            reference: targets.reference,
+            candidate: targets.candidate,
+            selector: compared.selector(),
+        })
+    }
+
+    /// Returns the calldata to call both implementations with.
+    pub fn calldata(&self, test_calldata: &Bytes) -> Bytes {
+        let mut calldata = test_calldata.to_vec();
+        if calldata.len() >= 4 {
+            calldata[..4].copy_from_slice(self.selector.as_slice());
+        }
+        calldata.into()
+    }
+
+    /// Compares the results of calling the reference and the candidate with the same calldata.
+    ///
+    /// Returns a description of the first divergence, if any.
+    pub fn compare(
+        &self,
+        config: &FuzzDifferentialConfig,
+        reference: &RawCallResult,
+        candidate: &RawCallResult,
+    ) -> Option<String> {
+        let describe = |reverted: bool| if reverted { "reverted" } else { "succeeded" };
+        if config.reverts && reference.reverted != candidate.reverted {
+            return Some(format!(
+                "the reference {} but the candidate {}",
+                describe(reference.reverted),
+                describe(candidate.reverted)
+            ))
+        }
+        let check_data = if reference.reverted { config.reverts } else { config.return_data };
+        if check_data &&
+            reference.reverted == candidate.reverted &&
+            reference.result != candidate.result
+        {
+            let kind = if reference.reverted { "revert data" } else { "return data" };
+            return Some(format!(
+                "{kind} differs: {} != {}",
+                hex::encode_prefixed(&reference.result),
+                hex::encode_prefixed(&candidate.result)
+            ))
+        }
+
+        if config.logs {
+            let logs = |result: &RawCallResult, target| {
+                result
+                    .logs
+                    .iter()
+                    .map(|log| (self.normalize(log.address, target), log.data.clone()))
+                    .collect::<Vec<(Address, LogData)>>()
+            };
+            let (reference_logs, candidate_logs) =
+                (logs(reference, self.reference), logs(candidate, self.candidate));
+            if reference_logs.len() != candidate_logs.len() {
+                return Some(format!(
+                    "the reference emitted {} logs but the candidate emitted {}",
+                    reference_logs.len(),
+                    candidate_logs.len()
+                ))
+            }
+            if let Some(i) = reference_logs.iter().zip(&candidate_logs).position(|(a, b)| a != b) {
+                return Some(format!("log #{i} differs"))
+            }
+        }
+
+        if config.state {
+            let reference_writes = self.storage_writes(reference, self.reference);
+            let candidate_writes = self.storage_writes(candidate, self.candidate);
+            let diverging = reference_writes
+                .keys()
+                .chain(candidate_writes.keys())
+                .find(|key| reference_writes.get(key) != candidate_writes.get(key));
+            if let Some((address, slot)) = diverging {
+                let account = if *address == self.reference {
+                    "the target".to_string()
+                } else {
+                    address.to_string()
+                };
+                return Some(format!("storage writes differ at slot {slot:#x} of {account}"))
+            }
+        }
+
+        None
+    }
+
+    /// Returns the storage slots written by a call, keyed by account and slot.
+    ///
+    /// Writes to `target` are keyed by the reference address so that both implementations can be
+    /// compared.
+    fn storage_writes(
+        &self,
+        result: &RawCallResult,
+        target: Address,
+    ) -> BTreeMap<(Address, U256), U256> {
+        result
+            .state_changeset
+            .iter()
+            .flat_map(|(address, account)| {
+                let address = self.normalize(*address, target);
+                account
+                    .storage
+                    .iter()
+                    .filter(|(_, value)| value.is_changed())
+                    .map(move |(slot, value)| ((address, *slot), value.present_value))
+            })
+            .collect()
+    }
+
+    /// Maps the address of the called implementation to the reference address.
+    fn normalize(&self, address: Address, target: Address) -> Address {
+        if address == target {
+            self.reference
+        } else {
+            address
+        }
+    }
+}
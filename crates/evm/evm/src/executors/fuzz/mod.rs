@@ -2,6 +2,7 @@ use crate::executors::{Executor, RawCallResult};
 use alloy_dyn_abi::JsonAbiExt;
 use alloy_json_abi::Function;
 use alloy_primitives::{Address, Bytes, U256};
+use alloy_sol_types::{Revert, SolError};
 use eyre::Result;
 use foundry_common::evm::Breakpoints;
 use foundry_config::FuzzConfig;
@@ -12,12 +13,31 @@ use foundry_evm_core::{
 use foundry_evm_coverage::HitMaps;
 use foundry_evm_fuzz::{
     strategies::{fuzz_calldata, fuzz_calldata_from_state, EvmFuzzState},
-    BaseCounterExample, CounterExample, FuzzCase, FuzzError, FuzzFixtures, FuzzTestResult,
+    BaseCounterExample, BranchComparisons, CounterExample, FuzzCase, FuzzError, FuzzFixtures,
+    FuzzReplay, FuzzTestResult,
 };
 use foundry_evm_traces::CallTraceArena;
 use indicatif::ProgressBar;
-use proptest::test_runner::{TestCaseError, TestError, TestRunner};
-use std::cell::RefCell;
+use parking_lot::RwLock;
+use proptest::{
+    strategy::{Just, Strategy},
+    test_runner::{TestCaseError, TestError, TestRunner},
+};
+use revm::interpreter::InstructionResult;
+use std::{
+    cell::RefCell,
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+mod corpus;
+pub use corpus::{fuzz_calldata_from_corpus, FuzzCorpus};
+
+mod differential;
+pub use differential::DifferentialTargets;
 
 mod types;
 pub use types::{CaseOutcome, CounterExampleOutcome, FuzzOutcome};
@@ -37,6 +57,8 @@ pub struct FuzzTestData {
     pub breakpoints: Option<Breakpoints>,
     // Stores coverage information for all fuzz cases.
     pub coverage: Option<HitMaps>,
+    // Stores the comparisons involving fuzzed inputs of all fuzz cases.
+    pub branch_comparisons: Option<BranchComparisons>,
 }
 
 /// Wrapper around an [`Executor`] which provides fuzzing support using [`proptest`].
@@ -53,24 +75,43 @@ pub struct FuzzedExecutor {
     sender: Address,
     /// The fuzz configuration
     config: FuzzConfig,
+    /// The implementations to compare, if this is a differential fuzz test
+    differential: Option<DifferentialTargets>,
 }
 
 impl FuzzedExecutor {
     /// Instantiates a fuzzed executor given a testrunner
     pub fn new(
-        executor: Executor,
+        mut executor: Executor,
         runner: TestRunner,
         sender: Address,
         config: FuzzConfig,
     ) -> Self {
-        Self { executor, runner, sender, config }
+        if config.coverage_guided {
+            executor.inspector_mut().collect_edge_coverage(true);
+        }
+        if config.branch_hints {
+            executor.inspector_mut().collect_branch_comparisons(true);
+        }
+        Self { executor, runner, sender, config, differential: None }
+    }
+
+    /// Turns this into a differential fuzzer, which calls both `targets` with the fuzzed calldata
+    /// instead of the test function, and fails if their results diverge.
+    pub fn with_differential(mut self, targets: DifferentialTargets) -> Self {
+        self.differential = Some(targets);
+        self
     }
 
     /// Fuzzes the provided function, assuming it is available at the contract at `address`
     /// If `should_fail` is set to `true`, then it will stop only when there's a success
     /// test case.
     ///
+    /// If coverage-guided fuzzing is enabled, inputs hitting new edges are kept in a corpus, which
+    /// is seeded from and persisted to `corpus_dir`, if given.
+    ///
     /// Returns a list of all the consumed gas and calldata of every fuzz case
+    #[allow(clippy::too_many_arguments)]
     pub fn fuzz(
         &self,
         func: &Function,
@@ -79,19 +120,221 @@ impl FuzzedExecutor {
         should_fail: bool,
         rd: &RevertDecoder,
         progress: Option<&ProgressBar>,
+        corpus_dir: Option<PathBuf>,
     ) -> FuzzTestResult {
-        // Stores the fuzz test execution data.
-        let execution_data = RefCell::new(FuzzTestData::default());
         let state = self.build_fuzz_state();
+        let corpus = Arc::new(RwLock::new(FuzzCorpus::new(corpus_dir)));
+        if self.config.coverage_guided {
+            self.seed_corpus(&mut corpus.write(), address, should_fail);
+        }
+
+        let threads = self.config.threads.clamp(1, self.config.runs.max(1) as usize);
+        let result = if threads == 1 {
+            let strat = self.strategy(func, fuzz_fixtures, &state, &corpus);
+            self.run_strategy(
+                self.runner.clone(),
+                &strat,
+                func,
+                address,
+                should_fail,
+                rd,
+                progress,
+                Some(&*corpus),
+                &AtomicBool::new(false),
+            )
+        } else {
+            self.fuzz_parallel(
+                threads,
+                func,
+                fuzz_fixtures,
+                address,
+                should_fail,
+                rd,
+                progress,
+                &state,
+                &corpus,
+            )
+        };
+
+        state.log_stats();
+        if self.config.coverage_guided {
+            let corpus = corpus.read();
+            trace!(inputs = corpus.len(), edges = corpus.edges(), "fuzz corpus stats");
+        }
+
+        result
+    }
+
+    /// Splits the fuzz runs across `threads` workers, each running on its own clone of the
+    /// executor, and merges their results.
+    ///
+    /// Workers share the fuzz dictionary and corpus, and all of them stop as soon as one finds a
+    /// counterexample.
+    #[allow(clippy::too_many_arguments)]
+    fn fuzz_parallel(
+        &self,
+        threads: usize,
+        func: &Function,
+        fuzz_fixtures: &FuzzFixtures,
+        address: Address,
+        should_fail: bool,
+        rd: &RevertDecoder,
+        progress: Option<&ProgressBar>,
+        state: &EvmFuzzState,
+        corpus: &Arc<RwLock<FuzzCorpus>>,
+    ) -> FuzzTestResult {
+        let mut runner = self.runner.clone();
+        let config = runner.config().clone();
+        let workers = (0..threads as u32)
+            .map(|i| {
+                let cases =
+                    config.cases / threads as u32 + u32::from(i < config.cases % threads as u32);
+                // Only the first worker replays and records persisted failures.
+                let failure_persistence =
+                    if i == 0 { config.failure_persistence.clone() } else { None };
+                let runner = TestRunner::new_with_rng(
+                    proptest::test_runner::Config { cases, failure_persistence, ..config.clone() },
+                    runner.new_rng(),
+                );
+                let worker =
+                    Self::new(self.executor.clone(), runner, self.sender, self.config.clone());
+                Self { differential: self.differential.clone(), ..worker }
+            })
+            .collect::<Vec<_>>();
+
+        let stop = AtomicBool::new(false);
+        let results = std::thread::scope(|scope| {
+            let handles = workers
+                .into_iter()
+                .map(|worker| {
+                    let stop = &stop;
+                    scope.spawn(move || {
+                        let strat = worker.strategy(func, fuzz_fixtures, state, corpus);
+                        worker.run_strategy(
+                            worker.runner.clone(),
+                            &strat,
+                            func,
+                            address,
+                            should_fail,
+                            rd,
+                            progress,
+                            Some(&**corpus),
+                            stop,
+                        )
+                    })
+                })
+                .collect::<Vec<_>>();
+            handles.into_iter().map(|handle| handle.join().unwrap()).collect::<Vec<_>>()
+        });
+
+        self.merge_results(results)
+    }
+
+    /// Merges the results of parallel fuzz workers into the result of the first failing worker,
+    /// or of the first worker if none failed.
+    fn merge_results(&self, mut results: Vec<FuzzTestResult>) -> FuzzTestResult {
+        let max_traces_to_collect = std::cmp::max(1, self.config.gas_report_samples) as usize;
+        let failed = results.iter().position(|result| !result.success).unwrap_or(0);
+        let mut merged = results.remove(failed);
+        for result in results {
+            if merged.first_case.calldata.is_empty() {
+                merged.first_case = result.first_case;
+            }
+            merged.gas_by_case.extend(result.gas_by_case);
+            let remaining = max_traces_to_collect.saturating_sub(merged.gas_report_traces.len());
+            merged.gas_report_traces.extend(result.gas_report_traces.into_iter().take(remaining));
+            match (&mut merged.coverage, result.coverage) {
+                (Some(prev), Some(coverage)) => prev.merge(coverage),
+                (prev @ None, coverage) => *prev = coverage,
+                _ => {}
+            }
+            match (&mut merged.branch_comparisons, result.branch_comparisons) {
+                (Some(prev), Some(comparisons)) => prev.merge(comparisons),
+                (prev @ None, comparisons) => *prev = comparisons,
+                _ => {}
+            }
+        }
+        merged
+    }
+
+    /// Returns the strategy generating calldata for the given function.
+    fn strategy(
+        &self,
+        func: &Function,
+        fuzz_fixtures: &FuzzFixtures,
+        state: &EvmFuzzState,
+        corpus: &Arc<RwLock<FuzzCorpus>>,
+    ) -> impl Strategy<Value = Bytes> {
         let dictionary_weight = self.config.dictionary.dictionary_weight.min(100);
-        let strat = proptest::prop_oneof![
+        // Half of the inputs are mutated from the corpus when fuzzing is coverage-guided.
+        let corpus_weight = if self.config.coverage_guided { 100 } else { 0 };
+        proptest::prop_oneof![
             100 - dictionary_weight => fuzz_calldata(func.clone(), fuzz_fixtures),
-            dictionary_weight => fuzz_calldata_from_state(func.clone(), &state),
-        ];
+            dictionary_weight => fuzz_calldata_from_state(func.clone(), state),
+            corpus_weight => fuzz_calldata_from_corpus(func.clone(), fuzz_fixtures, corpus.clone()),
+        ]
+    }
+
+    /// Executes the inputs persisted in the corpus directory to restore the edges they cover.
+    fn seed_corpus(&self, corpus: &mut FuzzCorpus, address: Address, should_fail: bool) {
+        for calldata in corpus.load_inputs() {
+            if let Ok(FuzzOutcome::Case(CaseOutcome { case, edge_coverage: Some(edges), .. })) =
+                self.single_fuzz(address, should_fail, calldata)
+            {
+                corpus.add(&case.calldata, edges);
+            }
+        }
+    }
+
+    /// Re-executes the exact failing case recorded in the given [FuzzReplay], without generating
+    /// any new inputs.
+    pub fn replay(
+        &self,
+        func: &Function,
+        replay: &FuzzReplay,
+        address: Address,
+        should_fail: bool,
+        rd: &RevertDecoder,
+    ) -> FuzzTestResult {
+        let runner = TestRunner::new(proptest::test_runner::Config {
+            failure_persistence: None,
+            cases: 1,
+            max_global_rejects: self.config.max_test_rejects,
+            max_shrink_iters: 0,
+            ..Default::default()
+        });
+        let strat = Just(replay.calldata.clone());
+        let stop = AtomicBool::new(false);
+        self.run_strategy(runner, &strat, func, address, should_fail, rd, None, None, &stop)
+    }
+
+    /// Runs the given calldata strategy against the fuzz test function until the runner is
+    /// exhausted, a counterexample is found or `stop` is set, recording inputs hitting new edges in
+    /// `corpus`.
+    #[allow(clippy::too_many_arguments)]
+    fn run_strategy<S: Strategy<Value = Bytes>>(
+        &self,
+        mut runner: TestRunner,
+        strat: &S,
+        func: &Function,
+        address: Address,
+        should_fail: bool,
+        rd: &RevertDecoder,
+        progress: Option<&ProgressBar>,
+        corpus: Option<&RwLock<FuzzCorpus>>,
+        stop: &AtomicBool,
+    ) -> FuzzTestResult {
+        // Stores the fuzz test execution data.
+        let execution_data = RefCell::new(FuzzTestData::default());
         // We want to collect at least one trace which will be displayed to user.
         let max_traces_to_collect = std::cmp::max(1, self.config.gas_report_samples) as usize;
 
-        let run_result = self.runner.clone().run(&strat, |calldata| {
+        let run_result = runner.run(strat, |calldata| {
+            // Another worker already found a counterexample.
+            if stop.load(Ordering::Relaxed) {
+                return Ok(());
+            }
+
             let fuzz_res = self.single_fuzz(address, should_fail, calldata)?;
 
             // If running with progress then increment current run.
@@ -101,6 +344,10 @@ impl FuzzedExecutor {
 
             match fuzz_res {
                 FuzzOutcome::Case(case) => {
+                    if let (Some(corpus), Some(edges)) = (corpus, case.edge_coverage) {
+                        corpus.write().add(&case.case.calldata, edges);
+                    }
+
                     let mut data = execution_data.borrow_mut();
                     data.gas_by_case.push((case.case.gas, case.case.stipend));
                     if data.first_case.is_none() {
@@ -120,6 +367,12 @@ impl FuzzedExecutor {
                         opt => *opt = case.coverage,
                     }
 
+                    if let Some(comparisons) = case.branch_comparisons {
+                        data.branch_comparisons
+                            .get_or_insert_with(Default::default)
+                            .merge(comparisons);
+                    }
+
                     Ok(())
                 }
                 FuzzOutcome::CounterExample(CounterExampleOutcome {
@@ -134,6 +387,7 @@ impl FuzzedExecutor {
                     // case.
                     let reason = rd.maybe_decode(&outcome.1.result, Some(status));
                     execution_data.borrow_mut().counterexample = outcome;
+                    stop.store(true, Ordering::Relaxed);
                     // HACK: we have to use an empty string here to denote `None`.
                     Err(TestCaseError::fail(reason.unwrap_or_default()))
                 }
@@ -163,6 +417,7 @@ impl FuzzedExecutor {
             breakpoints: last_run_breakpoints,
             gas_report_traces: traces,
             coverage: fuzz_result.coverage,
+            branch_comparisons: fuzz_result.branch_comparisons,
         };
 
         match run_result {
@@ -170,9 +425,8 @@ impl FuzzedExecutor {
             // `vm.assume` cheatcode, thus we surface this info to the user when the fuzz test
             // aborts due to too many global rejects, making the error message more actionable.
             Err(TestError::Abort(reason)) if reason.message() == "Too many global rejects" => {
-                result.reason = Some(
-                    FuzzError::TooManyRejects(self.runner.config().max_global_rejects).to_string(),
-                );
+                result.reason =
+                    Some(FuzzError::TooManyRejects(runner.config().max_global_rejects).to_string());
             }
             Err(TestError::Abort(reason)) => {
                 result.reason = Some(reason.to_string());
@@ -194,8 +448,6 @@ impl FuzzedExecutor {
             _ => {}
         }
 
-        state.log_stats();
-
         result
     }
 
@@ -207,6 +459,10 @@ impl FuzzedExecutor {
         should_fail: bool,
         calldata: alloy_primitives::Bytes,
     ) -> Result<FuzzOutcome, TestCaseError> {
+        if let Some(targets) = &self.differential {
+            return self.single_differential_fuzz(targets, address, calldata)
+        }
+
         let mut call = self
             .executor
             .call_raw(self.sender, address, calldata.clone(), U256::ZERO)
@@ -228,6 +484,8 @@ impl FuzzedExecutor {
                 case: FuzzCase { calldata, gas: call.gas_used, stipend: call.stipend },
                 traces: call.traces,
                 coverage: call.coverage,
+                edge_coverage: call.edge_coverage,
+                branch_comparisons: call.branch_comparisons,
                 breakpoints,
             }))
         } else {
@@ -239,6 +497,60 @@ impl FuzzedExecutor {
         }
     }
 
+    /// Calls both implementations with the fuzzed calldata from the test contract at `address`, and
+    /// returns a counterexample if their results diverge.
+    fn single_differential_fuzz(
+        &self,
+        targets: &DifferentialTargets,
+        address: Address,
+        calldata: Bytes,
+    ) -> Result<FuzzOutcome, TestCaseError> {
+        let input = targets.calldata(&calldata);
+        let call = |target| {
+            self.executor
+                .call_raw(address, target, input.clone(), U256::ZERO)
+                .map_err(|_| TestCaseError::fail(FuzzError::FailedContractCall))
+        };
+        let reference = call(targets.reference)?;
+        let mut candidate = call(targets.candidate)?;
+
+        let breakpoints = candidate
+            .cheatcodes
+            .as_ref()
+            .map_or_else(Default::default, |cheats| cheats.breakpoints.clone());
+
+        if let Some(divergence) = targets.compare(&self.config.differential, &reference, &candidate)
+        {
+            candidate.result =
+                Revert::from(format!("differential mismatch: {divergence}")).abi_encode().into();
+            return Ok(FuzzOutcome::CounterExample(CounterExampleOutcome {
+                exit_reason: InstructionResult::Revert,
+                counterexample: (calldata, candidate),
+                breakpoints,
+            }))
+        }
+
+        let coverage = match (reference.coverage, candidate.coverage) {
+            (Some(reference), Some(candidate)) => Some(reference.merged(candidate)),
+            (reference, candidate) => reference.or(candidate),
+        };
+        let edge_coverage = match (reference.edge_coverage, candidate.edge_coverage) {
+            (Some(mut reference), Some(candidate)) => {
+                reference.extend(candidate);
+                Some(reference)
+            }
+            (reference, candidate) => reference.or(candidate),
+        };
+        Ok(FuzzOutcome::Case(CaseOutcome {
+            case: FuzzCase { calldata, gas: candidate.gas_used, stipend: candidate.stipend },
+            traces: candidate.traces,
+            coverage,
+            edge_coverage,
+            branch_comparisons: candidate.branch_comparisons,
+            breakpoints,
+        }))
+    }
+
     /// Stores fuzz state for use with [fuzz_calldata_from_state]
     pub fn build_fuzz_state(&self) -> EvmFuzzState {
         if let Some(fork_db) = self.executor.backend().active_fork_db() {
@@ -2,9 +2,10 @@ use crate::executors::RawCallResult;
 use alloy_primitives::Bytes;
 use foundry_common::evm::Breakpoints;
 use foundry_evm_coverage::HitMaps;
-use foundry_evm_fuzz::FuzzCase;
+use foundry_evm_fuzz::{BranchComparisons, FuzzCase};
 use foundry_evm_traces::CallTraceArena;
 use revm::interpreter::InstructionResult;
+use std::collections::HashSet;
 
 /// Returned by a single fuzz in the case of a successful run
 #[derive(Debug)]
@@ -15,6 +16,10 @@ pub struct CaseOutcome {
     pub traces: Option<CallTraceArena>,
     /// The coverage info collected during the call
     pub coverage: Option<HitMaps>,
+    /// The hashes of the control flow edges taken during the call
+    pub edge_coverage: Option<HashSet<u64>>,
+    /// The comparisons involving fuzzed inputs made during the call
+    pub branch_comparisons: Option<BranchComparisons>,
     /// Breakpoints char pc map
     pub breakpoints: Breakpoints,
 }
@@ -65,6 +65,8 @@ pub struct FailedInvariantCaseData {
     pub shrink_run_limit: u32,
     /// Fail on revert, used to check sequence when shrinking.
     pub fail_on_revert: bool,
+    /// Whether to explain the shrunk sequence.
+    pub explain_failures: bool,
 }
 
 impl FailedInvariantCaseData {
@@ -97,6 +99,7 @@ impl FailedInvariantCaseData {
             inner_sequence: inner_sequence.to_vec(),
             shrink_run_limit: invariant_config.shrink_run_limit,
             fail_on_revert: invariant_config.fail_on_revert,
+            explain_failures: invariant_config.explain_failures,
         }
     }
 }
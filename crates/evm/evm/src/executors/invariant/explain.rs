@@ -0,0 +1,127 @@
+use super::{error::FailedInvariantCaseData, shrink::check_sequence};
+use crate::executors::Executor;
+use alloy_dyn_abi::{DynSolType, DynSolValue, JsonAbiExt, Specifier};
+use alloy_json_abi::Function;
+use alloy_primitives::{Address, B256, I256, U256};
+use foundry_common::contracts::ContractsByAddress;
+use foundry_evm_fuzz::{invariant::BasicTxDetails, CallExplanation};
+
+/// Explains a shrunk call sequence failure.
+///
+/// Replays the sequence once without each call, and once with each argument of each call reset
+/// to its default value. Calls without which the invariant holds are needed, and arguments that
+/// can't be reset without the invariant holding are essential to the failure.
+pub(crate) fn explain_sequence(
+    failed_case: &FailedInvariantCaseData,
+    calls: &[BasicTxDetails],
+    executor: &Executor,
+    contracts: &ContractsByAddress,
+    call_after_invariant: bool,
+) -> Vec<CallExplanation> {
+    let reproduces = |calls: &[BasicTxDetails], sequence: Vec<usize>| {
+        check_sequence(
+            executor.clone(),
+            calls,
+            sequence,
+            failed_case.addr,
+            failed_case.calldata.clone(),
+            failed_case.fail_on_revert,
+            call_after_invariant,
+        )
+        .map_or(false, |(success, _)| !success)
+    };
+
+    (0..calls.len())
+        .map(|index| {
+            let without_call = (0..calls.len()).filter(|&i| i != index).collect();
+            let mut explanation = CallExplanation {
+                removable: reproduces(calls, without_call),
+                ..Default::default()
+            };
+            if explanation.removable {
+                return explanation
+            }
+
+            let call = &calls[index];
+            let Some(function) = find_function(contracts, call) else { return explanation };
+            let Ok(args) = function.abi_decode_input(&call.call_details.calldata[4..], false)
+            else {
+                return explanation
+            };
+
+            for (i, param) in function.inputs.iter().enumerate() {
+                let name = if param.name.is_empty() { format!("#{i}") } else { param.name.clone() };
+                let Some(default) = param.resolve().ok().and_then(|ty| default_value(&ty)) else {
+                    continue
+                };
+
+                // An argument that already has its default value can't be needed.
+                let irrelevant = args[i] == default || {
+                    let mut args = args.clone();
+                    args[i] = default;
+                    let Ok(calldata) = function.abi_encode_input(&args) else { continue };
+                    let mut candidate = calls.to_vec();
+                    candidate[index].call_details.calldata = calldata.into();
+                    reproduces(&candidate, (0..calls.len()).collect())
+                };
+                if irrelevant {
+                    explanation.irrelevant_args.push(name);
+                } else {
+                    explanation.essential_args.push(name);
+                }
+            }
+            explanation
+        })
+        .collect()
+}
+
+/// Returns the function of the target contract called by the given call.
+fn find_function<'a>(
+    contracts: &'a ContractsByAddress,
+    call: &BasicTxDetails,
+) -> Option<&'a Function> {
+    let selector = call.call_details.calldata.get(..4)?;
+    let (_, abi) = contracts.get(&call.call_details.target)?;
+    abi.functions().find(|func| func.selector() == selector)
+}
+
+/// Returns the default value of the given type, i.e. zero, false or empty.
+fn default_value(ty: &DynSolType) -> Option<DynSolValue> {
+    Some(match ty {
+        DynSolType::Bool => DynSolValue::Bool(false),
+        DynSolType::Int(size) => DynSolValue::Int(I256::ZERO, *size),
+        DynSolType::Uint(size) => DynSolValue::Uint(U256::ZERO, *size),
+        DynSolType::FixedBytes(size) => DynSolValue::FixedBytes(B256::ZERO, *size),
+        DynSolType::Address => DynSolValue::Address(Address::ZERO),
+        DynSolType::Function => DynSolValue::Function(alloy_primitives::Function::ZERO),
+        DynSolType::Bytes => DynSolValue::Bytes(Vec::new()),
+        DynSolType::String => DynSolValue::String(String::new()),
+        DynSolType::Array(_) => DynSolValue::Array(Vec::new()),
+        DynSolType::FixedArray(ty, size) => {
+            DynSolValue::FixedArray(vec![default_value(ty)?; *size])
+        }
+        DynSolType::Tuple(types) => {
+            DynSolValue::Tuple(types.iter().map(default_value).collect::<Option<_>>()?)
+        }
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_values() {
+        let ty = DynSolType::parse("(uint8,address[],bool[2],string)").unwrap();
+        assert_eq!(
+            default_value(&ty),
+            Some(DynSolValue::Tuple(vec![
+                DynSolValue::Uint(U256::ZERO, 8),
+                DynSolValue::Array(vec![]),
+                DynSolValue::FixedArray(vec![DynSolValue::Bool(false); 2]),
+                DynSolValue::String(String::new()),
+            ]))
+        );
+    }
+}
@@ -1,6 +1,6 @@
 use crate::{
     executors::{Executor, RawCallResult},
-    inspectors::Fuzzer,
+    inspectors::{Fuzzer, CANCELLED_MESSAGE},
 };
 use alloy_primitives::{Address, Bytes, FixedBytes, Selector, U256};
 use alloy_sol_types::{sol, SolCall};
@@ -32,6 +32,8 @@ use std::{cell::RefCell, collections::btree_map::Entry, sync::Arc};
 
 mod error;
 pub use error::{InvariantFailures, InvariantFuzzError};
+
+mod explain;
 use foundry_evm_coverage::HitMaps;
 
 mod replay;
@@ -308,6 +310,11 @@ impl<'a> InvariantExecutor<'a> {
             }
 
             while current_run.depth < self.config.depth {
+                // Stop the campaign instead of reverting every remaining call once cancelled.
+                if current_run.executor.is_cancelled() {
+                    return Err(TestCaseError::fail(CANCELLED_MESSAGE))
+                }
+
                 let tx = current_run.inputs.last().ok_or_else(|| {
                     TestCaseError::fail("No input generated to call fuzzed target.")
                 })?;
@@ -1,6 +1,6 @@
 use super::{
     call_after_invariant_function, call_invariant_function, error::FailedInvariantCaseData,
-    shrink_sequence,
+    explain::explain_sequence, shrink_sequence,
 };
 use crate::executors::Executor;
 use alloy_dyn_abi::JsonAbiExt;
@@ -96,6 +96,8 @@ pub fn replay_run(
 }
 
 /// Replays the error case, shrinks the failing sequence and collects all necessary traces.
+///
+/// If requested, the calls of the shrunk sequence are explained in the counterexample.
 #[allow(clippy::too_many_arguments)]
 pub fn replay_error(
     failed_case: &FailedInvariantCaseData,
@@ -121,10 +123,20 @@ pub fn replay_error(
                 progress,
             )?;
 
+            let explanations = failed_case.explain_failures.then(|| {
+                explain_sequence(
+                    failed_case,
+                    &calls,
+                    &executor,
+                    &ided_contracts,
+                    invariant_contract.call_after_invariant,
+                )
+            });
+
             set_up_inner_replay(&mut executor, &failed_case.inner_sequence);
 
             // Replay calls to get the counterexample and to collect logs, traces and coverage.
-            replay_run(
+            let mut counterexample = replay_run(
                 invariant_contract,
                 executor,
                 known_contracts,
@@ -133,7 +145,13 @@ pub fn replay_error(
                 traces,
                 coverage,
                 &calls,
-            )
+            )?;
+            for (call, explanation) in
+                counterexample.iter_mut().zip(explanations.into_iter().flatten())
+            {
+                call.explanation = Some(explanation);
+            }
+            Ok(counterexample)
         }
     }
 }
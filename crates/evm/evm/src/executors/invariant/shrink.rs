@@ -7,83 +7,151 @@ use crate::executors::{
 use alloy_primitives::{Address, Bytes, U256};
 use foundry_evm_fuzz::invariant::BasicTxDetails;
 use indicatif::ProgressBar;
-use proptest::bits::{BitSetLike, VarBitSet};
-use std::cmp::min;
+use std::cmp::{max, min};
 
-#[derive(Clone, Copy, Debug)]
-struct Shrink {
-    call_index: usize,
-}
-
-/// Shrinker for a call sequence failure.
-/// Iterates sequence call sequence top down and removes calls one by one.
-/// If the failure is still reproducible with removed call then moves to the next one.
-/// If the failure is not reproducible then restore removed call and moves to next one.
+/// Delta-debugging shrinker for a call sequence failure.
+///
+/// Removes chunks of calls from the failing sequence, starting with halves and refining the
+/// granularity down to single calls, keeping every candidate that still reproduces the failure.
+/// Once no more calls can be removed, calls are moved next to the previous call made by the same
+/// sender, so that the resulting sequence groups related calls together.
+///
+/// The relative order of calls made by the same sender is never changed, and every call of the
+/// shrinked sequence is executed at the same block timestamp and number as in the failed sequence:
+/// calls that `warp` or `roll` are only dropped or moved if no later call depends on them.
 #[derive(Debug)]
 struct CallSequenceShrinker {
-    /// Length of call sequence to be shrinked.
-    call_sequence_len: usize,
     /// Call ids contained in current shrinked sequence.
-    included_calls: VarBitSet,
-    /// Current shrinked call id.
-    shrink: Shrink,
-    /// Previous shrinked call id.
-    prev_shrink: Option<Shrink>,
+    sequence: Vec<usize>,
+    /// Blocks each call of the failed sequence was executed at.
+    blocks: Vec<CallBlocks>,
+    /// Number of candidate sequences that can still be tested.
+    runs_left: u32,
+}
+
+/// Block timestamp and number at the start and at the end of a call of the failed sequence.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+struct CallBlocks {
+    start: (U256, U256),
+    end: (U256, U256),
 }
 
 impl CallSequenceShrinker {
-    fn new(call_sequence_len: usize) -> Self {
-        Self {
-            call_sequence_len,
-            included_calls: VarBitSet::saturated(call_sequence_len),
-            shrink: Shrink { call_index: 0 },
-            prev_shrink: None,
-        }
+    fn new(blocks: Vec<CallBlocks>, shrink_run_limit: u32) -> Self {
+        Self { sequence: (0..blocks.len()).collect(), blocks, runs_left: shrink_run_limit }
     }
 
-    /// Return candidate shrink sequence to be tested, by removing ids from original sequence.
-    fn current(&self) -> impl Iterator<Item = usize> + '_ {
-        (0..self.call_sequence_len).filter(|&call_id| self.included_calls.test(call_id))
+    /// Returns true if every call of the candidate starts at the block the previous call ended
+    /// at, as in the failed sequence.
+    fn keeps_blocks(&self, candidate: &[usize]) -> bool {
+        let Some(first) = self.blocks.first() else { return true };
+        let mut block = first.start;
+        candidate.iter().all(|&call| {
+            let blocks = self.blocks[call];
+            std::mem::replace(&mut block, blocks.end) == blocks.start
+        })
     }
 
-    /// Removes next call from sequence.
-    fn simplify(&mut self) -> bool {
-        if self.shrink.call_index >= self.call_sequence_len {
-            // We reached the end of call sequence, nothing left to simplify.
-            false
-        } else {
-            // Remove current call.
-            self.included_calls.clear(self.shrink.call_index);
-            // Record current call as previous call.
-            self.prev_shrink = Some(self.shrink);
-            // Remove next call index
-            self.shrink = Shrink { call_index: self.shrink.call_index + 1 };
-            true
+    /// Tests the candidate sequence and keeps it if it still reproduces the failure.
+    ///
+    /// Candidates changing the block a call is executed at are rejected without being tested.
+    /// Returns `None` if the shrink run limit was reached.
+    fn try_candidate(
+        &mut self,
+        candidate: Vec<usize>,
+        reproduces: &mut impl FnMut(&[usize]) -> bool,
+    ) -> Option<bool> {
+        if !self.keeps_blocks(&candidate) {
+            return Some(false);
         }
+        if self.runs_left == 0 {
+            return None;
+        }
+        self.runs_left -= 1;
+
+        let reproduced = reproduces(&candidate);
+        if reproduced {
+            self.sequence = candidate;
+        }
+        Some(reproduced)
     }
 
-    /// Reverts removed call from sequence and tries to simplify next call.
-    fn complicate(&mut self) -> bool {
-        match self.prev_shrink {
-            Some(shrink) => {
-                // Undo the last call removed.
-                self.included_calls.set(shrink.call_index);
-                self.prev_shrink = None;
-                // Try to simplify next call.
-                self.simplify()
+    /// Removes chunks of calls at increasing granularity until no single call can be removed.
+    ///
+    /// Returns `false` if the shrink run limit was reached.
+    fn remove_calls(&mut self, reproduces: &mut impl FnMut(&[usize]) -> bool) -> bool {
+        let mut granularity = 2;
+        while self.sequence.len() > 1 {
+            let chunk_len = self.sequence.len().div_ceil(granularity);
+            let mut removed = false;
+
+            let mut start = 0;
+            while start < self.sequence.len() {
+                let end = min(start + chunk_len, self.sequence.len());
+                let candidate =
+                    self.sequence[..start].iter().chain(&self.sequence[end..]).copied().collect();
+                match self.try_candidate(candidate, reproduces) {
+                    // The chunk was removed, the next chunk now starts at the same position.
+                    Some(true) => removed = true,
+                    Some(false) => start = end,
+                    None => return false,
+                }
+            }
+
+            if removed {
+                granularity = max(granularity - 1, 2);
+            } else if chunk_len == 1 {
+                break;
+            } else {
+                granularity = min(granularity * 2, self.sequence.len());
+            }
+        }
+        true
+    }
+
+    /// Moves each call right after the previous call made by the same sender, if the failure is
+    /// still reproduced.
+    ///
+    /// Returns whether the sequence was reordered and `false` if the shrink run limit was
+    /// reached.
+    fn group_senders(
+        &mut self,
+        calls: &[BasicTxDetails],
+        reproduces: &mut impl FnMut(&[usize]) -> bool,
+    ) -> (bool, bool) {
+        let mut reordered = false;
+        for index in 1..self.sequence.len() {
+            let sender = calls[self.sequence[index]].sender;
+            let Some(prev) =
+                self.sequence[..index].iter().rposition(|&call| calls[call].sender == sender)
+            else {
+                continue
+            };
+            if prev + 1 == index {
+                continue
+            }
+
+            // Only calls from other senders are skipped, so the order of the sender's calls is
+            // preserved.
+            let mut candidate = self.sequence.clone();
+            let call = candidate.remove(index);
+            candidate.insert(prev + 1, call);
+            match self.try_candidate(candidate, reproduces) {
+                Some(true) => reordered = true,
+                Some(false) => {}
+                None => return (reordered, false),
             }
-            None => false,
         }
+        (reordered, true)
     }
 }
 
 /// Shrinks the failure case to its smallest sequence of calls.
 ///
-/// Maximal shrinkage is guaranteed if the shrink_run_limit is not set to a value lower than the
-/// length of failed call sequence.
+/// Maximal shrinkage is guaranteed if the shrink_run_limit is high enough for the shrinker to
+/// try removing every single call of the shrinked sequence.
 ///
-/// The shrinked call sequence always respect the order failure is reproduced as it is tested
-/// top-down.
+/// The shrinked call sequence always respects the order of the calls made by each sender.
 pub(crate) fn shrink_sequence(
     failed_case: &FailedInvariantCaseData,
     calls: &[BasicTxDetails],
@@ -108,32 +176,60 @@ pub(crate) fn shrink_sequence(
         return Ok(vec![]);
     }
 
-    let mut shrinker = CallSequenceShrinker::new(calls.len());
-    for _ in 0..failed_case.shrink_run_limit {
-        // Check candidate sequence result.
-        match check_sequence(
+    // Check if candidate sequence still fails.
+    let mut reproduces = |sequence: &[usize]| {
+        if let Some(progress) = progress {
+            progress.inc(1);
+        }
+        check_sequence(
             executor.clone(),
             calls,
-            shrinker.current().collect(),
+            sequence.to_vec(),
             failed_case.addr,
             failed_case.calldata.clone(),
             failed_case.fail_on_revert,
             call_after_invariant,
-        ) {
-            // If candidate sequence still fails then shrink more if possible.
-            Ok((false, _)) if !shrinker.simplify() => break,
-            // If candidate sequence pass then restore last removed call and shrink other
-            // calls if possible.
-            Ok((true, _)) if !shrinker.complicate() => break,
-            _ => {}
-        }
+        )
+        .map_or(false, |(success, _)| !success)
+    };
 
-        if let Some(progress) = progress {
-            progress.inc(1);
+    let blocks = call_blocks(executor.clone(), calls)?;
+    let mut shrinker = CallSequenceShrinker::new(blocks, failed_case.shrink_run_limit);
+    if shrinker.remove_calls(&mut reproduces) {
+        // Grouping calls may allow further removals.
+        if let (true, true) = shrinker.group_senders(calls, &mut reproduces) {
+            shrinker.remove_calls(&mut reproduces);
         }
     }
+    trace!(target: "forge::test", "Shrinked sequence to {} calls.", shrinker.sequence.len());
 
-    Ok(shrinker.current().map(|idx| &calls[idx]).cloned().collect())
+    Ok(shrinker.sequence.into_iter().map(|idx| calls[idx].clone()).collect())
+}
+
+/// Executes the failed call sequence and records the blocks each call was executed at, including
+/// the changes made with `vm.warp` and `vm.roll`.
+fn call_blocks(mut executor: Executor, calls: &[BasicTxDetails]) -> eyre::Result<Vec<CallBlocks>> {
+    let block = executor
+        .inspector()
+        .cheatcodes
+        .as_ref()
+        .and_then(|cheatcodes| cheatcodes.block.as_ref())
+        .unwrap_or(&executor.env().block);
+    let mut start = (block.timestamp, block.number);
+
+    let mut blocks = Vec::with_capacity(calls.len());
+    for tx in calls {
+        let call_result = executor.transact_raw(
+            tx.sender,
+            tx.call_details.target,
+            tx.call_details.calldata.clone(),
+            U256::ZERO,
+        )?;
+        let end = (call_result.env.block.timestamp, call_result.env.block.number);
+        blocks.push(CallBlocks { start, end });
+        start = end;
+    }
+    Ok(blocks)
 }
 
 /// Checks if the given call sequence breaks the invariant.
@@ -176,3 +272,63 @@ pub fn check_sequence(
 
     Ok((success, true))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use foundry_evm_fuzz::invariant::CallDetails;
+
+    fn call(sender: Address) -> BasicTxDetails {
+        BasicTxDetails {
+            sender,
+            call_details: CallDetails { target: Address::ZERO, calldata: Bytes::new() },
+        }
+    }
+
+    #[test]
+    fn removes_irrelevant_calls() {
+        let mut shrinker = CallSequenceShrinker::new(vec![CallBlocks::default(); 20], 5000);
+        let mut reproduces = |sequence: &[usize]| [3, 11, 17].iter().all(|c| sequence.contains(c));
+        assert!(shrinker.remove_calls(&mut reproduces));
+        assert_eq!(shrinker.sequence, vec![3, 11, 17]);
+    }
+
+    #[test]
+    fn respects_shrink_run_limit() {
+        let mut shrinker = CallSequenceShrinker::new(vec![CallBlocks::default(); 20], 3);
+        let mut runs = 0;
+        let mut reproduces = |_: &[usize]| {
+            runs += 1;
+            false
+        };
+        assert!(!shrinker.remove_calls(&mut reproduces));
+        assert_eq!(runs, 3);
+        assert_eq!(shrinker.sequence.len(), 20);
+    }
+
+    #[test]
+    fn groups_calls_by_sender() {
+        let (alice, bob) = (Address::with_last_byte(1), Address::with_last_byte(2));
+        let calls = [call(alice), call(bob), call(alice), call(bob), call(alice)];
+        let mut shrinker =
+            CallSequenceShrinker::new(vec![CallBlocks::default(); calls.len()], 5000);
+        assert_eq!(shrinker.group_senders(&calls, &mut |_| true), (true, true));
+        assert_eq!(shrinker.sequence, vec![0, 2, 4, 1, 3]);
+    }
+
+    #[test]
+    fn keeps_warp_and_roll_order() {
+        let block = |timestamp: u64| (U256::from(timestamp), U256::from(1));
+        let span = |start, end| CallBlocks { start: block(start), end: block(end) };
+        // Call 1 warps from 1 to 2, calls 0 and 2 don't change the block.
+        let blocks = vec![span(1, 1), span(1, 2), span(2, 2)];
+
+        let mut shrinker = CallSequenceShrinker::new(blocks, 5000);
+        let mut reproduces = |sequence: &[usize]| sequence.contains(&2);
+        assert!(shrinker.remove_calls(&mut reproduces));
+        assert_eq!(shrinker.sequence, vec![1, 2]);
+
+        assert!(!shrinker.keeps_blocks(&[2, 1]));
+        assert!(shrinker.keeps_blocks(&[0, 1]));
+    }
+}
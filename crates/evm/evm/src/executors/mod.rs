@@ -8,31 +8,46 @@
 
 use crate::inspectors::{
     cheatcodes::BroadcastableTransactions, Cheatcodes, InspectorData, InspectorStack,
+    KeccakPreimages, MemoryProfile, TimeProfile,
 };
 use alloy_dyn_abi::{DynSolValue, FunctionExt, JsonAbiExt};
 use alloy_json_abi::Function;
 use alloy_primitives::{Address, Bytes, Log, U256};
+use alloy_rpc_types::{state::StateOverride, TransactionRequest};
+use alloy_serde::WithOtherFields;
 use alloy_sol_types::{sol, SolCall};
+use eyre::WrapErr;
 use foundry_evm_core::{
-    backend::{Backend, CowBackend, DatabaseError, DatabaseExt, DatabaseResult, GLOBAL_FAIL_SLOT},
+    backend::{
+        Backend, CowBackend, DatabaseError, DatabaseExt, DatabaseResult, LocalForkId,
+        GLOBAL_FAIL_SLOT,
+    },
     constants::{
         CALLER, CHEATCODE_ADDRESS, CHEATCODE_CONTRACT_HASH, DEFAULT_CREATE2_DEPLOYER,
         DEFAULT_CREATE2_DEPLOYER_CODE,
     },
     decode::RevertDecoder,
+    fork::CreateFork,
+    tx_type::custom_tx_type,
     utils::StateChangeset,
 };
 use foundry_evm_coverage::HitMaps;
-use foundry_evm_traces::CallTraceArena;
+use foundry_evm_fuzz::BranchComparisons;
+use foundry_evm_traces::{CallTraceArena, GasRefunds};
 use revm::{
     db::{DatabaseCommit, DatabaseRef},
     interpreter::{return_ok, InstructionResult},
     primitives::{
-        BlockEnv, Bytecode, Env, EnvWithHandlerCfg, ExecutionResult, Output, ResultAndState,
-        SpecId, TxEnv, TxKind,
+        Account, BlockEnv, Bytecode, Env, EnvWithHandlerCfg, ExecutionResult, Output,
+        ResultAndState, SpecId, TxEnv, TxKind,
     },
+    JournaledState,
+};
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, HashMap, HashSet},
+    sync::Arc,
 };
-use std::{borrow::Cow, collections::HashMap};
 
 mod builder;
 pub use builder::ExecutorBuilder;
@@ -43,6 +58,9 @@ pub use fuzz::FuzzedExecutor;
 pub mod invariant;
 pub use invariant::InvariantExecutor;
 
+mod strategy;
+pub use strategy::{EvmExecutorStrategy, ExecutorStrategy};
+
 mod trace;
 pub use trace::TracingExecutor;
 
@@ -82,6 +100,8 @@ pub struct Executor {
     gas_limit: u64,
     /// Whether `failed()` should be called on the test contract to determine if the test failed.
     legacy_assertions: bool,
+    /// The strategy transactions are executed with.
+    strategy: Arc<dyn ExecutorStrategy>,
 }
 
 impl Executor {
@@ -113,12 +133,27 @@ impl Executor {
             },
         );
 
-        Self { backend, env, inspector, gas_limit, legacy_assertions }
+        Self {
+            backend,
+            env,
+            inspector,
+            gas_limit,
+            legacy_assertions,
+            strategy: EvmExecutorStrategy::shared(),
+        }
     }
 
     fn clone_with_backend(&self, backend: Backend) -> Self {
         let env = EnvWithHandlerCfg::new_with_spec_id(Box::new(self.env().clone()), self.spec_id());
-        Self::new(backend, env, self.inspector().clone(), self.gas_limit, self.legacy_assertions)
+        let mut executor = Self::new(
+            backend,
+            env,
+            self.inspector().clone(),
+            self.gas_limit,
+            self.legacy_assertions,
+        );
+        executor.strategy = self.strategy.clone();
+        executor
     }
 
     /// Returns a reference to the EVM backend.
@@ -151,11 +186,40 @@ impl Executor {
         &mut self.inspector
     }
 
+    /// Returns true if the execution was cancelled through the token of the inspector stack.
+    pub fn is_cancelled(&self) -> bool {
+        self.inspector().interrupter.as_ref().is_some_and(|i| i.token().is_cancelled())
+    }
+
+    /// Returns the strategy transactions are executed with.
+    pub fn strategy(&self) -> &dyn ExecutorStrategy {
+        &*self.strategy
+    }
+
+    /// Sets the strategy transactions are executed with.
+    pub fn set_strategy(&mut self, strategy: Arc<dyn ExecutorStrategy>) {
+        self.strategy = strategy;
+    }
+
     /// Returns the EVM spec ID.
     pub fn spec_id(&self) -> SpecId {
         self.env.spec_id()
     }
 
+    /// Sets the EVM spec ID.
+    pub fn set_spec_id(&mut self, spec_id: SpecId) {
+        self.env.handler_cfg.spec_id = spec_id;
+    }
+
+    /// Creates a new fork and selects it, updating the environment to the one of the fork.
+    pub fn create_select_fork(&mut self, fork: CreateFork) -> eyre::Result<LocalForkId> {
+        let mut env = self.env().clone();
+        let mut journaled_state = JournaledState::new(self.spec_id(), Default::default());
+        let id = self.backend.create_select_fork(fork, &mut env, &mut journaled_state)?;
+        *self.env_mut() = env;
+        Ok(id)
+    }
+
     /// Creates the default CREATE2 Contract Deployer for local tests and scripts.
     pub fn deploy_create2_deployer(&mut self) -> eyre::Result<()> {
         trace!("deploying local create2 deployer");
@@ -372,6 +436,79 @@ impl Executor {
         self.call_with_env(env)
     }
 
+    /// Performs a raw call to an account on the current state of the VM, patched with the given
+    /// state overrides like `eth_call`.
+    ///
+    /// Neither the overrides nor the state after the call are persisted.
+    pub fn call_with_overrides(
+        &self,
+        from: Address,
+        to: Address,
+        calldata: Bytes,
+        value: U256,
+        overrides: &StateOverride,
+    ) -> eyre::Result<RawCallResult> {
+        let mut executor = self.clone();
+        executor.apply_state_override(overrides)?;
+        executor.call_raw(from, to, calldata, value)
+    }
+
+    /// Applies the given state overrides to the current state of the VM.
+    pub fn apply_state_override(&mut self, overrides: &StateOverride) -> eyre::Result<()> {
+        let backend = self.backend_mut();
+        for (&address, account_override) in overrides {
+            let mut info = backend.basic_ref(address)?.unwrap_or_default();
+            if let Some(nonce) = account_override.nonce {
+                info.nonce = nonce.to();
+            }
+            if let Some(balance) = account_override.balance {
+                info.balance = balance;
+            }
+            if let Some(code) = &account_override.code {
+                let code = Bytecode::new_raw(code.clone());
+                info.code_hash = code.hash_slow();
+                info.code = Some(code);
+            }
+            backend.insert_account_info(address, info);
+
+            match (&account_override.state, &account_override.state_diff) {
+                (Some(_), Some(_)) => {
+                    eyre::bail!("state and state_diff can't be used together for {address}")
+                }
+                // the full storage is replaced, unset slots are zero
+                (Some(state), None) => backend.replace_account_storage(
+                    address,
+                    state.iter().map(|(slot, value)| ((*slot).into(), (*value).into())).collect(),
+                )?,
+                (None, Some(state_diff)) => {
+                    for (slot, value) in state_diff {
+                        backend.insert_account_storage(address, (*slot).into(), (*value).into())?;
+                    }
+                }
+                (None, None) => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Prepares the execution of a transaction of a custom type registered for the chain of the
+    /// VM, by validating it and applying the preamble of its type to the current state.
+    ///
+    /// Does nothing for transactions of other types.
+    pub fn apply_custom_tx_type(
+        &mut self,
+        tx: &WithOtherFields<TransactionRequest>,
+    ) -> eyre::Result<()> {
+        let Some(tx_type) = custom_tx_type(self.env().cfg.chain_id, tx.transaction_type) else {
+            return Ok(())
+        };
+        tx_type.validate(tx).wrap_err_with(|| format!("invalid {} transaction", tx_type.name()))?;
+        let env = self.env().clone();
+        tx_type.preamble(tx, &env, &mut self.backend).wrap_err_with(|| {
+            format!("failed to apply the {} transaction preamble", tx_type.name())
+        })
+    }
+
     /// Performs a raw call to an account on the current state of the VM.
     pub fn transact_raw(
         &mut self,
@@ -391,7 +528,7 @@ impl Executor {
     pub fn call_with_env(&self, mut env: EnvWithHandlerCfg) -> eyre::Result<RawCallResult> {
         let mut inspector = self.inspector().clone();
         let mut backend = CowBackend::new_borrowed(self.backend());
-        let result = backend.inspect(&mut env, &mut inspector)?;
+        let result = self.strategy.call(&mut backend, &mut env, &mut inspector)?;
         convert_executed_result(env, inspector, result, backend.has_snapshot_failure())
     }
 
@@ -399,14 +536,83 @@ impl Executor {
     #[instrument(name = "transact", level = "debug", skip_all)]
     pub fn transact_with_env(&mut self, mut env: EnvWithHandlerCfg) -> eyre::Result<RawCallResult> {
         let mut inspector = self.inspector().clone();
+        let strategy = self.strategy.clone();
         let backend = self.backend_mut();
-        let result = backend.inspect(&mut env, &mut inspector)?;
+        let result = strategy.transact(backend, &mut env, &mut inspector)?;
         let mut result =
             convert_executed_result(env, inspector, result, backend.has_snapshot_failure())?;
         self.commit(&mut result);
         Ok(result)
     }
 
+    /// Executes an ordered bundle of transactions on top of the current state, in the block
+    /// configured in `self.env`.
+    ///
+    /// The state changes of every transaction, including reverted ones, are committed before the
+    /// next one is executed.
+    #[instrument(name = "simulate_bundle", level = "debug", skip_all)]
+    pub fn simulate_bundle(
+        &mut self,
+        txs: impl IntoIterator<Item = TxEnv>,
+    ) -> eyre::Result<Vec<BundleTransactionResult>> {
+        let mut results = Vec::new();
+        let mut cumulative_gas_used = 0;
+        for (index, tx) in txs.into_iter().enumerate() {
+            let mut env =
+                EnvWithHandlerCfg::new_with_spec_id(Box::new(self.env().clone()), self.spec_id());
+            env.tx = tx;
+
+            let mut inspector = self.inspector().clone();
+            let strategy = self.strategy.clone();
+            let backend = self.backend_mut();
+            let result = strategy
+                .transact(backend, &mut env, &mut inspector)
+                .wrap_err_with(|| format!("failed to execute transaction {index} of the bundle"))?;
+            let mut raw =
+                convert_executed_result(env, inspector, result, backend.has_snapshot_failure())?;
+
+            // diff against the state before committing the transaction
+            let state_diff = self.diff_changeset(&raw.state_changeset)?;
+            self.commit(&mut raw);
+
+            cumulative_gas_used += raw.gas_used;
+            results.push(BundleTransactionResult { raw, state_diff, cumulative_gas_used });
+        }
+        Ok(results)
+    }
+
+    /// Executes a transaction on the current state without committing it, and returns the changes
+    /// it would make to the accounts.
+    pub fn state_diff(
+        &self,
+        from: Address,
+        to: TxKind,
+        calldata: Bytes,
+        value: U256,
+    ) -> eyre::Result<BTreeMap<Address, AccountDiff>> {
+        let env = self.build_test_env(from, to, calldata, value);
+        let result = self.call_with_env(env)?;
+        Ok(self.diff_changeset(&result.state_changeset)?)
+    }
+
+    /// Diffs the touched accounts of the changeset against the current state.
+    fn diff_changeset(
+        &self,
+        changeset: &StateChangeset,
+    ) -> DatabaseResult<BTreeMap<Address, AccountDiff>> {
+        let mut state_diff = BTreeMap::new();
+        for (address, account) in changeset {
+            if !account.is_touched() {
+                continue
+            }
+            let diff = AccountDiff::new(self.backend(), account, *address)?;
+            if !diff.is_empty() {
+                state_diff.insert(*address, diff);
+            }
+        }
+        Ok(state_diff)
+    }
+
     /// Commit the changeset to the database and adjust `self.inspector_config` values according to
     /// the executed call result.
     ///
@@ -709,8 +915,21 @@ pub struct RawCallResult {
     pub labels: HashMap<Address, String>,
     /// The traces of the call
     pub traces: Option<CallTraceArena>,
+    /// The gas refunded to the calls of `traces`, keyed by node. The refund of the root call is
+    /// capped like the refund of the transaction.
+    pub gas_refunds: GasRefunds,
     /// The coverage info collected during the call
     pub coverage: Option<HitMaps>,
+    /// The hashes of the control flow edges taken during the call
+    pub edge_coverage: Option<HashSet<u64>>,
+    /// The comparisons involving fuzzed inputs made during the call
+    pub branch_comparisons: Option<BranchComparisons>,
+    /// The preimages of the `KECCAK256` hashes computed during the call
+    pub keccak_preimages: Option<KeccakPreimages>,
+    /// The memory, calldata and returndata sizes of the call frames entered during the call
+    pub memory_profile: Option<MemoryProfile>,
+    /// The wall-clock time spent in the functions called during the call
+    pub time_profile: Option<TimeProfile>,
     /// Scripted transactions generated from this call
     pub transactions: Option<BroadcastableTransactions>,
     /// The changeset of the state.
@@ -725,6 +944,65 @@ pub struct RawCallResult {
     pub chisel_state: Option<(Vec<U256>, Vec<u8>, InstructionResult)>,
 }
 
+/// The result of a transaction executed as part of a bundle by [`Executor::simulate_bundle`].
+#[derive(Debug)]
+pub struct BundleTransactionResult {
+    /// The result of the transaction.
+    pub raw: RawCallResult,
+    /// The accounts changed by the transaction.
+    pub state_diff: BTreeMap<Address, AccountDiff>,
+    /// The gas used by the bundle up to and including this transaction.
+    pub cumulative_gas_used: u64,
+}
+
+/// The changes a transaction made to an account, as `(before, after)` pairs.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AccountDiff {
+    pub balance: Option<(U256, U256)>,
+    pub nonce: Option<(u64, u64)>,
+    pub code: Option<(Bytes, Bytes)>,
+    pub storage: BTreeMap<U256, (U256, U256)>,
+}
+
+impl AccountDiff {
+    /// Diffs the changed `account` against its state in `db`.
+    fn new(db: &Backend, account: &Account, address: Address) -> DatabaseResult<Self> {
+        let before = db.basic_ref(address)?.unwrap_or_default();
+        let after = &account.info;
+
+        let code = if before.code_hash != after.code_hash {
+            let before_code = match before.code {
+                Some(code) => code.original_bytes(),
+                None => db.code_by_hash_ref(before.code_hash)?.original_bytes(),
+            };
+            let after_code = after.code.as_ref().map(|code| code.original_bytes());
+            Some((before_code, after_code.unwrap_or_default()))
+        } else {
+            None
+        };
+
+        Ok(Self {
+            balance: (before.balance != after.balance).then_some((before.balance, after.balance)),
+            nonce: (before.nonce != after.nonce).then_some((before.nonce, after.nonce)),
+            code,
+            storage: account
+                .storage
+                .iter()
+                .filter(|(_, slot)| slot.is_changed())
+                .map(|(key, slot)| (*key, (slot.original_value, slot.present_value)))
+                .collect(),
+        })
+    }
+
+    /// Returns `true` if the account didn't change.
+    pub fn is_empty(&self) -> bool {
+        self.balance.is_none() &&
+            self.nonce.is_none() &&
+            self.code.is_none() &&
+            self.storage.is_empty()
+    }
+}
+
 impl Default for RawCallResult {
     fn default() -> Self {
         Self {
@@ -738,7 +1016,13 @@ impl Default for RawCallResult {
             logs: Vec::new(),
             labels: HashMap::new(),
             traces: None,
+            gas_refunds: GasRefunds::new(),
             coverage: None,
+            edge_coverage: None,
+            branch_comparisons: None,
+            keccak_preimages: None,
+            memory_profile: None,
+            time_profile: None,
             transactions: None,
             state_changeset: HashMap::default(),
             env: EnvWithHandlerCfg::new_with_spec_id(Box::default(), SpecId::LATEST),
@@ -851,8 +1135,27 @@ fn convert_executed_result(
         _ => Bytes::new(),
     };
 
-    let InspectorData { mut logs, labels, traces, coverage, cheatcodes, chisel_state } =
-        inspector.collect();
+    let InspectorData {
+        mut logs,
+        labels,
+        traces,
+        mut gas_refunds,
+        coverage,
+        edge_coverage,
+        branch_comparisons,
+        keccak_preimages,
+        memory_profile,
+        time_profile,
+        cheatcodes,
+        chisel_state,
+    } = inspector.collect();
+
+    // The refund of the root call is the one of the transaction, after the refund cap.
+    if gas_refunded > 0 {
+        gas_refunds.insert(0, gas_refunded);
+    } else {
+        gas_refunds.remove(&0);
+    }
 
     if logs.is_empty() {
         logs = exec_logs;
@@ -874,7 +1177,13 @@ fn convert_executed_result(
         logs,
         labels,
         traces,
+        gas_refunds,
         coverage,
+        edge_coverage,
+        branch_comparisons,
+        keccak_preimages,
+        memory_profile,
+        time_profile,
         transactions,
         state_changeset,
         env,
@@ -0,0 +1,134 @@
+use crate::inspectors::InspectorStack;
+use foundry_evm_core::backend::{Backend, CowBackend};
+use revm::primitives::{EnvWithHandlerCfg, ResultAndState};
+use std::{fmt, sync::Arc};
+
+/// The VM an [`Executor`](super::Executor) delegates the execution of transactions to.
+///
+/// The default [`EvmExecutorStrategy`] executes everything with revm. Alternative strategies can
+/// execute transactions with a different VM, such as the zkSync era VM or Arbitrum Stylus, either
+/// for all of them or only for some, e.g. depending on the target of `env.tx` or on the chain
+/// configured in `env.cfg`, and fall back to [`EvmExecutorStrategy`] for the rest.
+///
+/// Strategies are given the executor's [`InspectorStack`] and are expected to drive it, so that
+/// cheatcodes, tracing, logs and coverage keep working regardless of the VM.
+pub trait ExecutorStrategy: fmt::Debug + Send + Sync {
+    /// Returns the name of the strategy.
+    fn name(&self) -> &'static str;
+
+    /// Executes the transaction configured in `env.tx` on top of the given backend, without
+    /// committing the state changes.
+    ///
+    /// Used for calls, whose state changes are discarded.
+    fn call(
+        &self,
+        backend: &mut CowBackend<'_>,
+        env: &mut EnvWithHandlerCfg,
+        inspector: &mut InspectorStack,
+    ) -> eyre::Result<ResultAndState>;
+
+    /// Executes the transaction configured in `env.tx` on top of the given backend, without
+    /// committing the state changes.
+    ///
+    /// Used for transactions, whose state changes are committed by the executor afterwards.
+    fn transact(
+        &self,
+        backend: &mut Backend,
+        env: &mut EnvWithHandlerCfg,
+        inspector: &mut InspectorStack,
+    ) -> eyre::Result<ResultAndState>;
+}
+
+/// The default [`ExecutorStrategy`], executing all transactions with revm.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct EvmExecutorStrategy;
+
+impl EvmExecutorStrategy {
+    /// Returns the strategy as a shared trait object.
+    pub fn shared() -> Arc<dyn ExecutorStrategy> {
+        Arc::new(Self)
+    }
+}
+
+impl ExecutorStrategy for EvmExecutorStrategy {
+    fn name(&self) -> &'static str {
+        "evm"
+    }
+
+    fn call(
+        &self,
+        backend: &mut CowBackend<'_>,
+        env: &mut EnvWithHandlerCfg,
+        inspector: &mut InspectorStack,
+    ) -> eyre::Result<ResultAndState> {
+        backend.inspect(env, inspector)
+    }
+
+    fn transact(
+        &self,
+        backend: &mut Backend,
+        env: &mut EnvWithHandlerCfg,
+        inspector: &mut InspectorStack,
+    ) -> eyre::Result<ResultAndState> {
+        backend.inspect(env, inspector)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::executors::ExecutorBuilder;
+    use alloy_primitives::{Address, Bytes, U256};
+    use revm::primitives::Env;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Counts the transactions it delegates to revm.
+    #[derive(Debug, Default)]
+    struct CountingStrategy {
+        calls: AtomicUsize,
+        transactions: AtomicUsize,
+    }
+
+    impl ExecutorStrategy for CountingStrategy {
+        fn name(&self) -> &'static str {
+            "counting"
+        }
+
+        fn call(
+            &self,
+            backend: &mut CowBackend<'_>,
+            env: &mut EnvWithHandlerCfg,
+            inspector: &mut InspectorStack,
+        ) -> eyre::Result<ResultAndState> {
+            self.calls.fetch_add(1, Ordering::Relaxed);
+            EvmExecutorStrategy.call(backend, env, inspector)
+        }
+
+        fn transact(
+            &self,
+            backend: &mut Backend,
+            env: &mut EnvWithHandlerCfg,
+            inspector: &mut InspectorStack,
+        ) -> eyre::Result<ResultAndState> {
+            self.transactions.fetch_add(1, Ordering::Relaxed);
+            EvmExecutorStrategy.transact(backend, env, inspector)
+        }
+    }
+
+    #[test]
+    fn executes_with_strategy() {
+        let strategy = Arc::new(CountingStrategy::default());
+        let mut executor = ExecutorBuilder::new()
+            .strategy(strategy.clone())
+            .build(Env::default(), Backend::spawn(None));
+        assert_eq!(executor.strategy().name(), "counting");
+
+        let to = Address::with_last_byte(1);
+        executor.call_raw(Address::ZERO, to, Bytes::new(), U256::ZERO).unwrap();
+        executor.transact_raw(Address::ZERO, to, Bytes::new(), U256::ZERO).unwrap();
+        executor.clone().call_raw(Address::ZERO, to, Bytes::new(), U256::ZERO).unwrap();
+
+        assert_eq!(strategy.calls.load(Ordering::Relaxed), 2);
+        assert_eq!(strategy.transactions.load(Ordering::Relaxed), 1);
+    }
+}
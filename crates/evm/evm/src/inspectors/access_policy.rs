@@ -0,0 +1,302 @@
+use alloy_primitives::{keccak256, Address, Bytes, B256, U256};
+use foundry_config::Config;
+use foundry_evm_core::outcome::FrameOverride;
+use parking_lot::Mutex;
+use revm::{
+    interpreter::{
+        opcode, CallInputs, CallOutcome, CreateInputs, CreateOutcome, Interpreter,
+        InterpreterResult,
+    },
+    Database, EvmContext, Inspector,
+};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Write,
+    ops::Range,
+    sync::Arc,
+};
+
+/// Restrictions on the accounts a test is allowed to access.
+///
+/// See [`AccessPolicyEnforcer`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AccessPolicy {
+    /// The addresses of the pre-existing contracts whose storage may be written to.
+    ///
+    /// If set, storage writes are only allowed to these contracts and to contracts created during
+    /// execution.
+    pub write_allowlist: Option<HashSet<Address>>,
+    /// The addresses that may not be called or read from.
+    pub read_denylist: HashSet<Address>,
+    /// The contracts whose code may only write to the storage declared by their layout.
+    pub declared_storage: Vec<DeclaredStorage>,
+}
+
+impl AccessPolicy {
+    /// Returns the policy configured in the given config.
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            write_allowlist: config
+                .write_allowlist
+                .as_ref()
+                .map(|allowlist| allowlist.iter().copied().collect()),
+            read_denylist: config.read_denylist.iter().copied().collect(),
+            declared_storage: Vec::new(),
+        }
+    }
+
+    /// Returns true if the policy doesn't restrict anything.
+    pub fn is_empty(&self) -> bool {
+        self.write_allowlist.is_none() &&
+            self.read_denylist.is_empty() &&
+            self.declared_storage.is_empty()
+    }
+}
+
+/// The storage slots that the code of a contract is allowed to write to, as declared by its storage
+/// layout.
+///
+/// Writes are checked wherever the code runs, including through a proxy that delegates to it.
+/// Slots of at least 2^64 are assumed to be derived from a hash, e.g. mapping entries or namespaced
+/// storage, and are always allowed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DeclaredStorage {
+    /// The name of the contract, used to describe violations.
+    pub name: String,
+    /// The deployed code of the contract.
+    pub code: Bytes,
+    /// The ranges of the code that differ between deployments, e.g. immutables, which are ignored
+    /// when matching code.
+    pub ignored: Vec<Range<usize>>,
+    /// The slots of the state variables, excluding storage gaps.
+    pub slots: Vec<Range<U256>>,
+}
+
+impl DeclaredStorage {
+    /// The first slot that is assumed to be derived from a hash.
+    pub const HASHED_SLOTS_START: U256 = U256::from_limbs([0, 1, 0, 0]);
+
+    /// Returns true if the given code is the code of the contract.
+    pub fn matches_code(&self, code: &[u8]) -> bool {
+        code.len() == self.code.len() &&
+            code.iter()
+                .zip(self.code.iter())
+                .enumerate()
+                .all(|(i, (a, b))| a == b || self.ignored.iter().any(|range| range.contains(&i)))
+    }
+
+    /// Returns true if the contract may write to the given slot.
+    pub fn declares(&self, slot: U256) -> bool {
+        slot >= Self::HASHED_SLOTS_START || self.slots.iter().any(|range| range.contains(&slot))
+    }
+}
+
+/// An inspector that enforces an [`AccessPolicy`].
+///
+/// Once the policy is violated, execution halts and every frame up to the top-level call reverts
+/// with a message describing the violation and the call path that led to it, even if the revert
+/// is caught on the way.
+#[derive(Clone, Debug, Default)]
+pub struct AccessPolicyEnforcer {
+    /// The enforced policy.
+    policy: AccessPolicy,
+    /// The contracts created during execution, which may always be written to.
+    ///
+    /// Shared between clones so that contracts deployed in earlier calls, such as the test
+    /// contract itself or the ones deployed in `setUp`, are known to later calls.
+    created: Arc<Mutex<HashSet<Address>>>,
+    /// The addresses of the frames leading to the current one, indexed by depth.
+    ///
+    /// `None` for a contract creation that hasn't started executing yet.
+    path: Vec<Option<Address>>,
+    /// The description of the first violation, if any.
+    violation: Option<String>,
+    /// The index of the [`DeclaredStorage`] matching each code hash seen so far, if any.
+    declared_storage: HashMap<B256, Option<usize>>,
+}
+
+impl AccessPolicyEnforcer {
+    /// Creates a new enforcer for the given policy.
+    pub fn new(policy: AccessPolicy) -> Self {
+        Self { policy, ..Default::default() }
+    }
+
+    /// Records that the policy was violated at the given depth. Only the first violation is
+    /// reported.
+    fn violated(&mut self, depth: usize, violation: String) {
+        if self.violation.is_some() {
+            return
+        }
+        let mut message = format!("access policy violation: {violation}; call path: ");
+        for (i, address) in self.path.iter().take(depth).enumerate() {
+            if i > 0 {
+                message.push_str(" -> ");
+            }
+            match address {
+                Some(address) => write!(message, "{address}").unwrap(),
+                None => message.push_str("<create>"),
+            }
+        }
+        self.violation = Some(message);
+    }
+
+    /// Returns true if the storage of the given contract may be written to.
+    fn can_write(&self, address: Address) -> bool {
+        self.policy.write_allowlist.as_ref().map_or(true, |allowlist| {
+            allowlist.contains(&address) || self.created.lock().contains(&address)
+        })
+    }
+
+    /// Returns the violation if the `SSTORE` about to be executed writes outside of the storage
+    /// declared by the code being executed.
+    fn check_declared_storage(&mut self, interp: &Interpreter) -> Option<String> {
+        if self.policy.declared_storage.is_empty() {
+            return None
+        }
+        let slot = interp.stack().peek(0).ok()?;
+        let code = interp.contract.bytecode.original_byte_slice();
+        let hash = interp.contract.hash.unwrap_or_else(|| keccak256(code));
+        let policy = &self.policy;
+        let index = (*self.declared_storage.entry(hash).or_insert_with(|| {
+            policy.declared_storage.iter().position(|declared| declared.matches_code(code))
+        }))?;
+        let declared = &policy.declared_storage[index];
+        (!declared.declares(slot)).then(|| {
+            format!(
+                "storage write to slot {slot:#x} of {} is outside the storage layout of {}",
+                interp.contract.target_address, declared.name
+            )
+        })
+    }
+
+    /// Overrides the given result with a revert if the policy was violated.
+    fn enforce(&self, result: &mut InterpreterResult) {
+        if let Some(revert) = self.check_frame() {
+            revert.apply(result);
+        }
+    }
+
+    /// Records a new frame at the given depth in the call path.
+    fn enter_frame(&mut self, depth: usize, address: Option<Address>) {
+        self.path.truncate(depth);
+        self.path.push(address);
+    }
+
+    /// Returns the revert to end frames with if the policy was violated.
+    fn check_frame(&self) -> Option<FrameOverride> {
+        self.violation.as_ref().map(|message| FrameOverride::cheatcode_error(message.clone()))
+    }
+}
+
+impl<DB: Database> Inspector<DB> for AccessPolicyEnforcer {
+    #[inline]
+    fn step(&mut self, interp: &mut Interpreter, ecx: &mut EvmContext<DB>) {
+        let depth = ecx.journaled_state.depth();
+        let address = interp.contract.target_address;
+
+        // The address of a created contract is only known once its initcode runs.
+        if let Some(frame) = depth.checked_sub(1).and_then(|i| self.path.get_mut(i)) {
+            if frame.is_none() {
+                *frame = Some(address);
+                self.created.lock().insert(address);
+            }
+        }
+
+        match interp.current_opcode() {
+            opcode::SSTORE => {
+                if !self.can_write(address) {
+                    self.violated(depth, format!("storage write to {address} is not allowed"));
+                } else if let Some(violation) = self.check_declared_storage(interp) {
+                    self.violated(depth, violation);
+                }
+            }
+            opcode::BALANCE | opcode::EXTCODESIZE | opcode::EXTCODECOPY | opcode::EXTCODEHASH => {
+                if let Ok(word) = interp.stack().peek(0) {
+                    let target = Address::from_word(word.into());
+                    if self.policy.read_denylist.contains(&target) {
+                        self.violated(depth, format!("read from {target} is denied"));
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        // Halt before executing anything else once the policy is violated.
+        if let Some(revert) = self.check_frame() {
+            revert.apply_to_interpreter(interp);
+        }
+    }
+
+    fn call(&mut self, ecx: &mut EvmContext<DB>, inputs: &mut CallInputs) -> Option<CallOutcome> {
+        let depth = ecx.journaled_state.depth();
+        self.enter_frame(depth, Some(inputs.target_address));
+        // Delegate calls execute the code of the bytecode address.
+        for address in [inputs.target_address, inputs.bytecode_address] {
+            if self.policy.read_denylist.contains(&address) {
+                self.violated(depth + 1, format!("call to {address} is denied"));
+                break
+            }
+        }
+        Some(self.check_frame()?.into_call_outcome(inputs))
+    }
+
+    fn call_end(
+        &mut self,
+        _context: &mut EvmContext<DB>,
+        _inputs: &CallInputs,
+        mut outcome: CallOutcome,
+    ) -> CallOutcome {
+        self.enforce(&mut outcome.result);
+        outcome
+    }
+
+    fn create(
+        &mut self,
+        ecx: &mut EvmContext<DB>,
+        inputs: &mut CreateInputs,
+    ) -> Option<CreateOutcome> {
+        let depth = ecx.journaled_state.depth();
+        self.enter_frame(depth, None);
+        Some(self.check_frame()?.into_create_outcome(inputs))
+    }
+
+    fn create_end(
+        &mut self,
+        _context: &mut EvmContext<DB>,
+        _inputs: &CreateInputs,
+        mut outcome: CreateOutcome,
+    ) -> CreateOutcome {
+        if let Some(address) = outcome.address {
+            self.created.lock().insert(address);
+        }
+        self.enforce(&mut outcome.result);
+        outcome
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::bytes;
+
+    #[test]
+    fn checks_declared_storage() {
+        let declared = DeclaredStorage {
+            name: "Counter".to_string(),
+            code: bytes!("6001600055"),
+            ignored: vec![1..2],
+            slots: vec![U256::ZERO..U256::from(2), U256::from(52)..U256::from(53)],
+        };
+
+        assert!(declared.matches_code(&bytes!("6001600055")));
+        assert!(declared.matches_code(&bytes!("60ff600055")));
+        assert!(!declared.matches_code(&bytes!("6001600155")));
+        assert!(!declared.matches_code(&bytes!("600160005500")));
+
+        assert!(declared.declares(U256::from(1)));
+        assert!(declared.declares(U256::from(52)));
+        assert!(!declared.declares(U256::from(2)));
+        assert!(declared.declares(DeclaredStorage::HASHED_SLOTS_START));
+        assert!(declared.declares(U256::from_be_bytes(keccak256("slot").0)));
+    }
+}
@@ -0,0 +1,82 @@
+use alloy_primitives::{B256, U256};
+use foundry_evm_core::{backend::DatabaseExt, constants::HISTORY_SERVE_WINDOW};
+use revm::{
+    interpreter::{opcode, InstructionResult, Interpreter},
+    primitives::BLOCK_HASH_HISTORY,
+    Database, EvmContext, Inspector,
+};
+
+/// An inspector that serves the hashes of blocks older than the 256 most recent ones to
+/// `BLOCKHASH`, for which the EVM pushes zero.
+///
+/// In forking mode the hashes are fetched through the fork backend, so that the real hashes of the
+/// blocks of the forked chain are returned however old they are. Otherwise the hashes of the last
+/// [`HISTORY_SERVE_WINDOW`] blocks are served from the database, like the block hash history
+/// contract of EIP-2935.
+#[derive(Clone, Debug, Default)]
+pub struct BlockHashOracle {
+    /// The hash to push in place of the result of the `BLOCKHASH` being executed, if any.
+    pending: Option<B256>,
+}
+
+impl BlockHashOracle {
+    /// Returns whether the hash of block `number` is served by the oracle at block `current`.
+    fn serves(number: U256, current: U256, is_forking: bool) -> bool {
+        let Some(age) = current.checked_sub(number) else { return false };
+        age > U256::from(BLOCK_HASH_HISTORY) &&
+            (is_forking || age <= U256::from(HISTORY_SERVE_WINDOW))
+    }
+}
+
+impl<DB: DatabaseExt> Inspector<DB> for BlockHashOracle {
+    #[inline]
+    fn step(&mut self, interp: &mut Interpreter, ecx: &mut EvmContext<DB>) {
+        if interp.current_opcode() != opcode::BLOCKHASH {
+            return
+        }
+        let Ok(number) = interp.stack().peek(0) else { return };
+        let is_forking = ecx.db.active_fork_id().is_some();
+        if !Self::serves(number, ecx.env.block.number, is_forking) {
+            return
+        }
+        match ecx.db.block_hash(number) {
+            Ok(hash) => self.pending = Some(hash),
+            Err(err) => warn!(%number, %err, "failed to get historical block hash"),
+        }
+    }
+
+    #[inline]
+    fn step_end(&mut self, interp: &mut Interpreter, _ecx: &mut EvmContext<DB>) {
+        if let Some(hash) = self.pending.take() {
+            if interp.instruction_result == InstructionResult::Continue {
+                if let Some(top) = interp.stack.data_mut().last_mut() {
+                    *top = U256::from_be_bytes(hash.0);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serves_blocks_past_the_evm_window() {
+        let current = U256::from(10_000);
+        let at = |age: u64| current - U256::from(age);
+
+        // Recent blocks are left to the EVM.
+        assert!(!BlockHashOracle::serves(at(1), current, false));
+        assert!(!BlockHashOracle::serves(at(256), current, true));
+        // Blocks at or after the current one have no hash.
+        assert!(!BlockHashOracle::serves(current, current, true));
+        assert!(!BlockHashOracle::serves(current + U256::from(1), current, true));
+
+        assert!(BlockHashOracle::serves(at(257), current, false));
+        assert!(BlockHashOracle::serves(at(8191), current, false));
+        assert!(!BlockHashOracle::serves(at(8192), current, false));
+        // Any block of the forked chain is served.
+        assert!(BlockHashOracle::serves(at(9_999), current, true));
+    }
+}
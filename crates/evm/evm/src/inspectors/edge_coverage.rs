@@ -0,0 +1,52 @@
+use alloy_primitives::B256;
+use revm::{
+    interpreter::{opcode, InstructionResult, Interpreter},
+    Database, EvmContext, Inspector,
+};
+use std::{
+    collections::{hash_map::DefaultHasher, HashSet},
+    hash::{Hash, Hasher},
+};
+
+/// An inspector that collects the control flow edges taken during execution.
+///
+/// An edge is the `(pc, jump destination)` pair of an executed `JUMP` or `JUMPI` in a given
+/// bytecode. Edges are stored as hashes so that the edges hit by a single run can be cheaply merged
+/// into the set of edges seen by the coverage-guided fuzzer.
+#[derive(Clone, Debug, Default)]
+pub struct EdgeCoverageCollector {
+    /// The hashes of the edges hit so far.
+    pub edges: HashSet<u64>,
+    /// The bytecode hash and program counter of the jump being executed, if any.
+    jump: Option<(Option<B256>, usize)>,
+}
+
+impl EdgeCoverageCollector {
+    /// Returns the hash of the edge from `pc` to `dest` in the bytecode with the given hash.
+    fn edge(code_hash: Option<B256>, pc: usize, dest: usize) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        (code_hash, pc, dest).hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+impl<DB: Database> Inspector<DB> for EdgeCoverageCollector {
+    #[inline]
+    fn step(&mut self, interp: &mut Interpreter, _context: &mut EvmContext<DB>) {
+        let op = interp.current_opcode();
+        if op == opcode::JUMP || op == opcode::JUMPI {
+            self.jump = Some((interp.contract.hash, interp.program_counter()));
+        }
+    }
+
+    #[inline]
+    fn step_end(&mut self, interp: &mut Interpreter, _context: &mut EvmContext<DB>) {
+        // The destination is only known once the jump has been executed, for `JUMPI` it is either
+        // the jump target or the next instruction.
+        if let Some((code_hash, pc)) = self.jump.take() {
+            if interp.instruction_result == InstructionResult::Continue {
+                self.edges.insert(Self::edge(code_hash, pc, interp.program_counter()));
+            }
+        }
+    }
+}
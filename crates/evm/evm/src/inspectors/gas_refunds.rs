@@ -0,0 +1,128 @@
+use foundry_evm_traces::GasRefunds;
+use revm::{
+    interpreter::{
+        CallInputs, CallOutcome, CreateInputs, CreateOutcome, EOFCreateInputs, InterpreterResult,
+    },
+    Database, EvmContext, Inspector,
+};
+
+/// An inspector that records the gas refunded to every call, alongside the tracer.
+///
+/// Calls are numbered in the order they start, which is the order the tracer adds their nodes to
+/// its arena in, so that the refunds can be rendered with the traces and reported with their gas.
+#[derive(Clone, Debug, Default)]
+pub struct GasRefundRecorder {
+    /// The number of calls started so far.
+    calls: usize,
+    /// The nodes of the calls being executed.
+    stack: Vec<usize>,
+    /// The non-zero refunds of the finished calls, keyed by node.
+    pub refunds: GasRefunds,
+}
+
+impl GasRefundRecorder {
+    fn enter(&mut self) {
+        self.stack.push(self.calls);
+        self.calls += 1;
+    }
+
+    fn exit(&mut self, result: &InterpreterResult) {
+        let Some(node) = self.stack.pop() else { return };
+        // The refunds of reverted calls are discarded.
+        if !result.result.is_ok() {
+            return
+        }
+        // The refund counter of a frame goes negative when it restores a slot cleared by a parent.
+        let refund = result.gas.refunded().max(0) as u64;
+        if refund > 0 {
+            self.refunds.insert(node, refund);
+        }
+    }
+}
+
+impl<DB: Database> Inspector<DB> for GasRefundRecorder {
+    fn call(&mut self, _ecx: &mut EvmContext<DB>, _inputs: &mut CallInputs) -> Option<CallOutcome> {
+        self.enter();
+        None
+    }
+
+    fn call_end(
+        &mut self,
+        _ecx: &mut EvmContext<DB>,
+        _inputs: &CallInputs,
+        outcome: CallOutcome,
+    ) -> CallOutcome {
+        self.exit(&outcome.result);
+        outcome
+    }
+
+    fn create(
+        &mut self,
+        _ecx: &mut EvmContext<DB>,
+        _inputs: &mut CreateInputs,
+    ) -> Option<CreateOutcome> {
+        self.enter();
+        None
+    }
+
+    fn create_end(
+        &mut self,
+        _ecx: &mut EvmContext<DB>,
+        _inputs: &CreateInputs,
+        outcome: CreateOutcome,
+    ) -> CreateOutcome {
+        self.exit(&outcome.result);
+        outcome
+    }
+
+    fn eofcreate(
+        &mut self,
+        _ecx: &mut EvmContext<DB>,
+        _inputs: &mut EOFCreateInputs,
+    ) -> Option<CreateOutcome> {
+        self.enter();
+        None
+    }
+
+    fn eofcreate_end(
+        &mut self,
+        _ecx: &mut EvmContext<DB>,
+        _inputs: &EOFCreateInputs,
+        outcome: CreateOutcome,
+    ) -> CreateOutcome {
+        self.exit(&outcome.result);
+        outcome
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use revm::interpreter::{Gas, InstructionResult};
+
+    fn result(refund: i64) -> InterpreterResult {
+        let mut gas = Gas::new(100_000);
+        gas.record_refund(refund);
+        InterpreterResult { result: InstructionResult::Return, output: Default::default(), gas }
+    }
+
+    #[test]
+    fn records_refunds_by_node() {
+        let mut recorder = GasRefundRecorder::default();
+        // root -> (a -> b), c, d
+        recorder.enter();
+        recorder.enter();
+        recorder.enter();
+        recorder.exit(&result(4_800));
+        recorder.exit(&result(-2_800));
+        recorder.enter();
+        recorder.exit(&result(0));
+        // d reverts
+        recorder.enter();
+        let mut reverted = result(2_400);
+        reverted.result = InstructionResult::Revert;
+        recorder.exit(&reverted);
+        recorder.exit(&result(19_900));
+        assert_eq!(recorder.refunds, GasRefunds::from([(0, 19_900), (2, 4_800)]));
+    }
+}
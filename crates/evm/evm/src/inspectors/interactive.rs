@@ -0,0 +1,170 @@
+use super::InterpreterView;
+use alloy_primitives::Address;
+use foundry_evm_traces::CallTraceArena;
+use parking_lot::Mutex;
+use revm::{interpreter::Interpreter, JournaledState};
+use std::{
+    fmt,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+/// What to do after a breakpoint has been handled.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BreakpointAction {
+    /// Resume execution until the next breakpoint.
+    Resume,
+    /// Execute the next instruction and pause again.
+    Step,
+    /// Resume execution and ignore all further breakpoints.
+    Detach,
+}
+
+/// The execution state at a breakpoint.
+pub struct BreakpointContext<'a> {
+    /// The label of the breakpoint, `None` when pausing after a step.
+    pub label: Option<&'a str>,
+    /// The address of the contract being executed.
+    pub address: Address,
+    /// The program counter of the instruction about to be executed.
+    pub pc: usize,
+    /// The interpreter, to inspect memory, the stack and the return data without copying them.
+    pub interpreter: InterpreterView<'a>,
+    /// The traces recorded so far, if tracing is enabled.
+    pub traces: Option<&'a CallTraceArena>,
+    /// The state of the accounts touched so far.
+    pub journaled_state: &'a JournaledState,
+}
+
+/// Handles the breakpoints hit during execution, e.g. by opening the debugger.
+///
+/// Execution is paused until the handler returns.
+pub trait BreakpointHandler: fmt::Debug + Send + Sync {
+    /// Called when a breakpoint is hit.
+    fn on_breakpoint(&self, context: &BreakpointContext<'_>) -> BreakpointAction;
+}
+
+/// A handle that inspectors can use to request execution to pause at the next instruction.
+///
+/// See [`InspectorStack::breakpoint_signal`](super::InspectorStack::breakpoint_signal).
+#[derive(Clone, Debug, Default)]
+pub struct BreakpointSignal(Arc<Mutex<Option<String>>>);
+
+impl BreakpointSignal {
+    /// Requests execution to pause with the given label.
+    pub fn trigger(&self, label: impl Into<String>) {
+        *self.0.lock() = Some(label.into());
+    }
+
+    /// Takes the label of the pending breakpoint, if any.
+    fn take(&self) -> Option<String> {
+        self.0.lock().take()
+    }
+}
+
+/// Pauses execution at breakpoints requested by the `breakpoint` cheatcode or through a
+/// [`BreakpointSignal`], and hands the current state over to a [`BreakpointHandler`].
+#[derive(Clone, Debug)]
+pub struct InteractiveDebugger {
+    /// The handler of the breakpoints.
+    handler: Arc<dyn BreakpointHandler>,
+    /// The signal used by inspectors to request a breakpoint.
+    signal: BreakpointSignal,
+    /// Whether to pause at the next instruction.
+    stepping: bool,
+    /// Whether the user detached from the debugger. Shared between clones so that it applies to
+    /// all further executions.
+    detached: Arc<AtomicBool>,
+}
+
+impl InteractiveDebugger {
+    /// Creates a new interactive debugger with the given breakpoint handler.
+    pub fn new(handler: Arc<dyn BreakpointHandler>) -> Self {
+        Self { handler, signal: Default::default(), stepping: false, detached: Default::default() }
+    }
+
+    /// Returns the signal used by inspectors to request a breakpoint.
+    pub fn signal(&self) -> &BreakpointSignal {
+        &self.signal
+    }
+
+    /// Pauses execution before the current instruction if a breakpoint was requested, either with
+    /// the given label or through the signal, or if stepping.
+    pub(crate) fn pause(
+        &mut self,
+        label: Option<String>,
+        interp: &Interpreter,
+        journaled_state: &JournaledState,
+        traces: Option<&CallTraceArena>,
+    ) {
+        let label = label.or_else(|| self.signal.take());
+        if self.detached.load(Ordering::Relaxed) || (label.is_none() && !self.stepping) {
+            return
+        }
+
+        let context = BreakpointContext {
+            label: label.as_deref(),
+            address: interp.contract.target_address,
+            pc: interp.program_counter(),
+            interpreter: InterpreterView::new(interp),
+            traces,
+            journaled_state,
+        };
+        let action = self.handler.on_breakpoint(&context);
+        self.stepping = action == BreakpointAction::Step;
+        if action == BreakpointAction::Detach {
+            self.detached.store(true, Ordering::Relaxed);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::executors::ExecutorBuilder;
+    use alloy_primitives::{bytes, Bytes, U256};
+    use foundry_evm_core::backend::Backend;
+    use revm::primitives::{AccountInfo, Bytecode, Env};
+
+    /// Steps once at the first breakpoint, then resumes.
+    #[derive(Debug, Default)]
+    struct RecordingHandler {
+        hits: Mutex<Vec<(Option<String>, usize)>>,
+        stacks: Mutex<Vec<Vec<U256>>>,
+    }
+
+    impl BreakpointHandler for RecordingHandler {
+        fn on_breakpoint(&self, context: &BreakpointContext<'_>) -> BreakpointAction {
+            let mut hits = self.hits.lock();
+            hits.push((context.label.map(String::from), context.pc));
+            self.stacks.lock().push(context.interpreter.stack_top(2).to_vec());
+            if hits.len() == 1 {
+                BreakpointAction::Step
+            } else {
+                BreakpointAction::Resume
+            }
+        }
+    }
+
+    #[test]
+    fn pauses_at_signaled_breakpoint() {
+        let handler = Arc::new(RecordingHandler::default());
+        let mut executor = ExecutorBuilder::new()
+            .inspectors(|stack| stack.interactive(handler.clone()))
+            .build(Env::default(), Backend::spawn(None));
+
+        // PUSH1 0x01 PUSH1 0x02 ADD STOP
+        let code = bytes!("600160020100");
+        let target = Address::with_last_byte(0x42);
+        let info = AccountInfo::from_bytecode(Bytecode::new_raw(code));
+        executor.backend_mut().insert_account_info(target, info);
+
+        executor.inspector().breakpoint_signal().unwrap().trigger("start");
+        executor.call_raw(Address::ZERO, target, Bytes::new(), U256::ZERO).unwrap();
+
+        assert_eq!(*handler.hits.lock(), [(Some("start".to_string()), 0), (None, 2)]);
+        assert_eq!(*handler.stacks.lock(), [vec![], vec![U256::from(1)]]);
+    }
+}
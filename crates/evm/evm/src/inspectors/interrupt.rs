@@ -0,0 +1,138 @@
+use foundry_evm_core::outcome::FrameOverride;
+use revm::{
+    interpreter::{CallInputs, CallOutcome, CreateInputs, CreateOutcome, Interpreter},
+    Database, EvmContext, Inspector,
+};
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+/// The revert message of the frames aborted by an [`Interrupter`].
+pub const CANCELLED_MESSAGE: &str = "execution cancelled";
+
+/// A cheaply cloneable flag used to request the cancellation of running executions.
+///
+/// All clones share the same flag, so the token can be handed to a signal handler or a timer
+/// while the executors check it.
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    /// Creates a new token that is not cancelled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Requests the cancellation of every execution observing this token.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns true if cancellation was requested.
+    #[inline]
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// An inspector that aborts execution once its [`CancellationToken`] is cancelled.
+///
+/// The token is checked at every step boundary. Once cancelled, the current instruction is not
+/// executed, new frames are not entered, and every frame up to the top-level call reverts with
+/// [`CANCELLED_MESSAGE`], so that the traces collected so far are still returned.
+#[derive(Clone, Debug)]
+pub struct Interrupter {
+    token: CancellationToken,
+}
+
+impl Interrupter {
+    /// Creates a new interrupter observing the given token.
+    pub fn new(token: CancellationToken) -> Self {
+        Self { token }
+    }
+
+    /// Returns the observed token.
+    pub fn token(&self) -> &CancellationToken {
+        &self.token
+    }
+
+    /// Returns the revert to end frames with if cancellation was requested.
+    fn check_frame(&self) -> Option<FrameOverride> {
+        self.token.is_cancelled().then(|| FrameOverride::cheatcode_error(CANCELLED_MESSAGE))
+    }
+}
+
+impl<DB: Database> Inspector<DB> for Interrupter {
+    #[inline]
+    fn step(&mut self, interp: &mut Interpreter, _context: &mut EvmContext<DB>) {
+        if let Some(revert) = self.check_frame() {
+            revert.apply_to_interpreter(interp);
+        }
+    }
+
+    fn call(
+        &mut self,
+        _context: &mut EvmContext<DB>,
+        inputs: &mut CallInputs,
+    ) -> Option<CallOutcome> {
+        Some(self.check_frame()?.into_call_outcome(inputs))
+    }
+
+    fn call_end(
+        &mut self,
+        _context: &mut EvmContext<DB>,
+        _inputs: &CallInputs,
+        mut outcome: CallOutcome,
+    ) -> CallOutcome {
+        if let Some(revert) = self.check_frame() {
+            revert.apply(&mut outcome.result);
+        }
+        outcome
+    }
+
+    fn create(
+        &mut self,
+        _context: &mut EvmContext<DB>,
+        inputs: &mut CreateInputs,
+    ) -> Option<CreateOutcome> {
+        Some(self.check_frame()?.into_create_outcome(inputs))
+    }
+
+    fn create_end(
+        &mut self,
+        _context: &mut EvmContext<DB>,
+        _inputs: &CreateInputs,
+        mut outcome: CreateOutcome,
+    ) -> CreateOutcome {
+        if let Some(revert) = self.check_frame() {
+            revert.apply(&mut outcome.result);
+        }
+        outcome
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::Bytes;
+    use alloy_sol_types::SolError;
+    use foundry_evm_core::abi::Vm;
+    use revm::interpreter::InstructionResult;
+
+    #[test]
+    fn token_clones_share_cancellation() {
+        let token = CancellationToken::new();
+        let interrupter = Interrupter::new(token.clone());
+        assert!(interrupter.check_frame().is_none());
+
+        token.cancel();
+        assert!(interrupter.token().is_cancelled());
+        let result = interrupter.check_frame().unwrap().into_result(1000);
+        assert_eq!(result.result, InstructionResult::Revert);
+        assert_eq!(
+            result.output,
+            Bytes::from(Vm::CheatcodeError { message: CANCELLED_MESSAGE.to_string() }.abi_encode())
+        );
+    }
+}
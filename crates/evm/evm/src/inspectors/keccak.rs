@@ -0,0 +1,186 @@
+use alloy_primitives::{Bytes, B256, U256};
+use revm::{
+    interpreter::{opcode, InstructionResult, Interpreter},
+    Database, EvmContext, Inspector,
+};
+use std::collections::HashMap;
+
+/// The maximum size of a `KECCAK256` input that is recorded.
+///
+/// Mapping and array slots are derived from inputs of at most 64 bytes, larger ones are only kept
+/// around so that hashes of short strings and packed keys can be resolved too.
+const MAX_PREIMAGE_SIZE: usize = 1024;
+
+/// The preimages of the hashes computed with `KECCAK256` during execution.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct KeccakPreimages {
+    /// The preimages, keyed by their hash.
+    pub preimages: HashMap<B256, Bytes>,
+}
+
+impl KeccakPreimages {
+    /// Records the preimage of the given hash.
+    pub fn insert(&mut self, hash: B256, preimage: Bytes) {
+        self.preimages.insert(hash, preimage);
+    }
+
+    /// Returns the preimage of the given hash, if it was recorded.
+    pub fn get(&self, hash: &B256) -> Option<&Bytes> {
+        self.preimages.get(hash)
+    }
+
+    /// Merges the preimages recorded in `other` into `self`.
+    pub fn extend(&mut self, other: Self) {
+        self.preimages.extend(other.preimages);
+    }
+
+    /// Returns the keys that were hashed with the given base slot, as Solidity does to compute
+    /// the slots of the entries of a mapping, sorted and deduplicated.
+    pub fn mapping_keys(&self, base: &B256) -> Vec<Bytes> {
+        let mut keys = self
+            .preimages
+            .values()
+            .filter(|preimage| preimage.len() > 32 && preimage.ends_with(base.as_slice()))
+            .map(|preimage| preimage.slice(..preimage.len() - 32))
+            .collect::<Vec<_>>();
+        keys.sort();
+        keys.dedup();
+        keys
+    }
+
+    /// Resolves a storage slot back to the mapping keys and base slot it was derived from, as
+    /// computed by Solidity.
+    ///
+    /// A slot `keccak256(key . base)` is rendered as `base[key]`, and the data location
+    /// `keccak256(base)` of a dynamic array or of long `bytes` as `base[]`. Nested mappings are
+    /// resolved recursively, e.g. `slot 0x2[0x..01][0x..02]`.
+    ///
+    /// Returns `None` if the slot is not the hash of a recorded preimage.
+    pub fn decode_slot(&self, slot: &B256) -> Option<String> {
+        let preimage = self.get(slot)?;
+        let (key, base) = match preimage.len() {
+            64 => (Some(&preimage[..32]), B256::from_slice(&preimage[32..])),
+            32 => (None, B256::from_slice(preimage)),
+            _ => return None,
+        };
+        let mut decoded = self
+            .decode_slot(&base)
+            .unwrap_or_else(|| format!("slot {:#x}", U256::from_be_bytes(base.0)));
+        match key {
+            Some(key) => decoded.push_str(&format!("[{}]", Bytes::copy_from_slice(key))),
+            None => decoded.push_str("[]"),
+        }
+        Some(decoded)
+    }
+}
+
+/// An inspector that records the preimages of the hashes computed with `KECCAK256`.
+///
+/// The input is read from memory before the opcode executes, and the hash from the top of the
+/// stack afterwards, so that tools can resolve mapping slots back to their keys, e.g. when
+/// rendering storage diffs.
+#[derive(Clone, Debug, Default)]
+pub struct KeccakPreimageCollector {
+    /// The preimages recorded so far.
+    pub preimages: KeccakPreimages,
+    /// The input of the `KECCAK256` being executed, if any.
+    pending: Option<Bytes>,
+}
+
+impl KeccakPreimageCollector {
+    /// Called with the input and output of every successfully executed `KECCAK256`.
+    pub fn keccak(&mut self, input: Bytes, output: B256) {
+        self.preimages.insert(output, input);
+    }
+}
+
+impl<DB: Database> Inspector<DB> for KeccakPreimageCollector {
+    #[inline]
+    fn step(&mut self, interp: &mut Interpreter, _context: &mut EvmContext<DB>) {
+        if interp.current_opcode() != opcode::KECCAK256 {
+            return
+        }
+        let (Ok(offset), Ok(size)) = (interp.stack().peek(0), interp.stack().peek(1)) else {
+            return
+        };
+        let Ok(size) = usize::try_from(size) else { return };
+        if size > MAX_PREIMAGE_SIZE {
+            return
+        }
+        let mut input = vec![0; size];
+        // Memory is only expanded by the opcode itself, anything past its current end is zero.
+        if let Ok(offset) = usize::try_from(offset) {
+            let memory = interp.shared_memory.context_memory();
+            if offset < memory.len() {
+                let available = &memory[offset..memory.len().min(offset.saturating_add(size))];
+                input[..available.len()].copy_from_slice(available);
+            }
+        } else if size != 0 {
+            return
+        }
+        self.pending = Some(input.into());
+    }
+
+    #[inline]
+    fn step_end(&mut self, interp: &mut Interpreter, _context: &mut EvmContext<DB>) {
+        if let Some(input) = self.pending.take() {
+            if interp.instruction_result == InstructionResult::Continue {
+                if let Ok(output) = interp.stack().peek(0) {
+                    self.keccak(input, output.into());
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::keccak256;
+
+    #[test]
+    fn decode_mapping_slots() {
+        let mut preimages = KeccakPreimages::default();
+        let mut record = |preimage: Vec<u8>| {
+            let hash = keccak256(&preimage);
+            preimages.insert(hash, preimage.into());
+            hash
+        };
+
+        // mapping(uint256 => mapping(uint256 => uint256)) at slot 2
+        let key1 = B256::with_last_byte(1);
+        let key2 = B256::with_last_byte(2);
+        let inner = record([key1.as_slice(), B256::with_last_byte(2).as_slice()].concat());
+        let slot = record([key2.as_slice(), inner.as_slice()].concat());
+        // uint256[] at slot 3
+        let array = record(B256::with_last_byte(3).to_vec());
+
+        assert_eq!(preimages.decode_slot(&inner).unwrap(), format!("slot 0x2[{key1}]"));
+        assert_eq!(preimages.decode_slot(&slot).unwrap(), format!("slot 0x2[{key1}][{key2}]"));
+        assert_eq!(preimages.decode_slot(&array).unwrap(), "slot 0x3[]");
+        assert_eq!(preimages.decode_slot(&B256::with_last_byte(2)), None);
+    }
+
+    #[test]
+    fn collect_mapping_keys() {
+        let mut preimages = KeccakPreimages::default();
+        let base = B256::with_last_byte(1);
+        for preimage in [
+            [B256::with_last_byte(7).as_slice(), base.as_slice()].concat(),
+            [b"alice".as_slice(), base.as_slice()].concat(),
+            [B256::with_last_byte(7).as_slice(), B256::with_last_byte(2).as_slice()].concat(),
+            base.to_vec(),
+        ] {
+            preimages.insert(keccak256(&preimage), preimage.into());
+        }
+
+        assert_eq!(
+            preimages.mapping_keys(&base),
+            vec![
+                Bytes::copy_from_slice(B256::with_last_byte(7).as_slice()),
+                Bytes::from_static(b"alice")
+            ]
+        );
+        assert!(preimages.mapping_keys(&B256::with_last_byte(3)).is_empty());
+    }
+}
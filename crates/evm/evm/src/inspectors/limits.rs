@@ -0,0 +1,165 @@
+use alloy_primitives::Address;
+use foundry_config::Config;
+use foundry_evm_core::{constants::CHEATCODE_ADDRESS, outcome::FrameOverride};
+use revm::{
+    interpreter::{
+        opcode, CallInputs, CallOutcome, CreateInputs, CreateOutcome, Interpreter,
+        InterpreterResult,
+    },
+    Database, EvmContext, Inspector,
+};
+
+/// Limits on the resources used by a single call into a test contract.
+///
+/// See [`ResourceLimiter`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ResourceLimits {
+    /// The maximum depth of nested calls, the top-level call being at depth zero.
+    pub max_call_depth: Option<usize>,
+    /// The maximum number of emitted events.
+    pub max_events: Option<usize>,
+    /// The maximum number of storage writes.
+    pub max_state_writes: Option<usize>,
+    /// The maximum size in bytes of the data returned by any call.
+    pub max_return_data_size: Option<usize>,
+}
+
+impl ResourceLimits {
+    /// Returns the limits configured in the given config.
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            max_call_depth: config.max_call_depth,
+            max_events: config.max_events,
+            max_state_writes: config.max_state_writes,
+            max_return_data_size: config.max_return_data_size,
+        }
+    }
+
+    /// Returns true if no limit is set.
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// An inspector that enforces [`ResourceLimits`].
+///
+/// Once a limit is exceeded, execution halts and every frame up to the top-level call reverts
+/// with a message describing the exceeded limit, even if the revert is caught on the way.
+#[derive(Clone, Debug, Default)]
+pub struct ResourceLimiter {
+    /// The enforced limits.
+    limits: ResourceLimits,
+    /// The number of events emitted so far.
+    events: usize,
+    /// The number of storage writes performed so far.
+    state_writes: usize,
+    /// The description of the first exceeded limit, if any.
+    violation: Option<String>,
+}
+
+impl ResourceLimiter {
+    /// Creates a new limiter enforcing the given limits.
+    pub fn new(limits: ResourceLimits) -> Self {
+        Self { limits, ..Default::default() }
+    }
+
+    /// Records that a limit was exceeded. Only the first violation is reported.
+    fn exceeded(&mut self, violation: String) {
+        self.violation.get_or_insert(violation);
+    }
+
+    /// Returns true if `count` exceeds the given limit.
+    fn over(count: usize, limit: Option<usize>) -> bool {
+        limit.is_some_and(|limit| count > limit)
+    }
+
+    /// Returns the revert to end frames with if a limit was exceeded.
+    fn revert(&self) -> Option<FrameOverride> {
+        self.violation.as_ref().map(|message| FrameOverride::cheatcode_error(message.clone()))
+    }
+
+    /// Overrides the given result with a revert if a limit was exceeded.
+    fn enforce(&self, result: &mut InterpreterResult) {
+        if let Some(revert) = self.revert() {
+            revert.apply(result);
+        }
+    }
+
+    /// Checks whether a new frame is allowed at the given depth, returning the revert to
+    /// short-circuit the frame with if not.
+    fn check_frame(&mut self, depth: usize, target: Option<Address>) -> Option<FrameOverride> {
+        if let Some(max) = self.limits.max_call_depth {
+            if depth > max && target != Some(CHEATCODE_ADDRESS) {
+                self.exceeded(format!("call depth limit of {max} exceeded"));
+            }
+        }
+        self.revert()
+    }
+}
+
+impl<DB: Database> Inspector<DB> for ResourceLimiter {
+    #[inline]
+    fn step(&mut self, interp: &mut Interpreter, _context: &mut EvmContext<DB>) {
+        match interp.current_opcode() {
+            opcode::LOG0..=opcode::LOG4 => {
+                self.events += 1;
+                if Self::over(self.events, self.limits.max_events) {
+                    let max = self.limits.max_events.unwrap();
+                    self.exceeded(format!("event limit of {max} exceeded"));
+                }
+            }
+            opcode::SSTORE => {
+                self.state_writes += 1;
+                if Self::over(self.state_writes, self.limits.max_state_writes) {
+                    let max = self.limits.max_state_writes.unwrap();
+                    self.exceeded(format!("state write limit of {max} exceeded"));
+                }
+            }
+            _ => {}
+        }
+
+        // Halt before executing anything else once a limit is exceeded.
+        if let Some(revert) = self.revert() {
+            revert.apply_to_interpreter(interp);
+        }
+    }
+
+    fn call(&mut self, ecx: &mut EvmContext<DB>, inputs: &mut CallInputs) -> Option<CallOutcome> {
+        let depth = ecx.journaled_state.depth();
+        Some(self.check_frame(depth, Some(inputs.target_address))?.into_call_outcome(inputs))
+    }
+
+    fn call_end(
+        &mut self,
+        _context: &mut EvmContext<DB>,
+        _inputs: &CallInputs,
+        mut outcome: CallOutcome,
+    ) -> CallOutcome {
+        let size = outcome.result.output.len();
+        if Self::over(size, self.limits.max_return_data_size) {
+            let max = self.limits.max_return_data_size.unwrap();
+            self.exceeded(format!("return data size limit of {max} bytes exceeded: {size} bytes"));
+        }
+        self.enforce(&mut outcome.result);
+        outcome
+    }
+
+    fn create(
+        &mut self,
+        ecx: &mut EvmContext<DB>,
+        inputs: &mut CreateInputs,
+    ) -> Option<CreateOutcome> {
+        let depth = ecx.journaled_state.depth();
+        Some(self.check_frame(depth, None)?.into_create_outcome(inputs))
+    }
+
+    fn create_end(
+        &mut self,
+        _context: &mut EvmContext<DB>,
+        _inputs: &CreateInputs,
+        mut outcome: CreateOutcome,
+    ) -> CreateOutcome {
+        self.enforce(&mut outcome.result);
+        outcome
+    }
+}
@@ -0,0 +1,194 @@
+use alloy_primitives::{Address, Selector};
+use revm::{
+    interpreter::{CallInputs, CallOutcome, CreateInputs, CreateOutcome, Interpreter},
+    Database, EvmContext, Inspector,
+};
+use serde::{Deserialize, Serialize};
+
+/// The memory used by a single call frame.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FrameMemory {
+    /// The address of the executed code, or of the created contract for creations.
+    pub address: Address,
+    /// The selector of the called function, if the calldata has one.
+    pub selector: Option<Selector>,
+    /// The depth of the frame, the top-level call being at depth zero.
+    pub depth: usize,
+    /// Whether the frame is a contract creation.
+    pub is_create: bool,
+    /// The size in bytes of the calldata, or of the init code for creations.
+    pub calldata_size: usize,
+    /// The largest size in bytes the memory of the frame was expanded to.
+    pub peak_memory: usize,
+    /// The size in bytes of the data returned by the frame.
+    pub returndata_size: usize,
+}
+
+impl FrameMemory {
+    /// Returns the gas paid to expand the memory of the frame to its peak size.
+    pub fn memory_gas(&self) -> u64 {
+        let words = (self.peak_memory as u64).div_ceil(32);
+        3 * words + words * words / 512
+    }
+}
+
+/// The memory used by the call frames of an execution, in the order they were entered.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MemoryProfile {
+    pub frames: Vec<FrameMemory>,
+}
+
+impl MemoryProfile {
+    /// Returns the largest memory size reached by any frame.
+    pub fn peak_memory(&self) -> usize {
+        self.frames.iter().map(|frame| frame.peak_memory).max().unwrap_or_default()
+    }
+
+    /// Returns the size of the largest data returned by any frame.
+    pub fn max_returndata_size(&self) -> usize {
+        self.frames.iter().map(|frame| frame.returndata_size).max().unwrap_or_default()
+    }
+
+    /// Returns the size of the largest calldata passed to any frame.
+    pub fn max_calldata_size(&self) -> usize {
+        self.frames.iter().map(|frame| frame.calldata_size).max().unwrap_or_default()
+    }
+
+    /// Returns the `n` frames that paid the most for memory expansion, most expensive first.
+    ///
+    /// Frames that never touched memory, like calls to precompiles and cheatcodes, are left out.
+    pub fn hotspots(&self, n: usize) -> Vec<&FrameMemory> {
+        let mut frames =
+            self.frames.iter().filter(|frame| frame.peak_memory > 0).collect::<Vec<_>>();
+        frames.sort_by_key(|frame| std::cmp::Reverse(frame.peak_memory));
+        frames.truncate(n);
+        frames
+    }
+
+    /// Appends the frames recorded in `other` to `self`.
+    pub fn extend(&mut self, other: Self) {
+        self.frames.extend(other.frames);
+    }
+}
+
+/// An inspector that records the memory expansion, calldata and returndata sizes of every call
+/// frame.
+///
+/// Memory only grows within a frame, so its size is sampled after every step and the largest one
+/// is kept.
+#[derive(Clone, Debug, Default)]
+pub struct MemoryProfiler {
+    /// The frames recorded so far.
+    pub profile: MemoryProfile,
+    /// The indices in `profile` of the frames being executed, innermost last.
+    open: Vec<usize>,
+}
+
+impl MemoryProfiler {
+    /// Records a new frame.
+    fn enter(&mut self, frame: FrameMemory) {
+        self.open.push(self.profile.frames.len());
+        self.profile.frames.push(frame);
+    }
+
+    /// Closes the innermost frame, returning it.
+    fn exit(&mut self, returndata_size: usize) -> Option<&mut FrameMemory> {
+        let frame = &mut self.profile.frames[self.open.pop()?];
+        frame.returndata_size = returndata_size;
+        Some(frame)
+    }
+}
+
+impl<DB: Database> Inspector<DB> for MemoryProfiler {
+    #[inline]
+    fn step_end(&mut self, interp: &mut Interpreter, _context: &mut EvmContext<DB>) {
+        if let Some(&index) = self.open.last() {
+            let frame = &mut self.profile.frames[index];
+            frame.peak_memory = frame.peak_memory.max(interp.shared_memory.len());
+        }
+    }
+
+    fn call(&mut self, ecx: &mut EvmContext<DB>, inputs: &mut CallInputs) -> Option<CallOutcome> {
+        self.enter(FrameMemory {
+            address: inputs.bytecode_address,
+            selector: inputs.input.get(..4).map(Selector::from_slice),
+            depth: ecx.journaled_state.depth(),
+            is_create: false,
+            calldata_size: inputs.input.len(),
+            ..Default::default()
+        });
+        None
+    }
+
+    fn call_end(
+        &mut self,
+        _context: &mut EvmContext<DB>,
+        _inputs: &CallInputs,
+        outcome: CallOutcome,
+    ) -> CallOutcome {
+        self.exit(outcome.result.output.len());
+        outcome
+    }
+
+    fn create(
+        &mut self,
+        ecx: &mut EvmContext<DB>,
+        inputs: &mut CreateInputs,
+    ) -> Option<CreateOutcome> {
+        self.enter(FrameMemory {
+            depth: ecx.journaled_state.depth(),
+            is_create: true,
+            calldata_size: inputs.init_code.len(),
+            ..Default::default()
+        });
+        None
+    }
+
+    fn create_end(
+        &mut self,
+        _context: &mut EvmContext<DB>,
+        _inputs: &CreateInputs,
+        outcome: CreateOutcome,
+    ) -> CreateOutcome {
+        if let Some(frame) = self.exit(outcome.result.output.len()) {
+            frame.address = outcome.address.unwrap_or_default();
+        }
+        outcome
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hotspots_by_peak_memory() {
+        let frame = |peak_memory| FrameMemory { peak_memory, ..Default::default() };
+        let profile = MemoryProfile { frames: vec![frame(64), frame(0), frame(4096), frame(96)] };
+
+        let hotspots = profile.hotspots(2);
+        assert_eq!(hotspots, [&frame(4096), &frame(96)]);
+        assert_eq!(profile.hotspots(10).len(), 3);
+        assert_eq!(profile.peak_memory(), 4096);
+
+        // 128 words: 3 * 128 + 128^2 / 512
+        assert_eq!(frame(4096).memory_gas(), 416);
+        assert_eq!(frame(1).memory_gas(), 3);
+    }
+
+    #[test]
+    fn records_nested_frames() {
+        let mut profiler = MemoryProfiler::default();
+        profiler.enter(FrameMemory { depth: 0, calldata_size: 4, ..Default::default() });
+        profiler.enter(FrameMemory { depth: 1, calldata_size: 68, ..Default::default() });
+        profiler.exit(32);
+        profiler.exit(0);
+        assert!(profiler.exit(0).is_none());
+
+        let frames = &profiler.profile.frames;
+        assert_eq!((frames[0].depth, frames[0].returndata_size), (0, 0));
+        assert_eq!((frames[1].depth, frames[1].returndata_size), (1, 32));
+        assert_eq!(profiler.profile.max_calldata_size(), 68);
+        assert_eq!(profiler.profile.max_returndata_size(), 32);
+    }
+}
@@ -1,17 +1,54 @@
 //! EVM inspectors.
 
-pub use foundry_cheatcodes::{self as cheatcodes, Cheatcodes, CheatsConfig};
-pub use foundry_evm_coverage::CoverageCollector;
-pub use foundry_evm_fuzz::Fuzzer;
+pub use foundry_cheatcodes::{
+    self as cheatcodes, Cheatcodes, CheatsConfig, CreateOverride, Fixtures,
+};
+pub use foundry_evm_coverage::{BranchMutation, BranchMutator, CoverageCollector};
+pub use foundry_evm_fuzz::{BranchHintCollector, Fuzzer};
 pub use foundry_evm_traces::{StackSnapshotType, TracingInspector, TracingInspectorConfig};
 
 pub use revm_inspectors::access_list::AccessListInspector;
 
+mod access_policy;
+pub use access_policy::{AccessPolicy, AccessPolicyEnforcer, DeclaredStorage};
+
+mod block_hash;
+pub use block_hash::BlockHashOracle;
+
 mod chisel_state;
 pub use chisel_state::ChiselState;
 
+mod edge_coverage;
+pub use edge_coverage::EdgeCoverageCollector;
+
+mod gas_refunds;
+pub use gas_refunds::GasRefundRecorder;
+
+mod interactive;
+pub use interactive::{
+    BreakpointAction, BreakpointContext, BreakpointHandler, BreakpointSignal, InteractiveDebugger,
+};
+
+mod interrupt;
+pub use interrupt::{CancellationToken, Interrupter, CANCELLED_MESSAGE};
+
+mod keccak;
+pub use keccak::{KeccakPreimageCollector, KeccakPreimages};
+
+mod limits;
+pub use limits::{ResourceLimiter, ResourceLimits};
+
 mod logs;
 pub use logs::LogCollector;
 
+mod memory;
+pub use memory::{FrameMemory, MemoryProfile, MemoryProfiler};
+
 mod stack;
 pub use stack::{InspectorData, InspectorStack, InspectorStackBuilder};
+
+mod time;
+pub use time::{FunctionTime, TimeProfile, TimeProfiler};
+
+mod view;
+pub use view::InterpreterView;
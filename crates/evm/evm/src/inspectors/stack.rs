@@ -1,15 +1,23 @@
 use super::{
-    Cheatcodes, CheatsConfig, ChiselState, CoverageCollector, Fuzzer, LogCollector,
-    StackSnapshotType, TracingInspector, TracingInspectorConfig,
+    AccessPolicy, AccessPolicyEnforcer, BlockHashOracle, BranchHintCollector, BranchMutation,
+    BranchMutator, BreakpointHandler, BreakpointSignal, CancellationToken, Cheatcodes,
+    CheatsConfig, ChiselState, CoverageCollector, EdgeCoverageCollector, Fuzzer, GasRefundRecorder,
+    InteractiveDebugger, Interrupter, KeccakPreimageCollector, KeccakPreimages, LogCollector,
+    MemoryProfile, MemoryProfiler, ResourceLimiter, ResourceLimits, StackSnapshotType, TimeProfile,
+    TimeProfiler, TracingInspector, TracingInspectorConfig,
 };
 use alloy_primitives::{Address, Bytes, Log, TxKind, U256};
 use foundry_cheatcodes::CheatcodesExecutor;
 use foundry_evm_core::{
     backend::{update_state, DatabaseExt},
+    eip7702::resolve_delegation,
+    gas::GasPolicy,
+    precompiles::CustomPrecompiles,
     InspectorExt,
 };
 use foundry_evm_coverage::HitMaps;
-use foundry_evm_traces::CallTraceArena;
+use foundry_evm_fuzz::BranchComparisons;
+use foundry_evm_traces::{CallTraceArena, GasRefunds};
 use revm::{
     inspectors::CustomPrintTracer,
     interpreter::{
@@ -22,7 +30,7 @@ use revm::{
     EvmContext, Inspector,
 };
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     ops::{Deref, DerefMut},
     sync::Arc,
 };
@@ -52,10 +60,36 @@ pub struct InspectorStackBuilder {
     pub logs: Option<bool>,
     /// Whether coverage info should be collected.
     pub coverage: Option<bool>,
+    /// Whether edge coverage should be collected for coverage-guided fuzzing.
+    pub edge_coverage: Option<bool>,
+    /// Whether comparisons involving fuzzed inputs should be collected for branch hints.
+    pub branch_comparisons: Option<bool>,
+    /// Whether the preimages of `KECCAK256` hashes should be recorded.
+    pub keccak_preimages: Option<bool>,
+    /// Whether the memory, calldata and returndata sizes of call frames should be recorded.
+    pub memory_profile: Option<bool>,
+    /// Whether the wall-clock time spent in every function should be sampled.
+    pub time_profile: Option<bool>,
+    /// Whether `BLOCKHASH` should return the hashes of blocks older than the last 256 ones.
+    pub historical_block_hashes: Option<bool>,
+    /// The mutation to apply to a branch, see `forge coverage --assertions`.
+    pub branch_mutation: Option<BranchMutation>,
     /// Whether to print all opcode traces into the console. Useful for debugging the EVM.
     pub print: Option<bool>,
     /// The chisel state inspector.
     pub chisel_state: Option<usize>,
+    /// The resource limits to enforce.
+    pub limits: Option<ResourceLimits>,
+    /// The access policy to enforce.
+    pub access_policy: Option<AccessPolicy>,
+    /// The handler of the breakpoints to pause execution at.
+    pub interactive: Option<Arc<dyn BreakpointHandler>>,
+    /// The token to abort execution with.
+    pub cancellation: Option<CancellationToken>,
+    /// The gas policy applied to the executed transactions.
+    pub gas_policy: Option<Arc<dyn GasPolicy>>,
+    /// The precompiles added to those of the spec.
+    pub custom_precompiles: Option<Arc<CustomPrecompiles>>,
     /// Whether to enable call isolation.
     /// In isolation mode all top-level calls are executed as a separate transaction in a separate
     /// EVM context, enabling more precise gas accounting and transaction state changes.
@@ -104,6 +138,48 @@ impl InspectorStackBuilder {
         self
     }
 
+    /// Set the resource limits to enforce.
+    #[inline]
+    pub fn limits(mut self, limits: ResourceLimits) -> Self {
+        self.limits = Some(limits);
+        self
+    }
+
+    /// Set the access policy to enforce.
+    #[inline]
+    pub fn access_policy(mut self, policy: AccessPolicy) -> Self {
+        self.access_policy = Some(policy);
+        self
+    }
+
+    /// Set the token to abort execution with, at the next step boundary once cancelled.
+    #[inline]
+    pub fn cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancellation = Some(token);
+        self
+    }
+
+    /// Pause execution at breakpoints and hand them over to the given handler.
+    #[inline]
+    pub fn interactive(mut self, handler: Arc<dyn BreakpointHandler>) -> Self {
+        self.interactive = Some(handler);
+        self
+    }
+
+    /// Set the gas policy applied to the executed transactions.
+    #[inline]
+    pub fn gas_policy(mut self, policy: Arc<dyn GasPolicy>) -> Self {
+        self.gas_policy = Some(policy);
+        self
+    }
+
+    /// Set the precompiles added to those of the spec.
+    #[inline]
+    pub fn custom_precompiles(mut self, precompiles: Arc<CustomPrecompiles>) -> Self {
+        self.custom_precompiles = Some(precompiles);
+        self
+    }
+
     /// Set whether to collect logs.
     #[inline]
     pub fn logs(mut self, yes: bool) -> Self {
@@ -118,6 +194,55 @@ impl InspectorStackBuilder {
         self
     }
 
+    /// Set whether to collect edge coverage for coverage-guided fuzzing.
+    #[inline]
+    pub fn edge_coverage(mut self, yes: bool) -> Self {
+        self.edge_coverage = Some(yes);
+        self
+    }
+
+    /// Set whether to collect comparisons involving fuzzed inputs for branch hints.
+    #[inline]
+    pub fn branch_comparisons(mut self, yes: bool) -> Self {
+        self.branch_comparisons = Some(yes);
+        self
+    }
+
+    /// Set whether to record the preimages of `KECCAK256` hashes.
+    #[inline]
+    pub fn keccak_preimages(mut self, yes: bool) -> Self {
+        self.keccak_preimages = Some(yes);
+        self
+    }
+
+    /// Set whether to record the memory, calldata and returndata sizes of call frames.
+    #[inline]
+    pub fn memory_profile(mut self, yes: bool) -> Self {
+        self.memory_profile = Some(yes);
+        self
+    }
+
+    /// Set whether to sample the wall-clock time spent in every function.
+    #[inline]
+    pub fn time_profile(mut self, yes: bool) -> Self {
+        self.time_profile = Some(yes);
+        self
+    }
+
+    /// Set whether `BLOCKHASH` returns the hashes of blocks older than the last 256 ones.
+    #[inline]
+    pub fn historical_block_hashes(mut self, yes: bool) -> Self {
+        self.historical_block_hashes = Some(yes);
+        self
+    }
+
+    /// Set the mutation to apply to a branch.
+    #[inline]
+    pub fn branch_mutation(mut self, mutation: BranchMutation) -> Self {
+        self.branch_mutation = Some(mutation);
+        self
+    }
+
     /// Set whether to enable the debugger.
     #[inline]
     pub fn debug(mut self, yes: bool) -> Self {
@@ -158,8 +283,21 @@ impl InspectorStackBuilder {
             debug,
             logs,
             coverage,
+            edge_coverage,
+            branch_comparisons,
+            keccak_preimages,
+            memory_profile,
+            time_profile,
+            historical_block_hashes,
+            branch_mutation,
             print,
             chisel_state,
+            limits,
+            access_policy,
+            interactive,
+            cancellation,
+            gas_policy,
+            custom_precompiles,
             enable_isolation,
         } = self;
         let mut stack = InspectorStack::new();
@@ -174,7 +312,34 @@ impl InspectorStackBuilder {
         if let Some(chisel_state) = chisel_state {
             stack.set_chisel(chisel_state);
         }
+        if let Some(limits) = limits {
+            stack.set_limits(limits);
+        }
+        if let Some(policy) = access_policy {
+            stack.set_access_policy(policy);
+        }
+        if let Some(handler) = interactive {
+            stack.set_interactive(handler);
+        }
+        if let Some(token) = cancellation {
+            stack.set_cancellation(token);
+        }
+        if let Some(policy) = gas_policy {
+            stack.set_gas_policy(policy);
+        }
+        if let Some(precompiles) = custom_precompiles {
+            stack.set_custom_precompiles(precompiles);
+        }
+        if let Some(mutation) = branch_mutation {
+            stack.set_branch_mutation(mutation);
+        }
         stack.collect_coverage(coverage.unwrap_or(false));
+        stack.collect_edge_coverage(edge_coverage.unwrap_or(false));
+        stack.collect_branch_comparisons(branch_comparisons.unwrap_or(false));
+        stack.collect_keccak_preimages(keccak_preimages.unwrap_or(false));
+        stack.collect_memory_profile(memory_profile.unwrap_or(false));
+        stack.collect_time_profile(time_profile.unwrap_or(false));
+        stack.serve_historical_block_hashes(historical_block_hashes.unwrap_or(false));
         stack.collect_logs(logs.unwrap_or(true));
         stack.print(print.unwrap_or(false));
         stack.tracing(trace.unwrap_or(false), debug.unwrap_or(false));
@@ -241,7 +406,14 @@ pub struct InspectorData {
     pub logs: Vec<Log>,
     pub labels: HashMap<Address, String>,
     pub traces: Option<CallTraceArena>,
+    /// The gas refunded to the calls of `traces`.
+    pub gas_refunds: GasRefunds,
     pub coverage: Option<HitMaps>,
+    pub edge_coverage: Option<HashSet<u64>>,
+    pub branch_comparisons: Option<BranchComparisons>,
+    pub keccak_preimages: Option<KeccakPreimages>,
+    pub memory_profile: Option<MemoryProfile>,
+    pub time_profile: Option<TimeProfile>,
     pub cheatcodes: Option<Cheatcodes>,
     pub chisel_state: Option<(Vec<U256>, Vec<u8>, InstructionResult)>,
 }
@@ -287,10 +459,24 @@ pub struct InspectorStack {
 pub struct InspectorStackInner {
     pub chisel_state: Option<ChiselState>,
     pub coverage: Option<CoverageCollector>,
+    pub edge_coverage: Option<EdgeCoverageCollector>,
+    pub branch_hints: Option<BranchHintCollector>,
     pub fuzzer: Option<Fuzzer>,
+    pub gas_refunds: Option<GasRefundRecorder>,
+    pub interactive: Option<InteractiveDebugger>,
+    pub interrupter: Option<Interrupter>,
+    pub keccak_preimages: Option<KeccakPreimageCollector>,
+    pub memory_profiler: Option<MemoryProfiler>,
+    pub time_profiler: Option<TimeProfiler>,
+    pub limiter: Option<ResourceLimiter>,
+    pub access_policy: Option<AccessPolicyEnforcer>,
+    pub block_hash_oracle: Option<BlockHashOracle>,
+    pub branch_mutator: Option<BranchMutator>,
     pub log_collector: Option<LogCollector>,
     pub printer: Option<CustomPrintTracer>,
     pub tracer: Option<TracingInspector>,
+    pub gas_policy: Option<Arc<dyn GasPolicy>>,
+    pub custom_precompiles: Option<Arc<CustomPrecompiles>>,
     pub enable_isolation: bool,
 
     /// Flag marking if we are in the inner EVM context.
@@ -339,7 +525,28 @@ impl InspectorStack {
                     )*
                 };
             }
-            push!(cheatcodes, chisel_state, coverage, fuzzer, log_collector, printer, tracer);
+            push!(
+                access_policy,
+                block_hash_oracle,
+                branch_hints,
+                branch_mutator,
+                cheatcodes,
+                chisel_state,
+                coverage,
+                edge_coverage,
+                fuzzer,
+                interactive,
+                interrupter,
+                keccak_preimages,
+                limiter,
+                log_collector,
+                memory_profiler,
+                time_profiler,
+                printer,
+                tracer,
+                gas_policy,
+                custom_precompiles
+            );
             if self.enable_isolation {
                 enabled.push("isolation");
             }
@@ -388,12 +595,96 @@ impl InspectorStack {
         self.chisel_state = Some(ChiselState::new(final_pc));
     }
 
+    /// Set the resource limits to enforce. No limiter is installed if no limit is set.
+    #[inline]
+    pub fn set_limits(&mut self, limits: ResourceLimits) {
+        self.limiter = (!limits.is_empty()).then(|| ResourceLimiter::new(limits));
+    }
+
+    /// Set the access policy to enforce. No enforcer is installed if the policy is empty.
+    #[inline]
+    pub fn set_access_policy(&mut self, policy: AccessPolicy) {
+        self.access_policy = (!policy.is_empty()).then(|| AccessPolicyEnforcer::new(policy));
+    }
+
+    /// Set the handler of the breakpoints to pause execution at.
+    #[inline]
+    pub fn set_interactive(&mut self, handler: Arc<dyn BreakpointHandler>) {
+        self.interactive = Some(InteractiveDebugger::new(handler));
+    }
+
+    /// Set the token to abort execution with, at the next step boundary once cancelled.
+    #[inline]
+    pub fn set_cancellation(&mut self, token: CancellationToken) {
+        self.interrupter = Some(Interrupter::new(token));
+    }
+
+    /// Set the gas policy applied to the executed transactions.
+    #[inline]
+    pub fn set_gas_policy(&mut self, policy: Arc<dyn GasPolicy>) {
+        self.gas_policy = Some(policy);
+    }
+
+    /// Set the precompiles added to those of the spec.
+    #[inline]
+    pub fn set_custom_precompiles(&mut self, precompiles: Arc<CustomPrecompiles>) {
+        self.custom_precompiles = Some(precompiles);
+    }
+
+    /// Returns the signal that inspectors can use to pause execution, if debugging interactively.
+    #[inline]
+    pub fn breakpoint_signal(&self) -> Option<BreakpointSignal> {
+        self.interactive.as_ref().map(|interactive| interactive.signal().clone())
+    }
+
     /// Set whether to enable the coverage collector.
     #[inline]
     pub fn collect_coverage(&mut self, yes: bool) {
         self.coverage = yes.then(Default::default);
     }
 
+    /// Set whether to enable the edge coverage collector.
+    #[inline]
+    pub fn collect_edge_coverage(&mut self, yes: bool) {
+        self.edge_coverage = yes.then(Default::default);
+    }
+
+    /// Set whether to enable the collector of comparisons involving fuzzed inputs.
+    #[inline]
+    pub fn collect_branch_comparisons(&mut self, yes: bool) {
+        self.branch_hints = yes.then(Default::default);
+    }
+
+    /// Set whether to enable the `KECCAK256` preimage collector.
+    #[inline]
+    pub fn collect_keccak_preimages(&mut self, yes: bool) {
+        self.keccak_preimages = yes.then(Default::default);
+    }
+
+    /// Set whether to enable the memory profiler.
+    #[inline]
+    pub fn collect_memory_profile(&mut self, yes: bool) {
+        self.memory_profiler = yes.then(Default::default);
+    }
+
+    /// Set whether to enable the time profiler.
+    #[inline]
+    pub fn collect_time_profile(&mut self, yes: bool) {
+        self.time_profiler = yes.then(Default::default);
+    }
+
+    /// Set whether `BLOCKHASH` returns the hashes of blocks older than the last 256 ones.
+    #[inline]
+    pub fn serve_historical_block_hashes(&mut self, yes: bool) {
+        self.block_hash_oracle = yes.then(Default::default);
+    }
+
+    /// Set the mutation to apply to a branch.
+    #[inline]
+    pub fn set_branch_mutation(&mut self, mutation: BranchMutation) {
+        self.branch_mutator = Some(BranchMutator::new(mutation));
+    }
+
     /// Set whether to enable call isolation.
     #[inline]
     pub fn enable_isolation(&mut self, yes: bool) {
@@ -417,8 +708,10 @@ impl InspectorStack {
     pub fn tracing(&mut self, yes: bool, debug: bool) {
         if !yes {
             self.tracer = None;
+            self.gas_refunds = None;
             return;
         }
+        self.gas_refunds = Some(Default::default());
         *self.tracer.get_or_insert_with(Default::default).config_mut() = TracingInspectorConfig {
             record_steps: debug,
             record_memory_snapshots: debug,
@@ -440,7 +733,20 @@ impl InspectorStack {
     pub fn collect(self) -> InspectorData {
         let Self {
             cheatcodes,
-            inner: InspectorStackInner { chisel_state, coverage, log_collector, tracer, .. },
+            inner:
+                InspectorStackInner {
+                    branch_hints,
+                    chisel_state,
+                    coverage,
+                    edge_coverage,
+                    gas_refunds,
+                    keccak_preimages,
+                    log_collector,
+                    memory_profiler,
+                    time_profiler,
+                    tracer,
+                    ..
+                },
         } = self;
 
         InspectorData {
@@ -450,7 +756,13 @@ impl InspectorStack {
                 .map(|cheatcodes| cheatcodes.labels.clone())
                 .unwrap_or_default(),
             traces: tracer.map(|tracer| tracer.into_traces()),
+            gas_refunds: gas_refunds.map(|recorder| recorder.refunds).unwrap_or_default(),
             coverage: coverage.map(|coverage| coverage.maps),
+            edge_coverage: edge_coverage.map(|edge_coverage| edge_coverage.edges),
+            branch_comparisons: branch_hints.map(|branch_hints| branch_hints.into_comparisons()),
+            keccak_preimages: keccak_preimages.map(|collector| collector.preimages),
+            memory_profile: memory_profiler.map(|profiler| profiler.profile),
+            time_profile: time_profiler.map(|profiler| profiler.profile),
             cheatcodes,
             chisel_state: chisel_state.and_then(|state| state.state),
         }
@@ -490,7 +802,18 @@ impl<'a> InspectorStackRefMut<'a> {
         let result = outcome.result.result;
         call_inspectors_adjust_depth!(
             #[ret]
-            [&mut self.fuzzer, &mut self.tracer, &mut self.cheatcodes, &mut self.printer,],
+            [
+                &mut self.memory_profiler,
+                &mut self.time_profiler,
+                &mut self.fuzzer,
+                &mut self.tracer,
+                &mut self.gas_refunds,
+                &mut self.cheatcodes,
+                &mut self.printer,
+                &mut self.limiter,
+                &mut self.access_policy,
+                &mut self.interrupter,
+            ],
             |inspector| {
                 let new_outcome = inspector.call_end(ecx, inputs, outcome.clone());
 
@@ -643,7 +966,13 @@ impl<'a> InspectorStackRefMut<'a> {
 impl<'a, DB: DatabaseExt> Inspector<DB> for InspectorStackRefMut<'a> {
     fn initialize_interp(&mut self, interpreter: &mut Interpreter, ecx: &mut EvmContext<DB>) {
         call_inspectors_adjust_depth!(
-            [&mut self.coverage, &mut self.tracer, &mut self.cheatcodes, &mut self.printer],
+            [
+                &mut self.time_profiler,
+                &mut self.coverage,
+                &mut self.tracer,
+                &mut self.cheatcodes,
+                &mut self.printer,
+            ],
             |inspector| inspector.initialize_interp(interpreter, ecx),
             self,
             ecx
@@ -653,21 +982,49 @@ impl<'a, DB: DatabaseExt> Inspector<DB> for InspectorStackRefMut<'a> {
     fn step(&mut self, interpreter: &mut Interpreter, ecx: &mut EvmContext<DB>) {
         call_inspectors_adjust_depth!(
             [
+                &mut self.time_profiler,
                 &mut self.fuzzer,
                 &mut self.tracer,
                 &mut self.coverage,
+                &mut self.edge_coverage,
+                &mut self.branch_hints,
+                &mut self.keccak_preimages,
                 &mut self.cheatcodes,
                 &mut self.printer,
+                &mut self.limiter,
+                &mut self.access_policy,
+                &mut self.interrupter,
+                &mut self.block_hash_oracle,
+                &mut self.branch_mutator,
             ],
             |inspector| inspector.step(interpreter, ecx),
             self,
             ecx
         );
+
+        if let Some(interactive) = &mut self.inner.interactive {
+            let label = self
+                .cheatcodes
+                .as_deref_mut()
+                .and_then(|cheatcodes| cheatcodes.pending_breakpoint.take())
+                .map(String::from);
+            let traces = self.inner.tracer.as_ref().map(|tracer| tracer.traces());
+            interactive.pause(label, interpreter, &ecx.journaled_state, traces);
+        }
     }
 
     fn step_end(&mut self, interpreter: &mut Interpreter, ecx: &mut EvmContext<DB>) {
         call_inspectors_adjust_depth!(
-            [&mut self.tracer, &mut self.chisel_state, &mut self.printer],
+            [
+                // Must come first so that the other inspectors see the served block hash.
+                &mut self.block_hash_oracle,
+                &mut self.tracer,
+                &mut self.edge_coverage,
+                &mut self.keccak_preimages,
+                &mut self.memory_profiler,
+                &mut self.chisel_state,
+                &mut self.printer,
+            ],
             |inspector| inspector.step_end(interpreter, ecx),
             self,
             ecx
@@ -686,12 +1043,26 @@ impl<'a, DB: DatabaseExt> Inspector<DB> for InspectorStackRefMut<'a> {
     fn call(&mut self, ecx: &mut EvmContext<DB>, call: &mut CallInputs) -> Option<CallOutcome> {
         if self.in_inner_context && ecx.journaled_state.depth == 0 {
             self.adjust_evm_data_for_inner_context(ecx);
+            // Errors are surfaced by revm when it loads the code itself.
+            let _ = resolve_delegation(ecx, call);
             return None;
         }
 
         call_inspectors_adjust_depth!(
             #[ret]
-            [&mut self.fuzzer, &mut self.tracer, &mut self.log_collector, &mut self.printer,],
+            [
+                &mut self.memory_profiler,
+                &mut self.time_profiler,
+                &mut self.fuzzer,
+                &mut self.tracer,
+                &mut self.gas_refunds,
+                &mut self.log_collector,
+                &mut self.branch_hints,
+                &mut self.printer,
+                &mut self.limiter,
+                &mut self.access_policy,
+                &mut self.interrupter,
+            ],
             |inspector| {
                 let mut out = None;
                 if let Some(output) = inspector.call(ecx, call) {
@@ -716,6 +1087,11 @@ impl<'a, DB: DatabaseExt> Inspector<DB> for InspectorStackRefMut<'a> {
         }
         ecx.journaled_state.depth -= self.in_inner_context as usize;
 
+        // Calls to accounts with a delegation designator execute the code of the delegate. This
+        // is done after the cheatcodes so that mocks and expected calls match the account itself.
+        // Errors are surfaced by revm when it loads the code itself.
+        let _ = resolve_delegation(ecx, call);
+
         if self.enable_isolation &&
             call.scheme == CallScheme::Call &&
             !self.in_inner_context &&
@@ -772,7 +1148,17 @@ impl<'a, DB: DatabaseExt> Inspector<DB> for InspectorStackRefMut<'a> {
 
         call_inspectors_adjust_depth!(
             #[ret]
-            [&mut self.tracer, &mut self.coverage, &mut self.cheatcodes],
+            [
+                &mut self.memory_profiler,
+                &mut self.time_profiler,
+                &mut self.tracer,
+                &mut self.gas_refunds,
+                &mut self.coverage,
+                &mut self.cheatcodes,
+                &mut self.limiter,
+                &mut self.access_policy,
+                &mut self.interrupter,
+            ],
             |inspector| inspector.create(ecx, create).map(Some),
             self,
             ecx
@@ -813,7 +1199,17 @@ impl<'a, DB: DatabaseExt> Inspector<DB> for InspectorStackRefMut<'a> {
 
         call_inspectors_adjust_depth!(
             #[ret]
-            [&mut self.tracer, &mut self.cheatcodes, &mut self.printer],
+            [
+                &mut self.memory_profiler,
+                &mut self.time_profiler,
+                &mut self.tracer,
+                &mut self.gas_refunds,
+                &mut self.cheatcodes,
+                &mut self.printer,
+                &mut self.limiter,
+                &mut self.access_policy,
+                &mut self.interrupter,
+            ],
             |inspector| {
                 let new_outcome = inspector.create_end(ecx, call, outcome.clone());
 
@@ -843,7 +1239,7 @@ impl<'a, DB: DatabaseExt> Inspector<DB> for InspectorStackRefMut<'a> {
 
         call_inspectors_adjust_depth!(
             #[ret]
-            [&mut self.tracer, &mut self.coverage, &mut self.cheatcodes],
+            [&mut self.tracer, &mut self.gas_refunds, &mut self.coverage, &mut self.cheatcodes],
             |inspector| inspector.eofcreate(ecx, create).map(Some),
             self,
             ecx
@@ -885,7 +1281,7 @@ impl<'a, DB: DatabaseExt> Inspector<DB> for InspectorStackRefMut<'a> {
 
         call_inspectors_adjust_depth!(
             #[ret]
-            [&mut self.tracer, &mut self.cheatcodes, &mut self.printer],
+            [&mut self.tracer, &mut self.gas_refunds, &mut self.cheatcodes, &mut self.printer],
             |inspector| {
                 let new_outcome = inspector.eofcreate_end(ecx, call, outcome.clone());
 
@@ -926,6 +1322,14 @@ impl<'a, DB: DatabaseExt> InspectorExt<DB> for InspectorStackRefMut<'a> {
 
         false
     }
+
+    fn gas_policy(&self) -> Option<Arc<dyn GasPolicy>> {
+        self.inner.gas_policy.clone()
+    }
+
+    fn custom_precompiles(&self) -> Option<Arc<CustomPrecompiles>> {
+        self.inner.custom_precompiles.clone()
+    }
 }
 
 impl<DB: DatabaseExt> Inspector<DB> for InspectorStack {
@@ -994,6 +1398,14 @@ impl<DB: DatabaseExt> InspectorExt<DB> for InspectorStack {
     ) -> bool {
         self.as_mut().should_use_create2_factory(ecx, inputs)
     }
+
+    fn gas_policy(&self) -> Option<Arc<dyn GasPolicy>> {
+        self.gas_policy.clone()
+    }
+
+    fn custom_precompiles(&self) -> Option<Arc<CustomPrecompiles>> {
+        self.custom_precompiles.clone()
+    }
 }
 
 impl<'a> Deref for InspectorStackRefMut<'a> {
@@ -0,0 +1,196 @@
+use alloy_primitives::{Address, Bytes, Selector};
+use revm::{
+    interpreter::{CallInputs, CallOutcome, CreateInputs, CreateOutcome, Interpreter},
+    Database, EvmContext, Inspector,
+};
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+/// The number of steps between two readings of the clock.
+///
+/// Reading the clock on every step would take longer than most instructions, the time between two
+/// readings is attributed to the innermost frame. The clock is also read whenever a frame is
+/// entered or exited, so that the time of a call is never attributed to its caller.
+const SAMPLE_INTERVAL: u64 = 128;
+
+/// The wall-clock time spent executing the code of a function.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FunctionTime {
+    /// The address of the executed code, or of the created contract for creations.
+    pub address: Address,
+    /// The selector of the called function, if the calldata has one.
+    pub selector: Option<Selector>,
+    /// Whether the frames are contract creations.
+    pub is_create: bool,
+    /// The executed code: the deployed code, or the init code for creations.
+    ///
+    /// Empty for accounts without code, like precompiles and cheatcodes.
+    pub code: Bytes,
+    /// The number of frames that executed the function.
+    pub calls: usize,
+    /// The time spent executing the function, excluding the calls it made.
+    pub self_time: Duration,
+}
+
+/// The wall-clock time spent in the functions of an execution, in the order they were first
+/// entered.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TimeProfile {
+    pub functions: Vec<FunctionTime>,
+}
+
+impl TimeProfile {
+    /// Returns the total time of the execution.
+    pub fn total_time(&self) -> Duration {
+        self.functions.iter().map(|function| function.self_time).sum()
+    }
+}
+
+/// An inspector that samples the wall-clock time spent in every function.
+///
+/// The frames calling the same function of an address are merged, and so are the creations of an
+/// address.
+#[derive(Clone, Debug, Default)]
+pub struct TimeProfiler {
+    /// The functions recorded so far.
+    pub profile: TimeProfile,
+    /// The indices in `profile` of the functions by address, selector and creation.
+    index: HashMap<(Address, Option<Selector>, bool), usize>,
+    /// The frames being executed, innermost last.
+    open: Vec<FunctionTime>,
+    /// The time of the last reading of the clock.
+    last: Option<Instant>,
+    /// The number of steps since the last reading of the clock.
+    steps: u64,
+}
+
+impl TimeProfiler {
+    /// Attributes the time since the last reading of the clock to the innermost frame.
+    fn sample(&mut self) {
+        let now = Instant::now();
+        if let (Some(last), Some(frame)) = (self.last, self.open.last_mut()) {
+            frame.self_time += now - last;
+        }
+        self.last = Some(now);
+        self.steps = 0;
+    }
+
+    /// Enters a new frame.
+    fn enter(&mut self, function: FunctionTime) {
+        self.sample();
+        self.open.push(FunctionTime { calls: 1, ..function });
+    }
+
+    /// Exits the innermost frame, merging it into the profile.
+    fn exit(&mut self, created: Option<Address>) {
+        self.sample();
+        let Some(mut function) = self.open.pop() else { return };
+        if let Some(address) = created {
+            function.address = address;
+        }
+        let key = (function.address, function.selector, function.is_create);
+        match self.index.get(&key) {
+            Some(&index) => {
+                let merged = &mut self.profile.functions[index];
+                merged.calls += function.calls;
+                merged.self_time += function.self_time;
+            }
+            None => {
+                self.index.insert(key, self.profile.functions.len());
+                self.profile.functions.push(function);
+            }
+        }
+    }
+}
+
+impl<DB: Database> Inspector<DB> for TimeProfiler {
+    fn initialize_interp(&mut self, interp: &mut Interpreter, _context: &mut EvmContext<DB>) {
+        if let Some(frame) = self.open.last_mut() {
+            frame.code = interp.contract.bytecode.original_bytes();
+        }
+    }
+
+    #[inline]
+    fn step(&mut self, _interp: &mut Interpreter, _context: &mut EvmContext<DB>) {
+        self.steps += 1;
+        if self.steps >= SAMPLE_INTERVAL {
+            self.sample();
+        }
+    }
+
+    fn call(
+        &mut self,
+        _context: &mut EvmContext<DB>,
+        inputs: &mut CallInputs,
+    ) -> Option<CallOutcome> {
+        self.enter(FunctionTime {
+            address: inputs.bytecode_address,
+            selector: inputs.input.get(..4).map(Selector::from_slice),
+            ..Default::default()
+        });
+        None
+    }
+
+    fn call_end(
+        &mut self,
+        _context: &mut EvmContext<DB>,
+        _inputs: &CallInputs,
+        outcome: CallOutcome,
+    ) -> CallOutcome {
+        self.exit(None);
+        outcome
+    }
+
+    fn create(
+        &mut self,
+        _context: &mut EvmContext<DB>,
+        _inputs: &mut CreateInputs,
+    ) -> Option<CreateOutcome> {
+        self.enter(FunctionTime { is_create: true, ..Default::default() });
+        None
+    }
+
+    fn create_end(
+        &mut self,
+        _context: &mut EvmContext<DB>,
+        _inputs: &CreateInputs,
+        outcome: CreateOutcome,
+    ) -> CreateOutcome {
+        self.exit(Some(outcome.address.unwrap_or_default()));
+        outcome
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merges_frames_of_the_same_function() {
+        let mut profiler = TimeProfiler::default();
+        let selector = Some(Selector::from([1, 2, 3, 4]));
+        let call =
+            FunctionTime { address: Address::with_last_byte(1), selector, ..Default::default() };
+
+        profiler.enter(FunctionTime { address: Address::with_last_byte(2), ..Default::default() });
+        profiler.enter(call.clone());
+        profiler.exit(None);
+        profiler.enter(call);
+        profiler.exit(None);
+        profiler.enter(FunctionTime { is_create: true, ..Default::default() });
+        profiler.exit(Some(Address::with_last_byte(3)));
+        profiler.exit(None);
+        profiler.exit(None);
+
+        let functions = &profiler.profile.functions;
+        assert_eq!(functions.len(), 3);
+        assert_eq!((functions[0].address, functions[0].calls), (Address::with_last_byte(1), 2));
+        assert_eq!(
+            (functions[1].address, functions[1].is_create),
+            (Address::with_last_byte(3), true)
+        );
+        assert_eq!((functions[2].address, functions[2].calls), (Address::with_last_byte(2), 1));
+    }
+}
@@ -0,0 +1,128 @@
+use alloy_primitives::{Address, U256};
+use revm::interpreter::Interpreter;
+use std::{borrow::Cow, fmt};
+
+/// A read-only view of the interpreter at the current instruction.
+///
+/// All accessors borrow from the interpreter, so inspecting memory, the stack or the return data
+/// does not copy them. Use [`memory_range`](Self::memory_range) and [`stack_top`](Self::stack_top)
+/// to look at just the parts that are needed, and copy those if they must outlive the step.
+#[derive(Clone, Copy)]
+pub struct InterpreterView<'a> {
+    interp: &'a Interpreter,
+}
+
+impl<'a> InterpreterView<'a> {
+    /// Creates a view of the given interpreter.
+    pub fn new(interp: &'a Interpreter) -> Self {
+        Self { interp }
+    }
+
+    /// Returns the address of the contract being executed.
+    pub fn address(&self) -> Address {
+        self.interp.contract.target_address
+    }
+
+    /// Returns the caller of the current call.
+    pub fn caller(&self) -> Address {
+        self.interp.contract.caller
+    }
+
+    /// Returns the program counter of the instruction about to be executed.
+    pub fn pc(&self) -> usize {
+        self.interp.program_counter()
+    }
+
+    /// Returns the opcode of the instruction about to be executed.
+    pub fn opcode(&self) -> u8 {
+        self.interp.current_opcode()
+    }
+
+    /// Returns the gas remaining in the current call.
+    pub fn gas_remaining(&self) -> u64 {
+        self.interp.gas.remaining()
+    }
+
+    /// Returns the input of the current call.
+    pub fn calldata(&self) -> &'a [u8] {
+        &self.interp.contract.input
+    }
+
+    /// Returns the whole stack, from the bottom to the top.
+    pub fn stack(&self) -> &'a [U256] {
+        self.interp.stack.data()
+    }
+
+    /// Returns the `n` topmost stack items, from the bottom to the top, or the whole stack if it
+    /// has fewer items.
+    pub fn stack_top(&self, n: usize) -> &'a [U256] {
+        let stack = self.stack();
+        &stack[stack.len().saturating_sub(n)..]
+    }
+
+    /// Returns the stack item `n` positions below the top, if any.
+    pub fn peek(&self, n: usize) -> Option<U256> {
+        self.interp.stack.peek(n).ok()
+    }
+
+    /// Returns the memory of the current call.
+    pub fn memory(&self) -> &'a [u8] {
+        self.interp.shared_memory.context_memory()
+    }
+
+    /// Returns the size of the memory of the current call.
+    pub fn memory_len(&self) -> usize {
+        self.memory().len()
+    }
+
+    /// Returns `len` bytes of memory starting at `offset`.
+    ///
+    /// Like the EVM, bytes past the end of memory read as zero. Only such ranges are copied.
+    pub fn memory_range(&self, offset: usize, len: usize) -> Cow<'a, [u8]> {
+        read_padded(self.memory(), offset, len)
+    }
+
+    /// Returns the return data of the last call made by the current call.
+    pub fn return_data(&self) -> &'a [u8] {
+        &self.interp.return_data_buffer
+    }
+}
+
+impl fmt::Debug for InterpreterView<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("InterpreterView")
+            .field("address", &self.address())
+            .field("pc", &self.pc())
+            .field("opcode", &self.opcode())
+            .field("stack_len", &self.stack().len())
+            .field("memory_len", &self.memory_len())
+            .finish()
+    }
+}
+
+/// Reads `len` bytes from `offset`, padding with zeros past the end of `data`.
+fn read_padded(data: &[u8], offset: usize, len: usize) -> Cow<'_, [u8]> {
+    let end = offset.saturating_add(len);
+    if end <= data.len() {
+        return Cow::Borrowed(&data[offset..end]);
+    }
+    let mut bytes = vec![0; len];
+    if offset < data.len() {
+        bytes[..data.len() - offset].copy_from_slice(&data[offset..]);
+    }
+    Cow::Owned(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_padded_memory() {
+        let data = [1, 2, 3, 4];
+        assert!(matches!(read_padded(&data, 1, 2), Cow::Borrowed([2, 3])));
+        assert_eq!(read_padded(&data, 2, 4), [3, 4, 0, 0][..]);
+        assert_eq!(read_padded(&data, 8, 2), [0, 0][..]);
+        assert!(read_padded(&data, 4, 0).is_empty());
+    }
+}
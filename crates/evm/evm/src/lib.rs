@@ -8,10 +8,14 @@
 #[macro_use]
 extern crate tracing;
 
+pub mod contract_tests;
 pub mod executors;
 pub mod inspectors;
 
-pub use foundry_evm_core::{backend, constants, decode, fork, opts, utils, InspectorExt};
+pub use foundry_evm_core::{
+    backend, constants, decode, eip7702, fork, gas, journal, opts, precompiles, rng, tx_type,
+    utils, InspectorExt,
+};
 pub use foundry_evm_coverage as coverage;
 pub use foundry_evm_fuzz as fuzz;
 pub use foundry_evm_traces as traces;
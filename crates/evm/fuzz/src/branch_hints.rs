@@ -0,0 +1,366 @@
+//! Hints on the inputs needed to reach the branches a fuzz test never took.
+//!
+//! [`BranchHintCollector`] records the comparisons between words of the fuzzed calldata and other
+//! values. A comparison which evaluated to the same result in every fuzz run guards a branch that
+//! was never taken, and the value the input was compared against tells how to take it. This is a
+//! cheap approximation of what a symbolic engine would solve for: only direct comparisons of
+//! unmodified inputs are tracked.
+
+use alloy_dyn_abi::DynSolType;
+use alloy_json_abi::Function;
+use alloy_primitives::{Address, I256, U256};
+use revm::{
+    interpreter::{opcode, CallInputs, CallOutcome, Interpreter},
+    Database, EvmContext, Inspector,
+};
+use std::{collections::HashMap, fmt};
+
+/// The maximum number of hints reported for a single test.
+const MAX_HINTS: usize = 5;
+
+/// A comparison opcode.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ComparisonOp {
+    Lt,
+    Gt,
+    Slt,
+    Sgt,
+    Eq,
+}
+
+impl ComparisonOp {
+    fn from_opcode(op: u8) -> Option<Self> {
+        match op {
+            opcode::LT => Some(Self::Lt),
+            opcode::GT => Some(Self::Gt),
+            opcode::SLT => Some(Self::Slt),
+            opcode::SGT => Some(Self::Sgt),
+            opcode::EQ => Some(Self::Eq),
+            _ => None,
+        }
+    }
+
+    fn is_signed(self) -> bool {
+        matches!(self, Self::Slt | Self::Sgt)
+    }
+
+    /// Returns the relation the input has with the other operand when the comparison is true.
+    fn relation(self, input_is_lhs: bool) -> Relation {
+        match (self, input_is_lhs) {
+            (Self::Lt | Self::Slt, true) | (Self::Gt | Self::Sgt, false) => Relation::Below,
+            (Self::Lt | Self::Slt, false) | (Self::Gt | Self::Sgt, true) => Relation::Exceed,
+            (Self::Eq, _) => Relation::Equal,
+        }
+    }
+}
+
+/// The relation an input needs to have with a value to take a branch.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Relation {
+    Below,
+    AtMost,
+    Exceed,
+    AtLeast,
+    Equal,
+    Differ,
+}
+
+impl Relation {
+    fn negate(self) -> Self {
+        match self {
+            Self::Below => Self::AtLeast,
+            Self::AtLeast => Self::Below,
+            Self::Exceed => Self::AtMost,
+            Self::AtMost => Self::Exceed,
+            Self::Equal => Self::Differ,
+            Self::Differ => Self::Equal,
+        }
+    }
+}
+
+impl fmt::Display for Relation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Below => "be below",
+            Self::AtMost => "be at most",
+            Self::Exceed => "exceed",
+            Self::AtLeast => "be at least",
+            Self::Equal => "equal",
+            Self::Differ => "differ from",
+        })
+    }
+}
+
+/// A comparison involving a fuzzed input, identified by the contract and program counter of the
+/// comparison and the index of the calldata word of the input.
+type SiteKey = (Address, usize, usize);
+
+/// The outcomes of a comparison involving a fuzzed input.
+#[derive(Clone, Debug)]
+struct ComparisonSite {
+    op: ComparisonOp,
+    input_is_lhs: bool,
+    /// The value the input was compared against, or `None` if it changed between comparisons.
+    other: Option<U256>,
+    seen_true: bool,
+    seen_false: bool,
+    /// The number of runs in which the comparison was executed.
+    runs: usize,
+}
+
+impl ComparisonSite {
+    fn merge(&mut self, other: Self) {
+        if self.op != other.op ||
+            self.input_is_lhs != other.input_is_lhs ||
+            self.other != other.other
+        {
+            self.other = None;
+        }
+        self.seen_true |= other.seen_true;
+        self.seen_false |= other.seen_false;
+        self.runs += other.runs;
+    }
+}
+
+/// The comparisons involving fuzzed inputs observed across fuzz runs.
+#[derive(Clone, Debug, Default)]
+pub struct BranchComparisons {
+    sites: HashMap<SiteKey, ComparisonSite>,
+    /// The number of runs the comparisons were collected from.
+    runs: usize,
+}
+
+impl BranchComparisons {
+    /// Merges the comparisons observed in other runs.
+    pub fn merge(&mut self, other: Self) {
+        self.runs += other.runs;
+        for (key, site) in other.sites {
+            match self.sites.get_mut(&key) {
+                Some(prev) => prev.merge(site),
+                None => {
+                    self.sites.insert(key, site);
+                }
+            }
+        }
+    }
+
+    /// Returns the hints on the inputs of `func` needed to take the branches that were never
+    /// taken.
+    ///
+    /// Only comparisons executed in at least half of the runs against the same value are
+    /// considered, as the others are likely coincidental matches of the inputs.
+    pub fn hints(&self, func: &Function) -> Vec<BranchHint> {
+        let params = param_words(func);
+        let mut hints = self
+            .sites
+            .iter()
+            .filter(|(_, site)| site.seen_true != site.seen_false && site.runs * 2 >= self.runs)
+            .filter_map(|(&(address, pc, word), site)| {
+                let other = site.other?;
+                let (name, ty) = params.get(word)?.as_ref()?;
+                let relation = site.op.relation(site.input_is_lhs);
+                let relation = if site.seen_true { relation.negate() } else { relation };
+                let value = match ty {
+                    DynSolType::Int(_) => I256::from_raw(other).to_string(),
+                    _ if site.op.is_signed() => I256::from_raw(other).to_string(),
+                    DynSolType::Address => Address::from_word(other.into()).to_string(),
+                    DynSolType::FixedBytes(_) => format!("{other:#066x}"),
+                    _ => other.to_string(),
+                };
+                Some(BranchHint { address, pc, param: name.clone(), relation, value })
+            })
+            .collect::<Vec<_>>();
+        hints.sort_by(|a, b| {
+            (&a.param, a.relation, &a.value).cmp(&(&b.param, b.relation, &b.value))
+        });
+        hints.dedup_by(|a, b| (&a.param, a.relation, &a.value) == (&b.param, b.relation, &b.value));
+        hints.truncate(MAX_HINTS);
+        hints
+    }
+}
+
+/// Returns the name and type of the value parameter held by each head word of the calldata of
+/// `func`, stopping at the first parameter spanning several words.
+fn param_words(func: &Function) -> Vec<Option<(String, DynSolType)>> {
+    let mut words = Vec::with_capacity(func.inputs.len());
+    for (i, param) in func.inputs.iter().enumerate() {
+        let Ok(ty) = param.selector_type().parse::<DynSolType>() else { break };
+        let name = if param.name.is_empty() { format!("arg{i}") } else { param.name.clone() };
+        match ty {
+            DynSolType::Bool |
+            DynSolType::Int(_) |
+            DynSolType::Uint(_) |
+            DynSolType::Address |
+            DynSolType::FixedBytes(_) => words.push(Some((name, ty))),
+            // Dynamic parameters are encoded as an offset in the head.
+            DynSolType::Bytes | DynSolType::String | DynSolType::Array(_) => words.push(None),
+            _ => break,
+        }
+    }
+    words
+}
+
+/// A hint on the input needed to take a branch that was never taken.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BranchHint {
+    /// The contract holding the branch.
+    pub address: Address,
+    /// The program counter of the comparison guarding the branch.
+    pub pc: usize,
+    /// The name of the fuzzed parameter.
+    pub param: String,
+    pub relation: Relation,
+    /// The value the parameter is compared against.
+    pub value: String,
+}
+
+impl fmt::Display for BranchHint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Self { address, pc, param, relation, value } = self;
+        write!(
+            f,
+            "to reach the branch at pc {pc} of {address}, `{param}` likely needs to {relation} {value}"
+        )
+    }
+}
+
+/// An inspector that records the comparisons involving the words of the fuzzed calldata.
+#[derive(Clone, Debug, Default)]
+pub struct BranchHintCollector {
+    /// The words of the calldata of the top-level call, following the selector.
+    inputs: Vec<U256>,
+    /// The comparisons observed during the call.
+    sites: HashMap<SiteKey, ComparisonSite>,
+}
+
+impl BranchHintCollector {
+    /// Returns the comparisons observed during the call.
+    pub fn into_comparisons(self) -> BranchComparisons {
+        BranchComparisons { sites: self.sites, runs: 1 }
+    }
+
+    /// Returns the index of the calldata word equal to `value`.
+    ///
+    /// Zero is ignored, as it's both a common input and a common operand.
+    fn input_word(&self, value: U256) -> Option<usize> {
+        if value.is_zero() {
+            return None
+        }
+        self.inputs.iter().position(|input| *input == value)
+    }
+}
+
+impl<DB: Database> Inspector<DB> for BranchHintCollector {
+    #[inline]
+    fn step(&mut self, interp: &mut Interpreter, _context: &mut EvmContext<DB>) {
+        if self.inputs.is_empty() {
+            return
+        }
+        let Some(op) = ComparisonOp::from_opcode(interp.current_opcode()) else { return };
+        let (Ok(a), Ok(b)) = (interp.stack().peek(0), interp.stack().peek(1)) else { return };
+
+        // Comparisons between two inputs, or of an input with itself as done when validating the
+        // decoded calldata, can't be turned into a hint.
+        let (word, input_is_lhs, other) = match (self.input_word(a), self.input_word(b)) {
+            (Some(word), None) => (word, true, b),
+            (None, Some(word)) => (word, false, a),
+            _ => return,
+        };
+        let result = match op {
+            ComparisonOp::Lt => a < b,
+            ComparisonOp::Gt => a > b,
+            ComparisonOp::Slt => I256::from_raw(a) < I256::from_raw(b),
+            ComparisonOp::Sgt => I256::from_raw(a) > I256::from_raw(b),
+            ComparisonOp::Eq => a == b,
+        };
+
+        let site = ComparisonSite {
+            op,
+            input_is_lhs,
+            other: Some(other),
+            seen_true: result,
+            seen_false: !result,
+            runs: 1,
+        };
+        let key = (interp.contract.target_address, interp.program_counter(), word);
+        match self.sites.get_mut(&key) {
+            Some(prev) => {
+                // The site is still only counted once for this run.
+                prev.merge(site);
+                prev.runs = 1;
+            }
+            None => {
+                self.sites.insert(key, site);
+            }
+        }
+    }
+
+    #[inline]
+    fn call(&mut self, ecx: &mut EvmContext<DB>, inputs: &mut CallInputs) -> Option<CallOutcome> {
+        if ecx.journaled_state.depth == 0 {
+            self.inputs = inputs
+                .input
+                .get(4..)
+                .unwrap_or_default()
+                .chunks_exact(32)
+                .map(U256::from_be_slice)
+                .collect();
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn site(op: ComparisonOp, input_is_lhs: bool, other: u64, result: bool) -> ComparisonSite {
+        ComparisonSite {
+            op,
+            input_is_lhs,
+            other: Some(U256::from(other)),
+            seen_true: result,
+            seen_false: !result,
+            runs: 1,
+        }
+    }
+
+    fn run(sites: impl IntoIterator<Item = (usize, ComparisonSite)>) -> BranchComparisons {
+        let sites = sites.into_iter().map(|(pc, site)| ((Address::ZERO, pc, 0), site)).collect();
+        BranchComparisons { sites, runs: 1 }
+    }
+
+    #[test]
+    fn can_hint_untaken_branches() {
+        let func = Function::parse("testDeposit(uint256 amount)").unwrap();
+        let mut comparisons = BranchComparisons::default();
+        for _ in 0..10 {
+            comparisons.merge(run([
+                // `1000 < amount` never true
+                (1, site(ComparisonOp::Lt, false, 1000, false)),
+                // `amount == 42` never true
+                (2, site(ComparisonOp::Eq, true, 42, false)),
+                // `amount < 7` both true and false
+                (3, ComparisonSite { seen_true: true, ..site(ComparisonOp::Lt, true, 7, false) }),
+            ]));
+        }
+
+        let hints = comparisons.hints(&func);
+        let hints =
+            hints.iter().map(|hint| (hint.relation, hint.value.as_str())).collect::<Vec<_>>();
+        assert_eq!(hints, vec![(Relation::Exceed, "1000"), (Relation::Equal, "42")]);
+    }
+
+    #[test]
+    fn ignores_unstable_and_rare_comparisons() {
+        let func = Function::parse("testDeposit(uint256 amount)").unwrap();
+        let mut comparisons = BranchComparisons::default();
+        comparisons.merge(run([(1, site(ComparisonOp::Gt, true, 5, false))]));
+        comparisons.merge(run([(1, site(ComparisonOp::Gt, true, 6, false))]));
+        comparisons.merge(run([(2, site(ComparisonOp::Gt, true, 5, false))]));
+        for _ in 0..3 {
+            comparisons.merge(run([]));
+        }
+        assert!(comparisons.hints(&func).is_empty());
+    }
+}
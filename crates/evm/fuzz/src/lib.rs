@@ -9,7 +9,7 @@
 extern crate tracing;
 
 use alloy_dyn_abi::{DynSolValue, JsonAbiExt};
-use alloy_primitives::{Address, Bytes, Log};
+use alloy_primitives::{Address, Bytes, Log, U256};
 use foundry_common::{calc, contracts::ContractsByAddress, evm::Breakpoints};
 use foundry_evm_coverage::HitMaps;
 use foundry_evm_traces::CallTraceArena;
@@ -19,6 +19,9 @@ use std::{collections::HashMap, fmt, sync::Arc};
 
 pub use proptest::test_runner::{Config as FuzzConfig, Reason};
 
+mod branch_hints;
+pub use branch_hints::{BranchComparisons, BranchHint, BranchHintCollector, Relation};
+
 mod error;
 pub use error::FuzzError;
 
@@ -53,6 +56,9 @@ pub struct BaseCounterExample {
     /// Traces
     #[serde(skip)]
     pub traces: Option<CallTraceArena>,
+    /// Which parts of the call are needed to reproduce the failure, if it was explained.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub explanation: Option<CallExplanation>,
 }
 
 impl BaseCounterExample {
@@ -78,6 +84,7 @@ impl BaseCounterExample {
                             foundry_common::fmt::format_tokens(&args).format(", ").to_string(),
                         ),
                         traces,
+                        explanation: None,
                     };
                 }
             }
@@ -91,6 +98,7 @@ impl BaseCounterExample {
             signature: None,
             args: None,
             traces,
+            explanation: None,
         }
     }
 
@@ -108,6 +116,7 @@ impl BaseCounterExample {
             signature: None,
             args: Some(foundry_common::fmt::format_tokens(&args).format(", ").to_string()),
             traces,
+            explanation: None,
         }
     }
 }
@@ -133,10 +142,70 @@ impl fmt::Display for BaseCounterExample {
         }
 
         if let Some(args) = &self.args {
-            write!(f, " args=[{args}]")
+            write!(f, " args=[{args}]")?
         } else {
-            write!(f, " args=[]")
+            write!(f, " args=[]")?
+        }
+
+        if let Some(explanation) = &self.explanation {
+            write!(f, " // {explanation}")?
+        }
+        Ok(())
+    }
+}
+
+/// Explains why a call of a shrunk invariant counterexample is part of it.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CallExplanation {
+    /// Whether the invariant is still broken without the call, e.g. if shrinking stopped early.
+    pub removable: bool,
+    /// The arguments that must keep their value to break the invariant, by name or position.
+    pub essential_args: Vec<String>,
+    /// The arguments that can be reset to their default value and still break the invariant.
+    pub irrelevant_args: Vec<String>,
+}
+
+impl fmt::Display for CallExplanation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.removable {
+            return f.write_str("not needed")
+        }
+        f.write_str("needed")?;
+        if !self.essential_args.is_empty() {
+            write!(f, "; essential args=[{}]", self.essential_args.iter().format(", "))?;
         }
+        if !self.irrelevant_args.is_empty() {
+            write!(f, "; irrelevant args=[{}]", self.irrelevant_args.iter().format(", "))?;
+        }
+        Ok(())
+    }
+}
+
+/// A persisted fuzz test failure, used to deterministically re-execute the failing case with
+/// `forge test --replay <file>`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FuzzReplay {
+    /// Identifier of the test contract, e.g. `test/Counter.t.sol:CounterTest`.
+    pub contract: String,
+    /// Signature of the fuzz test function.
+    pub test: String,
+    /// Seed of the fuzzing RNG the failure was found with.
+    pub seed: Option<U256>,
+    /// The full calldata of the failing case, including the function selector.
+    pub calldata: Bytes,
+    /// Decoded arguments of the failing case, for display purposes only.
+    pub args: Option<String>,
+}
+
+impl FuzzReplay {
+    /// Returns the name of the fuzz test function.
+    pub fn test_name(&self) -> &str {
+        self.test.split('(').next().unwrap_or(&self.test)
+    }
+
+    /// Returns true if this replay was recorded for the given test contract and function.
+    pub fn matches(&self, contract: &str, signature: &str) -> bool {
+        self.contract == contract && self.test == signature
     }
 }
 
@@ -184,6 +253,9 @@ pub struct FuzzTestResult {
 
     /// Breakpoints for debugger. Correspond to the same fuzz case as `traces`.
     pub breakpoints: Option<Breakpoints>,
+
+    /// The comparisons involving fuzzed inputs, if branch hints are enabled.
+    pub branch_comparisons: Option<BranchComparisons>,
 }
 
 impl FuzzTestResult {
@@ -8,7 +8,6 @@ use alloy_json_abi::Function;
 use alloy_primitives::Address;
 use parking_lot::RwLock;
 use proptest::prelude::*;
-use rand::seq::IteratorRandom;
 use std::{rc::Rc, sync::Arc};
 
 /// Given a target address, we generate random calldata.
@@ -31,10 +30,12 @@ pub fn override_call_strat(
         let func = {
             let contracts = contracts.targets.lock();
             let contract = contracts.get(&target_address).unwrap_or_else(|| {
-                // Choose a random contract if target selected by lazy strategy is not in fuzz run
-                // identified contracts. This can happen when contract is created in `setUp` call
-                // but is not included in targetContracts.
-                contracts.values().choose(&mut rand::thread_rng()).unwrap()
+                // Choose a contract derived from the target if target selected by lazy strategy is
+                // not in fuzz run identified contracts, so that the choice is reproduced with the
+                // seed. This can happen when contract is created in `setUp` call but is not
+                // included in targetContracts.
+                let index = target_address[19] as usize % contracts.len();
+                contracts.values().nth(index).unwrap()
             });
             let fuzzed_functions: Vec<_> = contract.abi_fuzzed_functions().cloned().collect();
             any::<prop::sample::Index>().prop_map(move |index| index.get(&fuzzed_functions).clone())
@@ -79,6 +79,13 @@ impl CallTraceDecoderBuilder {
     /// Sets the signature identifier for events and functions.
     #[inline]
     pub fn with_signature_identifier(mut self, identifier: SingleSignaturesIdentifier) -> Self {
+        // Errors from the local signature database can be decoded without a lookup.
+        if let Ok(identifier) = identifier.try_read() {
+            let errors = identifier.database().errors.values().flatten();
+            for error in errors.filter_map(|signature| Error::parse(signature).ok()) {
+                self.decoder.push_error(error);
+            }
+        }
         self.decoder.signature_identifier = Some(identifier);
         self
     }
@@ -3,8 +3,9 @@ use alloy_primitives::hex;
 use foundry_common::{
     abi::{get_event, get_func},
     fs,
-    selectors::{OpenChainClient, SelectorType},
+    selectors::{OpenChainClient, SelectorType, SignatureDatabase},
 };
+use foundry_config::Config;
 use serde::{Deserialize, Serialize};
 use std::{
     collections::{BTreeMap, HashSet},
@@ -21,10 +22,12 @@ struct CachedSignatures {
     functions: BTreeMap<String, String>,
 }
 
-/// An identifier that tries to identify functions and events using signatures found at
-/// `https://openchain.xyz`.
+/// An identifier that tries to identify functions and events using the project's local signature
+/// database and signatures found at `https://openchain.xyz`.
 #[derive(Debug)]
 pub struct SignaturesIdentifier {
+    /// The project's local signature database, which takes precedence over the API.
+    database: SignatureDatabase,
     /// Cached selectors for functions and events
     cached: CachedSignatures,
     /// Location where to save `CachedSignatures`
@@ -42,6 +45,25 @@ impl SignaturesIdentifier {
     pub fn new(
         cache_path: Option<PathBuf>,
         offline: bool,
+    ) -> eyre::Result<SingleSignaturesIdentifier> {
+        Self::with_database(cache_path, offline, SignatureDatabase::default())
+    }
+
+    /// Creates an identifier for the project with the given config, which uses the project's
+    /// signature database and honors the `offline` setting.
+    pub fn from_config(config: &Config) -> eyre::Result<SingleSignaturesIdentifier> {
+        Self::with_database(
+            Config::foundry_cache_dir(),
+            config.offline,
+            SignatureDatabase::load(config),
+        )
+    }
+
+    #[instrument(target = "evm::traces", skip(database))]
+    pub fn with_database(
+        cache_path: Option<PathBuf>,
+        offline: bool,
+        database: SignatureDatabase,
     ) -> eyre::Result<SingleSignaturesIdentifier> {
         let sign_eth_api = OpenChainClient::new()?;
 
@@ -59,6 +81,7 @@ impl SignaturesIdentifier {
                 CachedSignatures::default()
             };
             Self {
+                database,
                 cached,
                 cached_path: Some(path),
                 unavailable: HashSet::new(),
@@ -67,6 +90,7 @@ impl SignaturesIdentifier {
             }
         } else {
             Self {
+                database,
                 cached: Default::default(),
                 cached_path: None,
                 unavailable: HashSet::new(),
@@ -78,6 +102,11 @@ impl SignaturesIdentifier {
         Ok(Arc::new(RwLock::new(identifier)))
     }
 
+    /// Returns the project's local signature database.
+    pub fn database(&self) -> &SignatureDatabase {
+        &self.database
+    }
+
     #[instrument(target = "evm::traces", skip(self))]
     pub fn save(&self) {
         if let Some(cached_path) = &self.cached_path {
@@ -102,9 +131,9 @@ impl SignaturesIdentifier {
         identifiers: impl IntoIterator<Item = impl AsRef<[u8]>>,
         get_type: impl Fn(&str) -> eyre::Result<T>,
     ) -> Vec<Option<T>> {
-        let cache = match selector_type {
-            SelectorType::Function => &mut self.cached.functions,
-            SelectorType::Event => &mut self.cached.events,
+        let (cache, database) = match selector_type {
+            SelectorType::Function => (&mut self.cached.functions, &self.database.functions),
+            SelectorType::Event => (&mut self.cached.events, &self.database.events),
         };
 
         let hex_identifiers: Vec<String> =
@@ -113,6 +142,7 @@ impl SignaturesIdentifier {
         if !self.offline {
             let query: Vec<_> = hex_identifiers
                 .iter()
+                .filter(|v| !database.contains_key(v.as_str()))
                 .filter(|v| !cache.contains_key(v.as_str()))
                 .filter(|v| !self.unavailable.contains(v.as_str()))
                 .collect();
@@ -134,7 +164,13 @@ impl SignaturesIdentifier {
             }
         }
 
-        hex_identifiers.iter().map(|v| cache.get(v).and_then(|v| get_type(v).ok())).collect()
+        hex_identifiers
+            .iter()
+            .map(|v| {
+                let local = database.get(v).and_then(|signatures| signatures.first());
+                local.or_else(|| cache.get(v)).and_then(|v| get_type(v).ok())
+            })
+            .collect()
     }
 
     /// Identifies `Function`s from its cache or `https://api.openchain.xyz`
@@ -12,13 +12,12 @@ use alloy_primitives::{hex, LogData};
 use foundry_common::contracts::{ContractsByAddress, ContractsByArtifact};
 use foundry_evm_core::constants::CHEATCODE_ADDRESS;
 use futures::{future::BoxFuture, FutureExt};
-use revm_inspectors::tracing::types::TraceMemberOrder;
 use serde::{Deserialize, Serialize};
-use std::fmt::Write;
+use std::{collections::BTreeMap, fmt::Write};
 use yansi::{Color, Paint};
 
 pub use revm_inspectors::tracing::{
-    types::{CallKind, CallTrace, CallTraceNode},
+    types::{CallKind, CallLog, CallTrace, CallTraceNode, TraceMemberOrder},
     CallTraceArena, FourByteInspector, GethTraceBuilder, ParityTraceBuilder, StackSnapshotType,
     TracingInspector, TracingInspectorConfig,
 };
@@ -34,6 +33,12 @@ pub use decoder::{CallTraceDecoder, CallTraceDecoderBuilder};
 
 pub type Traces = Vec<(TraceKind, CallTraceArena)>;
 
+/// The gas refunded to the calls of a trace arena, keyed by the index of their node.
+///
+/// Refunds are only applied at the end of the transaction, so the gas used by a call doesn't
+/// account for them, except for the root call whose gas used is that of the transaction.
+pub type GasRefunds = BTreeMap<usize, u64>;
+
 #[derive(Default, Debug, Eq, PartialEq)]
 pub struct DecodedCallData {
     pub signature: String,
@@ -71,12 +76,24 @@ const RETURN: &str = "← ";
 pub async fn render_trace_arena(
     arena: &CallTraceArena,
     decoder: &CallTraceDecoder,
+) -> Result<String, std::fmt::Error> {
+    render_trace_arena_with_refunds(arena, decoder, &GasRefunds::new()).await
+}
+
+/// Render a collection of call traces, along with the gas refunded to each call.
+///
+/// The traces will be decoded using the given decoder, if possible.
+pub async fn render_trace_arena_with_refunds(
+    arena: &CallTraceArena,
+    decoder: &CallTraceDecoder,
+    refunds: &GasRefunds,
 ) -> Result<String, std::fmt::Error> {
     decoder.prefetch_signatures(arena.nodes()).await;
 
     fn inner<'a>(
         arena: &'a [CallTraceNode],
         decoder: &'a CallTraceDecoder,
+        refunds: &'a GasRefunds,
         s: &'a mut String,
         idx: usize,
         left: &'a str,
@@ -87,7 +104,12 @@ pub async fn render_trace_arena(
 
             // Display trace header
             let (trace, return_data) = render_trace(&node.trace, decoder).await?;
-            writeln!(s, "{left}{trace}")?;
+            match refunds.get(&idx) {
+                Some(refund) => {
+                    writeln!(s, "{left}{trace} {}", format!("(refund: {refund})").dim())?
+                }
+                None => writeln!(s, "{left}{trace}")?,
+            }
 
             // Display logs and subcalls
             let left_prefix = format!("{child}{BRANCH}");
@@ -111,6 +133,7 @@ pub async fn render_trace_arena(
                         inner(
                             arena,
                             decoder,
+                            refunds,
                             s,
                             node.children[*index],
                             &left_prefix,
@@ -146,7 +169,7 @@ pub async fn render_trace_arena(
     }
 
     let mut s = String::new();
-    inner(arena.nodes(), decoder, &mut s, 0, "  ", "  ").await?;
+    inner(arena.nodes(), decoder, refunds, &mut s, 0, "  ", "  ").await?;
     Ok(s)
 }
 
@@ -1,8 +1,8 @@
 // SPDX-License-Identifier: UNLICENSED
-pragma solidity ^0.8.13;
+pragma solidity {solidity_version};
 
 import {Test, console} from "forge-std/Test.sol";
-import {{contract_name}} from "../src/{contract_name}.sol"; 
+import {{contract_name}} from "{contract_path}"; 
 
 contract {contract_name}Test is Test {
     {contract_name} public {instance_name};
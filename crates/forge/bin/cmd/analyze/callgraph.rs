@@ -0,0 +1,478 @@
+use crate::cmd::{install, test::TestArgs};
+use alloy_primitives::{hex, Address, U256};
+use clap::{Parser, ValueEnum, ValueHint};
+use comfy_table::{presets::ASCII_MARKDOWN, Table};
+use eyre::Result;
+use forge::{
+    constants::{CHEATCODE_ADDRESS, HARDHAT_CONSOLE_ADDRESS},
+    revm::interpreter::opcode,
+    traces::{
+        identifier::TraceIdentifiers, CallTraceArena, CallTraceDecoder, CallTraceDecoderBuilder,
+        CallTraceNode, TraceKind,
+    },
+    MultiContractRunnerBuilder, TestOptions,
+};
+use forge_script::ScriptArgs;
+use foundry_cli::utils::LoadConfig;
+use foundry_common::{compile::ProjectCompiler, fs};
+use std::{collections::BTreeMap, fmt::Write, path::PathBuf, sync::Arc};
+use yansi::Paint;
+
+// Loads project's figment and merges the build cli arguments into it
+foundry_config::impl_figment_convert!(CallgraphArgs, test);
+
+/// CLI arguments for `forge analyze callgraph`.
+#[derive(Clone, Debug, Parser)]
+pub struct CallgraphArgs {
+    /// Analyze the given script instead of the test suite.
+    #[arg(long, value_hint = ValueHint::FilePath, value_name = "PATH")]
+    script: Option<String>,
+
+    /// The signature of the script function to run.
+    #[arg(long, default_value = "run()", requires = "script", value_name = "SIGNATURE")]
+    sig: String,
+
+    /// The format of the call graph.
+    #[arg(long, value_enum, default_value_t)]
+    graph_format: GraphFormat,
+
+    /// Write the call graph and the storage dependencies to files in the given directory instead
+    /// of printing them.
+    #[arg(long, value_hint = ValueHint::DirPath, value_name = "DIR")]
+    output_dir: Option<PathBuf>,
+
+    #[command(flatten)]
+    test: TestArgs,
+}
+
+/// The format of the call graph.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum GraphFormat {
+    /// A Graphviz DOT graph.
+    #[default]
+    Dot,
+    /// A Mermaid flowchart.
+    Mermaid,
+}
+
+impl CallgraphArgs {
+    pub async fn run(self) -> Result<()> {
+        let graph = if let Some(path) = &self.script {
+            self.analyze_script(path.clone()).await?
+        } else {
+            self.analyze_tests().await?
+        };
+
+        let rendered = match self.graph_format {
+            GraphFormat::Dot => graph.to_dot(),
+            GraphFormat::Mermaid => graph.to_mermaid(),
+        };
+        if let Some(dir) = &self.output_dir {
+            fs::create_dir_all(dir)?;
+            let extension = match self.graph_format {
+                GraphFormat::Dot => "dot",
+                GraphFormat::Mermaid => "mmd",
+            };
+            let graph_path = dir.join(format!("callgraph.{extension}"));
+            fs::write(&graph_path, rendered)?;
+            let storage_path = dir.join("storage.json");
+            fs::write_json_file(&storage_path, &graph.storage_report())?;
+            println!(
+                "{} call graph to {} and storage dependencies to {}",
+                "Wrote".green(),
+                graph_path.display(),
+                storage_path.display()
+            );
+        } else {
+            print!("{rendered}");
+            for (contract, table) in graph.storage_tables() {
+                println!("\nStorage dependencies of {contract}:\n{table}");
+            }
+        }
+        Ok(())
+    }
+
+    /// Runs the matching tests and aggregates the traces of their setup and execution.
+    async fn analyze_tests(&self) -> Result<CallGraph> {
+        let (mut config, evm_opts) = self.load_config_and_evm_opts_emit_warnings()?;
+        if install::install_missing_dependencies(&mut config, self.test.build_args().silent) &&
+            config.auto_detect_remappings
+        {
+            // need to re-configure here to also catch additional remappings
+            config = self.load_config();
+        }
+
+        let project = config.project()?;
+        let output =
+            ProjectCompiler::new().quiet_if(self.test.build_args().silent).compile(&project)?;
+
+        let env = evm_opts.evm_env().await?;
+        let config = Arc::new(config);
+        // Debug mode records the executed steps, from which the storage accesses are read.
+        let mut runner = MultiContractRunnerBuilder::new(config.clone())
+            .set_debug(true)
+            .initial_balance(evm_opts.initial_balance)
+            .evm_spec(config.evm_spec_id())
+            .sender(evm_opts.sender)
+            .with_fork(evm_opts.get_fork(&config, env.clone()))
+            .with_test_options(TestOptions {
+                fuzz: config.fuzz.clone(),
+                invariant: config.invariant.clone(),
+                ..Default::default()
+            })
+            .enable_isolation(evm_opts.isolate)
+            .build(project.root(), &output, env, evm_opts)?;
+
+        let known_contracts = runner.known_contracts.clone();
+        let results = runner.test_collect(&self.test.filter(&config));
+
+        let mut identifier = TraceIdentifiers::new().with_local(&known_contracts);
+        let mut decoder =
+            CallTraceDecoderBuilder::new().with_known_contracts(&known_contracts).build();
+
+        let mut graph = CallGraph::default();
+        for suite in results.values() {
+            for result in suite.test_results.values() {
+                decoder.clear_addresses();
+                decoder
+                    .labels
+                    .extend(result.labeled_addresses.iter().map(|(k, v)| (*k, v.clone())));
+                for (kind, arena) in &result.traces {
+                    if *kind == TraceKind::Deployment {
+                        continue
+                    }
+                    decoder.identify(arena, &mut identifier);
+                    graph.add(arena, &decoder);
+                }
+            }
+        }
+        Ok(graph)
+    }
+
+    /// Executes the script without broadcasting and aggregates its traces.
+    async fn analyze_script(&self, path: String) -> Result<CallGraph> {
+        let args = ScriptArgs {
+            path,
+            sig: self.sig.clone(),
+            opts: self.test.build_args().clone(),
+            evm_opts: self.test.evm_args().clone(),
+            ..Default::default()
+        };
+        let (traces, decoder) = args.execute_traces().await?;
+
+        let mut graph = CallGraph::default();
+        for (kind, arena) in &traces {
+            if *kind != TraceKind::Deployment {
+                graph.add(arena, &decoder);
+            }
+        }
+        Ok(graph)
+    }
+}
+
+/// Whether a storage slot was read, written or both.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+struct Access {
+    read: bool,
+    write: bool,
+}
+
+impl Access {
+    fn as_str(self) -> &'static str {
+        match (self.read, self.write) {
+            (true, true) => "RW",
+            (false, true) => "W",
+            _ => "R",
+        }
+    }
+}
+
+/// The calls between contracts and the storage accesses of their functions, aggregated over a
+/// set of traces.
+#[derive(Debug, Default)]
+pub(crate) struct CallGraph {
+    /// The number of calls to each function, keyed by the calling and the called contract.
+    pub(crate) calls: BTreeMap<(String, String), BTreeMap<String, usize>>,
+    /// The storage slots accessed by each function, keyed by the contract owning the storage.
+    storage: BTreeMap<String, BTreeMap<String, BTreeMap<U256, Access>>>,
+}
+
+impl CallGraph {
+    /// Adds the calls and storage accesses of a trace.
+    ///
+    /// Contracts are identified by their label or name, falling back to their address, so that
+    /// all instances of a contract are merged. Calls to the cheatcode and console addresses are
+    /// ignored.
+    fn add(&mut self, arena: &CallTraceArena, decoder: &CallTraceDecoder) {
+        let nodes = arena.nodes();
+        let names = nodes
+            .iter()
+            .map(|node| (contract_name(decoder, node.trace.address), function_name(decoder, node)))
+            .collect::<Vec<_>>();
+
+        for node in nodes {
+            let (contract, function) = &names[node.idx];
+            let address = node.trace.address;
+            if address == CHEATCODE_ADDRESS || address == HARDHAT_CONSOLE_ADDRESS {
+                continue
+            }
+            if let Some(parent) = node.parent {
+                let caller = names[parent].0.clone();
+                let count = self
+                    .calls
+                    .entry((caller, contract.clone()))
+                    .or_default()
+                    .entry(function.clone())
+                    .or_default();
+                *count += 1;
+            }
+
+            for step in &node.trace.steps {
+                let write = match step.op.get() {
+                    opcode::SLOAD => false,
+                    opcode::SSTORE => true,
+                    _ => continue,
+                };
+                // The slot is on top of the stack, which is recorded before the step executes.
+                let Some(slot) = step.stack.as_ref().and_then(|stack| stack.last()) else {
+                    continue
+                };
+                // Delegate calls access the storage of the calling contract.
+                let owner = if step.contract == address {
+                    contract.clone()
+                } else {
+                    contract_name(decoder, step.contract)
+                };
+                let access = self
+                    .storage
+                    .entry(owner)
+                    .or_default()
+                    .entry(function.clone())
+                    .or_default()
+                    .entry(*slot)
+                    .or_default();
+                access.read |= !write;
+                access.write |= write;
+            }
+        }
+    }
+
+    /// Renders the call graph as a Graphviz DOT graph.
+    pub(crate) fn to_dot(&self) -> String {
+        let mut out = String::from("digraph callgraph {\n    rankdir=LR;\n    node [shape=box];\n");
+        for ((caller, callee), functions) in &self.calls {
+            let label = edge_label(functions).iter().map(|f| escape_dot(f)).collect::<Vec<_>>();
+            let _ = writeln!(
+                out,
+                "    \"{}\" -> \"{}\" [label=\"{}\"];",
+                escape_dot(caller),
+                escape_dot(callee),
+                label.join("\\n")
+            );
+        }
+        out.push_str("}\n");
+        out
+    }
+
+    /// Renders the call graph as a Mermaid flowchart.
+    pub(crate) fn to_mermaid(&self) -> String {
+        let mut ids = BTreeMap::new();
+        for (caller, callee) in self.calls.keys() {
+            for contract in [caller, callee] {
+                let id = ids.len();
+                ids.entry(contract.as_str()).or_insert(id);
+            }
+        }
+
+        let mut out = String::from("flowchart LR\n");
+        for (contract, id) in &ids {
+            let _ = writeln!(out, "    n{id}[\"{}\"]", escape_mermaid(contract));
+        }
+        for ((caller, callee), functions) in &self.calls {
+            let label = edge_label(functions).iter().map(|f| escape_mermaid(f)).collect::<Vec<_>>();
+            let _ = writeln!(
+                out,
+                "    n{} -->|\"{}\"| n{}",
+                ids[caller.as_str()],
+                label.join("<br/>"),
+                ids[callee.as_str()]
+            );
+        }
+        out
+    }
+
+    /// Returns the storage dependency matrix of each contract, with a row per function and a
+    /// column per slot.
+    fn storage_tables(&self) -> Vec<(&str, Table)> {
+        self.storage
+            .iter()
+            .map(|(contract, functions)| {
+                let mut slots =
+                    functions.values().flat_map(|slots| slots.keys()).collect::<Vec<_>>();
+                slots.sort_unstable();
+                slots.dedup();
+
+                let mut table = Table::new();
+                table.load_preset(ASCII_MARKDOWN);
+                table.set_header(
+                    std::iter::once("Function".to_string())
+                        .chain(slots.iter().map(|slot| format_slot(slot))),
+                );
+                for (function, accesses) in functions {
+                    table.add_row(std::iter::once(function.clone()).chain(slots.iter().map(
+                        |slot| accesses.get(*slot).map_or("", |access| access.as_str()).to_string(),
+                    )));
+                }
+                (contract.as_str(), table)
+            })
+            .collect()
+    }
+
+    /// Returns the storage accesses as `contract => function => slot => "R" | "W" | "RW"`.
+    fn storage_report(&self) -> BTreeMap<&str, BTreeMap<&str, BTreeMap<String, &'static str>>> {
+        self.storage
+            .iter()
+            .map(|(contract, functions)| {
+                let functions = functions
+                    .iter()
+                    .map(|(function, slots)| {
+                        let slots = slots
+                            .iter()
+                            .map(|(slot, access)| (format!("{slot:#x}"), access.as_str()))
+                            .collect();
+                        (function.as_str(), slots)
+                    })
+                    .collect();
+                (contract.as_str(), functions)
+            })
+            .collect()
+    }
+}
+
+/// Returns the label or contract name of an address, or the address itself.
+fn contract_name(decoder: &CallTraceDecoder, address: Address) -> String {
+    if let Some(label) = decoder.labels.get(&address) {
+        return label.clone()
+    }
+    match decoder.contracts.get(&address) {
+        Some(identifier) => identifier.rsplit(':').next().unwrap_or(identifier).to_string(),
+        None => address.to_string(),
+    }
+}
+
+/// Returns the signature of the function called by a node, or its selector if unknown.
+fn function_name(decoder: &CallTraceDecoder, node: &CallTraceNode) -> String {
+    let trace = &node.trace;
+    if trace.kind.is_any_create() {
+        return "constructor".to_string()
+    }
+    let Some(selector) = trace.data.get(..4) else {
+        return if trace.data.is_empty() { "receive()" } else { "fallback()" }.to_string()
+    };
+    decoder
+        .functions
+        .get(selector)
+        .and_then(|functions| functions.first())
+        .map(|function| function.signature())
+        .unwrap_or_else(|| hex::encode_prefixed(selector))
+}
+
+/// Returns the called functions, with the number of calls if called more than once.
+fn edge_label(functions: &BTreeMap<String, usize>) -> Vec<String> {
+    functions
+        .iter()
+        .map(
+            |(function, count)| {
+                if *count > 1 {
+                    format!("{function} x{count}")
+                } else {
+                    function.clone()
+                }
+            },
+        )
+        .collect()
+}
+
+/// Shortens the large slots of mappings and dynamic arrays.
+fn format_slot(slot: &U256) -> String {
+    let hex = format!("{slot:#x}");
+    if hex.len() > 18 {
+        format!("{}…{}", &hex[..8], &hex[hex.len() - 6..])
+    } else {
+        hex
+    }
+}
+
+fn escape_dot(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+fn escape_mermaid(s: &str) -> String {
+    s.replace('"', "#quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn graph() -> CallGraph {
+        let mut graph = CallGraph::default();
+        graph.calls.insert(
+            ("CounterTest".to_string(), "Counter".to_string()),
+            BTreeMap::from([("increment()".to_string(), 2), ("number()".to_string(), 1)]),
+        );
+        graph.storage.insert(
+            "Counter".to_string(),
+            BTreeMap::from([
+                (
+                    "increment()".to_string(),
+                    BTreeMap::from([(U256::ZERO, Access { read: true, write: true })]),
+                ),
+                (
+                    "number()".to_string(),
+                    BTreeMap::from([(U256::MAX, Access { read: true, write: false })]),
+                ),
+            ]),
+        );
+        graph
+    }
+
+    #[test]
+    fn can_render_dot() {
+        assert_eq!(
+            graph().to_dot(),
+            "digraph callgraph {
+    rankdir=LR;
+    node [shape=box];
+    \"CounterTest\" -> \"Counter\" [label=\"increment() x2\\nnumber()\"];
+}
+"
+        );
+    }
+
+    #[test]
+    fn can_render_mermaid() {
+        assert_eq!(
+            graph().to_mermaid(),
+            "flowchart LR
+    n1[\"Counter\"]
+    n0[\"CounterTest\"]
+    n0 -->|\"increment() x2<br/>number()\"| n1
+"
+        );
+    }
+
+    #[test]
+    fn can_render_storage() {
+        let graph = graph();
+        let tables = graph.storage_tables();
+        assert_eq!(tables.len(), 1);
+        let table = tables[0].1.to_string();
+        assert!(table.contains("| increment() | RW  |"), "{table}");
+        assert!(table.contains("0xffffff…ffffff"), "{table}");
+
+        let report = serde_json::to_value(graph.storage_report()).unwrap();
+        assert_eq!(report["Counter"]["increment()"]["0x0"], "RW");
+        assert_eq!(report["Counter"]["number()"][format!("{:#x}", U256::MAX)], "R");
+    }
+}
@@ -0,0 +1,20 @@
+use clap::{Parser, Subcommand};
+
+mod callgraph;
+pub(crate) use callgraph::CallGraph;
+pub use callgraph::{CallgraphArgs, GraphFormat};
+
+/// CLI arguments for `forge analyze`.
+#[derive(Clone, Debug, Parser)]
+pub struct AnalyzeArgs {
+    #[command(subcommand)]
+    pub sub: AnalyzeSubcommands,
+}
+
+#[derive(Clone, Debug, Subcommand)]
+pub enum AnalyzeSubcommands {
+    /// Execute the tests, or a script, and report the calls between contracts and the storage
+    /// slots read and written by each function.
+    #[command(visible_alias = "cg")]
+    Callgraph(CallgraphArgs),
+}
@@ -0,0 +1,630 @@
+use super::call_graph;
+use alloy_json_abi::JsonAbi;
+use clap::{Parser, ValueHint};
+use comfy_table::{presets::ASCII_MARKDOWN, Table};
+use eyre::Result;
+use foundry_cli::{
+    opts::{CompilerArgs, CoreBuildArgs},
+    utils::{CommandUtils, Git, LoadConfig},
+};
+use foundry_common::{compile::ProjectCompiler, fs};
+use foundry_compilers::{
+    artifacts::output_selection::ContractOutputSelection, resolver::parse::SolData, Artifact, Graph,
+};
+use foundry_config::Config;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Serialize;
+use std::{
+    collections::BTreeMap,
+    fmt::Write,
+    path::{Path, PathBuf},
+};
+
+/// The maximum size of the runtime code of a contract, as defined by EIP-170.
+const RUNTIME_SIZE_LIMIT: usize = 24576;
+
+/// The maximum size of the initcode of a contract, as defined by EIP-3860.
+const INITCODE_SIZE_LIMIT: usize = 49152;
+
+/// Cheatcodes that reach outside of the EVM.
+const UNSAFE_CHEATCODES: &[&str] = &[
+    "ffi",
+    "readFile",
+    "readLine",
+    "writeFile",
+    "writeLine",
+    "removeFile",
+    "closeFile",
+    "setEnv",
+    "deriveKey",
+    "tryFfi",
+    "writeJson",
+    "writeToml",
+];
+
+static SPDX_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"SPDX-License-Identifier:\s*([^\s*]+)").unwrap());
+static CHEATCODE_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"\bvm\.(\w+)\s*[({]").unwrap());
+
+/// CLI arguments for `forge audit-prep`.
+#[derive(Clone, Debug, Parser)]
+pub struct AuditPrepArgs {
+    /// Write the report to the given file instead of stdout.
+    #[arg(long, short, value_hint = ValueHint::FilePath, value_name = "PATH")]
+    out: Option<PathBuf>,
+
+    /// Output the report as JSON instead of Markdown.
+    #[arg(long)]
+    json: bool,
+
+    /// The LCOV coverage report to summarize, as written by `forge coverage --report lcov`.
+    ///
+    /// Defaults to `lcov.info` in the project root, if it exists.
+    #[arg(long, value_hint = ValueHint::FilePath, value_name = "PATH")]
+    lcov: Option<PathBuf>,
+
+    #[command(flatten)]
+    build: CoreBuildArgs,
+}
+
+impl AuditPrepArgs {
+    pub fn run(self) -> Result<()> {
+        let Self { out, json, lcov, build } = self;
+
+        // Storage layouts are not part of the default output selection, and the low-level calls are
+        // read from the AST.
+        let mut extra_output = build.compiler.extra_output.clone();
+        if !extra_output.contains(&ContractOutputSelection::StorageLayout) {
+            extra_output.push(ContractOutputSelection::StorageLayout);
+        }
+        let build = CoreBuildArgs {
+            compiler: CompilerArgs { extra_output, ast: true, ..build.compiler },
+            ..build
+        };
+        let config = build.try_load_config_emit_warnings()?;
+        let project = build.project()?;
+        let output = ProjectCompiler::new().quiet(true).compile(&project)?;
+
+        let root = &config.root.0;
+        let mut report = AuditReport::default();
+
+        for (id, artifact) in output.artifact_ids() {
+            if !is_in(&id.source, &config.src, root) {
+                continue
+            }
+            let name = format!("{}:{}", relative(&id.source, root).display(), id.name);
+
+            let runtime_size = artifact.get_deployed_bytecode_bytes().map_or(0, |code| code.len());
+            let initcode_size = artifact.get_bytecode_bytes().map_or(0, |code| code.len());
+            if initcode_size > 0 {
+                report.sizes.push(ContractSize {
+                    contract: name.clone(),
+                    runtime_size,
+                    initcode_size,
+                });
+            }
+
+            if let Some(abi) = &artifact.abi {
+                let selectors = Selectors::new(abi);
+                if !selectors.is_empty() {
+                    report.selectors.insert(name.clone(), selectors);
+                }
+            }
+
+            if let Some(layout) = &artifact.storage_layout {
+                let slots = layout
+                    .storage
+                    .iter()
+                    .map(|slot| {
+                        let ty = layout.types.get(&slot.storage_type);
+                        StorageSlot {
+                            label: slot.label.clone(),
+                            ty: ty.map_or_else(|| "?".into(), |ty| ty.label.clone()),
+                            slot: slot.slot.clone(),
+                            offset: slot.offset,
+                            bytes: ty.map_or_else(|| "?".into(), |ty| ty.number_of_bytes.clone()),
+                        }
+                    })
+                    .collect::<Vec<_>>();
+                if !slots.is_empty() {
+                    report.storage_layouts.insert(name.clone(), slots);
+                }
+            }
+
+            if let Some(code) = artifact.get_deployed_bytecode_bytes() {
+                let counts = OpcodeCounts::new(&code);
+                if !counts.is_empty() {
+                    report.external_calls.opcodes.insert(name, counts);
+                }
+            }
+        }
+
+        report.external_calls.low_level_calls = call_graph::call_sites(&project, &output)?
+            .into_iter()
+            .filter(|site| site.kind.is_low_level())
+            .map(|site| CallSite { location: site.location, kind: site.callee })
+            .collect();
+
+        // Scan the sources of the project and of its dependencies.
+        let graph = Graph::<SolData>::resolve(&config.project_paths())?;
+        for file in graph.files().keys() {
+            let Ok(contents) = fs::read_to_string(file) else { continue };
+            let path = relative(file, root).display().to_string();
+
+            let license = SPDX_RE
+                .captures(&contents)
+                .map_or_else(|| "(missing)".to_string(), |caps| caps[1].to_string());
+            report.licenses.entry(license).or_default().push(path.clone());
+
+            if is_in(file, &config.test, root) || is_in(file, &config.script, root) {
+                for (line, cheatcode) in find_matches(&CHEATCODE_RE, &contents) {
+                    *report.cheatcodes.usage.entry(cheatcode.clone()).or_default() += 1;
+                    if UNSAFE_CHEATCODES.contains(&cheatcode.as_str()) {
+                        report
+                            .cheatcodes
+                            .unsafe_calls
+                            .push(CallSite { location: format!("{path}:{line}"), kind: cheatcode });
+                    }
+                }
+            }
+        }
+        // The graph is not ordered, sort the findings for stable output.
+        report.licenses.values_mut().for_each(|files| files.sort());
+        report.external_calls.low_level_calls.sort_by(|a, b| a.location.cmp(&b.location));
+        report.cheatcodes.unsafe_calls.sort_by(|a, b| a.location.cmp(&b.location));
+        report.cheatcodes.ffi_enabled = config.ffi;
+
+        report.dependencies = dependencies(&config);
+
+        let lcov = lcov.or_else(|| Some(root.join("lcov.info")).filter(|path| path.exists()));
+        if let Some(lcov) = lcov {
+            report.coverage = Some(CoverageSummary::from_lcov(&fs::read_to_string(&lcov)?, root));
+        }
+
+        let rendered =
+            if json { serde_json::to_string_pretty(&report)? } else { report.to_markdown() };
+        match out {
+            Some(out) => {
+                fs::write(&out, rendered)?;
+                println!("Audit report written to {}", out.display());
+            }
+            None => println!("{rendered}"),
+        }
+        Ok(())
+    }
+}
+
+/// The consolidated report of `forge audit-prep`.
+#[derive(Debug, Default, Serialize)]
+pub struct AuditReport {
+    pub sizes: Vec<ContractSize>,
+    pub selectors: BTreeMap<String, Selectors>,
+    pub storage_layouts: BTreeMap<String, Vec<StorageSlot>>,
+    pub external_calls: ExternalCalls,
+    pub cheatcodes: CheatcodeUsage,
+    pub coverage: Option<CoverageSummary>,
+    pub dependencies: Vec<Dependency>,
+    /// The source files of the project and its dependencies, by SPDX license identifier.
+    pub licenses: BTreeMap<String, Vec<String>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ContractSize {
+    pub contract: String,
+    pub runtime_size: usize,
+    pub initcode_size: usize,
+}
+
+/// The selectors of the functions and errors and the topics of the events of a contract, by
+/// signature.
+#[derive(Debug, Default, Serialize)]
+pub struct Selectors {
+    pub functions: BTreeMap<String, String>,
+    pub events: BTreeMap<String, String>,
+    pub errors: BTreeMap<String, String>,
+}
+
+impl Selectors {
+    fn new(abi: &JsonAbi) -> Self {
+        Self {
+            functions: abi.functions().map(|f| (f.signature(), f.selector().to_string())).collect(),
+            events: abi.events().map(|e| (e.signature(), e.selector().to_string())).collect(),
+            errors: abi.errors().map(|e| (e.signature(), e.selector().to_string())).collect(),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.functions.is_empty() && self.events.is_empty() && self.errors.is_empty()
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct StorageSlot {
+    pub label: String,
+    #[serde(rename = "type")]
+    pub ty: String,
+    pub slot: String,
+    pub offset: i64,
+    pub bytes: String,
+}
+
+/// The external calls made by the contracts of the project.
+#[derive(Debug, Default, Serialize)]
+pub struct ExternalCalls {
+    /// The number of call, create and self-destruct opcodes in the runtime code of each contract.
+    pub opcodes: BTreeMap<String, OpcodeCounts>,
+    /// The low-level calls and transfers in the sources.
+    pub low_level_calls: Vec<CallSite>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct OpcodeCounts {
+    pub call: usize,
+    pub staticcall: usize,
+    pub delegatecall: usize,
+    pub callcode: usize,
+    pub create: usize,
+    pub create2: usize,
+    pub selfdestruct: usize,
+}
+
+impl OpcodeCounts {
+    /// Counts the opcodes of the given runtime code, ignoring push data and the metadata.
+    fn new(code: &[u8]) -> Self {
+        // The CBOR-encoded metadata is appended to the code, followed by its length.
+        let code = match code {
+            [rest @ .., hi, lo] => {
+                let len = u16::from_be_bytes([*hi, *lo]) as usize;
+                rest.len().checked_sub(len).map_or(code, |end| &rest[..end])
+            }
+            _ => code,
+        };
+
+        let mut counts = Self::default();
+        let mut pc = 0;
+        while let Some(&op) = code.get(pc) {
+            match op {
+                0xf0 => counts.create += 1,
+                0xf1 => counts.call += 1,
+                0xf2 => counts.callcode += 1,
+                0xf4 => counts.delegatecall += 1,
+                0xf5 => counts.create2 += 1,
+                0xfa => counts.staticcall += 1,
+                0xff => counts.selfdestruct += 1,
+                // PUSH1..PUSH32
+                0x60..=0x7f => pc += (op - 0x5f) as usize,
+                _ => {}
+            }
+            pc += 1;
+        }
+        counts
+    }
+
+    fn is_empty(&self) -> bool {
+        self.total() == 0
+    }
+
+    fn total(&self) -> usize {
+        self.call +
+            self.staticcall +
+            self.delegatecall +
+            self.callcode +
+            self.create +
+            self.create2 +
+            self.selfdestruct
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct CallSite {
+    /// The location in the sources, `<path>:<line>`.
+    pub location: String,
+    /// The called function or cheatcode.
+    pub kind: String,
+}
+
+/// The cheatcodes used by the tests and scripts.
+#[derive(Debug, Default, Serialize)]
+pub struct CheatcodeUsage {
+    /// Whether `ffi` is enabled in the config.
+    pub ffi_enabled: bool,
+    /// The number of calls to each cheatcode.
+    pub usage: BTreeMap<String, usize>,
+    /// The calls to cheatcodes that reach outside of the EVM.
+    pub unsafe_calls: Vec<CallSite>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Dependency {
+    pub path: String,
+    /// The tag or commit the dependency is at, if it is a git repository.
+    pub version: Option<String>,
+}
+
+/// Summary of an LCOV coverage report.
+#[derive(Debug, Default, Serialize)]
+pub struct CoverageSummary {
+    pub files: BTreeMap<String, CoverageCounts>,
+    pub total: CoverageCounts,
+}
+
+#[derive(Clone, Copy, Debug, Default, Serialize)]
+pub struct CoverageCounts {
+    pub lines: (usize, usize),
+    pub functions: (usize, usize),
+    pub branches: (usize, usize),
+}
+
+impl CoverageSummary {
+    /// Parses the hit and found counters of an LCOV report.
+    fn from_lcov(lcov: &str, root: &Path) -> Self {
+        let mut summary = Self::default();
+        let mut current = None;
+        for line in lcov.lines() {
+            let Some((key, value)) = line.split_once(':') else { continue };
+            if key == "SF" {
+                let path = relative(Path::new(value), root).display().to_string();
+                current = Some(summary.files.entry(path).or_default());
+                continue
+            }
+            let (Some(counts), Ok(value)) = (current.as_deref_mut(), value.parse::<usize>()) else {
+                continue
+            };
+            match key {
+                "LH" => counts.lines.0 = value,
+                "LF" => counts.lines.1 = value,
+                "FNH" => counts.functions.0 = value,
+                "FNF" => counts.functions.1 = value,
+                "BRH" => counts.branches.0 = value,
+                "BRF" => counts.branches.1 = value,
+                _ => {}
+            }
+        }
+        for counts in summary.files.values() {
+            summary.total.lines.0 += counts.lines.0;
+            summary.total.lines.1 += counts.lines.1;
+            summary.total.functions.0 += counts.functions.0;
+            summary.total.functions.1 += counts.functions.1;
+            summary.total.branches.0 += counts.branches.0;
+            summary.total.branches.1 += counts.branches.1;
+        }
+        summary
+    }
+}
+
+impl AuditReport {
+    /// Renders the report as Markdown.
+    pub fn to_markdown(&self) -> String {
+        let mut s = String::from("# Audit preparation report\n");
+
+        let _ = writeln!(s, "\n## Contract sizes\n");
+        let mut table =
+            markdown_table(["Contract", "Runtime size (B)", "Initcode size (B)", "Margin (B)"]);
+        for size in &self.sizes {
+            let runtime = flag(size.runtime_size, RUNTIME_SIZE_LIMIT);
+            let initcode = flag(size.initcode_size, INITCODE_SIZE_LIMIT);
+            let margin = RUNTIME_SIZE_LIMIT as isize - size.runtime_size as isize;
+            table.add_row([size.contract.clone(), runtime, initcode, margin.to_string()]);
+        }
+        let _ = writeln!(s, "{table}");
+
+        let _ = writeln!(s, "\n## Selectors");
+        for (contract, selectors) in &self.selectors {
+            let _ = writeln!(s, "\n### {contract}\n");
+            let mut table = markdown_table(["Kind", "Signature", "Selector"]);
+            for (kind, entries) in [
+                ("function", &selectors.functions),
+                ("event", &selectors.events),
+                ("error", &selectors.errors),
+            ] {
+                for (signature, selector) in entries {
+                    table.add_row([kind, signature.as_str(), selector.as_str()]);
+                }
+            }
+            let _ = writeln!(s, "{table}");
+        }
+
+        let _ = writeln!(s, "\n## Storage layouts");
+        for (contract, slots) in &self.storage_layouts {
+            let _ = writeln!(s, "\n### {contract}\n");
+            let mut table = markdown_table(["Name", "Type", "Slot", "Offset", "Bytes"]);
+            for slot in slots {
+                table.add_row([
+                    &slot.label,
+                    &slot.ty,
+                    &slot.slot,
+                    &slot.offset.to_string(),
+                    &slot.bytes,
+                ]);
+            }
+            let _ = writeln!(s, "{table}");
+        }
+
+        let _ = writeln!(s, "\n## External calls\n");
+        let mut table = markdown_table([
+            "Contract",
+            "CALL",
+            "STATICCALL",
+            "DELEGATECALL",
+            "CALLCODE",
+            "CREATE",
+            "CREATE2",
+            "SELFDESTRUCT",
+        ]);
+        for (contract, counts) in &self.external_calls.opcodes {
+            table.add_row([
+                contract.clone(),
+                counts.call.to_string(),
+                counts.staticcall.to_string(),
+                counts.delegatecall.to_string(),
+                counts.callcode.to_string(),
+                counts.create.to_string(),
+                counts.create2.to_string(),
+                counts.selfdestruct.to_string(),
+            ]);
+        }
+        let _ = writeln!(s, "{table}");
+        if !self.external_calls.low_level_calls.is_empty() {
+            let _ = writeln!(s, "\nLow-level calls:\n");
+            for call in &self.external_calls.low_level_calls {
+                let _ = writeln!(s, "- `{}` at {}", call.kind, call.location);
+            }
+        }
+
+        let _ = writeln!(s, "\n## Cheatcodes used in tests and scripts\n");
+        let _ = writeln!(s, "ffi enabled: {}\n", self.cheatcodes.ffi_enabled);
+        let mut table = markdown_table(["Cheatcode", "Calls"]);
+        for (cheatcode, count) in &self.cheatcodes.usage {
+            table.add_row([cheatcode.clone(), count.to_string()]);
+        }
+        let _ = writeln!(s, "{table}");
+        if !self.cheatcodes.unsafe_calls.is_empty() {
+            let _ = writeln!(s, "\nUnsafe cheatcodes:\n");
+            for call in &self.cheatcodes.unsafe_calls {
+                let _ = writeln!(s, "- `{}` at {}", call.kind, call.location);
+            }
+        }
+
+        let _ = writeln!(s, "\n## Coverage\n");
+        match &self.coverage {
+            Some(coverage) => {
+                let mut table = markdown_table(["File", "Lines", "Functions", "Branches"]);
+                let files = coverage.files.iter().map(|(file, counts)| (file.as_str(), counts));
+                for (file, counts) in files.chain([("Total", &coverage.total)]) {
+                    table.add_row([
+                        file.to_string(),
+                        ratio(counts.lines),
+                        ratio(counts.functions),
+                        ratio(counts.branches),
+                    ]);
+                }
+                let _ = writeln!(s, "{table}");
+            }
+            None => {
+                let _ = writeln!(
+                    s,
+                    "No coverage report found, run `forge coverage --report lcov` first."
+                );
+            }
+        }
+
+        let _ = writeln!(s, "\n## Dependencies\n");
+        let mut table = markdown_table(["Path", "Version"]);
+        for dependency in &self.dependencies {
+            table.add_row([dependency.path.as_str(), dependency.version.as_deref().unwrap_or("-")]);
+        }
+        let _ = writeln!(s, "{table}");
+
+        let _ = writeln!(s, "\n## Licenses\n");
+        let mut table = markdown_table(["License", "Files"]);
+        for (license, files) in &self.licenses {
+            table.add_row([license.clone(), files.len().to_string()]);
+        }
+        let _ = writeln!(s, "{table}");
+
+        s
+    }
+}
+
+/// Returns the dependencies installed in the library directories, along with their version.
+fn dependencies(config: &Config) -> Vec<Dependency> {
+    let root = &config.root.0;
+    let mut dependencies = Vec::new();
+    for lib in &config.libs {
+        let Ok(entries) = std::fs::read_dir(root.join(lib)) else { continue };
+        let mut paths = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.is_dir())
+            .collect::<Vec<_>>();
+        paths.sort();
+        for path in paths {
+            let version = path.join(".git").exists().then(|| {
+                Git::new(&path)
+                    .cmd()
+                    .args(["describe", "--tags", "--always"])
+                    .get_stdout_lossy()
+                    .ok()
+            });
+            dependencies.push(Dependency {
+                path: relative(&path, root).display().to_string(),
+                version: version.flatten(),
+            });
+        }
+    }
+    dependencies
+}
+
+/// Returns the captures of the first group of the given regex, along with their line number.
+fn find_matches(re: &Regex, contents: &str) -> Vec<(usize, String)> {
+    contents
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim_start().starts_with("//"))
+        .flat_map(|(i, line)| re.captures_iter(line).map(move |caps| (i + 1, caps[1].to_string())))
+        .collect()
+}
+
+/// Returns whether `path` is inside `dir`, either of them being relative to `root`.
+fn is_in(path: &Path, dir: &Path, root: &Path) -> bool {
+    root.join(path).starts_with(root.join(dir))
+}
+
+fn relative<'a>(path: &'a Path, root: &Path) -> &'a Path {
+    path.strip_prefix(root).unwrap_or(path)
+}
+
+fn markdown_table<const N: usize, T: ToString>(header: [T; N]) -> Table {
+    let mut table = Table::new();
+    table.load_preset(ASCII_MARKDOWN);
+    table.set_header(header.map(|h| h.to_string()));
+    table
+}
+
+/// Renders a size, flagging it if it exceeds the given limit.
+fn flag(size: usize, limit: usize) -> String {
+    if size > limit {
+        format!("{size} (exceeds {limit})")
+    } else {
+        size.to_string()
+    }
+}
+
+fn ratio((hit, found): (usize, usize)) -> String {
+    if found == 0 {
+        return "-".to_string()
+    }
+    format!("{:.2}% ({hit}/{found})", hit as f64 * 100.0 / found as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn count_opcodes() {
+        // PUSH2 0xf1f1 CALL DELEGATECALL STOP, followed by 2 bytes of metadata
+        let code = [0x61, 0xf1, 0xf1, 0xf1, 0xf4, 0x00, 0xff, 0xff, 0x00, 0x02];
+        let counts = OpcodeCounts::new(&code);
+        assert_eq!(counts.call, 1);
+        assert_eq!(counts.delegatecall, 1);
+        assert_eq!(counts.selfdestruct, 0);
+        assert_eq!(counts.total(), 2);
+    }
+
+    #[test]
+    fn summarize_lcov() {
+        let lcov = "TN:\nSF:/root/src/A.sol\nFNF:2\nFNH:1\nLF:10\nLH:5\nBRF:0\nBRH:0\nend_of_record\nSF:/root/src/B.sol\nLF:10\nLH:10\nend_of_record\n";
+        let summary = CoverageSummary::from_lcov(lcov, Path::new("/root"));
+        assert_eq!(summary.files["src/A.sol"].functions, (1, 2));
+        assert_eq!(summary.total.lines, (15, 20));
+        assert_eq!(ratio(summary.total.lines), "75.00% (15/20)");
+    }
+
+    #[test]
+    fn find_cheatcodes() {
+        let src = "vm.ffi(inputs);\n// vm.ffi(commented);\nvm.prank{ value: 1 }(alice);\n";
+        let matches = find_matches(&CHEATCODE_RE, src);
+        assert_eq!(matches, [(1, "ffi".to_string()), (3, "prank".to_string())]);
+    }
+}
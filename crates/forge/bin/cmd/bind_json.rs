@@ -0,0 +1,393 @@
+use clap::{Parser, ValueHint};
+use eyre::{OptionExt, Result};
+use foundry_cli::{opts::CoreBuildArgs, utils::LoadConfig};
+use foundry_common::{compile::ProjectCompiler, fs};
+use foundry_compilers::artifacts::ast::{Node, NodeType};
+use foundry_config::impl_figment_convert;
+use std::{
+    collections::{btree_map::Entry, BTreeMap, HashMap},
+    fmt::Write,
+    path::{Path, PathBuf},
+};
+
+impl_figment_convert!(BindJsonArgs, build_args);
+
+const DEFAULT_OUT: &str = "utils/JsonBindings.sol";
+
+/// CLI arguments for `forge bind-json`.
+#[derive(Clone, Debug, Parser)]
+pub struct BindJsonArgs {
+    /// The path of the generated library, relative to the project root.
+    #[arg(long, value_hint = ValueHint::FilePath, value_name = "PATH", default_value = DEFAULT_OUT)]
+    out: PathBuf,
+
+    /// Check that the existing bindings are up to date instead of writing them.
+    ///
+    /// Exits with an error if the bindings don't match the project's structs.
+    #[arg(long)]
+    check: bool,
+
+    #[command(flatten)]
+    build_args: CoreBuildArgs,
+}
+
+impl BindJsonArgs {
+    pub fn run(self) -> Result<()> {
+        let mut config = self.try_load_config_emit_warnings()?;
+        // The struct definitions are read from the Solc AST.
+        config.ast = true;
+        let project = config.project()?;
+        let out = project.root().join(&self.out);
+
+        let output = ProjectCompiler::new().compile(&project)?;
+
+        // Index all the definitions struct members can reference, including ones in libraries.
+        // AST node IDs are only unique within a compiler run, so sources are grouped by version.
+        let mut versioned = HashMap::<_, (Definitions, Vec<usize>)>::new();
+        for (path, source_file, version) in output.output().sources.sources_with_version() {
+            let Some(ast) = &source_file.ast else { continue };
+            let is_bound =
+                !project.paths.has_library_ancestor(path) && project.root().join(path) != out;
+            let (defs, structs) = versioned.entry(version).or_default();
+            for node in &ast.nodes {
+                defs.collect(node, None, path, is_bound.then_some(&mut *structs));
+            }
+        }
+
+        let mut bindings = BTreeMap::new();
+        for (defs, structs) in versioned.values() {
+            for id in structs {
+                let def = &defs.structs[id];
+                let schema = match defs.encode_type(*id) {
+                    Ok(schema) => schema,
+                    Err(err) => {
+                        warn!(name = %def.qualified_name(), %err, "skipping struct");
+                        continue
+                    }
+                };
+                match bindings.entry(def.qualified_name()) {
+                    Entry::Vacant(entry) => {
+                        entry.insert(Binding { def, schema });
+                    }
+                    Entry::Occupied(entry) => {
+                        if entry.get().def.path != def.path {
+                            warn!(
+                                name = %def.qualified_name(),
+                                path = %def.path.display(),
+                                other = %entry.get().def.path.display(),
+                                "skipping struct with the same name as another struct"
+                            );
+                        }
+                    }
+                }
+            }
+        }
+
+        let library = render_library(&bindings);
+        if self.check {
+            let existing = fs::read_to_string(&out).unwrap_or_default();
+            if existing != library {
+                eyre::bail!(
+                    "JSON bindings at {} are out of date, run `forge bind-json` to regenerate them",
+                    self.out.display()
+                );
+            }
+            println!("JSON bindings are up to date.");
+            return Ok(())
+        }
+
+        if let Some(parent) = out.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&out, library)?;
+        println!("JSON bindings for {} structs written to {}", bindings.len(), self.out.display());
+        Ok(())
+    }
+}
+
+/// A struct definition found in the AST.
+#[derive(Debug)]
+pub(crate) struct StructDef {
+    pub(crate) name: String,
+    /// The contract the struct is declared in, if any.
+    pub(crate) contract: Option<String>,
+    /// The path of the source file the struct is declared in.
+    pub(crate) path: PathBuf,
+    /// The names and type nodes of the struct's members.
+    pub(crate) members: Vec<(String, Node)>,
+    /// The NatSpec documentation of the struct.
+    pub(crate) documentation: String,
+}
+
+impl StructDef {
+    /// Returns the name the struct is referenced by outside of its source file.
+    pub(crate) fn qualified_name(&self) -> String {
+        match &self.contract {
+            Some(contract) => format!("{contract}.{}", self.name),
+            None => self.name.clone(),
+        }
+    }
+
+    /// Returns the symbol to import to reference the struct.
+    pub(crate) fn import_name(&self) -> &str {
+        self.contract.as_deref().unwrap_or(&self.name)
+    }
+
+    /// Returns the qualified name of the struct as an identifier.
+    pub(crate) fn ident(&self) -> String {
+        self.qualified_name().replace('.', "_")
+    }
+}
+
+/// The definitions, by AST node ID, that struct members can reference.
+#[derive(Debug, Default)]
+pub(crate) struct Definitions {
+    pub(crate) structs: HashMap<usize, StructDef>,
+    /// Contracts, interfaces and libraries, which are encoded as addresses.
+    contracts: HashMap<usize, String>,
+    /// Enums, which are encoded as `uint8`.
+    enums: HashMap<usize, String>,
+    /// The underlying types of user-defined value types.
+    value_types: HashMap<usize, Node>,
+}
+
+impl Definitions {
+    /// Collects the definitions in the given node and its children, adding the IDs of the structs
+    /// to `bound` if given.
+    pub(crate) fn collect(
+        &mut self,
+        node: &Node,
+        contract: Option<&str>,
+        path: &Path,
+        mut bound: Option<&mut Vec<usize>>,
+    ) {
+        let Some(id) = node.id else { return };
+        let name = node.attribute::<String>("name").unwrap_or_default();
+        match node.node_type {
+            NodeType::ContractDefinition => {
+                for child in &node.nodes {
+                    self.collect(child, Some(&name), path, bound.as_deref_mut());
+                }
+                self.contracts.insert(id, name);
+            }
+            NodeType::StructDefinition => {
+                let members = node
+                    .attribute::<Vec<Node>>("members")
+                    .unwrap_or_default()
+                    .into_iter()
+                    .filter_map(|member| {
+                        Some((member.attribute("name")?, member.attribute("typeName")?))
+                    })
+                    .collect();
+                // Older compilers emit the documentation as a string, newer ones as a node.
+                let documentation = match node.attribute::<serde_json::Value>("documentation") {
+                    Some(serde_json::Value::String(text)) => text,
+                    Some(doc) => doc
+                        .get("text")
+                        .and_then(|text| text.as_str())
+                        .unwrap_or_default()
+                        .to_string(),
+                    None => String::new(),
+                };
+                let def = StructDef {
+                    name,
+                    contract: contract.map(str::to_string),
+                    path: path.to_path_buf(),
+                    members,
+                    documentation,
+                };
+                if let Some(bound) = bound {
+                    bound.push(id);
+                }
+                self.structs.insert(id, def);
+            }
+            NodeType::EnumDefinition => {
+                self.enums.insert(id, name);
+            }
+            NodeType::UserDefinedValueTypeDefinition => {
+                if let Some(underlying) = node.attribute("underlyingType") {
+                    self.value_types.insert(id, underlying);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Returns the EIP-712 `encodeType` string of the struct with the given ID.
+    ///
+    /// The referenced structs are appended to the struct's own type, sorted by name.
+    pub(crate) fn encode_type(&self, id: usize) -> Result<String> {
+        let mut deps = BTreeMap::new();
+        let main = self.encode_struct(id, &mut deps)?;
+        let main_name = &self.structs[&id].name;
+
+        let mut encoded = BTreeMap::<&str, String>::new();
+        let mut queue: Vec<usize> = deps.into_values().collect();
+        while let Some(dep) = queue.pop() {
+            let def = &self.structs[&dep];
+            if &def.name == main_name {
+                if dep == id {
+                    eyre::bail!("recursive structs can't be encoded");
+                }
+                eyre::bail!("references another struct named {}", def.name);
+            }
+            let mut nested = BTreeMap::new();
+            let ty = self.encode_struct(dep, &mut nested)?;
+            match encoded.entry(&def.name) {
+                Entry::Vacant(entry) => {
+                    entry.insert(ty);
+                    queue.extend(nested.into_values());
+                }
+                Entry::Occupied(entry) => {
+                    if *entry.get() != ty {
+                        eyre::bail!("references multiple structs named {}", def.name);
+                    }
+                }
+            }
+        }
+
+        Ok(encoded.into_values().fold(main, |acc, ty| acc + &ty))
+    }
+
+    /// Returns the EIP-712 type of a single struct, adding the IDs of the structs it references to
+    /// `deps`.
+    fn encode_struct(&self, id: usize, deps: &mut BTreeMap<String, usize>) -> Result<String> {
+        let def = &self.structs[&id];
+        let mut ty = format!("{}(", def.name);
+        for (i, (name, type_name)) in def.members.iter().enumerate() {
+            if i > 0 {
+                ty.push(',');
+            }
+            write!(ty, "{} {name}", self.member_type(type_name, deps)?)?;
+        }
+        ty.push(')');
+        Ok(ty)
+    }
+
+    /// Returns the ABI type of a struct member.
+    fn member_type(&self, type_name: &Node, deps: &mut BTreeMap<String, usize>) -> Result<String> {
+        match type_name.node_type {
+            NodeType::ElementaryTypeName => {
+                let name: String = type_name.attribute("name").ok_or_eyre("type has no name")?;
+                Ok(match name.as_str() {
+                    "uint" => "uint256".to_string(),
+                    "int" => "int256".to_string(),
+                    "byte" => "bytes1".to_string(),
+                    _ => name,
+                })
+            }
+            NodeType::UserDefinedTypeName => {
+                let id: usize = type_name
+                    .attribute("referencedDeclaration")
+                    .ok_or_eyre("type has no referenced declaration")?;
+                if let Some(def) = self.structs.get(&id) {
+                    deps.insert(def.name.clone(), id);
+                    Ok(def.name.clone())
+                } else if self.contracts.contains_key(&id) {
+                    Ok("address".to_string())
+                } else if self.enums.contains_key(&id) {
+                    Ok("uint8".to_string())
+                } else if let Some(underlying) = self.value_types.get(&id) {
+                    self.member_type(underlying, deps)
+                } else {
+                    eyre::bail!("unknown type declaration {id}")
+                }
+            }
+            NodeType::ArrayTypeName => {
+                let base: Node = type_name.attribute("baseType").ok_or_eyre("array has no type")?;
+                let base = self.member_type(&base, deps)?;
+                if type_name.attribute::<Node>("length").is_none() {
+                    return Ok(format!("{base}[]"))
+                }
+                // the length can be a constant expression, so read the evaluated type instead
+                let type_string: String = type_name
+                    .attribute::<serde_json::Value>("typeDescriptions")
+                    .and_then(|desc| desc.get("typeString")?.as_str().map(str::to_string))
+                    .ok_or_eyre("array has no type description")?;
+                let len = type_string
+                    .rsplit_once('[')
+                    .and_then(|(_, len)| len.split_once(']'))
+                    .map(|(len, _)| len)
+                    .ok_or_else(|| eyre::eyre!("invalid array type {type_string:?}"))?;
+                Ok(format!("{base}[{len}]"))
+            }
+            node_type => eyre::bail!("{node_type:?} members can't be encoded"),
+        }
+    }
+}
+
+/// A struct to generate bindings for.
+struct Binding<'a> {
+    def: &'a StructDef,
+    /// The EIP-712 `encodeType` string of the struct.
+    schema: String,
+}
+
+/// Renders the Solidity library with the JSON codecs of the given structs.
+fn render_library(bindings: &BTreeMap<String, Binding<'_>>) -> String {
+    let mut imports = BTreeMap::<&Path, Vec<&str>>::new();
+    for Binding { def, .. } in bindings.values() {
+        let symbols = imports.entry(&def.path).or_default();
+        if !symbols.contains(&def.import_name()) {
+            symbols.push(def.import_name());
+        }
+    }
+
+    let mut out = String::new();
+    out.push_str("// Automatically generated by `forge bind-json`, do not edit.\n\n");
+    out.push_str("pragma solidity >=0.6.2 <0.9.0;\npragma experimental ABIEncoderV2;\n\n");
+    for (path, symbols) in &imports {
+        let path = path.to_string_lossy().replace('\\', "/");
+        writeln!(out, "import {{{}}} from \"{path}\";", symbols.join(", ")).unwrap();
+    }
+    if !imports.is_empty() {
+        out.push('\n');
+    }
+
+    out.push_str(
+        r#"interface JsonBindingsVm {
+    function parseJsonTypeArray(string calldata json, string calldata key, string calldata typeDescription) external pure returns (bytes memory);
+    function parseJsonType(string calldata json, string calldata typeDescription) external pure returns (bytes memory);
+    function parseJsonType(string calldata json, string calldata key, string calldata typeDescription) external pure returns (bytes memory);
+    function serializeJsonType(string calldata typeDescription, bytes calldata value) external pure returns (string memory json);
+    function serializeJsonType(string calldata objectKey, string calldata valueKey, string calldata typeDescription, bytes calldata value) external returns (string memory json);
+}
+
+library JsonBindings {
+    JsonBindingsVm constant vm = JsonBindingsVm(address(uint160(uint256(keccak256("hevm cheat code")))));
+"#,
+    );
+
+    for (name, Binding { def, schema }) in bindings {
+        let ident = def.ident();
+        write!(
+            out,
+            r#"
+    string constant schema_{ident} = "{schema}";
+
+    function serialize({name} memory value) internal pure returns (string memory) {{
+        return vm.serializeJsonType(schema_{ident}, abi.encode(value));
+    }}
+
+    function serialize({name} memory value, string memory objectKey, string memory valueKey) internal returns (string memory) {{
+        return vm.serializeJsonType(objectKey, valueKey, schema_{ident}, abi.encode(value));
+    }}
+
+    function deserialize{ident}(string memory json) internal pure returns ({name} memory) {{
+        return abi.decode(vm.parseJsonType(json, schema_{ident}), ({name}));
+    }}
+
+    function deserialize{ident}(string memory json, string memory path) internal pure returns ({name} memory) {{
+        return abi.decode(vm.parseJsonType(json, path, schema_{ident}), ({name}));
+    }}
+
+    function deserialize{ident}Array(string memory json, string memory path) internal pure returns ({name}[] memory) {{
+        return abi.decode(vm.parseJsonTypeArray(json, path, schema_{ident}), ({name}[]));
+    }}
+"#
+        )
+        .unwrap();
+    }
+    out.push_str("}\n");
+    out
+}
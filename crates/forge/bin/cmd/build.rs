@@ -1,7 +1,10 @@
-use super::{install, watch::WatchArgs};
+use super::{install, selectors, watch::WatchArgs};
 use clap::Parser;
 use eyre::Result;
-use foundry_cli::{opts::CoreBuildArgs, utils::LoadConfig};
+use foundry_cli::{
+    opts::CoreBuildArgs,
+    utils::{self, LoadConfig},
+};
 use foundry_common::compile::ProjectCompiler;
 use foundry_compilers::{
     compilers::{multi::MultiCompilerLanguage, Language},
@@ -20,6 +23,7 @@ use foundry_config::{
 use serde::Serialize;
 use std::path::PathBuf;
 use watchexec::config::{InitConfig, RuntimeConfig};
+use yansi::Paint;
 
 foundry_config::merge_impl_figment_convert!(BuildArgs, args);
 
@@ -101,11 +105,20 @@ impl BuildArgs {
             .files(files)
             .print_names(self.names)
             .print_sizes(self.sizes)
+            .deny_warnings(config.deny_warnings_policy())
             .quiet(self.format_json)
             .bail(!self.format_json);
 
         let output = compiler.compile(&project)?;
 
+        if config.sync_selectors {
+            // The build succeeded regardless of the registry being reachable.
+            if let Err(err) = utils::block_on(selectors::sync_selectors(&config, &project, &output))
+            {
+                eprintln!("{}", format!("Warning: failed to sync selectors: {err}").yellow());
+            }
+        }
+
         if self.format_json {
             println!("{}", serde_json::to_string_pretty(&output.output())?);
         }
@@ -182,6 +195,15 @@ mod tests {
         assert_eq!(args.args.skip, Some(vec![SkipBuildFilter::Tests, SkipBuildFilter::Scripts]));
     }
 
+    #[test]
+    fn can_parse_jobs() {
+        let args: BuildArgs = BuildArgs::parse_from(["foundry-cli", "--jobs", "3"]);
+        assert_eq!(args.args.jobs, Some(3));
+        let config = Config::from(&args.args);
+        assert_eq!(config.compiler_jobs, Some(3));
+        assert!(config.compiler_jobs() <= 3);
+    }
+
     #[test]
     fn check_conflicts() {
         let args: std::result::Result<BuildArgs, clap::Error> =
@@ -0,0 +1,379 @@
+use super::analyze::{CallGraph, GraphFormat};
+use clap::Parser;
+use comfy_table::{presets::ASCII_MARKDOWN, Table};
+use eyre::Result;
+use foundry_cli::{opts::CoreBuildArgs, utils::LoadConfig};
+use foundry_common::{compile::ProjectCompiler, fs};
+use foundry_compilers::{
+    artifacts::ast::{Node, NodeType},
+    Project, ProjectCompileOutput,
+};
+use foundry_config::impl_figment_convert;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::path::Path;
+
+impl_figment_convert!(CallGraphArgs, build_args);
+
+/// CLI arguments for `forge call-graph`.
+#[derive(Clone, Debug, Parser)]
+pub struct CallGraphArgs {
+    /// Print the call sites as JSON.
+    #[arg(long, short, conflicts_with = "graph_format")]
+    json: bool,
+
+    /// Print the calls between contracts as a graph of the given format instead of the table of
+    /// call sites.
+    #[arg(long, value_enum, value_name = "FORMAT")]
+    graph_format: Option<GraphFormat>,
+
+    #[command(flatten)]
+    build_args: CoreBuildArgs,
+}
+
+impl CallGraphArgs {
+    pub fn run(self) -> Result<()> {
+        let mut config = self.try_load_config_emit_warnings()?;
+        // The call sites are read from the Solc AST.
+        config.ast = true;
+        let project = config.project()?;
+        let output = ProjectCompiler::new().quiet(true).compile(&project)?;
+        let sites = call_sites(&project, &output)?;
+
+        if self.json {
+            println!("{}", serde_json::to_string_pretty(&sites)?);
+        } else if let Some(format) = self.graph_format {
+            let mut graph = CallGraph::default();
+            for site in &sites {
+                let calls =
+                    graph.calls.entry((site.contract.clone(), site.target.clone())).or_default();
+                *calls.entry(site.callee.clone()).or_default() += 1;
+            }
+            match format {
+                GraphFormat::Dot => print!("{}", graph.to_dot()),
+                GraphFormat::Mermaid => print!("{}", graph.to_mermaid()),
+            }
+        } else if sites.is_empty() {
+            println!("No external calls found");
+        } else {
+            println!("{}", call_sites_table(&sites));
+        }
+        Ok(())
+    }
+}
+
+/// Returns the external calls made by the contracts in the sources of the project, sorted by
+/// caller.
+///
+/// The project must be compiled with the AST.
+pub(crate) fn call_sites(
+    project: &Project,
+    output: &ProjectCompileOutput,
+) -> Result<Vec<CallSite>> {
+    let root = project.root();
+    let mut sites = Vec::new();
+    for (path, source_file, _) in output.output().sources.sources_with_version() {
+        let Some(ast) = &source_file.ast else { continue };
+        if !root.join(path).starts_with(&project.paths.sources) {
+            continue
+        }
+        let source = fs::read_to_string(root.join(path))?;
+        let path = path.strip_prefix(root).unwrap_or(path);
+        for node in &ast.nodes {
+            collect_call_sites(node, path, &source, &mut sites);
+        }
+    }
+    sites.sort_by(|a, b| (&a.contract, &a.function).cmp(&(&b.contract, &b.function)));
+    Ok(sites)
+}
+
+/// The kind of an external call.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum CallKind {
+    /// A call to a function of a contract or interface.
+    External,
+    Call,
+    Delegatecall,
+    Staticcall,
+    Send,
+    Transfer,
+}
+
+impl CallKind {
+    /// Returns the kind of a low-level call on an address.
+    fn low_level(member: &str) -> Option<Self> {
+        Some(match member {
+            "call" => Self::Call,
+            "delegatecall" => Self::Delegatecall,
+            "staticcall" => Self::Staticcall,
+            "send" => Self::Send,
+            "transfer" => Self::Transfer,
+            _ => return None,
+        })
+    }
+
+    /// Returns whether the call is a low-level call or transfer on an address.
+    pub(crate) fn is_low_level(self) -> bool {
+        self != Self::External
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::External => "external",
+            Self::Call => "call",
+            Self::Delegatecall => "delegatecall",
+            Self::Staticcall => "staticcall",
+            Self::Send => "send",
+            Self::Transfer => "transfer",
+        }
+    }
+}
+
+/// An external call made by a function of the project.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub(crate) struct CallSite {
+    /// The `path:line` of the call.
+    pub(crate) location: String,
+    /// The contract the call is made from.
+    pub(crate) contract: String,
+    /// The function or modifier the call is made from.
+    pub(crate) function: String,
+    /// The type of the called account: a contract or interface name, or `address`.
+    pub(crate) target: String,
+    /// The called function, or the kind of a low-level call.
+    pub(crate) callee: String,
+    pub(crate) kind: CallKind,
+    /// Whether the call sends ether.
+    pub(crate) payable: bool,
+    /// Whether the calling function has a reentrancy guard modifier.
+    pub(crate) guarded: bool,
+}
+
+/// Collects the external calls of the functions and modifiers of the contracts in the node.
+fn collect_call_sites(node: &Node, path: &Path, source: &str, sites: &mut Vec<CallSite>) {
+    if node.node_type != NodeType::ContractDefinition {
+        return
+    }
+    let contract = node.attribute::<String>("name").unwrap_or_default();
+    for child in &node.nodes {
+        let function = match child.node_type {
+            NodeType::FunctionDefinition | NodeType::ModifierDefinition => {
+                match child.attribute::<String>("name").filter(|name| !name.is_empty()) {
+                    Some(name) => name,
+                    // Constructors, fallback and receive functions are unnamed.
+                    None => child.attribute::<String>("kind").unwrap_or_default(),
+                }
+            }
+            _ => continue,
+        };
+        let Some(body) = &child.body else { continue };
+        let guarded = child
+            .attribute::<Vec<Value>>("modifiers")
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|modifier| modifier["modifierName"]["name"].as_str())
+            .any(is_reentrancy_guard);
+
+        let mut calls = Vec::new();
+        collect_calls(&serde_json::to_value(body).unwrap_or_default(), &mut calls);
+        sites.extend(calls.into_iter().map(|call| CallSite {
+            location: format!("{}:{}", path.display(), line(source, call.offset)),
+            contract: contract.clone(),
+            function: function.clone(),
+            target: call.target,
+            callee: call.callee,
+            kind: call.kind,
+            payable: call.payable,
+            guarded,
+        }));
+    }
+}
+
+/// An external call found in a function body.
+#[derive(Debug, PartialEq, Eq)]
+struct Call {
+    /// The byte offset of the call in its source.
+    offset: usize,
+    target: String,
+    callee: String,
+    kind: CallKind,
+    payable: bool,
+}
+
+/// Collects the external calls of all the `FunctionCall` nodes in the JSON AST.
+fn collect_calls(value: &Value, calls: &mut Vec<Call>) {
+    match value {
+        Value::Object(node) => {
+            if node.get("nodeType").and_then(Value::as_str) == Some("FunctionCall") {
+                calls.extend(external_call(node));
+            }
+            for child in node.values() {
+                collect_calls(child, calls);
+            }
+        }
+        Value::Array(children) => {
+            for child in children {
+                collect_calls(child, calls);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Returns the external call made by a `FunctionCall` node, if any.
+fn external_call(node: &Map<String, Value>) -> Option<Call> {
+    let mut expression = node.get("expression")?;
+    // `target.f{value: ...}(...)`
+    let mut payable = false;
+    if expression["nodeType"] == "FunctionCallOptions" {
+        payable = expression["names"]
+            .as_array()
+            .is_some_and(|names| names.iter().any(|name| name == "value"));
+        expression = &expression["expression"];
+    }
+    if expression["nodeType"] != "MemberAccess" {
+        return None
+    }
+
+    let member = expression["memberName"].as_str()?;
+    let base = expression["expression"]["typeDescriptions"]["typeString"].as_str()?;
+    let (target, callee, kind) = if let Some(contract) = base.strip_prefix("contract ") {
+        // Skip internal library calls and calls to non-function members.
+        let function = expression["typeDescriptions"]["typeString"].as_str()?;
+        if !function.starts_with("function ") || !function.contains(" external") {
+            return None
+        }
+        (contract.to_string(), member.to_string(), CallKind::External)
+    } else if base == "address" || base == "address payable" {
+        let kind = CallKind::low_level(member)?;
+        payable |= matches!(kind, CallKind::Send | CallKind::Transfer);
+        (String::from("address"), member.to_string(), kind)
+    } else {
+        return None
+    };
+
+    let offset = node.get("src")?.as_str()?.split(':').next()?.parse().ok()?;
+    Some(Call { offset, target, callee, kind, payable })
+}
+
+/// Returns whether a modifier is a reentrancy guard, e.g. OpenZeppelin's `nonReentrant`.
+fn is_reentrancy_guard(modifier: &str) -> bool {
+    let modifier = modifier.to_ascii_lowercase();
+    modifier.contains("nonreentrant") || modifier.contains("reentrancyguard")
+}
+
+/// Returns the 1-based line of the byte offset in the source.
+fn line(source: &str, offset: usize) -> usize {
+    source.as_bytes()[..offset.min(source.len())].iter().filter(|b| **b == b'\n').count() + 1
+}
+
+fn call_sites_table(sites: &[CallSite]) -> Table {
+    let mut table = Table::new();
+    table.load_preset(ASCII_MARKDOWN);
+    table.set_header(["Location", "Caller", "Target", "Function", "Kind", "Payable", "Guarded"]);
+    for site in sites {
+        table.add_row([
+            site.location.clone(),
+            format!("{}.{}", site.contract, site.function),
+            site.target.clone(),
+            site.callee.clone(),
+            site.kind.as_str().to_string(),
+            if site.payable { "yes" } else { "" }.to_string(),
+            if site.guarded { "yes" } else { "" }.to_string(),
+        ]);
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn member_call(base: &str, member: &str, function: &str) -> Value {
+        json!({
+            "nodeType": "FunctionCall",
+            "src": "42:10:0",
+            "expression": {
+                "nodeType": "MemberAccess",
+                "memberName": member,
+                "typeDescriptions": { "typeString": function },
+                "expression": { "typeDescriptions": { "typeString": base } }
+            }
+        })
+    }
+
+    fn calls(value: Value) -> Vec<Call> {
+        let mut calls = Vec::new();
+        collect_calls(&value, &mut calls);
+        calls
+    }
+
+    #[test]
+    fn collects_external_calls() {
+        let body = json!({
+            "nodeType": "Block",
+            "statements": [
+                member_call(
+                    "contract IERC20",
+                    "transfer",
+                    "function (address,uint256) external returns (bool)"
+                ),
+                // A call to an internal library function.
+                member_call("contract Math", "max", "function (uint256) pure returns (uint256)"),
+                {
+                    "nodeType": "FunctionCall",
+                    "src": "60:10:0",
+                    "expression": {
+                        "nodeType": "FunctionCallOptions",
+                        "names": ["value"],
+                        "expression": member_call("address payable", "call", "")["expression"]
+                    }
+                }
+            ]
+        });
+        assert_eq!(
+            calls(body),
+            vec![
+                Call {
+                    offset: 42,
+                    target: "IERC20".to_string(),
+                    callee: "transfer".to_string(),
+                    kind: CallKind::External,
+                    payable: false,
+                },
+                Call {
+                    offset: 60,
+                    target: "address".to_string(),
+                    callee: "call".to_string(),
+                    kind: CallKind::Call,
+                    payable: true,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn sends_ether_with_transfer() {
+        let call = calls(member_call("address payable", "transfer", "")).remove(0);
+        assert_eq!(call.kind, CallKind::Transfer);
+        assert!(call.payable);
+        assert!(calls(member_call("uint256", "transfer", "")).is_empty());
+    }
+
+    #[test]
+    fn detects_reentrancy_guards() {
+        assert!(is_reentrancy_guard("nonReentrant"));
+        assert!(is_reentrancy_guard("nonReentrantView"));
+        assert!(!is_reentrancy_guard("onlyOwner"));
+    }
+
+    #[test]
+    fn finds_lines() {
+        let source = "a\nb\nc";
+        assert_eq!(line(source, 0), 1);
+        assert_eq!(line(source, 2), 2);
+        assert_eq!(line(source, 100), 3);
+    }
+}
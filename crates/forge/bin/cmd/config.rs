@@ -3,7 +3,7 @@ use clap::Parser;
 use eyre::Result;
 use foundry_cli::utils::LoadConfig;
 use foundry_common::{evm::EvmArgs, term::cli_warn};
-use foundry_config::fix::fix_tomls;
+use foundry_config::{find_project_root_path, fix::fix_tomls, validate, Config};
 
 foundry_config::impl_figment_convert!(ConfigArgs, opts, evm_opts);
 
@@ -22,6 +22,16 @@ pub struct ConfigArgs {
     #[arg(long)]
     fix: bool,
 
+    /// Print a JSON schema of `foundry.toml`.
+    #[arg(long, conflicts_with_all = ["basic", "fix", "validate"])]
+    schema: bool,
+
+    /// Check `foundry.toml` for unknown keys, values of the wrong type and deprecated keys.
+    ///
+    /// Exits with an error if any issue is found.
+    #[arg(long, conflicts_with_all = ["basic", "fix"])]
+    validate: bool,
+
     // support nested build arguments
     #[command(flatten)]
     opts: BuildArgs,
@@ -39,6 +49,36 @@ impl ConfigArgs {
             return Ok(())
         }
 
+        if self.schema {
+            println!("{}", serde_json::to_string_pretty(&validate::json_schema())?);
+            return Ok(())
+        }
+
+        if self.validate {
+            let root = match &self.opts.args.project_paths.root {
+                Some(root) => root.clone(),
+                None => find_project_root_path(None)?,
+            };
+            let path = root.join(Config::FILE_NAME);
+            let issues = validate::validate_file(&path)
+                .map_err(|err| eyre::eyre!("failed to read {}: {err}", path.display()))?;
+            if self.json {
+                let issues = issues.iter().map(ToString::to_string).collect::<Vec<_>>();
+                println!("{}", serde_json::to_string_pretty(&issues)?);
+            } else {
+                for issue in &issues {
+                    println!("{issue}");
+                }
+            }
+            if !issues.is_empty() {
+                eyre::bail!("found {} issues in {}", issues.len(), path.display());
+            }
+            if !self.json {
+                println!("{} is valid", path.display());
+            }
+            return Ok(())
+        }
+
         let config = self
             .try_load_config_unsanitized_emit_warnings()?
             // we explicitly normalize the version, so mimic the behavior when invoking solc
@@ -0,0 +1,221 @@
+use crate::cmd::test::FilterArgs;
+use alloy_primitives::{hex, Address};
+use clap::{Parser, Subcommand, ValueHint};
+use comfy_table::{presets::ASCII_MARKDOWN, Table};
+use eyre::Result;
+use forge::{result::TestStatus, MultiContractRunnerBuilder, TestOptions};
+use foundry_cli::{opts::CoreBuildArgs, utils::LoadConfig};
+use foundry_common::{compile::ProjectCompiler, evm::EvmArgs, fs};
+use foundry_evm::{
+    backend::Backend,
+    contract_tests::{record_calls, verify_call, CallMismatch, ContractTestFile},
+    executors::ExecutorBuilder,
+};
+use serde::Serialize;
+use std::{fmt, path::PathBuf, sync::Arc};
+use yansi::Paint;
+
+/// The default contract test file.
+const DEFAULT_FILE: &str = "contract-tests.json";
+
+/// CLI arguments for `forge contract-test`.
+#[derive(Clone, Debug, Parser)]
+pub struct ContractTestArgs {
+    #[command(subcommand)]
+    pub sub: ContractTestSubcommands,
+}
+
+#[derive(Clone, Debug, Subcommand)]
+pub enum ContractTestSubcommands {
+    /// Run the tests and record the external calls the contracts under test make to their
+    /// dependencies, along with the results they got back.
+    Record(RecordArgs),
+
+    /// Replay the recorded calls against the real dependencies on a fork, and report the calls
+    /// whose results differ.
+    Verify(VerifyArgs),
+}
+
+// Loads project's figment and merges the build cli arguments into it
+foundry_config::merge_impl_figment_convert!(RecordArgs, build, evm_opts);
+
+/// CLI arguments for `forge contract-test record`.
+#[derive(Clone, Debug, Parser)]
+pub struct RecordArgs {
+    /// The file to write the recorded calls to.
+    #[arg(long, default_value = DEFAULT_FILE, value_hint = ValueHint::FilePath, value_name = "FILE")]
+    pub file: PathBuf,
+
+    #[command(flatten)]
+    filter: FilterArgs,
+
+    #[command(flatten)]
+    evm_opts: EvmArgs,
+
+    #[command(flatten)]
+    build: CoreBuildArgs,
+}
+
+impl RecordArgs {
+    pub async fn run(self) -> Result<()> {
+        let (config, mut evm_opts) = self.load_config_and_evm_opts_emit_warnings()?;
+        // The calls are recorded from the traces of the tests.
+        evm_opts.verbosity = evm_opts.verbosity.max(3);
+
+        let project = config.project()?;
+        let output = ProjectCompiler::new().quiet(self.build.silent).compile(&project)?;
+
+        let env = evm_opts.evm_env().await?;
+        let config = Arc::new(config);
+        let mut runner = MultiContractRunnerBuilder::new(config.clone())
+            .initial_balance(evm_opts.initial_balance)
+            .evm_spec(config.evm_spec_id())
+            .sender(evm_opts.sender)
+            .with_fork(evm_opts.get_fork(&config, env.clone()))
+            .with_test_options(TestOptions {
+                fuzz: config.fuzz.clone(),
+                invariant: config.invariant.clone(),
+                ..Default::default()
+            })
+            .enable_isolation(evm_opts.isolate)
+            .build(project.root(), &output, env, evm_opts)?;
+
+        let filter = self.filter.clone().merge_with_config(&config);
+        let mut file = ContractTestFile::default();
+        let mut tests = 0;
+        for (suite, result) in runner.test_collect(&filter) {
+            let contract = suite.rsplit(':').next().unwrap_or(&suite);
+            for (test, result) in result.test_results {
+                // The calls of a failing test are not assumptions the contracts can rely on.
+                if result.status == TestStatus::Failure {
+                    eprintln!(
+                        "{} skipping the calls of failing test {contract}::{test}",
+                        "Warning:".yellow().bold()
+                    );
+                    continue
+                }
+                tests += 1;
+                file.extend(record_calls(&format!("{contract}::{test}"), &result.traces));
+            }
+        }
+
+        fs::write(&self.file, serde_json::to_string_pretty(&file)?)?;
+        println!(
+            "Recorded {} external calls of {tests} tests to {}",
+            file.calls.len(),
+            self.file.display()
+        );
+        Ok(())
+    }
+}
+
+// Loads project's figment and merges the build cli arguments into it
+foundry_config::merge_impl_figment_convert!(VerifyArgs, build, evm_opts);
+
+/// CLI arguments for `forge contract-test verify`.
+#[derive(Clone, Debug, Parser)]
+pub struct VerifyArgs {
+    /// The file to read the recorded calls from.
+    #[arg(long, default_value = DEFAULT_FILE, value_hint = ValueHint::FilePath, value_name = "FILE")]
+    pub file: PathBuf,
+
+    /// Print the result as JSON.
+    #[arg(long, help_heading = "Display options")]
+    pub json: bool,
+
+    #[command(flatten)]
+    evm_opts: EvmArgs,
+
+    #[command(flatten)]
+    build: CoreBuildArgs,
+}
+
+impl VerifyArgs {
+    pub async fn run(self) -> Result<()> {
+        let (config, evm_opts) = self.load_config_and_evm_opts()?;
+        if evm_opts.fork_url.is_none() {
+            eyre::bail!("verifying contract tests requires a fork, set one with --fork-url");
+        }
+        let file: ContractTestFile = fs::read_json_file(&self.file)?;
+
+        let env = evm_opts.evm_env().await?;
+        let backend = Backend::spawn(evm_opts.get_fork(&config, env.clone()));
+        let executor = ExecutorBuilder::new()
+            .gas_limit(evm_opts.gas_limit())
+            .spec(config.evm_spec_id())
+            .build(env, backend);
+
+        let mut report = VerifyReport { verified: 0, mismatches: Vec::new() };
+        for call in file.calls {
+            match verify_call(&executor, &call)? {
+                Some(mismatch) => report.mismatches.push(Mismatch {
+                    test: call.test,
+                    target: call.target,
+                    selector: call.calldata.get(..4).map(hex::encode_prefixed).unwrap_or_default(),
+                    mismatch,
+                }),
+                None => report.verified += 1,
+            }
+        }
+
+        if self.json {
+            println!("{}", serde_json::to_string_pretty(&report)?);
+        } else {
+            println!("{report}");
+        }
+
+        if !report.mismatches.is_empty() {
+            std::process::exit(1);
+        }
+        Ok(())
+    }
+}
+
+/// The result of replaying the recorded calls.
+#[derive(Debug, Serialize)]
+struct VerifyReport {
+    verified: usize,
+    mismatches: Vec<Mismatch>,
+}
+
+#[derive(Debug, Serialize)]
+struct Mismatch {
+    test: String,
+    target: Address,
+    selector: String,
+    #[serde(flatten)]
+    mismatch: CallMismatch,
+}
+
+impl fmt::Display for VerifyReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.mismatches.is_empty() {
+            return write!(
+                f,
+                "{} all {} recorded calls match the dependencies",
+                "Contract tests passed:".green().bold(),
+                self.verified
+            )
+        }
+
+        let mut table = Table::new();
+        table.load_preset(ASCII_MARKDOWN);
+        table.set_header(["Test", "Target", "Selector", "Mismatch"]);
+        for Mismatch { test, target, selector, mismatch } in &self.mismatches {
+            table.add_row([
+                test.clone(),
+                target.to_string(),
+                selector.clone(),
+                mismatch.to_string(),
+            ]);
+        }
+        writeln!(f, "{table}")?;
+        write!(
+            f,
+            "{} {} of {} recorded calls don't match the dependencies",
+            "Contract tests failed:".red().bold(),
+            self.mismatches.len(),
+            self.mismatches.len() + self.verified
+        )
+    }
+}
@@ -6,18 +6,20 @@ use forge::{
     coverage::{
         analysis::{SourceAnalysis, SourceAnalyzer, SourceFile, SourceFiles},
         anchors::find_anchors,
-        BytecodeReporter, ContractId, CoverageReport, CoverageReporter, DebugReporter, ItemAnchor,
+        AssertionCoverage, BranchMutation, BytecodeReporter, ContractId, CoverageItemKind,
+        CoverageReport, CoverageReporter, DebugReporter, HtmlReporter, ItemAnchor, LcovData,
         LcovReporter, SummaryReporter,
     },
     opts::EvmOpts,
+    result::TestOutcome,
     utils::IcPcMap,
-    MultiContractRunnerBuilder, TestOptions,
+    MultiContractRunner, MultiContractRunnerBuilder, TestFilter, TestOptions,
 };
 use foundry_cli::{
     p_println,
     utils::{LoadConfig, STATIC_FUZZ_SEED},
 };
-use foundry_common::{compile::ProjectCompiler, fs};
+use foundry_common::{compile::ProjectCompiler, fs, ContractsByArtifact};
 use foundry_compilers::{
     artifacts::{sourcemap::SourceMap, CompactBytecode, CompactDeployedBytecode},
     Artifact, ArtifactId, Project, ProjectCompileOutput,
@@ -26,7 +28,11 @@ use foundry_config::{Config, SolcReq};
 use rayon::prelude::*;
 use rustc_hash::FxHashMap;
 use semver::Version;
-use std::{collections::HashMap, path::PathBuf, sync::Arc};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 use yansi::Paint;
 
 // Loads project's figment and merges the build cli arguments into it
@@ -63,12 +69,34 @@ pub struct CoverageArgs {
     #[arg(long)]
     include_libs: bool,
 
+    /// Merge the given LCOV reports instead of running the tests.
+    ///
+    /// Hit counts of the same functions, lines and branches are summed, so that the reports of
+    /// sharded coverage runs can be combined into a single report. The merged data is reported
+    /// like a coverage run, e.g. `--report lcov --report-file merged.lcov` writes the merged LCOV
+    /// report to `merged.lcov`.
+    #[arg(long, num_args = 1.., value_hint = ValueHint::FilePath, value_name = "FILES")]
+    merge: Vec<PathBuf>,
+
+    /// Report the lines that are executed by the tests but never asserted on.
+    ///
+    /// Each branch executed by the tests is mutated in turn by inverting its condition, as if its
+    /// comparison operator was flipped, and the tests executing it are run again. If they all
+    /// still pass, the line of the branch is not asserted on. This is a cheap proxy for mutation
+    /// testing, which runs the tests once per executed branch.
+    #[arg(long, conflicts_with = "merge")]
+    assertions: bool,
+
     #[command(flatten)]
-    test: TestArgs,
+    pub(crate) test: TestArgs,
 }
 
 impl CoverageArgs {
     pub async fn run(self) -> Result<()> {
+        if !self.merge.is_empty() {
+            return self.merge()
+        }
+
         let (mut config, evm_opts) = self.load_config_and_evm_opts_emit_warnings()?;
 
         // install missing dependencies
@@ -93,6 +121,35 @@ impl CoverageArgs {
         self.collect(project, &output, report, Arc::new(config), evm_opts).await
     }
 
+    /// Merges the LCOV reports passed with `--merge`.
+    fn merge(self) -> Result<()> {
+        let mut data = LcovData::default();
+        for path in &self.merge {
+            let contents = fs::read_to_string(path)?;
+            let report = LcovData::parse(&contents)
+                .wrap_err_with(|| format!("failed to parse LCOV report {}", path.display()))?;
+            data.merge(report);
+        }
+
+        for report_kind in self.report {
+            match report_kind {
+                CoverageReportKind::Summary => {
+                    SummaryReporter::default().report_summaries(data.summary_by_file())
+                }
+                CoverageReportKind::Lcov => {
+                    let report_file =
+                        self.report_file.clone().unwrap_or_else(|| "lcov.info".into());
+                    data.write(&mut fs::create_file(&report_file)?)?;
+                    println!("Wrote LCOV report.");
+                }
+                kind => {
+                    eyre::bail!("the {kind:?} report is not supported when merging LCOV reports")
+                }
+            }
+        }
+        Ok(())
+    }
+
     /// Builds the project.
     fn build(&self, config: &Config) -> Result<(Project, ProjectCompileOutput)> {
         // Set up the project
@@ -232,7 +289,7 @@ impl CoverageArgs {
 
         // Build the contract runner
         let env = evm_opts.evm_env().await?;
-        let runner = MultiContractRunnerBuilder::new(config.clone())
+        let builder = MultiContractRunnerBuilder::new(config.clone())
             .initial_balance(evm_opts.initial_balance)
             .evm_spec(config.evm_spec_id())
             .sender(evm_opts.sender)
@@ -242,8 +299,8 @@ impl CoverageArgs {
                 invariant: config.invariant.clone(),
                 ..Default::default()
             })
-            .set_coverage(true)
-            .build(&root, output, env, evm_opts)?;
+            .set_coverage(true);
+        let runner = builder.clone().build(&root, output, env.clone(), evm_opts.clone())?;
 
         let known_contracts = runner.known_contracts.clone();
 
@@ -254,18 +311,22 @@ impl CoverageArgs {
 
         outcome.ensure_ok()?;
 
+        let branch_sites = if self.assertions {
+            find_branch_sites(&outcome, &known_contracts, &report)
+        } else {
+            Vec::new()
+        };
+
         // Add hit data to the coverage report
         let data = outcome.results.iter().flat_map(|(_, suite)| {
             let mut hits = Vec::new();
             for result in suite.test_results.values() {
                 let Some(hit_maps) = result.coverage.as_ref() else { continue };
                 for map in hit_maps.0.values() {
-                    if let Some((id, _)) = known_contracts.find_by_deployed_code(&map.bytecode) {
-                        hits.push((id, map, true));
-                    } else if let Some((id, _)) =
-                        known_contracts.find_by_creation_code(&map.bytecode)
+                    if let Some((id, is_deployed_code)) =
+                        find_artifact(&known_contracts, &map.bytecode)
                     {
-                        hits.push((id, map, false));
+                        hits.push((id, map, is_deployed_code));
                     }
                 }
             }
@@ -293,13 +354,11 @@ impl CoverageArgs {
             match report_kind {
                 CoverageReportKind::Summary => SummaryReporter::default().report(&report),
                 CoverageReportKind::Lcov => {
-                    if let Some(report_file) = self.report_file {
-                        return LcovReporter::new(&mut fs::create_file(root.join(report_file))?)
-                            .report(&report)
-                    } else {
-                        return LcovReporter::new(&mut fs::create_file(root.join("lcov.info"))?)
-                            .report(&report)
-                    }
+                    let report_file = self.report_file.as_deref().unwrap_or("lcov.info".as_ref());
+                    LcovReporter::new(&mut fs::create_file(root.join(report_file))?).report(&report)
+                }
+                CoverageReportKind::Html => {
+                    HtmlReporter::new(root.clone(), root.join("coverage")).report(&report)
                 }
                 CoverageReportKind::Bytecode => {
                     let destdir = root.join("bytecode-coverage");
@@ -310,15 +369,153 @@ impl CoverageArgs {
                 CoverageReportKind::Debug => DebugReporter.report(&report),
             }?;
         }
+
+        if self.assertions {
+            let count = branch_sites.len();
+            p_println!(!self.test.build_args().silent => "Mutating {count} executed branches...");
+            let runner = builder.set_coverage(false).build(&root, output, env, evm_opts)?;
+            let coverage =
+                tokio::task::spawn_blocking(move || check_assertions(runner, &branch_sites))
+                    .await?;
+            coverage.report();
+        }
         Ok(())
     }
 }
 
-// TODO: HTML
+/// A branch executed by the tests, see `--assertions`.
+struct BranchSite {
+    /// The mutation inverting the condition of the branch.
+    mutation: BranchMutation,
+    /// The source file and line of the branch.
+    path: PathBuf,
+    line: usize,
+    /// The names of the contracts of the tests executing the branch.
+    contracts: BTreeSet<String>,
+    /// The signatures of the tests executing the branch.
+    tests: BTreeSet<String>,
+}
+
+/// Matches the tests executing a [`BranchSite`].
+///
+/// Contracts and tests are matched separately, so that tests of other contracts with the same
+/// signature are matched too. They do not execute the branch, so they pass as they did before.
+struct BranchSiteFilter<'a>(&'a BranchSite);
+
+impl TestFilter for BranchSiteFilter<'_> {
+    fn matches_test(&self, test_name: &str) -> bool {
+        self.0.tests.contains(test_name)
+    }
+
+    fn matches_contract(&self, contract_name: &str) -> bool {
+        self.0.contracts.contains(contract_name)
+    }
+
+    fn matches_path(&self, _path: &Path) -> bool {
+        true
+    }
+}
+
+/// Returns the branches executed by the passing tests of the outcome.
+///
+/// A branch is executed if the `JUMPI` of its condition is hit, which is the instruction before
+/// the anchor of its first path.
+fn find_branch_sites(
+    outcome: &TestOutcome,
+    known_contracts: &ContractsByArtifact,
+    report: &CoverageReport,
+) -> Vec<BranchSite> {
+    let mut sites = BTreeMap::<BranchMutation, BranchSite>::new();
+    for (suite_name, suite) in &outcome.results {
+        let contract_name =
+            suite_name.rsplit_once(':').map_or(suite_name.as_str(), |(_, name)| name);
+        for (test_name, result) in suite.successes() {
+            let Some(hit_maps) = result.coverage.as_ref() else { continue };
+            for (&code_hash, map) in hit_maps.iter() {
+                let Some((artifact_id, is_deployed_code)) =
+                    find_artifact(known_contracts, &map.bytecode)
+                else {
+                    continue
+                };
+                let version = &artifact_id.version;
+                let Some(source_id) =
+                    report.get_source_id(version.clone(), artifact_id.source.clone())
+                else {
+                    continue
+                };
+                let contract_id = ContractId {
+                    version: version.clone(),
+                    source_id,
+                    contract_name: artifact_id.name.as_str().into(),
+                };
+                let (Some(anchors), Some(items)) =
+                    (report.anchors.get(&contract_id), report.items.get(version))
+                else {
+                    continue
+                };
+                let anchors = if is_deployed_code { &anchors.1 } else { &anchors.0 };
+                for anchor in anchors {
+                    let item = &items[anchor.item_id];
+                    if !matches!(item.kind, CoverageItemKind::Branch { path_id: 0, .. }) {
+                        continue
+                    }
+                    let Some(pc) = anchor.instruction.checked_sub(1) else { continue };
+                    if !map.hits.contains_key(&pc) {
+                        continue
+                    }
+                    let Some(path) =
+                        report.source_paths.get(&(version.clone(), item.loc.source_id))
+                    else {
+                        continue
+                    };
+                    let mutation = BranchMutation { code_hash, pc };
+                    let site = sites.entry(mutation).or_insert_with(|| BranchSite {
+                        mutation,
+                        path: path.clone(),
+                        line: item.loc.line,
+                        contracts: BTreeSet::new(),
+                        tests: BTreeSet::new(),
+                    });
+                    site.contracts.insert(contract_name.to_string());
+                    site.tests.insert(test_name.clone());
+                }
+            }
+        }
+    }
+    sites.into_values().collect()
+}
+
+/// Runs the tests executing each branch with its condition inverted.
+fn check_assertions(mut runner: MultiContractRunner, sites: &[BranchSite]) -> AssertionCoverage {
+    let mut coverage = AssertionCoverage::default();
+    for site in sites {
+        runner.branch_mutation = Some(site.mutation);
+        let results = runner.test_collect(&BranchSiteFilter(site));
+        let asserted = results.values().any(|suite| suite.failed() > 0);
+        coverage.add(site.path.clone(), site.line, asserted);
+    }
+    coverage
+}
+
+/// Returns the artifact whose deployed or creation code is the given bytecode, and whether it is
+/// the deployed code.
+fn find_artifact<'a>(
+    known_contracts: &'a ContractsByArtifact,
+    bytecode: &[u8],
+) -> Option<(&'a ArtifactId, bool)> {
+    if let Some((id, _)) = known_contracts.find_by_deployed_code(bytecode) {
+        Some((id, true))
+    } else {
+        known_contracts.find_by_creation_code(bytecode).map(|(id, _)| (id, false))
+    }
+}
+
 #[derive(Clone, Debug, ValueEnum)]
 pub enum CoverageReportKind {
     Summary,
     Lcov,
+    /// An HTML report in the `coverage` directory of the project.
+    Html,
     Debug,
     Bytecode,
 }
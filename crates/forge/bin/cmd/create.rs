@@ -2,7 +2,7 @@ use alloy_chains::Chain;
 use alloy_dyn_abi::{DynSolValue, JsonAbiExt, Specifier};
 use alloy_json_abi::{Constructor, JsonAbi};
 use alloy_network::{AnyNetwork, EthereumWallet, TransactionBuilder};
-use alloy_primitives::{hex, Address, Bytes};
+use alloy_primitives::{hex, keccak256, Address, Bytes, B256};
 use alloy_provider::{Provider, ProviderBuilder};
 use alloy_rpc_types::{AnyTransactionReceipt, TransactionRequest};
 use alloy_serde::WithOtherFields;
@@ -10,6 +10,7 @@ use alloy_signer::Signer;
 use alloy_transport::{Transport, TransportError};
 use clap::{Parser, ValueHint};
 use eyre::{Context, Result};
+use forge_script::{ScriptSequence, TransactionWithMetadata};
 use forge_verify::RetryArgs;
 use foundry_cli::{
     opts::{CoreBuildArgs, EthereumOpts, EtherscanOpts, TransactionOpts},
@@ -17,11 +18,16 @@ use foundry_cli::{
 };
 use foundry_common::{
     compile::{self},
+    create2::SaltMiner,
     fmt::parse_tokens,
 };
 use foundry_compilers::{artifacts::BytecodeObject, info::ContractInfo, utils::canonicalize};
+use foundry_evm::{constants::DEFAULT_CREATE2_DEPLOYER, traces::CallKind};
 use serde_json::json;
-use std::{borrow::Borrow, marker::PhantomData, path::PathBuf, sync::Arc};
+use std::{
+    borrow::Borrow, collections::VecDeque, marker::PhantomData, path::PathBuf, sync::Arc,
+    time::Duration,
+};
 
 /// CLI arguments for `forge create`.
 #[derive(Clone, Debug, Parser)]
@@ -65,8 +71,50 @@ pub struct CreateArgs {
     #[arg(long, requires = "verify")]
     show_standard_json_input: bool,
 
+    /// Deploy the contract through the deterministic CREATE2 deployer.
+    ///
+    /// The deployment is simulated first to make sure the contract ends up at the predicted
+    /// address, and the deployment is recorded in the broadcast directory, like script
+    /// transactions, in `broadcast/[contract_filename]/[chain_id]/create2-latest.json`.
+    #[arg(long, help_heading = "CREATE2 options")]
+    create2: bool,
+
+    /// The salt to deploy the contract with. Defaults to zero.
+    #[arg(
+        long,
+        requires = "create2",
+        conflicts_with = "salt_mine",
+        value_name = "SALT",
+        help_heading = "CREATE2 options"
+    )]
+    salt: Option<B256>,
+
+    /// Mine a salt for which the contract address starts with the given hex prefix.
+    #[arg(long, requires = "create2", value_name = "PREFIX", help_heading = "CREATE2 options")]
+    salt_mine: Option<String>,
+
+    /// The maximum number of seconds to mine a salt for.
+    #[arg(
+        long,
+        requires = "salt_mine",
+        default_value_t = 60,
+        value_name = "SECONDS",
+        help_heading = "CREATE2 options"
+    )]
+    salt_mine_timeout: u64,
+
+    /// The address of the CREATE2 deployer.
+    #[arg(
+        long,
+        requires = "create2",
+        default_value_t = DEFAULT_CREATE2_DEPLOYER,
+        value_name = "ADDRESS",
+        help_heading = "CREATE2 options"
+    )]
+    create2_deployer: Address,
+
     #[command(flatten)]
-    opts: CoreBuildArgs,
+    pub(crate) opts: CoreBuildArgs,
 
     #[command(flatten)]
     tx: TransactionOpts,
@@ -135,10 +183,35 @@ impl CreateArgs {
         } else {
             provider.get_chain_id().await?
         };
+
+        let record = if self.create2 {
+            let tx = TransactionWithMetadata {
+                opcode: CallKind::Create2,
+                contract_name: Some(self.contract.name.clone()),
+                rpc: config.get_rpc_url_or_localhost_http()?.into_owned(),
+                ..Default::default()
+            };
+            let paths = ScriptSequence::get_source_paths(
+                &config,
+                "create2",
+                &target_path,
+                chain_id,
+                false,
+            )?;
+            Some(ScriptSequence {
+                transactions: VecDeque::from([tx]),
+                paths: Some(paths),
+                chain: chain_id,
+                ..Default::default()
+            })
+        } else {
+            None
+        };
+
         if self.unlocked {
             // Deploy with unlocked account
             let sender = self.eth.wallet.from.expect("required");
-            self.deploy(abi, bin, params, provider, chain_id, sender).await
+            self.deploy(abi, bin, params, provider, chain_id, sender, record).await
         } else {
             // Deploy with signer
             let signer = self.eth.wallet.signer().await?;
@@ -146,7 +219,7 @@ impl CreateArgs {
             let provider = ProviderBuilder::<_, _, AnyNetwork>::default()
                 .wallet(EthereumWallet::new(signer))
                 .on_provider(provider);
-            self.deploy(abi, bin, params, provider, chain_id, deployer).await
+            self.deploy(abi, bin, params, provider, chain_id, deployer, record).await
         }
     }
 
@@ -192,6 +265,7 @@ impl CreateArgs {
             evm_version: self.opts.compiler.evm_version,
             show_standard_json_input: self.show_standard_json_input,
             guess_constructor_args: false,
+            progress: self.opts.progress,
         };
 
         // Check config for Etherscan API Keys to avoid preflight check failing if no
@@ -207,6 +281,9 @@ impl CreateArgs {
     }
 
     /// Deploys the contract
+    ///
+    /// CREATE2 deployments are saved to the `record` sequence.
+    #[allow(clippy::too_many_arguments)]
     async fn deploy<P: Provider<T, AnyNetwork>, T: Transport + Clone>(
         self,
         abi: JsonAbi,
@@ -215,6 +292,7 @@ impl CreateArgs {
         provider: P,
         chain: u64,
         deployer_address: Address,
+        record: Option<ScriptSequence>,
     ) -> Result<()> {
         let bin = bin.into_bytes().unwrap_or_else(|| {
             panic!("no bytecode found in bin object for {}", self.contract.name)
@@ -250,6 +328,12 @@ impl CreateArgs {
             deployer.tx.set_value(value);
         }
 
+        let create2 = if self.create2 {
+            Some(self.prepare_create2(&mut deployer.tx, &*provider).await?)
+        } else {
+            None
+        };
+
         deployer.tx.set_gas_limit(if let Some(gas_limit) = self.tx.gas_limit {
             Ok(gas_limit.to())
         } else {
@@ -295,22 +379,46 @@ impl CreateArgs {
         }
 
         // Deploy the actual contract
-        let (deployed_contract, receipt) = deployer.send_with_receipt().await?;
+        let tx = deployer.tx.clone();
+        let (deployed_contract, receipt) = if let Some(create2) = &create2 {
+            deployer.send_create2_with_receipt(create2.address).await?
+        } else {
+            deployer.send_with_receipt().await?
+        };
 
         let address = deployed_contract;
         if self.json {
-            let output = json!({
+            let mut output = json!({
                 "deployer": deployer_address.to_string(),
                 "deployedTo": address.to_string(),
                 "transactionHash": receipt.transaction_hash
             });
+            if let Some(create2) = &create2 {
+                output["create2Deployer"] = create2.deployer.to_string().into();
+                output["salt"] = create2.salt.to_string().into();
+                output["initCodeHash"] = create2.init_code_hash.to_string().into();
+            }
             println!("{output}");
         } else {
             println!("Deployer: {deployer_address}");
             println!("Deployed to: {address}");
             println!("Transaction hash: {:?}", receipt.transaction_hash);
+            if let Some(create2) = &create2 {
+                println!("CREATE2 deployer: {}", create2.deployer);
+                println!("Salt: {}", create2.salt);
+                println!("Init code hash: {}", create2.init_code_hash);
+            }
         };
 
+        if let Some(mut sequence) = record {
+            let recorded = &mut sequence.transactions[0];
+            recorded.hash = Some(receipt.transaction_hash);
+            recorded.contract_address = Some(address);
+            recorded.transaction = tx;
+            sequence.receipts.push(receipt);
+            sequence.save(self.json, true)?;
+        }
+
         if !self.verify {
             return Ok(());
         }
@@ -340,11 +448,58 @@ impl CreateArgs {
             evm_version: self.opts.compiler.evm_version,
             show_standard_json_input: self.show_standard_json_input,
             guess_constructor_args: false,
+            progress: self.opts.progress,
         };
         println!("Waiting for {} to detect contract deployment...", verify.verifier.verifier);
         verify.run().await
     }
 
+    /// Turns the creation transaction into a call to the CREATE2 deployer.
+    ///
+    /// The salt is mined if `--salt-mine` is set. The deployment is then simulated to make sure the
+    /// deployer creates the contract at the address predicted from the init code hash.
+    async fn prepare_create2<P: Provider<T, AnyNetwork>, T: Transport + Clone>(
+        &self,
+        tx: &mut WithOtherFields<TransactionRequest>,
+        provider: &P,
+    ) -> Result<Create2Deployment> {
+        let deployer = self.create2_deployer;
+        if provider.get_code_at(deployer).await?.is_empty() {
+            eyre::bail!("CREATE2 deployer {deployer} is not deployed on this chain");
+        }
+
+        let init_code = tx.input.input().cloned().unwrap_or_default();
+        let init_code_hash = keccak256(&init_code);
+        let salt = if let Some(prefix) = &self.salt_mine {
+            if !self.json {
+                println!("Mining salt for address prefix 0x{}...", prefix.trim_start_matches("0x"));
+            }
+            let timeout = Duration::from_secs(self.salt_mine_timeout);
+            mine_salt(deployer, init_code_hash, prefix, timeout, !self.json)?
+        } else {
+            self.salt.unwrap_or_default()
+        };
+
+        let address = deployer.create2(salt, init_code_hash);
+        if !provider.get_code_at(address).await?.is_empty() {
+            eyre::bail!("a contract is already deployed at {address} with salt {salt}");
+        }
+
+        let input: Bytes = salt.iter().copied().chain(init_code).collect();
+        tx.set_input(input);
+        tx.set_to(deployer);
+
+        let output = provider.call(tx).await.wrap_err("CREATE2 deployment simulation failed")?;
+        if output.as_ref() != address.as_slice() {
+            eyre::bail!(
+                "CREATE2 deployer returned {output} instead of {address}, the address predicted from \
+                 init code hash {init_code_hash}"
+            );
+        }
+
+        Ok(Create2Deployment { deployer, salt, init_code_hash, address })
+    }
+
     /// Parses the given constructor arguments into a vector of `DynSolValue`s, by matching them
     /// against the constructor's input params.
     ///
@@ -367,6 +522,50 @@ impl CreateArgs {
     }
 }
 
+/// A deployment through the CREATE2 deployer, checked by simulating it.
+#[derive(Clone, Debug)]
+struct Create2Deployment {
+    deployer: Address,
+    salt: B256,
+    init_code_hash: B256,
+    address: Address,
+}
+
+/// Mines a salt for which the CREATE2 address of the init code starts with the given hex prefix,
+/// giving up after `timeout`.
+///
+/// Returns the lowest matching salt, so the same inputs always result in the same salt.
+fn mine_salt(
+    deployer: Address,
+    init_code_hash: B256,
+    prefix: &str,
+    timeout: Duration,
+    progress: bool,
+) -> Result<B256> {
+    let prefix = prefix.trim_start_matches("0x").to_lowercase();
+    if prefix.len() > 40 {
+        eyre::bail!("address prefix must be at most 40 characters long");
+    }
+    let nibbles = prefix
+        .chars()
+        .map(|c| c.to_digit(16).map(|d| d as u8))
+        .collect::<Option<Vec<_>>>()
+        .ok_or_else(|| eyre::eyre!("invalid address prefix: {prefix}"))?;
+    let matches = |address: &Address| {
+        nibbles.iter().enumerate().all(|(i, nibble)| {
+            let byte = address[i / 2];
+            let actual = if i % 2 == 0 { byte >> 4 } else { byte & 0x0f };
+            actual == *nibble
+        })
+    };
+
+    let miner = SaltMiner::new(deployer, init_code_hash).timeout(timeout).progress(progress);
+    match miner.mine(matches) {
+        Some((_, salt)) => Ok(salt),
+        None => eyre::bail!("no salt found for address prefix 0x{prefix} in {timeout:?}"),
+    }
+}
+
 /// `ContractFactory` is a [`DeploymentTxFactory`] object with an
 /// [`Arc`] middleware. This type alias exists to preserve backwards
 /// compatibility with less-abstract Contracts.
@@ -461,6 +660,28 @@ where
 
         Ok((address, receipt))
     }
+
+    /// Broadcasts the deployment transaction sent to the CREATE2 deployer and returns the
+    /// predicted address of the contract, after checking that the transaction succeeded.
+    pub async fn send_create2_with_receipt(
+        self,
+        address: Address,
+    ) -> Result<(Address, AnyTransactionReceipt), ContractDeploymentError> {
+        let receipt = self
+            .client
+            .borrow()
+            .send_transaction(self.tx)
+            .await?
+            .with_required_confirmations(self.confs as u64)
+            .get_receipt()
+            .await?;
+
+        if !receipt.inner.inner.status() {
+            return Err(ContractDeploymentError::ContractNotDeployed);
+        }
+
+        Ok((address, receipt))
+    }
 }
 
 /// To deploy a contract to the Ethereum network, a `ContractFactory` can be
@@ -644,4 +865,41 @@ mod tests {
         let constructor: Constructor = serde_json::from_str(r#"{"type":"constructor","inputs":[{"name":"_points","type":"tuple[]","internalType":"struct Point[]","components":[{"name":"x","type":"uint256","internalType":"uint256"},{"name":"y","type":"uint256","internalType":"uint256"}]}],"stateMutability":"nonpayable"}"#).unwrap();
         let _params = args.parse_constructor_args(&constructor, &args.constructor_args).unwrap();
     }
+
+    #[test]
+    fn can_parse_create2() {
+        let args: CreateArgs = CreateArgs::parse_from([
+            "foundry-cli",
+            "src/Domains.sol:Domains",
+            "--create2",
+            "--salt-mine",
+            "0xdead",
+        ]);
+        assert!(args.create2);
+        assert_eq!(args.salt_mine.as_deref(), Some("0xdead"));
+        assert_eq!(args.create2_deployer, DEFAULT_CREATE2_DEPLOYER);
+
+        assert!(CreateArgs::try_parse_from([
+            "foundry-cli",
+            "src/Domains.sol:Domains",
+            "--salt-mine",
+            "0xdead"
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn can_mine_salt() {
+        let init_code_hash = keccak256([0x00]);
+        let mine = |prefix: &str, timeout| {
+            mine_salt(DEFAULT_CREATE2_DEPLOYER, init_code_hash, prefix, timeout, false)
+        };
+        let timeout = Duration::from_secs(60);
+        let salt = mine("0xAb", timeout).unwrap();
+        let address = DEFAULT_CREATE2_DEPLOYER.create2(salt, init_code_hash);
+        assert_eq!(address[0], 0xab);
+        assert_eq!(mine("ab", timeout).unwrap(), salt);
+        assert!(mine("0xzz", timeout).is_err());
+        assert!(mine(&"ab".repeat(20), Duration::from_millis(100)).is_err());
+    }
 }
@@ -0,0 +1,492 @@
+use clap::{Parser, Subcommand, ValueEnum, ValueHint};
+use comfy_table::{presets::ASCII_MARKDOWN, Table};
+use eyre::{Context, Result};
+use foundry_cli::utils::{CommandUtils, Git, LoadConfig};
+use foundry_common::fs;
+use foundry_config::{impl_figment_convert_basic, Config};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::{
+    collections::BTreeSet,
+    path::{Path, PathBuf},
+};
+
+/// Matches the whole SPDX license expression of a line, e.g. `MIT OR Apache-2.0`.
+static SPDX_EXPRESSION_RE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"SPDX-License-Identifier:\s*(.+?)\s*(?:\*/)?\s*$").unwrap());
+
+/// The directories of a dependency that contain its own dependencies, which are reported
+/// separately.
+const NESTED_DEPENDENCY_DIRS: &[&str] = &["lib", "node_modules", "dependencies"];
+
+/// CLI arguments for `forge deps`.
+#[derive(Clone, Debug, Parser)]
+pub struct DepsArgs {
+    #[command(subcommand)]
+    pub sub: DepsSubcommands,
+}
+
+#[derive(Clone, Debug, Subcommand)]
+pub enum DepsSubcommands {
+    /// Report the versions, commits, licenses and known audits of the installed dependencies.
+    ///
+    /// Covers the git dependencies and npm packages in the library directories, and the Soldeer
+    /// dependencies. Can be output as a CycloneDX SBOM.
+    Report(DepsReportArgs),
+}
+
+/// The format of a dependency report.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum ReportFormat {
+    /// A human readable table.
+    #[default]
+    Table,
+    /// The dependencies as JSON.
+    Json,
+    /// A CycloneDX 1.5 SBOM, as JSON.
+    Cyclonedx,
+}
+
+/// CLI arguments for `forge deps report`.
+#[derive(Clone, Debug, Parser)]
+pub struct DepsReportArgs {
+    /// The project's root path.
+    ///
+    /// By default root of the Git repository, if in one,
+    /// or the current working directory.
+    #[arg(long, value_hint = ValueHint::DirPath, value_name = "PATH")]
+    pub root: Option<PathBuf>,
+
+    /// A TOML registry of known audits of dependencies.
+    ///
+    /// Each `[[audits]]` entry has the `dependency` it covers, the `auditor`, and optionally the
+    /// `versions` (tags or commits) it covers, the `date` and a link to the `report`.
+    #[arg(long, value_hint = ValueHint::FilePath, value_name = "FILE")]
+    pub audits: Option<PathBuf>,
+
+    /// The format of the report.
+    #[arg(long, value_enum, default_value_t)]
+    pub format: ReportFormat,
+
+    /// Write the report to the given file instead of stdout.
+    #[arg(long, short, value_hint = ValueHint::FilePath, value_name = "FILE")]
+    pub out: Option<PathBuf>,
+}
+
+impl_figment_convert_basic!(DepsReportArgs);
+
+impl DepsReportArgs {
+    pub fn run(self) -> Result<()> {
+        let config = self.try_load_config_emit_warnings()?;
+        let registry = match &self.audits {
+            Some(path) => {
+                let registry = fs::read_to_string(path)?;
+                toml::from_str::<AuditRegistry>(&registry).wrap_err_with(|| {
+                    format!("failed to parse audit registry {}", path.display())
+                })?
+            }
+            None => AuditRegistry::default(),
+        };
+
+        let mut dependencies = installed_dependencies(&config);
+        for dependency in &mut dependencies {
+            dependency.audits = registry.audits_of(dependency);
+        }
+
+        let report = match self.format {
+            ReportFormat::Table => table(&dependencies),
+            ReportFormat::Json => serde_json::to_string_pretty(&dependencies)?,
+            ReportFormat::Cyclonedx => {
+                let project = config.root.0.file_name().map(|name| name.to_string_lossy());
+                serde_json::to_string_pretty(&cyclonedx(
+                    project.as_deref().unwrap_or("project"),
+                    &dependencies,
+                ))?
+            }
+        };
+        match &self.out {
+            Some(out) => fs::write(out, report)?,
+            None => println!("{report}"),
+        }
+        Ok(())
+    }
+}
+
+/// How a dependency was installed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DependencySource {
+    /// A git repository or submodule in a library directory.
+    Git,
+    /// An npm package in `node_modules`.
+    Npm,
+    /// A Soldeer dependency.
+    Soldeer,
+}
+
+/// An installed dependency of the project.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct DependencyInfo {
+    pub name: String,
+    /// The path of the dependency, relative to the project root.
+    pub path: String,
+    pub source: DependencySource,
+    /// The tag of a git dependency, or the version of a package.
+    pub version: Option<String>,
+    /// The commit a git dependency is at.
+    pub commit: Option<String>,
+    /// Where the dependency was retrieved from.
+    pub url: Option<String>,
+    /// The SPDX license expressions of the dependency's sources and package.
+    pub licenses: BTreeSet<String>,
+    /// The known audits of the dependency's version.
+    pub audits: Vec<Audit>,
+}
+
+/// A known audit of a dependency.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Audit {
+    /// The name of the dependency the audit covers.
+    pub dependency: String,
+    /// The tags or commits the audit covers, all versions if empty.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub versions: Vec<String>,
+    pub auditor: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub date: Option<String>,
+    /// A link to the audit report.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub report: Option<String>,
+}
+
+impl Audit {
+    /// Returns `true` if the audit covers the version of the dependency.
+    fn covers(&self, dependency: &DependencyInfo) -> bool {
+        if !self.dependency.eq_ignore_ascii_case(&dependency.name) {
+            return false
+        }
+        self.versions.is_empty() ||
+            self.versions.iter().any(|version| {
+                dependency.version.as_deref() == Some(version.as_str()) ||
+                    (version.len() >= 7 &&
+                        dependency
+                            .commit
+                            .as_ref()
+                            .is_some_and(|commit| commit.starts_with(version)))
+            })
+    }
+}
+
+/// A registry of known audits.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct AuditRegistry {
+    #[serde(default)]
+    audits: Vec<Audit>,
+}
+
+impl AuditRegistry {
+    fn audits_of(&self, dependency: &DependencyInfo) -> Vec<Audit> {
+        self.audits.iter().filter(|audit| audit.covers(dependency)).cloned().collect()
+    }
+}
+
+/// Returns the dependencies installed in the library directories and by Soldeer.
+fn installed_dependencies(config: &Config) -> Vec<DependencyInfo> {
+    let root = &config.root.0;
+    let mut dependencies = Vec::new();
+    for lib in &config.libs {
+        for path in subdirs(&root.join(lib)) {
+            // Scoped npm packages are nested in their scope, e.g. `@openzeppelin/contracts`.
+            let is_scope =
+                path.file_name().is_some_and(|name| name.to_string_lossy().starts_with('@'));
+            let paths = if is_scope { subdirs(&path) } else { vec![path] };
+            dependencies.extend(paths.into_iter().map(|path| library_dependency(&path, root)));
+        }
+    }
+
+    if let Some(soldeer) = &config.dependencies {
+        for (name, dependency) in soldeer.dependencies() {
+            let path = root.join("dependencies").join(format!("{name}-{}", dependency.version));
+            dependencies.push(DependencyInfo {
+                name: name.clone(),
+                path: relative(&path, root),
+                source: DependencySource::Soldeer,
+                version: Some(dependency.version.clone()),
+                commit: None,
+                url: dependency.url.clone(),
+                licenses: licenses(&path),
+                audits: Vec::new(),
+            });
+        }
+    }
+    dependencies
+}
+
+/// Returns the info of a git dependency or npm package in a library directory.
+fn library_dependency(path: &Path, root: &Path) -> DependencyInfo {
+    let package = fs::read_json_file::<serde_json::Value>(&path.join("package.json")).ok();
+    let package_field =
+        |field: &str| package.as_ref().and_then(|p| p[field].as_str()).map(str::to_string);
+
+    let mut dependency = DependencyInfo {
+        name: path.file_name().unwrap_or_default().to_string_lossy().into_owned(),
+        path: relative(path, root),
+        source: DependencySource::Git,
+        version: None,
+        commit: None,
+        url: None,
+        licenses: licenses(path),
+        audits: Vec::new(),
+    };
+    if path.join(".git").exists() {
+        let git = |args: &[&str]| Git::new(path).cmd().args(args).get_stdout_lossy().ok();
+        dependency.version = git(&["describe", "--tags", "--exact-match"]);
+        dependency.commit = git(&["rev-parse", "HEAD"]);
+        dependency.url = git(&["config", "--get", "remote.origin.url"]);
+    } else if package.is_some() {
+        dependency.source = DependencySource::Npm;
+        dependency.name = package_field("name").unwrap_or(dependency.name);
+        dependency.version = package_field("version");
+    }
+    if let Some(license) = package_field("license") {
+        dependency.licenses.insert(license);
+    }
+    dependency
+}
+
+/// Returns the SPDX license expressions of the Solidity sources of a dependency, skipping its own
+/// dependencies.
+fn licenses(path: &Path) -> BTreeSet<String> {
+    fs::files_with_ext(path, "sol")
+        .filter(|file| {
+            !file.strip_prefix(path).ok().and_then(|file| file.components().next()).is_some_and(
+                |dir| NESTED_DEPENDENCY_DIRS.iter().any(|nested| dir.as_os_str() == *nested),
+            )
+        })
+        .filter_map(|file| fs::read_to_string(file).ok())
+        .filter_map(|contents| {
+            contents
+                .lines()
+                .find_map(|line| SPDX_EXPRESSION_RE.captures(line))
+                .map(|caps| caps[1].to_string())
+        })
+        .collect()
+}
+
+fn subdirs(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(dir) else { return Vec::new() };
+    let mut paths = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_dir() && !path.file_name().is_some_and(|name| name == ".bin"))
+        .collect::<Vec<_>>();
+    paths.sort();
+    paths
+}
+
+fn relative(path: &Path, root: &Path) -> String {
+    path.strip_prefix(root).unwrap_or(path).display().to_string()
+}
+
+fn table(dependencies: &[DependencyInfo]) -> String {
+    let mut table = Table::new();
+    table.load_preset(ASCII_MARKDOWN);
+    table.set_header(["Dependency", "Path", "Version", "Commit", "Licenses", "Audits"]);
+    for dependency in dependencies {
+        let audits = dependency.audits.iter().map(|audit| match &audit.date {
+            Some(date) => format!("{} ({date})", audit.auditor),
+            None => audit.auditor.clone(),
+        });
+        table.add_row([
+            dependency.name.clone(),
+            dependency.path.clone(),
+            dependency.version.clone().unwrap_or_else(|| "-".to_string()),
+            dependency
+                .commit
+                .as_ref()
+                .map_or("-", |commit| &commit[..commit.len().min(8)])
+                .to_string(),
+            dependency.licenses.iter().cloned().collect::<Vec<_>>().join(", "),
+            audits.collect::<Vec<_>>().join(", "),
+        ]);
+    }
+    table.to_string()
+}
+
+/// Returns a CycloneDX 1.5 SBOM of the dependencies.
+fn cyclonedx(project: &str, dependencies: &[DependencyInfo]) -> serde_json::Value {
+    let components = dependencies
+        .iter()
+        .map(|dependency| {
+            let mut component = json!({
+                "type": "library",
+                "bom-ref": dependency.path,
+                "name": dependency.name,
+            });
+            if let Some(version) = dependency.version.as_ref().or(dependency.commit.as_ref()) {
+                component["version"] = json!(version);
+            }
+            if let Some(purl) = purl(dependency) {
+                component["purl"] = json!(purl);
+            }
+            if !dependency.licenses.is_empty() {
+                component["licenses"] = dependency
+                    .licenses
+                    .iter()
+                    .map(|license| {
+                        // Compound expressions aren't license IDs.
+                        if license.contains(' ') {
+                            json!({ "expression": license })
+                        } else {
+                            json!({ "license": { "id": license } })
+                        }
+                    })
+                    .collect();
+            }
+            if let Some(url) = &dependency.url {
+                let kind =
+                    if dependency.source == DependencySource::Git { "vcs" } else { "distribution" };
+                component["externalReferences"] = json!([{ "type": kind, "url": url }]);
+            }
+
+            let mut properties = Vec::new();
+            if let Some(commit) = &dependency.commit {
+                properties.push(json!({ "name": "foundry:commit", "value": commit }));
+            }
+            for audit in &dependency.audits {
+                let mut value = audit.auditor.clone();
+                if let Some(date) = &audit.date {
+                    value.push_str(&format!(" ({date})"));
+                }
+                if let Some(report) = &audit.report {
+                    value.push_str(&format!(" {report}"));
+                }
+                properties.push(json!({ "name": "foundry:audit", "value": value }));
+            }
+            if !properties.is_empty() {
+                component["properties"] = properties.into();
+            }
+            component
+        })
+        .collect::<Vec<_>>();
+
+    json!({
+        "bomFormat": "CycloneDX",
+        "specVersion": "1.5",
+        "version": 1,
+        "metadata": {
+            "tools": {
+                "components": [{
+                    "type": "application",
+                    "name": "forge",
+                    "version": env!("CARGO_PKG_VERSION"),
+                }],
+            },
+            "component": { "type": "application", "bom-ref": project, "name": project },
+        },
+        "components": components,
+        "dependencies": [{
+            "ref": project,
+            "dependsOn": dependencies.iter().map(|dependency| &dependency.path).collect::<Vec<_>>(),
+        }],
+    })
+}
+
+/// Returns the package URL of a dependency hosted on GitHub or npm.
+fn purl(dependency: &DependencyInfo) -> Option<String> {
+    let version = dependency.version.as_ref().or(dependency.commit.as_ref());
+    let purl = match dependency.source {
+        DependencySource::Npm => format!("pkg:npm/{}", dependency.name.replace('@', "%40")),
+        DependencySource::Git => {
+            let url = dependency.url.as_deref()?;
+            let repo = url
+                .strip_prefix("https://github.com/")
+                .or_else(|| url.strip_prefix("git@github.com:"))?
+                .trim_end_matches(".git")
+                .trim_end_matches('/');
+            format!("pkg:github/{}", repo.to_lowercase())
+        }
+        DependencySource::Soldeer => return None,
+    };
+    Some(match version {
+        Some(version) => format!("{purl}@{version}"),
+        None => purl,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dependency() -> DependencyInfo {
+        DependencyInfo {
+            name: "openzeppelin-contracts".to_string(),
+            path: "lib/openzeppelin-contracts".to_string(),
+            source: DependencySource::Git,
+            version: Some("v5.0.2".to_string()),
+            commit: Some("dbb6104ce834628e473d2173bbc9d47f81a9eec3".to_string()),
+            url: Some("https://github.com/OpenZeppelin/openzeppelin-contracts.git".to_string()),
+            licenses: ["MIT".to_string(), "MIT OR Apache-2.0".to_string()].into(),
+            audits: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn matches_audits() {
+        let registry: AuditRegistry = toml::from_str(
+            r#"
+[[audits]]
+dependency = "OpenZeppelin-Contracts"
+versions = ["v5.0.2"]
+auditor = "Auditor A"
+
+[[audits]]
+dependency = "openzeppelin-contracts"
+versions = ["dbb6104c"]
+auditor = "Auditor B"
+date = "2024-03-01"
+
+[[audits]]
+dependency = "openzeppelin-contracts"
+versions = ["v4.9.0"]
+auditor = "Auditor C"
+
+[[audits]]
+dependency = "forge-std"
+auditor = "Auditor D"
+"#,
+        )
+        .unwrap();
+        let auditors = registry
+            .audits_of(&dependency())
+            .into_iter()
+            .map(|audit| audit.auditor)
+            .collect::<Vec<_>>();
+        assert_eq!(auditors, ["Auditor A", "Auditor B"]);
+    }
+
+    #[test]
+    fn extracts_license_expressions() {
+        let caps = SPDX_EXPRESSION_RE.captures("/* SPDX-License-Identifier: MIT OR Apache-2.0 */");
+        assert_eq!(&caps.unwrap()[1], "MIT OR Apache-2.0");
+        let caps = SPDX_EXPRESSION_RE.captures("// SPDX-License-Identifier: UNLICENSED");
+        assert_eq!(&caps.unwrap()[1], "UNLICENSED");
+    }
+
+    #[test]
+    fn builds_cyclonedx_sbom() {
+        let sbom = cyclonedx("counter", &[dependency()]);
+        assert_eq!(sbom["bomFormat"], "CycloneDX");
+        let component = &sbom["components"][0];
+        assert_eq!(component["version"], "v5.0.2");
+        assert_eq!(component["purl"], "pkg:github/openzeppelin/openzeppelin-contracts@v5.0.2");
+        assert_eq!(
+            component["licenses"],
+            json!([{ "license": { "id": "MIT" } }, { "expression": "MIT OR Apache-2.0" }])
+        );
+        assert_eq!(sbom["dependencies"][0]["dependsOn"], json!(["lib/openzeppelin-contracts"]));
+    }
+}
@@ -0,0 +1,258 @@
+use alloy_json_abi::JsonAbi;
+use alloy_primitives::{hex, Address};
+use eyre::Result;
+use foundry_common::fs;
+use foundry_compilers::{
+    artifacts::{ConfigurableContractArtifact, DevDoc, StorageLayout, UserDoc},
+    ArtifactId,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+/// The version of the metadata bundle format, bumped on breaking changes.
+pub const METADATA_VERSION: u32 = 1;
+
+/// The file name of the metadata bundle in the export directory.
+pub const METADATA_FILE: &str = "metadata.json";
+
+/// The public interfaces, NatSpec, storage layouts and deployments of the contracts of a project,
+/// exported with `forge doc --export-metadata`.
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MetadataBundle {
+    pub version: u32,
+    /// The contracts by `<source path>:<name>`.
+    pub contracts: BTreeMap<String, ContractMetadata>,
+}
+
+/// The metadata of a contract.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContractMetadata {
+    pub name: String,
+    /// The path of the source defining the contract, relative to the project root.
+    pub source: PathBuf,
+    pub compiler_version: String,
+    pub abi: JsonAbi,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub userdoc: Option<UserDoc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub devdoc: Option<DevDoc>,
+    /// The custom errors of the ABI with their selectors.
+    pub errors: Vec<Signature>,
+    /// The events of the ABI with their topics.
+    pub events: Vec<Signature>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub storage_layout: Option<StorageLayout>,
+    /// The addresses the contract was last deployed at, by chain.
+    pub deployments: Vec<Deployment>,
+}
+
+/// The signature of an error or event, and its selector or topic.
+#[derive(Debug, PartialEq, Eq, Serialize)]
+pub struct Signature {
+    pub signature: String,
+    pub selector: String,
+}
+
+/// A deployment of a contract, as recorded in the broadcast directory.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Deployment {
+    pub chain_id: u64,
+    pub address: Address,
+}
+
+impl MetadataBundle {
+    /// Creates the bundle of the artifacts, the deployments are looked up by contract name.
+    pub fn new<'a>(
+        artifacts: impl IntoIterator<Item = (ArtifactId, &'a ConfigurableContractArtifact)>,
+        root: &Path,
+        deployments: &BTreeMap<String, Vec<Deployment>>,
+    ) -> Self {
+        let mut bundle = Self { version: METADATA_VERSION, ..Default::default() };
+        for (id, artifact) in artifacts {
+            let Some(abi) = &artifact.abi else { continue };
+            let source = id.source.strip_prefix(root).unwrap_or(&id.source).to_path_buf();
+            let key = format!("{}:{}", source.display(), id.name);
+            // Contracts compiled with several compiler versions are only exported once.
+            bundle.contracts.entry(key).or_insert_with(|| ContractMetadata {
+                errors: abi
+                    .errors()
+                    .map(|error| Signature {
+                        signature: error.signature(),
+                        selector: hex::encode_prefixed(error.selector()),
+                    })
+                    .collect(),
+                events: abi
+                    .events()
+                    .map(|event| Signature {
+                        signature: event.signature(),
+                        selector: hex::encode_prefixed(event.selector()),
+                    })
+                    .collect(),
+                deployments: deployments.get(&id.name).cloned().unwrap_or_default(),
+                name: id.name,
+                source,
+                compiler_version: id.version.to_string(),
+                abi: abi.clone(),
+                userdoc: artifact.userdoc.clone(),
+                devdoc: artifact.devdoc.clone(),
+                storage_layout: artifact.storage_layout.clone(),
+            });
+        }
+        bundle
+    }
+
+    /// Writes the bundle to [METADATA_FILE] in the directory, returning the path of the file.
+    pub fn write(&self, dir: &Path) -> Result<PathBuf> {
+        fs::create_dir_all(dir)?;
+        let path = dir.join(METADATA_FILE);
+        fs::write(&path, serde_json::to_string_pretty(self)?)?;
+        Ok(path)
+    }
+}
+
+/// A `[sig]-latest.json` file of the broadcast directory, written by `forge script` and
+/// `forge create --create2`.
+#[derive(Deserialize)]
+struct BroadcastRecord {
+    transactions: Vec<BroadcastTransaction>,
+    chain: u64,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BroadcastTransaction {
+    transaction_type: String,
+    contract_name: Option<String>,
+    contract_address: Option<Address>,
+}
+
+/// Returns the latest deployments of the contracts by name, as recorded in the `[sig]-latest.json`
+/// files of the broadcast directory.
+///
+/// Simulations, which are recorded in `dry-run` directories, are skipped.
+pub fn broadcast_deployments(broadcast: &Path) -> Result<BTreeMap<String, Vec<Deployment>>> {
+    let mut paths = Vec::new();
+    find_latest_runs(broadcast, &mut paths)?;
+    let mut deployments = BTreeMap::<String, Vec<Deployment>>::new();
+    for path in paths {
+        let record = match fs::read_json_file::<BroadcastRecord>(&path) {
+            Ok(record) => record,
+            Err(err) => {
+                warn!(%err, path = %path.display(), "skipping unreadable broadcast");
+                continue
+            }
+        };
+        for tx in record.transactions {
+            let (Some(name), Some(address)) = (tx.contract_name, tx.contract_address) else {
+                continue
+            };
+            if tx.transaction_type.starts_with("CREATE") {
+                deployments
+                    .entry(name)
+                    .or_default()
+                    .push(Deployment { chain_id: record.chain, address });
+            }
+        }
+    }
+    for deployments in deployments.values_mut() {
+        deployments.sort();
+        deployments.dedup();
+    }
+    Ok(deployments)
+}
+
+/// Collects the `[sig]-latest.json` files in the directory and its subdirectories, except for
+/// `dry-run` directories.
+fn find_latest_runs(dir: &Path, paths: &mut Vec<PathBuf>) -> Result<()> {
+    if !dir.is_dir() {
+        return Ok(())
+    }
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            if !path.ends_with("dry-run") {
+                find_latest_runs(&path, paths)?;
+            }
+        } else if path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| name.ends_with("-latest.json"))
+        {
+            paths.push(path);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn reads_deployments_from_broadcasts() {
+        let dir = tempfile::tempdir().unwrap();
+        let write = |path: &str, value: serde_json::Value| {
+            let path = dir.path().join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, value.to_string()).unwrap();
+        };
+        let address = |byte| Address::with_last_byte(byte);
+
+        write(
+            "Deploy.s.sol/1/run-latest.json",
+            json!({
+                "chain": 1,
+                "transactions": [
+                    {
+                        "transactionType": "CREATE",
+                        "contractName": "Token",
+                        "contractAddress": address(1),
+                    },
+                    {
+                        "transactionType": "CALL",
+                        "contractName": "Token",
+                        "contractAddress": address(1),
+                    },
+                ],
+            }),
+        );
+        write(
+            "Deploy.s.sol/1/dry-run/run-latest.json",
+            json!({
+                "chain": 1,
+                "transactions": [{
+                    "transactionType": "CREATE",
+                    "contractName": "Token",
+                    "contractAddress": address(2),
+                }],
+            }),
+        );
+        write(
+            "Vault.sol/10/create2-latest.json",
+            json!({
+                "chain": 10,
+                "transactions": [{
+                    "transactionType": "CREATE2",
+                    "contractName": "Vault",
+                    "contractAddress": address(3),
+                }],
+            }),
+        );
+
+        let deployments = broadcast_deployments(dir.path()).unwrap();
+        assert_eq!(
+            deployments,
+            BTreeMap::from([
+                ("Token".to_string(), vec![Deployment { chain_id: 1, address: address(1) }]),
+                ("Vault".to_string(), vec![Deployment { chain_id: 10, address: address(3) }]),
+            ])
+        );
+    }
+}
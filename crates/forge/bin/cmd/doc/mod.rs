@@ -5,9 +5,13 @@ use forge_doc::{
 };
 use foundry_cli::opts::GH_REPO_PREFIX_REGEX;
 use foundry_common::compile::ProjectCompiler;
+use foundry_compilers::artifacts::output_selection::ContractOutputSelection;
 use foundry_config::{find_project_root_path, load_config_with_root};
 use std::{path::PathBuf, process::Command};
 
+mod metadata;
+use metadata::{broadcast_deployments, MetadataBundle};
+
 mod server;
 use server::Server;
 
@@ -59,15 +63,59 @@ pub struct DocArgs {
     /// Whether to create docs for external libraries.
     #[arg(long, short)]
     include_libraries: bool,
+
+    /// Export a JSON bundle of the ABIs, NatSpec, custom errors, events, storage layouts and
+    /// deployment addresses of the project's contracts to `metadata.json` in the given directory,
+    /// instead of generating the documentation.
+    ///
+    /// The deployment addresses are the latest ones recorded in the broadcast directory by
+    /// `forge script --broadcast` and `forge create --create2`.
+    #[arg(
+        long,
+        value_hint = ValueHint::DirPath,
+        value_name = "PATH",
+        conflicts_with_all = ["build", "serve"],
+    )]
+    export_metadata: Option<PathBuf>,
 }
 
 impl DocArgs {
     pub fn run(self) -> Result<()> {
         let root = self.root.clone().unwrap_or(find_project_root_path(None)?);
-        let config = load_config_with_root(Some(root.clone()));
+        let mut config = load_config_with_root(Some(root.clone()));
+        if self.export_metadata.is_some() {
+            // NatSpec and storage layouts are not part of the default output selection.
+            for selection in [
+                ContractOutputSelection::UserDoc,
+                ContractOutputSelection::DevDoc,
+                ContractOutputSelection::StorageLayout,
+            ] {
+                if !config.extra_output.contains(&selection) {
+                    config.extra_output.push(selection);
+                }
+            }
+        }
         let project = config.project()?;
         let compiler = ProjectCompiler::new().quiet(true);
-        let _output = compiler.compile(&project)?;
+        let output = compiler.compile(&project)?;
+
+        if let Some(dir) = self.export_metadata {
+            let deployments = broadcast_deployments(&root.join(&config.broadcast))?;
+            let artifacts = output.artifact_ids().filter(|(id, _)| {
+                let source = root.join(&id.source);
+                source.starts_with(&project.paths.sources) ||
+                    (self.include_libraries &&
+                        project.paths.libraries.iter().any(|lib| source.starts_with(lib)))
+            });
+            let bundle = MetadataBundle::new(artifacts, &root, &deployments);
+            let path = bundle.write(&dir)?;
+            println!(
+                "Exported the metadata of {} contracts to {}",
+                bundle.contracts.len(),
+                path.display()
+            );
+            return Ok(())
+        }
 
         let mut doc_config = config.doc.clone();
         if let Some(out) = self.out {
@@ -0,0 +1,461 @@
+use super::bind_json::{Definitions, StructDef};
+use alloy_primitives::{keccak256, B256};
+use clap::{Parser, ValueHint};
+use eyre::{OptionExt, Result};
+use foundry_cli::{opts::CoreBuildArgs, utils::LoadConfig};
+use foundry_common::{compile::ProjectCompiler, fs};
+use foundry_compilers::artifacts::ast::{Node, NodeType};
+use foundry_config::impl_figment_convert;
+use serde::Serialize;
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Write,
+    path::{Path, PathBuf},
+};
+use yansi::Paint;
+
+impl_figment_convert!(Eip712Args, build_args);
+
+/// The NatSpec tag of the structs to generate EIP-712 types for.
+const EIP712_TAG: &str = "@custom:eip712";
+
+/// CLI arguments for `forge eip712`.
+#[derive(Clone, Debug, Parser)]
+pub struct Eip712Args {
+    /// Include all the project's structs, instead of only the ones annotated with
+    /// `@custom:eip712`.
+    #[arg(long)]
+    all: bool,
+
+    /// Write a Solidity library with the typehashes and `hashStruct` functions of the structs, and
+    /// EIP-712 domain helpers, to the given path, relative to the project root.
+    #[arg(long, value_hint = ValueHint::FilePath, value_name = "PATH")]
+    out: Option<PathBuf>,
+
+    /// Print the types as JSON.
+    #[arg(long, short)]
+    json: bool,
+
+    #[command(flatten)]
+    build_args: CoreBuildArgs,
+}
+
+impl Eip712Args {
+    pub fn run(self) -> Result<()> {
+        let mut config = self.try_load_config_emit_warnings()?;
+        // The struct definitions are read from the Solc AST.
+        config.ast = true;
+        let project = config.project()?;
+        let out = self.out.as_ref().map(|out| project.root().join(out));
+
+        let output = ProjectCompiler::new().quiet(self.json).compile(&project)?;
+
+        // AST node IDs are only unique within a compiler run, so sources are grouped by version.
+        let mut versioned = HashMap::<_, (Definitions, Vec<usize>, Vec<TypehashConstant>)>::new();
+        for (path, source_file, version) in output.output().sources.sources_with_version() {
+            let Some(ast) = &source_file.ast else { continue };
+            let is_project = !project.paths.has_library_ancestor(path) &&
+                out.as_ref() != Some(&project.root().join(path));
+            let (defs, structs, constants) = versioned.entry(version).or_default();
+            for node in &ast.nodes {
+                defs.collect(node, None, path, is_project.then_some(&mut *structs));
+                if is_project {
+                    collect_typehash_constants(node, None, path, constants);
+                }
+            }
+        }
+
+        let mut types = BTreeMap::new();
+        let mut library = Library::default();
+        let mut mismatches = Vec::new();
+        for (defs, structs, constants) in versioned.values() {
+            let mut encoded = HashMap::new();
+            for id in structs {
+                let def = &defs.structs[id];
+                let encode_type = match defs.encode_type(*id) {
+                    Ok(encode_type) => encode_type,
+                    Err(err) => {
+                        warn!(name = %def.qualified_name(), %err, "skipping struct");
+                        continue
+                    }
+                };
+                encoded.insert(*id, encode_type.clone());
+
+                if !self.all && !def.documentation.contains(EIP712_TAG) {
+                    continue
+                }
+                if out.is_some() {
+                    if let Err(err) = library.add(defs, *id) {
+                        warn!(name = %def.qualified_name(), %err, "skipping struct hash function");
+                    }
+                }
+                types.entry(def.qualified_name()).or_insert_with(|| Eip712Type {
+                    name: def.qualified_name(),
+                    path: def.path.clone(),
+                    type_hash: keccak256(&encode_type),
+                    encode_type,
+                });
+            }
+
+            mismatches.extend(constants.iter().filter_map(|c| c.check(defs, &encoded)));
+        }
+
+        if self.json {
+            println!("{}", serde_json::to_string_pretty(&types.values().collect::<Vec<_>>())?);
+        } else if types.is_empty() && !self.all {
+            println!(
+                "No structs annotated with `{EIP712_TAG}` found, use --all to include all structs."
+            );
+        } else {
+            for ty in types.values() {
+                println!("{} ({})", ty.name.bold(), ty.path.display());
+                println!("  encodeType: {}", ty.encode_type);
+                println!("  typehash:   {}", ty.type_hash);
+            }
+        }
+
+        if let (Some(out), Some(relative)) = (&out, &self.out) {
+            if let Some(parent) = out.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(out, library.render())?;
+            if !self.json {
+                println!("EIP-712 helpers written to {}", relative.display());
+            }
+        }
+
+        if !mismatches.is_empty() {
+            for mismatch in &mismatches {
+                eprintln!("{}", mismatch.red());
+            }
+            eyre::bail!("found {} mismatching typehash constants", mismatches.len());
+        }
+
+        Ok(())
+    }
+}
+
+/// The EIP-712 type of a struct.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Eip712Type {
+    name: String,
+    path: PathBuf,
+    encode_type: String,
+    type_hash: B256,
+}
+
+/// A hardcoded typehash constant declared in the project.
+#[derive(Debug)]
+struct TypehashConstant {
+    /// The name of the constant, qualified with its contract if any.
+    name: String,
+    path: PathBuf,
+    value: TypehashValue,
+}
+
+#[derive(Debug)]
+enum TypehashValue {
+    /// The hash of a string literal, `keccak256("...")`.
+    Hashed(String),
+    /// A hash literal.
+    Literal(B256),
+}
+
+impl TypehashConstant {
+    /// Checks the constant against the encoded types of the structs it refers to, returning a
+    /// description of the mismatch, if any.
+    ///
+    /// A hashed string refers to the structs named like its primary type, and a hash literal to the
+    /// structs named like the constant without its `TYPEHASH` suffix, e.g. `Permit` for
+    /// `PERMIT_TYPEHASH`. Constants that don't refer to any struct are ignored.
+    fn check(&self, defs: &Definitions, encoded: &HashMap<usize, String>) -> Option<String> {
+        let referenced = |matches: &dyn Fn(&StructDef) -> bool| {
+            let mut types = encoded
+                .iter()
+                .filter(|(id, _)| matches(&defs.structs[*id]))
+                .map(|(id, ty)| (&defs.structs[id].name, ty))
+                .collect::<Vec<_>>();
+            types.sort();
+            types
+        };
+
+        match &self.value {
+            TypehashValue::Hashed(encode_type) => {
+                let primary = encode_type.split('(').next()?;
+                let types = referenced(&|def| def.name == primary);
+                let (name, expected) = types.first()?;
+                if types.iter().any(|(_, ty)| *ty == encode_type) {
+                    return None
+                }
+                Some(format!(
+                    "{} in {} hashes `{encode_type}`, but struct {name} is encoded as `{expected}`",
+                    self.name,
+                    self.path.display()
+                ))
+            }
+            TypehashValue::Literal(hash) => {
+                let constant = self.name.rsplit('.').next().unwrap_or_default();
+                let constant = normalize(constant);
+                let constant = constant.strip_suffix("TYPEHASH")?;
+                let types = referenced(&|def| normalize(&def.name) == constant);
+                let (name, expected) = types.first()?;
+                if types.iter().any(|(_, ty)| keccak256(ty) == *hash) {
+                    return None
+                }
+                Some(format!(
+                    "{} in {} is {hash}, but the typehash of struct {name} is {} (`{expected}`)",
+                    self.name,
+                    self.path.display(),
+                    keccak256(expected)
+                ))
+            }
+        }
+    }
+}
+
+/// Normalizes a name for matching constants to structs, e.g. `PERMIT_TYPEHASH` to `PERMITTYPEHASH`
+/// and `Permit` to `PERMIT`.
+fn normalize(name: &str) -> String {
+    name.chars().filter(|c| c.is_ascii_alphanumeric()).collect::<String>().to_ascii_uppercase()
+}
+
+/// Collects the typehash constants in the given node and its children.
+fn collect_typehash_constants(
+    node: &Node,
+    contract: Option<&str>,
+    path: &Path,
+    constants: &mut Vec<TypehashConstant>,
+) {
+    let name = node.attribute::<String>("name").unwrap_or_default();
+    match node.node_type {
+        NodeType::ContractDefinition => {
+            for child in &node.nodes {
+                collect_typehash_constants(child, Some(&name), path, constants);
+            }
+        }
+        NodeType::VariableDeclaration => {
+            let is_constant = node.attribute::<bool>("constant").unwrap_or_default() ||
+                node.attribute::<String>("mutability").as_deref() == Some("constant");
+            if !is_constant || !normalize(&name).ends_with("TYPEHASH") {
+                return
+            }
+            let Some(value) = node.attribute::<Node>("value").and_then(|v| typehash_value(&v))
+            else {
+                return
+            };
+            constants.push(TypehashConstant {
+                name: match contract {
+                    Some(contract) => format!("{contract}.{name}"),
+                    None => name,
+                },
+                path: path.to_path_buf(),
+                value,
+            });
+        }
+        _ => {}
+    }
+}
+
+/// Returns the value of a typehash constant, if it's a hash literal or the hash of a string
+/// literal.
+fn typehash_value(node: &Node) -> Option<TypehashValue> {
+    match node.node_type {
+        NodeType::Literal => {
+            node.attribute::<String>("value")?.parse().ok().map(TypehashValue::Literal)
+        }
+        NodeType::FunctionCall => {
+            let expr: Node = node.attribute("expression")?;
+            if expr.node_type != NodeType::Identifier ||
+                expr.attribute::<String>("name").as_deref() != Some("keccak256")
+            {
+                return None
+            }
+            let args: Vec<Node> = node.attribute("arguments")?;
+            let [arg] = &args[..] else { return None };
+            if arg.node_type != NodeType::Literal ||
+                arg.attribute::<String>("kind").as_deref() != Some("string")
+            {
+                return None
+            }
+            arg.attribute("value").map(TypehashValue::Hashed)
+        }
+        _ => None,
+    }
+}
+
+/// The Solidity library written with `--out`.
+#[derive(Debug, Default)]
+struct Library {
+    /// The symbols to import, by path.
+    imports: BTreeMap<PathBuf, Vec<String>>,
+    /// The typehash constant and hash function of each struct, by qualified name.
+    structs: BTreeMap<String, String>,
+    /// The functions hashing arrays of dynamic types and structs, by element type.
+    array_helpers: BTreeMap<String, String>,
+}
+
+impl Library {
+    /// Adds the typehash and hash function of the struct with the given ID, and of the structs it
+    /// references.
+    fn add(&mut self, defs: &Definitions, id: usize) -> Result<()> {
+        let def = &defs.structs[&id];
+        let name = def.qualified_name();
+        if self.structs.contains_key(&name) {
+            return Ok(())
+        }
+
+        let encode_type = defs.encode_type(id)?;
+        let ident = def.ident();
+        // Reserve the entry first, so that recursive structs are only added once.
+        self.structs.insert(name.clone(), String::new());
+        let mut values = vec![format!("{ident}_TYPEHASH")];
+        for (member, type_name) in &def.members {
+            match self.encode_value(defs, type_name, &format!("value.{member}")) {
+                Ok(value) => values.push(value),
+                Err(err) => {
+                    self.structs.remove(&name);
+                    return Err(err)
+                }
+            }
+        }
+
+        let symbols = self.imports.entry(def.path.clone()).or_default();
+        if !symbols.iter().any(|symbol| symbol == def.import_name()) {
+            symbols.push(def.import_name().to_string());
+        }
+        self.structs.insert(
+            name.clone(),
+            format!(
+                r#"
+    bytes32 constant {ident}_TYPEHASH = keccak256("{encode_type}");
+
+    function hash({name} memory value) internal pure returns (bytes32) {{
+        return keccak256(abi.encode({}));
+    }}
+"#,
+                values.join(", ")
+            ),
+        );
+        Ok(())
+    }
+
+    /// Returns the expression encoding `value` in a struct hash, per `encodeData`.
+    fn encode_value(
+        &mut self,
+        defs: &Definitions,
+        type_name: &Node,
+        value: &str,
+    ) -> Result<String> {
+        match type_name.node_type {
+            NodeType::ElementaryTypeName => {
+                let name: String = type_name.attribute("name").ok_or_eyre("type has no name")?;
+                Ok(match name.as_str() {
+                    "string" | "bytes" => format!("keccak256(bytes({value}))"),
+                    _ => value.to_string(),
+                })
+            }
+            NodeType::UserDefinedTypeName => {
+                let id: usize = type_name
+                    .attribute("referencedDeclaration")
+                    .ok_or_eyre("type has no referenced declaration")?;
+                if defs.structs.contains_key(&id) {
+                    self.add(defs, id)?;
+                    Ok(format!("hash({value})"))
+                } else {
+                    // Contracts, enums and user-defined value types are ABI-encoded as their
+                    // underlying types.
+                    Ok(value.to_string())
+                }
+            }
+            NodeType::ArrayTypeName => {
+                let base: Node = type_name.attribute("baseType").ok_or_eyre("array has no type")?;
+                let base_name = base.attribute::<String>("name").unwrap_or_default();
+                let is_dynamic = type_name.attribute::<Node>("length").is_none();
+                match base.node_type {
+                    // Packed arrays pad their elements, which matches `encodeData` for static
+                    // elementary types.
+                    NodeType::ElementaryTypeName if !matches!(&*base_name, "string" | "bytes") => {
+                        Ok(format!("keccak256(abi.encodePacked({value}))"))
+                    }
+                    NodeType::ElementaryTypeName if is_dynamic => {
+                        self.add_array_helper(&base_name, "keccak256(bytes(values[i]))");
+                        Ok(format!("hash({value})"))
+                    }
+                    NodeType::UserDefinedTypeName if is_dynamic => {
+                        let id: usize = base
+                            .attribute("referencedDeclaration")
+                            .ok_or_eyre("type has no referenced declaration")?;
+                        let def = defs.structs.get(&id).ok_or_eyre(
+                            "only arrays of structs and elementary types are supported",
+                        )?;
+                        self.add(defs, id)?;
+                        self.add_array_helper(&def.qualified_name(), "hash(values[i])");
+                        Ok(format!("hash({value})"))
+                    }
+                    _ => eyre::bail!(
+                        "only dynamic arrays of structs, strings and bytes are supported"
+                    ),
+                }
+            }
+            node_type => eyre::bail!("{node_type:?} members can't be encoded"),
+        }
+    }
+
+    /// Adds the function hashing an array of `ty`, whose elements are encoded with `element`.
+    fn add_array_helper(&mut self, ty: &str, element: &str) {
+        self.array_helpers.entry(ty.to_string()).or_insert_with(|| {
+            format!(
+                r#"
+    function hash({ty}[] memory values) internal pure returns (bytes32) {{
+        bytes32[] memory hashes = new bytes32[](values.length);
+        for (uint256 i = 0; i < values.length; i++) {{
+            hashes[i] = {element};
+        }}
+        return keccak256(abi.encodePacked(hashes));
+    }}
+"#
+            )
+        });
+    }
+
+    /// Renders the library.
+    fn render(&self) -> String {
+        let mut out = String::new();
+        out.push_str("// Automatically generated by `forge eip712`, do not edit.\n\n");
+        out.push_str("pragma solidity >=0.6.2 <0.9.0;\npragma experimental ABIEncoderV2;\n\n");
+        for (path, symbols) in &self.imports {
+            let path = path.to_string_lossy().replace('\\', "/");
+            writeln!(out, "import {{{}}} from \"{path}\";", symbols.join(", ")).unwrap();
+        }
+        if !self.imports.is_empty() {
+            out.push('\n');
+        }
+
+        out.push_str(
+            r#"library Eip712 {
+    bytes32 constant EIP712_DOMAIN_TYPEHASH =
+        keccak256("EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)");
+
+    function domainSeparator(string memory name, string memory version, uint256 chainId, address verifyingContract)
+        internal
+        pure
+        returns (bytes32)
+    {
+        return keccak256(
+            abi.encode(EIP712_DOMAIN_TYPEHASH, keccak256(bytes(name)), keccak256(bytes(version)), chainId, verifyingContract)
+        );
+    }
+
+    function hashTypedData(bytes32 separator, bytes32 structHash) internal pure returns (bytes32) {
+        return keccak256(abi.encodePacked("\x19\x01", separator, structHash));
+    }
+"#,
+        );
+        for code in self.structs.values().chain(self.array_helpers.values()) {
+            out.push_str(code);
+        }
+        out.push_str("}\n");
+        out
+    }
+}
@@ -1,13 +1,16 @@
+use alloy_primitives::{keccak256, B256};
 use clap::{Parser, ValueHint};
 use eyre::{Context, Result};
 use forge_fmt::{format_to, parse};
-use foundry_cli::utils::{FoundryPathExt, LoadConfig};
+use foundry_cli::utils::{FoundryPathExt, Git, LoadConfig};
 use foundry_common::{fs, term::cli_warn};
 use foundry_compilers::{compilers::solc::SolcLanguage, solc::SOLC_EXTENSIONS};
-use foundry_config::{filter::expand_globs, impl_figment_convert_basic};
+use foundry_config::{filter::expand_globs, impl_figment_convert_basic, Config, FormatterConfig};
 use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
 use similar::{ChangeTag, TextDiff};
 use std::{
+    collections::BTreeMap,
     fmt::{self, Write},
     io,
     io::{Read, Write as _},
@@ -39,6 +42,12 @@ pub struct FmtArgs {
     /// In 'check' and stdin modes, outputs raw formatted code instead of the diff.
     #[arg(long, short)]
     raw: bool,
+
+    /// Only format the Solidity files staged in git.
+    ///
+    /// Useful for pre-commit hooks, e.g. `forge fmt --check --staged`.
+    #[arg(long, conflicts_with = "paths")]
+    staged: bool,
 }
 
 impl_figment_convert_basic!(FmtArgs);
@@ -55,6 +64,19 @@ impl FmtArgs {
 
         let cwd = std::env::current_dir()?;
         let input = match &self.paths[..] {
+            [] if self.staged => {
+                let root = Git::root_of(&config.root.0)?;
+                let staged: Vec<PathBuf> = Git::new(&root)
+                    .staged_files()?
+                    .into_iter()
+                    .filter(|path| path.is_sol() && !ignored.contains(&root.join(path)))
+                    .collect();
+                if staged.is_empty() {
+                    // Nothing staged, nothing to format.
+                    return Ok(())
+                }
+                Input::Staged { root, paths: staged }
+            }
             [] => {
                 // Retrieve the project paths, and filter out the ignored ones.
                 let project_paths: Vec<PathBuf> = config
@@ -94,15 +116,13 @@ impl FmtArgs {
             }
         };
 
-        let format = |source: String, path: Option<&Path>| -> Result<_> {
-            let name = match path {
-                Some(path) => {
-                    path.strip_prefix(&config.root.0).unwrap_or(path).display().to_string()
-                }
-                None => "stdin".to_string(),
-            };
+        // Hashes of the files that are known to be formatted, not used for stdin or raw output.
+        let cache_path = FmtCache::path(&config);
+        let cache = (config.cache && !self.raw && matches!(input, Input::Paths(_)))
+            .then(|| FmtCache::read(&cache_path, &config.fmt));
 
-            let parsed = parse(&source).wrap_err_with(|| {
+        let format = |source: &str, name: &str| -> Result<String> {
+            let parsed = parse(source).wrap_err_with(|| {
                 format!("Failed to parse Solidity code for {name}. Leaving source unchanged.")
             })?;
 
@@ -124,24 +144,47 @@ impl FmtArgs {
                      Debug info: {diags:?}"
                 )
             })?;
+            Ok(output)
+        };
 
-            if self.check || path.is_none() {
-                if self.raw {
-                    print!("{output}");
-                }
-
-                let diff = TextDiff::from_lines(&source, &output);
-                if diff.ratio() < 1.0 {
-                    return Ok(Some(format_diff_summary(&name, &diff)))
-                }
-            } else if let Some(path) = path {
-                fs::write(path, output)?;
+        // Returns the diff summary if the source is not formatted, in check and stdin modes.
+        let diff = |source: &str, output: &str, name: &str| {
+            if self.raw {
+                print!("{output}");
             }
-            Ok(None)
+            let diff = TextDiff::from_lines(source, output);
+            (diff.ratio() < 1.0).then(|| format_diff_summary(name, &diff))
         };
 
-        let diffs = match input {
-            Input::Stdin(source) => format(source, None).map(|diff| vec![diff]),
+        let results: Vec<(Option<String>, Option<(PathBuf, B256)>)> = match input {
+            Input::Stdin(source) => {
+                format(&source, "stdin").map(|output| vec![(diff(&source, &output, "stdin"), None)])
+            }
+            Input::Staged { root, paths } => {
+                // The index is locked while staging, so files are formatted one at a time.
+                let git = Git::new(&root);
+                paths
+                    .iter()
+                    .map(|path| {
+                        let source = git.staged_content(path)?;
+                        let name = path.display().to_string();
+                        let output = format(&source, &name)?;
+                        if self.check {
+                            return Ok((diff(&source, &output, &name), None))
+                        }
+
+                        if output != source {
+                            git.stage_content(path, &output)?;
+                            // Keep the working tree in sync, unless it has unstaged changes.
+                            let file = root.join(path);
+                            if fs::read_to_string(&file).is_ok_and(|current| current == source) {
+                                fs::write(&file, &output)?;
+                            }
+                        }
+                        Ok((None, None))
+                    })
+                    .collect()
+            }
             Input::Paths(paths) => {
                 if paths.is_empty() {
                     cli_warn!(
@@ -155,13 +198,35 @@ impl FmtArgs {
                     .par_iter()
                     .map(|path| {
                         let source = fs::read_to_string(path)?;
-                        format(source, Some(path))
+                        let relative = path.strip_prefix(&config.root.0).unwrap_or(path);
+                        if cache.as_ref().is_some_and(|cache| cache.is_formatted(relative, &source))
+                        {
+                            return Ok((None, None))
+                        }
+
+                        let name = relative.display().to_string();
+                        let output = format(&source, &name)?;
+                        if self.check {
+                            if let Some(diff) = diff(&source, &output, &name) {
+                                return Ok((Some(diff), None))
+                            }
+                        } else {
+                            fs::write(path, &output)?;
+                        }
+                        Ok((None, Some((relative.to_path_buf(), keccak256(output)))))
                     })
                     .collect()
             }
         }?;
 
-        let mut diffs = diffs.iter().flatten();
+        if let Some(mut cache) = cache {
+            cache.files.extend(results.iter().filter_map(|(_, formatted)| formatted.clone()));
+            if let Err(err) = cache.write(&cache_path) {
+                warn!("failed to write fmt cache: {err}");
+            }
+        }
+
+        let mut diffs = results.iter().filter_map(|(diff, _)| diff.as_ref());
         if let Some(first) = diffs.next() {
             // This branch is only reachable with stdin or --check
 
@@ -185,12 +250,67 @@ impl FmtArgs {
     }
 }
 
+/// Name of the file storing the hashes of formatted files, in the project's cache directory.
+const FMT_CACHE_FILENAME: &str = "fmt-cache.json";
+
+/// Content hashes of the files that are known to be formatted.
+///
+/// Files whose content didn't change since they were last formatted are skipped. The cache is
+/// invalidated whenever the formatter version or configuration changes.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct FmtCache {
+    /// Hash of the formatter version and configuration the files were formatted with.
+    config: B256,
+    /// Content hashes of the formatted files, keyed by path relative to the project root.
+    files: BTreeMap<PathBuf, B256>,
+}
+
+impl FmtCache {
+    /// Returns the path of the cache file for the given project.
+    fn path(config: &Config) -> PathBuf {
+        config.root.0.join(&config.cache_path).join(FMT_CACHE_FILENAME)
+    }
+
+    /// Reads the cache at the given path, discarding it if it was written with a different
+    /// formatter version or configuration.
+    fn read(path: &Path, fmt: &FormatterConfig) -> Self {
+        let config = Self::config_hash(fmt);
+        fs::read_json_file::<Self>(path)
+            .ok()
+            .filter(|cache| cache.config == config)
+            .unwrap_or_else(|| Self { config, files: BTreeMap::new() })
+    }
+
+    fn config_hash(fmt: &FormatterConfig) -> B256 {
+        let config = serde_json::to_vec(&(env!("CARGO_PKG_VERSION"), fmt)).unwrap_or_default();
+        keccak256(config)
+    }
+
+    /// Returns true if the file at the given path is known to be formatted.
+    fn is_formatted(&self, path: &Path, source: &str) -> bool {
+        self.files.get(path).is_some_and(|hash| *hash == keccak256(source))
+    }
+
+    fn write(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write_json_file(path, self)?;
+        Ok(())
+    }
+}
+
 struct Line(Option<usize>);
 
 #[derive(Debug)]
 enum Input {
     Stdin(String),
     Paths(Vec<PathBuf>),
+    /// Files staged in git, relative to the root of the repository.
+    Staged {
+        root: PathBuf,
+        paths: Vec<PathBuf>,
+    },
 }
 
 impl fmt::Display for Line {
@@ -0,0 +1,428 @@
+use alloy_json_abi::{Function, InternalType, Param, StateMutability};
+use clap::{Parser, ValueHint};
+use eyre::{Result, WrapErr};
+use foundry_cli::{opts::CoreBuildArgs, utils::LoadConfig};
+use foundry_common::{compile::ProjectCompiler, fs};
+use foundry_compilers::{info::ContractInfo, utils::canonicalize, Artifact};
+use std::{
+    collections::BTreeMap,
+    fmt::Write,
+    path::{Path, PathBuf},
+};
+use yansi::Paint;
+
+/// The maximum amount of ether sent along with calls to payable functions.
+const MAX_VALUE: &str = "100 ether";
+
+/// CLI arguments for `forge generate handlers`.
+#[derive(Clone, Debug, Parser)]
+pub struct GenerateHandlersArgs {
+    /// The contract to generate an invariant handler for.
+    ///
+    /// The identifier is in the form `(<path>:)?<contractname>`.
+    pub contract: ContractInfo,
+
+    /// The directory to write the handler to.
+    ///
+    /// Defaults to `handlers` in the test directory.
+    #[arg(long, value_hint = ValueHint::DirPath, value_name = "PATH")]
+    pub out: Option<PathBuf>,
+
+    /// The number of actors the handler calls the contract from.
+    #[arg(long, default_value_t = 3, value_name = "COUNT")]
+    pub actors: usize,
+
+    #[command(flatten)]
+    pub build: CoreBuildArgs,
+}
+
+impl GenerateHandlersArgs {
+    pub fn run(self) -> Result<()> {
+        let Self { mut contract, out, actors, build } = self;
+        if actors == 0 {
+            eyre::bail!("at least one actor is required");
+        }
+
+        let config = build.try_load_config_emit_warnings()?;
+        let project = build.project()?;
+        let root = &config.root.0;
+        let mut compiler = ProjectCompiler::new().quiet(true);
+        if let Some(contract_path) = &mut contract.path {
+            let target_path = canonicalize(root.join(&*contract_path))?;
+            *contract_path = target_path.to_string_lossy().to_string();
+            compiler = compiler.files([target_path]);
+        }
+        let output = compiler.compile(&project)?;
+
+        let (id, artifact) = output
+            .artifact_ids()
+            .find(|(id, _)| {
+                id.name == contract.name &&
+                    contract.path.as_ref().map_or(true, |path| id.source == Path::new(path))
+            })
+            .ok_or_else(|| eyre::eyre!("could not find artifact `{contract}`"))?;
+        let abi = artifact
+            .abi
+            .as_ref()
+            .ok_or_else(|| eyre::eyre!("`{contract}` does not have an ABI"))?;
+        let source = id.source.strip_prefix(root).unwrap_or(&id.source).to_path_buf();
+
+        let out = out.unwrap_or_else(|| config.test.join("handlers"));
+        let out = root.join(out);
+        fs::create_dir_all(&out)?;
+
+        let handler = Handler::new(&contract.name, &source, actors, abi.functions());
+        let handler_path = out.join(format!("{}.sol", handler.name));
+        fs::write(&handler_path, handler.render())?;
+        println!("{} handler: {}", "Generated".green(), handler_path.display());
+        for (signature, reason) in &handler.skipped {
+            println!("{} {signature}: {reason}", "Skipped".yellow());
+        }
+
+        // Only scaffold the invariant test once, it is meant to be edited.
+        let test_path = root.join(&config.test).join(format!("{}.invariant.t.sol", contract.name));
+        let deployable = abi.constructor.as_ref().map_or(true, |c| c.inputs.is_empty()) &&
+            artifact.get_bytecode_bytes().is_some_and(|code| !code.is_empty());
+        if test_path.exists() {
+            println!("{} {}: already exists", "Skipped".yellow(), test_path.display());
+        } else if !deployable {
+            println!(
+                "{} {}: `{}` cannot be deployed without constructor arguments",
+                "Skipped".yellow(),
+                test_path.display(),
+                contract.name
+            );
+        } else {
+            let handler_source = handler_path.strip_prefix(root).unwrap_or(&handler_path);
+            fs::write(&test_path, handler.render_test(handler_source))
+                .wrap_err("failed to write the invariant test")?;
+            println!("{} invariant test: {}", "Generated".green(), test_path.display());
+        }
+
+        Ok(())
+    }
+}
+
+/// An invariant handler for a contract.
+#[derive(Debug)]
+struct Handler {
+    /// The name of the handler contract.
+    name: String,
+    /// The name of the target contract.
+    target: String,
+    /// The path of the target contract, relative to the project root.
+    source: PathBuf,
+    actors: usize,
+    /// The handler functions, by name.
+    functions: BTreeMap<String, HandlerFunction>,
+    /// The signatures of the functions that were skipped, along with the reason.
+    skipped: Vec<(String, &'static str)>,
+}
+
+impl Handler {
+    fn new<'a>(
+        target: &str,
+        source: &Path,
+        actors: usize,
+        functions: impl IntoIterator<Item = &'a Function>,
+    ) -> Self {
+        let mut handler = Self {
+            name: format!("{target}Handler"),
+            target: target.to_string(),
+            source: source.to_path_buf(),
+            actors,
+            functions: BTreeMap::new(),
+            skipped: Vec::new(),
+        };
+
+        let mut overloads = BTreeMap::<&str, usize>::new();
+        for function in functions {
+            if matches!(function.state_mutability, StateMutability::Pure | StateMutability::View) {
+                continue
+            }
+            let Some(inputs) = function.inputs.iter().map(Input::new).collect::<Option<Vec<_>>>()
+            else {
+                handler.skipped.push((function.signature(), "unsupported parameter type"));
+                continue
+            };
+
+            let overload = overloads.entry(&function.name).or_default();
+            let name = match *overload {
+                0 => function.name.clone(),
+                n => format!("{}_{n}", function.name),
+            };
+            *overload += 1;
+
+            handler.functions.insert(
+                name,
+                HandlerFunction {
+                    target: function.name.clone(),
+                    signature: function.signature(),
+                    payable: function.state_mutability == StateMutability::Payable,
+                    inputs,
+                },
+            );
+        }
+        handler
+    }
+
+    /// Renders the Solidity source of the handler.
+    fn render(&self) -> String {
+        let Self { name, target, source, actors, functions, skipped } = self;
+        let mut s = String::new();
+        let _ = writeln!(s, "// SPDX-License-Identifier: UNLICENSED");
+        let _ = writeln!(s, "pragma solidity ^0.8.13;");
+        let _ = writeln!(s);
+        let _ = writeln!(s, "import {{Test}} from \"forge-std/Test.sol\";");
+        let _ = writeln!(s, "import {{{target}}} from \"{}\";", slash_path(source));
+        let _ = writeln!(s);
+        let _ =
+            writeln!(s, "/// @notice Calls `{target}` with bounded inputs from a set of actors.");
+        let _ = writeln!(s, "/// @dev Generated by `forge generate handlers`.");
+        let _ = writeln!(s, "contract {name} is Test {{");
+        let _ = writeln!(s, "    {target} public target;");
+        let _ = writeln!(s);
+        let _ = writeln!(s, "    address[] public actors;");
+        let _ = writeln!(s, "    address internal currentActor;");
+        let _ = writeln!(s);
+        let _ = writeln!(s, "    /// @notice The number of calls made to each function.");
+        let _ = writeln!(s, "    mapping(string => uint256) public calls;");
+        let _ = writeln!(s);
+        let _ = writeln!(s, "    modifier useActor(uint256 actorIndexSeed) {{");
+        let _ = writeln!(
+            s,
+            "        currentActor = actors[bound(actorIndexSeed, 0, actors.length - 1)];"
+        );
+        let _ = writeln!(s, "        vm.startPrank(currentActor);");
+        let _ = writeln!(s, "        _;");
+        let _ = writeln!(s, "        vm.stopPrank();");
+        let _ = writeln!(s, "    }}");
+        let _ = writeln!(s);
+        let _ = writeln!(s, "    modifier countCall(string memory signature) {{");
+        let _ = writeln!(s, "        calls[signature]++;");
+        let _ = writeln!(s, "        _;");
+        let _ = writeln!(s, "    }}");
+        let _ = writeln!(s);
+        let _ = writeln!(s, "    constructor({target} _target) {{");
+        let _ = writeln!(s, "        target = _target;");
+        let _ = writeln!(s, "        for (uint256 i = 0; i < {actors}; i++) {{");
+        let _ = writeln!(
+            s,
+            "            actors.push(makeAddr(string.concat(\"actor\", vm.toString(i))));"
+        );
+        let _ = writeln!(s, "        }}");
+        let _ = writeln!(s, "    }}");
+
+        for (name, function) in functions {
+            let _ = writeln!(s);
+            function.render(&mut s, name);
+        }
+
+        if !skipped.is_empty() {
+            let _ = writeln!(s);
+            let _ = writeln!(s, "    // Skipped functions:");
+            for (signature, reason) in skipped {
+                let _ = writeln!(s, "    // - {signature}: {reason}");
+            }
+        }
+        let _ = writeln!(s, "}}");
+        s
+    }
+
+    /// Renders the Solidity source of an invariant test that targets the handler.
+    fn render_test(&self, handler_source: &Path) -> String {
+        let Self { name, target, source, .. } = self;
+        let mut s = String::new();
+        let _ = writeln!(s, "// SPDX-License-Identifier: UNLICENSED");
+        let _ = writeln!(s, "pragma solidity ^0.8.13;");
+        let _ = writeln!(s);
+        let _ = writeln!(s, "import {{Test}} from \"forge-std/Test.sol\";");
+        let _ = writeln!(s, "import {{{target}}} from \"{}\";", slash_path(source));
+        let _ = writeln!(s, "import {{{name}}} from \"{}\";", slash_path(handler_source));
+        let _ = writeln!(s);
+        let _ = writeln!(s, "contract {target}InvariantTest is Test {{");
+        let _ = writeln!(s, "    {target} public target;");
+        let _ = writeln!(s, "    {name} public handler;");
+        let _ = writeln!(s);
+        let _ = writeln!(s, "    function setUp() public {{");
+        let _ = writeln!(s, "        target = new {target}();");
+        let _ = writeln!(s, "        handler = new {name}(target);");
+        let _ = writeln!(s, "        targetContract(address(handler));");
+        let _ = writeln!(s, "    }}");
+        let _ = writeln!(s);
+        let _ = writeln!(s, "    function invariant_example() public view {{");
+        let _ =
+            writeln!(s, "        // Assert the properties of `target` that must always hold here.");
+        let _ = writeln!(s, "    }}");
+        let _ = writeln!(s, "}}");
+        s
+    }
+}
+
+/// A handler function that forwards a call to the target.
+#[derive(Debug)]
+struct HandlerFunction {
+    /// The name of the target function.
+    target: String,
+    /// The signature of the target function.
+    signature: String,
+    payable: bool,
+    inputs: Vec<Input>,
+}
+
+impl HandlerFunction {
+    fn render(&self, s: &mut String, name: &str) {
+        let Self { target, signature, payable, inputs } = self;
+
+        let mut params = vec!["uint256 actorIndexSeed".to_string()];
+        let mut body = Vec::new();
+        let mut args = Vec::new();
+        for (i, input) in inputs.iter().enumerate() {
+            let arg = if input.name.is_empty() { format!("arg{i}") } else { input.name.clone() };
+            match &input.kind {
+                InputKind::Actor => {
+                    params.push(format!("uint256 {arg}Seed"));
+                    body.push(format!(
+                        "address {arg} = actors[bound({arg}Seed, 0, actors.length - 1)];"
+                    ));
+                }
+                InputKind::Uint(256) => {
+                    params.push(format!("uint256 {arg}"));
+                    body.push(format!("{arg} = bound({arg}, 0, type(uint256).max);"));
+                }
+                InputKind::Uint(bits) => {
+                    params.push(format!("uint{bits} {arg}"));
+                    body.push(format!(
+                        "{arg} = uint{bits}(bound(uint256({arg}), 0, type(uint{bits}).max));"
+                    ));
+                }
+                InputKind::Int(256) => {
+                    params.push(format!("int256 {arg}"));
+                    body.push(format!("{arg} = bound({arg}, type(int256).min, type(int256).max);"));
+                }
+                InputKind::Int(bits) => {
+                    params.push(format!("int{bits} {arg}"));
+                    body.push(format!(
+                        "{arg} = int{bits}(bound(int256({arg}), type(int{bits}).min, type(int{bits}).max));"
+                    ));
+                }
+                InputKind::Other { ty, dynamic } => {
+                    let location = if *dynamic { " memory" } else { "" };
+                    params.push(format!("{ty}{location} {arg}"));
+                }
+            }
+            args.push(arg);
+        }
+
+        let mut call = format!("target.{target}");
+        if *payable {
+            params.push("uint256 msgValue".to_string());
+            body.push(format!("msgValue = bound(msgValue, 0, {MAX_VALUE});"));
+            body.push("vm.deal(currentActor, msgValue);".to_string());
+            call.push_str("{value: msgValue}");
+        }
+        body.push(format!("{call}({});", args.join(", ")));
+
+        let _ = writeln!(
+            s,
+            "    function {name}({}) public useActor(actorIndexSeed) countCall(\"{signature}\") {{",
+            params.join(", ")
+        );
+        for line in body {
+            let _ = writeln!(s, "        {line}");
+        }
+        let _ = writeln!(s, "    }}");
+    }
+}
+
+/// A parameter of a target function.
+#[derive(Debug, PartialEq, Eq)]
+struct Input {
+    name: String,
+    kind: InputKind,
+}
+
+/// How the handler fills in a parameter.
+#[derive(Debug, PartialEq, Eq)]
+enum InputKind {
+    /// One of the actors, picked by an index seed.
+    Actor,
+    /// An unsigned integer of the given size, bounded to the range of its type.
+    Uint(usize),
+    /// A signed integer of the given size, bounded to the range of its type.
+    Int(usize),
+    /// Passed through as is.
+    Other { ty: String, dynamic: bool },
+}
+
+impl Input {
+    /// Returns how to fill in the given parameter, or `None` if its type is not supported, e.g.
+    /// tuples, enums and contracts.
+    fn new(param: &Param) -> Option<Self> {
+        let plain = match &param.internal_type {
+            None | Some(InternalType::AddressPayable(_)) => true,
+            Some(InternalType::Other { contract: None, ty }) => *ty == param.ty,
+            Some(_) => false,
+        };
+        if !plain || param.ty.starts_with("tuple") || param.ty.starts_with("function") {
+            return None
+        }
+
+        let ty = param.ty.as_str();
+        let kind = if ty == "address" {
+            InputKind::Actor
+        } else if let Some(bits) = ty.strip_prefix("uint").and_then(|bits| bits.parse().ok()) {
+            InputKind::Uint(bits)
+        } else if let Some(bits) = ty.strip_prefix("int").and_then(|bits| bits.parse().ok()) {
+            InputKind::Int(bits)
+        } else {
+            let dynamic = ty == "bytes" || ty == "string" || ty.ends_with(']');
+            InputKind::Other { ty: ty.to_string(), dynamic }
+        };
+        Some(Self { name: param.name.clone(), kind })
+    }
+}
+
+/// Formats a path for use in a Solidity import.
+fn slash_path(path: &Path) -> String {
+    path.to_string_lossy().replace('\\', "/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_bounded_handler() {
+        let functions = [
+            Function::parse("function transfer(address to, uint256 value)").unwrap(),
+            Function::parse("function deposit(uint64 amount) payable").unwrap(),
+            Function::parse("function deposit(int8 amount, string memo) payable").unwrap(),
+            Function::parse("function balanceOf(address) view returns (uint256)").unwrap(),
+            Function::parse("function batch((address,uint256)[] calls)").unwrap(),
+        ];
+        let handler = Handler::new("Token", Path::new("src/Token.sol"), 2, &functions);
+        assert_eq!(
+            handler.skipped,
+            [("batch((address,uint256)[])".to_string(), "unsupported parameter type")]
+        );
+
+        let source = handler.render();
+        assert!(source.contains("import {Token} from \"src/Token.sol\";"));
+        assert!(source.contains("contract TokenHandler is Test {"));
+        assert!(source.contains("for (uint256 i = 0; i < 2; i++) {"));
+        assert!(source.contains(
+            "function transfer(uint256 actorIndexSeed, uint256 toSeed, uint256 value) public useActor(actorIndexSeed) countCall(\"transfer(address,uint256)\") {"
+        ));
+        assert!(source.contains("address to = actors[bound(toSeed, 0, actors.length - 1)];"));
+        assert!(source.contains("value = bound(value, 0, type(uint256).max);"));
+        assert!(source.contains("target.transfer(to, value);"));
+        assert!(source.contains("amount = uint64(bound(uint256(amount), 0, type(uint64).max));"));
+        assert!(source.contains("target.deposit{value: msgValue}(amount);"));
+        assert!(source.contains("function deposit_1(uint256 actorIndexSeed, int8 amount, string memory memo, uint256 msgValue)"));
+        assert!(source
+            .contains("amount = int8(bound(int256(amount), type(int8).min, type(int8).max));"));
+        assert!(!source.contains("balanceOf"));
+        assert!(source.contains("// - batch((address,uint256)[]): unsupported parameter type"));
+    }
+}
@@ -4,6 +4,9 @@ use foundry_common::fs;
 use std::path::Path;
 use yansi::Paint;
 
+mod handlers;
+pub use handlers::GenerateHandlersArgs;
+
 /// CLI arguments for `forge generate`.
 #[derive(Debug, Parser)]
 pub struct GenerateArgs {
@@ -15,6 +18,8 @@ pub struct GenerateArgs {
 pub enum GenerateSubcommands {
     /// Scaffolds test file for given contract.
     Test(GenerateTestArgs),
+    /// Scaffolds an invariant handler with bounded inputs and actors for given contract.
+    Handlers(GenerateHandlersArgs),
 }
 
 #[derive(Debug, Parser)]
@@ -32,6 +37,8 @@ impl GenerateTestArgs {
         // Create the test file content.
         let test_content = include_str!("../../../assets/generated/TestTemplate.t.sol");
         let test_content = test_content
+            .replace("{solidity_version}", "^0.8.13")
+            .replace("{contract_path}", &format!("../src/{contract_name}.sol"))
             .replace("{contract_name}", &contract_name)
             .replace("{instance_name}", &instance_name);
 
@@ -1,22 +1,39 @@
+use alloy_primitives::{hex, Address, B256, U256};
+use alloy_provider::Provider;
+use alloy_rpc_types::BlockId;
 use clap::Parser;
 use comfy_table::{presets::ASCII_MARKDOWN, Table};
 use eyre::Result;
-use foundry_cli::opts::{CompilerArgs, CoreBuildArgs};
-use foundry_common::compile::ProjectCompiler;
+use foundry_cli::{
+    opts::{CompilerArgs, CoreBuildArgs, RpcOpts},
+    utils::{self, DecodeOpts, DecodedSlot, LoadConfig, MappingKey, StorageDecoder},
+};
+use foundry_common::{compile::ProjectCompiler, provider::RetryProvider};
 use foundry_compilers::{
     artifacts::{
+        ast::{Ast, Node, NodeType},
         output_selection::{
             BytecodeOutputSelection, ContractOutputSelection, DeployedBytecodeOutputSelection,
             EvmOutputSelection, EwasmOutputSelection,
         },
-        StorageLayout,
+        Libraries, Offsets, StorageLayout,
     },
     info::ContractInfo,
     utils::canonicalize,
+    Artifact, ConfigurableContractArtifact,
+};
+use foundry_config::{figment::Figment, Config};
+use foundry_evm::{
+    executors::TracingExecutor,
+    inspectors::KeccakPreimages,
+    opts::EvmOpts,
+    revm::{interpreter::opcode, primitives::EnvWithHandlerCfg},
+    utils::configure_tx_env,
 };
 use once_cell::sync::Lazy;
 use regex::Regex;
-use std::fmt;
+use serde::Serialize;
+use std::{collections::BTreeMap, fmt};
 
 /// CLI arguments for `forge inspect`.
 #[derive(Clone, Debug, Parser)]
@@ -32,6 +49,46 @@ pub struct InspectArgs {
     #[arg(long)]
     pub pretty: bool,
 
+    /// The address of a deployed instance of the contract to dump the current storage of.
+    ///
+    /// Only supported for the storage layout. The storage is read over RPC and decoded with the
+    /// layout of the local artifact: structs, arrays, strings and bytes are expanded into their
+    /// members, elements and values, and mappings into the entries of the keys given with `--key`
+    /// or hashed by the transactions given with `--tx`.
+    #[arg(long, value_name = "ADDRESS")]
+    pub at: Option<Address>,
+
+    /// The block height to read the storage at.
+    ///
+    /// Can also be the tags earliest, finalized, safe, latest, or pending.
+    #[arg(long, requires = "at")]
+    pub block: Option<BlockId>,
+
+    /// A mapping key to decode the entries of.
+    ///
+    /// Prefix it with the name of a state variable as `<VARIABLE>=<KEY>` to only use it for the
+    /// mappings of that variable.
+    ///
+    /// Can be specified multiple times.
+    #[arg(long = "key", value_name = "KEY", requires = "at")]
+    pub keys: Vec<MappingKey>,
+
+    /// A transaction to find the keys of mappings in.
+    ///
+    /// The transaction is replayed on top of the state of its parent block, and the entries of
+    /// the mappings whose slots it computed are decoded.
+    ///
+    /// Can be specified multiple times.
+    #[arg(long = "tx", value_name = "HASH", requires = "at")]
+    pub txs: Vec<B256>,
+
+    /// The maximum number of elements of an array to decode.
+    #[arg(long, default_value = "16", value_name = "N", requires = "at")]
+    pub max_elements: usize,
+
+    #[command(flatten)]
+    rpc: RpcOpts,
+
     /// All build arguments are supported
     #[command(flatten)]
     build: CoreBuildArgs,
@@ -39,10 +96,15 @@ pub struct InspectArgs {
 
 impl InspectArgs {
     pub fn run(self) -> Result<()> {
-        let Self { mut contract, field, build, pretty } = self;
+        let Self { mut contract, field, build, pretty, at, block, keys, txs, max_elements, rpc } =
+            self;
 
         trace!(target: "forge", ?field, ?contract, "running forge inspect");
 
+        if at.is_some() && field != ContractArtifactField::StorageLayout {
+            eyre::bail!("`--at` is only supported for the storage layout");
+        }
+
         // Map field to ContractOutputSelection
         let mut cos = build.compiler.extra_output;
         if !field.is_default() && !cos.iter().any(|selected| field == *selected) {
@@ -56,9 +118,17 @@ impl InspectArgs {
             build.compiler.optimize
         };
 
+        // Immutables are mapped to their names through the AST.
+        let ast = build.compiler.ast || field == ContractArtifactField::ImmutableReferences;
+
         // Build modified Args
         let modified_build_args = CoreBuildArgs {
-            compiler: CompilerArgs { extra_output: cos, optimize: optimized, ..build.compiler },
+            compiler: CompilerArgs {
+                extra_output: cos,
+                optimize: optimized,
+                ast,
+                ..build.compiler
+            },
             ..build
         };
 
@@ -107,7 +177,28 @@ impl InspectArgs {
                 print_json(&artifact.gas_estimates)?;
             }
             ContractArtifactField::StorageLayout => {
-                print_storage_layout(artifact.storage_layout.as_ref(), pretty)?;
+                if let Some(address) = at {
+                    let layout = artifact
+                        .storage_layout
+                        .as_ref()
+                        .filter(|layout| !layout.storage.is_empty())
+                        .ok_or_else(|| {
+                            eyre::eyre!("The storage layout of `{contract}` is empty")
+                        })?;
+                    let figment = Figment::from(&modified_build_args).merge(rpc);
+                    let opts = DecodeOpts { keys, max_elements, ..Default::default() };
+                    let decoded = utils::block_on(decode_live_storage(
+                        figment, address, block, layout, &txs, opts,
+                    ))?;
+                    let tree = StorageNode::tree(decoded);
+                    if pretty {
+                        tree.iter().for_each(|node| node.print(0));
+                    } else {
+                        print_json(&tree)?;
+                    }
+                } else {
+                    print_storage_layout(artifact.storage_layout.as_ref(), pretty)?;
+                }
             }
             ContractArtifactField::DevDoc => {
                 print_json(&artifact.devdoc)?;
@@ -144,6 +235,54 @@ impl InspectArgs {
                 }
                 print_json(&out)?;
             }
+            ContractArtifactField::MetadataDecoded => {
+                let code = artifact
+                    .get_deployed_bytecode_bytes()
+                    .ok_or_else(|| eyre::eyre!("Could not get deployed bytecode"))?;
+                print_json(&decode_metadata(&code)?)?;
+            }
+            ContractArtifactField::ImmutableReferences => {
+                let references = artifact
+                    .deployed_bytecode
+                    .as_ref()
+                    .map(|code| &code.immutable_references)
+                    .ok_or_else(|| eyre::eyre!("Could not get immutable references"))?;
+                if pretty {
+                    let names = artifact.ast.as_ref().map(immutable_names).unwrap_or_default();
+                    let mut table = Table::new();
+                    table.load_preset(ASCII_MARKDOWN);
+                    table.set_header(["Name", "AST id", "Offsets"]);
+                    for (id, offsets) in references {
+                        table.add_row([
+                            names.get(id).map_or("?", String::as_str),
+                            id,
+                            &format_offsets(offsets),
+                        ]);
+                    }
+                    println!("{table}");
+                } else {
+                    print_json(references)?;
+                }
+            }
+            ContractArtifactField::LinkedLibraries => {
+                let config = modified_build_args.load_config();
+                let libraries = linked_libraries(artifact, &config.parsed_libraries()?);
+                if pretty {
+                    let mut table = Table::new();
+                    table.load_preset(ASCII_MARKDOWN);
+                    table.set_header(["Library", "Address", "Placeholder offsets"]);
+                    for (name, library) in &libraries {
+                        table.add_row([
+                            name.as_str(),
+                            library.address.as_deref().unwrap_or("unlinked"),
+                            &format_offsets(&library.offsets),
+                        ]);
+                    }
+                    println!("{table}");
+                } else {
+                    print_json(&libraries)?;
+                }
+            }
             ContractArtifactField::Events => {
                 let mut out = serde_json::Map::new();
                 if let Some(abi) = &artifact.abi {
@@ -195,6 +334,231 @@ pub fn print_storage_layout(storage_layout: Option<&StorageLayout>, pretty: bool
     Ok(())
 }
 
+/// Reads and decodes the storage of the contract deployed at `address`, also decoding the entries
+/// of the mappings hashed by the given transactions.
+async fn decode_live_storage(
+    figment: Figment,
+    address: Address,
+    block: Option<BlockId>,
+    layout: &StorageLayout,
+    txs: &[B256],
+    mut opts: DecodeOpts,
+) -> Result<Vec<DecodedSlot>> {
+    let evm_opts = figment.extract::<EvmOpts>()?;
+    let config = Config::try_from(figment)?.sanitized();
+    let provider = utils::get_provider(&config)?;
+
+    let code = provider.get_code_at(address).block_id(block.unwrap_or_default()).await?;
+    if code.is_empty() {
+        eyre::bail!("{address} has no deployed code and thus no storage");
+    }
+
+    for &tx_hash in txs {
+        let preimages = replay_preimages(&provider, &config, evm_opts.clone(), tx_hash).await?;
+        opts.preimages.extend(preimages);
+    }
+
+    StorageDecoder::new(&provider, address, block, layout, &opts).decode().await
+}
+
+/// Replays a transaction on top of the state of its parent block, returning the preimages of the
+/// hashes it computed with `KECCAK256`.
+async fn replay_preimages(
+    provider: &RetryProvider,
+    config: &Config,
+    evm_opts: EvmOpts,
+    tx_hash: B256,
+) -> Result<KeccakPreimages> {
+    let tx = provider
+        .get_transaction_by_hash(tx_hash)
+        .await?
+        .ok_or_else(|| eyre::eyre!("transaction {tx_hash} not found"))?;
+    let block_number =
+        tx.block_number.ok_or_else(|| eyre::eyre!("transaction {tx_hash} is still pending"))?;
+
+    let mut config = config.clone();
+    config.fork_block_number = Some(block_number.saturating_sub(1));
+    let (mut env, fork, _) = TracingExecutor::get_fork_material(&config, evm_opts).await?;
+    env.block.number = U256::from(block_number);
+
+    let mut executor = TracingExecutor::new(env.clone(), fork, Some(config.evm_version), false);
+    executor.inspector_mut().collect_keccak_preimages(true);
+    let mut env = EnvWithHandlerCfg::new_with_spec_id(Box::new(env), executor.spec_id());
+    configure_tx_env(&mut env, &tx);
+    let mut result = executor.transact_with_env(env)?;
+    Ok(result.keccak_preimages.take().unwrap_or_default())
+}
+
+/// A value in the decoded storage of a contract, nested by path.
+#[derive(Debug, Default, PartialEq, Eq, Serialize)]
+struct StorageNode {
+    /// The state variable, struct member, or `[key]` of the value.
+    name: String,
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    slot: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    offset: Option<usize>,
+    /// The decoded value, or the length of a dynamic array.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    value: Option<String>,
+    /// The members, elements or entries of the value.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    entries: Vec<StorageNode>,
+}
+
+impl StorageNode {
+    /// Nests the decoded values by their paths.
+    fn tree(decoded: Vec<DecodedSlot>) -> Vec<Self> {
+        let mut root = Self::default();
+        for slot in decoded {
+            let mut node = &mut root;
+            for segment in slot.path {
+                // Values are decoded depth-first, so a parent is always the last entry.
+                if node.entries.last().map_or(true, |entry| entry.name != segment) {
+                    node.entries.push(Self { name: segment, ..Default::default() });
+                }
+                node = node.entries.last_mut().unwrap();
+            }
+            node.label = Some(slot.label);
+            node.slot = Some(utils::format_slot(slot.slot));
+            node.offset = Some(slot.offset);
+            node.value = Some(slot.value);
+        }
+        root.entries
+    }
+
+    /// Prints the value and its entries, indented by depth.
+    fn print(&self, depth: usize) {
+        let indent = "  ".repeat(depth);
+        match (&self.label, &self.value, &self.slot) {
+            (Some(label), Some(value), Some(slot)) => {
+                let offset = self.offset.filter(|&offset| offset != 0);
+                let offset = offset.map(|offset| format!(", offset {offset}")).unwrap_or_default();
+                println!("{indent}{}: {label} = {value} (slot {slot}{offset})", self.name);
+            }
+            _ => println!("{indent}{}", self.name),
+        }
+        for entry in &self.entries {
+            entry.print(depth + 1);
+        }
+    }
+}
+
+/// Decodes the CBOR-encoded metadata appended to the given runtime code.
+///
+/// Hashes are printed as hex, the IPFS hash also as an `ipfs://` link and the compiler version as
+/// `major.minor.patch`.
+fn decode_metadata(code: &[u8]) -> Result<serde_json::Map<String, serde_json::Value>> {
+    // The metadata is followed by its length as a big-endian `u16`.
+    let Some((len, code)) = code.split_last_chunk::<2>().map(|(code, len)| (*len, code)) else {
+        eyre::bail!("The bytecode is too short to contain metadata");
+    };
+    let len = u16::from_be_bytes(len) as usize;
+    let Some(cbor) = code.len().checked_sub(len).map(|start| &code[start..]) else {
+        eyre::bail!("The bytecode does not contain metadata");
+    };
+    let value: ciborium::Value = ciborium::from_reader(cbor)
+        .map_err(|err| eyre::eyre!("Could not decode the metadata: {err}"))?;
+    let ciborium::Value::Map(entries) = value else {
+        eyre::bail!("The metadata is not a CBOR map");
+    };
+
+    let mut out = serde_json::Map::new();
+    for (key, value) in entries {
+        let Some(key) = key.as_text().map(str::to_string) else { continue };
+        let value = match value {
+            ciborium::Value::Bytes(bytes) if key == "solc" && bytes.len() == 3 => {
+                format!("{}.{}.{}", bytes[0], bytes[1], bytes[2]).into()
+            }
+            ciborium::Value::Bytes(bytes) => {
+                if key == "ipfs" {
+                    let link = format!("ipfs://{}", bs58::encode(&bytes).into_string());
+                    out.insert("ipfsLink".to_string(), link.into());
+                }
+                hex::encode_prefixed(bytes).into()
+            }
+            ciborium::Value::Text(text) => text.into(),
+            ciborium::Value::Bool(b) => b.into(),
+            ciborium::Value::Integer(i) => i128::from(i).to_string().into(),
+            value => format!("{value:?}").into(),
+        };
+        out.insert(key, value);
+    }
+    Ok(out)
+}
+
+/// Returns the names of the immutable variables declared in the given source unit, by AST id.
+fn immutable_names(ast: &Ast) -> BTreeMap<String, String> {
+    fn visit(nodes: &[Node], names: &mut BTreeMap<String, String>) {
+        for node in nodes {
+            if node.node_type == NodeType::VariableDeclaration {
+                if let (Some(id), Some(name)) = (node.id, node.attribute::<String>("name")) {
+                    names.insert(id.to_string(), name);
+                }
+            }
+            visit(&node.nodes, names);
+        }
+    }
+
+    let mut names = BTreeMap::new();
+    visit(&ast.nodes, &mut names);
+    names
+}
+
+/// A library used by a contract.
+#[derive(Debug, Serialize)]
+struct LinkedLibrary {
+    /// The configured address of the library, if the contract was linked against it.
+    address: Option<String>,
+    /// The offsets of the placeholders left in the creation code, if not linked.
+    offsets: Vec<Offsets>,
+}
+
+/// Returns the libraries used by a contract, keyed by `<path>:<name>`.
+///
+/// Libraries configured with `libraries` are linked by the compiler and only show up in the code
+/// as `PUSH20 <address>`, all others leave placeholders in the creation code.
+fn linked_libraries(
+    artifact: &ConfigurableContractArtifact,
+    libraries: &Libraries,
+) -> BTreeMap<String, LinkedLibrary> {
+    let mut out = BTreeMap::new();
+    let Some(bytecode) = &artifact.bytecode else { return out };
+
+    for (file, names) in &bytecode.link_references {
+        for (name, offsets) in names {
+            out.insert(
+                format!("{file}:{name}"),
+                LinkedLibrary { address: None, offsets: offsets.clone() },
+            );
+        }
+    }
+
+    if let Some(code) = bytecode.object.as_bytes() {
+        for (file, names) in &libraries.libs {
+            for (name, address) in names {
+                let Ok(address) = address.parse::<Address>() else { continue };
+                let mut push = vec![opcode::PUSH20];
+                push.extend_from_slice(address.as_slice());
+                if code.windows(push.len()).any(|window| window == push) {
+                    out.insert(
+                        format!("{}:{name}", file.display()),
+                        LinkedLibrary { address: Some(address.to_string()), offsets: Vec::new() },
+                    );
+                }
+            }
+        }
+    }
+    out
+}
+
+/// Formats bytecode offsets as `start:length`.
+fn format_offsets(offsets: &[Offsets]) -> String {
+    offsets.iter().map(|o| format!("{}:{}", o.start, o.length)).collect::<Vec<_>>().join(", ")
+}
+
 /// Contract level output selection
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum ContractArtifactField {
@@ -214,6 +578,9 @@ pub enum ContractArtifactField {
     Ewasm,
     Errors,
     Events,
+    MetadataDecoded,
+    ImmutableReferences,
+    LinkedLibraries,
 }
 
 macro_rules! impl_value_enum {
@@ -300,6 +667,12 @@ impl_value_enum! {
         Ewasm             => "ewasm" | "e-wasm",
         Errors            => "errors" | "er",
         Events            => "events" | "ev",
+        MetadataDecoded   => "metadataDecoded" | "metadata-decoded" | "metadata_decoded"
+                             | "metadatadecoded",
+        ImmutableReferences => "immutableReferences" | "immutable-references"
+                             | "immutable_references" | "immutablereferences" | "immutables",
+        LinkedLibraries   => "linkedLibraries" | "linked-libraries" | "linked_libraries"
+                             | "linkedlibraries" | "libraries",
     }
 }
 
@@ -324,6 +697,12 @@ impl From<ContractArtifactField> for ContractOutputSelection {
             Caf::Ewasm => Self::Ewasm(EwasmOutputSelection::All),
             Caf::Errors => Self::Abi,
             Caf::Events => Self::Abi,
+            Caf::MetadataDecoded | Caf::ImmutableReferences => Self::Evm(
+                EvmOutputSelection::DeployedByteCode(DeployedBytecodeOutputSelection::All),
+            ),
+            Caf::LinkedLibraries => {
+                Self::Evm(EvmOutputSelection::ByteCode(BytecodeOutputSelection::All))
+            }
         }
     }
 }
@@ -336,8 +715,11 @@ impl PartialEq<ContractOutputSelection> for ContractArtifactField {
             (self, other),
             (Self::Abi | Self::Events, Cos::Abi) |
                 (Self::Errors, Cos::Abi) |
-                (Self::Bytecode, Cos::Evm(Eos::ByteCode(_))) |
-                (Self::DeployedBytecode, Cos::Evm(Eos::DeployedByteCode(_))) |
+                (Self::Bytecode | Self::LinkedLibraries, Cos::Evm(Eos::ByteCode(_))) |
+                (
+                    Self::DeployedBytecode | Self::MetadataDecoded | Self::ImmutableReferences,
+                    Cos::Evm(Eos::DeployedByteCode(_))
+                ) |
                 (Self::Assembly | Self::AssemblyOptimized, Cos::Evm(Eos::Assembly)) |
                 (Self::MethodIdentifiers, Cos::Evm(Eos::MethodIdentifiers)) |
                 (Self::GasEstimates, Cos::Evm(Eos::GasEstimates)) |
@@ -361,7 +743,14 @@ impl fmt::Display for ContractArtifactField {
 impl ContractArtifactField {
     /// Returns true if this field is generated by default.
     pub const fn is_default(&self) -> bool {
-        matches!(self, Self::Bytecode | Self::DeployedBytecode)
+        matches!(
+            self,
+            Self::Bytecode |
+                Self::DeployedBytecode |
+                Self::MetadataDecoded |
+                Self::ImmutableReferences |
+                Self::LinkedLibraries
+        )
     }
 }
 
@@ -425,4 +814,44 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn nests_decoded_storage() {
+        let decoded = |path: &[&str], value: &str| DecodedSlot {
+            name: path.concat(),
+            path: path.iter().map(|segment| segment.to_string()).collect(),
+            label: "uint256".to_string(),
+            slot: U256::from(1),
+            offset: 0,
+            number_of_bytes: "32".to_string(),
+            value: value.to_string(),
+            hex_value: String::new(),
+            contract: "C".to_string(),
+        };
+        let tree = StorageNode::tree(vec![
+            decoded(&["owner"], "1"),
+            decoded(&["pools"], "2"),
+            decoded(&["pools", "[0]", "fee"], "3"),
+            decoded(&["pools", "[0]", "owner"], "4"),
+            decoded(&["pools", "[1]", "fee"], "5"),
+        ]);
+
+        assert_eq!(tree.len(), 2);
+        assert_eq!(tree[0].value.as_deref(), Some("1"));
+        assert!(tree[0].entries.is_empty());
+
+        let pools = &tree[1];
+        assert_eq!(pools.value.as_deref(), Some("2"));
+        assert_eq!(
+            pools.entries.iter().map(|e| e.name.as_str()).collect::<Vec<_>>(),
+            ["[0]", "[1]"]
+        );
+        assert_eq!(pools.entries[0].label, None);
+        assert_eq!(pools.entries[0].entries.len(), 2);
+        assert_eq!(pools.entries[1].entries[0].value.as_deref(), Some("5"));
+
+        let json = serde_json::to_value(&tree).unwrap();
+        assert_eq!(json[1]["entries"][0]["entries"][1]["name"], "owner");
+        assert!(json[1]["entries"][0].get("type").is_none());
+    }
 }
@@ -0,0 +1,428 @@
+//! Conversion of Hardhat and Truffle configs to `foundry.toml`.
+
+use super::{js::JsValue, MigrationReport};
+use foundry_config::{BasicConfig, Chain, Config};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::path::PathBuf;
+
+/// The settings of a Hardhat or Truffle project that have an equivalent in `foundry.toml`.
+#[derive(Debug, Default, PartialEq)]
+pub struct MigratedConfig {
+    pub src: Option<String>,
+    pub test: Option<String>,
+    pub solc: Option<String>,
+    pub optimizer: Option<bool>,
+    pub optimizer_runs: Option<u64>,
+    pub evm_version: Option<String>,
+    pub via_ir: Option<bool>,
+    /// The RPC endpoints of the networks, by name.
+    pub rpc_endpoints: Vec<(String, String)>,
+    /// The Etherscan API key used for all chains.
+    pub etherscan_api_key: Option<String>,
+    /// The Etherscan API keys of specific chains.
+    pub etherscan: Vec<(String, String)>,
+}
+
+impl MigratedConfig {
+    /// Converts the exported object of a `hardhat.config` file.
+    pub fn from_hardhat(config: &JsValue, report: &mut MigrationReport) -> Self {
+        let mut this = Self::default();
+        for (key, value) in config.entries() {
+            match key.as_str() {
+                "solidity" => this.hardhat_solidity(value, report),
+                "paths" => {
+                    for (path, value) in value.entries() {
+                        let dir = match path.as_str() {
+                            "sources" => &mut this.src,
+                            "tests" => &mut this.test,
+                            _ => {
+                                report.skipped(format!("`paths.{path}` has no Foundry equivalent"));
+                                continue
+                            }
+                        };
+                        *dir = relative_path(&format!("paths.{path}"), value, report);
+                    }
+                    if !matches!(value, JsValue::Object(_)) {
+                        report.skipped(format!("`paths` is not a literal object: `{value}`"));
+                    }
+                }
+                "networks" => {
+                    for (name, network) in value.entries() {
+                        if name == "hardhat" {
+                            report.skipped(
+                                "`networks.hardhat` is replaced by `anvil`, pass `--fork-url` to \
+                                 fork a network",
+                            );
+                            continue
+                        }
+                        this.network(name, network.get("url"), network, report);
+                    }
+                }
+                "etherscan" => this.etherscan(value.get("apiKey"), report),
+                _ => report.skipped(format!("`{key}` has no Foundry equivalent")),
+            }
+        }
+        this
+    }
+
+    /// Converts the exported object of a `truffle-config` file.
+    pub fn from_truffle(config: &JsValue, report: &mut MigrationReport) -> Self {
+        let mut this = Self::default();
+        for (key, value) in config.entries() {
+            match key.as_str() {
+                "compilers" => match value.get("solc") {
+                    Some(solc) => {
+                        match solc.get("version").map(|v| (v, v.as_str())) {
+                            // `native` and `pragma` pick the compiler of each file.
+                            Some((_, Some("native" | "pragma"))) | None => {}
+                            Some((version, _)) => this.solc = solc_version(version, report),
+                        }
+                        if let Some(settings) = solc.get("settings") {
+                            this.solc_settings("compilers.solc.settings", settings, report);
+                        }
+                    }
+                    None => report.skipped("only the `solc` compiler of `compilers` is converted"),
+                },
+                // Settings of Truffle versions prior to 5.
+                "solc" => this.solc_settings("solc", value, report),
+                "contracts_directory" => this.src = relative_path(key, value, report),
+                "test_directory" => this.test = relative_path(key, value, report),
+                "networks" => {
+                    for (name, network) in value.entries() {
+                        if network.get("provider").is_some() {
+                            report.skipped(format!(
+                                "`networks.{name}` uses a provider, add its RPC URL to \
+                                 `rpc_endpoints` by hand"
+                            ));
+                            continue
+                        }
+                        let url = match (network.get("host"), network.get("port")) {
+                            (host, Some(port)) if network.get("url").is_none() => {
+                                let host = host.and_then(JsValue::as_str).unwrap_or("127.0.0.1");
+                                port.as_u64()
+                                    .map(|port| JsValue::String(format!("http://{host}:{port}")))
+                            }
+                            _ => network.get("url").cloned(),
+                        };
+                        this.network(name, url.as_ref(), network, report);
+                    }
+                }
+                "api_keys" => this.etherscan(value.get("etherscan"), report),
+                _ => report.skipped(format!("`{key}` has no Foundry equivalent")),
+            }
+        }
+        this
+    }
+
+    fn hardhat_solidity(&mut self, value: &JsValue, report: &mut MigrationReport) {
+        match value {
+            JsValue::String(_) => self.solc = solc_version(value, report),
+            JsValue::Object(_) => {
+                if let Some(JsValue::Array(compilers)) = value.get("compilers") {
+                    let Some(first) = compilers.first() else { return };
+                    if compilers.len() > 1 {
+                        // Foundry picks the compiler of each file from its pragma.
+                        report.skipped(format!(
+                            "`solidity.compilers` lists {} compilers, only the settings of the \
+                             first one are converted and the compiler version is auto-detected",
+                            compilers.len()
+                        ));
+                    } else if let Some(version) = first.get("version") {
+                        self.solc = solc_version(version, report);
+                    }
+                    if let Some(settings) = first.get("settings") {
+                        self.solc_settings("solidity.compilers[0].settings", settings, report);
+                    }
+                } else {
+                    if let Some(version) = value.get("version") {
+                        self.solc = solc_version(version, report);
+                    }
+                    if let Some(settings) = value.get("settings") {
+                        self.solc_settings("solidity.settings", settings, report);
+                    }
+                }
+                if value.get("overrides").is_some() {
+                    report.skipped(
+                        "`solidity.overrides` is not converted, Foundry compiles all files with the \
+                         same settings",
+                    );
+                }
+            }
+            _ => report.skipped(format!("`solidity` is not a literal: `{value}`")),
+        }
+    }
+
+    fn solc_settings(&mut self, path: &str, settings: &JsValue, report: &mut MigrationReport) {
+        for (key, value) in settings.entries() {
+            match key.as_str() {
+                "optimizer" => {
+                    self.optimizer = value.get("enabled").and_then(JsValue::as_bool);
+                    self.optimizer_runs = value.get("runs").and_then(JsValue::as_u64);
+                    if value.get("details").is_some() {
+                        report.skipped(format!(
+                            "`{path}.optimizer.details` is not converted, set `optimizer_details` \
+                             by hand"
+                        ));
+                    }
+                }
+                "evmVersion" => self.evm_version = value.as_str().map(str::to_string),
+                "viaIR" => self.via_ir = value.as_bool(),
+                // Foundry selects the outputs it needs.
+                "outputSelection" => {}
+                _ => report.skipped(format!("`{path}.{key}` is not converted")),
+            }
+        }
+    }
+
+    fn network(
+        &mut self,
+        name: &str,
+        url: Option<&JsValue>,
+        network: &JsValue,
+        report: &mut MigrationReport,
+    ) {
+        match url.map(|url| (url, env_value(url))) {
+            Some((_, Some(url))) => self.rpc_endpoints.push((name.to_string(), url)),
+            Some((url, None)) => {
+                report.skipped(format!("`networks.{name}.url` can't be evaluated: `{url}`"))
+            }
+            None => report.skipped(format!("`networks.{name}` has no URL")),
+        }
+        if network.get("accounts").is_some() || network.get("mnemonic").is_some() {
+            report.skipped(format!(
+                "the accounts of `networks.{name}` are not converted, pass them with `--account` \
+                 or `--private-key` instead"
+            ));
+        }
+    }
+
+    fn etherscan(&mut self, api_key: Option<&JsValue>, report: &mut MigrationReport) {
+        let Some(api_key) = api_key else { return };
+        if let JsValue::Object(keys) = api_key {
+            for (chain, key) in keys {
+                match (chain.parse::<Chain>(), env_value(key)) {
+                    (Ok(_), Some(key)) => self.etherscan.push((chain.clone(), key)),
+                    _ => report
+                        .skipped(format!("the Etherscan API key of `{chain}` is not converted")),
+                }
+            }
+        } else if let Some(key) = env_value(api_key) {
+            self.etherscan_api_key = Some(key);
+        } else {
+            report.skipped(format!("the Etherscan API key can't be evaluated: `{api_key}`"));
+        }
+    }
+
+    /// Renders the `foundry.toml` of the project.
+    pub fn to_toml(&self, remappings: &[String]) -> eyre::Result<String> {
+        let basic = BasicConfig {
+            profile: Config::DEFAULT_PROFILE,
+            src: self.src.as_deref().unwrap_or("contracts").into(),
+            out: "out".into(),
+            libs: vec!["node_modules".into(), "lib".into()],
+            remappings: Vec::new(),
+        };
+        let mut doc = basic.to_string_pretty()?.parse::<toml_edit::DocumentMut>()?;
+        let profile = &mut doc[Config::PROFILE_SECTION][Config::DEFAULT_PROFILE.as_str().as_str()];
+        profile["test"] = toml_edit::value(self.test.as_deref().unwrap_or("test"));
+        // Hardhat and Truffle use `cache` for their own cache.
+        profile["cache_path"] = toml_edit::value("cache_forge");
+        if !remappings.is_empty() {
+            profile["remappings"] = toml_edit::value(
+                remappings.iter().map(String::as_str).collect::<toml_edit::Array>(),
+            );
+        }
+        if let Some(solc) = &self.solc {
+            profile["solc_version"] = toml_edit::value(solc.as_str());
+        }
+        if let Some(optimizer) = self.optimizer {
+            profile["optimizer"] = toml_edit::value(optimizer);
+        }
+        if let Some(runs) = self.optimizer_runs {
+            profile["optimizer_runs"] = toml_edit::value(runs as i64);
+        }
+        if let Some(evm_version) = &self.evm_version {
+            profile["evm_version"] = toml_edit::value(evm_version.as_str());
+        }
+        if let Some(via_ir) = self.via_ir {
+            profile["via_ir"] = toml_edit::value(via_ir);
+        }
+        if let Some(key) = &self.etherscan_api_key {
+            profile["etherscan_api_key"] = toml_edit::value(key.as_str());
+        }
+
+        if !self.rpc_endpoints.is_empty() {
+            let mut endpoints = toml_edit::Table::new();
+            for (name, url) in &self.rpc_endpoints {
+                endpoints[name.as_str()] = toml_edit::value(url.as_str());
+            }
+            doc["rpc_endpoints"] = toml_edit::Item::Table(endpoints);
+        }
+        if !self.etherscan.is_empty() {
+            let mut etherscan = toml_edit::Table::new();
+            for (chain, key) in &self.etherscan {
+                let mut entry = toml_edit::InlineTable::new();
+                entry.insert("key", key.as_str().into());
+                etherscan[chain.as_str()] = toml_edit::value(entry);
+            }
+            doc["etherscan"] = toml_edit::Item::Table(etherscan);
+        }
+        Ok(doc.to_string())
+    }
+}
+
+/// Returns the relative directory of a `paths` entry.
+fn relative_path(key: &str, value: &JsValue, report: &mut MigrationReport) -> Option<String> {
+    let Some(path) = value.as_str() else {
+        report.skipped(format!("`{key}` can't be evaluated: `{value}`"));
+        return None
+    };
+    let path = PathBuf::from(path.trim_start_matches("./"));
+    if path.is_absolute() {
+        report.skipped(format!("`{key}` is an absolute path: `{}`", path.display()));
+        return None
+    }
+    Some(path.to_string_lossy().trim_end_matches('/').to_string())
+}
+
+fn solc_version(value: &JsValue, report: &mut MigrationReport) -> Option<String> {
+    match value.as_str().map(|v| (v, v.parse::<semver::Version>())) {
+        Some((version, Ok(_))) => Some(version.to_string()),
+        _ => {
+            report.skipped(format!("the compiler version `{value}` is not a release version"));
+            None
+        }
+    }
+}
+
+/// Returns the value of a string, converting references to environment variables to the
+/// `${VAR}` syntax of `foundry.toml`, e.g. `process.env.RPC_URL || ""` or
+/// `` `https://rpc.io/${vars.get("KEY")}` ``.
+pub fn env_value(value: &JsValue) -> Option<String> {
+    static ENV_VAR: Lazy<Regex> = Lazy::new(|| {
+        Regex::new(
+            r#"^(?:process\.env\.([A-Za-z_][A-Za-z0-9_]*)|process\.env\[["']([^"']+)["']\]|vars\.get\(["']([^"']+)["'][^)]*\))(?:\s*(?:\|\||\?\?).*)?$"#,
+        )
+        .unwrap()
+    });
+    static SUBSTITUTION: Lazy<Regex> = Lazy::new(|| Regex::new(r"\$\{([^}]*)\}").unwrap());
+
+    let env_var = |expr: &str| {
+        let caps = ENV_VAR.captures(expr.trim())?;
+        let name = caps.get(1).or(caps.get(2)).or(caps.get(3))?.as_str();
+        Some(format!("${{{name}}}"))
+    };
+
+    match value {
+        JsValue::String(s) => Some(s.clone()),
+        JsValue::Expr(expr) => {
+            if let Some(template) = expr.strip_prefix('`').and_then(|e| e.strip_suffix('`')) {
+                let mut out = String::new();
+                let mut last = 0;
+                for caps in SUBSTITUTION.captures_iter(template) {
+                    let all = caps.get(0).unwrap();
+                    out.push_str(&template[last..all.start()]);
+                    out.push_str(&env_var(&caps[1])?);
+                    last = all.end();
+                }
+                out.push_str(&template[last..]);
+                Some(out)
+            } else {
+                env_var(expr)
+            }
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cmd::migrate::js::parse_exported_object;
+
+    #[test]
+    fn converts_env_values() {
+        let expr = |s: &str| JsValue::Expr(s.to_string());
+        assert_eq!(env_value(&expr("process.env.RPC_URL")).unwrap(), "${RPC_URL}");
+        assert_eq!(env_value(&expr(r#"process.env.RPC_URL || """#)).unwrap(), "${RPC_URL}");
+        assert_eq!(env_value(&expr(r#"process.env["RPC_URL"]"#)).unwrap(), "${RPC_URL}");
+        assert_eq!(env_value(&expr(r#"vars.get("RPC_URL", "")"#)).unwrap(), "${RPC_URL}");
+        assert_eq!(
+            env_value(&expr("`https://eth.llamarpc.com/${process.env.KEY}`")).unwrap(),
+            "https://eth.llamarpc.com/${KEY}"
+        );
+        assert_eq!(env_value(&expr("`https://eth.llamarpc.com/${key}`")), None);
+        assert_eq!(env_value(&expr("getUrl()")), None);
+    }
+
+    #[test]
+    fn converts_hardhat_config() {
+        let src = r#"
+module.exports = {
+  solidity: {
+    compilers: [{ version: "0.8.20", settings: { optimizer: { enabled: true, runs: 200 }, evmVersion: "shanghai" } }],
+  },
+  paths: { sources: "./src/contracts", tests: "./test", artifacts: "./build" },
+  networks: {
+    hardhat: { forking: { url: process.env.MAINNET_RPC_URL } },
+    mainnet: { url: process.env.MAINNET_RPC_URL || "", accounts: [process.env.PRIVATE_KEY] },
+  },
+  etherscan: { apiKey: { mainnet: process.env.ETHERSCAN_KEY, someL3: "abc" } },
+  mocha: { timeout: 40000 },
+};
+"#;
+        let mut report = MigrationReport::default();
+        let config =
+            MigratedConfig::from_hardhat(&parse_exported_object(src).unwrap(), &mut report);
+        assert_eq!(
+            config,
+            MigratedConfig {
+                src: Some("src/contracts".to_string()),
+                test: Some("test".to_string()),
+                solc: Some("0.8.20".to_string()),
+                optimizer: Some(true),
+                optimizer_runs: Some(200),
+                evm_version: Some("shanghai".to_string()),
+                rpc_endpoints: vec![("mainnet".to_string(), "${MAINNET_RPC_URL}".to_string())],
+                etherscan: vec![("mainnet".to_string(), "${ETHERSCAN_KEY}".to_string())],
+                ..Default::default()
+            }
+        );
+        assert_eq!(report.skipped.len(), 5, "{:?}", report.skipped);
+
+        let toml = config.to_toml(&["@openzeppelin/=node_modules/@openzeppelin/".into()]).unwrap();
+        let parsed = toml::from_str::<toml::Table>(&toml).unwrap();
+        let profile = &parsed["profile"]["default"];
+        assert_eq!(profile["src"].as_str(), Some("src/contracts"));
+        assert_eq!(profile["optimizer_runs"].as_integer(), Some(200));
+        assert_eq!(profile["cache_path"].as_str(), Some("cache_forge"));
+        assert!(toml.contains(r#"mainnet = "${MAINNET_RPC_URL}""#), "{toml}");
+        assert!(toml.contains(r#"mainnet = { key = "${ETHERSCAN_KEY}" }"#), "{toml}");
+    }
+
+    #[test]
+    fn converts_truffle_config() {
+        let src = r#"
+module.exports = {
+  contracts_directory: "./contracts",
+  networks: {
+    development: { host: "127.0.0.1", port: 8545, network_id: "*" },
+    sepolia: { provider: () => new HDWalletProvider(mnemonic, url), network_id: 11155111 },
+  },
+  compilers: { solc: { version: "0.8.19", settings: { optimizer: { enabled: false, runs: 200 } } } },
+};
+"#;
+        let mut report = MigrationReport::default();
+        let config =
+            MigratedConfig::from_truffle(&parse_exported_object(src).unwrap(), &mut report);
+        assert_eq!(config.src.as_deref(), Some("contracts"));
+        assert_eq!(config.solc.as_deref(), Some("0.8.19"));
+        assert_eq!(config.optimizer, Some(false));
+        assert_eq!(
+            config.rpc_endpoints,
+            vec![("development".to_string(), "http://127.0.0.1:8545".to_string())]
+        );
+        assert_eq!(report.skipped.len(), 1, "{:?}", report.skipped);
+    }
+}
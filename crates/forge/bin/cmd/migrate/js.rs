@@ -0,0 +1,454 @@
+//! A lenient parser for the config files of JavaScript projects.
+//!
+//! Hardhat and Truffle configs are programs, not data. Only the literal parts of the exported
+//! object are parsed, any other expression is kept as its source text, see [`JsValue::Expr`].
+
+use regex::Regex;
+use std::fmt;
+
+/// A value of the exported config object.
+#[derive(Clone, Debug, PartialEq)]
+pub enum JsValue {
+    Object(Vec<(String, JsValue)>),
+    Array(Vec<JsValue>),
+    String(String),
+    Number(f64),
+    Bool(bool),
+    /// Any other expression, as its source text.
+    Expr(String),
+}
+
+impl JsValue {
+    /// Returns the value of the given key if this is an object.
+    pub fn get(&self, key: &str) -> Option<&Self> {
+        self.entries().iter().rev().find(|(k, _)| k == key).map(|(_, v)| v)
+    }
+
+    /// Returns the entries of the object, or nothing if this isn't an object.
+    pub fn entries(&self) -> &[(String, Self)] {
+        match self {
+            Self::Object(entries) => entries,
+            _ => &[],
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Self::Bool(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    pub fn as_u64(&self) -> Option<u64> {
+        match self {
+            Self::Number(n) if *n >= 0.0 && n.fract() == 0.0 => Some(*n as u64),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for JsValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Object(_) => f.write_str("{ .. }"),
+            Self::Array(_) => f.write_str("[ .. ]"),
+            Self::String(s) => write!(f, "{s:?}"),
+            Self::Number(n) => write!(f, "{n}"),
+            Self::Bool(b) => write!(f, "{b}"),
+            Self::Expr(expr) => f.write_str(expr),
+        }
+    }
+}
+
+/// Parses the object exported by a config file, i.e. by `module.exports = ..` or
+/// `export default ..`, following the variable it's assigned from if needed.
+pub fn parse_exported_object(src: &str) -> Option<JsValue> {
+    let src = strip_comments(src);
+    let exported = ["module.exports", "export default"].into_iter().find_map(|marker| {
+        let rest = src[src.find(marker)? + marker.len()..].trim_start();
+        Some(rest.strip_prefix('=').unwrap_or(rest).trim_start())
+    })?;
+
+    let object = if exported.starts_with('{') {
+        exported
+    } else {
+        let name: String = exported.chars().take_while(|c| is_ident_char(*c)).collect();
+        if name.is_empty() {
+            return None
+        }
+        let name = regex::escape(&name);
+        let declaration =
+            Regex::new(&format!(r"(?:const|let|var)\s+{name}\s*(?::[^=]+)?=\s*\{{")).unwrap();
+        let start = declaration.find(&src)?.end() - 1;
+        &src[start..]
+    };
+    // Parse the object itself, ignoring e.g. a trailing `satisfies HardhatUserConfig`.
+    Some(Parser { src: object, pos: 0 }.object())
+}
+
+/// Replaces the comments in the given source with whitespace, leaving strings untouched.
+fn strip_comments(src: &str) -> String {
+    let mut out = String::with_capacity(src.len());
+    let mut chars = src.chars().peekable();
+    let mut quote = None;
+    while let Some(c) = chars.next() {
+        if let Some(q) = quote {
+            out.push(c);
+            if c == '\\' {
+                out.extend(chars.next());
+            } else if c == q {
+                quote = None;
+            }
+            continue
+        }
+        match (c, chars.peek()) {
+            ('/', Some('/')) => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        out.push('\n');
+                        break
+                    }
+                }
+            }
+            ('/', Some('*')) => {
+                chars.next();
+                let mut prev = ' ';
+                for c in chars.by_ref() {
+                    if prev == '*' && c == '/' {
+                        break
+                    }
+                    prev = c;
+                }
+                out.push(' ');
+            }
+            ('"' | '\'' | '`', _) => {
+                quote = Some(c);
+                out.push(c);
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+fn is_ident_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '$'
+}
+
+struct Parser<'a> {
+    src: &'a str,
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<char> {
+        self.src[self.pos..].chars().next()
+    }
+
+    fn bump(&mut self) {
+        if let Some(c) = self.peek() {
+            self.pos += c.len_utf8();
+        }
+    }
+
+    fn skip_ws(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.bump();
+        }
+    }
+
+    /// Returns true if the next character ends a value.
+    fn at_value_end(&mut self) -> bool {
+        self.skip_ws();
+        matches!(self.peek(), None | Some(',' | '}' | ']' | ';' | ')'))
+    }
+
+    fn value(&mut self) -> JsValue {
+        self.skip_ws();
+        let start = self.pos;
+        let literal = match self.peek() {
+            Some('{') => Some(self.object()),
+            Some('[') => Some(self.array()),
+            Some(q @ ('"' | '\'' | '`')) => self.string(q).map(JsValue::String),
+            Some(c) if c.is_ascii_digit() || c == '-' || c == '.' => self.number(),
+            Some(c) if is_ident_char(c) => match self.ident() {
+                "true" => Some(JsValue::Bool(true)),
+                "false" => Some(JsValue::Bool(false)),
+                _ => None,
+            },
+            _ => None,
+        };
+        match literal {
+            Some(value) if self.at_value_end() => value,
+            _ => {
+                self.pos = start;
+                JsValue::Expr(self.expr())
+            }
+        }
+    }
+
+    fn object(&mut self) -> JsValue {
+        self.bump();
+        let mut entries = Vec::new();
+        loop {
+            self.skip_ws();
+            let key = match self.peek() {
+                None => break,
+                Some('}') => {
+                    self.bump();
+                    break
+                }
+                Some(',') => {
+                    self.bump();
+                    continue
+                }
+                Some(q @ ('"' | '\'' | '`')) => self.string(q).unwrap_or_default(),
+                Some('[') => {
+                    let start = self.pos;
+                    self.skip_balanced();
+                    self.src[start..self.pos].to_string()
+                }
+                Some('.') if self.src[self.pos..].starts_with("...") => {
+                    self.pos += 3;
+                    entries.push(("...".to_string(), JsValue::Expr(self.expr())));
+                    continue
+                }
+                Some(c) if is_ident_char(c) => self.ident().to_string(),
+                Some(_) => {
+                    // Not something we understand, skip to the next entry.
+                    let start = self.pos;
+                    self.expr();
+                    if self.pos == start {
+                        self.bump();
+                    }
+                    continue
+                }
+            };
+
+            self.skip_ws();
+            let value = match self.peek() {
+                Some(':') => {
+                    self.bump();
+                    self.value()
+                }
+                // A method.
+                Some('(') => {
+                    let start = self.pos;
+                    self.skip_balanced();
+                    self.skip_ws();
+                    if self.peek() == Some('{') {
+                        self.skip_balanced();
+                    }
+                    JsValue::Expr(format!("{key}{}", &self.src[start..self.pos]))
+                }
+                // A shorthand property.
+                Some(',' | '}') => JsValue::Expr(key.clone()),
+                _ => JsValue::Expr(self.expr()),
+            };
+            entries.push((key, value));
+        }
+        JsValue::Object(entries)
+    }
+
+    fn array(&mut self) -> JsValue {
+        self.bump();
+        let mut values = Vec::new();
+        loop {
+            self.skip_ws();
+            match self.peek() {
+                None => break,
+                Some(']') => {
+                    self.bump();
+                    break
+                }
+                Some(',') => self.bump(),
+                Some(_) => {
+                    let start = self.pos;
+                    values.push(self.value());
+                    if self.pos == start {
+                        self.bump();
+                    }
+                }
+            }
+        }
+        JsValue::Array(values)
+    }
+
+    /// Parses a string literal, returning `None` for template literals with substitutions.
+    fn string(&mut self, quote: char) -> Option<String> {
+        self.bump();
+        let mut s = String::new();
+        let mut template = false;
+        while let Some(c) = self.peek() {
+            self.bump();
+            match c {
+                '\\' => {
+                    let escaped = self.peek()?;
+                    self.bump();
+                    s.push(match escaped {
+                        'n' => '\n',
+                        't' => '\t',
+                        c => c,
+                    });
+                }
+                c if c == quote => return (!template).then_some(s),
+                '$' if quote == '`' && self.peek() == Some('{') => {
+                    template = true;
+                    s.push(c);
+                }
+                c => s.push(c),
+            }
+        }
+        None
+    }
+
+    fn number(&mut self) -> Option<JsValue> {
+        let start = self.pos;
+        while self.peek().is_some_and(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '.' | '_'))
+        {
+            self.bump();
+        }
+        let number = self.src[start..self.pos].replace('_', "");
+        number.parse().ok().map(JsValue::Number)
+    }
+
+    fn ident(&mut self) -> &str {
+        let start = self.pos;
+        while self.peek().is_some_and(is_ident_char) {
+            self.bump();
+        }
+        &self.src[start..self.pos]
+    }
+
+    /// Skips a bracketed group starting at the current position, including nested groups and
+    /// strings.
+    fn skip_balanced(&mut self) {
+        let mut depth = 0usize;
+        while let Some(c) = self.peek() {
+            match c {
+                '"' | '\'' | '`' => {
+                    self.string(c);
+                    if depth == 0 {
+                        return
+                    }
+                    continue
+                }
+                '(' | '[' | '{' => depth += 1,
+                ')' | ']' | '}' => {
+                    depth = depth.saturating_sub(1);
+                    if depth == 0 {
+                        self.bump();
+                        return
+                    }
+                }
+                _ => {}
+            }
+            self.bump();
+        }
+    }
+
+    /// Returns the source text of the expression at the current position, up to the end of the
+    /// value it's in.
+    fn expr(&mut self) -> String {
+        let start = self.pos;
+        while let Some(c) = self.peek() {
+            match c {
+                ',' | '}' | ']' | ';' | ')' => break,
+                '(' | '[' | '{' | '"' | '\'' | '`' => self.skip_balanced(),
+                _ => self.bump(),
+            }
+        }
+        self.src[start..self.pos].trim().to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_exported_config() {
+        let src = r#"
+require("@nomicfoundation/hardhat-toolbox");
+// module.exports = { ignored: true };
+
+/** @type import('hardhat/config').HardhatUserConfig */
+const config: HardhatUserConfig = {
+  solidity: {
+    version: "0.8.24",
+    settings: { optimizer: { enabled: true, runs: 1_000 }, viaIR: false },
+  },
+  networks: {
+    sepolia: {
+      url: `https://eth-sepolia.g.alchemy.com/v2/${process.env.ALCHEMY_KEY}`,
+      accounts: [PRIVATE_KEY],
+    },
+    local: { url: 'http://127.0.0.1:8545' },
+  },
+  gasReporter: { enabled: process.env.REPORT_GAS !== undefined },
+  paths,
+};
+
+export default config;
+"#;
+        let config = parse_exported_object(src).unwrap();
+        let solidity = config.get("solidity").unwrap();
+        assert_eq!(solidity.get("version").unwrap().as_str(), Some("0.8.24"));
+        let optimizer = solidity.get("settings").unwrap().get("optimizer").unwrap();
+        assert_eq!(optimizer.get("enabled").unwrap().as_bool(), Some(true));
+        assert_eq!(optimizer.get("runs").unwrap().as_u64(), Some(1000));
+
+        let networks = config.get("networks").unwrap();
+        assert_eq!(
+            networks.get("sepolia").unwrap().get("url").unwrap(),
+            &JsValue::Expr(
+                "`https://eth-sepolia.g.alchemy.com/v2/${process.env.ALCHEMY_KEY}`".to_string()
+            )
+        );
+        assert_eq!(
+            networks.get("local").unwrap().get("url").unwrap().as_str(),
+            Some("http://127.0.0.1:8545")
+        );
+        assert_eq!(
+            config.get("gasReporter").unwrap().get("enabled").unwrap(),
+            &JsValue::Expr("process.env.REPORT_GAS !== undefined".to_string())
+        );
+        assert_eq!(config.get("paths").unwrap(), &JsValue::Expr("paths".to_string()));
+        assert!(config.get("ignored").is_none());
+    }
+
+    #[test]
+    fn parses_truffle_config() {
+        let src = r#"
+const HDWalletProvider = require('@truffle/hdwallet-provider');
+module.exports = {
+  networks: {
+    development: { host: "127.0.0.1", port: 7545, network_id: "*" },
+    goerli: {
+      provider: () => new HDWalletProvider(mnemonic, `https://goerli.infura.io/v3/${key}`),
+      network_id: 5,
+    },
+  },
+  compilers: { solc: { version: "0.8.19", settings: { evmVersion: "paris" } } },
+};
+"#;
+        let config = parse_exported_object(src).unwrap();
+        let development = config.get("networks").unwrap().get("development").unwrap();
+        assert_eq!(development.get("port").unwrap().as_u64(), Some(7545));
+        let goerli = config.get("networks").unwrap().get("goerli").unwrap();
+        assert!(matches!(goerli.get("provider").unwrap(), JsValue::Expr(_)));
+        assert_eq!(goerli.get("network_id").unwrap().as_u64(), Some(5));
+        let solc = config.get("compilers").unwrap().get("solc").unwrap();
+        assert_eq!(
+            solc.get("settings").unwrap().get("evmVersion").unwrap().as_str(),
+            Some("paris")
+        );
+    }
+}
@@ -0,0 +1,320 @@
+use clap::{Parser, ValueHint};
+use eyre::{eyre, Result};
+use foundry_common::fs;
+use foundry_compilers::{solc::SOLC_EXTENSIONS, utils::source_files_iter};
+use foundry_config::Config;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::{
+    collections::BTreeSet,
+    fmt,
+    path::{Path, PathBuf},
+};
+use yansi::Paint;
+
+mod config;
+use config::MigratedConfig;
+
+mod js;
+
+/// CLI arguments for `forge migrate`.
+///
+/// Converts a Hardhat or Truffle project in place:
+/// 1. Convert the solc settings, paths, networks and Etherscan keys of its config to a
+///    `foundry.toml`.
+/// 2. Add remappings for the packages its sources import from `node_modules`.
+/// 3. Rewrite the imports that reach into `node_modules` by relative path to use the remappings.
+/// 4. Generate a test stub for each of its contracts.
+/// 5. Report what could not be converted.
+#[derive(Clone, Debug, Parser)]
+pub struct MigrateArgs {
+    /// The root directory of the project to migrate.
+    #[arg(value_hint = ValueHint::DirPath, default_value = ".", value_name = "PATH")]
+    pub root: PathBuf,
+
+    /// Print what would be converted without writing any file.
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// Overwrite an existing `foundry.toml`.
+    #[arg(long)]
+    pub force: bool,
+
+    /// Do not generate test stubs for the contracts of the project.
+    #[arg(long)]
+    pub no_tests: bool,
+}
+
+impl MigrateArgs {
+    pub fn run(self) -> Result<()> {
+        let root = dunce::canonicalize(&self.root)?;
+        let (kind, config_path) = ProjectKind::detect(&root)
+            .ok_or_else(|| eyre!("no Hardhat or Truffle config found in {}", root.display()))?;
+        let foundry_toml = root.join(Config::FILE_NAME);
+        if foundry_toml.exists() && !self.force && !self.dry_run {
+            eyre::bail!("{} already exists, pass --force to overwrite it", foundry_toml.display());
+        }
+
+        let mut report = MigrationReport::default();
+        let config_file = config_path.file_name().unwrap().to_string_lossy().to_string();
+        let exported = js::parse_exported_object(&fs::read_to_string(&config_path)?)
+            .ok_or_else(|| eyre!("could not find the exported config of {config_file}"))?;
+        let config = match kind {
+            ProjectKind::Hardhat => MigratedConfig::from_hardhat(&exported, &mut report),
+            ProjectKind::Truffle => MigratedConfig::from_truffle(&exported, &mut report),
+        };
+
+        let src = root.join(config.src.as_deref().unwrap_or("contracts"));
+        let test = root.join(config.test.as_deref().unwrap_or("test"));
+        let sources: Vec<_> = source_files_iter(&src, SOLC_EXTENSIONS).collect();
+        let mut files = sources.clone();
+        if test.exists() {
+            files.extend(source_files_iter(&test, SOLC_EXTENSIONS));
+        }
+
+        // Resolve the imported packages and rewrite the imports into `node_modules`.
+        let mut packages = BTreeSet::new();
+        for file in &files {
+            let relative = file.strip_prefix(&root).unwrap_or(file).display().to_string();
+            let content = fs::read_to_string(file)?;
+            let (rewritten, count) = rewrite_imports(&content, |import| {
+                if let Some((_, path)) = import.rsplit_once("node_modules/") {
+                    packages.insert(package_name(path).to_string());
+                    return Some(path.to_string())
+                }
+                if import.starts_with('.') || import.starts_with("forge-std/") {
+                    return None
+                }
+                let package = package_name(import);
+                if package == "truffle" {
+                    report.skipped(format!(
+                        "`{relative}` imports `{import}`, port it to forge-std's `Test`"
+                    ));
+                } else if root.join("node_modules").join(package).is_dir() {
+                    packages.insert(package.to_string());
+                } else {
+                    report.skipped(format!(
+                        "`{relative}` imports `{import}`, which is not installed in node_modules"
+                    ));
+                }
+                None
+            });
+            if count > 0 {
+                report.converted(format!(
+                    "rewrote {count} imports through node_modules in `{relative}`"
+                ));
+                if !self.dry_run {
+                    fs::write(file, rewritten)?;
+                }
+            }
+        }
+        let remappings: Vec<_> =
+            packages.iter().map(|package| format!("{package}/=node_modules/{package}/")).collect();
+        if !remappings.is_empty() {
+            report.converted(format!("{} remappings to node_modules", remappings.len()));
+        }
+
+        if !self.dry_run {
+            fs::write(&foundry_toml, config.to_toml(&remappings)?)?;
+        }
+        report.converted(format!("{config_file} to {}", Config::FILE_NAME));
+
+        if !self.no_tests {
+            for file in &sources {
+                generate_stubs(&root, file, &test, self.dry_run, &mut report)?;
+            }
+        }
+
+        println!("Migrated {kind} project at {}", root.display());
+        println!("{report}");
+        if self.dry_run {
+            println!("\nDry run, no files were written.");
+            return Ok(())
+        }
+        if !root.join("lib/forge-std").exists() {
+            println!(
+                "\nInstall forge-std with `forge install foundry-rs/forge-std`, then run `forge build`."
+            );
+        }
+        Ok(())
+    }
+}
+
+/// The kind of project to migrate.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ProjectKind {
+    Hardhat,
+    Truffle,
+}
+
+impl ProjectKind {
+    /// Returns the kind of project at the given root, along with its config file.
+    fn detect(root: &Path) -> Option<(Self, PathBuf)> {
+        const CONFIGS: &[(ProjectKind, &str)] = &[
+            (ProjectKind::Hardhat, "hardhat.config.ts"),
+            (ProjectKind::Hardhat, "hardhat.config.js"),
+            (ProjectKind::Hardhat, "hardhat.config.cjs"),
+            (ProjectKind::Hardhat, "hardhat.config.mjs"),
+            (ProjectKind::Hardhat, "hardhat.config.cts"),
+            (ProjectKind::Truffle, "truffle-config.js"),
+            (ProjectKind::Truffle, "truffle-config.cjs"),
+            (ProjectKind::Truffle, "truffle.js"),
+        ];
+        CONFIGS.iter().map(|(kind, name)| (*kind, root.join(name))).find(|(_, path)| path.is_file())
+    }
+}
+
+impl fmt::Display for ProjectKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Hardhat => f.write_str("Hardhat"),
+            Self::Truffle => f.write_str("Truffle"),
+        }
+    }
+}
+
+/// What was converted by a migration, and what has to be converted by hand.
+#[derive(Debug, Default)]
+pub struct MigrationReport {
+    pub converted: Vec<String>,
+    pub skipped: Vec<String>,
+}
+
+impl MigrationReport {
+    fn converted(&mut self, item: impl Into<String>) {
+        self.converted.push(item.into());
+    }
+
+    fn skipped(&mut self, item: impl Into<String>) {
+        self.skipped.push(item.into());
+    }
+}
+
+impl fmt::Display for MigrationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "\n{}", "Converted:".green().bold())?;
+        for item in &self.converted {
+            writeln!(f, "  - {item}")?;
+        }
+        if !self.skipped.is_empty() {
+            writeln!(f, "\n{}", "Not converted:".yellow().bold())?;
+            for item in &self.skipped {
+                writeln!(f, "  - {item}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Returns the package an import path starts with, e.g. `@openzeppelin/contracts` for
+/// `@openzeppelin/contracts/token/ERC20/ERC20.sol`.
+fn package_name(import: &str) -> &str {
+    let segments = if import.starts_with('@') { 2 } else { 1 };
+    match import.match_indices('/').nth(segments - 1) {
+        Some((idx, _)) => &import[..idx],
+        None => import,
+    }
+}
+
+/// Rewrites the import paths of a Solidity source with the given function, returning the new
+/// source and the number of rewritten imports.
+fn rewrite_imports(
+    content: &str,
+    mut rewrite: impl FnMut(&str) -> Option<String>,
+) -> (String, usize) {
+    static IMPORT: Lazy<Regex> = Lazy::new(|| {
+        Regex::new(r#"(?m)^(\s*import\s+(?:[^;"']*?\s+from\s+)?)(["'])([^"']+)["']"#).unwrap()
+    });
+    let mut count = 0;
+    let rewritten = IMPORT.replace_all(content, |caps: &regex::Captures<'_>| {
+        let quote = &caps[2];
+        match rewrite(&caps[3]) {
+            Some(path) => {
+                count += 1;
+                format!("{}{quote}{path}{quote}", &caps[1])
+            }
+            None => caps[0].to_string(),
+        }
+    });
+    (rewritten.into_owned(), count)
+}
+
+/// Generates a test stub for each contract in the given source file, unless one exists already.
+fn generate_stubs(
+    root: &Path,
+    file: &Path,
+    test: &Path,
+    dry_run: bool,
+    report: &mut MigrationReport,
+) -> Result<()> {
+    static CONTRACT: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?m)^\s*contract\s+(\w+)").unwrap());
+    static CONSTRUCTOR_ARGS: Lazy<Regex> =
+        Lazy::new(|| Regex::new(r"constructor\s*\(\s*[^)\s]").unwrap());
+    static PRAGMA: Lazy<Regex> = Lazy::new(|| Regex::new(r"pragma\s+solidity\s+([^;]+);").unwrap());
+
+    let content = fs::read_to_string(file)?;
+    let relative = file.strip_prefix(root).unwrap_or(file);
+    for caps in CONTRACT.captures_iter(&content) {
+        let contract_name = &caps[1];
+        let path = test.join(format!("{contract_name}.t.sol"));
+        if path.exists() {
+            continue
+        }
+        if CONSTRUCTOR_ARGS.is_match(&content) {
+            report.skipped(format!(
+                "no test stub was generated for `{contract_name}`, its constructor takes arguments"
+            ));
+            continue
+        }
+
+        let mut instance_name = contract_name.to_string();
+        instance_name[..1].make_ascii_lowercase();
+        let solidity_version =
+            PRAGMA.captures(&content).map_or("^0.8.13", |caps| caps.get(1).unwrap().as_str());
+        let stub = include_str!("../../../assets/generated/TestTemplate.t.sol")
+            .replace("{solidity_version}", solidity_version)
+            .replace("{contract_path}", &relative.display().to_string().replace('\\', "/"))
+            .replace("{contract_name}", contract_name)
+            .replace("{instance_name}", &instance_name);
+        if !dry_run {
+            fs::create_dir_all(test)?;
+            fs::write(&path, stub)?;
+        }
+        report.converted(format!(
+            "generated the test stub {}",
+            path.strip_prefix(root).unwrap_or(&path).display()
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rewrites_node_modules_imports() {
+        let src = r#"
+import "../node_modules/@openzeppelin/contracts/token/ERC20/ERC20.sol";
+import {Ownable} from '../../node_modules/solmate/src/auth/Owned.sol';
+import "./Local.sol";
+"#;
+        let (rewritten, count) = rewrite_imports(src, |import| {
+            import.rsplit_once("node_modules/").map(|(_, path)| path.to_string())
+        });
+        assert_eq!(count, 2);
+        assert!(rewritten.contains(r#"import "@openzeppelin/contracts/token/ERC20/ERC20.sol";"#));
+        assert!(rewritten.contains(r#"import {Ownable} from 'solmate/src/auth/Owned.sol';"#));
+        assert!(rewritten.contains(r#"import "./Local.sol";"#));
+    }
+
+    #[test]
+    fn package_names() {
+        assert_eq!(
+            package_name("@openzeppelin/contracts/access/Ownable.sol"),
+            "@openzeppelin/contracts"
+        );
+        assert_eq!(package_name("solmate/src/tokens/ERC20.sol"), "solmate");
+        assert_eq!(package_name("hardhat/console.sol"), "hardhat");
+    }
+}
@@ -39,15 +39,22 @@
 //! let config: Config = From::from(&args);
 //! ```
 
+pub mod analyze;
+pub mod audit_prep;
 pub mod bind;
+pub mod bind_json;
 pub mod build;
 pub mod cache;
+pub mod call_graph;
 pub mod clone;
 pub mod config;
+pub mod contract_test;
 pub mod coverage;
 pub mod create;
 pub mod debug;
+pub mod deps;
 pub mod doc;
+pub mod eip712;
 pub mod flatten;
 pub mod fmt;
 pub mod geiger;
@@ -55,12 +62,16 @@ pub mod generate;
 pub mod init;
 pub mod inspect;
 pub mod install;
+pub mod migrate;
+pub mod mutate;
 pub mod remappings;
 pub mod remove;
 pub mod selectors;
 pub mod snapshot;
 pub mod soldeer;
+pub mod storage_layout;
 pub mod test;
+pub mod test_report;
 pub mod tree;
 pub mod update;
 pub mod watch;
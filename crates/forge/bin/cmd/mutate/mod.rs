@@ -0,0 +1,425 @@
+use super::test::TestArgs;
+use alloy_primitives::{Keccak256, B256, U256};
+use clap::Parser;
+use comfy_table::{presets::ASCII_MARKDOWN, Table};
+use eyre::{Context, Result};
+use forge::{
+    opts::EvmOpts, result::SuiteResult, revm::primitives::Env, utils::PcIcMap,
+    MultiContractRunnerBuilder, TestOptions,
+};
+use foundry_cli::{
+    p_println,
+    utils::{LoadConfig, STATIC_FUZZ_SEED},
+};
+use foundry_common::{compile::with_compilation_reporter, ContractsByArtifact};
+use foundry_compilers::{
+    artifacts::{sourcemap::SourceMap, Source, Sources},
+    Artifact, ArtifactId, Project, ProjectCompileOutput,
+};
+use foundry_config::filter::GlobMatcher;
+use parking_lot::Mutex;
+use rayon::prelude::*;
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    path::{Path, PathBuf},
+};
+use yansi::Paint;
+
+mod mutants;
+use mutants::{generate_mutants, Mutant};
+
+mod state;
+use state::{ImpactDatabase, MutantResult, MutantStatus, MutationState, TestId, TestSetFilter};
+
+// Loads project's figment and merges the build cli arguments into it
+foundry_config::impl_figment_convert!(MutateArgs, test);
+
+/// CLI arguments for `forge mutate`.
+///
+/// 1. Run the tests once, recording which tests execute each line of the sources.
+/// 2. Generate mutants of the sources: swapped operators, removed `require`s and perturbed
+///    constants.
+/// 3. For each mutant, compile the mutated sources and run the tests executing the mutated line.
+/// 4. Report the mutation score and the mutants that no test killed.
+///
+/// Results are saved in the cache directory after every mutant, so that an interrupted run is
+/// resumed where it stopped, until the sources change.
+#[derive(Clone, Debug, Parser)]
+pub struct MutateArgs {
+    /// Only mutate the source files matching the specified glob pattern.
+    #[arg(long, value_name = "GLOB")]
+    mutate_path: Option<GlobMatcher>,
+
+    /// The number of mutants to test in parallel.
+    ///
+    /// Defaults to the number of available CPUs.
+    #[arg(long, value_name = "JOBS")]
+    mutant_jobs: Option<usize>,
+
+    /// Discard the results of previous runs instead of resuming them.
+    #[arg(long)]
+    fresh: bool,
+
+    /// Exit with an error if the mutation score is below the given percentage.
+    #[arg(long, value_name = "PERCENT")]
+    min_score: Option<f64>,
+
+    #[command(flatten)]
+    test: TestArgs,
+}
+
+impl MutateArgs {
+    pub async fn run(self) -> Result<()> {
+        let silent = self.test.build_args().silent;
+        let (mut config, evm_opts) = self.load_config_and_evm_opts_emit_warnings()?;
+        // Set fuzz seed so that the tests behave the same for every mutant.
+        config.fuzz.seed = Some(U256::from_be_bytes(STATIC_FUZZ_SEED));
+
+        // Mutants are compiled in memory, without touching the cache and the artifacts.
+        let project = config.create_project(false, true)?;
+        let root = project.root().to_path_buf();
+        let sources = project.paths.read_input_files()?;
+        let output = compile(&project, sources.clone())?;
+        if output.has_compiler_errors() {
+            eyre::bail!("{output}");
+        }
+
+        let mutants = sources
+            .iter()
+            .filter(|(path, _)| path.starts_with(&project.paths.sources))
+            .filter(|(path, _)| self.mutate_path.as_ref().map_or(true, |glob| glob.is_match(path)))
+            .flat_map(|(path, source)| {
+                let relative = path.strip_prefix(&root).unwrap_or(path).to_path_buf();
+                generate_mutants(relative, &source.content)
+            })
+            .collect::<Vec<_>>();
+
+        let state_path = config.cache_path.join(MutationState::FILE_NAME);
+        let sources_hash = hash_sources(&sources);
+        let mut state = if self.fresh {
+            MutationState { sources_hash, ..Default::default() }
+        } else {
+            MutationState::load(&state_path, sources_hash)
+        };
+
+        let env = evm_opts.evm_env().await?;
+        let builder = MultiContractRunnerBuilder::new(config.clone().into())
+            .initial_balance(evm_opts.initial_balance)
+            .evm_spec(config.evm_spec_id())
+            .sender(evm_opts.sender)
+            .with_fork(evm_opts.get_fork(&config, env.clone()))
+            .with_test_options(TestOptions {
+                fuzz: config.fuzz.clone(),
+                invariant: config.invariant.clone(),
+                ..Default::default()
+            });
+
+        let impact = if let Some(impact) = state.impact.clone() {
+            impact
+        } else {
+            p_println!(!silent => "Running the tests to find the tests executing each line...");
+            let mut runner = builder.clone().set_coverage(true).build(
+                &root,
+                &output,
+                env.clone(),
+                evm_opts.clone(),
+            )?;
+            let filter = self.test.filter(&config);
+            let known_contracts = runner.known_contracts.clone();
+            let results = tokio::task::spawn_blocking(move || runner.test_collect(&filter)).await?;
+            let failed = results.values().map(SuiteResult::failed).sum::<usize>();
+            if failed > 0 {
+                eyre::bail!("{failed} tests failed, mutation testing requires passing tests");
+            }
+            let mutated = mutants.iter().map(|mutant| &mutant.path).collect();
+            let impact =
+                impact_database(&results, &output, &known_contracts, &root, &sources, mutated);
+            state.impact = Some(impact.clone());
+            state.save(&state_path)?;
+            impact
+        };
+
+        let statuses = state.statuses();
+        let mut results = Vec::with_capacity(mutants.len());
+        let mut pending = Vec::new();
+        for mutant in mutants {
+            match statuses.get(&mutant) {
+                Some(&status) => results.push(MutantResult { mutant, status }),
+                None => pending.push(mutant),
+            }
+        }
+        drop(statuses);
+        let (count, resumed) = (pending.len(), results.len());
+        p_println!(!silent => "Testing {count} mutants, {resumed} resumed from a previous run...");
+
+        let state = Mutex::new(state);
+        let ctx = MutantTester {
+            project: &project,
+            root: &root,
+            sources: &sources,
+            impact: &impact,
+            builder: &builder,
+            env: &env,
+            evm_opts: &evm_opts,
+        };
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(self.mutant_jobs.unwrap_or_default())
+            .build()?;
+        let handle = tokio::runtime::Handle::current();
+        let tested = tokio::task::block_in_place(|| {
+            pool.install(|| {
+                pending
+                    .into_par_iter()
+                    .map(|mutant| {
+                        let _guard = handle.enter();
+                        let status = ctx.test(&mutant)?;
+                        if !silent {
+                            println!("{}: {mutant}", format_status(status));
+                        }
+                        let mut state = state.lock();
+                        let result = MutantResult { mutant, status };
+                        state.results.push(result.clone());
+                        state.save(&state_path)?;
+                        Ok(result)
+                    })
+                    .collect::<Result<Vec<_>>>()
+            })
+        })?;
+        results.extend(tested);
+        results.sort_by(|a, b| a.mutant.cmp(&b.mutant));
+
+        let score = MutationReport::new(&results).print();
+        if let Some(min_score) = self.min_score {
+            if score < min_score {
+                eyre::bail!("mutation score {score:.2}% is below the minimum of {min_score}%");
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Compiles and tests mutants.
+struct MutantTester<'a> {
+    project: &'a Project,
+    root: &'a Path,
+    sources: &'a Sources,
+    impact: &'a ImpactDatabase,
+    builder: &'a MultiContractRunnerBuilder,
+    env: &'a Env,
+    evm_opts: &'a EvmOpts,
+}
+
+impl MutantTester<'_> {
+    /// Runs the tests executing the line of the mutant against it.
+    fn test(&self, mutant: &Mutant) -> Result<MutantStatus> {
+        let Some(tests) = self.impact.tests(&mutant.path, mutant.line) else {
+            return Ok(MutantStatus::NotCovered)
+        };
+
+        let path = self.root.join(&mutant.path);
+        let mut sources = self.sources.clone();
+        let source = &sources[&path];
+        let mutated = Source::new(mutant.apply(&source.content));
+        sources.insert(path, mutated);
+        let output = match compile(self.project, sources) {
+            Ok(output) if !output.has_compiler_errors() => output,
+            _ => return Ok(MutantStatus::Invalid),
+        };
+
+        let mut runner = self.builder.clone().build(
+            self.root,
+            &output,
+            self.env.clone(),
+            self.evm_opts.clone(),
+        )?;
+        let results = runner.test_collect(&tests.iter().collect::<TestSetFilter>());
+        Ok(if results.values().any(|suite| suite.failed() > 0) {
+            MutantStatus::Killed
+        } else {
+            MutantStatus::Survived
+        })
+    }
+}
+
+/// Compiles the given sources of the project, without printing anything.
+fn compile(project: &Project, sources: Sources) -> Result<ProjectCompileOutput> {
+    with_compilation_reporter(true, || {
+        foundry_compilers::project::ProjectCompiler::with_sources(project, sources)?
+            .compile()
+            .wrap_err("failed to compile")
+    })
+}
+
+/// Returns the hash of the paths and contents of the sources.
+fn hash_sources(sources: &Sources) -> B256 {
+    let mut hasher = Keccak256::new();
+    for (path, source) in sources {
+        hasher.update(path.to_string_lossy().as_bytes());
+        hasher.update(source.content.as_bytes());
+    }
+    hasher.finalize()
+}
+
+/// Builds the impact database of the mutated files from the hits of the tests.
+fn impact_database(
+    results: &BTreeMap<String, SuiteResult>,
+    output: &ProjectCompileOutput,
+    known_contracts: &ContractsByArtifact,
+    root: &Path,
+    sources: &Sources,
+    mutated: BTreeSet<&PathBuf>,
+) -> ImpactDatabase {
+    let source_paths = output
+        .output()
+        .sources
+        .sources_with_version()
+        .map(|(path, source, version)| {
+            let path = path.strip_prefix(root).unwrap_or(path).to_path_buf();
+            ((version.clone(), source.id), path)
+        })
+        .collect::<HashMap<_, _>>();
+    let source_maps = output
+        .artifact_ids()
+        .map(|(id, artifact)| {
+            let source_map = |map: Option<Result<SourceMap, _>>| map.and_then(Result::ok);
+            (
+                id.with_stripped_file_prefixes(root),
+                (
+                    source_map(artifact.get_source_map()),
+                    source_map(artifact.get_source_map_deployed()),
+                ),
+            )
+        })
+        .collect::<BTreeMap<ArtifactId, _>>();
+    let line_starts = mutated
+        .iter()
+        .filter_map(|&path| {
+            let source = sources.get(&root.join(path))?;
+            let starts = std::iter::once(0)
+                .chain(source.content.match_indices('\n').map(|(i, _)| i + 1))
+                .collect::<Vec<_>>();
+            Some((path, starts))
+        })
+        .collect::<HashMap<_, _>>();
+
+    let mut impact = ImpactDatabase::default();
+    for (suite_name, suite) in results {
+        let contract = suite_name.rsplit_once(':').map_or(suite_name.as_str(), |(_, name)| name);
+        for (signature, result) in suite.successes() {
+            let Some(hit_maps) = &result.coverage else { continue };
+            let test = TestId { contract: contract.to_string(), signature: signature.clone() };
+            for map in hit_maps.values() {
+                let (id, source_map) = if let Some((id, _)) =
+                    known_contracts.find_by_deployed_code(&map.bytecode)
+                {
+                    (id, source_maps.get(id).and_then(|maps| maps.1.as_ref()))
+                } else if let Some((id, _)) = known_contracts.find_by_creation_code(&map.bytecode) {
+                    (id, source_maps.get(id).and_then(|maps| maps.0.as_ref()))
+                } else {
+                    continue
+                };
+                let Some(source_map) = source_map else { continue };
+                let pc_ic_map = PcIcMap::new(&map.bytecode);
+                for &pc in map.hits.keys() {
+                    let Some(element) = pc_ic_map.get(pc).and_then(|ic| source_map.get(ic)) else {
+                        continue
+                    };
+                    let Some(index) = element.index() else { continue };
+                    let Some(path) = source_paths.get(&(id.version.clone(), index)) else {
+                        continue
+                    };
+                    let Some(starts) = line_starts.get(path) else { continue };
+                    let offset = element.offset() as usize;
+                    let line = starts.partition_point(|&start| start <= offset);
+                    impact.add(path.clone(), line, test.clone());
+                }
+            }
+        }
+    }
+    impact
+}
+
+fn format_status(status: MutantStatus) -> String {
+    let text = status.to_string();
+    match status {
+        MutantStatus::Killed => text.green().to_string(),
+        MutantStatus::Survived => text.red().to_string(),
+        MutantStatus::NotCovered => text.yellow().to_string(),
+        MutantStatus::Invalid => text.dim().to_string(),
+    }
+}
+
+/// The number of mutants of each status, by source file.
+struct MutationReport<'a> {
+    results: &'a [MutantResult],
+    counts: BTreeMap<&'a Path, HashMap<MutantStatus, usize>>,
+}
+
+impl<'a> MutationReport<'a> {
+    fn new(results: &'a [MutantResult]) -> Self {
+        let mut counts = BTreeMap::<_, HashMap<_, _>>::new();
+        for result in results {
+            *counts
+                .entry(result.mutant.path.as_path())
+                .or_default()
+                .entry(result.status)
+                .or_default() += 1;
+        }
+        Self { results, counts }
+    }
+
+    /// Prints the report, returning the total mutation score.
+    fn print(&self) -> f64 {
+        let mut table = Table::new();
+        table.load_preset(ASCII_MARKDOWN);
+        table.set_header(["File", "Killed", "Survived", "Not covered", "Invalid", "Score"]);
+        let mut total = HashMap::new();
+        for (path, counts) in &self.counts {
+            for (status, count) in counts {
+                *total.entry(*status).or_default() += count;
+            }
+            table.add_row(row(path.display(), counts));
+        }
+        table.add_row(row("Total", &total));
+        println!("{table}");
+
+        for (title, status) in [
+            ("Surviving mutants:", MutantStatus::Survived),
+            ("Not covered:", MutantStatus::NotCovered),
+        ] {
+            let mut mutants =
+                self.results.iter().filter(|result| result.status == status).peekable();
+            if mutants.peek().is_some() {
+                println!("\n{}", title.bold());
+                for result in mutants {
+                    println!("  - {}", result.mutant);
+                }
+            }
+        }
+        score(&total)
+    }
+}
+
+fn row(name: impl ToString, counts: &HashMap<MutantStatus, usize>) -> Vec<String> {
+    let count = |status| counts.get(&status).copied().unwrap_or_default().to_string();
+    vec![
+        name.to_string(),
+        count(MutantStatus::Killed),
+        count(MutantStatus::Survived),
+        count(MutantStatus::NotCovered),
+        count(MutantStatus::Invalid),
+        format!("{:.2}%", score(counts)),
+    ]
+}
+
+/// Returns the percentage of the valid mutants that were killed.
+fn score(counts: &HashMap<MutantStatus, usize>) -> f64 {
+    let count = |status| counts.get(&status).copied().unwrap_or_default();
+    let killed = count(MutantStatus::Killed);
+    let valid = killed + count(MutantStatus::Survived) + count(MutantStatus::NotCovered);
+    if valid == 0 {
+        100.
+    } else {
+        killed as f64 / valid as f64 * 100.
+    }
+}
@@ -0,0 +1,243 @@
+use serde::{Deserialize, Serialize};
+use solang_parser::lexer::{Lexer, Token};
+use std::{fmt, path::PathBuf};
+
+/// The kind of change a [`Mutant`] makes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum MutationKind {
+    /// A binary operator is swapped for another one.
+    Operator,
+    /// A `require` or `assert` statement is removed.
+    RequireRemoval,
+    /// An integer literal is incremented or decremented.
+    Constant,
+}
+
+impl fmt::Display for MutationKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Operator => f.write_str("operator"),
+            Self::RequireRemoval => f.write_str("require removal"),
+            Self::Constant => f.write_str("constant"),
+        }
+    }
+}
+
+/// A single change to a source file.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct Mutant {
+    /// The path of the mutated file, relative to the project root.
+    pub path: PathBuf,
+    /// The line of the change.
+    pub line: usize,
+    /// The byte range of the replaced source.
+    pub start: usize,
+    pub end: usize,
+    pub kind: MutationKind,
+    pub original: String,
+    pub replacement: String,
+}
+
+impl Mutant {
+    /// Returns the given source with the change applied.
+    pub fn apply(&self, source: &str) -> String {
+        let mut mutated = String::with_capacity(source.len());
+        mutated.push_str(&source[..self.start]);
+        mutated.push_str(&self.replacement);
+        mutated.push_str(&source[self.end..]);
+        mutated
+    }
+}
+
+impl fmt::Display for Mutant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let original = self.original.split_whitespace().collect::<Vec<_>>().join(" ");
+        write!(f, "{}:{}: ", self.path.display(), self.line)?;
+        if self.replacement.is_empty() {
+            write!(f, "removed `{original}`")
+        } else {
+            write!(f, "`{original}` -> `{}`", self.replacement)
+        }
+    }
+}
+
+/// Returns the operators the given binary operator is swapped for.
+fn operator_swaps(operator: &str) -> &'static [&'static str] {
+    match operator {
+        "<" => &[">=", "<="],
+        "<=" => &[">", "<"],
+        ">" => &["<=", ">="],
+        ">=" => &["<", ">"],
+        "==" => &["!="],
+        "!=" => &["=="],
+        "&&" => &["||"],
+        "||" => &["&&"],
+        "+" => &["-"],
+        "-" => &["+"],
+        "*" => &["/"],
+        "/" => &["*"],
+        "%" => &["*"],
+        "+=" => &["-="],
+        "-=" => &["+="],
+        _ => &[],
+    }
+}
+
+/// Generates the mutants of a Solidity source file.
+///
+/// Only the bodies of functions, modifiers and constructors are mutated. Mutants are generated
+/// from the tokens of the source, so some of them may not compile, e.g. swapping the sign of a
+/// negative literal.
+pub fn generate_mutants(path: PathBuf, source: &str) -> Vec<Mutant> {
+    let mut comments = Vec::new();
+    let mut errors = Vec::new();
+    let tokens = Lexer::new(source, 0, &mut comments, &mut errors).collect::<Vec<_>>();
+    let line_starts = std::iter::once(0)
+        .chain(source.match_indices('\n').map(|(i, _)| i + 1))
+        .collect::<Vec<_>>();
+    let line = |offset: usize| line_starts.partition_point(|&start| start <= offset);
+
+    let mut mutants = Vec::new();
+    let mut push = |start: usize, end: usize, kind: MutationKind, replacement: String| {
+        mutants.push(Mutant {
+            path: path.clone(),
+            line: line(start),
+            start,
+            end,
+            kind,
+            original: source[start..end].to_string(),
+            replacement,
+        });
+    };
+
+    // The brace depth of the body being mutated, if any.
+    let mut body_depth = None;
+    let mut depth = 0usize;
+    let mut expects_body = false;
+    for (i, &(start, ref token, end)) in tokens.iter().enumerate() {
+        let text = &source[start..end];
+        match token {
+            Token::OpenCurlyBrace => {
+                if expects_body && body_depth.is_none() {
+                    body_depth = Some(depth);
+                }
+                expects_body = false;
+                depth += 1;
+                continue
+            }
+            Token::CloseCurlyBrace => {
+                depth = depth.saturating_sub(1);
+                if body_depth == Some(depth) {
+                    body_depth = None;
+                }
+                continue
+            }
+            Token::Semicolon if body_depth.is_none() => {
+                expects_body = false;
+                continue
+            }
+            _ => {}
+        }
+        if body_depth.is_none() {
+            if matches!(text, "function" | "modifier" | "constructor" | "fallback" | "receive") {
+                expects_body = true;
+            }
+            continue
+        }
+
+        match token {
+            Token::Identifier("require" | "assert") => {
+                let at_statement_start = i == 0 ||
+                    matches!(
+                        tokens[i - 1].1,
+                        Token::Semicolon | Token::OpenCurlyBrace | Token::CloseCurlyBrace
+                    );
+                if !at_statement_start {
+                    continue
+                }
+                if let Some(end) = statement_end(&tokens[i..]) {
+                    push(start, end, MutationKind::RequireRemoval, String::new());
+                }
+            }
+            Token::Number(integer, exponent) if exponent.is_empty() => {
+                let Ok(value) = integer.replace('_', "").parse::<u128>() else { continue };
+                if let Some(incremented) = value.checked_add(1) {
+                    push(start, end, MutationKind::Constant, incremented.to_string());
+                }
+                if let Some(decremented) = value.checked_sub(1) {
+                    push(start, end, MutationKind::Constant, decremented.to_string());
+                }
+            }
+            _ => {
+                for replacement in operator_swaps(text) {
+                    push(start, end, MutationKind::Operator, replacement.to_string());
+                }
+            }
+        }
+    }
+    mutants
+}
+
+/// Returns the end offset of the statement starting with the given tokens, including its
+/// semicolon.
+fn statement_end(tokens: &[(usize, Token<'_>, usize)]) -> Option<usize> {
+    let mut depth = 0usize;
+    for (_, token, end) in tokens {
+        match token {
+            Token::OpenParenthesis => depth += 1,
+            Token::CloseParenthesis => depth = depth.checked_sub(1)?,
+            Token::Semicolon if depth == 0 => return Some(*end),
+            Token::OpenCurlyBrace | Token::CloseCurlyBrace => return None,
+            _ => {}
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = r#"
+pragma solidity >=0.8.0;
+
+import "./Other.sol";
+
+interface IA {
+    function f(uint256 x) external returns (uint256);
+}
+
+contract A {
+    uint256 constant LIMIT = 10;
+
+    function f(uint256 x) external returns (uint256) {
+        // x < 1
+        require(x > 0, "zero");
+        return x + 2;
+    }
+}
+"#;
+
+    #[test]
+    fn mutates_function_bodies() {
+        let mutants = generate_mutants("src/A.sol".into(), SOURCE);
+        let described = mutants.iter().map(|mutant| mutant.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            described,
+            [
+                r#"src/A.sol:15: removed `require(x > 0, "zero");`"#,
+                "src/A.sol:15: `>` -> `<=`",
+                "src/A.sol:15: `>` -> `>=`",
+                "src/A.sol:15: `0` -> `1`",
+                "src/A.sol:16: `+` -> `-`",
+                "src/A.sol:16: `2` -> `3`",
+                "src/A.sol:16: `2` -> `1`",
+            ]
+        );
+
+        let mutated = mutants[0].apply(SOURCE);
+        assert!(mutated.contains("// x < 1\n        \n        return x + 2;"), "{mutated}");
+        assert!(mutants[4].apply(SOURCE).contains("return x - 2;"));
+    }
+}
@@ -0,0 +1,144 @@
+use super::mutants::Mutant;
+use alloy_primitives::B256;
+use eyre::Result;
+use forge::TestFilter;
+use foundry_common::fs;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    fmt,
+    path::{Path, PathBuf},
+};
+
+/// A test function of a test contract.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct TestId {
+    pub contract: String,
+    pub signature: String,
+}
+
+/// The tests executing each line of the mutated sources.
+///
+/// A line is executed by a test if the source map attributes an instruction hit by the test to a
+/// source range starting on that line.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct ImpactDatabase {
+    /// The tests executing each line, by source file relative to the project root.
+    pub lines: BTreeMap<PathBuf, BTreeMap<usize, BTreeSet<TestId>>>,
+}
+
+impl ImpactDatabase {
+    pub fn add(&mut self, path: PathBuf, line: usize, test: TestId) {
+        self.lines.entry(path).or_default().entry(line).or_default().insert(test);
+    }
+
+    /// Returns the tests executing the given line.
+    pub fn tests(&self, path: &Path, line: usize) -> Option<&BTreeSet<TestId>> {
+        self.lines.get(path)?.get(&line).filter(|tests| !tests.is_empty())
+    }
+}
+
+/// Matches a set of tests.
+///
+/// Contracts and tests are matched separately, so tests of the other contracts with the same
+/// signatures are matched too. These do not execute the mutated line, so they pass like they did
+/// without the mutation.
+pub struct TestSetFilter {
+    contracts: BTreeSet<String>,
+    signatures: BTreeSet<String>,
+}
+
+impl<'a> FromIterator<&'a TestId> for TestSetFilter {
+    fn from_iter<I: IntoIterator<Item = &'a TestId>>(tests: I) -> Self {
+        let (contracts, signatures) =
+            tests.into_iter().map(|test| (test.contract.clone(), test.signature.clone())).unzip();
+        Self { contracts, signatures }
+    }
+}
+
+impl TestFilter for TestSetFilter {
+    fn matches_test(&self, test_name: &str) -> bool {
+        self.signatures.contains(test_name)
+    }
+
+    fn matches_contract(&self, contract_name: &str) -> bool {
+        self.contracts.contains(contract_name)
+    }
+
+    fn matches_path(&self, _path: &Path) -> bool {
+        true
+    }
+}
+
+/// The outcome of testing a mutant.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum MutantStatus {
+    /// A test failed with the mutant.
+    Killed,
+    /// All the tests executing the mutant passed.
+    Survived,
+    /// No test executes the mutated line.
+    NotCovered,
+    /// The mutant does not compile.
+    Invalid,
+}
+
+impl fmt::Display for MutantStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Killed => f.write_str("killed"),
+            Self::Survived => f.write_str("survived"),
+            Self::NotCovered => f.write_str("not covered"),
+            Self::Invalid => f.write_str("invalid"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MutantResult {
+    #[serde(flatten)]
+    pub mutant: Mutant,
+    pub status: MutantStatus,
+}
+
+/// The state of a mutation testing run, saved after every mutant so that interrupted runs can be
+/// resumed.
+///
+/// The state is only valid for the sources it was computed for, and is discarded when any of them
+/// changes.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MutationState {
+    /// The hash of the sources of the project.
+    pub sources_hash: B256,
+    /// The tests executing each line, computed once per state.
+    pub impact: Option<ImpactDatabase>,
+    /// The results of the mutants tested so far.
+    pub results: Vec<MutantResult>,
+}
+
+impl MutationState {
+    /// The name of the state file in the cache directory.
+    pub const FILE_NAME: &'static str = "mutate.json";
+
+    /// Loads the state at the given path if it was saved for the same sources.
+    pub fn load(path: &Path, sources_hash: B256) -> Self {
+        match fs::read_json_file::<Self>(path) {
+            Ok(state) if state.sources_hash == sources_hash => state,
+            _ => Self { sources_hash, ..Default::default() },
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write_json_file(path, self)
+    }
+
+    /// Returns the results of the mutants tested so far.
+    pub fn statuses(&self) -> HashMap<&Mutant, MutantStatus> {
+        self.results.iter().map(|result| (&result.mutant, result.status)).collect()
+    }
+}
@@ -4,14 +4,19 @@ use comfy_table::Table;
 use eyre::Result;
 use foundry_cli::{
     opts::{CompilerArgs, CoreBuildArgs, ProjectPathsArgs},
-    utils::FoundryPathExt,
+    utils::{FoundryPathExt, LoadConfig},
 };
 use foundry_common::{
     compile::{compile_target, ProjectCompiler},
-    selectors::{import_selectors, SelectorImportData},
+    fs,
+    selectors::{OpenChainClient, SelectorImportData, SignatureDatabase},
 };
-use foundry_compilers::{artifacts::output_selection::ContractOutputSelection, info::ContractInfo};
-use std::fs::canonicalize;
+use foundry_compilers::{
+    artifacts::output_selection::ContractOutputSelection, info::ContractInfo, Project,
+    ProjectCompileOutput,
+};
+use foundry_config::Config;
+use std::{fs::canonicalize, path::PathBuf};
 
 /// CLI arguments for `forge selectors`.
 #[derive(Clone, Debug, Parser)]
@@ -32,7 +37,10 @@ pub enum SelectorsSubcommands {
     },
 
     /// Upload selectors to registry
-    #[command(visible_alias = "up")]
+    ///
+    /// Selectors are uploaded to OpenChain, or to the self-hosted registry set with
+    /// `selectors_registry`.
+    #[command(visible_aliases = ["up", "push"])]
     Upload {
         /// The name of the contract to upload selectors for.
         #[arg(required_unless_present = "all")]
@@ -46,6 +54,14 @@ pub enum SelectorsSubcommands {
         project_paths: ProjectPathsArgs,
     },
 
+    /// Download the signatures of the self-hosted selector registry set with `selectors_registry`
+    /// to the project's local signature database.
+    #[command(visible_alias = "pull")]
+    Download {
+        #[command(flatten)]
+        project_paths: ProjectPathsArgs,
+    },
+
     /// List selectors from current workspace
     #[command(visible_alias = "ls")]
     List {
@@ -56,6 +72,23 @@ pub enum SelectorsSubcommands {
         #[command(flatten)]
         project_paths: ProjectPathsArgs,
     },
+
+    /// Build the project's local signature database from the compiled artifacts.
+    ///
+    /// The database is used to decode selectors in traces, errors and `cast 4byte` without
+    /// querying OpenChain.
+    Cache {
+        /// Signature dumps to import, either OpenChain lookup responses or one signature per line.
+        #[arg(long, value_name = "FILE")]
+        import: Vec<PathBuf>,
+
+        /// Discard the existing database instead of adding to it.
+        #[arg(long)]
+        clean: bool,
+
+        #[command(flatten)]
+        project_paths: ProjectPathsArgs,
+    },
 }
 
 impl SelectorsSubcommands {
@@ -71,6 +104,8 @@ impl SelectorsSubcommands {
                     ..Default::default()
                 };
 
+                let config = build_args.try_load_config_emit_warnings()?;
+                let client = OpenChainClient::with_registry(config.selectors_registry)?;
                 let project = build_args.project()?;
                 let output = if let Some(name) = &contract {
                     let target_path = project.find_contract_path(name)?;
@@ -112,7 +147,7 @@ impl SelectorsSubcommands {
                     println!("Uploading selectors for {contract}...");
 
                     // upload abi to selector database
-                    import_selectors(SelectorImportData::Abi(vec![abi])).await?.describe();
+                    client.import_selectors(SelectorImportData::Abi(vec![abi])).await?.describe();
 
                     if artifacts.peek().is_some() {
                         println!()
@@ -175,6 +210,60 @@ impl SelectorsSubcommands {
                     println!("{table}");
                 }
             }
+            Self::Cache { import, clean, project_paths } => {
+                let build_args = CoreBuildArgs {
+                    project_paths,
+                    compiler: CompilerArgs {
+                        extra_output: vec![ContractOutputSelection::Abi],
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                };
+                let config = build_args.try_load_config_emit_warnings()?;
+                let path = SignatureDatabase::path(&config);
+                let mut db = if clean {
+                    SignatureDatabase::default()
+                } else {
+                    SignatureDatabase::read(&path)?
+                };
+
+                let project = build_args.project()?;
+                let output = ProjectCompiler::new().quiet(true).compile(&project)?;
+                for (_, artifact) in output.artifacts() {
+                    if let Some(abi) = &artifact.abi {
+                        db.add_abi(abi);
+                    }
+                }
+
+                for file in import {
+                    let count = db
+                        .import(&fs::read_to_string(&file)?)
+                        .map_err(|err| eyre::eyre!("failed to import {}: {err}", file.display()))?;
+                    println!("Imported {count} signatures from {}", file.display());
+                }
+
+                db.write(&path)?;
+                println!(
+                    "Cached {} functions, {} events and {} errors in {}",
+                    db.functions.values().map(|s| s.len()).sum::<usize>(),
+                    db.events.values().map(|s| s.len()).sum::<usize>(),
+                    db.errors.values().map(|s| s.len()).sum::<usize>(),
+                    path.display()
+                );
+            }
+            Self::Download { project_paths } => {
+                let build_args = CoreBuildArgs { project_paths, ..Default::default() };
+                let config = build_args.try_load_config_emit_warnings()?;
+                let client = OpenChainClient::with_registry(config.selectors_registry.clone())?;
+                let downloaded = client.export_signatures().await?;
+
+                let path = SignatureDatabase::path(&config);
+                let mut db = SignatureDatabase::read(&path)?;
+                let count = downloaded.len();
+                db.extend(downloaded);
+                db.write(&path)?;
+                println!("Downloaded {count} signatures to {}", path.display());
+            }
             Self::List { contract, project_paths } => {
                 println!("Listing selectors for contracts in the project...");
                 let build_args = CoreBuildArgs {
@@ -264,3 +353,31 @@ impl SelectorsSubcommands {
         Ok(())
     }
 }
+
+/// Uploads the selectors of the project's sources to the selector registry, and downloads the
+/// signatures of a self-hosted registry to the local signature database.
+pub async fn sync_selectors(
+    config: &Config,
+    project: &Project,
+    output: &ProjectCompileOutput,
+) -> Result<()> {
+    let client = OpenChainClient::with_registry(config.selectors_registry.clone())?;
+
+    let abis = output
+        .artifacts_with_files()
+        .filter(|(file, _, _)| file.starts_with(&project.paths.sources) && !file.is_sol_test())
+        .filter_map(|(_, _, artifact)| artifact.abi.clone())
+        .filter(|abi| !abi.functions.is_empty() || !abi.events.is_empty() || !abi.errors.is_empty())
+        .collect::<Vec<_>>();
+    if !abis.is_empty() {
+        client.import_selectors(SelectorImportData::Abi(abis)).await?;
+    }
+
+    if config.selectors_registry.is_some() {
+        let path = SignatureDatabase::path(config);
+        let mut db = SignatureDatabase::read(&path)?;
+        db.extend(client.export_signatures().await?);
+        db.write(&path)?;
+    }
+    Ok(())
+}
@@ -1,16 +1,23 @@
 use super::test;
-use alloy_primitives::U256;
+use alloy_primitives::{keccak256, B256, U256};
 use clap::{builder::RangedU64ValueParser, Parser, ValueHint};
 use eyre::{Context, Result};
 use forge::result::{SuiteTestResult, TestKindReport, TestOutcome};
-use foundry_cli::utils::STATIC_FUZZ_SEED;
+use foundry_cli::{
+    opts::CoreBuildArgs,
+    utils::{FoundryPathExt, LoadConfig, STATIC_FUZZ_SEED},
+};
+use foundry_common::compile::ProjectCompiler;
+use foundry_compilers::artifacts::{
+    output_selection::ContractOutputSelection, BytecodeObject, ConfigurableContractArtifact,
+};
 use once_cell::sync::Lazy;
 use regex::Regex;
+use serde::{Deserialize, Serialize};
 use std::{
     cmp::Ordering,
-    collections::HashMap,
+    collections::{BTreeMap, HashMap},
     fs,
-    io::{self, BufRead},
     path::{Path, PathBuf},
     str::FromStr,
 };
@@ -20,7 +27,7 @@ use yansi::Paint;
 /// A regex that matches a basic snapshot entry like
 /// `Test:testDeposit() (gas: 58804)`
 pub static RE_BASIC_SNAPSHOT_ENTRY: Lazy<Regex> = Lazy::new(|| {
-    Regex::new(r"(?P<file>(.*?)):(?P<sig>(\w+)\s*\((.*?)\))\s*\(((gas:)?\s*(?P<gas>\d+)|(runs:\s*(?P<runs>\d+),\s*μ:\s*(?P<avg>\d+),\s*~:\s*(?P<med>\d+))|(runs:\s*(?P<invruns>\d+),\s*calls:\s*(?P<calls>\d+),\s*reverts:\s*(?P<reverts>\d+)))\)").unwrap()
+    Regex::new(r"(?P<file>(.*?)):(?P<sig>(\w+)\s*\((.*?)\))\s*\(((gas:)?\s*(?P<gas>\d+)(,\s*refund:\s*(?P<refund>\d+))?|(runs:\s*(?P<runs>\d+),\s*μ:\s*(?P<avg>\d+),\s*~:\s*(?P<med>\d+))|(runs:\s*(?P<invruns>\d+),\s*calls:\s*(?P<calls>\d+),\s*reverts:\s*(?P<reverts>\d+)))\)").unwrap()
 });
 
 /// CLI arguments for `forge snapshot`.
@@ -32,6 +39,7 @@ pub struct SnapshotArgs {
     #[arg(
         conflicts_with = "snap",
         long,
+        visible_alias = "compare",
         value_hint = ValueHint::FilePath,
         value_name = "SNAPSHOT_FILE",
     )]
@@ -50,19 +58,25 @@ pub struct SnapshotArgs {
     )]
     check: Option<Option<PathBuf>>,
 
-    // Hidden because there is only one option
-    /// How to format the output.
-    #[arg(long, hide(true))]
+    /// How to format the snapshot file.
+    ///
+    /// JSON snapshots also record the gas used by each function called in a test.
+    #[arg(long, value_name = "FORMAT")]
     format: Option<Format>,
 
     /// Output file for the snapshot.
-    #[arg(
-        long,
-        default_value = ".gas-snapshot",
-        value_hint = ValueHint::FilePath,
-        value_name = "FILE",
-    )]
-    snap: PathBuf,
+    ///
+    /// Defaults to .gas-snapshot, or .code-snapshot with `--code`.
+    #[arg(long, value_hint = ValueHint::FilePath, value_name = "FILE")]
+    snap: Option<PathBuf>,
+
+    /// Snapshot the bytecode hashes of the contracts in the sources directory instead of the gas
+    /// used by the tests.
+    ///
+    /// With `--check`, exits with code 1 if the bytecode of any contract changed, listing the
+    /// source files whose changes caused it.
+    #[arg(long, conflicts_with_all = ["format", "tolerance", "threshold"])]
+    code: bool,
 
     /// Tolerates gas deviations up to the specified percentage.
     #[arg(
@@ -72,6 +86,18 @@ pub struct SnapshotArgs {
     )]
     tolerance: Option<u32>,
 
+    /// Exit with code 1 if a test, or a function recorded in a JSON snapshot, uses more than the
+    /// given percentage of gas over the snapshot.
+    ///
+    /// Only applies to `--diff`.
+    #[arg(
+        long,
+        requires = "diff",
+        value_parser = RangedU64ValueParser::<u32>::new().range(0..100),
+        value_name = "REGRESSION_THRESHOLD"
+    )]
+    threshold: Option<u32>,
+
     /// All test arguments are supported
     #[command(flatten)]
     pub(crate) test: test::TestArgs,
@@ -94,27 +120,67 @@ impl SnapshotArgs {
     }
 
     pub async fn run(mut self) -> Result<()> {
+        if self.code {
+            return self.run_code()
+        }
+
         // Set fuzz seed so gas snapshots are deterministic
         self.test.fuzz_seed = Some(U256::from_be_bytes(STATIC_FUZZ_SEED));
 
+        // Read the snapshot to diff against before running the tests, as the gas used by functions
+        // is only collected when writing or diffing against a JSON snapshot.
+        let snap = self.snap.clone().unwrap_or_else(|| ".gas-snapshot".into());
+        let diff_snaps = self
+            .diff
+            .as_ref()
+            .map(|path| read_snapshot(path.as_ref().unwrap_or(&snap)))
+            .transpose()?;
+        self.test.function_gas = matches!(self.format, Some(Format::Json)) ||
+            diff_snaps.iter().flatten().any(|snap| !snap.functions.is_empty());
+
         let outcome = self.test.execute_tests().await?;
         outcome.ensure_ok()?;
         let tests = self.config.apply(outcome);
 
-        if let Some(path) = self.diff {
-            let snap = path.as_ref().unwrap_or(&self.snap);
-            let snaps = read_snapshot(snap)?;
-            diff(tests, snaps)?;
+        if let Some(snaps) = diff_snaps {
+            if !diff(tests, snaps, self.threshold)? {
+                std::process::exit(1)
+            }
         } else if let Some(path) = self.check {
-            let snap = path.as_ref().unwrap_or(&self.snap);
-            let snaps = read_snapshot(snap)?;
+            let snaps = read_snapshot(path.as_ref().unwrap_or(&snap))?;
             if check(tests, snaps, self.tolerance) {
                 std::process::exit(0)
             } else {
                 std::process::exit(1)
             }
         } else {
-            write_to_snapshot_file(&tests, self.snap, self.format)?;
+            write_to_snapshot_file(&tests, snap, self.format)?;
+        }
+        Ok(())
+    }
+
+    /// Snapshots the bytecode hashes of the contracts instead of running the tests.
+    fn run_code(self) -> Result<()> {
+        let snap = self.snap.unwrap_or_else(|| ".code-snapshot".into());
+        let entries = code_snapshot(self.test.build_args())?;
+
+        if let Some(path) = self.diff {
+            let snaps = read_code_snapshot(path.as_ref().unwrap_or(&snap))?;
+            for line in diff_code(&entries, &snaps) {
+                println!("{line}");
+            }
+        } else if let Some(path) = self.check {
+            let snaps = read_code_snapshot(path.as_ref().unwrap_or(&snap))?;
+            let changes = diff_code(&entries, &snaps);
+            if !changes.is_empty() {
+                eprintln!("Unexpected bytecode changes:");
+                for line in changes {
+                    eprintln!("{line}");
+                }
+                std::process::exit(1)
+            }
+        } else {
+            fs::write(snap, serde_json::to_string_pretty(&entries)?)?;
         }
         Ok(())
     }
@@ -124,6 +190,7 @@ impl SnapshotArgs {
 #[derive(Clone, Debug)]
 pub enum Format {
     Table,
+    Json,
 }
 
 impl FromStr for Format {
@@ -132,6 +199,7 @@ impl FromStr for Format {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         match s {
             "t" | "table" => Ok(Self::Table),
+            "j" | "json" => Ok(Self::Json),
             _ => Err(format!("Unrecognized format `{s}`")),
         }
     }
@@ -194,11 +262,28 @@ impl SnapshotConfig {
 ///   `<signature>(gas:? 40181)` for normal tests
 ///   `<signature>(runs: 256, μ: 40181, ~: 40181)` for fuzz tests
 ///   `<signature>(runs: 256, calls: 40181, reverts: 40181)` for invariant tests
-#[derive(Clone, Debug, PartialEq, Eq)]
+///
+/// or, in JSON snapshots, an object with the same fields and the gas used by each function.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct SnapshotEntry {
     pub contract_name: String,
     pub signature: String,
+    #[serde(flatten)]
     pub gas_used: TestKindReport,
+    /// The gas used by each function called in the test, only recorded in JSON snapshots.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub functions: BTreeMap<String, u64>,
+}
+
+impl SnapshotEntry {
+    fn new(test: &SuiteTestResult) -> Self {
+        Self {
+            contract_name: test.contract_name().to_string(),
+            signature: test.signature.clone(),
+            gas_used: test.result.kind.report(),
+            functions: test.result.function_gas.clone(),
+        }
+    }
 }
 
 impl FromStr for SnapshotEntry {
@@ -216,7 +301,11 @@ impl FromStr for SnapshotEntry {
                                 signature: sig.as_str().to_string(),
                                 gas_used: TestKindReport::Unit {
                                     gas: gas.as_str().parse().unwrap(),
+                                    refund: cap
+                                        .name("refund")
+                                        .map_or(0, |refund| refund.as_str().parse().unwrap()),
                                 },
+                                functions: BTreeMap::new(),
                             })
                         } else if let Some(runs) = cap.name("runs") {
                             cap.name("avg")
@@ -229,6 +318,7 @@ impl FromStr for SnapshotEntry {
                                         median_gas: med.as_str().parse().unwrap(),
                                         mean_gas: avg.as_str().parse().unwrap(),
                                     },
+                                    functions: BTreeMap::new(),
                                 })
                         } else {
                             cap.name("invruns")
@@ -245,6 +335,7 @@ impl FromStr for SnapshotEntry {
                                         calls: calls.as_str().parse().unwrap(),
                                         reverts: reverts.as_str().parse().unwrap(),
                                     },
+                                    functions: BTreeMap::new(),
                                 })
                         }
                     })
@@ -254,27 +345,159 @@ impl FromStr for SnapshotEntry {
     }
 }
 
-/// Reads a list of snapshot entries from a snapshot file
+/// An entry in a code snapshot, keyed by the identifier of its contract.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CodeSnapshotEntry {
+    /// The hash of the creation code, without the trailing CBOR metadata.
+    pub creation: B256,
+    /// The hash of the runtime code, without the trailing CBOR metadata.
+    pub runtime: B256,
+    /// The keccak256 hashes of the sources compiled into the contract, by path, as recorded in
+    /// its metadata.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub sources: BTreeMap<String, String>,
+}
+
+impl CodeSnapshotEntry {
+    /// Creates the entry of a compiled contract, returns `None` if it has no bytecode, e.g. for
+    /// interfaces and abstract contracts.
+    fn new(
+        artifact: &ConfigurableContractArtifact,
+        root: &Path,
+        strip_metadata: bool,
+    ) -> Option<Self> {
+        let code_hash = |object: &BytecodeObject| -> Option<B256> {
+            match object {
+                BytecodeObject::Bytecode(code) if code.is_empty() => None,
+                BytecodeObject::Bytecode(code) if strip_metadata => {
+                    Some(keccak256(strip_cbor_metadata(code)))
+                }
+                BytecodeObject::Bytecode(code) => Some(keccak256(code)),
+                // Library placeholders are hashed as is.
+                BytecodeObject::Unlinked(code) => Some(keccak256(code)),
+            }
+        };
+        let creation = code_hash(&artifact.bytecode.as_ref()?.object)?;
+        let runtime = code_hash(&artifact.deployed_bytecode.as_ref()?.bytecode.as_ref()?.object)?;
+        let sources = artifact
+            .metadata
+            .iter()
+            .flat_map(|metadata| &metadata.sources.inner)
+            .map(|(path, source)| {
+                let path = Path::new(path).strip_prefix(root).unwrap_or(Path::new(path));
+                (path.to_string_lossy().to_string(), source.keccak256.clone())
+            })
+            .collect();
+        Some(Self { creation, runtime, sources })
+    }
+}
+
+/// Strips the CBOR metadata appended by solc from the code, whose length is encoded in its last
+/// two bytes.
+fn strip_cbor_metadata(code: &[u8]) -> &[u8] {
+    let Some(len) = code.len().checked_sub(2) else { return code };
+    let metadata_len = u16::from_be_bytes([code[len], code[len + 1]]) as usize;
+    len.checked_sub(metadata_len).map_or(code, |end| &code[..end])
+}
+
+/// Compiles the project and returns the code snapshot entries of the contracts in its sources
+/// directory, by identifier.
+fn code_snapshot(build_args: &CoreBuildArgs) -> Result<BTreeMap<String, CodeSnapshotEntry>> {
+    // The metadata records the sources compiled into each contract.
+    let mut build_args = build_args.clone();
+    build_args.compiler.extra_output.push(ContractOutputSelection::Metadata);
+    let config = build_args.try_load_config_emit_warnings()?;
+    let project = config.project()?;
+    let output = ProjectCompiler::new().quiet(build_args.silent).compile(&project)?;
+
+    let root = &project.paths.root;
+    let mut entries = BTreeMap::new();
+    for (file, name, artifact) in output.into_artifacts_with_files() {
+        if !file.starts_with(&project.paths.sources) || file.is_sol_test() {
+            continue
+        }
+        if let Some(entry) = CodeSnapshotEntry::new(&artifact, root, config.cbor_metadata) {
+            let file = file.strip_prefix(root).unwrap_or(&file);
+            entries.insert(format!("{}:{name}", file.display()), entry);
+        }
+    }
+    Ok(entries)
+}
+
+/// Reads the entries of a code snapshot file.
+fn read_code_snapshot(path: &Path) -> Result<BTreeMap<String, CodeSnapshotEntry>> {
+    let content = fs::read_to_string(path)
+        .wrap_err(format!("failed to read snapshot file \"{}\"", path.display()))?;
+    serde_json::from_str(&content)
+        .wrap_err(format!("failed to parse code snapshot file \"{}\"", path.display()))
+}
+
+/// Compares the code of the contracts with an existing code snapshot.
+///
+/// Returns a line for each contract that was added, removed or whose code changed, followed by
+/// the sources whose changes caused it.
+fn diff_code(
+    entries: &BTreeMap<String, CodeSnapshotEntry>,
+    snaps: &BTreeMap<String, CodeSnapshotEntry>,
+) -> Vec<String> {
+    let mut lines = Vec::new();
+    for (id, entry) in entries {
+        let Some(snap) = snaps.get(id) else {
+            lines.push(format!("added {id}"));
+            continue
+        };
+        let code = match (entry.creation != snap.creation, entry.runtime != snap.runtime) {
+            (_, true) => "runtime code",
+            (true, false) => "creation code",
+            (false, false) => continue,
+        };
+        lines.push(format!("changed {id} ({code})"));
+        for (path, hash) in &entry.sources {
+            match snap.sources.get(path) {
+                Some(snap_hash) if snap_hash == hash => {}
+                Some(_) => lines.push(format!("    changed source {path}")),
+                None => lines.push(format!("    added source {path}")),
+            }
+        }
+        for path in snap.sources.keys().filter(|path| !entry.sources.contains_key(*path)) {
+            lines.push(format!("    removed source {path}"));
+        }
+    }
+    for id in snaps.keys().filter(|id| !entries.contains_key(*id)) {
+        lines.push(format!("removed {id}"));
+    }
+    lines
+}
+
+/// Reads a list of snapshot entries from a snapshot file, in either format
 fn read_snapshot(path: impl AsRef<Path>) -> Result<Vec<SnapshotEntry>> {
     let path = path.as_ref();
-    let mut entries = Vec::new();
-    for line in io::BufReader::new(
-        fs::File::open(path)
-            .wrap_err(format!("failed to read snapshot file \"{}\"", path.display()))?,
-    )
-    .lines()
-    {
-        entries.push(SnapshotEntry::from_str(line?.as_str()).map_err(|err| eyre::eyre!("{err}"))?);
+    let content = fs::read_to_string(path)
+        .wrap_err(format!("failed to read snapshot file \"{}\"", path.display()))?;
+    if content.trim_start().starts_with('[') {
+        return serde_json::from_str(&content)
+            .wrap_err(format!("failed to parse JSON snapshot file \"{}\"", path.display()))
     }
-    Ok(entries)
+    content
+        .lines()
+        .map(|line| SnapshotEntry::from_str(line).map_err(|err| eyre::eyre!("{err}")))
+        .collect()
 }
 
 /// Writes a series of tests to a snapshot file after sorting them
 fn write_to_snapshot_file(
     tests: &[SuiteTestResult],
     path: impl AsRef<Path>,
-    _format: Option<Format>,
+    format: Option<Format>,
 ) -> Result<()> {
+    if let Some(Format::Json) = format {
+        let mut entries = tests.iter().map(SnapshotEntry::new).collect::<Vec<_>>();
+        entries.sort_by(|a, b| {
+            (&a.contract_name, &a.signature).cmp(&(&b.contract_name, &b.signature))
+        });
+        return Ok(fs::write(path, serde_json::to_string_pretty(&entries)?)?)
+    }
+
     let mut reports = tests
         .iter()
         .map(|test| {
@@ -295,6 +518,8 @@ pub struct SnapshotDiff {
     pub signature: String,
     pub source_gas_used: TestKindReport,
     pub target_gas_used: TestKindReport,
+    /// The functions whose gas changed, with the gas used in the source and target.
+    pub functions: Vec<(String, u64, u64)>,
 }
 
 impl SnapshotDiff {
@@ -312,6 +537,26 @@ impl SnapshotDiff {
     }
 }
 
+/// Returns the functions called in both the source and the target whose gas changed.
+fn function_changes(
+    source: &BTreeMap<String, u64>,
+    target: &BTreeMap<String, u64>,
+) -> Vec<(String, u64, u64)> {
+    source
+        .iter()
+        .filter_map(|(name, &source_gas)| {
+            let target_gas = *target.get(name)?;
+            (source_gas != target_gas).then(|| (name.clone(), source_gas, target_gas))
+        })
+        .collect()
+}
+
+/// Returns true if the gas increase from `target_gas` to `source_gas` exceeds the threshold.
+fn is_regression(source_gas: u64, target_gas: u64, threshold_pct: u32) -> bool {
+    source_gas > target_gas &&
+        (source_gas - target_gas) as f64 / target_gas as f64 * 100. > threshold_pct as f64
+}
+
 /// Compares the set of tests with an existing snapshot
 ///
 /// Returns true all tests match
@@ -349,20 +594,26 @@ fn check(tests: Vec<SuiteTestResult>, snaps: Vec<SnapshotEntry>, tolerance: Opti
 }
 
 /// Compare the set of tests with an existing snapshot
-fn diff(tests: Vec<SuiteTestResult>, snaps: Vec<SnapshotEntry>) -> Result<()> {
+///
+/// Returns false if any test or function regressed by more than `threshold` percent.
+fn diff(
+    tests: Vec<SuiteTestResult>,
+    snaps: Vec<SnapshotEntry>,
+    threshold: Option<u32>,
+) -> Result<bool> {
     let snaps = snaps
         .into_iter()
-        .map(|s| ((s.contract_name, s.signature), s.gas_used))
+        .map(|s| ((s.contract_name.clone(), s.signature.clone()), s))
         .collect::<HashMap<_, _>>();
     let mut diffs = Vec::with_capacity(tests.len());
     for test in tests.into_iter() {
-        if let Some(target_gas_used) =
-            snaps.get(&(test.contract_name().to_string(), test.signature.clone())).cloned()
+        if let Some(target) = snaps.get(&(test.contract_name().to_string(), test.signature.clone()))
         {
             diffs.push(SnapshotDiff {
                 source_gas_used: test.result.kind.report(),
+                functions: function_changes(&test.result.function_gas, &target.functions),
                 signature: test.signature,
-                target_gas_used,
+                target_gas_used: target.gas_used.clone(),
             });
         }
     }
@@ -373,6 +624,7 @@ fn diff(tests: Vec<SuiteTestResult>, snaps: Vec<SnapshotEntry>) -> Result<()> {
         a.gas_diff().abs().partial_cmp(&b.gas_diff().abs()).unwrap_or(Ordering::Equal)
     });
 
+    let mut regressions = Vec::new();
     for diff in diffs {
         let gas_change = diff.gas_change();
         overall_gas_change += gas_change;
@@ -384,6 +636,27 @@ fn diff(tests: Vec<SuiteTestResult>, snaps: Vec<SnapshotEntry>) -> Result<()> {
             fmt_change(gas_change),
             fmt_pct_change(gas_diff)
         );
+
+        let (source_gas, target_gas) = (diff.source_gas_used.gas(), diff.target_gas_used.gas());
+        if threshold.is_some_and(|threshold| is_regression(source_gas, target_gas, threshold)) {
+            regressions.push((diff.signature.clone(), source_gas, target_gas));
+        }
+
+        for (function, source_gas, target_gas) in diff.functions {
+            let gas_change = source_gas as i128 - target_gas as i128;
+            println!(
+                "    {function} (gas: {} ({})) ",
+                fmt_change(gas_change),
+                fmt_pct_change(gas_change as f64 / target_gas as f64)
+            );
+            if threshold.is_some_and(|threshold| is_regression(source_gas, target_gas, threshold)) {
+                regressions.push((
+                    format!("{}: {function}", diff.signature),
+                    source_gas,
+                    target_gas,
+                ));
+            }
+        }
     }
 
     let overall_gas_diff = overall_gas_change as f64 / overall_gas_used as f64;
@@ -392,7 +665,16 @@ fn diff(tests: Vec<SuiteTestResult>, snaps: Vec<SnapshotEntry>) -> Result<()> {
         fmt_change(overall_gas_change),
         fmt_pct_change(overall_gas_diff)
     );
-    Ok(())
+
+    if let Some(threshold) = threshold {
+        if !regressions.is_empty() {
+            eprintln!("Gas regressions exceeding {threshold}%:");
+        }
+        for (name, source_gas, target_gas) in &regressions {
+            eprintln!("  {name}: consumed \"{source_gas}\" gas, expected \"{target_gas}\" gas");
+        }
+    }
+    Ok(regressions.is_empty())
 }
 
 fn fmt_pct_change(change: f64) -> String {
@@ -455,11 +737,20 @@ mod tests {
             SnapshotEntry {
                 contract_name: "Test".to_string(),
                 signature: "deposit()".to_string(),
-                gas_used: TestKindReport::Unit { gas: 7222 }
+                gas_used: TestKindReport::Unit { gas: 7222, refund: 0 },
+                functions: BTreeMap::new(),
             }
         );
     }
 
+    #[test]
+    fn can_parse_refund_snapshot_entry() {
+        let s = "Test:withdraw() (gas: 9133, refund: 4800)";
+        let entry = SnapshotEntry::from_str(s).unwrap();
+        assert_eq!(entry.gas_used, TestKindReport::Unit { gas: 9133, refund: 4800 });
+        assert_eq!(entry.gas_used.to_string(), "(gas: 9133, refund: 4800)");
+    }
+
     #[test]
     fn can_parse_fuzz_snapshot_entry() {
         let s = "Test:deposit() (runs: 256, μ: 100, ~:200)";
@@ -469,7 +760,8 @@ mod tests {
             SnapshotEntry {
                 contract_name: "Test".to_string(),
                 signature: "deposit()".to_string(),
-                gas_used: TestKindReport::Fuzz { runs: 256, median_gas: 200, mean_gas: 100 }
+                gas_used: TestKindReport::Fuzz { runs: 256, median_gas: 200, mean_gas: 100 },
+                functions: BTreeMap::new(),
             }
         );
     }
@@ -483,7 +775,8 @@ mod tests {
             SnapshotEntry {
                 contract_name: "Test".to_string(),
                 signature: "deposit()".to_string(),
-                gas_used: TestKindReport::Invariant { runs: 256, calls: 100, reverts: 200 }
+                gas_used: TestKindReport::Invariant { runs: 256, calls: 100, reverts: 200 },
+                functions: BTreeMap::new(),
             }
         );
     }
@@ -497,8 +790,75 @@ mod tests {
             SnapshotEntry {
                 contract_name: "ERC20Invariants".to_string(),
                 signature: "invariantBalanceSum()".to_string(),
-                gas_used: TestKindReport::Invariant { runs: 256, calls: 3840, reverts: 2388 }
+                gas_used: TestKindReport::Invariant { runs: 256, calls: 3840, reverts: 2388 },
+                functions: BTreeMap::new(),
             }
         );
     }
+
+    #[test]
+    fn can_roundtrip_json_snapshot_entry() {
+        let entry = SnapshotEntry {
+            contract_name: "CounterTest".to_string(),
+            signature: "testIncrement()".to_string(),
+            gas_used: TestKindReport::Unit { gas: 31303, refund: 0 },
+            functions: BTreeMap::from([("Counter::increment()".to_string(), 22340)]),
+        };
+        let json = serde_json::to_value(&entry).unwrap();
+        assert_eq!(json["kind"], "unit");
+        assert_eq!(json["gas"], 31303);
+        assert!(json.get("refund").is_none());
+        assert_eq!(serde_json::from_value::<SnapshotEntry>(json).unwrap(), entry);
+    }
+
+    #[test]
+    fn test_regression_threshold() {
+        assert!(is_regression(106, 100, 5));
+        assert!(!is_regression(105, 100, 5));
+        assert!(!is_regression(90, 100, 5));
+        assert!(is_regression(101, 100, 0));
+
+        let source = BTreeMap::from([("A::a()".to_string(), 10), ("A::b()".to_string(), 20)]);
+        let target = BTreeMap::from([("A::a()".to_string(), 10), ("A::b()".to_string(), 15)]);
+        assert_eq!(function_changes(&source, &target), vec![("A::b()".to_string(), 20, 15)]);
+    }
+
+    #[test]
+    fn can_strip_cbor_metadata() {
+        assert_eq!(strip_cbor_metadata(&[0x60, 0x80, 0xa1, 0xff, 0x00, 0x02]), [0x60, 0x80]);
+        assert_eq!(strip_cbor_metadata(&[0x60, 0x80, 0x00, 0x02]), [] as [u8; 0]);
+        assert_eq!(strip_cbor_metadata(&[0x60, 0x80, 0xff]), [0x60, 0x80, 0xff]);
+        assert_eq!(strip_cbor_metadata(&[0x01]), [0x01]);
+    }
+
+    #[test]
+    fn can_diff_code_snapshots() {
+        let entry = |code: u8, source: &str| CodeSnapshotEntry {
+            creation: B256::with_last_byte(code),
+            runtime: B256::with_last_byte(code),
+            sources: BTreeMap::from([
+                ("src/A.sol".to_string(), source.to_string()),
+                ("src/Lib.sol".to_string(), "0x01".to_string()),
+            ]),
+        };
+        let snaps = BTreeMap::from([
+            ("src/A.sol:A".to_string(), entry(1, "0x01")),
+            ("src/B.sol:B".to_string(), entry(2, "0x01")),
+        ]);
+        assert!(diff_code(&snaps, &snaps).is_empty());
+
+        let entries = BTreeMap::from([
+            ("src/A.sol:A".to_string(), entry(3, "0x02")),
+            ("src/C.sol:C".to_string(), entry(4, "0x01")),
+        ]);
+        assert_eq!(
+            diff_code(&entries, &snaps),
+            [
+                "changed src/A.sol:A (runtime code)",
+                "    changed source src/A.sol",
+                "added src/C.sol:C",
+                "removed src/B.sol:B",
+            ]
+        );
+    }
 }
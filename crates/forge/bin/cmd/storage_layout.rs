@@ -0,0 +1,536 @@
+use crate::cmd::test::FilterArgs;
+use alloy_primitives::U256;
+use clap::Parser;
+use comfy_table::{presets::ASCII_MARKDOWN, Table};
+use eyre::{Context, Result};
+use forge::{result::TestStatus, MultiContractRunnerBuilder, TestOptions};
+use foundry_cli::{opts::CoreBuildArgs, utils::LoadConfig};
+use foundry_common::{compile::ProjectCompiler, evm::EvmArgs, ContractsByArtifact};
+use foundry_compilers::{
+    artifacts::{output_selection::ContractOutputSelection, Storage, StorageLayout, StorageType},
+    info::ContractInfo,
+    ArtifactId,
+};
+use foundry_evm::inspectors::DeclaredStorage;
+use serde::Serialize;
+use std::{fmt, ops::Range, sync::Arc};
+use yansi::Paint;
+
+// Loads project's figment and merges the build cli arguments into it
+foundry_config::merge_impl_figment_convert!(VerifyStorageLayoutArgs, build, evm_opts);
+
+/// CLI arguments for `forge verify-storage-layout`.
+#[derive(Clone, Debug, Parser)]
+pub struct VerifyStorageLayoutArgs {
+    /// The current implementation, in the form `(<path>:)?<contractname>`.
+    pub old: ContractInfo,
+
+    /// The new implementation, in the form `(<path>:)?<contractname>`.
+    pub new: ContractInfo,
+
+    /// Only compare the storage layouts, without checking the storage writes of the tests.
+    #[arg(long)]
+    pub no_tests: bool,
+
+    /// Print the result as JSON.
+    #[arg(long, help_heading = "Display options")]
+    pub json: bool,
+
+    #[command(flatten)]
+    filter: FilterArgs,
+
+    #[command(flatten)]
+    evm_opts: EvmArgs,
+
+    #[command(flatten)]
+    build: CoreBuildArgs,
+}
+
+impl VerifyStorageLayoutArgs {
+    pub async fn run(mut self) -> Result<()> {
+        // Storage layouts are not part of the default output selection.
+        if !self.build.compiler.extra_output.contains(&ContractOutputSelection::StorageLayout) {
+            self.build.compiler.extra_output.push(ContractOutputSelection::StorageLayout);
+        }
+        let (config, evm_opts) = self.load_config_and_evm_opts_emit_warnings()?;
+        let project = config.project()?;
+        let output =
+            ProjectCompiler::new().quiet(self.json || self.build.silent).compile(&project)?;
+
+        let find_layout = |contract: &ContractInfo| -> Result<(ArtifactId, StorageLayout)> {
+            let (id, artifact) = find_contract(output.artifact_ids(), contract)?;
+            let layout = artifact
+                .storage_layout
+                .clone()
+                .ok_or_else(|| eyre::eyre!("no storage layout found for {}", contract.name))?;
+            Ok((id, layout))
+        };
+        let (_, old_layout) = find_layout(&self.old)?;
+        let (new_id, new_layout) = find_layout(&self.new)?;
+
+        let mut report =
+            LayoutReport { issues: diff_layouts(&old_layout, &new_layout), ..Default::default() };
+
+        if !self.no_tests {
+            let env = evm_opts.evm_env().await?;
+            let config = Arc::new(config);
+            let mut runner = MultiContractRunnerBuilder::new(config.clone())
+                .initial_balance(evm_opts.initial_balance)
+                .evm_spec(config.evm_spec_id())
+                .sender(evm_opts.sender)
+                .with_fork(evm_opts.get_fork(&config, env.clone()))
+                .with_test_options(TestOptions {
+                    fuzz: config.fuzz.clone(),
+                    invariant: config.invariant.clone(),
+                    ..Default::default()
+                })
+                .enable_isolation(evm_opts.isolate)
+                .build(project.root(), &output, env, evm_opts)?;
+
+            let declared =
+                declared_storage(&runner.known_contracts, &self.new, &new_id, &new_layout)?;
+            runner.access_policy.declared_storage.push(declared);
+
+            let filter = self.filter.clone().merge_with_config(&config);
+            for (suite, result) in runner.test_collect(&filter) {
+                for (test, result) in result.test_results {
+                    if result.status != TestStatus::Failure {
+                        report.tests_passed += 1;
+                        continue
+                    }
+                    match result.reason {
+                        Some(reason) if reason.contains("outside the storage layout") => {
+                            report
+                                .violations
+                                .push(StorageViolation { test: format!("{suite}:{test}"), reason });
+                        }
+                        _ => report.tests_failed += 1,
+                    }
+                }
+            }
+        }
+
+        if self.json {
+            println!("{}", serde_json::to_string_pretty(&report)?);
+        } else {
+            println!("{report}");
+        }
+
+        if report.has_errors() {
+            std::process::exit(1);
+        }
+        Ok(())
+    }
+}
+
+/// Finds the single artifact matching the given contract.
+fn find_contract<T>(
+    artifacts: impl Iterator<Item = (ArtifactId, T)>,
+    contract: &ContractInfo,
+) -> Result<(ArtifactId, T)> {
+    let mut matches = artifacts.filter(|(id, _)| {
+        id.name == contract.name &&
+            contract.path.as_ref().map_or(true, |path| id.source.ends_with(path))
+    });
+    let found = matches.next().ok_or_else(|| eyre::eyre!("could not find {contract}"))?;
+    if matches.next().is_some() {
+        eyre::bail!("multiple contracts match {contract}, specify the path as `<path>:<name>`");
+    }
+    Ok(found)
+}
+
+/// Returns the storage that the new implementation may write to, as declared by its layout.
+fn declared_storage(
+    known_contracts: &ContractsByArtifact,
+    contract: &ContractInfo,
+    id: &ArtifactId,
+    layout: &StorageLayout,
+) -> Result<DeclaredStorage> {
+    let artifacts = known_contracts.iter().map(|(id, data)| (id.clone(), data));
+    let (_, data) = find_contract(artifacts, contract)
+        .wrap_err("the new implementation must be deployable to check the writes of the tests")?;
+    let code = data
+        .deployed_bytecode()
+        .cloned()
+        .ok_or_else(|| eyre::eyre!("{} has no deployed bytecode", id.name))?;
+    let ignored = data
+        .deployed_bytecode
+        .iter()
+        .flat_map(|code| code.immutable_references.values().flatten())
+        .map(|offsets| offsets.start as usize..(offsets.start + offsets.length) as usize)
+        .collect();
+    let slots = layout
+        .storage
+        .iter()
+        .filter(|var| !is_gap(var))
+        .filter_map(|var| Variable::new(var, layout))
+        .map(|var| var.slots())
+        .collect();
+    Ok(DeclaredStorage { name: id.name.clone(), code, ignored, slots })
+}
+
+/// Returns true if the variable is a storage gap reserved for future variables.
+fn is_gap(var: &Storage) -> bool {
+    var.label.starts_with("__gap")
+}
+
+/// A state variable, located in storage.
+struct Variable<'a> {
+    var: &'a Storage,
+    slot: U256,
+    offset: u64,
+    bytes: U256,
+}
+
+impl<'a> Variable<'a> {
+    fn new(var: &'a Storage, layout: &'a StorageLayout) -> Option<Self> {
+        let ty = layout.types.get(&var.storage_type)?;
+        Some(Self {
+            var,
+            slot: var.slot.parse().ok()?,
+            offset: var.offset.try_into().ok()?,
+            bytes: ty.number_of_bytes.parse().ok()?,
+        })
+    }
+
+    /// Returns the name of the variable, qualified by the contract declaring it.
+    fn name(&self) -> String {
+        let contract = self.var.contract.rsplit(':').next().unwrap_or(&self.var.contract);
+        format!("{contract}.{}", self.var.label)
+    }
+
+    /// Returns the range of storage bytes the variable occupies, counted from slot zero.
+    fn bytes(&self) -> Range<U256> {
+        let start = self.slot * U256::from(32) + U256::from(self.offset);
+        start..start + self.bytes
+    }
+
+    /// Returns the range of slots the variable occupies.
+    fn slots(&self) -> Range<U256> {
+        let bytes = self.bytes();
+        bytes.start / U256::from(32)..(bytes.end + U256::from(31)) / U256::from(32)
+    }
+}
+
+/// How severe a storage layout incompatibility is.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// Upgrading would corrupt the storage.
+    Error,
+    /// Upgrading is safe, but might not be intended.
+    Warning,
+}
+
+/// An incompatibility between the storage layouts of two implementations.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct LayoutIssue {
+    pub severity: Severity,
+    pub variable: String,
+    pub slot: String,
+    pub message: String,
+}
+
+/// A storage write of the new implementation outside of its layout, made while running a test.
+#[derive(Clone, Debug, Serialize)]
+pub struct StorageViolation {
+    pub test: String,
+    pub reason: String,
+}
+
+/// The result of `forge verify-storage-layout`.
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LayoutReport {
+    pub issues: Vec<LayoutIssue>,
+    pub violations: Vec<StorageViolation>,
+    pub tests_passed: usize,
+    pub tests_failed: usize,
+}
+
+impl LayoutReport {
+    /// Returns true if upgrading is not safe.
+    pub fn has_errors(&self) -> bool {
+        !self.violations.is_empty() ||
+            self.issues.iter().any(|issue| issue.severity == Severity::Error)
+    }
+}
+
+impl fmt::Display for LayoutReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !self.issues.is_empty() {
+            let mut table = Table::new();
+            table.load_preset(ASCII_MARKDOWN);
+            table.set_header(["Severity", "Variable", "Slot", "Issue"]);
+            for issue in &self.issues {
+                let severity = match issue.severity {
+                    Severity::Error => "error",
+                    Severity::Warning => "warning",
+                };
+                table.add_row([severity, &issue.variable, &issue.slot, &issue.message]);
+            }
+            writeln!(f, "{table}\n")?;
+        }
+        for violation in &self.violations {
+            writeln!(f, "{} {}: {}", "[VIOLATION]".red(), violation.test, violation.reason)?;
+        }
+        if self.tests_failed > 0 {
+            writeln!(
+                f,
+                "{} tests failed for other reasons and were not checked completely",
+                self.tests_failed
+            )?;
+        }
+        if self.has_errors() {
+            write!(f, "{}", "Storage layouts are incompatible".red())
+        } else {
+            write!(f, "{}", "Storage layouts are compatible".green())
+        }
+    }
+}
+
+/// Compares the storage layout of the new implementation against the old one.
+///
+/// Every variable of the old layout must be kept at the same slot and offset with a compatible
+/// type, and new variables may only use storage that was unused or reserved by a gap. Renames are
+/// reported as warnings.
+pub fn diff_layouts(old: &StorageLayout, new: &StorageLayout) -> Vec<LayoutIssue> {
+    let old_vars =
+        old.storage.iter().filter(|var| !is_gap(var)).filter_map(|var| Variable::new(var, old));
+    let old_vars = old_vars.collect::<Vec<_>>();
+    let new_vars = new.storage.iter().filter_map(|var| Variable::new(var, new)).collect::<Vec<_>>();
+
+    let mut issues = Vec::new();
+    let mut issue = |severity, var: &Variable<'_>, message: String| {
+        issues.push(LayoutIssue {
+            severity,
+            variable: var.name(),
+            slot: format!("{}:{}", var.slot, var.offset),
+            message,
+        })
+    };
+
+    for old_var in &old_vars {
+        let Some(new_var) = new_vars
+            .iter()
+            .find(|new_var| new_var.slot == old_var.slot && new_var.offset == old_var.offset)
+        else {
+            issue(Severity::Error, old_var, "was removed or moved to another slot".to_string());
+            continue
+        };
+        if is_gap(new_var.var) {
+            issue(Severity::Error, old_var, "was replaced by a storage gap".to_string());
+        } else if let Err(reason) =
+            check_type(old, &old_var.var.storage_type, new, &new_var.var.storage_type, true)
+        {
+            issue(Severity::Error, old_var, reason);
+        } else if old_var.var.label != new_var.var.label {
+            issue(Severity::Warning, old_var, format!("was renamed to `{}`", new_var.var.label));
+        }
+    }
+
+    for new_var in &new_vars {
+        let bytes = new_var.bytes();
+        let overlapped = old_vars.iter().find(|old_var| {
+            let old_bytes = old_var.bytes();
+            (new_var.slot != old_var.slot || new_var.offset != old_var.offset) &&
+                bytes.start < old_bytes.end &&
+                old_bytes.start < bytes.end
+        });
+        if let Some(old_var) = overlapped {
+            let message = format!("overlaps `{}` of the old layout", old_var.name());
+            issue(Severity::Error, new_var, message);
+        }
+    }
+
+    issues
+}
+
+/// Checks that values of the old type can be read as the new type.
+///
+/// `inline` is true if the value is stored in place, in which case its size may not change. This
+/// is not the case for mapping values, e.g. structs in mappings may get new members.
+fn check_type(
+    old: &StorageLayout,
+    old_id: &str,
+    new: &StorageLayout,
+    new_id: &str,
+    inline: bool,
+) -> Result<(), String> {
+    let (Some(old_ty), Some(new_ty)) = (old.types.get(old_id), new.types.get(new_id)) else {
+        return Ok(())
+    };
+    if old_ty.encoding != new_ty.encoding {
+        return Err(format!("changed type from `{}` to `{}`", old_ty.label, new_ty.label));
+    }
+    if inline && old_ty.number_of_bytes != new_ty.number_of_bytes {
+        return Err(format!(
+            "changed size from {} to {} bytes (`{}` to `{}`)",
+            old_ty.number_of_bytes, new_ty.number_of_bytes, old_ty.label, new_ty.label
+        ));
+    }
+
+    let members = |ty: &StorageType| {
+        ty.other
+            .get("members")
+            .and_then(|members| serde_json::from_value::<Vec<Storage>>(members.clone()).ok())
+    };
+    if let (Some(old_members), Some(new_members)) = (members(old_ty), members(new_ty)) {
+        for old_member in &old_members {
+            let Some(new_member) = new_members
+                .iter()
+                .find(|m| m.slot == old_member.slot && m.offset == old_member.offset)
+            else {
+                return Err(format!(
+                    "member `{}` of `{}` was removed or moved",
+                    old_member.label, old_ty.label
+                ));
+            };
+            check_type(old, &old_member.storage_type, new, &new_member.storage_type, true)
+                .map_err(|reason| format!("member `{}` {reason}", old_member.label))?;
+        }
+        return Ok(())
+    }
+
+    let label = |layout: &StorageLayout, id: &Option<String>| {
+        id.as_ref().and_then(|id| layout.types.get(id)).map(|ty| ty.label.clone())
+    };
+    if old_ty.key.is_some() || new_ty.key.is_some() {
+        if label(old, &old_ty.key) != label(new, &new_ty.key) {
+            return Err(format!("changed type from `{}` to `{}`", old_ty.label, new_ty.label));
+        }
+    }
+    if let (Some(old_value), Some(new_value)) = (&old_ty.value, &new_ty.value) {
+        return check_type(old, old_value, new, new_value, false)
+            .map_err(|reason| format!("value {reason}"));
+    }
+
+    let base =
+        |ty: &StorageType| ty.other.get("base").and_then(|base| base.as_str().map(String::from));
+    if let (Some(old_base), Some(new_base)) = (base(old_ty), base(new_ty)) {
+        return check_type(old, &old_base, new, &new_base, true)
+            .map_err(|reason| format!("element {reason}"));
+    }
+
+    // Structs may be renamed, other types must stay the same.
+    if !old_ty.label.starts_with("struct ") && old_ty.label != new_ty.label {
+        return Err(format!("changed type from `{}` to `{}`", old_ty.label, new_ty.label));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn layout(storage: serde_json::Value) -> StorageLayout {
+        serde_json::from_value(serde_json::json!({
+            "storage": storage,
+            "types": {
+                "t_uint256": { "encoding": "inplace", "label": "uint256", "numberOfBytes": "32" },
+                "t_uint128": { "encoding": "inplace", "label": "uint128", "numberOfBytes": "16" },
+                "t_address": { "encoding": "inplace", "label": "address", "numberOfBytes": "20" },
+                "t_array(t_uint256)2_storage": {
+                    "base": "t_uint256",
+                    "encoding": "inplace",
+                    "label": "uint256[2]",
+                    "numberOfBytes": "64"
+                },
+                "t_mapping(t_address,t_uint256)": {
+                    "encoding": "mapping",
+                    "key": "t_address",
+                    "label": "mapping(address => uint256)",
+                    "numberOfBytes": "32",
+                    "value": "t_uint256"
+                }
+            }
+        }))
+        .unwrap()
+    }
+
+    fn var(label: &str, slot: u64, offset: u64, ty: &str) -> serde_json::Value {
+        serde_json::json!({
+            "astId": 1,
+            "contract": "src/Impl.sol:Impl",
+            "label": label,
+            "offset": offset,
+            "slot": slot.to_string(),
+            "type": ty
+        })
+    }
+
+    #[test]
+    fn accepts_appended_variables_and_gap_usage() {
+        let old = layout(serde_json::json!([
+            var("owner", 0, 0, "t_address"),
+            var("__gap", 1, 0, "t_array(t_uint256)2_storage"),
+            var("balances", 3, 0, "t_mapping(t_address,t_uint256)"),
+        ]));
+        let new = layout(serde_json::json!([
+            var("owner", 0, 0, "t_address"),
+            var("total", 1, 0, "t_uint256"),
+            var("__gap", 2, 0, "t_uint256"),
+            var("balances", 3, 0, "t_mapping(t_address,t_uint256)"),
+            var("paused", 4, 0, "t_uint256"),
+        ]));
+        assert_eq!(diff_layouts(&old, &new), vec![]);
+    }
+
+    #[test]
+    fn rejects_incompatible_layouts() {
+        let old = layout(serde_json::json!([
+            var("owner", 0, 0, "t_address"),
+            var("total", 1, 0, "t_uint256"),
+            var("balances", 2, 0, "t_mapping(t_address,t_uint256)"),
+        ]));
+        let new = layout(serde_json::json!([
+            var("admin", 0, 0, "t_address"),
+            var("inserted", 1, 0, "t_uint128"),
+            var("total", 2, 0, "t_uint256"),
+        ]));
+        let issues = diff_layouts(&old, &new);
+        let messages = issues
+            .iter()
+            .map(|i| (i.severity, i.variable.as_str(), i.message.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            messages,
+            vec![
+                (Severity::Warning, "Impl.owner", "was renamed to `admin`"),
+                (
+                    Severity::Error,
+                    "Impl.total",
+                    "changed size from 32 to 16 bytes (`uint256` to `uint128`)"
+                ),
+                (
+                    Severity::Error,
+                    "Impl.balances",
+                    "changed type from `mapping(address => uint256)` to `uint256`"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn computes_variable_slots() {
+        let layout = layout(serde_json::json!([
+            var("a", 0, 0, "t_uint128"),
+            var("b", 0, 16, "t_uint128"),
+            var("c", 1, 0, "t_array(t_uint256)2_storage"),
+        ]));
+        let slots = layout
+            .storage
+            .iter()
+            .filter_map(|var| Variable::new(var, &layout))
+            .map(|var| var.slots())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            slots,
+            vec![
+                U256::ZERO..U256::from(1),
+                U256::ZERO..U256::from(1),
+                U256::from(1)..U256::from(3)
+            ]
+        );
+    }
+}
@@ -0,0 +1,88 @@
+use foundry_common::{compile::ContractSources, ContractsByArtifact};
+use foundry_debugger::{Debugger, ExitReason};
+use foundry_evm::inspectors::{BreakpointAction, BreakpointContext, BreakpointHandler};
+use std::{collections::BTreeMap, sync::Mutex};
+
+/// Opens the TUI debugger whenever a test pauses at a breakpoint.
+#[derive(Debug)]
+pub struct TuiBreakpointHandler {
+    sources: ContractSources,
+    known_contracts: ContractsByArtifact,
+    /// Makes sure that only one debugger is open at a time.
+    lock: Mutex<()>,
+}
+
+impl TuiBreakpointHandler {
+    pub fn new(sources: ContractSources, known_contracts: ContractsByArtifact) -> Self {
+        Self { sources, known_contracts, lock: Mutex::new(()) }
+    }
+}
+
+impl BreakpointHandler for TuiBreakpointHandler {
+    fn on_breakpoint(&self, context: &BreakpointContext<'_>) -> BreakpointAction {
+        let _guard = self.lock.lock().unwrap_or_else(|e| e.into_inner());
+        let Some(traces) = context.traces else { return BreakpointAction::Detach };
+
+        // Identify the contracts by their code, as traces are only decoded once the test is done.
+        let identified_contracts =
+            context.journaled_state.state.iter().filter_map(|(address, account)| {
+                let code = account.info.code.as_ref()?;
+                let (id, _) =
+                    self.known_contracts.find_by_deployed_code(code.original_byte_slice())?;
+                Some((*address, id.name.clone()))
+            });
+
+        let mut debugger = Debugger::builder()
+            .trace_arena(traces.clone())
+            .sources(self.sources.clone())
+            .identified_contracts(identified_contracts)
+            .live_state(state_lines(context))
+            .build();
+        match debugger.try_run() {
+            Ok(ExitReason::Resume) => BreakpointAction::Resume,
+            Ok(ExitReason::Step) => BreakpointAction::Step,
+            Ok(ExitReason::CharExit) => BreakpointAction::Detach,
+            Err(err) => {
+                error!(%err, "failed to run the debugger");
+                BreakpointAction::Detach
+            }
+        }
+    }
+}
+
+/// The number of stack items to show.
+const STACK_ITEMS: usize = 8;
+
+/// Describes the stack and memory of the current call and the state of the accounts loaded so
+/// far, sorted by address.
+fn state_lines(context: &BreakpointContext<'_>) -> Vec<String> {
+    let mut lines = Vec::new();
+    if let Some(label) = context.label {
+        lines.push(format!(
+            "Paused at breakpoint '{label}' in {} at pc {}",
+            context.address, context.pc
+        ));
+        lines.push(String::new());
+    }
+
+    let interpreter = &context.interpreter;
+    let stack = interpreter.stack_top(STACK_ITEMS).iter().rev().map(|item| format!("{item:#x}"));
+    lines.push(format!("Stack (top first): [{}]", stack.collect::<Vec<_>>().join(", ")));
+    lines.push(format!("Memory: {} bytes", interpreter.memory_len()));
+    lines.push(String::new());
+
+    let accounts = context.journaled_state.state.iter().collect::<BTreeMap<_, _>>();
+    for (address, account) in accounts {
+        let info = &account.info;
+        let code_size = info.code.as_ref().map_or(0, |code| code.len());
+        lines.push(format!(
+            "{address}: balance {}, nonce {}, code {code_size} bytes",
+            info.balance, info.nonce
+        ));
+        let storage = account.storage.iter().collect::<BTreeMap<_, _>>();
+        for (slot, value) in storage {
+            lines.push(format!("  {slot:#x}: {:#x}", value.present_value));
+        }
+    }
+    lines
+}
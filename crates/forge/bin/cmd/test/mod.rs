@@ -1,12 +1,15 @@
 use super::{install, test::filter::ProjectPathsAwareFilter, watch::WatchArgs};
 use alloy_primitives::U256;
-use clap::Parser;
+use clap::{Parser, ValueEnum};
 use eyre::Result;
 use forge::{
     decode::decode_console_logs,
-    gas_report::GasReport,
+    fuzz::FuzzReplay,
+    gas_report::{function_gas, GasReport},
     multi_runner::matches_contract,
-    result::{SuiteResult, TestOutcome, TestStatus},
+    report::{junit_report, TestReport, TestReportWriter, TraceReport},
+    result::{SuiteResult, TestOutcome, TestResult, TestStatus},
+    test_artifacts::{TestArtifact, TestArtifactsWriter, TEST_ARTIFACTS_DIR},
     traces::{identifier::SignaturesIdentifier, CallTraceDecoderBuilder, TraceKind},
     MultiContractRunner, MultiContractRunnerBuilder, TestFilter, TestOptions, TestOptionsBuilder,
 };
@@ -17,12 +20,15 @@ use foundry_cli::{
 use foundry_common::{
     compile::{ContractSources, ProjectCompiler},
     evm::EvmArgs,
-    shell,
+    progress::{self, ProgressMode},
+    shell, ContractsByArtifact,
 };
 use foundry_compilers::{
     artifacts::output_selection::OutputSelection,
     compilers::{multi::MultiCompilerLanguage, CompilerSettings, Language},
+    resolver::parse::SolData,
     utils::source_files_iter,
+    Graph,
 };
 use foundry_config::{
     figment,
@@ -33,10 +39,15 @@ use foundry_config::{
     get_available_profiles, Config,
 };
 use foundry_debugger::Debugger;
-use foundry_evm::traces::identifier::TraceIdentifiers;
+use foundry_evm::{
+    backend::AnalyzedBytecodeCache, fork::RpcUsageRegistry, inspectors::CancellationToken,
+    rng::RandomSource, traces::identifier::TraceIdentifiers,
+};
 use regex::Regex;
+use semver::Version;
 use std::{
     collections::{BTreeMap, BTreeSet},
+    fmt::Write,
     path::PathBuf,
     sync::{mpsc::channel, Arc},
     time::Instant,
@@ -45,11 +56,13 @@ use watchexec::config::{InitConfig, RuntimeConfig};
 use yansi::Paint;
 
 mod filter;
+mod interactive;
 mod summary;
+use interactive::TuiBreakpointHandler;
 use summary::TestSummaryReporter;
 
 pub use filter::FilterArgs;
-use forge::traces::render_trace_arena;
+use forge::traces::{render_trace_arena_with_refunds, GasRefunds};
 
 // Loads project's figment and merges the build cli arguments into it
 foundry_config::merge_impl_figment_convert!(TestArgs, opts, evm_opts);
@@ -76,6 +89,15 @@ pub struct TestArgs {
     #[arg(long, value_name = "TEST_FUNCTION")]
     debug: Option<Regex>,
 
+    /// Open the debugger whenever the test hits a breakpoint, instead of only once it's done.
+    ///
+    /// Breakpoints are set with the `breakpoint` cheatcode. From the debugger, execution can be
+    /// resumed with `r`, stepped one instruction at a time with `n`, and the state of the
+    /// accounts loaded so far can be inspected with `S`. Quitting ignores all further
+    /// breakpoints.
+    #[arg(long, requires = "debug")]
+    interactive: bool,
+
     /// Print a gas report.
     #[arg(long, env = "FORGE_GAS_REPORT")]
     gas_report: bool,
@@ -88,6 +110,21 @@ pub struct TestArgs {
     #[arg(long, short, help_heading = "Display options")]
     json: bool,
 
+    /// Output a machine-readable report of the test results instead of the usual output.
+    ///
+    /// `ndjson` streams a versioned JSON object per test as soon as its suite finishes, followed
+    /// by a summary, as described by `crates/forge/assets/test-report.schema.json`. Traces are
+    /// included depending on the verbosity. `junit` prints a JUnit XML report once all tests
+    /// have run.
+    #[arg(
+        long,
+        value_enum,
+        value_name = "FORMAT",
+        help_heading = "Display options",
+        conflicts_with_all = ["json", "debug", "list", "solc_matrix"]
+    )]
+    report: Option<TestReportFormat>,
+
     /// Stop running tests after the first failure.
     #[arg(long)]
     pub fail_fast: bool,
@@ -100,7 +137,11 @@ pub struct TestArgs {
     #[arg(long, short, help_heading = "Display options")]
     list: bool,
 
-    /// Set seed used to generate randomness during your fuzz runs.
+    /// Set the seed all the randomness of the run is derived from, both for the fuzzer and the
+    /// `vm.random*` cheatcodes.
+    ///
+    /// Defaults to the `FOUNDRY_SEED` environment variable, or a random seed which is printed
+    /// after the tests ran.
     #[arg(long)]
     pub fuzz_seed: Option<U256>,
 
@@ -111,6 +152,37 @@ pub struct TestArgs {
     #[arg(long)]
     pub fuzz_input_file: Option<String>,
 
+    /// Maximum number of candidate sequences to try when shrinking an invariant failure.
+    #[arg(long, value_name = "RUNS")]
+    pub shrink_runs: Option<u32>,
+
+    /// Explain invariant failures by finding which calls and arguments of the shrunk sequence
+    /// are needed to break the invariant.
+    #[arg(long)]
+    pub explain_failures: bool,
+
+    /// Run tests in deterministic mode.
+    ///
+    /// Host-dependent cheatcodes are forbidden and every test is executed twice to verify that its
+    /// results are reproducible.
+    #[arg(long)]
+    pub deterministic: bool,
+
+    /// Re-execute the exact failing case recorded in a fuzz replay file.
+    ///
+    /// Replay files are written to `cache/fuzz/replays` whenever a fuzz test fails.
+    #[arg(long, value_name = "FILE")]
+    pub replay: Option<PathBuf>,
+
+    /// Persist the logs, traces, gas data and counterexamples of every test to
+    /// `<out>/test-artifacts`, for inspection with `forge test-report`.
+    #[arg(long, conflicts_with = "json")]
+    pub test_artifacts: bool,
+
+    /// Whether to collect the gas used by the functions called in each test.
+    #[arg(skip)]
+    pub function_gas: bool,
+
     /// Max concurrent threads to use.
     /// Default value is the number of available CPUs.
     #[arg(long)]
@@ -137,8 +209,45 @@ pub struct TestArgs {
     pub detailed: bool,
 
     /// Show test execution progress.
+    ///
+    /// Always enabled with `--porcelain`, and disabled with `--quiet`.
     #[arg(long)]
     pub show_progress: bool,
+
+    /// Print performance statistics of the test run, such as the hit rate of the bytecode
+    /// analysis cache shared by the test threads.
+    #[arg(long, help_heading = "Display options")]
+    pub show_perf_stats: bool,
+
+    /// Record the memory expansion, calldata and returndata sizes of the call frames of unit
+    /// tests, and report the frames that expand memory the most.
+    #[arg(long, help_heading = "Display options")]
+    pub memory_profile: bool,
+
+    /// Sample the wall-clock time spent executing the code of each function called by unit tests,
+    /// and report the functions the test suite spends the most time in.
+    ///
+    /// Unlike gas, this shows why a test suite is slow, e.g. because of expensive cheatcodes or
+    /// precompiles. Sampling slows down the execution of the tests.
+    #[arg(long, help_heading = "Display options")]
+    pub time_profile: bool,
+
+    /// Compile and run the tests once for each of the given solc versions, e.g.
+    /// `--solc-matrix 0.8.20,0.8.26`.
+    ///
+    /// Sources whose pragmas, or whose imports' pragmas, don't allow a version are skipped for
+    /// that version. The results of all versions are summarized side by side.
+    #[arg(
+        long,
+        value_name = "VERSIONS",
+        value_delimiter = ',',
+        conflicts_with_all = ["debug", "json", "list", "use_solc"]
+    )]
+    pub solc_matrix: Vec<Version>,
+
+    /// The sources that can be compiled with the solc version of the current `--solc-matrix` run.
+    #[arg(skip)]
+    matrix_sources: Option<BTreeSet<PathBuf>>,
 }
 
 impl TestArgs {
@@ -147,12 +256,65 @@ impl TestArgs {
         &self.opts
     }
 
+    /// Returns the flattened [`EvmArgs`].
+    pub fn evm_args(&self) -> &EvmArgs {
+        &self.evm_opts
+    }
+
     pub async fn run(self) -> Result<TestOutcome> {
         trace!(target: "forge::test", "executing test command");
-        shell::set_shell(shell::Shell::from_args(self.opts.silent, self.json))?;
+        shell::set_shell(shell::Shell::from_args(
+            self.opts.silent,
+            self.json || self.report.is_some(),
+        ))?;
+        if !self.solc_matrix.is_empty() {
+            return self.run_solc_matrix().await
+        }
         self.execute_tests().await
     }
 
+    /// Runs the tests once for each version of the `--solc-matrix`, and prints the results of all
+    /// versions side by side.
+    ///
+    /// The returned outcome contains the suites of every version, suffixed with the version.
+    async fn run_solc_matrix(self) -> Result<TestOutcome> {
+        let config = self.load_config();
+        let mut runs = Vec::with_capacity(self.solc_matrix.len());
+        let mut max_threads = self.max_threads;
+        for version in &self.solc_matrix {
+            println!();
+            println!("{}", format!("Running tests with solc {version}").bold());
+
+            let sources = solc_compatible_sources(&config, version)?;
+            if !sources.iter().any(|path| path.starts_with(&config.test)) {
+                println!("No tests are compatible with solc {version}, skipping.");
+                runs.push((version.clone(), None));
+                continue
+            }
+
+            let mut args = self.clone();
+            args.solc_matrix.clear();
+            args.opts.use_solc = Some(version.to_string());
+            args.matrix_sources = Some(sources);
+            // The global thread pool can only be configured once.
+            args.max_threads = max_threads.take();
+            runs.push((version.clone(), Some(args.execute_tests().await?)));
+        }
+
+        summary::print_solc_matrix(&runs);
+
+        let mut outcome = TestOutcome::empty(self.allow_failure);
+        for (version, run) in runs {
+            let Some(run) = run else { continue };
+            outcome.results.extend(
+                run.results
+                    .into_iter()
+                    .map(|(name, suite)| (format!("{name} (solc {version})"), suite)),
+            );
+        }
+        Ok(outcome)
+    }
+
     /// Returns sources which include any tests to be executed.
     /// If no filters are provided, sources are filtered by existence of test/invariant methods in
     /// them, If filters are provided, sources are additionaly filtered by them.
@@ -166,7 +328,10 @@ impl TestArgs {
             *selection = OutputSelection::common_output_selection(["abi".to_string()]);
         });
 
-        let output = project.compile()?;
+        let output = match &self.matrix_sources {
+            Some(sources) => project.compile_files(sources.clone())?,
+            None => project.compile()?,
+        };
 
         if output.has_compiler_errors() {
             println!("{output}");
@@ -216,10 +381,12 @@ impl TestArgs {
         }
 
         // Always recompile all sources to ensure that `getCode` cheatcode can use any artifact.
-        test_sources.extend(source_files_iter(
-            &project.paths.sources,
-            MultiCompilerLanguage::FILE_EXTENSIONS,
-        ));
+        test_sources.extend(
+            source_files_iter(&project.paths.sources, MultiCompilerLanguage::FILE_EXTENSIONS)
+                .filter(|path| {
+                    self.matrix_sources.as_ref().map_or(true, |sources| sources.contains(path))
+                }),
+        );
 
         Ok(test_sources)
     }
@@ -263,12 +430,30 @@ impl TestArgs {
         }
 
         let mut filter = self.filter(&config);
+
+        // Load the fuzz failure to replay and only run the test it was recorded for.
+        let replay = self
+            .replay
+            .as_ref()
+            .map(|path| foundry_common::fs::read_json_file::<FuzzReplay>(path))
+            .transpose()?;
+        if let Some(replay) = &replay {
+            let (path, name) = replay.contract.rsplit_once(':').unwrap_or(("", &replay.contract));
+            let args = filter.args_mut();
+            args.test_pattern =
+                Some(Regex::new(&format!("^{}$", regex::escape(replay.test_name())))?);
+            args.contract_pattern = Some(Regex::new(&format!("^{}$", regex::escape(name)))?);
+            if !path.is_empty() {
+                args.path_pattern = Some(path.parse()?);
+            }
+        }
         trace!(target: "forge::test", ?filter, "using filter");
 
         let sources_to_compile = self.get_sources_to_compile(&config, &filter)?;
 
         let compiler = ProjectCompiler::new()
-            .quiet_if(self.json || self.opts.silent)
+            .quiet_if(self.json || self.report.is_some() || self.opts.silent)
+            .deny_warnings(config.deny_warnings_policy())
             .files(sources_to_compile);
 
         let output = compiler.compile(&project)?;
@@ -278,24 +463,51 @@ impl TestArgs {
         let toml = config.get_config_path();
         let profiles = get_available_profiles(toml)?;
 
+        // All the randomness of the run is derived from a single seed, which is printed so that
+        // the run can be reproduced. This is the only place the seed is resolved: replays reuse
+        // the seed of the recorded failure and deterministic runs default to a zero seed.
+        if config.fuzz.seed.is_none() {
+            let random = if let Some(seed) = replay.as_ref().and_then(|replay| replay.seed) {
+                RandomSource::new(seed)
+            } else if let Some(random) = RandomSource::from_env()? {
+                random
+            } else if config.deterministic {
+                RandomSource::new(U256::ZERO)
+            } else {
+                RandomSource::random()
+            };
+            config.fuzz.seed = Some(random.seed());
+        }
+
         let test_options: TestOptions = TestOptionsBuilder::default()
             .fuzz(config.fuzz.clone())
             .invariant(config.invariant.clone())
             .profiles(profiles)
+            .replay(replay)
+            .deterministic(config.deterministic)
+            .forks(config.forks.clone())
             .build(&output, project_root)?;
 
         // Determine print verbosity and executor verbosity
         let verbosity = evm_opts.verbosity;
-        if self.gas_report && evm_opts.verbosity < 3 {
+        if (self.gas_report || self.function_gas) && evm_opts.verbosity < 3 {
             evm_opts.verbosity = 3;
         }
 
+        // Forking from the latest block is inherently not reproducible.
+        if config.deterministic &&
+            evm_opts.fork_url.is_some() &&
+            evm_opts.fork_block_number.is_none()
+        {
+            eyre::bail!("deterministic mode requires a pinned `--fork-block-number` when forking");
+        }
+
         let env = evm_opts.evm_env().await?;
 
         // Prepare the test builder
         let should_debug = self.debug.is_some();
         let config = Arc::new(config);
-        let runner = MultiContractRunnerBuilder::new(config.clone())
+        let mut runner = MultiContractRunnerBuilder::new(config.clone())
             .set_debug(should_debug)
             .initial_balance(evm_opts.initial_balance)
             .evm_spec(config.evm_spec_id())
@@ -303,6 +515,8 @@ impl TestArgs {
             .with_fork(evm_opts.get_fork(&config, env.clone()))
             .with_test_options(test_options)
             .enable_isolation(evm_opts.isolate)
+            .set_memory_profile(self.memory_profile)
+            .set_time_profile(self.time_profile)
             .build(project_root, &output, env, evm_opts)?;
 
         if let Some(debug_test_pattern) = &self.debug {
@@ -317,6 +531,12 @@ impl TestArgs {
         }
 
         let libraries = runner.libraries.clone();
+        if self.interactive {
+            let sources =
+                ContractSources::from_project_output(&output, project.root(), Some(&libraries))?;
+            let handler = TuiBreakpointHandler::new(sources, runner.known_contracts.clone());
+            runner.interactive = Some(Arc::new(handler));
+        }
         let outcome = self.run_tests(runner, config, verbosity, &filter).await?;
 
         if should_debug {
@@ -379,16 +599,43 @@ impl TestArgs {
             return Ok(TestOutcome::new(results, self.allow_failure));
         }
 
+        if let Some(format) = self.report {
+            return self.report_tests(runner, format, verbosity, filter).await
+        }
+
         let remote_chain_id = runner.evm_opts.get_remote_chain_id().await;
         let known_contracts = runner.known_contracts.clone();
 
+        // Abort the running tests at the next step boundary on Ctrl-C, still reporting the traces
+        // collected so far. The interactive debugger handles its own key presses.
+        if !self.interactive {
+            let token = CancellationToken::new();
+            runner.cancellation = Some(token.clone());
+            tokio::spawn(async move {
+                if tokio::signal::ctrl_c().await.is_ok() {
+                    token.cancel();
+                    // A second Ctrl-C exits right away.
+                    if tokio::signal::ctrl_c().await.is_ok() {
+                        std::process::exit(130);
+                    }
+                }
+            });
+        }
+
         // Run tests.
         let (tx, rx) = channel::<(String, SuiteResult)>();
         let timer = Instant::now();
-        let show_progress = self.show_progress;
+        let show_progress = match progress::progress_mode() {
+            ProgressMode::Auto => self.show_progress,
+            ProgressMode::Quiet => false,
+            ProgressMode::Porcelain => true,
+        };
         let handle = tokio::task::spawn_blocking({
             let filter = filter.clone();
-            move || runner.test(&filter, tx, show_progress)
+            move || {
+                runner.test(&filter, tx, show_progress);
+                runner.rpc_usage
+            }
         });
 
         // Set up trace identifiers.
@@ -406,10 +653,8 @@ impl TestArgs {
             .with_verbosity(verbosity);
         // Signatures are of no value for gas reports.
         if !self.gas_report {
-            builder = builder.with_signature_identifier(SignaturesIdentifier::new(
-                Config::foundry_cache_dir(),
-                config.offline,
-            )?);
+            builder =
+                builder.with_signature_identifier(SignaturesIdentifier::from_config(&config)?);
         }
         let mut decoder = builder.build();
 
@@ -417,17 +662,25 @@ impl TestArgs {
             .gas_report
             .then(|| GasReport::new(config.gas_reports.clone(), config.gas_reports_ignore.clone()));
 
+        let mut artifacts = config.test_artifacts.then(|| {
+            TestArtifactsWriter::new(config.root.0.join(&config.out).join(TEST_ARTIFACTS_DIR))
+        });
+
         let mut outcome = TestOutcome::empty(self.allow_failure);
 
         let mut any_test_failed = false;
-        for (contract_name, suite_result) in rx {
+        for (contract_name, mut suite_result) in rx {
             let tests = &suite_result.test_results;
 
             // Clear the addresses and labels from previous test.
             decoder.clear_addresses();
 
             // We identify addresses if we're going to print *any* trace or gas report.
-            let identify_addresses = verbosity >= 3 || self.gas_report || self.debug.is_some();
+            let identify_addresses = verbosity >= 3 ||
+                self.gas_report ||
+                self.function_gas ||
+                self.debug.is_some() ||
+                artifacts.is_some();
 
             // Print suite header.
             println!();
@@ -441,9 +694,17 @@ impl TestArgs {
             }
 
             // Process individual test results, printing logs and traces when necessary.
-            for (name, result) in tests {
+            for (name, result) in &mut suite_result.test_results {
                 shell::println(result.short_result(name))?;
 
+                if !result.branch_hints.is_empty() {
+                    println!("Branch hints:");
+                    for hint in &result.branch_hints {
+                        println!("  {hint}");
+                    }
+                    println!();
+                }
+
                 // We only display logs at level 2 and above
                 if verbosity >= 2 {
                     // We only decode logs from Hardhat and DS-style console events
@@ -469,6 +730,7 @@ impl TestArgs {
 
                 // Identify addresses and decode traces.
                 let mut decoded_traces = Vec::with_capacity(result.traces.len());
+                let mut artifact_traces = Vec::new();
                 for (kind, arena) in &result.traces {
                     if identify_addresses {
                         decoder.identify(arena, &mut identifier);
@@ -489,11 +751,40 @@ impl TestArgs {
                         TraceKind::Deployment => false,
                     };
 
-                    if should_include {
-                        decoded_traces.push(render_trace_arena(arena, &decoder).await?);
+                    if should_include || artifacts.is_some() {
+                        let rendered = render_trace_arena_with_refunds(
+                            arena,
+                            &decoder,
+                            execution_refunds(result, *kind),
+                        )
+                        .await?;
+                        if artifacts.is_some() {
+                            artifact_traces.push((*kind, rendered.clone()));
+                        }
+                        if should_include {
+                            decoded_traces.push(rendered);
+                        }
                     }
                 }
 
+                if self.function_gas {
+                    let execution = result
+                        .traces
+                        .iter()
+                        .filter(|(kind, _)| *kind == TraceKind::Execution)
+                        .map(|(_, arena)| arena);
+                    result.function_gas = function_gas(execution, &decoder).await;
+                }
+
+                if let Some(artifacts) = &mut artifacts {
+                    artifacts.write(&TestArtifact::new(
+                        &contract_name,
+                        name,
+                        result,
+                        artifact_traces,
+                    ))?;
+                }
+
                 if !decoded_traces.is_empty() {
                     shell::println("Traces:")?;
                     for trace in &decoded_traces {
@@ -502,9 +793,11 @@ impl TestArgs {
                 }
 
                 if let Some(gas_report) = &mut gas_report {
-                    gas_report
-                        .analyze(result.traces.iter().map(|(_, arena)| arena), &decoder)
-                        .await;
+                    for (kind, arena) in &result.traces {
+                        gas_report
+                            .analyze_with_refunds(arena, execution_refunds(result, *kind), &decoder)
+                            .await;
+                    }
 
                     for trace in result.gas_report_traces.iter() {
                         decoder.clear_addresses();
@@ -539,6 +832,10 @@ impl TestArgs {
         outcome.last_run_decoder = Some(decoder);
         let duration = timer.elapsed();
 
+        if let Some(artifacts) = artifacts {
+            artifacts.finish(config.test_artifacts_retention)?;
+        }
+
         trace!(target: "forge::test", len=outcome.results.len(), %any_test_failed, "done with results");
 
         if let Some(gas_report) = gas_report {
@@ -549,6 +846,9 @@ impl TestArgs {
 
         if !outcome.results.is_empty() {
             shell::println(outcome.summary(duration))?;
+            if let Some(seed) = config.fuzz.seed {
+                shell::eprintln(format!("Seed: {}", RandomSource::new(seed)))?;
+            }
 
             if self.summary {
                 let mut summary_table = TestSummaryReporter::new(self.detailed);
@@ -557,6 +857,106 @@ impl TestArgs {
             }
         }
 
+        if self.show_perf_stats {
+            shell::println(format!("\nPerformance: {}", AnalyzedBytecodeCache::global().stats()))?;
+        }
+
+        if self.memory_profile {
+            shell::println(memory_hotspots_report(&outcome))?;
+        }
+
+        if self.time_profile {
+            shell::println(time_hotspots_report(&outcome, &known_contracts))?;
+        }
+
+        // Reattach the task.
+        let rpc_usage = match handle.await {
+            Ok(rpc_usage) => rpc_usage,
+            Err(e) => match e.try_into_panic() {
+                Ok(payload) => std::panic::resume_unwind(payload),
+                Err(e) => return Err(e.into()),
+            },
+        };
+
+        if !rpc_usage.total().is_empty() {
+            shell::println(rpc_usage_report(&outcome, &rpc_usage))?;
+        }
+        if let Some(err) = rpc_usage.budget_exceeded() {
+            return Err(err.into());
+        }
+
+        Ok(outcome)
+    }
+
+    /// Runs the tests and prints a machine-readable report of their results in the given format.
+    async fn report_tests(
+        &self,
+        runner: MultiContractRunner,
+        format: TestReportFormat,
+        verbosity: u8,
+        filter: &ProjectPathsAwareFilter,
+    ) -> Result<TestOutcome> {
+        let known_contracts = runner.known_contracts.clone();
+
+        let (tx, rx) = channel::<(String, SuiteResult)>();
+        let handle = tokio::task::spawn_blocking({
+            let filter = filter.clone();
+            move || runner.test(&filter, tx, false)
+        });
+
+        // Traces are only decoded with local artifacts, so that reports don't depend on the
+        // network.
+        let mut identifier = TraceIdentifiers::new().with_local(&known_contracts);
+        let mut decoder = CallTraceDecoderBuilder::new()
+            .with_known_contracts(&known_contracts)
+            .with_verbosity(verbosity)
+            .build();
+
+        let mut writer = TestReportWriter::new(std::io::stdout());
+        let mut outcome = TestOutcome::empty(self.allow_failure);
+        for (contract_name, suite_result) in rx {
+            if format == TestReportFormat::Ndjson {
+                for (name, result) in &suite_result.test_results {
+                    decoder.clear_addresses();
+                    decoder
+                        .labels
+                        .extend(result.labeled_addresses.iter().map(|(k, v)| (*k, v.clone())));
+
+                    // Same rules as the human-readable output.
+                    let mut traces = Vec::new();
+                    for (kind, arena) in &result.traces {
+                        let should_include = match kind {
+                            TraceKind::Execution => {
+                                (verbosity == 3 && result.status.is_failure()) || verbosity >= 4
+                            }
+                            TraceKind::Setup => {
+                                (verbosity == 4 && result.status.is_failure()) || verbosity >= 5
+                            }
+                            TraceKind::Deployment => false,
+                        };
+                        if should_include {
+                            decoder.identify(arena, &mut identifier);
+                            let rendered = render_trace_arena_with_refunds(
+                                arena,
+                                &decoder,
+                                execution_refunds(result, *kind),
+                            )
+                            .await?;
+                            traces.push(TraceReport { kind: *kind, rendered });
+                        }
+                    }
+
+                    writer.test(TestReport::new(&contract_name, name, result, traces))?;
+                }
+            }
+            outcome.results.insert(contract_name, suite_result);
+        }
+
+        match format {
+            TestReportFormat::Ndjson => writer.summary(&outcome)?,
+            TestReportFormat::Junit => print!("{}", junit_report(&outcome)),
+        }
+
         // Reattach the task.
         if let Err(e) = handle.await {
             match e.try_into_panic() {
@@ -588,6 +988,15 @@ impl TestArgs {
     }
 }
 
+/// The format of the machine-readable report of `forge test`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum TestReportFormat {
+    /// A stream of versioned JSON objects, one per line.
+    Ndjson,
+    /// A JUnit XML report.
+    Junit,
+}
+
 impl Provider for TestArgs {
     fn metadata(&self) -> Metadata {
         Metadata::named("Core Build Args Provider")
@@ -608,6 +1017,25 @@ impl Provider for TestArgs {
         }
         dict.insert("fuzz".to_string(), fuzz_dict.into());
 
+        let mut invariant_dict = Dict::default();
+        if let Some(shrink_runs) = self.shrink_runs {
+            invariant_dict.insert("shrink_run_limit".to_string(), shrink_runs.into());
+        }
+        if self.explain_failures {
+            invariant_dict.insert("explain_failures".to_string(), true.into());
+        }
+        if !invariant_dict.is_empty() {
+            dict.insert("invariant".to_string(), invariant_dict.into());
+        }
+
+        if self.deterministic {
+            dict.insert("deterministic".to_string(), true.into());
+        }
+
+        if self.test_artifacts {
+            dict.insert("test_artifacts".to_string(), true.into());
+        }
+
         if let Some(etherscan_api_key) =
             self.etherscan_api_key.as_ref().filter(|s| !s.trim().is_empty())
         {
@@ -618,6 +1046,87 @@ impl Provider for TestArgs {
     }
 }
 
+/// Formats the fork RPC usage of a test run, by provider and for the heaviest tests.
+fn rpc_usage_report(outcome: &TestOutcome, rpc_usage: &RpcUsageRegistry) -> String {
+    let mut report = format!("\nFork RPC usage: {}", rpc_usage.total());
+    for (url, usage) in rpc_usage.providers() {
+        // Only show the host, the rest of the URL may contain an API key.
+        let host = reqwest::Url::parse(&url)
+            .ok()
+            .and_then(|url| url.host_str().map(str::to_string))
+            .unwrap_or(url);
+        let _ = write!(report, "\n  {host}: {usage}");
+    }
+    let top = outcome.top_rpc_usage(5);
+    if !top.is_empty() {
+        report.push_str("\nTests with the heaviest RPC usage:");
+        for (contract, sig, usage) in top {
+            let _ = write!(report, "\n  {contract}::{sig}: {usage}");
+        }
+    }
+    report
+}
+
+/// Formats the call frames with the largest memory expansion of a test run.
+fn memory_hotspots_report(outcome: &TestOutcome) -> String {
+    let top = outcome.top_memory_hotspots(10);
+    if top.is_empty() {
+        return "\nMemory hotspots: no unit test expanded memory".to_string()
+    }
+    let mut report = "\nMemory hotspots:".to_string();
+    for (contract, sig, result, frame) in top {
+        let target = match result.labeled_addresses.get(&frame.address) {
+            Some(label) => label.clone(),
+            None => frame.address.to_string(),
+        };
+        let call = match (frame.is_create, frame.selector) {
+            (true, _) => format!("new {target}"),
+            (false, Some(selector)) => format!("{target}::{selector}"),
+            (false, None) => target,
+        };
+        let _ = write!(
+            report,
+            "\n  {contract}::{sig}: {call} at depth {}: {} bytes of memory ({} gas), \
+             {} bytes of calldata, {} bytes of returndata",
+            frame.depth,
+            frame.peak_memory,
+            frame.memory_gas(),
+            frame.calldata_size,
+            frame.returndata_size,
+        );
+    }
+    report
+}
+
+/// Formats the functions the unit tests of a test run spent the most time executing.
+fn time_hotspots_report(outcome: &TestOutcome, known_contracts: &ContractsByArtifact) -> String {
+    let (top, total) = outcome.top_time_hotspots(known_contracts, 10);
+    if top.is_empty() {
+        return "\nTime hotspots: no unit test was profiled".to_string()
+    }
+    let mut report = format!("\nTime hotspots ({total:.2?} spent executing unit tests):");
+    for hotspot in top {
+        let share = hotspot.time.as_secs_f64() / total.as_secs_f64().max(f64::EPSILON) * 100.;
+        let calls = if hotspot.calls == 1 { "call" } else { "calls" };
+        let _ = write!(
+            report,
+            "\n  {}: {:.2?} ({share:.1}%) in {} {calls}",
+            hotspot.function, hotspot.time, hotspot.calls,
+        );
+    }
+    report
+}
+
+/// Returns the gas refunded to the calls of a trace of the test, which are only recorded for the
+/// execution trace.
+fn execution_refunds(result: &TestResult, kind: TraceKind) -> &GasRefunds {
+    static NO_REFUNDS: GasRefunds = GasRefunds::new();
+    match kind {
+        TraceKind::Execution => &result.gas_refunds,
+        TraceKind::Setup | TraceKind::Deployment => &NO_REFUNDS,
+    }
+}
+
 /// Lists all matching tests
 fn list(
     runner: MultiContractRunner,
@@ -640,6 +1149,23 @@ fn list(
     Ok(TestOutcome::empty(false))
 }
 
+/// Returns the project's sources, tests and scripts that can be compiled with the given solc
+/// version, i.e. whose pragmas, and those of all their imports, allow it.
+fn solc_compatible_sources(config: &Config, version: &Version) -> Result<BTreeSet<PathBuf>> {
+    let paths = config.project_paths();
+    let graph = Graph::<SolData>::resolve(&paths)?;
+    let allows_version = |path: &PathBuf| {
+        graph.files().get(path).map_or(true, |&id| {
+            graph.node(id).data.version_req.as_ref().map_or(true, |req| req.matches(version))
+        })
+    };
+    Ok(paths
+        .input_files()
+        .into_iter()
+        .filter(|path| allows_version(path) && graph.imports(path).into_iter().all(allows_version))
+        .collect())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -652,12 +1178,48 @@ mod tests {
         assert!(args.watch.watch.is_some());
     }
 
+    #[test]
+    fn solc_matrix_parse() {
+        let args: TestArgs =
+            TestArgs::parse_from(["foundry-cli", "--solc-matrix", "0.8.20,0.8.26"]);
+        assert_eq!(args.solc_matrix, vec![Version::new(0, 8, 20), Version::new(0, 8, 26)]);
+
+        assert!(TestArgs::try_parse_from([
+            "foundry-cli",
+            "--solc-matrix",
+            "0.8.20",
+            "--use",
+            "0.8.26"
+        ])
+        .is_err());
+    }
+
     #[test]
     fn fuzz_seed() {
         let args: TestArgs = TestArgs::parse_from(["foundry-cli", "--fuzz-seed", "0x10"]);
         assert!(args.fuzz_seed.is_some());
     }
 
+    #[test]
+    fn replay_parse() {
+        let args: TestArgs = TestArgs::parse_from(["foundry-cli", "--replay", "replay.json"]);
+        assert_eq!(args.replay, Some(PathBuf::from("replay.json")));
+    }
+
+    #[test]
+    fn test_artifacts() {
+        let args: TestArgs = TestArgs::parse_from(["foundry-cli", "--test-artifacts"]);
+        assert!(args.test_artifacts);
+        let config = Config::from(&args);
+        assert!(config.test_artifacts);
+    }
+
+    #[test]
+    fn shrink_runs() {
+        let args: TestArgs = TestArgs::parse_from(["foundry-cli", "--shrink-runs", "100"]);
+        assert_eq!(args.shrink_runs, Some(100));
+    }
+
     // <https://github.com/foundry-rs/foundry/issues/5913>
     #[test]
     fn fuzz_seed_exists() {
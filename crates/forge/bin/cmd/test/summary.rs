@@ -2,6 +2,9 @@ use crate::cmd::test::TestOutcome;
 use comfy_table::{
     modifiers::UTF8_ROUND_CORNERS, Attribute, Cell, CellAlignment, Color, Row, Table,
 };
+use forge::result::TestStatus;
+use semver::Version;
+use std::collections::BTreeMap;
 
 /// A simple summary reporter that prints the test results in a table.
 pub struct TestSummaryReporter {
@@ -91,3 +94,49 @@ impl TestSummaryReporter {
         println!("\n{}", self.table);
     }
 }
+
+/// Prints the status of every test for each version of a `--solc-matrix` run, side by side.
+///
+/// Tests that weren't run with a version, e.g. because of their pragmas, are shown as `-`.
+pub(crate) fn print_solc_matrix(runs: &[(Version, Option<TestOutcome>)]) {
+    let mut tests = BTreeMap::<String, BTreeMap<usize, TestStatus>>::new();
+    for (i, (_, outcome)) in runs.iter().enumerate() {
+        let Some(outcome) = outcome else { continue };
+        for (contract, suite) in &outcome.results {
+            let (_, suite_name) = contract.split_once(':').unwrap_or(("", contract));
+            for (name, result) in &suite.test_results {
+                tests.entry(format!("{suite_name}::{name}")).or_default().insert(i, result.status);
+            }
+        }
+    }
+
+    let mut table = Table::new();
+    table.apply_modifier(UTF8_ROUND_CORNERS);
+    let mut header = Row::from(vec![Cell::new("Test")
+        .set_alignment(CellAlignment::Center)
+        .add_attribute(Attribute::Bold)]);
+    for (version, _) in runs {
+        header.add_cell(
+            Cell::new(format!("solc {version}"))
+                .set_alignment(CellAlignment::Center)
+                .add_attribute(Attribute::Bold),
+        );
+    }
+    table.set_header(header);
+
+    for (test, statuses) in tests {
+        let mut row = Row::from(vec![Cell::new(test)]);
+        for i in 0..runs.len() {
+            let cell = match statuses.get(&i) {
+                Some(TestStatus::Success) => Cell::new("PASS").fg(Color::Green),
+                Some(TestStatus::Failure) => Cell::new("FAIL").fg(Color::Red),
+                Some(TestStatus::Skipped) => Cell::new("SKIP").fg(Color::Yellow),
+                None => Cell::new("-"),
+            };
+            row.add_cell(cell.set_alignment(CellAlignment::Center));
+        }
+        table.add_row(row);
+    }
+
+    println!("\n{table}");
+}
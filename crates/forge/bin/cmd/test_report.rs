@@ -0,0 +1,157 @@
+use clap::{Parser, Subcommand, ValueHint};
+use eyre::{OptionExt, Result};
+use forge::{
+    result::{TestResult, TestStatus},
+    test_artifacts::{TestArtifact, TestArtifactsIndex, TestRun, TEST_ARTIFACTS_DIR},
+    traces::TraceKind,
+};
+use foundry_cli::utils;
+use std::path::PathBuf;
+use yansi::Paint;
+
+/// CLI arguments for `forge test-report`.
+#[derive(Clone, Debug, Parser)]
+pub struct TestReportArgs {
+    #[command(subcommand)]
+    pub sub: TestReportSubcommands,
+
+    /// The project's root path.
+    ///
+    /// By default root of the Git repository, if in one,
+    /// or the current working directory.
+    #[arg(long, global = true, value_hint = ValueHint::DirPath, value_name = "PATH")]
+    pub root: Option<PathBuf>,
+}
+
+#[derive(Clone, Debug, Subcommand)]
+pub enum TestReportSubcommands {
+    /// List the recorded test runs, or the tests of a run.
+    #[command(visible_alias = "ls")]
+    List {
+        /// List the tests of the given run, or of the latest run with `latest`.
+        #[arg(long, value_name = "RUN_ID")]
+        run: Option<String>,
+    },
+
+    /// Print the recorded artifacts of a test.
+    Open {
+        /// The test, either its name or signature, optionally prefixed with `<contract>::`.
+        test: String,
+
+        /// The run to look the test up in. Defaults to the latest run.
+        #[arg(long, value_name = "RUN_ID")]
+        run: Option<String>,
+
+        /// Print the artifacts as JSON.
+        #[arg(long, short)]
+        json: bool,
+    },
+
+    /// Remove the artifacts of old test runs.
+    Gc {
+        /// The number of runs to keep. Defaults to the `test_artifacts_retention` config value.
+        #[arg(long)]
+        keep: Option<usize>,
+    },
+}
+
+impl TestReportArgs {
+    pub fn run(self) -> Result<()> {
+        let config = utils::load_config_with_root(self.root);
+        let dir = config.root.0.join(&config.out).join(TEST_ARTIFACTS_DIR);
+        let mut index = TestArtifactsIndex::read(&dir)?;
+
+        match self.sub {
+            TestReportSubcommands::List { run: None } => {
+                if index.runs.is_empty() {
+                    println!("No test runs recorded. Run `forge test --test-artifacts` first.");
+                }
+                for run in &index.runs {
+                    println!("{} ({} tests, {} failed)", run.id, run.tests.len(), run.failures());
+                }
+            }
+            TestReportSubcommands::List { run: Some(id) } => {
+                let run = find_run(&index, Some(&id).filter(|id| *id != "latest"))?;
+                for test in &run.tests {
+                    let status = match test.status {
+                        TestStatus::Success => "[PASS]".green(),
+                        TestStatus::Failure => "[FAIL]".red(),
+                        TestStatus::Skipped => "[SKIP]".yellow(),
+                    };
+                    println!("{status} {}::{}", test.contract, test.test);
+                }
+            }
+            TestReportSubcommands::Open { test, run, json } => {
+                let run = find_run(&index, run.as_deref())?;
+                let mut matches = run.tests.iter().filter(|entry| entry.matches(&test));
+                let entry = matches
+                    .next()
+                    .ok_or_else(|| eyre::eyre!("no test matching `{test}` in {}", run.id))?;
+                if let Some(other) = matches.next() {
+                    eyre::bail!(
+                        "`{test}` matches several tests, e.g. {}::{} and {}::{}; \
+                         prefix it with the contract name",
+                        entry.contract,
+                        entry.test,
+                        other.contract,
+                        other.test
+                    );
+                }
+
+                let artifact = index.read_artifact(&dir, run, entry)?;
+                if json {
+                    println!("{}", serde_json::to_string_pretty(&artifact)?);
+                } else {
+                    print_artifact(&artifact);
+                }
+            }
+            TestReportSubcommands::Gc { keep } => {
+                let removed = index.gc(&dir, keep.unwrap_or(config.test_artifacts_retention))?;
+                index.write(&dir)?;
+                println!("Removed {} test runs.", removed.len());
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns the run with the given id, or the latest one.
+fn find_run<'a>(index: &'a TestArtifactsIndex, id: Option<&str>) -> Result<&'a TestRun> {
+    match id {
+        Some(id) => index.run(Some(id)).ok_or_else(|| eyre::eyre!("no test run with id `{id}`")),
+        None => index.run(None).ok_or_eyre("no test runs recorded"),
+    }
+}
+
+/// Prints the artifacts of a test the same way `forge test` prints its results.
+fn print_artifact(artifact: &TestArtifact) {
+    let result = TestResult {
+        status: artifact.status,
+        reason: artifact.reason.clone(),
+        counterexample: artifact.counterexample.clone(),
+        kind: artifact.kind.clone(),
+        duration: artifact.duration,
+        ..Default::default()
+    };
+    println!("{}", artifact.contract);
+    println!("{}", result.short_result(&artifact.test));
+    println!("Duration: {:?}", artifact.duration);
+
+    if !artifact.logs.is_empty() {
+        println!("Logs:");
+        for log in &artifact.logs {
+            println!("  {log}");
+        }
+        println!();
+    }
+
+    let traces = artifact.traces.iter().filter(|(kind, _)| *kind != TraceKind::Deployment);
+    let mut traces = traces.peekable();
+    if traces.peek().is_some() {
+        println!("Traces:");
+        for (_, trace) in traces {
+            println!("{trace}");
+        }
+    }
+}
@@ -2,13 +2,16 @@
 extern crate tracing;
 
 use clap::{CommandFactory, Parser};
-use clap_complete::generate;
 use eyre::Result;
 use foundry_cli::{handler, utils};
+use foundry_common::completions;
 use foundry_evm::inspectors::cheatcodes::{set_execution_context, ForgeContext};
 
 mod cmd;
-use cmd::{cache::CacheSubcommands, generate::GenerateSubcommands, watch};
+use cmd::{
+    analyze::AnalyzeSubcommands, cache::CacheSubcommands, contract_test::ContractTestSubcommands,
+    deps::DepsSubcommands, generate::GenerateSubcommands, watch,
+};
 
 mod opts;
 use opts::{Forge, ForgeSubcommand};
@@ -25,6 +28,7 @@ fn main() -> Result<()> {
 
     let opts = Forge::parse();
     init_execution_context(&opts.cmd);
+    init_progress_mode(&opts.cmd);
 
     match opts.cmd {
         ForgeSubcommand::Test(cmd) => {
@@ -45,6 +49,8 @@ fn main() -> Result<()> {
         }
         ForgeSubcommand::Coverage(cmd) => utils::block_on(cmd.run()),
         ForgeSubcommand::Bind(cmd) => cmd.run(),
+        ForgeSubcommand::BindJson(cmd) => cmd.run(),
+        ForgeSubcommand::Eip712(cmd) => cmd.run(),
         ForgeSubcommand::Build(cmd) => {
             if cmd.is_watch() {
                 utils::block_on(watch::watch_build(cmd))
@@ -66,8 +72,17 @@ fn main() -> Result<()> {
         ForgeSubcommand::Remove(cmd) => cmd.run(),
         ForgeSubcommand::Remappings(cmd) => cmd.run(),
         ForgeSubcommand::Init(cmd) => cmd.run(),
+        ForgeSubcommand::Migrate(cmd) => cmd.run(),
+        ForgeSubcommand::Mutate(cmd) => utils::block_on(cmd.run()),
         ForgeSubcommand::Completions { shell } => {
-            generate(shell, &mut Forge::command(), "forge", &mut std::io::stdout());
+            completions::generate(shell, &mut Forge::command(), "forge", &mut std::io::stdout())?;
+            Ok(())
+        }
+        ForgeSubcommand::CompleteCandidates { kind } => {
+            let config = utils::load_config();
+            for candidate in completions::candidates(kind, &config) {
+                println!("{candidate}");
+            }
             Ok(())
         }
         ForgeSubcommand::GenerateFigSpec => {
@@ -85,6 +100,7 @@ fn main() -> Result<()> {
             config.cleanup(&project)?;
             Ok(())
         }
+        ForgeSubcommand::TestReport(cmd) => cmd.run(),
         ForgeSubcommand::Snapshot(cmd) => {
             if cmd.is_watch() {
                 utils::block_on(watch::watch_snapshot(cmd))
@@ -105,13 +121,27 @@ fn main() -> Result<()> {
             }
             Ok(())
         }
+        ForgeSubcommand::Analyze(cmd) => match cmd.sub {
+            AnalyzeSubcommands::Callgraph(cmd) => utils::block_on(cmd.run()),
+        },
+        ForgeSubcommand::CallGraph(cmd) => cmd.run(),
+        ForgeSubcommand::AuditPrep(cmd) => cmd.run(),
         ForgeSubcommand::Doc(cmd) => cmd.run(),
         ForgeSubcommand::Selectors { command } => utils::block_on(command.run()),
         ForgeSubcommand::Generate(cmd) => match cmd.sub {
             GenerateSubcommands::Test(cmd) => cmd.run(),
+            GenerateSubcommands::Handlers(cmd) => cmd.run(),
         },
         ForgeSubcommand::VerifyBytecode(cmd) => utils::block_on(cmd.run()),
+        ForgeSubcommand::VerifyStorageLayout(cmd) => utils::block_on(cmd.run()),
+        ForgeSubcommand::ContractTest(cmd) => match cmd.sub {
+            ContractTestSubcommands::Record(cmd) => utils::block_on(cmd.run()),
+            ContractTestSubcommands::Verify(cmd) => utils::block_on(cmd.run()),
+        },
         ForgeSubcommand::Soldeer(cmd) => cmd.run(),
+        ForgeSubcommand::Deps(cmd) => match cmd.sub {
+            DepsSubcommands::Report(cmd) => cmd.run(),
+        },
     }
 }
 
@@ -136,3 +166,21 @@ fn init_execution_context(subcommand: &ForgeSubcommand) {
     };
     set_execution_context(context);
 }
+
+/// Set how the progress of long running phases is reported, based on the `--quiet` and
+/// `--porcelain` arguments of the `forge` subcommand used.
+fn init_progress_mode(subcommand: &ForgeSubcommand) {
+    let progress = match subcommand {
+        ForgeSubcommand::Test(cmd) => cmd.build_args().progress,
+        ForgeSubcommand::Snapshot(cmd) => cmd.test.build_args().progress,
+        ForgeSubcommand::Coverage(cmd) => cmd.test.build_args().progress,
+        ForgeSubcommand::Script(cmd) => cmd.opts.progress,
+        ForgeSubcommand::Build(cmd) => cmd.args.progress,
+        ForgeSubcommand::Debug(cmd) => cmd.opts.progress,
+        ForgeSubcommand::Create(cmd) => cmd.opts.progress,
+        ForgeSubcommand::VerifyContract(args) => args.progress,
+        ForgeSubcommand::VerifyCheck(args) => args.progress,
+        _ => return,
+    };
+    progress.init();
+}
@@ -1,12 +1,16 @@
 use crate::cmd::{
-    bind::BindArgs, build::BuildArgs, cache::CacheArgs, clone::CloneArgs, config, coverage,
-    create::CreateArgs, debug::DebugArgs, doc::DocArgs, flatten, fmt::FmtArgs, geiger, generate,
-    init::InitArgs, inspect, install::InstallArgs, remappings::RemappingArgs, remove::RemoveArgs,
-    selectors::SelectorsSubcommands, snapshot, soldeer, test, tree, update,
+    analyze, audit_prep, bind::BindArgs, bind_json::BindJsonArgs, build::BuildArgs,
+    cache::CacheArgs, call_graph::CallGraphArgs, clone::CloneArgs, config, contract_test, coverage,
+    create::CreateArgs, debug::DebugArgs, deps, doc::DocArgs, eip712::Eip712Args, flatten,
+    fmt::FmtArgs, geiger, generate, init::InitArgs, inspect, install::InstallArgs,
+    migrate::MigrateArgs, mutate::MutateArgs, remappings::RemappingArgs, remove::RemoveArgs,
+    selectors::SelectorsSubcommands, snapshot, soldeer, storage_layout, test,
+    test_report::TestReportArgs, tree, update,
 };
 use clap::{Parser, Subcommand, ValueHint};
 use forge_script::ScriptArgs;
 use forge_verify::{bytecode::VerifyBytecodeArgs, VerifyArgs, VerifyCheckArgs};
+use foundry_common::completions::CandidateKind;
 use std::path::PathBuf;
 
 const VERSION_MESSAGE: &str = concat!(
@@ -48,6 +52,12 @@ pub enum ForgeSubcommand {
     #[command(alias = "bi")]
     Bind(BindArgs),
 
+    /// Generate Solidity JSON codecs for the project's structs, using the JSON cheatcodes.
+    BindJson(BindJsonArgs),
+
+    /// Generate the EIP-712 typehashes of the project's structs, and check hardcoded typehashes.
+    Eip712(Eip712Args),
+
     /// Build the project's smart contracts.
     #[command(visible_aliases = ["b", "compile"])]
     Build(BuildArgs),
@@ -94,6 +104,18 @@ pub enum ForgeSubcommand {
     /// Create a new Forge project.
     Init(InitArgs),
 
+    /// Convert a Hardhat or Truffle project to a Forge project.
+    ///
+    /// Generates a `foundry.toml` from the project's config, adds remappings for its
+    /// `node_modules` dependencies, generates test stubs, and reports what could not be converted.
+    Migrate(MigrateArgs),
+
+    /// Run mutation testing on the project's sources.
+    ///
+    /// Generates mutants of the sources, runs the tests executing each of them, and reports the
+    /// mutation score along with the mutants that no test killed.
+    Mutate(MutateArgs),
+
     /// Generate shell completions script.
     #[command(visible_alias = "com")]
     Completions {
@@ -105,6 +127,13 @@ pub enum ForgeSubcommand {
     #[command(visible_alias = "fig")]
     GenerateFigSpec,
 
+    /// Print the values to complete for the given kind of argument, used by the shell completions.
+    #[command(name = "complete-candidates", hide = true)]
+    CompleteCandidates {
+        #[arg(value_enum)]
+        kind: CandidateKind,
+    },
+
     /// Remove the build artifacts and cache directories.
     #[command(visible_alias = "cl")]
     Clean {
@@ -119,6 +148,9 @@ pub enum ForgeSubcommand {
     /// Manage the Foundry cache.
     Cache(CacheArgs),
 
+    /// Inspect the artifacts recorded by `forge test --test-artifacts`.
+    TestReport(TestReportArgs),
+
     /// Create a snapshot of each test's gas usage.
     #[command(visible_alias = "s")]
     Snapshot(snapshot::SnapshotArgs),
@@ -145,6 +177,21 @@ pub enum ForgeSubcommand {
     /// Detects usage of unsafe cheat codes in a project and its dependencies.
     Geiger(geiger::GeigerArgs),
 
+    /// Analyze the execution of the project's tests or scripts.
+    Analyze(analyze::AnalyzeArgs),
+
+    /// List the external calls made by the project's contracts, read from their AST.
+    ///
+    /// Reports the type of the called account, the called function, whether ether is sent and
+    /// whether the calling function has a reentrancy guard.
+    CallGraph(CallGraphArgs),
+
+    /// Generate a report of the project for auditors.
+    ///
+    /// Includes contract sizes, selectors, storage layouts, external calls, cheatcode usage,
+    /// coverage, dependency versions and licenses.
+    AuditPrep(audit_prep::AuditPrepArgs),
+
     /// Generate documentation for the project.
     Doc(DocArgs),
 
@@ -162,8 +209,22 @@ pub enum ForgeSubcommand {
     #[clap(visible_alias = "vb")]
     VerifyBytecode(VerifyBytecodeArgs),
 
+    /// Verify that a new implementation can be upgraded to without corrupting storage.
+    ///
+    /// Compares the storage layouts of both implementations, and runs the tests to check that the
+    /// new implementation doesn't write outside of its declared layout.
+    VerifyStorageLayout(storage_layout::VerifyStorageLayoutArgs),
+
+    /// Record the external calls of the contracts under test, and verify them against the real
+    /// dependencies on a fork.
+    #[command(visible_alias = "ct")]
+    ContractTest(contract_test::ContractTestArgs),
+
     /// Soldeer dependency manager.
     Soldeer(soldeer::SoldeerArgs),
+
+    /// Inspect the project's dependencies.
+    Deps(deps::DepsArgs),
 }
 
 #[cfg(test)]
@@ -5,9 +5,9 @@ use evm_disassembler::disassemble_bytes;
 use foundry_common::fs;
 pub use foundry_evm::coverage::*;
 use std::{
-    collections::{hash_map, HashMap},
+    collections::{hash_map, BTreeMap, HashMap},
     io::Write,
-    path::{Path, PathBuf},
+    path::{Component, Path, PathBuf},
 };
 
 /// A coverage reporter.
@@ -43,21 +43,69 @@ impl SummaryReporter {
             .add_cell(format_cell(summary.function_hits, summary.function_count));
         self.table.add_row(row);
     }
-}
 
-impl CoverageReporter for SummaryReporter {
-    fn report(mut self, report: &CoverageReport) -> eyre::Result<()> {
-        for (path, summary) in report.summary_by_file() {
+    /// Prints the given summaries of each file, followed by their total.
+    pub fn report_summaries(
+        mut self,
+        summaries: impl IntoIterator<Item = (PathBuf, CoverageSummary)>,
+    ) {
+        for (path, summary) in summaries {
             self.total += &summary;
             self.add_row(path.display(), summary);
         }
 
         self.add_row("Total", self.total.clone());
         println!("{}", self.table);
+    }
+}
+
+impl CoverageReporter for SummaryReporter {
+    fn report(self, report: &CoverageReport) -> eyre::Result<()> {
+        self.report_summaries(report.summary_by_file());
         Ok(())
     }
 }
 
+/// Which of the lines with branches executed by the tests are asserted on, as measured by
+/// `forge coverage --assertions`.
+///
+/// A line is asserted on if inverting the condition of any of its branches fails a test that
+/// executes it.
+#[derive(Clone, Debug, Default)]
+pub struct AssertionCoverage {
+    /// Whether each line is asserted on, by source file.
+    pub lines: BTreeMap<PathBuf, BTreeMap<usize, bool>>,
+}
+
+impl AssertionCoverage {
+    /// Records whether mutating a branch on the given line failed a test.
+    pub fn add(&mut self, path: PathBuf, line: usize, asserted: bool) {
+        let entry = self.lines.entry(path).or_default().entry(line).or_insert(true);
+        *entry &= asserted;
+    }
+
+    /// Prints the asserted lines of each file, with the lines that are not asserted on.
+    pub fn report(&self) {
+        let mut table = Table::new();
+        table.load_preset(ASCII_MARKDOWN);
+        table.set_header(["File", "% Asserted lines", "Unasserted lines"]);
+        for (path, lines) in &self.lines {
+            let asserted = lines.values().filter(|asserted| **asserted).count();
+            let unasserted = lines
+                .iter()
+                .filter(|(_, asserted)| !**asserted)
+                .map(|(line, _)| line.to_string())
+                .collect::<Vec<_>>();
+            let mut row = Row::new();
+            row.add_cell(Cell::new(path.display()))
+                .add_cell(format_cell(asserted, lines.len()))
+                .add_cell(Cell::new(unasserted.join(", ")));
+            table.add_row(row);
+        }
+        println!("{table}");
+    }
+}
+
 fn format_cell(hits: usize, total: usize) -> Cell {
     let percentage = if total == 0 { 1. } else { hits as f64 / total as f64 };
 
@@ -101,7 +149,7 @@ impl<'a> CoverageReporter for LcovReporter<'a> {
                 let line = item.loc.line;
                 let hits = item.hits;
                 match item.kind {
-                    CoverageItemKind::Function { name } => {
+                    CoverageItemKind::Function { name } | CoverageItemKind::Modifier { name } => {
                         let name = format!("{}.{name}", item.loc.contract_name);
                         writeln!(self.destination, "FN:{line},{name}")?;
                         writeln!(self.destination, "FNDA:{hits},{name}")?;
@@ -144,6 +192,408 @@ impl<'a> CoverageReporter for LcovReporter<'a> {
     }
 }
 
+/// Coverage data read from LCOV reports.
+///
+/// This is used to merge the reports of multiple coverage runs, e.g. of sharded CI jobs, into a
+/// single report. Hit counts of the same function, line or branch are summed, and the summaries of
+/// each file are recomputed from the merged records.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LcovData {
+    /// The coverage records of each source file, by path.
+    pub files: BTreeMap<String, LcovRecord>,
+}
+
+/// The coverage records of a source file in an LCOV report.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LcovRecord {
+    /// The line and hit count of each function, by name.
+    pub functions: BTreeMap<String, (usize, usize)>,
+    /// The hit count of each line.
+    pub lines: BTreeMap<usize, usize>,
+    /// The hit count of each branch by line, block and branch, or `None` if the branch was never
+    /// evaluated.
+    pub branches: BTreeMap<(usize, usize, usize), Option<usize>>,
+}
+
+impl LcovData {
+    /// Parses an LCOV report.
+    ///
+    /// Lines reported multiple times in the same record, e.g. for lines containing multiple
+    /// statements, are counted once with their highest hit count.
+    pub fn parse(contents: &str) -> eyre::Result<Self> {
+        let mut data = Self::default();
+        let mut current: Option<(String, LcovRecord)> = None;
+        for (n, line) in contents.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue
+            }
+
+            let err = || eyre::eyre!("invalid LCOV record on line {}: {line}", n + 1);
+            let num = |value: &str| value.parse::<usize>().map_err(|_| err());
+            let (key, value) = line.split_once(':').unwrap_or((line, ""));
+            match key {
+                "TN" => {}
+                "SF" => current = Some((value.to_string(), LcovRecord::default())),
+                "end_of_record" => {
+                    let (path, record) = current.take().ok_or_else(err)?;
+                    data.add(path, record);
+                }
+                // Summaries are recomputed from the records.
+                "FNF" | "FNH" | "LF" | "LH" | "BRF" | "BRH" => {}
+                _ => {
+                    let (_, record) = current.as_mut().ok_or_else(err)?;
+                    let mut fields = value.split(',');
+                    let mut field = || fields.next().ok_or_else(err);
+                    match key {
+                        "FN" => {
+                            let line = num(field()?)?;
+                            record.functions.entry(field()?.to_string()).or_default().0 = line;
+                        }
+                        "FNDA" => {
+                            let hits = num(field()?)?;
+                            let function =
+                                record.functions.entry(field()?.to_string()).or_default();
+                            function.1 = function.1.max(hits);
+                        }
+                        "DA" => {
+                            let line = num(field()?)?;
+                            let hits = num(field()?)?;
+                            let entry = record.lines.entry(line).or_default();
+                            *entry = (*entry).max(hits);
+                        }
+                        "BRDA" => {
+                            let id = (num(field()?)?, num(field()?)?, num(field()?)?);
+                            let hits = match field()? {
+                                "-" => None,
+                                hits => Some(num(hits)?),
+                            };
+                            let entry = record.branches.entry(id).or_default();
+                            *entry = (*entry).max(hits);
+                        }
+                        _ => eyre::bail!("unsupported LCOV record on line {}: {line}", n + 1),
+                    }
+                }
+            }
+        }
+
+        if let Some((path, _)) = current {
+            eyre::bail!("missing end_of_record for {path}");
+        }
+        Ok(data)
+    }
+
+    /// Merges the given coverage data into this one.
+    pub fn merge(&mut self, other: Self) {
+        for (path, record) in other.files {
+            self.add(path, record);
+        }
+    }
+
+    /// Merges the record of a source file.
+    fn add(&mut self, path: String, record: LcovRecord) {
+        let merged = self.files.entry(path).or_default();
+        for (name, (line, hits)) in record.functions {
+            let function = merged.functions.entry(name).or_default();
+            if function.0 == 0 {
+                function.0 = line;
+            }
+            function.1 += hits;
+        }
+        for (line, hits) in record.lines {
+            *merged.lines.entry(line).or_default() += hits;
+        }
+        for (id, hits) in record.branches {
+            let entry = merged.branches.entry(id).or_default();
+            *entry = match (*entry, hits) {
+                (None, None) => None,
+                (a, b) => Some(a.unwrap_or_default() + b.unwrap_or_default()),
+            };
+        }
+    }
+
+    /// Returns the coverage summary of each source file.
+    ///
+    /// LCOV reports don't contain statements, so the statement counts are always zero.
+    pub fn summary_by_file(&self) -> impl Iterator<Item = (PathBuf, CoverageSummary)> + '_ {
+        self.files.iter().map(|(path, record)| (PathBuf::from(path), record.summary()))
+    }
+
+    /// Writes the coverage data as an LCOV report.
+    pub fn write(&self, destination: &mut dyn Write) -> eyre::Result<()> {
+        for (path, record) in &self.files {
+            let summary = record.summary();
+
+            writeln!(destination, "TN:")?;
+            writeln!(destination, "SF:{path}")?;
+
+            let mut functions = record.functions.iter().collect::<Vec<_>>();
+            functions.sort_by_key(|(name, (line, _))| (*line, *name));
+            for (name, (line, hits)) in functions {
+                writeln!(destination, "FN:{line},{name}")?;
+                writeln!(destination, "FNDA:{hits},{name}")?;
+            }
+            for (line, hits) in &record.lines {
+                writeln!(destination, "DA:{line},{hits}")?;
+            }
+            for ((line, block, branch), hits) in &record.branches {
+                let hits = hits.map_or_else(|| "-".to_string(), |hits| hits.to_string());
+                writeln!(destination, "BRDA:{line},{block},{branch},{hits}")?;
+            }
+
+            writeln!(destination, "FNF:{}", summary.function_count)?;
+            writeln!(destination, "FNH:{}", summary.function_hits)?;
+            writeln!(destination, "LF:{}", summary.line_count)?;
+            writeln!(destination, "LH:{}", summary.line_hits)?;
+            writeln!(destination, "BRF:{}", summary.branch_count)?;
+            writeln!(destination, "BRH:{}", summary.branch_hits)?;
+            writeln!(destination, "end_of_record")?;
+        }
+        Ok(())
+    }
+}
+
+impl LcovRecord {
+    /// Returns the coverage summary of the record.
+    pub fn summary(&self) -> CoverageSummary {
+        CoverageSummary {
+            line_count: self.lines.len(),
+            line_hits: self.lines.values().filter(|hits| **hits > 0).count(),
+            branch_count: self.branches.len(),
+            branch_hits: self.branches.values().filter(|hits| hits.is_some_and(|h| h > 0)).count(),
+            function_count: self.functions.len(),
+            function_hits: self.functions.values().filter(|(_, hits)| *hits > 0).count(),
+            ..Default::default()
+        }
+    }
+}
+
+/// A reporter that writes an HTML report.
+///
+/// The report consists of an index page with the coverage summary of each source file, and a page
+/// for each source file with its functions, and its source code annotated with the hits of each
+/// line and branch.
+pub struct HtmlReporter {
+    root: PathBuf,
+    destdir: PathBuf,
+}
+
+impl HtmlReporter {
+    /// Creates a reporter for the sources in `root`, which writes the report to `destdir`.
+    pub fn new(root: PathBuf, destdir: PathBuf) -> Self {
+        Self { root, destdir }
+    }
+}
+
+impl CoverageReporter for HtmlReporter {
+    fn report(self, report: &CoverageReport) -> eyre::Result<()> {
+        fs::create_dir_all(&self.destdir)?;
+
+        let mut total = CoverageSummary::default();
+        let mut rows = String::new();
+        for (path, items) in report.items_by_source() {
+            let summary = items.iter().fold(CoverageSummary::default(), |mut summary, item| {
+                summary += item;
+                summary
+            });
+            total += &summary;
+
+            let page = html_page_path(&path);
+            let source = fs::read_to_string(self.root.join(&path))?;
+            let depth = page.components().count() - 1;
+            let dest = self.destdir.join(&page);
+            fs::create_dir_all(dest.parent().expect("has parent"))?;
+            fs::write(dest, html_source_page(&path, &source, &items, &summary, depth))?;
+
+            rows.push_str(&format!(
+                "<tr><td><a href=\"{}\">{}</a></td>{}</tr>\n",
+                html_escape(&page.to_string_lossy()),
+                html_escape(&path.to_string_lossy()),
+                html_summary_cells(&summary)
+            ));
+        }
+        rows.push_str(&format!("<tr><th>Total</th>{}</tr>\n", html_summary_cells(&total)));
+
+        let body = format!(
+            "<table>\n<tr><th>File</th><th>% Lines</th><th>% Statements</th><th>% Branches</th>\
+             <th>% Funcs</th></tr>\n{rows}</table>"
+        );
+        let index = self.destdir.join("index.html");
+        fs::write(&index, html_document("Coverage report", &body))?;
+
+        println!("Wrote HTML report to {}.", index.display());
+
+        Ok(())
+    }
+}
+
+/// The hits of a line of a source file in the HTML report.
+#[derive(Default)]
+struct HtmlLine {
+    /// The hits of the line and of each statement on it.
+    hits: Vec<u64>,
+    /// The branch ID, path ID and hits of each branch on the line.
+    branches: Vec<(usize, usize, u64)>,
+}
+
+/// Returns the path of the page of a source file, relative to the report directory.
+fn html_page_path(path: &Path) -> PathBuf {
+    let mut page = path
+        .components()
+        .filter_map(|component| match component {
+            Component::Normal(component) => Some(component),
+            _ => None,
+        })
+        .collect::<PathBuf>()
+        .into_os_string();
+    page.push(".html");
+    page.into()
+}
+
+/// Renders the page of a source file, which is `depth` directories below the index.
+fn html_source_page(
+    path: &Path,
+    source: &str,
+    items: &[CoverageItem],
+    summary: &CoverageSummary,
+    depth: usize,
+) -> String {
+    let mut lines = BTreeMap::<usize, HtmlLine>::new();
+    let mut functions = Vec::new();
+    for item in items {
+        let line = lines.entry(item.loc.line).or_default();
+        match &item.kind {
+            CoverageItemKind::Line | CoverageItemKind::Statement => line.hits.push(item.hits),
+            CoverageItemKind::Branch { branch_id, path_id } => {
+                line.branches.push((*branch_id, *path_id, item.hits))
+            }
+            CoverageItemKind::Function { name } => functions.push(("function", name, item)),
+            CoverageItemKind::Modifier { name } => functions.push(("modifier", name, item)),
+        }
+    }
+    functions.sort_by_key(|(_, _, item)| item.loc.line);
+
+    let mut body = format!(
+        "<p><a href=\"{}index.html\">Index</a></p>\n<table>\n<tr><th>% Lines</th>\
+         <th>% Statements</th><th>% Branches</th><th>% Funcs</th></tr>\n<tr>{}</tr>\n</table>\n",
+        "../".repeat(depth),
+        html_summary_cells(summary)
+    );
+
+    body.push_str("<table>\n<tr><th>Function</th><th>Line</th><th>Hits</th></tr>\n");
+    for (kind, name, item) in functions {
+        body.push_str(&format!(
+            "<tr class=\"{}\"><td>{kind} {}.{}</td><td>{}</td><td>{}</td></tr>\n",
+            if item.hits > 0 { "hit" } else { "miss" },
+            html_escape(&item.loc.contract_name),
+            html_escape(name),
+            item.loc.line,
+            item.hits
+        ));
+    }
+    body.push_str("</table>\n<pre>\n");
+
+    for (number, text) in source.lines().enumerate() {
+        let number = number + 1;
+        let line = lines.get(&number);
+        let hits = line.and_then(|line| line.hits.iter().max());
+        let class = match line {
+            Some(line) if line.hits.iter().any(|hits| *hits == 0) => {
+                if hits.is_some_and(|hits| *hits > 0) {
+                    "partial"
+                } else {
+                    "miss"
+                }
+            }
+            Some(line) if !line.hits.is_empty() => "hit",
+            _ => "none",
+        };
+        let branches = line
+            .map(|line| {
+                line.branches
+                    .iter()
+                    .map(|(branch_id, path_id, hits)| {
+                        format!(
+                            "<span class=\"branch {}\" title=\"branch {branch_id}, path \
+                             {path_id}: {hits} hits\">{}</span>",
+                            if *hits > 0 { "hit" } else { "miss" },
+                            if *hits > 0 { '+' } else { '-' }
+                        )
+                    })
+                    .collect::<String>()
+            })
+            .unwrap_or_default();
+        body.push_str(&format!(
+            "<span class=\"{class}\"><span class=\"number\">{number:>5}</span> \
+             <span class=\"hits\">{:>6}</span> <span class=\"branches\">{branches}</span> \
+             {}</span>\n",
+            hits.map(|hits| hits.to_string()).unwrap_or_default(),
+            html_escape(text)
+        ));
+    }
+    body.push_str("</pre>");
+
+    html_document(&path.to_string_lossy(), &body)
+}
+
+/// Renders the summary cells of a row of the HTML report.
+fn html_summary_cells(summary: &CoverageSummary) -> String {
+    [
+        (summary.line_hits, summary.line_count),
+        (summary.statement_hits, summary.statement_count),
+        (summary.branch_hits, summary.branch_count),
+        (summary.function_hits, summary.function_count),
+    ]
+    .into_iter()
+    .map(|(hits, total)| {
+        let percentage = if total == 0 { 1. } else { hits as f64 / total as f64 };
+        let class = match percentage {
+            _ if total == 0 => "none",
+            _ if percentage < 0.5 => "miss",
+            _ if percentage < 0.75 => "partial",
+            _ => "hit",
+        };
+        format!("<td class=\"{class}\">{:.2}% ({hits}/{total})</td>", percentage * 100.)
+    })
+    .collect()
+}
+
+/// Renders an HTML document.
+fn html_document(title: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n\
+         <style>{HTML_STYLE}</style>\n</head>\n<body>\n<h1>{}</h1>\n{body}\n</body>\n\
+         </html>\n",
+        html_escape(title),
+        html_escape(title)
+    )
+}
+
+/// The style sheet of the HTML report.
+const HTML_STYLE: &str = "body { font-family: sans-serif; } \
+    table { border-collapse: collapse; margin-bottom: 1em; } \
+    td, th { border: 1px solid #ccc; padding: 2px 8px; text-align: left; } \
+    pre { font-size: 13px; } \
+    .number, .hits { color: #888; } \
+    .hit { background: #dfd; } .miss { background: #fdd; } .partial { background: #ffd; } \
+    .branch { font-weight: bold; padding: 0 2px; }";
+
+/// Escapes the given text for use in HTML.
+fn html_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
 /// A super verbose reporter for debugging coverage while it is still unstable.
 pub struct DebugReporter;
 
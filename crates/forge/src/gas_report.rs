@@ -2,7 +2,7 @@
 
 use crate::{
     constants::{CHEATCODE_ADDRESS, HARDHAT_CONSOLE_ADDRESS},
-    traces::{CallTraceArena, CallTraceDecoder, CallTraceNode, DecodedCallData},
+    traces::{CallTraceArena, CallTraceDecoder, CallTraceNode, DecodedCallData, GasRefunds},
 };
 use comfy_table::{presets::ASCII_MARKDOWN, *};
 use foundry_common::{calc, TestFunctionExt};
@@ -68,11 +68,29 @@ impl GasReport {
         decoder: &CallTraceDecoder,
     ) {
         for node in arenas.into_iter().flat_map(|arena| arena.nodes()) {
-            self.analyze_node(node, decoder).await;
+            self.analyze_node(node, 0, decoder).await;
         }
     }
 
-    async fn analyze_node(&mut self, node: &CallTraceNode, decoder: &CallTraceDecoder) {
+    /// Analyzes the given trace, along with the gas refunded to its calls.
+    pub async fn analyze_with_refunds(
+        &mut self,
+        arena: &CallTraceArena,
+        refunds: &GasRefunds,
+        decoder: &CallTraceDecoder,
+    ) {
+        for node in arena.nodes() {
+            let refund = refunds.get(&node.idx).copied().unwrap_or_default();
+            self.analyze_node(node, refund, decoder).await;
+        }
+    }
+
+    async fn analyze_node(
+        &mut self,
+        node: &CallTraceNode,
+        refund: u64,
+        decoder: &CallTraceDecoder,
+    ) {
         let trace = &node.trace;
 
         if trace.address == CHEATCODE_ADDRESS || trace.address == HARDHAT_CONSOLE_ADDRESS {
@@ -115,6 +133,7 @@ impl GasReport {
                     .entry(signature.clone())
                     .or_default();
                 gas_info.calls.push(trace.gas_used);
+                gas_info.refunds.push(refund);
             }
         }
     }
@@ -131,6 +150,7 @@ impl GasReport {
                     func.max = func.calls.last().copied().unwrap_or_default();
                     func.mean = calc::mean(&func.calls);
                     func.median = calc::median_sorted(&func.calls);
+                    func.refund = calc::mean(&func.refunds);
                 }
             }
         }
@@ -138,6 +158,41 @@ impl GasReport {
     }
 }
 
+/// Returns the total gas used by each function called in the given traces, keyed by
+/// `<contract name>::<signature>`.
+///
+/// Calls to cheatcodes, the console and test functions are ignored.
+pub async fn function_gas(
+    arenas: impl IntoIterator<Item = &CallTraceArena>,
+    decoder: &CallTraceDecoder,
+) -> BTreeMap<String, u64> {
+    let mut gas = BTreeMap::new();
+    for node in arenas.into_iter().flat_map(|arena| arena.nodes()) {
+        let trace = &node.trace;
+        if trace.address == CHEATCODE_ADDRESS ||
+            trace.address == HARDHAT_CONSOLE_ADDRESS ||
+            trace.kind.is_any_create()
+        {
+            continue
+        }
+
+        let decoded = decoder.decode_function(trace).await;
+        let (Some(contract), Some(DecodedCallData { signature, .. })) =
+            (&decoded.contract, decoded.func)
+        else {
+            continue
+        };
+        let name = signature.split('(').next().unwrap();
+        if name.test_function_kind().is_known() {
+            continue
+        }
+
+        let contract = contract.rsplit(':').next().unwrap_or(contract);
+        *gas.entry(format!("{contract}::{signature}")).or_default() += trace.gas_used;
+    }
+    gas
+}
+
 impl Display for GasReport {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
         for (name, contract) in &self.contracts {
@@ -157,28 +212,43 @@ impl Display for GasReport {
             ]);
             table.add_row([contract.gas.to_string(), contract.size.to_string()]);
 
-            table.add_row([
+            // The refunds are only shown for the contracts whose functions got any.
+            let has_refunds = contract
+                .functions
+                .values()
+                .flat_map(|sigs| sigs.values())
+                .any(|gas_info| gas_info.refund > 0);
+
+            let mut header = vec![
                 Cell::new("Function Name").add_attribute(Attribute::Bold).fg(Color::Magenta),
                 Cell::new("min").add_attribute(Attribute::Bold).fg(Color::Green),
                 Cell::new("avg").add_attribute(Attribute::Bold).fg(Color::Yellow),
                 Cell::new("median").add_attribute(Attribute::Bold).fg(Color::Yellow),
                 Cell::new("max").add_attribute(Attribute::Bold).fg(Color::Red),
                 Cell::new("# calls").add_attribute(Attribute::Bold),
-            ]);
+            ];
+            if has_refunds {
+                header.push(Cell::new("avg refund").add_attribute(Attribute::Bold).fg(Color::Cyan));
+            }
+            table.add_row(header);
             contract.functions.iter().for_each(|(fname, sigs)| {
                 sigs.iter().for_each(|(sig, gas_info)| {
                     // show function signature if overloaded else name
                     let fn_display =
                         if sigs.len() == 1 { fname.clone() } else { sig.replace(':', "") };
 
-                    table.add_row([
+                    let mut row = vec![
                         Cell::new(fn_display).add_attribute(Attribute::Bold),
                         Cell::new(gas_info.min.to_string()).fg(Color::Green),
                         Cell::new(gas_info.mean.to_string()).fg(Color::Yellow),
                         Cell::new(gas_info.median.to_string()).fg(Color::Yellow),
                         Cell::new(gas_info.max.to_string()).fg(Color::Red),
                         Cell::new(gas_info.calls.len().to_string()),
-                    ]);
+                    ];
+                    if has_refunds {
+                        row.push(Cell::new(gas_info.refund.to_string()).fg(Color::Cyan));
+                    }
+                    table.add_row(row);
                 })
             });
             writeln!(f, "{table}")?;
@@ -198,9 +268,17 @@ pub struct ContractInfo {
 
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct GasInfo {
+    /// The gas used by each call. Refunds are applied at the end of the transaction, so this
+    /// doesn't account for them.
     pub calls: Vec<u64>,
+    /// The gas refunded to each call.
+    #[serde(default)]
+    pub refunds: Vec<u64>,
     pub min: u64,
     pub mean: u64,
     pub median: u64,
     pub max: u64,
+    /// The mean of `refunds`.
+    #[serde(default)]
+    pub refund: u64,
 }
@@ -4,15 +4,19 @@
 #[macro_use]
 extern crate tracing;
 
+use crate::fuzz::FuzzReplay;
 use foundry_compilers::ProjectCompileOutput;
 use foundry_config::{
-    validate_profiles, Config, FuzzConfig, InlineConfig, InlineConfigError, InlineConfigParser,
-    InvariantConfig, NatSpec,
+    validate_profiles, Config, ForkSpec, FuzzConfig, InlineConfig, InlineConfigError,
+    InlineConfigParser, InlineConfigParserError, InlineEvmConfig, InvariantConfig, NatSpec,
 };
 use proptest::test_runner::{
     FailurePersistence, FileFailurePersistence, RngAlgorithm, TestRng, TestRunner,
 };
-use std::path::Path;
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::Path,
+};
 
 pub mod coverage;
 
@@ -25,8 +29,11 @@ mod runner;
 pub use runner::ContractRunner;
 
 mod progress;
+pub mod report;
 pub mod result;
 
+pub mod test_artifacts;
+
 // TODO: remove
 pub use foundry_common::traits::TestFilter;
 pub use foundry_evm::*;
@@ -44,6 +51,15 @@ pub struct TestOptions {
     pub inline_fuzz: InlineConfig<FuzzConfig>,
     /// Contains per-test specific "invariant" configurations.
     pub inline_invariant: InlineConfig<InvariantConfig>,
+    /// Contains per-test specific "evm" configurations.
+    pub inline_evm: InlineConfig<InlineEvmConfig>,
+    /// The forks test contracts run on by default, by contract.
+    pub forks: HashMap<String, ForkSpec>,
+    /// Persisted fuzz failure to re-execute instead of fuzzing, see `forge test --replay`.
+    pub replay: Option<FuzzReplay>,
+    /// Whether every test is executed twice to verify that its results are reproducible, see
+    /// `forge test --deterministic`.
+    pub deterministic: bool,
 }
 
 impl TestOptions {
@@ -55,16 +71,20 @@ impl TestOptions {
         profiles: Vec<String>,
         base_fuzz: FuzzConfig,
         base_invariant: InvariantConfig,
+        named_forks: &BTreeMap<String, String>,
     ) -> Result<Self, InlineConfigError> {
         let natspecs: Vec<NatSpec> = NatSpec::parse(output, root);
         let mut inline_invariant = InlineConfig::<InvariantConfig>::default();
         let mut inline_fuzz = InlineConfig::<FuzzConfig>::default();
+        let mut inline_evm = InlineConfig::<InlineEvmConfig>::default();
+        let mut forks = HashMap::new();
 
         for natspec in natspecs {
             // Perform general validation
             validate_profiles(&natspec, &profiles)?;
             FuzzConfig::validate_configs(&natspec)?;
             InvariantConfig::validate_configs(&natspec)?;
+            InlineEvmConfig::validate_configs(&natspec)?;
 
             // Apply in-line configurations for the current profile
             let configs: Vec<String> = natspec.current_profile_configs().collect();
@@ -72,6 +92,22 @@ impl TestOptions {
             let f: &str = &natspec.function;
             let line: String = natspec.debug_context();
 
+            // Forks are set for whole contracts, all the other settings for single tests.
+            if f.is_empty() {
+                match ForkSpec::from_configs(&configs, named_forks) {
+                    Ok(Some(fork)) => {
+                        forks.insert(c.to_string(), fork);
+                    }
+                    Ok(None) => { /* No fork set, do nothing */ }
+                    Err(e) => Err(InlineConfigError { line, source: e })?,
+                }
+                continue
+            }
+            if ForkSpec::is_set_by(natspec.config_lines()) {
+                let source = InlineConfigParserError::InvalidConfigProperty("fork".into());
+                Err(InlineConfigError { line, source })?
+            }
+
             match base_fuzz.try_merge(&configs) {
                 Ok(Some(conf)) => inline_fuzz.insert(c, f, conf),
                 Ok(None) => { /* No inline config found, do nothing */ }
@@ -83,9 +119,24 @@ impl TestOptions {
                 Ok(None) => { /* No inline config found, do nothing */ }
                 Err(e) => Err(InlineConfigError { line: line.clone(), source: e })?,
             }
+
+            match InlineEvmConfig::default().try_merge(&configs) {
+                Ok(Some(conf)) => inline_evm.insert(c, f, conf),
+                Ok(None) => { /* No inline config found, do nothing */ }
+                Err(e) => Err(InlineConfigError { line: line.clone(), source: e })?,
+            }
         }
 
-        Ok(Self { fuzz: base_fuzz, invariant: base_invariant, inline_fuzz, inline_invariant })
+        Ok(Self {
+            fuzz: base_fuzz,
+            invariant: base_invariant,
+            inline_fuzz,
+            inline_invariant,
+            inline_evm,
+            forks,
+            replay: None,
+            deterministic: false,
+        })
     }
 
     /// Returns a "fuzz" test runner instance. Parameters are used to select tight scoped fuzz
@@ -144,6 +195,32 @@ impl TestOptions {
         self.inline_invariant.get(contract_id, test_fn).unwrap_or(&self.invariant)
     }
 
+    /// Returns the "evm" settings overridden for a contract-function pair, if any.
+    ///
+    /// - `contract_id` is the id of the test contract, expressed as a relative path from the
+    ///   project root.
+    /// - `test_fn` is the name of the test function declared inside the test contract.
+    pub fn evm_config(&self, contract_id: &str, test_fn: &str) -> Option<&InlineEvmConfig> {
+        self.inline_evm.get(contract_id, test_fn)
+    }
+
+    /// Returns the fork the given test contract runs on by default, if any.
+    ///
+    /// - `contract_id` is the id of the test contract, expressed as a relative path from the
+    ///   project root.
+    pub fn fork(&self, contract_id: &str) -> Option<&ForkSpec> {
+        self.forks.get(contract_id)
+    }
+
+    /// Returns the persisted fuzz failure to replay for the given contract-function pair, if any.
+    ///
+    /// - `contract_id` is the id of the test contract, expressed as a relative path from the
+    ///   project root.
+    /// - `signature` is the signature of the test function declared inside the test contract.
+    pub fn fuzz_replay(&self, contract_id: &str, signature: &str) -> Option<&FuzzReplay> {
+        self.replay.as_ref().filter(|replay| replay.matches(contract_id, signature))
+    }
+
     pub fn fuzzer_with_cases(
         &self,
         cases: u32,
@@ -177,6 +254,9 @@ pub struct TestOptionsBuilder {
     fuzz: Option<FuzzConfig>,
     invariant: Option<InvariantConfig>,
     profiles: Option<Vec<String>>,
+    replay: Option<FuzzReplay>,
+    deterministic: bool,
+    forks: BTreeMap<String, String>,
 }
 
 impl TestOptionsBuilder {
@@ -192,6 +272,26 @@ impl TestOptionsBuilder {
         self
     }
 
+    /// Sets a persisted fuzz failure to re-execute instead of fuzzing the matching test.
+    pub fn replay(mut self, replay: Option<FuzzReplay>) -> Self {
+        self.replay = replay;
+        self
+    }
+
+    /// Sets whether tests should be run in deterministic mode.
+    ///
+    /// The seed of the run is not set here, it is taken from the [`FuzzConfig`].
+    pub fn deterministic(mut self, deterministic: bool) -> Self {
+        self.deterministic = deterministic;
+        self
+    }
+
+    /// Sets the named forks test contracts can run on, see [`Config::forks`].
+    pub fn forks(mut self, forks: BTreeMap<String, String>) -> Self {
+        self.forks = forks;
+        self
+    }
+
     /// Sets available configuration profiles. Profiles are useful to validate existing in-line
     /// configurations. This argument is necessary in case a `compile_output`is provided.
     pub fn profiles(mut self, p: Vec<String>) -> Self {
@@ -214,6 +314,10 @@ impl TestOptionsBuilder {
             self.profiles.unwrap_or_else(|| vec![Config::selected_profile().into()]);
         let base_fuzz = self.fuzz.unwrap_or_default();
         let base_invariant = self.invariant.unwrap_or_default();
-        TestOptions::new(output, root, profiles, base_fuzz, base_invariant)
+        let mut options =
+            TestOptions::new(output, root, profiles, base_fuzz, base_invariant, &self.forks)?;
+        options.replay = self.replay;
+        options.deterministic = self.deterministic;
+        Ok(options)
     }
 }
@@ -13,12 +13,21 @@ use foundry_compilers::{
 };
 use foundry_config::Config;
 use foundry_evm::{
-    backend::Backend, decode::RevertDecoder, executors::ExecutorBuilder, fork::CreateFork,
-    inspectors::CheatsConfig, opts::EvmOpts, revm,
+    backend::{Backend, DatabaseExt},
+    decode::RevertDecoder,
+    executors::ExecutorBuilder,
+    fork::{CreateFork, RpcUsageRegistry},
+    inspectors::{
+        AccessPolicy, BranchMutation, BreakpointHandler, CancellationToken, CheatsConfig,
+        CreateOverride, Fixtures, ResourceLimits,
+    },
+    opts::EvmOpts,
+    precompiles::{CustomPrecompile, CustomPrecompiles},
+    revm,
 };
 use foundry_linking::{LinkOutput, Linker};
 use rayon::prelude::*;
-use revm::primitives::SpecId;
+use revm::primitives::{AccountInfo, Bytecode, SpecId};
 use std::{
     borrow::Borrow,
     collections::BTreeMap,
@@ -60,6 +69,16 @@ pub struct MultiContractRunner {
     pub coverage: bool,
     /// Whether to collect debug info
     pub debug: bool,
+    /// Whether to record the memory used by the call frames of unit tests
+    pub memory_profile: bool,
+    /// Whether to sample the time spent in the functions called by unit tests
+    pub time_profile: bool,
+    /// The handler of the breakpoints to pause the tests at, if debugging interactively.
+    pub interactive: Option<Arc<dyn BreakpointHandler>>,
+    /// The token to abort the running tests with, see [`CancellationToken`].
+    pub cancellation: Option<CancellationToken>,
+    /// The mutation to apply to a branch of the tested contracts, see [`BranchMutation`].
+    pub branch_mutation: Option<BranchMutation>,
     /// Settings related to fuzz and/or invariant tests
     pub test_options: TestOptions,
     /// Whether to enable call isolation
@@ -70,6 +89,18 @@ pub struct MultiContractRunner {
     pub libs_to_deploy: Vec<Bytes>,
     /// Library addresses used to link contracts.
     pub libraries: Libraries,
+    /// Contracts whose creation code is substituted in tests, see `test_overrides` in the config.
+    pub create_overrides: Vec<CreateOverride>,
+    /// RPC usage of the forks of the last test run.
+    pub rpc_usage: Arc<RpcUsageRegistry>,
+    /// Precompiles implemented in Rust, see `precompiles` in the config.
+    pub custom_precompiles: Arc<CustomPrecompiles>,
+    /// Deployed code of the precompiles implemented as Solidity shims.
+    pub precompile_shims: Vec<(Address, Bytes)>,
+    /// Restrictions on the accounts and storage the tests may access.
+    pub access_policy: AccessPolicy,
+    /// The fixtures saved by the test contracts with `vm.saveFixture`.
+    pub fixtures: Fixtures,
 }
 
 impl MultiContractRunner {
@@ -162,7 +193,13 @@ impl MultiContractRunner {
         trace!("running all tests");
 
         // The DB backend that serves all the data.
-        let db = Backend::spawn(self.fork.take());
+        let mut db = Backend::spawn(self.fork.take());
+        self.rpc_usage = db.rpc_usage().clone();
+        for (address, code) in &self.precompile_shims {
+            let info = AccountInfo::from_bytecode(Bytecode::new_raw(code.clone()));
+            db.insert_account_info(*address, info);
+            db.add_persistent_account(*address);
+        }
 
         let find_timer = Instant::now();
         let contracts = self.matching_contracts(filter).collect::<Vec<_>>();
@@ -192,10 +229,7 @@ impl MultiContractRunner {
                         Some(&tests_progress),
                     );
 
-                    tests_progress
-                        .inner
-                        .lock()
-                        .end_suite_progress(&id.identifier(), result.summary());
+                    tests_progress.inner.lock().end_suite_progress(&id.identifier(), &result);
 
                     (id.identifier(), result)
                 })
@@ -228,22 +262,54 @@ impl MultiContractRunner {
         let identifier = artifact_id.identifier();
         let mut span_name = identifier.as_str();
 
-        let cheats_config = CheatsConfig::new(
-            &self.config,
-            self.evm_opts.clone(),
-            Some(self.known_contracts.clone()),
-            None,
-            Some(artifact_id.version.clone()),
-        );
+        let cheats_config = CheatsConfig {
+            deterministic: self.test_options.deterministic,
+            seed: self.test_options.fuzz.seed,
+            create_overrides: self.create_overrides.clone(),
+            fixtures: self.fixtures.clone(),
+            granted_capabilities: self
+                .config
+                .cheatcode_permissions_policy()
+                .map(|policy| policy.granted(&artifact_id.source, &artifact_id.name)),
+            ..CheatsConfig::new(
+                &self.config,
+                self.evm_opts.clone(),
+                Some(self.known_contracts.clone()),
+                None,
+                Some(artifact_id.version.clone()),
+            )
+        };
 
         let executor = ExecutorBuilder::new()
             .inspectors(|stack| {
-                stack
+                let stack = stack
                     .cheatcodes(Arc::new(cheats_config))
                     .trace(self.evm_opts.verbosity >= 3 || self.debug)
                     .debug(self.debug)
                     .coverage(self.coverage)
-                    .enable_isolation(self.isolation)
+                    .memory_profile(self.memory_profile)
+                    .time_profile(self.time_profile)
+                    .historical_block_hashes(self.config.historical_block_hashes)
+                    .limits(ResourceLimits::from_config(&self.config))
+                    .access_policy(self.access_policy.clone())
+                    .enable_isolation(self.isolation);
+                let stack = if self.custom_precompiles.is_empty() {
+                    stack
+                } else {
+                    stack.custom_precompiles(self.custom_precompiles.clone())
+                };
+                let stack = match &self.interactive {
+                    Some(handler) => stack.interactive(handler.clone()),
+                    None => stack,
+                };
+                let stack = match self.branch_mutation {
+                    Some(mutation) => stack.branch_mutation(mutation),
+                    None => stack,
+                };
+                match &self.cancellation {
+                    Some(token) => stack.cancellation(token.clone()),
+                    None => stack,
+                }
             })
             .spec(self.evm_spec)
             .gas_limit(self.evm_opts.gas_limit())
@@ -299,10 +365,16 @@ pub struct MultiContractRunnerBuilder {
     pub coverage: bool,
     /// Whether or not to collect debug info
     pub debug: bool,
+    /// Whether or not to record the memory used by the call frames of unit tests
+    pub memory_profile: bool,
+    /// Whether or not to sample the time spent in the functions called by unit tests
+    pub time_profile: bool,
     /// Whether to enable call isolation
     pub isolation: bool,
     /// Settings related to fuzz and/or invariant tests
     pub test_options: Option<TestOptions>,
+    /// Rust implementations of the precompiles of the config, keyed by name
+    pub precompiles: BTreeMap<String, Arc<dyn CustomPrecompile>>,
 }
 
 impl MultiContractRunnerBuilder {
@@ -315,8 +387,11 @@ impl MultiContractRunnerBuilder {
             fork: Default::default(),
             coverage: Default::default(),
             debug: Default::default(),
+            memory_profile: Default::default(),
+            time_profile: Default::default(),
             isolation: Default::default(),
             test_options: Default::default(),
+            precompiles: Default::default(),
         }
     }
 
@@ -355,11 +430,32 @@ impl MultiContractRunnerBuilder {
         self
     }
 
+    pub fn set_memory_profile(mut self, enable: bool) -> Self {
+        self.memory_profile = enable;
+        self
+    }
+
+    pub fn set_time_profile(mut self, enable: bool) -> Self {
+        self.time_profile = enable;
+        self
+    }
+
     pub fn enable_isolation(mut self, enable: bool) -> Self {
         self.isolation = enable;
         self
     }
 
+    /// Registers the Rust implementation of the precompile with the given name in the
+    /// `precompiles` section of the config.
+    pub fn with_precompile(
+        mut self,
+        name: impl Into<String>,
+        precompile: Arc<dyn CustomPrecompile>,
+    ) -> Self {
+        self.precompiles.insert(name.into(), precompile);
+        self
+    }
+
     /// Given an EVM, proceeds to return a runner which is able to execute all tests
     /// against that evm
     pub fn build<C: Compiler>(
@@ -413,6 +509,11 @@ impl MultiContractRunnerBuilder {
         }
 
         let known_contracts = ContractsByArtifact::new(linked_contracts);
+        let create_overrides = resolve_create_overrides(&self.config, &known_contracts)?;
+        let (custom_precompiles, precompile_shims) =
+            resolve_precompiles(&self.config, &known_contracts, &self.precompiles)?;
+
+        let access_policy = AccessPolicy::from_config(&self.config);
 
         Ok(MultiContractRunner {
             contracts: deployable_contracts,
@@ -425,13 +526,81 @@ impl MultiContractRunnerBuilder {
             config: self.config,
             coverage: self.coverage,
             debug: self.debug,
+            memory_profile: self.memory_profile,
+            time_profile: self.time_profile,
+            interactive: None,
+            cancellation: None,
+            branch_mutation: None,
             test_options: self.test_options.unwrap_or_default(),
             isolation: self.isolation,
             known_contracts,
             libs_to_deploy,
             libraries,
+            create_overrides,
+            rpc_usage: Default::default(),
+            custom_precompiles: Arc::new(custom_precompiles),
+            precompile_shims,
+            access_policy,
+            fixtures: Default::default(),
+        })
+    }
+}
+
+/// Resolves the `test_overrides` of the config to the creation code of the contracts.
+fn resolve_create_overrides(
+    config: &Config,
+    known_contracts: &ContractsByArtifact,
+) -> Result<Vec<CreateOverride>> {
+    let creation_code = |name: &str| -> Result<Bytes> {
+        let (_, contract) = known_contracts
+            .find_by_name_or_identifier(name)?
+            .ok_or_else(|| eyre::eyre!("test override: could not find contract `{name}`"))?;
+        contract
+            .bytecode()
+            .cloned()
+            .ok_or_else(|| eyre::eyre!("test override: `{name}` has no creation code"))
+    };
+    config
+        .test_overrides
+        .iter()
+        .map(|(original, replacement)| {
+            Ok(CreateOverride {
+                original: creation_code(original)?,
+                replacement: creation_code(replacement)?,
+            })
         })
+        .collect()
+}
+
+/// Resolves the `precompiles` of the config to the registered Rust implementations and the
+/// deployed code of the Solidity shims.
+fn resolve_precompiles(
+    config: &Config,
+    known_contracts: &ContractsByArtifact,
+    implementations: &BTreeMap<String, Arc<dyn CustomPrecompile>>,
+) -> Result<(CustomPrecompiles, Vec<(Address, Bytes)>)> {
+    let mut precompiles = CustomPrecompiles::default();
+    let mut shims = Vec::new();
+    for (name, precompile) in &config.precompiles {
+        if let Some(shim) = &precompile.shim {
+            let (_, contract) = known_contracts
+                .find_by_name_or_identifier(shim)?
+                .ok_or_else(|| eyre::eyre!("precompile `{name}`: could not find shim `{shim}`"))?;
+            let code = contract
+                .deployed_bytecode()
+                .filter(|code| !code.is_empty())
+                .ok_or_else(|| eyre::eyre!("precompile `{name}`: shim `{shim}` has no code"))?;
+            shims.push((precompile.address, code.clone()));
+        } else {
+            let implementation = implementations.get(name).ok_or_else(|| {
+                eyre::eyre!(
+                    "precompile `{name}`: no shim set and no Rust implementation registered"
+                )
+            })?;
+            precompiles.insert(precompile.address, implementation.clone());
+        }
     }
+    Ok((precompiles, shims))
 }
 
 pub fn matches_contract(id: &ArtifactId, abi: &JsonAbi, filter: &dyn TestFilter) -> bool {
@@ -1,3 +1,5 @@
+use crate::result::SuiteResult;
+use foundry_common::progress::{self, Phase};
 use indicatif::{MultiProgress, ProgressBar};
 use parking_lot::Mutex;
 use std::{collections::HashMap, sync::Arc, time::Duration};
@@ -20,7 +22,7 @@ pub struct TestsProgressState {
 impl TestsProgressState {
     // Creates overall tests progress state.
     pub fn new(suites_len: usize, threads_no: usize) -> Self {
-        let multi = MultiProgress::new();
+        let multi = progress::multi_progress();
         let overall_progress = multi.add(ProgressBar::new(suites_len as u64));
         overall_progress.set_style(
             indicatif::ProgressStyle::with_template("{bar:40.cyan/blue} {pos:>7}/{len:7} {msg}")
@@ -28,28 +30,36 @@ impl TestsProgressState {
                 .progress_chars("##-"),
         );
         overall_progress.set_message(format!("completed (with {} threads)", threads_no as u64));
+        progress::event(Phase::Test, "start", format!("{suites_len} suites"));
         Self { multi, overall_progress, suites_progress: HashMap::default() }
     }
 
     /// Creates new test suite progress and add it to overall progress.
     pub fn start_suite_progress(&mut self, suite_name: &String) {
         let suite_progress = self.multi.add(ProgressBar::new_spinner());
-        suite_progress.set_style(
-            indicatif::ProgressStyle::with_template("{spinner} {wide_msg:.bold.dim}")
-                .unwrap()
-                .tick_chars("⠁⠂⠄⡀⢀⠠⠐⠈ "),
-        );
+        suite_progress.set_style(progress::spinner_style("{spinner} {wide_msg:.bold.dim}"));
         suite_progress.set_message(format!("{suite_name} "));
         suite_progress.enable_steady_tick(Duration::from_millis(100));
+        progress::event(Phase::Test, "suite-start", suite_name);
         self.suites_progress.insert(suite_name.to_owned(), suite_progress);
     }
 
     /// Prints suite result summary and removes it from overall progress.
-    pub fn end_suite_progress(&mut self, suite_name: &String, result_summary: String) {
+    pub fn end_suite_progress(&mut self, suite_name: &String, result: &SuiteResult) {
         if let Some(suite_progress) = self.suites_progress.remove(suite_name) {
             self.multi.suspend(|| {
-                println!("{suite_name}\n  ↪ {result_summary}");
+                println!("{suite_name}\n  ↪ {}", result.summary());
             });
+            progress::event(
+                Phase::Test,
+                "suite-finish",
+                format!(
+                    "{suite_name}: {} passed, {} failed, {} skipped",
+                    result.passed(),
+                    result.failed(),
+                    result.skipped()
+                ),
+            );
             suite_progress.finish_and_clear();
             // Increment test progress bar to reflect completed test suite.
             self.overall_progress.inc(1);
@@ -69,14 +79,15 @@ impl TestsProgressState {
         if let Some(suite_progress) = self.suites_progress.get(suite_name) {
             let fuzz_progress =
                 self.multi.insert_after(suite_progress, ProgressBar::new(runs as u64));
-            fuzz_progress.set_style(
-                indicatif::ProgressStyle::with_template(
-                    "    ↪ {prefix:.bold.dim}: [{pos}/{len}]{msg} Runs",
-                )
-                .unwrap()
-                .tick_chars("⠁⠂⠄⡀⢀⠠⠐⠈ "),
-            );
+            fuzz_progress.set_style(progress::spinner_style(
+                "    ↪ {prefix:.bold.dim}: [{pos}/{len}]{msg} Runs",
+            ));
             fuzz_progress.set_prefix(test_name.to_string());
+            progress::event(
+                Phase::Fuzz,
+                "start",
+                format!("{suite_name}::{test_name}: {runs} runs"),
+            );
             Some(fuzz_progress)
         } else {
             None
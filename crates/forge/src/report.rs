@@ -0,0 +1,325 @@
+//! Machine-readable test reports.
+//!
+//! Two formats are supported:
+//! - [`NDJSON`](TestReportWriter::test): a stream of JSON objects, one per line, with one `test`
+//!   event per test as soon as its suite finishes and a final `summary` event. Every event carries
+//!   the [`REPORT_VERSION`] and follows [`REPORT_SCHEMA`].
+//! - [JUnit XML](junit_report), for CI systems.
+
+use crate::{
+    fuzz::{BaseCounterExample, CounterExample},
+    inspectors::FrameMemory,
+    result::{TestKindReport, TestOutcome, TestResult, TestStatus},
+    traces::TraceKind,
+};
+use alloy_primitives::Address;
+use serde::Serialize;
+use std::{
+    collections::BTreeMap,
+    fmt::Write as _,
+    io::{self, Write},
+    time::Duration,
+};
+
+/// The version of the NDJSON report format.
+///
+/// Bumped whenever a field is removed or changes meaning. Fields may be added without bumping it.
+pub const REPORT_VERSION: u32 = 2;
+
+/// The JSON schema of the events of the NDJSON report.
+pub const REPORT_SCHEMA: &str = include_str!("../assets/test-report.schema.json");
+
+/// An event of the NDJSON report.
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ReportEvent<'a> {
+    /// A test finished.
+    Test(TestReport<'a>),
+    /// All tests finished.
+    Summary(SummaryReport),
+}
+
+/// The result of a single test.
+#[derive(Debug, Serialize)]
+pub struct TestReport<'a> {
+    /// The identifier of the test contract, `<path>:<name>`.
+    pub contract: &'a str,
+    /// The signature of the test function.
+    pub test: &'a str,
+    pub status: TestStatus,
+    pub reason: Option<&'a str>,
+    pub duration_ms: u64,
+    /// The kind of the test, along with its gas data.
+    #[serde(flatten)]
+    pub kind: TestKindReport,
+    /// The failing calls, a single one for fuzz tests and a sequence for invariant tests.
+    pub counterexample: Option<Vec<&'a BaseCounterExample>>,
+    /// The decoded console logs.
+    pub logs: &'a [String],
+    /// The rendered traces, depending on the verbosity.
+    pub traces: Vec<TraceReport>,
+    /// The labels assigned to addresses during the test.
+    pub labels: BTreeMap<Address, &'a str>,
+    /// Hints on the inputs needed to reach the branches a fuzz test never took.
+    pub branch_hints: &'a [String],
+    /// The call frames with the largest memory expansion, if profiled.
+    #[serde(skip_serializing_if = "<[_]>::is_empty")]
+    pub memory_hotspots: &'a [FrameMemory],
+}
+
+impl<'a> TestReport<'a> {
+    /// Creates the report of a test from its result and rendered traces.
+    pub fn new(
+        contract: &'a str,
+        test: &'a str,
+        result: &'a TestResult,
+        traces: Vec<TraceReport>,
+    ) -> Self {
+        Self {
+            contract,
+            test,
+            status: result.status,
+            reason: result.reason.as_deref(),
+            duration_ms: millis(result.duration),
+            kind: result.kind.report(),
+            counterexample: result.counterexample.as_ref().map(
+                |counterexample| match counterexample {
+                    CounterExample::Single(call) => vec![call],
+                    CounterExample::Sequence(calls) => calls.iter().collect(),
+                },
+            ),
+            logs: &result.decoded_logs,
+            traces,
+            labels: result
+                .labeled_addresses
+                .iter()
+                .map(|(address, label)| (*address, label.as_str()))
+                .collect(),
+            branch_hints: &result.branch_hints,
+            memory_hotspots: &result.memory_hotspots,
+        }
+    }
+}
+
+/// A rendered trace.
+#[derive(Debug, Serialize)]
+pub struct TraceReport {
+    pub kind: TraceKind,
+    pub rendered: String,
+}
+
+/// The summary of a test run.
+#[derive(Debug, Serialize)]
+pub struct SummaryReport {
+    pub passed: usize,
+    pub failed: usize,
+    pub skipped: usize,
+    pub duration_ms: u64,
+}
+
+impl SummaryReport {
+    /// Creates the summary of the given outcome.
+    pub fn new(outcome: &TestOutcome) -> Self {
+        Self {
+            passed: outcome.passed(),
+            failed: outcome.failed(),
+            skipped: outcome.skipped(),
+            duration_ms: millis(outcome.total_time()),
+        }
+    }
+}
+
+/// Writes the NDJSON report, one event per line.
+pub struct TestReportWriter<W> {
+    writer: W,
+}
+
+impl<W: Write> TestReportWriter<W> {
+    pub fn new(writer: W) -> Self {
+        Self { writer }
+    }
+
+    /// Writes the result of a test.
+    pub fn test(&mut self, report: TestReport<'_>) -> io::Result<()> {
+        self.write(ReportEvent::Test(report))
+    }
+
+    /// Writes the summary of the run.
+    pub fn summary(&mut self, outcome: &TestOutcome) -> io::Result<()> {
+        self.write(ReportEvent::Summary(SummaryReport::new(outcome)))
+    }
+
+    fn write(&mut self, event: ReportEvent<'_>) -> io::Result<()> {
+        #[derive(Serialize)]
+        struct Versioned<'a> {
+            version: u32,
+            #[serde(flatten)]
+            event: ReportEvent<'a>,
+        }
+
+        serde_json::to_writer(&mut self.writer, &Versioned { version: REPORT_VERSION, event })?;
+        self.writer.write_all(b"\n")?;
+        self.writer.flush()
+    }
+}
+
+/// Renders the outcome of a test run as a JUnit XML report, with one test suite per test
+/// contract.
+pub fn junit_report(outcome: &TestOutcome) -> String {
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let _ = writeln!(
+        xml,
+        "<testsuites name=\"forge\" tests=\"{}\" failures=\"{}\" skipped=\"{}\" time=\"{}\">",
+        outcome.passed() + outcome.failed() + outcome.skipped(),
+        outcome.failed(),
+        outcome.skipped(),
+        seconds(outcome.total_time()),
+    );
+    for (contract, suite) in &outcome.results {
+        let _ = writeln!(
+            xml,
+            "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" skipped=\"{}\" time=\"{}\">",
+            escape(contract),
+            suite.len(),
+            suite.failed(),
+            suite.skipped(),
+            seconds(suite.duration),
+        );
+        for (test, result) in &suite.test_results {
+            let _ = write!(
+                xml,
+                "    <testcase name=\"{}\" classname=\"{}\" time=\"{}\"",
+                escape(test),
+                escape(contract),
+                seconds(result.duration),
+            );
+            if result.status == TestStatus::Success && result.decoded_logs.is_empty() {
+                xml.push_str("/>\n");
+                continue
+            }
+            xml.push_str(">\n");
+            match result.status {
+                TestStatus::Success => {}
+                TestStatus::Failure => {
+                    let message = result.reason.as_deref().unwrap_or_default();
+                    let _ = write!(xml, "      <failure message=\"{}\">", escape(message));
+                    let calls = match &result.counterexample {
+                        Some(CounterExample::Single(call)) => std::slice::from_ref(call),
+                        Some(CounterExample::Sequence(calls)) => calls.as_slice(),
+                        None => &[],
+                    };
+                    let calls = calls.iter().map(ToString::to_string).collect::<Vec<_>>();
+                    xml.push_str(&escape(&calls.join("\n")));
+                    xml.push_str("</failure>\n");
+                }
+                TestStatus::Skipped => {
+                    let message = result.reason.as_deref().unwrap_or_default();
+                    let _ = writeln!(xml, "      <skipped message=\"{}\"/>", escape(message));
+                }
+            }
+            if !result.decoded_logs.is_empty() {
+                let logs = result.decoded_logs.join("\n");
+                let _ = writeln!(xml, "      <system-out>{}</system-out>", escape(&logs));
+            }
+            xml.push_str("    </testcase>\n");
+        }
+        xml.push_str("  </testsuite>\n");
+    }
+    xml.push_str("</testsuites>\n");
+    xml
+}
+
+/// Escapes the XML special characters of the given text.
+fn escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn millis(duration: Duration) -> u64 {
+    duration.as_millis().try_into().unwrap_or(u64::MAX)
+}
+
+fn seconds(duration: Duration) -> String {
+    format!("{:.3}", duration.as_secs_f64())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::result::{SuiteResult, TestKind};
+
+    fn outcome() -> TestOutcome {
+        let passed = TestResult {
+            status: TestStatus::Success,
+            kind: TestKind::Unit { gas: 42, refund: 0 },
+            decoded_logs: vec!["a < b".to_string()],
+            ..Default::default()
+        };
+        let failed = TestResult {
+            status: TestStatus::Failure,
+            reason: Some("assertion \"failed\"".to_string()),
+            ..Default::default()
+        };
+        let test_results = BTreeMap::from([
+            ("testFail()".to_string(), failed),
+            ("testPass()".to_string(), passed),
+        ]);
+        let suite = SuiteResult::new(Duration::from_millis(5), test_results, Vec::new());
+        TestOutcome::new(BTreeMap::from([("src/T.t.sol:T".to_string(), suite)]), false)
+    }
+
+    #[test]
+    fn ndjson_report_follows_schema() {
+        let outcome = outcome();
+        let mut out = Vec::new();
+        let mut writer = TestReportWriter::new(&mut out);
+        for (contract, suite) in &outcome.results {
+            for (test, result) in &suite.test_results {
+                writer.test(TestReport::new(contract, test, result, Vec::new())).unwrap();
+            }
+        }
+        writer.summary(&outcome).unwrap();
+
+        let schema: serde_json::Value = serde_json::from_str(REPORT_SCHEMA).unwrap();
+        let events = String::from_utf8(out).unwrap();
+        let events = events
+            .lines()
+            .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(events.len(), 3);
+        assert_eq!(events[0]["reason"], "assertion \"failed\"");
+        assert_eq!(events[1]["kind"], "unit");
+        assert_eq!(events[1]["gas"], 42);
+        assert_eq!(events[2]["passed"], 1);
+
+        for event in &events {
+            assert_eq!(event["version"], REPORT_VERSION);
+            let ty = event["type"].as_str().unwrap();
+            let required = schema["definitions"][ty]["required"].as_array().unwrap();
+            for field in required {
+                let field = field.as_str().unwrap();
+                assert!(event.get(field).is_some(), "{ty} event is missing `{field}`");
+            }
+        }
+    }
+
+    #[test]
+    fn junit_report_escapes() {
+        let xml = junit_report(&outcome());
+        assert!(xml.contains(
+            r#"<testsuite name="src/T.t.sol:T" tests="2" failures="1" skipped="0" time="0.005">"#
+        ));
+        assert!(xml.contains(r#"<failure message="assertion &quot;failed&quot;">"#));
+        assert!(xml.contains("<system-out>a &lt; b</system-out>"));
+    }
+}
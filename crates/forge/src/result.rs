@@ -5,14 +5,19 @@ use crate::{
     fuzz::{BaseCounterExample, FuzzedCases},
     gas_report::GasReport,
 };
-use alloy_primitives::{Address, Log};
+use alloy_primitives::{Address, Bytes, Log};
 use eyre::Report;
-use foundry_common::{evm::Breakpoints, get_contract_name, get_file_name, shell};
+use foundry_common::{
+    evm::Breakpoints, get_contract_name, get_file_name, serde_helpers::serialize_sorted, shell,
+    ContractData, ContractsByArtifact,
+};
 use foundry_evm::{
     coverage::HitMaps,
     executors::{EvmError, RawCallResult},
+    fork::RpcUsage,
     fuzz::{CounterExample, FuzzCase, FuzzFixtures, FuzzTestResult},
-    traces::{CallTraceArena, CallTraceDecoder, TraceKind, Traces},
+    inspectors::{FrameMemory, FunctionTime, TimeProfile},
+    traces::{CallTraceArena, CallTraceDecoder, GasRefunds, TraceKind, Traces},
 };
 use serde::{Deserialize, Serialize};
 use std::{
@@ -22,6 +27,44 @@ use std::{
 };
 use yansi::Paint;
 
+/// The number of call frames with the largest memory expansion kept for each test.
+pub const MEMORY_HOTSPOTS_PER_TEST: usize = 5;
+
+/// The wall-clock time the unit tests spent executing a function, see
+/// [`TestOutcome::top_time_hotspots`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TimeHotspot {
+    /// The function, as `<contract>::<signature>`.
+    pub function: String,
+    /// The number of times the function was called.
+    pub calls: usize,
+    /// The time spent executing the function, excluding the calls it made.
+    pub time: Duration,
+}
+
+/// Returns the name of a profiled function, using the ABI of the contract whose code it ran, or
+/// the label or address of the account and the selector otherwise.
+fn time_hotspot_name(
+    function: &FunctionTime,
+    contract: Option<&ContractData>,
+    labels: &HashMap<Address, String>,
+) -> String {
+    let account = match (contract, labels.get(&function.address)) {
+        (Some(contract), _) => contract.name.clone(),
+        (None, Some(label)) => label.clone(),
+        (None, None) => function.address.to_string(),
+    };
+    let signature = match function.selector {
+        _ if function.is_create => "constructor".to_string(),
+        Some(selector) => contract
+            .and_then(|contract| contract.abi.functions().find(|f| f.selector() == selector))
+            .map(|f| f.signature())
+            .unwrap_or_else(|| selector.to_string()),
+        None => "fallback".to_string(),
+    };
+    format!("{account}::{signature}")
+}
+
 /// The aggregated result of a test run.
 #[derive(Clone, Debug)]
 pub struct TestOutcome {
@@ -126,6 +169,88 @@ impl TestOutcome {
         self.results.values().map(|suite| suite.duration).sum()
     }
 
+    /// Returns the `n` tests with the heaviest fork RPC requests, heaviest first, as
+    /// `(contract name, signature, usage)`.
+    pub fn top_rpc_usage(&self, n: usize) -> Vec<(&str, &str, RpcUsage)> {
+        let mut tests = self
+            .results
+            .iter()
+            .flat_map(|(id, suite)| {
+                suite.test_results.iter().map(move |(sig, result)| {
+                    (get_contract_name(id), sig.as_str(), result.rpc_usage)
+                })
+            })
+            .filter(|(_, _, usage)| !usage.is_empty())
+            .collect::<Vec<_>>();
+        tests.sort_by_key(|(_, _, usage)| std::cmp::Reverse(usage.weight));
+        tests.truncate(n);
+        tests
+    }
+
+    /// Returns the `n` call frames with the largest memory expansion across all tests, largest
+    /// first, as `(contract name, signature, result, frame)`.
+    pub fn top_memory_hotspots(&self, n: usize) -> Vec<(&str, &str, &TestResult, &FrameMemory)> {
+        let mut frames = self
+            .results
+            .iter()
+            .flat_map(|(id, suite)| {
+                suite.test_results.iter().flat_map(move |(sig, result)| {
+                    result
+                        .memory_hotspots
+                        .iter()
+                        .map(move |frame| (get_contract_name(id), sig.as_str(), result, frame))
+                })
+            })
+            .collect::<Vec<_>>();
+        frames.sort_by_key(|(_, _, _, frame)| std::cmp::Reverse(frame.peak_memory));
+        frames.truncate(n);
+        frames
+    }
+
+    /// Returns the `n` functions the unit tests spent the most time executing across all tests,
+    /// most first, along with the total time spent executing the unit tests.
+    ///
+    /// The functions are named after the local contract whose code they run, if any.
+    pub fn top_time_hotspots(
+        &self,
+        known_contracts: &ContractsByArtifact,
+        n: usize,
+    ) -> (Vec<TimeHotspot>, Duration) {
+        let mut contracts = HashMap::<Bytes, Option<&ContractData>>::new();
+        let mut hotspots = HashMap::<String, TimeHotspot>::new();
+        let mut total = Duration::ZERO;
+        let results = self.results.values().flat_map(|suite| suite.test_results.values());
+        for result in results {
+            let Some(profile) = &result.time_profile else { continue };
+            for function in &profile.functions {
+                // Precompiles and cheatcodes have no code.
+                let contract = if function.code.is_empty() {
+                    None
+                } else {
+                    *contracts.entry(function.code.clone()).or_insert_with(|| {
+                        let found = if function.is_create {
+                            known_contracts.find_by_creation_code(&function.code)
+                        } else {
+                            known_contracts.find_by_deployed_code(&function.code)
+                        };
+                        found.map(|(_, contract)| contract)
+                    })
+                };
+                let name = time_hotspot_name(function, contract, &result.labeled_addresses);
+                let hotspot = hotspots
+                    .entry(name.clone())
+                    .or_insert_with(|| TimeHotspot { function: name, ..Default::default() });
+                hotspot.calls += function.calls;
+                hotspot.time += function.self_time;
+                total += function.self_time;
+            }
+        }
+        let mut hotspots = hotspots.into_values().collect::<Vec<_>>();
+        hotspots.sort_by(|a, b| b.time.cmp(&a.time).then_with(|| a.function.cmp(&b.function)));
+        hotspots.truncate(n);
+        (hotspots, total)
+    }
+
     /// Formats the aggregated summary of all test suites into a string (for printing).
     pub fn summary(&self, wall_clock_time: Duration) -> String {
         let num_test_suites = self.results.len();
@@ -366,6 +491,10 @@ pub struct TestResult {
     #[serde(skip)]
     pub traces: Traces,
 
+    /// The gas refunded to the calls of the execution trace of a unit test, keyed by node.
+    #[serde(skip)]
+    pub gas_refunds: GasRefunds,
+
     /// Additional traces to use for gas report.
     #[serde(skip)]
     pub gas_report_traces: Vec<Vec<CallTraceArena>>,
@@ -375,11 +504,40 @@ pub struct TestResult {
     pub coverage: Option<HitMaps>,
 
     /// Labeled addresses
+    #[serde(serialize_with = "serialize_sorted")]
     pub labeled_addresses: HashMap<Address, String>,
 
+    /// The gas used by the functions called during the test, keyed by
+    /// `<contract name>::<signature>`.
+    ///
+    /// Only collected by `forge snapshot` when writing or diffing against JSON snapshots.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub function_gas: BTreeMap<String, u64>,
+
+    /// Hints on the inputs needed to reach the branches a fuzz test never took.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub branch_hints: Vec<String>,
+
+    /// The fork RPC requests sent by the test.
+    #[serde(default, skip_serializing_if = "RpcUsage::is_empty")]
+    pub rpc_usage: RpcUsage,
+
+    /// The call frames of the test with the largest memory expansion, largest first.
+    ///
+    /// Only collected for unit tests, with `forge test --memory-profile`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub memory_hotspots: Vec<FrameMemory>,
+
+    /// The wall-clock time spent in the functions called by the test.
+    ///
+    /// Only collected for unit tests, with `forge test --time-profile`.
+    #[serde(skip)]
+    pub time_profile: Option<TimeProfile>,
+
     pub duration: Duration,
 
     /// pc breakpoint char map
+    #[serde(serialize_with = "serialize_sorted")]
     pub breakpoints: Breakpoints,
 }
 
@@ -469,14 +627,22 @@ impl TestResult {
         reason: Option<String>,
         raw_call_result: RawCallResult,
     ) -> Self {
-        self.kind =
-            TestKind::Unit { gas: raw_call_result.gas_used.wrapping_sub(raw_call_result.stipend) };
+        self.kind = TestKind::Unit {
+            gas: raw_call_result.gas_used.wrapping_sub(raw_call_result.stipend),
+            refund: raw_call_result.gas_refunded,
+        };
 
         // Record logs, labels, traces and merge coverages.
         self.logs.extend(raw_call_result.logs);
         self.labeled_addresses.extend(raw_call_result.labels);
         self.traces.extend(raw_call_result.traces.map(|traces| (TraceKind::Execution, traces)));
+        self.gas_refunds = raw_call_result.gas_refunds;
         self.merge_coverages(raw_call_result.coverage);
+        if let Some(profile) = &raw_call_result.memory_profile {
+            self.memory_hotspots =
+                profile.hotspots(MEMORY_HOTSPOTS_PER_TEST).into_iter().cloned().collect();
+        }
+        self.time_profile = raw_call_result.time_profile;
 
         self.status = match success {
             true => TestStatus::Success,
@@ -555,6 +721,14 @@ impl TestResult {
         self
     }
 
+    /// Returns the fail result for a differential fuzz test whose targets could not be resolved.
+    pub fn differential_setup_fail(mut self, e: Report) -> Self {
+        self.status = TestStatus::Failure;
+        self.reason = Some(format!("failed to set up differential fuzzing: {e:#}"));
+        self.decoded_logs = decode_console_logs(&self.logs);
+        self
+    }
+
     /// Returns the invariant test result.
     pub fn invariant_result(
         mut self,
@@ -602,19 +776,36 @@ impl TestResult {
 }
 
 /// Data report by a test.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
 pub enum TestKindReport {
-    Unit { gas: u64 },
-    Fuzz { runs: usize, mean_gas: u64, median_gas: u64 },
-    Invariant { runs: usize, calls: usize, reverts: usize },
+    /// The gas of a unit test is net of its refund, which is reported separately.
+    Unit {
+        gas: u64,
+        #[serde(default, skip_serializing_if = "is_zero")]
+        refund: u64,
+    },
+    Fuzz {
+        runs: usize,
+        mean_gas: u64,
+        median_gas: u64,
+    },
+    Invariant {
+        runs: usize,
+        calls: usize,
+        reverts: usize,
+    },
 }
 
 impl fmt::Display for TestKindReport {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match *self {
-            Self::Unit { gas } => {
+            Self::Unit { gas, refund: 0 } => {
                 write!(f, "(gas: {gas})")
             }
+            Self::Unit { gas, refund } => {
+                write!(f, "(gas: {gas}, refund: {refund})")
+            }
             Self::Fuzz { runs, mean_gas, median_gas } => {
                 write!(f, "(runs: {runs}, μ: {mean_gas}, ~: {median_gas})")
             }
@@ -629,7 +820,7 @@ impl TestKindReport {
     /// Returns the main gas value to compare against
     pub fn gas(&self) -> u64 {
         match *self {
-            Self::Unit { gas } => gas,
+            Self::Unit { gas, .. } => gas,
             // We use the median for comparisons
             Self::Fuzz { median_gas, .. } => median_gas,
             // We return 0 since it's not applicable
@@ -638,11 +829,21 @@ impl TestKindReport {
     }
 }
 
+fn is_zero(n: &u64) -> bool {
+    *n == 0
+}
+
 /// Various types of tests
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum TestKind {
     /// A unit test.
-    Unit { gas: u64 },
+    Unit {
+        /// The gas used, after the refund.
+        gas: u64,
+        /// The gas refunded, capped to a fraction of the gas used by the transaction.
+        #[serde(default)]
+        refund: u64,
+    },
     /// A fuzz test.
     Fuzz {
         /// we keep this for the debugger
@@ -657,7 +858,7 @@ pub enum TestKind {
 
 impl Default for TestKind {
     fn default() -> Self {
-        Self::Unit { gas: 0 }
+        Self::Unit { gas: 0, refund: 0 }
     }
 }
 
@@ -665,7 +866,7 @@ impl TestKind {
     /// The gas consumed by this test
     pub fn report(&self) -> TestKindReport {
         match *self {
-            Self::Unit { gas } => TestKindReport::Unit { gas },
+            Self::Unit { gas, refund } => TestKindReport::Unit { gas, refund },
             Self::Fuzz { first_case: _, runs, mean_gas, median_gas } => {
                 TestKindReport::Fuzz { runs, mean_gas, median_gas }
             }
@@ -4,7 +4,7 @@ use crate::{
     fuzz::{invariant::BasicTxDetails, BaseCounterExample},
     multi_runner::{is_matching_test, TestContract},
     progress::{start_fuzz_progress, TestsProgress},
-    result::{SuiteResult, TestResult, TestSetup},
+    result::{SuiteResult, TestResult, TestSetup, TestStatus},
     TestFilter, TestOptions,
 };
 use alloy_dyn_abi::DynSolValue;
@@ -15,22 +15,24 @@ use foundry_common::{
     contracts::{ContractsByAddress, ContractsByArtifact},
     TestFunctionExt, TestFunctionKind,
 };
-use foundry_config::{FuzzConfig, InvariantConfig};
+use foundry_config::{evm_spec_id, FuzzConfig, InlineEvmConfig, InvariantConfig};
 use foundry_evm::{
     constants::CALLER,
     decode::RevertDecoder,
     executors::{
-        fuzz::FuzzedExecutor,
+        fuzz::{DifferentialTargets, FuzzedExecutor},
         invariant::{
             check_sequence, replay_error, replay_run, InvariantExecutor, InvariantFuzzError,
         },
         CallResult, EvmError, ExecutionErr, Executor, RawCallResult,
     },
+    fork::{CreateFork, RpcUsageTracker},
     fuzz::{
         fixture_name,
         invariant::{CallDetails, InvariantContract},
-        CounterExample, FuzzFixtures,
+        CounterExample, FuzzFixtures, FuzzReplay,
     },
+    inspectors::CANCELLED_MESSAGE,
     traces::{load_contracts, TraceKind},
 };
 use proptest::test_runner::TestRunner;
@@ -38,6 +40,8 @@ use rayon::prelude::*;
 use std::{
     cmp::min,
     collections::{BTreeMap, HashMap},
+    path::Path,
+    sync::Arc,
     time::Instant,
 };
 
@@ -306,9 +310,44 @@ impl<'a> ContractRunner<'a> {
             match_sig
         });
 
+        // Contracts annotated with a fork are set up on it. The forks are shared by all the suites,
+        // so contracts on the same fork share its cache.
+        if let Some(fork) = test_options.fork(self.name) {
+            let config = InlineEvmConfig {
+                fork_url: Some(fork.url.clone()),
+                fork_block_number: fork.block_number,
+                ..Default::default()
+            };
+            if let Err(err) = self.apply_evm_config(&config) {
+                return SuiteResult::new(
+                    start.elapsed(),
+                    [(
+                        "setUp()".to_string(),
+                        TestResult::fail(format!("failed to fork {fork}: {err}")),
+                    )]
+                    .into(),
+                    warnings,
+                )
+            }
+        }
+
         // Invariant testing requires tracing to figure out what contracts were created.
         // We also want to disable `debug` for setup since we won't be using those traces.
         let has_invariants = self.contract.abi.functions().any(|func| func.is_invariant_test());
+
+        // Tests overriding the environment the contract is deployed in are set up on their own,
+        // starting from the state before the setup of the suite.
+        let pristine = self
+            .contract
+            .abi
+            .functions()
+            .any(|func| {
+                test_options
+                    .evm_config(self.name, &func.name)
+                    .is_some_and(InlineEvmConfig::requires_setup)
+            })
+            .then(|| self.executor.clone());
+
         let prev_tracer = self.executor.inspector_mut().tracer.take();
         self.executor.set_tracing(prev_tracer.is_some() || has_invariants, false);
 
@@ -369,35 +408,79 @@ impl<'a> ContractRunner<'a> {
                 )
                 .entered();
 
-                let setup = setup.clone();
-                let mut res = match kind {
-                    TestFunctionKind::UnitTest { should_fail } => {
-                        self.run_unit_test(func, should_fail, setup)
+                let run = || {
+                    // Don't start new tests once the run was cancelled.
+                    if self.executor.is_cancelled() {
+                        return TestResult::fail(CANCELLED_MESSAGE.to_string());
                     }
-                    TestFunctionKind::FuzzTest { should_fail } => {
-                        let runner = test_options.fuzz_runner(self.name, &func.name);
-                        let fuzz_config = test_options.fuzz_config(self.name, &func.name);
 
-                        self.run_fuzz_test(func, should_fail, runner, setup, fuzz_config.clone())
-                    }
-                    TestFunctionKind::InvariantTest => {
-                        let runner = test_options.invariant_runner(self.name, &func.name);
-                        let invariant_config = test_options.invariant_config(self.name, &func.name);
-
-                        self.run_invariant_test(
-                            runner,
-                            setup,
-                            invariant_config.clone(),
-                            func,
-                            call_after_invariant,
-                            &known_contracts,
-                            identified_contracts.as_ref().unwrap(),
-                        )
+                    let own;
+                    let (this, setup, identified_contracts) =
+                        match test_options.evm_config(self.name, &func.name) {
+                            Some(config) => {
+                                own = match self.evm_config_runner(
+                                    config,
+                                    pristine.as_ref(),
+                                    &setup,
+                                    call_setup,
+                                    kind.is_invariant_test().then_some(&known_contracts),
+                                ) {
+                                    Ok(own) if own.1.reason.is_none() => own,
+                                    Ok((_, setup, _)) => return TestResult::setup_fail(setup),
+                                    Err(err) => return TestResult::fail(err.to_string()),
+                                };
+                                let identified = own.2.as_ref().or(identified_contracts.as_ref());
+                                (&own.0, &own.1, identified)
+                            }
+                            None => (&self, &setup, identified_contracts.as_ref()),
+                        };
+
+                    match kind {
+                        TestFunctionKind::UnitTest { should_fail } => {
+                            this.run_unit_test(func, should_fail, setup.clone())
+                        }
+                        TestFunctionKind::FuzzTest { should_fail } => {
+                            let runner = test_options.fuzz_runner(self.name, &func.name);
+                            let fuzz_config = test_options.fuzz_config(self.name, &func.name);
+                            let replay = test_options.fuzz_replay(self.name, &sig);
+
+                            this.run_fuzz_test(
+                                func,
+                                should_fail,
+                                runner,
+                                setup.clone(),
+                                fuzz_config.clone(),
+                                replay,
+                            )
+                        }
+                        TestFunctionKind::InvariantTest => {
+                            let runner = test_options.invariant_runner(self.name, &func.name);
+                            let invariant_config =
+                                test_options.invariant_config(self.name, &func.name);
+
+                            this.run_invariant_test(
+                                runner,
+                                setup.clone(),
+                                invariant_config.clone(),
+                                func,
+                                call_after_invariant,
+                                &known_contracts,
+                                identified_contracts.unwrap(),
+                            )
+                        }
+                        _ => unreachable!(),
                     }
-                    _ => unreachable!(),
                 };
 
+                // Fork requests are attributed to the test whose thread sends them.
+                let rpc_usage = Arc::new(RpcUsageTracker::default());
+                let mut res = rpc_usage.scope(run);
+                if test_options.deterministic {
+                    check_reproducible(&mut res, &run());
+                }
+
                 res.duration = start.elapsed();
+                res.rpc_usage = rpc_usage.usage();
 
                 (sig, res)
             })
@@ -407,6 +490,81 @@ impl<'a> ContractRunner<'a> {
         SuiteResult::new(duration, test_results, warnings)
     }
 
+    /// Returns a runner for a test that overrides "evm" settings, along with the setup of its
+    /// contract.
+    ///
+    /// If the settings change the environment the contract is deployed in, the contract is
+    /// deployed and set up again on the `pristine` executor, otherwise the setup of the suite is
+    /// reused.
+    ///
+    /// The contracts created by the new setup are identified if `known_contracts` is given, for
+    /// invariant tests.
+    fn evm_config_runner(
+        &self,
+        config: &InlineEvmConfig,
+        pristine: Option<&Executor>,
+        setup: &TestSetup,
+        call_setup: bool,
+        known_contracts: Option<&ContractsByArtifact>,
+    ) -> Result<(Self, TestSetup, Option<ContractsByAddress>)> {
+        let mut runner = self.clone();
+        if !config.requires_setup() {
+            runner.apply_evm_config(config)?;
+            return Ok((runner, setup.clone(), None))
+        }
+
+        runner.executor = pristine.expect("no pristine executor for the test setup").clone();
+        runner.apply_evm_config(config)?;
+
+        let prev_tracer = runner.executor.inspector_mut().tracer.take();
+        runner.executor.set_tracing(prev_tracer.is_some() || known_contracts.is_some(), false);
+        let setup = runner.setup(call_setup);
+        runner.executor.inspector_mut().tracer = prev_tracer;
+
+        let identified_contracts =
+            known_contracts.map(|known| load_contracts(setup.traces.iter().map(|(_, t)| t), known));
+        Ok((runner, setup, identified_contracts))
+    }
+
+    /// Applies the overridden "evm" settings to the executor, forking if requested.
+    fn apply_evm_config(&mut self, config: &InlineEvmConfig) -> Result<()> {
+        if let Some(evm_version) = &config.evm_version {
+            self.executor.set_spec_id(evm_spec_id(evm_version));
+        }
+        if let Some(isolate) = config.isolate {
+            self.executor.inspector_mut().enable_isolation(isolate);
+        }
+        if let Some(gas_limit) = config.gas_limit {
+            self.executor.set_gas_limit(gas_limit);
+        }
+
+        if config.fork_url.is_none() && config.fork_block_number.is_none() {
+            return Ok(())
+        }
+        let Some(cheats) = self.executor.inspector().cheatcodes.as_ref().map(|c| c.config.clone())
+        else {
+            eyre::bail!("forking a test requires cheatcodes")
+        };
+        let Some(url) = config.fork_url.as_deref().or(cheats.evm_opts.fork_url.as_deref()) else {
+            eyre::bail!(
+                "`evm.fork-block-number` requires a fork URL, set one with `evm.fork-url` or `--fork-url`"
+            )
+        };
+        let url = cheats.rpc_url(url).map_err(|err| eyre::eyre!("{err}"))?;
+        let mut evm_opts = cheats.evm_opts.clone();
+        evm_opts.fork_block_number = config.fork_block_number;
+        evm_opts.fork_state = None;
+        let fork = CreateFork {
+            enable_caching: !cheats.no_storage_caching &&
+                cheats.rpc_storage_caching.enable_for_endpoint(&url),
+            url,
+            env: self.executor.env().clone(),
+            evm_opts,
+        };
+        self.executor.create_select_fork(fork)?;
+        Ok(())
+    }
+
     /// Runs a single unit test.
     ///
     /// Calls the given functions and returns the `TestResult`.
@@ -615,6 +773,10 @@ impl<'a> ContractRunner<'a> {
         )
     }
 
+    /// Runs a single fuzz test.
+    ///
+    /// If a `replay` is given, only the recorded failing case is re-executed. Otherwise, the
+    /// failing case, if any, is persisted to the fuzz replay file of the test.
     pub fn run_fuzz_test(
         &self,
         func: &Function,
@@ -622,23 +784,40 @@ impl<'a> ContractRunner<'a> {
         runner: TestRunner,
         setup: TestSetup,
         fuzz_config: FuzzConfig,
+        replay: Option<&FuzzReplay>,
     ) -> TestResult {
         let address = setup.address;
         let fuzz_fixtures = setup.fuzz_fixtures.clone();
         let test_result = TestResult::new(setup);
 
-        // Run fuzz test
-        let progress = start_fuzz_progress(self.progress, self.name, &func.name, fuzz_config.runs);
-        let fuzzed_executor =
-            FuzzedExecutor::new(self.executor.clone(), runner, self.sender, fuzz_config);
-        let result = fuzzed_executor.fuzz(
-            func,
-            &fuzz_fixtures,
-            address,
-            should_fail,
-            self.revert_decoder,
-            progress.as_ref(),
-        );
+        let seed = fuzz_config.seed;
+        let replay_file = fuzz_config.replay_file(self.name, &func.name);
+        let corpus_dir = fuzz_config.corpus_path(self.name, &func.name);
+        let mut fuzzed_executor =
+            FuzzedExecutor::new(self.executor.clone(), runner, self.sender, fuzz_config.clone());
+        if func.is_differential_test() {
+            match DifferentialTargets::resolve(&self.executor, self.sender, address, func) {
+                Ok(targets) => fuzzed_executor = fuzzed_executor.with_differential(targets),
+                Err(err) => return test_result.differential_setup_fail(err),
+            }
+        }
+        let result = if let Some(replay) = replay {
+            // Replay recorded failure without generating new inputs.
+            fuzzed_executor.replay(func, replay, address, should_fail, self.revert_decoder)
+        } else {
+            // Run fuzz test
+            let progress =
+                start_fuzz_progress(self.progress, self.name, &func.name, fuzz_config.runs);
+            fuzzed_executor.fuzz(
+                func,
+                &fuzz_fixtures,
+                address,
+                should_fail,
+                self.revert_decoder,
+                progress.as_ref(),
+                corpus_dir,
+            )
+        };
 
         // Check the last test result and skip the test
         // if it's marked as so.
@@ -646,6 +825,57 @@ impl<'a> ContractRunner<'a> {
             return test_result.single_skip()
         }
 
-        test_result.fuzz_result(result)
+        // Persist the failing case so it can be replayed with `forge test --replay`.
+        if let (None, Some(CounterExample::Single(counterexample)), Some(replay_file)) =
+            (replay, &result.counterexample, replay_file)
+        {
+            let replay = FuzzReplay {
+                contract: self.name.to_string(),
+                test: func.signature(),
+                seed,
+                calldata: counterexample.calldata.clone(),
+                args: counterexample.args.clone(),
+            };
+            if let Err(err) = persist_fuzz_replay(&replay_file, &replay) {
+                error!(%err, "Failed to record fuzz replay");
+            }
+        }
+
+        let branch_hints = result
+            .branch_comparisons
+            .as_ref()
+            .map(|comparisons| comparisons.hints(func).iter().map(ToString::to_string).collect())
+            .unwrap_or_default();
+        let mut test_result = test_result.fuzz_result(result);
+        test_result.branch_hints = branch_hints;
+        test_result
+    }
+}
+
+/// Marks the test as failed if executing it again produced a different result.
+fn check_reproducible(result: &mut TestResult, rerun: &TestResult) {
+    let mismatch = if result.status != rerun.status {
+        "status"
+    } else if result.reason != rerun.reason {
+        "revert reason"
+    } else if result.logs != rerun.logs || result.decoded_logs != rerun.decoded_logs {
+        "logs"
+    } else if result.labeled_addresses != rerun.labeled_addresses {
+        "labeled addresses"
+    } else if result.kind.report() != rerun.kind.report() {
+        "gas usage"
+    } else {
+        return
+    };
+    result.status = TestStatus::Failure;
+    result.reason = Some(format!("non-deterministic {mismatch} when executing the test twice"));
+}
+
+/// Writes the given fuzz replay to `path`, creating parent directories as needed.
+fn persist_fuzz_replay(path: &Path, replay: &FuzzReplay) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        foundry_common::fs::create_dir_all(parent)?;
     }
+    foundry_common::fs::write_json_file(path, replay)?;
+    Ok(())
 }
@@ -0,0 +1,261 @@
+//! Artifacts of test runs, persisted to `<out>/test-artifacts`.
+//!
+//! Every run is written to its own directory, with one file per test:
+//!
+//! ```text
+//! <out>/test-artifacts/
+//! ├── index.json
+//! └── <run id>/
+//!     └── <contract file>/<contract name>/<test signature>.json
+//! ```
+//!
+//! The index lists the runs, oldest first, along with the status of their tests.
+
+use crate::{
+    fuzz::CounterExample,
+    result::{TestKind, TestResult, TestStatus},
+    traces::TraceKind,
+};
+use eyre::Result;
+use foundry_common::fs;
+use serde::{Deserialize, Serialize};
+use std::{
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// The name of the test artifacts directory, relative to the project's `out` directory.
+pub const TEST_ARTIFACTS_DIR: &str = "test-artifacts";
+
+/// The name of the index file in the test artifacts directory.
+const INDEX_FILE: &str = "index.json";
+
+/// The artifacts of a single test.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TestArtifact {
+    /// The identifier of the test contract, `<path>:<name>`.
+    pub contract: String,
+    /// The signature of the test function.
+    pub test: String,
+    pub status: TestStatus,
+    pub reason: Option<String>,
+    pub counterexample: Option<CounterExample>,
+    /// The kind of the test, along with its gas data.
+    pub kind: TestKind,
+    /// The decoded console logs.
+    pub logs: Vec<String>,
+    /// The rendered traces.
+    pub traces: Vec<(TraceKind, String)>,
+    pub duration: Duration,
+}
+
+impl TestArtifact {
+    /// Creates the artifact of a test from its result and rendered traces.
+    pub fn new(
+        contract: &str,
+        test: &str,
+        result: &TestResult,
+        traces: Vec<(TraceKind, String)>,
+    ) -> Self {
+        Self {
+            contract: contract.to_string(),
+            test: test.to_string(),
+            status: result.status,
+            reason: result.reason.clone(),
+            counterexample: result.counterexample.clone(),
+            kind: result.kind.clone(),
+            logs: result.decoded_logs.clone(),
+            traces,
+            duration: result.duration,
+        }
+    }
+}
+
+/// A test listed in the index.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TestArtifactEntry {
+    pub contract: String,
+    pub test: String,
+    pub status: TestStatus,
+    /// The path of the artifact, relative to the run's directory.
+    pub path: PathBuf,
+}
+
+impl TestArtifactEntry {
+    /// Returns `true` if the test matches the given pattern, which is either the name or signature
+    /// of the test, optionally prefixed with `<contract name>::`.
+    pub fn matches(&self, pattern: &str) -> bool {
+        let (contract, test) = match pattern.rsplit_once("::") {
+            Some((contract, test)) => (Some(contract), test),
+            None => (None, pattern),
+        };
+        let name = self.test.split('(').next().unwrap_or_default();
+        let contract_name = self.contract.rsplit(':').next().unwrap_or_default();
+        (test == self.test || test == name) &&
+            contract.map_or(true, |c| c == contract_name || c == self.contract)
+    }
+}
+
+/// A test run listed in the index.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TestRun {
+    /// The identifier of the run, which is also the name of its directory.
+    pub id: String,
+    /// The time the run started at, in seconds since the epoch.
+    pub timestamp: u64,
+    pub tests: Vec<TestArtifactEntry>,
+}
+
+impl TestRun {
+    /// Returns the number of failed tests.
+    pub fn failures(&self) -> usize {
+        self.tests.iter().filter(|test| test.status.is_failure()).count()
+    }
+}
+
+/// The index of the test artifacts directory.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TestArtifactsIndex {
+    /// The runs, oldest first.
+    pub runs: Vec<TestRun>,
+}
+
+impl TestArtifactsIndex {
+    /// Reads the index of the given test artifacts directory, which is empty if it doesn't exist.
+    pub fn read(dir: &Path) -> Result<Self> {
+        let path = dir.join(INDEX_FILE);
+        if !path.exists() {
+            return Ok(Self::default())
+        }
+        Ok(fs::read_json_file(&path)?)
+    }
+
+    /// Writes the index to the given test artifacts directory.
+    pub fn write(&self, dir: &Path) -> Result<()> {
+        fs::create_dir_all(dir)?;
+        fs::write_json_file(&dir.join(INDEX_FILE), self)?;
+        Ok(())
+    }
+
+    /// Returns the run with the given id, or the latest one.
+    pub fn run(&self, id: Option<&str>) -> Option<&TestRun> {
+        match id {
+            Some(id) => self.runs.iter().find(|run| run.id == id),
+            None => self.runs.last(),
+        }
+    }
+
+    /// Removes all but the `keep` most recent runs, along with their artifacts.
+    ///
+    /// Returns the ids of the removed runs.
+    pub fn gc(&mut self, dir: &Path, keep: usize) -> Result<Vec<String>> {
+        let remove = self.runs.len().saturating_sub(keep);
+        let removed = self.runs.drain(..remove).map(|run| run.id).collect::<Vec<_>>();
+        for id in &removed {
+            let run_dir = dir.join(id);
+            if run_dir.exists() {
+                fs::remove_dir_all(&run_dir)?;
+            }
+        }
+        Ok(removed)
+    }
+
+    /// Reads the artifact of a test of the given run.
+    pub fn read_artifact(
+        &self,
+        dir: &Path,
+        run: &TestRun,
+        entry: &TestArtifactEntry,
+    ) -> Result<TestArtifact> {
+        Ok(fs::read_json_file(&dir.join(&run.id).join(&entry.path))?)
+    }
+}
+
+/// Writes the artifacts of a test run.
+#[derive(Debug)]
+pub struct TestArtifactsWriter {
+    dir: PathBuf,
+    run: TestRun,
+}
+
+impl TestArtifactsWriter {
+    /// Starts a new run in the given test artifacts directory.
+    pub fn new(dir: PathBuf) -> Self {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+        let run = TestRun {
+            id: format!("run-{}", now.as_millis()),
+            timestamp: now.as_secs(),
+            tests: vec![],
+        };
+        Self { dir, run }
+    }
+
+    /// Writes the artifact of a test.
+    pub fn write(&mut self, artifact: &TestArtifact) -> Result<()> {
+        let (path, name) = artifact.contract.rsplit_once(':').unwrap_or(("", &artifact.contract));
+        let relative = Path::new(path).join(name).join(format!("{}.json", artifact.test));
+
+        let full = self.dir.join(&self.run.id).join(&relative);
+        fs::create_dir_all(full.parent().expect("has parent"))?;
+        fs::write_json_file(&full, artifact)?;
+
+        self.run.tests.push(TestArtifactEntry {
+            contract: artifact.contract.clone(),
+            test: artifact.test.clone(),
+            status: artifact.status,
+            path: relative,
+        });
+        Ok(())
+    }
+
+    /// Adds the run to the index, and removes the artifacts of runs beyond `retention`.
+    pub fn finish(self, retention: usize) -> Result<TestRun> {
+        let mut index = TestArtifactsIndex::read(&self.dir)?;
+        index.runs.push(self.run.clone());
+        index.gc(&self.dir, retention.max(1))?;
+        index.write(&self.dir)?;
+        Ok(self.run)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(contract: &str, test: &str) -> TestArtifactEntry {
+        TestArtifactEntry {
+            contract: contract.to_string(),
+            test: test.to_string(),
+            status: TestStatus::Success,
+            path: PathBuf::new(),
+        }
+    }
+
+    #[test]
+    fn can_match_entries() {
+        let entry = entry("test/Counter.t.sol:CounterTest", "testIncrement(uint256)");
+        assert!(entry.matches("testIncrement"));
+        assert!(entry.matches("testIncrement(uint256)"));
+        assert!(entry.matches("CounterTest::testIncrement"));
+        assert!(entry.matches("test/Counter.t.sol:CounterTest::testIncrement"));
+        assert!(!entry.matches("OtherTest::testIncrement"));
+        assert!(!entry.matches("testIncr"));
+    }
+
+    #[test]
+    fn can_gc_runs() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut index = TestArtifactsIndex::default();
+        for i in 0..3 {
+            let id = format!("run-{i}");
+            std::fs::create_dir_all(dir.path().join(&id)).unwrap();
+            index.runs.push(TestRun { id, timestamp: i, tests: vec![] });
+        }
+
+        let removed = index.gc(dir.path(), 2).unwrap();
+        assert_eq!(removed, vec!["run-0".to_string()]);
+        assert!(!dir.path().join("run-0").exists());
+        assert!(dir.path().join("run-1").exists());
+        assert_eq!(index.run(None).unwrap().id, "run-2");
+    }
+}
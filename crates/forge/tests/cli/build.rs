@@ -77,3 +77,18 @@ contract ValidContract {}
 
     cmd.args(["build"]).assert_success();
 });
+
+// tests that `--porcelain` reports the compilation as events on stderr, and `--quiet` hides it
+forgetest_init!(can_report_porcelain_build_progress, |prj, cmd| {
+    cmd.args(["build", "--force", "--porcelain"]);
+    let output = cmd.unchecked_output();
+    assert!(output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.lines().any(|line| line.starts_with("compile\tstart\t")), "{stderr}");
+    assert!(stderr.lines().any(|line| line.starts_with("compile\tfinish\t")), "{stderr}");
+
+    cmd.forge_fuse().args(["build", "--force", "--quiet"]);
+    let stdout = cmd.stdout_lossy();
+    assert!(!stdout.contains("Compiling"), "\n{stdout}");
+    assert!(stdout.contains("Compiler run successful"), "\n{stdout}");
+});
@@ -744,6 +744,90 @@ contract ATest is DSTest {
     let _ = cmd.output();
 });
 
+// test that `forge snapshot` can write JSON snapshots and fail on gas regressions
+forgetest!(can_diff_json_snapshot, |prj, cmd| {
+    prj.insert_ds_test();
+
+    prj.add_source(
+        "ATest.t.sol",
+        r#"
+import "./test.sol";
+contract Counter {
+    uint256 public number;
+    function increment() public {
+        number++;
+    }
+}
+contract ATest is DSTest {
+    Counter counter = new Counter();
+    function testIncrement() public {
+        counter.increment();
+    }
+}
+   "#,
+    )
+    .unwrap();
+
+    cmd.args(["snapshot", "--format", "json"]).assert_success();
+    let snapshot: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(prj.root().join(".gas-snapshot")).unwrap())
+            .unwrap();
+    let entry = &snapshot[0];
+    assert_eq!(entry["contract_name"], "ATest");
+    assert_eq!(entry["signature"], "testIncrement()");
+    assert_eq!(entry["kind"], "unit");
+    let increment = entry["functions"]["Counter::increment()"].as_u64().unwrap();
+    assert!(increment > 0);
+
+    cmd.forge_fuse().args(["snapshot", "--diff", "--threshold", "0"]).assert_success();
+
+    // pretend the functions used less gas when the snapshot was taken
+    let mut snapshot = snapshot;
+    snapshot[0]["gas"] = 1000.into();
+    snapshot[0]["functions"]["Counter::increment()"] = (increment / 2).into();
+    fs::write(prj.root().join(".gas-snapshot"), snapshot.to_string()).unwrap();
+
+    cmd.forge_fuse().args(["snapshot", "--diff", "--threshold", "10"]);
+    let output = cmd.unchecked_output();
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Gas regressions exceeding 10%"), "{stderr}");
+    assert!(stderr.contains("testIncrement(): Counter::increment()"), "{stderr}");
+});
+
+// test that `forge snapshot --code` records bytecode hashes and fails on bytecode changes
+forgetest!(can_check_code_snapshot, |prj, cmd| {
+    let counter = r#"
+contract Counter {
+    uint256 public number;
+    function increment() public {
+        number++;
+    }
+}
+   "#;
+    prj.add_source("Counter.sol", counter).unwrap();
+
+    cmd.args(["snapshot", "--code"]).assert_success();
+    let snapshot: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(prj.root().join(".code-snapshot")).unwrap())
+            .unwrap();
+    let entry = &snapshot["src/Counter.sol:Counter"];
+    assert!(entry["runtime"].is_string());
+    assert!(entry["sources"]["src/Counter.sol"].is_string());
+
+    // comments only change the metadata
+    prj.add_source("Counter.sol", &format!("// comment\n{counter}")).unwrap();
+    cmd.forge_fuse().args(["snapshot", "--code", "--check"]).assert_success();
+
+    prj.add_source("Counter.sol", &counter.replace("number++", "number += 2")).unwrap();
+    cmd.forge_fuse().args(["snapshot", "--code", "--check"]);
+    let output = cmd.unchecked_output();
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("changed src/Counter.sol:Counter (runtime code)"), "{stderr}");
+    assert!(stderr.contains("changed source src/Counter.sol"), "{stderr}");
+});
+
 // test that `forge build` does not print `(with warnings)` if file path is ignored
 forgetest!(can_compile_without_warnings_ignored_file_paths, |prj, cmd| {
     // Ignoring path and setting empty error_codes as default would set would set some error codes
@@ -1597,6 +1681,157 @@ forgetest_init!(can_bind, |_prj, cmd| {
     cmd.assert_non_empty_stdout();
 });
 
+// checks forge bind-json generates JSON codecs that round-trip the project's structs
+forgetest!(can_bind_json, |prj, cmd| {
+    prj.insert_ds_test();
+    prj.add_source(
+        "Structs.sol",
+        r#"
+contract Registry {
+    enum Kind { A, B }
+
+    struct Entry {
+        address owner;
+        Kind kind;
+        uint256[2] values;
+    }
+}
+
+struct Config {
+    string name;
+    Registry.Entry[] entries;
+}
+"#,
+    )
+    .unwrap();
+
+    cmd.arg("bind-json");
+    cmd.assert_non_empty_stdout();
+    let bindings = fs::read_to_string(prj.root().join("utils/JsonBindings.sol")).unwrap();
+    assert!(bindings.contains(
+        r#"schema_Config = "Config(string name,Entry[] entries)Entry(address owner,uint8 kind,uint256[2] values)";"#
+    ));
+    assert!(bindings.contains("function deserializeRegistry_EntryArray("));
+
+    prj.add_source(
+        "BindingsTest.sol",
+        r#"
+import "./test.sol";
+import {Config, Registry} from "src/Structs.sol";
+import {JsonBindings} from "utils/JsonBindings.sol";
+
+contract BindingsTest is DSTest {
+    using JsonBindings for *;
+
+    function testRoundTrip() public {
+        Registry.Entry[] memory entries = new Registry.Entry[](1);
+        entries[0] = Registry.Entry(address(1), Registry.Kind.B, [uint256(2), 3]);
+        Config memory config = Config("foo", entries);
+
+        Config memory decoded = JsonBindings.deserializeConfig(config.serialize());
+        assertEq(decoded.name, "foo");
+        assertEq(decoded.entries[0].owner, address(1));
+        assertEq(uint8(decoded.entries[0].kind), 1);
+        assertEq(decoded.entries[0].values[1], 3);
+    }
+}
+"#,
+    )
+    .unwrap();
+
+    cmd.forge_fuse().args(["test", "--mc", "BindingsTest"]).assert_success();
+
+    // the bindings don't include the test's structs, so they're still up to date
+    cmd.forge_fuse().args(["bind-json", "--check"]).assert_success();
+});
+
+forgetest!(can_generate_eip712, |prj, cmd| {
+    prj.insert_ds_test();
+    prj.add_source(
+        "Mail.sol",
+        r#"
+struct Person {
+    string name;
+    address wallet;
+}
+
+struct Mail {
+    Person from;
+    Person to;
+    string contents;
+}
+
+contract Mailbox {
+    bytes32 constant PERSON_TYPEHASH = 0xb9d8c78acf9b987311de6c7b45bb6a9c8e1bf361fa7fd3467a2163f994c79500;
+    bytes32 constant MAIL_TYPEHASH = keccak256("Mail(address from,address to,string contents)");
+}
+"#,
+    )
+    .unwrap();
+
+    // the hardcoded mail typehash doesn't match the struct
+    cmd.args(["eip712", "--all"]);
+    let (stdout, stderr) = cmd.unchecked_output_lossy();
+    assert!(stdout
+        .contains("Mail(Person from,Person to,string contents)Person(string name,address wallet)"));
+    assert!(stdout.contains("0xa0cedeb2dc280ba39b857546d74f5549c3a1d7bdc2dd96bf881f76108e23dac2"));
+    assert!(stderr.contains("Mailbox.MAIL_TYPEHASH"), "{stderr}");
+    assert!(!stderr.contains("Mailbox.PERSON_TYPEHASH"), "{stderr}");
+
+    prj.add_source(
+        "Mail.sol",
+        r#"
+struct Person {
+    string name;
+    address wallet;
+}
+
+struct Mail {
+    Person from;
+    Person to;
+    string contents;
+}
+
+contract Mailbox {
+    bytes32 constant PERSON_TYPEHASH = 0xb9d8c78acf9b987311de6c7b45bb6a9c8e1bf361fa7fd3467a2163f994c79500;
+    bytes32 constant MAIL_TYPEHASH =
+        keccak256("Mail(Person from,Person to,string contents)Person(string name,address wallet)");
+}
+"#,
+    )
+    .unwrap();
+    cmd.forge_fuse().args(["eip712", "--all", "--out", "utils/Eip712.sol"]).assert_success();
+
+    prj.add_source(
+        "Eip712Test.sol",
+        r#"
+import "./test.sol";
+import {Mail, Person} from "src/Mail.sol";
+import {Eip712} from "utils/Eip712.sol";
+
+contract Eip712Test is DSTest {
+    function testHashTypedData() public {
+        Person memory cow = Person("Cow", 0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826);
+        Person memory bob = Person("Bob", 0xbBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB);
+        Mail memory mail = Mail(cow, bob, "Hello, Bob!");
+
+        bytes32 separator =
+            Eip712.domainSeparator("Ether Mail", "1", 1, 0xCcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC);
+        assertEq(separator, 0xf2cee375fa42b42143804025fc449deafd50cc031ca257e0b194a650a912090f);
+        assertEq(Eip712.hash(mail), 0xc52c0ee5d84264471806290a3f2c4cecfc5490626bf912d01f240d7a274b371e);
+        assertEq(
+            Eip712.hashTypedData(separator, Eip712.hash(mail)),
+            0xbe609aee343fb3c4b28e1df9e632fca64fcfaede20f02e86244efddf30957bd2
+        );
+    }
+}
+"#,
+    )
+    .unwrap();
+
+    cmd.forge_fuse().args(["test", "--mc", "Eip712Test"]).assert_success();
+});
+
 // checks missing dependencies are auto installed
 forgetest_init!(can_install_missing_deps_test, |prj, cmd| {
     // wipe forge-std
@@ -1777,3 +2012,489 @@ forgetest_init!(can_inspect_counter_pretty, |prj, cmd| {
 }"
     );
 });
+
+// checks that `forge audit-prep` reports on the template project
+forgetest_init!(can_generate_audit_report, |prj, cmd| {
+    prj.add_source(
+        "Vault.sol",
+        r#"
+pragma solidity ^0.8.0;
+
+interface IERC20 {
+    function transfer(address to, uint256 amount) external returns (bool);
+}
+
+contract Vault {
+    function withdraw(IERC20 token, uint256 amount) external {
+        token.transfer(msg.sender, amount);
+    }
+
+    function sweep(address payable to) external {
+        // to.transfer(address(this).balance);
+        (bool ok,) = to.call{value: address(this).balance}("");
+        require(ok);
+    }
+}
+"#,
+    )
+    .unwrap();
+
+    cmd.args(["audit-prep"]);
+    let out = cmd.stdout_lossy();
+    for section in ["## Contract sizes", "## Selectors", "## Storage layouts", "## Licenses"] {
+        assert!(out.contains(section), "missing `{section}`:\n{out}");
+    }
+    assert!(out.contains("src/Counter.sol:Counter"));
+    assert!(out.contains("increment()"));
+    assert!(out.contains("run `forge coverage --report lcov` first"));
+
+    cmd.forge_fuse().args(["audit-prep", "--json"]);
+    let report: serde_json::Value = serde_json::from_str(&cmd.stdout_lossy()).unwrap();
+    let slots = &report["storage_layouts"]["src/Counter.sol:Counter"];
+    assert_eq!(slots[0]["label"], "number");
+    let unlicensed = report["licenses"]["UNLICENSED"].as_array().unwrap();
+    assert!(unlicensed.contains(&"src/Counter.sol".into()));
+    // Token transfers and commented out code are not low-level calls.
+    assert_eq!(
+        report["external_calls"]["low_level_calls"],
+        serde_json::json!([{ "location": "src/Vault.sol:15", "kind": "call" }])
+    );
+});
+
+// checks that `forge call-graph` lists the external calls of the project
+forgetest_init!(can_list_external_calls, |prj, cmd| {
+    prj.add_source(
+        "Vault.sol",
+        r#"
+pragma solidity ^0.8.0;
+
+interface IERC20 {
+    function transfer(address to, uint256 amount) external returns (bool);
+}
+
+contract Vault {
+    modifier nonReentrant() {
+        _;
+    }
+
+    function withdraw(IERC20 token, uint256 amount) external nonReentrant {
+        token.transfer(msg.sender, amount);
+    }
+
+    function sweep(address payable to) external {
+        (bool ok,) = to.call{value: address(this).balance}("");
+        require(ok);
+    }
+}
+"#,
+    )
+    .unwrap();
+
+    cmd.args(["call-graph"]);
+    let out = cmd.stdout_lossy();
+    let row = |caller: &str| {
+        let line = out.lines().find(|line| line.contains(caller)).unwrap();
+        line.split('|').map(str::trim).filter(|cell| !cell.is_empty()).collect::<Vec<_>>()
+    };
+    assert_eq!(
+        row("Vault.withdraw"),
+        ["src/Vault.sol:14", "Vault.withdraw", "IERC20", "transfer", "external", "yes"]
+    );
+    assert_eq!(
+        row("Vault.sweep"),
+        ["src/Vault.sol:18", "Vault.sweep", "address", "call", "call", "yes"]
+    );
+
+    cmd.forge_fuse().args(["call-graph", "--json"]);
+    let sites: serde_json::Value = serde_json::from_str(&cmd.stdout_lossy()).unwrap();
+    assert_eq!(sites.as_array().unwrap().len(), 2);
+    assert_eq!(sites[0]["function"], "sweep");
+    assert_eq!(sites[0]["payable"], true);
+    assert_eq!(sites[0]["guarded"], false);
+    assert_eq!(sites[1]["payable"], false);
+    assert_eq!(sites[1]["guarded"], true);
+
+    cmd.forge_fuse().args(["call-graph", "--graph-format", "dot"]);
+    let out = cmd.stdout_lossy();
+    assert!(out.contains("\"Vault\" -> \"IERC20\" [label=\"transfer\"];"), "{out}");
+});
+
+// checks that `forge generate handlers` scaffolds a handler and an invariant test that pass
+forgetest_init!(can_generate_invariant_handlers, |prj, cmd| {
+    cmd.args(["generate", "handlers", "Counter", "--actors", "2"]);
+    let out = cmd.stdout_lossy();
+    assert!(out.contains("Generated handler"), "{out}");
+    assert!(out.contains("Generated invariant test"), "{out}");
+
+    let handler = fs::read_to_string(prj.root().join("test/handlers/CounterHandler.sol")).unwrap();
+    assert!(handler.contains("import {Counter} from \"src/Counter.sol\";"));
+    assert!(handler.contains("newNumber = bound(newNumber, 0, type(uint256).max);"));
+    assert!(handler.contains("target.increment();"));
+    assert!(prj.root().join("test/Counter.invariant.t.sol").exists());
+
+    cmd.forge_fuse().args(["test", "--mc", "CounterInvariantTest"]);
+    let out = cmd.stdout_lossy();
+    assert!(out.contains("[PASS] invariant_example()"), "{out}");
+
+    // The invariant test is only scaffolded once.
+    cmd.forge_fuse().args(["generate", "handlers", "src/Counter.sol:Counter"]);
+    assert!(cmd.stdout_lossy().contains("already exists"));
+});
+
+forgetest_init!(can_analyze_callgraph, |prj, cmd| {
+    cmd.args(["analyze", "callgraph", "--mt", "test_Increment"]);
+    let out = cmd.stdout_lossy();
+    assert!(out.contains("digraph callgraph {"), "{out}");
+    assert!(out.contains("\"CounterTest\" -> \"Counter\""), "{out}");
+    assert!(out.contains("increment()"), "{out}");
+    assert!(out.contains("Storage dependencies of Counter:"), "{out}");
+    assert!(out.contains("| increment() | RW  |"), "{out}");
+    assert!(out.contains("| number()    | R   |"), "{out}");
+
+    cmd.forge_fuse().args([
+        "analyze",
+        "callgraph",
+        "--mt",
+        "test_Increment",
+        "--graph-format",
+        "mermaid",
+        "--output-dir",
+        "analysis",
+    ]);
+    cmd.assert_non_empty_stdout();
+    let graph = fs::read_to_string(prj.root().join("analysis/callgraph.mmd")).unwrap();
+    assert!(graph.starts_with("flowchart LR"), "{graph}");
+    let storage = fs::read_to_string(prj.root().join("analysis/storage.json")).unwrap();
+    let storage: serde_json::Value = serde_json::from_str(&storage).unwrap();
+    assert_eq!(storage["Counter"]["increment()"]["0x0"], "RW");
+});
+
+// checks that `forge fmt --staged` only formats the files staged in git
+forgetest!(can_fmt_staged_files, |prj, cmd| {
+    cmd.git_init();
+
+    let unformatted = "contract Staged {uint256 x;}\n";
+    let staged = prj.root().join("src/Staged.sol");
+    let unstaged = prj.root().join("src/Unstaged.sol");
+    fs::create_dir_all(prj.root().join("src")).unwrap();
+    fs::write(&staged, unformatted).unwrap();
+    fs::write(&unstaged, unformatted).unwrap();
+    cmd.cmd_in_current_dir("git").args(["add", "src/Staged.sol"]).output().unwrap();
+
+    cmd.forge_fuse().args(["fmt", "--check", "--staged"]);
+    cmd.assert_err();
+
+    cmd.forge_fuse().args(["fmt", "--staged"]);
+    cmd.assert_success();
+    let formatted = fs::read_to_string(&staged).unwrap();
+    assert_ne!(formatted, unformatted);
+    assert_eq!(fs::read_to_string(&unstaged).unwrap(), unformatted);
+
+    let show = |cmd: &TestCommand| {
+        let output = cmd.cmd_in_current_dir("git").args(["show", ":src/Staged.sol"]).output();
+        String::from_utf8(output.unwrap().stdout).unwrap()
+    };
+    assert_eq!(show(&cmd), formatted);
+
+    // the staged content is formatted, without touching unstaged changes
+    fs::write(&staged, unformatted).unwrap();
+    cmd.cmd_in_current_dir("git").args(["add", "src/Staged.sol"]).output().unwrap();
+    let modified = "contract Staged {uint256 y;}\n";
+    fs::write(&staged, modified).unwrap();
+
+    cmd.forge_fuse().args(["fmt", "--staged"]);
+    cmd.assert_success();
+    assert_eq!(show(&cmd), formatted);
+    assert_eq!(fs::read_to_string(&staged).unwrap(), modified);
+});
+
+// checks that `forge fmt` caches the formatted files
+forgetest!(can_cache_formatted_files, |prj, cmd| {
+    let source = prj.root().join("src/Cached.sol");
+    fs::create_dir_all(prj.root().join("src")).unwrap();
+    fs::write(&source, "contract Cached {uint256 x;}\n").unwrap();
+
+    cmd.args(["fmt"]);
+    cmd.assert_success();
+    let formatted = fs::read_to_string(&source).unwrap();
+
+    let cache = fs::read_to_string(prj.root().join("cache/fmt-cache.json")).unwrap();
+    let cache: serde_json::Value = serde_json::from_str(&cache).unwrap();
+    assert_eq!(cache["files"].as_object().unwrap().len(), 1);
+    assert!(cache["files"]["src/Cached.sol"].is_string());
+
+    cmd.forge_fuse().args(["fmt", "--check"]);
+    cmd.assert_success();
+    assert_eq!(fs::read_to_string(&source).unwrap(), formatted);
+
+    // modified files are checked again
+    fs::write(&source, "contract Cached {uint256 y;}\n").unwrap();
+    cmd.forge_fuse().args(["fmt", "--check"]);
+    cmd.assert_err();
+});
+
+// checks that `forge selectors cache` builds the local signature database
+forgetest_init!(can_cache_selectors, |prj, cmd| {
+    let dump = prj.root().join("signatures.txt");
+    fs::write(&dump, "function approve(address,uint256)\nerror Unauthorized()\n").unwrap();
+
+    cmd.args(["selectors", "cache", "--import"]).arg(&dump);
+    let out = cmd.stdout_lossy();
+    assert!(out.contains("Imported 2 signatures"), "{out}");
+
+    let db = fs::read_to_string(prj.root().join("cache/signatures.json")).unwrap();
+    let db: serde_json::Value = serde_json::from_str(&db).unwrap();
+    assert_eq!(db["functions"]["0xd09de08a"][0], "increment()");
+    assert_eq!(db["functions"]["0x095ea7b3"][0], "approve(address,uint256)");
+    assert_eq!(db["errors"]["0x82b42900"][0], "Unauthorized()");
+});
+
+// checks that `forge selectors download` adds the signatures of a self-hosted registry to the local
+// signature database
+forgetest_init!(can_download_selectors, |prj, cmd| {
+    cmd.args(["selectors", "download"]);
+    let err = cmd.stderr_lossy();
+    assert!(err.contains("OpenChain does not support downloading signatures"), "{err}");
+
+    // serves a single `/export` request
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let registry = format!("http://{}/signature-database/v1/", listener.local_addr().unwrap());
+    let server = std::thread::spawn(move || {
+        use std::io::{Read, Write};
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut request = [0; 1024];
+        let len = stream.read(&mut request).unwrap();
+        let body = "function approve(address,uint256)\nerror Unauthorized()\n";
+        write!(stream, "HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n{body}", body.len()).unwrap();
+        String::from_utf8_lossy(&request[..len]).into_owned()
+    });
+
+    prj.write_config(Config { selectors_registry: Some(registry), ..Default::default() });
+    cmd.forge_fuse().args(["selectors", "download"]);
+    let out = cmd.stdout_lossy();
+    assert!(out.contains("Downloaded 2 signatures"), "{out}");
+    assert!(server.join().unwrap().starts_with("GET /signature-database/v1/export "));
+
+    let db = fs::read_to_string(prj.root().join("cache/signatures.json")).unwrap();
+    let db: serde_json::Value = serde_json::from_str(&db).unwrap();
+    assert_eq!(db["functions"]["0x095ea7b3"][0], "approve(address,uint256)");
+    assert_eq!(db["errors"]["0x82b42900"][0], "Unauthorized()");
+});
+
+// checks that `forge verify-storage-layout` rejects layout changes and writes outside the layout
+forgetest_init!(can_verify_storage_layout, |prj, cmd| {
+    prj.add_source(
+        "Upgrades.sol",
+        r#"
+contract V1 {
+    address owner;
+    uint256 number;
+    uint256[48] __gap;
+}
+
+contract V2 {
+    address owner;
+    uint256 number;
+    uint256 total;
+    uint256[47] __gap;
+
+    function poke() external {
+        total = 1;
+        assembly {
+            sstore(100, 1)
+        }
+    }
+}
+
+contract V3 {
+    uint256 number;
+    address owner;
+}
+"#,
+    )
+    .unwrap();
+    prj.add_test(
+        "Upgrades.t.sol",
+        r#"
+import {Test} from "forge-std/Test.sol";
+import {V2} from "src/Upgrades.sol";
+
+contract UpgradesTest is Test {
+    function test_Poke() public {
+        new V2().poke();
+    }
+}
+"#,
+    )
+    .unwrap();
+
+    cmd.args(["verify-storage-layout", "V1", "V2", "--no-tests"]);
+    let out = cmd.stdout_lossy();
+    assert!(out.contains("Storage layouts are compatible"), "{out}");
+
+    cmd.forge_fuse().args(["verify-storage-layout", "V1", "V3", "--no-tests"]);
+    let (out, _) = cmd.unchecked_output_lossy();
+    assert!(out.contains("Storage layouts are incompatible"), "{out}");
+    assert!(out.contains("V1.owner"), "{out}");
+
+    cmd.forge_fuse().args(["verify-storage-layout", "V1", "V2", "--mt", "test_Poke", "--json"]);
+    let (out, _) = cmd.unchecked_output_lossy();
+    let report: serde_json::Value = serde_json::from_str(&out).unwrap();
+    assert_eq!(report["issues"], serde_json::json!([]));
+    let reason = report["violations"][0]["reason"].as_str().unwrap();
+    assert!(reason.contains("storage write to slot 0x64"), "{reason}");
+});
+
+forgetest_init!(can_complete_candidates, |prj, cmd| {
+    cmd.args(["build"]).assert_success();
+
+    cmd.forge_fuse().args(["complete-candidates", "test-contracts"]);
+    let out = cmd.stdout_lossy();
+    assert!(out.lines().any(|line| line == "CounterTest"), "{out}");
+
+    cmd.forge_fuse().args(["complete-candidates", "tests"]);
+    let out = cmd.stdout_lossy();
+    assert!(out.lines().any(|line| line == "test_Increment"), "{out}");
+
+    cmd.forge_fuse().args(["completions", "bash"]);
+    let out = cmd.stdout_lossy();
+    assert!(out.contains("forge complete-candidates"), "{out}");
+});
+
+forgetest_init!(can_record_contract_tests, |prj, cmd| {
+    prj.add_source(
+        "Consumer.sol",
+        r#"
+interface IOracle {
+    function latestAnswer() external view returns (int256);
+}
+
+contract Consumer {
+    IOracle constant ORACLE = IOracle(0x5f4eC3Df9cbd43714FE2740f5E3616155c5b8419);
+
+    function price() external view returns (int256) {
+        return ORACLE.latestAnswer();
+    }
+}
+"#,
+    )
+    .unwrap();
+    prj.add_test(
+        "Consumer.t.sol",
+        r#"
+import {Test} from "forge-std/Test.sol";
+import {Consumer, IOracle} from "src/Consumer.sol";
+
+contract ConsumerTest is Test {
+    function test_Price() public {
+        Consumer consumer = new Consumer();
+        vm.mockCall(
+            0x5f4eC3Df9cbd43714FE2740f5E3616155c5b8419,
+            abi.encodeCall(IOracle.latestAnswer, ()),
+            abi.encode(int256(2000))
+        );
+        assertEq(consumer.price(), 2000);
+    }
+}
+"#,
+    )
+    .unwrap();
+
+    cmd.args(["contract-test", "record", "--mc", "ConsumerTest"]);
+    let out = cmd.stdout_lossy();
+    assert!(out.contains("Recorded 1 external calls of 1 tests"), "{out}");
+
+    let file: serde_json::Value = serde_json::from_str(
+        &std::fs::read_to_string(prj.root().join("contract-tests.json")).unwrap(),
+    )
+    .unwrap();
+    let call = &file["calls"][0];
+    assert_eq!(call["test"], "ConsumerTest::test_Price");
+    assert_eq!(
+        call["target"].as_str().unwrap().to_lowercase(),
+        "0x5f4ec3df9cbd43714fe2740f5e3616155c5b8419"
+    );
+    assert_eq!(call["calldata"], "0x50d25bcd");
+    assert_eq!(call["success"], true);
+
+    cmd.forge_fuse().args(["contract-test", "verify"]);
+    let (_, err) = cmd.unchecked_output_lossy();
+    assert!(err.contains("requires a fork"), "{err}");
+});
+
+forgetest_init!(can_report_dependencies, |prj, cmd| {
+    std::fs::write(
+        prj.root().join("audits.toml"),
+        "[[audits]]\ndependency = \"forge-std\"\nauditor = \"Acme Security\"\n",
+    )
+    .unwrap();
+
+    cmd.args(["deps", "report", "--audits", "audits.toml"]);
+    let out = cmd.stdout_lossy();
+    assert!(out.contains("lib/forge-std"), "{out}");
+    assert!(out.contains("Acme Security"), "{out}");
+
+    cmd.forge_fuse().args(["deps", "report", "--format", "cyclonedx"]);
+    let sbom: serde_json::Value = serde_json::from_str(&cmd.stdout_lossy()).unwrap();
+    assert_eq!(sbom["bomFormat"], "CycloneDX");
+    assert_eq!(sbom["components"][0]["name"], "forge-std");
+    assert!(sbom["components"][0]["licenses"].is_array());
+});
+
+forgetest!(can_migrate_hardhat_project, |prj, cmd| {
+    prj.wipe();
+    let root = prj.root();
+    fs::write(
+        root.join("hardhat.config.js"),
+        r#"
+require("@nomicfoundation/hardhat-toolbox");
+
+module.exports = {
+  solidity: { version: "0.8.24", settings: { optimizer: { enabled: true, runs: 1000 } } },
+  networks: { sepolia: { url: process.env.SEPOLIA_RPC_URL || "", accounts: [] } },
+  gasReporter: { enabled: true },
+};
+"#,
+    )
+    .unwrap();
+    fs::create_dir_all(root.join("contracts")).unwrap();
+    fs::write(
+        root.join("contracts/Token.sol"),
+        r#"// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.24;
+
+import "../node_modules/@openzeppelin/contracts/token/ERC20/ERC20.sol";
+
+contract Token {}
+"#,
+    )
+    .unwrap();
+    fs::create_dir_all(root.join("node_modules/@openzeppelin/contracts")).unwrap();
+
+    cmd.arg("migrate");
+    let out = cmd.stdout_lossy();
+    assert!(out.contains("Migrated Hardhat project"), "{out}");
+    assert!(out.contains("`gasReporter` has no Foundry equivalent"), "{out}");
+    assert!(out.contains("the accounts of `networks.sepolia` are not converted"), "{out}");
+
+    let config = read_string(root.join(Config::FILE_NAME));
+    assert!(config.contains(r#"src = "contracts""#), "{config}");
+    assert!(config.contains(r#"solc_version = "0.8.24""#), "{config}");
+    assert!(config.contains(r#"optimizer_runs = 1000"#), "{config}");
+    assert!(config.contains(r#"sepolia = "${SEPOLIA_RPC_URL}""#), "{config}");
+    assert!(
+        config.contains("@openzeppelin/contracts/=node_modules/@openzeppelin/contracts/"),
+        "{config}"
+    );
+
+    let token = read_string(root.join("contracts/Token.sol"));
+    assert!(token.contains(r#"import "@openzeppelin/contracts/token/ERC20/ERC20.sol";"#));
+    let stub = read_string(root.join("test/Token.t.sol"));
+    assert!(stub.contains("pragma solidity ^0.8.24;"), "{stub}");
+    assert!(stub.contains(r#"import {Token} from "contracts/Token.sol";"#), "{stub}");
+
+    // An existing config is not overwritten.
+    cmd.forge_fuse().arg("migrate");
+    let (_, err) = cmd.unchecked_output_lossy();
+    assert!(err.contains("pass --force to overwrite it"), "{err}");
+});
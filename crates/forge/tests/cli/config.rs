@@ -97,6 +97,12 @@ forgetest!(can_extract_config_values, |prj, cmd| {
         block_gas_limit: Some(100u64.into()),
         disable_block_gas_limit: false,
         memory_limit: 1 << 27,
+        max_call_depth: None,
+        max_events: None,
+        max_state_writes: None,
+        max_return_data_size: None,
+        write_allowlist: None,
+        read_denylist: vec![],
         eth_rpc_url: Some("localhost".to_string()),
         eth_rpc_jwt: None,
         etherscan_api_key: None,
@@ -109,6 +115,9 @@ forgetest!(can_extract_config_values, |prj, cmd| {
         ignored_error_codes: vec![],
         ignored_file_paths: vec![],
         deny_warnings: false,
+        deny_warnings_from: vec![],
+        compiler_jobs: None,
+        compiler_memory_limit: None,
         via_ir: true,
         ast: false,
         rpc_storage_caching: StorageCachingConfig {
@@ -117,6 +126,7 @@ forgetest!(can_extract_config_values, |prj, cmd| {
         },
         no_storage_caching: true,
         no_rpc_rate_limit: true,
+        rpc_budget: Some(100_000),
         use_literal_content: false,
         bytecode_hash: Default::default(),
         cbor_metadata: true,
@@ -125,22 +135,33 @@ forgetest!(can_extract_config_values, |prj, cmd| {
         allow_paths: vec![],
         include_paths: vec![],
         rpc_endpoints: Default::default(),
+        forks: Default::default(),
         build_info: false,
         build_info_path: None,
+        selectors_registry: None,
+        sync_selectors: false,
         fmt: Default::default(),
         doc: Default::default(),
         fs_permissions: Default::default(),
+        cheatcode_permissions: vec![],
         labels: Default::default(),
+        test_overrides: Default::default(),
+        precompiles: Default::default(),
         prague: true,
         isolate: true,
         unchecked_cheatcode_artifacts: false,
         create2_library_salt: Config::DEFAULT_CREATE2_LIBRARY_SALT,
         vyper: Default::default(),
+        tenderly: Default::default(),
         skip: vec![],
         dependencies: Default::default(),
         warnings: vec![],
         assertions_revert: true,
         legacy_assertions: false,
+        historical_block_hashes: false,
+        deterministic: false,
+        test_artifacts: false,
+        test_artifacts_retention: 5,
         _non_exhaustive: (),
     };
     prj.write_config(input.clone());
@@ -694,3 +715,48 @@ forgetest!(normalize_config_evm_version, |_prj, cmd| {
     let config: Config = serde_json::from_str(&output).unwrap();
     assert_eq!(config.evm_version, EvmVersion::Istanbul);
 });
+
+// checks that `forge config --validate` reports every issue of the config
+forgetest!(can_validate_config, |prj, cmd| {
+    fs::write(
+        prj.root().join(Config::FILE_NAME),
+        r#"
+[profile.default]
+optimizer_run = 200
+via_ir = "yes"
+solc_version = "0.8.26"
+
+[fuzz]
+runs = 100
+"#,
+    )
+    .unwrap();
+
+    cmd.args(["config", "--validate"]);
+    let (stdout, stderr) = cmd.unchecked_output_lossy();
+    assert!(
+        stdout.contains(
+            "unknown key `profile.default.optimizer_run`, did you mean `optimizer_runs`?"
+        ),
+        "{stdout}"
+    );
+    assert!(stdout.contains("invalid value for `profile.default.via_ir`"), "{stdout}");
+    assert!(
+        stdout.contains("`profile.default.solc_version` is deprecated, use `solc` instead"),
+        "{stdout}"
+    );
+    assert!(stderr.contains("found 3 issues"), "{stderr}");
+
+    prj.write_config(Config::default());
+    cmd.forge_fuse().args(["config", "--validate"]);
+    assert!(cmd.stdout_lossy().contains("is valid"));
+});
+
+// checks that the exported schema describes the config options
+forgetest!(can_export_config_schema, |_prj, cmd| {
+    cmd.args(["config", "--schema"]);
+    let schema: serde_json::Value = serde_json::from_str(&cmd.stdout_lossy()).unwrap();
+    let profile = &schema["definitions"]["profile"]["properties"];
+    assert_eq!(profile["optimizer_runs"]["type"], "integer");
+    assert_eq!(profile["fuzz"]["properties"]["runs"]["default"], 256);
+});
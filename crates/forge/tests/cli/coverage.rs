@@ -76,3 +76,182 @@ contract AContractTest is DSTest {
     };
     assert!(lcov_data.lines().any(valid_line), "{lcov_data}");
 });
+
+forgetest!(merge_lcov_coverage, |prj, cmd| {
+    let a = prj.root().join("a.lcov");
+    let b = prj.root().join("b.lcov");
+    let merged = prj.root().join("merged.lcov");
+    std::fs::write(
+        &a,
+        "TN:\nSF:src/A.sol\nFN:3,A.foo\nFNDA:1,A.foo\nDA:4,1\nDA:4,1\nDA:7,0\nBRDA:7,0,0,-\nBRDA:7,0,1,1\nFNF:1\nFNH:1\nLF:2\nLH:1\nBRF:2\nBRH:1\nend_of_record\n",
+    )
+    .unwrap();
+    std::fs::write(
+        &b,
+        "TN:\nSF:src/A.sol\nFN:3,A.foo\nFNDA:2,A.foo\nDA:4,2\nDA:7,1\nBRDA:7,0,0,1\nBRDA:7,0,1,-\nFNF:1\nFNH:1\nLF:2\nLH:2\nBRF:2\nBRH:1\nend_of_record\nTN:\nSF:src/B.sol\nDA:1,0\nLF:1\nLH:0\nend_of_record\n",
+    )
+    .unwrap();
+
+    cmd.arg("coverage")
+        .arg("--merge")
+        .args([&a, &b])
+        .args(["--report", "summary", "--report", "lcov", "--report-file"])
+        .arg(&merged);
+    let stdout = cmd.stdout_lossy();
+    assert!(stdout.contains("src/A.sol"), "{stdout}");
+    assert!(stdout.contains("src/B.sol"), "{stdout}");
+
+    let merged = std::fs::read_to_string(merged).unwrap();
+    assert_eq!(
+        merged,
+        "TN:\nSF:src/A.sol\nFN:3,A.foo\nFNDA:3,A.foo\nDA:4,3\nDA:7,1\nBRDA:7,0,0,1\nBRDA:7,0,1,1\nFNF:1\nFNH:1\nLF:2\nLH:2\nBRF:2\nBRH:2\nend_of_record\nTN:\nSF:src/B.sol\nDA:1,0\nFNF:0\nFNH:0\nLF:1\nLH:0\nBRF:0\nBRH:0\nend_of_record\n"
+    );
+});
+
+forgetest!(branch_and_modifier_coverage, |prj, cmd| {
+    prj.insert_ds_test();
+    prj.add_source(
+        "AContract.sol",
+        r#"
+contract AContract {
+    uint256 public i;
+
+    modifier nonZero(uint256 x) {
+        require(x != 0, "zero");
+        _;
+    }
+
+    function foo(uint256 x) public nonZero(x) {
+        i = x;
+    }
+
+    function bar(uint256 x) public nonZero(x) {
+        if (x > 5) {
+            i = 1;
+        } else {
+            i = 2;
+        }
+    }
+}
+    "#,
+    )
+    .unwrap();
+
+    prj.add_source(
+        "AContractTest.sol",
+        r#"
+import "./test.sol";
+import {AContract} from "./AContract.sol";
+
+contract AContractTest is DSTest {
+    AContract a;
+
+    function setUp() public {
+        a = new AContract();
+    }
+
+    function testBar() public {
+        a.bar(1);
+        a.bar(10);
+    }
+}
+    "#,
+    )
+    .unwrap();
+
+    let lcov_info = prj.root().join("lcov.info");
+    cmd.arg("coverage").args(["--report", "lcov", "--report", "html", "--report-file"]);
+    cmd.arg(&lcov_info);
+    cmd.assert_success();
+
+    let lcov_data = std::fs::read_to_string(lcov_info).unwrap();
+    // The modifier is only applied through `bar`, but is hit nonetheless.
+    let re = Regex::new(r"FNDA:(\d+),AContract\.nonZero").unwrap();
+    let hits = re.captures(&lcov_data).unwrap().get(1).unwrap().as_str();
+    assert!(hits.parse::<u64>().unwrap() > 0, "{lcov_data}");
+    // Both arms of the `if` statement are hit.
+    let source = std::fs::read_to_string(prj.root().join("src/AContract.sol")).unwrap();
+    let line = source.lines().position(|line| line.contains("if (x > 5)")).unwrap() + 1;
+    let re = Regex::new(&format!(r"BRDA:{line},(\d+),(\d+),(\S+)")).unwrap();
+    let arms = re.captures_iter(&lcov_data).map(|caps| caps[3].to_string()).collect::<Vec<_>>();
+    assert_eq!(arms.len(), 2, "{lcov_data}");
+    assert!(arms.iter().all(|hits| hits != "-"), "{lcov_data}");
+
+    let index = std::fs::read_to_string(prj.root().join("coverage/index.html")).unwrap();
+    assert!(index.contains("src/AContract.sol.html"), "{index}");
+    let page = std::fs::read_to_string(prj.root().join("coverage/src/AContract.sol.html")).unwrap();
+    assert!(page.contains("modifier AContract.nonZero"), "{page}");
+});
+
+forgetest!(assertion_coverage, |prj, cmd| {
+    prj.insert_ds_test();
+    prj.add_source(
+        "AContract.sol",
+        r#"
+contract AContract {
+    uint256 public i;
+    uint256 public j;
+
+    function foo(uint256 x) public {
+        if (x > 5) {
+            i = 1;
+        } else {
+            i = 2;
+        }
+    }
+
+    function bar(uint256 x) public {
+        if (x > 5) {
+            j = 1;
+        } else {
+            j = 2;
+        }
+    }
+}
+    "#,
+    )
+    .unwrap();
+
+    prj.add_source(
+        "AContractTest.sol",
+        r#"
+import "./test.sol";
+import {AContract} from "./AContract.sol";
+
+contract AContractTest is DSTest {
+    AContract a;
+
+    function setUp() public {
+        a = new AContract();
+    }
+
+    function testFoo() public {
+        a.foo(10);
+        assertEq(a.i(), 1);
+    }
+
+    function testBar() public {
+        a.bar(10);
+    }
+}
+    "#,
+    )
+    .unwrap();
+
+    let out = cmd.args(["coverage", "--assertions"]).stdout_lossy();
+    let source = std::fs::read_to_string(prj.root().join("src/AContract.sol")).unwrap();
+    let lines = source
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| line.contains("if (x > 5)"))
+        .map(|(i, _)| i + 1)
+        .collect::<Vec<_>>();
+    // The branch of `foo` is asserted on, the one of `bar` is not. The assertion coverage is
+    // printed after the summary.
+    let row = out.lines().filter(|line| line.contains("src/AContract.sol")).last().unwrap();
+    assert!(row.contains("(1/2)"), "{out}");
+    assert!(
+        row.trim_end().trim_end_matches('|').trim_end().ends_with(&lines[1].to_string()),
+        "{out}"
+    );
+});
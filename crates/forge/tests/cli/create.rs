@@ -308,3 +308,51 @@ contract UniswapV2Swap {
     let (stdout, _) = cmd.output_lossy();
     assert!(stdout.contains("Deployed to: 0x5FbDB2315678afecb367f032d93F642f64180aa3"));
 });
+
+// tests that we can deploy through the CREATE2 deployer with a mined salt
+forgetest_async!(can_create2_with_mined_salt, |prj, cmd| {
+    foundry_test_utils::util::initialize(prj.root());
+
+    let (_api, handle) = spawn(NodeConfig::test()).await;
+    let rpc = handle.http_endpoint();
+    let wallet = handle.dev_wallets().next().unwrap();
+    let pk = hex::encode(wallet.credential().to_bytes());
+
+    // explicitly byte code hash for consistent checks
+    let config = Config { bytecode_hash: BytecodeHash::None, ..Default::default() };
+    prj.write_config(config);
+
+    cmd.forge_fuse().args([
+        "create",
+        format!("./src/{TEMPLATE_CONTRACT}.sol:{TEMPLATE_CONTRACT}").as_str(),
+        "--rpc-url",
+        rpc.as_str(),
+        "--private-key",
+        pk.as_str(),
+        "--create2",
+        "--salt-mine",
+        "0xc0",
+    ]);
+
+    let (stdout, _) = cmd.output_lossy();
+    let address = stdout
+        .lines()
+        .find_map(|line| line.strip_prefix("Deployed to: "))
+        .and_then(|address| Address::from_str(address).ok())
+        .unwrap_or_else(|| panic!("no deployed address in output:\n{stdout}"));
+    assert_eq!(address[0], 0xc0);
+    assert!(stdout.contains("Salt: 0x"));
+
+    let record = prj
+        .root()
+        .join("broadcast")
+        .join(format!("{TEMPLATE_CONTRACT}.sol"))
+        .join(handle.config().get_chain_id().to_string())
+        .join("create2-latest.json");
+    let record: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(record).unwrap()).unwrap();
+    let tx = &record["transactions"][0];
+    assert_eq!(tx["transactionType"], "CREATE2");
+    assert_eq!(tx["contractName"], TEMPLATE_CONTRACT);
+    assert_eq!(Address::from_str(tx["contractAddress"].as_str().unwrap()).unwrap(), address);
+});
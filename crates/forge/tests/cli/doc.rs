@@ -6,3 +6,41 @@ fn can_generate_solmate_docs() {
         setup_forge_remote(RemoteProject::new("transmissions11/solmate").set_build(false));
     prj.forge_command().args(["doc", "--build"]).assert_success();
 }
+
+forgetest_init!(can_export_metadata, |prj, cmd| {
+    let broadcast = prj.root().join("broadcast/Counter.s.sol/1");
+    std::fs::create_dir_all(&broadcast).unwrap();
+    std::fs::write(
+        broadcast.join("run-latest.json"),
+        r#"{
+            "chain": 1,
+            "transactions": [{
+                "transactionType": "CREATE",
+                "contractName": "Counter",
+                "contractAddress": "0x5fbdb2315678afecb367f032d93f642f64180aa3"
+            }]
+        }"#,
+    )
+    .unwrap();
+
+    cmd.args(["doc", "--export-metadata", "metadata"]);
+    cmd.assert_success();
+
+    let metadata: serde_json::Value = serde_json::from_str(
+        &std::fs::read_to_string(prj.root().join("metadata/metadata.json")).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(metadata["version"], 1);
+    let counter = &metadata["contracts"]["src/Counter.sol:Counter"];
+    assert_eq!(counter["name"], "Counter");
+    assert!(counter["abi"].as_array().unwrap().iter().any(|item| item["name"] == "increment"));
+    assert!(counter["storageLayout"]["storage"].as_array().is_some_and(|slots| slots.len() == 1));
+    assert_eq!(
+        counter["deployments"],
+        serde_json::json!([
+            { "chainId": 1, "address": "0x5FbDB2315678afecb367f032d93F642f64180aa3" }
+        ])
+    );
+    // Tests and scripts are not part of the public interface of the project.
+    assert!(metadata["contracts"].get("test/Counter.t.sol:CounterTest").is_none());
+});
@@ -14,6 +14,7 @@ mod create;
 mod debug;
 mod doc;
 mod multi_script;
+mod mutate;
 mod script;
 mod soldeer;
 mod svm;
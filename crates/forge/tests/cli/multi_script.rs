@@ -22,6 +22,37 @@ forgetest_async!(can_deploy_multi_chain_script_without_lib, |prj, cmd| {
     assert_eq!(api2.transaction_count(tester.accounts_pub[1], None).await.unwrap().to::<u32>(), 3);
 });
 
+forgetest_async!(can_deploy_interleaved_multi_chain_script, |prj, cmd| {
+    let (api1, handle1) = spawn(NodeConfig::test()).await;
+    let (api2, handle2) = spawn(NodeConfig::test().with_chain_id(Some(31338u64))).await;
+    let mut tester = ScriptTester::new_broadcast_without_endpoint(cmd, prj.root());
+
+    tester
+        .load_private_keys(&[0, 1])
+        .await
+        .add_sig("MultiChainBroadcastNoLink", "deployInterleaved(string memory,string memory)")
+        .args(&[&handle1.http_endpoint(), &handle2.http_endpoint()])
+        .broadcast(ScriptOutcome::OkBroadcast);
+
+    assert_eq!(api1.transaction_count(tester.accounts_pub[0], None).await.unwrap().to::<u32>(), 2);
+    assert_eq!(api2.transaction_count(tester.accounts_pub[0], None).await.unwrap().to::<u32>(), 1);
+    assert_eq!(api2.transaction_count(tester.accounts_pub[1], None).await.unwrap().to::<u32>(), 1);
+
+    // every chain gets its own broadcast artifact next to the multi chain one, without touching
+    // the artifacts of single chain runs
+    let root = prj.root();
+    let multi = root.join("broadcast/multi/Broadcast.t.sol-latest");
+    assert!(multi.join("deployInterleaved.json").exists());
+    assert!(!root.join("broadcast/Broadcast.t.sol").exists());
+    for (chain, num_txs) in [(31337, 2), (31338, 2)] {
+        let path = multi.join(format!("{chain}/deployInterleaved.json"));
+        let sequence: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap();
+        assert_eq!(sequence["chain"], chain);
+        assert_eq!(sequence["transactions"].as_array().unwrap().len(), num_txs);
+    }
+});
+
 forgetest_async!(can_not_deploy_multi_chain_script_with_lib, |prj, cmd| {
     let (_, handle1) = spawn(NodeConfig::test()).await;
     let (_, handle2) = spawn(NodeConfig::test()).await;
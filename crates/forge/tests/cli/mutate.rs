@@ -0,0 +1,59 @@
+//! Contains various tests for `forge mutate`.
+
+forgetest!(reports_surviving_mutants, |prj, cmd| {
+    prj.insert_ds_test();
+    prj.add_source(
+        "AContract.sol",
+        r#"
+contract AContract {
+    uint256 public i;
+    uint256 public j;
+
+    function foo(uint256 x) public {
+        i = x + 1;
+    }
+
+    function bar(uint256 x) public {
+        j = x + 1;
+    }
+}
+    "#,
+    )
+    .unwrap();
+
+    prj.add_source(
+        "AContractTest.sol",
+        r#"
+import "./test.sol";
+import {AContract} from "./AContract.sol";
+
+contract AContractTest is DSTest {
+    AContract a;
+
+    function setUp() public {
+        a = new AContract();
+    }
+
+    function testFoo() public {
+        a.foo(10);
+        assertEq(a.i(), 11);
+    }
+
+    function testBar() public {
+        a.bar(10);
+    }
+}
+    "#,
+    )
+    .unwrap();
+
+    cmd.args(["mutate", "--mutate-path", "AContract.sol"]);
+    let out = cmd.stdout_lossy();
+    assert!(out.contains("Surviving mutants:"), "{out}");
+    assert!(out.contains("  - src/AContract.sol:11: `+` -> `-`"), "{out}");
+    assert!(!out.contains("  - src/AContract.sol:7:"), "{out}");
+    assert!(out.contains("50.00%"), "{out}");
+
+    // The results are resumed, and the minimum score is enforced.
+    cmd.args(["--min-score", "100"]).assert_err();
+});
@@ -1,7 +1,7 @@
 //! Contains various tests related to `forge script`.
 
 use crate::constants::TEMPLATE_CONTRACT;
-use alloy_primitives::{hex, Address, Bytes};
+use alloy_primitives::{hex, Address, Bytes, U256};
 use anvil::{spawn, NodeConfig};
 use foundry_test_utils::{rpc, util::OutputExt, ScriptOutcome, ScriptTester};
 use regex::Regex;
@@ -192,6 +192,7 @@ contract DeployScript is Script {
         "-vvvvv",
         "--slow",
         "--broadcast",
+        "--yes",
         "--unlocked",
     ]);
 
@@ -244,6 +245,7 @@ contract DeployScript is Script {
         "-vvvvv",
         "--slow",
         "--broadcast",
+        "--yes",
         "--private-key",
         &private_key,
     ]);
@@ -349,6 +351,7 @@ contract DeployScript is Script {
         &handle.http_endpoint(),
         "-vvvvv",
         "--broadcast",
+        "--yes",
         "--slow",
         "--skip-simulation",
         "--private-key",
@@ -402,6 +405,7 @@ contract RunScript is Script {
         &handle.http_endpoint(),
         "-vvvvv",
         "--broadcast",
+        "--yes",
         "--slow",
         "--skip-simulation",
         "--gas-estimate-multiplier",
@@ -1261,6 +1265,7 @@ contract SimpleScript is Script {
         "--sender",
         format!("{dev:?}").as_str(),
         "--broadcast",
+        "--yes",
         "--unlocked",
         "--with-gas-price",
         "2000000",
@@ -1280,6 +1285,7 @@ contract SimpleScript is Script {
         "--sender",
         format!("{dev:?}").as_str(),
         "--broadcast",
+        "--yes",
         "--unlocked",
     ]);
 
@@ -1313,6 +1319,7 @@ contract SimpleScript is Script {
         "--fork-url",
         &handle.http_endpoint(),
         "--broadcast",
+        "--yes",
         "--unlocked",
     ]);
 
@@ -1349,6 +1356,7 @@ contract SimpleScript is Script {
         "--fork-url",
         &handle.http_endpoint(),
         "--broadcast",
+        "--yes",
         "--unlocked",
     ]);
 
@@ -1451,3 +1459,433 @@ forgetest_async!(can_deploy_library_create2_different_sender, |prj, cmd| {
         .assert_nonce_increment(&[(2, 2)])
         .await;
 });
+
+// Tests that calls with an attached blob are broadcasted as EIP-4844 blob transactions
+forgetest_async!(can_broadcast_blob_transaction, |prj, cmd| {
+    foundry_test_utils::util::initialize(prj.root());
+    let script = prj
+        .add_source(
+            "Foo",
+            r#"
+import "forge-std/Script.sol";
+
+interface BlobVm {
+    function attachBlob(bytes calldata data) external;
+}
+
+contract BlobReceiver {
+    bytes32 public blobHash;
+
+    function receiveBlob() external {
+        blobHash = blobhash(0);
+    }
+}
+
+contract BlobScript is Script {
+    function run() external {
+        vm.startBroadcast();
+        BlobReceiver receiver = new BlobReceiver();
+        BlobVm(address(vm)).attachBlob("hello blob");
+        receiver.receiveBlob();
+        require(receiver.blobHash() != bytes32(0), "blob hash not set");
+        vm.stopBroadcast();
+    }
+}
+   "#,
+        )
+        .unwrap();
+
+    let (_api, handle) = spawn(NodeConfig::test().silent()).await;
+    let private_key =
+        "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80".to_string();
+    cmd.set_current_dir(prj.root());
+
+    cmd.args([
+        "script",
+        &format!("{}:BlobScript", script.display()),
+        "--root",
+        prj.root().to_str().unwrap(),
+        "--fork-url",
+        &handle.http_endpoint(),
+        "--evm-version",
+        "cancun",
+        "--slow",
+        "--broadcast",
+        "--yes",
+        "--private-key",
+        &private_key,
+    ]);
+
+    let output = cmd.stdout_lossy();
+    assert!(output.contains("ONCHAIN EXECUTION COMPLETE & SUCCESSFUL"), "{output}");
+
+    let run_latest = foundry_common::fs::json_files(&prj.root().join("broadcast"))
+        .find(|path| path.ends_with("run-latest.json"))
+        .expect("no broadcast file");
+    let broadcast = std::fs::read_to_string(run_latest).unwrap();
+    assert!(broadcast.contains("blobVersionedHashes"), "{broadcast}");
+});
+
+// Tests that the gas limit, fees and type set with `txGasLimit`, `txFees` and `txType` override the
+// estimated ones for the next broadcasted transaction only
+forgetest_async!(can_override_tx_gas_and_fees, |prj, cmd| {
+    foundry_test_utils::util::initialize(prj.root());
+    let script = prj
+        .add_source(
+            "Foo",
+            r#"
+import "forge-std/Script.sol";
+
+interface TxVm {
+    function txGasLimit(uint64 gasLimit) external;
+    function txFees(uint256 gasPrice) external;
+    function txFees(uint256 maxFeePerGas, uint256 maxPriorityFeePerGas) external;
+    function txType(uint8 transactionType) external;
+}
+
+contract Counter {
+    uint256 public count;
+
+    function increment() external {
+        count++;
+    }
+}
+
+contract TxOverridesScript is Script {
+    function run() external {
+        vm.startBroadcast();
+        Counter counter = new Counter();
+        TxVm(address(vm)).txGasLimit(300000);
+        TxVm(address(vm)).txFees(3 gwei);
+        TxVm(address(vm)).txType(0);
+        counter.increment();
+        TxVm(address(vm)).txFees(5 gwei, 1 gwei);
+        counter.increment();
+        vm.stopBroadcast();
+    }
+}
+   "#,
+        )
+        .unwrap();
+
+    let (_api, handle) = spawn(NodeConfig::test().silent()).await;
+    let private_key =
+        "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80".to_string();
+    cmd.set_current_dir(prj.root());
+
+    cmd.args([
+        "script",
+        &format!("{}:TxOverridesScript", script.display()),
+        "--root",
+        prj.root().to_str().unwrap(),
+        "--fork-url",
+        &handle.http_endpoint(),
+        "--slow",
+        "--broadcast",
+        "--yes",
+        "--private-key",
+        &private_key,
+    ]);
+
+    let output = cmd.stdout_lossy();
+    assert!(output.contains("ONCHAIN EXECUTION COMPLETE & SUCCESSFUL"), "{output}");
+    assert!(output.contains("Gas limit was set in script to 300000"), "{output}");
+
+    let run_latest = foundry_common::fs::json_files(&prj.root().join("broadcast"))
+        .find(|path| path.ends_with("run-latest.json"))
+        .expect("no broadcast file");
+    let broadcast: Value = foundry_common::fs::read_json_file(&run_latest).unwrap();
+    let txs = broadcast["transactions"].as_array().unwrap();
+    assert_eq!(txs.len(), 3);
+
+    let legacy = &txs[1]["transaction"];
+    assert_eq!(legacy["gas"], "0x493e0");
+    assert_eq!(legacy["gasPrice"], "0xb2d05e00");
+    assert_eq!(legacy["type"], "0x0");
+    assert!(legacy.get("maxFeePerGas").is_none());
+
+    let eip1559 = &txs[2]["transaction"];
+    assert_eq!(eip1559["maxFeePerGas"], "0x12a05f200");
+    assert_eq!(eip1559["maxPriorityFeePerGas"], "0x3b9aca00");
+    assert_ne!(eip1559["gas"], "0x493e0");
+
+    let receipts = broadcast["receipts"].as_array().unwrap();
+    assert_eq!(receipts[1]["effectiveGasPrice"], "0xb2d05e00");
+});
+
+// Tests that broadcasting aborts before sending anything if a sender can't cover its transactions
+forgetest_async!(aborts_broadcast_on_unfunded_sender, |prj, cmd| {
+    foundry_test_utils::util::initialize(prj.root());
+    let script = prj
+        .add_source(
+            "Foo",
+            r#"
+import "forge-std/Script.sol";
+
+contract Counter {
+    uint256 public count;
+}
+
+contract DeployScript is Script {
+    function run() external {
+        vm.startBroadcast();
+        new Counter();
+        vm.stopBroadcast();
+    }
+}
+   "#,
+        )
+        .unwrap();
+
+    let (api, handle) = spawn(NodeConfig::test().silent()).await;
+    // an account without any funds
+    let private_key =
+        "0000000000000000000000000000000000000000000000000000000000000001".to_string();
+    cmd.set_current_dir(prj.root());
+
+    cmd.args([
+        "script",
+        &format!("{}:DeployScript", script.display()),
+        "--root",
+        prj.root().to_str().unwrap(),
+        "--fork-url",
+        &handle.http_endpoint(),
+        "--broadcast",
+        "--yes",
+        "--private-key",
+        &private_key,
+    ]);
+
+    let (stdout, stderr) = cmd.unchecked_output_lossy();
+    assert!(stdout.contains("Broadcast plan"), "{stdout}");
+    assert!(stdout.contains("Contracts to be created"), "{stdout}");
+    assert!(stderr.contains("deployment precondition(s) failed"), "{stderr}");
+    assert_eq!(api.block_number().unwrap(), U256::ZERO);
+});
+
+// Tests that the broadcast plan shows the calls and balance changes of the transactions, and that
+// broadcasting requires approval
+forgetest_async!(requires_approval_of_broadcast_plan, |prj, cmd| {
+    foundry_test_utils::util::initialize(prj.root());
+    let script = prj
+        .add_source(
+            "Foo",
+            r#"
+import "forge-std/Script.sol";
+
+contract Counter {
+    uint256 public count;
+
+    function increment(uint256 by) external payable {
+        count += by;
+    }
+}
+
+contract PlanScript is Script {
+    function run() external {
+        vm.startBroadcast();
+        Counter counter = new Counter();
+        counter.increment{value: 1 ether}(2);
+        vm.stopBroadcast();
+    }
+}
+   "#,
+        )
+        .unwrap();
+
+    let (api, handle) = spawn(NodeConfig::test().silent()).await;
+    let plan = prj.root().join("plan.json");
+    cmd.set_current_dir(prj.root());
+
+    cmd.args([
+        "script",
+        &format!("{}:PlanScript", script.display()),
+        "--root",
+        prj.root().to_str().unwrap(),
+        "--fork-url",
+        &handle.http_endpoint(),
+        "--broadcast",
+        "--plan-json",
+        plan.to_str().unwrap(),
+        "--private-key",
+        "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80",
+    ]);
+
+    let (stdout, stderr) = cmd.unchecked_output_lossy();
+    assert!(stdout.contains("Counter::increment(uint256) with [2]"), "{stdout}");
+    assert!(stdout.contains("Balance changes:"), "{stdout}");
+    assert!(stderr.contains("pass --yes"), "{stderr}");
+    assert_eq!(api.block_number().unwrap(), U256::ZERO);
+
+    let plan: Value = serde_json::from_str(&std::fs::read_to_string(plan).unwrap()).unwrap();
+    assert_eq!(plan[0]["transactions"].as_array().unwrap().len(), 2);
+    assert!(!plan[0]["balanceDeltas"].as_object().unwrap().is_empty());
+
+    cmd.arg("--yes");
+    let stdout = cmd.stdout_lossy();
+    assert!(stdout.contains("ONCHAIN EXECUTION COMPLETE & SUCCESSFUL"), "{stdout}");
+    assert_eq!(api.block_number().unwrap(), U256::from(2));
+});
+
+// Tests that the scripts declared in `dependsOn()` are executed before the script and that their
+// deployments are available through `getDeployment`.
+forgetest!(can_run_script_dependencies, |prj, cmd| {
+    foundry_test_utils::util::initialize(prj.root());
+    let script = prj
+        .add_script(
+            "Deploy",
+            r#"
+import "forge-std/Script.sol";
+
+interface Deployments {
+    function getDeployment(string calldata contractName) external view returns (address);
+}
+
+contract Counter {
+    uint256 public count;
+
+    function increment() external {
+        count++;
+    }
+}
+
+contract DeployCounter is Script {
+    function run() external {
+        vm.broadcast();
+        new Counter();
+    }
+}
+
+contract IncrementCounter is Script {
+    function dependsOn() external pure returns (string[] memory deps) {
+        deps = new string[](1);
+        deps[0] = "DeployCounter";
+    }
+
+    function run() external {
+        Counter counter = Counter(Deployments(address(vm)).getDeployment("Counter"));
+        vm.broadcast();
+        counter.increment();
+        require(counter.count() == 1, "wrong count");
+    }
+}
+   "#,
+        )
+        .unwrap();
+
+    cmd.args(["script", &format!("{}:IncrementCounter", script.display())]);
+    let stdout = cmd.stdout_lossy();
+    assert!(stdout.contains("Script ran successfully."), "{stdout}");
+
+    // A dependency cycle is rejected.
+    prj.add_script(
+        "Cycle",
+        r#"
+import "forge-std/Script.sol";
+
+contract A is Script {
+    function dependsOn() external pure returns (string[] memory deps) {
+        deps = new string[](1);
+        deps[0] = "B";
+    }
+
+    function run() external {}
+}
+
+contract B is Script {
+    function dependsOn() external pure returns (string[] memory deps) {
+        deps = new string[](1);
+        deps[0] = "A";
+    }
+
+    function run() external {}
+}
+   "#,
+    )
+    .unwrap();
+
+    cmd.forge_fuse().args(["script", "A"]);
+    let stderr = cmd.stderr_lossy();
+    assert!(stderr.contains("circular script dependency: A -> B -> A"), "{stderr}");
+});
+
+// Tests that the contracts of a manifest are deployed and called without a script.
+forgetest_async!(can_deploy_manifest, |prj, cmd| {
+    prj.add_source(
+        "Vault",
+        r#"
+contract Token {
+    mapping(address => uint256) public balanceOf;
+
+    constructor(address holder, uint256 supply) {
+        balanceOf[holder] = supply;
+    }
+}
+
+contract Vault {
+    Token public token;
+    address public owner;
+
+    constructor(Token _token) {
+        require(address(_token).code.length != 0, "token not deployed");
+        token = _token;
+    }
+
+    function initialize(address _owner) external {
+        owner = _owner;
+    }
+}
+   "#,
+    )
+    .unwrap();
+    std::fs::write(
+        prj.root().join("deploy.toml"),
+        r#"
+[[deploy]]
+name = "token"
+contract = "Token"
+args = ["0x000000000000000000000000000000000000bEEF", "1000"]
+
+[[deploy]]
+name = "vault"
+contract = "Vault"
+args = ["@token"]
+calls = [{ function = "initialize(address)", args = ["@token"] }]
+"#,
+    )
+    .unwrap();
+
+    let (_api, handle) = spawn(NodeConfig::test()).await;
+    let dev = handle.dev_accounts().next().unwrap();
+    cmd.args([
+        "script",
+        "--manifest",
+        "deploy.toml",
+        "--fork-url",
+        &handle.http_endpoint(),
+        "--sender",
+        &dev.to_string(),
+        "--broadcast",
+        "--yes",
+        "--unlocked",
+    ]);
+    let stdout = cmd.stdout_lossy();
+    assert!(stdout.contains("token: 0x"), "{stdout}");
+    assert!(stdout.contains("vault: 0x"), "{stdout}");
+    assert!(stdout.contains("ONCHAIN EXECUTION COMPLETE & SUCCESSFUL"), "{stdout}");
+
+    let broadcast = prj.root().join("broadcast/deploy.s.sol/31337/run-latest.json");
+    let sequence: Value =
+        serde_json::from_str(&std::fs::read_to_string(broadcast).unwrap()).unwrap();
+    assert_eq!(sequence["transactions"].as_array().unwrap().len(), 3);
+
+    // References must point to prior deployments.
+    std::fs::write(
+        prj.root().join("deploy.toml"),
+        "[[deploy]]\nname = \"vault\"\ncontract = \"Vault\"\nargs = [\"@token\"]\n",
+    )
+    .unwrap();
+    cmd.forge_fuse().args(["script", "--manifest", "deploy.toml"]);
+    let stderr = cmd.stderr_lossy();
+    assert!(stderr.contains("unknown deployment `@token`"), "{stderr}");
+});
@@ -1,12 +1,12 @@
 //! Contains various tests for `forge test`.
 
 use alloy_primitives::U256;
-use foundry_config::{Config, FuzzConfig};
+use foundry_config::{CheatcodeCapability, CheatcodePermission, Config, FuzzConfig};
 use foundry_test_utils::{
     rpc,
     util::{OutputExt, OTHER_SOLC_VERSION, SOLC_VERSION},
 };
-use std::{path::PathBuf, str::FromStr};
+use std::{collections::BTreeMap, path::PathBuf, str::FromStr};
 
 // tests that test filters are handled correctly
 forgetest!(can_set_filter_values, |prj, cmd| {
@@ -222,6 +222,105 @@ forgetest_init!(can_test_repeatedly, |_prj, cmd| {
     }
 });
 
+// checks that the bytecode analysis cache is shared by the tests and reported
+forgetest_init!(can_show_perf_stats, |_prj, cmd| {
+    cmd.args(["test", "--show-perf-stats"]);
+    let stdout = cmd.stdout_lossy();
+    assert!(stdout.contains("Performance: bytecode analysis cache:"), "{stdout}");
+    assert!(!stdout.contains(" 0 hits"), "{stdout}");
+});
+
+// checks that the call frames expanding memory the most are reported
+forgetest!(can_report_memory_hotspots, |prj, cmd| {
+    prj.insert_ds_test();
+    prj.add_source(
+        "Memory.t.sol",
+        r#"
+pragma solidity ^0.8.0;
+
+import "./test.sol";
+
+contract Buffer {
+    function fill(uint256 size) public pure returns (uint256) {
+        bytes memory buffer = new bytes(size);
+        return buffer.length;
+    }
+}
+
+contract MemoryTest is DSTest {
+    function testLargeBuffer() public {
+        assertEq(new Buffer().fill(100_000), 100_000);
+    }
+}
+   "#,
+    )
+    .unwrap();
+
+    cmd.args(["test", "--memory-profile"]);
+    let stdout = cmd.stdout_lossy();
+    assert!(stdout.contains("Memory hotspots:"), "{stdout}");
+    assert!(stdout.contains("MemoryTest::testLargeBuffer()"), "{stdout}");
+    // The largest frame is the one allocating the buffer.
+    let peak: usize = stdout
+        .split("Memory hotspots:")
+        .nth(1)
+        .and_then(|report| report.split(" bytes of memory").next())
+        .and_then(|line| line.rsplit(' ').next())
+        .and_then(|peak| peak.parse().ok())
+        .unwrap();
+    assert!(peak > 100_000, "{stdout}");
+});
+
+forgetest!(can_report_time_hotspots, |prj, cmd| {
+    prj.insert_ds_test();
+    prj.add_source(
+        "Time.t.sol",
+        r#"
+pragma solidity ^0.8.0;
+
+import "./test.sol";
+
+contract Hasher {
+    function hash(uint256 rounds) public pure returns (bytes32 digest) {
+        for (uint256 i = 0; i < rounds; i++) {
+            digest = keccak256(abi.encode(digest, i));
+        }
+    }
+}
+
+contract TimeTest is DSTest {
+    function testHash() public {
+        new Hasher().hash(10_000);
+    }
+}
+   "#,
+    )
+    .unwrap();
+
+    cmd.args(["test", "--time-profile"]);
+    let stdout = cmd.stdout_lossy();
+    assert!(stdout.contains("Time hotspots ("), "{stdout}");
+    assert!(stdout.contains("Hasher::hash(uint256)"), "{stdout}");
+});
+
+// tests that `forge test --porcelain` reports suites and fuzz campaigns as events on stderr
+forgetest_init!(can_report_porcelain_test_progress, |prj, cmd| {
+    cmd.args(["test", "--porcelain"]);
+    let output = cmd.unchecked_output();
+    assert!(output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("fuzz\tstart\ttest/Counter.t.sol:CounterTest::testFuzz_SetNumber: "),
+        "{stderr}"
+    );
+    assert!(
+        stderr.contains(
+            "test\tsuite-finish\ttest/Counter.t.sol:CounterTest: 2 passed, 0 failed, 0 skipped"
+        ),
+        "{stderr}"
+    );
+});
+
 // tests that `forge test` will run a test only once after changing the version
 forgetest!(runs_tests_exactly_once_with_changed_versions, |prj, cmd| {
     prj.insert_ds_test();
@@ -549,6 +648,154 @@ contract Dummy {
     cmd.assert_success()
 });
 
+forgetest!(can_record_transient_storage, |prj, cmd| {
+    prj.insert_ds_test();
+    prj.add_source(
+        "TransientTest.sol",
+        r#"pragma solidity 0.8.24;
+import "./test.sol";
+
+interface Vm {
+    struct TransientWrite {
+        bytes32 slot;
+        bytes32 previousValue;
+        bytes32 newValue;
+    }
+
+    function recordTransient() external;
+    function getTransientWrites(address target) external returns (TransientWrite[] memory writes);
+    function tload(address target, bytes32 slot) external view returns (bytes32 data);
+    function tstore(address target, bytes32 slot, bytes32 value) external;
+}
+
+contract Lock {
+    function locked() public view returns (bool isLocked) {
+        assembly {
+            isLocked := tload(0)
+        }
+    }
+
+    function run() public {
+        require(!locked(), "locked");
+        assembly {
+            tstore(0, 1)
+        }
+        assembly {
+            tstore(0, 0)
+        }
+    }
+}
+
+contract TransientTest is DSTest {
+    Vm constant vm = Vm(HEVM_ADDRESS);
+
+    function testRecordTransient() public {
+        Lock lock = new Lock();
+        vm.recordTransient();
+        lock.run();
+
+        Vm.TransientWrite[] memory writes = vm.getTransientWrites(address(lock));
+        assertEq(writes.length, 2);
+        assertEq(writes[0].slot, bytes32(0));
+        assertEq(writes[0].previousValue, bytes32(0));
+        assertEq(writes[0].newValue, bytes32(uint256(1)));
+        assertEq(writes[1].previousValue, bytes32(uint256(1)));
+        assertEq(writes[1].newValue, bytes32(0));
+        assertEq(vm.getTransientWrites(address(this)).length, 0);
+    }
+
+    function testTloadTstore() public {
+        Lock lock = new Lock();
+        vm.tstore(address(lock), bytes32(0), bytes32(uint256(1)));
+        assertEq(vm.tload(address(lock), bytes32(0)), bytes32(uint256(1)));
+        assertTrue(lock.locked());
+
+        (bool success,) = address(lock).call(abi.encodeCall(Lock.run, ()));
+        assertTrue(!success);
+    }
+}
+"#,
+    )
+    .unwrap();
+
+    cmd.args(["test", "--evm-version", "cancun"]).assert_success();
+});
+
+// tests that `historical_block_hashes` serves the hashes of blocks older than the last 256 ones
+forgetest!(can_serve_historical_block_hashes, |prj, cmd| {
+    prj.insert_ds_test();
+    prj.add_source(
+        "BlockHash.t.sol",
+        r#"pragma solidity 0.8.24;
+import "./test.sol";
+
+interface Vm {
+    function roll(uint256 newHeight) external;
+}
+
+contract BlockHashTest is DSTest {
+    Vm constant vm = Vm(HEVM_ADDRESS);
+
+    function testHistoricalBlockHash() public {
+        vm.roll(10_000);
+        assertEq(blockhash(9_700), keccak256("9700"));
+        assertEq(blockhash(10_000 - 8191), keccak256("1809"));
+        // Older than the EIP-2935 window.
+        assertEq(blockhash(10_000 - 8192), bytes32(0));
+        assertEq(blockhash(10_000), bytes32(0));
+    }
+}
+"#,
+    )
+    .unwrap();
+
+    // Disabled by default, like on chain.
+    cmd.args(["test"]);
+    cmd.assert_err();
+
+    prj.write_config(Config { historical_block_hashes: true, ..Default::default() });
+    cmd.assert_success();
+});
+
+forgetest!(can_run_tests_with_solc_matrix, |prj, cmd| {
+    prj.insert_ds_test();
+    prj.add_test(
+        "Old.t.sol",
+        r#"pragma solidity >=0.8.20;
+import "src/test.sol";
+
+contract OldTest is DSTest {
+    function testOld() public {
+        assertTrue(true);
+    }
+}
+"#,
+    )
+    .unwrap();
+    prj.add_test(
+        "New.t.sol",
+        r#"pragma solidity ^0.8.24;
+import "src/test.sol";
+
+contract NewTest is DSTest {
+    function testNew() public {
+        assertTrue(true);
+    }
+}
+"#,
+    )
+    .unwrap();
+
+    cmd.args(["test", "--solc-matrix", "0.8.20,0.8.24"]);
+    let stdout = cmd.stdout_lossy();
+    let matrix = stdout.lines().filter(|line| line.contains("::")).collect::<Vec<_>>();
+    assert_eq!(matrix.len(), 2, "{stdout}");
+    assert!(matrix[0].contains("NewTest::testNew"), "{stdout}");
+    assert!(matrix[0].contains('-') && matrix[0].contains("PASS"), "{stdout}");
+    assert!(matrix[1].contains("OldTest::testOld"), "{stdout}");
+    assert_eq!(matrix[1].matches("PASS").count(), 2, "{stdout}");
+});
+
 forgetest_init!(should_not_shrink_fuzz_failure, |prj, cmd| {
     prj.wipe_contracts();
 
@@ -593,6 +840,43 @@ contract CounterTest is Test {
     assert_eq!(extract_number_of_runs(stderr), 61);
 });
 
+forgetest_init!(can_replay_fuzz_failure, |prj, cmd| {
+    prj.wipe_contracts();
+
+    let config = Config {
+        fuzz: { FuzzConfig { runs: 256, seed: Some(U256::from(100)), ..Default::default() } },
+        ..Default::default()
+    };
+    prj.write_config(config);
+
+    prj.add_test(
+        "CounterFuzz.t.sol",
+        r#"pragma solidity 0.8.24;
+import {Test} from "forge-std/Test.sol";
+
+contract CounterTest is Test {
+    function testFuzzReplay(uint256 x) public pure {
+        require(x < 1000, "too large");
+    }
+}
+     "#,
+    )
+    .unwrap();
+
+    cmd.args(["test"]);
+    cmd.assert_err();
+
+    let replay_file = prj
+        .root()
+        .join("cache/fuzz/replays/test/CounterFuzz.t.sol/CounterTest/testFuzzReplay.json");
+    assert!(replay_file.exists());
+
+    cmd.forge_fuse().args(["test", "--replay"]).arg(&replay_file);
+    let (stdout, _) = cmd.unchecked_output_lossy();
+    assert!(stdout.contains("too large; counterexample:"), "{stdout}");
+    assert!(stdout.contains("Ran 1 test for test/CounterFuzz.t.sol:CounterTest"), "{stdout}");
+});
+
 forgetest_init!(should_exit_early_on_invariant_failure, |prj, cmd| {
     prj.wipe_contracts();
     prj.add_test(
@@ -636,3 +920,344 @@ fn extract_number_of_runs(stderr: String) -> usize {
     });
     runs.unwrap().parse::<usize>().unwrap()
 }
+
+forgetest_init!(can_run_tests_deterministically, |prj, cmd| {
+    prj.wipe_contracts();
+    prj.add_test(
+        "Deterministic.t.sol",
+        r#"pragma solidity 0.8.24;
+import {Test} from "forge-std/Test.sol";
+
+contract DeterministicTest is Test {
+    function testUnixTime() public {
+        vm.warp(10);
+        assertEq(vm.unixTime(), 10_000);
+    }
+
+    function testFfi() public {
+        string[] memory inputs = new string[](1);
+        inputs[0] = "date";
+        vm.ffi(inputs);
+    }
+}
+     "#,
+    )
+    .unwrap();
+
+    cmd.args(["test", "--deterministic", "--ffi"]);
+    let (stdout, _) = cmd.unchecked_output_lossy();
+    assert!(stdout.contains("[PASS] testUnixTime()"), "{stdout}");
+    assert!(stdout.contains("`ffi` is not allowed in deterministic mode"), "{stdout}");
+});
+
+forgetest_init!(deterministic_runs_have_the_same_output, |prj, cmd| {
+    prj.wipe_contracts();
+    prj.add_test(
+        "Deterministic.t.sol",
+        r#"pragma solidity 0.8.24;
+import {Test, console} from "forge-std/Test.sol";
+
+contract DeterministicTest is Test {
+    function testLabels() public {
+        for (uint256 i; i < 16; i++) {
+            vm.label(vm.randomAddress(), vm.toString(i));
+        }
+        console.log(vm.randomUint());
+    }
+
+    function testFuzzLabels(address a, uint256 x) public {
+        vm.label(a, vm.toString(x));
+    }
+}
+     "#,
+    )
+    .unwrap();
+
+    // Durations are the only part of the output that may change between runs.
+    fn strip_durations(value: &mut serde_json::Value) {
+        match value {
+            serde_json::Value::Object(map) => {
+                map.remove("duration");
+                map.values_mut().for_each(strip_durations);
+            }
+            serde_json::Value::Array(values) => values.iter_mut().for_each(strip_durations),
+            _ => {}
+        }
+    }
+    cmd.args(["test", "--deterministic", "--json"]);
+    let mut run = || {
+        let mut output: serde_json::Value = serde_json::from_str(&cmd.stdout_lossy()).unwrap();
+        strip_durations(&mut output);
+        output
+    };
+    let first = run();
+    let second = run();
+    let stdout = serde_json::to_string(&first).unwrap();
+    assert_eq!(stdout, serde_json::to_string(&second).unwrap());
+
+    // Labels are serialized in the order of their addresses.
+    let labels = &first["test/Deterministic.t.sol:DeterministicTest"]["test_results"]
+        ["testLabels()"]["labeled_addresses"];
+    let addresses =
+        labels.as_object().unwrap().keys().map(|key| key.to_lowercase()).collect::<Vec<_>>();
+    assert!(addresses.len() >= 16, "{stdout}");
+    assert!(addresses.windows(2).all(|pair| pair[0] < pair[1]), "{stdout}");
+});
+
+forgetest_init!(can_scope_cheatcodes_to_test_paths, |prj, cmd| {
+    prj.wipe_contracts();
+    let test = |name: &str| {
+        format!(
+            r#"pragma solidity 0.8.24;
+import {{Test}} from "forge-std/Test.sol";
+
+contract {name}Test is Test {{
+    function testFfi() public {{
+        string[] memory inputs = new string[](2);
+        inputs[0] = "echo";
+        inputs[1] = "0x01";
+        vm.ffi(inputs);
+    }}
+}}
+"#
+        )
+    };
+    prj.add_test("ffi/Allowed.t.sol", &test("Allowed")).unwrap();
+    prj.add_test("Denied.t.sol", &test("Denied")).unwrap();
+    prj.write_config(Config {
+        ffi: true,
+        cheatcode_permissions: vec![CheatcodePermission::new(
+            "test/ffi/**".parse().unwrap(),
+            [CheatcodeCapability::Ffi],
+        )],
+        ..Default::default()
+    });
+
+    cmd.args(["test"]);
+    let (stdout, _) = cmd.unchecked_output_lossy();
+    let allowed = stdout.split("AllowedTest").nth(1).unwrap_or_default();
+    assert!(allowed.contains("[PASS] testFfi()"), "{stdout}");
+    let denied = stdout.split("DeniedTest").nth(1).unwrap_or_default();
+    assert!(denied.contains("[FAIL"), "{stdout}");
+    assert!(
+        stdout.contains("`ffi` is not allowed: the `ffi` capability is not granted"),
+        "{stdout}"
+    );
+});
+
+forgetest_init!(can_scope_transact_to_test_paths, |prj, cmd| {
+    prj.wipe_contracts();
+    prj.add_test(
+        "Transact.t.sol",
+        r#"pragma solidity 0.8.24;
+import {Test} from "forge-std/Test.sol";
+
+contract TransactTest is Test {
+    function testTransact() public {
+        vm.transact(bytes32(uint256(1)));
+    }
+}
+"#,
+    )
+    .unwrap();
+    prj.write_config(Config {
+        cheatcode_permissions: vec![CheatcodePermission::new(
+            "test/fork/**".parse().unwrap(),
+            [CheatcodeCapability::Http],
+        )],
+        ..Default::default()
+    });
+
+    cmd.args(["test"]);
+    let (stdout, _) = cmd.unchecked_output_lossy();
+    assert!(stdout.contains("[FAIL"), "{stdout}");
+    assert!(
+        stdout.contains("`transact_0` is not allowed: the `http` capability is not granted"),
+        "{stdout}"
+    );
+});
+
+forgetest_init!(can_reproduce_run_from_seed, |prj, cmd| {
+    prj.wipe_contracts();
+    prj.add_test(
+        "Seed.t.sol",
+        r#"pragma solidity 0.8.24;
+import {Test, console} from "forge-std/Test.sol";
+
+contract SeedTest is Test {
+    function testRandom() public {
+        console.log(vm.randomUint());
+    }
+}
+     "#,
+    )
+    .unwrap();
+
+    cmd.env("FOUNDRY_SEED", "0x2a");
+    cmd.args(["test", "-vv"]);
+    let (stdout, stderr) = cmd.output_lossy();
+    assert!(stderr.contains("Seed: 0x2a"), "{stderr}");
+    let random = stdout.lines().skip_while(|line| !line.contains("Logs:")).nth(1).unwrap();
+
+    let (stdout, _) = cmd.output_lossy();
+    assert!(stdout.contains(random), "{stdout}");
+
+    cmd.forge_fuse().args(["test", "-vv", "--fuzz-seed", "0x2b"]);
+    let (stdout, stderr) = cmd.output_lossy();
+    assert!(stderr.contains("Seed: 0x2b"), "{stderr}");
+    assert!(!stdout.contains(random), "{stdout}");
+});
+
+forgetest_init!(can_record_test_artifacts, |prj, cmd| {
+    prj.wipe_contracts();
+    prj.add_test(
+        "Artifacts.t.sol",
+        r#"pragma solidity 0.8.24;
+import {Test, console} from "forge-std/Test.sol";
+
+contract ArtifactsTest is Test {
+    function testLog() public {
+        console.log("hello artifacts");
+    }
+
+    function testFail() public {
+        revert("boom");
+    }
+}
+     "#,
+    )
+    .unwrap();
+
+    cmd.args(["test", "--test-artifacts"]);
+    cmd.unchecked_output();
+    let index = prj.root().join("out/test-artifacts/index.json");
+    assert!(index.exists());
+
+    cmd.forge_fuse().args(["test-report", "ls", "--run", "latest"]);
+    let stdout = cmd.stdout_lossy();
+    assert!(stdout.contains("ArtifactsTest::testLog()"), "{stdout}");
+
+    cmd.forge_fuse().args(["test-report", "open", "ArtifactsTest::testLog"]);
+    let stdout = cmd.stdout_lossy();
+    assert!(stdout.contains("[PASS] testLog()"), "{stdout}");
+    assert!(stdout.contains("hello artifacts"), "{stdout}");
+
+    cmd.forge_fuse().args(["test-report", "gc", "--keep", "0"]);
+    cmd.assert_non_empty_stdout();
+    assert!(!prj.root().join("out/test-artifacts").read_dir().unwrap().any(|entry| entry
+        .unwrap()
+        .file_name()
+        .to_string_lossy()
+        .starts_with("run-")));
+});
+
+forgetest_init!(can_emit_test_reports, |prj, cmd| {
+    prj.wipe_contracts();
+    prj.add_test(
+        "Report.t.sol",
+        r#"pragma solidity 0.8.24;
+import {Test, console} from "forge-std/Test.sol";
+
+contract ReportTest is Test {
+    function testLog() public {
+        console.log("hello report");
+    }
+
+    function testFail() public {
+        revert("boom");
+    }
+}
+     "#,
+    )
+    .unwrap();
+
+    cmd.args(["test", "--report", "ndjson"]);
+    let stdout = String::from_utf8_lossy(&cmd.unchecked_output().stdout).into_owned();
+    let events = stdout
+        .lines()
+        .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(events.len(), 3, "{stdout}");
+    assert!(events.iter().all(|event| event["version"] == 2), "{stdout}");
+    assert_eq!(events[1]["test"], "testLog()");
+    assert_eq!(events[1]["logs"][0], "hello report");
+    assert_eq!(events[2]["type"], "summary");
+    assert_eq!(events[2]["failed"], 0);
+
+    cmd.forge_fuse().args(["test", "--report", "junit"]);
+    let stdout = cmd.stdout_lossy();
+    assert!(stdout.starts_with("<?xml"), "{stdout}");
+    assert!(stdout.contains(r#"<testcase name="testLog()""#), "{stdout}");
+});
+
+// tests that `test_overrides` substitutes implementations wherever they are created
+forgetest_init!(can_override_implementations, |prj, cmd| {
+    prj.wipe_contracts();
+    let config = Config {
+        test_overrides: BTreeMap::from([("ChainlinkOracle".to_string(), "MockOracle".to_string())]),
+        ..Default::default()
+    };
+    prj.write_config(config);
+    prj.add_source(
+        "Oracle.sol",
+        r#"pragma solidity 0.8.24;
+
+contract ChainlinkOracle {
+    uint256 public immutable decimals;
+
+    constructor(uint256 _decimals) {
+        decimals = _decimals;
+    }
+
+    function price() public pure returns (uint256) {
+        revert("no feed");
+    }
+}
+
+contract MockOracle {
+    uint256 public immutable decimals;
+
+    constructor(uint256 _decimals) {
+        decimals = _decimals;
+    }
+
+    function price() public pure returns (uint256) {
+        return 42;
+    }
+}
+
+contract Vault {
+    ChainlinkOracle public oracle = new ChainlinkOracle(8);
+}
+     "#,
+    )
+    .unwrap();
+    prj.add_test(
+        "Vault.t.sol",
+        r#"pragma solidity 0.8.24;
+import {Test} from "forge-std/Test.sol";
+import {Vault} from "../src/Oracle.sol";
+
+contract VaultTest is Test {
+    function testMockedPrice() public {
+        Vault vault = new Vault();
+        assertEq(vault.oracle().price(), 42);
+        assertEq(vault.oracle().decimals(), 8);
+    }
+}
+     "#,
+    )
+    .unwrap();
+
+    cmd.args(["test"]);
+    let stdout = cmd.stdout_lossy();
+    assert!(stdout.contains("[PASS] testMockedPrice()"), "{stdout}");
+});
+
+// tests that the arguments of `forge test` do not clash, e.g. the short flags of the build options
+forgetest!(can_parse_test_help, |_prj, cmd| {
+    cmd.args(["test", "--help"]);
+    let stdout = cmd.stdout_lossy();
+    assert!(stdout.contains("--jobs <JOBS>"), "{stdout}");
+    assert!(stdout.contains("-j, --json"), "{stdout}");
+});
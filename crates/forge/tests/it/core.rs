@@ -1,6 +1,7 @@
 //! Forge tests for core functionality.
 
 use crate::{config::*, test_helpers::TEST_DATA_DEFAULT};
+use alloy_primitives::address;
 use forge::result::SuiteResult;
 use foundry_evm::traces::TraceKind;
 use foundry_test_utils::Filter;
@@ -106,6 +107,28 @@ async fn test_core() {
                     ("testFlagSetFailure()", true, None, None, None),
                 ],
             ),
+            (
+                "default/core/AccessPolicy.t.sol:AccessPolicyTest",
+                vec![
+                    ("testWriteCreated()", true, None, None, None),
+                    ("testWriteAllowed()", true, None, None, None),
+                    ("testWriteNotAllowed()", true, None, None, None),
+                    ("testCaughtWriteNotAllowed()", true, None, None, None),
+                    ("testCallDenied()", true, None, None, None),
+                    ("testReadDenied()", true, None, None, None),
+                ],
+            ),
+            (
+                "default/core/ResourceLimits.t.sol:ResourceLimitsTest",
+                vec![
+                    ("testWithinLimits()", true, None, None, None),
+                    ("testCallDepth()", true, None, None, None),
+                    ("testCaughtCallDepth()", true, None, None, None),
+                    ("testEvents()", true, None, None, None),
+                    ("testStateWrites()", true, None, None, None),
+                    ("testReturnData()", true, None, None, None),
+                ],
+            ),
         ]),
     );
 }
@@ -803,3 +826,94 @@ async fn test_legacy_assertions() {
         )]),
     );
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_resource_limits() {
+    let filter = Filter::new(".*", ".*ResourceLimitsTest", ".*");
+    let mut config = TEST_DATA_DEFAULT.config.clone();
+    config.max_call_depth = Some(4);
+    config.max_events = Some(10);
+    config.max_state_writes = Some(10);
+    config.max_return_data_size = Some(1024);
+    let mut runner = TEST_DATA_DEFAULT.runner_with_config(config);
+    let results = runner.test_collect(&filter);
+
+    assert_multiple(
+        &results,
+        BTreeMap::from([(
+            "default/core/ResourceLimits.t.sol:ResourceLimitsTest",
+            vec![
+                ("testWithinLimits()", true, None, None, None),
+                (
+                    "testCallDepth()",
+                    false,
+                    Some("call depth limit of 4 exceeded".to_string()),
+                    None,
+                    None,
+                ),
+                (
+                    "testCaughtCallDepth()",
+                    false,
+                    Some("call depth limit of 4 exceeded".to_string()),
+                    None,
+                    None,
+                ),
+                ("testEvents()", false, Some("event limit of 10 exceeded".to_string()), None, None),
+                (
+                    "testStateWrites()",
+                    false,
+                    Some("state write limit of 10 exceeded".to_string()),
+                    None,
+                    None,
+                ),
+                (
+                    "testReturnData()",
+                    false,
+                    Some("return data size limit of 1024 bytes exceeded: 2112 bytes".to_string()),
+                    None,
+                    None,
+                ),
+            ],
+        )]),
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_access_policy() {
+    let filter = Filter::new(".*", ".*AccessPolicyTest", ".*");
+    let mut config = TEST_DATA_DEFAULT.config.clone();
+    config.write_allowlist = Some(vec![address!("0000000000000000000000000000000000001111")]);
+    config.read_denylist = vec![address!("0000000000000000000000000000000000003333")];
+    let mut runner = TEST_DATA_DEFAULT.runner_with_config(config);
+    let results = runner.test_collect(&filter);
+
+    let suite = &results["default/core/AccessPolicy.t.sol:AccessPolicyTest"];
+    let reason = |test: &str| suite.test_results[test].reason.clone().unwrap_or_default();
+    assert_eq!(reason("testWriteCreated()"), "");
+    assert_eq!(reason("testWriteAllowed()"), "");
+
+    let not_allowed = "0x0000000000000000000000000000000000002222";
+    let denied = "0x0000000000000000000000000000000000003333";
+    for (test, violation, last_call) in [
+        (
+            "testWriteNotAllowed()",
+            format!("storage write to {not_allowed} is not allowed"),
+            not_allowed,
+        ),
+        (
+            "testCaughtWriteNotAllowed()",
+            format!("storage write to {not_allowed} is not allowed"),
+            not_allowed,
+        ),
+        ("testCallDenied()", format!("call to {denied} is denied"), denied),
+        ("testReadDenied()", format!("read from {denied} is denied"), ""),
+    ] {
+        let reason = reason(test);
+        assert!(
+            reason.starts_with(&format!("access policy violation: {violation}; call path: ")),
+            "{test}: {reason}"
+        );
+        assert!(reason.ends_with(last_call), "{test}: {reason}");
+        assert!(!suite.test_results[test].status.is_success(), "{test}");
+    }
+}
@@ -99,6 +99,14 @@ async fn test_create_same_fork() {
     TestConfig::with_filter(runner, filter).run().await;
 }
 
+/// Tests that test contracts run on the fork they are annotated with
+#[tokio::test(flavor = "multi_thread")]
+async fn test_contract_fork() {
+    let runner = TEST_DATA_DEFAULT.runner();
+    let filter = Filter::new(".*", ".*", &format!(".*fork{RE_PATH_SEPARATOR}ContractFork"));
+    TestConfig::with_filter(runner, filter).run().await;
+}
+
 /// Test that `no_storage_caching` config is properly applied
 #[tokio::test(flavor = "multi_thread")]
 async fn test_storage_caching_config() {
@@ -4,7 +4,7 @@ use crate::{config::*, test_helpers::TEST_DATA_DEFAULT};
 use alloy_primitives::{Bytes, U256};
 use forge::{
     fuzz::CounterExample,
-    result::{SuiteResult, TestStatus},
+    result::{SuiteResult, TestKind, TestStatus},
 };
 use foundry_test_utils::Filter;
 use std::collections::BTreeMap;
@@ -12,7 +12,7 @@ use std::collections::BTreeMap;
 #[tokio::test(flavor = "multi_thread")]
 async fn test_fuzz() {
     let filter = Filter::new(".*", ".*", ".*fuzz/")
-        .exclude_tests(r"invariantCounter|testIncrement\(address\)|testNeedle\(uint256\)|testSuccessChecker\(uint256\)|testSuccessChecker2\(int256\)|testSuccessChecker3\(uint32\)|testStorageOwner\(address\)|testImmutableOwner\(address\)")
+        .exclude_tests(r"invariantCounter|testIncrement\(address\)|testNeedle\(uint256\)|testSuccessChecker\(uint256\)|testSuccessChecker2\(int256\)|testSuccessChecker3\(uint32\)|testStorageOwner\(address\)|testImmutableOwner\(address\)|testNestedBranches\(uint256,uint256,uint256\)|testMagicValue\(uint256\)|testDiff_")
         .exclude_paths("invariant");
     let mut runner = TEST_DATA_DEFAULT.runner();
     let suite_result = runner.test_collect(&filter);
@@ -75,6 +75,38 @@ async fn test_successful_fuzz_cases() {
     }
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_parallel_fuzz() {
+    let filter = Filter::new(".*", ".*", ".*fuzz/(Fuzz|FuzzPositive).t.sol");
+    let mut runner = TEST_DATA_DEFAULT.runner();
+    runner.test_options.fuzz.runs = 1000;
+    runner.test_options.fuzz.threads = 4;
+    let suite_result = runner.test_collect(&filter);
+
+    assert!(!suite_result.is_empty());
+
+    for (_, SuiteResult { test_results, .. }) in suite_result {
+        for (test_name, result) in test_results {
+            if test_name == "testFailFuzz(uint8)" {
+                assert_eq!(result.status, TestStatus::Failure, "{test_name}");
+                continue;
+            }
+            assert_eq!(
+                result.status,
+                TestStatus::Success,
+                "Test {} did not pass as expected.\nReason: {:?}",
+                test_name,
+                result.reason,
+            );
+            // Runs of all the workers are accounted for.
+            match result.kind {
+                TestKind::Fuzz { runs, .. } => assert_eq!(runs, 1000, "{test_name}"),
+                _ => unreachable!(),
+            }
+        }
+    }
+}
+
 /// Test that showcases PUSH collection on normal fuzzing. Ignored until we collect them in a
 /// smarter way.
 #[tokio::test(flavor = "multi_thread")]
@@ -154,6 +186,86 @@ async fn test_persist_fuzz_failure() {
     assert_ne!(initial_calldata, new_calldata);
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_coverage_guided_fuzz() {
+    let filter = Filter::new(".*", ".*", ".*fuzz/FuzzCoverageGuided.t.sol");
+    let corpus_dir = tempfile::tempdir().unwrap();
+    let mut runner = TEST_DATA_DEFAULT.runner();
+    runner.test_options.fuzz.runs = 10000;
+    runner.test_options.fuzz.seed = Some(U256::from(1u32));
+    runner.test_options.fuzz.coverage_guided = true;
+    runner.test_options.fuzz.corpus_dir = Some(corpus_dir.path().to_path_buf());
+
+    let results = runner.test_collect(&filter);
+    let result = results
+        .get("default/fuzz/FuzzCoverageGuided.t.sol:FuzzCoverageGuidedTest")
+        .unwrap()
+        .test_results
+        .get("testNestedBranches(uint256,uint256,uint256)")
+        .unwrap();
+    assert_eq!(result.status, TestStatus::Failure);
+    assert_eq!(result.reason, Some("revert: found".to_string()));
+
+    // inputs reaching new branches are persisted to the corpus of the test
+    let test_corpus = corpus_dir.path().join("FuzzCoverageGuidedTest").join("testNestedBranches");
+    assert!(std::fs::read_dir(test_corpus).unwrap().count() > 1);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_fuzz_branch_hints() {
+    let filter = Filter::new(".*", ".*", ".*fuzz/FuzzBranchHints.t.sol");
+    let mut runner = TEST_DATA_DEFAULT.runner();
+    runner.test_options.fuzz.seed = Some(U256::from(1u32));
+    runner.test_options.fuzz.dictionary.dictionary_weight = 0;
+    runner.test_options.fuzz.branch_hints = true;
+
+    let results = runner.test_collect(&filter);
+    let result = results
+        .get("default/fuzz/FuzzBranchHints.t.sol:FuzzBranchHintsTest")
+        .unwrap()
+        .test_results
+        .get("testMagicValue(uint256)")
+        .unwrap();
+    assert_eq!(result.status, TestStatus::Success);
+    assert_eq!(result.branch_hints.len(), 1, "{:?}", result.branch_hints);
+    assert!(
+        result.branch_hints[0].ends_with("`amount` likely needs to equal 123456789"),
+        "{:?}",
+        result.branch_hints
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_fuzz_differential() {
+    let filter = Filter::new(".*", ".*", ".*fuzz/FuzzDifferential.t.sol");
+    let mut runner = TEST_DATA_DEFAULT.runner();
+    runner.test_options.fuzz.seed = Some(U256::from(1u32));
+
+    let results = runner.test_collect(&filter);
+    let suite = &results["default/fuzz/FuzzDifferential.t.sol:FuzzDifferentialTest"].test_results;
+    let average = &suite["testDiff_average(uint256,uint256)"];
+    assert_eq!(average.status, TestStatus::Success, "{:?}", average.reason);
+
+    let naive = &suite["testDiff_naiveAverage(uint256,uint256)"];
+    assert_eq!(naive.status, TestStatus::Failure);
+    let reason = naive.reason.as_deref().unwrap();
+    assert!(
+        reason
+            .ends_with("differential mismatch: the reference succeeded but the candidate reverted"),
+        "{reason}"
+    );
+    assert!(matches!(naive.counterexample, Some(CounterExample::Single(_))));
+
+    let no_targets = &results["default/fuzz/FuzzDifferential.t.sol:FuzzDifferentialNoTargetsTest"]
+        .test_results["testDiff_average(uint256,uint256)"];
+    assert_eq!(no_targets.status, TestStatus::Failure);
+    assert!(no_targets
+        .reason
+        .as_deref()
+        .unwrap()
+        .starts_with("failed to set up differential fuzzing"));
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn test_scrape_bytecode() {
     let filter = Filter::new(".*", ".*", ".*fuzz/FuzzScrapeBytecode.t.sol");
@@ -43,6 +43,18 @@ async fn inline_config_run_invariant() {
     }
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn inline_config_run_evm() {
+    let filter = Filter::new(".*", ".*", ".*inline/EvmInlineConf.t.sol");
+    let mut runner = TEST_DATA_DEFAULT.runner();
+    let result = runner.test_collect(&filter);
+    let suite_result = result.get("default/inline/EvmInlineConf.t.sol:EvmInlineConf").unwrap();
+    for test in ["testInlineGasLimit()", "testInlineEvmVersion()"] {
+        let test_result = suite_result.test_results.get(test).unwrap();
+        assert!(test_result.status.is_success(), "{test}: {:?}", test_result.reason);
+    }
+}
+
 #[test]
 fn build_test_options() {
     let root = &TEST_DATA_DEFAULT.project.paths.root;
@@ -310,6 +310,24 @@ async fn test_shrink(opts: TestOptions, contract_pattern: &str) {
     };
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_invariant_explain_failures() {
+    let filter = Filter::new(".*", ".*", ".*fuzz/invariant/common/InvariantExplainFailures.t.sol");
+    let mut runner = TEST_DATA_DEFAULT.runner();
+    runner.test_options.invariant.explain_failures = true;
+
+    match get_counterexample!(runner, &filter) {
+        CounterExample::Single(_) => panic!("CounterExample should be a sequence."),
+        CounterExample::Sequence(sequence) => {
+            assert_eq!(sequence.len(), 1);
+            let explanation = sequence[0].explanation.as_ref().unwrap();
+            assert!(!explanation.removable);
+            assert_eq!(explanation.essential_args, ["key"]);
+            assert_eq!(explanation.irrelevant_args, ["noise"]);
+        }
+    };
+}
+
 #[tokio::test(flavor = "multi_thread")]
 #[cfg_attr(windows, ignore = "for some reason there's different rng")]
 async fn test_shrink_big_sequence() {
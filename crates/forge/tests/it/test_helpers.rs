@@ -93,6 +93,11 @@ impl ForgeTestProfile {
                 gas_report_samples: 256,
                 failure_persist_dir: Some(tempfile::tempdir().unwrap().into_path()),
                 failure_persist_file: Some("testfailure".to_string()),
+                coverage_guided: false,
+                corpus_dir: None,
+                threads: 1,
+                branch_hints: false,
+                differential: Default::default(),
             })
             .invariant(InvariantConfig {
                 runs: 256,
@@ -110,6 +115,7 @@ impl ForgeTestProfile {
                 max_assume_rejects: 65536,
                 gas_report_samples: 256,
                 failure_persist_dir: Some(tempfile::tempdir().unwrap().into_path()),
+                explain_failures: false,
             })
             .build(output, Path::new(self.project().root()))
             .expect("Config loaded")
@@ -1,10 +1,12 @@
 use crate::{
-    build::LinkedBuildData, progress::ScriptProgress, sequence::ScriptSequenceKind,
-    verify::BroadcastedState, ScriptArgs, ScriptConfig,
+    build::LinkedBuildData,
+    progress::ScriptProgress,
+    sequence::{ScriptSequence, ScriptSequenceKind},
+    verify::BroadcastedState,
+    ScriptArgs, ScriptConfig,
 };
 use alloy_chains::Chain;
-use alloy_eips::eip2718::Encodable2718;
-use alloy_network::{AnyNetwork, EthereumWallet, TransactionBuilder};
+use alloy_network::{AnyNetwork, TransactionBuilder};
 use alloy_primitives::{utils::format_units, Address, TxHash};
 use alloy_provider::{utils::Eip1559Estimation, Provider};
 use alloy_rpc_types::TransactionRequest;
@@ -19,6 +21,8 @@ use foundry_common::{
     shell,
 };
 use foundry_config::Config;
+use foundry_evm::tx_type::custom_tx_type;
+use foundry_wallets::sender::{TransactionSender, UnlockedSender, WalletSender};
 use futures::{future::join_all, StreamExt};
 use itertools::Itertools;
 use std::{
@@ -56,7 +60,7 @@ pub async fn next_nonce(caller: Address, provider_url: &str) -> eyre::Result<u64
 pub async fn send_transaction(
     provider: Arc<RetryProvider>,
     mut tx: WithOtherFields<TransactionRequest>,
-    kind: SendTransactionKind<'_>,
+    sender: &dyn TransactionSender,
     sequential_broadcast: bool,
     is_fixed_gas_limit: bool,
     estimate_via_rpc: bool,
@@ -64,7 +68,7 @@ pub async fn send_transaction(
 ) -> Result<TxHash> {
     let from = tx.from.expect("no sender");
 
-    if sequential_broadcast {
+    if sequential_broadcast && sender.uses_sender_nonce() {
         let nonce = provider.get_transaction_count(from).await?;
 
         let tx_nonce = tx.nonce.expect("no nonce");
@@ -79,68 +83,150 @@ pub async fn send_transaction(
         estimate_gas(&mut tx, &provider, estimate_multiplier).await?;
     }
 
-    let pending = match kind {
-        SendTransactionKind::Unlocked(addr) => {
-            debug!("sending transaction from unlocked account {:?}: {:?}", addr, tx);
+    let chain_id = match tx.chain_id {
+        Some(chain_id) => chain_id,
+        None => provider.get_chain_id().await?,
+    };
+    if let Some(tx_type) = custom_tx_type(chain_id, tx.transaction_type) {
+        // Custom transaction types are signed and encoded by their type, which alloy can't do.
+        tx_type
+            .validate(&tx)
+            .wrap_err_with(|| format!("invalid {} transaction", tx_type.name()))?;
+        let signature = sender.sign_hash(&tx_type.signature_hash(&tx)?).await?;
+        let raw = tx_type.encode_signed(&tx, &signature)?;
+        debug!("sending {} transaction: {:?}", tx_type.name(), tx);
+        return Ok(*provider.send_raw_transaction(&raw).await?.tx_hash())
+    }
 
-            // Submit the transaction
-            provider.send_transaction(tx).await?
-        }
-        SendTransactionKind::Raw(signer) => {
-            debug!("sending transaction: {:?}", tx);
+    sender.send_transaction(&provider, tx).await
+}
 
-            let signed = tx.build(signer).await?;
+/// The senders of _all_ transactions, by address.
+#[derive(Default)]
+pub struct TransactionSenders {
+    senders: HashMap<Address, Arc<dyn TransactionSender>>,
+}
+
+impl TransactionSenders {
+    /// Sends the transactions of the given addresses with `sender`.
+    pub fn insert_all(
+        &mut self,
+        addresses: impl IntoIterator<Item = Address>,
+        sender: Arc<dyn TransactionSender>,
+    ) {
+        self.senders.extend(addresses.into_iter().map(|addr| (addr, sender.clone())));
+    }
 
-            // Submit the raw transaction
-            provider.send_raw_transaction(signed.encoded_2718().as_ref()).await?
+    /// Returns the [`TransactionSender`] for the given address
+    ///
+    /// Returns an error if no matching sender is found
+    pub fn for_sender(&self, addr: &Address) -> Result<&dyn TransactionSender> {
+        match self.senders.get(addr) {
+            Some(sender) => Ok(sender.as_ref()),
+            None => bail!("No matching signer for {:?} found", addr),
         }
-    };
+    }
 
-    Ok(*pending.tx_hash())
-}
+    /// How many signers are set
+    pub fn signers_count(&self) -> usize {
+        self.senders.len()
+    }
 
-/// How to send a single transaction
-#[derive(Clone)]
-pub enum SendTransactionKind<'a> {
-    Unlocked(Address),
-    Raw(&'a EthereumWallet),
+    /// Returns whether all transactions are executed with the nonces of their senders.
+    pub fn use_sender_nonces(&self) -> bool {
+        self.senders.values().all(|sender| sender.uses_sender_nonce())
+    }
 }
 
-/// Represents how to send _all_ transactions
-pub enum SendTransactionsKind {
-    /// Send via `eth_sendTransaction` and rely on the  `from` address being unlocked.
-    Unlocked(HashSet<Address>),
-    /// Send a signed transaction via `eth_sendRawTransaction`
-    Raw(HashMap<Address, EthereumWallet>),
+/// The fees to pay for the transactions of a sequence that haven't been broadcasted yet.
+pub struct SequenceFees {
+    gas_price: Option<u128>,
+    eip1559_fees: Option<Eip1559Estimation>,
+    blob_gas_price: Option<u128>,
 }
 
-impl SendTransactionsKind {
-    /// Returns the [`SendTransactionKind`] for the given address
-    ///
-    /// Returns an error if no matching signer is found or the address is not unlocked
-    pub fn for_sender(&self, addr: &Address) -> Result<SendTransactionKind<'_>> {
-        match self {
-            Self::Unlocked(unlocked) => {
-                if !unlocked.contains(addr) {
-                    bail!("Sender address {:?} is not unlocked", addr)
+impl SequenceFees {
+    /// Makes a one-time estimation of the fees, unless they are set by the arguments.
+    pub async fn estimate(
+        args: &ScriptArgs,
+        sequence: &ScriptSequence,
+        provider: &RetryProvider,
+    ) -> Result<Self> {
+        let is_legacy = Chain::from(sequence.chain).is_legacy() || args.legacy;
+        let (gas_price, eip1559_fees) = match (
+            is_legacy,
+            args.with_gas_price,
+            args.priority_gas_price,
+        ) {
+            (true, Some(gas_price), _) => (Some(gas_price.to()), None),
+            (true, None, _) => (Some(provider.get_gas_price().await?), None),
+            (false, Some(max_fee_per_gas), Some(max_priority_fee_per_gas)) => (
+                None,
+                Some(Eip1559Estimation {
+                    max_fee_per_gas: max_fee_per_gas.to(),
+                    max_priority_fee_per_gas: max_priority_fee_per_gas.to(),
+                }),
+            ),
+            (false, _, _) => {
+                let mut fees = provider.estimate_eip1559_fees(None).await.wrap_err("Failed to estimate EIP1559 fees. This chain might not support EIP1559, try adding --legacy to your command.")?;
+
+                if let Some(gas_price) = args.with_gas_price {
+                    fees.max_fee_per_gas = gas_price.to();
+                }
+
+                if let Some(priority_gas_price) = args.priority_gas_price {
+                    fees.max_priority_fee_per_gas = priority_gas_price.to();
                 }
-                Ok(SendTransactionKind::Unlocked(*addr))
+
+                (None, Some(fees))
+            }
+        };
+
+        // Blob transactions additionally need a max fee per blob gas
+        let has_blobs = sequence
+            .transactions
+            .iter()
+            .skip(sequence.receipts.len())
+            .any(|tx| tx.tx().sidecar.is_some());
+        let blob_gas_price = match (has_blobs, args.blob_gas_price) {
+            (false, _) => None,
+            (true, _) if is_legacy => {
+                bail!("EIP-4844 blob transactions can't be sent as legacy transactions")
             }
-            Self::Raw(wallets) => {
-                if let Some(wallet) = wallets.get(addr) {
-                    Ok(SendTransactionKind::Raw(wallet))
-                } else {
-                    bail!("No matching signer for {:?} found", addr)
+            (true, Some(blob_gas_price)) => Some(blob_gas_price.to()),
+            (true, None) => Some(provider.get_blob_base_fee().await.wrap_err(
+                "Failed to get the blob base fee. This chain might not support EIP-4844.",
+            )?),
+        };
+
+        Ok(Self { gas_price, eip1559_fees, blob_gas_price })
+    }
+
+    /// Sets the fees of the transaction, unless the script set them with `vm.txFees`.
+    ///
+    /// Transactions whose type was set with `vm.txType` are given fees of that type.
+    pub fn apply(&self, tx: &mut WithOtherFields<TransactionRequest>) {
+        if tx.gas_price.is_none() && tx.max_fee_per_gas.is_none() {
+            let is_legacy = matches!(tx.transaction_type, Some(0 | 1));
+            match (self.gas_price, self.eip1559_fees) {
+                (Some(gas_price), _) if tx.transaction_type == Some(2) => {
+                    tx.set_max_priority_fee_per_gas(gas_price);
+                    tx.set_max_fee_per_gas(gas_price);
+                }
+                (Some(gas_price), _) => tx.set_gas_price(gas_price),
+                (None, Some(eip1559_fees)) if is_legacy => {
+                    tx.set_gas_price(eip1559_fees.max_fee_per_gas)
                 }
+                (None, Some(eip1559_fees)) => {
+                    tx.set_max_priority_fee_per_gas(eip1559_fees.max_priority_fee_per_gas);
+                    tx.set_max_fee_per_gas(eip1559_fees.max_fee_per_gas);
+                }
+                (None, None) => unreachable!("no gas price nor EIP1559 fees"),
             }
         }
-    }
 
-    /// How many signers are set
-    pub fn signers_count(&self) -> usize {
-        match self {
-            Self::Unlocked(addr) => addr.len(),
-            Self::Raw(signers) => signers.len(),
+        if tx.sidecar.is_some() && tx.max_fee_per_blob_gas.is_none() {
+            tx.max_fee_per_blob_gas = self.blob_gas_price;
         }
     }
 }
@@ -202,33 +288,53 @@ impl BundledState {
             );
         }
 
-        let send_kind = if self.args.unlocked {
-            SendTransactionsKind::Unlocked(required_addresses)
-        } else {
-            let signers = self.script_wallets.into_multi_wallet().into_signers()?;
-            let mut missing_addresses = Vec::new();
-
-            for addr in &required_addresses {
-                if !signers.contains_key(addr) {
-                    missing_addresses.push(addr);
-                }
-            }
-
+        let mut send_kind = TransactionSenders::default();
+        if self.args.unlocked {
+            send_kind.insert_all(required_addresses, Arc::new(UnlockedSender));
+        } else if let Some(remote) = self.args.sender.remote_signer()? {
+            let accounts = remote.accounts().await?;
+            let missing_addresses =
+                required_addresses.iter().filter(|addr| !accounts.contains(addr)).collect_vec();
             if !missing_addresses.is_empty() {
                 eyre::bail!(
-                    "No associated wallet for addresses: {:?}. Unlocked wallets: {:?}",
+                    "The remote signer can't sign for addresses: {:?}. Remote accounts: {:?}",
                     missing_addresses,
-                    signers.keys().collect::<Vec<_>>()
+                    accounts
                 );
             }
+            send_kind.insert_all(required_addresses, Arc::new(remote));
+        } else {
+            let signers = self.script_wallets.into_multi_wallet().into_signers()?;
+
+            if self.args.sender.bundler.is_some() {
+                // The senders are smart accounts, which must all be owned by the same wallet.
+                let Ok((_, owner)) = signers.into_iter().exactly_one() else {
+                    eyre::bail!("Sending through a bundler requires exactly one wallet");
+                };
+                let bundler = self.args.sender.bundler(owner)?.expect("bundler is set");
+                send_kind.insert_all(required_addresses, Arc::new(bundler));
+            } else {
+                let mut missing_addresses = Vec::new();
+
+                for addr in &required_addresses {
+                    if !signers.contains_key(addr) {
+                        missing_addresses.push(addr);
+                    }
+                }
 
-            let signers = signers
-                .into_iter()
-                .map(|(addr, signer)| (addr, EthereumWallet::new(signer)))
-                .collect();
+                if !missing_addresses.is_empty() {
+                    eyre::bail!(
+                        "No associated wallet for addresses: {:?}. Unlocked wallets: {:?}",
+                        missing_addresses,
+                        signers.keys().collect::<Vec<_>>()
+                    );
+                }
 
-            SendTransactionsKind::Raw(signers)
-        };
+                for (addr, signer) in signers {
+                    send_kind.insert_all([addr], Arc::new(WalletSender::new(signer)));
+                }
+            }
+        }
 
         let progress = ScriptProgress::default();
 
@@ -241,36 +347,7 @@ impl BundledState {
             let seq_progress = progress.get_sequence_progress(i, sequence);
 
             if already_broadcasted < sequence.transactions.len() {
-                let is_legacy = Chain::from(sequence.chain).is_legacy() || self.args.legacy;
-                // Make a one-time gas price estimation
-                let (gas_price, eip1559_fees) = match (
-                    is_legacy,
-                    self.args.with_gas_price,
-                    self.args.priority_gas_price,
-                ) {
-                    (true, Some(gas_price), _) => (Some(gas_price.to()), None),
-                    (true, None, _) => (Some(provider.get_gas_price().await?), None),
-                    (false, Some(max_fee_per_gas), Some(max_priority_fee_per_gas)) => (
-                        None,
-                        Some(Eip1559Estimation {
-                            max_fee_per_gas: max_fee_per_gas.to(),
-                            max_priority_fee_per_gas: max_priority_fee_per_gas.to(),
-                        }),
-                    ),
-                    (false, _, _) => {
-                        let mut fees = provider.estimate_eip1559_fees(None).await.wrap_err("Failed to estimate EIP1559 fees. This chain might not support EIP1559, try adding --legacy to your command.")?;
-
-                        if let Some(gas_price) = self.args.with_gas_price {
-                            fees.max_fee_per_gas = gas_price.to();
-                        }
-
-                        if let Some(priority_gas_price) = self.args.priority_gas_price {
-                            fees.max_priority_fee_per_gas = priority_gas_price.to();
-                        }
-
-                        (None, Some(fees))
-                    }
-                };
+                let fees = SequenceFees::estimate(&self.args, sequence, &provider).await?;
 
                 // Iterate through transactions, matching the `from` field with the associated
                 // wallet. Then send the transaction. Panics if we find a unknown `from`
@@ -293,13 +370,7 @@ impl BundledState {
                             tx.set_create();
                         }
 
-                        if let Some(gas_price) = gas_price {
-                            tx.set_gas_price(gas_price);
-                        } else {
-                            let eip1559_fees = eip1559_fees.expect("was set above");
-                            tx.set_max_priority_fee_per_gas(eip1559_fees.max_priority_fee_per_gas);
-                            tx.set_max_fee_per_gas(eip1559_fees.max_fee_per_gas);
-                        }
+                        fees.apply(&mut tx);
 
                         Ok((tx, kind, is_fixed_gas_limit))
                     })
@@ -316,6 +387,7 @@ impl BundledState {
                 let sequential_broadcast = estimate_via_rpc ||
                     self.args.slow ||
                     send_kind.signers_count() != 1 ||
+                    !send_kind.use_sender_nonces() ||
                     !has_batch_support(sequence.chain);
 
                 // We send transactions and wait for receipts in batches.
@@ -334,7 +406,7 @@ impl BundledState {
                         let fut = send_transaction(
                             provider.clone(),
                             tx.clone(),
-                            kind.clone(),
+                            *kind,
                             sequential_broadcast,
                             *is_fixed_gas_limit,
                             estimate_via_rpc,
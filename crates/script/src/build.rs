@@ -194,6 +194,7 @@ impl PreprocessedState {
 
         let output = ProjectCompiler::new()
             .quiet_if(args.opts.silent)
+            .deny_warnings(script_config.config.deny_warnings_policy())
             .files(sources_to_compile)
             .compile(&project)?;
 
@@ -0,0 +1,338 @@
+//! Signing of the script transactions in an external ceremony.
+//!
+//! `--export-unsigned <DIR>` writes the transactions that would be broadcast to
+//! `<DIR>/<CHAIN>.json` as EIP-2718 encoded signing payloads, along with a human-readable summary
+//! in `<DIR>/<CHAIN>.txt`. The signers fill in the `signature` of every transaction, and
+//! `--import-signed <DIR>` broadcasts the signed transactions.
+
+use crate::{
+    broadcast::{estimate_gas, BundledState, SequenceFees},
+    progress::ScriptProgress,
+    sequence::ScriptSequence,
+    verify::BroadcastedState,
+};
+use alloy_consensus::{SignableTransaction, TxEnvelope, TypedTransaction};
+use alloy_eips::eip2718::Encodable2718;
+use alloy_network::TransactionBuilder;
+use alloy_primitives::{keccak256, Address, Bytes, Signature, TxKind, B256, U256};
+use alloy_provider::Provider;
+use alloy_rpc_types::TransactionRequest;
+use alloy_serde::WithOtherFields;
+use eyre::{bail, Result, WrapErr};
+use foundry_cli::utils::has_different_gas_calc;
+use foundry_common::{fs, provider::try_get_http_provider, shell};
+use serde::{Deserialize, Serialize};
+use std::{fmt::Write, path::Path, sync::Arc};
+
+/// A transaction of the script, ready to be signed.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnsignedTransaction {
+    /// The index of the transaction in the sequence.
+    pub index: usize,
+    pub chain_id: u64,
+    /// The EIP-2718 type of the transaction.
+    pub tx_type: u8,
+    pub from: Address,
+    pub to: Option<Address>,
+    pub value: U256,
+    pub nonce: u64,
+    /// The contract and function called, if known.
+    pub description: String,
+    /// The EIP-2718 encoded unsigned transaction.
+    pub payload: Bytes,
+    /// The hash to sign, the keccak256 hash of the payload.
+    pub signing_hash: B256,
+    /// The request the payload was built from.
+    pub request: WithOtherFields<TransactionRequest>,
+    /// The 65-byte signature of the signing hash, filled in by the signer.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<Bytes>,
+}
+
+impl UnsignedTransaction {
+    /// Builds the transaction from a request with all fields required for signing.
+    fn new(
+        index: usize,
+        description: String,
+        request: WithOtherFields<TransactionRequest>,
+    ) -> Result<Self> {
+        let tx = build_typed_tx(&request, index)?;
+        let payload = signing_payload(&tx);
+        Ok(Self {
+            index,
+            chain_id: request.chain_id.unwrap_or_default(),
+            tx_type: tx.tx_type() as u8,
+            from: request.from.unwrap_or_default(),
+            to: request.to.and_then(|to| to.to().copied()),
+            value: request.value.unwrap_or_default(),
+            nonce: request.nonce.unwrap_or_default(),
+            description,
+            signing_hash: keccak256(&payload),
+            payload,
+            request,
+            signature: None,
+        })
+    }
+
+    /// Returns the signed transaction, checking that it was signed by its sender and that the
+    /// payload wasn't modified.
+    fn into_signed(self) -> Result<TxEnvelope> {
+        let index = self.index;
+        let Some(signature) = &self.signature else {
+            bail!("Transaction {index} on chain {} has no signature.", self.chain_id)
+        };
+        let signature = Signature::try_from(&signature[..])
+            .wrap_err_with(|| format!("Invalid signature of transaction {index}."))?;
+
+        let tx = build_typed_tx(&self.request, index)?;
+        if keccak256(signing_payload(&tx)) != self.signing_hash {
+            bail!("The request of transaction {index} does not match its signing hash.")
+        }
+        let signer = signature.recover_address_from_prehash(&self.signing_hash)?;
+        if signer != self.from {
+            bail!("Transaction {index} was signed by {signer} instead of {}.", self.from)
+        }
+
+        Ok(match tx {
+            TypedTransaction::Legacy(tx) => tx.into_signed(signature).into(),
+            TypedTransaction::Eip2930(tx) => tx.into_signed(signature).into(),
+            TypedTransaction::Eip1559(tx) => tx.into_signed(signature).into(),
+            TypedTransaction::Eip4844(tx) => tx.into_signed(signature).into(),
+        })
+    }
+
+    /// Returns whether the transaction is the one the script is about to send.
+    fn matches(&self, request: &TransactionRequest) -> bool {
+        self.request.from == request.from &&
+            self.request.nonce == request.nonce &&
+            self.request.value.unwrap_or_default() == request.value.unwrap_or_default() &&
+            self.request.input.input() == request.input.input()
+    }
+}
+
+fn build_typed_tx(
+    request: &WithOtherFields<TransactionRequest>,
+    index: usize,
+) -> Result<TypedTransaction> {
+    request
+        .inner
+        .clone()
+        .build_typed_tx()
+        .map_err(|_| eyre::eyre!("Transaction {index} is missing fields required for signing."))
+}
+
+/// Returns the EIP-2718 encoded payload to sign.
+fn signing_payload(tx: &TypedTransaction) -> Bytes {
+    let mut payload = Vec::new();
+    match tx {
+        TypedTransaction::Legacy(tx) => tx.encode_for_signing(&mut payload),
+        TypedTransaction::Eip2930(tx) => tx.encode_for_signing(&mut payload),
+        TypedTransaction::Eip1559(tx) => tx.encode_for_signing(&mut payload),
+        TypedTransaction::Eip4844(tx) => tx.encode_for_signing(&mut payload),
+    }
+    payload.into()
+}
+
+/// Returns the path of the transactions of a chain in the ceremony directory.
+fn transactions_path(dir: &Path, chain: u64) -> std::path::PathBuf {
+    dir.join(format!("{chain}.json"))
+}
+
+/// Returns the human-readable summary of the transactions.
+fn summary(transactions: &[UnsignedTransaction]) -> String {
+    let mut s = String::new();
+    for tx in transactions {
+        let to = tx.to.map_or_else(|| "CREATE".to_string(), |to| to.to_string());
+        let _ = writeln!(s, "#{} (chain {}, type {})", tx.index, tx.chain_id, tx.tx_type);
+        let _ = writeln!(s, "  from:         {}", tx.from);
+        let _ = writeln!(s, "  to:           {to}");
+        let _ = writeln!(s, "  nonce:        {}", tx.nonce);
+        let _ = writeln!(s, "  value:        {}", tx.value);
+        if !tx.description.is_empty() {
+            let _ = writeln!(s, "  call:         {}", tx.description);
+        }
+        let _ = writeln!(s, "  signing hash: {}", tx.signing_hash);
+        s.push('\n');
+    }
+    s
+}
+
+/// Returns a description of the contract and function signature the transaction calls, and the
+/// arguments of the call.
+pub(crate) fn describe(
+    contract_name: Option<&str>,
+    function: Option<&str>,
+    arguments: &[String],
+) -> String {
+    let mut description = match (
+        contract_name.filter(|name| !name.is_empty()),
+        function.filter(|function| !function.is_empty()),
+    ) {
+        (Some(contract), Some(function)) => format!("{contract}::{function}"),
+        (Some(name), None) | (None, Some(name)) => name.to_string(),
+        (None, None) => return String::new(),
+    };
+    if !arguments.is_empty() {
+        let _ = write!(description, " with [{}]", arguments.join(", "));
+    }
+    description
+}
+
+impl BundledState {
+    /// Writes the transactions that haven't been broadcasted yet to `dir`, ready to be signed.
+    pub async fn export_unsigned(self, dir: &Path) -> Result<()> {
+        fs::create_dir_all(dir)?;
+
+        for sequence in self.sequence.sequences() {
+            let provider = Arc::new(try_get_http_provider(sequence.rpc_url())?);
+            let fees = SequenceFees::estimate(&self.args, sequence, &provider).await?;
+            let estimate_via_rpc =
+                has_different_gas_calc(sequence.chain) || self.args.skip_simulation;
+
+            let mut transactions = Vec::new();
+            for (index, tx_with_metadata) in
+                sequence.transactions.iter().enumerate().skip(sequence.receipts.len())
+            {
+                let mut tx = tx_with_metadata.tx().clone();
+                tx.set_chain_id(sequence.chain);
+                if tx.to().is_none() {
+                    tx.set_create();
+                }
+                fees.apply(&mut tx);
+                if !tx_with_metadata.is_fixed_gas_limit && estimate_via_rpc {
+                    estimate_gas(&mut tx, &provider, self.args.gas_estimate_multiplier).await?;
+                }
+
+                let description = describe(
+                    tx_with_metadata.contract_name.as_deref(),
+                    tx_with_metadata.function.as_deref(),
+                    tx_with_metadata.arguments.as_deref().unwrap_or_default(),
+                );
+                transactions.push(UnsignedTransaction::new(index, description, tx)?);
+            }
+
+            let path = transactions_path(dir, sequence.chain);
+            fs::write_json_file(&path, &transactions)?;
+            fs::write(path.with_extension("txt"), summary(&transactions))?;
+            shell::println(format!(
+                "Exported {} unsigned transactions for chain {} to {}",
+                transactions.len(),
+                sequence.chain,
+                path.display()
+            ))?;
+        }
+
+        Ok(())
+    }
+
+    /// Broadcasts the transactions signed in an external ceremony, read from `dir`.
+    pub async fn broadcast_signed(mut self, dir: &Path) -> Result<BroadcastedState> {
+        let progress = ScriptProgress::default();
+
+        for i in 0..self.sequence.sequences().len() {
+            let sequence = &self.sequence.sequences()[i];
+            let signed = signed_transactions(dir, sequence)?;
+            let provider = Arc::new(try_get_http_provider(sequence.rpc_url())?);
+
+            let seq_progress = progress.get_sequence_progress(i, sequence);
+            seq_progress.inner.write().set_status("Sending signed transactions");
+            for (index, tx) in signed {
+                let pending = provider
+                    .send_raw_transaction(tx.encoded_2718().as_ref())
+                    .await
+                    .wrap_err("Failed to send transaction")?;
+                let tx_hash = *pending.tx_hash();
+
+                self.sequence.sequences_mut()[i].add_pending(index, tx_hash);
+                self.sequence.save(true, false)?;
+                seq_progress.inner.write().tx_sent(tx_hash);
+            }
+
+            let sequence = &mut self.sequence.sequences_mut()[i];
+            progress.wait_for_pending(i, sequence, &provider).await?;
+            self.sequence.save(true, false)?;
+            seq_progress.inner.write().finish();
+        }
+
+        shell::println("\n\n==========================")?;
+        shell::println("\nONCHAIN EXECUTION COMPLETE & SUCCESSFUL.")?;
+
+        Ok(BroadcastedState {
+            args: self.args,
+            script_config: self.script_config,
+            build_data: self.build_data,
+            sequence: self.sequence,
+        })
+    }
+}
+
+/// Reads the signed transactions of the sequence that haven't been broadcasted yet, checking
+/// that they are the transactions of the script.
+fn signed_transactions(dir: &Path, sequence: &ScriptSequence) -> Result<Vec<(usize, TxEnvelope)>> {
+    let path = transactions_path(dir, sequence.chain);
+    let exported: Vec<UnsignedTransaction> = fs::read_json_file(&path)?;
+
+    let mut signed = Vec::new();
+    for (index, tx_with_metadata) in
+        sequence.transactions.iter().enumerate().skip(sequence.receipts.len())
+    {
+        let Some(tx) = exported.iter().find(|tx| tx.index == index) else {
+            bail!(
+                "Transaction {index} for chain {} is missing from {}.",
+                sequence.chain,
+                path.display()
+            )
+        };
+        if tx.chain_id != sequence.chain || !tx.matches(tx_with_metadata.tx()) {
+            bail!(
+                "Transaction {index} in {} differs from the transaction of the script. Export the \
+                 transactions again.",
+                path.display()
+            )
+        }
+        if let Some(TxKind::Call(to)) = tx_with_metadata.tx().to {
+            if tx.to != Some(to) {
+                bail!("Transaction {index} in {} has a different recipient.", path.display())
+            }
+        }
+        signed.push((index, tx.clone().into_signed()?));
+    }
+    Ok(signed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_signer::SignerSync;
+    use alloy_signer_local::PrivateKeySigner;
+
+    fn request(from: Address) -> WithOtherFields<TransactionRequest> {
+        let mut tx = WithOtherFields::new(TransactionRequest::default());
+        tx.set_from(from);
+        tx.set_to(Address::with_last_byte(1));
+        tx.set_nonce(3);
+        tx.set_chain_id(1);
+        tx.set_gas_limit(21000);
+        tx.set_max_fee_per_gas(100);
+        tx.set_max_priority_fee_per_gas(1);
+        tx
+    }
+
+    #[test]
+    fn signs_exported_transaction() {
+        let signer = PrivateKeySigner::random();
+        let tx = request(signer.address());
+        let mut unsigned = UnsignedTransaction::new(0, String::new(), tx.clone()).unwrap();
+        assert_eq!(unsigned.tx_type, 2);
+        assert!(unsigned.matches(&tx));
+
+        let signature = signer.sign_hash_sync(&unsigned.signing_hash).unwrap();
+        unsigned.signature = Some(signature.as_bytes().into());
+        assert!(unsigned.clone().into_signed().is_ok());
+
+        // A modified request no longer matches the signed hash.
+        unsigned.request.set_nonce(4);
+        assert!(unsigned.into_signed().is_err());
+    }
+}
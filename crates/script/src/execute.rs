@@ -18,7 +18,7 @@ use foundry_common::{
     provider::get_http_provider,
     shell, ContractsByArtifact,
 };
-use foundry_config::{Config, NamedChain};
+use foundry_config::NamedChain;
 use foundry_debugger::Debugger;
 use foundry_evm::{
     decode::decode_console_logs,
@@ -84,6 +84,17 @@ impl LinkedState {
     }
 }
 
+/// A script the target script depends on, declared by its `dependsOn()` function.
+#[derive(Clone, Debug)]
+pub struct ScriptDependency {
+    /// Name of the script contract.
+    pub name: String,
+    /// Linked creation code of the script contract.
+    pub bytecode: Bytes,
+    /// Whether the script has a `setUp()` function.
+    pub setup: bool,
+}
+
 /// Same as [LinkedState], but also contains [ExecutionData].
 #[derive(Debug)]
 pub struct PreExecutionState {
@@ -138,8 +149,10 @@ impl PreExecutionState {
 
     /// Executes the script using the provided runner and returns the [ScriptResult].
     pub async fn execute_with_runner(&self, runner: &mut ScriptRunner) -> Result<ScriptResult> {
+        let dependencies = self.resolve_dependencies(runner)?;
         let (address, mut setup_result) = runner.setup(
             &self.build_data.predeploy_libraries,
+            &dependencies,
             self.execution_data.bytecode.clone(),
             needs_setup(&self.execution_data.abi),
             self.script_config.sender_nonce,
@@ -172,6 +185,70 @@ impl PreExecutionState {
         Ok(setup_result)
     }
 
+    /// Resolves the scripts the target script depends on, transitively, in the order they must be
+    /// executed in: every script comes after the scripts it depends on.
+    fn resolve_dependencies(&self, runner: &ScriptRunner) -> Result<Vec<ScriptDependency>> {
+        fn visit(
+            state: &PreExecutionState,
+            runner: &ScriptRunner,
+            name: &str,
+            bytecode: &Bytes,
+            abi: &JsonAbi,
+            path: &mut Vec<String>,
+            resolved: &mut Vec<ScriptDependency>,
+        ) -> Result<()> {
+            for dependency in runner.dependencies(bytecode.clone(), abi)? {
+                let (id, contract) = state
+                    .build_data
+                    .known_contracts
+                    .find_by_name_or_identifier(&dependency)?
+                    .ok_or_else(|| {
+                        eyre::eyre!("script {name} depends on unknown contract {dependency}")
+                    })?;
+                let dependency = &id.name;
+                if path.contains(dependency) {
+                    eyre::bail!(
+                        "circular script dependency: {} -> {dependency}",
+                        path.join(" -> ")
+                    );
+                }
+                if resolved.iter().any(|resolved| resolved.name == *dependency) {
+                    continue
+                }
+
+                let bytecode = contract
+                    .bytecode()
+                    .ok_or_else(|| eyre::eyre!("script {dependency} has no bytecode"))?;
+                ensure_clean_constructor(&contract.abi)?;
+
+                path.push(dependency.clone());
+                visit(state, runner, dependency, bytecode, &contract.abi, path, resolved)?;
+                path.pop();
+
+                resolved.push(ScriptDependency {
+                    name: dependency.clone(),
+                    bytecode: bytecode.clone(),
+                    setup: needs_setup(&contract.abi),
+                });
+            }
+            Ok(())
+        }
+
+        let name = &self.build_data.build_data.target.name;
+        let mut path = vec![name.clone()];
+        let mut resolved = Vec::new();
+        visit(
+            self,
+            runner,
+            name,
+            &self.execution_data.bytecode,
+            &self.execution_data.abi,
+            &mut path,
+            &mut resolved,
+        )?;
+        Ok(resolved)
+    }
+
     /// It finds the deployer from the running script and uses it to predeploy libraries.
     ///
     /// If there are multiple candidate addresses, it skips everything and lets `--sender` deploy
@@ -287,16 +364,8 @@ impl ExecutedState {
         let txs = self.execution_result.transactions.clone().unwrap_or_default();
         let rpc_data = RpcData::from_transactions(&txs);
 
-        if rpc_data.is_multi_chain() {
-            shell::eprintln(format!(
-                "{}",
-                "Multi chain deployment is still under development. Use with caution.".yellow()
-            ))?;
-            if !self.build_data.libraries.is_empty() {
-                eyre::bail!(
-                    "Multi chain deployment does not support library linking at the moment."
-                )
-            }
+        if rpc_data.is_multi_chain() && !self.build_data.libraries.is_empty() {
+            eyre::bail!("Multi chain deployment does not support library linking at the moment.")
         }
         rpc_data.check_shanghai_support().await?;
 
@@ -320,9 +389,8 @@ impl ExecutedState {
             .with_labels(self.execution_result.labeled_addresses.clone())
             .with_verbosity(self.script_config.evm_opts.verbosity)
             .with_known_contracts(known_contracts)
-            .with_signature_identifier(SignaturesIdentifier::new(
-                Config::foundry_cache_dir(),
-                self.script_config.config.offline,
+            .with_signature_identifier(SignaturesIdentifier::from_config(
+                &self.script_config.config,
             )?)
             .build();
 
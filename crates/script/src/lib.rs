@@ -43,17 +43,20 @@ use foundry_evm::{
         CheatsConfig,
     },
     opts::EvmOpts,
-    traces::Traces,
+    traces::{CallTraceDecoder, Traces},
 };
-use foundry_wallets::MultiWalletOpts;
+use foundry_wallets::{MultiWalletOpts, SenderOpts};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::{collections::HashMap, path::PathBuf};
 use yansi::Paint;
 
 mod broadcast;
 mod build;
+mod ceremony;
 mod execute;
+mod manifest;
 mod multi_sequence;
+mod plan;
 mod progress;
 mod providers;
 mod receipts;
@@ -63,6 +66,9 @@ mod simulate;
 mod transaction;
 mod verify;
 
+pub use sequence::ScriptSequence;
+pub use transaction::TransactionWithMetadata;
+
 // Loads project's figment and merges the build cli arguments into it
 foundry_config::merge_impl_figment_convert!(ScriptArgs, opts, evm_opts);
 
@@ -73,7 +79,11 @@ pub struct ScriptArgs {
     ///
     /// If multiple contracts exist in the same file you must specify the target contract with
     /// --target-contract.
-    #[arg(value_hint = ValueHint::FilePath)]
+    #[arg(
+        value_hint = ValueHint::FilePath,
+        required_unless_present = "manifest",
+        default_value = "",
+    )]
     pub path: String,
 
     /// Arguments to pass to the script function.
@@ -83,6 +93,19 @@ pub struct ScriptArgs {
     #[arg(long, visible_alias = "tc", value_name = "CONTRACT_NAME")]
     pub target_contract: Option<String>,
 
+    /// Deploys the contracts of a TOML or JSON manifest instead of running a script.
+    ///
+    /// The manifest lists the contracts to deploy in `[[deploy]]` entries, in order, each with a
+    /// `name`, the `contract` to deploy, its constructor `args`, and the `calls` to make after
+    /// deploying it. Arguments and call targets reference prior deployments as `@name`.
+    #[arg(
+        long,
+        value_name = "PATH",
+        value_hint = ValueHint::FilePath,
+        conflicts_with_all = ["path", "target_contract", "sig"],
+    )]
+    pub manifest: Option<PathBuf>,
+
     /// The signature of the function you want to call in the contract, or raw calldata.
     #[arg(long, short, default_value = "run()")]
     pub sig: String,
@@ -106,6 +129,48 @@ pub struct ScriptArgs {
     #[arg(long)]
     pub broadcast: bool,
 
+    /// Broadcasts the transactions without asking for approval of the broadcast plan.
+    #[arg(long)]
+    pub yes: bool,
+
+    /// Writes the broadcast plan to the given file as JSON.
+    #[arg(long, value_name = "PATH", value_hint = ValueHint::FilePath, requires = "broadcast")]
+    pub plan_json: Option<PathBuf>,
+
+    /// Broadcasts even if the checks performed before broadcasting fail.
+    ///
+    /// By default, the addresses of all created contracts are predicted and checked to be free,
+    /// and the balances of all senders are checked to cover their transactions on all chains. The
+    /// plan is still shown.
+    #[arg(long)]
+    pub skip_preconditions: bool,
+
+    /// Writes the transactions to the given directory for signing in an external ceremony, instead
+    /// of broadcasting them.
+    ///
+    /// For every chain, `<CHAIN>.json` holds the EIP-2718 encoded unsigned transactions and their
+    /// signing hashes, and `<CHAIN>.txt` a human-readable summary of them.
+    #[arg(
+        long,
+        value_name = "DIR",
+        value_hint = ValueHint::DirPath,
+        conflicts_with_all = &["broadcast", "resume"],
+    )]
+    pub export_unsigned: Option<PathBuf>,
+
+    /// Broadcasts the transactions signed in an external ceremony.
+    ///
+    /// The directory must hold the files written by `--export-unsigned`, with the `signature` of
+    /// every transaction filled in. Requires `--broadcast`.
+    #[arg(
+        long,
+        value_name = "DIR",
+        value_hint = ValueHint::DirPath,
+        requires = "broadcast",
+        conflicts_with_all = &["export_unsigned", "resume", "unlocked"],
+    )]
+    pub import_signed: Option<PathBuf>,
+
     /// Batch size of transactions.
     ///
     /// This is ignored and set to 1 if batching is not available or `--slow` is enabled.
@@ -123,7 +188,16 @@ pub struct ScriptArgs {
     /// Send via `eth_sendTransaction` using the `--from` argument or `$ETH_FROM` as sender
     #[arg(
         long,
-        conflicts_with_all = &["private_key", "private_keys", "froms", "ledger", "trezor", "aws"],
+        conflicts_with_all = &[
+            "private_key",
+            "private_keys",
+            "froms",
+            "ledger",
+            "trezor",
+            "aws",
+            "remote_signer",
+            "bundler",
+        ],
     )]
     pub unlocked: bool,
 
@@ -178,12 +252,26 @@ pub struct ScriptArgs {
     )]
     pub with_gas_price: Option<U256>,
 
+    /// Max fee per blob gas for EIP-4844 blob transactions.
+    ///
+    /// If not set, the current blob base fee is used.
+    #[arg(
+        long,
+        env = "ETH_BLOB_GAS_PRICE",
+        value_parser = foundry_cli::utils::parse_ether_value,
+        value_name = "PRICE",
+    )]
+    pub blob_gas_price: Option<U256>,
+
     #[command(flatten)]
     pub opts: CoreBuildArgs,
 
     #[command(flatten)]
     pub wallets: MultiWalletOpts,
 
+    #[command(flatten)]
+    pub sender: SenderOpts,
+
     #[command(flatten)]
     pub evm_opts: EvmArgs,
 
@@ -195,12 +283,18 @@ pub struct ScriptArgs {
 }
 
 impl ScriptArgs {
-    async fn preprocess(self) -> Result<PreprocessedState> {
+    async fn preprocess(mut self) -> Result<PreprocessedState> {
         let script_wallets =
             ScriptWallets::new(self.wallets.get_multi_wallet().await?, self.evm_opts.sender);
 
         let (config, mut evm_opts) = self.load_config_and_evm_opts_emit_warnings()?;
 
+        if let Some(path) = &self.manifest {
+            let script = manifest::Manifest::read(path)?.write_script(&config, path)?;
+            self.path = script.to_string_lossy().into_owned();
+            self.target_contract = Some(manifest::MANIFEST_CONTRACT.to_string());
+        }
+
         if let Some(sender) = self.maybe_load_private_key()? {
             evm_opts.sender = sender;
         }
@@ -210,6 +304,28 @@ impl ScriptArgs {
         Ok(PreprocessedState { args: self, script_config, script_wallets })
     }
 
+    /// Executes the script locally and returns the traces of the execution, along with the
+    /// decoder that identified the addresses in them.
+    ///
+    /// The transactions of the script are neither simulated nor broadcast. As with `--debug`, the
+    /// traces record the executed steps.
+    pub async fn execute_traces(mut self) -> Result<(Traces, CallTraceDecoder)> {
+        self.debug = true;
+        let state = self
+            .preprocess()
+            .await?
+            .compile()?
+            .link()
+            .await?
+            .prepare_execution()
+            .await?
+            .execute()
+            .await?
+            .prepare_simulation()
+            .await?;
+        Ok((state.execution_result.traces, state.execution_artifacts.decoder))
+    }
+
     /// Executes the script
     pub async fn run_script(self) -> Result<()> {
         trace!(target: "script", "executing script command");
@@ -268,6 +384,10 @@ impl ScriptArgs {
             pre_simulation.fill_metadata().await?.bundle().await?
         };
 
+        if let Some(dir) = bundled.args.export_unsigned.clone() {
+            return bundled.export_unsigned(&dir).await;
+        }
+
         // Exit early in case user didn't provide any broadcast/verify related flags.
         if !bundled.args.broadcast && !bundled.args.resume && !bundled.args.verify {
             shell::println("\nSIMULATION COMPLETE. To broadcast these transactions, add --broadcast and wallet configuration(s) to the previous command. See forge script --help for more.")?;
@@ -279,8 +399,16 @@ impl ScriptArgs {
             bundled.verify_preflight_check()?;
         }
 
-        // Wait for pending txes and broadcast others.
-        let broadcasted = bundled.wait_for_pending().await?.broadcast().await?;
+        // Wait for pending txes, check that the remaining ones can be broadcasted and broadcast
+        // them.
+        let bundled = bundled.wait_for_pending().await?;
+        if bundled.args.broadcast {
+            bundled.review_plan().await?;
+        }
+        let broadcasted = match bundled.args.import_signed.clone() {
+            Some(dir) => bundled.broadcast_signed(&dir).await?,
+            None => bundled.broadcast().await?,
+        };
 
         if broadcasted.args.verify {
             broadcasted.verify().await?;
@@ -593,6 +721,7 @@ impl ScriptConfig {
                         )
                         .into(),
                     )
+                    .historical_block_hashes(self.config.historical_block_hashes)
                     .enable_isolation(self.evm_opts.isolate)
             });
         }
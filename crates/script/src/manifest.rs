@@ -0,0 +1,446 @@
+//! Declarative deployments, run by `forge script --manifest`.
+//!
+//! A manifest lists the contracts to deploy, in order, with their constructor arguments and the
+//! calls to make after deploying them. Arguments may reference the address of a prior deployment
+//! with `@name`. The manifest is compiled into a script with the encoded deployments, which then
+//! goes through the same simulation and broadcast as any other script.
+
+use alloy_dyn_abi::{DynSolValue, JsonAbiExt};
+use alloy_primitives::{hex, keccak256, Address, U256};
+use eyre::{OptionExt, Result, WrapErr};
+use foundry_common::{
+    abi::{coerce_value, encode_function_args, get_func},
+    compile::ProjectCompiler,
+    fs, ContractsByArtifact,
+};
+use foundry_config::Config;
+use serde::Deserialize;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+
+/// The name of the script contract generated from a manifest.
+pub const MANIFEST_CONTRACT: &str = "Manifest";
+
+/// A deployment manifest, in TOML or JSON.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Manifest {
+    /// The contracts to deploy, in order.
+    #[serde(default)]
+    pub deploy: Vec<Deployment>,
+}
+
+/// A contract deployment of a manifest.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Deployment {
+    /// The name later arguments reference the deployment by, which it's also labeled with.
+    pub name: String,
+    /// The name or identifier of the contract, e.g. `Token` or `src/Token.sol:Token`.
+    pub contract: String,
+    /// The constructor arguments.
+    #[serde(default)]
+    pub args: Vec<Value>,
+    /// The ether sent with the deployment.
+    #[serde(default)]
+    pub value: Option<Value>,
+    /// The calls made right after the deployment.
+    #[serde(default)]
+    pub calls: Vec<Call>,
+}
+
+/// A call made after a deployment.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Call {
+    /// The called account, an address or a reference to a deployment. Defaults to the deployment
+    /// the call is made after.
+    #[serde(default)]
+    pub target: Option<String>,
+    /// The signature of the called function, e.g. `initialize(address,uint256)`.
+    pub function: String,
+    /// The arguments of the call.
+    #[serde(default)]
+    pub args: Vec<Value>,
+    /// The ether sent with the call.
+    #[serde(default)]
+    pub value: Option<Value>,
+}
+
+impl Manifest {
+    /// Reads a manifest, as JSON if the file has a `.json` extension and as TOML otherwise.
+    pub fn read(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)?;
+        let manifest = if path.extension().is_some_and(|ext| ext == "json") {
+            serde_json::from_str(&content).map_err(eyre::Error::from)
+        } else {
+            toml::from_str(&content).map_err(eyre::Error::from)
+        };
+        manifest.wrap_err_with(|| format!("invalid manifest: {}", path.display()))
+    }
+
+    /// Compiles the project and writes the script running the manifest to the cache.
+    ///
+    /// Returns the path of the script.
+    pub fn write_script(&self, config: &Config, path: &Path) -> Result<PathBuf> {
+        let project = config.project()?;
+        let output = ProjectCompiler::new().quiet(true).compile(&project)?;
+        let contracts = ContractsByArtifact::new(
+            output.artifact_ids().map(|(id, artifact)| (id, artifact.clone().into())),
+        );
+        let steps = self.steps(&contracts)?;
+
+        let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+        let script = config.cache_path.join("manifests").join(format!("{stem}.s.sol"));
+        fs::create_dir_all(script.parent().unwrap())?;
+        fs::write(&script, script_source(path, &steps))?;
+        Ok(script)
+    }
+
+    /// Returns the steps of the manifest: its deployments, each followed by its calls.
+    fn steps(&self, contracts: &ContractsByArtifact) -> Result<Vec<Step>> {
+        eyre::ensure!(!self.deploy.is_empty(), "the manifest has no deployments");
+
+        // The index of the step of every deployment, by name.
+        let mut deployments = Vec::<(&str, usize)>::new();
+        let mut steps = Vec::new();
+        for deployment in &self.deploy {
+            let name = deployment.name.as_str();
+            eyre::ensure!(
+                !name.is_empty() && !name.starts_with('@'),
+                "invalid deployment name `{name}`"
+            );
+            eyre::ensure!(
+                deployments.iter().all(|(other, _)| *other != name),
+                "duplicate deployment `{name}`"
+            );
+
+            let (_, contract) = contracts
+                .find_by_name_or_identifier(&deployment.contract)?
+                .ok_or_else(|| eyre::eyre!("contract `{}` not found", deployment.contract))?;
+            let bytecode = contract.bytecode().ok_or_else(|| {
+                eyre::eyre!(
+                    "contract `{}` has no bytecode, or links libraries, which manifests don't \
+                     support",
+                    deployment.contract
+                )
+            })?;
+            let constructor_args = args(&deployment.args, &deployments)?;
+            let mut data = bytecode.to_vec();
+            match &contract.abi.constructor {
+                Some(constructor) => {
+                    eyre::ensure!(
+                        constructor.inputs.len() == constructor_args.len(),
+                        "`{name}`: {} expects {} constructor arguments, got {}",
+                        deployment.contract,
+                        constructor.inputs.len(),
+                        constructor_args.len()
+                    );
+                    let values = std::iter::zip(&constructor.inputs, &constructor_args)
+                        .map(|(input, arg)| coerce_value(&input.selector_type(), arg))
+                        .collect::<Result<Vec<_>>>()
+                        .wrap_err_with(|| format!("`{name}`: invalid constructor arguments"))?;
+                    data.extend(constructor.abi_encode_input(&values)?);
+                }
+                None => eyre::ensure!(
+                    constructor_args.is_empty(),
+                    "`{name}`: {} has no constructor arguments",
+                    deployment.contract
+                ),
+            }
+            let index = steps.len();
+            steps.push(Step {
+                name: name.to_string(),
+                target: Address::ZERO,
+                target_deployment: None,
+                value: value(deployment.value.as_ref())?,
+                patches: patches(&data, bytecode.len(), &deployments),
+                data,
+            });
+            deployments.push((name, index));
+
+            for call in &deployment.calls {
+                let (target, target_deployment) = match call.target.as_deref() {
+                    None => (Address::ZERO, Some(index)),
+                    Some(target) => match target.strip_prefix('@') {
+                        Some(reference) => (Address::ZERO, Some(find(&deployments, reference)?)),
+                        None => (
+                            target
+                                .parse()
+                                .wrap_err_with(|| format!("invalid call target `{target}`"))?,
+                            None,
+                        ),
+                    },
+                };
+                let function = get_func(&call.function)?;
+                let call_args = args(&call.args, &deployments)?;
+                eyre::ensure!(
+                    function.inputs.len() == call_args.len(),
+                    "`{name}`: {} expects {} arguments, got {}",
+                    call.function,
+                    function.inputs.len(),
+                    call_args.len()
+                );
+                let data = encode_function_args(&function, &call_args).wrap_err_with(|| {
+                    format!("`{name}`: invalid arguments of {}", call.function)
+                })?;
+                steps.push(Step {
+                    name: String::new(),
+                    target,
+                    target_deployment,
+                    value: value(call.value.as_ref())?,
+                    patches: patches(&data, 4, &deployments),
+                    data,
+                });
+            }
+        }
+        Ok(steps)
+    }
+}
+
+/// A deployment or call made by the script running a manifest.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Step {
+    /// The name of the deployment, empty for calls.
+    name: String,
+    target: Address,
+    /// The step of the deployment called, which replaces `target`.
+    target_deployment: Option<usize>,
+    value: U256,
+    /// The creation code and constructor arguments of a deployment, or the calldata of a call.
+    data: Vec<u8>,
+    /// The offsets of the words of `data` to replace with the address of the deployment of a
+    /// step.
+    patches: Vec<(usize, usize)>,
+}
+
+impl Step {
+    fn to_value(&self) -> DynSolValue {
+        let patches = self
+            .patches
+            .iter()
+            .map(|&(offset, step)| {
+                DynSolValue::Tuple(vec![
+                    DynSolValue::Uint(U256::from(offset), 256),
+                    DynSolValue::Uint(U256::from(step), 256),
+                ])
+            })
+            .collect();
+        DynSolValue::Tuple(vec![
+            DynSolValue::String(self.name.clone()),
+            DynSolValue::Address(self.target),
+            // 1-based, zero if the target isn't a deployment.
+            DynSolValue::Uint(U256::from(self.target_deployment.map_or(0, |step| step + 1)), 256),
+            DynSolValue::Uint(self.value, 256),
+            DynSolValue::Bytes(self.data.clone()),
+            DynSolValue::Array(patches),
+        ])
+    }
+}
+
+/// Returns the address standing in for the deployment with the given name until it's deployed.
+fn placeholder(name: &str) -> Address {
+    Address::from_word(keccak256(format!("forge manifest deployment: {name}")))
+}
+
+/// Returns the step of the deployment with the given name.
+fn find(deployments: &[(&str, usize)], name: &str) -> Result<usize> {
+    deployments.iter().find(|(other, _)| *other == name).map(|(_, step)| *step).ok_or_else(|| {
+        eyre::eyre!("unknown deployment `@{name}`, deployments can only reference prior ones")
+    })
+}
+
+/// Returns the offsets of the ABI-encoded words of `data`, starting at `start`, that are the
+/// placeholders of deployments, along with the steps of those deployments.
+fn patches(data: &[u8], start: usize, deployments: &[(&str, usize)]) -> Vec<(usize, usize)> {
+    let placeholders = deployments
+        .iter()
+        .map(|(name, step)| (placeholder(name).into_word(), *step))
+        .collect::<Vec<_>>();
+    data.get(start..)
+        .unwrap_or_default()
+        .chunks_exact(32)
+        .enumerate()
+        .filter_map(|(i, word)| {
+            let (_, step) = placeholders.iter().find(|(placeholder, _)| placeholder == word)?;
+            Some((start + i * 32, *step))
+        })
+        .collect()
+}
+
+/// Converts the arguments of a manifest to the strings they are parsed from, replacing the
+/// references to deployments with their placeholders.
+fn args(values: &[Value], deployments: &[(&str, usize)]) -> Result<Vec<String>> {
+    values.iter().map(|value| arg(value, deployments)).collect()
+}
+
+fn arg(value: &Value, deployments: &[(&str, usize)]) -> Result<String> {
+    Ok(match value {
+        Value::String(s) => match s.strip_prefix('@') {
+            Some(name) => {
+                find(deployments, name)?;
+                placeholder(name).to_string()
+            }
+            None => s.clone(),
+        },
+        Value::Number(n) => n.to_string(),
+        Value::Bool(b) => b.to_string(),
+        Value::Array(values) => format!("[{}]", args(values, deployments)?.join(", ")),
+        Value::Null | Value::Object(_) => eyre::bail!("unsupported argument: {value}"),
+    })
+}
+
+/// Parses the ether sent with a deployment or call, e.g. `1000` or `"1 ether"`.
+fn value(value: Option<&Value>) -> Result<U256> {
+    let Some(value) = value else { return Ok(U256::ZERO) };
+    coerce_value("uint256", &arg(value, &[])?)?
+        .as_uint()
+        .map(|(value, _)| value)
+        .ok_or_eyre("invalid value")
+}
+
+/// Returns the source of the script running the steps of a manifest.
+fn script_source(manifest: &Path, steps: &[Step]) -> String {
+    let steps =
+        DynSolValue::Tuple(vec![DynSolValue::Array(steps.iter().map(Step::to_value).collect())])
+            .abi_encode_params();
+    format!(
+        r#"// SPDX-License-Identifier: UNLICENSED
+// Generated by `forge script --manifest {manifest}`, do not edit.
+pragma solidity >=0.8.0;
+
+interface Vm {{
+    function broadcast() external;
+    function label(address account, string calldata newLabel) external;
+}}
+
+contract {MANIFEST_CONTRACT} {{
+    struct Patch {{
+        uint256 offset;
+        uint256 deployment;
+    }}
+
+    struct Step {{
+        string name;
+        address target;
+        uint256 targetDeployment;
+        uint256 value;
+        bytes data;
+        Patch[] patches;
+    }}
+
+    Vm private constant vm = Vm(0x7109709ECfa91a80626fF3989D68f67F5b1DD12D);
+    address private constant CONSOLE = 0x000000000000000000636F6e736F6c652e6c6f67;
+    bytes private constant STEPS = hex"{steps}";
+
+    function run() external {{
+        Step[] memory steps = abi.decode(STEPS, (Step[]));
+        address[] memory deployments = new address[](steps.length);
+        for (uint256 i = 0; i < steps.length; i++) {{
+            Step memory step = steps[i];
+            bytes memory data = step.data;
+            for (uint256 j = 0; j < step.patches.length; j++) {{
+                uint256 offset = step.patches[j].offset;
+                address deployment = deployments[step.patches[j].deployment];
+                assembly {{
+                    mstore(add(add(data, 32), offset), deployment)
+                }}
+            }}
+
+            uint256 value = step.value;
+            if (bytes(step.name).length == 0) {{
+                address target = step.targetDeployment == 0
+                    ? step.target
+                    : deployments[step.targetDeployment - 1];
+                vm.broadcast();
+                (bool success, bytes memory result) = target.call{{value: value}}(data);
+                if (!success) {{
+                    assembly {{
+                        revert(add(result, 32), mload(result))
+                    }}
+                }}
+                continue;
+            }}
+
+            address deployed;
+            vm.broadcast();
+            assembly {{
+                deployed := create(value, add(data, 32), mload(data))
+            }}
+            require(
+                deployed != address(0), string(abi.encodePacked("failed to deploy ", step.name))
+            );
+            deployments[i] = deployed;
+            vm.label(deployed, step.name);
+
+            bytes memory payload =
+                abi.encodeWithSignature("log(string,address)", step.name, deployed);
+            address console = CONSOLE;
+            assembly {{
+                pop(staticcall(gas(), console, add(payload, 32), mload(payload), 0, 0))
+            }}
+        }}
+    }}
+}}
+"#,
+        manifest = manifest.display(),
+        steps = hex::encode(steps),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_manifests() {
+        let manifest: Manifest = toml::from_str(
+            r#"
+            [[deploy]]
+            name = "token"
+            contract = "Token"
+            args = ["Token", 18, ["@vault", "0x0000000000000000000000000000000000000001"]]
+            value = "1 ether"
+            calls = [{ function = "mint(address,uint256)", args = ["@token", "1000"] }]
+            "#,
+        )
+        .unwrap();
+        let deployment = &manifest.deploy[0];
+        assert!(args(&deployment.args, &[]).is_err());
+        assert_eq!(
+            args(&deployment.args, &[("vault", 0)]).unwrap(),
+            [
+                "Token".to_string(),
+                "18".to_string(),
+                format!("[{}, 0x0000000000000000000000000000000000000001]", placeholder("vault")),
+            ]
+        );
+        assert_eq!(value(deployment.value.as_ref()).unwrap(), U256::from(10).pow(U256::from(18)));
+        assert_eq!(deployment.calls[0].target, None);
+
+        let json: Manifest =
+            serde_json::from_str(r#"{"deploy": [{"name": "token", "contract": "Token"}]}"#)
+                .unwrap();
+        assert!(json.deploy[0].args.is_empty());
+        assert!(toml::from_str::<Manifest>("[[deploy]]\nname = \"a\"\ncontract = \"A\"\nsalt = 1")
+            .is_err());
+    }
+
+    #[test]
+    fn patches_references() {
+        let deployments = [("token", 0), ("vault", 2)];
+        let function = get_func("f(uint256,address,address[])").unwrap();
+        let call_args = args(
+            &[
+                Value::from(1),
+                Value::from("@vault"),
+                Value::from(vec!["@token", "0x0000000000000000000000000000000000000001"]),
+            ],
+            &deployments,
+        )
+        .unwrap();
+        let data = encode_function_args(&function, &call_args).unwrap();
+        // The elements of the array follow its offset and length.
+        assert_eq!(patches(&data, 4, &deployments), [(36, 2), (132, 0)]);
+    }
+}
@@ -6,6 +6,7 @@ use foundry_compilers::ArtifactId;
 use foundry_config::Config;
 use serde::{Deserialize, Serialize};
 use std::{
+    collections::HashSet,
     io::{BufWriter, Write},
     path::PathBuf,
 };
@@ -45,7 +46,35 @@ impl MultiChainSequence {
     ) -> Result<Self> {
         let (path, sensitive_path) = Self::get_paths(config, sig, target, dry_run)?;
 
-        Ok(Self { deployments, path, sensitive_path, timestamp: now().as_secs() })
+        let mut sequence = Self { deployments, path, sensitive_path, timestamp: now().as_secs() };
+        sequence.set_deployment_paths(config, sig, target, dry_run)?;
+        Ok(sequence)
+    }
+
+    /// Sets the paths of every deployment, so that each chain's transactions are also saved to
+    /// `./broadcast/multi/contract_filename-latest/chain_id/`, see
+    /// [`MultiChainSequence::get_deployment_paths`].
+    ///
+    /// Deployments are only saved separately if every one of them targets a different chain.
+    pub fn set_deployment_paths(
+        &mut self,
+        config: &Config,
+        sig: &str,
+        target: &ArtifactId,
+        dry_run: bool,
+    ) -> Result<()> {
+        let chains = self.deployments.iter().map(|sequence| sequence.chain).collect::<HashSet<_>>();
+        let unique_chains = chains.len() == self.deployments.len();
+
+        for sequence in &mut self.deployments {
+            sequence.paths = if unique_chains {
+                Some(Self::get_deployment_paths(config, sig, target, sequence.chain, dry_run)?)
+            } else {
+                None
+            };
+        }
+
+        Ok(())
     }
 
     /// Gets paths in the formats
@@ -89,6 +118,29 @@ impl MultiChainSequence {
         Ok((broadcast, cache))
     }
 
+    /// Gets paths in the formats
+    /// ./broadcast/multi/contract_filename-latest/chain_id/sig.json and
+    /// ./cache/multi/contract_filename-latest/chain_id/sig.json, for the deployment to the given
+    /// chain.
+    ///
+    /// These are kept apart from the paths of single chain deployments, so that a multi chain
+    /// deployment doesn't overwrite the latest single chain run of the script.
+    pub fn get_deployment_paths(
+        config: &Config,
+        sig: &str,
+        target: &ArtifactId,
+        chain_id: u64,
+        dry_run: bool,
+    ) -> Result<(PathBuf, PathBuf)> {
+        let (broadcast, cache) = Self::get_paths(config, sig, target, dry_run)?;
+        let per_chain = |path: PathBuf| -> Result<PathBuf> {
+            let dir = path.parent().wrap_err("No parent directory.")?.join(chain_id.to_string());
+            fs::create_dir_all(&dir)?;
+            Ok(dir.join(path.file_name().wrap_err("No filename.")?))
+        };
+        Ok((per_chain(broadcast)?, per_chain(cache)?))
+    }
+
     /// Loads the sequences for the multi chain deployment.
     pub fn load(config: &Config, sig: &str, target: &ArtifactId, dry_run: bool) -> Result<Self> {
         let (path, sensitive_path) = Self::get_paths(config, sig, target, dry_run)?;
@@ -104,11 +156,12 @@ impl MultiChainSequence {
 
         sequence.path = path;
         sequence.sensitive_path = sensitive_path;
+        sequence.set_deployment_paths(config, sig, target, dry_run)?;
 
         Ok(sequence)
     }
 
-    /// Saves the transactions as file if it's a standalone deployment.
+    /// Saves the transactions of all chains, and of every chain separately if possible.
     pub fn save(&mut self, silent: bool, save_ts: bool) -> Result<()> {
         self.deployments.iter_mut().for_each(|sequence| sequence.sort_receipts());
 
@@ -149,6 +202,11 @@ impl MultiChainSequence {
             println!("Sensitive details saved to: {}\n", self.sensitive_path.display());
         }
 
+        // per chain writes
+        for sequence in &mut self.deployments {
+            sequence.save(silent, save_ts)?;
+        }
+
         Ok(())
     }
 }
@@ -0,0 +1,595 @@
+//! The plan of the transactions shown before broadcasting, with address prediction and funding
+//! checks.
+
+use crate::{
+    broadcast::BundledState, ceremony::describe, providers::ProviderInfo, sequence::ScriptSequence,
+    transaction::TransactionWithMetadata,
+};
+use alloy_eips::eip4844::DATA_GAS_PER_BLOB;
+use alloy_primitives::{utils::format_units, Address, Bytes, TxKind, B256, I256, U256};
+use alloy_provider::Provider;
+use alloy_rpc_types::TransactionRequest;
+use dialoguer::Confirm;
+use eyre::{Result, WrapErr};
+use foundry_common::{fs, shell};
+use foundry_evm::{constants::DEFAULT_CREATE2_DEPLOYER, executors::AccountDiff};
+use serde::Serialize;
+use std::{collections::BTreeMap, fmt, io::IsTerminal};
+
+/// How a planned contract is created.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum CreationKind {
+    /// Created by a `CREATE` transaction with the given sender nonce.
+    Create { nonce: u64 },
+    /// Created through the CREATE2 deployer with the given salt.
+    Create2 { salt: B256 },
+    /// Created by another contract during a transaction.
+    Nested,
+}
+
+impl fmt::Display for CreationKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Create { nonce } => write!(f, "CREATE (nonce {nonce})"),
+            Self::Create2 { salt } => write!(f, "CREATE2 (salt {salt})"),
+            Self::Nested => f.write_str("created by a contract"),
+        }
+    }
+}
+
+/// A contract that will be created by the broadcasted transactions.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlannedContract {
+    pub address: Address,
+    pub name: Option<String>,
+    pub kind: CreationKind,
+}
+
+/// The funds a sender needs to broadcast its transactions.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FundingRequirement {
+    pub sender: Address,
+    /// The maximum cost of the sender's transactions, including value transfers.
+    pub required: U256,
+    /// The current balance of the sender.
+    pub balance: U256,
+    /// The value sent to the sender by other transactions of the script.
+    pub incoming: U256,
+}
+
+impl FundingRequirement {
+    /// Returns the amount the sender is missing to cover its transactions.
+    pub fn shortfall(&self) -> U256 {
+        self.required.saturating_sub(self.balance.saturating_add(self.incoming))
+    }
+}
+
+/// A value changed by a transaction.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Change<T> {
+    pub from: T,
+    pub to: T,
+}
+
+impl<T> From<(T, T)> for Change<T> {
+    fn from((from, to): (T, T)) -> Self {
+        Self { from, to }
+    }
+}
+
+/// The simulated changes of a transaction to an account.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlannedAccountDiff {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub balance: Option<Change<U256>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<Change<u64>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<Change<Bytes>>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub storage: BTreeMap<B256, Change<B256>>,
+}
+
+impl From<AccountDiff> for PlannedAccountDiff {
+    fn from(diff: AccountDiff) -> Self {
+        let AccountDiff { balance, nonce, code, storage } = diff;
+        Self {
+            balance: balance.map(Into::into),
+            nonce: nonce.map(Into::into),
+            code: code.map(Into::into),
+            storage: storage
+                .into_iter()
+                .map(|(slot, (from, to))| {
+                    (slot.into(), Change { from: from.into(), to: to.into() })
+                })
+                .collect(),
+        }
+    }
+}
+
+/// A transaction that will be broadcasted.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlannedTransaction {
+    /// The index of the transaction in the sequence.
+    pub index: usize,
+    pub from: Option<Address>,
+    pub to: Option<Address>,
+    pub value: U256,
+    pub nonce: Option<u64>,
+    /// The decoded call, e.g. `Counter::increment(uint256) with [1]`.
+    pub call: String,
+    /// The address of the contract created by the transaction, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contract_address: Option<Address>,
+    /// The simulated changes of the transaction to the state.
+    pub state_diff: BTreeMap<Address, PlannedAccountDiff>,
+}
+
+impl PlannedTransaction {
+    fn new(index: usize, tx: &TransactionWithMetadata) -> Self {
+        let request = tx.tx();
+        Self {
+            index,
+            from: request.from,
+            to: request.to.and_then(|to| to.to().copied()),
+            value: request.value.unwrap_or_default(),
+            nonce: request.nonce,
+            call: describe(
+                tx.contract_name.as_deref(),
+                tx.function.as_deref(),
+                tx.arguments.as_deref().unwrap_or_default(),
+            ),
+            contract_address: tx.contract_address,
+            state_diff: tx
+                .state_diff
+                .iter()
+                .map(|(address, diff)| (*address, diff.clone().into()))
+                .collect(),
+        }
+    }
+}
+
+/// Sums the simulated balance changes of the transactions by account.
+pub fn balance_deltas(transactions: &[PlannedTransaction]) -> BTreeMap<Address, I256> {
+    let mut deltas = BTreeMap::<Address, I256>::new();
+    for tx in transactions {
+        for (address, diff) in &tx.state_diff {
+            if let Some(Change { from, to }) = diff.balance {
+                let delta = I256::from_raw(to).saturating_sub(I256::from_raw(from));
+                let entry = deltas.entry(*address).or_default();
+                *entry = entry.saturating_add(delta);
+            }
+        }
+    }
+    deltas.retain(|_, delta| !delta.is_zero());
+    deltas
+}
+
+/// The transactions, the contracts created and the funds required on a chain.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChainPlan {
+    pub chain: u64,
+    pub transactions: Vec<PlannedTransaction>,
+    pub contracts: Vec<PlannedContract>,
+    pub funding: Vec<FundingRequirement>,
+    /// The net balance changes of the accounts touched by the transactions.
+    pub balance_deltas: BTreeMap<Address, I256>,
+    /// The preconditions that don't hold on the chain.
+    pub errors: Vec<String>,
+}
+
+impl ChainPlan {
+    /// Computes the plan of the transactions of the sequence that haven't been broadcasted yet,
+    /// and checks it against the current state of the chain.
+    pub async fn new(
+        sequence: &ScriptSequence,
+        legacy: bool,
+        gas_price: Option<U256>,
+        blob_gas_price: Option<U256>,
+    ) -> Result<Self> {
+        let info = ProviderInfo::new(sequence.rpc_url(), legacy).await?;
+        let provider = &info.provider;
+        let fee_per_gas = match gas_price {
+            Some(gas_price) => gas_price.to(),
+            None => info.gas_price()?,
+        };
+
+        let transactions =
+            sequence.transactions.iter().skip(sequence.receipts.len()).collect::<Vec<_>>();
+        let planned = transactions
+            .iter()
+            .enumerate()
+            .map(|(i, tx)| PlannedTransaction::new(sequence.receipts.len() + i, tx))
+            .collect::<Vec<_>>();
+        let balance_deltas = balance_deltas(&planned);
+        let blob_fee = match blob_gas_price {
+            Some(blob_gas_price) => blob_gas_price.to(),
+            None if transactions.iter().any(|tx| tx.tx().sidecar.is_some()) => {
+                provider.get_blob_base_fee().await.wrap_err("Failed to get the blob base fee")?
+            }
+            None => 0,
+        };
+
+        let mut contracts = Vec::new();
+        let mut errors = Vec::new();
+        let mut required = BTreeMap::<Address, U256>::new();
+        let mut incoming = BTreeMap::<Address, U256>::new();
+        let mut first_nonces = BTreeMap::<Address, u64>::new();
+        let mut uses_create2 = false;
+
+        for tx in transactions {
+            let request = tx.tx();
+            let Some(from) = request.from else { continue };
+
+            let cost = max_cost(request, fee_per_gas, blob_fee);
+            let entry = required.entry(from).or_default();
+            *entry = entry.saturating_add(cost);
+            if let (Some(TxKind::Call(to)), Some(value)) = (request.to, request.value) {
+                let entry = incoming.entry(to).or_default();
+                *entry = entry.saturating_add(value);
+            }
+            if let Some(nonce) = request.nonce {
+                first_nonces.entry(from).or_insert(nonce);
+            }
+
+            if let Some((address, kind)) = predict_address(request) {
+                uses_create2 |= matches!(kind, CreationKind::Create2 { .. });
+                if let Some(simulated) = tx.contract_address.filter(|addr| !addr.is_zero()) {
+                    if simulated != address {
+                        errors.push(format!(
+                            "the predicted address {address} of a {kind} deployment differs from \
+                             the simulated address {simulated}"
+                        ));
+                    }
+                }
+                contracts.push(PlannedContract {
+                    address,
+                    name: tx.contract_name.clone().filter(|name| !name.is_empty()),
+                    kind,
+                });
+            }
+            contracts.extend(tx.additional_contracts.iter().map(|contract| PlannedContract {
+                address: contract.address,
+                name: None,
+                kind: CreationKind::Nested,
+            }));
+        }
+
+        if uses_create2 && provider.get_code_at(DEFAULT_CREATE2_DEPLOYER).await?.is_empty() {
+            errors.push(format!("the CREATE2 deployer {DEFAULT_CREATE2_DEPLOYER} is not deployed"));
+        }
+
+        for contract in &contracts {
+            if !provider.get_code_at(contract.address).await?.is_empty() {
+                errors.push(format!(
+                    "there is already code at {}, where {} would be deployed",
+                    contract.address,
+                    contract.name.as_deref().unwrap_or("a contract"),
+                ));
+            }
+        }
+
+        // Predicted CREATE addresses are only valid if the senders' nonces didn't change.
+        for (sender, nonce) in first_nonces {
+            let current = provider.get_transaction_count(sender).await?;
+            if current != nonce {
+                errors.push(format!(
+                    "the nonce of {sender} is {current}, but the script expects {nonce}"
+                ));
+            }
+        }
+
+        let mut funding = Vec::with_capacity(required.len());
+        for (sender, required) in required {
+            let balance = provider.get_balance(sender).await?;
+            let incoming = incoming.get(&sender).copied().unwrap_or_default();
+            let requirement = FundingRequirement { sender, required, balance, incoming };
+            let shortfall = requirement.shortfall();
+            if !shortfall.is_zero() {
+                errors.push(format!(
+                    "{sender} needs {} ETH more to cover its transactions",
+                    format_eth(shortfall)
+                ));
+            }
+            funding.push(requirement);
+        }
+
+        Ok(Self {
+            chain: info.chain,
+            transactions: planned,
+            contracts,
+            funding,
+            balance_deltas,
+            errors,
+        })
+    }
+
+    /// Prints the plan.
+    pub fn print(&self) -> Result<()> {
+        shell::println(format!("\nChain {}", self.chain))?;
+
+        shell::println("\nTransactions:")?;
+        for tx in &self.transactions {
+            let from = tx.from.map(|from| from.to_string()).unwrap_or_default();
+            let to = match (tx.to, tx.contract_address) {
+                (Some(to), _) => to.to_string(),
+                (None, Some(address)) => format!("new contract at {address}"),
+                (None, None) => "new contract".to_string(),
+            };
+            shell::println(format!("  #{} {from} -> {to}", tx.index))?;
+            if !tx.call.is_empty() {
+                shell::println(format!("    call: {}", tx.call))?;
+            }
+            if !tx.value.is_zero() {
+                shell::println(format!("    value: {} ETH", format_eth(tx.value)))?;
+            }
+            for (address, diff) in &tx.state_diff {
+                shell::println(format!("    {address}"))?;
+                if let Some(Change { from, to }) = &diff.balance {
+                    shell::println(format!("      balance: {from} -> {to}"))?;
+                }
+                if let Some(Change { from, to }) = &diff.nonce {
+                    shell::println(format!("      nonce: {from} -> {to}"))?;
+                }
+                if let Some(Change { from, to }) = &diff.code {
+                    shell::println(format!(
+                        "      code: {} bytes -> {} bytes",
+                        from.len(),
+                        to.len()
+                    ))?;
+                }
+                for (slot, Change { from, to }) in &diff.storage {
+                    shell::println(format!("      {slot}: {from} -> {to}"))?;
+                }
+            }
+        }
+
+        if !self.contracts.is_empty() {
+            shell::println("\nContracts to be created:")?;
+            for contract in &self.contracts {
+                shell::println(format!(
+                    "  {} {} {}",
+                    contract.address,
+                    contract.name.as_deref().unwrap_or("<unknown>"),
+                    contract.kind,
+                ))?;
+            }
+        }
+
+        shell::println("\nFunding plan:")?;
+        for requirement in &self.funding {
+            let shortfall = requirement.shortfall();
+            let status = if shortfall.is_zero() {
+                "ok".to_string()
+            } else {
+                format!("missing {} ETH", format_eth(shortfall))
+            };
+            shell::println(format!(
+                "  {} requires {} ETH, has {} ETH{}: {status}",
+                requirement.sender,
+                format_eth(requirement.required),
+                format_eth(requirement.balance),
+                if requirement.incoming.is_zero() {
+                    String::new()
+                } else {
+                    format!(" (+{} ETH from the script)", format_eth(requirement.incoming))
+                },
+            ))?;
+        }
+
+        if !self.balance_deltas.is_empty() {
+            shell::println("\nBalance changes:")?;
+            for (address, delta) in &self.balance_deltas {
+                let sign = if delta.is_negative() { "-" } else { "+" };
+                shell::println(format!(
+                    "  {address} {sign}{} ETH",
+                    format_eth(delta.unsigned_abs())
+                ))?;
+            }
+        }
+
+        for error in &self.errors {
+            shell::println(format!("  Error: {error}"))?;
+        }
+        Ok(())
+    }
+}
+
+impl BundledState {
+    /// Prints the plan of the transactions on all chains and asks for approval to broadcast them.
+    ///
+    /// The plan shows the decoded calls and simulated state changes of the transactions, the
+    /// resulting balance changes and the contracts that will be created. It also checks that the
+    /// predicted addresses are free and that the senders can afford their transactions, failing
+    /// before anything is broadcasted unless `--skip-preconditions` is set.
+    ///
+    /// Approval is skipped with `--yes` or when resuming, and required otherwise.
+    pub async fn review_plan(&self) -> Result<()> {
+        shell::println("\n==========================")?;
+        shell::println("\nBroadcast plan")?;
+
+        let mut plans = Vec::new();
+        for sequence in self.sequence.sequences() {
+            if sequence.receipts.len() >= sequence.transactions.len() {
+                continue
+            }
+            let plan = ChainPlan::new(
+                sequence,
+                self.args.legacy,
+                self.args.with_gas_price,
+                self.args.blob_gas_price,
+            )
+            .await?;
+            plan.print()?;
+            plans.push(plan);
+        }
+        shell::println("\n==========================")?;
+
+        if let Some(path) = &self.args.plan_json {
+            fs::write_json_file(path, &plans)?;
+            shell::println(format!("\nWrote the broadcast plan to {}", path.display()))?;
+        }
+
+        let errors = plans.iter().map(|plan| plan.errors.len()).sum::<usize>();
+        if errors > 0 && !self.args.skip_preconditions {
+            eyre::bail!(
+                "{errors} deployment precondition(s) failed; \
+                 pass --skip-preconditions to broadcast anyway"
+            );
+        }
+
+        if self.args.yes || self.args.resume {
+            return Ok(())
+        }
+        if self.args.non_interactive || !std::io::stdin().is_terminal() {
+            eyre::bail!("Broadcasting requires approval; pass --yes to broadcast without a prompt");
+        }
+        if !Confirm::new().with_prompt("Do you want to broadcast these transactions?").interact()? {
+            eyre::bail!("Broadcast cancelled.");
+        }
+        Ok(())
+    }
+}
+
+/// Returns the address and kind of the contract directly created by the transaction, if any.
+pub fn predict_address(tx: &TransactionRequest) -> Option<(Address, CreationKind)> {
+    let from = tx.from?;
+    match tx.to {
+        None | Some(TxKind::Create) => {
+            let nonce = tx.nonce?;
+            Some((from.create(nonce), CreationKind::Create { nonce }))
+        }
+        Some(TxKind::Call(to)) if to == DEFAULT_CREATE2_DEPLOYER => {
+            let input = tx.input.input()?;
+            if input.len() < 32 {
+                return None
+            }
+            let salt = B256::from_slice(&input[..32]);
+            let address = DEFAULT_CREATE2_DEPLOYER.create2_from_code(salt, &input[32..]);
+            Some((address, CreationKind::Create2 { salt }))
+        }
+        Some(TxKind::Call(_)) => None,
+    }
+}
+
+/// Returns the maximum amount the transaction can cost its sender.
+///
+/// The fees set in the transaction take precedence over the given estimates.
+pub fn max_cost(tx: &TransactionRequest, fee_per_gas: u128, blob_fee: u128) -> U256 {
+    let fee_per_gas = tx.gas_price.or(tx.max_fee_per_gas).unwrap_or(fee_per_gas);
+    let gas = U256::from(tx.gas.unwrap_or_default()).saturating_mul(U256::from(fee_per_gas));
+
+    let blobs = tx.sidecar.as_ref().map_or(0, |sidecar| sidecar.blobs.len()) as u128;
+    let blob_fee = tx.max_fee_per_blob_gas.unwrap_or(blob_fee);
+    let blob_gas =
+        U256::from(blobs * DATA_GAS_PER_BLOB as u128).saturating_mul(U256::from(blob_fee));
+
+    tx.value.unwrap_or_default().saturating_add(gas).saturating_add(blob_gas)
+}
+
+fn format_eth(value: U256) -> String {
+    let formatted = format_units(value, 18).unwrap_or_else(|_| value.to_string());
+    formatted.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::{address, Bytes};
+    use alloy_rpc_types::TransactionInput;
+
+    #[test]
+    fn predicts_create_address() {
+        let from = address!("f39Fd6e51aad88F6F4ce6aB8827279cffFb92266");
+        let tx = TransactionRequest {
+            from: Some(from),
+            to: Some(TxKind::Create),
+            nonce: Some(3),
+            ..Default::default()
+        };
+        assert_eq!(predict_address(&tx), Some((from.create(3), CreationKind::Create { nonce: 3 })));
+    }
+
+    #[test]
+    fn predicts_create2_address() {
+        let salt = B256::with_last_byte(1);
+        let init_code = Bytes::from_static(&[0x60, 0x80, 0x60, 0x40]);
+        let tx = TransactionRequest {
+            from: Some(Address::with_last_byte(1)),
+            to: Some(TxKind::Call(DEFAULT_CREATE2_DEPLOYER)),
+            input: TransactionInput::new([salt.as_slice(), &init_code[..]].concat().into()),
+            ..Default::default()
+        };
+        assert_eq!(
+            predict_address(&tx),
+            Some((
+                DEFAULT_CREATE2_DEPLOYER.create2_from_code(salt, &init_code),
+                CreationKind::Create2 { salt }
+            ))
+        );
+
+        let call = TransactionRequest {
+            from: Some(Address::with_last_byte(1)),
+            to: Some(TxKind::Call(Address::with_last_byte(2))),
+            ..Default::default()
+        };
+        assert_eq!(predict_address(&call), None);
+    }
+
+    #[test]
+    fn computes_max_cost() {
+        let tx =
+            TransactionRequest { value: Some(U256::from(5)), gas: Some(100), ..Default::default() };
+        assert_eq!(max_cost(&tx, 2, 0), U256::from(205));
+
+        let tx = TransactionRequest { max_fee_per_gas: Some(3), ..tx };
+        assert_eq!(max_cost(&tx, 2, 0), U256::from(305));
+    }
+
+    #[test]
+    fn sums_balance_deltas() {
+        let sender = Address::with_last_byte(1);
+        let receiver = Address::with_last_byte(2);
+        let transfer = |from: u64, to: u64| PlannedAccountDiff {
+            balance: Some(Change { from: U256::from(from), to: U256::from(to) }),
+            ..Default::default()
+        };
+        let tx = |state_diff: BTreeMap<Address, PlannedAccountDiff>| PlannedTransaction {
+            index: 0,
+            from: Some(sender),
+            to: Some(receiver),
+            value: U256::ZERO,
+            nonce: None,
+            call: String::new(),
+            contract_address: None,
+            state_diff,
+        };
+        let transactions = [
+            tx([(sender, transfer(10, 7)), (receiver, transfer(0, 3))].into()),
+            tx([(sender, transfer(7, 4)), (receiver, transfer(3, 6))].into()),
+            tx([(receiver, transfer(6, 6))].into()),
+        ];
+
+        let deltas = balance_deltas(&transactions);
+        assert_eq!(deltas.len(), 2);
+        assert_eq!(deltas[&sender], I256::try_from(-6).unwrap());
+        assert_eq!(deltas[&receiver], I256::try_from(6).unwrap());
+    }
+
+    #[test]
+    fn computes_shortfall() {
+        let requirement = FundingRequirement {
+            sender: Address::ZERO,
+            required: U256::from(10),
+            balance: U256::from(4),
+            incoming: U256::from(2),
+        };
+        assert_eq!(requirement.shortfall(), U256::from(4));
+    }
+}
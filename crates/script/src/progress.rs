@@ -6,7 +6,10 @@ use alloy_chains::Chain;
 use alloy_primitives::B256;
 use eyre::Result;
 use foundry_cli::utils::init_progress;
-use foundry_common::provider::RetryProvider;
+use foundry_common::{
+    progress::{self, Phase},
+    provider::RetryProvider,
+};
 use futures::StreamExt;
 use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
 use parking_lot::RwLock;
@@ -71,11 +74,7 @@ impl SequenceProgressState {
         // Avoid showing more than 10 spinners.
         if self.tx_spinners.len() < 10 {
             let spinner = ProgressBar::new_spinner()
-                .with_style(
-                    ProgressStyle::with_template("    {spinner:.green} {msg}")
-                        .unwrap()
-                        .tick_chars("⠁⠂⠄⡀⢀⠠⠐⠈"),
-                )
+                .with_style(progress::spinner_style("    {spinner:.green} {msg}"))
                 .with_message(format!("{} {}", "[Pending]".yellow(), tx_hash));
 
             let spinner = self.multi.insert_before(&self.txs, spinner);
@@ -84,6 +83,7 @@ impl SequenceProgressState {
             self.tx_spinners.insert(tx_hash, spinner);
         }
         self.txs.inc(1);
+        progress::event(Phase::Broadcast, "sent", tx_hash);
     }
 
     /// Removes the pending transaction spinner and advances confirmed transactions progress bar.
@@ -105,6 +105,7 @@ impl SequenceProgressState {
     /// Sets status for the current sequence progress.
     pub fn set_status(&mut self, status: &str) {
         self.top_spinner.set_message(format!(" | {status}"));
+        progress::event(Phase::Broadcast, "status", status);
     }
 
     /// Hides transactions and receipts progress bar, leaving only top line with the latest set
@@ -131,12 +132,18 @@ impl SequenceProgress {
 }
 
 /// Container for multiple [SequenceProgress] instances keyed by sequence index.
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone)]
 pub struct ScriptProgress {
     state: Arc<RwLock<HashMap<usize, SequenceProgress>>>,
     multi: MultiProgress,
 }
 
+impl Default for ScriptProgress {
+    fn default() -> Self {
+        Self { state: Default::default(), multi: progress::multi_progress() }
+    }
+}
+
 impl ScriptProgress {
     /// Returns a [SequenceProgress] instance for the given sequence index. If it doesn't exist,
     /// creates one.
@@ -190,6 +197,7 @@ impl ScriptProgress {
             match result {
                 Err(err) => {
                     errors.push(format!("Failure on receiving a receipt for {tx_hash:?}:\n{err}"));
+                    progress::event(Phase::Broadcast, "error", tx_hash);
 
                     seq_progress.inner.write().finish_tx_spinner(tx_hash);
                 }
@@ -197,11 +205,13 @@ impl ScriptProgress {
                     // We want to remove it from pending so it will be re-broadcast.
                     deployment_sequence.remove_pending(tx_hash);
                     errors.push(format!("Transaction dropped from the mempool: {tx_hash:?}"));
+                    progress::event(Phase::Broadcast, "dropped", tx_hash);
 
                     seq_progress.inner.write().finish_tx_spinner(tx_hash);
                 }
                 Ok(TxStatus::Success(receipt)) => {
                     trace!(tx_hash=?tx_hash, "received tx receipt");
+                    progress::event(Phase::Broadcast, "confirmed", tx_hash);
 
                     let msg = format_receipt(deployment_sequence.chain.into(), &receipt);
                     seq_progress.inner.write().finish_tx_spinner_with_msg(tx_hash, &msg)?;
@@ -214,6 +224,7 @@ impl ScriptProgress {
                     // if this is not removed from pending, then the script becomes
                     // un-resumable. Is this desirable on reverts?
                     warn!(tx_hash=?tx_hash, "Transaction Failure");
+                    progress::event(Phase::Broadcast, "reverted", tx_hash);
                     deployment_sequence.remove_pending(receipt.transaction_hash);
 
                     let msg = format_receipt(deployment_sequence.chain.into(), &receipt);
@@ -1,6 +1,8 @@
 use super::ScriptResult;
-use crate::build::ScriptPredeployLibraries;
-use alloy_primitives::{Address, Bytes, TxKind, U256};
+use crate::{build::ScriptPredeployLibraries, execute::ScriptDependency};
+use alloy_dyn_abi::{DynSolValue, FunctionExt};
+use alloy_json_abi::{Function, JsonAbi};
+use alloy_primitives::{Address, Bytes, TxKind, B256, U256};
 use alloy_rpc_types::TransactionRequest;
 use eyre::Result;
 use foundry_cheatcodes::BroadcastableTransaction;
@@ -12,7 +14,7 @@ use foundry_evm::{
     revm::interpreter::{return_ok, InstructionResult},
     traces::{TraceKind, Traces},
 };
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
 use yansi::Paint;
 
 /// Drives script execution
@@ -27,10 +29,12 @@ impl ScriptRunner {
         Self { executor, evm_opts }
     }
 
-    /// Deploys the libraries and broadcast contract. Calls setUp method if requested.
+    /// Deploys the libraries, executes the scripts the script depends on and deploys the script
+    /// contract. Calls setUp method if requested.
     pub fn setup(
         &mut self,
         libraries: &ScriptPredeployLibraries,
+        dependencies: &[ScriptDependency],
         code: Bytes,
         setup: bool,
         sender_nonce: u64,
@@ -125,6 +129,33 @@ impl ScriptRunner {
             }
         };
 
+        // Execute the scripts this script depends on, in order, on top of each other.
+        let mut dependency_logs = Vec::new();
+        let mut dependency_labels = HashMap::new();
+        for dependency in dependencies {
+            let result = self.run_dependency(dependency)?;
+            traces.extend(result.traces);
+            dependency_logs.extend(result.logs);
+            dependency_labels.extend(result.labeled_addresses);
+            if let Some(txs) = result.transactions {
+                library_transactions.extend(txs);
+            }
+            if !result.success {
+                println!("{}", format!("\nScript {} failed.", dependency.name).red());
+                return Ok((
+                    Address::ZERO,
+                    ScriptResult {
+                        success: false,
+                        logs: dependency_logs,
+                        traces,
+                        labeled_addresses: dependency_labels,
+                        transactions: Some(library_transactions),
+                        ..Default::default()
+                    },
+                ))
+            }
+        }
+
         let address = CALLER.create(self.executor.get_nonce(CALLER)?);
 
         // Set the contracts initial balance before deployment, so it is available during the
@@ -134,16 +165,18 @@ impl ScriptRunner {
         // Deploy an instance of the contract
         let DeployResult {
             address,
-            raw: RawCallResult { mut logs, traces: constructor_traces, .. },
+            raw: RawCallResult { logs: constructor_logs, traces: constructor_traces, .. },
         } = self
             .executor
             .deploy(CALLER, code, U256::ZERO, None)
             .map_err(|err| eyre::eyre!("Failed to deploy script:\n{}", err))?;
 
         traces.extend(constructor_traces.map(|traces| (TraceKind::Deployment, traces)));
+        let mut logs = dependency_logs;
+        logs.extend(constructor_logs);
 
         // Optionally call the `setUp` function
-        let (success, gas_used, labeled_addresses, transactions) = if !setup {
+        let (success, gas_used, mut labeled_addresses, transactions) = if !setup {
             self.executor.backend_mut().set_test_contract(address);
             (true, 0, Default::default(), Some(library_transactions))
         } else {
@@ -188,6 +221,7 @@ impl ScriptRunner {
                 Err(e) => return Err(e.into()),
             }
         };
+        labeled_addresses.extend(dependency_labels);
 
         Ok((
             address,
@@ -205,6 +239,118 @@ impl ScriptRunner {
         ))
     }
 
+    /// Returns the names of the scripts the given script depends on, as returned by its
+    /// `dependsOn()` function, if any.
+    ///
+    /// The script is deployed on a copy of the executor, so that this has no side effects.
+    pub fn dependencies(&self, code: Bytes, abi: &JsonAbi) -> Result<Vec<String>> {
+        let Some(func) =
+            abi.function("dependsOn").and_then(|funcs| funcs.iter().find(|f| f.inputs.is_empty()))
+        else {
+            return Ok(Vec::new())
+        };
+
+        let mut executor = self.executor.clone();
+        executor.set_balance(CALLER, U256::MAX)?;
+        let DeployResult { address, .. } = executor
+            .deploy(CALLER, code, U256::ZERO, None)
+            .map_err(|err| eyre::eyre!("Failed to deploy script:\n{}", err))?;
+        let result =
+            executor.call_raw(CALLER, address, func.selector().to_vec().into(), U256::ZERO)?;
+        eyre::ensure!(!result.reverted, "`dependsOn()` reverted");
+
+        match func.abi_decode_output(&result.result, false)?.as_slice() {
+            [DynSolValue::Array(names)] => names
+                .iter()
+                .map(|name| name.as_str().map(str::to_string))
+                .collect::<Option<_>>()
+                .ok_or_else(|| eyre::eyre!("`dependsOn()` must return a `string[]`")),
+            _ => eyre::bail!("`dependsOn()` must return a `string[]`"),
+        }
+    }
+
+    /// Deploys a script the current script depends on and executes its `setUp()` and `run()`
+    /// functions, committing their state changes so that they are visible to the scripts executed
+    /// after it.
+    ///
+    /// The contracts deployed by its broadcasted transactions are added to the deployments
+    /// registry, read with the `getDeployment` cheatcode.
+    fn run_dependency(&mut self, dependency: &ScriptDependency) -> Result<ScriptResult> {
+        trace!(target: "script", name = %dependency.name, "executing dependency");
+
+        let address = CALLER.create(self.executor.get_nonce(CALLER)?);
+        self.executor.set_balance(address, self.evm_opts.initial_balance)?;
+        let DeployResult { address, raw } = self
+            .executor
+            .deploy(CALLER, dependency.bytecode.clone(), U256::ZERO, None)
+            .map_err(|err| eyre::eyre!("Failed to deploy script {}:\n{}", dependency.name, err))?;
+
+        let mut result = ScriptResult { success: true, ..Default::default() };
+        let merge = |result: &mut ScriptResult, raw: RawCallResult, kind: TraceKind| {
+            result.success &= !raw.reverted;
+            result.logs.extend(raw.logs);
+            result.traces.extend(raw.traces.map(|traces| (kind, traces)));
+            result.labeled_addresses.extend(raw.labels);
+            if let Some(txs) = raw.transactions {
+                result.transactions.get_or_insert_with(Default::default).extend(txs);
+            }
+        };
+        merge(&mut result, raw, TraceKind::Deployment);
+
+        if dependency.setup {
+            match self.executor.setup(Some(self.evm_opts.sender), address, None) {
+                Ok(raw) => merge(&mut result, raw, TraceKind::Setup),
+                Err(EvmError::Execution(err)) => {
+                    merge(&mut result, err.raw, TraceKind::Setup);
+                    result.success = false;
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+
+        if result.success {
+            let calldata = Function::parse("run()")?.selector().to_vec().into();
+            let raw =
+                self.executor.transact_raw(self.evm_opts.sender, address, calldata, U256::ZERO)?;
+            merge(&mut result, raw, TraceKind::Execution);
+        }
+
+        self.register_deployments(result.transactions.iter().flatten());
+
+        Ok(result)
+    }
+
+    /// Adds the contracts created by the given transactions to the deployments registry, by
+    /// matching their creation code against the known contracts.
+    fn register_deployments<'a>(
+        &mut self,
+        transactions: impl IntoIterator<Item = &'a BroadcastableTransaction>,
+    ) {
+        let Some(cheatcodes) = self.executor.inspector_mut().cheatcodes.as_mut() else { return };
+        let config = cheatcodes.config.clone();
+        let Some(known_contracts) = &config.available_artifacts else { return };
+
+        for tx in transactions {
+            let tx = &tx.transaction;
+            let (Some(from), Some(input)) = (tx.from, tx.input.input()) else { continue };
+            let (address, initcode) = match tx.to {
+                None | Some(TxKind::Create) => {
+                    let Some(nonce) = tx.nonce else { continue };
+                    (from.create(nonce), &input[..])
+                }
+                Some(TxKind::Call(DEFAULT_CREATE2_DEPLOYER)) if input.len() > 32 => {
+                    let (salt, initcode) = input.split_at(32);
+                    let salt = B256::from_slice(salt);
+                    (DEFAULT_CREATE2_DEPLOYER.create2_from_code(salt, initcode), initcode)
+                }
+                Some(TxKind::Call(_)) => continue,
+            };
+            if let Some((id, _)) = known_contracts.find_by_creation_code(initcode) {
+                cheatcodes.deployments.insert(id.name.clone(), address);
+            }
+        }
+    }
+
     /// Executes the method that will collect all broadcastable transactions.
     pub fn script(&mut self, address: Address, calldata: Bytes) -> Result<ScriptResult> {
         self.call(self.evm_opts.sender, address, calldata, U256::ZERO, false)
@@ -66,6 +66,7 @@ impl ScriptSequenceKind {
             Self::Multi(sequence) => {
                 (sequence.path, sequence.sensitive_path) =
                     MultiChainSequence::get_paths(config, sig, target, false)?;
+                sequence.set_deployment_paths(config, sig, target, false)?;
             }
         };
 
@@ -93,7 +94,8 @@ pub struct ScriptSequence {
     pub pending: Vec<TxHash>,
     #[serde(skip)]
     /// Contains paths to the sequence files
-    /// None if sequence should not be saved to disk (e.g. part of a multi-chain sequence)
+    /// None if sequence should not be saved to disk (e.g. part of a multi-chain sequence with
+    /// several deployments on the same chain)
     pub paths: Option<(PathBuf, PathBuf)>,
     pub returns: HashMap<String, NestedValue>,
     pub timestamp: u64,
@@ -225,12 +227,23 @@ impl ScriptSequence {
         target: &ArtifactId,
         chain_id: u64,
         dry_run: bool,
+    ) -> Result<(PathBuf, PathBuf)> {
+        Self::get_source_paths(config, sig, &target.source, chain_id, dry_run)
+    }
+
+    /// Same as [`Self::get_paths`], for the transactions of a contract defined in `source`.
+    pub fn get_source_paths(
+        config: &Config,
+        sig: &str,
+        source: &Path,
+        chain_id: u64,
+        dry_run: bool,
     ) -> Result<(PathBuf, PathBuf)> {
         let mut broadcast = config.broadcast.to_path_buf();
         let mut cache = config.cache_path.to_path_buf();
         let mut common = PathBuf::new();
 
-        let target_fname = target.source.file_name().wrap_err("No filename.")?;
+        let target_fname = source.file_name().wrap_err("No filename.")?;
         common.push(target_fname);
         common.push(chain_id.to_string());
         if dry_run {
@@ -14,6 +14,7 @@ use crate::{
 };
 use alloy_network::TransactionBuilder;
 use alloy_primitives::{utils::format_units, Address, TxKind, U256};
+use alloy_serde::WithOtherFields;
 use eyre::{Context, Result};
 use foundry_cheatcodes::{BroadcastableTransactions, ScriptWallets};
 use foundry_cli::utils::{has_different_gas_calc, now};
@@ -99,15 +100,27 @@ impl PreSimulationState {
                 let mut runner = runners.get(&rpc).expect("invalid rpc url").write();
 
                 let mut tx = transaction.transaction;
+                let from =
+                    tx.from.expect("transaction doesn't have a `from` address at execution time");
                 let to = if let Some(TxKind::Call(to)) = tx.to { Some(to) } else { None };
+
+                // Transactions of custom types run after the preamble of their type.
+                runner.executor.apply_custom_tx_type(&WithOtherFields::new(tx.clone()))?;
+
+                // Record the changes of the transaction for the broadcast plan.
+                let state_diff = if self.args.broadcast {
+                    runner.executor.state_diff(
+                        from,
+                        tx.to.unwrap_or(TxKind::Create),
+                        tx.input.input().cloned().unwrap_or_default(),
+                        tx.value.unwrap_or_default(),
+                    )?
+                } else {
+                    Default::default()
+                };
+
                 let result = runner
-                    .simulate(
-                        tx.from
-                            .expect("transaction doesn't have a `from` address at execution time"),
-                        to,
-                        tx.input.clone().into_input(),
-                        tx.value,
-                    )
+                    .simulate(from, to, tx.input.clone().into_input(), tx.value)
                     .wrap_err("Internal EVM error during simulation")?;
 
                 if !result.success {
@@ -133,7 +146,7 @@ impl PreSimulationState {
                         tx.gas = Some(gas as u128);
                     }
                 }
-                let tx = TransactionWithMetadata::new(
+                let mut tx = TransactionWithMetadata::new(
                     tx,
                     rpc,
                     &result,
@@ -142,6 +155,7 @@ impl PreSimulationState {
                     created_contracts,
                     is_fixed_gas_limit,
                 )?;
+                tx.state_diff = state_diff;
 
                 eyre::Ok((Some(tx), result.traces))
             })
@@ -259,71 +273,81 @@ impl FilledTransactionsState {
         }
 
         let mut total_gas_per_rpc: HashMap<String, u128> = HashMap::new();
-
-        // Batches sequence of transactions from different rpcs.
-        let mut new_sequence = VecDeque::new();
         let mut manager = ProvidersManager::default();
         let mut sequences = vec![];
 
-        // Peeking is used to check if the next rpc url is different. If so, it creates a
-        // [`ScriptSequence`] from all the collected transactions up to this point.
-        let mut txes_iter = self.transactions.clone().into_iter().peekable();
-
-        while let Some(mut tx) = txes_iter.next() {
-            let tx_rpc = tx.rpc.clone();
-            let provider_info = manager.get_or_init_provider(&tx.rpc, self.args.legacy).await?;
-
-            // Handles chain specific requirements.
-            tx.transaction.set_chain_id(provider_info.chain);
-
-            if !self.args.skip_simulation {
-                let tx = tx.tx_mut();
-
-                if has_different_gas_calc(provider_info.chain) {
-                    trace!("estimating with different gas calculation");
-                    let gas = tx.gas.expect("gas is set by simulation.");
-
-                    // We are trying to show the user an estimation of the total gas usage.
-                    //
-                    // However, some transactions might depend on previous ones. For
-                    // example, tx1 might deploy a contract that tx2 uses. That
-                    // will result in the following `estimate_gas` call to fail,
-                    // since tx1 hasn't been broadcasted yet.
-                    //
-                    // Not exiting here will not be a problem when actually broadcasting, because
-                    // for chains where `has_different_gas_calc` returns true,
-                    // we await each transaction before broadcasting the next
-                    // one.
-                    if let Err(err) =
-                        estimate_gas(tx, &provider_info.provider, self.args.gas_estimate_multiplier)
-                            .await
-                    {
-                        trace!("gas estimation failed: {err}");
-
-                        // Restore gas value, since `estimate_gas` will remove it.
-                        tx.set_gas_limit(gas);
-                    }
-                }
-
-                let total_gas = total_gas_per_rpc.entry(tx_rpc.clone()).or_insert(0);
-                *total_gas += tx.gas.expect("gas is set");
+        // Groups the transactions by rpc, in the order in which the rpcs were first used. Chains
+        // don't depend on each other's state, so this keeps the transactions of every chain in
+        // execution order, even if the script switched back and forth between forks.
+        //
+        // A transaction only joins the last group of its rpc if its sender has no transactions in
+        // the groups after it, as different rpcs may point to the same chain. Otherwise, it starts
+        // a new group, so that the transactions of every sender are broadcast in nonce order.
+        let mut transactions_per_rpc: Vec<(String, VecDeque<TransactionWithMetadata>)> = vec![];
+        for tx in self.transactions.iter().cloned() {
+            let group = transactions_per_rpc.iter().rposition(|(rpc, _)| *rpc == tx.rpc);
+            let group = group.filter(|&group| {
+                transactions_per_rpc[group + 1..].iter().all(|(_, txs)| {
+                    txs.iter().all(|other| other.transaction.from != tx.transaction.from)
+                })
+            });
+            match group {
+                Some(group) => transactions_per_rpc[group].1.push_back(tx),
+                None => transactions_per_rpc.push((tx.rpc.clone(), VecDeque::from([tx]))),
             }
+        }
+
+        for (tx_rpc, transactions) in transactions_per_rpc {
+            let provider_info = manager.get_or_init_provider(&tx_rpc, self.args.legacy).await?;
+            let mut new_sequence = VecDeque::with_capacity(transactions.len());
+
+            for mut tx in transactions {
+                // Handles chain specific requirements.
+                tx.transaction.set_chain_id(provider_info.chain);
+
+                if !self.args.skip_simulation {
+                    let tx = tx.tx_mut();
+
+                    if has_different_gas_calc(provider_info.chain) {
+                        trace!("estimating with different gas calculation");
+                        let gas = tx.gas.expect("gas is set by simulation.");
+
+                        // We are trying to show the user an estimation of the total gas usage.
+                        //
+                        // However, some transactions might depend on previous ones. For
+                        // example, tx1 might deploy a contract that tx2 uses. That
+                        // will result in the following `estimate_gas` call to fail,
+                        // since tx1 hasn't been broadcasted yet.
+                        //
+                        // Not exiting here will not be a problem when actually broadcasting,
+                        // because for chains where `has_different_gas_calc` returns true,
+                        // we await each transaction before broadcasting the next
+                        // one.
+                        if let Err(err) = estimate_gas(
+                            tx,
+                            &provider_info.provider,
+                            self.args.gas_estimate_multiplier,
+                        )
+                        .await
+                        {
+                            trace!("gas estimation failed: {err}");
+
+                            // Restore gas value, since `estimate_gas` will remove it.
+                            tx.set_gas_limit(gas);
+                        }
+                    }
 
-            new_sequence.push_back(tx);
-            // We only create a [`ScriptSequence`] object when we collect all the rpc related
-            // transactions.
-            if let Some(next_tx) = txes_iter.peek() {
-                if next_tx.rpc == tx_rpc {
-                    continue;
+                    let total_gas = total_gas_per_rpc.entry(tx_rpc.clone()).or_insert(0);
+                    *total_gas += tx.gas.expect("gas is set");
                 }
+
+                new_sequence.push_back(tx);
             }
 
             let sequence =
                 self.create_sequence(is_multi_deployment, provider_info.chain, new_sequence)?;
 
             sequences.push(sequence);
-
-            new_sequence = VecDeque::new();
         }
 
         if !self.args.skip_simulation {
@@ -5,7 +5,9 @@ use alloy_rpc_types::request::TransactionRequest;
 use alloy_serde::WithOtherFields;
 use eyre::{ContextCompat, Result, WrapErr};
 use foundry_common::{fmt::format_token_raw, ContractData, SELECTOR_LEN};
-use foundry_evm::{constants::DEFAULT_CREATE2_DEPLOYER, traces::CallTraceDecoder};
+use foundry_evm::{
+    constants::DEFAULT_CREATE2_DEPLOYER, executors::AccountDiff, traces::CallTraceDecoder,
+};
 use itertools::Itertools;
 use revm_inspectors::tracing::types::CallKind;
 use serde::{Deserialize, Serialize};
@@ -39,6 +41,9 @@ pub struct TransactionWithMetadata {
     pub transaction: WithOtherFields<TransactionRequest>,
     pub additional_contracts: Vec<AdditionalContract>,
     pub is_fixed_gas_limit: bool,
+    /// The simulated changes of the transaction to the state, shown in the broadcast plan.
+    #[serde(skip)]
+    pub state_diff: BTreeMap<Address, AccountDiff>,
 }
 
 fn default_string() -> Option<String> {
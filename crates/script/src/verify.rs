@@ -150,6 +150,7 @@ impl VerifyBundle {
                     evm_version: None,
                     show_standard_json_input: false,
                     guess_constructor_args: false,
+                    progress: Default::default(),
                 };
 
                 return Some(verify)
@@ -181,7 +181,7 @@ impl ScriptTester {
     }
 
     pub fn broadcast(&mut self, expected: ScriptOutcome) -> &mut Self {
-        self.arg("--broadcast").run(expected)
+        self.args(&["--broadcast", "--yes"]).run(expected)
     }
 
     pub fn resume(&mut self, expected: ScriptOutcome) -> &mut Self {
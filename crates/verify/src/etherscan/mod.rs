@@ -11,7 +11,12 @@ use foundry_block_explorers::{
     Client,
 };
 use foundry_cli::utils::{self, read_constructor_args_file, LoadConfig};
-use foundry_common::{abi::encode_function_args, retry::Retry, shell};
+use foundry_common::{
+    abi::encode_function_args,
+    progress::{self, Phase, ProgressMode},
+    retry::Retry,
+    shell,
+};
 use foundry_compilers::{artifacts::BytecodeObject, Artifact};
 use foundry_config::{Chain, Config};
 use foundry_evm::constants::DEFAULT_CREATE2_DEPLOYER;
@@ -121,6 +126,7 @@ impl VerificationProvider for EtherscanVerificationProvider {
                 resp.result,
                 etherscan.address_url(args.address)
             );
+            progress::event(Phase::Verify, "submitted", &resp.result);
 
             if args.watch {
                 let check_args = VerifyCheckArgs {
@@ -128,6 +134,7 @@ impl VerificationProvider for EtherscanVerificationProvider {
                     etherscan: args.etherscan,
                     retry: RETRY_CHECK_ON_VERIFY,
                     verifier: args.verifier,
+                    progress: args.progress,
                 };
                 // return check_args.run().await
                 return self.check(check_args).await
@@ -149,7 +156,8 @@ impl VerificationProvider for EtherscanVerificationProvider {
             &config,
         )?;
         let retry: Retry = args.retry.into();
-        retry
+        let spinner = progress::spinner("Waiting for verification result");
+        let result = retry
             .run_async(|| {
                 async {
                     let resp = etherscan
@@ -159,10 +167,16 @@ impl VerificationProvider for EtherscanVerificationProvider {
 
                     trace!(target: "forge::verify", ?resp, "Received verification response");
 
-                    eprintln!(
-                        "Contract verification status:\nResponse: `{}`\nDetails: `{}`",
-                        resp.message, resp.result
-                    );
+                    spinner.set_message(format!("Verification status: {}", resp.result));
+                    progress::event(Phase::Verify, "status", &resp.result);
+                    if progress::progress_mode() == ProgressMode::Auto {
+                        spinner.suspend(|| {
+                            eprintln!(
+                                "Contract verification status:\nResponse: `{}`\nDetails: `{}`",
+                                resp.message, resp.result
+                            )
+                        });
+                    }
 
                     if resp.result == "Pending in queue" {
                         return Err(eyre!("Verification is still pending...",))
@@ -173,17 +187,18 @@ impl VerificationProvider for EtherscanVerificationProvider {
                     }
 
                     if resp.result == "Already Verified" {
-                        println!("Contract source code already verified");
+                        spinner.suspend(|| println!("Contract source code already verified"));
                         return Ok(())
                     }
 
                     if resp.status == "0" {
+                        spinner.finish_and_clear();
                         println!("Contract failed to verify.");
                         std::process::exit(1);
                     }
 
                     if resp.result == "Pass - Verified" {
-                        println!("Contract successfully verified");
+                        spinner.suspend(|| println!("Contract successfully verified"));
                     }
 
                     Ok(())
@@ -191,7 +206,9 @@ impl VerificationProvider for EtherscanVerificationProvider {
                 .boxed()
             })
             .await
-            .wrap_err("Checking verification result failed:")
+            .wrap_err("Checking verification result failed:");
+        spinner.finish_and_clear();
+        result
     }
 }
 
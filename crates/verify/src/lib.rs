@@ -13,7 +13,7 @@ use alloy_provider::Provider;
 use clap::{Parser, ValueHint};
 use eyre::Result;
 use foundry_cli::{
-    opts::{EtherscanOpts, RpcOpts},
+    opts::{EtherscanOpts, ProgressArgs, RpcOpts},
     utils::{self, LoadConfig},
 };
 use foundry_common::{compile::ProjectCompiler, ContractsByArtifact};
@@ -146,6 +146,9 @@ pub struct VerifyArgs {
 
     #[command(flatten)]
     pub verifier: VerifierArgs,
+
+    #[command(flatten)]
+    pub progress: ProgressArgs,
 }
 
 impl_figment_convert!(VerifyArgs);
@@ -340,6 +343,9 @@ pub struct VerifyCheckArgs {
 
     #[command(flatten)]
     verifier: VerifierArgs,
+
+    #[command(flatten)]
+    pub progress: ProgressArgs,
 }
 
 impl_figment_convert_cast!(VerifyCheckArgs);
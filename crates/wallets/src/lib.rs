@@ -11,11 +11,13 @@ extern crate tracing;
 pub mod error;
 pub mod multi_wallet;
 pub mod raw_wallet;
+pub mod sender;
 pub mod utils;
 pub mod wallet;
 pub mod wallet_signer;
 
 pub use multi_wallet::MultiWalletOpts;
 pub use raw_wallet::RawWalletOpts;
+pub use sender::{SenderOpts, TransactionSender};
 pub use wallet::WalletOpts;
 pub use wallet_signer::{PendingSigner, WalletSigner};
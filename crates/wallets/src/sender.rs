@@ -0,0 +1,442 @@
+//! Senders that broadcast transactions on behalf of their `from` address.
+
+use crate::wallet_signer::WalletSigner;
+use alloy_consensus::SignableTransaction;
+use alloy_eips::eip2718::Encodable2718;
+use alloy_network::{EthereumWallet, TransactionBuilder, TxSigner};
+use alloy_primitives::{address, keccak256, Address, Bytes, Signature, TxHash, TxKind, B256, U256};
+use alloy_provider::Provider;
+use alloy_rpc_types::TransactionRequest;
+use alloy_serde::WithOtherFields;
+use alloy_signer::Signer;
+use alloy_sol_types::{sol, SolCall, SolValue};
+use async_trait::async_trait;
+use clap::Parser;
+use eyre::{Context, ContextCompat, Result};
+use foundry_common::provider::{try_get_http_provider, RetryProvider};
+use serde::{Deserialize, Serialize};
+use std::{sync::Arc, time::Duration};
+
+/// The address of the ERC-4337 v0.6 entry point.
+pub const ENTRY_POINT_V06: Address = address!("5FF137D4b0FDCD49DcA30c7CF57E578a026d2789");
+
+/// The signature used to estimate the gas of user operations, which passes the ECDSA checks of
+/// common smart accounts without being valid.
+const DUMMY_SIGNATURE: [u8; 65] = alloy_primitives::hex!("fffffffffffffffffffffffffffffff0000000000000000000000000000000007aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa1c");
+
+/// How long to wait for a bundler to include a user operation.
+const USER_OPERATION_TIMEOUT: Duration = Duration::from_secs(300);
+
+sol! {
+    interface IEntryPoint {
+        function getNonce(address sender, uint192 key) external view returns (uint256 nonce);
+    }
+
+    interface ISmartAccount {
+        function execute(address dest, uint256 value, bytes calldata func) external;
+    }
+}
+
+/// Options to send transactions through a remote signer or an ERC-4337 bundler instead of a
+/// local wallet.
+#[derive(Clone, Debug, Default, Serialize, Parser)]
+#[command(next_help_heading = "Sender options")]
+pub struct SenderOpts {
+    /// Sign transactions with the remote signer at the given URL, e.g. Web3Signer.
+    ///
+    /// The signer must support `eth_accounts` and `eth_signTransaction`.
+    #[arg(long, value_name = "URL", conflicts_with = "bundler")]
+    pub remote_signer: Option<String>,
+
+    /// Send transactions as ERC-4337 user operations through the bundler at the given URL.
+    ///
+    /// The senders of the transactions are smart accounts, owned by the single configured
+    /// wallet and implementing `execute(address,uint256,bytes)`.
+    #[arg(long, value_name = "URL")]
+    pub bundler: Option<String>,
+
+    /// The address of the ERC-4337 entry point used by the bundler.
+    ///
+    /// Defaults to the v0.6 entry point.
+    #[arg(long, value_name = "ADDRESS", requires = "bundler")]
+    pub entry_point: Option<Address>,
+}
+
+impl SenderOpts {
+    /// Returns the remote signer, if set.
+    pub fn remote_signer(&self) -> Result<Option<RemoteSigner>> {
+        self.remote_signer.as_deref().map(RemoteSigner::new).transpose()
+    }
+
+    /// Returns the bundler sender for accounts owned by `owner`, if a bundler is set.
+    pub fn bundler(&self, owner: WalletSigner) -> Result<Option<BundlerSender>> {
+        self.bundler
+            .as_deref()
+            .map(|url| BundlerSender::new(url, self.entry_point.unwrap_or(ENTRY_POINT_V06), owner))
+            .transpose()
+    }
+}
+
+/// Sends transactions on behalf of their `from` address.
+#[async_trait]
+pub trait TransactionSender: Send + Sync {
+    /// Sends the transaction to the chain of `provider`, returning the hash of the transaction
+    /// that executes it.
+    async fn send_transaction(
+        &self,
+        provider: &RetryProvider,
+        tx: WithOtherFields<TransactionRequest>,
+    ) -> Result<TxHash>;
+
+    /// Returns whether the transactions are executed with the nonce of their `from` address.
+    ///
+    /// Smart accounts keep separate nonces for user operations, so the nonces of their
+    /// transactions can't be checked before sending them.
+    fn uses_sender_nonce(&self) -> bool {
+        true
+    }
+
+    /// Signs the hash with the key of the sender.
+    ///
+    /// Used for transactions of custom types, which are signed and encoded by their type and
+    /// sent with `eth_sendRawTransaction`.
+    async fn sign_hash(&self, _hash: &B256) -> Result<Signature> {
+        eyre::bail!("this sender can't sign transactions of custom types")
+    }
+}
+
+/// Sends transactions with `eth_sendTransaction`, relying on the node to sign them.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct UnlockedSender;
+
+#[async_trait]
+impl TransactionSender for UnlockedSender {
+    async fn send_transaction(
+        &self,
+        provider: &RetryProvider,
+        tx: WithOtherFields<TransactionRequest>,
+    ) -> Result<TxHash> {
+        debug!("sending transaction from unlocked account {:?}: {:?}", tx.from, tx);
+        Ok(*provider.send_transaction(tx).await?.tx_hash())
+    }
+}
+
+/// Signs transactions with a local key or a hardware wallet, and sends them with
+/// `eth_sendRawTransaction`.
+#[derive(Clone)]
+pub struct WalletSender {
+    wallet: EthereumWallet,
+    signer: Arc<WalletSigner>,
+}
+
+impl WalletSender {
+    /// Creates a sender signing with `signer`.
+    pub fn new(signer: WalletSigner) -> Self {
+        let signer = Arc::new(signer);
+        Self { wallet: EthereumWallet::new(SharedSigner(signer.clone())), signer }
+    }
+}
+
+#[async_trait]
+impl TransactionSender for WalletSender {
+    async fn send_transaction(
+        &self,
+        provider: &RetryProvider,
+        tx: WithOtherFields<TransactionRequest>,
+    ) -> Result<TxHash> {
+        debug!("sending transaction: {:?}", tx);
+        let signed = tx.build(&self.wallet).await?;
+        Ok(*provider.send_raw_transaction(signed.encoded_2718().as_ref()).await?.tx_hash())
+    }
+
+    async fn sign_hash(&self, hash: &B256) -> Result<Signature> {
+        Ok(self.signer.sign_hash(hash).await?)
+    }
+}
+
+/// A [`WalletSigner`] shared between a [`WalletSender`] and its wallet.
+struct SharedSigner(Arc<WalletSigner>);
+
+#[async_trait]
+impl TxSigner<Signature> for SharedSigner {
+    fn address(&self) -> Address {
+        TxSigner::address(&*self.0)
+    }
+
+    async fn sign_transaction(
+        &self,
+        tx: &mut dyn SignableTransaction<Signature>,
+    ) -> alloy_signer::Result<Signature> {
+        self.0.sign_transaction(tx).await
+    }
+}
+
+/// Signs transactions with a remote signer, e.g. Web3Signer, and sends them with
+/// `eth_sendRawTransaction`.
+#[derive(Clone)]
+pub struct RemoteSigner {
+    provider: RetryProvider,
+}
+
+impl RemoteSigner {
+    /// Creates a signer for the remote signer at `url`.
+    pub fn new(url: &str) -> Result<Self> {
+        let provider = try_get_http_provider(url)
+            .wrap_err_with(|| format!("invalid remote signer URL: {url}"))?;
+        Ok(Self { provider })
+    }
+
+    /// Returns the accounts the remote signer can sign for.
+    pub async fn accounts(&self) -> Result<Vec<Address>> {
+        self.provider.get_accounts().await.wrap_err("Failed to get the remote signer accounts")
+    }
+
+    /// Signs the transaction, returning it EIP-2718 encoded.
+    pub async fn sign_transaction(
+        &self,
+        tx: &WithOtherFields<TransactionRequest>,
+    ) -> Result<Bytes> {
+        self.provider
+            .raw_request("eth_signTransaction".into(), (tx,))
+            .await
+            .wrap_err("Failed to sign the transaction with the remote signer")
+    }
+}
+
+#[async_trait]
+impl TransactionSender for RemoteSigner {
+    async fn send_transaction(
+        &self,
+        provider: &RetryProvider,
+        tx: WithOtherFields<TransactionRequest>,
+    ) -> Result<TxHash> {
+        debug!("sending transaction signed remotely: {:?}", tx);
+        let signed = self.sign_transaction(&tx).await?;
+        Ok(*provider.send_raw_transaction(&signed).await?.tx_hash())
+    }
+}
+
+/// An ERC-4337 v0.6 user operation.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserOperation {
+    pub sender: Address,
+    pub nonce: U256,
+    pub init_code: Bytes,
+    pub call_data: Bytes,
+    pub call_gas_limit: U256,
+    pub verification_gas_limit: U256,
+    pub pre_verification_gas: U256,
+    pub max_fee_per_gas: U256,
+    pub max_priority_fee_per_gas: U256,
+    pub paymaster_and_data: Bytes,
+    pub signature: Bytes,
+}
+
+impl UserOperation {
+    /// Returns the hash that the owner of the sender signs, as computed by the entry point.
+    pub fn hash(&self, entry_point: Address, chain_id: u64) -> B256 {
+        let packed = (
+            self.sender,
+            self.nonce,
+            keccak256(&self.init_code),
+            keccak256(&self.call_data),
+            self.call_gas_limit,
+            self.verification_gas_limit,
+            self.pre_verification_gas,
+            self.max_fee_per_gas,
+            self.max_priority_fee_per_gas,
+            keccak256(&self.paymaster_and_data),
+        )
+            .abi_encode();
+        keccak256((keccak256(packed), entry_point, U256::from(chain_id)).abi_encode())
+    }
+}
+
+/// The gas limits of a user operation estimated by a bundler.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct UserOperationGas {
+    pre_verification_gas: U256,
+    verification_gas_limit: U256,
+    call_gas_limit: U256,
+}
+
+/// The receipt of a user operation included by a bundler.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct UserOperationReceipt {
+    success: bool,
+    receipt: BundleReceipt,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BundleReceipt {
+    transaction_hash: TxHash,
+}
+
+/// Sends transactions as ERC-4337 user operations of smart accounts through a bundler.
+///
+/// Every transaction is wrapped in a call to `execute(to, value, data)` of its sender, signed by
+/// the owner of the account. Contract creations must go through a factory, e.g. the CREATE2
+/// deployer.
+#[derive(Clone)]
+pub struct BundlerSender {
+    bundler: RetryProvider,
+    entry_point: Address,
+    owner: Arc<WalletSigner>,
+}
+
+impl BundlerSender {
+    /// Creates a sender for the bundler at `url`, signing with `owner`.
+    pub fn new(url: &str, entry_point: Address, owner: WalletSigner) -> Result<Self> {
+        let bundler =
+            try_get_http_provider(url).wrap_err_with(|| format!("invalid bundler URL: {url}"))?;
+        Ok(Self { bundler, entry_point, owner: Arc::new(owner) })
+    }
+
+    /// Builds the unsigned user operation executing the transaction.
+    async fn user_operation(
+        &self,
+        provider: &RetryProvider,
+        tx: &TransactionRequest,
+    ) -> Result<UserOperation> {
+        let sender = tx.from.wrap_err("transaction has no sender")?;
+        let Some(TxKind::Call(dest)) = tx.to else {
+            eyre::bail!(
+                "contract creations can't be sent as user operations; deploy through a factory, \
+                 e.g. with CREATE2"
+            )
+        };
+        let call_data = ISmartAccount::executeCall {
+            dest,
+            value: tx.value.unwrap_or_default(),
+            func: tx.input.input().cloned().unwrap_or_default(),
+        }
+        .abi_encode();
+
+        let get_nonce = TransactionRequest {
+            to: Some(TxKind::Call(self.entry_point)),
+            input: Bytes::from(
+                IEntryPoint::getNonceCall { sender, key: Default::default() }.abi_encode(),
+            )
+            .into(),
+            ..Default::default()
+        };
+        let nonce = provider
+            .call(&WithOtherFields::new(get_nonce))
+            .await
+            .wrap_err("Failed to get the nonce of the smart account")?;
+        let nonce = IEntryPoint::getNonceCall::abi_decode_returns(&nonce, true)?.nonce;
+
+        let max_fee_per_gas =
+            tx.max_fee_per_gas.or(tx.gas_price).wrap_err("transaction has no gas price")?;
+        let max_priority_fee_per_gas = tx.max_priority_fee_per_gas.unwrap_or(max_fee_per_gas);
+
+        Ok(UserOperation {
+            sender,
+            nonce,
+            call_data: call_data.into(),
+            max_fee_per_gas: U256::from(max_fee_per_gas),
+            max_priority_fee_per_gas: U256::from(max_priority_fee_per_gas),
+            ..Default::default()
+        })
+    }
+
+    /// Waits for the bundler to include the user operation, returning the hash of the
+    /// transaction that includes it.
+    async fn wait_for_inclusion(&self, hash: B256) -> Result<TxHash> {
+        let started = std::time::Instant::now();
+        loop {
+            let receipt: Option<UserOperationReceipt> = self
+                .bundler
+                .raw_request("eth_getUserOperationReceipt".into(), (hash,))
+                .await
+                .wrap_err("Failed to get the receipt of the user operation")?;
+            if let Some(receipt) = receipt {
+                if !receipt.success {
+                    eyre::bail!(
+                        "user operation {hash} reverted in transaction {}",
+                        receipt.receipt.transaction_hash
+                    );
+                }
+                return Ok(receipt.receipt.transaction_hash)
+            }
+            if started.elapsed() > USER_OPERATION_TIMEOUT {
+                eyre::bail!("timed out waiting for the bundler to include user operation {hash}");
+            }
+            tokio::time::sleep(Duration::from_secs(2)).await;
+        }
+    }
+}
+
+#[async_trait]
+impl TransactionSender for BundlerSender {
+    async fn send_transaction(
+        &self,
+        provider: &RetryProvider,
+        tx: WithOtherFields<TransactionRequest>,
+    ) -> Result<TxHash> {
+        let mut op = self.user_operation(provider, &tx).await?;
+
+        op.signature = DUMMY_SIGNATURE.into();
+        let gas: UserOperationGas = self
+            .bundler
+            .raw_request("eth_estimateUserOperationGas".into(), (&op, self.entry_point))
+            .await
+            .wrap_err("Failed to estimate the gas of the user operation")?;
+        op.pre_verification_gas = gas.pre_verification_gas;
+        op.verification_gas_limit = gas.verification_gas_limit;
+        op.call_gas_limit = gas.call_gas_limit;
+
+        let chain_id = provider.get_chain_id().await?;
+        let signature =
+            self.owner.sign_message(op.hash(self.entry_point, chain_id).as_slice()).await?;
+        op.signature = signature.as_bytes().into();
+
+        debug!("sending user operation: {:?}", op);
+        let hash: B256 = self
+            .bundler
+            .raw_request("eth_sendUserOperation".into(), (&op, self.entry_point))
+            .await
+            .wrap_err("Failed to send the user operation")?;
+        self.wait_for_inclusion(hash).await
+    }
+
+    fn uses_sender_nonce(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serializes_user_operation() {
+        let op = UserOperation {
+            sender: Address::with_last_byte(1),
+            nonce: U256::from(2),
+            call_gas_limit: U256::from(3),
+            ..Default::default()
+        };
+        let json = serde_json::to_value(&op).unwrap();
+        assert_eq!(json["sender"], "0x0000000000000000000000000000000000000001");
+        assert_eq!(json["nonce"], "0x2");
+        assert_eq!(json["callGasLimit"], "0x3");
+        assert_eq!(json["initCode"], "0x");
+        assert_eq!(json["paymasterAndData"], "0x");
+    }
+
+    #[test]
+    fn user_operation_hash_commits_to_chain() {
+        let op = UserOperation { sender: Address::with_last_byte(1), ..Default::default() };
+        let hash = op.hash(ENTRY_POINT_V06, 1);
+        assert_ne!(hash, op.hash(ENTRY_POINT_V06, 10));
+        assert_ne!(hash, op.hash(Address::ZERO, 1));
+
+        let signed = UserOperation { signature: DUMMY_SIGNATURE.into(), ..op.clone() };
+        assert_eq!(hash, signed.hash(ENTRY_POINT_V06, 1));
+    }
+}
@@ -826,4 +826,68 @@ contract AssertionsTest is DSTest {
 
         vm.assertApproxEqRel(uint256(0), uint256(0), uint256(0));
     }
+
+    function testAssertApproxEqUlp() public {
+        vm.assertApproxEqUlp(uint256(1e18 + 3), uint256(1e18), 3, 18);
+        vm.assertApproxEqUlp(int256(-1e6), int256(-1e6 + 1), 1, 6);
+
+        vm._expectCheatcodeRevert(
+            bytes(
+                "assertion failed: 1.000000000000000003 !~= 1.000000000000000000 (max delta: 2 ulp, real delta: 3 ulp)"
+            )
+        );
+        vm.assertApproxEqUlp(uint256(1e18 + 3), uint256(1e18), 2, 18);
+
+        vm._expectCheatcodeRevert(
+            bytes(string.concat(errorMessage, ": -1.000000 !~= -0.999999 (max delta: 0 ulp, real delta: 1 ulp)"))
+        );
+        vm.assertApproxEqUlp(int256(-1e6), int256(-1e6 + 1), 0, 6, errorMessage);
+    }
+
+    function testAssertApproxEqRelWad() public {
+        vm.assertApproxEqRelWad(uint256(1.05e18), uint256(1e18), 0.05e18);
+        vm.assertApproxEqRelWad(int256(-1.05e18), int256(-1e18), 0.05e18);
+        // compared without overflowing
+        vm.assertApproxEqRelWad(type(uint256).max, type(uint256).max - 1, 1);
+        vm.assertApproxEqRelWad(int256(0), int256(0), 0);
+
+        vm._expectCheatcodeRevert(
+            bytes(
+                "assertion failed: 1.050000000000000000 !~= 1.000000000000000000 (max delta: 1.0000000000000000%, real delta: 5.0000000000000000%)"
+            )
+        );
+        vm.assertApproxEqRelWad(uint256(1.05e18), uint256(1e18), 0.01e18);
+
+        vm._expectCheatcodeRevert(
+            bytes(
+                string.concat(
+                    errorMessage,
+                    ": 1.000000000000000000 !~= 0.000000000000000000 (max delta: 0.0000000000000000%, real delta: undefined)"
+                )
+            )
+        );
+        vm.assertApproxEqRelWad(int256(1e18), int256(0), 0, errorMessage);
+    }
+
+    function testAssertApproxEqRelRay() public {
+        vm.assertApproxEqRelRay(uint256(1e27 + 1), uint256(1e27), 1);
+        vm.assertApproxEqRelRay(int256(-2e27), int256(-1e27), 1e27);
+
+        vm._expectCheatcodeRevert(
+            bytes(
+                "assertion failed: 1.000000000000000000000000001 !~= 1.000000000000000000000000000 (max delta: 0.0000000000000000000000000%, real delta: 0.0000000000000000000000001%)"
+            )
+        );
+        vm.assertApproxEqRelRay(uint256(1e27 + 1), uint256(1e27), 0);
+
+        vm._expectCheatcodeRevert(
+            bytes(
+                string.concat(
+                    errorMessage,
+                    ": -3.000000000000000000000000000 !~= -1.000000000000000000000000000 (max delta: 100.0000000000000000000000000%, real delta: 200.0000000000000000000000000%)"
+                )
+            )
+        );
+        vm.assertApproxEqRelRay(int256(-3e27), int256(-1e27), 1e27, errorMessage);
+    }
 }
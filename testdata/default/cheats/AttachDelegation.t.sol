@@ -0,0 +1,73 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+pragma solidity 0.8.18;
+
+import "ds-test/test.sol";
+import "cheats/Vm.sol";
+
+contract Wallet {
+    uint256 public count;
+
+    function execute(address target, bytes calldata data) public payable returns (bytes memory) {
+        require(msg.sender == address(this), "only self");
+        (bool success, bytes memory result) = target.call{value: msg.value}(data);
+        require(success, "call failed");
+        return result;
+    }
+
+    function increment() public {
+        count++;
+    }
+}
+
+contract AttachDelegationTest is DSTest {
+    Vm constant vm = Vm(HEVM_ADDRESS);
+
+    uint256 constant ALICE_KEY = 0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80;
+    address constant ALICE = 0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266;
+
+    Wallet implementation;
+
+    function setUp() public {
+        implementation = new Wallet();
+    }
+
+    function testAttachDelegation() public {
+        Vm.SignedDelegation memory delegation = vm.signDelegation(address(implementation), ALICE_KEY);
+        assertEq(delegation.implementation, address(implementation));
+        assertEq(delegation.nonce, 0);
+
+        vm.attachDelegation(delegation);
+        assertEq(vm.getNonce(ALICE), 1);
+        assertEq(keccak256(ALICE.code), keccak256(abi.encodePacked(hex"ef0100", address(implementation))));
+
+        // Calls to the account execute the implementation with the storage of the account.
+        Wallet(ALICE).increment();
+        assertEq(Wallet(ALICE).count(), 1);
+        assertEq(implementation.count(), 0);
+
+        vm.prank(ALICE);
+        bytes memory result = Wallet(ALICE).execute(ALICE, abi.encodeCall(Wallet.count, ()));
+        assertEq(abi.decode(result, (uint256)), 1);
+    }
+
+    function testSignAndAttachDelegation() public {
+        vm.signAndAttachDelegation(address(implementation), ALICE_KEY);
+        Wallet(ALICE).increment();
+        assertEq(Wallet(ALICE).count(), 1);
+
+        // A new delegation replaces the previous one.
+        Wallet other = new Wallet();
+        vm.signAndAttachDelegation(address(other), ALICE_KEY);
+        assertEq(vm.getNonce(ALICE), 2);
+        assertEq(Wallet(ALICE).count(), 1);
+    }
+
+    function testRevertIfReplayed() public {
+        Vm.SignedDelegation memory delegation = vm.signDelegation(address(implementation), ALICE_KEY);
+        vm.attachDelegation(delegation);
+
+        try vm.attachDelegation(delegation) {
+            fail();
+        } catch {}
+    }
+}
@@ -389,6 +389,22 @@ contract MultiChainBroadcastNoLink is DSTest {
         new NoLink();
     }
 
+    function deployInterleaved(string memory sforkA, string memory sforkB) public {
+        uint256 forkA = vm.createSelectFork(sforkA);
+        vm.broadcast(address(ACCOUNT_A));
+        new NoLink();
+
+        vm.createSelectFork(sforkB);
+        vm.broadcast(address(ACCOUNT_B));
+        new NoLink();
+        vm.broadcast(address(ACCOUNT_A));
+        new NoLink();
+
+        vm.selectFork(forkA);
+        vm.broadcast(address(ACCOUNT_A));
+        new NoLink();
+    }
+
     function deployError(string memory sforkA, string memory sforkB) public {
         uint256 forkA = vm.createFork(sforkA);
         uint256 forkB = vm.createFork(sforkB);
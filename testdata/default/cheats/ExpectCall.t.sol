@@ -494,3 +494,99 @@ contract ExpectCallMixedTest is DSTest {
         target.add(1, 2);
     }
 }
+
+contract ExpectCallMetadataTest is DSTest {
+    Vm constant vm = Vm(HEVM_ADDRESS);
+
+    function exposed_addHardGasLimit(NestedContract target) public {
+        target.addHardGasLimit();
+    }
+
+    function exposed_forwardPay(NestedContract target) public {
+        target.forwardPay{value: 1}();
+    }
+
+    function exposed_sum(NestedContract target) public {
+        target.sum();
+    }
+
+    function anyMetadata() internal pure returns (Vm.ExpectedCallMetadata memory) {
+        return Vm.ExpectedCallMetadata({
+            minGas: 0,
+            maxGas: type(uint64).max,
+            minValue: 0,
+            maxValue: type(uint256).max,
+            depth: 0,
+            context: Vm.ExpectedCallContext.Any
+        });
+    }
+
+    function testExpectCallWithGas() public {
+        Contract inner = new Contract();
+        NestedContract target = new NestedContract(inner);
+        vm.expectCallWithGas(address(inner), 49_000, 50_000, abi.encodeWithSelector(inner.add.selector, 1, 1));
+        this.exposed_addHardGasLimit(target);
+    }
+
+    function testExpectCallWithGasExcludesStipend() public {
+        Contract inner = new Contract();
+        NestedContract target = new NestedContract(inner);
+        vm.expectCallWithGas(address(inner), 50_000, 50_000, abi.encodeWithSelector(inner.pay.selector, 1), 1);
+        this.exposed_forwardPay(target);
+    }
+
+    function testFailExpectCallWithGasOutOfRange() public {
+        Contract inner = new Contract();
+        NestedContract target = new NestedContract(inner);
+        vm.expectCallWithGas(address(inner), 0, 49_999, abi.encodeWithSelector(inner.add.selector, 1, 1));
+        this.exposed_addHardGasLimit(target);
+    }
+
+    function testExpectCallWithMetadataStaticContext() public {
+        Contract inner = new Contract();
+        NestedContract target = new NestedContract(inner);
+        Vm.ExpectedCallMetadata memory metadata = anyMetadata();
+        metadata.depth = 3;
+        metadata.context = Vm.ExpectedCallContext.Static;
+        vm.expectCallWithMetadata(address(inner), abi.encodeWithSelector(inner.numberA.selector), metadata);
+        this.exposed_sum(target);
+    }
+
+    function testFailExpectCallWithMetadataWrongContext() public {
+        Contract inner = new Contract();
+        NestedContract target = new NestedContract(inner);
+        Vm.ExpectedCallMetadata memory metadata = anyMetadata();
+        metadata.context = Vm.ExpectedCallContext.NonStatic;
+        vm.expectCallWithMetadata(address(inner), abi.encodeWithSelector(inner.numberA.selector), metadata);
+        this.exposed_sum(target);
+    }
+
+    function testFailExpectCallWithMetadataWrongDepth() public {
+        Contract inner = new Contract();
+        NestedContract target = new NestedContract(inner);
+        Vm.ExpectedCallMetadata memory metadata = anyMetadata();
+        metadata.depth = 2;
+        vm.expectCallWithMetadata(address(inner), abi.encodeWithSelector(inner.numberA.selector), metadata);
+        this.exposed_sum(target);
+    }
+
+    function testExpectCallWithMetadataValueRange() public {
+        Contract inner = new Contract();
+        NestedContract target = new NestedContract(inner);
+        Vm.ExpectedCallMetadata memory metadata = anyMetadata();
+        metadata.minValue = 1;
+        metadata.maxValue = 10;
+        metadata.context = Vm.ExpectedCallContext.NonStatic;
+        vm.expectCallWithMetadata(address(inner), abi.encodeWithSelector(inner.pay.selector), metadata, 1);
+        this.exposed_forwardPay(target);
+    }
+
+    function testFailExpectCallWithMetadataValueOutOfRange() public {
+        Contract inner = new Contract();
+        NestedContract target = new NestedContract(inner);
+        Vm.ExpectedCallMetadata memory metadata = anyMetadata();
+        metadata.minValue = 2;
+        vm.expectCallWithMetadata(address(inner), abi.encodeWithSelector(inner.pay.selector), metadata);
+        this.exposed_forwardPay(target);
+    }
+}
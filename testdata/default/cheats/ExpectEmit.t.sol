@@ -24,6 +24,8 @@ contract Emitter {
 
     event SomethingNonIndexed(uint256 data);
 
+    event Multiple(uint256 indexed topic1, uint256 a, uint256 b, uint256 c);
+
     function emitEvent(uint256 topic1, uint256 topic2, uint256 topic3, uint256 data) public {
         emit Something(topic1, topic2, topic3, data);
     }
@@ -38,6 +40,10 @@ contract Emitter {
         emit Something(topic1[1], topic2[1], topic3[1], data[1]);
     }
 
+    function emitMultipleData(uint256 topic1, uint256 a, uint256 b, uint256 c) public {
+        emit Multiple(topic1, a, b, c);
+    }
+
     function emitAndNest() public {
         emit Something(1, 2, 3, 4);
         emitNested(Emitter(address(this)), 1, 2, 3, 4);
@@ -119,6 +125,8 @@ contract ExpectEmitTest is DSTest {
 
     event SomethingNonIndexed(uint256 data);
 
+    event Multiple(uint256 indexed topic1, uint256 a, uint256 b, uint256 c);
+
     event A(uint256 indexed topic1);
     event B(uint256 indexed topic1);
     event C(uint256 indexed topic1);
@@ -569,6 +577,90 @@ contract ExpectEmitTest is DSTest {
         emitter.emitWindowAndOnTest(this);
     }
 
+    /// emitWindow() emits events A, B, C, D, E.
+    /// We should be able to match [E, C, A] when expecting them unordered.
+    function testCanMatchUnorderedEvents() public {
+        vm.expectEmitUnordered();
+        emit E(5);
+        vm.expectEmitUnordered(true, false, false, true);
+        emit C(3);
+        vm.expectEmitUnordered(address(emitter));
+        emit A(1);
+
+        emitter.emitWindow();
+    }
+
+    /// emitWindow() emits events A, B, C, D, E.
+    /// We should not be able to match [A, A] unordered, as each expected event must be matched by
+    /// a distinct log.
+    function testFailUnorderedEventsMustBeDistinct() public {
+        vm.expectEmitUnordered();
+        emit A(1);
+        vm.expectEmitUnordered();
+        emit A(1);
+
+        emitter.emitWindow();
+    }
+
+    /// emitWindow() emits events A, B, C, D, E.
+    /// We should be able to match the ordered [C, D] while also matching an unordered [A].
+    function testCanMatchOrderedAndUnorderedEvents() public {
+        vm.expectEmit();
+        emit C(3);
+        vm.expectEmit();
+        emit D(4);
+        vm.expectEmitUnordered();
+        emit A(1);
+
+        emitter.emitWindow();
+    }
+
+    /// emitWindow() emits events A, B, C, D, E.
+    /// We should not be able to match the ordered [D, C], even with an unordered expectation around.
+    function testFailOrderedEventsWithUnorderedEvents() public {
+        vm.expectEmitUnordered();
+        emit E(5);
+        vm.expectEmit();
+        emit D(4);
+        vm.expectEmit();
+        emit C(3);
+
+        emitter.emitWindow();
+    }
+
+    function testExpectEmitDataMask() public {
+        bool[] memory checkData = new bool[](3);
+        checkData[0] = true;
+        checkData[2] = true;
+
+        vm.expectEmit(true, false, false, checkData);
+        emit Multiple(1, 2, 0, 4);
+        emitter.emitMultipleData(1, 2, 3, 4);
+
+        vm.expectEmit(true, false, false, checkData, address(emitter));
+        emit Multiple(1, 2, 0, 4);
+        emitter.emitMultipleData(1, 2, 5, 4);
+    }
+
+    /// Data words past the end of the mask are always checked.
+    function testFailExpectEmitDataMaskShorterThanData() public {
+        bool[] memory checkData = new bool[](1);
+
+        vm.expectEmit(true, false, false, checkData);
+        emit Multiple(1, 2, 0, 4);
+        emitter.emitMultipleData(1, 2, 3, 4);
+    }
+
+    function testFailExpectEmitDataMask() public {
+        bool[] memory checkData = new bool[](3);
+        checkData[0] = true;
+        checkData[2] = true;
+
+        vm.expectEmit(true, false, false, checkData);
+        emit Multiple(1, 2, 3, 0);
+        emitter.emitMultipleData(1, 2, 3, 4);
+    }
+
     /// We should not be able to expect emits if we're expecting the function reverts, no matter
     /// if the function reverts or not.
     function testFailEmitWindowWithRevertDisallowed() public {
@@ -0,0 +1,72 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+pragma solidity 0.8.18;
+
+import "ds-test/test.sol";
+import "cheats/Vm.sol";
+
+contract FixtureToken {
+    mapping(address => uint256) public balanceOf;
+
+    constructor(address holder, uint256 supply) {
+        balanceOf[holder] = supply;
+    }
+
+    function transfer(address to, uint256 amount) external {
+        balanceOf[msg.sender] -= amount;
+        balanceOf[to] += amount;
+    }
+}
+
+abstract contract ProtocolFixture is DSTest {
+    Vm constant vm = Vm(HEVM_ADDRESS);
+
+    FixtureToken token;
+
+    function setUp() public {
+        (bool found, bytes memory data) = vm.loadFixture("protocol");
+        if (found) {
+            token = abi.decode(data, (FixtureToken));
+            return;
+        }
+        token = new FixtureToken(address(0xbeef), 1000);
+        vm.saveFixture("protocol", abi.encode(token));
+    }
+}
+
+contract FixtureTest is ProtocolFixture {
+    function testFixtureIsSetUp() public {
+        assertEq(token.balanceOf(address(0xbeef)), 1000);
+    }
+
+    function testLoadFixtureRestoresState() public {
+        vm.prank(address(0xbeef));
+        token.transfer(address(this), 100);
+        assertEq(token.balanceOf(address(this)), 100);
+
+        (bool found, bytes memory data) = vm.loadFixture("protocol");
+        assertTrue(found);
+        assertEq(abi.decode(data, (address)), address(token));
+        assertEq(token.balanceOf(address(0xbeef)), 1000);
+        assertEq(token.balanceOf(address(this)), 0);
+    }
+
+    function testCanDeployAfterFixture() public {
+        FixtureToken other = new FixtureToken(address(this), 1);
+        assertTrue(address(other) != address(token));
+        assertEq(token.balanceOf(address(0xbeef)), 1000);
+    }
+
+    function testLoadMissingFixture() public {
+        (bool found, bytes memory data) = vm.loadFixture("missing");
+        assertTrue(!found);
+        assertEq(data.length, 0);
+    }
+}
+
+contract FixtureReuseTest is ProtocolFixture {
+    function testFixtureIsShared() public {
+        (bool found,) = vm.loadFixture("protocol");
+        assertTrue(found);
+        assertEq(token.balanceOf(address(0xbeef)), 1000);
+    }
+}
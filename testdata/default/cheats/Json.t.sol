@@ -372,3 +372,70 @@ contract WriteJsonTest is DSTest {
         assertEq(decodedAddress, ex);
     }
 }
+
+contract JsonTypeTest is DSTest {
+    Vm constant vm = Vm(HEVM_ADDRESS);
+
+    struct Inner {
+        address owner;
+        bytes32 salt;
+    }
+
+    struct Outer {
+        uint256 amount;
+        string name;
+        Inner[] inners;
+    }
+
+    string constant schema_Outer = "Outer(uint256 amount,string name,Inner[] inners)Inner(address owner,bytes32 salt)";
+
+    function test_parseJsonType() public {
+        string memory json =
+            '{"name":"foo","inners":[{"salt":"0x0000000000000000000000000000000000000000000000000000000000000001","owner":"0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266"}],"amount":"0x10"}';
+        Outer memory outer = abi.decode(vm.parseJsonType(json, schema_Outer), (Outer));
+        assertEq(outer.amount, 16);
+        assertEq(outer.name, "foo");
+        assertEq(outer.inners.length, 1);
+        assertEq(outer.inners[0].owner, 0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266);
+        assertEq(outer.inners[0].salt, bytes32(uint256(1)));
+
+        uint256 amount = abi.decode(vm.parseJsonType(json, ".amount", "uint256"), (uint256));
+        assertEq(amount, 16);
+
+        Inner[] memory inners = abi.decode(
+            vm.parseJsonTypeArray(json, ".inners", "Inner(address owner,bytes32 salt)"), (Inner[])
+        );
+        assertEq(inners[0].owner, outer.inners[0].owner);
+    }
+
+    function test_parseJsonTypeRoundTrip() public {
+        Inner[] memory inners = new Inner[](1);
+        inners[0] = Inner(address(1), bytes32(uint256(2)));
+        Outer memory outer = Outer(42, "baz", inners);
+
+        string memory json = vm.serializeJsonType(schema_Outer, abi.encode(outer));
+        bytes memory encoded = vm.parseJsonType(json, schema_Outer);
+        assertEq(encoded, abi.encode(outer));
+
+        Outer memory decoded = abi.decode(encoded, (Outer));
+        assertEq(decoded.amount, 42);
+        assertEq(decoded.name, "baz");
+        assertEq(decoded.inners[0].owner, address(1));
+        assertEq(decoded.inners[0].salt, bytes32(uint256(2)));
+    }
+
+    function test_serializeJsonType() public {
+        Inner[] memory inners = new Inner[](2);
+        inners[0] = Inner(address(1), bytes32(uint256(2)));
+        inners[1] = Inner(address(3), bytes32(uint256(4)));
+        Outer memory outer = Outer(type(uint256).max, "bar", inners);
+
+        string memory json = vm.serializeJsonType(schema_Outer, abi.encode(outer));
+        Outer memory decoded = abi.decode(vm.parseJsonType(json, schema_Outer), (Outer));
+        assertEq(keccak256(abi.encode(decoded)), keccak256(abi.encode(outer)));
+
+        json = vm.serializeJsonType("root", "outer", schema_Outer, abi.encode(outer));
+        decoded = abi.decode(vm.parseJsonType(json, ".outer", schema_Outer), (Outer));
+        assertEq(decoded.name, "bar");
+    }
+}
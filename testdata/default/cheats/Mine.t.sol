@@ -0,0 +1,55 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+pragma solidity 0.8.18;
+
+import "ds-test/test.sol";
+import "cheats/Vm.sol";
+
+contract MineTest is DSTest {
+    Vm constant vm = Vm(HEVM_ADDRESS);
+
+    function setUp() public {
+        vm.roll(100);
+        vm.warp(1000);
+        vm.fee(1 gwei);
+    }
+
+    function testMine() public {
+        vm.mine(10);
+        assertEq(vm.getBlockNumber(), 110, "mine failed");
+        assertEq(vm.getBlockTimestamp(), 1120, "mine failed");
+        assertEq(block.basefee, 1 gwei, "mine failed");
+    }
+
+    function testMineTo() public {
+        vm.mineTo(150);
+        assertEq(vm.getBlockNumber(), 150, "mineTo failed");
+        assertEq(vm.getBlockTimestamp(), 1600, "mineTo failed");
+
+        vm._expectCheatcodeRevert(
+            bytes("cannot mine to block 149 before the current block 150; use `roll` instead")
+        );
+        vm.mineTo(149);
+    }
+
+    function testMineSchedule() public {
+        vm.setMineSchedule(2, 10_000);
+        vm.mine(2);
+        assertEq(vm.getBlockTimestamp(), 1004, "schedule failed");
+        assertEq(block.basefee, 1.265625 gwei, "base fee did not increase");
+
+        vm.setMineSchedule(2, 0);
+        vm.mine(1);
+        assertEq(block.basefee, 1.107421875 gwei, "base fee did not decrease");
+
+        vm._expectCheatcodeRevert(bytes("gas used must be at most 10000 basis points"));
+        vm.setMineSchedule(2, 10_001);
+    }
+
+    function testMineBlockHashes() public {
+        vm.mine(300);
+        assertEq(blockhash(400), 0, "current block has a hash");
+        assertEq(blockhash(399), keccak256("399"), "mined block hash is incorrect");
+        assertEq(blockhash(144), keccak256("144"), "mined block hash is incorrect");
+        assertEq(blockhash(143), 0, "block out of range has a hash");
+    }
+}
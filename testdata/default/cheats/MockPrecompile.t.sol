@@ -0,0 +1,54 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+pragma solidity 0.8.18;
+
+import "ds-test/test.sol";
+import "cheats/Vm.sol";
+
+contract MockPrecompileTest is DSTest {
+    Vm constant vm = Vm(HEVM_ADDRESS);
+
+    function setUp() public {
+        vm.mockPrecompile(address(2), "foo", abi.encode(bytes32(uint256(1))));
+    }
+
+    function testMockPrecompileFromSetUp() public {
+        assertEq(sha256("foo"), bytes32(uint256(1)));
+        // other inputs are executed by the precompile
+        assertEq(sha256("bar"), 0xfcde2b2edba56bf408601fb721fe9b5c338d10ee429ea04fae5511b68fbf8fb9);
+    }
+
+    function testMockEcrecover() public {
+        bytes32 digest = keccak256("hello");
+        bytes32 r = bytes32(uint256(1));
+        bytes32 s = bytes32(uint256(2));
+        vm.mockPrecompile(address(1), abi.encode(digest, uint8(27), r, s), abi.encode(address(0xdead)));
+        assertEq(ecrecover(digest, 27, r, s), address(0xdead));
+
+        (uint8 v, bytes32 r1, bytes32 s1) = vm.sign(1, digest);
+        assertEq(ecrecover(digest, v, r1, s1), vm.addr(1));
+    }
+
+    function testMockBn254Add() public {
+        bytes memory input = abi.encode(uint256(1), uint256(2), uint256(1), uint256(2));
+        vm.mockPrecompile(address(6), input, abi.encode(uint256(3), uint256(4)));
+
+        (bool success, bytes memory output) = address(6).staticcall(input);
+        assertTrue(success);
+        (uint256 x, uint256 y) = abi.decode(output, (uint256, uint256));
+        assertEq(x, 3);
+        assertEq(y, 4);
+
+        // clearing the mocks restores the precompile
+        vm.clearMockedCalls();
+        (success, output) = address(6).staticcall(input);
+        assertTrue(success);
+        (x, y) = abi.decode(output, (uint256, uint256));
+        assertEq(x, 0x030644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd3);
+        assertEq(y, 0x15ed738c0e0a7c92e7845f96b2ae9c0a68a6a449e3538fc7ff3ebf7a5a18a2c4);
+    }
+
+    function testMockPrecompileNotPrecompile() public {
+        vm._expectCheatcodeRevert(bytes("0x0000000000000000000000000000000000000000 is not a precompile"));
+        vm.mockPrecompile(address(0), "", "");
+    }
+}
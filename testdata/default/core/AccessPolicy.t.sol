@@ -0,0 +1,56 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+pragma solidity 0.8.18;
+
+import "ds-test/test.sol";
+import "cheats/Vm.sol";
+
+contract Counter {
+    uint256 public count;
+
+    function increment() public {
+        count++;
+    }
+}
+
+contract AccessPolicyTest is DSTest {
+    Vm constant vm = Vm(HEVM_ADDRESS);
+
+    // pre-existing contracts, as if they were forked
+    Counter constant allowed = Counter(address(0x1111));
+    Counter constant notAllowed = Counter(address(0x2222));
+    Counter constant denied = Counter(address(0x3333));
+
+    Counter created;
+
+    function setUp() public {
+        created = new Counter();
+        vm.etch(address(allowed), address(created).code);
+        vm.etch(address(notAllowed), address(created).code);
+        vm.etch(address(denied), address(created).code);
+    }
+
+    function testWriteCreated() public {
+        created.increment();
+        new Counter().increment();
+    }
+
+    function testWriteAllowed() public {
+        allowed.increment();
+    }
+
+    function testWriteNotAllowed() public {
+        notAllowed.increment();
+    }
+
+    function testCaughtWriteNotAllowed() public {
+        try notAllowed.increment() {} catch {}
+    }
+
+    function testCallDenied() public view {
+        denied.count();
+    }
+
+    function testReadDenied() public view {
+        assertTrue(address(denied).code.length > 0);
+    }
+}
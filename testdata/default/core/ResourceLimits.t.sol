@@ -0,0 +1,57 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+pragma solidity 0.8.18;
+
+contract Recursive {
+    function recurse(uint256 n) public returns (uint256) {
+        if (n == 0) return 0;
+        return this.recurse(n - 1) + 1;
+    }
+
+    function data(uint256 size) public pure returns (bytes memory) {
+        return new bytes(size);
+    }
+}
+
+contract ResourceLimitsTest {
+    event Ping(uint256 i);
+
+    Recursive recursive;
+    mapping(uint256 => uint256) slots;
+
+    function setUp() public {
+        recursive = new Recursive();
+    }
+
+    function testWithinLimits() public {
+        recursive.recurse(2);
+        recursive.data(64);
+        for (uint256 i = 0; i < 5; i++) {
+            emit Ping(i);
+            slots[i] = i + 1;
+        }
+    }
+
+    function testCallDepth() public {
+        recursive.recurse(10);
+    }
+
+    function testCaughtCallDepth() public {
+        try recursive.recurse(10) {} catch {}
+    }
+
+    function testEvents() public {
+        for (uint256 i = 0; i < 20; i++) {
+            emit Ping(i);
+        }
+    }
+
+    function testStateWrites() public {
+        for (uint256 i = 0; i < 20; i++) {
+            slots[i] = i + 1;
+        }
+    }
+
+    function testReturnData() public view {
+        recursive.data(2048);
+    }
+}
@@ -0,0 +1,40 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+pragma solidity 0.8.18;
+
+import "ds-test/test.sol";
+import "cheats/Vm.sol";
+
+/// forge-config: default.fork = "rpcAlias@15977624"
+contract ContractForkTest is DSTest {
+    address constant WETH_TOKEN_ADDR = 0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2;
+    Vm constant vm = Vm(HEVM_ADDRESS);
+
+    function setUp() public {
+        assertEq(block.number, 15_977_624);
+    }
+
+    function testRunsOnFork() public {
+        assertEq(block.chainid, 1);
+        assertEq(block.number, 15_977_624);
+        assertGt(WETH_TOKEN_ADDR.code.length, 0);
+        assertEq(vm.activeFork(), 0);
+    }
+}
+
+/// forge-config: default.fork = "rpcAlias@15977624"
+contract ContractForkSameTest is DSTest {
+    address constant WETH_TOKEN_ADDR = 0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2;
+
+    function testSharesFork() public {
+        assertEq(block.number, 15_977_624);
+        assertGt(WETH_TOKEN_ADDR.code.length, 0);
+    }
+}
+
+contract ContractNoForkTest is DSTest {
+    address constant WETH_TOKEN_ADDR = 0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2;
+
+    function testRunsLocally() public {
+        assertEq(WETH_TOKEN_ADDR.code.length, 0);
+    }
+}
@@ -0,0 +1,12 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+pragma solidity 0.8.18;
+
+import "ds-test/test.sol";
+
+contract FuzzBranchHintsTest is DSTest {
+    function testMagicValue(uint256 amount) public pure {
+        if (amount == 123456789) {
+            revert("found");
+        }
+    }
+}
@@ -0,0 +1,16 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+pragma solidity 0.8.18;
+
+import "ds-test/test.sol";
+
+contract FuzzCoverageGuidedTest is DSTest {
+    function testNestedBranches(uint256 a, uint256 b, uint256 c) public pure {
+        if (a % 32 == 7) {
+            if (b % 32 == 13) {
+                if (c % 32 == 21) {
+                    revert("found");
+                }
+            }
+        }
+    }
+}
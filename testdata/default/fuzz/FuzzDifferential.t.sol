@@ -0,0 +1,58 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+pragma solidity 0.8.18;
+
+import "ds-test/test.sol";
+
+contract ReferenceAverage {
+    event Averaged(uint256 average);
+
+    function average(uint256 a, uint256 b) public returns (uint256) {
+        uint256 result = (a & b) + ((a ^ b) >> 1);
+        emit Averaged(result);
+        return result;
+    }
+
+    function naiveAverage(uint256 a, uint256 b) public pure returns (uint256) {
+        return (a & b) + ((a ^ b) >> 1);
+    }
+}
+
+contract OptimizedAverage {
+    event Averaged(uint256 average);
+
+    function average(uint256 a, uint256 b) public returns (uint256 result) {
+        assembly {
+            result := add(and(a, b), shr(1, xor(a, b)))
+        }
+        emit Averaged(result);
+    }
+
+    // Reverts on overflow.
+    function naiveAverage(uint256 a, uint256 b) public pure returns (uint256) {
+        return (a + b) / 2;
+    }
+}
+
+// Differential tests are not executed themselves: their name and parameters describe the function
+// that is called on both targets with the fuzzed inputs.
+contract FuzzDifferentialTest is DSTest {
+    ReferenceAverage reference;
+    OptimizedAverage optimized;
+
+    function setUp() public {
+        reference = new ReferenceAverage();
+        optimized = new OptimizedAverage();
+    }
+
+    function differentialTargets() public view returns (address, address) {
+        return (address(reference), address(optimized));
+    }
+
+    function testDiff_average(uint256, uint256) public pure {}
+
+    function testDiff_naiveAverage(uint256, uint256) public pure {}
+}
+
+contract FuzzDifferentialNoTargetsTest is DSTest {
+    function testDiff_average(uint256, uint256) public pure {}
+}
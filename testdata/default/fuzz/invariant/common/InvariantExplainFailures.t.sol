@@ -0,0 +1,32 @@
+// SPDX-License-Identifier: UNLICENSED
+pragma solidity ^0.8.13;
+
+import "ds-test/test.sol";
+
+contract ExplainHandler {
+    bool public broken;
+
+    function breakWith(uint256 key, uint256 noise) public {
+        if (key > 10) {
+            broken = true;
+        }
+    }
+}
+
+contract InvariantExplainFailures is DSTest {
+    ExplainHandler handler;
+
+    function setUp() public {
+        handler = new ExplainHandler();
+    }
+
+    function targetContracts() public view returns (address[] memory) {
+        address[] memory targets = new address[](1);
+        targets[0] = address(handler);
+        return targets;
+    }
+
+    function invariant_notBroken() public {
+        require(!handler.broken(), "broken");
+    }
+}
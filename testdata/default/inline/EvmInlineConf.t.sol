@@ -0,0 +1,25 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+pragma solidity >=0.8.0;
+
+import "ds-test/test.sol";
+
+contract EvmInlineConf is DSTest {
+    uint256 value;
+
+    function setUp() public {
+        value = 1;
+    }
+
+    /// forge-config: default.evm.gas-limit = 1000000
+    function testInlineGasLimit() public {
+        assertLe(gasleft(), 1000000);
+    }
+
+    /// forge-config: default.evm.evm-version = paris
+    function testInlineEvmVersion() public {
+        // The point evaluation precompile was introduced in Cancun.
+        (bool success,) = address(0x0A).staticcall("");
+        assertTrue(success);
+        assertEq(value, 1);
+    }
+}